@@ -12,7 +12,7 @@ use crate::block_verification::{
     signature_verify_chain_segment, BlockError, FullyVerifiedBlock, GossipVerifiedBlock,
     IntoFullyVerifiedBlock,
 };
-use crate::chain_config::ChainConfig;
+use crate::chain_config::{ChainConfig, DEFAULT_MAXIMUM_GOSSIP_CLOCK_DISPARITY_MS};
 use crate::early_attester_cache::EarlyAttesterCache;
 use crate::errors::{BeaconChainError as Error, BlockProductionError};
 use crate::eth1_chain::{Eth1Chain, Eth1ChainBackend};
@@ -21,6 +21,11 @@ use crate::execution_payload::get_execution_payload;
 use crate::fork_choice_signal::{ForkChoiceSignalRx, ForkChoiceSignalTx, ForkChoiceWaitResult};
 use crate::head_tracker::HeadTracker;
 use crate::historical_blocks::HistoricalBlockError;
+use crate::light_client_server_cache::LightClientServerCache;
+use crate::light_client_update_verification::{
+    Error as LightClientUpdateError, VerifiedLightClientFinalityUpdate,
+    VerifiedLightClientOptimisticUpdate,
+};
 use crate::migrate::BackgroundMigrator;
 use crate::naive_aggregation_pool::{
     AggregatedAttestationMap, Error as NaiveAggregationError, NaiveAggregationPool,
@@ -34,6 +39,7 @@ use crate::observed_attesters::{
 };
 use crate::observed_block_producers::ObservedBlockProducers;
 use crate::observed_operations::{ObservationOutcome, ObservedOperations};
+use crate::pending_blobs_cache::PendingBlobsCache;
 use crate::persisted_beacon_chain::{PersistedBeaconChain, DUMMY_CANONICAL_HEAD_BLOCK_ROOT};
 use crate::persisted_fork_choice::PersistedForkChoice;
 use crate::pre_finalization_cache::PreFinalizationBlockCache;
@@ -53,9 +59,10 @@ use crate::BeaconForkChoiceStore;
 use crate::BeaconSnapshot;
 use crate::{metrics, BeaconChainError};
 use eth2::types::{
-    EventKind, SseBlock, SseChainReorg, SseFinalizedCheckpoint, SseHead, SseLateHead, SyncDuty,
+    EventKind, SseBlock, SseChainReorg, SseDutiesReorg, SseFinalizedCheckpoint, SseHead,
+    SseLateHead, SyncDuty,
 };
-use execution_layer::{ExecutionLayer, PayloadAttributes, PayloadStatus};
+use execution_layer::{BlockProposalContents, ExecutionLayer, PayloadAttributes, PayloadStatus};
 use fork_choice::{AttestationFromBlock, ForkChoice, InvalidationOperation};
 use futures::channel::mpsc::Sender;
 use itertools::process_results;
@@ -71,7 +78,9 @@ use ssz::Encode;
 use state_processing::{
     common::get_indexed_attestation,
     per_block_processing,
-    per_block_processing::{errors::AttestationValidationError, is_merge_transition_complete},
+    per_block_processing::{
+        errors::AttestationValidationError, get_expected_withdrawals, is_merge_transition_complete,
+    },
     per_slot_processing,
     state_advance::{complete_state_advance, partial_state_advance},
     BlockSignatureStrategy, SigVerifiedOp, VerifyBlockRoot,
@@ -81,6 +90,7 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::io::prelude::*;
 use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use store::iter::{BlockRootsIterator, ParentRootBlockIterator, StateRootsIterator};
@@ -88,6 +98,7 @@ use store::{
     DatabaseBlock, Error as DBError, HotColdDB, KeyValueStore, KeyValueStoreOp, StoreItem, StoreOp,
 };
 use task_executor::ShutdownReason;
+use tracing::{debug_span, info_span};
 use tree_hash::TreeHash;
 use types::beacon_state::CloneConfig;
 use types::*;
@@ -163,10 +174,12 @@ pub enum ProduceBlockVerification {
     NoVerification,
 }
 
-/// The accepted clock drift for nodes gossiping blocks and attestations. See:
+/// The default accepted clock drift for nodes gossiping blocks and attestations, which can be
+/// overridden by `ChainConfig::maximum_gossip_clock_disparity_ms`. See:
 ///
 /// https://github.com/ethereum/eth2.0-specs/blob/v0.12.1/specs/phase0/p2p-interface.md#configuration
-pub const MAXIMUM_GOSSIP_CLOCK_DISPARITY: Duration = Duration::from_millis(500);
+pub const MAXIMUM_GOSSIP_CLOCK_DISPARITY: Duration =
+    Duration::from_millis(DEFAULT_MAXIMUM_GOSSIP_CLOCK_DISPARITY_MS);
 
 #[derive(Debug, PartialEq)]
 pub enum AttestationProcessingOutcome {
@@ -331,6 +344,9 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     /// Maintains a record of which validators we've seen attester slashings for.
     pub(crate) observed_attester_slashings:
         Mutex<ObservedOperations<AttesterSlashing<T::EthSpec>, T::EthSpec>>,
+    /// Maintains a record of which validators we've seen BLS to execution changes for.
+    pub(crate) observed_bls_to_execution_changes:
+        Mutex<ObservedOperations<SignedBlsToExecutionChange, T::EthSpec>>,
     /// Provides information from the Ethereum 1 (PoW) chain.
     pub eth1_chain: Option<Eth1Chain<T::Eth1Chain, T::EthSpec>>,
     /// Interfaces with the execution client.
@@ -371,6 +387,10 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub block_times_cache: Arc<RwLock<BlockTimesCache>>,
     /// A cache used to track pre-finalization block roots for quick rejection.
     pub pre_finalization_block_cache: PreFinalizationBlockCache,
+    /// A cache of the light client updates computed from imported blocks.
+    pub light_client_server_cache: LightClientServerCache<T>,
+    /// The blobs of blocks yet to be imported, which are stored along with the block.
+    pub pending_blobs: PendingBlobsCache<T::EthSpec>,
     /// Sender given to tasks, so that if they encounter a state in which execution cannot
     /// continue they can request that everything shuts down.
     pub shutdown_sender: Sender<ShutdownReason>,
@@ -382,6 +402,8 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub slasher: Option<Arc<Slasher<T::EthSpec>>>,
     /// Provides monitoring of a set of explicitly defined validators.
     pub validator_monitor: RwLock<ValidatorMonitor<T::EthSpec>>,
+    /// Set whilst the resident memory of the process is above `ChainConfig::memory_watermark`.
+    pub(crate) memory_watermark_exceeded: AtomicBool,
 }
 
 type BeaconBlockAndState<T, Payload> = (BeaconBlock<T, Payload>, BeaconState<T>);
@@ -512,6 +534,36 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .map(|slot| slot.epoch(T::EthSpec::slots_per_epoch()))
     }
 
+    /// Returns the earliest epoch for which peers must serve blob sidecars, or `None` if Deneb is
+    /// not scheduled or the epoch is unavailable.
+    ///
+    /// Blocks before this epoch are imported without their blobs.
+    pub fn blob_sidecars_boundary(&self) -> Option<Epoch> {
+        let deneb_fork_epoch = self.spec.deneb_fork_epoch?;
+        let current_epoch = self.epoch().ok()?;
+        Some(std::cmp::max(
+            deneb_fork_epoch,
+            current_epoch.saturating_sub(self.spec.min_epochs_for_blob_sidecars_requests),
+        ))
+    }
+
+    /// Returns the accepted clock drift for nodes gossiping blocks and attestations.
+    pub fn maximum_gossip_clock_disparity(&self) -> Duration {
+        Duration::from_millis(self.config.maximum_gossip_clock_disparity_ms)
+    }
+
+    /// Returns `true` if the resident memory of the process is above the configured memory
+    /// watermark, in which case expensive work should be deferred or refused.
+    pub fn memory_watermark_exceeded(&self) -> bool {
+        self.memory_watermark_exceeded.load(AtomicOrdering::Relaxed)
+    }
+
+    /// Record whether the memory watermark is exceeded, returning the previous value.
+    pub(crate) fn set_memory_watermark_exceeded(&self, exceeded: bool) -> bool {
+        self.memory_watermark_exceeded
+            .swap(exceeded, AtomicOrdering::Relaxed)
+    }
+
     /// Iterates across all `(block_root, slot)` pairs from `start_slot`
     /// to the head of the chain (inclusive).
     ///
@@ -612,6 +664,66 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .map(|result| result.map_err(|e| e.into())))
     }
 
+    /// Returns `attester_duties_reorg` and `proposer_duties_reorg` events for each of the duties
+    /// of the current and next epochs (relative to `slot`) whose dependent root differs between
+    /// `old_head` and `new_head`.
+    ///
+    /// Proposer duties for the next epoch are not considered since their dependent root is the
+    /// last block of the current epoch, which isn't yet settled.
+    fn duties_reorg_events(
+        &self,
+        slot: Slot,
+        old_head: &BeaconSnapshot<T::EthSpec>,
+        new_head: &BeaconSnapshot<T::EthSpec>,
+    ) -> Vec<EventKind<T::EthSpec>> {
+        let slots_per_epoch = T::EthSpec::slots_per_epoch();
+        let current_epoch = slot.epoch(slots_per_epoch);
+
+        // The root of the block at `decision_slot` in the chain of `head`, which is the head block
+        // itself if the chain has no blocks after `decision_slot`.
+        let dependent_root = |head: &BeaconSnapshot<T::EthSpec>, decision_slot: Slot| {
+            if head.beacon_state.slot() <= decision_slot {
+                Some(head.beacon_block_root)
+            } else {
+                head.beacon_state
+                    .get_block_root(decision_slot)
+                    .ok()
+                    .copied()
+            }
+        };
+        let duties_reorg = |decision_slot: Slot, epoch: Epoch| {
+            let old_dependent_root = dependent_root(old_head, decision_slot)?;
+            let new_dependent_root = dependent_root(new_head, decision_slot)?;
+            (old_dependent_root != new_dependent_root).then(|| SseDutiesReorg {
+                epoch,
+                old_dependent_root,
+                new_dependent_root,
+                slot: new_head.beacon_block.slot(),
+                new_head_block: new_head.beacon_block_root,
+            })
+        };
+
+        let mut events = vec![];
+        for epoch in [current_epoch, current_epoch + 1] {
+            // Attester duties for `epoch` are decided by the last block of `epoch - 2`.
+            let decision_slot = epoch
+                .start_slot(slots_per_epoch)
+                .saturating_sub(slots_per_epoch + 1);
+            if let Some(reorg) = duties_reorg(decision_slot, epoch) {
+                events.push(EventKind::AttesterDutiesReorg(reorg));
+            }
+        }
+        // Proposer duties for `current_epoch` are decided by the last block of the previous epoch.
+        let decision_slot = current_epoch
+            .start_slot(slots_per_epoch)
+            .saturating_sub(1_u64);
+        if let Some(reorg) = duties_reorg(decision_slot, current_epoch) {
+            events.push(EventKind::ProposerDutiesReorg(reorg));
+        }
+
+        events
+    }
+
     /// Iterate through the current chain to find the slot intersecting with the given beacon state.
     /// The maximum depth this will search is `SLOTS_PER_HISTORICAL_ROOT`, and if that depth is reached
     /// and no intersection is found, the finalized slot will be returned.
@@ -1009,7 +1121,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .map_err(|_| Error::BlockVariantLacksExecutionPayload(*block_root))?
             .execution_payload_header;
 
-        let exec_block_hash = execution_payload_header.block_hash;
+        let exec_block_hash = execution_payload_header.block_hash();
 
         let execution_payload = self
             .execution_layer
@@ -1023,7 +1135,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // Verify payload integrity.
         let header_from_payload = ExecutionPayloadHeader::from(&execution_payload);
         if header_from_payload != *execution_payload_header {
-            for txn in &execution_payload.transactions {
+            for txn in execution_payload.transactions() {
                 debug!(
                     self.log,
                     "Reconstructed txn";
@@ -1036,8 +1148,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 exec_block_hash,
                 canonical_payload_root: execution_payload_header.tree_hash_root(),
                 reconstructed_payload_root: header_from_payload.tree_hash_root(),
-                canonical_transactions_root: execution_payload_header.transactions_root,
-                reconstructed_transactions_root: header_from_payload.transactions_root,
+                canonical_transactions_root: execution_payload_header.transactions_root(),
+                reconstructed_transactions_root: header_from_payload.transactions_root(),
             });
         }
 
@@ -1182,11 +1294,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .altair_fork_epoch
             .ok_or(Error::AltairForkDisabled)?;
 
-        let load_slot = std::cmp::max(
-            self.spec.epochs_per_sync_committee_period * sync_committee_period.saturating_sub(1),
-            altair_fork_epoch,
-        )
-        .start_slot(T::EthSpec::slots_per_epoch());
+        let previous_period_start = Epoch::sync_committee_period_start(
+            sync_committee_period.saturating_sub(1),
+            &self.spec,
+        )?;
+        let load_slot = std::cmp::max(previous_period_start, altair_fork_epoch)
+            .start_slot(T::EthSpec::slots_per_epoch());
 
         self.state_at_slot(load_slot, StateSkipConfig::WithoutStateRoots)
     }
@@ -1595,7 +1708,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .try_attest(request_slot, request_index, &self.spec)
         {
             // The cache matched this request, return the value.
-            Ok(Some(attestation)) => return Ok(attestation),
+            Ok(Some(attestation)) => {
+                self.record_attestation_production(
+                    request_slot,
+                    attestation.data.beacon_block_root,
+                );
+                return Ok(attestation);
+            }
             // The cache did not match this request, proceed with the rest of this function.
             Ok(None) => (),
             // The cache returned an error. Log the error and proceed with the rest of this
@@ -1769,6 +1888,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             };
         drop(cache_timer);
 
+        self.record_attestation_production(request_slot, beacon_block_root);
+
         Ok(Attestation {
             aggregation_bits: BitList::with_capacity(committee_len)?,
             data: AttestationData {
@@ -1782,6 +1903,26 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         })
     }
 
+    /// Record the time at which the first attestation for the current `slot` was produced, and
+    /// the block it voted for.
+    fn record_attestation_production(&self, slot: Slot, beacon_block_root: Hash256) {
+        if self.slot_clock.now() != Some(slot) {
+            return;
+        }
+
+        let timestamp = timestamp_now();
+        if self.block_times_cache.write().set_attestation_produced(
+            slot,
+            beacon_block_root,
+            timestamp,
+        ) {
+            metrics::observe_duration(
+                &metrics::ATTESTATION_PRODUCTION_SLOT_START_DELAY_TIME,
+                get_slot_delay_ms(timestamp, slot, &self.slot_clock),
+            );
+        }
+    }
+
     /// Performs the same validation as `Self::verify_unaggregated_attestation_for_gossip`, but for
     /// multiple attestations using batch BLS verification. Batch verification can provide
     /// significant CPU-time savings compared to individual verification.
@@ -1900,6 +2041,34 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         })
     }
 
+    /// Accepts some `LightClientFinalityUpdate` from the network and attempts to verify it,
+    /// returning `Ok(_)` if it is valid to be (re)broadcast on the gossip network.
+    pub fn verify_finality_update_for_gossip(
+        &self,
+        update: LightClientFinalityUpdate<T::EthSpec>,
+        seen_timestamp: Duration,
+    ) -> Result<VerifiedLightClientFinalityUpdate<T>, LightClientUpdateError> {
+        metrics::inc_counter(&metrics::FINALITY_UPDATE_PROCESSING_REQUESTS);
+        VerifiedLightClientFinalityUpdate::verify(update, self, seen_timestamp).map(|v| {
+            metrics::inc_counter(&metrics::FINALITY_UPDATE_PROCESSING_SUCCESSES);
+            v
+        })
+    }
+
+    /// Accepts some `LightClientOptimisticUpdate` from the network and attempts to verify it,
+    /// returning `Ok(_)` if it is valid to be (re)broadcast on the gossip network.
+    pub fn verify_optimistic_update_for_gossip(
+        &self,
+        update: LightClientOptimisticUpdate<T::EthSpec>,
+        seen_timestamp: Duration,
+    ) -> Result<VerifiedLightClientOptimisticUpdate<T>, LightClientUpdateError> {
+        metrics::inc_counter(&metrics::OPTIMISTIC_UPDATE_PROCESSING_REQUESTS);
+        VerifiedLightClientOptimisticUpdate::verify(update, self, seen_timestamp).map(|v| {
+            metrics::inc_counter(&metrics::OPTIMISTIC_UPDATE_PROCESSING_SUCCESSES);
+            v
+        })
+    }
+
     /// Accepts some attestation-type object and attempts to verify it in the context of fork
     /// choice. If it is valid it is applied to `self.fork_choice`.
     ///
@@ -2247,6 +2416,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         &self,
         attester_slashing: SigVerifiedOp<AttesterSlashing<T::EthSpec>>,
     ) -> Result<(), Error> {
+        // Add to fork choice.
+        self.fork_choice
+            .write()
+            .on_attester_slashing(attester_slashing.as_inner());
+
         if self.eth1_chain.is_some() {
             self.op_pool
                 .insert_attester_slashing(attester_slashing, self.head_info()?.fork)
@@ -2254,6 +2428,39 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(())
     }
 
+    /// Verify a BLS to execution change before allowing it to propagate on the gossip network.
+    pub fn verify_bls_to_execution_change_for_gossip(
+        &self,
+        bls_to_execution_change: SignedBlsToExecutionChange,
+    ) -> Result<ObservationOutcome<SignedBlsToExecutionChange>, Error> {
+        let wall_clock_state = self.wall_clock_state()?;
+
+        // BLS to execution changes are only valid on gossip from the Capella fork onwards.
+        let capella_enabled = self.spec.capella_fork_epoch.map_or(false, |fork_epoch| {
+            wall_clock_state.current_epoch() >= fork_epoch
+        });
+        if !capella_enabled {
+            return Err(Error::BlsToExecutionChangeBeforeCapella);
+        }
+
+        Ok(self
+            .observed_bls_to_execution_changes
+            .lock()
+            .verify_and_observe(bls_to_execution_change, &wall_clock_state, &self.spec)?)
+    }
+
+    /// Accept a pre-verified BLS to execution change and queue it for inclusion in an appropriate
+    /// block.
+    pub fn import_bls_to_execution_change(
+        &self,
+        bls_to_execution_change: SigVerifiedOp<SignedBlsToExecutionChange>,
+    ) {
+        if self.eth1_chain.is_some() {
+            self.op_pool
+                .insert_bls_to_execution_change(bls_to_execution_change);
+        }
+    }
+
     /// Attempt to obtain sync committee duties from the head.
     pub fn sync_committee_duties_from_head(
         &self,
@@ -2432,6 +2639,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     ) -> Result<GossipVerifiedBlock<T>, BlockError<T::EthSpec>> {
         let slot = block.slot();
         let graffiti_string = block.message().body().graffiti().as_utf8_lossy();
+        let _span = info_span!("block_gossip_verification", slot = %slot).entered();
 
         match GossipVerifiedBlock::new(block, self) {
             Ok(verified) => {
@@ -2481,9 +2689,15 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // Increment the Prometheus counter for block processing requests.
         metrics::inc_counter(&metrics::BLOCK_PROCESSING_REQUESTS);
 
+        // Hold off background compaction of the database until the block is processed.
+        let _import_guard = self.store.begin_block_import();
+
         // Clone the block so we can provide it to the event handler.
         let block = unverified_block.block().clone();
 
+        // The parent of the spans of each stage of verification and import.
+        let _span = info_span!("block_import", slot = %block.slot()).entered();
+
         // A small closure to group the verification and import errors.
         let import_block = |unverified_block: B| -> Result<Hash256, BlockError<T::EthSpec>> {
             let fully_verified = unverified_block.into_fully_verified_block(self)?;
@@ -2579,10 +2793,16 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // We perform this _before_ adding the block to fork choice because the pubkey cache is
         // used by attestation processing which will only process an attestation if the block is
         // known to fork choice. This ordering ensure that the pubkey cache is always up-to-date.
-        self.validator_pubkey_cache
-            .try_write_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
+        //
+        // The new keys are persisted atomically with the block and its state below, and only added
+        // to the in-memory cache once that write succeeds. The fork choice write-lock is held
+        // until then, so the block isn't visible to attestation processing before its keys are.
+        let (pubkey_cache_ops, pending_pubkeys) = self
+            .validator_pubkey_cache
+            .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
             .ok_or(Error::ValidatorPubkeyCacheLockTimeout)?
             .import_new_pubkeys(&state)?;
+        ops.extend(pubkey_cache_ops);
 
         // For the current and next epoch of this state, ensure we have the shuffling from this
         // block in our cache.
@@ -2662,6 +2882,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         {
             let _fork_choice_block_timer =
                 metrics::start_timer(&metrics::FORK_CHOICE_PROCESS_BLOCK_TIMES);
+            let _fork_choice_block_span = debug_span!("block_fork_choice").entered();
             let block_delay = self
                 .slot_clock
                 .seconds_from_current_slot_start(self.spec.seconds_per_slot)
@@ -2680,10 +2901,17 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 .map_err(|e| BlockError::BeaconChainError(e.into()))?;
         }
 
+        // Register each attester slashing in the block with fork choice.
+        for attester_slashing in block.body().attester_slashings() {
+            fork_choice.on_attester_slashing(attester_slashing);
+        }
+
         // Allow the validator monitor to learn about a new valid state.
-        self.validator_monitor
-            .write()
-            .process_valid_state(current_slot.epoch(T::EthSpec::slots_per_epoch()), &state);
+        self.validator_monitor.write().process_valid_state(
+            current_slot.epoch(T::EthSpec::slots_per_epoch()),
+            &state,
+            &self.spec,
+        );
         let validator_monitor = self.validator_monitor.read();
 
         // Register each attestation in the block with the fork choice service.
@@ -2793,17 +3021,25 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             // `SyncCommittee` for the sync_aggregate should correspond to the duty slot
             let duty_epoch = block.slot().epoch(T::EthSpec::slots_per_epoch());
             let sync_committee = self.sync_committee_at_epoch(duty_epoch)?;
-            let participant_pubkeys = sync_committee
+            let mut participant_pubkeys = vec![];
+            let mut non_participant_pubkeys = vec![];
+            for (pubkey, bit) in sync_committee
                 .pubkeys
                 .iter()
                 .zip(sync_aggregate.sync_committee_bits.iter())
-                .filter_map(|(pubkey, bit)| bit.then(|| pubkey))
-                .collect::<Vec<_>>();
+            {
+                if bit {
+                    participant_pubkeys.push(pubkey);
+                } else {
+                    non_participant_pubkeys.push(pubkey);
+                }
+            }
 
             validator_monitor.register_sync_aggregate_in_block(
                 block.slot(),
                 block.parent_root(),
                 participant_pubkeys,
+                non_participant_pubkeys,
             );
         }
 
@@ -2838,13 +3074,44 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             }
         }
 
+        // Compute the light client updates signed in this block. Skip blocks from before the
+        // previous epoch to avoid the work during sync.
+        if self.config.enable_light_client_server
+            && block.slot().epoch(T::EthSpec::slots_per_epoch()) + 1 >= current_epoch
+        {
+            if let Err(e) = self.light_client_server_cache.recompute_and_cache_updates(
+                &self.store,
+                block_root,
+                &block,
+                &mut state,
+            ) {
+                debug!(
+                    self.log,
+                    "Failed to compute light client updates";
+                    "block_root" => ?block_root,
+                    "error" => ?e,
+                );
+            }
+        }
+
         let db_write_timer = metrics::start_timer(&metrics::BLOCK_PROCESSING_DB_WRITE);
+        let db_write_span = debug_span!("block_db_write").entered();
 
         // Store the block and its state, and execute the confirmation batch for the intermediate
         // states, which will delete their temporary flags.
         // If the write fails, revert fork choice to the version from disk, else we can
         // end up with blocks in fork choice that are missing from disk.
         // See https://github.com/sigp/lighthouse/issues/2028
+        match self.pending_blobs.take_sidecars(block_root, &signed_block) {
+            Ok(Some(blobs)) => ops.push(StoreOp::PutBlobs(block_root, blobs)),
+            Ok(None) => {}
+            Err(e) => warn!(
+                self.log,
+                "Unable to store blobs for block";
+                "block_root" => ?block_root,
+                "error" => ?e,
+            ),
+        }
         ops.push(StoreOp::PutBlock(block_root, Box::new(signed_block)));
         ops.push(StoreOp::PutState(block.state_root(), &state));
         let txn_lock = self.store.hot_db.begin_rw_transaction();
@@ -2872,6 +3139,23 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
         drop(txn_lock);
 
+        if !pending_pubkeys.is_empty() {
+            match self
+                .validator_pubkey_cache
+                .try_write_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
+            {
+                Some(mut cache) => cache.commit_pubkeys(pending_pubkeys),
+                // The keys are on disk, and will be added by the next block whose state includes
+                // them.
+                None => error!(
+                    self.log,
+                    "Unable to update validator pubkey cache";
+                    "error" => ?Error::ValidatorPubkeyCacheLockTimeout,
+                    "block_root" => ?block_root,
+                ),
+            }
+        }
+
         // The fork choice write-lock is dropped *after* the on-disk database has been updated.
         // This prevents inconsistency between the two at the expense of concurrency.
         drop(fork_choice);
@@ -2921,6 +3205,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
 
         metrics::stop_timer(db_write_timer);
+        drop(db_write_span);
 
         metrics::inc_counter(&metrics::BLOCK_PROCESSING_SUCCESSES);
 
@@ -3019,7 +3304,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     ///
     /// The produced block will not be inherently valid, it must be signed by a block producer.
     /// Block signing is out of the scope of this function and should be done by a separate program.
-    pub fn produce_block<Payload: ExecPayload<T::EthSpec>>(
+    pub fn produce_block<Payload: AbstractExecPayload<T::EthSpec>>(
         self: &Arc<Self>,
         randao_reveal: Signature,
         slot: Slot,
@@ -3034,7 +3319,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     }
 
     /// Same as `produce_block` but allowing for configuration of RANDAO-verification.
-    pub fn produce_block_with_verification<Payload: ExecPayload<T::EthSpec>>(
+    pub fn produce_block_with_verification<Payload: AbstractExecPayload<T::EthSpec>>(
         self: &Arc<Self>,
         randao_reveal: Signature,
         slot: Slot,
@@ -3043,9 +3328,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     ) -> Result<BeaconBlockAndState<T::EthSpec, Payload>, BlockProductionError> {
         metrics::inc_counter(&metrics::BLOCK_PRODUCTION_REQUESTS);
         let _complete_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_TIMES);
+        let _span = info_span!("block_production", slot = %slot).entered();
 
         let fork_choice_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_FORK_CHOICE_TIMES);
+        let fork_choice_span = debug_span!("block_production_fork_choice").entered();
         self.wait_for_fork_choice_before_block_production(slot)?;
+        drop(fork_choice_span);
         drop(fork_choice_timer);
 
         // Producing a block requires the tree hash cache, so clone a full state corresponding to
@@ -3054,6 +3342,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // signed. If we miss the cache or we're producing a block that conflicts with the head,
         // fall back to getting the head from `slot - 1`.
         let state_load_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_STATE_LOAD_TIMES);
+        let state_load_span = debug_span!("block_production_state_load").entered();
         let head_info = self
             .head_info()
             .map_err(BlockProductionError::UnableToGetHeadInfo)?;
@@ -3093,6 +3382,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
             (state, None)
         };
+        drop(state_load_span);
         drop(state_load_timer);
 
         self.produce_block_on_state::<Payload>(
@@ -3117,7 +3407,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// The provided `state_root_opt` should only ever be set to `Some` if the contained value is
     /// equal to the root of `state`. Providing this value will serve as an optimization to avoid
     /// performing a tree hash in some scenarios.
-    pub fn produce_block_on_state<Payload: ExecPayload<T::EthSpec>>(
+    pub fn produce_block_on_state<Payload: AbstractExecPayload<T::EthSpec>>(
         &self,
         mut state: BeaconState<T::EthSpec>,
         state_root_opt: Option<Hash256>,
@@ -3234,6 +3524,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 }))
         };
 
+        // The blobs for the payload's blob transactions, from Deneb onwards.
+        let mut blobs_bundle = None;
         let inner_block = match &state {
             BeaconState::Base(_) => BeaconBlock::Base(BeaconBlockBase {
                 slot,
@@ -3276,7 +3568,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             BeaconState::Merge(_) => {
                 let sync_aggregate = get_sync_aggregate()?;
                 let execution_payload =
-                    get_execution_payload::<T, Payload>(self, &state, proposer_index)?;
+                    get_execution_payload::<T, Payload>(self, &state, proposer_index)?.payload;
                 BeaconBlock::Merge(BeaconBlockMerge {
                     slot,
                     proposer_index,
@@ -3296,6 +3588,69 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     },
                 })
             }
+            BeaconState::Capella(_) => {
+                let sync_aggregate = get_sync_aggregate()?;
+                let execution_payload =
+                    get_execution_payload::<T, Payload>(self, &state, proposer_index)?.payload;
+                let bls_to_execution_changes = self
+                    .op_pool
+                    .get_bls_to_execution_changes(&state, &self.spec);
+                BeaconBlock::Capella(BeaconBlockCapella {
+                    slot,
+                    proposer_index,
+                    parent_root,
+                    state_root: Hash256::zero(),
+                    body: BeaconBlockBodyCapella {
+                        randao_reveal,
+                        eth1_data,
+                        graffiti,
+                        proposer_slashings: proposer_slashings.into(),
+                        attester_slashings: attester_slashings.into(),
+                        attestations,
+                        deposits,
+                        voluntary_exits: voluntary_exits.into(),
+                        sync_aggregate,
+                        execution_payload,
+                        bls_to_execution_changes: bls_to_execution_changes.into(),
+                    },
+                })
+            }
+            BeaconState::Deneb(_) => {
+                let sync_aggregate = get_sync_aggregate()?;
+                let BlockProposalContents {
+                    payload: execution_payload,
+                    blobs_bundle: bundle,
+                } = get_execution_payload::<T, Payload>(self, &state, proposer_index)?;
+                let bls_to_execution_changes = self
+                    .op_pool
+                    .get_bls_to_execution_changes(&state, &self.spec);
+                // Blinded payloads come without a bundle, their commitments are left empty.
+                let blob_kzg_commitments = bundle
+                    .as_ref()
+                    .map(|bundle| bundle.commitments.clone())
+                    .unwrap_or_default();
+                blobs_bundle = bundle;
+                BeaconBlock::Deneb(BeaconBlockDeneb {
+                    slot,
+                    proposer_index,
+                    parent_root,
+                    state_root: Hash256::zero(),
+                    body: BeaconBlockBodyDeneb {
+                        randao_reveal,
+                        eth1_data,
+                        graffiti,
+                        proposer_slashings: proposer_slashings.into(),
+                        attester_slashings: attester_slashings.into(),
+                        attestations,
+                        deposits,
+                        voluntary_exits: voluntary_exits.into(),
+                        sync_aggregate,
+                        execution_payload,
+                        bls_to_execution_changes: bls_to_execution_changes.into(),
+                        blob_kzg_commitments,
+                    },
+                })
+            }
         };
 
         let block = SignedBeaconBlock::from_block(
@@ -3318,6 +3673,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
 
         let process_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_PROCESS_TIMES);
+        let process_span = debug_span!("block_production_state_transition").entered();
         let signature_strategy = match verification {
             ProduceBlockVerification::VerifyRandao => BlockSignatureStrategy::VerifyRandao,
             ProduceBlockVerification::NoVerification => BlockSignatureStrategy::NoVerification,
@@ -3330,15 +3686,26 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             VerifyBlockRoot::True,
             &self.spec,
         )?;
+        drop(process_span);
         drop(process_timer);
 
         let state_root_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_STATE_ROOT_TIMES);
+        let state_root_span = debug_span!("block_production_state_root").entered();
         let state_root = state.update_tree_hash_cache()?;
+        drop(state_root_span);
         drop(state_root_timer);
 
         let (mut block, _) = block.deconstruct();
         *block.state_root_mut() = state_root;
 
+        // Hold the blobs until the signed block is imported, when they're stored with it.
+        if let Some(blobs_bundle) = blobs_bundle {
+            if !blobs_bundle.blobs.is_empty() {
+                self.pending_blobs
+                    .put_bundle(block.canonical_root(), blobs_bundle);
+            }
+        }
+
         metrics::inc_counter(&metrics::BLOCK_PRODUCTION_SUCCESSES);
 
         trace!(
@@ -3439,6 +3806,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     pub fn fork_choice_at_slot(self: &Arc<Self>, slot: Slot) -> Result<(), Error> {
         metrics::inc_counter(&metrics::FORK_CHOICE_REQUESTS);
         let _timer = metrics::start_timer(&metrics::FORK_CHOICE_TIMES);
+        let _span = info_span!("head_update", slot = %slot).entered();
 
         let result = self.fork_choice_internal(slot);
 
@@ -3620,6 +3988,23 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .beacon_state
             .attester_shuffling_decision_root(self.genesis_block_root, RelativeEpoch::Current);
 
+        // Determine whether the re-org invalidates any duties whilst the old head is still
+        // available.
+        let duties_reorg_events = match self.event_handler.as_ref() {
+            Some(event_handler)
+                if is_reorg
+                    && (event_handler.has_attester_duties_reorg_subscribers()
+                        || event_handler.has_proposer_duties_reorg_subscribers()) =>
+            {
+                let old_head = self
+                    .canonical_head
+                    .try_read_for(HEAD_LOCK_TIMEOUT)
+                    .ok_or(Error::CanonicalHeadLockTimeout)?;
+                self.duties_reorg_events(slot, &old_head, &new_head)
+            }
+            _ => vec![],
+        };
+
         drop(lag_timer);
 
         // Clear the early attester cache in case it conflicts with `self.canonical_head`.
@@ -3691,6 +4076,26 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     .unwrap_or_else(|| Duration::from_secs(0)),
             );
 
+            // If attestations for the block's slot were already produced for an earlier block,
+            // the block arrived or was processed too late to be attested to.
+            let attestation = self
+                .block_times_cache
+                .read()
+                .get_attestation_production(head_slot);
+            if let Some(attestation) = attestation {
+                if attestation.beacon_block_root != beacon_block_root {
+                    metrics::inc_counter(&metrics::BEACON_BLOCK_HEAD_AFTER_ATTESTATION_TOTAL);
+                    debug!(
+                        self.log,
+                        "Head block set after attestation production";
+                        "block_root" => ?beacon_block_root,
+                        "slot" => head_slot,
+                        "attested_block_root" => ?attestation.beacon_block_root,
+                        "block_delay" => ?block_delay_total,
+                    );
+                }
+            }
+
             // If the block was enshrined as head too late for attestations to be created for it,
             // log a debug warning and increment a metric.
             if late_head {
@@ -3800,6 +4205,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 }));
             }
 
+            for event in duties_reorg_events {
+                event_handler.register(event);
+            }
+
             if !block_from_sync && late_head && event_handler.has_late_head_subscribers() {
                 let peer_info = self
                     .block_times_cache
@@ -3875,6 +4284,38 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .map_err(Error::PrepareProposerBlockingFailed)?
     }
 
+    /// Compute the withdrawals that a payload built upon the head at `prepare_slot` must contain.
+    ///
+    /// Returns `Ok(None)` if `prepare_slot` is prior to the Capella fork.
+    fn get_expected_withdrawals_at_slot(
+        &self,
+        prepare_slot: Slot,
+    ) -> Result<Option<Vec<Withdrawal>>, Error> {
+        match self.spec.fork_name_at_slot::<T::EthSpec>(prepare_slot) {
+            ForkName::Base | ForkName::Altair | ForkName::Merge => return Ok(None),
+            ForkName::Capella | ForkName::Deneb => (),
+        }
+
+        let (mut state, state_root) = self.with_head(|head| {
+            Ok::<_, Error>((
+                head.beacon_state
+                    .clone_with(CloneConfig::committee_caches_only()),
+                head.beacon_state_root(),
+            ))
+        })?;
+
+        // The withdrawals sweep only depends on validator balances and credentials, so a partial
+        // advance (without state roots) is sufficient.
+        if state.slot() < prepare_slot {
+            partial_state_advance(&mut state, Some(state_root), prepare_slot, &self.spec)?;
+        }
+
+        let withdrawals = get_expected_withdrawals(&state, &self.spec)
+            .map_err(Error::WithdrawalsComputationFailed)?;
+
+        Ok(Some(withdrawals.to_vec()))
+    }
+
     /// Determines the beacon proposer for the next slot. If that proposer is registered in the
     /// `execution_layer`, provide the `execution_layer` with the necessary information to produce
     /// `PayloadAttributes` for future calls to fork choice.
@@ -4014,6 +4455,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             suggested_fee_recipient: execution_layer
                 .get_suggested_fee_recipient(proposer as u64)
                 .await,
+            withdrawals: self.get_expected_withdrawals_at_slot(prepare_slot)?,
+            parent_beacon_block_root: match self.spec.fork_name_at_slot::<T::EthSpec>(prepare_slot)
+            {
+                ForkName::Base | ForkName::Altair | ForkName::Merge | ForkName::Capella => None,
+                ForkName::Deneb => Some(head.block_root),
+            },
         };
 
         debug!(
@@ -4356,6 +4803,31 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(status)
     }
 
+    /// Returns `true` if the head block was imported optimistically and has not yet been verified
+    /// by the execution layer.
+    pub fn is_optimistic_head(&self) -> Result<bool, BeaconChainError> {
+        self.head_safety_status()
+            .map(|status| matches!(status, HeadSafetyStatus::Unsafe(_)))
+    }
+
+    /// Returns `true` if the block with `block_root` was imported optimistically and has not yet
+    /// been verified by the execution layer.
+    ///
+    /// Blocks which are not known to fork choice are assumed to be finalized, they are only
+    /// optimistic if the finalized block is.
+    pub fn is_optimistic_block(&self, block_root: &Hash256) -> Result<bool, BeaconChainError> {
+        let fork_choice = self.fork_choice.read();
+
+        if let Some(status) = fork_choice.get_block_execution_status(block_root) {
+            return Ok(status.is_optimistic());
+        }
+
+        let finalized_root = fork_choice.finalized_checkpoint().root;
+        Ok(fork_choice
+            .get_block_execution_status(&finalized_root)
+            .map_or(false, |status| status.is_optimistic()))
+    }
+
     /// This function takes a configured weak subjectivity `Checkpoint` and the latest finalized `Checkpoint`.
     /// If the weak subjectivity checkpoint and finalized checkpoint share the same epoch, we compare
     /// roots. If we the weak subjectivity checkpoint is from an older epoch, we iterate back through
@@ -4492,7 +4964,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 );
             });
 
-        self.op_pool.prune_all(head_state, self.epoch()?);
+        self.op_pool
+            .prune_all(head_state, self.epoch()?, &self.spec);
 
         self.store_migrator.process_finalization(
             new_finalized_state_root.into(),
@@ -4503,6 +4976,26 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         self.attester_cache
             .prune_below(new_finalized_checkpoint.epoch);
 
+        if let Some(eth1_chain) = self.eth1_chain.as_ref() {
+            // Deposits can only be finalized once they have all been processed, otherwise they
+            // may still be required for block production.
+            match self.get_state(&new_finalized_state_root, None) {
+                Ok(Some(finalized_state))
+                    if finalized_state.eth1_deposit_index()
+                        >= finalized_state.eth1_data().deposit_count =>
+                {
+                    eth1_chain.finalize_eth1_data(finalized_state.eth1_data().clone());
+                }
+                Ok(_) => (),
+                Err(e) => warn!(
+                    self.log,
+                    "Unable to finalize eth1 deposits";
+                    "error" => ?e,
+                    "state_root" => ?new_finalized_state_root,
+                ),
+            }
+        }
+
         if let Some(event_handler) = self.event_handler.as_ref() {
             if event_handler.has_finalized_subscribers() {
                 event_handler.register(EventKind::FinalizedCheckpoint(SseFinalizedCheckpoint {
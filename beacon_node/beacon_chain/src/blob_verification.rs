@@ -0,0 +1,196 @@
+//! Provides verification for the blob sidecars received from peers over RPC, against the block
+//! which commits to them.
+//!
+//! A block's blobs are only accepted if there is exactly one sidecar for each of the block's
+//! commitments, and each sidecar carries the block's signed header and proves its commitment is
+//! included in the block.
+//!
+//! The KZG proofs of the blobs are not verified.
+
+use std::sync::Arc;
+use strum::AsRefStr;
+use types::{BlobSidecar, BlobSidecarList, EthSpec, Hash256, SignedBeaconBlock};
+
+/// Returned when the blob sidecars received for a block are not valid.
+///
+/// ## Peer scoring
+///
+/// All of these errors indicate a faulty peer, since the block is known to the peer which sent it.
+#[derive(Debug, AsRefStr)]
+pub enum Error {
+    /// A sidecar was received for a block which was not received, or which does not commit to a
+    /// blob at the sidecar's index.
+    UnexpectedBlob { block_root: Hash256, index: u64 },
+    /// More than one sidecar was received for the same blob.
+    DuplicateBlob { block_root: Hash256, index: u64 },
+    /// No sidecar was received for one of the block's commitments.
+    MissingBlob { block_root: Hash256, index: u64 },
+    /// The sidecar's commitment differs from the block's commitment at the same index.
+    CommitmentMismatch { block_root: Hash256, index: u64 },
+    /// The sidecar's signed header is not the header of the block.
+    HeaderMismatch { block_root: Hash256, index: u64 },
+    /// The sidecar's commitment inclusion proof is invalid.
+    InvalidInclusionProof { block_root: Hash256, index: u64 },
+}
+
+/// Verify that `blobs` are exactly the blob sidecars of `block`, returning them ordered by index.
+///
+/// Returns `None` if the block does not commit to any blobs.
+pub fn verify_blobs_for_block<E: EthSpec>(
+    block: &SignedBeaconBlock<E>,
+    block_root: Hash256,
+    mut blobs: Vec<Arc<BlobSidecar<E>>>,
+) -> Result<Option<BlobSidecarList<E>>, Error> {
+    let commitments = block
+        .message()
+        .body()
+        .blob_kzg_commitments()
+        .map(|commitments| commitments.to_vec())
+        .unwrap_or_default();
+
+    blobs.sort_unstable_by_key(|blob| blob.index);
+    for (position, blob) in blobs.iter().enumerate() {
+        let index = blob.index;
+        if position > 0 && blobs[position - 1].index == index {
+            return Err(Error::DuplicateBlob { block_root, index });
+        }
+        let commitment = commitments
+            .get(index as usize)
+            .ok_or(Error::UnexpectedBlob { block_root, index })?;
+        if blob.kzg_commitment != *commitment {
+            return Err(Error::CommitmentMismatch { block_root, index });
+        }
+        if blob.signed_block_header != block.signed_block_header() {
+            return Err(Error::HeaderMismatch { block_root, index });
+        }
+        if !blob.verify_blob_sidecar_inclusion_proof() {
+            return Err(Error::InvalidInclusionProof { block_root, index });
+        }
+    }
+
+    if blobs.len() < commitments.len() {
+        // The blobs are sorted and unique, so the first missing index is the number of leading
+        // blobs which have their position as index.
+        let index = blobs
+            .iter()
+            .enumerate()
+            .take_while(|(position, blob)| blob.index == *position as u64)
+            .count() as u64;
+        return Err(Error::MissingBlob { block_root, index });
+    }
+
+    if blobs.is_empty() {
+        return Ok(None);
+    }
+    let count = blobs.len();
+    BlobSidecarList::<E>::new(blobs)
+        .map(Some)
+        .map_err(|_| Error::UnexpectedBlob {
+            block_root,
+            index: count as u64 - 1,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+    use types::{
+        BeaconBlock, BeaconBlockDeneb, Blob, ForkName, KzgCommitment, KzgProof, MinimalEthSpec,
+        Signature,
+    };
+
+    type E = MinimalEthSpec;
+
+    fn block_with_blobs(
+        rng: &mut XorShiftRng,
+        count: usize,
+    ) -> (SignedBeaconBlock<E>, Vec<Arc<BlobSidecar<E>>>) {
+        let spec = ForkName::Deneb.make_genesis_spec(E::default_spec());
+        let mut block = BeaconBlock::<E>::Deneb(BeaconBlockDeneb::empty(&spec));
+        *block.body_mut().blob_kzg_commitments_mut().unwrap() = (0..count)
+            .map(|_| KzgCommitment::random_for_test(rng))
+            .collect::<Vec<_>>()
+            .into();
+        let block = SignedBeaconBlock::from_block(block, Signature::empty());
+        let blobs = (0..count)
+            .map(|index| {
+                let proof = KzgProof::random_for_test(rng);
+                Arc::new(BlobSidecar::new(index, Blob::<E>::default(), &block, proof).unwrap())
+            })
+            .collect();
+        (block, blobs)
+    }
+
+    #[test]
+    fn valid_blobs() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let (block, mut blobs) = block_with_blobs(rng, 3);
+        let block_root = block.canonical_root();
+        blobs.reverse();
+
+        let verified = verify_blobs_for_block(&block, block_root, blobs)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            verified.iter().map(|blob| blob.index).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+
+        let (block, blobs) = block_with_blobs(rng, 0);
+        assert!(
+            verify_blobs_for_block(&block, block.canonical_root(), blobs)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn invalid_blobs() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let (block, blobs) = block_with_blobs(rng, 3);
+        let block_root = block.canonical_root();
+
+        let missing = vec![blobs[0].clone(), blobs[2].clone()];
+        assert!(matches!(
+            verify_blobs_for_block(&block, block_root, missing),
+            Err(Error::MissingBlob { index: 1, .. })
+        ));
+
+        let duplicate = vec![blobs[0].clone(), blobs[1].clone(), blobs[1].clone()];
+        assert!(matches!(
+            verify_blobs_for_block(&block, block_root, duplicate),
+            Err(Error::DuplicateBlob { index: 1, .. })
+        ));
+
+        let (other_block, other_blobs) = block_with_blobs(rng, 4);
+        let mut unexpected = blobs.clone();
+        unexpected.push(other_blobs[3].clone());
+        assert!(matches!(
+            verify_blobs_for_block(&block, block_root, unexpected),
+            Err(Error::UnexpectedBlob { index: 3, .. })
+        ));
+
+        let mismatched = other_blobs[..3].to_vec();
+        assert!(matches!(
+            verify_blobs_for_block(&block, block_root, mismatched),
+            Err(Error::CommitmentMismatch { index: 0, .. })
+        ));
+
+        let mut wrong_header = (*blobs[0]).clone();
+        wrong_header.signed_block_header = other_block.signed_block_header();
+        let wrong_header = vec![Arc::new(wrong_header), blobs[1].clone(), blobs[2].clone()];
+        assert!(matches!(
+            verify_blobs_for_block(&block, block_root, wrong_header),
+            Err(Error::HeaderMismatch { index: 0, .. })
+        ));
+
+        let mut bad_proof = (*blobs[2]).clone();
+        bad_proof.kzg_commitment_inclusion_proof[0] = Hash256::repeat_byte(1);
+        let bad_proof = vec![blobs[0].clone(), blobs[1].clone(), Arc::new(bad_proof)];
+        assert!(matches!(
+            verify_blobs_for_block(&block, block_root, bad_proof),
+            Err(Error::InvalidInclusionProof { index: 2, .. })
+        ));
+    }
+}
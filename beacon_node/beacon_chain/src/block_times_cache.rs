@@ -8,6 +8,9 @@
 //! - The block was observed late.
 //! - We were too slow to import it.
 //! - We were too slow to set it as head.
+//!
+//! The time at which the first attestation of each slot was produced is also recorded, to show
+//! whether the block of the slot was set as head in time to be attested to.
 
 use eth2::types::{Hash256, Slot};
 use std::collections::HashMap;
@@ -73,9 +76,31 @@ impl BlockTimesCacheValue {
     }
 }
 
+/// The first unaggregated attestation produced for a slot.
+#[derive(Clone, Copy)]
+pub struct AttestationProduction {
+    pub timestamp: Duration,
+    pub beacon_block_root: BlockRoot,
+}
+
+/// The timings of a single slot, each relative to the start of the slot.
+#[derive(Default)]
+pub struct SlotDelays {
+    /// The block of the slot, preferring the block which was set as head if there are several.
+    pub block_root: Option<BlockRoot>,
+    pub observed: Option<Duration>,
+    pub imported: Option<Duration>,
+    pub set_as_head: Option<Duration>,
+    pub attestation_produced: Option<Duration>,
+    /// Whether the first attestation of the slot voted for an earlier block than the block of the
+    /// slot. `None` if there was no block or no attestation.
+    pub attested_to_parent: Option<bool>,
+}
+
 #[derive(Default)]
 pub struct BlockTimesCache {
     pub cache: HashMap<BlockRoot, BlockTimesCacheValue>,
+    pub attestations: HashMap<Slot, AttestationProduction>,
 }
 
 /// Helper methods to read from and write to the cache.
@@ -115,6 +140,62 @@ impl BlockTimesCache {
         block_times.timestamps.set_as_head = Some(timestamp);
     }
 
+    /// Record the production of an attestation at `slot`, returning `true` if it's the first.
+    pub fn set_attestation_produced(
+        &mut self,
+        slot: Slot,
+        beacon_block_root: BlockRoot,
+        timestamp: Duration,
+    ) -> bool {
+        if self.attestations.contains_key(&slot) {
+            return false;
+        }
+        self.attestations.insert(
+            slot,
+            AttestationProduction {
+                timestamp,
+                beacon_block_root,
+            },
+        );
+        true
+    }
+
+    pub fn get_attestation_production(&self, slot: Slot) -> Option<AttestationProduction> {
+        self.attestations.get(&slot).copied()
+    }
+
+    pub fn get_slot_delays(&self, slot: Slot, slot_start_time: Duration) -> SlotDelays {
+        let since_slot_start =
+            |timestamp: Option<Duration>| timestamp?.checked_sub(slot_start_time);
+
+        let block = self
+            .cache
+            .iter()
+            .filter(|(_, block_times)| block_times.slot == slot)
+            .min_by_key(|(_, block_times)| {
+                let timestamps = &block_times.timestamps;
+                (
+                    timestamps.set_as_head.is_none(),
+                    timestamps.observed.is_none(),
+                    timestamps.observed,
+                )
+            });
+        let attestation = self.attestations.get(&slot);
+
+        SlotDelays {
+            block_root: block.map(|(block_root, _)| *block_root),
+            observed: block.and_then(|(_, times)| since_slot_start(times.timestamps.observed)),
+            imported: block.and_then(|(_, times)| since_slot_start(times.timestamps.imported)),
+            set_as_head: block
+                .and_then(|(_, times)| since_slot_start(times.timestamps.set_as_head)),
+            attestation_produced: attestation
+                .and_then(|attestation| since_slot_start(Some(attestation.timestamp))),
+            attested_to_parent: block
+                .zip(attestation)
+                .map(|((block_root, _), attestation)| attestation.beacon_block_root != *block_root),
+        }
+    }
+
     pub fn get_block_delays(
         &self,
         block_root: BlockRoot,
@@ -139,5 +220,7 @@ impl BlockTimesCache {
     pub fn prune(&mut self, current_slot: Slot) {
         self.cache
             .retain(|_, cache| cache.slot > current_slot.saturating_sub(64_u64));
+        self.attestations
+            .retain(|slot, _| *slot > current_slot.saturating_sub(64_u64));
     }
 }
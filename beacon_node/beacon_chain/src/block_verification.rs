@@ -40,6 +40,7 @@
 //!            END
 //!
 //! ```
+use crate::crash_dump::BlockProcessingFailure;
 use crate::execution_payload::{
     notify_new_payload, validate_execution_payload_for_gossip, validate_merge_block,
 };
@@ -47,10 +48,7 @@ use crate::snapshot_cache::PreProcessingSnapshot;
 use crate::validator_monitor::HISTORIC_EPOCHS as VALIDATOR_MONITOR_HISTORIC_EPOCHS;
 use crate::validator_pubkey_cache::ValidatorPubkeyCache;
 use crate::{
-    beacon_chain::{
-        BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT, MAXIMUM_GOSSIP_CLOCK_DISPARITY,
-        VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT,
-    },
+    beacon_chain::{BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT, VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT},
     metrics, BeaconChain, BeaconChainError, BeaconChainTypes,
 };
 use derivative::Derivative;
@@ -76,6 +74,7 @@ use std::io::Write;
 use std::sync::Arc;
 use std::time::Duration;
 use store::{Error as DBError, HotColdDB, HotStateSummary, KeyValueStore, StoreOp};
+use tracing::debug_span;
 use tree_hash::TreeHash;
 use types::ExecPayload;
 use types::{
@@ -611,14 +610,26 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
         block: SignedBeaconBlock<T::EthSpec>,
         chain: &BeaconChain<T>,
     ) -> Result<Self, BlockError<T::EthSpec>> {
-        // If the block is valid for gossip we don't supply it to the slasher here because
-        // we assume it will be transformed into a fully verified block. We *do* need to supply
-        // it to the slasher if an error occurs, because that's the end of this block's journey,
-        // and it could be a repeat proposal (a likely cause for slashing!).
+        // Every block observed on gossip is supplied to the slasher, whether or not it is valid,
+        // because it could be one half of a double proposal (a likely cause for slashing!).
+        //
+        // A block which is valid for gossip is supplied immediately rather than once it is fully
+        // verified, as it may never be imported, e.g. if its execution payload is invalid or it
+        // is dropped before processing. Supplying the same header again after full verification
+        // is harmless.
         let header = block.signed_block_header();
-        Self::new_without_slasher_checks(block, chain).map_err(|e| {
-            process_block_slash_info(chain, BlockSlashInfo::from_early_error(header, e))
-        })
+        match Self::new_without_slasher_checks(block, chain) {
+            Ok(verified) => {
+                if let Some(slasher) = chain.slasher.as_ref() {
+                    slasher.accept_block_header(header);
+                }
+                Ok(verified)
+            }
+            Err(e) => Err(process_block_slash_info(
+                chain,
+                BlockSlashInfo::from_early_error(header, e),
+            )),
+        }
     }
 
     /// As for new, but doesn't pass the block to the slasher.
@@ -634,7 +645,7 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
         // Do not gossip or process blocks from future slots.
         let present_slot_with_tolerance = chain
             .slot_clock
-            .now_with_future_tolerance(MAXIMUM_GOSSIP_CLOCK_DISPARITY)
+            .now_with_future_tolerance(chain.maximum_gossip_clock_disparity())
             .ok_or(BeaconChainError::UnableToReadSlot)?;
         if block.slot() > present_slot_with_tolerance {
             return Err(BlockError::FutureSlot {
@@ -853,6 +864,8 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
         block_root: Hash256,
         chain: &BeaconChain<T>,
     ) -> Result<Self, BlockError<T::EthSpec>> {
+        let _span = debug_span!("block_signature_verification").entered();
+
         // Ensure the block is the correct structure for the fork at `block.slot()`.
         block
             .fork_name(&chain.spec)
@@ -906,6 +919,8 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
         from: GossipVerifiedBlock<T>,
         chain: &BeaconChain<T>,
     ) -> Result<Self, BlockError<T::EthSpec>> {
+        let _span = debug_span!("block_signature_verification").entered();
+
         let (mut parent, block) = if let Some(parent) = from.parent {
             (parent, from.block)
         } else {
@@ -1252,7 +1267,15 @@ impl<'a, T: BeaconChainTypes> FullyVerifiedBlock<'a, T> {
         );
         write_block(&block, block_root, &chain.log);
 
+        // Keep a copy of the pre-state if it's to be saved when processing fails.
+        let crash_dump_pre_state = chain
+            .config
+            .crash_dump_dir
+            .as_ref()
+            .map(|_| state.clone_with(CloneConfig::none()));
+
         let core_timer = metrics::start_timer(&metrics::BLOCK_PROCESSING_CORE);
+        let core_span = debug_span!("block_state_transition").entered();
 
         if let Err(err) = per_block_processing(
             &mut state,
@@ -1263,6 +1286,18 @@ impl<'a, T: BeaconChainTypes> FullyVerifiedBlock<'a, T> {
             VerifyBlockRoot::True,
             &chain.spec,
         ) {
+            if let (Some(dump_dir), Some(pre_state)) =
+                (&chain.config.crash_dump_dir, &crash_dump_pre_state)
+            {
+                BlockProcessingFailure {
+                    block: &block,
+                    block_root,
+                    pre_state,
+                    post_state: None,
+                }
+                .save(dump_dir, &err, &chain.log);
+            }
+
             match err {
                 // Capture `BeaconStateError` so that we can easily distinguish between a block
                 // that's invalid and one that caused an internal error.
@@ -1271,6 +1306,7 @@ impl<'a, T: BeaconChainTypes> FullyVerifiedBlock<'a, T> {
             }
         };
 
+        drop(core_span);
         metrics::stop_timer(core_timer);
 
         /*
@@ -1294,10 +1330,24 @@ impl<'a, T: BeaconChainTypes> FullyVerifiedBlock<'a, T> {
          */
 
         if block.state_root() != state_root {
-            return Err(BlockError::StateRootMismatch {
+            let err = BlockError::StateRootMismatch {
                 block: block.state_root(),
                 local: state_root,
-            });
+            };
+
+            if let (Some(dump_dir), Some(pre_state)) =
+                (&chain.config.crash_dump_dir, &crash_dump_pre_state)
+            {
+                BlockProcessingFailure {
+                    block: &block,
+                    block_root,
+                    pre_state,
+                    post_state: Some(&state),
+                }
+                .save(dump_dir, &err, &chain.log);
+            }
+
+            return Err(err);
         }
 
         if valid_merge_transition_block {
@@ -25,6 +25,7 @@ use slasher::Slasher;
 use slog::{crit, error, info, Logger};
 use slot_clock::{SlotClock, TestingSlotClock};
 use std::marker::PhantomData;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::time::Duration;
 use store::{Error as StoreError, HotColdDB, ItemStore, KeyValueStoreOp};
@@ -540,11 +541,13 @@ where
         mut self,
         auto_register: bool,
         validators: Vec<PublicKeyBytes>,
+        individual_tracking_threshold: usize,
         log: Logger,
     ) -> Self {
         self.validator_monitor = Some(ValidatorMonitor::new(
             validators,
             auto_register,
+            individual_tracking_threshold,
             log.clone(),
         ));
         self
@@ -675,10 +678,28 @@ where
             }
         }
 
-        let validator_pubkey_cache = self.validator_pubkey_cache.map(Ok).unwrap_or_else(|| {
-            ValidatorPubkeyCache::new(&canonical_head.beacon_state, store.clone())
-                .map_err(|e| format!("Unable to init validator pubkey cache: {:?}", e))
-        })?;
+        let mut validator_pubkey_cache =
+            self.validator_pubkey_cache.map(Ok).unwrap_or_else(|| {
+                ValidatorPubkeyCache::new(&canonical_head.beacon_state, store.clone())
+                    .map_err(|e| format!("Unable to init validator pubkey cache: {:?}", e))
+            })?;
+
+        // Persist any keys of the head state which are missing from a persisted cache, e.g. one
+        // written by a previous version which didn't store keys atomically with blocks.
+        let (pubkey_cache_ops, pending_pubkeys) = validator_pubkey_cache
+            .import_new_pubkeys(&canonical_head.beacon_state)
+            .map_err(|e| format!("Unable to update validator pubkey cache: {:?}", e))?;
+        if !pending_pubkeys.is_empty() {
+            info!(
+                log,
+                "Repaired validator pubkey cache";
+                "imported_keys" => pending_pubkeys.len(),
+            );
+            store
+                .do_atomically(pubkey_cache_ops)
+                .map_err(|e| format!("Unable to persist validator pubkey cache: {:?}", e))?;
+            validator_pubkey_cache.commit_pubkeys(pending_pubkeys);
+        }
 
         let migrator_config = self.store_migrator_config.unwrap_or_default();
         let store_migrator = BackgroundMigrator::new(
@@ -692,6 +713,7 @@ where
             validator_monitor.process_valid_state(
                 slot.epoch(TEthSpec::slots_per_epoch()),
                 &canonical_head.beacon_state,
+                &self.spec,
             );
         }
 
@@ -756,6 +778,7 @@ where
             observed_voluntary_exits: <_>::default(),
             observed_proposer_slashings: <_>::default(),
             observed_attester_slashings: <_>::default(),
+            observed_bls_to_execution_changes: <_>::default(),
             eth1_chain: self.eth1_chain,
             execution_layer: self.execution_layer,
             genesis_validators_root: canonical_head.beacon_state.genesis_validators_root(),
@@ -775,6 +798,8 @@ where
             beacon_proposer_cache: <_>::default(),
             block_times_cache: <_>::default(),
             pre_finalization_block_cache: <_>::default(),
+            light_client_server_cache: <_>::default(),
+            pending_blobs: <_>::default(),
             validator_pubkey_cache: TimeoutRwLock::new(validator_pubkey_cache),
             attester_cache: <_>::default(),
             early_attester_cache: <_>::default(),
@@ -785,6 +810,7 @@ where
             graffiti: self.graffiti,
             slasher: self.slasher.clone(),
             validator_monitor: RwLock::new(validator_monitor),
+            memory_watermark_exceeded: AtomicBool::new(false),
         };
 
         let head = beacon_chain
@@ -932,6 +958,7 @@ fn descriptive_db_error(item: &str, error: &StoreError) -> String {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::validator_monitor::DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD;
     use eth2_hashing::hash;
     use genesis::{
         generate_deterministic_keypairs, interop_genesis_state, DEFAULT_ETH1_BLOCK_HASH,
@@ -988,7 +1015,12 @@ mod test {
             .testing_slot_clock(Duration::from_secs(1))
             .expect("should configure testing slot clock")
             .shutdown_sender(shutdown_tx)
-            .monitor_validators(true, vec![], log.clone())
+            .monitor_validators(
+                true,
+                vec![],
+                DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD,
+                log.clone(),
+            )
             .build()
             .expect("should build");
 
@@ -1,7 +1,9 @@
 use serde_derive::{Deserialize, Serialize};
+use std::path::PathBuf;
 use types::Checkpoint;
 
 pub const DEFAULT_FORK_CHOICE_BEFORE_PROPOSAL_TIMEOUT: u64 = 250;
+pub const DEFAULT_MAXIMUM_GOSSIP_CLOCK_DISPARITY_MS: u64 = 500;
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 pub struct ChainConfig {
@@ -16,6 +18,9 @@ pub struct ChainConfig {
     pub weak_subjectivity_checkpoint: Option<Checkpoint>,
     /// Determine whether to reconstruct historic states, usually after a checkpoint sync.
     pub reconstruct_historic_states: bool,
+    /// Determine whether to reconstruct the execution payloads of finalized blocks which are
+    /// missing from the database.
+    pub reconstruct_payloads: bool,
     /// Whether timeouts on `TimeoutRwLock`s are enabled or not.
     pub enable_lock_timeouts: bool,
     /// The max size of a message that can be sent over the network.
@@ -24,6 +29,22 @@ pub struct ChainConfig {
     ///
     /// If set to 0 then block proposal will not wait for fork choice at all.
     pub fork_choice_before_proposal_timeout_ms: u64,
+    /// Whether to compute the light client updates of imported blocks, so that they can be
+    /// verified and forwarded on gossip.
+    pub enable_light_client_server: bool,
+    /// The number of milliseconds by which the clocks of nodes gossiping blocks and attestations
+    /// are permitted to differ from our own.
+    pub maximum_gossip_clock_disparity_ms: u64,
+    /// The resident memory size, in bytes, above which the node sheds load by shrinking caches,
+    /// rejecting expensive API queries and pausing backfill sync.
+    ///
+    /// If `None`, memory usage is not monitored.
+    pub memory_watermark: Option<u64>,
+    /// A directory to which the block and states are saved when a block fails with a
+    /// consensus-level error, such as a state root mismatch.
+    ///
+    /// If `None`, failures are not saved.
+    pub crash_dump_dir: Option<PathBuf>,
 }
 
 impl Default for ChainConfig {
@@ -32,9 +53,14 @@ impl Default for ChainConfig {
             import_max_skip_slots: None,
             weak_subjectivity_checkpoint: None,
             reconstruct_historic_states: false,
+            reconstruct_payloads: false,
             enable_lock_timeouts: true,
             max_network_size: 10 * 1_048_576, // 10M
             fork_choice_before_proposal_timeout_ms: DEFAULT_FORK_CHOICE_BEFORE_PROPOSAL_TIMEOUT,
+            enable_light_client_server: false,
+            maximum_gossip_clock_disparity_ms: DEFAULT_MAXIMUM_GOSSIP_CLOCK_DISPARITY_MS,
+            memory_watermark: None,
+            crash_dump_dir: None,
         }
     }
 }
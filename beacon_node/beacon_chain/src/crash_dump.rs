@@ -0,0 +1,122 @@
+//! Saves the block and states involved in a consensus-level block processing failure, so that
+//! the failure can be reproduced from a user's report.
+//!
+//! Each failure is saved to its own directory, named by a correlation ID which is also logged:
+//!
+//! - `block.ssz`: the offending block.
+//! - `pre_state.ssz`: the state the block was applied to, advanced to the block's slot.
+//! - `post_state.ssz`: the state after applying the block, if processing completed.
+//! - `error.txt`: a description of the failure.
+use slog::{error, warn, Logger};
+use ssz::Encode;
+use std::fmt::Debug;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use types::{BeaconState, EthSpec, Hash256, SignedBeaconBlock};
+
+/// The maximum number of failures kept in the crash dump directory, to stop a stream of invalid
+/// blocks from filling the disk.
+pub const MAX_CRASH_DUMPS: usize = 16;
+
+pub const BLOCK_FILENAME: &str = "block.ssz";
+pub const PRE_STATE_FILENAME: &str = "pre_state.ssz";
+pub const POST_STATE_FILENAME: &str = "post_state.ssz";
+pub const ERROR_FILENAME: &str = "error.txt";
+
+/// The block and states involved in a block processing failure.
+pub struct BlockProcessingFailure<'a, E: EthSpec> {
+    pub block: &'a SignedBeaconBlock<E>,
+    pub block_root: Hash256,
+    pub pre_state: &'a BeaconState<E>,
+    pub post_state: Option<&'a BeaconState<E>>,
+}
+
+impl<'a, E: EthSpec> BlockProcessingFailure<'a, E> {
+    /// Save the failure to a new directory within `dump_dir`, logging its correlation ID.
+    ///
+    /// Errors are logged rather than returned, since they mustn't affect block processing.
+    pub fn save(&self, dump_dir: &Path, error: &impl Debug, log: &Logger) {
+        let correlation_id = self.correlation_id();
+        match self.write(dump_dir, &correlation_id, error) {
+            Ok(Some(path)) => error!(
+                log,
+                "Saved block processing failure";
+                "correlation_id" => &correlation_id,
+                "path" => ?path,
+                "block_root" => ?self.block_root,
+                "slot" => self.block.slot(),
+                "error" => ?error,
+                "info" => "please include these files when reporting this error",
+            ),
+            Ok(None) => warn!(
+                log,
+                "Not saving block processing failure";
+                "reason" => "too many crash dumps",
+                "path" => ?dump_dir,
+                "block_root" => ?self.block_root,
+            ),
+            Err(e) => error!(
+                log,
+                "Failed to save block processing failure";
+                "path" => ?dump_dir,
+                "block_root" => ?self.block_root,
+                "error" => e,
+            ),
+        }
+    }
+
+    /// A unique ID for the failure, from the time it occurred and the offending block root.
+    fn correlation_id(&self) -> String {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        let block_root = format!("{:x}", self.block_root);
+        format!("{}-{}", timestamp, &block_root[..8])
+    }
+
+    /// Write the failure to `dump_dir/correlation_id`, returning `None` if `dump_dir` is full.
+    fn write(
+        &self,
+        dump_dir: &Path,
+        correlation_id: &str,
+        error: &impl Debug,
+    ) -> Result<Option<PathBuf>, String> {
+        fs::create_dir_all(dump_dir)
+            .map_err(|e| format!("Unable to create {}: {:?}", dump_dir.display(), e))?;
+        let num_dumps = fs::read_dir(dump_dir)
+            .map_err(|e| format!("Unable to read {}: {:?}", dump_dir.display(), e))?
+            .count();
+        if num_dumps >= MAX_CRASH_DUMPS {
+            return Ok(None);
+        }
+
+        let path = dump_dir.join(correlation_id);
+        fs::create_dir_all(&path)
+            .map_err(|e| format!("Unable to create {}: {:?}", path.display(), e))?;
+        let write = |filename: &str, bytes: &[u8]| {
+            fs::write(path.join(filename), bytes)
+                .map_err(|e| format!("Unable to write {}: {:?}", filename, e))
+        };
+
+        write(
+            ERROR_FILENAME,
+            format!(
+                "version: {}\nslot: {}\nblock_root: {:?}\nerror: {:?}\n",
+                lighthouse_version::VERSION,
+                self.block.slot(),
+                self.block_root,
+                error
+            )
+            .as_bytes(),
+        )?;
+        write(BLOCK_FILENAME, &self.block.as_ssz_bytes())?;
+        write(PRE_STATE_FILENAME, &self.pre_state.as_ssz_bytes())?;
+        if let Some(post_state) = self.post_state {
+            write(POST_STATE_FILENAME, &post_state.as_ssz_bytes())?;
+        }
+
+        Ok(Some(path))
+    }
+}
@@ -170,8 +170,8 @@ fn get_sync_status<T: EthSpec>(
 
 #[derive(Encode, Decode, Clone)]
 pub struct SszEth1 {
-    use_dummy_backend: bool,
-    backend_bytes: Vec<u8>,
+    pub use_dummy_backend: bool,
+    pub backend_bytes: Vec<u8>,
 }
 
 impl StoreItem for SszEth1 {
@@ -263,6 +263,17 @@ where
         }
     }
 
+    /// Inform the backend that `eth1_data` is part of a finalized beacon state, allowing the
+    /// deposits it includes to be finalized.
+    ///
+    /// Only call this once all of the deposits in `eth1_data` have been included in the finalized
+    /// state, since proofs can not be produced for finalized deposits.
+    pub fn finalize_eth1_data(&self, eth1_data: Eth1Data) {
+        if !self.use_dummy_backend {
+            self.backend.finalize_eth1_data(eth1_data);
+        }
+    }
+
     /// Returns a status indicating how synced our caches are with the eth1 chain.
     pub fn sync_status(
         &self,
@@ -339,6 +350,9 @@ pub trait Eth1ChainBackend<T: EthSpec>: Sized + Send + Sync {
     /// an idea of how up-to-date the remote eth1 node is.
     fn head_block(&self) -> Option<Eth1Block>;
 
+    /// Finalize the deposits included in `eth1_data`, which is part of a finalized beacon state.
+    fn finalize_eth1_data(&self, eth1_data: Eth1Data);
+
     /// Encode the `Eth1ChainBackend` instance to bytes.
     fn as_bytes(&self) -> Vec<u8>;
 
@@ -393,6 +407,9 @@ impl<T: EthSpec> Eth1ChainBackend<T> for DummyEth1ChainBackend<T> {
         None
     }
 
+    /// The dummy back-end has no deposits to finalize.
+    fn finalize_eth1_data(&self, _: Eth1Data) {}
+
     /// Return empty Vec<u8> for dummy backend.
     fn as_bytes(&self) -> Vec<u8> {
         Vec::new()
@@ -561,6 +578,11 @@ impl<T: EthSpec> Eth1ChainBackend<T> for CachingEth1Backend<T> {
         self.core.head_block()
     }
 
+    /// Deposits are finalized by the eth1 service during its next update.
+    fn finalize_eth1_data(&self, eth1_data: Eth1Data) {
+        self.core.set_to_finalize(Some(eth1_data));
+    }
+
     /// Return encoded byte representation of the block and deposit caches.
     fn as_bytes(&self) -> Vec<u8> {
         self.core.as_bytes()
@@ -1,4 +1,4 @@
-pub use eth2::types::{EventKind, SseBlock, SseFinalizedCheckpoint, SseHead};
+pub use eth2::types::{EventKind, SseBlock, SseFinalizedCheckpoint, SseHead, SseSlasherSlashing};
 use slog::{trace, Logger};
 use tokio::sync::broadcast;
 use tokio::sync::broadcast::{error::SendError, Receiver, Sender};
@@ -16,6 +16,9 @@ pub struct ServerSentEventHandler<T: EthSpec> {
     contribution_tx: Sender<EventKind<T>>,
     late_head: Sender<EventKind<T>>,
     block_reward_tx: Sender<EventKind<T>>,
+    attester_duties_reorg_tx: Sender<EventKind<T>>,
+    proposer_duties_reorg_tx: Sender<EventKind<T>>,
+    slasher_slashing_tx: Sender<EventKind<T>>,
     log: Logger,
 }
 
@@ -34,6 +37,9 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
         let (contribution_tx, _) = broadcast::channel(capacity);
         let (late_head, _) = broadcast::channel(capacity);
         let (block_reward_tx, _) = broadcast::channel(capacity);
+        let (attester_duties_reorg_tx, _) = broadcast::channel(capacity);
+        let (proposer_duties_reorg_tx, _) = broadcast::channel(capacity);
+        let (slasher_slashing_tx, _) = broadcast::channel(capacity);
 
         Self {
             attestation_tx,
@@ -45,6 +51,9 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
             contribution_tx,
             late_head,
             block_reward_tx,
+            attester_duties_reorg_tx,
+            proposer_duties_reorg_tx,
+            slasher_slashing_tx,
             log,
         }
     }
@@ -70,6 +79,12 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
                 .map(|count| trace!(self.log, "Registering server-sent contribution and proof event"; "receiver_count" => count)),
             EventKind::LateHead(late_head) => self.late_head.send(EventKind::LateHead(late_head))
                 .map(|count| trace!(self.log, "Registering server-sent late head event"; "receiver_count" => count)),
+            EventKind::AttesterDutiesReorg(reorg) => self.attester_duties_reorg_tx.send(EventKind::AttesterDutiesReorg(reorg))
+                .map(|count| trace!(self.log, "Registering server-sent attester duties reorg event"; "receiver_count" => count)),
+            EventKind::ProposerDutiesReorg(reorg) => self.proposer_duties_reorg_tx.send(EventKind::ProposerDutiesReorg(reorg))
+                .map(|count| trace!(self.log, "Registering server-sent proposer duties reorg event"; "receiver_count" => count)),
+            EventKind::SlasherSlashing(slashing) => self.slasher_slashing_tx.send(EventKind::SlasherSlashing(slashing))
+                .map(|count| trace!(self.log, "Registering server-sent slasher slashing event"; "receiver_count" => count)),
             EventKind::BlockReward(block_reward) => self.block_reward_tx.send(EventKind::BlockReward(block_reward))
                 .map(|count| trace!(self.log, "Registering server-sent contribution and proof event"; "receiver_count" => count)),
         };
@@ -114,6 +129,18 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
         self.block_reward_tx.subscribe()
     }
 
+    pub fn subscribe_attester_duties_reorg(&self) -> Receiver<EventKind<T>> {
+        self.attester_duties_reorg_tx.subscribe()
+    }
+
+    pub fn subscribe_proposer_duties_reorg(&self) -> Receiver<EventKind<T>> {
+        self.proposer_duties_reorg_tx.subscribe()
+    }
+
+    pub fn subscribe_slasher_slashing(&self) -> Receiver<EventKind<T>> {
+        self.slasher_slashing_tx.subscribe()
+    }
+
     pub fn has_attestation_subscribers(&self) -> bool {
         self.attestation_tx.receiver_count() > 0
     }
@@ -149,4 +176,16 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
     pub fn has_block_reward_subscribers(&self) -> bool {
         self.block_reward_tx.receiver_count() > 0
     }
+
+    pub fn has_attester_duties_reorg_subscribers(&self) -> bool {
+        self.attester_duties_reorg_tx.receiver_count() > 0
+    }
+
+    pub fn has_proposer_duties_reorg_subscribers(&self) -> bool {
+        self.proposer_duties_reorg_tx.receiver_count() > 0
+    }
+
+    pub fn has_slasher_slashing_subscribers(&self) -> bool {
+        self.slasher_slashing_tx.receiver_count() > 0
+    }
 }
@@ -11,16 +11,17 @@ use crate::{
     BeaconChain, BeaconChainError, BeaconChainTypes, BlockError, BlockProductionError,
     ExecutionPayloadError,
 };
-use execution_layer::PayloadStatus;
+use execution_layer::{BlockProposalContents, PayloadStatus};
 use fork_choice::{InvalidationOperation, PayloadVerificationStatus};
 use proto_array::{Block as ProtoBlock, ExecutionStatus};
 use slog::debug;
 use slot_clock::SlotClock;
 use state_processing::per_block_processing::{
-    compute_timestamp_at_slot, is_execution_enabled, is_merge_transition_complete,
-    partially_verify_execution_payload,
+    compute_timestamp_at_slot, get_expected_withdrawals, is_execution_enabled,
+    is_merge_transition_complete, partially_verify_execution_payload,
 };
 use std::sync::Arc;
+use tracing::debug_span;
 use types::*;
 
 /// Verify that `execution_payload` contained by `block` is considered valid by an execution
@@ -41,6 +42,8 @@ pub fn notify_new_payload<T: BeaconChainTypes>(
         return Ok(PayloadVerificationStatus::Irrelevant);
     }
 
+    let _span = debug_span!("block_payload_notify").entered();
+
     let execution_payload = block.execution_payload()?;
 
     // Perform the initial stages of payload verification.
@@ -50,12 +53,29 @@ pub fn notify_new_payload<T: BeaconChainTypes>(
     partially_verify_execution_payload(state, execution_payload, &chain.spec)
         .map_err(BlockError::PerBlockProcessingError)?;
 
+    // From Deneb the execution engine also verifies the blob transactions against the versioned
+    // hashes of the block's KZG commitments.
+    let versioned_hashes = block
+        .body()
+        .blob_kzg_commitments()
+        .map(|commitments| {
+            commitments
+                .iter()
+                .map(KzgCommitment::calculate_versioned_hash)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
     let execution_layer = chain
         .execution_layer
         .as_ref()
         .ok_or(ExecutionPayloadError::NoExecutionConnection)?;
     let new_payload_response = execution_layer.block_on(|execution_layer| {
-        execution_layer.notify_new_payload(&execution_payload.execution_payload)
+        execution_layer.notify_new_payload(
+            &execution_payload.execution_payload,
+            versioned_hashes,
+            block.parent_root(),
+        )
     });
 
     match new_payload_response {
@@ -207,7 +227,7 @@ pub fn validate_execution_payload_for_gossip<T: BeaconChainTypes>(
             }
         };
 
-        if is_merge_transition_complete || execution_payload != &<_>::default() {
+        if is_merge_transition_complete || !execution_payload.is_default() {
             let expected_timestamp = chain
                 .slot_clock
                 .start_of(block.slot())
@@ -233,6 +253,8 @@ pub fn validate_execution_payload_for_gossip<T: BeaconChainTypes>(
 
 /// Gets an execution payload for inclusion in a block.
 ///
+/// The default payload for the fork of `state` is returned if a terminal block has not been found.
+///
 /// ## Errors
 ///
 /// Will return an error when using a pre-merge fork `state`. Ensure to only run this function
@@ -243,23 +265,31 @@ pub fn validate_execution_payload_for_gossip<T: BeaconChainTypes>(
 /// Equivalent to the `get_execution_payload` function in the Validator Guide:
 ///
 /// https://github.com/ethereum/consensus-specs/blob/v1.1.5/specs/merge/validator.md#block-proposal
-pub fn get_execution_payload<T: BeaconChainTypes, Payload: ExecPayload<T::EthSpec>>(
+pub fn get_execution_payload<T: BeaconChainTypes, Payload: AbstractExecPayload<T::EthSpec>>(
     chain: &BeaconChain<T>,
     state: &BeaconState<T::EthSpec>,
     proposer_index: u64,
-) -> Result<Payload, BlockProductionError> {
-    Ok(
-        prepare_execution_payload_blocking::<T, Payload>(chain, state, proposer_index)?
-            .unwrap_or_default(),
-    )
+) -> Result<BlockProposalContents<T::EthSpec, Payload>, BlockProductionError> {
+    let _span = debug_span!("block_production_payload").entered();
+
+    match prepare_execution_payload_blocking::<T, Payload>(chain, state, proposer_index)? {
+        Some(block_proposal_contents) => Ok(block_proposal_contents),
+        None => {
+            let fork_name = chain.spec.fork_name_at_slot::<T::EthSpec>(state.slot());
+            Ok(BlockProposalContents::default_at_fork(fork_name)?)
+        }
+    }
 }
 
 /// Wraps the async `prepare_execution_payload` function as a blocking task.
-pub fn prepare_execution_payload_blocking<T: BeaconChainTypes, Payload: ExecPayload<T::EthSpec>>(
+pub fn prepare_execution_payload_blocking<
+    T: BeaconChainTypes,
+    Payload: AbstractExecPayload<T::EthSpec>,
+>(
     chain: &BeaconChain<T>,
     state: &BeaconState<T::EthSpec>,
     proposer_index: u64,
-) -> Result<Option<Payload>, BlockProductionError> {
+) -> Result<Option<BlockProposalContents<T::EthSpec, Payload>>, BlockProductionError> {
     let execution_layer = chain
         .execution_layer
         .as_ref()
@@ -286,11 +316,14 @@ pub fn prepare_execution_payload_blocking<T: BeaconChainTypes, Payload: ExecPayl
 /// Equivalent to the `prepare_execution_payload` function in the Validator Guide:
 ///
 /// https://github.com/ethereum/consensus-specs/blob/v1.1.5/specs/merge/validator.md#block-proposal
-pub async fn prepare_execution_payload<T: BeaconChainTypes, Payload: ExecPayload<T::EthSpec>>(
+pub async fn prepare_execution_payload<
+    T: BeaconChainTypes,
+    Payload: AbstractExecPayload<T::EthSpec>,
+>(
     chain: &BeaconChain<T>,
     state: &BeaconState<T::EthSpec>,
     proposer_index: u64,
-) -> Result<Option<Payload>, BlockProductionError> {
+) -> Result<Option<BlockProposalContents<T::EthSpec, Payload>>, BlockProductionError> {
     let spec = &chain.spec;
     let execution_layer = chain
         .execution_layer
@@ -317,7 +350,7 @@ pub async fn prepare_execution_payload<T: BeaconChainTypes, Payload: ExecPayload
             return Ok(None);
         }
     } else {
-        state.latest_execution_payload_header()?.block_hash
+        state.latest_execution_payload_header()?.block_hash()
     };
 
     let timestamp = compute_timestamp_at_slot(state, spec).map_err(BeaconStateError::from)?;
@@ -343,17 +376,36 @@ pub async fn prepare_execution_payload<T: BeaconChainTypes, Payload: ExecPayload
                 .map(|ep| ep.block_hash())
         };
 
+    // From Capella onwards the payload must contain exactly the withdrawals expected by `state`.
+    let withdrawals = match state {
+        BeaconState::Base(_) | BeaconState::Altair(_) | BeaconState::Merge(_) => None,
+        BeaconState::Capella(_) | BeaconState::Deneb(_) => {
+            Some(get_expected_withdrawals(state, spec)?.to_vec())
+        }
+    };
+
+    // From Deneb onwards the payload is built with knowledge of the parent beacon block root.
+    let parent_beacon_block_root = match state {
+        BeaconState::Base(_)
+        | BeaconState::Altair(_)
+        | BeaconState::Merge(_)
+        | BeaconState::Capella(_) => None,
+        BeaconState::Deneb(_) => Some(state.latest_block_header().canonical_root()),
+    };
+
     // Note: the suggested_fee_recipient is stored in the `execution_layer`, it will add this parameter.
-    let execution_payload = execution_layer
+    let block_proposal_contents = execution_layer
         .get_payload::<T::EthSpec, Payload>(
             parent_hash,
             timestamp,
             random,
             finalized_block_hash.unwrap_or_else(ExecutionBlockHash::zero),
             proposer_index,
+            withdrawals,
+            parent_beacon_block_root,
         )
         .await
         .map_err(BlockProductionError::GetPayloadFailed)?;
 
-    Ok(Some(execution_payload))
+    Ok(Some(block_proposal_contents))
 }
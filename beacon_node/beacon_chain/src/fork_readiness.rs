@@ -0,0 +1,237 @@
+//! Checks whether the node is ready for the next scheduled fork, so that problems can be fixed
+//! before the fork rather than after.
+use crate::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{ForkReadiness, ReadinessCheck, ReadinessStatus};
+use execution_layer::http::{
+    ENGINE_FORKCHOICE_UPDATED_V1, ENGINE_FORKCHOICE_UPDATED_V2, ENGINE_FORKCHOICE_UPDATED_V3,
+    ENGINE_GET_PAYLOAD_V1, ENGINE_GET_PAYLOAD_V2, ENGINE_GET_PAYLOAD_V3, ENGINE_NEW_PAYLOAD_V1,
+    ENGINE_NEW_PAYLOAD_V2, ENGINE_NEW_PAYLOAD_V3,
+};
+use slot_clock::SlotClock;
+use types::{ChainSpec, Epoch, EthSpec, ForkName};
+
+/// Readiness for a fork is logged from this long before the fork.
+pub const FORK_READINESS_PREPARATION_SECONDS: u64 = 60 * 60 * 24 * 3;
+
+pub const CONFIG_CHECK: &str = "config";
+pub const EXECUTION_ENGINE_CHECK: &str = "execution_engine";
+pub const ENGINE_API_CHECK: &str = "engine_api";
+
+/// Returns the engine API methods the execution engine must support from `fork`.
+pub fn required_engine_methods(fork: ForkName) -> &'static [&'static str] {
+    match fork {
+        ForkName::Base | ForkName::Altair => &[],
+        ForkName::Merge => &[
+            ENGINE_NEW_PAYLOAD_V1,
+            ENGINE_GET_PAYLOAD_V1,
+            ENGINE_FORKCHOICE_UPDATED_V1,
+        ],
+        ForkName::Capella => &[
+            ENGINE_NEW_PAYLOAD_V2,
+            ENGINE_GET_PAYLOAD_V2,
+            ENGINE_FORKCHOICE_UPDATED_V2,
+        ],
+        ForkName::Deneb => &[
+            ENGINE_NEW_PAYLOAD_V3,
+            ENGINE_GET_PAYLOAD_V3,
+            ENGINE_FORKCHOICE_UPDATED_V3,
+        ],
+    }
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Returns the next fork scheduled after the current slot, and its epoch.
+    pub fn next_scheduled_fork(&self) -> Option<(ForkName, Epoch)> {
+        let current_slot = self.slot_clock.now_or_genesis()?;
+        self.spec.next_fork_epoch::<T::EthSpec>(current_slot)
+    }
+
+    /// Check the readiness of the node for the next scheduled fork, returning `None` if no fork is
+    /// scheduled.
+    pub async fn check_fork_readiness(&self) -> Option<ForkReadiness> {
+        let (fork, fork_epoch) = self.next_scheduled_fork()?;
+        let fork_slot = fork_epoch.start_slot(T::EthSpec::slots_per_epoch());
+        let seconds_until_fork = self
+            .slot_clock
+            .duration_to_slot(fork_slot)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut checks = vec![check_config(fork, fork_epoch, &self.spec)];
+        if !required_engine_methods(fork).is_empty() {
+            checks.extend(self.check_execution_engine(fork).await);
+        }
+
+        Some(ForkReadiness {
+            fork,
+            fork_epoch,
+            seconds_until_fork,
+            ready: checks
+                .iter()
+                .all(|check| check.status != ReadinessStatus::NotReady),
+            checks,
+        })
+    }
+
+    /// Check that the execution engine is online and supports the engine API methods of `fork`.
+    async fn check_execution_engine(&self, fork: ForkName) -> Vec<ReadinessCheck> {
+        let execution_layer = if let Some(execution_layer) = &self.execution_layer {
+            execution_layer
+        } else {
+            return vec![ReadinessCheck {
+                name: EXECUTION_ENGINE_CHECK.to_string(),
+                status: ReadinessStatus::NotReady,
+                info: format!(
+                    "No execution endpoint is configured, which is required from the {} fork",
+                    fork
+                ),
+            }];
+        };
+
+        let synced = if execution_layer.is_synced().await {
+            ReadinessCheck {
+                name: EXECUTION_ENGINE_CHECK.to_string(),
+                status: ReadinessStatus::Ready,
+                info: "The execution engine is online and synced".to_string(),
+            }
+        } else {
+            ReadinessCheck {
+                name: EXECUTION_ENGINE_CHECK.to_string(),
+                status: ReadinessStatus::NotReady,
+                info: "The execution engine is offline or syncing".to_string(),
+            }
+        };
+
+        let (status, info) = match execution_layer.get_engine_capabilities().await {
+            Ok(Some(capabilities)) => {
+                let missing = required_engine_methods(fork)
+                    .iter()
+                    .filter(|method| !capabilities.contains(**method))
+                    .copied()
+                    .collect::<Vec<_>>();
+                if missing.is_empty() {
+                    (
+                        ReadinessStatus::Ready,
+                        format!(
+                            "The execution engine supports the engine API methods of the {} fork",
+                            fork
+                        ),
+                    )
+                } else {
+                    (
+                        ReadinessStatus::NotReady,
+                        format!(
+                            "The execution engine doesn't support {}, update it to a release \
+                             which supports the {} fork",
+                            missing.join(", "),
+                            fork
+                        ),
+                    )
+                }
+            }
+            Ok(None) => (
+                ReadinessStatus::Unknown,
+                "The execution engine doesn't support engine_exchangeCapabilities, check that it's \
+                 a release which supports the fork"
+                    .to_string(),
+            ),
+            Err(e) => (
+                ReadinessStatus::Unknown,
+                format!(
+                    "Unable to query the capabilities of the execution engine: {:?}",
+                    e
+                ),
+            ),
+        };
+
+        vec![
+            synced,
+            ReadinessCheck {
+                name: ENGINE_API_CHECK.to_string(),
+                status,
+                info,
+            },
+        ]
+    }
+}
+
+/// Check that `fork` is scheduled after all of the forks before it, with a distinct fork version.
+///
+/// A mistake in either would cause the node to follow a different chain to its peers after the
+/// fork.
+fn check_config(fork: ForkName, fork_epoch: Epoch, spec: &ChainSpec) -> ReadinessCheck {
+    let fork_version = spec.fork_version_for_name(fork);
+
+    let mut problems = vec![];
+    let mut previous = fork.previous_fork();
+    while let Some(previous_fork) = previous {
+        match spec.fork_epoch(previous_fork) {
+            Some(epoch) if epoch <= fork_epoch => (),
+            _ => problems.push(format!(
+                "the {} fork isn't scheduled before epoch {}",
+                previous_fork, fork_epoch
+            )),
+        }
+        if spec.fork_version_for_name(previous_fork) == fork_version {
+            problems.push(format!(
+                "the fork version 0x{} is also used by the {} fork",
+                hex::encode(fork_version),
+                previous_fork
+            ));
+        }
+        previous = previous_fork.previous_fork();
+    }
+
+    if problems.is_empty() {
+        ReadinessCheck {
+            name: CONFIG_CHECK.to_string(),
+            status: ReadinessStatus::Ready,
+            info: format!(
+                "The {} fork is scheduled for epoch {} with fork version 0x{}",
+                fork,
+                fork_epoch,
+                hex::encode(fork_version)
+            ),
+        }
+    } else {
+        ReadinessCheck {
+            name: CONFIG_CHECK.to_string(),
+            status: ReadinessStatus::NotReady,
+            info: format!(
+                "The network config is inconsistent: {}. Update Lighthouse or check the \
+                 --testnet-dir",
+                problems.join(", ")
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_check() {
+        let fork_epoch = Epoch::new(1_000_000);
+        let spec = ChainSpec::mainnet();
+        assert_eq!(
+            check_config(ForkName::Capella, fork_epoch, &spec).status,
+            ReadinessStatus::Ready
+        );
+
+        // The Capella fork can't happen before the Bellatrix fork.
+        let mut spec = ChainSpec::mainnet();
+        spec.bellatrix_fork_epoch = Some(fork_epoch + 1);
+        assert_eq!(
+            check_config(ForkName::Capella, fork_epoch, &spec).status,
+            ReadinessStatus::NotReady
+        );
+
+        let mut spec = ChainSpec::mainnet();
+        spec.capella_fork_version = spec.bellatrix_fork_version;
+        assert_eq!(
+            check_config(ForkName::Capella, fork_epoch, &spec).status,
+            ReadinessStatus::NotReady
+        );
+    }
+}
@@ -8,7 +8,7 @@ use state_processing::{
 use std::borrow::Cow;
 use std::iter;
 use std::time::Duration;
-use store::{chunked_vector::BlockRoots, AnchorInfo, ChunkWriter, KeyValueStore};
+use store::{chunked_vector::BlockRoots, AnchorInfo, ChunkWriter, KeyValueStore, StoreOp};
 use types::{Hash256, SignedBlindedBeaconBlock, Slot};
 
 /// Use a longer timeout on the pubkey cache.
@@ -93,6 +93,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         let mut cold_batch = Vec::with_capacity(blocks.len());
         let mut hot_batch = Vec::with_capacity(blocks.len());
+        let mut blob_batch = vec![];
 
         for block in blocks_to_import.iter().rev() {
             // Check chain integrity.
@@ -109,6 +110,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             // Store block in the hot database without payload.
             self.store
                 .blinded_block_as_kv_store_ops(&block_root, block, &mut hot_batch);
+            if let Some(blobs) = self.pending_blobs.take_downloaded_sidecars(block_root) {
+                blob_batch.push(StoreOp::PutBlobs(block_root, blobs));
+            }
 
             // Store block roots, including at all skip slots in the freezer DB.
             for slot in (block.slot().as_usize()..prev_block_slot.as_usize()).rev() {
@@ -179,6 +183,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // Write the I/O batches to disk, writing the blocks themselves first, as it's better
         // for the hot DB to contain extra blocks than for the cold DB to point to blocks that
         // do not exist.
+        self.store.do_atomically(blob_batch)?;
         self.store.hot_db.do_atomically(hot_batch)?;
         self.store.cold_db.do_atomically(cold_batch)?;
 
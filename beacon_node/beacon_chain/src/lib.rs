@@ -5,20 +5,26 @@ mod beacon_chain;
 mod beacon_fork_choice_store;
 pub mod beacon_proposer_cache;
 mod beacon_snapshot;
+pub mod blob_verification;
 pub mod block_reward;
 mod block_times_cache;
 mod block_verification;
 pub mod builder;
 pub mod chain_config;
+pub mod crash_dump;
 mod early_attester_cache;
 mod errors;
 pub mod eth1_chain;
 pub mod events;
 mod execution_payload;
 pub mod fork_choice_signal;
+pub mod fork_readiness;
 pub mod fork_revert;
 mod head_tracker;
 pub mod historical_blocks;
+pub mod light_client_server_cache;
+pub mod light_client_update_verification;
+pub mod memory_watermark;
 mod metrics;
 pub mod migrate;
 mod naive_aggregation_pool;
@@ -26,6 +32,8 @@ mod observed_aggregates;
 mod observed_attesters;
 mod observed_block_producers;
 pub mod observed_operations;
+pub mod payload_reconstruction_service;
+mod pending_blobs_cache;
 mod persisted_beacon_chain;
 mod persisted_fork_choice;
 mod pre_finalization_cache;
@@ -54,6 +62,7 @@ pub use beacon_fork_choice_store::{BeaconForkChoiceStore, Error as ForkChoiceSto
 pub use block_verification::{BlockError, ExecutionPayloadError, GossipVerifiedBlock};
 pub use eth1_chain::{Eth1Chain, Eth1ChainBackend};
 pub use events::ServerSentEventHandler;
+pub use light_client_update_verification::Error as LightClientUpdateError;
 pub use metrics::scrape_for_metrics;
 pub use parking_lot;
 pub use slot_clock;
@@ -0,0 +1,166 @@
+//! Computes the light client updates published on gossip from the blocks imported by the chain.
+//!
+//! A `LightClientFinalityUpdate` or `LightClientOptimisticUpdate` signed in some block attests to
+//! the block's parent. Rather than loading the parent's state when the child is imported, the
+//! small parts of each block's post-state required for updates are cached as the block is imported.
+//!
+//! The latest updates are also used to verify updates received on gossip, so that they only need
+//! to be computed once rather than for each peer which forwards an update.
+
+use crate::{metrics, BeaconChainError, BeaconChainTypes, BeaconStore};
+use lru::LruCache;
+use parking_lot::{Mutex, RwLock};
+use types::consts::altair::MIN_SYNC_COMMITTEE_PARTICIPANTS;
+use types::light_client_finality_update::{FinalizedRootProofLen, FINALIZED_ROOT_INDEX};
+use types::{
+    BeaconBlock, BeaconBlockHeader, BeaconState, FixedVector, Hash256, LightClientFinalityUpdate,
+    LightClientOptimisticUpdate, Slot,
+};
+
+/// The number of recent blocks for which the data required to produce updates is kept.
+///
+/// Updates can only be produced for a block while the data of its parent is cached, so this only
+/// needs to cover the blocks of short re-orgs.
+const PREV_BLOCK_CACHE_SIZE: usize = 32;
+
+/// The parts of a block and its post-state needed to produce updates when a child of the block is
+/// imported.
+struct LightClientCachedData {
+    header: BeaconBlockHeader,
+    finalized_block_root: Hash256,
+    finality_branch: FixedVector<Hash256, FinalizedRootProofLen>,
+}
+
+/// Caches the latest light client updates computed from imported blocks.
+pub struct LightClientServerCache<T: BeaconChainTypes> {
+    latest_finality_update: RwLock<Option<LightClientFinalityUpdate<T::EthSpec>>>,
+    latest_optimistic_update: RwLock<Option<LightClientOptimisticUpdate<T::EthSpec>>>,
+    /// The `finalized_header.slot` of the latest finality update forwarded on gossip.
+    forwarded_finalized_slot: Mutex<Option<Slot>>,
+    /// The `attested_header.slot` of the latest optimistic update forwarded on gossip.
+    forwarded_attested_slot: Mutex<Option<Slot>>,
+    prev_block_cache: Mutex<LruCache<Hash256, LightClientCachedData>>,
+}
+
+impl<T: BeaconChainTypes> Default for LightClientServerCache<T> {
+    fn default() -> Self {
+        Self {
+            latest_finality_update: RwLock::new(None),
+            latest_optimistic_update: RwLock::new(None),
+            forwarded_finalized_slot: Mutex::new(None),
+            forwarded_attested_slot: Mutex::new(None),
+            prev_block_cache: Mutex::new(LruCache::new(PREV_BLOCK_CACHE_SIZE)),
+        }
+    }
+}
+
+impl<T: BeaconChainTypes> LightClientServerCache<T> {
+    /// Caches the data of the imported `block` and its post-`state`, then recomputes the latest
+    /// updates if `block` carries a sync aggregate over its parent.
+    pub fn recompute_and_cache_updates(
+        &self,
+        store: &BeaconStore<T>,
+        block_root: Hash256,
+        block: &BeaconBlock<T::EthSpec>,
+        state: &mut BeaconState<T::EthSpec>,
+    ) -> Result<(), BeaconChainError> {
+        let sync_aggregate = match block.body().sync_aggregate() {
+            Ok(sync_aggregate) => sync_aggregate,
+            // There are no updates prior to Altair.
+            Err(_) => return Ok(()),
+        };
+        let _timer = metrics::start_timer(&metrics::LIGHT_CLIENT_SERVER_CACHE_RECOMPUTE_TIMES);
+
+        let finality_branch = state.compute_merkle_proof(FINALIZED_ROOT_INDEX)?;
+        self.prev_block_cache.lock().put(
+            block_root,
+            LightClientCachedData {
+                header: block.block_header(),
+                finalized_block_root: state.finalized_checkpoint().root,
+                finality_branch: finality_branch.into(),
+            },
+        );
+
+        if (sync_aggregate.num_set_bits() as u64) < MIN_SYNC_COMMITTEE_PARTICIPANTS {
+            return Ok(());
+        }
+
+        // The sync aggregate is signed over the block at the previous slot, which is the parent.
+        let mut prev_block_cache = self.prev_block_cache.lock();
+        let attested = match prev_block_cache.get(&block.parent_root()) {
+            Some(attested) => attested,
+            None => return Ok(()),
+        };
+        let signature_slot = block.slot();
+
+        let mut latest_optimistic_update = self.latest_optimistic_update.write();
+        let is_latest_optimistic = latest_optimistic_update.as_ref().map_or(true, |update| {
+            attested.header.slot > update.attested_header.slot
+        });
+        if is_latest_optimistic {
+            *latest_optimistic_update = Some(LightClientOptimisticUpdate {
+                attested_header: attested.header.clone(),
+                sync_aggregate: sync_aggregate.clone(),
+                signature_slot,
+            });
+        }
+        drop(latest_optimistic_update);
+
+        // The finalized root is zero until the first checkpoint is finalized.
+        if attested.finalized_block_root.is_zero() {
+            return Ok(());
+        }
+        let mut latest_finality_update = self.latest_finality_update.write();
+        let is_latest_finality = latest_finality_update.as_ref().map_or(true, |update| {
+            attested.header.slot > update.attested_header.slot
+        });
+        if is_latest_finality {
+            let finalized_header = store
+                .get_blinded_block(&attested.finalized_block_root)?
+                .ok_or(BeaconChainError::MissingBeaconBlock(
+                    attested.finalized_block_root,
+                ))?
+                .message()
+                .block_header();
+            *latest_finality_update = Some(LightClientFinalityUpdate {
+                attested_header: attested.header.clone(),
+                finalized_header,
+                finality_branch: attested.finality_branch.clone(),
+                sync_aggregate: sync_aggregate.clone(),
+                signature_slot,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the finality update computed from the latest imported block.
+    pub fn get_latest_finality_update(&self) -> Option<LightClientFinalityUpdate<T::EthSpec>> {
+        self.latest_finality_update.read().clone()
+    }
+
+    /// Returns the optimistic update computed from the latest imported block.
+    pub fn get_latest_optimistic_update(&self) -> Option<LightClientOptimisticUpdate<T::EthSpec>> {
+        self.latest_optimistic_update.read().clone()
+    }
+
+    /// Records that a finality update for `finalized_slot` is being forwarded on gossip. Returns
+    /// `false` if an update for the same or a later finalized slot has already been forwarded.
+    pub(crate) fn observe_forwarded_finality_update(&self, finalized_slot: Slot) -> bool {
+        Self::observe_forwarded(&mut self.forwarded_finalized_slot.lock(), finalized_slot)
+    }
+
+    /// Records that an optimistic update for `attested_slot` is being forwarded on gossip. Returns
+    /// `false` if an update for the same or a later attested slot has already been forwarded.
+    pub(crate) fn observe_forwarded_optimistic_update(&self, attested_slot: Slot) -> bool {
+        Self::observe_forwarded(&mut self.forwarded_attested_slot.lock(), attested_slot)
+    }
+
+    fn observe_forwarded(forwarded_slot: &mut Option<Slot>, slot: Slot) -> bool {
+        if forwarded_slot.map_or(false, |forwarded_slot| slot <= forwarded_slot) {
+            return false;
+        }
+        *forwarded_slot = Some(slot);
+        true
+    }
+}
@@ -0,0 +1,187 @@
+//! Provides verification for the light client updates received on gossip:
+//!
+//! - `LightClientFinalityUpdate`
+//! - `LightClientOptimisticUpdate`
+//!
+//! An update is only forwarded if it matches the latest update computed from the blocks we have
+//! imported (see `crate::light_client_server_cache`), so no signatures need to be verified and
+//! the same update received from many peers is only computed once.
+
+use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use derivative::Derivative;
+use slot_clock::SlotClock;
+use std::time::Duration;
+use strum::AsRefStr;
+use types::{
+    consts::merge::INTERVALS_PER_SLOT, LightClientFinalityUpdate, LightClientOptimisticUpdate, Slot,
+};
+
+/// Returned when a light client update was not successfully verified.
+///
+/// None of these errors indicate a faulty peer, so they all result in the update being ignored.
+#[derive(Debug, AsRefStr)]
+pub enum Error {
+    /// The update was received before one third of its `signature_slot` had elapsed, so the block
+    /// at that slot may not have been seen by the network yet.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The peer's clock may be ahead of ours.
+    TooEarly,
+    /// An update for the same or a later slot has already been forwarded.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The update may be valid, it's just no longer new.
+    PriorUpdateForwarded,
+    /// The update doesn't match the latest update computed from the blocks we have imported.
+    ///
+    /// ## Peer scoring
+    ///
+    /// We may not have imported the block at `signature_slot` yet.
+    MismatchedLocalUpdate,
+    /// No update has been computed from the blocks we have imported.
+    ///
+    /// ## Peer scoring
+    ///
+    /// We may be syncing or may not be serving light client data.
+    NoLocalUpdate,
+    /// The start time of `signature_slot` could not be determined.
+    ///
+    /// ## Peer scoring
+    ///
+    /// We were unable to process this update due to an internal error.
+    SigSlotStartIsNone,
+    /// There was an error whilst processing the update.
+    ///
+    /// ## Peer scoring
+    ///
+    /// We were unable to process this update due to an internal error.
+    BeaconChainError(BeaconChainError),
+}
+
+impl From<BeaconChainError> for Error {
+    fn from(e: BeaconChainError) -> Self {
+        Error::BeaconChainError(e)
+    }
+}
+
+/// A `LightClientFinalityUpdate` that has been verified for propagation on the gossip network.
+#[derive(Derivative)]
+#[derivative(Clone(bound = "T: BeaconChainTypes"))]
+pub struct VerifiedLightClientFinalityUpdate<T: BeaconChainTypes> {
+    update: LightClientFinalityUpdate<T::EthSpec>,
+    seen_timestamp: Duration,
+}
+
+impl<T: BeaconChainTypes> VerifiedLightClientFinalityUpdate<T> {
+    /// Returns `Ok(Self)` if the `update` is valid to be (re)published on the gossip network.
+    pub fn verify(
+        update: LightClientFinalityUpdate<T::EthSpec>,
+        chain: &BeaconChain<T>,
+        seen_timestamp: Duration,
+    ) -> Result<Self, Error> {
+        verify_signature_slot_elapsed(update.signature_slot, chain, seen_timestamp)?;
+
+        let local_update = chain
+            .light_client_server_cache
+            .get_latest_finality_update()
+            .ok_or(Error::NoLocalUpdate)?;
+        if update != local_update {
+            return Err(Error::MismatchedLocalUpdate);
+        }
+
+        if !chain
+            .light_client_server_cache
+            .observe_forwarded_finality_update(update.finalized_header.slot)
+        {
+            return Err(Error::PriorUpdateForwarded);
+        }
+
+        Ok(Self {
+            update,
+            seen_timestamp,
+        })
+    }
+
+    /// Returns the verified update.
+    pub fn update(&self) -> &LightClientFinalityUpdate<T::EthSpec> {
+        &self.update
+    }
+
+    /// Returns the time at which the update was received.
+    pub fn seen_timestamp(&self) -> Duration {
+        self.seen_timestamp
+    }
+}
+
+/// A `LightClientOptimisticUpdate` that has been verified for propagation on the gossip network.
+#[derive(Derivative)]
+#[derivative(Clone(bound = "T: BeaconChainTypes"))]
+pub struct VerifiedLightClientOptimisticUpdate<T: BeaconChainTypes> {
+    update: LightClientOptimisticUpdate<T::EthSpec>,
+    seen_timestamp: Duration,
+}
+
+impl<T: BeaconChainTypes> VerifiedLightClientOptimisticUpdate<T> {
+    /// Returns `Ok(Self)` if the `update` is valid to be (re)published on the gossip network.
+    pub fn verify(
+        update: LightClientOptimisticUpdate<T::EthSpec>,
+        chain: &BeaconChain<T>,
+        seen_timestamp: Duration,
+    ) -> Result<Self, Error> {
+        verify_signature_slot_elapsed(update.signature_slot, chain, seen_timestamp)?;
+
+        let local_update = chain
+            .light_client_server_cache
+            .get_latest_optimistic_update()
+            .ok_or(Error::NoLocalUpdate)?;
+        if update != local_update {
+            return Err(Error::MismatchedLocalUpdate);
+        }
+
+        if !chain
+            .light_client_server_cache
+            .observe_forwarded_optimistic_update(update.attested_header.slot)
+        {
+            return Err(Error::PriorUpdateForwarded);
+        }
+
+        Ok(Self {
+            update,
+            seen_timestamp,
+        })
+    }
+
+    /// Returns the verified update.
+    pub fn update(&self) -> &LightClientOptimisticUpdate<T::EthSpec> {
+        &self.update
+    }
+
+    /// Returns the time at which the update was received.
+    pub fn seen_timestamp(&self) -> Duration {
+        self.seen_timestamp
+    }
+}
+
+/// Verify that one third of `signature_slot` had elapsed when the update was seen, with respect to
+/// the `maximum_gossip_clock_disparity`.
+fn verify_signature_slot_elapsed<T: BeaconChainTypes>(
+    signature_slot: Slot,
+    chain: &BeaconChain<T>,
+    seen_timestamp: Duration,
+) -> Result<(), Error> {
+    let signature_slot_start = chain
+        .slot_clock
+        .start_of(signature_slot)
+        .ok_or(Error::SigSlotStartIsNone)?;
+    let one_third_slot_duration = chain.slot_clock.slot_duration() / INTERVALS_PER_SLOT as u32;
+    let earliest_seen_timestamp = (signature_slot_start + one_third_slot_duration)
+        .saturating_sub(chain.maximum_gossip_clock_disparity());
+
+    if seen_timestamp < earliest_seen_timestamp {
+        Err(Error::TooEarly)
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+//! Monitors the resident memory of the process and sheds load whilst it is above
+//! `ChainConfig::memory_watermark`, rather than allowing the process to be killed by the OS.
+//!
+//! Whilst the watermark is exceeded the block processing, shuffling and store caches are shrunk,
+//! and `BeaconChain::memory_watermark_exceeded` returns `true` so that expensive API queries are
+//! refused and backfill sync is paused.
+use crate::beacon_chain::{ATTESTATION_CACHE_LOCK_TIMEOUT, BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT};
+use crate::{metrics, BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::ProcessResources;
+use slog::{info, warn};
+use std::sync::Arc;
+use std::time::Duration;
+use task_executor::TaskExecutor;
+use tokio::time::sleep;
+
+/// The interval at which the resident memory of the process is checked.
+pub const MEMORY_WATERMARK_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Once exceeded, the watermark is considered to be exceeded until memory usage falls below this
+/// percentage of it, to avoid flapping between the two states.
+pub const MEMORY_WATERMARK_RECOVERY_PERCENT: u64 = 90;
+
+const MB: u64 = 1_048_576;
+
+/// Spawns a routine which checks the resident memory of the process at an interval and sheds load
+/// whilst it is above the memory watermark.
+///
+/// The service will not be started if no `memory_watermark` is configured.
+pub fn spawn_memory_watermark_service<T: BeaconChainTypes>(
+    executor: TaskExecutor,
+    chain: Arc<BeaconChain<T>>,
+) {
+    let watermark = match chain.config.memory_watermark {
+        Some(watermark) => watermark,
+        None => return,
+    };
+
+    executor.spawn(
+        async move { memory_watermark_service(chain, watermark).await },
+        "memory_watermark_service",
+    );
+}
+
+/// Loop indefinitely, checking memory usage at `MEMORY_WATERMARK_CHECK_INTERVAL`.
+async fn memory_watermark_service<T: BeaconChainTypes>(chain: Arc<BeaconChain<T>>, watermark: u64) {
+    let recovery_level = watermark / 100 * MEMORY_WATERMARK_RECOVERY_PERCENT;
+
+    loop {
+        let rss = match ProcessResources::observe() {
+            Ok(resources) => resources.resident_set_size,
+            Err(e) => {
+                warn!(
+                    chain.log,
+                    "Unable to monitor memory usage";
+                    "info" => "the memory watermark will not be enforced",
+                    "error" => e,
+                );
+                chain.set_memory_watermark_exceeded(false);
+                return;
+            }
+        };
+
+        let was_exceeded = chain.memory_watermark_exceeded();
+        let exceeded = if was_exceeded {
+            rss >= recovery_level
+        } else {
+            rss > watermark
+        };
+        chain.set_memory_watermark_exceeded(exceeded);
+        metrics::set_gauge(&metrics::MEMORY_WATERMARK_EXCEEDED, exceeded as i64);
+
+        if exceeded {
+            let actions = shed_load(&chain);
+            metrics::inc_counter(&metrics::MEMORY_WATERMARK_LOAD_SHEDDING);
+            warn!(
+                chain.log,
+                "Memory usage above watermark, shedding load";
+                "rss_mb" => rss / MB,
+                "watermark_mb" => watermark / MB,
+                "actions" => actions.join(", "),
+            );
+        } else if was_exceeded {
+            info!(
+                chain.log,
+                "Memory usage recovered below watermark";
+                "rss_mb" => rss / MB,
+                "watermark_mb" => watermark / MB,
+                "info" => "resuming backfill sync and expensive API queries",
+            );
+        }
+
+        sleep(MEMORY_WATERMARK_CHECK_INTERVAL).await;
+    }
+}
+
+/// Shrink the in-memory caches of the `chain`, returning a description of each action taken.
+fn shed_load<T: BeaconChainTypes>(chain: &BeaconChain<T>) -> Vec<String> {
+    let mut actions = vec![
+        "rejecting expensive API queries".to_string(),
+        "pausing backfill sync".to_string(),
+    ];
+
+    match chain
+        .snapshot_cache
+        .try_write_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
+    {
+        Some(mut snapshot_cache) => actions.push(format!(
+            "removed {} snapshots",
+            snapshot_cache.shrink_to_head()
+        )),
+        None => actions.push("snapshot cache lock timed out".to_string()),
+    }
+
+    match chain
+        .shuffling_cache
+        .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+    {
+        Some(mut shuffling_cache) => {
+            actions.push(format!("removed {} shufflings", shuffling_cache.clear()))
+        }
+        None => actions.push("shuffling cache lock timed out".to_string()),
+    }
+
+    actions.push(format!(
+        "removed {} store cache entries",
+        chain.store.clear_caches()
+    ));
+
+    actions
+}
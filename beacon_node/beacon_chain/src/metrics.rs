@@ -279,6 +279,10 @@ lazy_static! {
         "attestation_production_cache_prime_seconds",
         "Time spent loading a new state from the disk due to a cache miss"
     );
+    pub static ref ATTESTATION_PRODUCTION_SLOT_START_DELAY_TIME: Result<Histogram> = try_create_histogram(
+        "beacon_attestation_production_slot_start_delay_time",
+        "Duration between the start of the slot and the production of its first attestation.",
+    );
 }
 
 // Second lazy-static block is used to account for macro recursion limit.
@@ -554,6 +558,19 @@ lazy_static! {
             "The attestation inclusion distance calculated during per epoch processing",
             &["validator"]
         );
+    pub static ref VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_INCLUSION_DISTANCE_SLOTS: Result<Histogram> =
+        try_create_histogram_with_buckets(
+            "validator_monitor_prev_epoch_on_chain_inclusion_distance_slots",
+            "The distribution of attestation inclusion distances of all monitored validators",
+            vec![1.0, 2.0, 3.0, 4.0, 6.0, 8.0, 16.0, 32.0]
+        );
+    pub static ref VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_HIT_RATE: Result<GaugeVec> =
+        try_create_float_gauge_vec(
+            "validator_monitor_prev_epoch_on_chain_hit_rate",
+            "The proportion of active monitored validators with an on-chain attestation matching \
+            the flag (any, head or target)",
+            &["flag"]
+        );
     pub static ref VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATIONS_TOTAL: Result<IntGaugeVec> =
         try_create_int_gauge_vec(
             "validator_monitor_prev_epoch_attestations_total",
@@ -741,6 +758,11 @@ lazy_static! {
         "Number of times a validator's sync committee message has been seen in a sync aggregate",
         &["src", "validator"]
     );
+    pub static ref VALIDATOR_MONITOR_SYNC_COMMITTEE_MESSAGE_MISSED_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "validator_monitor_sync_committee_message_missed_total",
+        "Number of times a validator's sync committee message has been missing from a block's sync aggregate",
+        &["validator"]
+    );
     pub static ref VALIDATOR_MONITOR_ATTESTATION_IN_BLOCK_DELAY_SLOTS: Result<IntGaugeVec> = try_create_int_gauge_vec(
         "validator_monitor_attestation_in_block_delay_slots",
         "The excess slots (beyond the minimum delay) between the attestation slot and the block slot.",
@@ -751,6 +773,11 @@ lazy_static! {
         "Number of beacon blocks seen",
         &["src", "validator"]
     );
+    pub static ref VALIDATOR_MONITOR_MISSED_BLOCKS_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "validator_monitor_missed_blocks_total",
+        "Number of block proposals missed by a validator",
+        &["validator"]
+    );
     pub static ref VALIDATOR_MONITOR_BEACON_BLOCK_DELAY_SECONDS: Result<HistogramVec> = try_create_histogram_vec(
         "validator_monitor_beacon_block_delay_seconds",
         "The delay between when the validator should send the block and when it was received.",
@@ -796,6 +823,11 @@ lazy_static! {
         "Triggered when the duration between the start of the block's slot and the current time \
         will result in failed attestations.",
     );
+    pub static ref BEACON_BLOCK_HEAD_AFTER_ATTESTATION_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_head_after_attestation_total",
+        "Count of blocks set as head after attestations for their slot were produced for the parent \
+        block.",
+    );
 
     /*
      * General block metrics
@@ -841,6 +873,30 @@ lazy_static! {
         "Full runtime of sync contribution gossip verification"
     );
 
+    /*
+     * Light Client Update Verification
+     */
+    pub static ref FINALITY_UPDATE_PROCESSING_REQUESTS: Result<IntCounter> = try_create_int_counter(
+        "beacon_light_client_finality_update_processing_requests_total",
+        "Count of all light client finality updates submitted for processing"
+    );
+    pub static ref FINALITY_UPDATE_PROCESSING_SUCCESSES: Result<IntCounter> = try_create_int_counter(
+        "beacon_light_client_finality_update_processing_successes_total",
+        "Number of light client finality updates verified for gossip"
+    );
+    pub static ref OPTIMISTIC_UPDATE_PROCESSING_REQUESTS: Result<IntCounter> = try_create_int_counter(
+        "beacon_light_client_optimistic_update_processing_requests_total",
+        "Count of all light client optimistic updates submitted for processing"
+    );
+    pub static ref OPTIMISTIC_UPDATE_PROCESSING_SUCCESSES: Result<IntCounter> = try_create_int_counter(
+        "beacon_light_client_optimistic_update_processing_successes_total",
+        "Number of light client optimistic updates verified for gossip"
+    );
+    pub static ref LIGHT_CLIENT_SERVER_CACHE_RECOMPUTE_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_light_client_server_cache_recompute_updates_seconds",
+        "Time taken to compute the light client updates of an imported block"
+    );
+
     /*
      * General Sync Committee Contribution Processing
      */
@@ -922,6 +978,30 @@ lazy_static! {
             "beacon_pre_finalization_block_lookup_count",
             "Number of block roots subject to single block lookups"
         );
+
+    /*
+     * Payload reconstruction.
+     */
+    pub static ref PAYLOAD_RECONSTRUCTION_NEXT_SLOT: Result<IntGauge> = try_create_int_gauge(
+        "beacon_payload_reconstruction_next_slot",
+        "The slot from which execution payloads remain to be reconstructed"
+    );
+    pub static ref PAYLOAD_RECONSTRUCTION_PAYLOADS: Result<IntCounter> = try_create_int_counter(
+        "beacon_payload_reconstruction_payloads_total",
+        "Count of execution payloads reconstructed from the execution layer"
+    );
+
+    /*
+     * Memory watermark.
+     */
+    pub static ref MEMORY_WATERMARK_EXCEEDED: Result<IntGauge> = try_create_int_gauge(
+        "beacon_memory_watermark_exceeded",
+        "Set to 1 whilst the resident memory of the process is above the memory watermark"
+    );
+    pub static ref MEMORY_WATERMARK_LOAD_SHEDDING: Result<IntCounter> = try_create_int_counter(
+        "beacon_memory_watermark_load_shedding_total",
+        "Count of times that caches were shrunk due to the memory watermark being exceeded"
+    );
 }
 
 /// Scrape the `beacon_chain` for metrics that are not constantly updated (e.g., the present slot,
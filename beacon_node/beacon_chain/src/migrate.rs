@@ -15,7 +15,7 @@ use store::{Error, ItemStore, StoreItem, StoreOp};
 pub use store::{HotColdDB, MemoryStore};
 use types::{
     BeaconState, BeaconStateError, BeaconStateHash, Checkpoint, Epoch, EthSpec, Hash256,
-    SignedBeaconBlockHash, Slot,
+    LightClientBootstrap, SignedBeaconBlockHash, Slot,
 };
 
 /// Compact at least this frequently, finalization permitting (7 days).
@@ -260,6 +260,22 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             }
         };
 
+        // Store the light client bootstrap for the finalized block whilst its state is still in
+        // the hot database.
+        if let Err(e) = Self::store_light_client_bootstrap(
+            &db,
+            notif.finalized_checkpoint.root,
+            finalized_state_root.into(),
+            &finalized_state,
+        ) {
+            warn!(
+                log,
+                "Unable to store light client bootstrap";
+                "block_root" => ?notif.finalized_checkpoint.root,
+                "error" => ?e
+            );
+        }
+
         match migrate_database(db.clone(), finalized_state_root.into(), &finalized_state) {
             Ok(()) => {}
             Err(Error::HotColdDBError(HotColdDBError::FreezeSlotUnaligned(slot))) => {
@@ -279,6 +295,16 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             }
         };
 
+        // Delete the history which has left the retention window, if configured.
+        if let Err(e) = db.prune_history() {
+            warn!(log, "History pruning failed"; "error" => ?e);
+        }
+
+        // Delete the blobs which have left their own retention window.
+        if let Err(e) = db.prune_blobs() {
+            warn!(log, "Blob pruning failed"; "error" => ?e);
+        }
+
         // Finally, compact the database so that new free space is properly reclaimed.
         if let Err(e) = Self::run_compaction(
             db,
@@ -292,6 +318,36 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         debug!(log, "Database consolidation complete");
     }
 
+    /// Compute and store the light client bootstrap for the finalized block `block_root`.
+    ///
+    /// Blocks whose state predates Altair are ignored.
+    fn store_light_client_bootstrap(
+        db: &HotColdDB<E, Hot, Cold>,
+        block_root: Hash256,
+        finalized_state_root: Hash256,
+        finalized_state: &BeaconState<E>,
+    ) -> Result<(), Error> {
+        let block = db
+            .get_blinded_block(&block_root)?
+            .ok_or(Error::BlockNotFound(block_root))?;
+
+        // The finalized state is the state at the start of the finalized epoch, which is only the
+        // post-state of the finalized block if the first slot of the epoch wasn't skipped.
+        let mut state = if block.state_root() == finalized_state_root {
+            finalized_state.clone()
+        } else {
+            db.get_state(&block.state_root(), Some(block.slot()))?
+                .ok_or(HotColdDBError::MissingStateToFreeze(block.state_root()))?
+        };
+
+        if state.current_sync_committee().is_err() {
+            return Ok(());
+        }
+
+        let bootstrap = LightClientBootstrap::from_beacon_state(&mut state)?;
+        db.put_light_client_bootstrap(&block_root, &bootstrap)
+    }
+
     /// Spawn a new child thread to run the migration process.
     ///
     /// Return a channel handle for sending requests to the thread.
@@ -565,6 +621,12 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             head_tracker_lock.remove(&head_hash);
         }
 
+        // Blobs are deleted via `do_atomically` so that the store's blob info accounts for them.
+        let blob_batch: Vec<StoreOp<E>> = abandoned_blocks
+            .iter()
+            .map(|block_root| StoreOp::DeleteBlobs((*block_root).into()))
+            .collect();
+
         let batch: Vec<StoreOp<E>> = abandoned_blocks
             .into_iter()
             .map(Into::into)
@@ -597,6 +659,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         kv_batch.push(store.pruning_checkpoint_store_op(new_finalized_checkpoint));
 
         store.hot_db.do_atomically(kv_batch)?;
+        store.do_atomically(blob_batch)?;
         debug!(log, "Database pruning complete");
 
         Ok(PruningOutcome::Successful {
@@ -606,6 +669,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
 
     /// Compact the database if it has been more than `COMPACTION_PERIOD_SECONDS` since it
     /// was last compacted.
+    ///
+    /// The compaction is incremental and throttled so that block imports aren't stalled.
     pub fn run_compaction(
         db: Arc<HotColdDB<E, Hot, Cold>>,
         old_finalized_epoch: Epoch,
@@ -637,7 +702,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
                 "old_finalized_epoch" => old_finalized_epoch,
                 "new_finalized_epoch" => new_finalized_epoch,
             );
-            db.compact()?;
+            db.compact_incrementally()?;
 
             let finish_time = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
@@ -4,7 +4,8 @@ use state_processing::{SigVerifiedOp, VerifyOperation};
 use std::collections::HashSet;
 use std::marker::PhantomData;
 use types::{
-    AttesterSlashing, BeaconState, ChainSpec, EthSpec, ProposerSlashing, SignedVoluntaryExit,
+    AttesterSlashing, BeaconState, ChainSpec, EthSpec, ProposerSlashing,
+    SignedBlsToExecutionChange, SignedVoluntaryExit,
 };
 
 /// Number of validator indices to store on the stack in `observed_validators`.
@@ -20,6 +21,8 @@ pub struct ObservedOperations<T: ObservableOperation<E>, E: EthSpec> {
     ///
     /// For voluntary exits, this is the set of all `signed_voluntary_exit.message.validator_index`.
     /// For proposer slashings, this is the set of all `proposer_slashing.index`.
+    /// For BLS to execution changes, this is the set of all
+    /// `signed_bls_to_execution_change.message.validator_index`.
     /// For attester slashings, this is the set of all validators who would be slashed by
     /// previously seen attester slashings, i.e. those validators in the intersection of
     /// `attestation_1.attester_indices` and `attestation_2.attester_indices`.
@@ -54,6 +57,12 @@ impl<E: EthSpec> ObservableOperation<E> for ProposerSlashing {
     }
 }
 
+impl<E: EthSpec> ObservableOperation<E> for SignedBlsToExecutionChange {
+    fn observed_validators(&self) -> SmallVec<[u64; SMALL_VEC_SIZE]> {
+        std::iter::once(self.message.validator_index).collect()
+    }
+}
+
 impl<E: EthSpec> ObservableOperation<E> for AttesterSlashing<E> {
     fn observed_validators(&self) -> SmallVec<[u64; SMALL_VEC_SIZE]> {
         let attestation_1_indices = self
@@ -0,0 +1,248 @@
+//! Reconstructs the execution payloads of finalized blocks which were pruned from the database.
+//!
+//! Payloads are fetched from the execution layer in batches using
+//! `engine_getPayloadBodiesByRangeV1`, falling back to `eth_getBlockByHash` for any payload whose
+//! body isn't served, and are stored in the freezer database. Progress is stored atomically with
+//! each batch, so that reconstruction resumes where it left off after a restart.
+use crate::{metrics, BeaconChain, BeaconChainError, BeaconChainTypes};
+use execution_layer::{ExecutionLayer, ExecutionPayloadBodyV1};
+use slog::{debug, info, warn};
+use slot_clock::SlotClock;
+use std::sync::Arc;
+use store::PayloadReconstructionProgress;
+use task_executor::TaskExecutor;
+use tokio::time::sleep;
+use tree_hash::TreeHash;
+use types::{EthSpec, ExecutionPayload, ExecutionPayloadHeader, Slot};
+
+/// The number of slots of payloads to reconstruct in each batch.
+pub const PAYLOAD_RECONSTRUCTION_BATCH_SLOTS: u64 = 256;
+
+/// Spawns a routine which reconstructs missing execution payloads of finalized blocks, from the
+/// Bellatrix fork up to the split slot, and then keeps pace with finalization.
+///
+/// The service will not be started if there is no `execution_layer` on the `chain`, or if the
+/// Bellatrix fork isn't scheduled.
+pub fn start_payload_reconstruction_service<T: BeaconChainTypes>(
+    executor: TaskExecutor,
+    chain: Arc<BeaconChain<T>>,
+) {
+    let execution_layer = match chain.execution_layer.clone() {
+        Some(execution_layer) => execution_layer,
+        None => {
+            warn!(
+                chain.log,
+                "Not reconstructing execution payloads";
+                "reason" => "no execution endpoint",
+            );
+            return;
+        }
+    };
+    let bellatrix_slot = match chain.spec.bellatrix_fork_epoch {
+        Some(epoch) => epoch.start_slot(T::EthSpec::slots_per_epoch()),
+        None => return,
+    };
+
+    executor.spawn(
+        async move { payload_reconstruction_service(chain, execution_layer, bellatrix_slot).await },
+        "payload_reconstruction_service",
+    );
+}
+
+/// Loop indefinitely, reconstructing a batch of payloads at a time and waiting an epoch whenever
+/// all payloads prior to the split slot are present.
+async fn payload_reconstruction_service<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    execution_layer: ExecutionLayer,
+    bellatrix_slot: Slot,
+) {
+    let epoch_duration = chain.slot_clock.slot_duration() * T::EthSpec::slots_per_epoch() as u32;
+
+    match chain.store.get_payload_reconstruction_progress() {
+        Ok(progress) => info!(
+            chain.log,
+            "Beginning execution payload reconstruction";
+            "start_slot" => progress.map_or(bellatrix_slot, |progress| progress.next_slot),
+            "split_slot" => chain.store.get_split_slot(),
+        ),
+        Err(e) => warn!(
+            chain.log,
+            "Unable to load payload reconstruction progress";
+            "error" => ?e,
+        ),
+    }
+
+    let mut caught_up = false;
+    loop {
+        match reconstruct_payload_batch(&chain, &execution_layer, bellatrix_slot).await {
+            Ok(Some((next_slot, count))) => {
+                caught_up = false;
+                debug!(
+                    chain.log,
+                    "Reconstructed execution payloads";
+                    "count" => count,
+                    "next_slot" => next_slot,
+                    "split_slot" => chain.store.get_split_slot(),
+                );
+            }
+            Ok(None) => {
+                if !caught_up {
+                    info!(
+                        chain.log,
+                        "Execution payload reconstruction complete";
+                        "split_slot" => chain.store.get_split_slot(),
+                    );
+                    caught_up = true;
+                }
+                sleep(epoch_duration).await;
+            }
+            Err(e) => {
+                warn!(
+                    chain.log,
+                    "Execution payload reconstruction failed";
+                    "error" => ?e,
+                    "info" => "retrying in one epoch",
+                );
+                sleep(epoch_duration).await;
+            }
+        }
+    }
+}
+
+/// Reconstruct the missing payloads of the next batch of finalized blocks.
+///
+/// Returns the slot from which the next batch starts and the number of payloads reconstructed, or
+/// `None` if there are no finalized slots left to reconstruct.
+async fn reconstruct_payload_batch<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    execution_layer: &ExecutionLayer,
+    bellatrix_slot: Slot,
+) -> Result<Option<(Slot, usize)>, BeaconChainError> {
+    let store = &chain.store;
+
+    // Blocks prior to the oldest block slot are unavailable, either because they have not been
+    // backfilled or because they have been pruned.
+    let start_slot = store
+        .get_payload_reconstruction_progress()?
+        .map_or(bellatrix_slot, |progress| progress.next_slot)
+        .max(bellatrix_slot)
+        .max(store.get_oldest_block_slot());
+    let split_slot = store.get_split_slot();
+    if start_slot >= split_slot {
+        return Ok(None);
+    }
+    let end_slot = std::cmp::min(start_slot + PAYLOAD_RECONSTRUCTION_BATCH_SLOTS, split_slot);
+
+    // Collect the headers of the blocks whose payloads are missing, in block number order.
+    let mut missing = vec![];
+    let mut prev_block_root = None;
+    for result in chain.forwards_iter_block_roots_until(start_slot, end_slot - 1)? {
+        let (block_root, _) = result?;
+        if prev_block_root == Some(block_root) || store.execution_payload_exists(&block_root)? {
+            prev_block_root = Some(block_root);
+            continue;
+        }
+        prev_block_root = Some(block_root);
+
+        let block = store
+            .get_blinded_block(&block_root)?
+            .ok_or(BeaconChainError::MissingBeaconBlock(block_root))?;
+        let header = match block.message().execution_payload() {
+            Ok(payload) => payload.execution_payload_header.clone(),
+            Err(_) => continue,
+        };
+        // Blocks prior to the merge have an empty payload that doesn't need to be stored.
+        if header != ExecutionPayloadHeader::default() {
+            missing.push((block_root, block.slot(), header));
+        }
+    }
+
+    let bodies = match (missing.first(), missing.last()) {
+        (Some((_, _, first)), Some((_, _, last))) => {
+            let start = first.block_number;
+            let count = last.block_number.saturating_sub(start) + 1;
+            execution_layer
+                .get_payload_bodies_by_range::<T::EthSpec>(start, count)
+                .await
+                .unwrap_or_else(|e| {
+                    debug!(
+                        chain.log,
+                        "Unable to fetch payload bodies by range";
+                        "error" => ?e,
+                        "start" => start,
+                        "count" => count,
+                    );
+                    vec![]
+                })
+        }
+        _ => vec![],
+    };
+    let first_block_number = missing
+        .first()
+        .map_or(0, |(_, _, header)| header.block_number());
+
+    let mut payloads = Vec::with_capacity(missing.len());
+    for (block_root, slot, header) in missing {
+        let body = bodies
+            .get(header.block_number().saturating_sub(first_block_number) as usize)
+            .cloned()
+            .flatten();
+        let payload = reconstruct_payload(execution_layer, body, header, slot).await?;
+        payloads.push((block_root, payload));
+    }
+
+    store.store_reconstructed_execution_payloads(
+        &payloads,
+        PayloadReconstructionProgress {
+            next_slot: end_slot,
+        },
+    )?;
+    metrics::set_gauge(
+        &metrics::PAYLOAD_RECONSTRUCTION_NEXT_SLOT,
+        end_slot.as_u64() as i64,
+    );
+    metrics::inc_counter_by(
+        &metrics::PAYLOAD_RECONSTRUCTION_PAYLOADS,
+        payloads.len() as u64,
+    );
+
+    Ok(Some((end_slot, payloads.len())))
+}
+
+/// Combine `body` with `header` to form the payload of the block at `slot`, fetching the payload
+/// by its block hash if the body is missing or doesn't match the header.
+async fn reconstruct_payload<E: EthSpec>(
+    execution_layer: &ExecutionLayer,
+    body: Option<ExecutionPayloadBodyV1<E>>,
+    header: ExecutionPayloadHeader<E>,
+    slot: Slot,
+) -> Result<ExecutionPayload<E>, BeaconChainError> {
+    if let Some(payload) = body.and_then(|body| body.to_payload(header.clone())) {
+        return Ok(payload);
+    }
+
+    let exec_block_hash = header.block_hash();
+    let payload = execution_layer
+        .get_payload_by_block_hash(exec_block_hash)
+        .await
+        .map_err(|e| {
+            BeaconChainError::ExecutionLayerErrorPayloadReconstruction(exec_block_hash, e)
+        })?
+        .ok_or(BeaconChainError::BlockHashMissingFromExecutionLayer(
+            exec_block_hash,
+        ))?;
+
+    let header_from_payload = ExecutionPayloadHeader::from(&payload);
+    if header_from_payload != header {
+        return Err(BeaconChainError::InconsistentPayloadReconstructed {
+            slot,
+            exec_block_hash,
+            canonical_payload_root: header.tree_hash_root(),
+            reconstructed_payload_root: header_from_payload.tree_hash_root(),
+            canonical_transactions_root: header.transactions_root(),
+            reconstructed_transactions_root: header_from_payload.transactions_root(),
+        });
+    }
+
+    Ok(payload)
+}
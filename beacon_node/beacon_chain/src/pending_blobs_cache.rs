@@ -0,0 +1,83 @@
+//! Holds the blobs of blocks which are yet to be imported, so that the blob sidecars can be stored
+//! atomically with their block.
+//!
+//! Blobs are either the bundle of a locally produced block or the verified sidecars of a block
+//! downloaded from a peer.
+use crate::BeaconChainError;
+use execution_layer::BlobsBundle;
+use lru::LruCache;
+use parking_lot::Mutex;
+use ssz_types::VariableList;
+use std::sync::Arc;
+use types::{BlobSidecar, BlobSidecarList, EthSpec, Hash256, SignedBeaconBlock};
+
+/// The number of blocks for which blobs are held.
+///
+/// Blocks are imported soon after their blobs are received, so only a few need to be held.
+const PENDING_BLOBS_CACHE_SIZE: usize = 32;
+/// The number of downloaded blocks for which blob sidecars are held.
+///
+/// Range sync downloads whole batches before processing them, so this must hold the blobs of
+/// several batches.
+const PENDING_SIDECARS_CACHE_SIZE: usize = 1024;
+
+pub struct PendingBlobsCache<E: EthSpec> {
+    /// The blobs bundles of locally produced blocks, by block root.
+    bundles: Mutex<LruCache<Hash256, BlobsBundle<E>>>,
+    /// The blob sidecars of blocks downloaded from peers, by block root.
+    sidecars: Mutex<LruCache<Hash256, BlobSidecarList<E>>>,
+}
+
+impl<E: EthSpec> Default for PendingBlobsCache<E> {
+    fn default() -> Self {
+        Self {
+            bundles: Mutex::new(LruCache::new(PENDING_BLOBS_CACHE_SIZE)),
+            sidecars: Mutex::new(LruCache::new(PENDING_SIDECARS_CACHE_SIZE)),
+        }
+    }
+}
+
+impl<E: EthSpec> PendingBlobsCache<E> {
+    /// Hold the blobs bundle of a locally produced block until the block is imported.
+    ///
+    /// The block root is unaffected by the proposer's signature, so the unsigned block's root may
+    /// be used.
+    pub fn put_bundle(&self, block_root: Hash256, bundle: BlobsBundle<E>) {
+        self.bundles.lock().put(block_root, bundle);
+    }
+
+    /// Hold the blob sidecars of a block downloaded from a peer until the block is imported.
+    ///
+    /// The sidecars must have been verified against the block, see `blob_verification`.
+    pub fn put_sidecars(&self, block_root: Hash256, sidecars: BlobSidecarList<E>) {
+        self.sidecars.lock().put(block_root, sidecars);
+    }
+
+    /// Remove and return the blob sidecars held for a block downloaded from a peer.
+    pub fn take_downloaded_sidecars(&self, block_root: Hash256) -> Option<BlobSidecarList<E>> {
+        self.sidecars.lock().pop(&block_root)
+    }
+
+    /// Remove and return the blob sidecars held for `block`, or `None` if none are held.
+    pub fn take_sidecars(
+        &self,
+        block_root: Hash256,
+        block: &SignedBeaconBlock<E>,
+    ) -> Result<Option<BlobSidecarList<E>>, BeaconChainError> {
+        let bundle = match self.bundles.lock().pop(&block_root) {
+            Some(bundle) => bundle,
+            None => return Ok(self.take_downloaded_sidecars(block_root)),
+        };
+
+        let sidecars = Vec::from(bundle.blobs)
+            .into_iter()
+            .zip(Vec::from(bundle.proofs))
+            .enumerate()
+            .map(|(index, (blob, kzg_proof))| {
+                BlobSidecar::new(index, blob, block, kzg_proof).map(Arc::new)
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(BeaconChainError::BeaconStateError)?;
+        Ok(Some(VariableList::new(sidecars)?))
+    }
+}
@@ -1,18 +1,94 @@
 //! Utilities for managing database schema changes.
+mod migration_schema_v10;
+mod migration_schema_v11;
 mod migration_schema_v6;
 mod migration_schema_v7;
 mod migration_schema_v8;
 mod migration_schema_v9;
 mod types;
 
-use crate::beacon_chain::{BeaconChainTypes, FORK_CHOICE_DB_KEY};
-use crate::persisted_fork_choice::{PersistedForkChoiceV1, PersistedForkChoiceV7};
-use slog::{warn, Logger};
+use crate::beacon_chain::{BeaconChainTypes, ETH1_CACHE_DB_KEY, FORK_CHOICE_DB_KEY};
+use crate::eth1_chain::SszEth1;
+use crate::persisted_fork_choice::{
+    PersistedForkChoiceV1, PersistedForkChoiceV11, PersistedForkChoiceV7, PersistedForkChoiceV8,
+};
+use slog::{info, warn, Logger};
 use std::path::Path;
 use std::sync::Arc;
 use store::hot_cold_store::{HotColdDB, HotColdDBError};
 use store::metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION};
-use store::{Error as StoreError, StoreItem};
+use store::{get_key_for_col, DBColumn, Error as StoreError, KeyValueStoreOp, StoreItem};
+
+type StepColumns = (&'static [DBColumn], &'static [DBColumn]);
+
+/// Return the hot and freezer database columns rewritten by the migration between the adjacent
+/// schema versions `from` and `to`, or `None` if `migrate_schema` doesn't support it.
+fn migration_step_columns(from: SchemaVersion, to: SchemaVersion) -> Option<StepColumns> {
+    match (from, to) {
+        (SchemaVersion(5), SchemaVersion(6))
+        | (SchemaVersion(6), SchemaVersion(7))
+        | (SchemaVersion(7), SchemaVersion(8)) => Some((&[DBColumn::ForkChoice], &[])),
+        (SchemaVersion(8), SchemaVersion(9)) | (SchemaVersion(9), SchemaVersion(8)) => {
+            Some((&[DBColumn::BeaconBlock, DBColumn::ExecPayload], &[]))
+        }
+        (SchemaVersion(9), SchemaVersion(10)) | (SchemaVersion(10), SchemaVersion(9)) => {
+            Some((&[DBColumn::Eth1Cache], &[]))
+        }
+        (SchemaVersion(10), SchemaVersion(11)) | (SchemaVersion(11), SchemaVersion(10)) => {
+            Some((&[DBColumn::ForkChoice], &[]))
+        }
+        (SchemaVersion(11), SchemaVersion(12)) => Some((&[], &[])),
+        (SchemaVersion(12), SchemaVersion(11)) => Some((&[], &[DBColumn::BeaconStateDiff])),
+        _ => None,
+    }
+}
+
+/// A migration between adjacent schema versions, as applied by `migrate_schema`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MigrationStep {
+    pub from: SchemaVersion,
+    pub to: SchemaVersion,
+    /// The columns of the hot database which the migration reads and rewrites.
+    pub columns: &'static [DBColumn],
+    /// The columns of the freezer database which the migration reads and rewrites.
+    pub freezer_columns: &'static [DBColumn],
+}
+
+/// Return the migrations between adjacent schema versions which `migrate_schema` applies to
+/// migrate from `from` to `to`, without applying them.
+///
+/// Returns an error if any of the migrations is unsupported.
+pub fn migration_plan(
+    from: SchemaVersion,
+    to: SchemaVersion,
+) -> Result<Vec<MigrationStep>, StoreError> {
+    let versions: Vec<u64> = if from.as_u64() <= to.as_u64() {
+        (from.as_u64()..=to.as_u64()).collect()
+    } else {
+        (to.as_u64()..=from.as_u64()).rev().collect()
+    };
+
+    versions
+        .windows(2)
+        .map(|pair| {
+            let (step_from, step_to) = (SchemaVersion(pair[0]), SchemaVersion(pair[1]));
+            migration_step_columns(step_from, step_to)
+                .map(|(columns, freezer_columns)| MigrationStep {
+                    from: step_from,
+                    to: step_to,
+                    columns,
+                    freezer_columns,
+                })
+                .ok_or_else(|| {
+                    HotColdDBError::UnsupportedSchemaVersion {
+                        target_version: step_to,
+                        current_version: step_from,
+                    }
+                    .into()
+                })
+        })
+        .collect()
+}
 
 /// Migrate the database from one schema version to another, applying all requisite mutations.
 pub fn migrate_schema<T: BeaconChainTypes>(
@@ -31,6 +107,12 @@ pub fn migrate_schema<T: BeaconChainTypes>(
             migrate_schema::<T>(db.clone(), datadir, from, next, log.clone())?;
             migrate_schema::<T>(db, datadir, next, to, log)
         }
+        // Downgrade across multiple versions by recursively migrating one step at a time.
+        (_, _) if to.as_u64() + 1 < from.as_u64() => {
+            let next = SchemaVersion(from.as_u64() - 1);
+            migrate_schema::<T>(db.clone(), datadir, from, next, log.clone())?;
+            migrate_schema::<T>(db, datadir, next, to, log)
+        }
 
         //
         // Migrations from before SchemaVersion(5) are deprecated.
@@ -127,6 +209,88 @@ pub fn migrate_schema<T: BeaconChainTypes>(
             migration_schema_v9::downgrade_from_v9::<T>(db.clone(), log)?;
             db.store_schema_version(to)
         }
+        // Upgrade from v9 to v10 to add deposit finalization to the eth1 cache.
+        (SchemaVersion(9), SchemaVersion(10)) => {
+            let mut ops = vec![];
+            if let Some(persisted_eth1) = db.get_item::<SszEth1>(&ETH1_CACHE_DB_KEY)? {
+                let upgraded_eth1 = migration_schema_v10::update_eth1_cache(persisted_eth1)?;
+                ops.push(upgraded_eth1.as_kv_store_op(ETH1_CACHE_DB_KEY));
+            }
+
+            db.store_schema_version_atomically(to, ops)?;
+
+            Ok(())
+        }
+        // Downgrade from v10 to v9, dropping the eth1 cache if any deposits have been finalized.
+        (SchemaVersion(10), SchemaVersion(9)) => {
+            let mut ops = vec![];
+            if let Some(persisted_eth1) = db.get_item::<SszEth1>(&ETH1_CACHE_DB_KEY)? {
+                match migration_schema_v10::downgrade_eth1_cache(persisted_eth1)? {
+                    Some(downgraded_eth1) => {
+                        ops.push(downgraded_eth1.as_kv_store_op(ETH1_CACHE_DB_KEY))
+                    }
+                    None => {
+                        warn!(
+                            log,
+                            "Dropping finalized eth1 deposit cache";
+                            "info" => "the cache will be rebuilt from the eth1 node"
+                        );
+                        let key = get_key_for_col(
+                            SszEth1::db_column().into(),
+                            ETH1_CACHE_DB_KEY.as_bytes(),
+                        );
+                        ops.push(KeyValueStoreOp::DeleteKey(key));
+                    }
+                }
+            }
+
+            db.store_schema_version_atomically(to, ops)?;
+
+            Ok(())
+        }
+        // Upgrade from v10 to v11 to add equivocating indices to the fork choice store.
+        (SchemaVersion(10), SchemaVersion(11)) => {
+            let mut ops = vec![];
+            if let Some(fork_choice) = db.get_item::<PersistedForkChoiceV8>(&FORK_CHOICE_DB_KEY)? {
+                let updated_fork_choice = migration_schema_v11::update_fork_choice(fork_choice);
+                ops.push(updated_fork_choice.as_kv_store_op(FORK_CHOICE_DB_KEY));
+            }
+
+            db.store_schema_version_atomically(to, ops)?;
+
+            Ok(())
+        }
+        // Downgrade from v11 to v10, dropping the equivocating indices.
+        (SchemaVersion(11), SchemaVersion(10)) => {
+            let mut ops = vec![];
+            if let Some(fork_choice) = db.get_item::<PersistedForkChoiceV11>(&FORK_CHOICE_DB_KEY)? {
+                let downgraded_fork_choice =
+                    migration_schema_v11::downgrade_fork_choice(fork_choice, &log);
+                ops.push(downgraded_fork_choice.as_kv_store_op(FORK_CHOICE_DB_KEY));
+            }
+
+            db.store_schema_version_atomically(to, ops)?;
+
+            Ok(())
+        }
+        // Upgrade from v11 to v12, allowing restore points to be stored as diffs in the freezer
+        // database. Existing restore points are left in full.
+        (SchemaVersion(11), SchemaVersion(12)) => db.store_schema_version(to),
+        // Downgrade from v12 to v11, rewriting the restore points stored as diffs in full. The
+        // schema version is only updated once every restore point has been converted.
+        (SchemaVersion(12), SchemaVersion(11)) => {
+            db.convert_state_diffs_to_restore_points(|examined, total| {
+                if examined % 64 == 0 {
+                    info!(
+                        log,
+                        "Converting state diffs to full restore points";
+                        "examined" => examined,
+                        "total" => total,
+                    );
+                }
+            })?;
+            db.store_schema_version(to)
+        }
         // Anything else is an error.
         (_, _) => Err(HotColdDBError::UnsupportedSchemaVersion {
             target_version: to,
@@ -0,0 +1,37 @@
+use crate::eth1_chain::SszEth1;
+use eth1::{SszEth1CacheV1, SszEth1CacheV10};
+use ssz::{Decode, Encode};
+use store::Error;
+
+/// Upgrade the persisted eth1 cache to support deposit finalization.
+pub fn update_eth1_cache(persisted_eth1: SszEth1) -> Result<SszEth1, Error> {
+    // The dummy backend doesn't persist any caches.
+    if persisted_eth1.backend_bytes.is_empty() {
+        return Ok(persisted_eth1);
+    }
+
+    let eth1_cache_v1 = SszEth1CacheV1::from_ssz_bytes(&persisted_eth1.backend_bytes)?;
+    let eth1_cache_v10 = SszEth1CacheV10::from(eth1_cache_v1);
+
+    Ok(SszEth1 {
+        use_dummy_backend: persisted_eth1.use_dummy_backend,
+        backend_bytes: eth1_cache_v10.as_ssz_bytes(),
+    })
+}
+
+/// Downgrade the persisted eth1 cache to the format used prior to deposit finalization.
+///
+/// Returns `None` if deposits have already been finalized, in which case the cache can't be
+/// represented in the old format and must be rebuilt from the eth1 node.
+pub fn downgrade_eth1_cache(persisted_eth1: SszEth1) -> Result<Option<SszEth1>, Error> {
+    if persisted_eth1.backend_bytes.is_empty() {
+        return Ok(Some(persisted_eth1));
+    }
+
+    let eth1_cache_v10 = SszEth1CacheV10::from_ssz_bytes(&persisted_eth1.backend_bytes)?;
+
+    Ok(eth1_cache_v10.to_v1().map(|eth1_cache_v1| SszEth1 {
+        use_dummy_backend: persisted_eth1.use_dummy_backend,
+        backend_bytes: eth1_cache_v1.as_ssz_bytes(),
+    }))
+}
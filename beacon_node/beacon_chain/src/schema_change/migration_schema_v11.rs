@@ -0,0 +1,71 @@
+use crate::beacon_fork_choice_store::{PersistedForkChoiceStoreV11, PersistedForkChoiceStoreV8};
+use crate::persisted_fork_choice::{PersistedForkChoiceV11, PersistedForkChoiceV8};
+use slog::{warn, Logger};
+
+/// Add the (initially empty) set of equivocating indices to the fork choice store.
+pub fn update_fork_choice(fork_choice: PersistedForkChoiceV8) -> PersistedForkChoiceV11 {
+    let PersistedForkChoiceStoreV8 {
+        balances_cache,
+        time,
+        finalized_checkpoint,
+        justified_checkpoint,
+        justified_balances,
+        best_justified_checkpoint,
+        proposer_boost_root,
+    } = fork_choice.fork_choice_store;
+
+    PersistedForkChoiceV11 {
+        fork_choice: fork_choice.fork_choice,
+        fork_choice_store: PersistedForkChoiceStoreV11 {
+            balances_cache,
+            time,
+            finalized_checkpoint,
+            justified_checkpoint,
+            justified_balances,
+            best_justified_checkpoint,
+            proposer_boost_root,
+            equivocating_indices: vec![],
+        },
+    }
+}
+
+/// Drop the equivocating indices from the fork choice store.
+///
+/// The votes of any equivocating validators will count towards fork choice again after the
+/// downgrade, until their slashings are processed.
+pub fn downgrade_fork_choice(
+    fork_choice: PersistedForkChoiceV11,
+    log: &Logger,
+) -> PersistedForkChoiceV8 {
+    let PersistedForkChoiceStoreV11 {
+        balances_cache,
+        time,
+        finalized_checkpoint,
+        justified_checkpoint,
+        justified_balances,
+        best_justified_checkpoint,
+        proposer_boost_root,
+        equivocating_indices,
+    } = fork_choice.fork_choice_store;
+
+    if !equivocating_indices.is_empty() {
+        warn!(
+            log,
+            "Dropping equivocating indices from fork choice";
+            "count" => equivocating_indices.len(),
+        );
+    }
+
+    PersistedForkChoiceV8 {
+        fork_choice: fork_choice.fork_choice,
+        fork_choice_store: PersistedForkChoiceStoreV8 {
+            balances_cache,
+            time,
+            finalized_checkpoint,
+            justified_checkpoint,
+            justified_balances,
+            best_justified_checkpoint,
+            proposer_boost_root,
+        },
+    }
+}
@@ -45,6 +45,13 @@ impl ShufflingCache {
             self.cache.put(key, committee_cache.clone());
         }
     }
+
+    /// Remove all entries, returning the number removed.
+    pub fn clear(&mut self) -> usize {
+        let len = self.cache.len();
+        self.cache.clear();
+        len
+    }
 }
 
 /// Contains the shuffling IDs for a beacon block.
@@ -350,6 +350,19 @@ impl<T: EthSpec> SnapshotCache<T> {
         })
     }
 
+    /// Removes all snapshots other than the head, and the head's pre-state, returning the number
+    /// of snapshots removed.
+    pub fn shrink_to_head(&mut self) -> usize {
+        let len = self.snapshots.len();
+        let head_block_root = self.head_block_root;
+        self.snapshots
+            .retain(|snapshot| snapshot.beacon_block_root == head_block_root);
+        for snapshot in &mut self.snapshots {
+            snapshot.pre_state = None;
+        }
+        len - self.snapshots.len()
+    }
+
     /// Inform the cache that the head of the beacon chain has changed.
     ///
     /// The snapshot that matches this `head_block_root` will never be ejected from the cache
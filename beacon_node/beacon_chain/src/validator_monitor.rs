@@ -3,6 +3,7 @@
 //! This component should not affect consensus.
 
 use crate::metrics;
+use eth2::lighthouse::{MonitoredValidatorSummary, ValidatorMonitorEpochSummary};
 use parking_lot::RwLock;
 use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
@@ -25,6 +26,14 @@ use types::{
 /// will be kept around for `HISTORIC_EPOCHS` before it is pruned.
 pub const HISTORIC_EPOCHS: usize = 4;
 
+/// Once more than this number of validators are monitored, metrics are aggregated across all
+/// monitored validators rather than reported for each validator, to limit the number of
+/// Prometheus time series. Per-validator detail remains available via the HTTP API.
+pub const DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD: usize = 64;
+
+/// The `validator` label of aggregated metrics.
+pub const TOTAL_LABEL: &str = "total";
+
 #[derive(Debug)]
 pub enum Error {
     InvalidPubkey(String),
@@ -92,6 +101,24 @@ struct EpochSummary {
     pub proposer_slashings: usize,
     /// The number of attester slashings observed.
     pub attester_slashings: usize,
+
+    /*
+     * Duty outcomes in the current epoch.
+     */
+    /// Whether an attestation was included on-chain and matched the head and target, once known.
+    pub on_chain_attestation: Option<OnChainAttestation>,
+    /// The number of block proposals missed.
+    pub missed_blocks: usize,
+    /// The number of times a validator's sync signature was missing from the sync aggregate.
+    pub sync_signature_block_misses: usize,
+}
+
+/// The outcome of a validator's attestation for an epoch, as reflected on-chain.
+#[derive(Clone, Copy)]
+struct OnChainAttestation {
+    included: bool,
+    matched_head: bool,
+    matched_target: bool,
 }
 
 impl EpochSummary {
@@ -160,6 +187,75 @@ impl EpochSummary {
     pub fn register_attester_slashing(&mut self) {
         self.attester_slashings += 1;
     }
+
+    fn register_on_chain_attestation(&mut self, on_chain_attestation: OnChainAttestation) {
+        self.on_chain_attestation = Some(on_chain_attestation);
+    }
+
+    pub fn register_missed_block(&mut self) {
+        self.missed_blocks += 1;
+    }
+
+    pub fn register_sync_signature_block_miss(&mut self) {
+        self.sync_signature_block_misses += 1;
+    }
+
+    /// Add the counts of `other` to `self`, for reporting the total across several validators.
+    ///
+    /// The minimum delays and inclusion distances of `self` are left unchanged.
+    fn add_counts(&mut self, other: &Self) {
+        self.attestations += other.attestations;
+        self.attestation_aggregate_inclusions += other.attestation_aggregate_inclusions;
+        self.attestation_block_inclusions += other.attestation_block_inclusions;
+        self.blocks += other.blocks;
+        self.aggregates += other.aggregates;
+        self.sync_committee_messages += other.sync_committee_messages;
+        self.sync_signature_block_inclusions += other.sync_signature_block_inclusions;
+        self.sync_signature_contribution_inclusions += other.sync_signature_contribution_inclusions;
+        self.sync_contributions += other.sync_contributions;
+        self.exits += other.exits;
+        self.proposer_slashings += other.proposer_slashings;
+        self.attester_slashings += other.attester_slashings;
+        self.missed_blocks += other.missed_blocks;
+        self.sync_signature_block_misses += other.sync_signature_block_misses;
+    }
+
+    fn to_api_summary(&self, epoch: Epoch) -> ValidatorMonitorEpochSummary {
+        let millis = |delay: Option<Duration>| delay.map(|delay| delay.as_millis() as u64);
+        ValidatorMonitorEpochSummary {
+            epoch,
+            attestations: self.attestations as u64,
+            attestation_min_delay_ms: millis(self.attestation_min_delay),
+            attestation_aggregate_inclusions: self.attestation_aggregate_inclusions as u64,
+            attestation_block_inclusions: self.attestation_block_inclusions as u64,
+            attestation_min_block_inclusion_distance: self.attestation_min_block_inclusion_distance,
+            attestation_included_on_chain: self
+                .on_chain_attestation
+                .map(|attestation| attestation.included),
+            attestation_matched_head: self
+                .on_chain_attestation
+                .map(|attestation| attestation.matched_head),
+            attestation_matched_target: self
+                .on_chain_attestation
+                .map(|attestation| attestation.matched_target),
+            blocks: self.blocks as u64,
+            block_min_delay_ms: millis(self.block_min_delay),
+            missed_blocks: self.missed_blocks as u64,
+            aggregates: self.aggregates as u64,
+            aggregate_min_delay_ms: millis(self.aggregate_min_delay),
+            sync_committee_messages: self.sync_committee_messages as u64,
+            sync_committee_message_min_delay_ms: millis(self.sync_committee_message_min_delay),
+            sync_signature_block_inclusions: self.sync_signature_block_inclusions as u64,
+            sync_signature_block_misses: self.sync_signature_block_misses as u64,
+            sync_signature_contribution_inclusions: self.sync_signature_contribution_inclusions
+                as u64,
+            sync_contributions: self.sync_contributions as u64,
+            sync_contribution_min_delay_ms: millis(self.sync_contribution_min_delay),
+            exits: self.exits as u64,
+            proposer_slashings: self.proposer_slashings as u64,
+            attester_slashings: self.attester_slashings as u64,
+        }
+    }
 }
 
 type SummaryMap = HashMap<Epoch, EpochSummary>;
@@ -168,6 +264,8 @@ type SummaryMap = HashMap<Epoch, EpochSummary>;
 struct MonitoredValidator {
     /// A human-readable identifier for the validator.
     pub id: String,
+    /// The validator's public key.
+    pub pubkey: PublicKeyBytes,
     /// The validator index in the state.
     pub index: Option<u64>,
     /// A history of the validator over time.
@@ -180,6 +278,7 @@ impl MonitoredValidator {
             id: index
                 .map(|i| i.to_string())
                 .unwrap_or_else(|| pubkey.to_string()),
+            pubkey,
             index,
             summaries: <_>::default(),
         }
@@ -248,16 +347,28 @@ pub struct ValidatorMonitor<T> {
     indices: HashMap<u64, PublicKeyBytes>,
     /// If true, allow the automatic registration of validators.
     auto_register: bool,
+    /// Metrics are only reported for each validator while no more than this number of validators
+    /// are monitored.
+    individual_tracking_threshold: usize,
+    /// The `(validator_index, slot)` of the block proposals already reported as missed.
+    missed_blocks: HashSet<(u64, Slot)>,
     log: Logger,
     _phantom: PhantomData<T>,
 }
 
 impl<T: EthSpec> ValidatorMonitor<T> {
-    pub fn new(pubkeys: Vec<PublicKeyBytes>, auto_register: bool, log: Logger) -> Self {
+    pub fn new(
+        pubkeys: Vec<PublicKeyBytes>,
+        auto_register: bool,
+        individual_tracking_threshold: usize,
+        log: Logger,
+    ) -> Self {
         let mut s = Self {
             validators: <_>::default(),
             indices: <_>::default(),
             auto_register,
+            individual_tracking_threshold,
+            missed_blocks: <_>::default(),
             log,
             _phantom: PhantomData,
         };
@@ -267,6 +378,21 @@ impl<T: EthSpec> ValidatorMonitor<T> {
         s
     }
 
+    /// Returns `true` if metrics are reported for each validator, rather than aggregated across all
+    /// monitored validators.
+    pub fn individual_tracking(&self) -> bool {
+        self.validators.len() <= self.individual_tracking_threshold
+    }
+
+    /// Returns the `validator` label of the metrics for the validator with `id`.
+    fn metric_label<'a>(&self, id: &'a str) -> &'a str {
+        if self.individual_tracking() {
+            id
+        } else {
+            TOTAL_LABEL
+        }
+    }
+
     /// Add some validators to `self` for additional monitoring.
     fn add_validator_pubkey(&mut self, pubkey: PublicKeyBytes) {
         let index_opt = self
@@ -288,7 +414,12 @@ impl<T: EthSpec> ValidatorMonitor<T> {
 
     /// Reads information from the given `state`. The `state` *must* be valid (i.e, able to be
     /// imported).
-    pub fn process_valid_state(&mut self, current_epoch: Epoch, state: &BeaconState<T>) {
+    pub fn process_valid_state(
+        &mut self,
+        current_epoch: Epoch,
+        state: &BeaconState<T>,
+        spec: &ChainSpec,
+    ) {
         // Add any new validator indices.
         state
             .validators()
@@ -303,81 +434,197 @@ impl<T: EthSpec> ValidatorMonitor<T> {
                 self.indices.insert(i, validator.pubkey);
             });
 
-        // Update metrics for individual validators.
-        for monitored_validator in self.validators.values() {
-            if let Some(i) = monitored_validator.index {
-                let i = i as usize;
-                let id = &monitored_validator.id;
+        if self.individual_tracking() {
+            // Update metrics for individual validators.
+            for monitored_validator in self.validators.values() {
+                if let Some(i) = monitored_validator.index {
+                    let i = i as usize;
+                    let id = &monitored_validator.id;
 
-                if let Some(balance) = state.balances().get(i) {
-                    metrics::set_int_gauge(
-                        &metrics::VALIDATOR_MONITOR_BALANCE_GWEI,
-                        &[id],
-                        *balance as i64,
-                    );
+                    if let Some(balance) = state.balances().get(i) {
+                        metrics::set_int_gauge(
+                            &metrics::VALIDATOR_MONITOR_BALANCE_GWEI,
+                            &[id],
+                            *balance as i64,
+                        );
+                    }
+
+                    if let Some(validator) = state.validators().get(i) {
+                        metrics::set_int_gauge(
+                            &metrics::VALIDATOR_MONITOR_EFFECTIVE_BALANCE_GWEI,
+                            &[id],
+                            u64_to_i64(validator.effective_balance),
+                        );
+                        metrics::set_int_gauge(
+                            &metrics::VALIDATOR_MONITOR_SLASHED,
+                            &[id],
+                            if validator.slashed { 1 } else { 0 },
+                        );
+                        metrics::set_int_gauge(
+                            &metrics::VALIDATOR_MONITOR_ACTIVE,
+                            &[id],
+                            if validator.is_active_at(current_epoch) {
+                                1
+                            } else {
+                                0
+                            },
+                        );
+                        metrics::set_int_gauge(
+                            &metrics::VALIDATOR_MONITOR_EXITED,
+                            &[id],
+                            if validator.is_exited_at(current_epoch) {
+                                1
+                            } else {
+                                0
+                            },
+                        );
+                        metrics::set_int_gauge(
+                            &metrics::VALIDATOR_MONITOR_WITHDRAWABLE,
+                            &[id],
+                            if validator.is_withdrawable_at(current_epoch) {
+                                1
+                            } else {
+                                0
+                            },
+                        );
+                        metrics::set_int_gauge(
+                            &metrics::VALIDATOR_ACTIVATION_ELIGIBILITY_EPOCH,
+                            &[id],
+                            u64_to_i64(validator.activation_eligibility_epoch),
+                        );
+                        metrics::set_int_gauge(
+                            &metrics::VALIDATOR_ACTIVATION_EPOCH,
+                            &[id],
+                            u64_to_i64(validator.activation_epoch),
+                        );
+                        metrics::set_int_gauge(
+                            &metrics::VALIDATOR_EXIT_EPOCH,
+                            &[id],
+                            u64_to_i64(validator.exit_epoch),
+                        );
+                        metrics::set_int_gauge(
+                            &metrics::VALIDATOR_WITHDRAWABLE_EPOCH,
+                            &[id],
+                            u64_to_i64(validator.withdrawable_epoch),
+                        );
+                    }
                 }
+            }
+        } else {
+            self.aggregate_state_metrics(current_epoch, state);
+        }
 
-                if let Some(validator) = state.validators().get(i) {
-                    metrics::set_int_gauge(
-                        &metrics::VALIDATOR_MONITOR_EFFECTIVE_BALANCE_GWEI,
-                        &[id],
-                        u64_to_i64(validator.effective_balance),
-                    );
-                    metrics::set_int_gauge(
-                        &metrics::VALIDATOR_MONITOR_SLASHED,
-                        &[id],
-                        if validator.slashed { 1 } else { 0 },
-                    );
-                    metrics::set_int_gauge(
-                        &metrics::VALIDATOR_MONITOR_ACTIVE,
-                        &[id],
-                        if validator.is_active_at(current_epoch) {
-                            1
-                        } else {
-                            0
-                        },
-                    );
-                    metrics::set_int_gauge(
-                        &metrics::VALIDATOR_MONITOR_EXITED,
-                        &[id],
-                        if validator.is_exited_at(current_epoch) {
-                            1
-                        } else {
-                            0
-                        },
-                    );
-                    metrics::set_int_gauge(
-                        &metrics::VALIDATOR_MONITOR_WITHDRAWABLE,
-                        &[id],
-                        if validator.is_withdrawable_at(current_epoch) {
-                            1
-                        } else {
-                            0
-                        },
-                    );
-                    metrics::set_int_gauge(
-                        &metrics::VALIDATOR_ACTIVATION_ELIGIBILITY_EPOCH,
-                        &[id],
-                        u64_to_i64(validator.activation_eligibility_epoch),
-                    );
-                    metrics::set_int_gauge(
-                        &metrics::VALIDATOR_ACTIVATION_EPOCH,
-                        &[id],
-                        u64_to_i64(validator.activation_epoch),
-                    );
-                    metrics::set_int_gauge(
-                        &metrics::VALIDATOR_EXIT_EPOCH,
-                        &[id],
-                        u64_to_i64(validator.exit_epoch),
+        self.check_missed_blocks(state, spec);
+    }
+
+    /// Update the metrics derived from the `state` with the totals across all monitored validators.
+    fn aggregate_state_metrics(&self, current_epoch: Epoch, state: &BeaconState<T>) {
+        let mut balance = 0;
+        let mut effective_balance = 0;
+        let mut slashed = 0;
+        let mut active = 0;
+        let mut exited = 0;
+        let mut withdrawable = 0;
+        for i in self.validators.values().filter_map(|v| v.index) {
+            let i = i as usize;
+            if let Some(validator_balance) = state.balances().get(i) {
+                balance += *validator_balance;
+            }
+            if let Some(validator) = state.validators().get(i) {
+                effective_balance += validator.effective_balance;
+                slashed += validator.slashed as i64;
+                active += validator.is_active_at(current_epoch) as i64;
+                exited += validator.is_exited_at(current_epoch) as i64;
+                withdrawable += validator.is_withdrawable_at(current_epoch) as i64;
+            }
+        }
+
+        let label = &[TOTAL_LABEL];
+        metrics::set_int_gauge(
+            &metrics::VALIDATOR_MONITOR_BALANCE_GWEI,
+            label,
+            u64_to_i64(balance),
+        );
+        metrics::set_int_gauge(
+            &metrics::VALIDATOR_MONITOR_EFFECTIVE_BALANCE_GWEI,
+            label,
+            u64_to_i64(effective_balance),
+        );
+        metrics::set_int_gauge(&metrics::VALIDATOR_MONITOR_SLASHED, label, slashed);
+        metrics::set_int_gauge(&metrics::VALIDATOR_MONITOR_ACTIVE, label, active);
+        metrics::set_int_gauge(&metrics::VALIDATOR_MONITOR_EXITED, label, exited);
+        metrics::set_int_gauge(
+            &metrics::VALIDATOR_MONITOR_WITHDRAWABLE,
+            label,
+            withdrawable,
+        );
+    }
+
+    /// Log and count the block proposals missed by monitored validators in the skipped slots of
+    /// the `state`'s current epoch.
+    fn check_missed_blocks(&mut self, state: &BeaconState<T>, spec: &ChainSpec) {
+        let slots_per_epoch = T::slots_per_epoch();
+        let epoch = state.current_epoch();
+
+        // Only the slots prior to the state's latest block are known to have been skipped.
+        let start_slot = std::cmp::max(epoch.start_slot(slots_per_epoch), Slot::new(1));
+        let end_slot = state.latest_block_header().slot;
+        let skipped_slots = (start_slot.as_u64()..end_slot.as_u64())
+            .map(Slot::new)
+            .filter(|slot| {
+                matches!(
+                    (state.get_block_root(*slot), state.get_block_root(*slot - 1)),
+                    (Ok(root), Ok(prev_root)) if root == prev_root
+                )
+            })
+            .collect::<Vec<_>>();
+        if skipped_slots.is_empty() {
+            return;
+        }
+
+        let proposers = match state.get_beacon_proposer_indices(spec) {
+            Ok(proposers) => proposers,
+            Err(e) => {
+                debug!(
+                    self.log,
+                    "Unable to check for missed blocks";
+                    "error" => ?e,
+                    "epoch" => epoch,
+                );
+                return;
+            }
+        };
+
+        for slot in skipped_slots {
+            let proposer = match proposers.get(slot.as_usize() % slots_per_epoch as usize) {
+                Some(proposer) => *proposer as u64,
+                None => continue,
+            };
+            // Borrow the fields directly so that `self.missed_blocks` can be mutated.
+            let validator = self
+                .indices
+                .get(&proposer)
+                .and_then(|pubkey| self.validators.get(pubkey));
+            if let Some(validator) = validator {
+                if self.missed_blocks.insert((proposer, slot)) {
+                    metrics::inc_counter_vec(
+                        &metrics::VALIDATOR_MONITOR_MISSED_BLOCKS_TOTAL,
+                        &[self.metric_label(&validator.id)],
                     );
-                    metrics::set_int_gauge(
-                        &metrics::VALIDATOR_WITHDRAWABLE_EPOCH,
-                        &[id],
-                        u64_to_i64(validator.withdrawable_epoch),
+                    validator.with_epoch_summary(epoch, |summary| summary.register_missed_block());
+                    warn!(
+                        self.log,
+                        "Validator missed a block proposal";
+                        "epoch" => %epoch,
+                        "slot" => %slot,
+                        "validator" => %validator.id,
                     );
                 }
             }
         }
+
+        self.missed_blocks
+            .retain(|(_, slot)| slot.epoch(slots_per_epoch) + HISTORIC_EPOCHS as u64 >= epoch);
     }
 
     pub fn process_validator_statuses(
@@ -391,6 +638,9 @@ impl<T: EthSpec> ValidatorMonitor<T> {
         let mut head_miss = Vec::new();
         let mut target_miss = Vec::new();
         let mut suboptimal_inclusion = Vec::new();
+        let mut num_active = 0;
+        let mut num_in_sync_committee = 0;
+        let individual_tracking = self.individual_tracking();
 
         // We subtract two from the state of the epoch that generated these summaries.
         //
@@ -402,6 +652,7 @@ impl<T: EthSpec> ValidatorMonitor<T> {
             if let Some(i) = monitored_validator.index {
                 let i = i as usize;
                 let id = &monitored_validator.id;
+                let label = self.metric_label(id);
 
                 /*
                  * These metrics are reflected differently between Base and Altair.
@@ -426,6 +677,15 @@ impl<T: EthSpec> ValidatorMonitor<T> {
                     // attestations.
                     continue;
                 }
+                num_active += 1;
+
+                monitored_validator.with_epoch_summary(prev_epoch, |summary| {
+                    summary.register_on_chain_attestation(OnChainAttestation {
+                        included: previous_epoch_matched_any,
+                        matched_head: previous_epoch_matched_head,
+                        matched_target: previous_epoch_matched_target,
+                    })
+                });
 
                 // Indicates if any attestation made it on-chain.
                 //
@@ -434,7 +694,7 @@ impl<T: EthSpec> ValidatorMonitor<T> {
                 if previous_epoch_matched_any {
                     metrics::inc_counter_vec(
                         &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_ATTESTER_HIT,
-                        &[id],
+                        &[label],
                     );
                     attestation_success.push(id);
                     debug!(
@@ -449,7 +709,7 @@ impl<T: EthSpec> ValidatorMonitor<T> {
                 } else {
                     metrics::inc_counter_vec(
                         &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_ATTESTER_MISS,
-                        &[id],
+                        &[label],
                     );
                     attestation_miss.push(id);
                     debug!(
@@ -464,12 +724,12 @@ impl<T: EthSpec> ValidatorMonitor<T> {
                 if previous_epoch_matched_head {
                     metrics::inc_counter_vec(
                         &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_HEAD_ATTESTER_HIT,
-                        &[id],
+                        &[label],
                     );
                 } else {
                     metrics::inc_counter_vec(
                         &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_HEAD_ATTESTER_MISS,
-                        &[id],
+                        &[label],
                     );
                     head_miss.push(id);
                     debug!(
@@ -484,12 +744,12 @@ impl<T: EthSpec> ValidatorMonitor<T> {
                 if previous_epoch_matched_target {
                     metrics::inc_counter_vec(
                         &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_TARGET_ATTESTER_HIT,
-                        &[id],
+                        &[label],
                     );
                 } else {
                     metrics::inc_counter_vec(
                         &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_TARGET_ATTESTER_MISS,
-                        &[id],
+                        &[label],
                     );
                     target_miss.push(id);
                     debug!(
@@ -522,11 +782,17 @@ impl<T: EthSpec> ValidatorMonitor<T> {
                         );
                     }
 
-                    metrics::set_int_gauge(
-                        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_INCLUSION_DISTANCE,
-                        &[id],
-                        inclusion_delay as i64,
+                    metrics::observe(
+                        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_INCLUSION_DISTANCE_SLOTS,
+                        inclusion_delay as f64,
                     );
+                    if individual_tracking {
+                        metrics::set_int_gauge(
+                            &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_INCLUSION_DISTANCE,
+                            &[id],
+                            inclusion_delay as i64,
+                        );
+                    }
                 }
 
                 // Indicates the number of sync committee signatures that made it into
@@ -537,11 +803,14 @@ impl<T: EthSpec> ValidatorMonitor<T> {
                 let current_epoch = epoch - 1;
                 if let Some(sync_committee) = summary.sync_committee() {
                     if sync_committee.contains(pubkey) {
-                        metrics::set_int_gauge(
-                            &metrics::VALIDATOR_MONITOR_VALIDATOR_IN_CURRENT_SYNC_COMMITTEE,
-                            &[id],
-                            1,
-                        );
+                        num_in_sync_committee += 1;
+                        if individual_tracking {
+                            metrics::set_int_gauge(
+                                &metrics::VALIDATOR_MONITOR_VALIDATOR_IN_CURRENT_SYNC_COMMITTEE,
+                                &[id],
+                                1,
+                            );
+                        }
                         let epoch_summary = monitored_validator.summaries.read();
                         if let Some(summary) = epoch_summary.get(&current_epoch) {
                             info!(
@@ -554,11 +823,13 @@ impl<T: EthSpec> ValidatorMonitor<T> {
                             );
                         }
                     } else {
-                        metrics::set_int_gauge(
-                            &metrics::VALIDATOR_MONITOR_VALIDATOR_IN_CURRENT_SYNC_COMMITTEE,
-                            &[id],
-                            0,
-                        );
+                        if individual_tracking {
+                            metrics::set_int_gauge(
+                                &metrics::VALIDATOR_MONITOR_VALIDATOR_IN_CURRENT_SYNC_COMMITTEE,
+                                &[id],
+                                0,
+                            );
+                        }
                         debug!(
                             self.log,
                             "Validator isn't part of the current sync committee";
@@ -570,6 +841,29 @@ impl<T: EthSpec> ValidatorMonitor<T> {
             }
         }
 
+        if !individual_tracking && summary.sync_committee().is_some() {
+            metrics::set_int_gauge(
+                &metrics::VALIDATOR_MONITOR_VALIDATOR_IN_CURRENT_SYNC_COMMITTEE,
+                &[TOTAL_LABEL],
+                num_in_sync_committee,
+            );
+        }
+
+        // The proportion of active monitored validators whose attestations made it on-chain.
+        if num_active > 0 {
+            for (flag, num_misses) in [
+                ("any", attestation_miss.len()),
+                ("head", head_miss.len()),
+                ("target", target_miss.len()),
+            ] {
+                metrics::set_float_gauge_vec(
+                    &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_HIT_RATE,
+                    &[flag],
+                    1.0 - num_misses as f64 / num_active as f64,
+                );
+            }
+        }
+
         // Aggregate logging for attestation success/failures over an epoch
         // for all validators managed by the validator monitor.
         if !attestation_success.is_empty() {
@@ -632,11 +926,50 @@ impl<T: EthSpec> ValidatorMonitor<T> {
             .and_then(|pubkey| self.validators.get(pubkey))
     }
 
+    /// Returns the public key of the validator with `validator_index`, if it is monitored.
+    pub fn get_monitored_validator_pubkey(&self, validator_index: u64) -> Option<PublicKeyBytes> {
+        self.indices
+            .get(&validator_index)
+            .filter(|pubkey| self.validators.contains_key(*pubkey))
+            .copied()
+    }
+
     /// Returns the number of validators monitored by `self`.
     pub fn num_validators(&self) -> usize {
         self.validators.len()
     }
 
+    /// Returns `true` if validators may be registered automatically.
+    pub fn auto_register(&self) -> bool {
+        self.auto_register
+    }
+
+    /// If `self.auto_register == true`, add the validator with `pubkey` and, if known,
+    /// `validator_index` to `self.monitored_validators`, returning `true` if it wasn't already
+    /// monitored. Otherwise, do nothing.
+    ///
+    /// Unlike `auto_register_local_validator`, this allows validators which are not yet in the
+    /// state to be monitored from the moment they're deposited.
+    pub fn auto_register_local_validator_pubkey(
+        &mut self,
+        pubkey: PublicKeyBytes,
+        validator_index: Option<u64>,
+    ) -> bool {
+        if !self.auto_register || self.validators.contains_key(&pubkey) {
+            return false;
+        }
+
+        info!(
+            self.log,
+            "Started monitoring validator";
+            "pubkey" => %pubkey,
+            "validator" => ?validator_index,
+        );
+        self.validators
+            .insert(pubkey, MonitoredValidator::new(pubkey, validator_index));
+        true
+    }
+
     /// If `self.auto_register == true`, add the `validator_index` to `self.monitored_validators`.
     /// Otherwise, do nothing.
     pub fn auto_register_local_validator(&mut self, validator_index: u64) {
@@ -692,12 +1025,16 @@ impl<T: EthSpec> ValidatorMonitor<T> {
         slot_clock: &S,
     ) {
         if let Some(id) = self.get_validator_id(block.proposer_index()) {
+            let label = self.metric_label(id);
             let delay = get_block_delay_ms(seen_timestamp, block, slot_clock);
 
-            metrics::inc_counter_vec(&metrics::VALIDATOR_MONITOR_BEACON_BLOCK_TOTAL, &[src, id]);
+            metrics::inc_counter_vec(
+                &metrics::VALIDATOR_MONITOR_BEACON_BLOCK_TOTAL,
+                &[src, label],
+            );
             metrics::observe_timer_vec(
                 &metrics::VALIDATOR_MONITOR_BEACON_BLOCK_DELAY_SECONDS,
-                &[src, id],
+                &[src, label],
                 delay,
             );
 
@@ -762,14 +1099,15 @@ impl<T: EthSpec> ValidatorMonitor<T> {
         indexed_attestation.attesting_indices.iter().for_each(|i| {
             if let Some(validator) = self.get_validator(*i) {
                 let id = &validator.id;
+                let label = self.metric_label(id);
 
                 metrics::inc_counter_vec(
                     &metrics::VALIDATOR_MONITOR_UNAGGREGATED_ATTESTATION_TOTAL,
-                    &[src, id],
+                    &[src, label],
                 );
                 metrics::observe_timer_vec(
                     &metrics::VALIDATOR_MONITOR_UNAGGREGATED_ATTESTATION_DELAY_SECONDS,
-                    &[src, id],
+                    &[src, label],
                     delay,
                 );
 
@@ -846,14 +1184,15 @@ impl<T: EthSpec> ValidatorMonitor<T> {
         let aggregator_index = signed_aggregate_and_proof.message.aggregator_index;
         if let Some(validator) = self.get_validator(aggregator_index) {
             let id = &validator.id;
+            let label = self.metric_label(id);
 
             metrics::inc_counter_vec(
                 &metrics::VALIDATOR_MONITOR_AGGREGATED_ATTESTATION_TOTAL,
-                &[src, id],
+                &[src, label],
             );
             metrics::observe_timer_vec(
                 &metrics::VALIDATOR_MONITOR_AGGREGATED_ATTESTATION_DELAY_SECONDS,
-                &[src, id],
+                &[src, label],
                 delay,
             );
 
@@ -877,14 +1216,15 @@ impl<T: EthSpec> ValidatorMonitor<T> {
         indexed_attestation.attesting_indices.iter().for_each(|i| {
             if let Some(validator) = self.get_validator(*i) {
                 let id = &validator.id;
+                let label = self.metric_label(id);
 
                 metrics::inc_counter_vec(
                     &metrics::VALIDATOR_MONITOR_ATTESTATION_IN_AGGREGATE_TOTAL,
-                    &[src, id],
+                    &[src, label],
                 );
                 metrics::observe_timer_vec(
                     &metrics::VALIDATOR_MONITOR_ATTESTATION_IN_AGGREGATE_DELAY_SECONDS,
-                    &[src, id],
+                    &[src, label],
                     delay,
                 );
 
@@ -931,16 +1271,19 @@ impl<T: EthSpec> ValidatorMonitor<T> {
         indexed_attestation.attesting_indices.iter().for_each(|i| {
             if let Some(validator) = self.get_validator(*i) {
                 let id = &validator.id;
+                let label = self.metric_label(id);
 
                 metrics::inc_counter_vec(
                     &metrics::VALIDATOR_MONITOR_ATTESTATION_IN_BLOCK_TOTAL,
-                    &["block", id],
-                );
-                metrics::set_int_gauge(
-                    &metrics::VALIDATOR_MONITOR_ATTESTATION_IN_BLOCK_DELAY_SLOTS,
-                    &["block", id],
-                    delay.as_u64() as i64,
+                    &["block", label],
                 );
+                if self.individual_tracking() {
+                    metrics::set_int_gauge(
+                        &metrics::VALIDATOR_MONITOR_ATTESTATION_IN_BLOCK_DELAY_SLOTS,
+                        &["block", id],
+                        delay.as_u64() as i64,
+                    );
+                }
 
                 info!(
                     self.log,
@@ -1000,6 +1343,7 @@ impl<T: EthSpec> ValidatorMonitor<T> {
     ) {
         if let Some(validator) = self.get_validator(sync_committee_message.validator_index) {
             let id = &validator.id;
+            let label = self.metric_label(id);
 
             let epoch = sync_committee_message.slot.epoch(T::slots_per_epoch());
             let delay = get_message_delay_ms(
@@ -1011,11 +1355,11 @@ impl<T: EthSpec> ValidatorMonitor<T> {
 
             metrics::inc_counter_vec(
                 &metrics::VALIDATOR_MONITOR_SYNC_COMMITTEE_MESSAGES_TOTAL,
-                &[src, id],
+                &[src, label],
             );
             metrics::observe_timer_vec(
                 &metrics::VALIDATOR_MONITOR_SYNC_COMMITTEE_MESSAGES_DELAY_SECONDS,
-                &[src, id],
+                &[src, label],
                 delay,
             );
 
@@ -1092,14 +1436,15 @@ impl<T: EthSpec> ValidatorMonitor<T> {
         let aggregator_index = sync_contribution.message.aggregator_index;
         if let Some(validator) = self.get_validator(aggregator_index) {
             let id = &validator.id;
+            let label = self.metric_label(id);
 
             metrics::inc_counter_vec(
                 &metrics::VALIDATOR_MONITOR_SYNC_CONTRIBUTIONS_TOTAL,
-                &[src, id],
+                &[src, label],
             );
             metrics::observe_timer_vec(
                 &metrics::VALIDATOR_MONITOR_SYNC_CONTRIBUTIONS_DELAY_SECONDS,
-                &[src, id],
+                &[src, label],
                 delay,
             );
 
@@ -1122,10 +1467,11 @@ impl<T: EthSpec> ValidatorMonitor<T> {
         for validator_pubkey in participant_pubkeys.iter() {
             if let Some(validator) = self.validators.get(validator_pubkey) {
                 let id = &validator.id;
+                let label = self.metric_label(id);
 
                 metrics::inc_counter_vec(
                     &metrics::VALIDATOR_MONITOR_SYNC_COMMITTEE_MESSAGE_IN_CONTRIBUTION_TOTAL,
-                    &[src, id],
+                    &[src, label],
                 );
 
                 info!(
@@ -1147,21 +1493,50 @@ impl<T: EthSpec> ValidatorMonitor<T> {
     }
 
     /// Register that the `sync_aggregate` was included in a *valid* `BeaconBlock`.
+    ///
+    /// The `non_participant_pubkeys` are the members of the sync committee whose signatures are
+    /// missing from the `sync_aggregate`.
     pub fn register_sync_aggregate_in_block(
         &self,
         slot: Slot,
         beacon_block_root: Hash256,
         participant_pubkeys: Vec<&PublicKeyBytes>,
+        non_participant_pubkeys: Vec<&PublicKeyBytes>,
     ) {
         let epoch = slot.epoch(T::slots_per_epoch());
 
+        for validator_pubkey in non_participant_pubkeys {
+            if let Some(validator) = self.validators.get(validator_pubkey) {
+                let id = &validator.id;
+                let label = self.metric_label(id);
+
+                metrics::inc_counter_vec(
+                    &metrics::VALIDATOR_MONITOR_SYNC_COMMITTEE_MESSAGE_MISSED_TOTAL,
+                    &[label],
+                );
+                validator.with_epoch_summary(epoch, |summary| {
+                    summary.register_sync_signature_block_miss();
+                });
+
+                warn!(
+                    self.log,
+                    "Sync signature missing from block";
+                    "head" => %beacon_block_root,
+                    "epoch" => %epoch,
+                    "slot" => %slot,
+                    "validator" => %id,
+                );
+            }
+        }
+
         for validator_pubkey in participant_pubkeys {
             if let Some(validator) = self.validators.get(validator_pubkey) {
                 let id = &validator.id;
+                let label = self.metric_label(id);
 
                 metrics::inc_counter_vec(
                     &metrics::VALIDATOR_MONITOR_SYNC_COMMITTEE_MESSAGE_IN_BLOCK_TOTAL,
-                    &["block", id],
+                    &["block", label],
                 );
 
                 info!(
@@ -1198,9 +1573,10 @@ impl<T: EthSpec> ValidatorMonitor<T> {
     fn register_voluntary_exit(&self, src: &str, exit: &VoluntaryExit) {
         if let Some(validator) = self.get_validator(exit.validator_index) {
             let id = &validator.id;
+            let label = self.metric_label(id);
             let epoch = exit.epoch;
 
-            metrics::inc_counter_vec(&metrics::VALIDATOR_MONITOR_EXIT_TOTAL, &[src, id]);
+            metrics::inc_counter_vec(&metrics::VALIDATOR_MONITOR_EXIT_TOTAL, &[src, label]);
 
             info!(
                 self.log,
@@ -1238,10 +1614,11 @@ impl<T: EthSpec> ValidatorMonitor<T> {
 
         if let Some(validator) = self.get_validator(proposer) {
             let id = &validator.id;
+            let label = self.metric_label(id);
 
             metrics::inc_counter_vec(
                 &metrics::VALIDATOR_MONITOR_PROPOSER_SLASHING_TOTAL,
-                &[src, id],
+                &[src, label],
             );
 
             crit!(
@@ -1290,11 +1667,12 @@ impl<T: EthSpec> ValidatorMonitor<T> {
             .filter_map(|index| self.get_validator(*index))
             .for_each(|validator| {
                 let id = &validator.id;
+                let label = self.metric_label(id);
                 let epoch = data.slot.epoch(T::slots_per_epoch());
 
                 metrics::inc_counter_vec(
                     &metrics::VALIDATOR_MONITOR_ATTESTER_SLASHING_TOTAL,
-                    &[src, id],
+                    &[src, label],
                 );
 
                 crit!(
@@ -1338,137 +1716,174 @@ impl<T: EthSpec> ValidatorMonitor<T> {
                 epoch - 2
             };
 
-            for (_, validator) in self.validators.iter() {
-                let id = &validator.id;
+            let individual_tracking = self.individual_tracking();
+            let mut total = EpochSummary::default();
+            for validator in self.validators.values() {
                 let summaries = validator.summaries.read();
 
                 if let Some(summary) = summaries.get(&previous_epoch) {
-                    /*
-                     * Attestations
-                     */
-                    metrics::set_gauge_vec(
-                        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATIONS_TOTAL,
-                        &[id],
-                        summary.attestations as i64,
-                    );
+                    if individual_tracking {
+                        set_epoch_summary_gauges(&validator.id, summary);
+                    } else {
+                        total.add_counts(summary);
+                    }
+
+                    // With individual tracking disabled, the delays of all validators are observed
+                    // by the same histograms.
+                    let label = self.metric_label(&validator.id);
                     if let Some(delay) = summary.attestation_min_delay {
                         metrics::observe_timer_vec(
                             &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATIONS_MIN_DELAY_SECONDS,
-                            &[id],
+                            &[label],
                             delay,
                         );
                     }
-                    metrics::set_gauge_vec(
-                        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATION_AGGREGATE_INCLUSIONS,
-                        &[id],
-                        summary.attestation_aggregate_inclusions as i64,
-                    );
-                    metrics::set_gauge_vec(
-                        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATION_BLOCK_INCLUSIONS,
-                        &[id],
-                        summary.attestation_block_inclusions as i64,
-                    );
-                    if let Some(distance) = summary.attestation_min_block_inclusion_distance {
-                        metrics::set_gauge_vec(
-                            &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATION_BLOCK_MIN_INCLUSION_DISTANCE,
-                            &[id],
-                            distance.as_u64() as i64,
-                        );
-                    }
-                    /*
-                     * Sync committee messages
-                     */
-                    metrics::set_gauge_vec(
-                        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_SYNC_COMMITTEE_MESSAGES_TOTAL,
-                        &[id],
-                        summary.sync_committee_messages as i64,
-                    );
                     if let Some(delay) = summary.sync_committee_message_min_delay {
                         metrics::observe_timer_vec(
                             &metrics::VALIDATOR_MONITOR_PREV_EPOCH_SYNC_COMMITTEE_MESSAGES_MIN_DELAY_SECONDS,
-                            &[id],
+                            &[label],
                             delay,
                         );
                     }
-                    metrics::set_gauge_vec(
-                        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_SYNC_CONTRIBUTION_INCLUSIONS,
-                        &[id],
-                        summary.sync_signature_contribution_inclusions as i64,
-                    );
-                    metrics::set_gauge_vec(
-                        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_SYNC_SIGNATURE_BLOCK_INCLUSIONS,
-                        &[id],
-                        summary.sync_signature_block_inclusions as i64,
-                    );
-
-                    /*
-                     * Sync contributions
-                     */
-                    metrics::set_gauge_vec(
-                        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_SYNC_CONTRIBUTIONS_TOTAL,
-                        &[id],
-                        summary.sync_contributions as i64,
-                    );
                     if let Some(delay) = summary.sync_contribution_min_delay {
                         metrics::observe_timer_vec(
                             &metrics::VALIDATOR_MONITOR_PREV_EPOCH_SYNC_CONTRIBUTION_MIN_DELAY_SECONDS,
-                            &[id],
+                            &[label],
                             delay,
                         );
                     }
-
-                    /*
-                     * Blocks
-                     */
-                    metrics::set_gauge_vec(
-                        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_BEACON_BLOCKS_TOTAL,
-                        &[id],
-                        summary.blocks as i64,
-                    );
                     if let Some(delay) = summary.block_min_delay {
                         metrics::observe_timer_vec(
                             &metrics::VALIDATOR_MONITOR_PREV_EPOCH_BEACON_BLOCKS_MIN_DELAY_SECONDS,
-                            &[id],
+                            &[label],
                             delay,
                         );
                     }
-                    /*
-                     * Aggregates
-                     */
-                    metrics::set_gauge_vec(
-                        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_AGGREGATES_TOTAL,
-                        &[id],
-                        summary.aggregates as i64,
-                    );
                     if let Some(delay) = summary.aggregate_min_delay {
                         metrics::observe_timer_vec(
                             &metrics::VALIDATOR_MONITOR_PREV_EPOCH_AGGREGATES_MIN_DELAY_SECONDS,
-                            &[id],
+                            &[label],
                             delay,
                         );
                     }
-                    /*
-                     * Other
-                     */
-                    metrics::set_gauge_vec(
-                        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_EXITS_TOTAL,
-                        &[id],
-                        summary.exits as i64,
-                    );
-                    metrics::set_gauge_vec(
-                        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_PROPOSER_SLASHINGS_TOTAL,
-                        &[id],
-                        summary.proposer_slashings as i64,
-                    );
-                    metrics::set_gauge_vec(
-                        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ATTESTER_SLASHINGS_TOTAL,
-                        &[id],
-                        summary.attester_slashings as i64,
-                    );
                 }
             }
+
+            if !individual_tracking {
+                set_epoch_summary_gauges(TOTAL_LABEL, &total);
+            }
         }
     }
+
+    /// Returns the recent history of each monitored validator, for reporting via the HTTP API.
+    pub fn validator_summaries(&self) -> Vec<MonitoredValidatorSummary> {
+        self.validators
+            .values()
+            .map(|validator| {
+                let summaries = validator.summaries.read();
+                let mut epochs = summaries
+                    .iter()
+                    .map(|(epoch, summary)| summary.to_api_summary(*epoch))
+                    .collect::<Vec<_>>();
+                epochs.sort_by_key(|summary| summary.epoch);
+                MonitoredValidatorSummary {
+                    pubkey: validator.pubkey,
+                    index: validator.index,
+                    epochs,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Set the gauges for the counts of a validator's `summary` of the previous epoch. The minimum
+/// block inclusion distance is only set if known.
+fn set_epoch_summary_gauges(label: &str, summary: &EpochSummary) {
+    /*
+     * Attestations
+     */
+    metrics::set_gauge_vec(
+        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATIONS_TOTAL,
+        &[label],
+        summary.attestations as i64,
+    );
+    metrics::set_gauge_vec(
+        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATION_AGGREGATE_INCLUSIONS,
+        &[label],
+        summary.attestation_aggregate_inclusions as i64,
+    );
+    metrics::set_gauge_vec(
+        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATION_BLOCK_INCLUSIONS,
+        &[label],
+        summary.attestation_block_inclusions as i64,
+    );
+    if let Some(distance) = summary.attestation_min_block_inclusion_distance {
+        metrics::set_gauge_vec(
+            &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATION_BLOCK_MIN_INCLUSION_DISTANCE,
+            &[label],
+            distance.as_u64() as i64,
+        );
+    }
+    /*
+     * Sync committee messages
+     */
+    metrics::set_gauge_vec(
+        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_SYNC_COMMITTEE_MESSAGES_TOTAL,
+        &[label],
+        summary.sync_committee_messages as i64,
+    );
+    metrics::set_gauge_vec(
+        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_SYNC_CONTRIBUTION_INCLUSIONS,
+        &[label],
+        summary.sync_signature_contribution_inclusions as i64,
+    );
+    metrics::set_gauge_vec(
+        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_SYNC_SIGNATURE_BLOCK_INCLUSIONS,
+        &[label],
+        summary.sync_signature_block_inclusions as i64,
+    );
+    /*
+     * Sync contributions
+     */
+    metrics::set_gauge_vec(
+        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_SYNC_CONTRIBUTIONS_TOTAL,
+        &[label],
+        summary.sync_contributions as i64,
+    );
+    /*
+     * Blocks
+     */
+    metrics::set_gauge_vec(
+        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_BEACON_BLOCKS_TOTAL,
+        &[label],
+        summary.blocks as i64,
+    );
+    /*
+     * Aggregates
+     */
+    metrics::set_gauge_vec(
+        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_AGGREGATES_TOTAL,
+        &[label],
+        summary.aggregates as i64,
+    );
+    /*
+     * Other
+     */
+    metrics::set_gauge_vec(
+        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_EXITS_TOTAL,
+        &[label],
+        summary.exits as i64,
+    );
+    metrics::set_gauge_vec(
+        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_PROPOSER_SLASHINGS_TOTAL,
+        &[label],
+        summary.proposer_slashings as i64,
+    );
+    metrics::set_gauge_vec(
+        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ATTESTER_SLASHINGS_TOTAL,
+        &[label],
+        summary.attester_slashings as i64,
+    );
 }
 
 /// Returns the duration since the unix epoch.
@@ -1,9 +1,9 @@
 use crate::errors::BeaconChainError;
 use crate::{BeaconChainTypes, BeaconStore};
 use ssz::{Decode, Encode};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
-use store::{DBColumn, Error as StoreError, StoreItem};
+use store::{DBColumn, Error as StoreError, StoreItem, StoreOp};
 use types::{BeaconState, Hash256, PublicKey, PublicKeyBytes};
 
 /// Provides a mapping of `validator_index -> validator_publickey`.
@@ -15,8 +15,11 @@ use types::{BeaconState, Hash256, PublicKey, PublicKeyBytes};
 ///    keys in compressed form and they are needed in decompressed form for signature verification.
 ///    Decompression is expensive when many keys are involved.
 ///
-/// The cache has a `backing` that it uses to maintain a persistent, on-disk
-/// copy of itself. This allows it to be restored between process invocations.
+/// The cache is persisted in the `PubkeyCache` column of the hot database, so that it can be
+/// restored between process invocations. Newly imported keys are written atomically with the
+/// block that introduced them, so the persisted cache always covers the states in the database.
+/// They're only added to the in-memory cache once that write has succeeded, see
+/// `import_new_pubkeys` and `commit_pubkeys`.
 pub struct ValidatorPubkeyCache<T: BeaconChainTypes> {
     pubkeys: Vec<PublicKey>,
     indices: HashMap<PublicKeyBytes, usize>,
@@ -27,8 +30,7 @@ pub struct ValidatorPubkeyCache<T: BeaconChainTypes> {
 impl<T: BeaconChainTypes> ValidatorPubkeyCache<T> {
     /// Create a new public key cache using the keys in `state.validators`.
     ///
-    /// Also creates a new persistence file, returning an error if there is already a file at
-    /// `persistence_path`.
+    /// Also persists the keys to the database.
     pub fn new(
         state: &BeaconState<T::EthSpec>,
         store: BeaconStore<T>,
@@ -40,7 +42,9 @@ impl<T: BeaconChainTypes> ValidatorPubkeyCache<T> {
             store,
         };
 
-        cache.import_new_pubkeys(state)?;
+        let (store_ops, pending) = cache.import_new_pubkeys(state)?;
+        cache.store.do_atomically(store_ops)?;
+        cache.commit_pubkeys(pending);
 
         Ok(cache)
     }
@@ -73,63 +77,66 @@ impl<T: BeaconChainTypes> ValidatorPubkeyCache<T> {
         })
     }
 
-    /// Scan the given `state` and add any new validator public keys.
+    /// Scan the given `state` for validator public keys which are missing from `self`.
     ///
-    /// Does not delete any keys from `self` if they don't appear in `state`.
+    /// Does not modify `self`. Returns the database operations which persist the new keys, along
+    /// with the keys themselves. The operations should be applied atomically with the storage of
+    /// `state` (or the block which produced it), so that the persisted cache never lags behind
+    /// the states in the database, and the keys passed to `commit_pubkeys` once that succeeds.
     pub fn import_new_pubkeys(
-        &mut self,
+        &self,
         state: &BeaconState<T::EthSpec>,
-    ) -> Result<(), BeaconChainError> {
-        if state.validators().len() > self.pubkeys.len() {
-            self.import(
-                state.validators()[self.pubkeys.len()..]
-                    .iter()
-                    .map(|v| v.pubkey),
-            )
-        } else {
-            Ok(())
-        }
-    }
-
-    /// Adds zero or more validators to `self`.
-    fn import<I>(&mut self, validator_keys: I) -> Result<(), BeaconChainError>
-    where
-        I: Iterator<Item = PublicKeyBytes> + ExactSizeIterator,
-    {
-        self.pubkey_bytes.reserve(validator_keys.len());
-        self.pubkeys.reserve(validator_keys.len());
-        self.indices.reserve(validator_keys.len());
-
-        for pubkey in validator_keys {
-            let i = self.pubkeys.len();
-
-            if self.indices.contains_key(&pubkey) {
+    ) -> Result<(Vec<StoreOp<'static, T::EthSpec>>, PendingPubkeys), BeaconChainError> {
+        let start_index = self.pubkeys.len();
+        let new_keys = state
+            .validators()
+            .iter()
+            .skip(start_index)
+            .map(|v| v.pubkey);
+
+        let mut store_ops = Vec::with_capacity(new_keys.len());
+        let mut pubkeys = Vec::with_capacity(new_keys.len());
+        let mut seen = HashSet::with_capacity(new_keys.len());
+        for (i, pubkey) in (start_index..).zip(new_keys) {
+            if self.indices.contains_key(&pubkey) || !seen.insert(pubkey) {
                 return Err(BeaconChainError::DuplicateValidatorPublicKey);
             }
 
-            // The item is written to disk _before_ it is written into
-            // the local struct.
-            //
-            // This means that a pubkey cache read from disk will always be equivalent to or
-            // _later than_ the cache that was running in the previous instance of Lighthouse.
-            //
-            // The motivation behind this ordering is that we do not want to have states that
-            // reference a pubkey that is not in our cache. However, it's fine to have pubkeys
-            // that are never referenced in a state.
-            self.store
-                .put_item(&DatabasePubkey::key_for_index(i), &DatabasePubkey(pubkey))?;
-
-            self.pubkeys.push(
-                (&pubkey)
-                    .try_into()
-                    .map_err(BeaconChainError::InvalidValidatorPubkeyBytes)?,
-            );
-            self.pubkey_bytes.push(pubkey);
-
-            self.indices.insert(pubkey, i);
+            store_ops.push(StoreOp::KeyValueOp(
+                DatabasePubkey(pubkey).as_kv_store_op(DatabasePubkey::key_for_index(i)),
+            ));
+            let decompressed: PublicKey = (&pubkey)
+                .try_into()
+                .map_err(BeaconChainError::InvalidValidatorPubkeyBytes)?;
+            pubkeys.push((pubkey, decompressed));
         }
 
-        Ok(())
+        Ok((
+            store_ops,
+            PendingPubkeys {
+                start_index,
+                pubkeys,
+            },
+        ))
+    }
+
+    /// Add keys returned by `import_new_pubkeys` to `self`, after their database operations have
+    /// been applied.
+    ///
+    /// Keys which have been added since `pending` was created, e.g. by a concurrent block import,
+    /// are skipped.
+    pub fn commit_pubkeys(&mut self, pending: PendingPubkeys) {
+        let already_added = self.pubkeys.len().saturating_sub(pending.start_index);
+        let new_keys = pending.pubkeys.into_iter().skip(already_added);
+
+        self.pubkey_bytes.reserve(new_keys.len());
+        self.pubkeys.reserve(new_keys.len());
+        self.indices.reserve(new_keys.len());
+        for (pubkey_bytes, pubkey) in new_keys {
+            self.indices.insert(pubkey_bytes, self.pubkeys.len());
+            self.pubkey_bytes.push(pubkey_bytes);
+            self.pubkeys.push(pubkey);
+        }
     }
 
     /// Get the public key for a validator with index `i`.
@@ -158,6 +165,27 @@ impl<T: BeaconChainTypes> ValidatorPubkeyCache<T> {
     }
 }
 
+/// Validator public keys returned by `ValidatorPubkeyCache::import_new_pubkeys` which have yet to
+/// be added to the cache.
+#[must_use = "the keys are only added to the cache by `commit_pubkeys`"]
+pub struct PendingPubkeys {
+    /// The validator index of the first key.
+    start_index: usize,
+    pubkeys: Vec<(PublicKeyBytes, PublicKey)>,
+}
+
+impl PendingPubkeys {
+    /// Returns the number of pending keys.
+    pub fn len(&self) -> usize {
+        self.pubkeys.len()
+    }
+
+    /// Returns `true` if there are no pending keys.
+    pub fn is_empty(&self) -> bool {
+        self.pubkeys.is_empty()
+    }
+}
+
 /// Wrapper for a public key stored in the database.
 ///
 /// Keyed by the validator index as `Hash256::from_low_u64_be(index)`.
@@ -253,23 +281,26 @@ mod test {
 
         // Try adding a state with the same number of keypairs.
         let (state, keypairs) = get_state(8);
-        cache
+        let (_, pending) = cache
             .import_new_pubkeys(&state)
             .expect("should import pubkeys");
+        cache.commit_pubkeys(pending);
         check_cache_get(&cache, &keypairs[..]);
 
         // Try adding a state with less keypairs.
         let (state, _) = get_state(1);
-        cache
+        let (_, pending) = cache
             .import_new_pubkeys(&state)
             .expect("should import pubkeys");
+        cache.commit_pubkeys(pending);
         check_cache_get(&cache, &keypairs[..]);
 
         // Try adding a state with more keypairs.
         let (state, keypairs) = get_state(12);
-        cache
+        let (_, pending) = cache
             .import_new_pubkeys(&state)
             .expect("should import pubkeys");
+        cache.commit_pubkeys(pending);
         check_cache_get(&cache, &keypairs[..]);
     }
 
@@ -291,9 +322,11 @@ mod test {
 
         // Add some more keypairs.
         let (state, keypairs) = get_state(12);
-        cache
+        let (ops, pending) = cache
             .import_new_pubkeys(&state)
             .expect("should import pubkeys");
+        store.do_atomically(ops).expect("should persist pubkeys");
+        cache.commit_pubkeys(pending);
         check_cache_get(&cache, &keypairs[..]);
         drop(cache);
 
@@ -301,4 +334,41 @@ mod test {
         let cache = ValidatorPubkeyCache::load_from_store(store).expect("should open cache");
         check_cache_get(&cache, &keypairs[..]);
     }
+
+    #[test]
+    fn unpersisted_pubkeys_are_reimported() {
+        let (state, keypairs) = get_state(8);
+
+        let store = get_store();
+        let cache = ValidatorPubkeyCache::new(&state, store.clone()).expect("should create cache");
+        drop(cache);
+
+        // Import more keys without persisting them, as if the write of the block which introduced
+        // them failed. They aren't added to the cache.
+        let cache =
+            ValidatorPubkeyCache::load_from_store(store.clone()).expect("should open cache");
+        let (new_state, new_keypairs) = get_state(12);
+        let (ops, pending) = cache
+            .import_new_pubkeys(&new_state)
+            .expect("should import pubkeys");
+        assert_eq!(ops.len(), 4);
+        assert_eq!(pending.len(), 4);
+        check_cache_get(&cache, &keypairs[..]);
+        drop(cache);
+
+        // Only the persisted keys are loaded, and the rest are imported again from the state.
+        let mut cache =
+            ValidatorPubkeyCache::load_from_store(store.clone()).expect("should open cache");
+        check_cache_get(&cache, &keypairs[..]);
+        let (ops, pending) = cache
+            .import_new_pubkeys(&new_state)
+            .expect("should import pubkeys");
+        assert_eq!(ops.len(), 4);
+        store.do_atomically(ops).expect("should persist pubkeys");
+        cache.commit_pubkeys(pending);
+        check_cache_get(&cache, &new_keypairs[..]);
+
+        let cache = ValidatorPubkeyCache::load_from_store(store).expect("should open cache");
+        check_cache_get(&cache, &new_keypairs[..]);
+    }
 }
@@ -1,9 +1,12 @@
 #![cfg(not(debug_assertions))]
 
+use beacon_chain::crash_dump::{
+    BLOCK_FILENAME, ERROR_FILENAME, POST_STATE_FILENAME, PRE_STATE_FILENAME,
+};
 use beacon_chain::test_utils::{
     AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
 };
-use beacon_chain::{BeaconSnapshot, BlockError, ChainSegmentResult};
+use beacon_chain::{BeaconSnapshot, BlockError, ChainConfig, ChainSegmentResult};
 use lazy_static::lazy_static;
 use logging::test_logger;
 use slasher::{Config as SlasherConfig, Slasher};
@@ -875,6 +878,39 @@ fn verify_block_for_gossip_slashing_detection() {
     slasher_dir.close().unwrap();
 }
 
+#[test]
+fn verify_block_for_gossip_slashing_detection_without_import() {
+    let slasher_dir = tempdir().unwrap();
+    let slasher = Arc::new(
+        Slasher::open(SlasherConfig::new(slasher_dir.path().into()), test_logger()).unwrap(),
+    );
+
+    let inner_slasher = slasher.clone();
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .keypairs(KEYPAIRS.to_vec())
+        .fresh_ephemeral_store()
+        .initial_mutator(Box::new(move |builder| builder.slasher(inner_slasher)))
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+
+    let state = harness.get_current_state();
+    let (block1, _) = harness.make_block(state.clone(), Slot::new(1));
+    let (block2, _) = harness.make_block(state, Slot::new(1));
+
+    // The first block is valid for gossip but is never imported.
+    harness.chain.verify_block_for_gossip(block1).unwrap();
+    unwrap_err(harness.chain.verify_block_for_gossip(block2));
+
+    slasher.process_queued(Epoch::new(0)).unwrap();
+    let proposer_slashings = slasher.get_proposer_slashings();
+    assert_eq!(proposer_slashings.len(), 1);
+    drop(harness);
+    drop(slasher);
+    slasher_dir.close().unwrap();
+}
+
 #[test]
 fn verify_block_for_gossip_doppelganger_detection() {
     let harness = get_harness(VALIDATOR_COUNT);
@@ -1162,3 +1198,49 @@ fn add_altair_block_to_base_chain() {
         }
     ));
 }
+
+#[test]
+fn crash_dump_on_state_root_mismatch() {
+    let dump_dir = tempdir().unwrap();
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .keypairs(KEYPAIRS.to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .chain_config(ChainConfig {
+            crash_dump_dir: Some(dump_dir.path().to_path_buf()),
+            ..ChainConfig::default()
+        })
+        .build();
+    harness.advance_slot();
+
+    let mut snapshots = CHAIN_SEGMENT[..1].to_vec();
+    let (mut block, signature) = snapshots[0].beacon_block.clone().deconstruct();
+    *block.state_root_mut() = Hash256::repeat_byte(0xff);
+    snapshots[0].beacon_block = SignedBeaconBlock::from_block(block, signature);
+    update_proposal_signatures(&mut snapshots, &harness);
+
+    assert!(
+        matches!(
+            harness
+                .chain
+                .process_block(snapshots[0].beacon_block.clone()),
+            Err(BlockError::StateRootMismatch { .. })
+        ),
+        "should not import a block with an invalid state root"
+    );
+
+    let dumps = std::fs::read_dir(dump_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    assert_eq!(dumps.len(), 1, "should save one crash dump");
+    for filename in [
+        BLOCK_FILENAME,
+        PRE_STATE_FILENAME,
+        POST_STATE_FILENAME,
+        ERROR_FILENAME,
+    ] {
+        assert!(dumps[0].join(filename).exists(), "should save {}", filename);
+    }
+}
@@ -892,6 +892,8 @@ fn payload_preparation() {
             .get_randao_mix(head.beacon_state.current_epoch())
             .unwrap(),
         suggested_fee_recipient: fee_recipient,
+        withdrawals: None,
+        parent_beacon_block_root: None,
     };
     assert_eq!(rig.previous_payload_attributes(), payload_attributes);
 }
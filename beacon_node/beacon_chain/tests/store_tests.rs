@@ -5,6 +5,7 @@ use beacon_chain::builder::BeaconChainBuilder;
 use beacon_chain::test_utils::{
     test_spec, AttestationStrategy, BeaconChainHarness, BlockStrategy, DiskHarnessType,
 };
+use beacon_chain::validator_monitor::DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD;
 use beacon_chain::{
     historical_blocks::HistoricalBlockError, migrate::MigratorConfig, BeaconChain,
     BeaconChainError, BeaconChainTypes, BeaconSnapshot, ChainConfig, ServerSentEventHandler,
@@ -22,11 +23,11 @@ use std::sync::Arc;
 use std::time::Duration;
 use store::{
     iter::{BlockRootsIterator, StateRootsIterator},
-    HotColdDB, LevelDB, StoreConfig,
+    DBColumn, HotColdDB, IntegrityError, KeyValueStore, LevelDB, StoreConfig, StoreOp,
 };
 use tempfile::{tempdir, TempDir};
 use tree_hash::TreeHash;
-use types::test_utils::{SeedableRng, XorShiftRng};
+use types::test_utils::{SeedableRng, TestRandom, XorShiftRng};
 use types::*;
 
 // Should ideally be divisible by 3.
@@ -58,6 +59,39 @@ fn get_store_with_spec(
         .expect("disk store should initialize")
 }
 
+fn get_store_with_restore_points_per_snapshot(
+    db_path: &TempDir,
+    restore_points_per_snapshot: u64,
+) -> Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>> {
+    get_store_with_config(
+        db_path,
+        StoreConfig {
+            slots_per_restore_point: 2 * E::slots_per_epoch(),
+            restore_points_per_snapshot,
+            ..StoreConfig::default()
+        },
+    )
+}
+
+fn get_store_with_config(
+    db_path: &TempDir,
+    config: StoreConfig,
+) -> Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>> {
+    let hot_path = db_path.path().join("hot_db");
+    let cold_path = db_path.path().join("cold_db");
+    let log = test_logger();
+
+    HotColdDB::open(
+        &hot_path,
+        &cold_path,
+        |_, _, _| Ok(()),
+        config,
+        test_spec::<E>(),
+        log,
+    )
+    .expect("disk store should initialize")
+}
+
 fn get_harness(
     store: Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>,
     validator_count: usize,
@@ -269,6 +303,438 @@ fn split_slot_restore() {
     assert_eq!(store.get_split_slot(), split_slot);
 }
 
+#[test]
+fn state_diff_restore_points() {
+    let num_blocks_produced = E::slots_per_epoch() * 12;
+    let db_path = tempdir().unwrap();
+    let store = get_store_with_restore_points_per_snapshot(&db_path, 4);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        num_blocks_produced as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    // Some restore points are stored as diffs, and all states load correctly.
+    assert!(store
+        .cold_db
+        .iter_column(DBColumn::BeaconStateDiff)
+        .next()
+        .is_some());
+    check_finalization(&harness, num_blocks_produced);
+    check_chain_dump(&harness, num_blocks_produced + 1);
+}
+
+#[test]
+fn convert_restore_points_to_diffs() {
+    let num_blocks_produced = E::slots_per_epoch() * 12;
+    let db_path = tempdir().unwrap();
+
+    let restore_point_roots = |store: &HotColdDB<E, LevelDB<E>, LevelDB<E>>| {
+        let slots_per_restore_point = store.get_config().slots_per_restore_point;
+        (0..store.get_split_slot().as_u64())
+            .step_by(slots_per_restore_point as usize)
+            .map(|slot| {
+                store
+                    .load_cold_state_by_slot(Slot::new(slot))
+                    .unwrap()
+                    .unwrap()
+                    .canonical_root()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Build a chain with every restore point stored in full.
+    let roots = {
+        let store = get_store_with_restore_points_per_snapshot(&db_path, 1);
+        let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+        harness.extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        );
+        assert!(store
+            .cold_db
+            .iter_column(DBColumn::BeaconStateDiff)
+            .next()
+            .is_none());
+        restore_point_roots(&store)
+    };
+    assert!(roots.len() > 2);
+
+    // Re-open the store with diffs enabled and convert the existing restore points.
+    let store = get_store_with_restore_points_per_snapshot(&db_path, 4);
+    let converted = store.convert_restore_points_to_diffs(|_, _| ()).unwrap();
+    assert!(converted > 0);
+    assert_eq!(restore_point_roots(&store), roots);
+}
+
+#[test]
+fn convert_state_diffs_to_restore_points() {
+    let num_blocks_produced = E::slots_per_epoch() * 12;
+    let db_path = tempdir().unwrap();
+    let store = get_store_with_restore_points_per_snapshot(&db_path, 4);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        num_blocks_produced as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+    assert!(store
+        .cold_db
+        .iter_column(DBColumn::BeaconStateDiff)
+        .next()
+        .is_some());
+
+    // Converting back leaves no diffs for a schema which predates them, and all states still load.
+    let converted = store
+        .convert_state_diffs_to_restore_points(|_, _| ())
+        .unwrap();
+    assert!(converted > 0);
+    assert!(store
+        .cold_db
+        .iter_column(DBColumn::BeaconStateDiff)
+        .next()
+        .is_none());
+    check_finalization(&harness, num_blocks_produced);
+    check_chain_dump(&harness, num_blocks_produced + 1);
+}
+
+#[test]
+fn incremental_compaction_clears_debt() {
+    let num_blocks_produced = E::slots_per_epoch() * 5;
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        num_blocks_produced as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    // Deleting a state from the hot database adds to the compaction debt.
+    let stats = store.compaction_stats();
+    store
+        .do_atomically(vec![StoreOp::DeleteState(
+            Hash256::repeat_byte(0x42),
+            Some(Slot::new(0)),
+        )])
+        .unwrap();
+    assert_eq!(
+        store.compaction_stats().deleted_states_since_compaction,
+        stats.deleted_states_since_compaction + 1
+    );
+
+    store.compact_incrementally().unwrap();
+    let new_stats = store.compaction_stats();
+    assert_eq!(new_stats.compactions, stats.compactions + 1);
+    assert_eq!(new_stats.deleted_states_since_compaction, 0);
+    check_finalization(&harness, num_blocks_produced);
+}
+
+#[test]
+fn epoch_boundary_state_cache() {
+    let num_blocks_produced = E::slots_per_epoch() * 4 + 2;
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        num_blocks_produced as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let boundary_slot = Slot::new(E::slots_per_epoch() * 4);
+    let boundary_state_root = harness
+        .chain
+        .state_root_at_slot(boundary_slot)
+        .unwrap()
+        .unwrap();
+    let state_root = harness
+        .chain
+        .state_root_at_slot(boundary_slot + 1)
+        .unwrap()
+        .unwrap();
+
+    // The second load replays from the cached epoch boundary state.
+    let state = store.get_state(&state_root, None).unwrap().unwrap();
+    let cached_state = store.get_state(&state_root, None).unwrap().unwrap();
+    assert_eq!(cached_state.slot(), boundary_slot + 1);
+    assert_eq!(cached_state.canonical_root(), state_root);
+    assert_eq!(cached_state, state);
+
+    // Deleting the epoch boundary state also removes it from the cache.
+    store
+        .do_atomically(vec![StoreOp::DeleteState(
+            boundary_state_root,
+            Some(boundary_slot),
+        )])
+        .unwrap();
+    assert!(store.get_state(&state_root, None).is_err());
+}
+
+#[test]
+fn prune_history_outside_retention_window() {
+    let num_blocks_produced = E::slots_per_epoch() * 12;
+    let db_path = tempdir().unwrap();
+    let store = get_store_with_config(
+        &db_path,
+        StoreConfig {
+            slots_per_restore_point: 2 * E::slots_per_epoch(),
+            restore_points_per_snapshot: 4,
+            history_retention_epochs: Some(4),
+            prune_history_blocks: true,
+            ..StoreConfig::default()
+        },
+    );
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        num_blocks_produced as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let anchor = store.get_anchor_info().expect("history should be pruned");
+    let oldest_slot = anchor.state_upper_limit;
+    let retention_start = store.get_split_slot() - 4 * E::slots_per_epoch();
+    assert!(oldest_slot > 0);
+    assert!(oldest_slot <= retention_start);
+    assert!(store.block_backfill_complete(&anchor));
+
+    // Blocks are kept for `MIN_EPOCHS_FOR_BLOCK_REQUESTS`, which exceeds the length of the chain.
+    assert_eq!(anchor.oldest_block_slot, 0);
+    assert!(store.has_min_block_history(harness.chain.slot().unwrap()));
+
+    // States prior to the window are gone, other than that of genesis.
+    for slot in 1..oldest_slot.as_u64() {
+        assert!(store
+            .load_cold_state_by_slot(Slot::new(slot))
+            .unwrap()
+            .is_none());
+    }
+    assert!(store
+        .load_cold_state_by_slot(Slot::new(0))
+        .unwrap()
+        .is_some());
+    let retained_block_root = harness
+        .chain
+        .block_root_at_slot(Slot::new(1), WhenSlotSkipped::None)
+        .unwrap()
+        .unwrap();
+    assert!(store
+        .get_blinded_block(&retained_block_root)
+        .unwrap()
+        .is_some());
+
+    // States in the window still load, and the head remains fully functional.
+    for slot in oldest_slot.as_u64()..store.get_split_slot().as_u64() {
+        assert!(store
+            .load_cold_state_by_slot(Slot::new(slot))
+            .unwrap()
+            .is_some());
+    }
+    check_finalization(&harness, num_blocks_produced);
+}
+
+#[test]
+fn prune_blobs_outside_retention_window() {
+    let mut spec = ForkName::Deneb.make_genesis_spec(E::default_spec());
+    spec.min_epochs_for_blob_sidecars_requests = 2;
+    let db_path = tempdir().unwrap();
+    let store = get_store_with_spec(&db_path, spec.clone());
+    let harness = BeaconChainHarness::builder(MinimalEthSpec)
+        .spec(spec)
+        .keypairs(KEYPAIRS[0..LOW_VALIDATOR_COUNT].to_vec())
+        .fresh_disk_store(store.clone())
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+
+    let initial_blocks = E::slots_per_epoch() * 2;
+    harness.extend_chain(
+        initial_blocks as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    // Store a blob for each of the initial blocks.
+    let rng = &mut XorShiftRng::from_seed([42; 16]);
+    let block_roots = (1..=initial_blocks)
+        .map(|slot| {
+            harness
+                .chain
+                .block_root_at_slot(Slot::new(slot), WhenSlotSkipped::None)
+                .unwrap()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    for block_root in &block_roots {
+        let blobs =
+            BlobSidecarList::<E>::new(vec![Arc::new(BlobSidecar::random_for_test(rng))]).unwrap();
+        store
+            .do_atomically(vec![StoreOp::PutBlobs(*block_root, blobs)])
+            .unwrap();
+    }
+    let blob_info = store.get_blob_info();
+    assert_eq!(blob_info.blocks_with_blobs, initial_blocks);
+    assert_eq!(blob_info.blob_sidecars, initial_blocks);
+    assert_eq!(
+        blob_info.blob_bytes,
+        initial_blocks * BlobSidecar::<E>::max_size() as u64
+    );
+
+    // Overwriting blobs doesn't count them twice.
+    let blobs = store.get_blobs(&block_roots[0]).unwrap().unwrap();
+    store
+        .do_atomically(vec![StoreOp::PutBlobs(block_roots[0], blobs)])
+        .unwrap();
+    assert_eq!(store.get_blob_info(), blob_info);
+
+    harness.extend_chain(
+        (E::slots_per_epoch() * 18) as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    // The blobs of the initial blocks have left the retention window, but the blocks remain.
+    let blob_info = store.get_blob_info();
+    assert!(blob_info.oldest_blob_slot > initial_blocks);
+    assert!(blob_info.oldest_blob_slot <= store.blob_retention_start_slot());
+    assert_eq!(blob_info.blocks_with_blobs, 0);
+    assert_eq!(blob_info.blob_sidecars, 0);
+    assert_eq!(blob_info.blob_bytes, 0);
+    for block_root in &block_roots {
+        assert!(store.get_blobs(block_root).unwrap().is_none());
+        assert!(store.get_blinded_block(block_root).unwrap().is_some());
+    }
+}
+
+#[test]
+fn replay_blocks_parallel_across_restore_points() {
+    let num_blocks_produced = E::slots_per_epoch() * 12;
+    let slots_per_restore_point = 2 * E::slots_per_epoch();
+    let db_path = tempdir().unwrap();
+    let store = get_store_with_config(
+        &db_path,
+        StoreConfig {
+            slots_per_restore_point,
+            max_replay_threads: 3,
+            ..StoreConfig::default()
+        },
+    );
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        num_blocks_produced as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    let start_slot = Slot::new(1);
+    let end_slot = store.get_latest_restore_point_slot() - 1;
+    let end_block_root = harness
+        .chain
+        .block_root_at_slot(end_slot, WhenSlotSkipped::Prev)
+        .unwrap()
+        .unwrap();
+    let blocks = store
+        .load_blocks_to_replay(start_slot, end_slot, end_block_root)
+        .unwrap();
+    let start_state = store.load_cold_state_by_slot(start_slot).unwrap().unwrap();
+
+    let chunk_states = store
+        .replay_blocks_parallel(
+            start_state,
+            blocks,
+            end_slot,
+            |state, blocks, chunk_end_slot| {
+                let state = BlockReplayer::<E>::new(state, &harness.spec)
+                    .no_signature_verification()
+                    .apply_blocks(blocks, Some(chunk_end_slot))
+                    .unwrap()
+                    .into_state();
+                Ok::<_, store::Error>(state)
+            },
+        )
+        .unwrap();
+
+    // One chunk from the start state, and one from each restore point prior to the end slot.
+    assert_eq!(
+        chunk_states.len() as u64,
+        1 + (end_slot.as_u64() - 1) / slots_per_restore_point
+    );
+
+    // Each chunk ends at the next restore point, or the end slot.
+    for (i, state) in chunk_states.iter().enumerate() {
+        let expected_slot = std::cmp::min(
+            Slot::new((i as u64 + 1) * slots_per_restore_point),
+            end_slot,
+        );
+        let expected_state = store
+            .load_cold_state_by_slot(expected_slot)
+            .unwrap()
+            .unwrap();
+        assert_eq!(state.slot(), expected_slot);
+        assert_eq!(state.canonical_root(), expected_state.canonical_root());
+    }
+}
+
+#[test]
+fn integrity_check_detects_missing_states() {
+    let num_blocks_produced = E::slots_per_epoch() * 8;
+    let slots_per_restore_point = 2 * E::slots_per_epoch();
+    let db_path = tempdir().unwrap();
+    let store = get_store_with_config(
+        &db_path,
+        StoreConfig {
+            slots_per_restore_point,
+            ..StoreConfig::default()
+        },
+    );
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness.extend_chain(
+        num_blocks_produced as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+    store.check_integrity().unwrap();
+
+    // Lose the latest restore point, as if the freezer DB were restored from an older backup.
+    let restore_point_index =
+        store.get_latest_restore_point_slot().as_u64() / slots_per_restore_point;
+    store
+        .cold_db
+        .key_delete(
+            DBColumn::BeaconRestorePoint.into(),
+            Hash256::from_low_u64_be(restore_point_index).as_bytes(),
+        )
+        .unwrap();
+    assert!(matches!(
+        store.check_integrity(),
+        Err(IntegrityError::FreezerBehindHotDatabase { .. })
+    ));
+
+    // Lose the split state from the hot DB.
+    let split = store.get_split_info();
+    store
+        .hot_db
+        .key_delete(
+            DBColumn::BeaconStateSummary.into(),
+            split.state_root.as_bytes(),
+        )
+        .unwrap();
+    assert!(matches!(
+        store.check_integrity(),
+        Err(IntegrityError::MissingSplitState { .. })
+    ));
+}
+
 // Check attestation processing and `load_epoch_boundary_state` in the presence of a split DB.
 // This is a bit of a monster test in that it tests lots of different things, but until they're
 // tested elsewhere, this is as good a place as any.
@@ -2042,7 +2508,7 @@ fn weak_subjectivity_sync() {
                 log.clone(),
                 1,
             )))
-            .monitor_validators(true, vec![], log)
+            .monitor_validators(true, vec![], DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD, log)
             .build()
             .expect("should build"),
     );
@@ -1,6 +1,8 @@
 use crate::config::{ClientGenesis, Config as ClientConfig};
 use crate::notifier::spawn_notifier;
 use crate::Client;
+use beacon_chain::memory_watermark::spawn_memory_watermark_service;
+use beacon_chain::payload_reconstruction_service::start_payload_reconstruction_service;
 use beacon_chain::proposer_prep_service::start_proposer_prep_service;
 use beacon_chain::schema_change::migrate_schema;
 use beacon_chain::{
@@ -8,13 +10,13 @@ use beacon_chain::{
     eth1_chain::{CachingEth1Backend, Eth1Chain},
     slot_clock::{SlotClock, SystemTimeSlotClock},
     state_advance_timer::spawn_state_advance_timer,
-    store::{HotColdDB, ItemStore, LevelDB, StoreConfig},
+    store::{FreezerDB, HotColdDB, HotDB, ItemStore, StoreConfig},
     BeaconChain, BeaconChainTypes, Eth1ChainBackend, ServerSentEventHandler,
 };
 use environment::RuntimeContext;
 use eth1::{Config as Eth1Config, Service as Eth1Service};
 use eth2::{
-    types::{BlockId, StateId},
+    types::{Accept, BlockId, StateId},
     BeaconNodeHttpClient, Error as ApiError, Timeouts,
 };
 use execution_layer::ExecutionLayer;
@@ -22,6 +24,7 @@ use genesis::{interop_genesis_state, Eth1GenesisService, DEFAULT_ETH1_BLOCK_HASH
 use lighthouse_network::{prometheus_client::registry::Registry, NetworkGlobals};
 use monitoring_api::{MonitoringHttpClient, ProcessType};
 use network::{NetworkConfig, NetworkMessage, NetworkService};
+use sensitive_url::SensitiveUrl;
 use slasher::Slasher;
 use slasher_service::SlasherService;
 use slog::{debug, info, warn, Logger};
@@ -42,6 +45,81 @@ pub const ETH1_GENESIS_UPDATE_INTERVAL_MILLIS: u64 = 7_000;
 /// Timeout for checkpoint sync HTTP requests.
 pub const CHECKPOINT_SYNC_HTTP_TIMEOUT: Duration = Duration::from_secs(60);
 
+/// Timeout for downloading the genesis state.
+pub const GENESIS_STATE_HTTP_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Load the genesis state of the network, from the network config if it includes one, or
+/// otherwise by downloading it from `genesis_state_url`.
+///
+/// A URL with a path is expected to serve the SSZ-encoded state directly, whereas a URL without
+/// one is treated as a beacon node or checkpoint sync server and queried for its genesis state
+/// using the standard API. A downloaded state is only accepted if its root matches
+/// `genesis_state_root`.
+async fn load_genesis_state<E: EthSpec>(
+    context: &RuntimeContext<E>,
+    genesis_state_url: Option<&SensitiveUrl>,
+    genesis_state_root: Option<Hash256>,
+    spec: &ChainSpec,
+) -> Result<BeaconState<E>, String> {
+    if let Some(genesis_state_bytes) = context
+        .eth2_network_config
+        .as_ref()
+        .and_then(|network_config| network_config.genesis_state_bytes.as_ref())
+    {
+        return BeaconState::from_ssz_bytes(genesis_state_bytes, spec)
+            .map_err(|e| format!("Unable to parse genesis state SSZ: {:?}", e));
+    }
+
+    let url = genesis_state_url
+        .ok_or("The genesis state is not known for this network, use --genesis-state-url")?;
+    let expected_root = genesis_state_root
+        .ok_or("A --genesis-state-root is required to verify the downloaded genesis state")?;
+
+    info!(
+        context.log(),
+        "Downloading genesis state";
+        "url" => %url,
+        "info" => "this may take some time on networks with many validators",
+    );
+
+    let remote =
+        BeaconNodeHttpClient::new(url.clone(), Timeouts::set_all(GENESIS_STATE_HTTP_TIMEOUT));
+    let mut genesis_state = if url.full.path().trim_end_matches('/').is_empty() {
+        remote
+            .get_debug_beacon_states_ssz::<E>(StateId::Genesis, spec)
+            .await
+            .map_err(|e| format!("Error fetching genesis state from {}: {:?}", url, e))?
+    } else {
+        remote
+            .get_bytes_opt_accept_header(url.full.clone(), Accept::Ssz)
+            .await
+            .map_err(|e| format!("Error downloading genesis state from {}: {:?}", url, e))?
+            .map(|bytes| BeaconState::from_ssz_bytes(&bytes, spec))
+            .transpose()
+            .map_err(|e| format!("Unable to parse genesis state SSZ: {:?}", e))?
+    }
+    .ok_or_else(|| format!("Genesis state missing from {}, it returned 404", url))?;
+
+    let genesis_state_root = genesis_state
+        .update_tree_hash_cache()
+        .map_err(|e| format!("Unable to compute genesis state root: {:?}", e))?;
+    if genesis_state_root != expected_root {
+        return Err(format!(
+            "Downloaded genesis state has root {:?}, expected {:?}",
+            genesis_state_root, expected_root
+        ));
+    }
+
+    info!(
+        context.log(),
+        "Downloaded genesis state";
+        "state_root" => ?genesis_state_root,
+        "genesis_time" => genesis_state.genesis_time(),
+    );
+
+    Ok(genesis_state)
+}
+
 /// Builds a `Client` instance.
 ///
 /// ## Notes
@@ -138,6 +216,8 @@ where
         let eth_spec_instance = self.eth_spec_instance.clone();
         let chain_config = config.chain.clone();
         let graffiti = config.graffiti;
+        let genesis_state_url = config.genesis_state_url.clone();
+        let genesis_state_root = config.genesis_state_root;
 
         let store = store.ok_or("beacon_chain_start_method requires a store")?;
         let runtime_context =
@@ -175,6 +255,7 @@ where
             .monitor_validators(
                 config.validator_monitor_auto,
                 config.validator_monitor_pubkeys.clone(),
+                config.validator_monitor_individual_tracking_threshold,
                 runtime_context
                     .service_context("val_mon".to_string())
                     .log()
@@ -231,23 +312,25 @@ where
                 )?;
                 builder.genesis_state(genesis_state).map(|v| (v, None))?
             }
-            ClientGenesis::SszBytes {
-                genesis_state_bytes,
-            } => {
+            ClientGenesis::GenesisState => {
                 info!(
                     context.log(),
                     "Starting from known genesis state";
                 );
 
-                let genesis_state = BeaconState::from_ssz_bytes(&genesis_state_bytes, &spec)
-                    .map_err(|e| format!("Unable to parse genesis state SSZ: {:?}", e))?;
+                let genesis_state = load_genesis_state(
+                    &runtime_context,
+                    genesis_state_url.as_ref(),
+                    genesis_state_root,
+                    &spec,
+                )
+                .await?;
 
                 builder.genesis_state(genesis_state).map(|v| (v, None))?
             }
             ClientGenesis::WeakSubjSszBytes {
                 anchor_state_bytes,
                 anchor_block_bytes,
-                genesis_state_bytes,
             } => {
                 info!(context.log(), "Starting checkpoint sync");
 
@@ -255,17 +338,19 @@ where
                     .map_err(|e| format!("Unable to parse weak subj state SSZ: {:?}", e))?;
                 let anchor_block = SignedBeaconBlock::from_ssz_bytes(&anchor_block_bytes, &spec)
                     .map_err(|e| format!("Unable to parse weak subj block SSZ: {:?}", e))?;
-                let genesis_state = BeaconState::from_ssz_bytes(&genesis_state_bytes, &spec)
-                    .map_err(|e| format!("Unable to parse genesis state SSZ: {:?}", e))?;
+                let genesis_state = load_genesis_state(
+                    &runtime_context,
+                    genesis_state_url.as_ref(),
+                    genesis_state_root,
+                    &spec,
+                )
+                .await?;
 
                 builder
                     .weak_subjectivity_state(anchor_state, anchor_block, genesis_state)
                     .map(|v| (v, None))?
             }
-            ClientGenesis::CheckpointSyncUrl {
-                genesis_state_bytes,
-                url,
-            } => {
+            ClientGenesis::CheckpointSyncUrl { url } => {
                 info!(
                     context.log(),
                     "Starting checkpoint sync";
@@ -326,8 +411,13 @@ where
                         format!("Checkpoint state missing from remote: {:?}", state_root)
                     })?;
 
-                let genesis_state = BeaconState::from_ssz_bytes(&genesis_state_bytes, &spec)
-                    .map_err(|e| format!("Unable to parse genesis state SSZ: {:?}", e))?;
+                let genesis_state = load_genesis_state(
+                    &runtime_context,
+                    genesis_state_url.as_ref(),
+                    genesis_state_root,
+                    &spec,
+                )
+                .await?;
 
                 info!(
                     context.log(),
@@ -337,9 +427,81 @@ where
                     "state_root" => ?state_root,
                 );
 
+                // Load the finalized deposit tree from the remote so that deposit logs prior to
+                // the checkpoint don't need to be downloaded from the execution chain.
+                let deposit_snapshot = if config.sync_eth1_chain && !config.dummy_eth1_backend {
+                    match remote.get_deposit_snapshot().await {
+                        Ok(Some(response)) => {
+                            let snapshot = response.data;
+                            if !snapshot.is_valid() {
+                                warn!(
+                                    context.log(),
+                                    "Remote deposit snapshot is invalid";
+                                    "info" => "deposits will be downloaded from the execution chain",
+                                );
+                                None
+                            } else if snapshot.deposit_count > state.eth1_deposit_index() {
+                                warn!(
+                                    context.log(),
+                                    "Remote deposit snapshot is ahead of checkpoint";
+                                    "snapshot_deposit_count" => snapshot.deposit_count,
+                                    "checkpoint_deposit_index" => state.eth1_deposit_index(),
+                                );
+                                None
+                            } else {
+                                Some(snapshot)
+                            }
+                        }
+                        Ok(None) => {
+                            warn!(
+                                context.log(),
+                                "Remote did not return a deposit snapshot";
+                                "info" => "deposits will be downloaded from the execution chain",
+                            );
+                            None
+                        }
+                        Err(e) => {
+                            warn!(
+                                context.log(),
+                                "Unable to fetch deposit snapshot from remote";
+                                "error" => ?e,
+                            );
+                            None
+                        }
+                    }
+                } else {
+                    None
+                };
+
+                let eth1_service =
+                    deposit_snapshot.and_then(|snapshot| match Eth1Service::from_deposit_snapshot(
+                        config.eth1.clone(),
+                        context.log().clone(),
+                        spec.clone(),
+                        &snapshot,
+                    ) {
+                        Ok(service) => {
+                            info!(
+                                context.log(),
+                                "Loaded deposit tree snapshot";
+                                "deposit_count" => snapshot.deposit_count,
+                                "execution_block_height" => snapshot.execution_block_height,
+                            );
+                            Some(service)
+                        }
+                        Err(e) => {
+                            warn!(
+                                context.log(),
+                                "Unable to load deposit tree snapshot";
+                                "error" => e,
+                            );
+                            None
+                        }
+                    });
+
                 builder
                     .weak_subjectivity_state(state, block, genesis_state)
-                    .map(|v| (v, None))?
+                    .map(|v| (v, eth1_service))?
             }
             ClientGenesis::DepositContract => {
                 info!(
@@ -375,6 +537,9 @@ where
                         network_tx: None,
                         network_globals: None,
                         eth1_service: Some(genesis_service.eth1_service.clone()),
+                        db_path: None,
+                        freezer_db_path: None,
+                        log_levels: context.log_levels.clone(),
                         log: context.log().clone(),
                     });
 
@@ -594,6 +759,9 @@ where
                 network_tx: self.network_send.clone(),
                 network_globals: self.network_globals.clone(),
                 eth1_service: self.eth1_service.clone(),
+                db_path: self.db_path.clone(),
+                freezer_db_path: self.freezer_db_path.clone(),
+                log_levels: runtime_context.log_levels.clone(),
                 log: log.clone(),
             });
 
@@ -710,6 +878,15 @@ where
             }
 
             start_proposer_prep_service(runtime_context.executor.clone(), beacon_chain.clone());
+
+            spawn_memory_watermark_service(runtime_context.executor.clone(), beacon_chain.clone());
+
+            if beacon_chain.config.reconstruct_payloads {
+                start_payload_reconstruction_service(
+                    runtime_context.executor.clone(),
+                    beacon_chain.clone(),
+                );
+            }
         }
 
         Ok(Client {
@@ -759,7 +936,7 @@ where
 }
 
 impl<TSlotClock, TEth1Backend, TEthSpec>
-    ClientBuilder<Witness<TSlotClock, TEth1Backend, TEthSpec, LevelDB<TEthSpec>, LevelDB<TEthSpec>>>
+    ClientBuilder<Witness<TSlotClock, TEth1Backend, TEthSpec, HotDB<TEthSpec>, FreezerDB<TEthSpec>>>
 where
     TSlotClock: SlotClock + 'static,
     TEth1Backend: Eth1ChainBackend<TEthSpec> + 'static,
@@ -800,6 +977,15 @@ where
             context.log().clone(),
         )
         .map_err(|e| format!("Unable to open database: {:?}", e))?;
+
+        store.check_integrity().map_err(|e| {
+            format!(
+                "Database integrity check failed: {}. {}",
+                e,
+                e.remediation()
+            )
+        })?;
+
         self.store = Some(store);
         Ok(self)
     }
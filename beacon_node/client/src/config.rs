@@ -1,10 +1,11 @@
+use beacon_chain::validator_monitor::DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD;
 use directory::DEFAULT_ROOT_DIR;
 use network::NetworkConfig;
 use sensitive_url::SensitiveUrl;
 use serde_derive::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
-use types::{Graffiti, PublicKeyBytes};
+use types::{Graffiti, Hash256, PublicKeyBytes};
 
 /// Default directory name for the freezer database under the top-level data dir.
 const DEFAULT_FREEZER_DB_DIR: &str = "freezer_db";
@@ -22,20 +23,19 @@ pub enum ClientGenesis {
     /// Connects to an eth1 node and waits until it can create the genesis state from the deposit
     /// contract.
     DepositContract,
-    /// Loads the genesis state from SSZ-encoded `BeaconState` bytes.
+    /// Starts from the genesis state of the network, which is either included in the network
+    /// config or downloaded from `Config::genesis_state_url`.
+    GenesisState,
+    /// Starts from the SSZ-encoded checkpoint state and block.
     ///
     /// We include the bytes instead of the `BeaconState<E>` because the `EthSpec` type
     /// parameter would be very annoying.
-    SszBytes { genesis_state_bytes: Vec<u8> },
     WeakSubjSszBytes {
-        genesis_state_bytes: Vec<u8>,
         anchor_state_bytes: Vec<u8>,
         anchor_block_bytes: Vec<u8>,
     },
-    CheckpointSyncUrl {
-        genesis_state_bytes: Vec<u8>,
-        url: SensitiveUrl,
-    },
+    /// Starts from a checkpoint state and block downloaded from the beacon node at `url`.
+    CheckpointSyncUrl { url: SensitiveUrl },
 }
 
 impl Default for ClientGenesis {
@@ -64,10 +64,16 @@ pub struct Config {
     pub validator_monitor_auto: bool,
     /// A list of validator pubkeys to monitor.
     pub validator_monitor_pubkeys: Vec<PublicKeyBytes>,
+    /// Once more validators than this are monitored, their metrics are aggregated.
+    pub validator_monitor_individual_tracking_threshold: usize,
     #[serde(skip)]
     /// The `genesis` field is not serialized or deserialized by `serde` to ensure it is defined
     /// via the CLI at runtime, instead of from a configuration file saved to disk.
     pub genesis: ClientGenesis,
+    /// A URL from which to download the genesis state, if it isn't included in the network config.
+    pub genesis_state_url: Option<SensitiveUrl>,
+    /// The expected root of the genesis state downloaded from `genesis_state_url`.
+    pub genesis_state_root: Option<Hash256>,
     pub store: store::StoreConfig,
     pub network: network::NetworkConfig,
     pub chain: beacon_chain::ChainConfig,
@@ -87,6 +93,8 @@ impl Default for Config {
             freezer_db_path: None,
             log_file: PathBuf::from(""),
             genesis: <_>::default(),
+            genesis_state_url: None,
+            genesis_state_root: None,
             store: <_>::default(),
             network: NetworkConfig::default(),
             chain: <_>::default(),
@@ -101,6 +109,7 @@ impl Default for Config {
             slasher: None,
             validator_monitor_auto: false,
             validator_monitor_pubkeys: vec![],
+            validator_monitor_individual_tracking_threshold: DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD,
         }
     }
 }
@@ -1,5 +1,9 @@
 use crate::metrics;
-use beacon_chain::{BeaconChain, BeaconChainTypes, HeadSafetyStatus};
+use beacon_chain::{
+    fork_readiness::FORK_READINESS_PREPARATION_SECONDS, BeaconChain, BeaconChainTypes,
+    HeadSafetyStatus,
+};
+use eth2::lighthouse::ReadinessStatus;
 use lighthouse_network::{types::SyncState, NetworkGlobals};
 use parking_lot::Mutex;
 use slog::{crit, debug, error, info, warn, Logger};
@@ -320,6 +324,7 @@ pub fn spawn_notifier<T: BeaconChainTypes>(
             }
 
             eth1_logging(&beacon_chain, &log);
+            fork_readiness_logging(current_slot, &beacon_chain, &log).await;
         }
     };
 
@@ -329,6 +334,80 @@ pub fn spawn_notifier<T: BeaconChainTypes>(
     Ok(())
 }
 
+/// Log the readiness of the node for the next scheduled fork, along with a countdown to the fork.
+///
+/// Readiness is logged once per epoch from `FORK_READINESS_PREPARATION_SECONDS` before the fork,
+/// and every slot in the epoch before the fork.
+async fn fork_readiness_logging<T: BeaconChainTypes>(
+    current_slot: Slot,
+    beacon_chain: &BeaconChain<T>,
+    log: &Logger,
+) {
+    let slots_per_epoch = T::EthSpec::slots_per_epoch();
+    let (fork, fork_epoch) = match beacon_chain.next_scheduled_fork() {
+        Some(next_fork) => next_fork,
+        None => return,
+    };
+    let time_until_fork = match beacon_chain
+        .slot_clock
+        .duration_to_slot(fork_epoch.start_slot(slots_per_epoch))
+    {
+        Some(duration) => duration,
+        None => return,
+    };
+
+    let is_final_epoch = current_slot + slots_per_epoch >= fork_epoch.start_slot(slots_per_epoch);
+    if time_until_fork > Duration::from_secs(FORK_READINESS_PREPARATION_SECONDS)
+        || (current_slot % slots_per_epoch != 0 && !is_final_epoch)
+    {
+        return;
+    }
+
+    let readiness = match beacon_chain.check_fork_readiness().await {
+        Some(readiness) => readiness,
+        None => return,
+    };
+
+    for check in &readiness.checks {
+        match check.status {
+            ReadinessStatus::Ready => (),
+            ReadinessStatus::NotReady => error!(
+                log,
+                "Not ready for fork";
+                "info" => &check.info,
+                "check" => &check.name,
+                "fork" => %fork,
+            ),
+            ReadinessStatus::Unknown => warn!(
+                log,
+                "Unable to check fork readiness";
+                "info" => &check.info,
+                "check" => &check.name,
+                "fork" => %fork,
+            ),
+        }
+    }
+
+    if readiness.ready {
+        info!(
+            log,
+            "Ready for fork";
+            "time_until_fork" => seconds_pretty(time_until_fork.as_secs_f64()),
+            "fork_epoch" => fork_epoch,
+            "fork" => %fork,
+        );
+    } else {
+        warn!(
+            log,
+            "Fork approaching";
+            "info" => "the node must be fixed before the fork to stay in sync",
+            "time_until_fork" => seconds_pretty(time_until_fork.as_secs_f64()),
+            "fork_epoch" => fork_epoch,
+            "fork" => %fork,
+        );
+    }
+}
+
 fn eth1_logging<T: BeaconChainTypes>(beacon_chain: &BeaconChain<T>, log: &Logger) {
     let current_slot_opt = beacon_chain.slot().ok();
 
@@ -1,9 +1,9 @@
-use crate::DepositLog;
+use crate::{DepositLog, Eth1Block};
 use ssz_derive::{Decode, Encode};
 use state_processing::common::DepositDataTree;
 use std::cmp::Ordering;
 use tree_hash::TreeHash;
-use types::{Deposit, Hash256, DEPOSIT_TREE_DEPTH};
+use types::{Deposit, DepositTreeSnapshot, Hash256, DEPOSIT_TREE_DEPTH};
 
 #[derive(Debug, PartialEq)]
 pub enum Error {
@@ -21,37 +21,113 @@ pub enum Error {
     /// A log with the given index is already present in the cache and it does not match the one
     /// provided.
     DuplicateDistinctLog(u64),
+    /// Attempted to insert log with given index after the log had been finalized
+    FinalizedLogInsert {
+        log_index: u64,
+        finalized_index: u64,
+    },
     /// The deposit count must always be large enough to account for the requested deposit range.
     ///
     /// E.g., you cannot request deposit 10 when the deposit count is 9.
     DepositCountInvalid { deposit_count: u64, range_end: u64 },
+    /// You can't request deposits on or before the finalized deposit
+    DepositRangeInvalid {
+        range_start: u64,
+        finalized_count: u64,
+    },
+    /// You can't finalize what's already been finalized and the cache must have the logs
+    /// that you wish to finalize
+    InvalidFinalizeIndex {
+        requested_count: u64,
+        currently_finalized: u64,
+        deposit_count: u64,
+    },
     /// Error with the merkle tree for deposits.
     DepositTree(merkle_proof::MerkleTreeError),
     /// An unexpected condition was encountered.
     Internal(String),
+    /// This is for errors that should never occur
+    PleaseNotifyTheDevs,
 }
 
+pub type SszDepositCache = SszDepositCacheV10;
+
+/// The persisted deposit cache prior to schema v10, which does not support finalization.
 #[derive(Encode, Decode, Clone)]
-pub struct SszDepositCache {
-    logs: Vec<DepositLog>,
-    leaves: Vec<Hash256>,
-    deposit_contract_deploy_block: u64,
-    deposit_roots: Vec<Hash256>,
+pub struct SszDepositCacheV1 {
+    pub logs: Vec<DepositLog>,
+    pub leaves: Vec<Hash256>,
+    pub deposit_contract_deploy_block: u64,
+    pub deposit_roots: Vec<Hash256>,
+}
+
+#[derive(Encode, Decode, Clone)]
+pub struct SszDepositCacheV10 {
+    pub logs: Vec<DepositLog>,
+    pub leaves: Vec<Hash256>,
+    pub deposit_contract_deploy_block: u64,
+    pub finalized_deposit_count: u64,
+    pub finalized_block_height: u64,
+    pub deposit_tree_snapshot: Option<DepositTreeSnapshot>,
+    pub deposit_roots: Vec<Hash256>,
+}
+
+impl From<SszDepositCacheV1> for SszDepositCacheV10 {
+    fn from(cache: SszDepositCacheV1) -> Self {
+        Self {
+            logs: cache.logs,
+            leaves: cache.leaves,
+            deposit_contract_deploy_block: cache.deposit_contract_deploy_block,
+            finalized_deposit_count: 0,
+            finalized_block_height: 0,
+            deposit_tree_snapshot: None,
+            deposit_roots: cache.deposit_roots,
+        }
+    }
 }
 
-impl SszDepositCache {
+impl SszDepositCacheV10 {
+    /// Convert to the persisted format used prior to schema v10.
+    ///
+    /// Returns `None` if any deposits have been finalized, since they are no longer stored.
+    pub fn to_v1(&self) -> Option<SszDepositCacheV1> {
+        if self.finalized_deposit_count != 0 {
+            return None;
+        }
+        Some(SszDepositCacheV1 {
+            logs: self.logs.clone(),
+            leaves: self.leaves.clone(),
+            deposit_contract_deploy_block: self.deposit_contract_deploy_block,
+            deposit_roots: self.deposit_roots.clone(),
+        })
+    }
+
     pub fn from_deposit_cache(cache: &DepositCache) -> Self {
         Self {
             logs: cache.logs.clone(),
             leaves: cache.leaves.clone(),
             deposit_contract_deploy_block: cache.deposit_contract_deploy_block,
+            finalized_deposit_count: cache.finalized_deposit_count,
+            finalized_block_height: cache.finalized_block_height,
+            deposit_tree_snapshot: cache.deposit_tree.get_snapshot(),
             deposit_roots: cache.deposit_roots.clone(),
         }
     }
 
     pub fn to_deposit_cache(&self) -> Result<DepositCache, String> {
-        let deposit_tree =
-            DepositDataTree::create(&self.leaves, self.leaves.len(), DEPOSIT_TREE_DEPTH);
+        let deposit_tree = match &self.deposit_tree_snapshot {
+            Some(snapshot) => {
+                let mut tree = DepositDataTree::from_snapshot(snapshot, DEPOSIT_TREE_DEPTH)
+                    .map_err(|e| format!("Invalid SszDepositCache: {:?}", e))?;
+                for leaf in &self.leaves {
+                    tree.push_leaf(*leaf).map_err(|e| {
+                        format!("Invalid SszDepositCache: unable to push leaf: {:?}", e)
+                    })?;
+                }
+                tree
+            }
+            None => DepositDataTree::create(&self.leaves, self.leaves.len(), DEPOSIT_TREE_DEPTH),
+        };
         // Check for invalid SszDepositCache conditions
         if self.leaves.len() != self.logs.len() {
             return Err("Invalid SszDepositCache: logs and leaves should have equal length".into());
@@ -67,6 +143,8 @@ impl SszDepositCache {
             logs: self.logs.clone(),
             leaves: self.leaves.clone(),
             deposit_contract_deploy_block: self.deposit_contract_deploy_block,
+            finalized_deposit_count: self.finalized_deposit_count,
+            finalized_block_height: self.finalized_block_height,
             deposit_tree,
             deposit_roots: self.deposit_roots.clone(),
         })
@@ -76,15 +154,26 @@ impl SszDepositCache {
 /// Mirrors the merkle tree of deposits in the eth1 deposit contract.
 ///
 /// Provides `Deposit` objects with merkle proofs included.
+///
+/// Once deposits have been finalized (see `Self::finalize`) their logs are dropped from the cache
+/// and proofs can no longer be produced for them. Only the finalized portion of the merkle tree is
+/// retained, which can be served as an EIP-4881 `DepositTreeSnapshot`.
+#[cfg_attr(test, derive(PartialEq))]
 pub struct DepositCache {
+    /// Logs of the deposits which have not been finalized, starting at index
+    /// `finalized_deposit_count`.
     logs: Vec<DepositLog>,
     leaves: Vec<Hash256>,
     deposit_contract_deploy_block: u64,
+    /// The number of deposits which have been finalized.
+    finalized_deposit_count: u64,
+    /// The height of the eth1 block at which deposits were finalized.
+    finalized_block_height: u64,
     /// An incremental merkle tree which represents the current state of the
     /// deposit contract tree.
     deposit_tree: DepositDataTree,
     /// Vector of deposit roots. `deposit_roots[i]` denotes `deposit_root` at
-    /// `deposit_index` `i`.
+    /// `deposit_index` `i + finalized_deposit_count`.
     deposit_roots: Vec<Hash256>,
 }
 
@@ -96,6 +185,8 @@ impl Default for DepositCache {
             logs: Vec::new(),
             leaves: Vec::new(),
             deposit_contract_deploy_block: 1,
+            finalized_deposit_count: 0,
+            finalized_block_height: 0,
             deposit_tree,
             deposit_roots,
         }
@@ -118,29 +209,145 @@ impl DepositCache {
         }
     }
 
-    /// Returns the number of deposits available in the cache.
+    /// Create a new `DepositCache` containing only the finalized deposits of `snapshot`.
+    ///
+    /// The snapshot must be valid (see `DepositTreeSnapshot::is_valid`).
+    pub fn from_deposit_snapshot(
+        deposit_contract_deploy_block: u64,
+        snapshot: &DepositTreeSnapshot,
+    ) -> Result<Self, String> {
+        let deposit_tree = DepositDataTree::from_snapshot(snapshot, DEPOSIT_TREE_DEPTH)
+            .map_err(|e| format!("Invalid DepositSnapshot: {:?}", e))?;
+        Ok(DepositCache {
+            logs: Vec::new(),
+            leaves: Vec::new(),
+            deposit_contract_deploy_block,
+            finalized_deposit_count: snapshot.deposit_count,
+            finalized_block_height: snapshot.execution_block_height,
+            deposit_roots: vec![deposit_tree.root()],
+            deposit_tree,
+        })
+    }
+
+    /// Returns the number of deposits that have been finalized.
+    pub fn finalized_deposit_count(&self) -> u64 {
+        self.finalized_deposit_count
+    }
+
+    /// Returns the height of the eth1 block at which deposits were finalized.
+    pub fn finalized_block_height(&self) -> u64 {
+        self.finalized_block_height
+    }
+
+    /// Finalize the deposits up to and including the deposits in `eth1_block`.
+    ///
+    /// The logs of the finalized deposits are dropped from the cache.
+    pub fn finalize(&mut self, eth1_block: Eth1Block) -> Result<(), Error> {
+        let deposits_to_finalize = eth1_block.deposit_count.ok_or_else(|| {
+            Error::Internal("Finalized block must have a deposit count".to_string())
+        })?;
+        if deposits_to_finalize > self.deposit_count() {
+            return Err(Error::InvalidFinalizeIndex {
+                requested_count: deposits_to_finalize,
+                currently_finalized: self.finalized_deposit_count,
+                deposit_count: self.deposit_count(),
+            });
+        }
+        if deposits_to_finalize == self.finalized_deposit_count {
+            // Nothing new to finalize.
+            return Ok(());
+        }
+        if deposits_to_finalize < self.finalized_deposit_count {
+            return Err(Error::InvalidFinalizeIndex {
+                requested_count: deposits_to_finalize,
+                currently_finalized: self.finalized_deposit_count,
+                deposit_count: self.deposit_count(),
+            });
+        }
+
+        let deposit_root = eth1_block.deposit_root.ok_or_else(|| {
+            Error::Internal("Finalized block must have a deposit root".to_string())
+        })?;
+        let finalized_log_count = (deposits_to_finalize - self.finalized_deposit_count) as usize;
+        let expected_root = self
+            .deposit_roots
+            .get(finalized_log_count)
+            .ok_or(Error::PleaseNotifyTheDevs)?;
+        if *expected_root != deposit_root {
+            return Err(Error::Internal(format!(
+                "Finalized deposit root {:?} does not match cached root {:?}",
+                deposit_root, expected_root
+            )));
+        }
+
+        self.deposit_tree
+            .finalize(types::FinalizedExecutionBlock {
+                deposit_root,
+                deposit_count: deposits_to_finalize,
+                block_hash: eth1_block.hash,
+                block_height: eth1_block.number,
+            })
+            .map_err(Error::DepositTree)?;
+
+        self.logs.drain(0..finalized_log_count);
+        self.leaves.drain(0..finalized_log_count);
+        self.deposit_roots.drain(0..finalized_log_count);
+        self.finalized_deposit_count = deposits_to_finalize;
+        self.finalized_block_height = eth1_block.number;
+
+        Ok(())
+    }
+
+    /// Returns the deposit tree snapshot of the finalized deposits, if any have been finalized.
+    pub fn get_deposit_snapshot(&self) -> Option<DepositTreeSnapshot> {
+        self.deposit_tree.get_snapshot()
+    }
+
+    /// Returns the number of non-finalized deposits available in the cache.
     pub fn len(&self) -> usize {
         self.logs.len()
     }
 
-    /// True if the cache does not store any blocks.
+    /// True if the cache does not store any non-finalized deposits.
     pub fn is_empty(&self) -> bool {
         self.logs.is_empty()
     }
 
+    /// Returns the total number of deposits known to the cache, including finalized deposits.
+    pub fn deposit_count(&self) -> u64 {
+        self.finalized_deposit_count + self.logs.len() as u64
+    }
+
     /// Returns the block number for the most recent deposit in the cache.
     pub fn latest_block_number(&self) -> Option<u64> {
         self.logs.last().map(|log| log.block_number)
     }
 
-    /// Returns an iterator over all the logs in `self`.
+    /// Returns an iterator over all the non-finalized logs in `self`.
     pub fn iter(&self) -> impl Iterator<Item = &DepositLog> {
         self.logs.iter()
     }
 
     /// Returns the i'th deposit log.
+    ///
+    /// Returns `None` if the log has been finalized or is not yet known.
     pub fn get(&self, i: usize) -> Option<&DepositLog> {
-        self.logs.get(i)
+        let finalized_deposit_count = self.finalized_deposit_count as usize;
+        if i < finalized_deposit_count {
+            None
+        } else {
+            self.logs.get(i - finalized_deposit_count)
+        }
+    }
+
+    /// Returns the deposit root with DEPOSIT COUNT (not index) i.
+    pub fn get_root(&self, i: usize) -> Option<&Hash256> {
+        let finalized_deposit_count = self.finalized_deposit_count as usize;
+        if i < finalized_deposit_count {
+            None
+        } else {
+            self.deposit_roots.get(i - finalized_deposit_count)
+        }
     }
 
     /// Adds `log` to self.
@@ -152,8 +359,9 @@ impl DepositCache {
     ///
     /// - If a log with index `log.index - 1` is not already present in `self` (ignored when empty).
     /// - If a log with `log.index` is already known, but the given `log` is distinct to it.
+    /// - If a log with `log.index` has already been finalized.
     pub fn insert_log(&mut self, log: DepositLog) -> Result<DepositCacheInsertOutcome, Error> {
-        match log.index.cmp(&(self.logs.len() as u64)) {
+        match log.index.cmp(&self.deposit_count()) {
             Ordering::Equal => {
                 let deposit = log.deposit_data.tree_hash_root();
                 self.leaves.push(deposit);
@@ -165,7 +373,16 @@ impl DepositCache {
                 Ok(DepositCacheInsertOutcome::Inserted)
             }
             Ordering::Less => {
-                if self.logs[log.index as usize] == log {
+                // Logs at or below the finalized index may be re-downloaded after a restart, they
+                // can't be compared against anything.
+                if log.index < self.finalized_deposit_count {
+                    return Err(Error::FinalizedLogInsert {
+                        log_index: log.index,
+                        finalized_index: self.finalized_deposit_count - 1,
+                    });
+                }
+                let index = (log.index - self.finalized_deposit_count) as usize;
+                if self.logs[index] == log {
                     Ok(DepositCacheInsertOutcome::Duplicate)
                 } else {
                     Err(Error::DuplicateDistinctLog(log.index))
@@ -173,7 +390,7 @@ impl DepositCache {
             }
             Ordering::Greater => Err(Error::NonConsecutive {
                 log_index: log.index,
-                expected: self.logs.len(),
+                expected: self.deposit_count() as usize,
             }),
         }
     }
@@ -189,6 +406,7 @@ impl DepositCache {
     ///
     /// - If `deposit_count` is larger than `end`.
     /// - There are not sufficient deposits in the tree to generate the proof.
+    /// - If `start` is prior to the finalized deposit count.
     pub fn get_deposits(
         &self,
         start: u64,
@@ -202,48 +420,68 @@ impl DepositCache {
                 deposit_count,
                 range_end: end,
             })
-        } else if end > self.logs.len() as u64 {
+        } else if end > self.deposit_count() {
             // The range of requested deposits exceeds the deposits stored locally.
             Err(Error::InsufficientDeposits {
                 requested: end,
-                known_deposits: self.logs.len(),
+                known_deposits: self.deposit_count() as usize,
             })
-        } else if deposit_count > self.leaves.len() as u64 {
+        } else if deposit_count > self.deposit_count() {
             // There are not `deposit_count` known deposit roots, so we can't build the merkle tree
             // to prove into.
             Err(Error::InsufficientDeposits {
                 requested: deposit_count,
-                known_deposits: self.logs.len(),
+                known_deposits: self.deposit_count() as usize,
+            })
+        } else if start < self.finalized_deposit_count {
+            // Proofs can't be generated for finalized deposits.
+            Err(Error::DepositRangeInvalid {
+                range_start: start,
+                finalized_count: self.finalized_deposit_count,
             })
         } else {
+            let finalized_deposit_count = self.finalized_deposit_count;
             let leaves = self
                 .leaves
-                .get(0..deposit_count as usize)
+                .get(0..(deposit_count - finalized_deposit_count) as usize)
                 .ok_or_else(|| Error::Internal("Unable to get known leaves".into()))?;
 
             // Note: there is likely a more optimal solution than recreating the `DepositDataTree`
             // each time this function is called.
             //
-            // Perhaps a base merkle tree could be maintained that contains all deposits up to the
-            // last finalized eth1 deposit count. Then, that tree could be cloned and extended for
-            // each of these calls.
-
-            let tree = DepositDataTree::create(leaves, deposit_count as usize, tree_depth);
+            // The tree is rebuilt from the finalized snapshot (if any) and extended with the
+            // remaining leaves up to `deposit_count`.
+            let tree = match self.deposit_tree.get_snapshot() {
+                Some(snapshot) => {
+                    let mut tree = DepositDataTree::from_snapshot(&snapshot, tree_depth)
+                        .map_err(Error::DepositTree)?;
+                    for leaf in leaves {
+                        tree.push_leaf(*leaf).map_err(Error::DepositTree)?;
+                    }
+                    tree
+                }
+                None => DepositDataTree::create(leaves, deposit_count as usize, tree_depth),
+            };
 
             let deposits = self
                 .logs
-                .get(start as usize..end as usize)
+                .get(
+                    (start - finalized_deposit_count) as usize
+                        ..(end - finalized_deposit_count) as usize,
+                )
                 .ok_or_else(|| Error::Internal("Unable to get known log".into()))?
                 .iter()
                 .map(|deposit_log| {
-                    let (_leaf, proof) = tree.generate_proof(deposit_log.index as usize);
+                    let (_leaf, proof) = tree
+                        .generate_proof(deposit_log.index as usize)
+                        .map_err(Error::DepositTree)?;
 
-                    Deposit {
+                    Ok(Deposit {
                         proof: proof.into(),
                         data: deposit_log.deposit_data.clone(),
-                    }
+                    })
                 })
-                .collect();
+                .collect::<Result<Vec<_>, Error>>()?;
 
             Ok((tree.root(), deposits))
         }
@@ -252,9 +490,13 @@ impl DepositCache {
     /// Returns the number of deposits with valid signatures that have been observed up to and
     /// including the block at `block_number`.
     ///
-    /// Returns `None` if the `block_number` is zero or prior to contract deployment.
+    /// Returns `None` if the `block_number` is zero or prior to contract deployment, or if any
+    /// deposits have been finalized (the signatures of finalized deposits are unknown).
     pub fn get_valid_signature_count(&self, block_number: u64) -> Option<usize> {
-        if block_number == 0 || block_number < self.deposit_contract_deploy_block {
+        if block_number == 0
+            || block_number < self.deposit_contract_deploy_block
+            || self.finalized_deposit_count != 0
+        {
             None
         } else {
             Some(
@@ -270,16 +512,22 @@ impl DepositCache {
     /// Returns the number of deposits that have been observed up to and
     /// including the block at `block_number`.
     ///
-    /// Returns `None` if the `block_number` is zero or prior to contract deployment.
+    /// Returns `None` if the `block_number` is zero or prior to contract deployment, or prior to
+    /// the block at which deposits were finalized.
     pub fn get_deposit_count_from_cache(&self, block_number: u64) -> Option<u64> {
-        if block_number == 0 || block_number < self.deposit_contract_deploy_block {
+        if block_number == 0
+            || block_number < self.deposit_contract_deploy_block
+            || block_number < self.finalized_block_height
+        {
             None
         } else {
             Some(
-                self.logs
-                    .iter()
-                    .take_while(|deposit| deposit.block_number <= block_number)
-                    .count() as u64,
+                self.finalized_deposit_count
+                    + self
+                        .logs
+                        .iter()
+                        .take_while(|deposit| deposit.block_number <= block_number)
+                        .count() as u64,
             )
         }
     }
@@ -289,8 +537,8 @@ impl DepositCache {
     /// Fetches the `deposit_count` on or just before the queried `block_number`
     /// and queries the `deposit_roots` map to get the corresponding `deposit_root`.
     pub fn get_deposit_root_from_cache(&self, block_number: u64) -> Option<Hash256> {
-        let index = self.get_deposit_count_from_cache(block_number)?;
-        Some(*self.deposit_roots.get(index as usize)?)
+        let count = self.get_deposit_count_from_cache(block_number)?;
+        self.get_root(count as usize).cloned()
     }
 }
 
@@ -453,4 +701,136 @@ pub mod tests {
         // Range higher than count.
         assert!(tree.get_deposits(0, 4, 2, TREE_DEPTH).is_err());
     }
+
+    fn cache_with_deposits(n: u64) -> DepositCache {
+        let mut cache = DepositCache::default();
+        for i in 0..n {
+            let mut log = example_log();
+            log.index = i;
+            log.block_number = i;
+            log.deposit_data.withdrawal_credentials = Hash256::from_low_u64_be(i);
+            cache.insert_log(log).expect("should add consecutive logs");
+        }
+        cache
+    }
+
+    fn finalized_block(cache: &DepositCache, deposit_count: u64) -> Eth1Block {
+        Eth1Block {
+            hash: Hash256::from_low_u64_be(deposit_count),
+            number: deposit_count - 1,
+            timestamp: 0,
+            deposit_root: cache.get_root(deposit_count as usize).cloned(),
+            deposit_count: Some(deposit_count),
+        }
+    }
+
+    #[test]
+    fn finalize_and_get_deposits() {
+        let n = 32;
+        let finalized = 16;
+        let full = cache_with_deposits(n);
+        let mut cache = cache_with_deposits(n);
+
+        cache
+            .finalize(finalized_block(&full, finalized))
+            .expect("should finalize");
+        assert_eq!(cache.finalized_deposit_count(), finalized);
+        assert_eq!(cache.len(), (n - finalized) as usize);
+        assert_eq!(cache.deposit_count(), n);
+
+        // Proofs for non-finalized deposits are unchanged.
+        assert_eq!(
+            cache.get_deposits(finalized, n, n, TREE_DEPTH),
+            full.get_deposits(finalized, n, n, TREE_DEPTH)
+        );
+        assert_eq!(
+            cache.get_deposits(finalized + 2, n - 4, n - 2, TREE_DEPTH),
+            full.get_deposits(finalized + 2, n - 4, n - 2, TREE_DEPTH)
+        );
+
+        // Proofs can't be produced for finalized deposits.
+        assert!(cache.get_deposits(0, 4, n, TREE_DEPTH).is_err());
+
+        // Deposit counts and roots are only known after the finalized block.
+        assert_eq!(cache.get_deposit_count_from_cache(finalized - 2), None);
+        assert_eq!(
+            cache.get_deposit_count_from_cache(20),
+            full.get_deposit_count_from_cache(20)
+        );
+        assert_eq!(
+            cache.get_deposit_root_from_cache(20),
+            full.get_deposit_root_from_cache(20)
+        );
+
+        // Finalized logs can't be re-inserted.
+        let mut log = example_log();
+        log.index = 0;
+        assert!(cache.insert_log(log).is_err());
+    }
+
+    #[test]
+    fn finalize_invalid() {
+        let n = 16;
+        let full = cache_with_deposits(n);
+        let mut cache = cache_with_deposits(n);
+
+        // Can't finalize more deposits than are known.
+        let mut block = finalized_block(&full, n);
+        block.deposit_count = Some(n + 1);
+        assert!(cache.finalize(block).is_err());
+
+        // Can't finalize with a mismatched deposit root.
+        let mut block = finalized_block(&full, n);
+        block.deposit_root = full.get_root(n as usize - 1).cloned();
+        assert!(cache.finalize(block).is_err());
+
+        // Can't un-finalize deposits.
+        cache
+            .finalize(finalized_block(&full, 8))
+            .expect("should finalize");
+        assert!(cache.finalize(finalized_block(&full, 4)).is_err());
+    }
+
+    #[test]
+    fn deposit_snapshot_round_trip() {
+        let n = 40;
+        let finalized = 27;
+        let full = cache_with_deposits(n);
+        let mut cache = cache_with_deposits(n);
+
+        assert_eq!(cache.get_deposit_snapshot(), None);
+
+        cache
+            .finalize(finalized_block(&full, finalized))
+            .expect("should finalize");
+
+        let snapshot = cache
+            .get_deposit_snapshot()
+            .expect("should have a snapshot");
+        assert!(snapshot.is_valid());
+        assert_eq!(snapshot.deposit_count, finalized);
+        assert_eq!(
+            Some(&snapshot.deposit_root),
+            full.get_root(finalized as usize)
+        );
+
+        // A cache initialized from the snapshot can be extended with the remaining deposits.
+        let mut from_snapshot =
+            DepositCache::from_deposit_snapshot(1, &snapshot).expect("should load snapshot");
+        for log in full.iter().skip(finalized as usize) {
+            from_snapshot
+                .insert_log(log.clone())
+                .expect("should add consecutive logs");
+        }
+        assert_eq!(
+            from_snapshot.get_deposits(finalized, n, n, TREE_DEPTH),
+            full.get_deposits(finalized, n, n, TREE_DEPTH)
+        );
+
+        // The finalized cache survives persistence.
+        let decoded = SszDepositCache::from_deposit_cache(&cache)
+            .to_deposit_cache()
+            .expect("should decode cache");
+        assert!(decoded == cache);
+    }
 }
@@ -1,7 +1,7 @@
 use crate::Config;
 use crate::{
     block_cache::{BlockCache, Eth1Block},
-    deposit_cache::{DepositCache, SszDepositCache},
+    deposit_cache::{DepositCache, SszDepositCache, SszDepositCacheV1, SszDepositCacheV10},
     service::EndpointsCache,
 };
 use parking_lot::RwLock;
@@ -9,7 +9,7 @@ use ssz::four_byte_option_impl;
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use std::sync::Arc;
-use types::ChainSpec;
+use types::{ChainSpec, DepositTreeSnapshot, Eth1Data};
 
 // Define "legacy" implementations of `Option<u64>` which use four bytes for encoding the union
 // selector.
@@ -29,6 +29,20 @@ impl DepositUpdater {
             last_processed_block: None,
         }
     }
+
+    /// Create a `DepositUpdater` containing only the finalized deposits of `snapshot`.
+    ///
+    /// Deposit logs will be downloaded starting from the block following the snapshot.
+    pub fn from_snapshot(
+        deposit_contract_deploy_block: u64,
+        snapshot: &DepositTreeSnapshot,
+    ) -> Result<Self, String> {
+        let last_processed_block = Some(snapshot.execution_block_height);
+        Ok(Self {
+            cache: DepositCache::from_deposit_snapshot(deposit_contract_deploy_block, snapshot)?,
+            last_processed_block,
+        })
+    }
 }
 
 #[derive(Default)]
@@ -38,6 +52,8 @@ pub struct Inner {
     pub endpoints_cache: RwLock<Option<Arc<EndpointsCache>>>,
     pub config: RwLock<Config>,
     pub remote_head_block: RwLock<Option<Eth1Block>>,
+    /// The `Eth1Data` of the latest finalized beacon state, awaiting deposit finalization.
+    pub to_finalize: RwLock<Option<Eth1Data>>,
     pub spec: ChainSpec,
 }
 
@@ -70,15 +86,47 @@ impl Inner {
     }
 }
 
+pub type SszEth1Cache = SszEth1CacheV10;
+
+/// The persisted eth1 cache prior to schema v10, which does not support deposit finalization.
+#[derive(Encode, Decode, Clone)]
+pub struct SszEth1CacheV1 {
+    pub block_cache: BlockCache,
+    pub deposit_cache: SszDepositCacheV1,
+    #[ssz(with = "four_byte_option_u64")]
+    pub last_processed_block: Option<u64>,
+}
+
 #[derive(Encode, Decode, Clone)]
-pub struct SszEth1Cache {
-    block_cache: BlockCache,
-    deposit_cache: SszDepositCache,
+pub struct SszEth1CacheV10 {
+    pub block_cache: BlockCache,
+    pub deposit_cache: SszDepositCacheV10,
     #[ssz(with = "four_byte_option_u64")]
-    last_processed_block: Option<u64>,
+    pub last_processed_block: Option<u64>,
+}
+
+impl From<SszEth1CacheV1> for SszEth1CacheV10 {
+    fn from(cache: SszEth1CacheV1) -> Self {
+        Self {
+            block_cache: cache.block_cache,
+            deposit_cache: cache.deposit_cache.into(),
+            last_processed_block: cache.last_processed_block,
+        }
+    }
 }
 
-impl SszEth1Cache {
+impl SszEth1CacheV10 {
+    /// Convert to the persisted format used prior to schema v10.
+    ///
+    /// Returns `None` if any deposits have been finalized, since they are no longer stored.
+    pub fn to_v1(&self) -> Option<SszEth1CacheV1> {
+        Some(SszEth1CacheV1 {
+            block_cache: self.block_cache.clone(),
+            deposit_cache: self.deposit_cache.to_v1()?,
+            last_processed_block: self.last_processed_block,
+        })
+    }
+
     pub fn from_inner(inner: &Inner) -> Self {
         let deposit_updater = inner.deposit_cache.read();
         let block_cache = inner.block_cache.read();
@@ -100,6 +148,7 @@ impl SszEth1Cache {
             // Set the remote head_block zero when creating a new instance. We only care about
             // present and future eth1 nodes.
             remote_head_block: RwLock::new(None),
+            to_finalize: RwLock::new(None),
             config: RwLock::new(config),
             spec,
         })
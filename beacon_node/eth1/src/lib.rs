@@ -10,9 +10,9 @@ mod metrics;
 mod service;
 
 pub use block_cache::{BlockCache, Eth1Block};
-pub use deposit_cache::DepositCache;
+pub use deposit_cache::{DepositCache, SszDepositCache, SszDepositCacheV1, SszDepositCacheV10};
 pub use deposit_log::DepositLog;
-pub use inner::SszEth1Cache;
+pub use inner::{SszEth1Cache, SszEth1CacheV1, SszEth1CacheV10};
 pub use service::{
     BlockCacheUpdateOutcome, Config, DepositCacheUpdateOutcome, Error, Service, DEFAULT_CHAIN_ID,
     DEFAULT_NETWORK_ID,
@@ -21,7 +21,7 @@ use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock as TRwLock;
 use tokio::time::{interval_at, Duration, Instant};
-use types::{ChainSpec, EthSpec, Unsigned};
+use types::{ChainSpec, DepositTreeSnapshot, Eth1Data, EthSpec, Unsigned};
 
 /// Indicates the default eth1 network id we use for the deposit contract.
 pub const DEFAULT_NETWORK_ID: Eth1Id = Eth1Id::Goerli;
@@ -504,6 +504,7 @@ impl Service {
                 )),
                 endpoints_cache: RwLock::new(None),
                 remote_head_block: RwLock::new(None),
+                to_finalize: RwLock::new(None),
                 config: RwLock::new(config),
                 spec,
             }),
@@ -511,6 +512,49 @@ impl Service {
         }
     }
 
+    /// Creates a new service with a deposit cache initialized from `deposit_snapshot`. Does not
+    /// attempt to connect to the eth1 node.
+    ///
+    /// Deposit logs are only downloaded for blocks following the snapshot.
+    pub fn from_deposit_snapshot(
+        config: Config,
+        log: Logger,
+        spec: ChainSpec,
+        deposit_snapshot: &DepositTreeSnapshot,
+    ) -> Result<Self, String> {
+        if !deposit_snapshot.is_valid() {
+            return Err("Invalid deposit snapshot: deposit root mismatch".to_string());
+        }
+
+        let deposit_cache =
+            DepositUpdater::from_snapshot(config.deposit_contract_deploy_block, deposit_snapshot)?;
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                block_cache: <_>::default(),
+                deposit_cache: RwLock::new(deposit_cache),
+                endpoints_cache: RwLock::new(None),
+                remote_head_block: RwLock::new(None),
+                to_finalize: RwLock::new(None),
+                config: RwLock::new(config),
+                spec,
+            }),
+            log,
+        })
+    }
+
+    /// Returns the EIP-4881 snapshot of the finalized deposits, if any have been finalized.
+    pub fn get_deposit_snapshot(&self) -> Option<DepositTreeSnapshot> {
+        self.deposits().read().cache.get_deposit_snapshot()
+    }
+
+    /// Set the `Eth1Data` of the latest finalized beacon state.
+    ///
+    /// The deposits it includes will be finalized during the next cache update.
+    pub fn set_to_finalize(&self, eth1_data: Option<Eth1Data>) {
+        *self.inner.to_finalize.write() = eth1_data;
+    }
+
     /// Returns the follow distance that has been shortened to accommodate for differences in the
     /// spacing between blocks.
     ///
@@ -804,9 +848,63 @@ impl Service {
         let (deposit_outcome, block_outcome) =
             futures::try_join!(update_deposit_cache, update_block_cache)?;
 
+        self.finalize_deposits();
+
         Ok((deposit_outcome, block_outcome))
     }
 
+    /// Finalize the deposit cache up to the `Eth1Data` set with `set_to_finalize`.
+    ///
+    /// The eth1 block referenced by the `Eth1Data` must be in the block cache, otherwise
+    /// finalization is deferred to a later update.
+    fn finalize_deposits(&self) {
+        let eth1_data = match self.inner.to_finalize.read().clone() {
+            Some(eth1_data) => eth1_data,
+            None => return,
+        };
+
+        let eth1_block = self
+            .blocks()
+            .read()
+            .iter()
+            .find(|block| block.hash == eth1_data.block_hash)
+            .cloned();
+
+        let eth1_block = match eth1_block {
+            Some(block) => block,
+            None => {
+                debug!(
+                    self.log,
+                    "Eth1 block not yet cached for finalization";
+                    "block_hash" => ?eth1_data.block_hash,
+                );
+                return;
+            }
+        };
+
+        let mut deposit_cache = self.deposits().write();
+        let already_finalized = deposit_cache.cache.finalized_deposit_count();
+        match deposit_cache.cache.finalize(eth1_block) {
+            Ok(()) => {
+                *self.inner.to_finalize.write() = None;
+                if eth1_data.deposit_count > already_finalized {
+                    debug!(
+                        self.log,
+                        "Finalized deposit cache";
+                        "deposit_count" => eth1_data.deposit_count,
+                        "block_hash" => ?eth1_data.block_hash,
+                    );
+                }
+            }
+            Err(e) => error!(
+                self.log,
+                "Failed to finalize deposit cache";
+                "error" => ?e,
+                "block_hash" => ?eth1_data.block_hash,
+            ),
+        }
+    }
+
     /// A looping future that updates the cache, then waits `config.auto_update_interval` before
     /// updating it again.
     ///
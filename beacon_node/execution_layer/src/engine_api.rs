@@ -6,9 +6,12 @@ pub use json_structures::TransitionConfigurationV1;
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use slog::Logger;
+use tree_hash::TreeHash;
 pub use types::{
-    Address, EthSpec, ExecutionBlockHash, ExecutionPayload, ExecutionPayloadHeader, FixedVector,
-    Hash256, Uint256, VariableList,
+    Address, Blob, EthSpec, ExecutionBlockHash, ExecutionPayload, ExecutionPayloadCapella,
+    ExecutionPayloadDeneb, ExecutionPayloadHeader, ExecutionPayloadMerge, FixedVector, Hash256,
+    KzgCommitment, KzgCommitments, KzgProof, Transactions, Uint256, VariableList, VersionedHash,
+    Withdrawal, Withdrawals,
 };
 
 pub mod auth;
@@ -49,6 +52,7 @@ pub enum Error {
     },
     DeserializeTransaction(ssz_types::Error),
     DeserializeTransactions(ssz_types::Error),
+    DeserializeWithdrawals(ssz_types::Error),
 }
 
 impl From<reqwest::Error> for Error {
@@ -153,13 +157,188 @@ pub struct ExecutionBlockWithTransactions<T: EthSpec> {
     #[serde(rename = "hash")]
     pub block_hash: ExecutionBlockHash,
     pub transactions: Vec<Transaction>,
+    /// Only present for blocks from Capella (Shanghai) onwards.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawals: Option<Vec<json_structures::JsonWithdrawal>>,
+    /// Only present for blocks from Deneb (Cancun) onwards.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "json_structures::serde_optional_u64_hex_be"
+    )]
+    pub blob_gas_used: Option<u64>,
+    /// Only present for blocks from Deneb (Cancun) onwards.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "json_structures::serde_optional_u64_hex_be"
+    )]
+    pub excess_blob_gas: Option<u64>,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+/// The parts of an execution payload that are not included in its `ExecutionPayloadHeader`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExecutionPayloadBodyV1<T: EthSpec> {
+    pub transactions: Transactions<T>,
+    /// `None` for payloads from before Capella.
+    pub withdrawals: Option<Withdrawals<T>>,
+}
+
+impl<T: EthSpec> ExecutionPayloadBodyV1<T> {
+    /// Combine the body with `header` to form a full payload, returning `None` if the body's
+    /// transactions or withdrawals don't match the header's roots.
+    pub fn to_payload(self, header: ExecutionPayloadHeader<T>) -> Option<ExecutionPayload<T>> {
+        if self.transactions.tree_hash_root() != header.transactions_root() {
+            return None;
+        }
+
+        match (header, self.withdrawals) {
+            (ExecutionPayloadHeader::Merge(header), None) => {
+                // Use this verbose deconstruction pattern to ensure no field is left unused.
+                let types::ExecutionPayloadHeaderMerge {
+                    parent_hash,
+                    fee_recipient,
+                    state_root,
+                    receipts_root,
+                    logs_bloom,
+                    prev_randao,
+                    block_number,
+                    gas_limit,
+                    gas_used,
+                    timestamp,
+                    extra_data,
+                    base_fee_per_gas,
+                    block_hash,
+                    transactions_root: _,
+                } = header;
+
+                Some(ExecutionPayload::Merge(ExecutionPayloadMerge {
+                    parent_hash,
+                    fee_recipient,
+                    state_root,
+                    receipts_root,
+                    logs_bloom,
+                    prev_randao,
+                    block_number,
+                    gas_limit,
+                    gas_used,
+                    timestamp,
+                    extra_data,
+                    base_fee_per_gas,
+                    block_hash,
+                    transactions: self.transactions,
+                }))
+            }
+            (ExecutionPayloadHeader::Capella(header), Some(withdrawals)) => {
+                if withdrawals.tree_hash_root() != header.withdrawals_root {
+                    return None;
+                }
+
+                // Use this verbose deconstruction pattern to ensure no field is left unused.
+                let types::ExecutionPayloadHeaderCapella {
+                    parent_hash,
+                    fee_recipient,
+                    state_root,
+                    receipts_root,
+                    logs_bloom,
+                    prev_randao,
+                    block_number,
+                    gas_limit,
+                    gas_used,
+                    timestamp,
+                    extra_data,
+                    base_fee_per_gas,
+                    block_hash,
+                    transactions_root: _,
+                    withdrawals_root: _,
+                } = header;
+
+                Some(ExecutionPayload::Capella(ExecutionPayloadCapella {
+                    parent_hash,
+                    fee_recipient,
+                    state_root,
+                    receipts_root,
+                    logs_bloom,
+                    prev_randao,
+                    block_number,
+                    gas_limit,
+                    gas_used,
+                    timestamp,
+                    extra_data,
+                    base_fee_per_gas,
+                    block_hash,
+                    transactions: self.transactions,
+                    withdrawals,
+                }))
+            }
+            (ExecutionPayloadHeader::Deneb(header), Some(withdrawals)) => {
+                if withdrawals.tree_hash_root() != header.withdrawals_root {
+                    return None;
+                }
+
+                // Use this verbose deconstruction pattern to ensure no field is left unused.
+                let types::ExecutionPayloadHeaderDeneb {
+                    parent_hash,
+                    fee_recipient,
+                    state_root,
+                    receipts_root,
+                    logs_bloom,
+                    prev_randao,
+                    block_number,
+                    gas_limit,
+                    gas_used,
+                    timestamp,
+                    extra_data,
+                    base_fee_per_gas,
+                    block_hash,
+                    transactions_root: _,
+                    withdrawals_root: _,
+                    blob_gas_used,
+                    excess_blob_gas,
+                } = header;
+
+                Some(ExecutionPayload::Deneb(ExecutionPayloadDeneb {
+                    parent_hash,
+                    fee_recipient,
+                    state_root,
+                    receipts_root,
+                    logs_bloom,
+                    prev_randao,
+                    block_number,
+                    gas_limit,
+                    gas_used,
+                    timestamp,
+                    extra_data,
+                    base_fee_per_gas,
+                    block_hash,
+                    transactions: self.transactions,
+                    withdrawals,
+                    blob_gas_used,
+                    excess_blob_gas,
+                }))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct PayloadAttributes {
     pub timestamp: u64,
     pub prev_randao: Hash256,
     pub suggested_fee_recipient: Address,
+    /// The withdrawals to include in the payload, `Some` from Capella onwards.
+    pub withdrawals: Option<Vec<Withdrawal>>,
+    /// The root of the block the payload will be built upon, `Some` from Deneb onwards.
+    pub parent_beacon_block_root: Option<Hash256>,
+}
+
+/// The blobs for the blob transactions of a payload, along with their KZG commitments and proofs.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlobsBundle<T: EthSpec> {
+    pub commitments: KzgCommitments<T>,
+    pub proofs: VariableList<KzgProof, T::MaxBlobCommitmentsPerBlock>,
+    pub blobs: VariableList<Blob<T>, T::MaxBlobCommitmentsPerBlock>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
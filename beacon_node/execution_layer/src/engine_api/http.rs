@@ -8,6 +8,7 @@ use reqwest::header::CONTENT_TYPE;
 use sensitive_url::SensitiveUrl;
 use serde::de::DeserializeOwned;
 use serde_json::json;
+use std::collections::HashSet;
 use std::marker::PhantomData;
 use std::time::Duration;
 use types::{BlindedPayload, EthSpec, ExecutionPayloadHeader, SignedBeaconBlock};
@@ -17,6 +18,9 @@ pub use reqwest::Client;
 const STATIC_ID: u32 = 1;
 pub const JSONRPC_VERSION: &str = "2.0";
 
+/// The JSON-RPC error code returned for methods which the server doesn't support.
+pub const METHOD_NOT_FOUND_CODE: i64 = -32601;
+
 pub const RETURN_FULL_TRANSACTION_OBJECTS: bool = false;
 
 pub const ETH_GET_BLOCK_BY_NUMBER: &str = "eth_getBlockByNumber";
@@ -29,19 +33,46 @@ pub const ETH_SYNCING: &str = "eth_syncing";
 pub const ETH_SYNCING_TIMEOUT: Duration = Duration::from_millis(250);
 
 pub const ENGINE_NEW_PAYLOAD_V1: &str = "engine_newPayloadV1";
+pub const ENGINE_NEW_PAYLOAD_V2: &str = "engine_newPayloadV2";
+pub const ENGINE_NEW_PAYLOAD_V3: &str = "engine_newPayloadV3";
 pub const ENGINE_NEW_PAYLOAD_TIMEOUT: Duration = Duration::from_secs(6);
 
 pub const ENGINE_GET_PAYLOAD_V1: &str = "engine_getPayloadV1";
+pub const ENGINE_GET_PAYLOAD_V2: &str = "engine_getPayloadV2";
+pub const ENGINE_GET_PAYLOAD_V3: &str = "engine_getPayloadV3";
 pub const ENGINE_GET_PAYLOAD_TIMEOUT: Duration = Duration::from_secs(2);
 
 pub const ENGINE_FORKCHOICE_UPDATED_V1: &str = "engine_forkchoiceUpdatedV1";
+pub const ENGINE_FORKCHOICE_UPDATED_V2: &str = "engine_forkchoiceUpdatedV2";
+pub const ENGINE_FORKCHOICE_UPDATED_V3: &str = "engine_forkchoiceUpdatedV3";
 pub const ENGINE_FORKCHOICE_UPDATED_TIMEOUT: Duration = Duration::from_secs(6);
 
+pub const ENGINE_GET_PAYLOAD_BODIES_BY_RANGE_V1: &str = "engine_getPayloadBodiesByRangeV1";
+pub const ENGINE_GET_PAYLOAD_BODIES_TIMEOUT: Duration = Duration::from_secs(10);
+
 pub const ENGINE_EXCHANGE_TRANSITION_CONFIGURATION_V1: &str =
     "engine_exchangeTransitionConfigurationV1";
 pub const ENGINE_EXCHANGE_TRANSITION_CONFIGURATION_V1_TIMEOUT: Duration =
     Duration::from_millis(500);
 
+pub const ENGINE_EXCHANGE_CAPABILITIES: &str = "engine_exchangeCapabilities";
+pub const ENGINE_EXCHANGE_CAPABILITIES_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The engine API methods supported by Lighthouse, as sent to `engine_exchangeCapabilities`.
+pub const LIGHTHOUSE_CAPABILITIES: &[&str] = &[
+    ENGINE_NEW_PAYLOAD_V1,
+    ENGINE_NEW_PAYLOAD_V2,
+    ENGINE_NEW_PAYLOAD_V3,
+    ENGINE_GET_PAYLOAD_V1,
+    ENGINE_GET_PAYLOAD_V2,
+    ENGINE_GET_PAYLOAD_V3,
+    ENGINE_FORKCHOICE_UPDATED_V1,
+    ENGINE_FORKCHOICE_UPDATED_V2,
+    ENGINE_FORKCHOICE_UPDATED_V3,
+    ENGINE_GET_PAYLOAD_BODIES_BY_RANGE_V1,
+    ENGINE_EXCHANGE_TRANSITION_CONFIGURATION_V1,
+];
+
 pub const BUILDER_GET_PAYLOAD_HEADER_V1: &str = "builder_getPayloadHeaderV1";
 pub const BUILDER_GET_PAYLOAD_HEADER_TIMEOUT: Duration = Duration::from_secs(2);
 
@@ -168,9 +199,29 @@ impl HttpJsonRpc<EngineApi> {
             .await
     }
 
-    pub async fn new_payload_v1<T: EthSpec>(
+    /// Calls `engine_newPayloadV1`, `engine_newPayloadV2` or `engine_newPayloadV3`, depending on
+    /// the fork of `execution_payload`.
+    ///
+    /// The `versioned_hashes` and `parent_beacon_block_root` are only sent from Deneb onwards.
+    pub async fn new_payload<T: EthSpec>(
         &self,
         execution_payload: ExecutionPayload<T>,
+        versioned_hashes: Vec<VersionedHash>,
+        parent_beacon_block_root: Hash256,
+    ) -> Result<PayloadStatusV1, Error> {
+        match execution_payload {
+            ExecutionPayload::Merge(payload) => self.new_payload_v1(payload).await,
+            ExecutionPayload::Capella(payload) => self.new_payload_v2(payload).await,
+            ExecutionPayload::Deneb(payload) => {
+                self.new_payload_v3(payload, versioned_hashes, parent_beacon_block_root)
+                    .await
+            }
+        }
+    }
+
+    pub async fn new_payload_v1<T: EthSpec>(
+        &self,
+        execution_payload: ExecutionPayloadMerge<T>,
     ) -> Result<PayloadStatusV1, Error> {
         let params = json!([JsonExecutionPayloadV1::from(execution_payload)]);
 
@@ -181,6 +232,38 @@ impl HttpJsonRpc<EngineApi> {
         Ok(response.into())
     }
 
+    pub async fn new_payload_v2<T: EthSpec>(
+        &self,
+        execution_payload: ExecutionPayloadCapella<T>,
+    ) -> Result<PayloadStatusV1, Error> {
+        let params = json!([JsonExecutionPayloadV2::from(execution_payload)]);
+
+        let response: JsonPayloadStatusV1 = self
+            .rpc_request(ENGINE_NEW_PAYLOAD_V2, params, ENGINE_NEW_PAYLOAD_TIMEOUT)
+            .await?;
+
+        Ok(response.into())
+    }
+
+    pub async fn new_payload_v3<T: EthSpec>(
+        &self,
+        execution_payload: ExecutionPayloadDeneb<T>,
+        versioned_hashes: Vec<VersionedHash>,
+        parent_beacon_block_root: Hash256,
+    ) -> Result<PayloadStatusV1, Error> {
+        let params = json!([
+            JsonExecutionPayloadV3::from(execution_payload),
+            versioned_hashes,
+            parent_beacon_block_root
+        ]);
+
+        let response: JsonPayloadStatusV1 = self
+            .rpc_request(ENGINE_NEW_PAYLOAD_V3, params, ENGINE_NEW_PAYLOAD_TIMEOUT)
+            .await?;
+
+        Ok(response.into())
+    }
+
     pub async fn get_payload_v1<T: EthSpec>(
         &self,
         payload_id: PayloadId,
@@ -194,6 +277,57 @@ impl HttpJsonRpc<EngineApi> {
         Ok(response.into())
     }
 
+    pub async fn get_payload_v2<T: EthSpec>(
+        &self,
+        payload_id: PayloadId,
+    ) -> Result<ExecutionPayload<T>, Error> {
+        let params = json!([JsonPayloadIdRequest::from(payload_id)]);
+
+        let response: JsonGetPayloadResponseV2<T> = self
+            .rpc_request(ENGINE_GET_PAYLOAD_V2, params, ENGINE_GET_PAYLOAD_TIMEOUT)
+            .await?;
+
+        Ok(response.execution_payload.into())
+    }
+
+    /// Returns the payload along with the blobs bundle for its blob transactions.
+    pub async fn get_payload_v3<T: EthSpec>(
+        &self,
+        payload_id: PayloadId,
+    ) -> Result<(ExecutionPayload<T>, BlobsBundle<T>), Error> {
+        let params = json!([JsonPayloadIdRequest::from(payload_id)]);
+
+        let response: JsonGetPayloadResponseV3<T> = self
+            .rpc_request(ENGINE_GET_PAYLOAD_V3, params, ENGINE_GET_PAYLOAD_TIMEOUT)
+            .await?;
+
+        Ok((
+            response.execution_payload.into(),
+            response.blobs_bundle.into(),
+        ))
+    }
+
+    pub async fn get_payload_bodies_by_range_v1<T: EthSpec>(
+        &self,
+        start: u64,
+        count: u64,
+    ) -> Result<Vec<Option<ExecutionPayloadBodyV1<T>>>, Error> {
+        let params = json!([format!("{:#x}", start), format!("{:#x}", count)]);
+
+        let response: Vec<Option<JsonExecutionPayloadBodyV1<T>>> = self
+            .rpc_request(
+                ENGINE_GET_PAYLOAD_BODIES_BY_RANGE_V1,
+                params,
+                ENGINE_GET_PAYLOAD_BODIES_TIMEOUT,
+            )
+            .await?;
+
+        Ok(response
+            .into_iter()
+            .map(|body| body.map(Into::into))
+            .collect())
+    }
+
     pub async fn forkchoice_updated_v1(
         &self,
         forkchoice_state: ForkChoiceState,
@@ -215,6 +349,48 @@ impl HttpJsonRpc<EngineApi> {
         Ok(response.into())
     }
 
+    pub async fn forkchoice_updated_v2(
+        &self,
+        forkchoice_state: ForkChoiceState,
+        payload_attributes: Option<PayloadAttributes>,
+    ) -> Result<ForkchoiceUpdatedResponse, Error> {
+        let params = json!([
+            JsonForkChoiceStateV1::from(forkchoice_state),
+            payload_attributes.map(JsonPayloadAttributesV2::from)
+        ]);
+
+        let response: JsonForkchoiceUpdatedV1Response = self
+            .rpc_request(
+                ENGINE_FORKCHOICE_UPDATED_V2,
+                params,
+                ENGINE_FORKCHOICE_UPDATED_TIMEOUT,
+            )
+            .await?;
+
+        Ok(response.into())
+    }
+
+    pub async fn forkchoice_updated_v3(
+        &self,
+        forkchoice_state: ForkChoiceState,
+        payload_attributes: Option<PayloadAttributes>,
+    ) -> Result<ForkchoiceUpdatedResponse, Error> {
+        let params = json!([
+            JsonForkChoiceStateV1::from(forkchoice_state),
+            payload_attributes.map(JsonPayloadAttributesV3::from)
+        ]);
+
+        let response: JsonForkchoiceUpdatedV1Response = self
+            .rpc_request(
+                ENGINE_FORKCHOICE_UPDATED_V3,
+                params,
+                ENGINE_FORKCHOICE_UPDATED_TIMEOUT,
+            )
+            .await?;
+
+        Ok(response.into())
+    }
+
     pub async fn exchange_transition_configuration_v1(
         &self,
         transition_configuration: TransitionConfigurationV1,
@@ -231,6 +407,21 @@ impl HttpJsonRpc<EngineApi> {
 
         Ok(response)
     }
+
+    /// Returns the engine API methods supported by the execution engine.
+    pub async fn exchange_capabilities(&self) -> Result<HashSet<String>, Error> {
+        let params = json!([LIGHTHOUSE_CAPABILITIES]);
+
+        let response: Vec<String> = self
+            .rpc_request(
+                ENGINE_EXCHANGE_CAPABILITIES,
+                params,
+                ENGINE_EXCHANGE_CAPABILITIES_TIMEOUT,
+            )
+            .await?;
+
+        Ok(response.into_iter().collect())
+    }
 }
 
 impl HttpJsonRpc<BuilderApi> {
@@ -587,6 +778,8 @@ mod test {
                                 timestamp: 5,
                                 prev_randao: Hash256::zero(),
                                 suggested_fee_recipient: Address::repeat_byte(0),
+                                withdrawals: None,
+                                parent_beacon_block_root: None,
                             }),
                         )
                         .await;
@@ -622,6 +815,8 @@ mod test {
                             timestamp: 5,
                             prev_randao: Hash256::zero(),
                             suggested_fee_recipient: Address::repeat_byte(0),
+                            withdrawals: None,
+                            parent_beacon_block_root: None,
                         }),
                     )
                     .await
@@ -652,13 +847,61 @@ mod test {
             .await;
     }
 
+    #[tokio::test]
+    async fn get_payload_bodies_by_range_v1_request() {
+        Tester::new(true)
+            .assert_request_equals(
+                |client| async move {
+                    let _ = client
+                        .get_payload_bodies_by_range_v1::<MainnetEthSpec>(42, 32)
+                        .await;
+                },
+                json!({
+                    "id": STATIC_ID,
+                    "jsonrpc": JSONRPC_VERSION,
+                    "method": ENGINE_GET_PAYLOAD_BODIES_BY_RANGE_V1,
+                    "params": ["0x2a", "0x20"]
+                }),
+            )
+            .await;
+
+        Tester::new(false)
+            .assert_auth_failure(|client| async move {
+                client
+                    .get_payload_bodies_by_range_v1::<MainnetEthSpec>(42, 32)
+                    .await
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn exchange_capabilities_request() {
+        Tester::new(true)
+            .assert_request_equals(
+                |client| async move {
+                    let _ = client.exchange_capabilities().await;
+                },
+                json!({
+                    "id": STATIC_ID,
+                    "jsonrpc": JSONRPC_VERSION,
+                    "method": ENGINE_EXCHANGE_CAPABILITIES,
+                    "params": [LIGHTHOUSE_CAPABILITIES]
+                }),
+            )
+            .await;
+
+        Tester::new(false)
+            .assert_auth_failure(|client| async move { client.exchange_capabilities().await })
+            .await;
+    }
+
     #[tokio::test]
     async fn new_payload_v1_request() {
         Tester::new(true)
             .assert_request_equals(
                 |client| async move {
                     let _ = client
-                        .new_payload_v1::<MainnetEthSpec>(ExecutionPayload {
+                        .new_payload_v1::<MainnetEthSpec>(ExecutionPayloadMerge {
                             parent_hash: ExecutionBlockHash::repeat_byte(0),
                             fee_recipient: Address::repeat_byte(1),
                             state_root: Hash256::repeat_byte(1),
@@ -703,7 +946,7 @@ mod test {
         Tester::new(false)
             .assert_auth_failure(|client| async move {
                 client
-                    .new_payload_v1::<MainnetEthSpec>(ExecutionPayload {
+                    .new_payload_v1::<MainnetEthSpec>(ExecutionPayloadMerge {
                         parent_hash: ExecutionBlockHash::repeat_byte(0),
                         fee_recipient: Address::repeat_byte(1),
                         state_root: Hash256::repeat_byte(1),
@@ -724,6 +967,214 @@ mod test {
             .await;
     }
 
+    #[tokio::test]
+    async fn new_payload_v2_request() {
+        Tester::new(true)
+            .assert_request_equals(
+                |client| async move {
+                    let _ = client
+                        .new_payload_v2::<MainnetEthSpec>(ExecutionPayloadCapella {
+                            parent_hash: ExecutionBlockHash::repeat_byte(0),
+                            fee_recipient: Address::repeat_byte(1),
+                            state_root: Hash256::repeat_byte(1),
+                            receipts_root: Hash256::repeat_byte(0),
+                            logs_bloom: vec![1; 256].into(),
+                            prev_randao: Hash256::repeat_byte(1),
+                            block_number: 0,
+                            gas_limit: 1,
+                            gas_used: 2,
+                            timestamp: 42,
+                            extra_data: vec![].into(),
+                            base_fee_per_gas: Uint256::from(1),
+                            block_hash: ExecutionBlockHash::repeat_byte(1),
+                            transactions: vec![].into(),
+                            withdrawals: vec![Withdrawal {
+                                index: 1,
+                                validator_index: 42,
+                                address: Address::repeat_byte(1),
+                                amount: 32,
+                            }]
+                            .into(),
+                        })
+                        .await;
+                },
+                json!({
+                    "id": STATIC_ID,
+                    "jsonrpc": JSONRPC_VERSION,
+                    "method": ENGINE_NEW_PAYLOAD_V2,
+                    "params": [{
+                        "parentHash": HASH_00,
+                        "feeRecipient": ADDRESS_01,
+                        "stateRoot": HASH_01,
+                        "receiptsRoot": HASH_00,
+                        "logsBloom": LOGS_BLOOM_01,
+                        "prevRandao": HASH_01,
+                        "blockNumber": "0x0",
+                        "gasLimit": "0x1",
+                        "gasUsed": "0x2",
+                        "timestamp": "0x2a",
+                        "extraData": "0x",
+                        "baseFeePerGas": "0x1",
+                        "blockHash": HASH_01,
+                        "transactions": [],
+                        "withdrawals": [{
+                            "index": "0x1",
+                            "validatorIndex": "0x2a",
+                            "address": ADDRESS_01,
+                            "amount": "0x20",
+                        }],
+                    }]
+                }),
+            )
+            .await;
+    }
+
+    #[tokio::test]
+    async fn new_payload_v3_request() {
+        Tester::new(true)
+            .assert_request_equals(
+                |client| async move {
+                    let _ = client
+                        .new_payload_v3::<MainnetEthSpec>(
+                            ExecutionPayloadDeneb {
+                                parent_hash: ExecutionBlockHash::repeat_byte(0),
+                                fee_recipient: Address::repeat_byte(1),
+                                state_root: Hash256::repeat_byte(1),
+                                receipts_root: Hash256::repeat_byte(0),
+                                logs_bloom: vec![1; 256].into(),
+                                prev_randao: Hash256::repeat_byte(1),
+                                block_number: 0,
+                                gas_limit: 1,
+                                gas_used: 2,
+                                timestamp: 42,
+                                extra_data: vec![].into(),
+                                base_fee_per_gas: Uint256::from(1),
+                                block_hash: ExecutionBlockHash::repeat_byte(1),
+                                transactions: vec![].into(),
+                                withdrawals: vec![].into(),
+                                blob_gas_used: 131072,
+                                excess_blob_gas: 0,
+                            },
+                            vec![Hash256::repeat_byte(1)],
+                            Hash256::zero(),
+                        )
+                        .await;
+                },
+                json!({
+                    "id": STATIC_ID,
+                    "jsonrpc": JSONRPC_VERSION,
+                    "method": ENGINE_NEW_PAYLOAD_V3,
+                    "params": [{
+                        "parentHash": HASH_00,
+                        "feeRecipient": ADDRESS_01,
+                        "stateRoot": HASH_01,
+                        "receiptsRoot": HASH_00,
+                        "logsBloom": LOGS_BLOOM_01,
+                        "prevRandao": HASH_01,
+                        "blockNumber": "0x0",
+                        "gasLimit": "0x1",
+                        "gasUsed": "0x2",
+                        "timestamp": "0x2a",
+                        "extraData": "0x",
+                        "baseFeePerGas": "0x1",
+                        "blockHash": HASH_01,
+                        "transactions": [],
+                        "withdrawals": [],
+                        "blobGasUsed": "0x20000",
+                        "excessBlobGas": "0x0",
+                    },
+                    [HASH_01],
+                    HASH_00]
+                }),
+            )
+            .await;
+    }
+
+    #[tokio::test]
+    async fn forkchoice_updated_v3_with_payload_attributes_request() {
+        Tester::new(true)
+            .assert_request_equals(
+                |client| async move {
+                    let _ = client
+                        .forkchoice_updated_v3(
+                            ForkChoiceState {
+                                head_block_hash: ExecutionBlockHash::repeat_byte(1),
+                                safe_block_hash: ExecutionBlockHash::repeat_byte(1),
+                                finalized_block_hash: ExecutionBlockHash::zero(),
+                            },
+                            Some(PayloadAttributes {
+                                timestamp: 5,
+                                prev_randao: Hash256::zero(),
+                                suggested_fee_recipient: Address::repeat_byte(0),
+                                withdrawals: Some(vec![]),
+                                parent_beacon_block_root: Some(Hash256::repeat_byte(1)),
+                            }),
+                        )
+                        .await;
+                },
+                json!({
+                    "id": STATIC_ID,
+                    "jsonrpc": JSONRPC_VERSION,
+                    "method": ENGINE_FORKCHOICE_UPDATED_V3,
+                    "params": [{
+                        "headBlockHash": HASH_01,
+                        "safeBlockHash": HASH_01,
+                        "finalizedBlockHash": HASH_00,
+                    },
+                    {
+                        "timestamp":"0x5",
+                        "prevRandao": HASH_00,
+                        "suggestedFeeRecipient": ADDRESS_00,
+                        "withdrawals": [],
+                        "parentBeaconBlockRoot": HASH_01
+                    }]
+                }),
+            )
+            .await;
+    }
+
+    #[tokio::test]
+    async fn forkchoice_updated_v2_with_payload_attributes_request() {
+        Tester::new(true)
+            .assert_request_equals(
+                |client| async move {
+                    let _ = client
+                        .forkchoice_updated_v2(
+                            ForkChoiceState {
+                                head_block_hash: ExecutionBlockHash::repeat_byte(1),
+                                safe_block_hash: ExecutionBlockHash::repeat_byte(1),
+                                finalized_block_hash: ExecutionBlockHash::zero(),
+                            },
+                            Some(PayloadAttributes {
+                                timestamp: 5,
+                                prev_randao: Hash256::zero(),
+                                suggested_fee_recipient: Address::repeat_byte(0),
+                                withdrawals: Some(vec![]),
+                                parent_beacon_block_root: None,
+                            }),
+                        )
+                        .await;
+                },
+                json!({
+                    "id": STATIC_ID,
+                    "jsonrpc": JSONRPC_VERSION,
+                    "method": ENGINE_FORKCHOICE_UPDATED_V2,
+                    "params": [{
+                        "headBlockHash": HASH_01,
+                        "safeBlockHash": HASH_01,
+                        "finalizedBlockHash": HASH_00,
+                    },
+                    {
+                        "timestamp":"0x5",
+                        "prevRandao": HASH_00,
+                        "suggestedFeeRecipient": ADDRESS_00,
+                        "withdrawals": []
+                    }]
+                }),
+            )
+            .await;
+    }
+
     #[tokio::test]
     async fn forkchoice_updated_v1_request() {
         Tester::new(true)
@@ -805,6 +1256,8 @@ mod test {
                                 timestamp: 5,
                                 prev_randao: Hash256::zero(),
                                 suggested_fee_recipient: Address::from_str("0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b").unwrap(),
+                                withdrawals: None,
+                                parent_beacon_block_root: None,
                             })
                         )
                         .await;
@@ -852,6 +1305,8 @@ mod test {
                                 timestamp: 5,
                                 prev_randao: Hash256::zero(),
                                 suggested_fee_recipient: Address::from_str("0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b").unwrap(),
+                                withdrawals: None,
+                                parent_beacon_block_root: None,
                             })
                         )
                         .await
@@ -911,7 +1366,7 @@ mod test {
                         .await
                         .unwrap();
 
-                    let expected = ExecutionPayload {
+                    let expected = ExecutionPayload::Merge(ExecutionPayloadMerge {
                             parent_hash: ExecutionBlockHash::from_str("0x3b8fb240d288781d4aac94d3fd16809ee413bc99294a085798a589dae51ddd4a").unwrap(),
                             fee_recipient: Address::from_str("0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b").unwrap(),
                             state_root: Hash256::from_str("0xca3149fa9e37db08d1cd49c9061db1002ef1cd58db2210f2115c8c989b2bdf45").unwrap(),
@@ -926,7 +1381,7 @@ mod test {
                             base_fee_per_gas: Uint256::from(7),
                             block_hash: ExecutionBlockHash::from_str("0x6359b8381a370e2f54072a5784ddd78b6ed024991558c511d4452eb4f6ac898c").unwrap(),
                         transactions: vec![].into(),
-                        };
+                        });
 
                     assert_eq!(payload, expected);
                 },
@@ -936,7 +1391,7 @@ mod test {
                 // engine_newPayloadV1 REQUEST validation
                 |client| async move {
                     let _ = client
-                        .new_payload_v1::<MainnetEthSpec>(ExecutionPayload {
+                        .new_payload_v1::<MainnetEthSpec>(ExecutionPayloadMerge {
                             parent_hash: ExecutionBlockHash::from_str("0x3b8fb240d288781d4aac94d3fd16809ee413bc99294a085798a589dae51ddd4a").unwrap(),
                             fee_recipient: Address::from_str("0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b").unwrap(),
                             state_root: Hash256::from_str("0xca3149fa9e37db08d1cd49c9061db1002ef1cd58db2210f2115c8c989b2bdf45").unwrap(),
@@ -990,7 +1445,7 @@ mod test {
                 })],
                 |client| async move {
                     let response = client
-                        .new_payload_v1::<MainnetEthSpec>(ExecutionPayload::default())
+                        .new_payload_v1::<MainnetEthSpec>(ExecutionPayloadMerge::default())
                         .await
                         .unwrap();
 
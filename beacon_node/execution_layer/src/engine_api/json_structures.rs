@@ -1,8 +1,8 @@
 use super::*;
 use serde::{Deserialize, Serialize};
 use types::{
-    EthSpec, ExecutionBlockHash, ExecutionPayloadHeader, FixedVector, Transaction, Unsigned,
-    VariableList,
+    EthSpec, ExecutionBlockHash, ExecutionPayloadHeader, ExecutionPayloadHeaderMerge, FixedVector,
+    Transaction, Unsigned, VariableList, Withdrawal,
 };
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -103,7 +103,7 @@ impl<T: EthSpec> From<JsonExecutionPayloadHeaderV1<T>> for ExecutionPayloadHeade
             transactions_root,
         } = e;
 
-        Self {
+        ExecutionPayloadHeader::Merge(ExecutionPayloadHeaderMerge {
             parent_hash,
             fee_recipient,
             state_root,
@@ -118,7 +118,7 @@ impl<T: EthSpec> From<JsonExecutionPayloadHeaderV1<T>> for ExecutionPayloadHeade
             base_fee_per_gas,
             block_hash,
             transactions_root,
-        }
+        })
     }
 }
 
@@ -149,10 +149,10 @@ pub struct JsonExecutionPayloadV1<T: EthSpec> {
         VariableList<Transaction<T::MaxBytesPerTransaction>, T::MaxTransactionsPerPayload>,
 }
 
-impl<T: EthSpec> From<ExecutionPayload<T>> for JsonExecutionPayloadV1<T> {
-    fn from(e: ExecutionPayload<T>) -> Self {
+impl<T: EthSpec> From<ExecutionPayloadMerge<T>> for JsonExecutionPayloadV1<T> {
+    fn from(e: ExecutionPayloadMerge<T>) -> Self {
         // Use this verbose deconstruction pattern to ensure no field is left unused.
-        let ExecutionPayload {
+        let ExecutionPayloadMerge {
             parent_hash,
             fee_recipient,
             state_root,
@@ -188,7 +188,7 @@ impl<T: EthSpec> From<ExecutionPayload<T>> for JsonExecutionPayloadV1<T> {
     }
 }
 
-impl<T: EthSpec> From<JsonExecutionPayloadV1<T>> for ExecutionPayload<T> {
+impl<T: EthSpec> From<JsonExecutionPayloadV1<T>> for ExecutionPayloadMerge<T> {
     fn from(e: JsonExecutionPayloadV1<T>) -> Self {
         // Use this verbose deconstruction pattern to ensure no field is left unused.
         let JsonExecutionPayloadV1 {
@@ -227,6 +227,424 @@ impl<T: EthSpec> From<JsonExecutionPayloadV1<T>> for ExecutionPayload<T> {
     }
 }
 
+impl<T: EthSpec> From<JsonExecutionPayloadV1<T>> for ExecutionPayload<T> {
+    fn from(e: JsonExecutionPayloadV1<T>) -> Self {
+        ExecutionPayload::Merge(e.into())
+    }
+}
+
+#[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(bound = "T: EthSpec", rename_all = "camelCase")]
+pub struct JsonExecutionPayloadV2<T: EthSpec> {
+    pub parent_hash: ExecutionBlockHash,
+    pub fee_recipient: Address,
+    pub state_root: Hash256,
+    pub receipts_root: Hash256,
+    #[serde(with = "serde_logs_bloom")]
+    pub logs_bloom: FixedVector<u8, T::BytesPerLogsBloom>,
+    pub prev_randao: Hash256,
+    #[serde(with = "eth2_serde_utils::u64_hex_be")]
+    pub block_number: u64,
+    #[serde(with = "eth2_serde_utils::u64_hex_be")]
+    pub gas_limit: u64,
+    #[serde(with = "eth2_serde_utils::u64_hex_be")]
+    pub gas_used: u64,
+    #[serde(with = "eth2_serde_utils::u64_hex_be")]
+    pub timestamp: u64,
+    #[serde(with = "ssz_types::serde_utils::hex_var_list")]
+    pub extra_data: VariableList<u8, T::MaxExtraDataBytes>,
+    pub base_fee_per_gas: Uint256,
+    pub block_hash: ExecutionBlockHash,
+    #[serde(with = "ssz_types::serde_utils::list_of_hex_var_list")]
+    pub transactions:
+        VariableList<Transaction<T::MaxBytesPerTransaction>, T::MaxTransactionsPerPayload>,
+    pub withdrawals: VariableList<JsonWithdrawal, T::MaxWithdrawalsPerPayload>,
+}
+
+impl<T: EthSpec> From<ExecutionPayloadCapella<T>> for JsonExecutionPayloadV2<T> {
+    fn from(e: ExecutionPayloadCapella<T>) -> Self {
+        // Use this verbose deconstruction pattern to ensure no field is left unused.
+        let ExecutionPayloadCapella {
+            parent_hash,
+            fee_recipient,
+            state_root,
+            receipts_root,
+            logs_bloom,
+            prev_randao,
+            block_number,
+            gas_limit,
+            gas_used,
+            timestamp,
+            extra_data,
+            base_fee_per_gas,
+            block_hash,
+            transactions,
+            withdrawals,
+        } = e;
+
+        Self {
+            parent_hash,
+            fee_recipient,
+            state_root,
+            receipts_root,
+            logs_bloom,
+            prev_randao,
+            block_number,
+            gas_limit,
+            gas_used,
+            timestamp,
+            extra_data,
+            base_fee_per_gas,
+            block_hash,
+            transactions,
+            withdrawals: withdrawals
+                .into_iter()
+                .map(Into::into)
+                .collect::<Vec<_>>()
+                .into(),
+        }
+    }
+}
+
+impl<T: EthSpec> From<JsonExecutionPayloadV2<T>> for ExecutionPayloadCapella<T> {
+    fn from(e: JsonExecutionPayloadV2<T>) -> Self {
+        // Use this verbose deconstruction pattern to ensure no field is left unused.
+        let JsonExecutionPayloadV2 {
+            parent_hash,
+            fee_recipient,
+            state_root,
+            receipts_root,
+            logs_bloom,
+            prev_randao,
+            block_number,
+            gas_limit,
+            gas_used,
+            timestamp,
+            extra_data,
+            base_fee_per_gas,
+            block_hash,
+            transactions,
+            withdrawals,
+        } = e;
+
+        Self {
+            parent_hash,
+            fee_recipient,
+            state_root,
+            receipts_root,
+            logs_bloom,
+            prev_randao,
+            block_number,
+            gas_limit,
+            gas_used,
+            timestamp,
+            extra_data,
+            base_fee_per_gas,
+            block_hash,
+            transactions,
+            withdrawals: withdrawals
+                .into_iter()
+                .map(Into::into)
+                .collect::<Vec<_>>()
+                .into(),
+        }
+    }
+}
+
+impl<T: EthSpec> From<JsonExecutionPayloadV2<T>> for ExecutionPayload<T> {
+    fn from(e: JsonExecutionPayloadV2<T>) -> Self {
+        ExecutionPayload::Capella(e.into())
+    }
+}
+
+#[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(bound = "T: EthSpec", rename_all = "camelCase")]
+pub struct JsonExecutionPayloadV3<T: EthSpec> {
+    pub parent_hash: ExecutionBlockHash,
+    pub fee_recipient: Address,
+    pub state_root: Hash256,
+    pub receipts_root: Hash256,
+    #[serde(with = "serde_logs_bloom")]
+    pub logs_bloom: FixedVector<u8, T::BytesPerLogsBloom>,
+    pub prev_randao: Hash256,
+    #[serde(with = "eth2_serde_utils::u64_hex_be")]
+    pub block_number: u64,
+    #[serde(with = "eth2_serde_utils::u64_hex_be")]
+    pub gas_limit: u64,
+    #[serde(with = "eth2_serde_utils::u64_hex_be")]
+    pub gas_used: u64,
+    #[serde(with = "eth2_serde_utils::u64_hex_be")]
+    pub timestamp: u64,
+    #[serde(with = "ssz_types::serde_utils::hex_var_list")]
+    pub extra_data: VariableList<u8, T::MaxExtraDataBytes>,
+    pub base_fee_per_gas: Uint256,
+    pub block_hash: ExecutionBlockHash,
+    #[serde(with = "ssz_types::serde_utils::list_of_hex_var_list")]
+    pub transactions:
+        VariableList<Transaction<T::MaxBytesPerTransaction>, T::MaxTransactionsPerPayload>,
+    pub withdrawals: VariableList<JsonWithdrawal, T::MaxWithdrawalsPerPayload>,
+    #[serde(with = "eth2_serde_utils::u64_hex_be")]
+    pub blob_gas_used: u64,
+    #[serde(with = "eth2_serde_utils::u64_hex_be")]
+    pub excess_blob_gas: u64,
+}
+
+impl<T: EthSpec> From<ExecutionPayloadDeneb<T>> for JsonExecutionPayloadV3<T> {
+    fn from(e: ExecutionPayloadDeneb<T>) -> Self {
+        // Use this verbose deconstruction pattern to ensure no field is left unused.
+        let ExecutionPayloadDeneb {
+            parent_hash,
+            fee_recipient,
+            state_root,
+            receipts_root,
+            logs_bloom,
+            prev_randao,
+            block_number,
+            gas_limit,
+            gas_used,
+            timestamp,
+            extra_data,
+            base_fee_per_gas,
+            block_hash,
+            transactions,
+            withdrawals,
+            blob_gas_used,
+            excess_blob_gas,
+        } = e;
+
+        Self {
+            parent_hash,
+            fee_recipient,
+            state_root,
+            receipts_root,
+            logs_bloom,
+            prev_randao,
+            block_number,
+            gas_limit,
+            gas_used,
+            timestamp,
+            extra_data,
+            base_fee_per_gas,
+            block_hash,
+            transactions,
+            withdrawals: withdrawals
+                .into_iter()
+                .map(Into::into)
+                .collect::<Vec<_>>()
+                .into(),
+            blob_gas_used,
+            excess_blob_gas,
+        }
+    }
+}
+
+impl<T: EthSpec> From<JsonExecutionPayloadV3<T>> for ExecutionPayloadDeneb<T> {
+    fn from(e: JsonExecutionPayloadV3<T>) -> Self {
+        // Use this verbose deconstruction pattern to ensure no field is left unused.
+        let JsonExecutionPayloadV3 {
+            parent_hash,
+            fee_recipient,
+            state_root,
+            receipts_root,
+            logs_bloom,
+            prev_randao,
+            block_number,
+            gas_limit,
+            gas_used,
+            timestamp,
+            extra_data,
+            base_fee_per_gas,
+            block_hash,
+            transactions,
+            withdrawals,
+            blob_gas_used,
+            excess_blob_gas,
+        } = e;
+
+        Self {
+            parent_hash,
+            fee_recipient,
+            state_root,
+            receipts_root,
+            logs_bloom,
+            prev_randao,
+            block_number,
+            gas_limit,
+            gas_used,
+            timestamp,
+            extra_data,
+            base_fee_per_gas,
+            block_hash,
+            transactions,
+            withdrawals: withdrawals
+                .into_iter()
+                .map(Into::into)
+                .collect::<Vec<_>>()
+                .into(),
+            blob_gas_used,
+            excess_blob_gas,
+        }
+    }
+}
+
+impl<T: EthSpec> From<JsonExecutionPayloadV3<T>> for ExecutionPayload<T> {
+    fn from(e: JsonExecutionPayloadV3<T>) -> Self {
+        ExecutionPayload::Deneb(e.into())
+    }
+}
+
+/// The response to `engine_getPayloadV2`.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "T: EthSpec", rename_all = "camelCase")]
+pub struct JsonGetPayloadResponseV2<T: EthSpec> {
+    pub execution_payload: JsonExecutionPayloadV2<T>,
+    pub block_value: Uint256,
+}
+
+/// The response to `engine_getPayloadV3`.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "T: EthSpec", rename_all = "camelCase")]
+pub struct JsonGetPayloadResponseV3<T: EthSpec> {
+    pub execution_payload: JsonExecutionPayloadV3<T>,
+    pub block_value: Uint256,
+    pub blobs_bundle: JsonBlobsBundleV1<T>,
+    #[serde(default)]
+    pub should_override_builder: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "T: EthSpec")]
+pub struct JsonBlobsBundleV1<T: EthSpec> {
+    pub commitments: KzgCommitments<T>,
+    pub proofs: VariableList<KzgProof, T::MaxBlobCommitmentsPerBlock>,
+    #[serde(with = "serde_blobs")]
+    pub blobs: VariableList<Blob<T>, T::MaxBlobCommitmentsPerBlock>,
+}
+
+impl<T: EthSpec> From<BlobsBundle<T>> for JsonBlobsBundleV1<T> {
+    fn from(b: BlobsBundle<T>) -> Self {
+        // Use this verbose deconstruction pattern to ensure no field is left unused.
+        let BlobsBundle {
+            commitments,
+            proofs,
+            blobs,
+        } = b;
+
+        Self {
+            commitments,
+            proofs,
+            blobs,
+        }
+    }
+}
+
+impl<T: EthSpec> From<JsonBlobsBundleV1<T>> for BlobsBundle<T> {
+    fn from(j: JsonBlobsBundleV1<T>) -> Self {
+        // Use this verbose deconstruction pattern to ensure no field is left unused.
+        let JsonBlobsBundleV1 {
+            commitments,
+            proofs,
+            blobs,
+        } = j;
+
+        Self {
+            commitments,
+            proofs,
+            blobs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonWithdrawal {
+    #[serde(with = "eth2_serde_utils::u64_hex_be")]
+    pub index: u64,
+    #[serde(with = "eth2_serde_utils::u64_hex_be")]
+    pub validator_index: u64,
+    pub address: Address,
+    #[serde(with = "eth2_serde_utils::u64_hex_be")]
+    pub amount: u64,
+}
+
+impl From<Withdrawal> for JsonWithdrawal {
+    fn from(w: Withdrawal) -> Self {
+        // Use this verbose deconstruction pattern to ensure no field is left unused.
+        let Withdrawal {
+            index,
+            validator_index,
+            address,
+            amount,
+        } = w;
+
+        Self {
+            index,
+            validator_index,
+            address,
+            amount,
+        }
+    }
+}
+
+impl From<JsonWithdrawal> for Withdrawal {
+    fn from(j: JsonWithdrawal) -> Self {
+        // Use this verbose deconstruction pattern to ensure no field is left unused.
+        let JsonWithdrawal {
+            index,
+            validator_index,
+            address,
+            amount,
+        } = j;
+
+        Self {
+            index,
+            validator_index,
+            address,
+            amount,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "T: EthSpec", rename_all = "camelCase")]
+pub struct JsonExecutionPayloadBodyV1<T: EthSpec> {
+    #[serde(with = "ssz_types::serde_utils::list_of_hex_var_list")]
+    pub transactions: Transactions<T>,
+    /// `null` for payloads from before Capella.
+    #[serde(default)]
+    pub withdrawals: Option<VariableList<JsonWithdrawal, T::MaxWithdrawalsPerPayload>>,
+}
+
+impl<T: EthSpec> From<ExecutionPayloadBodyV1<T>> for JsonExecutionPayloadBodyV1<T> {
+    fn from(b: ExecutionPayloadBodyV1<T>) -> Self {
+        Self {
+            transactions: b.transactions,
+            withdrawals: b.withdrawals.map(|withdrawals| {
+                withdrawals
+                    .into_iter()
+                    .map(Into::into)
+                    .collect::<Vec<_>>()
+                    .into()
+            }),
+        }
+    }
+}
+
+impl<T: EthSpec> From<JsonExecutionPayloadBodyV1<T>> for ExecutionPayloadBodyV1<T> {
+    fn from(b: JsonExecutionPayloadBodyV1<T>) -> Self {
+        Self {
+            transactions: b.transactions,
+            withdrawals: b.withdrawals.map(|withdrawals| {
+                withdrawals
+                    .into_iter()
+                    .map(Into::into)
+                    .collect::<Vec<_>>()
+                    .into()
+            }),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct JsonPayloadAttributesV1 {
@@ -236,6 +654,7 @@ pub struct JsonPayloadAttributesV1 {
     pub suggested_fee_recipient: Address,
 }
 
+/// Converts attributes without withdrawals, i.e. for a pre-Capella payload.
 impl From<PayloadAttributes> for JsonPayloadAttributesV1 {
     fn from(p: PayloadAttributes) -> Self {
         // Use this verbose deconstruction pattern to ensure no field is left unused.
@@ -243,6 +662,8 @@ impl From<PayloadAttributes> for JsonPayloadAttributesV1 {
             timestamp,
             prev_randao,
             suggested_fee_recipient,
+            withdrawals: _,
+            parent_beacon_block_root: _,
         } = p;
 
         Self {
@@ -266,6 +687,119 @@ impl From<JsonPayloadAttributesV1> for PayloadAttributes {
             timestamp,
             prev_randao,
             suggested_fee_recipient,
+            withdrawals: None,
+            parent_beacon_block_root: None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonPayloadAttributesV2 {
+    #[serde(with = "eth2_serde_utils::u64_hex_be")]
+    pub timestamp: u64,
+    pub prev_randao: Hash256,
+    pub suggested_fee_recipient: Address,
+    pub withdrawals: Vec<JsonWithdrawal>,
+}
+
+impl From<PayloadAttributes> for JsonPayloadAttributesV2 {
+    fn from(p: PayloadAttributes) -> Self {
+        // Use this verbose deconstruction pattern to ensure no field is left unused.
+        let PayloadAttributes {
+            timestamp,
+            prev_randao,
+            suggested_fee_recipient,
+            withdrawals,
+            parent_beacon_block_root: _,
+        } = p;
+
+        Self {
+            timestamp,
+            prev_randao,
+            suggested_fee_recipient,
+            withdrawals: withdrawals
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        }
+    }
+}
+
+impl From<JsonPayloadAttributesV2> for PayloadAttributes {
+    fn from(j: JsonPayloadAttributesV2) -> Self {
+        // Use this verbose deconstruction pattern to ensure no field is left unused.
+        let JsonPayloadAttributesV2 {
+            timestamp,
+            prev_randao,
+            suggested_fee_recipient,
+            withdrawals,
+        } = j;
+
+        Self {
+            timestamp,
+            prev_randao,
+            suggested_fee_recipient,
+            withdrawals: Some(withdrawals.into_iter().map(Into::into).collect()),
+            parent_beacon_block_root: None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonPayloadAttributesV3 {
+    #[serde(with = "eth2_serde_utils::u64_hex_be")]
+    pub timestamp: u64,
+    pub prev_randao: Hash256,
+    pub suggested_fee_recipient: Address,
+    pub withdrawals: Vec<JsonWithdrawal>,
+    pub parent_beacon_block_root: Hash256,
+}
+
+impl From<PayloadAttributes> for JsonPayloadAttributesV3 {
+    fn from(p: PayloadAttributes) -> Self {
+        // Use this verbose deconstruction pattern to ensure no field is left unused.
+        let PayloadAttributes {
+            timestamp,
+            prev_randao,
+            suggested_fee_recipient,
+            withdrawals,
+            parent_beacon_block_root,
+        } = p;
+
+        Self {
+            timestamp,
+            prev_randao,
+            suggested_fee_recipient,
+            withdrawals: withdrawals
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            parent_beacon_block_root: parent_beacon_block_root.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<JsonPayloadAttributesV3> for PayloadAttributes {
+    fn from(j: JsonPayloadAttributesV3) -> Self {
+        // Use this verbose deconstruction pattern to ensure no field is left unused.
+        let JsonPayloadAttributesV3 {
+            timestamp,
+            prev_randao,
+            suggested_fee_recipient,
+            withdrawals,
+            parent_beacon_block_root,
+        } = j;
+
+        Self {
+            timestamp,
+            prev_randao,
+            suggested_fee_recipient,
+            withdrawals: Some(withdrawals.into_iter().map(Into::into).collect()),
+            parent_beacon_block_root: Some(parent_beacon_block_root),
         }
     }
 }
@@ -312,7 +846,7 @@ impl From<JsonForkChoiceStateV1> for ForkChoiceState {
     }
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum JsonPayloadStatusV1Status {
     Valid,
@@ -520,3 +1054,74 @@ pub mod serde_logs_bloom {
             .map_err(|e| serde::de::Error::custom(format!("invalid logs bloom: {:?}", e)))
     }
 }
+
+/// Serializes a list of blobs as a list of 0x-prefixed hex strings.
+pub mod serde_blobs {
+    use super::*;
+    use serde::ser::SerializeSeq;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S, U, N>(
+        blobs: &VariableList<FixedVector<u8, U>, N>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        U: Unsigned,
+        N: Unsigned,
+    {
+        let mut seq = serializer.serialize_seq(Some(blobs.len()))?;
+        for blob in blobs.iter() {
+            seq.serialize_element(&eth2_serde_utils::hex::encode(&blob[..]))?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D, U, N>(
+        deserializer: D,
+    ) -> Result<VariableList<FixedVector<u8, U>, N>, D::Error>
+    where
+        D: Deserializer<'de>,
+        U: Unsigned,
+        N: Unsigned,
+    {
+        let blobs = Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|hex_string| {
+                let bytes =
+                    eth2_serde_utils::hex::decode(hex_string).map_err(serde::de::Error::custom)?;
+                FixedVector::new(bytes)
+                    .map_err(|e| serde::de::Error::custom(format!("invalid blob: {:?}", e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        VariableList::new(blobs)
+            .map_err(|e| serde::de::Error::custom(format!("too many blobs: {:?}", e)))
+    }
+}
+
+/// Serializes an optional `u64` as a 0x-prefixed big-endian hex string.
+pub mod serde_optional_u64_hex_be {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    struct Wrapper(#[serde(with = "eth2_serde_utils::u64_hex_be")] u64);
+
+    pub fn serialize<S>(num: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match num {
+            Some(num) => eth2_serde_utils::u64_hex_be::serialize(num, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|Wrapper(num)| num))
+    }
+}
@@ -11,7 +11,7 @@ use lru::LruCache;
 use slog::{crit, debug, info, warn, Logger};
 use std::future::Future;
 use tokio::sync::{Mutex, RwLock};
-use types::{Address, ExecutionBlockHash, Hash256};
+use types::{Address, ExecutionBlockHash, Hash256, Withdrawal};
 
 /// The number of payload IDs that will be stored for each `Engine`.
 ///
@@ -56,6 +56,8 @@ struct PayloadIdCacheKey {
     pub timestamp: u64,
     pub prev_randao: Hash256,
     pub suggested_fee_recipient: Address,
+    pub withdrawals: Option<Vec<Withdrawal>>,
+    pub parent_beacon_block_root: Option<Hash256>,
 }
 
 /// An execution engine.
@@ -83,6 +85,8 @@ impl<T> Engine<T> {
         timestamp: u64,
         prev_randao: Hash256,
         suggested_fee_recipient: Address,
+        withdrawals: Option<Vec<Withdrawal>>,
+        parent_beacon_block_root: Option<Hash256>,
     ) -> Option<PayloadId> {
         self.payload_id_cache
             .lock()
@@ -92,6 +96,8 @@ impl<T> Engine<T> {
                 timestamp,
                 prev_randao,
                 suggested_fee_recipient,
+                withdrawals,
+                parent_beacon_block_root,
             })
             .cloned()
     }
@@ -105,14 +111,32 @@ impl Builder for Engine<EngineApi> {
         payload_attributes: Option<PayloadAttributes>,
         log: &Logger,
     ) -> Result<ForkchoiceUpdatedResponse, EngineApiError> {
-        let response = self
-            .api
-            .forkchoice_updated_v1(forkchoice_state, payload_attributes)
-            .await?;
+        // Attributes with a parent beacon block root are only understood by
+        // `engine_forkchoiceUpdatedV3`, and those with withdrawals by `engine_forkchoiceUpdatedV2`.
+        let response = if payload_attributes
+            .as_ref()
+            .map_or(false, |pa| pa.parent_beacon_block_root.is_some())
+        {
+            self.api
+                .forkchoice_updated_v3(forkchoice_state, payload_attributes.clone())
+                .await?
+        } else if payload_attributes
+            .as_ref()
+            .map_or(false, |pa| pa.withdrawals.is_some())
+        {
+            self.api
+                .forkchoice_updated_v2(forkchoice_state, payload_attributes.clone())
+                .await?
+        } else {
+            self.api
+                .forkchoice_updated_v1(forkchoice_state, payload_attributes.clone())
+                .await?
+        };
 
         if let Some(payload_id) = response.payload_id {
-            if let Some(key) =
-                payload_attributes.map(|pa| PayloadIdCacheKey::new(&forkchoice_state, &pa))
+            if let Some(key) = payload_attributes
+                .as_ref()
+                .map(|pa| PayloadIdCacheKey::new(&forkchoice_state, pa))
             {
                 self.payload_id_cache.lock().await.put(key, payload_id);
             } else {
@@ -139,7 +163,7 @@ impl Builder for Engine<BuilderApi> {
         let payload_attributes = pa.ok_or(EngineApiError::InvalidBuilderQuery)?;
         let response = self
             .api
-            .forkchoice_updated_v1(forkchoice_state, Some(payload_attributes))
+            .forkchoice_updated_v1(forkchoice_state, Some(payload_attributes.clone()))
             .await?;
 
         if let Some(payload_id) = response.payload_id {
@@ -239,6 +263,19 @@ impl Engines {
         false
     }
 
+    /// Returns `true` if every engine is offline or rejecting our authentication.
+    pub async fn all_offline(&self) -> bool {
+        for engine in &self.engines {
+            if matches!(
+                *engine.state.read().await,
+                EngineState::Synced | EngineState::Syncing
+            ) {
+                return false;
+            }
+        }
+        true
+    }
+
     /// Run the `EngineApi::upcheck` function on all nodes which are currently offline.
     ///
     /// This can be used to try and recover any offline nodes.
@@ -530,6 +567,8 @@ impl PayloadIdCacheKey {
             timestamp: attributes.timestamp,
             prev_randao: attributes.prev_randao,
             suggested_fee_recipient: attributes.suggested_fee_recipient,
+            withdrawals: attributes.withdrawals.clone(),
+            parent_beacon_block_root: attributes.parent_beacon_block_root,
         }
     }
 }
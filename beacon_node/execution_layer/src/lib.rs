@@ -7,7 +7,7 @@
 use crate::engine_api::Builder;
 use crate::engines::Builders;
 use auth::{Auth, JwtKey};
-use engine_api::Error as ApiError;
+pub use engine_api::Error as ApiError;
 pub use engine_api::*;
 pub use engine_api::{http, http::HttpJsonRpc};
 pub use engines::ForkChoiceState;
@@ -19,8 +19,7 @@ use sensitive_url::SensitiveUrl;
 use serde::{Deserialize, Serialize};
 use slog::{crit, debug, error, info, trace, warn, Logger};
 use slot_clock::SlotClock;
-use std::collections::HashMap;
-use std::convert::TryInto;
+use std::collections::{HashMap, HashSet};
 use std::future::Future;
 use std::io::Write;
 use std::path::PathBuf;
@@ -32,8 +31,8 @@ use tokio::{
     time::{sleep, sleep_until, Instant},
 };
 use types::{
-    BlindedPayload, BlockType, ChainSpec, Epoch, ExecPayload, ExecutionBlockHash,
-    ProposerPreparationData, SignedBeaconBlock, Slot,
+    AbstractExecPayload, BeaconStateError, BlindedPayload, BlockType, ChainSpec, Epoch,
+    ExecutionBlockHash, ForkName, ProposerPreparationData, SignedBeaconBlock, Slot,
 };
 
 mod engine_api;
@@ -99,6 +98,25 @@ pub struct Proposer {
     payload_attributes: PayloadAttributes,
 }
 
+/// A payload returned by `get_payload`, along with the blobs bundle for its blob transactions.
+///
+/// The `blobs_bundle` is only `Some` for full payloads from Deneb onwards.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockProposalContents<T: EthSpec, Payload> {
+    pub payload: Payload,
+    pub blobs_bundle: Option<BlobsBundle<T>>,
+}
+
+impl<T: EthSpec, Payload: AbstractExecPayload<T>> BlockProposalContents<T, Payload> {
+    /// The contents of a block at `fork_name` that is produced prior to the merge transition.
+    pub fn default_at_fork(fork_name: ForkName) -> Result<Self, BeaconStateError> {
+        Ok(Self {
+            payload: Payload::default_at_fork(fork_name)?,
+            blobs_bundle: None,
+        })
+    }
+}
+
 struct Inner {
     engines: Engines,
     builders: Builders,
@@ -458,6 +476,37 @@ impl ExecutionLayer {
         self.engines().any_synced().await
     }
 
+    /// Returns `true` if none of the engines are reachable, regardless of whether they're synced.
+    pub async fn is_offline(&self) -> bool {
+        self.engines().all_offline().await
+    }
+
+    /// Returns the engine API methods supported by the first engine to respond, or `None` if the
+    /// engine predates `engine_exchangeCapabilities`.
+    pub async fn get_engine_capabilities(&self) -> Result<Option<HashSet<String>>, Error> {
+        match self
+            .engines()
+            .first_success(|engine| engine.api.exchange_capabilities())
+            .await
+        {
+            Ok(capabilities) => Ok(Some(capabilities)),
+            Err(errors)
+                if errors.iter().any(|error| {
+                    matches!(
+                        error,
+                        EngineError::Api {
+                            error: ApiError::ServerMessage { code, .. },
+                            ..
+                        } if *code == http::METHOD_NOT_FOUND_CODE
+                    )
+                }) =>
+            {
+                Ok(None)
+            }
+            Err(errors) => Err(Error::EngineErrors(errors)),
+        }
+    }
+
     /// Updates the proposer preparation data provided by validators
     pub fn update_proposer_preparation_blocking(
         &self,
@@ -569,7 +618,8 @@ impl ExecutionLayer {
         }
     }
 
-    /// Maps to the `engine_getPayload` JSON-RPC call.
+    /// Maps to the `engine_getPayload` JSON-RPC call, or to `builder_getPayloadHeader` when
+    /// producing a blinded payload.
     ///
     /// However, it will attempt to call `self.prepare_payload` if it cannot find an existing
     /// payload id for the given parameters.
@@ -578,141 +628,185 @@ impl ExecutionLayer {
     ///
     /// The result will be returned from the first node that returns successfully. No more nodes
     /// will be contacted.
-    pub async fn get_payload<T: EthSpec, Payload: ExecPayload<T>>(
+    pub async fn get_payload<T: EthSpec, Payload: AbstractExecPayload<T>>(
         &self,
         parent_hash: ExecutionBlockHash,
         timestamp: u64,
         prev_randao: Hash256,
         finalized_block_hash: ExecutionBlockHash,
         proposer_index: u64,
-    ) -> Result<Payload, Error> {
+        withdrawals: Option<Vec<Withdrawal>>,
+        parent_beacon_block_root: Option<Hash256>,
+    ) -> Result<BlockProposalContents<T, Payload>, Error> {
         let _timer = metrics::start_timer_vec(
             &metrics::EXECUTION_LAYER_REQUEST_TIMES,
             &[metrics::GET_PAYLOAD],
         );
 
-        let suggested_fee_recipient = self.get_suggested_fee_recipient(proposer_index).await;
+        let payload_attributes = PayloadAttributes {
+            timestamp,
+            prev_randao,
+            suggested_fee_recipient: self.get_suggested_fee_recipient(proposer_index).await,
+            withdrawals,
+            parent_beacon_block_root,
+        };
 
         match Payload::block_type() {
             BlockType::Blinded => {
-                debug!(
-                    self.log(),
-                    "Issuing builder_getPayloadHeader";
-                    "suggested_fee_recipient" => ?suggested_fee_recipient,
-                    "prev_randao" => ?prev_randao,
-                    "timestamp" => timestamp,
-                    "parent_hash" => ?parent_hash,
-                );
-                self.builders()
-                    .first_success_without_retry(|engine| async move {
-                        let payload_id = engine
-                            .get_payload_id(
-                                parent_hash,
-                                timestamp,
-                                prev_randao,
-                                suggested_fee_recipient,
-                            )
-                            .await
-                            .ok_or(ApiError::MissingPayloadId {
-                                parent_hash,
-                                timestamp,
-                                prev_randao,
-                                suggested_fee_recipient,
-                            })?;
-                        engine
-                            .api
-                            .get_payload_header_v1::<T>(payload_id)
-                            .await?
-                            .try_into()
-                            .map_err(|_| ApiError::PayloadConversionLogicFlaw)
-                    })
-                    .await
-                    .map_err(Error::EngineErrors)
+                let header = self
+                    .get_payload_header::<T>(parent_hash, &payload_attributes)
+                    .await?;
+                let payload = Payload::from_execution_payload_header(header)
+                    .ok_or(Error::ApiError(ApiError::PayloadConversionLogicFlaw))?;
+
+                Ok(BlockProposalContents {
+                    payload,
+                    blobs_bundle: None,
+                })
             }
             BlockType::Full => {
-                debug!(
-                    self.log(),
-                    "Issuing engine_getPayload";
-                    "suggested_fee_recipient" => ?suggested_fee_recipient,
-                    "prev_randao" => ?prev_randao,
-                    "timestamp" => timestamp,
-                    "parent_hash" => ?parent_hash,
-                );
-                self.engines()
-                    .first_success(|engine| async move {
-                        let payload_id = if let Some(id) = engine
-                            .get_payload_id(
-                                parent_hash,
-                                timestamp,
-                                prev_randao,
-                                suggested_fee_recipient,
-                            )
-                            .await
-                        {
-                            // The payload id has been cached for this engine.
-                            metrics::inc_counter_vec(
-                                &metrics::EXECUTION_LAYER_PRE_PREPARED_PAYLOAD_ID,
-                                &[metrics::HIT],
-                            );
-                            id
-                        } else {
-                            // The payload id has *not* been cached for this engine. Trigger an artificial
-                            // fork choice update to retrieve a payload ID.
-                            //
-                            // TODO(merge): a better algorithm might try to favour a node that already had a
-                            // cached payload id, since a payload that has had more time to produce is
-                            // likely to be more profitable.
-                            metrics::inc_counter_vec(
-                                &metrics::EXECUTION_LAYER_PRE_PREPARED_PAYLOAD_ID,
-                                &[metrics::MISS],
-                            );
-                            let fork_choice_state = ForkChoiceState {
-                                head_block_hash: parent_hash,
-                                safe_block_hash: parent_hash,
-                                finalized_block_hash,
-                            };
-                            let payload_attributes = PayloadAttributes {
-                                timestamp,
-                                prev_randao,
-                                suggested_fee_recipient,
-                            };
-
-                            let response = engine
-                                .notify_forkchoice_updated(
-                                    fork_choice_state,
-                                    Some(payload_attributes),
-                                    self.log(),
-                                )
-                                .await?;
-
-                            match response.payload_id {
-                                Some(payload_id) => payload_id,
-                                None => {
-                                    error!(
-                                        self.log(),
-                                        "Exec engine unable to produce payload";
-                                        "msg" => "No payload ID, the engine is likely syncing. \
-                                                  This has the potential to cause a missed block \
-                                                  proposal.",
-                                        "status" => ?response.payload_status
-                                    );
-                                    return Err(ApiError::PayloadIdUnavailable);
-                                }
-                            }
-                        };
-
-                        engine
-                            .api
-                            .get_payload_v1::<T>(payload_id)
-                            .await
-                            .map(Into::into)
-                    })
-                    .await
-                    .map_err(Error::EngineErrors)
+                let (payload, blobs_bundle) = self
+                    .get_full_payload::<T>(parent_hash, &payload_attributes, finalized_block_hash)
+                    .await?;
+
+                Ok(BlockProposalContents {
+                    payload: Payload::from_execution_payload(payload),
+                    blobs_bundle,
+                })
             }
         }
     }
 
+    /// Maps to the `builder_getPayloadHeader` JSON-RPC call.
+    async fn get_payload_header<T: EthSpec>(
+        &self,
+        parent_hash: ExecutionBlockHash,
+        payload_attributes: &PayloadAttributes,
+    ) -> Result<ExecutionPayloadHeader<T>, Error> {
+        debug!(
+            self.log(),
+            "Issuing builder_getPayloadHeader";
+            "suggested_fee_recipient" => ?payload_attributes.suggested_fee_recipient,
+            "prev_randao" => ?payload_attributes.prev_randao,
+            "timestamp" => payload_attributes.timestamp,
+            "parent_hash" => ?parent_hash,
+        );
+        self.builders()
+            .first_success_without_retry(|engine| async move {
+                let payload_id = engine
+                    .get_payload_id(
+                        parent_hash,
+                        payload_attributes.timestamp,
+                        payload_attributes.prev_randao,
+                        payload_attributes.suggested_fee_recipient,
+                        payload_attributes.withdrawals.clone(),
+                        payload_attributes.parent_beacon_block_root,
+                    )
+                    .await
+                    .ok_or(ApiError::MissingPayloadId {
+                        parent_hash,
+                        timestamp: payload_attributes.timestamp,
+                        prev_randao: payload_attributes.prev_randao,
+                        suggested_fee_recipient: payload_attributes.suggested_fee_recipient,
+                    })?;
+                engine.api.get_payload_header_v1::<T>(payload_id).await
+            })
+            .await
+            .map_err(Error::EngineErrors)
+    }
+
+    /// Maps to the `engine_getPayload` JSON-RPC call, returning the blobs bundle from Deneb
+    /// onwards.
+    async fn get_full_payload<T: EthSpec>(
+        &self,
+        parent_hash: ExecutionBlockHash,
+        payload_attributes: &PayloadAttributes,
+        finalized_block_hash: ExecutionBlockHash,
+    ) -> Result<(ExecutionPayload<T>, Option<BlobsBundle<T>>), Error> {
+        debug!(
+            self.log(),
+            "Issuing engine_getPayload";
+            "suggested_fee_recipient" => ?payload_attributes.suggested_fee_recipient,
+            "prev_randao" => ?payload_attributes.prev_randao,
+            "timestamp" => payload_attributes.timestamp,
+            "parent_hash" => ?parent_hash,
+        );
+        self.engines()
+            .first_success(|engine| async move {
+                let payload_id = if let Some(id) = engine
+                    .get_payload_id(
+                        parent_hash,
+                        payload_attributes.timestamp,
+                        payload_attributes.prev_randao,
+                        payload_attributes.suggested_fee_recipient,
+                        payload_attributes.withdrawals.clone(),
+                        payload_attributes.parent_beacon_block_root,
+                    )
+                    .await
+                {
+                    // The payload id has been cached for this engine.
+                    metrics::inc_counter_vec(
+                        &metrics::EXECUTION_LAYER_PRE_PREPARED_PAYLOAD_ID,
+                        &[metrics::HIT],
+                    );
+                    id
+                } else {
+                    // The payload id has *not* been cached for this engine. Trigger an artificial
+                    // fork choice update to retrieve a payload ID.
+                    //
+                    // TODO(merge): a better algorithm might try to favour a node that already had a
+                    // cached payload id, since a payload that has had more time to produce is
+                    // likely to be more profitable.
+                    metrics::inc_counter_vec(
+                        &metrics::EXECUTION_LAYER_PRE_PREPARED_PAYLOAD_ID,
+                        &[metrics::MISS],
+                    );
+                    let fork_choice_state = ForkChoiceState {
+                        head_block_hash: parent_hash,
+                        safe_block_hash: parent_hash,
+                        finalized_block_hash,
+                    };
+
+                    let response = engine
+                        .notify_forkchoice_updated(
+                            fork_choice_state,
+                            Some(payload_attributes.clone()),
+                            self.log(),
+                        )
+                        .await?;
+
+                    match response.payload_id {
+                        Some(payload_id) => payload_id,
+                        None => {
+                            error!(
+                                self.log(),
+                                "Exec engine unable to produce payload";
+                                "msg" => "No payload ID, the engine is likely syncing. \
+                                          This has the potential to cause a missed block \
+                                          proposal.",
+                                "status" => ?response.payload_status
+                            );
+                            return Err(ApiError::PayloadIdUnavailable);
+                        }
+                    }
+                };
+
+                // Payloads with blobs can only be retrieved with `engine_getPayloadV3`, and
+                // those with withdrawals with `engine_getPayloadV2`.
+                if payload_attributes.parent_beacon_block_root.is_some() {
+                    let (payload, blobs_bundle) =
+                        engine.api.get_payload_v3::<T>(payload_id).await?;
+                    Ok((payload, Some(blobs_bundle)))
+                } else if payload_attributes.withdrawals.is_some() {
+                    Ok((engine.api.get_payload_v2::<T>(payload_id).await?, None))
+                } else {
+                    Ok((engine.api.get_payload_v1::<T>(payload_id).await?, None))
+                }
+            })
+            .await
+            .map_err(Error::EngineErrors)
+    }
+
     /// Maps to the `engine_newPayload` JSON-RPC call.
     ///
     /// ## Fallback Behaviour
@@ -726,9 +820,14 @@ impl ExecutionLayer {
     /// - Invalid, if any nodes return invalid.
     /// - Syncing, if any nodes return syncing.
     /// - An error, if all nodes return an error.
+    ///
+    /// The `versioned_hashes` of the block's blob KZG commitments and the `parent_beacon_block_root`
+    /// are only sent to the execution engine from Deneb onwards.
     pub async fn notify_new_payload<T: EthSpec>(
         &self,
         execution_payload: &ExecutionPayload<T>,
+        versioned_hashes: Vec<VersionedHash>,
+        parent_beacon_block_root: Hash256,
     ) -> Result<PayloadStatus, Error> {
         let _timer = metrics::start_timer_vec(
             &metrics::EXECUTION_LAYER_REQUEST_TIMES,
@@ -738,18 +837,24 @@ impl ExecutionLayer {
         trace!(
             self.log(),
             "Issuing engine_newPayload";
-            "parent_hash" => ?execution_payload.parent_hash,
-            "block_hash" => ?execution_payload.block_hash,
-            "block_number" => execution_payload.block_number,
+            "parent_hash" => ?execution_payload.parent_hash(),
+            "block_hash" => ?execution_payload.block_hash(),
+            "block_number" => execution_payload.block_number(),
         );
 
         let broadcast_results = self
             .engines()
-            .broadcast(|engine| engine.api.new_payload_v1(execution_payload.clone()))
+            .broadcast(|engine| {
+                engine.api.new_payload(
+                    execution_payload.clone(),
+                    versioned_hashes.clone(),
+                    parent_beacon_block_root,
+                )
+            })
             .await;
 
         process_multiple_payload_statuses(
-            execution_payload.block_hash,
+            execution_payload.block_hash(),
             broadcast_results.into_iter(),
             self.log(),
         )
@@ -849,7 +954,7 @@ impl ExecutionLayer {
         let payload_attributes = self.payload_attributes(next_slot, head_block_root).await;
 
         // Compute the "lookahead", the time between when the payload will be produced and now.
-        if let Some(payload_attributes) = payload_attributes {
+        if let Some(payload_attributes) = &payload_attributes {
             if let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) {
                 let timestamp = Duration::from_secs(payload_attributes.timestamp);
                 if let Some(lookahead) = timestamp.checked_sub(now) {
@@ -880,11 +985,16 @@ impl ExecutionLayer {
             .set_latest_forkchoice_state(forkchoice_state)
             .await;
 
+        let payload_attributes = &payload_attributes;
         let broadcast_results = self
             .engines()
             .broadcast(|engine| async move {
                 engine
-                    .notify_forkchoice_updated(forkchoice_state, payload_attributes, self.log())
+                    .notify_forkchoice_updated(
+                        forkchoice_state,
+                        payload_attributes.clone(),
+                        self.log(),
+                    )
                     .await
             })
             .await;
@@ -894,7 +1004,11 @@ impl ExecutionLayer {
             self.builders()
                 .broadcast_without_retry(|engine| async move {
                     engine
-                        .notify_forkchoice_updated(forkchoice_state, payload_attributes, self.log())
+                        .notify_forkchoice_updated(
+                            forkchoice_state,
+                            payload_attributes.clone(),
+                            self.log(),
+                        )
                         .await
                 })
                 .await
@@ -1235,22 +1349,97 @@ impl ExecutionLayer {
         )
         .map_err(ApiError::DeserializeTransactions)?;
 
-        Ok(Some(ExecutionPayload {
-            parent_hash: block.parent_hash,
-            fee_recipient: block.fee_recipient,
-            state_root: block.state_root,
-            receipts_root: block.receipts_root,
-            logs_bloom: block.logs_bloom,
-            prev_randao: block.prev_randao,
-            block_number: block.block_number,
-            gas_limit: block.gas_limit,
-            gas_used: block.gas_used,
-            timestamp: block.timestamp,
-            extra_data: block.extra_data,
-            base_fee_per_gas: block.base_fee_per_gas,
-            block_hash: block.block_hash,
-            transactions,
-        }))
+        // Only blocks from Capella onwards have withdrawals, and only those from Deneb onwards have
+        // blob gas fields.
+        let payload = match (
+            block.withdrawals,
+            block.blob_gas_used,
+            block.excess_blob_gas,
+        ) {
+            (Some(withdrawals), Some(blob_gas_used), Some(excess_blob_gas)) => {
+                ExecutionPayload::Deneb(ExecutionPayloadDeneb {
+                    parent_hash: block.parent_hash,
+                    fee_recipient: block.fee_recipient,
+                    state_root: block.state_root,
+                    receipts_root: block.receipts_root,
+                    logs_bloom: block.logs_bloom,
+                    prev_randao: block.prev_randao,
+                    block_number: block.block_number,
+                    gas_limit: block.gas_limit,
+                    gas_used: block.gas_used,
+                    timestamp: block.timestamp,
+                    extra_data: block.extra_data,
+                    base_fee_per_gas: block.base_fee_per_gas,
+                    block_hash: block.block_hash,
+                    transactions,
+                    withdrawals: VariableList::new(
+                        withdrawals.into_iter().map(Into::into).collect(),
+                    )
+                    .map_err(ApiError::DeserializeWithdrawals)?,
+                    blob_gas_used,
+                    excess_blob_gas,
+                })
+            }
+            (Some(withdrawals), _, _) => ExecutionPayload::Capella(ExecutionPayloadCapella {
+                parent_hash: block.parent_hash,
+                fee_recipient: block.fee_recipient,
+                state_root: block.state_root,
+                receipts_root: block.receipts_root,
+                logs_bloom: block.logs_bloom,
+                prev_randao: block.prev_randao,
+                block_number: block.block_number,
+                gas_limit: block.gas_limit,
+                gas_used: block.gas_used,
+                timestamp: block.timestamp,
+                extra_data: block.extra_data,
+                base_fee_per_gas: block.base_fee_per_gas,
+                block_hash: block.block_hash,
+                transactions,
+                withdrawals: VariableList::new(withdrawals.into_iter().map(Into::into).collect())
+                    .map_err(ApiError::DeserializeWithdrawals)?,
+            }),
+            (None, _, _) => ExecutionPayload::Merge(ExecutionPayloadMerge {
+                parent_hash: block.parent_hash,
+                fee_recipient: block.fee_recipient,
+                state_root: block.state_root,
+                receipts_root: block.receipts_root,
+                logs_bloom: block.logs_bloom,
+                prev_randao: block.prev_randao,
+                block_number: block.block_number,
+                gas_limit: block.gas_limit,
+                gas_used: block.gas_used,
+                timestamp: block.timestamp,
+                extra_data: block.extra_data,
+                base_fee_per_gas: block.base_fee_per_gas,
+                block_hash: block.block_hash,
+                transactions,
+            }),
+        };
+
+        Ok(Some(payload))
+    }
+
+    /// Fetch the bodies of the payloads with block numbers from `start` to `start + count - 1`.
+    ///
+    /// The returned bodies are in block number order, with `None` for any payload that the EE
+    /// doesn't have. The EE may return fewer than `count` bodies if the range extends beyond its
+    /// latest block.
+    pub async fn get_payload_bodies_by_range<T: EthSpec>(
+        &self,
+        start: u64,
+        count: u64,
+    ) -> Result<Vec<Option<ExecutionPayloadBodyV1<T>>>, Error> {
+        let _timer = metrics::start_timer(&metrics::EXECUTION_LAYER_GET_PAYLOAD_BODIES_BY_RANGE);
+
+        self.engines()
+            .first_success(|engine| async move {
+                engine
+                    .api
+                    .get_payload_bodies_by_range_v1(start, count)
+                    .await
+            })
+            .await
+            .map_err(Error::EngineErrors)
     }
 
     pub async fn propose_blinded_beacon_block<T: EthSpec>(
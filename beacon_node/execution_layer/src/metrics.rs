@@ -35,4 +35,8 @@ lazy_static::lazy_static! {
         "execution_layer_get_payload_by_block_hash_time",
         "Time to reconstruct a payload from the EE using eth_getBlockByHash"
     );
+    pub static ref EXECUTION_LAYER_GET_PAYLOAD_BODIES_BY_RANGE: Result<Histogram> = try_create_histogram(
+        "execution_layer_get_payload_bodies_by_range_time",
+        "Time to fetch payload bodies from the EE using engine_getPayloadBodiesByRangeV1"
+    );
 }
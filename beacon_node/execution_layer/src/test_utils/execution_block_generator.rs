@@ -9,7 +9,10 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tree_hash::TreeHash;
 use tree_hash_derive::TreeHash;
-use types::{EthSpec, ExecutionBlockHash, ExecutionPayload, Hash256, Uint256};
+use types::{
+    EthSpec, ExecutionBlockHash, ExecutionPayload, ExecutionPayloadCapella, ExecutionPayloadDeneb,
+    ExecutionPayloadMerge, Hash256, Uint256,
+};
 
 const GAS_LIMIT: u64 = 16384;
 const GAS_USED: u64 = GAS_LIMIT - 1;
@@ -25,21 +28,21 @@ impl<T: EthSpec> Block<T> {
     pub fn block_number(&self) -> u64 {
         match self {
             Block::PoW(block) => block.block_number,
-            Block::PoS(payload) => payload.block_number,
+            Block::PoS(payload) => payload.block_number(),
         }
     }
 
     pub fn parent_hash(&self) -> ExecutionBlockHash {
         match self {
             Block::PoW(block) => block.parent_hash,
-            Block::PoS(payload) => payload.parent_hash,
+            Block::PoS(payload) => payload.parent_hash(),
         }
     }
 
     pub fn block_hash(&self) -> ExecutionBlockHash {
         match self {
             Block::PoW(block) => block.block_hash,
-            Block::PoS(payload) => payload.block_hash,
+            Block::PoS(payload) => payload.block_hash(),
         }
     }
 
@@ -59,9 +62,9 @@ impl<T: EthSpec> Block<T> {
                 total_difficulty: block.total_difficulty,
             },
             Block::PoS(payload) => ExecutionBlock {
-                block_hash: payload.block_hash,
-                block_number: payload.block_number,
-                parent_hash: payload.parent_hash,
+                block_hash: payload.block_hash(),
+                block_number: payload.block_number(),
+                parent_hash: payload.parent_hash(),
                 total_difficulty,
             },
         }
@@ -238,7 +241,7 @@ impl<T: EthSpec> ExecutionBlockGenerator<T> {
     }
 
     pub fn new_payload(&mut self, payload: ExecutionPayload<T>) -> PayloadStatusV1 {
-        let parent = if let Some(parent) = self.blocks.get(&payload.parent_hash) {
+        let parent = if let Some(parent) = self.blocks.get(&payload.parent_hash()) {
             parent
         } else {
             return PayloadStatusV1 {
@@ -248,7 +251,7 @@ impl<T: EthSpec> ExecutionBlockGenerator<T> {
             };
         };
 
-        if payload.block_number != parent.block_number() + 1 {
+        if payload.block_number() != parent.block_number() + 1 {
             return PayloadStatusV1 {
                 status: PayloadStatusV1Status::Invalid,
                 latest_valid_hash: Some(parent.block_hash()),
@@ -256,8 +259,8 @@ impl<T: EthSpec> ExecutionBlockGenerator<T> {
             };
         }
 
-        let valid_hash = payload.block_hash;
-        self.pending_payloads.insert(payload.block_hash, payload);
+        let valid_hash = payload.block_hash();
+        self.pending_payloads.insert(valid_hash, payload);
 
         PayloadStatusV1 {
             status: PayloadStatusV1Status::Valid,
@@ -320,24 +323,67 @@ impl<T: EthSpec> ExecutionBlockGenerator<T> {
                 let id = payload_id_from_u64(self.next_payload_id);
                 self.next_payload_id += 1;
 
-                let mut execution_payload = ExecutionPayload {
-                    parent_hash: forkchoice_state.head_block_hash,
-                    fee_recipient: attributes.suggested_fee_recipient,
-                    receipts_root: Hash256::repeat_byte(42),
-                    state_root: Hash256::repeat_byte(43),
-                    logs_bloom: vec![0; 256].into(),
-                    prev_randao: attributes.prev_randao,
-                    block_number: parent.block_number() + 1,
-                    gas_limit: GAS_LIMIT,
-                    gas_used: GAS_USED,
-                    timestamp: attributes.timestamp,
-                    extra_data: "block gen was here".as_bytes().to_vec().into(),
-                    base_fee_per_gas: Uint256::one(),
-                    block_hash: ExecutionBlockHash::zero(),
-                    transactions: vec![].into(),
-                };
-
-                execution_payload.block_hash =
+                let mut execution_payload =
+                    match (attributes.withdrawals, attributes.parent_beacon_block_root) {
+                        (Some(withdrawals), Some(_)) => {
+                            ExecutionPayload::Deneb(ExecutionPayloadDeneb {
+                                parent_hash: forkchoice_state.head_block_hash,
+                                fee_recipient: attributes.suggested_fee_recipient,
+                                receipts_root: Hash256::repeat_byte(42),
+                                state_root: Hash256::repeat_byte(43),
+                                logs_bloom: vec![0; 256].into(),
+                                prev_randao: attributes.prev_randao,
+                                block_number: parent.block_number() + 1,
+                                gas_limit: GAS_LIMIT,
+                                gas_used: GAS_USED,
+                                timestamp: attributes.timestamp,
+                                extra_data: "block gen was here".as_bytes().to_vec().into(),
+                                base_fee_per_gas: Uint256::one(),
+                                block_hash: ExecutionBlockHash::zero(),
+                                transactions: vec![].into(),
+                                withdrawals: withdrawals.into(),
+                                blob_gas_used: 0,
+                                excess_blob_gas: 0,
+                            })
+                        }
+                        (Some(withdrawals), None) => {
+                            ExecutionPayload::Capella(ExecutionPayloadCapella {
+                                parent_hash: forkchoice_state.head_block_hash,
+                                fee_recipient: attributes.suggested_fee_recipient,
+                                receipts_root: Hash256::repeat_byte(42),
+                                state_root: Hash256::repeat_byte(43),
+                                logs_bloom: vec![0; 256].into(),
+                                prev_randao: attributes.prev_randao,
+                                block_number: parent.block_number() + 1,
+                                gas_limit: GAS_LIMIT,
+                                gas_used: GAS_USED,
+                                timestamp: attributes.timestamp,
+                                extra_data: "block gen was here".as_bytes().to_vec().into(),
+                                base_fee_per_gas: Uint256::one(),
+                                block_hash: ExecutionBlockHash::zero(),
+                                transactions: vec![].into(),
+                                withdrawals: withdrawals.into(),
+                            })
+                        }
+                        (None, _) => ExecutionPayload::Merge(ExecutionPayloadMerge {
+                            parent_hash: forkchoice_state.head_block_hash,
+                            fee_recipient: attributes.suggested_fee_recipient,
+                            receipts_root: Hash256::repeat_byte(42),
+                            state_root: Hash256::repeat_byte(43),
+                            logs_bloom: vec![0; 256].into(),
+                            prev_randao: attributes.prev_randao,
+                            block_number: parent.block_number() + 1,
+                            gas_limit: GAS_LIMIT,
+                            gas_used: GAS_USED,
+                            timestamp: attributes.timestamp,
+                            extra_data: "block gen was here".as_bytes().to_vec().into(),
+                            base_fee_per_gas: Uint256::one(),
+                            block_hash: ExecutionBlockHash::zero(),
+                            transactions: vec![].into(),
+                        }),
+                    };
+
+                *execution_payload.block_hash_mut() =
                     ExecutionBlockHash::from_root(execution_payload.tree_hash_root());
 
                 self.payload_ids.insert(id, execution_payload);
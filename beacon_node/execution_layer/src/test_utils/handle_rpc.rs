@@ -1,10 +1,10 @@
-use super::Context;
+use super::{Block, Context};
 use crate::engine_api::{http::*, *};
 use crate::json_structures::*;
 use serde::de::DeserializeOwned;
 use serde_json::Value as JsonValue;
 use std::sync::Arc;
-use types::EthSpec;
+use types::{EthSpec, ExecutionPayload, Uint256};
 
 pub async fn handle_rpc<T: EthSpec>(
     body: JsonValue,
@@ -56,26 +56,32 @@ pub async fn handle_rpc<T: EthSpec>(
             )
             .unwrap())
         }
-        ENGINE_NEW_PAYLOAD_V1 => {
-            let request: JsonExecutionPayloadV1<T> = get_param(params, 0)?;
+        ENGINE_NEW_PAYLOAD_V1 | ENGINE_NEW_PAYLOAD_V2 | ENGINE_NEW_PAYLOAD_V3 => {
+            let request: ExecutionPayload<T> = match method {
+                ENGINE_NEW_PAYLOAD_V3 => get_param::<JsonExecutionPayloadV3<T>>(params, 0)?.into(),
+                ENGINE_NEW_PAYLOAD_V2 => get_param::<JsonExecutionPayloadV2<T>>(params, 0)?.into(),
+                _ => get_param::<JsonExecutionPayloadV1<T>>(params, 0)?.into(),
+            };
 
-            let (static_response, should_import) =
-                if let Some(mut response) = ctx.static_new_payload_response.lock().clone() {
-                    if response.status.status == PayloadStatusV1Status::Valid {
-                        response.status.latest_valid_hash = Some(request.block_hash)
-                    }
+            let (static_response, should_import) = if let Some(status) = ctx
+                .payload_statuses
+                .lock()
+                .get(&request.block_hash())
+                .cloned()
+            {
+                (Some(status), false)
+            } else if let Some(mut response) = ctx.static_new_payload_response.lock().clone() {
+                if response.status.status == PayloadStatusV1Status::Valid {
+                    response.status.latest_valid_hash = Some(request.block_hash())
+                }
 
-                    (Some(response.status), response.should_import)
-                } else {
-                    (None, true)
-                };
+                (Some(response.status), response.should_import)
+            } else {
+                (None, true)
+            };
 
             let dynamic_response = if should_import {
-                Some(
-                    ctx.execution_block_generator
-                        .write()
-                        .new_payload(request.into()),
-                )
+                Some(ctx.execution_block_generator.write().new_payload(request))
             } else {
                 None
             };
@@ -94,23 +100,131 @@ pub async fn handle_rpc<T: EthSpec>(
                 .get_payload(&id)
                 .ok_or_else(|| format!("no payload for id {:?}", id))?;
 
-            Ok(serde_json::to_value(JsonExecutionPayloadV1::from(response)).unwrap())
+            match response {
+                ExecutionPayload::Merge(payload) => {
+                    Ok(serde_json::to_value(JsonExecutionPayloadV1::from(payload)).unwrap())
+                }
+                ExecutionPayload::Capella(_) => Err(format!(
+                    "payload for id {:?} has withdrawals, use {}",
+                    id, ENGINE_GET_PAYLOAD_V2
+                )),
+                ExecutionPayload::Deneb(_) => Err(format!(
+                    "payload for id {:?} has blob gas fields, use {}",
+                    id, ENGINE_GET_PAYLOAD_V3
+                )),
+            }
+        }
+        ENGINE_GET_PAYLOAD_V2 => {
+            let request: JsonPayloadIdRequest = get_param(params, 0)?;
+            let id = request.into();
+
+            let response = ctx
+                .execution_block_generator
+                .write()
+                .get_payload(&id)
+                .ok_or_else(|| format!("no payload for id {:?}", id))?;
+
+            match response {
+                ExecutionPayload::Capella(payload) => {
+                    Ok(serde_json::to_value(JsonGetPayloadResponseV2 {
+                        execution_payload: JsonExecutionPayloadV2::from(payload),
+                        block_value: Uint256::zero(),
+                    })
+                    .unwrap())
+                }
+                ExecutionPayload::Merge(_) => Err(format!(
+                    "payload for id {:?} has no withdrawals, use {}",
+                    id, ENGINE_GET_PAYLOAD_V1
+                )),
+                ExecutionPayload::Deneb(_) => Err(format!(
+                    "payload for id {:?} has blob gas fields, use {}",
+                    id, ENGINE_GET_PAYLOAD_V3
+                )),
+            }
+        }
+        ENGINE_GET_PAYLOAD_V3 => {
+            let request: JsonPayloadIdRequest = get_param(params, 0)?;
+            let id = request.into();
+
+            let response = ctx
+                .execution_block_generator
+                .write()
+                .get_payload(&id)
+                .ok_or_else(|| format!("no payload for id {:?}", id))?;
+
+            match response {
+                // The mock execution layer never includes blob transactions, so the bundle is
+                // always empty.
+                ExecutionPayload::Deneb(payload) => {
+                    Ok(serde_json::to_value(JsonGetPayloadResponseV3 {
+                        execution_payload: JsonExecutionPayloadV3::from(payload),
+                        block_value: Uint256::zero(),
+                        blobs_bundle: BlobsBundle::<T>::default().into(),
+                        should_override_builder: false,
+                    })
+                    .unwrap())
+                }
+                ExecutionPayload::Merge(_) | ExecutionPayload::Capella(_) => Err(format!(
+                    "payload for id {:?} has no blob gas fields, use {} or {}",
+                    id, ENGINE_GET_PAYLOAD_V1, ENGINE_GET_PAYLOAD_V2
+                )),
+            }
+        }
+        ENGINE_GET_PAYLOAD_BODIES_BY_RANGE_V1 => {
+            let parse_quantity = |index| {
+                get_param::<String>(params, index).and_then(|s| {
+                    s.strip_prefix("0x")
+                        .and_then(|s| u64::from_str_radix(s, 16).ok())
+                        .ok_or_else(|| format!("invalid quantity in params[{}]: {}", index, s))
+                })
+            };
+            let start = parse_quantity(0)?;
+            let count = parse_quantity(1)?;
+
+            let generator = ctx.execution_block_generator.read();
+            let bodies = (start..start.saturating_add(count))
+                .map(|number| match generator.block_by_number(number) {
+                    Some(Block::PoS(payload)) => Some(JsonExecutionPayloadBodyV1::<T> {
+                        transactions: payload.transactions().clone(),
+                        withdrawals: payload.withdrawals().ok().map(|withdrawals| {
+                            withdrawals
+                                .iter()
+                                .cloned()
+                                .map(Into::into)
+                                .collect::<Vec<_>>()
+                                .into()
+                        }),
+                    }),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+
+            Ok(serde_json::to_value(bodies).unwrap())
         }
-        ENGINE_FORKCHOICE_UPDATED_V1 => {
+        ENGINE_FORKCHOICE_UPDATED_V1
+        | ENGINE_FORKCHOICE_UPDATED_V2
+        | ENGINE_FORKCHOICE_UPDATED_V3 => {
             let forkchoice_state: JsonForkChoiceStateV1 = get_param(params, 0)?;
-            let payload_attributes: Option<JsonPayloadAttributesV1> = get_param(params, 1)?;
+            let payload_attributes: Option<PayloadAttributes> = match method {
+                ENGINE_FORKCHOICE_UPDATED_V3 => {
+                    get_param::<Option<JsonPayloadAttributesV3>>(params, 1)?.map(Into::into)
+                }
+                ENGINE_FORKCHOICE_UPDATED_V2 => {
+                    get_param::<Option<JsonPayloadAttributesV2>>(params, 1)?.map(Into::into)
+                }
+                _ => get_param::<Option<JsonPayloadAttributesV1>>(params, 1)?.map(Into::into),
+            };
 
             let head_block_hash = forkchoice_state.head_block_hash;
 
             let mut response = ctx
                 .execution_block_generator
                 .write()
-                .forkchoice_updated_v1(
-                    forkchoice_state.into(),
-                    payload_attributes.map(|json| json.into()),
-                )?;
+                .forkchoice_updated_v1(forkchoice_state.into(), payload_attributes)?;
 
-            if let Some(mut status) = ctx.static_forkchoice_updated_response.lock().clone() {
+            if let Some(status) = ctx.payload_statuses.lock().get(&head_block_hash).cloned() {
+                response.payload_status = status.into();
+            } else if let Some(mut status) = ctx.static_forkchoice_updated_response.lock().clone() {
                 if status.status == PayloadStatusV1Status::Valid {
                     status.latest_valid_hash = Some(head_block_hash)
                 }
@@ -120,6 +234,7 @@ pub async fn handle_rpc<T: EthSpec>(
 
             Ok(serde_json::to_value(response).unwrap())
         }
+        ENGINE_EXCHANGE_CAPABILITIES => Ok(serde_json::to_value(LIGHTHOUSE_CAPABILITIES).unwrap()),
         other => Err(format!(
             "The method {} does not exist/is not available",
             other
@@ -94,6 +94,8 @@ impl<T: EthSpec> MockExecutionLayer<T> {
                     timestamp,
                     prev_randao,
                     suggested_fee_recipient: Address::repeat_byte(42),
+                    withdrawals: None,
+                    parent_beacon_block_root: None,
                 },
             )
             .await;
@@ -117,17 +119,24 @@ impl<T: EthSpec> MockExecutionLayer<T> {
                 prev_randao,
                 finalized_block_hash,
                 validator_index,
+                None,
+                None,
             )
             .await
             .unwrap()
+            .payload
             .execution_payload;
-        let block_hash = payload.block_hash;
-        assert_eq!(payload.parent_hash, parent_hash);
-        assert_eq!(payload.block_number, block_number);
-        assert_eq!(payload.timestamp, timestamp);
-        assert_eq!(payload.prev_randao, prev_randao);
+        let block_hash = payload.block_hash();
+        assert_eq!(payload.parent_hash(), parent_hash);
+        assert_eq!(payload.block_number(), block_number);
+        assert_eq!(payload.timestamp(), timestamp);
+        assert_eq!(payload.prev_randao(), prev_randao);
 
-        let status = self.el.notify_new_payload(&payload).await.unwrap();
+        let status = self
+            .el
+            .notify_new_payload(&payload, vec![], Hash256::zero())
+            .await
+            .unwrap();
         assert_eq!(status, PayloadStatus::Valid);
 
         // Use junk values for slot/head-root to ensure there is no payload supplied.
@@ -12,6 +12,7 @@ use parking_lot::{Mutex, RwLock, RwLockWriteGuard};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use slog::{info, Logger};
+use std::collections::HashMap;
 use std::convert::Infallible;
 use std::future::Future;
 use std::marker::PhantomData;
@@ -69,6 +70,7 @@ impl<T: EthSpec> MockServer<T> {
             preloaded_responses,
             static_new_payload_response: <_>::default(),
             static_forkchoice_updated_response: <_>::default(),
+            payload_statuses: <_>::default(),
             _phantom: PhantomData,
         });
 
@@ -134,6 +136,12 @@ impl<T: EthSpec> MockServer<T> {
         *self.ctx.static_forkchoice_updated_response.lock() = Some(status);
     }
 
+    /// Respond with `status` to any `newPayload` or `forkchoiceUpdated` request for `block_hash`,
+    /// taking priority over the static responses.
+    pub fn set_payload_status(&self, block_hash: ExecutionBlockHash, status: PayloadStatusV1) {
+        self.ctx.payload_statuses.lock().insert(block_hash, status);
+    }
+
     fn valid_status() -> PayloadStatusV1 {
         PayloadStatusV1 {
             status: PayloadStatusV1Status::Valid,
@@ -358,6 +366,7 @@ pub struct Context<T: EthSpec> {
     pub previous_request: Arc<Mutex<Option<serde_json::Value>>>,
     pub static_new_payload_response: Arc<Mutex<Option<StaticNewPayloadResponse>>>,
     pub static_forkchoice_updated_response: Arc<Mutex<Option<PayloadStatusV1>>>,
+    pub payload_statuses: Arc<Mutex<HashMap<ExecutionBlockHash, PayloadStatusV1>>>,
     pub _phantom: PhantomData<T>,
 }
 
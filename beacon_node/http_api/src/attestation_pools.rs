@@ -0,0 +1,107 @@
+//! Contains the handlers for the `lighthouse/naive_aggregation_pool` and
+//! `lighthouse/op_pool/attestations` endpoints, which expose the contents of the attestation pools
+//! for debugging attestation inclusion.
+use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use eth2::lighthouse::OpPoolAttestation;
+use eth2::types::AttestationPoolQuery;
+use state_processing::state_advance::complete_state_advance;
+use std::cmp;
+use std::collections::HashMap;
+use std::sync::Arc;
+use types::{Attestation, CloneConfig, EthSpec, RelativeEpoch};
+use warp_utils::reject::{beacon_chain_error, beacon_state_error, custom_server_error, not_synced};
+
+fn query_matches<E: EthSpec>(query: &AttestationPoolQuery, attestation: &Attestation<E>) -> bool {
+    query
+        .slot
+        .map_or(true, |slot| slot == attestation.data.slot)
+        && query
+            .committee_index
+            .map_or(true, |index| index == attestation.data.index)
+}
+
+/// Returns the unaggregated attestations which have been aggregated in the naive aggregation pool.
+pub fn naive_aggregation_pool<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    query: AttestationPoolQuery,
+) -> Vec<Attestation<T::EthSpec>> {
+    chain
+        .naive_aggregation_pool
+        .read()
+        .iter()
+        .filter(|attestation| query_matches(&query, attestation))
+        .cloned()
+        .collect()
+}
+
+/// Returns the attestations in the operation pool, indicating which of them would be packed into
+/// a block proposed on the current head at the next available slot.
+pub fn op_pool_attestations<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    query: AttestationPoolQuery,
+) -> Result<Vec<OpPoolAttestation<T::EthSpec>>, warp::Rejection> {
+    let (mut state, state_root) = chain
+        .with_head(|head| {
+            Ok::<_, BeaconChainError>((
+                head.beacon_state
+                    .clone_with(CloneConfig::committee_caches_only()),
+                head.beacon_state_root(),
+            ))
+        })
+        .map_err(beacon_chain_error)?;
+
+    // A block can't be proposed at the slot of the head, so pack for at least the slot after it.
+    let proposal_slot = cmp::max(chain.slot().map_err(beacon_chain_error)?, state.slot() + 1);
+
+    // Don't advance the head state further than a block is permitted to skip, so a node which is
+    // far behind the wall clock can't be made to process an unbounded number of slots. Without a
+    // configured limit, one epoch suffices since older attestations can't be packed anyway.
+    let max_skip_slots = chain
+        .config
+        .import_max_skip_slots
+        .unwrap_or_else(T::EthSpec::slots_per_epoch);
+    if proposal_slot > state.slot() + max_skip_slots {
+        return Err(not_synced(format!(
+            "head slot {} is more than {} slots behind the proposal slot {}",
+            state.slot(),
+            max_skip_slots,
+            proposal_slot
+        )));
+    }
+
+    complete_state_advance(&mut state, Some(state_root), proposal_slot, &chain.spec)
+        .map_err(|e| custom_server_error(format!("unable to advance head state: {:?}", e)))?;
+    state
+        .build_committee_cache(RelativeEpoch::Current, &chain.spec)
+        .map_err(beacon_state_error)?;
+
+    let mut prev_filter_cache = HashMap::new();
+    let prev_attestation_filter = |att: &&Attestation<T::EthSpec>| {
+        chain.filter_op_pool_attestation(&mut prev_filter_cache, *att, &state)
+    };
+    let mut curr_filter_cache = HashMap::new();
+    let curr_attestation_filter = |att: &&Attestation<T::EthSpec>| {
+        chain.filter_op_pool_attestation(&mut curr_filter_cache, *att, &state)
+    };
+
+    let packed = chain
+        .op_pool
+        .get_attestations(
+            &state,
+            prev_attestation_filter,
+            curr_attestation_filter,
+            &chain.spec,
+        )
+        .map_err(|e| custom_server_error(format!("unable to pack attestations: {:?}", e)))?;
+
+    Ok(chain
+        .op_pool
+        .get_filtered_attestations(|attestation| query_matches(&query, attestation))
+        .into_iter()
+        .map(|attestation| OpPoolAttestation {
+            num_attesters: attestation.aggregation_bits.num_set_bits(),
+            would_pack: packed.contains(&attestation),
+            attestation,
+        })
+        .collect())
+}
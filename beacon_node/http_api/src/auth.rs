@@ -0,0 +1,121 @@
+//! Optional token-based authorization for the HTTP API.
+//!
+//! Endpoints are grouped into three tiers:
+//!
+//! - `Public`: read-only endpoints which are always available.
+//! - `Validator`: endpoints used by a validator client (block production, pool submission, etc).
+//! - `Admin`: endpoints which are expensive or modify the node (state downloads, database
+//!   operations).
+//!
+//! A tier is only enforced if a token is configured for it, or for a higher tier. A token grants
+//! access to its own tier and every tier below it.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use subtle::ConstantTimeEq;
+use warp::filters::BoxedFilter;
+use warp::Filter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AuthTier {
+    Public,
+    Validator,
+    Admin,
+}
+
+impl fmt::Display for AuthTier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuthTier::Public => write!(f, "public"),
+            AuthTier::Validator => write!(f, "validator"),
+            AuthTier::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthConfig {
+    /// Token granting access to the `Validator` tier.
+    pub validator_token: Option<String>,
+    /// Token granting access to the `Admin` and `Validator` tiers.
+    pub admin_token: Option<String>,
+}
+
+impl AuthConfig {
+    /// Returns `true` if requests for endpoints in `tier` must be authorized.
+    pub fn is_enforced(&self, tier: AuthTier) -> bool {
+        match tier {
+            AuthTier::Public => false,
+            AuthTier::Validator => self.validator_token.is_some() || self.admin_token.is_some(),
+            AuthTier::Admin => self.admin_token.is_some(),
+        }
+    }
+
+    /// Returns the highest tier granted by the given `Authorization` header value.
+    ///
+    /// Only the `Bearer <token>` scheme is accepted, any other header grants the `Public` tier.
+    pub fn tier_for_header(&self, header: &str) -> AuthTier {
+        let token = match bearer_token(header) {
+            Some(token) => token,
+            None => return AuthTier::Public,
+        };
+
+        if token_matches(self.admin_token.as_deref(), token) {
+            AuthTier::Admin
+        } else if token_matches(self.validator_token.as_deref(), token) {
+            AuthTier::Validator
+        } else {
+            AuthTier::Public
+        }
+    }
+
+    /// Check that a request with the given `Authorization` header may access an endpoint in the
+    /// `required` tier.
+    pub fn authorize(&self, header: Option<&str>, required: AuthTier) -> Result<(), String> {
+        if !self.is_enforced(required) {
+            return Ok(());
+        }
+
+        let header = header
+            .ok_or_else(|| format!("missing Authorization header for {} endpoint", required))?;
+
+        if self.tier_for_header(header) >= required {
+            Ok(())
+        } else {
+            Err(format!(
+                "token does not grant access to {} endpoint",
+                required
+            ))
+        }
+    }
+}
+
+/// Returns the token of an `Authorization` header using the `Bearer` scheme.
+pub fn bearer_token(header: &str) -> Option<&str> {
+    header.strip_prefix("Bearer ").map(str::trim)
+}
+
+/// Compares `token` against the `expected` token in constant time, so the response time of a
+/// request doesn't reveal how much of a guessed token is correct.
+fn token_matches(expected: Option<&str>, token: &str) -> bool {
+    expected.map_or(false, |expected| {
+        bool::from(expected.as_bytes().ct_eq(token.as_bytes()))
+    })
+}
+
+/// Returns a filter which rejects requests that are not authorized to access the `required` tier.
+///
+/// This filter should be applied after path matching so that unauthorized requests for unknown
+/// paths still result in a 404.
+pub fn auth_filter(config: &AuthConfig, required: AuthTier) -> BoxedFilter<()> {
+    let config = config.clone();
+    warp::header::optional::<String>("Authorization")
+        .and_then(move |header: Option<String>| {
+            let result = config
+                .authorize(header.as_deref(), required)
+                .map_err(warp_utils::reject::invalid_auth);
+            async move { result }
+        })
+        .untuple_one()
+        .boxed()
+}
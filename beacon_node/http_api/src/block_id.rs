@@ -1,7 +1,7 @@
 use beacon_chain::{BeaconChain, BeaconChainTypes, WhenSlotSkipped};
 use eth2::types::BlockId as CoreBlockId;
 use std::str::FromStr;
-use types::{BlindedPayload, Hash256, SignedBeaconBlock, Slot};
+use types::{BlindedPayload, EthSpec, Hash256, SignedBeaconBlock, Slot};
 
 /// Wraps `eth2::types::BlockId` and provides a simple way to obtain a block or root for a given
 /// `BlockId`.
@@ -51,6 +51,67 @@ impl BlockId {
         }
     }
 
+    /// Returns `true` if the block identified by `self` has not yet been verified by the
+    /// execution layer.
+    pub fn is_execution_optimistic<T: BeaconChainTypes>(
+        &self,
+        chain: &BeaconChain<T>,
+    ) -> Result<bool, warp::Rejection> {
+        match &self.0 {
+            CoreBlockId::Head => chain
+                .is_optimistic_head()
+                .map_err(warp_utils::reject::beacon_chain_error),
+            CoreBlockId::Genesis => Ok(false),
+            _ => chain
+                .is_optimistic_block(&self.root(chain)?)
+                .map_err(warp_utils::reject::beacon_chain_error),
+        }
+    }
+
+    /// Returns `true` if the block identified by `self` is finalized, i.e. it is a canonical block
+    /// no later than the finalized checkpoint.
+    ///
+    /// Blocks which are unknown to the node are considered finalized, so that they result in a 404
+    /// rather than revealing anything about the fork choice.
+    pub fn is_finalized<T: BeaconChainTypes>(
+        &self,
+        chain: &BeaconChain<T>,
+    ) -> Result<bool, warp::Rejection> {
+        let finalized_checkpoint = chain
+            .head_info()
+            .map(|head| head.finalized_checkpoint)
+            .map_err(warp_utils::reject::beacon_chain_error)?;
+        let finalized_slot = finalized_checkpoint
+            .epoch
+            .start_slot(T::EthSpec::slots_per_epoch());
+
+        match &self.0 {
+            CoreBlockId::Genesis | CoreBlockId::Finalized => Ok(true),
+            CoreBlockId::Slot(slot) => Ok(*slot <= finalized_slot),
+            CoreBlockId::Head | CoreBlockId::Justified | CoreBlockId::Root(_) => {
+                let root = self.root(chain)?;
+
+                // Fork choice only contains the finalized block and its descendants.
+                if chain.fork_choice.read().contains_block(&root) {
+                    return Ok(root == finalized_checkpoint.root);
+                }
+
+                let slot = match chain
+                    .get_blinded_block(&root)
+                    .map_err(warp_utils::reject::beacon_chain_error)?
+                {
+                    Some(block) => block.slot(),
+                    None => return Ok(true),
+                };
+                let canonical_root = chain
+                    .block_root_at_slot(slot, WhenSlotSkipped::None)
+                    .map_err(warp_utils::reject::beacon_chain_error)?;
+
+                Ok(slot <= finalized_slot && canonical_root == Some(root))
+            }
+        }
+    }
+
     /// Return the `SignedBeaconBlock` identified by `self`.
     pub fn blinded_block<T: BeaconChainTypes>(
         &self,
@@ -11,7 +11,7 @@ use std::marker::PhantomData;
 use std::sync::Arc;
 use types::{
     BeaconCommittee, BeaconState, BeaconStateError, BlindedPayload, ChainSpec, Epoch, EthSpec,
-    Hash256, OwnedBeaconCommittee, RelativeEpoch, SignedBeaconBlock, Slot,
+    Hash256, OwnedBeaconCommittee, RelativeEpoch, SignedBeaconBlock, Slot, Unsigned,
 };
 use warp_utils::reject::{beacon_chain_error, custom_bad_request, custom_server_error};
 
@@ -138,6 +138,25 @@ impl<T: EthSpec> PackingEfficiencyHandler<T> {
         Ok(attestations_in_block.len())
     }
 
+    /// Compute an upper bound on the number of available attestations which could be included in
+    /// a single block.
+    ///
+    /// Each aggregate in a block can only cover a single committee, so at best a block includes
+    /// every available attestation from the `max_attestations` committees with the most available
+    /// attestations.
+    fn max_includable_attestations(&self, max_attestations: usize) -> usize {
+        let mut available_per_committee: HashMap<(Slot, u64), usize> = HashMap::new();
+        for attestation in &self.available_attestations {
+            *available_per_committee
+                .entry((attestation.slot, attestation.committee_index))
+                .or_default() += 1;
+        }
+
+        let mut counts = available_per_committee.into_values().collect::<Vec<_>>();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        counts.into_iter().take(max_attestations).sum()
+    }
+
     fn add_attestations(&mut self, slot: Slot) -> Result<(), PackingEfficiencyError> {
         let committees = self.get_committees_at_slot(slot)?;
         for committee in committees {
@@ -320,10 +339,12 @@ pub fn get_block_packing_efficiency<T: BeaconChainTypes>(
             graffiti: block_message.body().graffiti().as_utf8_lossy(),
         };
 
-        // Store the count of available attestations at this point.
-        // In the future it may be desirable to check that the number of available attestations
-        // does not exceed the maximum possible amount given the length of available committees.
+        // Store the count of available attestations at this point, along with the maximum number
+        // of them which could fit in a block.
         let available_count = handler.lock().available_attestations.len();
+        let max_includable_count = handler
+            .lock()
+            .max_includable_attestations(<T::EthSpec as EthSpec>::MaxAttestations::to_usize());
 
         // Get all attestations included in the block.
         let included = handler.lock().apply_block(block)?;
@@ -334,6 +355,7 @@ pub fn get_block_packing_efficiency<T: BeaconChainTypes>(
             proposer_info,
             available_attestations: available_count,
             included_attestations: included,
+            max_includable_attestations: max_includable_count,
             prior_skip_slots: handler.lock().prior_skip_slots,
         };
 
@@ -3,7 +3,8 @@ use eth2::lighthouse::{BlockReward, BlockRewardsQuery};
 use slog::{warn, Logger};
 use state_processing::BlockReplayer;
 use std::sync::Arc;
-use warp_utils::reject::{beacon_chain_error, beacon_state_error, custom_bad_request};
+use types::BeaconState;
+use warp_utils::reject::{beacon_chain_error, custom_bad_request};
 
 pub fn get_block_rewards<T: BeaconChainTypes>(
     query: BlockRewardsQuery,
@@ -36,45 +37,53 @@ pub fn get_block_rewards<T: BeaconChainTypes>(
         .map_err(beacon_chain_error)?
         .ok_or_else(|| custom_bad_request(format!("prior state at slot {} unknown", prior_slot)))?;
 
-    let mut state = chain
+    let state = chain
         .get_state(&state_root, Some(prior_slot))
         .and_then(|maybe_state| maybe_state.ok_or(BeaconChainError::MissingBeaconState(state_root)))
         .map_err(beacon_chain_error)?;
 
-    state
-        .build_all_caches(&chain.spec)
-        .map_err(beacon_state_error)?;
+    let replay_chunk = |mut state: BeaconState<T::EthSpec>, blocks: Vec<_>, chunk_end_slot| {
+        state.build_all_caches(&chain.spec)?;
+        let chunk_start_slot = state.slot();
 
-    let mut block_rewards = Vec::with_capacity(blocks.len());
+        let mut block_rewards = Vec::with_capacity(blocks.len());
 
-    let block_replayer = BlockReplayer::new(state, &chain.spec)
-        .pre_block_hook(Box::new(|state, block| {
-            // Compute block reward.
-            let block_reward =
-                chain.compute_block_reward(block.message(), block.canonical_root(), state)?;
-            block_rewards.push(block_reward);
-            Ok(())
-        }))
-        .state_root_iter(
-            chain
-                .forwards_iter_state_roots_until(prior_slot, end_slot)
-                .map_err(beacon_chain_error)?,
-        )
-        .no_signature_verification()
-        .minimal_block_root_verification()
-        .apply_blocks(blocks, None)
-        .map_err(beacon_chain_error)?;
+        let block_replayer = BlockReplayer::new(state, &chain.spec)
+            .pre_block_hook(Box::new(|state, block| {
+                // Compute block reward.
+                let block_reward =
+                    chain.compute_block_reward(block.message(), block.canonical_root(), state)?;
+                block_rewards.push(block_reward);
+                Ok(())
+            }))
+            .state_root_iter(
+                chain.forwards_iter_state_roots_until(chunk_start_slot, chunk_end_slot)?,
+            )
+            .no_signature_verification()
+            .minimal_block_root_verification()
+            .apply_blocks(blocks, None)?;
 
-    if block_replayer.state_root_miss() {
-        warn!(
-            log,
-            "Block reward state root miss";
-            "start_slot" => start_slot,
-            "end_slot" => end_slot,
-        );
-    }
+        if block_replayer.state_root_miss() {
+            warn!(
+                log,
+                "Block reward state root miss";
+                "start_slot" => chunk_start_slot,
+                "end_slot" => chunk_end_slot,
+            );
+        }
+
+        drop(block_replayer);
+
+        Ok::<_, BeaconChainError>(block_rewards)
+    };
+
+    // Replay the range in chunks between restore points, which can be processed in parallel.
+    let chunk_rewards = chain
+        .store
+        .replay_blocks_parallel(state, blocks, end_slot, replay_chunk)
+        .map_err(beacon_chain_error)?;
 
-    drop(block_replayer);
+    let block_rewards = chunk_rewards.into_iter().flatten().collect();
 
     Ok(block_rewards)
 }
@@ -0,0 +1,68 @@
+//! Contains the handler for the `lighthouse/beacon/block_roots` endpoint, which serves the roots of
+//! the canonical blocks in a range of slots without loading the blocks themselves.
+use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes, HistoricalBlockError};
+use eth2::lighthouse::{BlockRootsQuery, SlotBlockRoot};
+use std::cmp;
+use std::sync::Arc;
+use warp_utils::reject::{beacon_chain_error, custom_bad_request};
+
+/// The maximum number of slots that may be requested at once.
+pub const MAX_BLOCK_ROOTS_COUNT: u64 = 8_192;
+
+/// Returns the slot and root of each canonical block in the `query.count` slots starting at
+/// `query.start_slot`. Skipped slots are omitted.
+pub fn get_block_roots<T: BeaconChainTypes>(
+    query: BlockRootsQuery,
+    chain: Arc<BeaconChain<T>>,
+) -> Result<Vec<SlotBlockRoot>, warp::Rejection> {
+    let start_slot = query.start_slot;
+
+    if query.count > MAX_BLOCK_ROOTS_COUNT {
+        return Err(custom_bad_request(format!(
+            "count {} exceeds the maximum of {}",
+            query.count, MAX_BLOCK_ROOTS_COUNT
+        )));
+    }
+
+    let head_slot = chain.head_info().map_err(beacon_chain_error)?.slot;
+    let end_slot = cmp::min(start_slot + query.count, head_slot + 1);
+    if start_slot >= end_slot {
+        return Ok(vec![]);
+    }
+
+    // Begin one slot early where possible, so that a skipped `start_slot` can be recognised by its
+    // root repeating that of the prior slot.
+    let iter_start_slot = if start_slot > chain.store.get_oldest_block_slot() {
+        start_slot - 1
+    } else {
+        start_slot
+    };
+
+    let iter = chain
+        .forwards_iter_block_roots_until(iter_start_slot, end_slot - 1)
+        .map_err(|e| match e {
+            BeaconChainError::HistoricalBlockError(HistoricalBlockError::BlockOutOfRange {
+                oldest_block_slot,
+                ..
+            }) => custom_bad_request(format!(
+                "blocks prior to slot {} are not available",
+                oldest_block_slot
+            )),
+            e => beacon_chain_error(e),
+        })?;
+
+    let mut block_roots = vec![];
+    let mut prev_root = None;
+    for result in iter {
+        let (root, slot) = result.map_err(beacon_chain_error)?;
+        if slot >= end_slot {
+            break;
+        }
+        if prev_root != Some(root) && slot >= start_slot {
+            block_roots.push(SlotBlockRoot { slot, root });
+        }
+        prev_root = Some(root);
+    }
+
+    Ok(block_roots)
+}
@@ -1,6 +1,6 @@
 use beacon_chain::store::{metadata::CURRENT_SCHEMA_VERSION, AnchorInfo};
 use beacon_chain::{BeaconChain, BeaconChainTypes};
-use eth2::lighthouse::DatabaseInfo;
+use eth2::lighthouse::{BlockHistoryInfo, DatabaseInfo};
 use std::sync::Arc;
 use types::SignedBlindedBeaconBlock;
 
@@ -11,12 +11,42 @@ pub fn info<T: BeaconChainTypes>(
     let split = store.get_split_info();
     let config = store.get_config().clone();
     let anchor = store.get_anchor_info();
+    let current_slot = chain
+        .slot()
+        .map_err(warp_utils::reject::beacon_chain_error)?;
+
+    let anchor_slot = anchor
+        .as_ref()
+        .map_or(chain.spec.genesis_slot, |anchor| anchor.anchor_slot);
+    let oldest_block_slot = store.get_oldest_block_slot();
+    let backfill_target_slot = store
+        .backfill_target_slot()
+        .unwrap_or(chain.spec.genesis_slot);
+    let backfill_complete = anchor
+        .as_ref()
+        .map_or(true, |anchor| store.block_backfill_complete(anchor));
+    let backfill_progress_percent = if backfill_complete || anchor_slot <= backfill_target_slot {
+        100.0
+    } else {
+        let backfilled = anchor_slot.saturating_sub(oldest_block_slot).as_u64();
+        let total = anchor_slot.saturating_sub(backfill_target_slot).as_u64();
+        backfilled as f64 * 100.0 / total as f64
+    };
 
     Ok(DatabaseInfo {
         schema_version: CURRENT_SCHEMA_VERSION.as_u64(),
         config,
         split,
         anchor,
+        block_history: BlockHistoryInfo {
+            anchor_slot,
+            oldest_block_slot,
+            backfill_target_slot,
+            backfill_complete,
+            backfill_progress_percent,
+            min_block_history_available: store.has_min_block_history(current_slot),
+        },
+        blob_info: store.get_blob_info(),
     })
 }
 
@@ -0,0 +1,171 @@
+//! Manually triggered database compaction and pruning.
+//!
+//! These operations can take minutes on a large database, so rather than holding the request open
+//! each one runs on its own thread and is reported as a job which can be polled for progress. Only
+//! one job may run at a time, as they all contend for the same database.
+
+use beacon_chain::store::Error as StoreError;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{DatabaseJob, DatabaseJobKind, DatabaseJobStatus};
+use parking_lot::Mutex;
+use slog::{error, info, Logger};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The number of finished jobs to remember.
+const MAX_FINISHED_JOBS: usize = 16;
+
+#[derive(Default)]
+pub struct DatabaseJobs {
+    inner: Mutex<Jobs>,
+}
+
+#[derive(Default)]
+struct Jobs {
+    next_id: u64,
+    /// All running and recently finished jobs, oldest first.
+    jobs: VecDeque<DatabaseJob>,
+}
+
+impl DatabaseJobs {
+    /// Start a job of the given `kind` in the background, returning its initial status.
+    ///
+    /// Returns a 400 if another job is already running.
+    pub fn start<T: BeaconChainTypes>(
+        self: &Arc<Self>,
+        kind: DatabaseJobKind,
+        chain: Arc<BeaconChain<T>>,
+        log: Logger,
+    ) -> Result<DatabaseJob, warp::Rejection> {
+        let job = {
+            let mut inner = self.inner.lock();
+            if let Some(running) = inner
+                .jobs
+                .iter()
+                .find(|job| job.status == DatabaseJobStatus::Running)
+            {
+                return Err(warp_utils::reject::custom_bad_request(format!(
+                    "database job {} is already running",
+                    running.id
+                )));
+            }
+
+            let job = DatabaseJob {
+                id: inner.next_id,
+                kind,
+                status: DatabaseJobStatus::Running,
+                items_processed: 0,
+                items_total: None,
+                started_at: timestamp_now(),
+                finished_at: None,
+                error: None,
+            };
+            inner.next_id += 1;
+            inner.jobs.push_back(job.clone());
+            inner.prune();
+            job
+        };
+
+        let jobs = self.clone();
+        let id = job.id;
+        std::thread::Builder::new()
+            .name("database_job".to_string())
+            .spawn(move || jobs.run(id, kind, chain, log))
+            .map_err(|e| {
+                self.finish(job.id, Err(format!("unable to spawn thread: {}", e)));
+                warp_utils::reject::custom_server_error(format!(
+                    "unable to start database job: {}",
+                    e
+                ))
+            })?;
+
+        Ok(job)
+    }
+
+    /// All running and recently finished jobs, oldest first.
+    pub fn list(&self) -> Vec<DatabaseJob> {
+        self.inner.lock().jobs.iter().cloned().collect()
+    }
+
+    pub fn get(&self, id: u64) -> Option<DatabaseJob> {
+        self.inner
+            .lock()
+            .jobs
+            .iter()
+            .find(|job| job.id == id)
+            .cloned()
+    }
+
+    fn run<T: BeaconChainTypes>(
+        &self,
+        id: u64,
+        kind: DatabaseJobKind,
+        chain: Arc<BeaconChain<T>>,
+        log: Logger,
+    ) {
+        info!(log, "Starting database job"; "id" => id, "kind" => ?kind);
+
+        let progress = |processed: usize, total: usize| {
+            self.update(id, |job| {
+                job.items_processed = processed as u64;
+                job.items_total = Some(total as u64);
+            })
+        };
+        let result = match kind {
+            DatabaseJobKind::Compact => chain.store.compact(),
+            DatabaseJobKind::PrunePayloads => {
+                chain.store.prune_execution_payloads(progress).map(|_| ())
+            }
+            DatabaseJobKind::PruneStates => chain.store.prune_historic_states(progress).map(|_| ()),
+        };
+
+        match &result {
+            Ok(()) => info!(log, "Database job completed"; "id" => id, "kind" => ?kind),
+            Err(e) => error!(
+                log,
+                "Database job failed";
+                "id" => id,
+                "kind" => ?kind,
+                "error" => ?e
+            ),
+        }
+        self.finish(id, result.map_err(|e: StoreError| format!("{:?}", e)));
+    }
+
+    fn finish(&self, id: u64, result: Result<(), String>) {
+        self.update(id, |job| {
+            job.finished_at = Some(timestamp_now());
+            match result {
+                Ok(()) => job.status = DatabaseJobStatus::Completed,
+                Err(e) => {
+                    job.status = DatabaseJobStatus::Failed;
+                    job.error = Some(e);
+                }
+            }
+        })
+    }
+
+    fn update(&self, id: u64, f: impl FnOnce(&mut DatabaseJob)) {
+        if let Some(job) = self.inner.lock().jobs.iter_mut().find(|job| job.id == id) {
+            f(job)
+        }
+    }
+}
+
+impl Jobs {
+    /// Forget the oldest finished jobs once more than `MAX_FINISHED_JOBS` have accumulated.
+    ///
+    /// Only the most recently started job may be running, so the oldest jobs are always finished.
+    fn prune(&mut self) {
+        while self.jobs.len() > MAX_FINISHED_JOBS + 1 {
+            self.jobs.pop_front();
+        }
+    }
+}
+
+fn timestamp_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
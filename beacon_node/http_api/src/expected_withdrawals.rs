@@ -0,0 +1,68 @@
+use crate::version::inconsistent_fork_rejection;
+use state_processing::per_block_processing::get_expected_withdrawals as compute_withdrawals;
+use state_processing::state_advance::partial_state_advance;
+use types::{BeaconState, ChainSpec, EthSpec, ForkName, Slot, Withdrawal};
+
+/// Compute the withdrawals which would be included in a payload built on top of `state`.
+///
+/// If `proposal_slot` is provided it must be later than the slot of `state`, and no later than the
+/// end of the epoch after the state's epoch. Withdrawals further ahead can't be predicted reliably
+/// and advancing the state that far would be needlessly expensive.
+pub fn get_expected_withdrawals<E: EthSpec>(
+    state: &BeaconState<E>,
+    proposal_slot: Option<Slot>,
+    spec: &ChainSpec,
+) -> Result<Vec<Withdrawal>, warp::Rejection> {
+    let mut state = state.clone();
+
+    if let Some(proposal_slot) = proposal_slot {
+        if proposal_slot <= state.slot() {
+            return Err(warp_utils::reject::custom_bad_request(format!(
+                "proposal slot {} must be greater than the state slot {}",
+                proposal_slot,
+                state.slot()
+            )));
+        }
+
+        let max_proposal_slot = (state.current_epoch() + 2).start_slot(E::slots_per_epoch()) - 1;
+        if proposal_slot > max_proposal_slot {
+            return Err(warp_utils::reject::custom_bad_request(format!(
+                "proposal slot {} is too far ahead of the state slot {}, the maximum is {}",
+                proposal_slot,
+                state.slot(),
+                max_proposal_slot
+            )));
+        }
+
+        // Intermediate state roots are irrelevant to the withdrawals sweep, so a partial advance
+        // suffices.
+        let state_root = state
+            .update_tree_hash_cache()
+            .map_err(warp_utils::reject::beacon_state_error)?;
+        partial_state_advance(&mut state, Some(state_root), proposal_slot, spec).map_err(|e| {
+            warp_utils::reject::custom_server_error(format!(
+                "unable to advance state to proposal slot: {:?}",
+                e
+            ))
+        })?;
+    }
+
+    let fork_name = state.fork_name(spec).map_err(inconsistent_fork_rejection)?;
+
+    match fork_name {
+        ForkName::Base | ForkName::Altair | ForkName::Merge => {
+            Err(warp_utils::reject::custom_bad_request(format!(
+                "withdrawals are not supported by the {} fork",
+                fork_name
+            )))
+        }
+        ForkName::Capella | ForkName::Deneb => compute_withdrawals(&state, spec)
+            .map(|withdrawals| withdrawals.to_vec())
+            .map_err(|e| {
+                warp_utils::reject::custom_server_error(format!(
+                    "unable to compute withdrawals: {:?}",
+                    e
+                ))
+            }),
+    }
+}
@@ -0,0 +1,54 @@
+//! Supports serving only finalized data from the state and block endpoints.
+//!
+//! Finalized states and blocks never change, so responses for them can be cached indefinitely by
+//! a CDN sitting in front of a checkpoint sync provider. Refusing non-finalized data also avoids
+//! revealing the node's view of fork choice.
+use crate::block_id::BlockId;
+use crate::state_id::StateId;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use std::sync::Arc;
+use warp_utils::task::blocking_task;
+
+/// Reject `state_id` with a 404 if `serve_finalized_only` is set and it isn't finalized.
+pub async fn check_state_id<T: BeaconChainTypes>(
+    state_id: StateId,
+    chain: Arc<BeaconChain<T>>,
+    serve_finalized_only: bool,
+) -> Result<StateId, warp::Rejection> {
+    if !serve_finalized_only {
+        return Ok(state_id);
+    }
+
+    blocking_task(move || {
+        if state_id.is_finalized(&chain)? {
+            Ok(state_id)
+        } else {
+            Err(warp_utils::reject::custom_not_found(
+                "state is not finalized and this node only serves finalized data".to_string(),
+            ))
+        }
+    })
+    .await
+}
+
+/// Reject `block_id` with a 404 if `serve_finalized_only` is set and it isn't finalized.
+pub async fn check_block_id<T: BeaconChainTypes>(
+    block_id: BlockId,
+    chain: Arc<BeaconChain<T>>,
+    serve_finalized_only: bool,
+) -> Result<BlockId, warp::Rejection> {
+    if !serve_finalized_only {
+        return Ok(block_id);
+    }
+
+    blocking_task(move || {
+        if block_id.is_finalized(&chain)? {
+            Ok(block_id)
+        } else {
+            Err(warp_utils::reject::custom_not_found(
+                "block is not finalized and this node only serves finalized data".to_string(),
+            ))
+        }
+    })
+    .await
+}
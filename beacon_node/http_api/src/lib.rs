@@ -6,35 +6,56 @@
 //! used for development.
 
 mod attestation_performance;
+mod attestation_pools;
 mod attester_duties;
+mod auth;
 mod block_id;
 mod block_packing_efficiency;
 mod block_rewards;
+mod block_roots;
 mod database;
+mod database_jobs;
+mod expected_withdrawals;
+mod finalized_only;
 mod metrics;
+mod peers;
+mod proofs;
 mod proposer_duties;
+mod publish_blocks;
+mod rate_limit;
+mod slot_delays;
 mod state_id;
+mod state_replay;
 mod sync_committees;
+mod system;
 mod validator_inclusion;
+mod validators;
 mod version;
 
+pub use auth::{AuthConfig, AuthTier};
 use beacon_chain::{
     attestation_verification::VerifiedAttestation,
-    observed_operations::ObservationOutcome,
-    validator_monitor::{get_block_delay_ms, timestamp_now},
-    AttestationError as AttnError, BeaconChain, BeaconChainError, BeaconChainTypes,
-    HeadSafetyStatus, ProduceBlockVerification, WhenSlotSkipped,
+    memory_watermark::MEMORY_WATERMARK_CHECK_INTERVAL, observed_operations::ObservationOutcome,
+    validator_monitor::timestamp_now, AttestationError as AttnError, BeaconChain, BeaconChainError,
+    BeaconChainTypes, HeadSafetyStatus, ProduceBlockVerification, WhenSlotSkipped,
 };
 use block_id::BlockId;
+use database_jobs::DatabaseJobs;
+use eth2::lighthouse::DatabaseJobKind;
 use eth2::types::{self as api_types, EndpointVersion, ValidatorId};
-use lighthouse_network::{types::SyncState, EnrExt, NetworkGlobals, PeerId, PubsubMessage};
+use lighthouse_network::{
+    types::SyncState, EnrExt, EnrUpdate, Eth2Enr, NetworkGlobals, PeerId, PeerInfo, PubsubMessage,
+};
 use lighthouse_version::version_with_platform;
+use logging::LogLevels;
 use network::NetworkMessage;
+pub use rate_limit::RateLimitConfig;
 use serde::{Deserialize, Serialize};
 use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use ssz::Encode;
 use state_id::StateId;
+use state_replay::StateReplayQueue;
 use std::borrow::Cow;
 use std::convert::TryInto;
 use std::future::Future;
@@ -42,15 +63,15 @@ use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
-use tokio::sync::mpsc::UnboundedSender;
+use std::time::Duration;
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
 use tokio_stream::{wrappers::BroadcastStream, StreamExt};
 use types::{
-    Attestation, AttesterSlashing, BeaconBlockBodyMerge, BeaconBlockMerge, BeaconStateError,
-    BlindedPayload, CommitteeCache, ConfigAndPreset, Epoch, EthSpec, ForkName, FullPayload,
-    ProposerPreparationData, ProposerSlashing, RelativeEpoch, Signature, SignedAggregateAndProof,
-    SignedBeaconBlock, SignedBeaconBlockMerge, SignedBlindedBeaconBlock,
-    SignedContributionAndProof, SignedVoluntaryExit, Slot, SyncCommitteeMessage,
-    SyncContributionData,
+    Attestation, AttesterSlashing, BeaconStateError, BlindedPayload, CommitteeCache,
+    ConfigAndPreset, Epoch, EthSpec, ForkName, FullPayload, Hash256, ProposerPreparationData,
+    ProposerSlashing, RelativeEpoch, Signature, SignedAggregateAndProof, SignedBeaconBlock,
+    SignedBlindedBeaconBlock, SignedBlsToExecutionChange, SignedContributionAndProof,
+    SignedVoluntaryExit, Slot, SyncCommitteeMessage, SyncContributionData,
 };
 use version::{
     add_consensus_version_header, fork_versioned_response, inconsistent_fork_rejection,
@@ -93,6 +114,10 @@ pub struct Context<T: BeaconChainTypes> {
     pub network_tx: Option<UnboundedSender<NetworkMessage<T::EthSpec>>>,
     pub network_globals: Option<Arc<NetworkGlobals<T::EthSpec>>>,
     pub eth1_service: Option<eth1::Service>,
+    pub db_path: Option<PathBuf>,
+    pub freezer_db_path: Option<PathBuf>,
+    /// The levels of the terminal logs, which can be changed via `lighthouse/logging`.
+    pub log_levels: Option<LogLevels>,
     pub log: Logger,
 }
 
@@ -106,6 +131,12 @@ pub struct Config {
     pub serve_legacy_spec: bool,
     pub tls_config: Option<TlsConfig>,
     pub allow_sync_stalled: bool,
+    pub auth: AuthConfig,
+    pub enable_compression: bool,
+    pub max_concurrent_state_replays: usize,
+    pub state_replay_time_budget: Duration,
+    pub rate_limit: Option<RateLimitConfig>,
+    pub serve_finalized_only: bool,
 }
 
 impl Default for Config {
@@ -118,6 +149,12 @@ impl Default for Config {
             serve_legacy_spec: true,
             tls_config: None,
             allow_sync_stalled: false,
+            auth: AuthConfig::default(),
+            enable_compression: true,
+            max_concurrent_state_replays: 2,
+            state_replay_time_budget: Duration::from_secs(60),
+            rate_limit: None,
+            serve_finalized_only: false,
         }
     }
 }
@@ -254,15 +291,31 @@ pub fn serve<T: BeaconChainTypes>(
     let cors_builder = {
         let builder = warp::cors()
             .allow_methods(vec!["GET", "POST"])
-            .allow_headers(vec!["Content-Type"]);
+            .allow_headers(vec!["Content-Type", "Authorization"]);
 
-        warp_utils::cors::set_builder_origins(
+        warp_utils::cors::set_builder_origin_patterns(
             builder,
             config.allow_origin.as_deref(),
             (config.listen_addr, config.listen_port),
         )?
     };
 
+    // Reject requests from origins which don't match any of the configured origin patterns.
+    let origin_filter = warp_utils::cors::origin_filter(config.allow_origin.as_deref());
+
+    // Create filters which restrict access to the validator and admin endpoints.
+    let validator_auth = auth::auth_filter(&config.auth, AuthTier::Validator);
+    let admin_auth = auth::auth_filter(&config.auth, AuthTier::Admin);
+
+    // Create a filter which enforces the per-client request quotas, if configured.
+    let rate_limit = rate_limit::rate_limit_filter(
+        config
+            .rate_limit
+            .clone()
+            .map(|config| Arc::new(rate_limit::RateLimiter::new(config))),
+        &config.auth,
+    );
+
     // Sanity check.
     if !config.enabled {
         crit!(log, "Cannot start disabled HTTP server");
@@ -322,6 +375,30 @@ pub fn serve<T: BeaconChainTypes>(
                 }
             });
 
+    // Create filters which parse state and block IDs from the path, rejecting the IDs of
+    // non-finalized data if the server only serves finalized data.
+    let serve_finalized_only = config.serve_finalized_only;
+    let state_id_or_err = warp::path::param::<StateId>()
+        .or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid state ID".to_string(),
+            ))
+        })
+        .and(chain_filter.clone())
+        .and_then(move |state_id: StateId, chain: Arc<BeaconChain<T>>| {
+            finalized_only::check_state_id(state_id, chain, serve_finalized_only)
+        });
+    let block_id_or_err = warp::path::param::<BlockId>()
+        .or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid block ID".to_string(),
+            ))
+        })
+        .and(chain_filter.clone())
+        .and_then(move |block_id: BlockId, chain: Arc<BeaconChain<T>>| {
+            finalized_only::check_block_id(block_id, chain, serve_finalized_only)
+        });
+
     // Create a `warp` filter that provides access to the network sender channel.
     let inner_ctx = ctx.clone();
     let network_tx_filter = warp::any()
@@ -348,6 +425,19 @@ pub fn serve<T: BeaconChainTypes>(
             }
         });
 
+    // Create a `warp` filter that provides access to the log levels.
+    let inner_ctx = ctx.clone();
+    let log_levels_filter = warp::any()
+        .map(move || inner_ctx.log_levels.clone())
+        .and_then(|log_levels| async move {
+            match log_levels {
+                Some(log_levels) => Ok(log_levels),
+                None => Err(warp_utils::reject::custom_not_found(
+                    "Log levels can't be changed for this logger.".to_string(),
+                )),
+            }
+        });
+
     // Create a `warp` filter that rejects requests whilst the node is syncing.
     let not_while_syncing_filter =
         warp::any()
@@ -393,8 +483,27 @@ pub fn serve<T: BeaconChainTypes>(
             )
             .untuple_one();
 
+    // Create a `warp` filter that rejects expensive requests whilst memory usage is above the
+    // memory watermark.
+    let memory_watermark_filter = warp::any()
+        .and(chain_filter.clone())
+        .and_then(|chain: Arc<BeaconChain<T>>| async move {
+            if chain.memory_watermark_exceeded() {
+                Err(warp_utils::reject::service_unavailable(
+                    "memory usage is above the watermark".to_string(),
+                    Some(MEMORY_WATERMARK_CHECK_INTERVAL),
+                ))
+            } else {
+                Ok(())
+            }
+        })
+        .untuple_one();
+
     // Create a `warp` filter that rejects requests unless the head has been verified by the
     // execution layer.
+    //
+    // An optimistic head results in a 503, as the node is still syncing with the execution layer.
+    // Validator clients should not produce blocks or perform duties on an unverified head.
     let only_with_safe_head = warp::any()
         .and(chain_filter.clone())
         .and_then(move |chain: Arc<BeaconChain<T>>| async move {
@@ -406,12 +515,10 @@ pub fn serve<T: BeaconChainTypes>(
             })?;
             match status {
                 HeadSafetyStatus::Safe(_) => Ok(()),
-                HeadSafetyStatus::Unsafe(hash) => {
-                    Err(warp_utils::reject::custom_server_error(format!(
-                        "optimistic head hash {:?} has not been verified by the execution layer",
-                        hash
-                    )))
-                }
+                HeadSafetyStatus::Unsafe(hash) => Err(warp_utils::reject::not_synced(format!(
+                    "optimistic head hash {:?} has not been verified by the execution layer",
+                    hash
+                ))),
                 HeadSafetyStatus::Invalid(hash) => {
                     Err(warp_utils::reject::custom_server_error(format!(
                         "the head block has an invalid payload {:?}, this may be unrecoverable",
@@ -426,6 +533,21 @@ pub fn serve<T: BeaconChainTypes>(
     let inner_ctx = ctx.clone();
     let log_filter = warp::any().map(move || inner_ctx.log.clone());
 
+    // Create a `warp` filter that provides access to the historical state replay queue.
+    let state_replay_queue = Arc::new(StateReplayQueue::new(
+        config.max_concurrent_state_replays,
+        config.state_replay_time_budget,
+    ));
+    let state_replay_queue_filter = warp::any().map(move || state_replay_queue.clone());
+
+    // Create a `warp` filter that provides access to manually triggered database jobs.
+    let database_jobs = Arc::new(DatabaseJobs::default());
+    let database_jobs_filter = warp::any().map(move || database_jobs.clone());
+
+    // Database paths, used to report the size of the database on disk.
+    let db_path = ctx.db_path.clone();
+    let freezer_db_path = ctx.freezer_db_path.clone();
+
     /*
      *
      * Start of HTTP method definitions.
@@ -459,11 +581,7 @@ pub fn serve<T: BeaconChainTypes>(
     let beacon_states_path = eth1_v1
         .and(warp::path("beacon"))
         .and(warp::path("states"))
-        .and(warp::path::param::<StateId>().or_else(|_| async {
-            Err(warp_utils::reject::custom_bad_request(
-                "Invalid state ID".to_string(),
-            ))
-        }))
+        .and(state_id_or_err.clone())
         .and(chain_filter.clone());
 
     // GET beacon/states/{state_id}/root
@@ -473,10 +591,12 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path::end())
         .and_then(|state_id: StateId, chain: Arc<BeaconChain<T>>| {
             blocking_json_task(move || {
+                let execution_optimistic = state_id.is_execution_optimistic(&chain)?;
                 state_id
                     .root(&chain)
                     .map(api_types::RootData::from)
                     .map(api_types::GenericResponse::from)
+                    .map(|resp| resp.add_execution_optimistic(execution_optimistic))
             })
         });
 
@@ -486,7 +606,15 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("fork"))
         .and(warp::path::end())
         .and_then(|state_id: StateId, chain: Arc<BeaconChain<T>>| {
-            blocking_json_task(move || state_id.fork(&chain).map(api_types::GenericResponse::from))
+            blocking_json_task(move || {
+                state_id.map_state_and_execution_optimistic(
+                    &chain,
+                    |state, execution_optimistic| {
+                        Ok(api_types::GenericResponse::from(state.fork())
+                            .add_execution_optimistic(execution_optimistic))
+                    },
+                )
+            })
         });
 
     // GET beacon/states/{state_id}/finality_checkpoints
@@ -496,15 +624,19 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path::end())
         .and_then(|state_id: StateId, chain: Arc<BeaconChain<T>>| {
             blocking_json_task(move || {
-                state_id
-                    .map_state(&chain, |state| {
-                        Ok(api_types::FinalityCheckpointsData {
-                            previous_justified: state.previous_justified_checkpoint(),
-                            current_justified: state.current_justified_checkpoint(),
-                            finalized: state.finalized_checkpoint(),
-                        })
-                    })
-                    .map(api_types::GenericResponse::from)
+                state_id.map_state_and_execution_optimistic(
+                    &chain,
+                    |state, execution_optimistic| {
+                        Ok(
+                            api_types::GenericResponse::from(api_types::FinalityCheckpointsData {
+                                previous_justified: state.previous_justified_checkpoint(),
+                                current_justified: state.current_justified_checkpoint(),
+                                finalized: state.finalized_checkpoint(),
+                            })
+                            .add_execution_optimistic(execution_optimistic),
+                        )
+                    },
+                )
             })
         });
 
@@ -520,40 +652,26 @@ pub fn serve<T: BeaconChainTypes>(
              query_res: Result<api_types::ValidatorBalancesQuery, warp::Rejection>| {
                 blocking_json_task(move || {
                     let query = query_res?;
-                    state_id
-                        .map_state(&chain, |state| {
-                            Ok(state
-                                .validators()
-                                .iter()
-                                .zip(state.balances().iter())
-                                .enumerate()
-                                // filter by validator id(s) if provided
-                                .filter(|(index, (validator, _))| {
-                                    query.id.as_ref().map_or(true, |ids| {
-                                        ids.iter().any(|id| match id {
-                                            ValidatorId::PublicKey(pubkey) => {
-                                                &validator.pubkey == pubkey
-                                            }
-                                            ValidatorId::Index(param_index) => {
-                                                *param_index == *index as u64
-                                            }
-                                        })
-                                    })
-                                })
-                                .map(|(index, (_, balance))| {
-                                    Some(api_types::ValidatorBalanceData {
-                                        index: index as u64,
-                                        balance: *balance,
-                                    })
-                                })
-                                .collect::<Vec<_>>())
-                        })
-                        .map(api_types::GenericResponse::from)
+                    validators::get_validator_balances(state_id, chain, query.id)
+                })
+            },
+        );
+
+    // POST beacon/states/{state_id}/validator_balances
+    let post_beacon_state_validator_balances = beacon_states_path
+        .clone()
+        .and(warp::path("validator_balances"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and_then(
+            |state_id: StateId, chain: Arc<BeaconChain<T>>, ids: Vec<ValidatorId>| {
+                blocking_json_task(move || {
+                    validators::get_validator_balances(state_id, chain, Some(ids))
                 })
             },
         );
 
-    // GET beacon/states/{state_id}/validators?id,status
+    // GET beacon/states/{state_id}/validators?id,status,offset,limit
     let get_beacon_state_validators = beacon_states_path
         .clone()
         .and(warp::path("validators"))
@@ -563,59 +681,32 @@ pub fn serve<T: BeaconChainTypes>(
             |state_id: StateId,
              chain: Arc<BeaconChain<T>>,
              query_res: Result<api_types::ValidatorsQuery, warp::Rejection>| {
-                blocking_json_task(move || {
+                blocking_task(move || {
                     let query = query_res?;
-                    state_id
-                        .map_state(&chain, |state| {
-                            let epoch = state.current_epoch();
-                            let far_future_epoch = chain.spec.far_future_epoch;
-
-                            Ok(state
-                                .validators()
-                                .iter()
-                                .zip(state.balances().iter())
-                                .enumerate()
-                                // filter by validator id(s) if provided
-                                .filter(|(index, (validator, _))| {
-                                    query.id.as_ref().map_or(true, |ids| {
-                                        ids.iter().any(|id| match id {
-                                            ValidatorId::PublicKey(pubkey) => {
-                                                &validator.pubkey == pubkey
-                                            }
-                                            ValidatorId::Index(param_index) => {
-                                                *param_index == *index as u64
-                                            }
-                                        })
-                                    })
-                                })
-                                // filter by status(es) if provided and map the result
-                                .filter_map(|(index, (validator, balance))| {
-                                    let status = api_types::ValidatorStatus::from_validator(
-                                        validator,
-                                        epoch,
-                                        far_future_epoch,
-                                    );
+                    validators::get_validators(
+                        state_id,
+                        chain,
+                        query.id,
+                        query.status,
+                        query.offset,
+                        query.limit,
+                    )
+                })
+            },
+        );
 
-                                    let status_matches =
-                                        query.status.as_ref().map_or(true, |statuses| {
-                                            statuses.contains(&status)
-                                                || statuses.contains(&status.superstatus())
-                                        });
-
-                                    if status_matches {
-                                        Some(api_types::ValidatorData {
-                                            index: index as u64,
-                                            balance: *balance,
-                                            status,
-                                            validator: validator.clone(),
-                                        })
-                                    } else {
-                                        None
-                                    }
-                                })
-                                .collect::<Vec<_>>())
-                        })
-                        .map(api_types::GenericResponse::from)
+    // POST beacon/states/{state_id}/validators
+    let post_beacon_state_validators = beacon_states_path
+        .clone()
+        .and(warp::path("validators"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and_then(
+            |state_id: StateId,
+             chain: Arc<BeaconChain<T>>,
+             body: api_types::ValidatorsRequestBody| {
+                blocking_task(move || {
+                    validators::get_validators(state_id, chain, body.ids, body.statuses, None, None)
                 })
             },
         );
@@ -633,8 +724,9 @@ pub fn serve<T: BeaconChainTypes>(
         .and_then(
             |state_id: StateId, chain: Arc<BeaconChain<T>>, validator_id: ValidatorId| {
                 blocking_json_task(move || {
-                    state_id
-                        .map_state(&chain, |state| {
+                    state_id.map_state_and_execution_optimistic(
+                        &chain,
+                        |state, execution_optimistic| {
                             let index_opt = match &validator_id {
                                 ValidatorId::PublicKey(pubkey) => {
                                     state.validators().iter().position(|v| v.pubkey == *pubkey)
@@ -660,14 +752,18 @@ pub fn serve<T: BeaconChainTypes>(
                                         validator: validator.clone(),
                                     })
                                 })
+                                .map(|data| {
+                                    api_types::GenericResponse::from(data)
+                                        .add_execution_optimistic(execution_optimistic)
+                                })
                                 .ok_or_else(|| {
                                     warp_utils::reject::custom_not_found(format!(
                                         "unknown validator: {}",
                                         validator_id
                                     ))
                                 })
-                        })
-                        .map(api_types::GenericResponse::from)
+                        },
+                    )
                 })
             },
         );
@@ -686,67 +782,72 @@ pub fn serve<T: BeaconChainTypes>(
                 });
 
                 blocking_json_task(move || {
-                    query_state_id.map_state(&chain, |state| {
-                        let epoch = state.slot().epoch(T::EthSpec::slots_per_epoch());
-
-                        let committee_cache = if state
-                            .committee_cache_is_initialized(RelativeEpoch::Current)
-                        {
-                            state
-                                .committee_cache(RelativeEpoch::Current)
-                                .map(Cow::Borrowed)
-                        } else {
-                            CommitteeCache::initialized(state, epoch, &chain.spec).map(Cow::Owned)
-                        }
-                        .map_err(BeaconChainError::BeaconStateError)
-                        .map_err(warp_utils::reject::beacon_chain_error)?;
-
-                        // Use either the supplied slot or all slots in the epoch.
-                        let slots = query.slot.map(|slot| vec![slot]).unwrap_or_else(|| {
-                            epoch.slot_iter(T::EthSpec::slots_per_epoch()).collect()
-                        });
+                    query_state_id.map_state_and_execution_optimistic(
+                        &chain,
+                        |state, execution_optimistic| {
+                            let epoch = state.slot().epoch(T::EthSpec::slots_per_epoch());
+
+                            let committee_cache =
+                                if state.committee_cache_is_initialized(RelativeEpoch::Current) {
+                                    state
+                                        .committee_cache(RelativeEpoch::Current)
+                                        .map(Cow::Borrowed)
+                                } else {
+                                    CommitteeCache::initialized(state, epoch, &chain.spec)
+                                        .map(Cow::Owned)
+                                }
+                                .map_err(BeaconChainError::BeaconStateError)
+                                .map_err(warp_utils::reject::beacon_chain_error)?;
 
-                        // Use either the supplied committee index or all available indices.
-                        let indices = query.index.map(|index| vec![index]).unwrap_or_else(|| {
-                            (0..committee_cache.committees_per_slot()).collect()
-                        });
+                            // Use either the supplied slot or all slots in the epoch.
+                            let slots = query.slot.map(|slot| vec![slot]).unwrap_or_else(|| {
+                                epoch.slot_iter(T::EthSpec::slots_per_epoch()).collect()
+                            });
 
-                        let mut response = Vec::with_capacity(slots.len() * indices.len());
+                            // Use either the supplied committee index or all available indices.
+                            let indices =
+                                query.index.map(|index| vec![index]).unwrap_or_else(|| {
+                                    (0..committee_cache.committees_per_slot()).collect()
+                                });
 
-                        for slot in slots {
-                            // It is not acceptable to query with a slot that is not within the
-                            // specified epoch.
-                            if slot.epoch(T::EthSpec::slots_per_epoch()) != epoch {
-                                return Err(warp_utils::reject::custom_bad_request(format!(
-                                    "{} is not in epoch {}",
-                                    slot, epoch
-                                )));
-                            }
+                            let mut response = Vec::with_capacity(slots.len() * indices.len());
 
-                            for &index in &indices {
-                                let committee = committee_cache
-                                    .get_beacon_committee(slot, index)
-                                    .ok_or_else(|| {
-                                    warp_utils::reject::custom_bad_request(format!(
-                                        "committee index {} does not exist in epoch {}",
-                                        index, epoch
-                                    ))
-                                })?;
+                            for slot in slots {
+                                // It is not acceptable to query with a slot that is not within the
+                                // specified epoch.
+                                if slot.epoch(T::EthSpec::slots_per_epoch()) != epoch {
+                                    return Err(warp_utils::reject::custom_bad_request(format!(
+                                        "{} is not in epoch {}",
+                                        slot, epoch
+                                    )));
+                                }
 
-                                response.push(api_types::CommitteeData {
-                                    index,
-                                    slot,
-                                    validators: committee
-                                        .committee
-                                        .iter()
-                                        .map(|i| *i as u64)
-                                        .collect(),
-                                });
+                                for &index in &indices {
+                                    let committee = committee_cache
+                                        .get_beacon_committee(slot, index)
+                                        .ok_or_else(|| {
+                                            warp_utils::reject::custom_bad_request(format!(
+                                                "committee index {} does not exist in epoch {}",
+                                                index, epoch
+                                            ))
+                                        })?;
+
+                                    response.push(api_types::CommitteeData {
+                                        index,
+                                        slot,
+                                        validators: committee
+                                            .committee
+                                            .iter()
+                                            .map(|i| *i as u64)
+                                            .collect(),
+                                    });
+                                }
                             }
-                        }
 
-                        Ok(api_types::GenericResponse::from(response))
-                    })
+                            Ok(api_types::GenericResponse::from(response)
+                                .add_execution_optimistic(execution_optimistic))
+                        },
+                    )
                 })
             },
         );
@@ -762,28 +863,32 @@ pub fn serve<T: BeaconChainTypes>(
              chain: Arc<BeaconChain<T>>,
              query: api_types::SyncCommitteesQuery| {
                 blocking_json_task(move || {
-                    let sync_committee = state_id.map_state(&chain, |state| {
-                        let current_epoch = state.current_epoch();
-                        let epoch = query.epoch.unwrap_or(current_epoch);
-                        state
-                            .get_built_sync_committee(epoch, &chain.spec)
-                            .map(|committee| committee.clone())
-                            .map_err(|e| match e {
-                                BeaconStateError::SyncCommitteeNotKnown { .. } => {
-                                    warp_utils::reject::custom_bad_request(format!(
+                    let (sync_committee, execution_optimistic) = state_id
+                        .map_state_and_execution_optimistic(
+                            &chain,
+                            |state, execution_optimistic| {
+                                let current_epoch = state.current_epoch();
+                                let epoch = query.epoch.unwrap_or(current_epoch);
+                                state
+                                    .get_built_sync_committee(epoch, &chain.spec)
+                                    .map(|committee| (committee.clone(), execution_optimistic))
+                                    .map_err(|e| match e {
+                                        BeaconStateError::SyncCommitteeNotKnown { .. } => {
+                                            warp_utils::reject::custom_bad_request(format!(
                                         "state at epoch {} has no sync committee for epoch {}",
                                         current_epoch, epoch
                                     ))
-                                }
-                                BeaconStateError::IncorrectStateVariant => {
-                                    warp_utils::reject::custom_bad_request(format!(
-                                        "state at epoch {} is not activated for Altair",
-                                        current_epoch,
-                                    ))
-                                }
-                                e => warp_utils::reject::beacon_state_error(e),
-                            })
-                    })?;
+                                        }
+                                        BeaconStateError::IncorrectStateVariant => {
+                                            warp_utils::reject::custom_bad_request(format!(
+                                                "state at epoch {} is not activated for Altair",
+                                                current_epoch,
+                                            ))
+                                        }
+                                        e => warp_utils::reject::beacon_state_error(e),
+                                    })
+                            },
+                        )?;
 
                     let validators = chain
                         .validator_indices(sync_committee.pubkeys.iter())
@@ -801,7 +906,43 @@ pub fn serve<T: BeaconChainTypes>(
                         validator_aggregates,
                     };
 
-                    Ok(api_types::GenericResponse::from(response))
+                    Ok(api_types::GenericResponse::from(response)
+                        .add_execution_optimistic(execution_optimistic))
+                })
+            },
+        );
+
+    // GET beacon/deposit_snapshot
+    let get_beacon_deposit_snapshot = eth1_v1
+        .and(warp::path("beacon"))
+        .and(warp::path("deposit_snapshot"))
+        .and(warp::path::end())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
+        .and(eth1_service_filter.clone())
+        .and_then(
+            |accept_header: Option<api_types::Accept>, eth1_service: eth1::Service| {
+                blocking_task(move || {
+                    let snapshot = eth1_service.get_deposit_snapshot().ok_or_else(|| {
+                        warp_utils::reject::custom_not_found(
+                            "no finalized deposit snapshot is available".to_string(),
+                        )
+                    })?;
+                    match accept_header {
+                        Some(api_types::Accept::Ssz) => Response::builder()
+                            .status(200)
+                            .header("Content-Type", "application/octet-stream")
+                            .body(snapshot.as_ssz_bytes().into())
+                            .map_err(|e| {
+                                warp_utils::reject::custom_server_error(format!(
+                                    "failed to create response: {}",
+                                    e
+                                ))
+                            }),
+                        _ => Ok(
+                            warp::reply::json(&api_types::GenericResponse::from(snapshot))
+                                .into_response(),
+                        ),
+                    }
                 })
             },
         );
@@ -820,14 +961,22 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path::end())
         .and(chain_filter.clone())
         .and_then(
-            |query: api_types::HeadersQuery, chain: Arc<BeaconChain<T>>| {
+            move |query: api_types::HeadersQuery, chain: Arc<BeaconChain<T>>| {
                 blocking_json_task(move || {
-                    let (root, block) = match (query.slot, query.parent_root) {
+                    let (root, block, execution_optimistic) = match (query.slot, query.parent_root)
+                    {
                         // No query parameters, return the canonical head block.
-                        (None, None) => chain
-                            .head_beacon_block()
-                            .map_err(warp_utils::reject::beacon_chain_error)
-                            .map(|block| (block.canonical_root(), block.into()))?,
+                        (None, None) => {
+                            let execution_optimistic = chain
+                                .is_optimistic_head()
+                                .map_err(warp_utils::reject::beacon_chain_error)?;
+                            chain
+                                .head_beacon_block()
+                                .map_err(warp_utils::reject::beacon_chain_error)
+                                .map(|block| {
+                                    (block.canonical_root(), block.into(), execution_optimistic)
+                                })?
+                        }
                         // Only the parent root parameter, do a forwards-iterator lookup.
                         (None, Some(parent_root)) => {
                             let parent = BlockId::from_root(parent_root).blinded_block(&chain)?;
@@ -847,15 +996,19 @@ pub fn serve<T: BeaconChainTypes>(
                                     ))
                                 })?;
 
-                            BlockId::from_root(root)
+                            let block_id = BlockId::from_root(root);
+                            let execution_optimistic = block_id.is_execution_optimistic(&chain)?;
+                            block_id
                                 .blinded_block(&chain)
-                                .map(|block| (root, block))?
+                                .map(|block| (root, block, execution_optimistic))?
                         }
                         // Slot is supplied, search by slot and optionally filter by
                         // parent root.
                         (Some(slot), parent_root_opt) => {
                             let root = BlockId::from_slot(slot).root(&chain)?;
-                            let block = BlockId::from_root(root).blinded_block(&chain)?;
+                            let block_id = BlockId::from_root(root);
+                            let execution_optimistic = block_id.is_execution_optimistic(&chain)?;
+                            let block = block_id.blinded_block(&chain)?;
 
                             // If the parent root was supplied, check that it matches the block
                             // obtained via a slot lookup.
@@ -868,10 +1021,17 @@ pub fn serve<T: BeaconChainTypes>(
                                 }
                             }
 
-                            (root, block)
+                            (root, block, execution_optimistic)
                         }
                     };
 
+                    if serve_finalized_only && !BlockId::from_root(root).is_finalized(&chain)? {
+                        return Err(warp_utils::reject::custom_not_found(
+                            "block is not finalized and this node only serves finalized data"
+                                .to_string(),
+                        ));
+                    }
+
                     let data = api_types::BlockHeaderData {
                         root,
                         canonical: true,
@@ -881,7 +1041,8 @@ pub fn serve<T: BeaconChainTypes>(
                         },
                     };
 
-                    Ok(api_types::GenericResponse::from(vec![data]))
+                    Ok(api_types::GenericResponse::from(vec![data])
+                        .add_execution_optimistic(execution_optimistic))
                 })
             },
         );
@@ -890,16 +1051,13 @@ pub fn serve<T: BeaconChainTypes>(
     let get_beacon_headers_block_id = eth1_v1
         .and(warp::path("beacon"))
         .and(warp::path("headers"))
-        .and(warp::path::param::<BlockId>().or_else(|_| async {
-            Err(warp_utils::reject::custom_bad_request(
-                "Invalid block ID".to_string(),
-            ))
-        }))
+        .and(block_id_or_err.clone())
         .and(warp::path::end())
         .and(chain_filter.clone())
         .and_then(|block_id: BlockId, chain: Arc<BeaconChain<T>>| {
             blocking_json_task(move || {
                 let root = block_id.root(&chain)?;
+                let execution_optimistic = block_id.is_execution_optimistic(&chain)?;
                 let block = BlockId::from_root(root).blinded_block(&chain)?;
 
                 let canonical = chain
@@ -916,7 +1074,8 @@ pub fn serve<T: BeaconChainTypes>(
                     },
                 };
 
-                Ok(api_types::GenericResponse::from(data))
+                Ok(api_types::GenericResponse::from(data)
+                    .add_execution_optimistic(execution_optimistic))
             })
         });
 
@@ -924,232 +1083,119 @@ pub fn serve<T: BeaconChainTypes>(
      * beacon/blocks
      */
 
-    // POST beacon/blocks
+    // POST beacon/blocks?broadcast_validation
     let post_beacon_blocks = eth1_v1
         .and(warp::path("beacon"))
         .and(warp::path("blocks"))
         .and(warp::path::end())
+        .and(validator_auth.clone())
+        .and(warp::query::<api_types::BroadcastValidationQuery>())
         .and(warp::body::json())
         .and(chain_filter.clone())
         .and(network_tx_filter.clone())
         .and(log_filter.clone())
         .and_then(
-            |block: SignedBeaconBlock<T::EthSpec>,
+            |query: api_types::BroadcastValidationQuery,
+             block: SignedBeaconBlock<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
              network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
              log: Logger| {
                 blocking_json_task(move || {
-                    let seen_timestamp = timestamp_now();
-
-                    // Send the block, regardless of whether or not it is valid. The API
-                    // specification is very clear that this is the desired behaviour.
-                    publish_pubsub_message(
+                    publish_blocks::publish_block(
+                        block,
+                        query.broadcast_validation,
+                        chain,
                         &network_tx,
-                        PubsubMessage::BeaconBlock(Box::new(block.clone())),
-                    )?;
-
-                    // Determine the delay after the start of the slot, register it with metrics.
-                    let delay =
-                        get_block_delay_ms(seen_timestamp, block.message(), &chain.slot_clock);
-                    metrics::observe_duration(
-                        &metrics::HTTP_API_BLOCK_BROADCAST_DELAY_TIMES,
-                        delay,
-                    );
-
-                    match chain.process_block(block.clone()) {
-                        Ok(root) => {
-                            info!(
-                                log,
-                                "Valid block from HTTP API";
-                                "block_delay" => ?delay,
-                                "root" => format!("{}", root),
-                                "proposer_index" => block.message().proposer_index(),
-                                "slot" => block.slot(),
-                            );
-
-                            // Notify the validator monitor.
-                            chain.validator_monitor.read().register_api_block(
-                                seen_timestamp,
-                                block.message(),
-                                root,
-                                &chain.slot_clock,
-                            );
-
-                            // Update the head since it's likely this block will become the new
-                            // head.
-                            chain
-                                .fork_choice()
-                                .map_err(warp_utils::reject::beacon_chain_error)?;
-
-                            // Perform some logging to inform users if their blocks are being produced
-                            // late.
-                            //
-                            // Check to see the thresholds are non-zero to avoid logging errors with small
-                            // slot times (e.g., during testing)
-                            let crit_threshold = chain.slot_clock.unagg_attestation_production_delay();
-                            let error_threshold = crit_threshold / 2;
-                            if delay >= crit_threshold {
-                                crit!(
-                                    log,
-                                    "Block was broadcast too late";
-                                    "msg" => "system may be overloaded, block likely to be orphaned",
-                                    "delay_ms" => delay.as_millis(),
-                                    "slot" => block.slot(),
-                                    "root" => ?root,
-                                )
-                            } else if delay >= error_threshold  {
-                                error!(
-                                    log,
-                                    "Block broadcast was delayed";
-                                    "msg" => "system may be overloaded, block may be orphaned",
-                                    "delay_ms" => delay.as_millis(),
-                                    "slot" => block.slot(),
-                                    "root" => ?root,
-                                )
-                            }
-
-                            Ok(())
-                        }
-                        Err(e) => {
-                            let msg = format!("{:?}", e);
-                            error!(
-                                log,
-                                "Invalid block provided to HTTP API";
-                                "reason" => &msg
-                            );
-                            Err(warp_utils::reject::broadcast_without_import(msg))
-                        }
-                    }
+                        log,
+                    )
                 })
             },
         );
 
     /*
-     * beacon/blocks
+     * beacon/blinded_blocks
      */
 
-    // POST beacon/blocks
+    // POST beacon/blinded_blocks?broadcast_validation
     let post_beacon_blinded_blocks = eth1_v1
         .and(warp::path("beacon"))
         .and(warp::path("blinded_blocks"))
         .and(warp::path::end())
+        .and(validator_auth.clone())
+        .and(warp::query::<api_types::BroadcastValidationQuery>())
         .and(warp::body::json())
         .and(chain_filter.clone())
         .and(network_tx_filter.clone())
         .and(log_filter.clone())
         .and_then(
-            |block: SignedBeaconBlock<T::EthSpec, BlindedPayload<_>>,
+            |query: api_types::BroadcastValidationQuery,
+             block: SignedBeaconBlock<T::EthSpec, BlindedPayload<_>>,
              chain: Arc<BeaconChain<T>>,
              network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
-             _log: Logger| {
+             log: Logger| {
                 blocking_json_task(move || {
-                    if let Some(el) = chain.execution_layer.as_ref() {
-                        //FIXME(sean): we may not always receive the payload in this response because it
-                        // should be the relay's job to propogate the block. However, since this block is
-                        // already signed and sent this might be ok (so long as the relay validates
-                        // the block before revealing the payload).
-
-                        //FIXME(sean) additionally, this endpoint should serve blocks prior to Bellatrix, and should
-                        // be able to support the normal block proposal flow, because at some point full block endpoints
-                        // will be deprecated from the beacon API. This will entail creating full blocks in
-                        // `validator/blinded_blocks`, caching their payloads, and transforming them into blinded
-                        // blocks. We will access the payload of those blocks here. This flow should happen if the
-                        // execution layer has no payload builders or if we have not yet finalized post-merge transition.
-                        let payload = el
-                            .block_on(|el| el.propose_blinded_beacon_block(&block))
-                            .map_err(|e| {
-                                warp_utils::reject::custom_server_error(format!(
-                                    "proposal failed: {:?}",
-                                    e
-                                ))
-                            })?;
-                        let new_block = SignedBeaconBlock::Merge(SignedBeaconBlockMerge {
-                            message: BeaconBlockMerge {
-                                slot: block.message().slot(),
-                                proposer_index: block.message().proposer_index(),
-                                parent_root: block.message().parent_root(),
-                                state_root: block.message().state_root(),
-                                body: BeaconBlockBodyMerge {
-                                    randao_reveal: block.message().body().randao_reveal().clone(),
-                                    eth1_data: block.message().body().eth1_data().clone(),
-                                    graffiti: *block.message().body().graffiti(),
-                                    proposer_slashings: block
-                                        .message()
-                                        .body()
-                                        .proposer_slashings()
-                                        .clone(),
-                                    attester_slashings: block
-                                        .message()
-                                        .body()
-                                        .attester_slashings()
-                                        .clone(),
-                                    attestations: block.message().body().attestations().clone(),
-                                    deposits: block.message().body().deposits().clone(),
-                                    voluntary_exits: block
-                                        .message()
-                                        .body()
-                                        .voluntary_exits()
-                                        .clone(),
-                                    sync_aggregate: block
-                                        .message()
-                                        .body()
-                                        .sync_aggregate()
-                                        .unwrap()
-                                        .clone(),
-                                    execution_payload: payload.into(),
-                                },
-                            },
-                            signature: block.signature().clone(),
-                        });
-
-                        // Send the block, regardless of whether or not it is valid. The API
-                        // specification is very clear that this is the desired behaviour.
-                        publish_pubsub_message(
-                            &network_tx,
-                            PubsubMessage::BeaconBlock(Box::new(new_block.clone())),
-                        )?;
-
-                        match chain.process_block(new_block) {
-                            Ok(_) => {
-                                // Update the head since it's likely this block will become the new
-                                // head.
-                                chain
-                                    .fork_choice()
-                                    .map_err(warp_utils::reject::beacon_chain_error)?;
-
-                                Ok(())
-                            }
-                            Err(e) => {
-                                let msg = format!("{:?}", e);
-
-                                Err(warp_utils::reject::broadcast_without_import(msg))
-                            }
-                        }
-                    } else {
-                        Err(warp_utils::reject::custom_server_error(
-                            "no execution layer found".to_string(),
-                        ))
-                    }
+                    publish_blocks::publish_blinded_block(
+                        block,
+                        query.broadcast_validation,
+                        chain,
+                        &network_tx,
+                        log,
+                    )
                 })
             },
         );
 
-    let block_id_or_err = warp::path::param::<BlockId>().or_else(|_| async {
-        Err(warp_utils::reject::custom_bad_request(
-            "Invalid block ID".to_string(),
-        ))
-    });
+    // GET beacon/light_client/bootstrap/{block_root}
+    let get_beacon_light_client_bootstrap = eth1_v1
+        .and(warp::path("beacon"))
+        .and(warp::path("light_client"))
+        .and(warp::path("bootstrap"))
+        .and(warp::path::param::<Hash256>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid block root".to_string(),
+            ))
+        }))
+        .and(warp::path::end())
+        .and(chain_filter.clone())
+        .and_then(|block_root: Hash256, chain: Arc<BeaconChain<T>>| {
+            blocking_task(move || {
+                let bootstrap = chain
+                    .store
+                    .get_light_client_bootstrap(&block_root)
+                    .map_err(|e| {
+                        warp_utils::reject::beacon_chain_error(BeaconChainError::DBError(e))
+                    })?
+                    .ok_or_else(|| {
+                        warp_utils::reject::custom_not_found(format!(
+                            "no light client bootstrap for block {:?}",
+                            block_root
+                        ))
+                    })?;
+                let fork_name = chain
+                    .spec
+                    .fork_name_at_slot::<T::EthSpec>(bootstrap.header.slot);
+                let res = api_types::ForkVersionedResponse {
+                    version: Some(fork_name),
+                    execution_optimistic: None,
+                    data: bootstrap,
+                };
+                Ok(add_consensus_version_header(
+                    warp::reply::json(&res),
+                    fork_name,
+                ))
+            })
+        });
 
     let beacon_blocks_path_v1 = eth1_v1
         .and(warp::path("beacon"))
         .and(warp::path("blocks"))
-        .and(block_id_or_err)
+        .and(block_id_or_err.clone())
         .and(chain_filter.clone());
 
     let beacon_blocks_path_any = any_version
         .and(warp::path("beacon"))
         .and(warp::path("blocks"))
-        .and(block_id_or_err)
+        .and(block_id_or_err.clone())
         .and(chain_filter.clone());
 
     // GET beacon/blocks/{block_id}
@@ -1163,6 +1209,7 @@ pub fn serve<T: BeaconChainTypes>(
              chain: Arc<BeaconChain<T>>,
              accept_header: Option<api_types::Accept>| {
                 async move {
+                    let execution_optimistic = block_id.is_execution_optimistic(&chain)?;
                     let block = block_id.full_block(&chain).await?;
                     let fork_name = block
                         .fork_name(&chain.spec)
@@ -1178,8 +1225,13 @@ pub fn serve<T: BeaconChainTypes>(
                                     e
                                 ))
                             }),
-                        _ => fork_versioned_response(endpoint_version, fork_name, block)
-                            .map(|res| warp::reply::json(&res).into_response()),
+                        _ => fork_versioned_response(
+                            endpoint_version,
+                            fork_name,
+                            Some(execution_optimistic),
+                            block,
+                        )
+                        .map(|res| warp::reply::json(&res).into_response()),
                     }
                     .map(|resp| add_consensus_version_header(resp, fork_name))
                 }
@@ -1193,10 +1245,12 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path::end())
         .and_then(|block_id: BlockId, chain: Arc<BeaconChain<T>>| {
             blocking_json_task(move || {
+                let execution_optimistic = block_id.is_execution_optimistic(&chain)?;
                 block_id
                     .root(&chain)
                     .map(api_types::RootData::from)
                     .map(api_types::GenericResponse::from)
+                    .map(|resp| resp.add_execution_optimistic(execution_optimistic))
             })
         });
 
@@ -1207,10 +1261,12 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path::end())
         .and_then(|block_id: BlockId, chain: Arc<BeaconChain<T>>| {
             blocking_json_task(move || {
+                let execution_optimistic = block_id.is_execution_optimistic(&chain)?;
                 block_id
                     .blinded_block(&chain)
                     .map(|block| block.message().body().attestations().clone())
                     .map(api_types::GenericResponse::from)
+                    .map(|resp| resp.add_execution_optimistic(execution_optimistic))
             })
         });
 
@@ -1228,6 +1284,7 @@ pub fn serve<T: BeaconChainTypes>(
         .clone()
         .and(warp::path("attestations"))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(warp::body::json())
         .and(network_tx_filter.clone())
         .and(log_filter.clone())
@@ -1360,6 +1417,7 @@ pub fn serve<T: BeaconChainTypes>(
         .clone()
         .and(warp::path("attester_slashings"))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(warp::body::json())
         .and(network_tx_filter.clone())
         .and_then(
@@ -1417,6 +1475,7 @@ pub fn serve<T: BeaconChainTypes>(
         .clone()
         .and(warp::path("proposer_slashings"))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(warp::body::json())
         .and(network_tx_filter.clone())
         .and_then(
@@ -1472,6 +1531,7 @@ pub fn serve<T: BeaconChainTypes>(
         .clone()
         .and(warp::path("voluntary_exits"))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(warp::body::json())
         .and(network_tx_filter.clone())
         .and_then(
@@ -1520,28 +1580,119 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
-    // POST beacon/pool/sync_committees
-    let post_beacon_pool_sync_committees = beacon_pool_path
+    // POST beacon/pool/bls_to_execution_changes
+    let post_beacon_pool_bls_to_execution_changes = beacon_pool_path
         .clone()
-        .and(warp::path("sync_committees"))
+        .and(warp::path("bls_to_execution_changes"))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(warp::body::json())
         .and(network_tx_filter.clone())
-        .and(log_filter.clone())
         .and_then(
             |chain: Arc<BeaconChain<T>>,
-             signatures: Vec<SyncCommitteeMessage>,
-             network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
-             log: Logger| {
+             address_changes: Vec<SignedBlsToExecutionChange>,
+             network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>| {
                 blocking_json_task(move || {
-                    sync_committees::process_sync_committee_signatures(
-                        signatures, network_tx, &chain, log,
-                    )?;
+                    let mut failures = vec![];
+
+                    for (index, address_change) in address_changes.into_iter().enumerate() {
+                        match chain.verify_bls_to_execution_change_for_gossip(address_change) {
+                            Ok(ObservationOutcome::New(verified_address_change)) => {
+                                publish_pubsub_message(
+                                    &network_tx,
+                                    PubsubMessage::BlsToExecutionChange(Box::new(
+                                        verified_address_change.clone().into_inner(),
+                                    )),
+                                )?;
+
+                                chain.import_bls_to_execution_change(verified_address_change);
+                            }
+                            Ok(ObservationOutcome::AlreadyKnown) => (),
+                            Err(e) => failures.push(api_types::Failure::new(
+                                index,
+                                format!("Verification: {:?}", e),
+                            )),
+                        }
+                    }
+
+                    if failures.is_empty() {
+                        Ok(())
+                    } else {
+                        Err(warp_utils::reject::indexed_bad_request(
+                            "error processing BLS to execution changes".to_string(),
+                            failures,
+                        ))
+                    }
+                })
+            },
+        );
+
+    // GET beacon/pool/bls_to_execution_changes
+    let get_beacon_pool_bls_to_execution_changes = beacon_pool_path
+        .clone()
+        .and(warp::path("bls_to_execution_changes"))
+        .and(warp::path::end())
+        .and_then(|chain: Arc<BeaconChain<T>>| {
+            blocking_json_task(move || {
+                let address_changes = chain.op_pool.get_all_bls_to_execution_changes();
+                Ok(api_types::GenericResponse::from(address_changes))
+            })
+        });
+
+    // POST beacon/pool/sync_committees
+    let post_beacon_pool_sync_committees = beacon_pool_path
+        .clone()
+        .and(warp::path("sync_committees"))
+        .and(warp::path::end())
+        .and(validator_auth.clone())
+        .and(warp::body::json())
+        .and(network_tx_filter.clone())
+        .and(log_filter.clone())
+        .and_then(
+            |chain: Arc<BeaconChain<T>>,
+             signatures: Vec<SyncCommitteeMessage>,
+             network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
+             log: Logger| {
+                blocking_json_task(move || {
+                    sync_committees::process_sync_committee_signatures(
+                        signatures, network_tx, &chain, log,
+                    )?;
                     Ok(api_types::GenericResponse::from(()))
                 })
             },
         );
 
+    /*
+     * builder/states/{state_id}
+     */
+
+    // GET builder/states/{state_id}/expected_withdrawals
+    let get_expected_withdrawals = eth1_v1
+        .and(warp::path("builder"))
+        .and(warp::path("states"))
+        .and(state_id_or_err.clone())
+        .and(warp::path("expected_withdrawals"))
+        .and(warp::path::end())
+        .and(warp::query::<api_types::ExpectedWithdrawalsQuery>())
+        .and(chain_filter.clone())
+        .and_then(
+            |state_id: StateId,
+             query: api_types::ExpectedWithdrawalsQuery,
+             chain: Arc<BeaconChain<T>>| {
+                blocking_json_task(move || {
+                    state_id
+                        .map_state(&chain, |state| {
+                            expected_withdrawals::get_expected_withdrawals(
+                                state,
+                                query.proposal_slot,
+                                &chain.spec,
+                            )
+                        })
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
     /*
      * config
      */
@@ -1605,47 +1756,59 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("debug"))
         .and(warp::path("beacon"))
         .and(warp::path("states"))
-        .and(warp::path::param::<StateId>().or_else(|_| async {
-            Err(warp_utils::reject::custom_bad_request(
-                "Invalid state ID".to_string(),
-            ))
-        }))
+        .and(state_id_or_err.clone())
         .and(warp::path::end())
+        .and(admin_auth.clone())
         .and(warp::header::optional::<api_types::Accept>("accept"))
         .and(chain_filter.clone())
+        .and(state_replay_queue_filter)
         .and_then(
             |endpoint_version: EndpointVersion,
              state_id: StateId,
              accept_header: Option<api_types::Accept>,
-             chain: Arc<BeaconChain<T>>| {
+             chain: Arc<BeaconChain<T>>,
+             state_replay_queue: Arc<StateReplayQueue>| {
                 blocking_task(move || match accept_header {
-                    Some(api_types::Accept::Ssz) => {
-                        let state = state_id.state(&chain)?;
-                        let fork_name = state
-                            .fork_name(&chain.spec)
-                            .map_err(inconsistent_fork_rejection)?;
-                        Response::builder()
-                            .status(200)
-                            .header("Content-Type", "application/octet-stream")
-                            .body(state.as_ssz_bytes().into())
-                            .map(|resp| add_consensus_version_header(resp, fork_name))
-                            .map_err(|e| {
-                                warp_utils::reject::custom_server_error(format!(
-                                    "failed to create response: {}",
-                                    e
-                                ))
-                            })
-                    }
-                    _ => state_id.map_state(&chain, |state| {
-                        let fork_name = state
-                            .fork_name(&chain.spec)
-                            .map_err(inconsistent_fork_rejection)?;
-                        let res = fork_versioned_response(endpoint_version, fork_name, &state)?;
-                        Ok(add_consensus_version_header(
-                            warp::reply::json(&res).into_response(),
-                            fork_name,
-                        ))
-                    }),
+                    Some(api_types::Accept::Ssz) => state_id
+                        .map_state_and_execution_optimistic_with_replay_queue(
+                            &chain,
+                            &state_replay_queue,
+                            |state, _| {
+                                let fork_name = state
+                                    .fork_name(&chain.spec)
+                                    .map_err(inconsistent_fork_rejection)?;
+                                Response::builder()
+                                    .status(200)
+                                    .header("Content-Type", "application/octet-stream")
+                                    .body(state.as_ssz_bytes().into())
+                                    .map(|resp| add_consensus_version_header(resp, fork_name))
+                                    .map_err(|e| {
+                                        warp_utils::reject::custom_server_error(format!(
+                                            "failed to create response: {}",
+                                            e
+                                        ))
+                                    })
+                            },
+                        ),
+                    _ => state_id.map_state_and_execution_optimistic_with_replay_queue(
+                        &chain,
+                        &state_replay_queue,
+                        |state, execution_optimistic| {
+                            let fork_name = state
+                                .fork_name(&chain.spec)
+                                .map_err(inconsistent_fork_rejection)?;
+                            let res = fork_versioned_response(
+                                endpoint_version,
+                                fork_name,
+                                Some(execution_optimistic),
+                                &state,
+                            )?;
+                            Ok(add_consensus_version_header(
+                                warp::reply::json(&res).into_response(),
+                                fork_name,
+                            ))
+                        },
+                    ),
                 })
             },
         );
@@ -1662,8 +1825,17 @@ pub fn serve<T: BeaconChainTypes>(
                 let heads = chain
                     .heads()
                     .into_iter()
-                    .map(|(root, slot)| api_types::ChainHeadData { slot, root })
-                    .collect::<Vec<_>>();
+                    .map(|(root, slot)| {
+                        let execution_optimistic = chain
+                            .is_optimistic_block(&root)
+                            .map_err(warp_utils::reject::beacon_chain_error)?;
+                        Ok(api_types::ChainHeadData {
+                            slot,
+                            root,
+                            execution_optimistic: Some(execution_optimistic),
+                        })
+                    })
+                    .collect::<Result<Vec<_>, warp::Rejection>>()?;
                 Ok(api_types::GenericResponse::from(heads))
             })
         });
@@ -1744,8 +1916,26 @@ pub fn serve<T: BeaconChainTypes>(
                     // Taking advantage of saturating subtraction on slot.
                     let sync_distance = current_slot - head_slot;
 
+                    let is_optimistic = chain
+                        .is_optimistic_head()
+                        .map_err(warp_utils::reject::beacon_chain_error)?;
+
+                    let el_offline = chain
+                        .execution_layer
+                        .as_ref()
+                        .map(|el| el.block_on_generic(|el| el.is_offline()))
+                        .transpose()
+                        .map_err(|e| {
+                            warp_utils::reject::custom_server_error(format!(
+                                "unable to check the execution engine: {:?}",
+                                e
+                            ))
+                        })?;
+
                     let syncing_data = api_types::SyncingData {
                         is_syncing: network_globals.sync_state.read().is_syncing(),
+                        is_optimistic: Some(is_optimistic),
+                        el_offline,
                         head_slot,
                         sync_distance,
                     };
@@ -1958,7 +2148,9 @@ pub fn serve<T: BeaconChainTypes>(
             ))
         }))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(not_while_syncing_filter.clone())
+        .and(only_with_safe_head.clone())
         .and(chain_filter.clone())
         .and(log_filter.clone())
         .and_then(|epoch: Epoch, chain: Arc<BeaconChain<T>>, log: Logger| {
@@ -1975,7 +2167,9 @@ pub fn serve<T: BeaconChainTypes>(
             ))
         }))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(not_while_syncing_filter.clone())
+        .and(only_with_safe_head.clone())
         .and(warp::query::<api_types::ValidatorBlocksQuery>())
         .and(chain_filter.clone())
         .and_then(
@@ -2022,7 +2216,7 @@ pub fn serve<T: BeaconChainTypes>(
                         .to_ref()
                         .fork_name(&chain.spec)
                         .map_err(inconsistent_fork_rejection)?;
-                    fork_versioned_response(endpoint_version, fork_name, block)
+                    fork_versioned_response(endpoint_version, fork_name, None, block)
                 })
             },
         );
@@ -2037,7 +2231,9 @@ pub fn serve<T: BeaconChainTypes>(
             ))
         }))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(not_while_syncing_filter.clone())
+        .and(only_with_safe_head.clone())
         .and(warp::query::<api_types::ValidatorBlocksQuery>())
         .and(chain_filter.clone())
         .and_then(
@@ -2084,7 +2280,7 @@ pub fn serve<T: BeaconChainTypes>(
                         .to_ref()
                         .fork_name(&chain.spec)
                         .map_err(inconsistent_fork_rejection)?;
-                    fork_versioned_response(endpoint_version, fork_name, block)
+                    fork_versioned_response(endpoint_version, fork_name, None, block)
                 })
             },
         );
@@ -2094,6 +2290,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("validator"))
         .and(warp::path("attestation_data"))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(warp::query::<api_types::ValidatorAttestationDataQuery>())
         .and(not_while_syncing_filter.clone())
         .and(only_with_safe_head.clone())
@@ -2127,6 +2324,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("validator"))
         .and(warp::path("aggregate_attestation"))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(warp::query::<api_types::ValidatorAggregateAttestationQuery>())
         .and(not_while_syncing_filter.clone())
         .and(only_with_safe_head.clone())
@@ -2166,7 +2364,9 @@ pub fn serve<T: BeaconChainTypes>(
             ))
         }))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(not_while_syncing_filter.clone())
+        .and(only_with_safe_head.clone())
         .and(warp::body::json())
         .and(chain_filter.clone())
         .and_then(
@@ -2188,7 +2388,9 @@ pub fn serve<T: BeaconChainTypes>(
             ))
         }))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(not_while_syncing_filter.clone())
+        .and(only_with_safe_head.clone())
         .and(warp::body::json())
         .and(chain_filter.clone())
         .and_then(
@@ -2204,6 +2406,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("validator"))
         .and(warp::path("sync_committee_contribution"))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(warp::query::<SyncContributionData>())
         .and(not_while_syncing_filter.clone())
         .and(only_with_safe_head)
@@ -2228,6 +2431,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("validator"))
         .and(warp::path("aggregate_and_proofs"))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(not_while_syncing_filter.clone())
         .and(chain_filter.clone())
         .and(warp::body::json())
@@ -2328,6 +2532,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("validator"))
         .and(warp::path("contribution_and_proofs"))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(not_while_syncing_filter.clone())
         .and(chain_filter.clone())
         .and(warp::body::json())
@@ -2355,6 +2560,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("validator"))
         .and(warp::path("beacon_committee_subscriptions"))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(warp::body::json())
         .and(network_tx_filter.clone())
         .and(chain_filter.clone())
@@ -2395,6 +2601,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("validator"))
         .and(warp::path("prepare_beacon_proposer"))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(not_while_syncing_filter.clone())
         .and(chain_filter.clone())
         .and(warp::addr::remote())
@@ -2449,6 +2656,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("validator"))
         .and(warp::path("sync_committee_subscriptions"))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(warp::body::json())
         .and(network_tx_filter)
         .and(chain_filter.clone())
@@ -2480,6 +2688,7 @@ pub fn serve<T: BeaconChainTypes>(
     let post_lighthouse_liveness = warp::path("lighthouse")
         .and(warp::path("liveness"))
         .and(warp::path::end())
+        .and(validator_auth.clone())
         .and(warp::body::json())
         .and(chain_filter.clone())
         .and_then(
@@ -2519,6 +2728,70 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/validator_monitor
+    let get_lighthouse_validator_monitor = warp::path("lighthouse")
+        .and(warp::path("validator_monitor"))
+        .and(warp::path::end())
+        .and(validator_auth.clone())
+        .and(chain_filter.clone())
+        .and_then(|chain: Arc<BeaconChain<T>>| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(
+                    chain.validator_monitor.read().validator_summaries(),
+                ))
+            })
+        });
+
+    // POST lighthouse/validator_monitor
+    let post_lighthouse_validator_monitor = warp::path("lighthouse")
+        .and(warp::path("validator_monitor"))
+        .and(warp::path::end())
+        .and(validator_auth.clone())
+        .and(warp::body::json())
+        .and(chain_filter.clone())
+        .and_then(
+            |request: eth2::lighthouse::ValidatorMonitorRegistrationRequest,
+             chain: Arc<BeaconChain<T>>| {
+                blocking_json_task(move || {
+                    let validator_indices = request
+                        .pubkeys
+                        .iter()
+                        .map(|pubkey| {
+                            chain
+                                .validator_index(pubkey)
+                                .map(|index| index.map(|index| index as u64))
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(warp_utils::reject::beacon_chain_error)?;
+
+                    let mut validator_monitor = chain.validator_monitor.write();
+                    if !validator_monitor.auto_register() {
+                        return Err(warp_utils::reject::custom_bad_request(
+                            "automatic registration is disabled, see --validator-monitor-auto"
+                                .to_string(),
+                        ));
+                    }
+
+                    let newly_monitored = request
+                        .pubkeys
+                        .into_iter()
+                        .zip(validator_indices)
+                        .filter(|(pubkey, validator_index)| {
+                            validator_monitor
+                                .auto_register_local_validator_pubkey(*pubkey, *validator_index)
+                        })
+                        .count();
+
+                    Ok(api_types::GenericResponse::from(
+                        eth2::lighthouse::ValidatorMonitorRegistration {
+                            newly_monitored: newly_monitored as u64,
+                            num_monitored: validator_monitor.num_validators() as u64,
+                        },
+                    ))
+                })
+            },
+        );
+
     // GET lighthouse/health
     let get_lighthouse_health = warp::path("lighthouse")
         .and(warp::path("health"))
@@ -2531,6 +2804,21 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/system
+    let get_lighthouse_system = warp::path("lighthouse")
+        .and(warp::path("system"))
+        .and(warp::path::end())
+        .and(admin_auth.clone())
+        .and(chain_filter.clone())
+        .and_then(move |chain: Arc<BeaconChain<T>>| {
+            let db_path = db_path.clone();
+            let freezer_db_path = freezer_db_path.clone();
+            blocking_json_task(move || {
+                system::resources(chain, db_path, freezer_db_path)
+                    .map(api_types::GenericResponse::from)
+            })
+        });
+
     // GET lighthouse/syncing
     let get_lighthouse_syncing = warp::path("lighthouse")
         .and(warp::path("syncing"))
@@ -2544,6 +2832,46 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/sync/lookups
+    let get_lighthouse_sync_lookups = warp::path("lighthouse")
+        .and(warp::path("sync"))
+        .and(warp::path("lookups"))
+        .and(warp::path::end())
+        .and(network_globals.clone())
+        .and_then(|network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(
+                    network_globals.sync_lookups.read().clone(),
+                ))
+            })
+        });
+
+    // GET lighthouse/ready
+    let get_lighthouse_ready = warp::path("lighthouse")
+        .and(warp::path("ready"))
+        .and(warp::path::end())
+        .and(chain_filter.clone())
+        .and_then(|chain: Arc<BeaconChain<T>>| async move {
+            let readiness = chain.check_fork_readiness().await;
+            Ok::<_, warp::Rejection>(warp::reply::json(&api_types::GenericResponse::from(
+                readiness,
+            )))
+        });
+
+    // GET lighthouse/beacon_processor/queues
+    let get_lighthouse_beacon_processor_queues = warp::path("lighthouse")
+        .and(warp::path("beacon_processor"))
+        .and(warp::path("queues"))
+        .and(warp::path::end())
+        .and(network_globals.clone())
+        .and_then(|network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(
+                    network_globals.beacon_processor_queues.read().clone(),
+                ))
+            })
+        });
+
     // GET lighthouse/nat
     let get_lighthouse_nat = warp::path("lighthouse")
         .and(warp::path("nat"))
@@ -2560,6 +2888,73 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/network/port_mappings
+    let get_lighthouse_network_port_mappings = warp::path("lighthouse")
+        .and(warp::path("network"))
+        .and(warp::path("port_mappings"))
+        .and(warp::path::end())
+        .and(network_globals.clone())
+        .and_then(|network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(
+                    network_globals.port_mappings.read().clone(),
+                ))
+            })
+        });
+
+    // GET lighthouse/network/clock_skew
+    let get_lighthouse_network_clock_skew = warp::path("lighthouse")
+        .and(warp::path("network"))
+        .and(warp::path("clock_skew"))
+        .and(warp::path::end())
+        .and(network_globals.clone())
+        .and_then(|network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(
+                    network_globals.clock_skew_samples.read().status(),
+                ))
+            })
+        });
+
+    // POST lighthouse/enr
+    let post_lighthouse_enr = warp::path("lighthouse")
+        .and(warp::path("enr"))
+        .and(warp::path::end())
+        .and(admin_auth.clone())
+        .and(warp::body::json())
+        .and(network_tx_filter.clone())
+        .and_then(
+            |request: eth2::lighthouse::EnrUpdateRequest,
+             network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>| async move {
+                let update = EnrUpdate {
+                    ip: request.ip,
+                    tcp_port: request.tcp_port,
+                    udp_port: request.udp_port,
+                    custom_fields: request
+                        .custom_fields
+                        .into_iter()
+                        .map(|field| (field.key, field.value))
+                        .collect(),
+                };
+                let (result_tx, result_rx) = oneshot::channel();
+                publish_network_message(
+                    &network_tx,
+                    NetworkMessage::UpdateEnr { update, result_tx },
+                )?;
+
+                let enr = result_rx
+                    .await
+                    .map_err(|_| {
+                        warp_utils::reject::custom_server_error(
+                            "network service did not respond".to_string(),
+                        )
+                    })?
+                    .map_err(warp_utils::reject::custom_bad_request)?;
+
+                Ok::<_, warp::Rejection>(warp::reply::json(&api_types::GenericResponse::from(enr)))
+            },
+        );
+
     // GET lighthouse/peers
     let get_lighthouse_peers = warp::path("lighthouse")
         .and(warp::path("peers"))
@@ -2571,10 +2966,7 @@ pub fn serve<T: BeaconChainTypes>(
                     .peers
                     .read()
                     .peers()
-                    .map(|(peer_id, peer_info)| eth2::lighthouse::Peer {
-                        peer_id: peer_id.to_string(),
-                        peer_info: peer_info.clone(),
-                    })
+                    .map(|(peer_id, peer_info)| api_peer(peer_id, peer_info))
                     .collect::<Vec<_>>())
             })
         });
@@ -2584,21 +2976,74 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("peers"))
         .and(warp::path("connected"))
         .and(warp::path::end())
-        .and(network_globals)
+        .and(network_globals.clone())
         .and_then(|network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
             blocking_json_task(move || {
                 Ok(network_globals
                     .peers
                     .read()
                     .connected_peers()
-                    .map(|(peer_id, peer_info)| eth2::lighthouse::Peer {
-                        peer_id: peer_id.to_string(),
-                        peer_info: peer_info.clone(),
-                    })
+                    .map(|(peer_id, peer_info)| api_peer(peer_id, peer_info))
                     .collect::<Vec<_>>())
             })
         });
 
+    // GET lighthouse/peers/diversity
+    let get_lighthouse_peers_diversity = warp::path("lighthouse")
+        .and(warp::path("peers"))
+        .and(warp::path("diversity"))
+        .and(warp::path::end())
+        .and(network_globals.clone())
+        .and_then(|network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(
+                    network_globals.peer_diversity.read().clone(),
+                ))
+            })
+        });
+
+    // GET lighthouse/gossipsub/scores
+    let get_lighthouse_gossipsub_scores = warp::path("lighthouse")
+        .and(warp::path("gossipsub"))
+        .and(warp::path("scores"))
+        .and(warp::path::end())
+        .and(admin_auth.clone())
+        .and(network_globals.clone())
+        .and_then(|network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(
+                    network_globals.gossipsub_scores.read().clone(),
+                ))
+            })
+        });
+
+    // GET lighthouse/peers/verbose
+    let get_lighthouse_peers_verbose = warp::path("lighthouse")
+        .and(warp::path("peers"))
+        .and(warp::path("verbose"))
+        .and(warp::path::end())
+        .and(network_globals)
+        .and(chain_filter.clone())
+        .and_then(
+            |network_globals: Arc<NetworkGlobals<T::EthSpec>>, chain: Arc<BeaconChain<T>>| {
+                blocking_json_task(move || {
+                    let head_slot = chain
+                        .best_slot()
+                        .map_err(warp_utils::reject::beacon_chain_error)?;
+                    Ok(api_types::GenericResponse::from(
+                        network_globals
+                            .peers
+                            .read()
+                            .peers()
+                            .map(|(peer_id, peer_info)| {
+                                peers::verbose_peer_data(peer_id, peer_info, head_slot)
+                            })
+                            .collect::<Vec<_>>(),
+                    ))
+                })
+            },
+        );
+
     // GET lighthouse/proto_array
     let get_lighthouse_proto_array = warp::path("lighthouse")
         .and(warp::path("proto_array"))
@@ -2714,13 +3159,48 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/naive_aggregation_pool?slot,committee_index
+    let get_lighthouse_naive_aggregation_pool = warp::path("lighthouse")
+        .and(warp::path("naive_aggregation_pool"))
+        .and(warp::path::end())
+        .and(warp::query::<api_types::AttestationPoolQuery>())
+        .and(chain_filter.clone())
+        .and_then(
+            |query: api_types::AttestationPoolQuery, chain: Arc<BeaconChain<T>>| {
+                blocking_json_task(move || {
+                    Ok(api_types::GenericResponse::from(
+                        attestation_pools::naive_aggregation_pool(chain, query),
+                    ))
+                })
+            },
+        );
+
+    // GET lighthouse/op_pool/attestations?slot,committee_index
+    let get_lighthouse_op_pool_attestations = warp::path("lighthouse")
+        .and(warp::path("op_pool"))
+        .and(warp::path("attestations"))
+        .and(warp::path::end())
+        .and(admin_auth.clone())
+        .and(warp::query::<api_types::AttestationPoolQuery>())
+        .and(chain_filter.clone())
+        .and_then(
+            |query: api_types::AttestationPoolQuery, chain: Arc<BeaconChain<T>>| {
+                blocking_json_task(move || {
+                    attestation_pools::op_pool_attestations(chain, query)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
     // GET lighthouse/beacon/states/{state_id}/ssz
     let get_lighthouse_beacon_states_ssz = warp::path("lighthouse")
         .and(warp::path("beacon"))
         .and(warp::path("states"))
-        .and(warp::path::param::<StateId>())
+        .and(state_id_or_err.clone())
         .and(warp::path("ssz"))
         .and(warp::path::end())
+        .and(admin_auth.clone())
+        .and(memory_watermark_filter.clone())
         .and(chain_filter.clone())
         .and_then(|state_id: StateId, chain: Arc<BeaconChain<T>>| {
             blocking_task(move || {
@@ -2738,6 +3218,51 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/beacon/block_roots?start_slot,count
+    let get_lighthouse_beacon_block_roots = warp::path("lighthouse")
+        .and(warp::path("beacon"))
+        .and(warp::path("block_roots"))
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::BlockRootsQuery>())
+        .and(chain_filter.clone())
+        .and_then(|query, chain: Arc<BeaconChain<T>>| {
+            blocking_json_task(move || {
+                block_roots::get_block_roots(query, chain).map(api_types::GenericResponse::from)
+            })
+        });
+
+    // GET lighthouse/beacon/proofs/states/{state_id}?gindex
+    let get_lighthouse_beacon_proofs_state = warp::path("lighthouse")
+        .and(warp::path("beacon"))
+        .and(warp::path("proofs"))
+        .and(warp::path("states"))
+        .and(state_id_or_err.clone())
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::MerkleProofQuery>())
+        .and(chain_filter.clone())
+        .and_then(|state_id, query, chain: Arc<BeaconChain<T>>| {
+            blocking_json_task(move || {
+                proofs::get_state_proof(state_id, query, chain)
+                    .map(api_types::GenericResponse::from)
+            })
+        });
+
+    // GET lighthouse/beacon/proofs/blocks/{block_id}?gindex
+    let get_lighthouse_beacon_proofs_block = warp::path("lighthouse")
+        .and(warp::path("beacon"))
+        .and(warp::path("proofs"))
+        .and(warp::path("blocks"))
+        .and(block_id_or_err.clone())
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::MerkleProofQuery>())
+        .and(chain_filter.clone())
+        .and_then(|block_id, query, chain: Arc<BeaconChain<T>>| {
+            blocking_json_task(move || {
+                proofs::get_block_proof(block_id, query, chain)
+                    .map(api_types::GenericResponse::from)
+            })
+        });
+
     // GET lighthouse/staking
     let get_lighthouse_staking = warp::path("lighthouse")
         .and(warp::path("staking"))
@@ -2757,12 +3282,62 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/logging
+    let get_lighthouse_logging = warp::path("lighthouse")
+        .and(warp::path("logging"))
+        .and(warp::path::end())
+        .and(admin_auth.clone())
+        .and(log_levels_filter.clone())
+        .and_then(|log_levels: LogLevels| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(log_levels_config(
+                    &log_levels,
+                )))
+            })
+        });
+
+    // POST lighthouse/logging
+    let post_lighthouse_logging = warp::path("lighthouse")
+        .and(warp::path("logging"))
+        .and(warp::path::end())
+        .and(admin_auth.clone())
+        .and(warp::body::json())
+        .and(log_levels_filter)
+        .and(log_filter.clone())
+        .and_then(
+            |config: eth2::lighthouse::LogLevelsConfig, log_levels: LogLevels, log: Logger| {
+                blocking_json_task(move || {
+                    let debug_level = logging::parse_level(&config.debug_level)
+                        .map_err(warp_utils::reject::custom_bad_request)?;
+                    let overrides = config
+                        .overrides
+                        .iter()
+                        .map(|(module, level)| {
+                            logging::parse_level(level).map(|level| (module.clone(), level))
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(warp_utils::reject::custom_bad_request)?;
+
+                    log_levels.set(debug_level, overrides);
+                    let config = log_levels_config(&log_levels);
+                    info!(
+                        log,
+                        "Log levels changed";
+                        "debug_level" => &config.debug_level,
+                        "overrides" => ?config.overrides,
+                    );
+                    Ok(api_types::GenericResponse::from(config))
+                })
+            },
+        );
+
     let database_path = warp::path("lighthouse").and(warp::path("database"));
 
     // GET lighthouse/database/info
     let get_lighthouse_database_info = database_path
         .and(warp::path("info"))
         .and(warp::path::end())
+        .and(admin_auth.clone())
         .and(chain_filter.clone())
         .and_then(|chain: Arc<BeaconChain<T>>| blocking_json_task(move || database::info(chain)));
 
@@ -2770,6 +3345,7 @@ pub fn serve<T: BeaconChainTypes>(
     let post_lighthouse_database_reconstruct = database_path
         .and(warp::path("reconstruct"))
         .and(warp::path::end())
+        .and(admin_auth.clone())
         .and(not_while_syncing_filter)
         .and(chain_filter.clone())
         .and_then(|chain: Arc<BeaconChain<T>>| {
@@ -2779,10 +3355,89 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // POST lighthouse/database/compact
+    let post_lighthouse_database_compact = database_path
+        .and(warp::path("compact"))
+        .and(warp::path::end())
+        .and(admin_auth.clone())
+        .and(database_jobs_filter.clone())
+        .and(chain_filter.clone())
+        .and(log_filter.clone())
+        .and_then(
+            |jobs: Arc<DatabaseJobs>, chain: Arc<BeaconChain<T>>, log: Logger| {
+                blocking_json_task(move || {
+                    jobs.start(DatabaseJobKind::Compact, chain, log)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // POST lighthouse/database/prune_payloads
+    let post_lighthouse_database_prune_payloads = database_path
+        .and(warp::path("prune_payloads"))
+        .and(warp::path::end())
+        .and(admin_auth.clone())
+        .and(database_jobs_filter.clone())
+        .and(chain_filter.clone())
+        .and(log_filter.clone())
+        .and_then(
+            |jobs: Arc<DatabaseJobs>, chain: Arc<BeaconChain<T>>, log: Logger| {
+                blocking_json_task(move || {
+                    jobs.start(DatabaseJobKind::PrunePayloads, chain, log)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // POST lighthouse/database/prune_states
+    let post_lighthouse_database_prune_states = database_path
+        .and(warp::path("prune_states"))
+        .and(warp::path::end())
+        .and(admin_auth.clone())
+        .and(database_jobs_filter.clone())
+        .and(chain_filter.clone())
+        .and(log_filter.clone())
+        .and_then(
+            |jobs: Arc<DatabaseJobs>, chain: Arc<BeaconChain<T>>, log: Logger| {
+                blocking_json_task(move || {
+                    jobs.start(DatabaseJobKind::PruneStates, chain, log)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/database/jobs
+    let get_lighthouse_database_jobs = database_path
+        .and(warp::path("jobs"))
+        .and(warp::path::end())
+        .and(admin_auth.clone())
+        .and(database_jobs_filter.clone())
+        .and_then(|jobs: Arc<DatabaseJobs>| {
+            blocking_json_task(move || Ok(api_types::GenericResponse::from(jobs.list())))
+        });
+
+    // GET lighthouse/database/jobs/{id}
+    let get_lighthouse_database_jobs_id = database_path
+        .and(warp::path("jobs"))
+        .and(warp::path::param::<u64>())
+        .and(warp::path::end())
+        .and(admin_auth.clone())
+        .and(database_jobs_filter.clone())
+        .and_then(|id: u64, jobs: Arc<DatabaseJobs>| {
+            blocking_json_task(move || {
+                jobs.get(id)
+                    .map(api_types::GenericResponse::from)
+                    .ok_or_else(|| {
+                        warp_utils::reject::custom_not_found(format!("database job {}", id))
+                    })
+            })
+        });
+
     // POST lighthouse/database/historical_blocks
     let post_lighthouse_database_historical_blocks = database_path
         .and(warp::path("historical_blocks"))
         .and(warp::path::end())
+        .and(admin_auth.clone())
         .and(warp::body::json())
         .and(chain_filter.clone())
         .and(log_filter.clone())
@@ -2806,6 +3461,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("block_rewards"))
         .and(warp::query::<eth2::lighthouse::BlockRewardsQuery>())
         .and(warp::path::end())
+        .and(memory_watermark_filter.clone())
         .and(chain_filter.clone())
         .and(log_filter.clone())
         .and_then(|query, chain, log| {
@@ -2819,6 +3475,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path::param::<String>())
         .and(warp::query::<eth2::lighthouse::AttestationPerformanceQuery>())
         .and(warp::path::end())
+        .and(memory_watermark_filter.clone())
         .and(chain_filter.clone())
         .and_then(|target, query, chain: Arc<BeaconChain<T>>| {
             blocking_json_task(move || {
@@ -2826,12 +3483,18 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/analysis/block_packing
     // GET lighthouse/analysis/block_packing_efficiency
     let get_lighthouse_block_packing_efficiency = warp::path("lighthouse")
         .and(warp::path("analysis"))
-        .and(warp::path("block_packing_efficiency"))
+        .and(
+            warp::path("block_packing")
+                .or(warp::path("block_packing_efficiency"))
+                .unify(),
+        )
         .and(warp::query::<eth2::lighthouse::BlockPackingEfficiencyQuery>())
         .and(warp::path::end())
+        .and(memory_watermark_filter.clone())
         .and(chain_filter.clone())
         .and_then(|query, chain: Arc<BeaconChain<T>>| {
             blocking_json_task(move || {
@@ -2839,6 +3502,18 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/analysis/slot_delays
+    let get_lighthouse_slot_delays = warp::path("lighthouse")
+        .and(warp::path("analysis"))
+        .and(warp::path("slot_delays"))
+        .and(warp::query::<eth2::lighthouse::SlotDelaysQuery>())
+        .and(warp::path::end())
+        .and(memory_watermark_filter.clone())
+        .and(chain_filter.clone())
+        .and_then(|query, chain: Arc<BeaconChain<T>>| {
+            blocking_json_task(move || slot_delays::get_slot_delays(query, chain))
+        });
+
     let get_events = eth1_v1
         .and(warp::path("events"))
         .and(warp::path::end())
@@ -2875,6 +3550,15 @@ pub fn serve<T: BeaconChainTypes>(
                                 api_types::EventTopic::LateHead => {
                                     event_handler.subscribe_late_head()
                                 }
+                                api_types::EventTopic::AttesterDutiesReorg => {
+                                    event_handler.subscribe_attester_duties_reorg()
+                                }
+                                api_types::EventTopic::ProposerDutiesReorg => {
+                                    event_handler.subscribe_proposer_duties_reorg()
+                                }
+                                api_types::EventTopic::SlasherSlashing => {
+                                    event_handler.subscribe_slasher_slashing()
+                                }
                                 api_types::EventTopic::BlockReward => {
                                     event_handler.subscribe_block_reward()
                                 }
@@ -2912,6 +3596,8 @@ pub fn serve<T: BeaconChainTypes>(
 
     // Define the ultimate set of routes that will be provided to the server.
     let routes = warp::get()
+        .and(origin_filter.clone())
+        .and(rate_limit.clone())
         .and(
             get_beacon_genesis
                 .boxed()
@@ -2919,12 +3605,14 @@ pub fn serve<T: BeaconChainTypes>(
                 .or(get_beacon_state_fork.boxed())
                 .or(get_beacon_state_finality_checkpoints.boxed())
                 .or(get_beacon_state_validator_balances.boxed())
+                .or(get_beacon_deposit_snapshot.boxed())
                 .or(get_beacon_state_validators_id.boxed())
                 .or(get_beacon_state_validators.boxed())
                 .or(get_beacon_state_committees.boxed())
                 .or(get_beacon_state_sync_committees.boxed())
                 .or(get_beacon_headers.boxed())
                 .or(get_beacon_headers_block_id.boxed())
+                .or(get_beacon_light_client_bootstrap.boxed())
                 .or(get_beacon_block.boxed())
                 .or(get_beacon_block_attestations.boxed())
                 .or(get_beacon_block_root.boxed())
@@ -2932,6 +3620,8 @@ pub fn serve<T: BeaconChainTypes>(
                 .or(get_beacon_pool_attester_slashings.boxed())
                 .or(get_beacon_pool_proposer_slashings.boxed())
                 .or(get_beacon_pool_voluntary_exits.boxed())
+                .or(get_beacon_pool_bls_to_execution_changes.boxed())
+                .or(get_expected_withdrawals.boxed())
                 .or(get_config_fork_schedule.boxed())
                 .or(get_config_spec.boxed())
                 .or(get_config_deposit_contract.boxed())
@@ -2951,25 +3641,44 @@ pub fn serve<T: BeaconChainTypes>(
                 .or(get_validator_aggregate_attestation.boxed())
                 .or(get_validator_sync_committee_contribution.boxed())
                 .or(get_lighthouse_health.boxed())
+                .or(get_lighthouse_system.boxed())
                 .or(get_lighthouse_syncing.boxed())
+                .or(get_lighthouse_sync_lookups.boxed())
+                .or(get_lighthouse_beacon_processor_queues.boxed())
+                .or(get_lighthouse_ready.boxed())
                 .or(get_lighthouse_nat.boxed())
+                .or(get_lighthouse_network_port_mappings.boxed())
+                .or(get_lighthouse_network_clock_skew.boxed())
                 .or(get_lighthouse_peers.boxed())
                 .or(get_lighthouse_peers_connected.boxed())
+                .or(get_lighthouse_peers_diversity.boxed())
+                .or(get_lighthouse_gossipsub_scores.boxed())
+                .or(get_lighthouse_peers_verbose.boxed())
                 .or(get_lighthouse_proto_array.boxed())
                 .or(get_lighthouse_validator_inclusion_global.boxed())
                 .or(get_lighthouse_validator_inclusion.boxed())
                 .or(get_lighthouse_eth1_syncing.boxed())
                 .or(get_lighthouse_eth1_block_cache.boxed())
                 .or(get_lighthouse_eth1_deposit_cache.boxed())
+                .or(get_lighthouse_naive_aggregation_pool.boxed())
+                .or(get_lighthouse_op_pool_attestations.boxed())
                 .or(get_lighthouse_beacon_states_ssz.boxed())
+                .or(get_lighthouse_beacon_block_roots.boxed())
+                .or(get_lighthouse_beacon_proofs_state.boxed())
+                .or(get_lighthouse_beacon_proofs_block.boxed())
                 .or(get_lighthouse_staking.boxed())
+                .or(get_lighthouse_validator_monitor.boxed())
+                .or(get_lighthouse_logging.boxed())
                 .or(get_lighthouse_database_info.boxed())
+                .or(get_lighthouse_database_jobs.boxed())
+                .or(get_lighthouse_database_jobs_id.boxed())
                 .or(get_lighthouse_block_rewards.boxed())
                 .or(get_lighthouse_attestation_performance.boxed())
                 .or(get_lighthouse_block_packing_efficiency.boxed())
+                .or(get_lighthouse_slot_delays.boxed())
                 .or(get_events.boxed()),
         )
-        .or(warp::post().and(
+        .or(warp::post().and(origin_filter).and(rate_limit).and(
             post_beacon_blocks
                 .boxed()
                 .or(post_beacon_blinded_blocks.boxed())
@@ -2977,7 +3686,10 @@ pub fn serve<T: BeaconChainTypes>(
                 .or(post_beacon_pool_attester_slashings.boxed())
                 .or(post_beacon_pool_proposer_slashings.boxed())
                 .or(post_beacon_pool_voluntary_exits.boxed())
+                .or(post_beacon_pool_bls_to_execution_changes.boxed())
                 .or(post_beacon_pool_sync_committees.boxed())
+                .or(post_beacon_state_validators.boxed())
+                .or(post_beacon_state_validator_balances.boxed())
                 .or(post_validator_duties_attester.boxed())
                 .or(post_validator_duties_sync.boxed())
                 .or(post_validator_aggregate_and_proofs.boxed())
@@ -2986,14 +3698,32 @@ pub fn serve<T: BeaconChainTypes>(
                 .or(post_validator_sync_committee_subscriptions.boxed())
                 .or(post_validator_prepare_beacon_proposer.boxed())
                 .or(post_lighthouse_liveness.boxed())
+                .or(post_lighthouse_validator_monitor.boxed())
+                .or(post_lighthouse_logging.boxed())
+                .or(post_lighthouse_enr.boxed())
                 .or(post_lighthouse_database_reconstruct.boxed())
+                .or(post_lighthouse_database_compact.boxed())
+                .or(post_lighthouse_database_prune_payloads.boxed())
+                .or(post_lighthouse_database_prune_states.boxed())
                 .or(post_lighthouse_database_historical_blocks.boxed()),
         ))
         .recover(warp_utils::reject::handle_rejection)
         .with(slog_logging(log.clone()))
         .with(prometheus_metrics())
         // Add a `Server` header.
-        .map(|reply| warp::reply::with_header(reply, "Server", &version_with_platform()))
+        .map(|reply| warp::reply::with_header(reply, "Server", &version_with_platform()));
+
+    // Compress responses for clients which accept a supported encoding.
+    let enable_compression = config.enable_compression;
+    let routes = warp::header::optional::<String>("accept-encoding")
+        .and(routes)
+        .map(move |accept_encoding: Option<String>, reply| {
+            if enable_compression {
+                warp_utils::compression::compress_reply(accept_encoding, reply)
+            } else {
+                reply.into_response()
+            }
+        })
         .with(cors_builder.build());
 
     let http_socket: SocketAddr = SocketAddr::new(config.listen_addr, config.listen_port);
@@ -3054,3 +3784,30 @@ fn publish_network_message<T: EthSpec>(
         ))
     })
 }
+
+/// Converts an entry of the peer database into its representation in the HTTP API.
+fn api_peer<T: EthSpec>(peer_id: &PeerId, peer_info: &PeerInfo<T>) -> eth2::lighthouse::Peer<T> {
+    eth2::lighthouse::Peer {
+        peer_id: peer_id.to_string(),
+        peer_info: peer_info.clone(),
+        enr_custom_fields: peer_info
+            .enr()
+            .map(|enr| enr.custom_fields())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(key, value)| eth2::lighthouse::EnrField { key, value })
+            .collect(),
+    }
+}
+
+/// Converts the current log levels into their representation in the HTTP API.
+fn log_levels_config(log_levels: &LogLevels) -> eth2::lighthouse::LogLevelsConfig {
+    eth2::lighthouse::LogLevelsConfig {
+        debug_level: logging::level_name(log_levels.default_level()).to_string(),
+        overrides: log_levels
+            .overrides()
+            .into_iter()
+            .map(|(module, level)| (module, logging::level_name(level).to_string()))
+            .collect(),
+    }
+}
@@ -0,0 +1,75 @@
+use eth2::lighthouse::{PeerClient, PeerPenalties, VerbosePeerData};
+use eth2::types::{PeerDirection, PeerState};
+use lighthouse_network::{EnrExt, PeerId, PeerInfo, Subnet, SyncStatus};
+use types::{EthSpec, Slot};
+
+/// Scores of trusted peers are infinite, which can't be represented in JSON.
+fn json_score(score: f64) -> f64 {
+    if score.is_finite() {
+        score
+    } else {
+        f64::MAX
+    }
+}
+
+/// Build the `lighthouse/peers/verbose` response for a single peer.
+///
+/// The `head_slot` is our own head slot, used to compute the peer's sync distance.
+pub fn verbose_peer_data<T: EthSpec>(
+    peer_id: &PeerId,
+    peer_info: &PeerInfo<T>,
+    head_slot: Slot,
+) -> VerbosePeerData {
+    let score = peer_info.score();
+    let peer_actions = peer_info.peer_actions();
+    let client = peer_info.client();
+
+    let mut attestation_subnets = vec![];
+    let mut sync_committee_subnets = vec![];
+    for subnet in peer_info.subnets() {
+        match subnet {
+            Subnet::Attestation(id) => attestation_subnets.push(**id),
+            Subnet::SyncCommittee(id) => sync_committee_subnets.push(**id),
+        }
+    }
+    attestation_subnets.sort_unstable();
+    sync_committee_subnets.sort_unstable();
+
+    let sync_info = match peer_info.sync_status() {
+        SyncStatus::Synced { info }
+        | SyncStatus::Advanced { info }
+        | SyncStatus::Behind { info } => Some(info),
+        SyncStatus::IrrelevantPeer | SyncStatus::Unknown => None,
+    };
+
+    VerbosePeerData {
+        peer_id: peer_id.to_string(),
+        enr: peer_info.enr().map(|enr| enr.to_base64()),
+        state: PeerState::from_peer_connection_status(peer_info.connection_status()),
+        direction: peer_info
+            .connection_direction()
+            .map(PeerDirection::from_connection_direction),
+        is_trusted: peer_info.is_trusted(),
+        score: json_score(score.score()),
+        lighthouse_score: json_score(score.lighthouse_score()),
+        gossipsub_score: json_score(score.gossipsub_score()),
+        penalties: PeerPenalties {
+            fatal: peer_actions.fatal,
+            low_tolerance: peer_actions.low_tolerance_error,
+            mid_tolerance: peer_actions.mid_tolerance_error,
+            high_tolerance: peer_actions.high_tolerance_error,
+        },
+        client: PeerClient {
+            kind: client.kind.to_string(),
+            version: client.version.clone(),
+            os_version: client.os_version.clone(),
+            agent_string: client.agent_string.clone(),
+        },
+        attestation_subnets,
+        sync_committee_subnets,
+        head_slot: sync_info.map(|info| info.head_slot),
+        finalized_epoch: sync_info.map(|info| info.finalized_epoch),
+        sync_distance: sync_info
+            .map(|info| info.head_slot.as_u64() as i64 - head_slot.as_u64() as i64),
+    }
+}
@@ -0,0 +1,70 @@
+//! Contains the handlers for the `lighthouse/beacon/proofs` endpoints, which serve Merkle proofs of
+//! the fields of states and blocks so that they may be verified against a trusted root.
+use crate::block_id::BlockId;
+use crate::state_id::StateId;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{MerkleProof, MerkleProofQuery};
+use std::sync::Arc;
+use types::{BeaconStateError, MerkleProofContainer};
+use warp_utils::reject::{beacon_state_error, custom_bad_request};
+
+/// Returns a proof of the node at `query.gindex` against the root of the state at `state_id`.
+pub fn get_state_proof<T: BeaconChainTypes>(
+    state_id: StateId,
+    query: MerkleProofQuery,
+    chain: Arc<BeaconChain<T>>,
+) -> Result<MerkleProof, warp::Rejection> {
+    let gindex = query_gindex(&query)?;
+    let mut state = state_id.state(&chain)?;
+
+    let (leaf, proof) = state
+        .compute_merkle_proof_with_leaf(gindex)
+        .map_err(proof_error)?;
+    let root = state.update_tree_hash_cache().map_err(beacon_state_error)?;
+
+    Ok(MerkleProof {
+        root,
+        gindex: query.gindex,
+        leaf,
+        proof,
+    })
+}
+
+/// Returns a proof of the node at `query.gindex` against the root of the block at `block_id`.
+pub fn get_block_proof<T: BeaconChainTypes>(
+    block_id: BlockId,
+    query: MerkleProofQuery,
+    chain: Arc<BeaconChain<T>>,
+) -> Result<MerkleProof, warp::Rejection> {
+    let gindex = query_gindex(&query)?;
+    // A blinded block has the same tree hash root as the full block.
+    let block = block_id.blinded_block(&chain)?;
+
+    let (leaf, proof) = block
+        .message()
+        .compute_merkle_proof(gindex)
+        .map_err(proof_error)?;
+    let root = block.canonical_root();
+
+    Ok(MerkleProof {
+        root,
+        gindex: query.gindex,
+        leaf,
+        proof,
+    })
+}
+
+fn query_gindex(query: &MerkleProofQuery) -> Result<usize, warp::Rejection> {
+    usize::try_from(query.gindex)
+        .map_err(|_| custom_bad_request(format!("gindex {} is too large", query.gindex)))
+}
+
+fn proof_error(e: BeaconStateError) -> warp::Rejection {
+    match e {
+        BeaconStateError::IndexNotSupported(gindex) => custom_bad_request(format!(
+            "gindex {} does not refer to a provable field",
+            gindex
+        )),
+        e => beacon_state_error(e),
+    }
+}
@@ -0,0 +1,229 @@
+//! Contains the logic for publishing blocks received via the `beacon/blocks` and
+//! `beacon/blinded_blocks` endpoints, at the broadcast validation level requested by the caller.
+use crate::metrics;
+use beacon_chain::validator_monitor::{get_block_delay_ms, timestamp_now};
+use beacon_chain::{BeaconChain, BeaconChainTypes, BlockError};
+use eth2::types::BroadcastValidation;
+use lighthouse_network::PubsubMessage;
+use network::NetworkMessage;
+use slog::{crit, debug, error, info, warn, Logger};
+use slot_clock::SlotClock;
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::info_span;
+use types::{BlindedPayload, ExecPayload, ExecutionPayload, Hash256, SignedBeaconBlock};
+
+/// Verify `block` to the given `validation_level`, broadcast it and import it into the chain.
+///
+/// With `BroadcastValidation::Gossip` the block is broadcast as soon as it passes gossip
+/// verification, and an error importing it results in a 202. With the stricter levels the block
+/// is only broadcast once it has been fully verified and imported, otherwise a 400 is returned.
+pub fn publish_block<T: BeaconChainTypes>(
+    block: SignedBeaconBlock<T::EthSpec>,
+    validation_level: BroadcastValidation,
+    chain: Arc<BeaconChain<T>>,
+    network_tx: &UnboundedSender<NetworkMessage<T::EthSpec>>,
+    log: Logger,
+) -> Result<(), warp::Rejection> {
+    let seen_timestamp = timestamp_now();
+    // The parent of the gossip verification and import spans of the block.
+    let _span = info_span!("block_publish", slot = %block.slot()).entered();
+
+    // Determine the delay after the start of the slot, register it with metrics.
+    let delay = get_block_delay_ms(seen_timestamp, block.message(), &chain.slot_clock);
+    metrics::observe_duration(&metrics::HTTP_API_BLOCK_BROADCAST_DELAY_TIMES, delay);
+
+    let gossip_verified_block = match chain.verify_block_for_gossip(block.clone()) {
+        Ok(gossip_verified_block) => gossip_verified_block,
+        Err(BlockError::BlockIsAlreadyKnown) => {
+            debug!(
+                log,
+                "Block from HTTP API already known";
+                "slot" => block.slot(),
+            );
+            return Ok(());
+        }
+        Err(e) => {
+            warn!(
+                log,
+                "Block from HTTP API failed gossip verification";
+                "reason" => ?e,
+                "slot" => block.slot(),
+            );
+            return Err(warp_utils::reject::object_invalid(format!("{:?}", e)));
+        }
+    };
+    let block_root = gossip_verified_block.block_root;
+
+    // Check for an equivocation before importing the block, so that a slashable block is neither
+    // broadcast nor added to fork choice.
+    if validation_level == BroadcastValidation::ConsensusAndEquivocation {
+        check_slashable_proposal(&chain, &block, block_root)?;
+    }
+
+    let broadcast = || {
+        crate::publish_pubsub_message(
+            network_tx,
+            PubsubMessage::BeaconBlock(Box::new(block.clone())),
+        )
+    };
+
+    if validation_level == BroadcastValidation::Gossip {
+        broadcast()?;
+    }
+
+    match chain.process_block(gossip_verified_block) {
+        Ok(root) => {
+            if validation_level != BroadcastValidation::Gossip {
+                broadcast()?;
+            }
+
+            info!(
+                log,
+                "Valid block from HTTP API";
+                "block_delay" => ?delay,
+                "root" => format!("{}", root),
+                "proposer_index" => block.message().proposer_index(),
+                "slot" => block.slot(),
+            );
+
+            // Notify the validator monitor.
+            chain.validator_monitor.read().register_api_block(
+                seen_timestamp,
+                block.message(),
+                root,
+                &chain.slot_clock,
+            );
+
+            // Update the head since it's likely this block will become the new head.
+            chain
+                .fork_choice()
+                .map_err(warp_utils::reject::beacon_chain_error)?;
+
+            // Perform some logging to inform users if their blocks are being produced late.
+            //
+            // Check to see the thresholds are non-zero to avoid logging errors with small
+            // slot times (e.g., during testing)
+            let crit_threshold = chain.slot_clock.unagg_attestation_production_delay();
+            let error_threshold = crit_threshold / 2;
+            if delay >= crit_threshold {
+                crit!(
+                    log,
+                    "Block was broadcast too late";
+                    "msg" => "system may be overloaded, block likely to be orphaned",
+                    "delay_ms" => delay.as_millis(),
+                    "slot" => block.slot(),
+                    "root" => ?root,
+                )
+            } else if delay >= error_threshold {
+                error!(
+                    log,
+                    "Block broadcast was delayed";
+                    "msg" => "system may be overloaded, block may be orphaned",
+                    "delay_ms" => delay.as_millis(),
+                    "slot" => block.slot(),
+                    "root" => ?root,
+                )
+            }
+
+            Ok(())
+        }
+        Err(BlockError::BeaconChainError(e)) => Err(warp_utils::reject::beacon_chain_error(e)),
+        Err(e) => {
+            let msg = format!("{:?}", e);
+            error!(
+                log,
+                "Invalid block provided to HTTP API";
+                "reason" => &msg,
+                "root" => ?block_root,
+            );
+            if validation_level == BroadcastValidation::Gossip {
+                Err(warp_utils::reject::broadcast_without_import(msg))
+            } else {
+                Err(warp_utils::reject::object_invalid(msg))
+            }
+        }
+    }
+}
+
+/// Unblind `block` and publish it as per `publish_block`.
+///
+/// Blocks without an execution payload are unblinded locally, all others have their payload
+/// revealed by the execution layer's builder.
+pub fn publish_blinded_block<T: BeaconChainTypes>(
+    block: SignedBeaconBlock<T::EthSpec, BlindedPayload<T::EthSpec>>,
+    validation_level: BroadcastValidation,
+    chain: Arc<BeaconChain<T>>,
+    network_tx: &UnboundedSender<NetworkMessage<T::EthSpec>>,
+    log: Logger,
+) -> Result<(), warp::Rejection> {
+    let full_block = reconstruct_block(&chain, block)?;
+    publish_block(full_block, validation_level, chain, network_tx, log)
+}
+
+fn reconstruct_block<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    block: SignedBeaconBlock<T::EthSpec, BlindedPayload<T::EthSpec>>,
+) -> Result<SignedBeaconBlock<T::EthSpec>, warp::Rejection> {
+    let payload = match block.message().execution_payload() {
+        // Blocks prior to Bellatrix don't have a payload.
+        Err(_) => None,
+        // Blocks prior to the merge transition have an empty payload.
+        Ok(payload) if payload.is_default() => {
+            ExecutionPayload::empty_for_fork(payload.fork_name())
+        }
+        Ok(_) => {
+            let el = chain.execution_layer.as_ref().ok_or_else(|| {
+                warp_utils::reject::custom_server_error("no execution layer found".to_string())
+            })?;
+            let payload = el
+                .block_on(|el| el.propose_blinded_beacon_block(&block))
+                .map_err(|e| {
+                    warp_utils::reject::custom_server_error(format!("proposal failed: {:?}", e))
+                })?;
+            Some(payload)
+        }
+    };
+
+    block.try_into_full_block(payload).ok_or_else(|| {
+        warp_utils::reject::custom_server_error("unable to add payload to block".to_string())
+    })
+}
+
+/// Returns an error if fork choice knows of another block from the proposer of `block` at the
+/// same slot, i.e. if broadcasting `block` would be a slashable equivocation.
+fn check_slashable_proposal<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    block: &SignedBeaconBlock<T::EthSpec>,
+    block_root: Hash256,
+) -> Result<(), warp::Rejection> {
+    let conflicting_roots = chain
+        .fork_choice
+        .read()
+        .proto_array()
+        .core_proto_array()
+        .nodes
+        .iter()
+        .filter(|node| node.slot == block.slot() && node.root != block_root)
+        .map(|node| node.root)
+        .collect::<Vec<_>>();
+
+    for root in conflicting_roots {
+        let conflicting_block = chain
+            .get_blinded_block(&root)
+            .map_err(warp_utils::reject::beacon_chain_error)?;
+
+        if conflicting_block.map_or(false, |conflicting_block| {
+            conflicting_block.message().proposer_index() == block.message().proposer_index()
+        }) {
+            return Err(warp_utils::reject::object_invalid(format!(
+                "proposer {} has already produced block {:?} at slot {}",
+                block.message().proposer_index(),
+                root,
+                block.slot()
+            )));
+        }
+    }
+
+    Ok(())
+}
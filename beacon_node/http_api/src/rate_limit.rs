@@ -0,0 +1,215 @@
+//! Optional per-client rate limiting for the HTTP API.
+//!
+//! Each client has a token bucket which refills at a constant rate up to a maximum burst size.
+//! Every request withdraws tokens according to the cost class of the endpoint it targets, and
+//! requests which can't be paid for are rejected with a 429.
+//!
+//! Clients presenting a valid validator or admin token are tracked by that token, so that a
+//! validator client sharing an address with other users isn't starved by them. All other clients
+//! are tracked by their remote IP address.
+
+use crate::auth::{bearer_token, AuthConfig, AuthTier};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use warp::filters::BoxedFilter;
+use warp::path::FullPath;
+use warp::Filter;
+
+/// Once this many clients are being tracked, clients with full buckets are forgotten.
+const PRUNE_THRESHOLD: usize = 4_096;
+
+/// The class of an endpoint, which determines how many tokens a request to it costs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestCost {
+    /// Endpoints which are answered from in-memory data, e.g. node status and head queries.
+    Cheap,
+    /// All endpoints which aren't explicitly cheap or expensive.
+    Standard,
+    /// Endpoints which load or serialize whole states, or which replay historical data.
+    Expensive,
+}
+
+impl RequestCost {
+    /// Classify a request by its full path (e.g. `/eth/v1/node/health`).
+    pub fn for_path(path: &str) -> Self {
+        const EXPENSIVE_PREFIXES: &[&str] = &[
+            "/eth/v1/debug/beacon/states/",
+            "/eth/v2/debug/beacon/states/",
+            "/lighthouse/beacon/states/",
+            "/lighthouse/analysis/",
+            "/lighthouse/op_pool/",
+            "/lighthouse/validator_inclusion/",
+        ];
+        const EXPENSIVE_SUFFIXES: &[&str] = &[
+            "/validators",
+            "/validator_balances",
+            "/committees",
+            "/sync_committees",
+        ];
+        const CHEAP_PREFIXES: &[&str] = &[
+            "/eth/v1/node/",
+            "/eth/v1/config/",
+            "/eth/v1/beacon/genesis",
+            "/eth/v1/beacon/headers",
+            "/lighthouse/health",
+            "/lighthouse/syncing",
+        ];
+
+        let path = path.trim_end_matches('/');
+
+        if EXPENSIVE_PREFIXES
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+            || (path.starts_with("/eth/v1/beacon/states/")
+                && EXPENSIVE_SUFFIXES
+                    .iter()
+                    .any(|suffix| path.ends_with(suffix)))
+        {
+            RequestCost::Expensive
+        } else if CHEAP_PREFIXES.iter().any(|prefix| path.starts_with(prefix)) {
+            RequestCost::Cheap
+        } else {
+            RequestCost::Standard
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// The maximum number of tokens in a client's bucket, i.e. the largest burst of requests.
+    pub burst: u64,
+    /// The number of tokens added to each client's bucket per second.
+    pub tokens_per_second: u64,
+    /// The cost of a request to a `RequestCost::Cheap` endpoint.
+    pub cheap_cost: u64,
+    /// The cost of a request to a `RequestCost::Standard` endpoint.
+    pub standard_cost: u64,
+    /// The cost of a request to a `RequestCost::Expensive` endpoint.
+    pub expensive_cost: u64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            burst: 200,
+            tokens_per_second: 20,
+            cheap_cost: 1,
+            standard_cost: 5,
+            expensive_cost: 50,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Returns the number of tokens a request of the given class costs.
+    ///
+    /// Costs are capped at the burst size so that every request can eventually be served.
+    pub fn cost(&self, cost: RequestCost) -> u64 {
+        let cost = match cost {
+            RequestCost::Cheap => self.cheap_cost,
+            RequestCost::Standard => self.standard_cost,
+            RequestCost::Expensive => self.expensive_cost,
+        };
+        std::cmp::min(cost, self.burst)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ClientId {
+    Token(String),
+    Ip(IpAddr),
+    Unknown,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<ClientId, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Withdraw `cost` tokens from the bucket of `client`.
+    ///
+    /// Returns the time until enough tokens will be available if the bucket can't cover `cost`.
+    fn try_consume(&self, client: ClientId, cost: u64, now: Instant) -> Result<(), Duration> {
+        let burst = self.config.burst as f64;
+        let rate = self.config.tokens_per_second as f64;
+        let refill = |bucket: &Bucket| {
+            let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+            (bucket.tokens + elapsed * rate).min(burst)
+        };
+
+        let mut buckets = self.buckets.lock();
+
+        if buckets.len() >= PRUNE_THRESHOLD {
+            buckets.retain(|_, bucket| refill(bucket) < burst);
+        }
+
+        let bucket = buckets.entry(client).or_insert(Bucket {
+            tokens: burst,
+            updated: now,
+        });
+        bucket.tokens = refill(bucket);
+        bucket.updated = now;
+
+        let cost = cost as f64;
+        if bucket.tokens >= cost {
+            bucket.tokens -= cost;
+            Ok(())
+        } else if rate > 0.0 {
+            Err(Duration::from_secs_f64((cost - bucket.tokens) / rate))
+        } else {
+            Err(Duration::MAX)
+        }
+    }
+}
+
+fn client_id(auth: &AuthConfig, header: Option<&str>, remote: Option<SocketAddr>) -> ClientId {
+    match header {
+        Some(header) if auth.tier_for_header(header) > AuthTier::Public => {
+            ClientId::Token(bearer_token(header).unwrap_or_default().to_string())
+        }
+        _ => remote.map_or(ClientId::Unknown, |addr| ClientId::Ip(addr.ip())),
+    }
+}
+
+/// Returns a filter which rejects requests from clients that have exhausted their quota.
+///
+/// If `limiter` is `None` every request is allowed.
+pub fn rate_limit_filter(limiter: Option<Arc<RateLimiter>>, auth: &AuthConfig) -> BoxedFilter<()> {
+    let auth = auth.clone();
+    warp::path::full()
+        .and(warp::header::optional::<String>("Authorization"))
+        .and(warp::addr::remote())
+        .and_then(
+            move |path: FullPath, header: Option<String>, remote: Option<SocketAddr>| {
+                let result = limiter.as_ref().map_or(Ok(()), |limiter| {
+                    let client = client_id(&auth, header.as_deref(), remote);
+                    let cost = limiter.config.cost(RequestCost::for_path(path.as_str()));
+                    limiter
+                        .try_consume(client, cost, Instant::now())
+                        .map_err(|retry_after| {
+                            warp_utils::reject::too_many_requests(limiter.config.burst, retry_after)
+                        })
+                });
+                async move { result }
+            },
+        )
+        .untuple_one()
+        .boxed()
+}
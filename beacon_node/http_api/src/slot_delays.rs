@@ -0,0 +1,56 @@
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{SlotDelays, SlotDelaysQuery};
+use slot_clock::SlotClock;
+use std::sync::Arc;
+use std::time::Duration;
+use warp_utils::reject::custom_bad_request;
+
+/// The beacon chain only retains the timings of the most recent slots.
+const MAX_REQUEST_RANGE_SLOTS: u64 = 64;
+
+pub fn get_slot_delays<T: BeaconChainTypes>(
+    query: SlotDelaysQuery,
+    chain: Arc<BeaconChain<T>>,
+) -> Result<Vec<SlotDelays>, warp::Rejection> {
+    let start_slot = query.start_slot;
+    let end_slot = query.end_slot;
+
+    if start_slot > end_slot {
+        return Err(custom_bad_request(format!(
+            "invalid start and end: {}, {}",
+            start_slot, end_slot
+        )));
+    }
+    let num_slots = end_slot.as_u64() - start_slot.as_u64() + 1;
+    if num_slots > MAX_REQUEST_RANGE_SLOTS {
+        return Err(custom_bad_request(format!(
+            "range of {} slots exceeds the maximum of {}",
+            num_slots, MAX_REQUEST_RANGE_SLOTS
+        )));
+    }
+
+    let block_times_cache = chain.block_times_cache.read();
+    let slot_delays = (start_slot.as_u64()..=end_slot.as_u64())
+        .map(|slot| {
+            let slot = slot.into();
+            let slot_start = chain
+                .slot_clock
+                .start_of(slot)
+                .unwrap_or_else(|| Duration::from_secs(0));
+            let delays = block_times_cache.get_slot_delays(slot, slot_start);
+            let as_millis = |delay: Option<Duration>| delay.map(|delay| delay.as_millis() as u64);
+
+            SlotDelays {
+                slot,
+                block_root: delays.block_root,
+                observed_delay_ms: as_millis(delays.observed),
+                imported_delay_ms: as_millis(delays.imported),
+                set_as_head_delay_ms: as_millis(delays.set_as_head),
+                attestation_delay_ms: as_millis(delays.attestation_produced),
+                attested_to_parent: delays.attested_to_parent,
+            }
+        })
+        .collect();
+
+    Ok(slot_delays)
+}
@@ -1,6 +1,8 @@
-use beacon_chain::{BeaconChain, BeaconChainTypes};
+use crate::state_replay::StateReplayQueue;
+use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes, WhenSlotSkipped};
 use eth2::types::StateId as CoreStateId;
 use std::str::FromStr;
+use types::beacon_state::CloneConfig;
 use types::{BeaconState, EthSpec, Fork, Hash256, Slot};
 
 /// Wraps `eth2::types::StateId` and provides common state-access functionality. E.g., reading
@@ -48,6 +50,97 @@ impl StateId {
             })
     }
 
+    /// Returns `true` if the state identified by `self` was derived from a block which has not yet
+    /// been verified by the execution layer.
+    pub fn is_execution_optimistic<T: BeaconChainTypes>(
+        &self,
+        chain: &BeaconChain<T>,
+    ) -> Result<bool, warp::Rejection> {
+        let block_root = match &self.0 {
+            CoreStateId::Head => {
+                return chain
+                    .is_optimistic_head()
+                    .map_err(warp_utils::reject::beacon_chain_error)
+            }
+            CoreStateId::Genesis => return Ok(false),
+            CoreStateId::Finalized => chain
+                .head_info()
+                .map(|head| head.finalized_checkpoint.root)
+                .map_err(warp_utils::reject::beacon_chain_error)?,
+            CoreStateId::Justified => chain
+                .head_info()
+                .map(|head| head.current_justified_checkpoint.root)
+                .map_err(warp_utils::reject::beacon_chain_error)?,
+            CoreStateId::Slot(slot) => {
+                match chain
+                    .block_root_at_slot(*slot, WhenSlotSkipped::Prev)
+                    .map_err(warp_utils::reject::beacon_chain_error)?
+                {
+                    Some(block_root) => block_root,
+                    // Slots later than the head are served from advanced head states.
+                    None => {
+                        return chain
+                            .is_optimistic_head()
+                            .map_err(warp_utils::reject::beacon_chain_error)
+                    }
+                }
+            }
+            CoreStateId::Root(_) => {
+                return self.map_state_and_execution_optimistic(chain, |_, execution_optimistic| {
+                    Ok(execution_optimistic)
+                })
+            }
+        };
+
+        chain
+            .is_optimistic_block(&block_root)
+            .map_err(warp_utils::reject::beacon_chain_error)
+    }
+
+    /// Returns `true` if the state identified by `self` is finalized, i.e. it is a canonical state
+    /// no later than the finalized checkpoint.
+    ///
+    /// States which are unknown to the node are considered finalized, so that they result in a 404
+    /// rather than revealing anything about the fork choice.
+    pub fn is_finalized<T: BeaconChainTypes>(
+        &self,
+        chain: &BeaconChain<T>,
+    ) -> Result<bool, warp::Rejection> {
+        let head = chain
+            .head_info()
+            .map_err(warp_utils::reject::beacon_chain_error)?;
+        let slots_per_epoch = T::EthSpec::slots_per_epoch();
+        let finalized_slot = head.finalized_checkpoint.epoch.start_slot(slots_per_epoch);
+
+        let slot = match &self.0 {
+            CoreStateId::Genesis | CoreStateId::Finalized => return Ok(true),
+            CoreStateId::Head => head.slot,
+            CoreStateId::Justified => head
+                .current_justified_checkpoint
+                .epoch
+                .start_slot(slots_per_epoch),
+            CoreStateId::Slot(slot) => *slot,
+            CoreStateId::Root(root) => {
+                let slot = match chain.store.load_state_slot(root).map_err(|e| {
+                    warp_utils::reject::beacon_chain_error(BeaconChainError::DBError(e))
+                })? {
+                    Some(slot) => slot,
+                    None => return Ok(true),
+                };
+                let canonical_root = chain
+                    .state_root_at_slot(slot)
+                    .map_err(warp_utils::reject::beacon_chain_error)?;
+
+                if canonical_root != Some(*root) {
+                    return Ok(false);
+                }
+                slot
+            }
+        };
+
+        Ok(slot <= finalized_slot)
+    }
+
     /// Return the `fork` field of the state identified by `self`.
     pub fn fork<T: BeaconChainTypes>(
         &self,
@@ -71,17 +164,45 @@ impl StateId {
             _ => (self.root(chain)?, None),
         };
 
-        chain
-            .get_state(&state_root, slot_opt)
-            .map_err(warp_utils::reject::beacon_chain_error)
-            .and_then(|opt| {
-                opt.ok_or_else(|| {
-                    warp_utils::reject::custom_not_found(format!(
-                        "beacon state at root {}",
-                        state_root
+        load_state(chain, state_root, slot_opt)
+    }
+
+    /// Return the state identified by `self` and whether it was derived from a block which has
+    /// not yet been verified by the execution layer.
+    ///
+    /// Unlike separate calls to `Self::state` and `Self::is_execution_optimistic`, the flag always
+    /// describes the returned state, even if the head changes in between.
+    pub fn state_and_execution_optimistic<T: BeaconChainTypes>(
+        &self,
+        chain: &BeaconChain<T>,
+    ) -> Result<(BeaconState<T::EthSpec>, bool), warp::Rejection> {
+        let (state, block_root) = match &self.0 {
+            CoreStateId::Head => chain
+                .with_head(|snapshot| {
+                    Ok::<_, BeaconChainError>((
+                        snapshot
+                            .beacon_state
+                            .clone_with(CloneConfig::committee_caches_only()),
+                        snapshot.beacon_block_root,
                     ))
                 })
-            })
+                .map_err(warp_utils::reject::beacon_chain_error)?,
+            core_state_id => {
+                let state_root = self.root(chain)?;
+                let slot_opt = match core_state_id {
+                    CoreStateId::Slot(slot) => Some(*slot),
+                    _ => None,
+                };
+                let state = load_state(chain, state_root, slot_opt)?;
+                let block_root = state.get_latest_block_root(state_root);
+                (state, block_root)
+            }
+        };
+
+        let execution_optimistic = chain
+            .is_optimistic_block(&block_root)
+            .map_err(warp_utils::reject::beacon_chain_error)?;
+        Ok((state, execution_optimistic))
     }
 
     /// Map a function across the `BeaconState` identified by `self`.
@@ -103,6 +224,77 @@ impl StateId {
             _ => func(&self.state(chain)?),
         }
     }
+
+    /// Map a function across the `BeaconState` identified by `self`, also providing whether the
+    /// state was derived from a block which has not yet been verified by the execution layer.
+    pub fn map_state_and_execution_optimistic<T: BeaconChainTypes, F, U>(
+        &self,
+        chain: &BeaconChain<T>,
+        func: F,
+    ) -> Result<U, warp::Rejection>
+    where
+        F: Fn(&BeaconState<T::EthSpec>, bool) -> Result<U, warp::Rejection>,
+    {
+        match &self.0 {
+            CoreStateId::Head => {
+                let execution_optimistic = chain
+                    .is_optimistic_head()
+                    .map_err(warp_utils::reject::beacon_chain_error)?;
+                chain
+                    .with_head(|snapshot| Ok(func(&snapshot.beacon_state, execution_optimistic)))
+                    .map_err(warp_utils::reject::beacon_chain_error)?
+            }
+            CoreStateId::Root(state_root) => {
+                let state = self.state(chain)?;
+                let block_root = state.get_latest_block_root(*state_root);
+                let execution_optimistic = chain
+                    .is_optimistic_block(&block_root)
+                    .map_err(warp_utils::reject::beacon_chain_error)?;
+                func(&state, execution_optimistic)
+            }
+            _ => {
+                let execution_optimistic = self.is_execution_optimistic(chain)?;
+                func(&self.state(chain)?, execution_optimistic)
+            }
+        }
+    }
+
+    /// As `map_state_and_execution_optimistic`, except that historical states which must be
+    /// reconstructed by block replay are loaded via the bounded `replay_queue`.
+    pub fn map_state_and_execution_optimistic_with_replay_queue<T: BeaconChainTypes, F, U>(
+        &self,
+        chain: &BeaconChain<T>,
+        replay_queue: &StateReplayQueue,
+        func: F,
+    ) -> Result<U, warp::Rejection>
+    where
+        F: Fn(&BeaconState<T::EthSpec>, bool) -> Result<U, warp::Rejection>,
+    {
+        if let CoreStateId::Slot(slot) = &self.0 {
+            if let Some(state) = replay_queue.replay_state(chain, *slot)? {
+                let execution_optimistic = self.is_execution_optimistic(chain)?;
+                return func(&state, execution_optimistic);
+            }
+        }
+
+        self.map_state_and_execution_optimistic(chain, func)
+    }
+}
+
+/// Load the state with `state_root` from the database, returning a 404 if it is unknown.
+fn load_state<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    state_root: Hash256,
+    slot_opt: Option<Slot>,
+) -> Result<BeaconState<T::EthSpec>, warp::Rejection> {
+    chain
+        .get_state(&state_root, slot_opt)
+        .map_err(warp_utils::reject::beacon_chain_error)
+        .and_then(|opt| {
+            opt.ok_or_else(|| {
+                warp_utils::reject::custom_not_found(format!("beacon state at root {}", state_root))
+            })
+        })
 }
 
 impl FromStr for StateId {
@@ -0,0 +1,88 @@
+//! Bounds the work done when serving historical states from the freezer database.
+//!
+//! States which don't lie on a restore point have to be reconstructed by replaying blocks on top
+//! of the nearest restore point, which can take a long time on archive nodes with a sparse
+//! `slots_per_restore_point`. To stop a handful of requests from monopolising the node, only a
+//! limited number of replays may run at once and each replay is abandoned once it exceeds its
+//! time budget.
+use beacon_chain::memory_watermark::MEMORY_WATERMARK_CHECK_INTERVAL;
+use beacon_chain::store::{hot_cold_store::HotColdDBError, Error as StoreError};
+use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use types::{BeaconState, Slot};
+
+pub struct StateReplayQueue {
+    permits: Semaphore,
+    time_budget: Duration,
+}
+
+impl StateReplayQueue {
+    pub fn new(max_concurrent_replays: usize, time_budget: Duration) -> Self {
+        Self {
+            permits: Semaphore::new(max_concurrent_replays),
+            time_budget,
+        }
+    }
+
+    /// Load the frozen state at `slot` if it needs to be reconstructed by block replay.
+    ///
+    /// Returns `Ok(None)` if the state at `slot` doesn't require replay (e.g. it's in the hot
+    /// database or lies on a restore point), in which case it should be loaded as normal.
+    ///
+    /// Returns a 503 with a `Retry-After` header if the maximum number of concurrent replays are
+    /// already running.
+    pub fn replay_state<T: BeaconChainTypes>(
+        &self,
+        chain: &BeaconChain<T>,
+        slot: Slot,
+    ) -> Result<Option<BeaconState<T::EthSpec>>, warp::Rejection> {
+        let slots_per_restore_point = chain.store.get_config().slots_per_restore_point;
+        if slot >= chain.store.get_split_slot() || slot % slots_per_restore_point == 0 {
+            return Ok(None);
+        }
+
+        if chain.memory_watermark_exceeded() {
+            return Err(warp_utils::reject::service_unavailable(
+                "memory usage is above the watermark".to_string(),
+                Some(MEMORY_WATERMARK_CHECK_INTERVAL),
+            ));
+        }
+
+        let _permit = self.permits.try_acquire().map_err(|_| {
+            warp_utils::reject::service_unavailable(
+                "too many historical states are being reconstructed".to_string(),
+                Some(self.time_budget),
+            )
+        })?;
+
+        let deadline = Instant::now() + self.time_budget;
+
+        match chain
+            .store
+            .load_cold_state_by_slot_with_deadline(slot, Some(deadline))
+        {
+            Ok(Some(state)) => Ok(Some(state)),
+            Ok(None) => Err(warp_utils::reject::custom_not_found(format!(
+                "beacon state at slot {}",
+                slot
+            ))),
+            Err(StoreError::HotColdDBError(HotColdDBError::StateReplayDeadlineExceeded {
+                replayed_to_slot,
+                ..
+            })) => Err(warp_utils::reject::service_unavailable(
+                format!(
+                    "reconstructing the state at slot {} exceeded the time budget of {}s \
+                     (reached slot {})",
+                    slot,
+                    self.time_budget.as_secs(),
+                    replayed_to_slot
+                ),
+                None,
+            )),
+            Err(e) => Err(warp_utils::reject::beacon_chain_error(
+                BeaconChainError::DBError(e),
+            )),
+        }
+    }
+}
@@ -6,14 +6,12 @@ use beacon_chain::sync_committee_verification::{
 };
 use beacon_chain::{
     validator_monitor::timestamp_now, BeaconChain, BeaconChainError, BeaconChainTypes,
-    StateSkipConfig, MAXIMUM_GOSSIP_CLOCK_DISPARITY,
 };
 use eth2::types::{self as api_types};
 use lighthouse_network::PubsubMessage;
 use network::NetworkMessage;
 use slog::{error, warn, Logger};
 use slot_clock::SlotClock;
-use std::cmp::max;
 use std::collections::HashMap;
 use tokio::sync::mpsc::UnboundedSender;
 use types::{
@@ -71,15 +69,15 @@ fn duties_from_state_load<T: BeaconChainTypes>(
     chain: &BeaconChain<T>,
 ) -> Result<Vec<Option<SyncDuty>>, BeaconChainError> {
     // Determine what the current epoch would be if we fast-forward our system clock by
-    // `MAXIMUM_GOSSIP_CLOCK_DISPARITY`.
+    // the `maximum_gossip_clock_disparity`.
     //
     // Most of the time, `tolerant_current_epoch` will be equal to `current_epoch`. However, during
-    // the last `MAXIMUM_GOSSIP_CLOCK_DISPARITY` duration of the epoch `tolerant_current_epoch`
+    // the last `maximum_gossip_clock_disparity` duration of the epoch `tolerant_current_epoch`
     // will equal `current_epoch + 1`
     let current_epoch = chain.epoch()?;
     let tolerant_current_epoch = chain
         .slot_clock
-        .now_with_future_tolerance(MAXIMUM_GOSSIP_CLOCK_DISPARITY)
+        .now_with_future_tolerance(chain.maximum_gossip_clock_disparity())
         .ok_or(BeaconChainError::UnableToReadSlot)?
         .epoch(T::EthSpec::slots_per_epoch());
 
@@ -90,21 +88,8 @@ fn duties_from_state_load<T: BeaconChainTypes>(
         // Empty response if the epoch is pre-Altair.
         Ok(vec![])
     } else if sync_committee_period <= max_sync_committee_period {
-        // Load the state at the start of the *previous* sync committee period.
-        // This is sufficient for historical duties, and efficient in the case where the head
-        // is lagging the current epoch and we need duties for the next period (because we only
-        // have to transition the head to start of the current period).
-        //
-        // We also need to ensure that the load slot is after the Altair fork.
-        let load_slot = max(
-            chain.spec.epochs_per_sync_committee_period * sync_committee_period.saturating_sub(1),
-            altair_fork_epoch,
-        )
-        .start_slot(T::EthSpec::slots_per_epoch());
-
-        let state = chain.state_at_slot(load_slot, StateSkipConfig::WithoutStateRoots)?;
-
-        state
+        chain
+            .state_for_sync_committee_period(sync_committee_period)?
             .get_sync_committee_duties(request_epoch, request_indices, &chain.spec)
             .map_err(BeaconChainError::SyncDutiesError)
     } else {
@@ -0,0 +1,45 @@
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use directory::size_of_dir;
+use eth2::lighthouse::{CompactionData, DatabaseResources, ProcessResources, SystemResources};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Observe the resources used by the beacon node process and its database.
+///
+/// Database sizes are only reported if the database paths are known, i.e. the database is
+/// stored on disk.
+pub fn resources<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    db_path: Option<PathBuf>,
+    freezer_db_path: Option<PathBuf>,
+) -> Result<SystemResources, warp::Rejection> {
+    let process = ProcessResources::observe().map_err(warp_utils::reject::custom_server_error)?;
+
+    let store = &chain.store;
+    let last_compaction_timestamp = store
+        .load_compaction_timestamp()
+        .map_err(|e| {
+            warp_utils::reject::custom_server_error(format!(
+                "unable to load compaction timestamp: {:?}",
+                e
+            ))
+        })?
+        .map(|timestamp| timestamp.as_secs());
+    let compaction_stats = store.compaction_stats();
+
+    Ok(SystemResources {
+        process,
+        database: DatabaseResources {
+            hot_db_bytes: db_path.as_deref().map(size_of_dir),
+            freezer_db_bytes: freezer_db_path.as_deref().map(size_of_dir),
+            compaction: CompactionData {
+                compact_on_prune: store.compact_on_prune(),
+                last_compaction_timestamp,
+                compactions_since_start: compaction_stats.compactions,
+                last_compaction_duration_ms: compaction_stats
+                    .last_compaction_duration
+                    .map(|duration| duration.as_millis() as u64),
+            },
+        },
+    })
+}
@@ -0,0 +1,175 @@
+//! Contains the handlers for the `beacon/states/{state_id}/validators` and
+//! `beacon/states/{state_id}/validator_balances` endpoints.
+//!
+//! The `GET` and `POST` variants of each endpoint share the same filtering, the `POST` variants
+//! just take their ids and statuses from the request body rather than the query string.
+//!
+//! The validators response body is streamed in chunks so that serving the full validator set
+//! doesn't require buffering the entire JSON response in memory.
+use crate::state_id::StateId;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::types::{self as api_types, ValidatorId, ValidatorStatus};
+use futures::stream::{self, StreamExt};
+use std::cmp;
+use std::collections::HashSet;
+use std::sync::Arc;
+use types::{BeaconState, Epoch, EthSpec, PublicKeyBytes, Validator};
+use warp::http::Response;
+use warp::hyper::Body;
+
+/// The number of validators serialized into each chunk of the response body.
+const VALIDATORS_PER_CHUNK: usize = 4_096;
+
+/// The validator ids requested by a client, split into sets so that each validator is matched in
+/// constant time however many ids are requested.
+struct ValidatorIds {
+    indices: HashSet<u64>,
+    pubkeys: HashSet<PublicKeyBytes>,
+}
+
+impl ValidatorIds {
+    fn new(ids: &[ValidatorId]) -> Self {
+        let mut indices = HashSet::new();
+        let mut pubkeys = HashSet::new();
+        for id in ids {
+            match id {
+                ValidatorId::Index(index) => {
+                    indices.insert(*index);
+                }
+                ValidatorId::PublicKey(pubkey) => {
+                    pubkeys.insert(*pubkey);
+                }
+            }
+        }
+        Self { indices, pubkeys }
+    }
+}
+
+/// Returns `true` if the validator at `index` matches any of `ids`, or if `ids` is `None`.
+fn id_matches(ids: Option<&ValidatorIds>, index: usize, validator: &Validator) -> bool {
+    ids.map_or(true, |ids| {
+        ids.indices.contains(&(index as u64)) || ids.pubkeys.contains(&validator.pubkey)
+    })
+}
+
+/// Returns the indices and statuses of the validators in `state` which match any of `ids` and
+/// any of `statuses`, in index order. A `None` filter matches every validator.
+fn filter_validators<'a, E: EthSpec>(
+    state: &'a BeaconState<E>,
+    ids: Option<&'a ValidatorIds>,
+    statuses: Option<&'a [ValidatorStatus]>,
+    far_future_epoch: Epoch,
+) -> impl Iterator<Item = (usize, ValidatorStatus)> + 'a {
+    let epoch = state.current_epoch();
+
+    state
+        .validators()
+        .iter()
+        .enumerate()
+        .filter(move |(index, validator)| id_matches(ids, *index, validator))
+        .filter_map(move |(index, validator)| {
+            let status = ValidatorStatus::from_validator(validator, epoch, far_future_epoch);
+
+            let status_matches = statuses.map_or(true, |statuses| {
+                statuses.contains(&status) || statuses.contains(&status.superstatus())
+            });
+
+            status_matches.then(|| (index, status))
+        })
+}
+
+/// Serve the validators in the given state, filtered by `ids` and `statuses`.
+///
+/// The `offset` and `limit` are applied after filtering.
+pub fn get_validators<T: BeaconChainTypes>(
+    state_id: StateId,
+    chain: Arc<BeaconChain<T>>,
+    ids: Option<Vec<ValidatorId>>,
+    statuses: Option<Vec<ValidatorStatus>>,
+    offset: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Response<Body>, warp::Rejection> {
+    let (state, execution_optimistic) = state_id.state_and_execution_optimistic(&chain)?;
+    let ids = ids.as_deref().map(ValidatorIds::new);
+
+    let offset = offset.unwrap_or(0) as usize;
+    let limit = limit.map_or(usize::MAX, |limit| limit as usize);
+
+    // Only the indices and statuses of the matching validators are collected here, the response
+    // data is built from the state as each chunk is serialized.
+    let matches = filter_validators(
+        &state,
+        ids.as_ref(),
+        statuses.as_deref(),
+        chain.spec.far_future_epoch,
+    )
+    .skip(offset)
+    .take(limit)
+    .collect::<Vec<_>>();
+
+    let num_chunks = (matches.len() + VALIDATORS_PER_CHUNK - 1) / VALIDATORS_PER_CHUNK;
+    let chunks = stream::iter(0..num_chunks).map(move |chunk_index| {
+        let start = chunk_index * VALIDATORS_PER_CHUNK;
+        let end = cmp::min(start + VALIDATORS_PER_CHUNK, matches.len());
+
+        let mut bytes = vec![];
+        for (i, (index, status)) in matches[start..end].iter().enumerate() {
+            if start + i > 0 {
+                bytes.push(b',');
+            }
+            let data = api_types::ValidatorData {
+                index: *index as u64,
+                balance: state.balances()[*index],
+                status: *status,
+                validator: state.validators()[*index].clone(),
+            };
+            serde_json::to_writer(&mut bytes, &data)?;
+        }
+        Ok::<_, serde_json::Error>(bytes)
+    });
+
+    let prefix = format!(
+        "{{\"execution_optimistic\":{},\"data\":[",
+        execution_optimistic
+    );
+    let body = stream::iter(Some(Ok(prefix.into_bytes())))
+        .chain(chunks)
+        .chain(stream::iter(Some(Ok(b"]}".to_vec()))));
+
+    Response::builder()
+        .status(200)
+        .header("Content-Type", "application/json")
+        .body(Body::wrap_stream(body))
+        .map_err(|e| {
+            warp_utils::reject::custom_server_error(format!("failed to create response: {}", e))
+        })
+}
+
+/// Serve the balances of the validators in the given state which match any of `ids`.
+pub fn get_validator_balances<T: BeaconChainTypes>(
+    state_id: StateId,
+    chain: Arc<BeaconChain<T>>,
+    ids: Option<Vec<ValidatorId>>,
+) -> Result<
+    api_types::ExecutionOptimisticResponse<Vec<api_types::ValidatorBalanceData>>,
+    warp::Rejection,
+> {
+    let ids = ids.as_deref().map(ValidatorIds::new);
+
+    state_id.map_state_and_execution_optimistic(&chain, |state, execution_optimistic| {
+        let balances = state
+            .validators()
+            .iter()
+            .zip(state.balances().iter())
+            .enumerate()
+            .filter(|(index, (validator, _))| id_matches(ids.as_ref(), *index, validator))
+            .map(|(index, (_, balance))| api_types::ValidatorBalanceData {
+                index: index as u64,
+                balance: *balance,
+            })
+            .collect::<Vec<_>>();
+
+        Ok(api_types::GenericResponse::from(balances)
+            .add_execution_optimistic(execution_optimistic))
+    })
+}
@@ -7,20 +7,25 @@ use warp::reply::{self, Reply, WithHeader};
 pub const V1: EndpointVersion = EndpointVersion(1);
 pub const V2: EndpointVersion = EndpointVersion(2);
 
+/// Wrap `data` in a `ForkVersionedResponse`.
+///
+/// The `version` and `execution_optimistic` fields are only included in responses to V2 requests.
 pub fn fork_versioned_response<T: Serialize>(
     endpoint_version: EndpointVersion,
     fork_name: ForkName,
+    execution_optimistic: Option<bool>,
     data: T,
 ) -> Result<ForkVersionedResponse<T>, warp::reject::Rejection> {
-    let fork_name = if endpoint_version == V1 {
-        None
+    let (fork_name, execution_optimistic) = if endpoint_version == V1 {
+        (None, None)
     } else if endpoint_version == V2 {
-        Some(fork_name)
+        (Some(fork_name), execution_optimistic)
     } else {
         return Err(unsupported_version_rejection(endpoint_version));
     };
     Ok(ForkVersionedResponse {
         version: fork_name,
+        execution_optimistic,
         data,
     })
 }
@@ -3,7 +3,7 @@ use beacon_chain::{
     BeaconChain, BeaconChainTypes,
 };
 use eth2::{BeaconNodeHttpClient, Timeouts};
-use http_api::{Config, Context};
+use http_api::{AuthConfig, Config, Context};
 use lighthouse_network::{
     discv5::enr::{CombinedKey, EnrBuilder},
     libp2p::{core::connection::ConnectionId, swarm::NetworkBehaviour},
@@ -11,9 +11,10 @@ use lighthouse_network::{
     types::{EnrAttestationBitfield, EnrSyncCommitteeBitfield, SyncState},
     ConnectedPoint, Enr, NetworkGlobals, PeerId, PeerManager,
 };
+use logging::LogLevels;
 use network::NetworkMessage;
 use sensitive_url::SensitiveUrl;
-use slog::Logger;
+use slog::{Level, Logger};
 use std::future::Future;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::Arc;
@@ -48,6 +49,14 @@ pub struct ApiServer<E: EthSpec, SFut: Future<Output = ()>> {
 
 impl<E: EthSpec> InteractiveTester<E> {
     pub async fn new(spec: Option<ChainSpec>, validator_count: usize) -> Self {
+        Self::new_with_config(spec, validator_count, test_config()).await
+    }
+
+    pub async fn new_with_config(
+        spec: Option<ChainSpec>,
+        validator_count: usize,
+        config: Config,
+    ) -> Self {
         let harness = BeaconChainHarness::builder(E::default())
             .spec_or_default(spec)
             .deterministic_keypairs(validator_count)
@@ -60,7 +69,8 @@ impl<E: EthSpec> InteractiveTester<E> {
             shutdown_tx: _server_shutdown,
             network_rx,
             ..
-        } = create_api_server(harness.chain.clone(), harness.logger().clone()).await;
+        } = create_api_server_with_config(harness.chain.clone(), harness.logger().clone(), config)
+            .await;
 
         tokio::spawn(server);
 
@@ -83,9 +93,36 @@ impl<E: EthSpec> InteractiveTester<E> {
     }
 }
 
+/// The HTTP API config used by `create_api_server`.
+pub fn test_config() -> Config {
+    Config {
+        enabled: true,
+        listen_addr: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+        listen_port: 0,
+        allow_origin: None,
+        serve_legacy_spec: true,
+        tls_config: None,
+        allow_sync_stalled: false,
+        auth: AuthConfig::default(),
+        enable_compression: true,
+        max_concurrent_state_replays: 2,
+        state_replay_time_budget: Duration::from_secs(60),
+        rate_limit: None,
+        serve_finalized_only: false,
+    }
+}
+
 pub async fn create_api_server<T: BeaconChainTypes>(
     chain: Arc<BeaconChain<T>>,
     log: Logger,
+) -> ApiServer<T::EthSpec, impl Future<Output = ()>> {
+    create_api_server_with_config(chain, log, test_config()).await
+}
+
+pub async fn create_api_server_with_config<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    log: Logger,
+    config: Config,
 ) -> ApiServer<T::EthSpec, impl Future<Output = ()>> {
     let (network_tx, network_rx) = mpsc::unbounded_channel();
 
@@ -126,19 +163,14 @@ pub async fn create_api_server<T: BeaconChainTypes>(
     let eth1_service = eth1::Service::new(eth1::Config::default(), log.clone(), chain.spec.clone());
 
     let context = Arc::new(Context {
-        config: Config {
-            enabled: true,
-            listen_addr: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
-            listen_port: 0,
-            allow_origin: None,
-            serve_legacy_spec: true,
-            tls_config: None,
-            allow_sync_stalled: false,
-        },
+        config,
         chain: Some(chain.clone()),
         network_tx: Some(network_tx),
         network_globals: Some(network_globals),
         eth1_service: Some(eth1_service),
+        db_path: None,
+        freezer_db_path: None,
+        log_levels: Some(LogLevels::new(Level::Info, vec![])),
         log,
     });
     let ctx = context.clone();
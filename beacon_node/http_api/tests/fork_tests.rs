@@ -74,7 +74,7 @@ async fn sync_committee_duties_across_fork() {
 
     // Sync duties should also be available for the next period.
     let current_period = fork_epoch.sync_committee_period(&spec).unwrap();
-    let next_period_epoch = spec.epochs_per_sync_committee_period * (current_period + 1);
+    let next_period_epoch = Epoch::sync_committee_period_start(current_period + 1, &spec).unwrap();
 
     let next_period_duties = client
         .post_validator_duties_sync(next_period_epoch, &all_validators_u64)
@@ -85,7 +85,8 @@ async fn sync_committee_duties_across_fork() {
 
     // Sync duties should *not* be available for the period after the next period.
     // We expect a 400 (bad request) response.
-    let next_next_period_epoch = spec.epochs_per_sync_committee_period * (current_period + 2);
+    let next_next_period_epoch =
+        Epoch::sync_committee_period_start(current_period + 2, &spec).unwrap();
     assert_eq!(
         client
             .post_validator_duties_sync(next_next_period_epoch, &all_validators_u64)
@@ -280,7 +281,7 @@ async fn sync_committee_indices_across_fork() {
         .unwrap();
 
     let current_period = fork_epoch.sync_committee_period(&spec).unwrap();
-    let next_period_epoch = spec.epochs_per_sync_committee_period * (current_period + 1);
+    let next_period_epoch = Epoch::sync_committee_period_start(current_period + 1, &spec).unwrap();
     assert!(next_period_epoch > fork_epoch);
 
     for epoch in [
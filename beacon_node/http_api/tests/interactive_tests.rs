@@ -1,9 +1,19 @@
 //! Generic tests that make use of the (newer) `InteractiveApiTester`
 use crate::common::*;
 use beacon_chain::test_utils::{AttestationStrategy, BlockStrategy};
-use eth2::types::DepositContractData;
+use eth2::reqwest::{
+    header::{HeaderMap, HeaderValue, AUTHORIZATION},
+    Client,
+};
+use eth2::types::{BlockId, DepositContractData, EventKind, EventTopic, SseDutiesReorg, StateId};
+use eth2::{BeaconNodeHttpClient, Timeouts};
+use futures::stream::StreamExt;
+use http_api::{AuthConfig, Config, RateLimitConfig};
+use sensitive_url::SensitiveUrl;
+use std::path::PathBuf;
+use std::time::Duration;
 use tree_hash::TreeHash;
-use types::{EthSpec, FullPayload, MainnetEthSpec, Slot};
+use types::{Epoch, EthSpec, FullPayload, MainnetEthSpec, Slot};
 
 type E = MainnetEthSpec;
 
@@ -125,3 +135,407 @@ pub async fn fork_choice_before_proposal() {
     // D's parent is B.
     assert_eq!(block_d.parent_root(), block_root_b.into());
 }
+
+/// Return a client for the same server as `client` which sends `token` as a bearer token.
+fn client_with_token(client: &BeaconNodeHttpClient, token: &str) -> BeaconNodeHttpClient {
+    client_with_authorization(client, &format!("Bearer {}", token))
+}
+
+/// Return a client for the same server as `client` which sends `authorization` as its
+/// `Authorization` header.
+fn client_with_authorization(
+    client: &BeaconNodeHttpClient,
+    authorization: &str,
+) -> BeaconNodeHttpClient {
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_str(authorization).unwrap());
+    BeaconNodeHttpClient::from_components(
+        SensitiveUrl::parse(client.as_ref()).unwrap(),
+        Client::builder().default_headers(headers).build().unwrap(),
+        Timeouts::set_all(Duration::from_secs(1)),
+    )
+}
+
+// Test that validator and admin endpoints are restricted when tokens are configured.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn http_api_auth_tiers() {
+    let config = Config {
+        auth: AuthConfig {
+            validator_token: Some("validator".to_string()),
+            admin_token: Some("admin".to_string()),
+        },
+        ..test_config()
+    };
+    let tester = InteractiveTester::<E>::new_with_config(None, 32, config).await;
+
+    let public = &tester.client;
+    let wrong = client_with_token(public, "wrong");
+    let no_scheme = client_with_authorization(public, "admin");
+    let validator = client_with_token(public, "validator");
+    let admin = client_with_token(public, "admin");
+
+    // Public endpoints don't require a token.
+    public.get_beacon_genesis().await.unwrap();
+    wrong.get_beacon_genesis().await.unwrap();
+
+    // Validator endpoints require the validator or admin token, sent with the `Bearer` scheme.
+    for client in [public, &wrong, &no_scheme] {
+        assert_eq!(
+            client
+                .get_validator_duties_proposer(Epoch::new(0))
+                .await
+                .unwrap_err()
+                .status()
+                .map(Into::into),
+            Some(403)
+        );
+    }
+    validator
+        .get_validator_duties_proposer(Epoch::new(0))
+        .await
+        .unwrap();
+    admin
+        .get_validator_duties_proposer(Epoch::new(0))
+        .await
+        .unwrap();
+
+    // Admin endpoints require the admin token.
+    for client in [public, &wrong, &validator] {
+        assert_eq!(
+            client
+                .get_lighthouse_database_info()
+                .await
+                .unwrap_err()
+                .status()
+                .map(Into::into),
+            Some(403)
+        );
+    }
+    admin.get_lighthouse_database_info().await.unwrap();
+}
+
+// Test that a validator client configured with `--beacon-nodes-token-file` is authorized for
+// validator endpoints.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn http_api_validator_client_token_file() {
+    let config = Config {
+        auth: AuthConfig {
+            validator_token: Some("validator".to_string()),
+            admin_token: None,
+        },
+        ..test_config()
+    };
+    let tester = InteractiveTester::<E>::new_with_config(None, 32, config).await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let token_file = dir.path().join("token");
+    std::fs::write(&token_file, "validator\n").unwrap();
+
+    let url = SensitiveUrl::parse(tester.client.as_ref()).unwrap();
+    let vc_client = |token_files: Option<Vec<PathBuf>>| {
+        let vc_config = validator_client::Config {
+            beacon_nodes: vec![url.clone()],
+            beacon_nodes_token_files: token_files,
+            ..Default::default()
+        };
+        BeaconNodeHttpClient::from_components(
+            url.clone(),
+            validator_client::build_beacon_node_client(&vc_config, 0, Duration::from_secs(1))
+                .unwrap(),
+            Timeouts::set_all(Duration::from_secs(1)),
+        )
+    };
+
+    assert_eq!(
+        vc_client(None)
+            .get_validator_duties_proposer(Epoch::new(0))
+            .await
+            .unwrap_err()
+            .status()
+            .map(Into::into),
+        Some(403)
+    );
+    vc_client(Some(vec![token_file]))
+        .get_validator_duties_proposer(Epoch::new(0))
+        .await
+        .unwrap();
+}
+
+// Test that clients are rate limited per IP address or API token, and that exhausted clients
+// receive a 429 with the standard rate limiting headers.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn http_api_rate_limit() {
+    let config = Config {
+        auth: AuthConfig {
+            validator_token: Some("validator".to_string()),
+            admin_token: None,
+        },
+        rate_limit: Some(RateLimitConfig {
+            burst: 10,
+            tokens_per_second: 1,
+            cheap_cost: 1,
+            standard_cost: 5,
+            expensive_cost: 10,
+        }),
+        ..test_config()
+    };
+    let tester = InteractiveTester::<E>::new_with_config(None, 32, config).await;
+
+    let public = &tester.client;
+    let validator = client_with_token(public, "validator");
+
+    // Exhaust the bucket for this IP address with cheap requests.
+    for _ in 0..10 {
+        public.get_beacon_genesis().await.unwrap();
+    }
+
+    let response = Client::new()
+        .get(format!(
+            "{}/eth/v1/beacon/genesis",
+            public.as_ref().trim_end_matches('/')
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 429);
+    let headers = response.headers();
+    assert_eq!(headers.get("retry-after").unwrap(), "1");
+    assert_eq!(headers.get("ratelimit-limit").unwrap(), "10");
+    assert_eq!(headers.get("ratelimit-remaining").unwrap(), "0");
+
+    // Clients with a valid token have their own bucket, which expensive requests drain at once.
+    validator
+        .get_beacon_states_validators(StateId::Head, None, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        validator
+            .get_beacon_genesis()
+            .await
+            .unwrap_err()
+            .status()
+            .map(Into::into),
+        Some(429)
+    );
+}
+
+/// Build a chain with enough finalized epochs that the early states are only available by
+/// replaying blocks from the freezer database.
+async fn tester_with_frozen_states(config: Config) -> InteractiveTester<E> {
+    let tester = InteractiveTester::<E>::new_with_config(None, 32, config).await;
+    let harness = &tester.harness;
+
+    harness.advance_slot();
+    harness.extend_chain(
+        E::slots_per_epoch() as usize * 5,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    assert!(
+        harness.chain.store.get_split_slot() > 1,
+        "precondition: early states are frozen"
+    );
+
+    tester
+}
+
+// Test that historical states between restore points are reconstructed by replay.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn historical_state_replay() {
+    let tester = tester_with_frozen_states(test_config()).await;
+
+    let state = tester
+        .client
+        .get_debug_beacon_states::<E>(StateId::Slot(Slot::new(1)))
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    assert_eq!(state.slot(), Slot::new(1));
+}
+
+// Test that historical state replays are rejected with a `Retry-After` header when the replay
+// queue is full, while states that don't require replay are still served.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn historical_state_replay_queue_full() {
+    let config = Config {
+        max_concurrent_state_replays: 0,
+        state_replay_time_budget: Duration::from_secs(30),
+        ..test_config()
+    };
+    let tester = tester_with_frozen_states(config).await;
+
+    let response = Client::new()
+        .get(format!(
+            "{}/eth/v2/debug/beacon/states/1",
+            tester.client.as_ref().trim_end_matches('/')
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 503);
+    assert_eq!(
+        response
+            .headers()
+            .get("retry-after")
+            .and_then(|value| value.to_str().ok()),
+        Some("30")
+    );
+
+    tester
+        .client
+        .get_debug_beacon_states::<E>(StateId::Head)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+// Test that only finalized states and blocks are served when `serve_finalized_only` is set.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn http_api_serve_finalized_only() {
+    let config = Config {
+        serve_finalized_only: true,
+        ..test_config()
+    };
+    let tester = tester_with_frozen_states(config).await;
+    let client = &tester.client;
+    let head = tester.harness.chain.head_info().unwrap();
+    let finalized_slot = head
+        .finalized_checkpoint
+        .epoch
+        .start_slot(E::slots_per_epoch());
+
+    // Finalized states are served.
+    for state_id in [
+        StateId::Genesis,
+        StateId::Finalized,
+        StateId::Slot(Slot::new(1)),
+        StateId::Slot(finalized_slot),
+    ] {
+        assert!(client
+            .get_beacon_states_root(state_id)
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    // Non-finalized states are refused.
+    for state_id in [
+        StateId::Head,
+        StateId::Slot(head.slot),
+        StateId::Root(head.state_root),
+    ] {
+        assert!(client
+            .get_beacon_states_root(state_id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    // Finalized blocks are served.
+    for block_id in [
+        BlockId::Genesis,
+        BlockId::Finalized,
+        BlockId::Root(head.finalized_checkpoint.root),
+    ] {
+        assert!(client
+            .get_beacon_blocks_root(block_id)
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    // Non-finalized blocks are refused, including the head header.
+    for block_id in [
+        BlockId::Head,
+        BlockId::Slot(head.slot),
+        BlockId::Root(head.block_root),
+    ] {
+        assert!(client
+            .get_beacon_blocks_root(block_id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+    assert!(client
+        .get_beacon_headers(None, None)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+// Test that re-orging the last block of an epoch notifies subscribers that the duties which depend
+// on it have changed.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn duties_reorg_events() {
+    // Validator count needs to be at least 32 or proposer boost gets set to 0 when computing
+    // `validator_count // 32`.
+    let validator_count = 32;
+    let slots_per_epoch = E::slots_per_epoch();
+    let num_initial = slots_per_epoch - 2;
+
+    let tester = InteractiveTester::<E>::new(None, validator_count).await;
+    let harness = &tester.harness;
+
+    harness.advance_slot();
+    harness.extend_chain(
+        num_initial as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    // B is the last block of epoch 0 and is re-orged out by C, which is proposer boosted at the
+    // first slot of epoch 1.
+    //
+    // A | B | - |
+    // ^ | - | C |
+    let slot_a = Slot::new(num_initial);
+    let slot_b = slot_a + 1;
+    let slot_c = slot_a + 2;
+
+    let state_a = harness.get_current_state();
+    let block_root_a = harness.chain.head_info().unwrap().block_root;
+    let (block_b, _) = harness.make_block(state_a.clone(), slot_b);
+    let block_root_b = harness.process_block(slot_b, block_b).unwrap();
+
+    let events = tester
+        .client
+        .get_events::<E>(&[
+            EventTopic::AttesterDutiesReorg,
+            EventTopic::ProposerDutiesReorg,
+        ])
+        .await
+        .unwrap();
+
+    let (block_c, _) = harness.make_block(state_a, slot_c);
+    let block_root_c = harness.process_block(slot_c, block_c).unwrap();
+    assert_eq!(
+        harness.chain.head_info().unwrap().block_root,
+        block_root_c.into()
+    );
+
+    let events = tokio::time::timeout(Duration::from_secs(10), events.take(2).collect::<Vec<_>>())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>();
+
+    // Attester duties for epoch 2 and proposer duties for epoch 1 both depend on the last block of
+    // epoch 0, which is now A instead of B.
+    let reorg = |epoch| SseDutiesReorg {
+        epoch: Epoch::new(epoch),
+        old_dependent_root: block_root_b.into(),
+        new_dependent_root: block_root_a,
+        slot: slot_c,
+        new_head_block: block_root_c.into(),
+    };
+    assert_eq!(
+        events,
+        vec![
+            EventKind::AttesterDutiesReorg(reorg(2)),
+            EventKind::ProposerDutiesReorg(reorg(1)),
+        ]
+    );
+}
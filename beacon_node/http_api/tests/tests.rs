@@ -6,6 +6,11 @@ use beacon_chain::{
 };
 use environment::null_logger;
 use eth2::{
+    lighthouse::{
+        BeaconProcessorQueues, ClockSkewStatus, DatabaseJob, DatabaseJobKind, DatabaseJobStatus,
+        EnrField, EnrUpdateRequest, LogLevelsConfig, PeerDiversity, PortMappingStatus,
+        SlotBlockRoot, SyncLookups,
+    },
     mixin::{RequestAccept, ResponseForkName, ResponseOptional},
     reqwest::RequestBuilder,
     types::*,
@@ -469,6 +474,38 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_expected_withdrawals_pre_capella(self) -> Self {
+        for state_id in self.interesting_state_ids() {
+            let result = self.client.get_expected_withdrawals(state_id, None).await;
+
+            if self.get_state(state_id).is_some() {
+                assert_eq!(
+                    result.unwrap_err().status().map(Into::into),
+                    Some(400),
+                    "{:?}",
+                    state_id
+                );
+            } else {
+                assert!(result.unwrap().is_none(), "{:?}", state_id);
+            }
+        }
+
+        self
+    }
+
+    pub async fn test_get_expected_withdrawals_proposal_slot_too_far(self) -> Self {
+        let head_slot = self.chain.head_info().unwrap().slot;
+        let too_far = (head_slot.epoch(E::slots_per_epoch()) + 2).start_slot(E::slots_per_epoch());
+
+        let result = self
+            .client
+            .get_expected_withdrawals(StateId::Head, Some(too_far))
+            .await;
+        assert_eq!(result.unwrap_err().status().map(Into::into), Some(400));
+
+        self
+    }
+
     pub async fn test_beacon_states_finality_checkpoints(self) -> Self {
         for state_id in self.interesting_state_ids() {
             let result = self
@@ -553,6 +590,15 @@ impl ApiTester {
 
                 assert_eq!(result_index_ids, expected, "{:?}", state_id);
                 assert_eq!(result_pubkey_ids, expected, "{:?}", state_id);
+
+                let result_post = self
+                    .client
+                    .post_beacon_states_validator_balances(state_id, validator_pubkey_ids)
+                    .await
+                    .unwrap()
+                    .map(|res| res.data);
+
+                assert_eq!(result_post, expected, "{:?}", state_id);
             }
         }
 
@@ -641,6 +687,19 @@ impl ApiTester {
 
                     assert_eq!(result_index_ids, expected, "{:?}", state_id);
                     assert_eq!(result_pubkey_ids, expected, "{:?}", state_id);
+
+                    let result_post = self
+                        .client
+                        .post_beacon_states_validators(
+                            state_id,
+                            Some(validator_pubkey_ids),
+                            (!statuses.is_empty()).then(|| statuses.clone()),
+                        )
+                        .await
+                        .unwrap()
+                        .map(|res| res.data);
+
+                    assert_eq!(result_post, expected, "{:?}", state_id);
                 }
             }
         }
@@ -648,6 +707,63 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_beacon_states_validators_pagination(self) -> Self {
+        for state_id in self.interesting_state_ids() {
+            let all = self
+                .client
+                .get_beacon_states_validators(state_id, None, None)
+                .await
+                .unwrap()
+                .map(|res| res.data);
+
+            let all = match all {
+                Some(all) => all,
+                None => continue,
+            };
+            assert_eq!(
+                all.len(),
+                self.get_state(state_id).unwrap().validators().len(),
+                "{:?}",
+                state_id
+            );
+
+            let num_validators = all.len() as u64;
+            for (offset, limit) in [
+                (None, None),
+                (Some(0), Some(1)),
+                (Some(1), Some(3)),
+                (Some(3), None),
+                (None, Some(num_validators + 1)),
+                (Some(num_validators), None),
+                (Some(num_validators + 10), Some(2)),
+                (Some(0), Some(0)),
+            ] {
+                let result = self
+                    .client
+                    .get_beacon_states_validators_paginated(state_id, None, None, offset, limit)
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .data;
+
+                let expected = all
+                    .iter()
+                    .skip(offset.unwrap_or(0) as usize)
+                    .take(limit.map_or(usize::MAX, |limit| limit as usize))
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                assert_eq!(
+                    result, expected,
+                    "{:?} offset: {:?} limit: {:?}",
+                    state_id, offset, limit
+                );
+            }
+        }
+
+        self
+    }
+
     pub async fn test_beacon_states_validator_id(self) -> Self {
         for state_id in self.interesting_state_ids() {
             let state_opt = self.get_state(state_id);
@@ -769,6 +885,59 @@ impl ApiTester {
         self.chain.get_block(&root).await.unwrap()
     }
 
+    pub async fn test_execution_optimistic_flags(self) -> Self {
+        // The test chain is never optimistic, but the flag should always be present.
+        for state_id in [StateId::Head, StateId::Finalized, StateId::Genesis] {
+            let root = self
+                .client
+                .get_beacon_states_root(state_id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(root.execution_optimistic, Some(false));
+
+            let checkpoints = self
+                .client
+                .get_beacon_states_finality_checkpoints(state_id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(checkpoints.execution_optimistic, Some(false));
+        }
+
+        for block_id in [BlockId::Head, BlockId::Finalized, BlockId::Genesis] {
+            let root = self
+                .client
+                .get_beacon_blocks_root(block_id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(root.execution_optimistic, Some(false));
+
+            let block = self
+                .client
+                .get_beacon_blocks::<E>(block_id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(block.execution_optimistic, Some(false));
+        }
+
+        let syncing = self.client.get_node_syncing().await.unwrap();
+        assert_eq!(syncing.data.is_optimistic, Some(false));
+
+        self
+    }
+
+    pub async fn test_get_deposit_snapshot(self) -> Self {
+        let result = self.client.get_deposit_snapshot().await.unwrap();
+
+        // The deposit tree of the test eth1 service is never finalized.
+        assert!(result.is_none());
+
+        self
+    }
+
     pub async fn test_beacon_headers_all_slots(self) -> Self {
         for slot in 0..CHAIN_LENGTH {
             let slot = Slot::from(slot);
@@ -935,15 +1104,137 @@ impl ApiTester {
         self
     }
 
+    /// Returns `self.next_block` with an invalid state root, re-signed by its proposer so that it
+    /// passes gossip verification but fails consensus verification.
+    fn next_block_with_invalid_state_root(&self) -> SignedBeaconBlock<E> {
+        let (mut block, _) = self.next_block.clone().deconstruct();
+        *block.state_root_mut() = Hash256::repeat_byte(0xff);
+
+        let proposer_index = block.proposer_index() as usize;
+        let head_state = self.chain.head_beacon_state().unwrap();
+        block.sign(
+            &self.validator_keypairs[proposer_index].sk,
+            &head_state.fork(),
+            head_state.genesis_validators_root(),
+            &self.chain.spec,
+        )
+    }
+
     pub async fn test_post_beacon_blocks_invalid(mut self) -> Self {
+        let next_block = self.next_block_with_invalid_state_root();
+
+        assert!(self.client.post_beacon_blocks(&next_block).await.is_err());
+
+        assert!(
+            self.network_rx.recv().await.is_some(),
+            "gossip valid blocks should be sent to network"
+        );
+
+        self
+    }
+
+    pub async fn test_post_beacon_blocks_invalid_gossip(mut self) -> Self {
         let mut next_block = self.next_block.clone();
         *next_block.message_mut().proposer_index_mut() += 1;
 
-        assert!(self.client.post_beacon_blocks(&next_block).await.is_err());
+        let err = self
+            .client
+            .post_beacon_blocks(&next_block)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().map(Into::into), Some(400));
+
+        assert!(
+            self.network_rx.try_recv().is_err(),
+            "gossip invalid blocks should not be sent to network"
+        );
+
+        self
+    }
+
+    pub async fn test_post_beacon_blocks_valid_with_validation(
+        mut self,
+        validation_level: BroadcastValidation,
+    ) -> Self {
+        self.client
+            .post_beacon_blocks_with_validation(&self.next_block, validation_level)
+            .await
+            .unwrap();
+
+        assert!(
+            self.network_rx.recv().await.is_some(),
+            "valid blocks should be sent to network"
+        );
+
+        self
+    }
+
+    pub async fn test_post_beacon_blocks_invalid_with_validation(
+        mut self,
+        validation_level: BroadcastValidation,
+    ) -> Self {
+        let next_block = self.next_block_with_invalid_state_root();
+
+        let err = self
+            .client
+            .post_beacon_blocks_with_validation(&next_block, validation_level)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().map(Into::into), Some(400));
+
+        assert!(
+            self.network_rx.try_recv().is_err(),
+            "consensus invalid blocks should not be sent to network"
+        );
+
+        self
+    }
+
+    pub async fn test_post_beacon_blocks_equivocation(mut self) -> Self {
+        // Import `next_block` without gossip verification, so that only fork choice knows of it.
+        self.chain.process_block(self.next_block.clone()).unwrap();
+
+        let err = self
+            .client
+            .post_beacon_blocks_with_validation(
+                &self.reorg_block,
+                BroadcastValidation::ConsensusAndEquivocation,
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().map(Into::into), Some(400));
+
+        assert!(
+            self.network_rx.try_recv().is_err(),
+            "equivocating blocks should not be sent to network"
+        );
+        assert!(
+            !self
+                .chain
+                .fork_choice
+                .read()
+                .contains_block(&self.reorg_block.canonical_root()),
+            "equivocating blocks should not be imported"
+        );
+
+        self
+    }
+
+    pub async fn test_post_beacon_blinded_blocks_valid(mut self) -> Self {
+        let next_block = SignedBlindedBeaconBlock::from(self.next_block.clone());
+
+        self.client
+            .post_beacon_blinded_blocks(&next_block)
+            .await
+            .unwrap();
 
         assert!(
             self.network_rx.recv().await.is_some(),
-            "invalid blocks should be sent to network"
+            "valid blinded blocks should be sent to network"
+        );
+        assert_eq!(
+            self.chain.head_info().unwrap().block_root,
+            self.next_block.canonical_root()
         );
 
         self
@@ -1316,9 +1607,15 @@ impl ApiTester {
         let result = self.client.get_node_syncing().await.unwrap().data;
         let head_slot = self.chain.head_info().unwrap().slot;
         let sync_distance = self.chain.slot().unwrap() - head_slot;
+        let el_offline = match &self.chain.execution_layer {
+            Some(el) => Some(el.is_offline().await),
+            None => None,
+        };
 
         let expected = SyncingData {
             is_syncing: false,
+            is_optimistic: Some(false),
+            el_offline,
             head_slot,
             sync_distance,
         };
@@ -2240,65 +2537,313 @@ impl ApiTester {
         self
     }
 
-    pub async fn test_get_lighthouse_syncing(self) -> Self {
-        self.client.get_lighthouse_syncing().await.unwrap();
+    #[cfg(target_os = "linux")]
+    pub async fn test_get_lighthouse_system(self) -> Self {
+        let result = self.client.get_lighthouse_system().await.unwrap().data;
+
+        assert!(result.process.open_file_descriptors > 0);
+        // The test harness uses an in-memory database.
+        assert_eq!(result.database.hot_db_bytes, None);
+        assert_eq!(result.database.freezer_db_bytes, None);
 
         self
     }
 
-    pub async fn test_get_lighthouse_proto_array(self) -> Self {
-        self.client.get_lighthouse_proto_array().await.unwrap();
+    #[cfg(not(target_os = "linux"))]
+    pub async fn test_get_lighthouse_system(self) -> Self {
+        self.client.get_lighthouse_system().await.unwrap_err();
 
         self
     }
 
-    pub async fn test_get_lighthouse_validator_inclusion_global(self) -> Self {
-        let epoch = self.chain.epoch().unwrap() - 1;
-        self.client
-            .get_lighthouse_validator_inclusion_global(epoch)
-            .await
-            .unwrap();
+    pub async fn test_post_lighthouse_enr(mut self) -> Self {
+        let request = EnrUpdateRequest {
+            ip: Some("203.0.113.7".parse().unwrap()),
+            tcp_port: Some(9100),
+            udp_port: None,
+            custom_fields: vec![EnrField {
+                key: "client".to_string(),
+                value: b"lighthouse".to_vec(),
+            }],
+        };
+
+        // Respond on behalf of the network service, which performs the update.
+        let client = &self.client;
+        let network_rx = &mut self.network_rx;
+        let local_enr = self.local_enr.clone();
+        let network_service = async {
+            // Skip any messages published by earlier tests.
+            loop {
+                match network_rx.recv().await {
+                    Some(NetworkMessage::UpdateEnr { update, result_tx }) => {
+                        assert_eq!(update.ip, request.ip);
+                        assert_eq!(update.tcp_port, Some(9100));
+                        assert_eq!(update.udp_port, None);
+                        assert_eq!(
+                            update.custom_fields,
+                            vec![("client".to_string(), b"lighthouse".to_vec())]
+                        );
+                        result_tx.send(Ok(local_enr.clone())).unwrap();
+                        break;
+                    }
+                    Some(_) => continue,
+                    None => panic!("network channel closed"),
+                }
+            }
+        };
+
+        let (result, ()) = tokio::join!(client.post_lighthouse_enr(&request), network_service);
+        assert_eq!(result.unwrap().data, self.local_enr);
 
         self
     }
 
-    pub async fn test_get_lighthouse_validator_inclusion(self) -> Self {
-        let epoch = self.chain.epoch().unwrap() - 1;
+    pub async fn test_get_lighthouse_attestation_pools(self) -> Self {
         self.client
-            .get_lighthouse_validator_inclusion(epoch, ValidatorId::Index(0))
+            .post_beacon_pool_attestations(self.attestations.as_slice())
             .await
             .unwrap();
 
+        let slot = self.attestations[0].data.slot;
+
+        let result = self
+            .client
+            .get_lighthouse_naive_aggregation_pool::<E>(Some(slot), None)
+            .await
+            .unwrap()
+            .data;
+        let expected = self
+            .chain
+            .naive_aggregation_pool
+            .read()
+            .iter()
+            .filter(|attestation| attestation.data.slot == slot)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert!(!result.is_empty());
+        assert_eq!(result, expected);
+
+        let result = self
+            .client
+            .get_lighthouse_naive_aggregation_pool::<E>(None, Some(u64::MAX))
+            .await
+            .unwrap()
+            .data;
+        assert!(
+            result.is_empty(),
+            "no attestations have this committee index"
+        );
+
+        let result = self
+            .client
+            .get_lighthouse_op_pool_attestations::<E>(Some(slot), None)
+            .await
+            .unwrap()
+            .data;
+        let expected = self
+            .chain
+            .op_pool
+            .get_filtered_attestations(|attestation| attestation.data.slot == slot);
+        assert_eq!(result.len(), expected.len());
+        for pooled in result {
+            assert!(expected.contains(&pooled.attestation));
+            assert_eq!(
+                pooled.num_attesters,
+                pooled.attestation.aggregation_bits.num_set_bits()
+            );
+        }
+
         self
     }
 
-    pub async fn test_get_lighthouse_eth1_syncing(self) -> Self {
-        self.client.get_lighthouse_eth1_syncing().await.unwrap();
+    pub async fn test_get_lighthouse_op_pool_attestations_head_too_far_behind(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_op_pool_attestations::<E>(None, None)
+            .await;
+        assert_eq!(result.unwrap_err().status().map(Into::into), Some(503));
 
         self
     }
 
-    pub async fn test_get_lighthouse_eth1_block_cache(self) -> Self {
-        let blocks = self.client.get_lighthouse_eth1_block_cache().await.unwrap();
+    pub async fn test_get_lighthouse_syncing(self) -> Self {
+        self.client.get_lighthouse_syncing().await.unwrap();
 
-        assert!(blocks.data.is_empty());
+        self
+    }
+
+    pub async fn test_get_lighthouse_sync_lookups(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_sync_lookups()
+            .await
+            .unwrap()
+            .data;
+
+        // The test network globals aren't updated by a running sync manager.
+        assert_eq!(result, SyncLookups::default());
 
         self
     }
 
-    pub async fn test_get_lighthouse_eth1_deposit_cache(self) -> Self {
-        let deposits = self
+    pub async fn test_get_lighthouse_beacon_processor_queues(self) -> Self {
+        let result = self
             .client
-            .get_lighthouse_eth1_deposit_cache()
+            .get_lighthouse_beacon_processor_queues()
             .await
-            .unwrap();
+            .unwrap()
+            .data;
 
-        assert!(deposits.data.is_empty());
+        // The test network globals aren't updated by a running beacon processor.
+        assert_eq!(result, BeaconProcessorQueues::default());
 
         self
     }
 
-    pub async fn test_get_lighthouse_beacon_states_ssz(self) -> Self {
+    pub async fn test_get_lighthouse_ready(self) -> Self {
+        let result = self.client.get_lighthouse_ready().await.unwrap().data;
+
+        assert_eq!(
+            result.map(|readiness| (readiness.fork, readiness.fork_epoch)),
+            self.chain.next_scheduled_fork()
+        );
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_network_port_mappings(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_network_port_mappings()
+            .await
+            .unwrap()
+            .data;
+
+        // The test network globals aren't backed by a running network service.
+        assert_eq!(result, PortMappingStatus::default());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_network_clock_skew(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_network_clock_skew()
+            .await
+            .unwrap()
+            .data;
+
+        // No gossip has been received, so there are no samples to estimate from.
+        assert_eq!(result, ClockSkewStatus::default());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_peers_diversity(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_peers_diversity()
+            .await
+            .unwrap()
+            .data;
+
+        // The distribution is only computed by the peer manager heartbeat.
+        assert_eq!(result, PeerDiversity::default());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_gossipsub_scores(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_gossipsub_scores()
+            .await
+            .unwrap()
+            .data;
+
+        // The test network globals aren't backed by a running libp2p service.
+        assert!(result.peers.is_empty());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_peers_verbose(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_peers_verbose()
+            .await
+            .unwrap()
+            .data;
+
+        let peer = result
+            .iter()
+            .find(|peer| peer.peer_id == self.external_peer_id.to_string())
+            .expect("external peer should be known");
+
+        assert_eq!(peer.state, PeerState::Connected);
+        assert_eq!(peer.direction, Some(PeerDirection::Inbound));
+        assert!(!peer.is_trusted);
+        assert_eq!(peer.penalties, eth2::lighthouse::PeerPenalties::default());
+        // No status message has been received from the peer.
+        assert_eq!(peer.head_slot, None);
+        assert_eq!(peer.sync_distance, None);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_proto_array(self) -> Self {
+        self.client.get_lighthouse_proto_array().await.unwrap();
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_validator_inclusion_global(self) -> Self {
+        let epoch = self.chain.epoch().unwrap() - 1;
+        self.client
+            .get_lighthouse_validator_inclusion_global(epoch)
+            .await
+            .unwrap();
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_validator_inclusion(self) -> Self {
+        let epoch = self.chain.epoch().unwrap() - 1;
+        self.client
+            .get_lighthouse_validator_inclusion(epoch, ValidatorId::Index(0))
+            .await
+            .unwrap();
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_eth1_syncing(self) -> Self {
+        self.client.get_lighthouse_eth1_syncing().await.unwrap();
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_eth1_block_cache(self) -> Self {
+        let blocks = self.client.get_lighthouse_eth1_block_cache().await.unwrap();
+
+        assert!(blocks.data.is_empty());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_eth1_deposit_cache(self) -> Self {
+        let deposits = self
+            .client
+            .get_lighthouse_eth1_deposit_cache()
+            .await
+            .unwrap();
+
+        assert!(deposits.data.is_empty());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_beacon_states_ssz(self) -> Self {
         for state_id in self.interesting_state_ids() {
             let result = self
                 .client
@@ -2315,6 +2860,150 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_beacon_block_roots(self) -> Self {
+        let head_slot = self.chain.head_info().unwrap().slot;
+
+        let ranges = [
+            (Slot::new(0), 1),
+            (Slot::new(0), SLOTS_PER_EPOCH * 2),
+            (Slot::new(SLOTS_PER_EPOCH - 1), 4),
+            (Slot::new(SLOTS_PER_EPOCH * 3), SLOTS_PER_EPOCH),
+            (head_slot - 2, 10),
+            (head_slot + 1, 10),
+            (Slot::new(4), 0),
+        ];
+
+        for (start_slot, count) in ranges {
+            let result = self
+                .client
+                .get_lighthouse_beacon_block_roots(start_slot, count)
+                .await
+                .unwrap()
+                .data;
+
+            let expected = (start_slot.as_u64()..start_slot.as_u64() + count)
+                .map(Slot::new)
+                .filter(|slot| *slot <= head_slot)
+                .filter_map(|slot| {
+                    self.chain
+                        .block_root_at_slot(slot, WhenSlotSkipped::None)
+                        .unwrap()
+                        .map(|root| SlotBlockRoot { slot, root })
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(result, expected, "{} {}", start_slot, count);
+        }
+
+        // Requests for too many slots are rejected.
+        assert_eq!(
+            self.client
+                .get_lighthouse_beacon_block_roots(Slot::new(0), 8_193)
+                .await
+                .unwrap_err()
+                .status()
+                .map(Into::into),
+            Some(400)
+        );
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_beacon_proofs(self) -> Self {
+        let head = self.chain.head().unwrap();
+
+        // `BeaconState.finalized_checkpoint.root`.
+        let state_proof = self
+            .client
+            .get_lighthouse_beacon_proofs_state(StateId::Head, 105)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(state_proof.root, head.beacon_state_root());
+        assert_eq!(
+            state_proof.leaf,
+            head.beacon_state.finalized_checkpoint().root
+        );
+        assert!(types::generalized_index::verify_generalized_index_proof(
+            state_proof.leaf,
+            &state_proof.proof,
+            105,
+            state_proof.root
+        ));
+
+        // `BeaconBlock.state_root`.
+        let block_proof = self
+            .client
+            .get_lighthouse_beacon_proofs_block(BlockId::Head, 11)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(block_proof.root, head.beacon_block_root);
+        assert_eq!(block_proof.leaf, head.beacon_block.state_root());
+        assert!(types::generalized_index::verify_generalized_index_proof(
+            block_proof.leaf,
+            &block_proof.proof,
+            11,
+            block_proof.root
+        ));
+
+        // Indices within lists are rejected.
+        assert_eq!(
+            self.client
+                .get_lighthouse_beacon_proofs_state(StateId::Head, 43 * 2)
+                .await
+                .unwrap_err()
+                .status()
+                .map(Into::into),
+            Some(400)
+        );
+
+        self
+    }
+
+    pub async fn test_get_light_client_bootstrap(self) -> Self {
+        let finalized_root = self.chain.head_info().unwrap().finalized_checkpoint.root;
+
+        let response = self
+            .client
+            .get_light_client_bootstrap::<E>(finalized_root)
+            .await
+            .unwrap()
+            .unwrap();
+        let bootstrap = response.data;
+
+        let block = self
+            .chain
+            .get_blinded_block(&finalized_root)
+            .unwrap()
+            .unwrap();
+        let state = self
+            .chain
+            .get_state(&block.state_root(), Some(block.slot()))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(response.version, Some(ForkName::Altair));
+        assert_eq!(bootstrap.header.canonical_root(), finalized_root);
+        assert_eq!(
+            bootstrap.current_sync_committee,
+            state.current_sync_committee().unwrap().clone()
+        );
+        assert!(bootstrap.is_valid_branch());
+
+        // Bootstraps are only stored for finalized blocks.
+        let head_root = self.chain.head_info().unwrap().block_root;
+        assert_eq!(
+            self.client
+                .get_light_client_bootstrap::<E>(head_root)
+                .await
+                .unwrap(),
+            None
+        );
+
+        self
+    }
+
     pub async fn test_get_lighthouse_staking(self) -> Self {
         let result = self.client.get_lighthouse_staking().await.unwrap();
 
@@ -2328,6 +3017,7 @@ impl ApiTester {
 
         assert_eq!(info.anchor, self.chain.store.get_anchor_info());
         assert_eq!(info.split, self.chain.store.get_split_info());
+        assert_eq!(info.blob_info, self.chain.store.get_blob_info());
         assert_eq!(
             info.schema_version,
             store::metadata::CURRENT_SCHEMA_VERSION.as_u64()
@@ -2336,6 +3026,68 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_analysis_block_packing(self) -> Self {
+        let start_epoch = Epoch::new(1);
+        let end_epoch = Epoch::new(2);
+
+        let result = self
+            .client
+            .get_lighthouse_analysis_block_packing(start_epoch, end_epoch)
+            .await
+            .unwrap();
+
+        assert!(!result.is_empty());
+        for block in result {
+            assert!(block.slot >= start_epoch.start_slot(E::slots_per_epoch()));
+            assert!(block.slot <= end_epoch.end_slot(E::slots_per_epoch()));
+            assert!(block.max_includable_attestations <= block.available_attestations);
+        }
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_analysis_slot_delays(self) -> Self {
+        let slot = self.chain.slot().unwrap();
+        let attestation = self
+            .chain
+            .produce_unaggregated_attestation(slot, 0)
+            .unwrap();
+        let attestation_production = self
+            .chain
+            .block_times_cache
+            .read()
+            .get_attestation_production(slot)
+            .unwrap();
+        assert_eq!(
+            attestation_production.beacon_block_root,
+            attestation.data.beacon_block_root
+        );
+
+        let result = self
+            .client
+            .get_lighthouse_analysis_slot_delays(slot - 1, slot)
+            .await
+            .unwrap();
+        assert_eq!(
+            result.iter().map(|delays| delays.slot).collect::<Vec<_>>(),
+            vec![slot - 1, slot]
+        );
+
+        // The range must be ordered and within the history retained by the beacon chain.
+        assert!(self
+            .client
+            .get_lighthouse_analysis_slot_delays(slot, slot - 1)
+            .await
+            .is_err());
+        assert!(self
+            .client
+            .get_lighthouse_analysis_slot_delays(slot, slot + 64)
+            .await
+            .is_err());
+
+        self
+    }
+
     pub async fn test_post_lighthouse_database_reconstruct(self) -> Self {
         let response = self
             .client
@@ -2346,6 +3098,182 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_post_lighthouse_database_jobs(self) -> Self {
+        let job = self
+            .client
+            .post_lighthouse_database_prune_payloads()
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(job.kind, DatabaseJobKind::PrunePayloads);
+
+        let job = self.wait_for_database_job(job.id).await;
+        assert_eq!(job.status, DatabaseJobStatus::Completed);
+        // The test harness uses an in-memory database, which never has payloads to prune.
+        assert_eq!(job.items_processed, 0);
+        assert_eq!(job.items_total, Some(0));
+        assert!(job.finished_at.is_some());
+
+        let compact = self
+            .client
+            .post_lighthouse_database_compact()
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(compact.kind, DatabaseJobKind::Compact);
+        assert!(compact.id > job.id);
+        let compact = self.wait_for_database_job(compact.id).await;
+        assert_eq!(compact.status, DatabaseJobStatus::Completed);
+
+        let jobs = self
+            .client
+            .get_lighthouse_database_jobs()
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(jobs, vec![job, compact.clone()]);
+
+        assert!(self
+            .client
+            .get_lighthouse_database_job(compact.id + 1)
+            .await
+            .unwrap()
+            .is_none());
+
+        self
+    }
+
+    async fn wait_for_database_job(&self, id: u64) -> DatabaseJob {
+        loop {
+            let job = self
+                .client
+                .get_lighthouse_database_job(id)
+                .await
+                .unwrap()
+                .unwrap()
+                .data;
+            if job.status != DatabaseJobStatus::Running {
+                return job;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    pub async fn test_post_lighthouse_logging(self) -> Self {
+        let initial = self.client.get_lighthouse_logging().await.unwrap().data;
+        assert_eq!(initial.debug_level, "info");
+        assert!(initial.overrides.is_empty());
+
+        let levels = LogLevelsConfig {
+            debug_level: "warn".to_string(),
+            overrides: vec![
+                ("network".to_string(), "debug".to_string()),
+                ("beacon_chain".to_string(), "info".to_string()),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        let result = self
+            .client
+            .post_lighthouse_logging(&levels)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(result, levels);
+        assert_eq!(
+            self.client.get_lighthouse_logging().await.unwrap().data,
+            levels
+        );
+
+        // An unknown level is rejected without changing the levels.
+        let invalid = LogLevelsConfig {
+            debug_level: "loud".to_string(),
+            overrides: Default::default(),
+        };
+        assert!(self.client.post_lighthouse_logging(&invalid).await.is_err());
+        assert_eq!(
+            self.client.get_lighthouse_logging().await.unwrap().data,
+            levels
+        );
+
+        self
+    }
+
+    pub async fn test_post_lighthouse_validator_monitor(self) -> Self {
+        // A validator which hasn't been deposited yet may also be monitored.
+        let unknown_pubkey = Keypair::random().pk.compress();
+        let pubkeys = self
+            .validator_keypairs
+            .iter()
+            .take(2)
+            .map(|keypair| keypair.pk.compress())
+            .chain(std::iter::once(unknown_pubkey))
+            .collect::<Vec<_>>();
+
+        let num_monitored = self.chain.validator_monitor.read().num_validators() as u64;
+        let result = self
+            .client
+            .post_lighthouse_validator_monitor(&pubkeys)
+            .await
+            .unwrap()
+            .data;
+
+        assert!(result.newly_monitored >= 1);
+        assert_eq!(result.num_monitored, num_monitored + result.newly_monitored);
+        {
+            let validator_monitor = self.chain.validator_monitor.read();
+            for (index, pubkey) in pubkeys.iter().take(2).enumerate() {
+                assert_eq!(
+                    validator_monitor.get_monitored_validator_pubkey(index as u64),
+                    Some(*pubkey)
+                );
+            }
+        }
+
+        // Registering the same validators again has no effect.
+        let result = self
+            .client
+            .post_lighthouse_validator_monitor(&pubkeys)
+            .await
+            .unwrap()
+            .data;
+
+        assert_eq!(result.newly_monitored, 0);
+        assert_eq!(
+            result.num_monitored,
+            self.chain.validator_monitor.read().num_validators() as u64
+        );
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_validator_monitor(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_validator_monitor()
+            .await
+            .unwrap()
+            .data;
+
+        let validator_monitor = self.chain.validator_monitor.read();
+        assert_eq!(result.len(), validator_monitor.num_validators());
+        for summary in result {
+            if let Some(index) = summary.index {
+                assert_eq!(
+                    validator_monitor.get_monitored_validator_pubkey(index),
+                    Some(summary.pubkey)
+                );
+            }
+            assert!(summary
+                .epochs
+                .windows(2)
+                .all(|epochs| epochs[0].epoch < epochs[1].epoch));
+        }
+        drop(validator_monitor);
+
+        self
+    }
+
     pub async fn test_post_lighthouse_liveness(self) -> Self {
         let epoch = self.chain.epoch().unwrap();
         let head_state = self.chain.head_beacon_state().unwrap();
@@ -2530,10 +3458,10 @@ impl ApiTester {
             epoch: self.next_block.slot().epoch(E::slots_per_epoch()),
         });
 
-        self.client
-            .post_beacon_blocks(&self.reorg_block)
-            .await
-            .unwrap();
+        // The reorg block is a repeat proposal, which fails gossip verification on the HTTP API,
+        // so import it directly.
+        self.chain.process_block(self.reorg_block.clone()).unwrap();
+        self.chain.fork_choice().unwrap();
 
         let reorg_event = poll_events(
             &mut chain_reorg_event_future,
@@ -2656,6 +3584,16 @@ async fn get_events_altair() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_light_client_bootstrap() {
+    let mut spec = E::default_spec();
+    spec.altair_fork_epoch = Some(Epoch::new(0));
+    ApiTester::new_from_spec(spec)
+        .await
+        .test_get_light_client_bootstrap()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_events_from_genesis() {
     ApiTester::new_from_genesis()
@@ -2674,16 +3612,26 @@ async fn beacon_get() {
         .await
         .test_beacon_states_fork()
         .await
+        .test_get_expected_withdrawals_pre_capella()
+        .await
+        .test_get_expected_withdrawals_proposal_slot_too_far()
+        .await
         .test_beacon_states_finality_checkpoints()
         .await
         .test_beacon_states_validators()
         .await
+        .test_beacon_states_validators_pagination()
+        .await
         .test_beacon_states_validator_balances()
         .await
         .test_beacon_states_committees()
         .await
         .test_beacon_states_validator_id()
         .await
+        .test_get_deposit_snapshot()
+        .await
+        .test_execution_optimistic_flags()
+        .await
         .test_beacon_headers_all_slots()
         .await
         .test_beacon_headers_all_parents()
@@ -2719,6 +3667,56 @@ async fn post_beacon_blocks_invalid() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn post_beacon_blocks_invalid_gossip() {
+    ApiTester::new()
+        .await
+        .test_post_beacon_blocks_invalid_gossip()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn post_beacon_blocks_valid_consensus() {
+    ApiTester::new()
+        .await
+        .test_post_beacon_blocks_valid_with_validation(BroadcastValidation::Consensus)
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn post_beacon_blocks_valid_consensus_and_equivocation() {
+    ApiTester::new()
+        .await
+        .test_post_beacon_blocks_valid_with_validation(
+            BroadcastValidation::ConsensusAndEquivocation,
+        )
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn post_beacon_blocks_equivocation() {
+    ApiTester::new()
+        .await
+        .test_post_beacon_blocks_equivocation()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn post_beacon_blocks_invalid_consensus() {
+    ApiTester::new()
+        .await
+        .test_post_beacon_blocks_invalid_with_validation(BroadcastValidation::Consensus)
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn post_beacon_blinded_blocks_valid() {
+    ApiTester::new()
+        .await
+        .test_post_beacon_blinded_blocks_valid()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn beacon_pools_post_attestations_valid() {
     ApiTester::new()
@@ -2973,14 +3971,45 @@ async fn get_validator_beacon_committee_subscriptions() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_lighthouse_op_pool_attestations_with_skip_slots() {
+    ApiTester::new()
+        .await
+        .skip_slots(E::slots_per_epoch() * 2)
+        .test_get_lighthouse_op_pool_attestations_head_too_far_behind()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn lighthouse_endpoints() {
     ApiTester::new()
         .await
         .test_get_lighthouse_health()
         .await
+        .test_get_lighthouse_system()
+        .await
+        .test_get_lighthouse_attestation_pools()
+        .await
+        .test_post_lighthouse_enr()
+        .await
         .test_get_lighthouse_syncing()
         .await
+        .test_get_lighthouse_sync_lookups()
+        .await
+        .test_get_lighthouse_beacon_processor_queues()
+        .await
+        .test_get_lighthouse_ready()
+        .await
+        .test_get_lighthouse_peers_verbose()
+        .await
+        .test_get_lighthouse_gossipsub_scores()
+        .await
+        .test_get_lighthouse_network_port_mappings()
+        .await
+        .test_get_lighthouse_network_clock_skew()
+        .await
+        .test_get_lighthouse_peers_diversity()
+        .await
         .test_get_lighthouse_proto_array()
         .await
         .test_get_lighthouse_validator_inclusion()
@@ -2995,12 +4024,28 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_beacon_states_ssz()
         .await
+        .test_get_lighthouse_beacon_block_roots()
+        .await
+        .test_get_lighthouse_beacon_proofs()
+        .await
         .test_get_lighthouse_staking()
         .await
         .test_get_lighthouse_database_info()
         .await
+        .test_get_lighthouse_analysis_block_packing()
+        .await
+        .test_get_lighthouse_analysis_slot_delays()
+        .await
         .test_post_lighthouse_database_reconstruct()
         .await
+        .test_post_lighthouse_database_jobs()
+        .await
         .test_post_lighthouse_liveness()
+        .await
+        .test_post_lighthouse_validator_monitor()
+        .await
+        .test_get_lighthouse_validator_monitor()
+        .await
+        .test_post_lighthouse_logging()
         .await;
 }
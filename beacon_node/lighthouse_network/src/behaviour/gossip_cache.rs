@@ -34,6 +34,8 @@ pub struct GossipCache {
     signed_contribution_and_proof: Option<Duration>,
     /// Timeout for sync commitee messages.
     sync_committee_message: Option<Duration>,
+    /// Timeout for BLS to execution changes.
+    bls_to_execution_change: Option<Duration>,
 }
 
 #[derive(Default)]
@@ -55,6 +57,8 @@ pub struct GossipCacheBuilder {
     signed_contribution_and_proof: Option<Duration>,
     /// Timeout for sync commitee messages.
     sync_committee_message: Option<Duration>,
+    /// Timeout for BLS to execution changes.
+    bls_to_execution_change: Option<Duration>,
 }
 
 #[allow(dead_code)]
@@ -113,6 +117,12 @@ impl GossipCacheBuilder {
         self
     }
 
+    /// Timeout for BLS to execution changes.
+    pub fn bls_to_execution_change_timeout(mut self, timeout: Duration) -> Self {
+        self.bls_to_execution_change = Some(timeout);
+        self
+    }
+
     pub fn build(self) -> GossipCache {
         let GossipCacheBuilder {
             default_timeout,
@@ -124,6 +134,7 @@ impl GossipCacheBuilder {
             attester_slashing,
             signed_contribution_and_proof,
             sync_committee_message,
+            bls_to_execution_change,
         } = self;
         GossipCache {
             expirations: DelayQueue::default(),
@@ -136,6 +147,7 @@ impl GossipCacheBuilder {
             attester_slashing: attester_slashing.or(default_timeout),
             signed_contribution_and_proof: signed_contribution_and_proof.or(default_timeout),
             sync_committee_message: sync_committee_message.or(default_timeout),
+            bls_to_execution_change: bls_to_execution_change.or(default_timeout),
         }
     }
 }
@@ -158,6 +170,9 @@ impl GossipCache {
             GossipKind::AttesterSlashing => self.attester_slashing,
             GossipKind::SignedContributionAndProof => self.signed_contribution_and_proof,
             GossipKind::SyncCommitteeMessage(_) => self.sync_committee_message,
+            GossipKind::BlsToExecutionChange => self.bls_to_execution_change,
+            // Light client updates are superseded every slot, so they aren't worth caching.
+            GossipKind::LightClientFinalityUpdate | GossipKind::LightClientOptimisticUpdate => None,
         };
         let expire_timeout = match expire_timeout {
             Some(expire_timeout) => expire_timeout,
@@ -1,11 +1,15 @@
+use crate::behaviour::topic_score_tracker::TopicScoreComponents;
 use crate::types::{GossipEncoding, GossipKind, GossipTopic};
 use crate::{error, TopicHash};
 use libp2p::gossipsub::{
     GossipsubConfig, IdentTopic as Topic, PeerScoreParams, PeerScoreThresholds, TopicScoreParams,
 };
+use serde_derive::{Deserialize, Serialize};
 use std::cmp::max;
 use std::collections::HashMap;
+use std::fs::File;
 use std::marker::PhantomData;
+use std::path::Path;
 use std::time::Duration;
 use types::{ChainSpec, EnrForkId, EthSpec, Slot, SubnetId};
 
@@ -23,14 +27,228 @@ const MESH_MESSAGE_DELIVERIES_WINDOW: u64 = 2;
 // Const as this is used in the peer manager to prevent gossip from disconnecting peers.
 pub const GREYLIST_THRESHOLD: f64 = -16000.0;
 
-/// Builds the peer score thresholds.
-pub fn lighthouse_gossip_thresholds() -> PeerScoreThresholds {
-    PeerScoreThresholds {
+/// Builds the peer score thresholds, applying any `overrides`.
+pub fn lighthouse_gossip_thresholds(overrides: &GossipsubScoringOverrides) -> PeerScoreThresholds {
+    let mut thresholds = PeerScoreThresholds {
         gossip_threshold: -4000.0,
         publish_threshold: -8000.0,
         graylist_threshold: GREYLIST_THRESHOLD,
         accept_px_threshold: 100.0,
         opportunistic_graft_threshold: 5.0,
+    };
+    overrides.thresholds.apply(&mut thresholds);
+    thresholds
+}
+
+/// The gossipsub topics which are scored. All attestation subnets share the same parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoredTopic {
+    BeaconBlock,
+    BeaconAggregateAndProof,
+    BeaconAttestation,
+    VoluntaryExit,
+    ProposerSlashing,
+    AttesterSlashing,
+}
+
+impl ScoredTopic {
+    /// Returns `None` for topics which don't have scoring parameters (i.e. sync committee, light
+    /// client and BLS to execution change topics).
+    pub fn from_kind(kind: &GossipKind) -> Option<Self> {
+        match kind {
+            GossipKind::BeaconBlock => Some(ScoredTopic::BeaconBlock),
+            GossipKind::BeaconAggregateAndProof => Some(ScoredTopic::BeaconAggregateAndProof),
+            GossipKind::Attestation(_) => Some(ScoredTopic::BeaconAttestation),
+            GossipKind::VoluntaryExit => Some(ScoredTopic::VoluntaryExit),
+            GossipKind::ProposerSlashing => Some(ScoredTopic::ProposerSlashing),
+            GossipKind::AttesterSlashing => Some(ScoredTopic::AttesterSlashing),
+            GossipKind::SignedContributionAndProof
+            | GossipKind::SyncCommitteeMessage(_)
+            | GossipKind::LightClientFinalityUpdate
+            | GossipKind::LightClientOptimisticUpdate
+            | GossipKind::BlsToExecutionChange => None,
+        }
+    }
+}
+
+/// Overrides for the gossipsub scoring parameters, as loaded from `--gossipsub-scoring-file`.
+///
+/// Any parameter which isn't set keeps the value Lighthouse derives from the chain spec. This is
+/// also the format in which the parameters in effect are reported by the HTTP API, with every
+/// parameter set.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GossipsubScoringOverrides {
+    /// The proportion of its initial value below which a counter decays to zero. Changing this
+    /// rescales all of the derived decay parameters.
+    pub decay_to_zero: Option<f64>,
+    pub topic_score_cap: Option<f64>,
+    pub app_specific_weight: Option<f64>,
+    pub ip_colocation_factor_weight: Option<f64>,
+    pub ip_colocation_factor_threshold: Option<f64>,
+    pub behaviour_penalty_weight: Option<f64>,
+    pub behaviour_penalty_threshold: Option<f64>,
+    pub behaviour_penalty_decay: Option<f64>,
+    pub thresholds: ThresholdOverrides,
+    pub topics: HashMap<ScoredTopic, TopicScoreOverrides>,
+}
+
+/// Overrides for the peer score thresholds.
+///
+/// The graylist threshold can't be overridden, as the peer manager relies on it to stop gossipsub
+/// scores from disconnecting peers on their own.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThresholdOverrides {
+    pub gossip_threshold: Option<f64>,
+    pub publish_threshold: Option<f64>,
+    pub accept_px_threshold: Option<f64>,
+    pub opportunistic_graft_threshold: Option<f64>,
+}
+
+/// Overrides for the scoring parameters of a single topic.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TopicScoreOverrides {
+    pub topic_weight: Option<f64>,
+    pub time_in_mesh_weight: Option<f64>,
+    pub time_in_mesh_cap: Option<f64>,
+    pub first_message_deliveries_weight: Option<f64>,
+    pub first_message_deliveries_decay: Option<f64>,
+    pub first_message_deliveries_cap: Option<f64>,
+    pub mesh_message_deliveries_weight: Option<f64>,
+    pub mesh_message_deliveries_decay: Option<f64>,
+    pub mesh_message_deliveries_cap: Option<f64>,
+    pub mesh_message_deliveries_threshold: Option<f64>,
+    pub mesh_failure_penalty_weight: Option<f64>,
+    pub mesh_failure_penalty_decay: Option<f64>,
+    pub invalid_message_deliveries_weight: Option<f64>,
+    pub invalid_message_deliveries_decay: Option<f64>,
+}
+
+/// Overwrite each field of `$target` with the corresponding field of `$overrides`, if it is set.
+macro_rules! apply_overrides {
+    ($overrides: expr, $target: expr, $($field: ident),*) => {
+        $(
+            if let Some(value) = $overrides.$field {
+                $target.$field = value;
+            }
+        )*
+    };
+}
+
+impl GossipsubScoringOverrides {
+    /// Load the overrides from a YAML (or JSON) file.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let file =
+            File::open(path).map_err(|e| format!("Unable to open {}: {:?}", path.display(), e))?;
+        serde_yaml::from_reader(file)
+            .map_err(|e| format!("Unable to parse {}: {:?}", path.display(), e))
+    }
+
+    /// Describe the given parameters, setting every override.
+    pub fn from_params(params: &PeerScoreParams, thresholds: &PeerScoreThresholds) -> Self {
+        Self {
+            decay_to_zero: Some(params.decay_to_zero),
+            topic_score_cap: Some(params.topic_score_cap),
+            app_specific_weight: Some(params.app_specific_weight),
+            ip_colocation_factor_weight: Some(params.ip_colocation_factor_weight),
+            ip_colocation_factor_threshold: Some(params.ip_colocation_factor_threshold),
+            behaviour_penalty_weight: Some(params.behaviour_penalty_weight),
+            behaviour_penalty_threshold: Some(params.behaviour_penalty_threshold),
+            behaviour_penalty_decay: Some(params.behaviour_penalty_decay),
+            thresholds: ThresholdOverrides {
+                gossip_threshold: Some(thresholds.gossip_threshold),
+                publish_threshold: Some(thresholds.publish_threshold),
+                accept_px_threshold: Some(thresholds.accept_px_threshold),
+                opportunistic_graft_threshold: Some(thresholds.opportunistic_graft_threshold),
+            },
+            topics: params
+                .topics
+                .iter()
+                .filter_map(|(topic_hash, params)| {
+                    let topic = GossipTopic::decode(topic_hash.as_str()).ok()?;
+                    let scored_topic = ScoredTopic::from_kind(topic.kind())?;
+                    Some((scored_topic, TopicScoreOverrides::from_params(params)))
+                })
+                .collect(),
+        }
+    }
+
+    fn apply(&self, params: &mut PeerScoreParams) {
+        apply_overrides!(
+            self,
+            params,
+            topic_score_cap,
+            app_specific_weight,
+            ip_colocation_factor_weight,
+            ip_colocation_factor_threshold,
+            behaviour_penalty_weight,
+            behaviour_penalty_threshold,
+            behaviour_penalty_decay
+        );
+    }
+
+    fn apply_to_topic(&self, topic: ScoredTopic, params: &mut TopicScoreParams) {
+        if let Some(overrides) = self.topics.get(&topic) {
+            overrides.apply(params);
+        }
+    }
+}
+
+impl ThresholdOverrides {
+    fn apply(&self, thresholds: &mut PeerScoreThresholds) {
+        apply_overrides!(
+            self,
+            thresholds,
+            gossip_threshold,
+            publish_threshold,
+            accept_px_threshold,
+            opportunistic_graft_threshold
+        );
+    }
+}
+
+impl TopicScoreOverrides {
+    fn from_params(params: &TopicScoreParams) -> Self {
+        Self {
+            topic_weight: Some(params.topic_weight),
+            time_in_mesh_weight: Some(params.time_in_mesh_weight),
+            time_in_mesh_cap: Some(params.time_in_mesh_cap),
+            first_message_deliveries_weight: Some(params.first_message_deliveries_weight),
+            first_message_deliveries_decay: Some(params.first_message_deliveries_decay),
+            first_message_deliveries_cap: Some(params.first_message_deliveries_cap),
+            mesh_message_deliveries_weight: Some(params.mesh_message_deliveries_weight),
+            mesh_message_deliveries_decay: Some(params.mesh_message_deliveries_decay),
+            mesh_message_deliveries_cap: Some(params.mesh_message_deliveries_cap),
+            mesh_message_deliveries_threshold: Some(params.mesh_message_deliveries_threshold),
+            mesh_failure_penalty_weight: Some(params.mesh_failure_penalty_weight),
+            mesh_failure_penalty_decay: Some(params.mesh_failure_penalty_decay),
+            invalid_message_deliveries_weight: Some(params.invalid_message_deliveries_weight),
+            invalid_message_deliveries_decay: Some(params.invalid_message_deliveries_decay),
+        }
+    }
+
+    fn apply(&self, params: &mut TopicScoreParams) {
+        apply_overrides!(
+            self,
+            params,
+            topic_weight,
+            time_in_mesh_weight,
+            time_in_mesh_cap,
+            first_message_deliveries_weight,
+            first_message_deliveries_decay,
+            first_message_deliveries_cap,
+            mesh_message_deliveries_weight,
+            mesh_message_deliveries_decay,
+            mesh_message_deliveries_cap,
+            mesh_message_deliveries_threshold,
+            mesh_failure_penalty_weight,
+            mesh_failure_penalty_decay,
+            invalid_message_deliveries_weight,
+            invalid_message_deliveries_decay
+        );
     }
 }
 
@@ -49,11 +267,16 @@ pub struct PeerScoreSettings<TSpec: EthSpec> {
     target_committee_size: usize,
     target_aggregators_per_committee: usize,
     attestation_subnet_count: u64,
+    overrides: GossipsubScoringOverrides,
     phantom: PhantomData<TSpec>,
 }
 
 impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
-    pub fn new(chain_spec: &ChainSpec, gs_config: &GossipsubConfig) -> PeerScoreSettings<TSpec> {
+    pub fn new(
+        chain_spec: &ChainSpec,
+        gs_config: &GossipsubConfig,
+        overrides: GossipsubScoringOverrides,
+    ) -> PeerScoreSettings<TSpec> {
         let slot = Duration::from_secs(chain_spec.seconds_per_slot);
         let beacon_attestation_subnet_weight = 1.0 / chain_spec.attestation_subnet_count as f64;
         let max_positive_score = (MAX_IN_MESH_SCORE + MAX_FIRST_MESSAGE_DELIVERIES_SCORE)
@@ -70,12 +293,13 @@ impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
             beacon_attestation_subnet_weight,
             max_positive_score,
             decay_interval: max(Duration::from_secs(1), slot),
-            decay_to_zero: 0.01,
+            decay_to_zero: overrides.decay_to_zero.unwrap_or(0.01),
             mesh_n: gs_config.mesh_n(),
             max_committees_per_slot: chain_spec.max_committees_per_slot,
             target_committee_size: chain_spec.target_committee_size,
             target_aggregators_per_committee: chain_spec.target_aggregators_per_committee as usize,
             attestation_subnet_count: chain_spec.attestation_subnet_count,
+            overrides,
             phantom: PhantomData,
         }
     }
@@ -106,6 +330,7 @@ impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
 
         params.topic_score_cap = self.max_positive_score * 0.5;
         params.ip_colocation_factor_weight = -params.topic_score_cap;
+        self.overrides.apply(&mut params);
 
         params.topics = HashMap::new();
 
@@ -118,8 +343,8 @@ impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
         //first all fixed topics
         params.topics.insert(
             get_hash(GossipKind::VoluntaryExit),
-            Self::get_topic_params(
-                self,
+            self.get_topic_params(
+                ScoredTopic::VoluntaryExit,
                 VOLUNTARY_EXIT_WEIGHT,
                 4.0 / TSpec::slots_per_epoch() as f64,
                 self.epoch * 100,
@@ -128,8 +353,8 @@ impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
         );
         params.topics.insert(
             get_hash(GossipKind::AttesterSlashing),
-            Self::get_topic_params(
-                self,
+            self.get_topic_params(
+                ScoredTopic::AttesterSlashing,
                 ATTESTER_SLASHING_WEIGHT,
                 1.0 / 5.0 / TSpec::slots_per_epoch() as f64,
                 self.epoch * 100,
@@ -138,8 +363,8 @@ impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
         );
         params.topics.insert(
             get_hash(GossipKind::ProposerSlashing),
-            Self::get_topic_params(
-                self,
+            self.get_topic_params(
+                ScoredTopic::ProposerSlashing,
                 PROPOSER_SLASHING_WEIGHT,
                 1.0 / 5.0 / TSpec::slots_per_epoch() as f64,
                 self.epoch * 100,
@@ -180,23 +405,23 @@ impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
         let multiple_bursts_per_subnet_per_epoch = committees_per_slot as u64
             >= 2 * self.attestation_subnet_count / TSpec::slots_per_epoch();
 
-        let beacon_block_params = Self::get_topic_params(
-            self,
+        let beacon_block_params = self.get_topic_params(
+            ScoredTopic::BeaconBlock,
             BEACON_BLOCK_WEIGHT,
             1.0,
             self.epoch * 20,
             Some((TSpec::slots_per_epoch() * 5, 3.0, self.epoch, current_slot)),
         );
 
-        let beacon_aggregate_proof_params = Self::get_topic_params(
-            self,
+        let beacon_aggregate_proof_params = self.get_topic_params(
+            ScoredTopic::BeaconAggregateAndProof,
             BEACON_AGGREGATE_PROOF_WEIGHT,
             aggregators_per_slot,
             self.epoch,
             Some((TSpec::slots_per_epoch() * 2, 4.0, self.epoch, current_slot)),
         );
-        let beacon_attestation_subnet_params = Self::get_topic_params(
-            self,
+        let beacon_attestation_subnet_params = self.get_topic_params(
+            ScoredTopic::BeaconAttestation,
             self.beacon_attestation_subnet_weight,
             active_validators as f64
                 / self.attestation_subnet_count as f64
@@ -293,6 +518,7 @@ impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
 
     fn get_topic_params(
         &self,
+        topic: ScoredTopic,
         topic_weight: f64,
         expected_message_rate: f64,
         first_message_decay_time: Duration,
@@ -354,6 +580,101 @@ impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
             -self.max_positive_score / t_params.topic_weight;
         t_params.invalid_message_deliveries_decay = self.score_parameter_decay(self.epoch * 50);
 
+        self.overrides.apply_to_topic(topic, &mut t_params);
         t_params
     }
 }
+
+/// A snapshot of the gossipsub scores of connected peers, as reported by the HTTP API.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GossipsubScores {
+    /// The scoring parameters in effect, in the format of `--gossipsub-scoring-file`.
+    pub params: GossipsubScoringOverrides,
+    pub peers: Vec<PeerGossipsubScore>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PeerGossipsubScore {
+    pub peer_id: String,
+    /// The peer's total gossipsub score, before it is weighted into the Lighthouse peer score.
+    pub score: f64,
+    /// The topics the peer is subscribed to.
+    pub topics: Vec<PeerTopicScore>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PeerTopicScore {
+    pub topic: String,
+    /// The parameters which score this topic, or `None` if it isn't scored.
+    pub scored_topic: Option<ScoredTopic>,
+    /// Whether the peer is in our mesh for this topic, and so accruing the mesh components of its
+    /// score.
+    pub in_mesh: bool,
+    /// The components of the peer's score for this topic, or `None` if it isn't scored.
+    pub components: Option<TopicScoreComponents>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::MainnetEthSpec;
+
+    const OVERRIDES: &str = "
+topic_score_cap: 100.0
+thresholds:
+  gossip_threshold: -100.0
+topics:
+  beacon_block:
+    topic_weight: 2.0
+  beacon_attestation:
+    mesh_message_deliveries_weight: 0.0
+";
+
+    #[test]
+    fn overrides_are_applied() {
+        let overrides: GossipsubScoringOverrides = serde_yaml::from_str(OVERRIDES).unwrap();
+        let spec = ChainSpec::mainnet();
+        let settings = PeerScoreSettings::<MainnetEthSpec>::new(
+            &spec,
+            &GossipsubConfig::default(),
+            overrides.clone(),
+        );
+        let thresholds = lighthouse_gossip_thresholds(&overrides);
+        let params = settings
+            .get_peer_score_params(
+                100_000,
+                &thresholds,
+                &EnrForkId::default(),
+                Slot::new(1_000),
+            )
+            .unwrap();
+
+        assert_eq!(thresholds.gossip_threshold, -100.0);
+        assert_eq!(thresholds.graylist_threshold, GREYLIST_THRESHOLD);
+        assert_eq!(params.topic_score_cap, 100.0);
+
+        let effective = GossipsubScoringOverrides::from_params(&params, &thresholds);
+        let topic = |topic: ScoredTopic| &effective.topics[&topic];
+        assert_eq!(topic(ScoredTopic::BeaconBlock).topic_weight, Some(2.0));
+        assert_eq!(
+            topic(ScoredTopic::BeaconAggregateAndProof).topic_weight,
+            Some(BEACON_AGGREGATE_PROOF_WEIGHT)
+        );
+        assert_eq!(
+            topic(ScoredTopic::BeaconAttestation).mesh_message_deliveries_weight,
+            Some(0.0)
+        );
+        assert_eq!(effective.topics.len(), 6);
+    }
+
+    #[test]
+    fn unknown_overrides_are_rejected() {
+        assert!(
+            serde_yaml::from_str::<GossipsubScoringOverrides>("topic_score_caps: 1.0").is_err()
+        );
+        assert!(serde_yaml::from_str::<GossipsubScoringOverrides>(
+            "topics:\n  sync_committee:\n    topic_weight: 1.0"
+        )
+        .is_err());
+    }
+}
@@ -1,19 +1,21 @@
 use crate::behaviour::gossipsub_scoring_parameters::{
-    lighthouse_gossip_thresholds, PeerScoreSettings,
+    lighthouse_gossip_thresholds, GossipsubScores, GossipsubScoringOverrides, PeerGossipsubScore,
+    PeerScoreSettings, PeerTopicScore, ScoredTopic,
 };
+use crate::behaviour::topic_score_tracker::TopicScoreTracker;
 use crate::config::gossipsub_config;
 use crate::discovery::{
     subnet_predicate, Discovery, DiscoveryEvent, FIND_NODE_QUERY_CLOSEST_PEERS,
 };
 use crate::peer_manager::{
-    config::Config as PeerManagerCfg, peerdb::score::PeerAction, peerdb::score::ReportSource,
-    ConnectionDirection, PeerManager, PeerManagerEvent,
+    config::Config as PeerManagerCfg, peerdb::persisted::PersistedPeers, peerdb::score::PeerAction,
+    peerdb::score::ReportSource, ConnectionDirection, PeerManager, PeerManagerEvent,
 };
 use crate::rpc::*;
 use crate::service::{Context as ServiceContext, METADATA_FILENAME};
 use crate::types::{
-    subnet_from_topic_hash, GossipEncoding, GossipKind, GossipTopic, SnappyTransform, Subnet,
-    SubnetDiscovery,
+    core_topics_to_subscribe, subnet_from_topic_hash, GossipEncoding, GossipKind, GossipTopic,
+    SnappyTransform, Subnet, SubnetDiscovery,
 };
 use crate::Eth2Enr;
 use crate::{error, metrics, Enr, NetworkGlobals, PubsubMessage, TopicHash};
@@ -27,7 +29,7 @@ use libp2p::{
         metrics::Config as GossipsubMetricsConfig,
         subscription_filter::{MaxCountSubscriptionFilter, WhitelistSubscriptionFilter},
         Gossipsub as BaseGossipsub, GossipsubEvent, IdentTopic as Topic, MessageAcceptance,
-        MessageAuthenticity, MessageId,
+        MessageAuthenticity, MessageId, PeerScoreParams, PeerScoreThresholds,
     },
     identify::{Identify, IdentifyConfig, IdentifyEvent},
     swarm::{
@@ -39,7 +41,7 @@ use libp2p::{
 };
 use slog::{crit, debug, o, trace, warn};
 use ssz::Encode;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -48,16 +50,18 @@ use std::{
     marker::PhantomData,
     sync::Arc,
     task::{Context, Poll},
+    time::Instant,
 };
 use types::{
-    consts::altair::SYNC_COMMITTEE_SUBNET_COUNT, EnrForkId, EthSpec, ForkContext,
-    SignedBeaconBlock, Slot, SubnetId, SyncSubnetId,
+    consts::altair::SYNC_COMMITTEE_SUBNET_COUNT, BlobSidecar, EnrForkId, EthSpec, ForkContext,
+    ForkName, SignedBeaconBlock, Slot, SubnetId, SyncSubnetId,
 };
 
 use self::gossip_cache::GossipCache;
 
 mod gossip_cache;
 pub mod gossipsub_scoring_parameters;
+pub mod topic_score_tracker;
 
 /// The number of peers we target per subnet for discovery queries.
 pub const TARGET_SUBNET_PEERS: usize = 6;
@@ -188,6 +192,14 @@ pub struct Behaviour<AppReqId: ReqId, TSpec: EthSpec> {
     /// Gossipsub score parameters.
     #[behaviour(ignore)]
     score_settings: PeerScoreSettings<TSpec>,
+    /// The gossipsub score parameters currently in effect.
+    #[behaviour(ignore)]
+    score_params: PeerScoreParams,
+    #[behaviour(ignore)]
+    score_thresholds: PeerScoreThresholds,
+    /// Mirrors the per-topic components of the gossipsub score of each peer.
+    #[behaviour(ignore)]
+    topic_score_tracker: TopicScoreTracker,
     /// The interval for updating gossipsub scores
     #[behaviour(ignore)]
     update_gossipsub_scores: tokio::time::Interval,
@@ -268,9 +280,14 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Behaviour<AppReqId, TSpec> {
         let active_validators = TSpec::minimum_validator_count();
         let current_slot = Slot::new(0);
 
-        let thresholds = lighthouse_gossip_thresholds();
+        let scoring_overrides = config
+            .gossipsub_scoring_overrides
+            .clone()
+            .unwrap_or_default();
+        let thresholds = lighthouse_gossip_thresholds(&scoring_overrides);
 
-        let score_settings = PeerScoreSettings::new(ctx.chain_spec, &config.gs_config);
+        let score_settings =
+            PeerScoreSettings::new(ctx.chain_spec, &config.gs_config, scoring_overrides);
 
         // Prepare scoring parameters
         let params = score_settings.get_peer_score_params(
@@ -286,13 +303,30 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Behaviour<AppReqId, TSpec> {
         let update_gossipsub_scores = tokio::time::interval(params.decay_interval);
 
         gossipsub
-            .with_peer_score(params, thresholds)
-            .expect("Valid score params and thresholds");
+            .with_peer_score(params.clone(), thresholds.clone())
+            .map_err(|e| format!("Invalid gossipsub score parameters: {}", e))?;
+
+        // Trusted peers are explicit gossipsub peers: we always exchange messages with them,
+        // regardless of their gossipsub score or our mesh.
+        for peer_id in &config.trusted_peers {
+            gossipsub.add_explicit_peer(&PeerId::from(peer_id.clone()));
+        }
 
         let peer_manager_cfg = PeerManagerCfg {
             discovery_enabled: !config.disable_discovery,
             metrics_enabled: config.metrics_enabled,
             target_peer_count: config.target_peers,
+            trusted_peer_addresses: config.trusted_peer_addresses.clone(),
+            peer_allowlist: config.peer_allowlist.as_ref().map(|peers| {
+                peers
+                    .iter()
+                    .map(|peer_id| PeerId::from(peer_id.clone()))
+                    .collect()
+            }),
+            max_client_share: config.peer_diversity_max_client_share,
+            max_asn_share: config.peer_diversity_max_asn_share,
+            asn_database: config.asn_database.clone(),
+            outbound_only: config.disable_inbound,
             ..Default::default()
         };
 
@@ -307,6 +341,7 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Behaviour<AppReqId, TSpec> {
             .voluntary_exit_timeout(half_epoch * 2)
             .proposer_slashing_timeout(half_epoch * 2)
             .attester_slashing_timeout(half_epoch * 2)
+            .bls_to_execution_change_timeout(half_epoch * 2)
             // .signed_contribution_and_proof_timeout(timeout) // Do not retry
             // .sync_committee_message_timeout(timeout) // Do not retry
             .build();
@@ -327,6 +362,9 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Behaviour<AppReqId, TSpec> {
             network_dir: config.network_dir.clone(),
             log: behaviour_log,
             score_settings,
+            score_params: params,
+            score_thresholds: thresholds,
+            topic_score_tracker: TopicScoreTracker::new(),
             fork_context: ctx.fork_context,
             gossip_cache,
             update_gossipsub_scores,
@@ -380,13 +418,19 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Behaviour<AppReqId, TSpec> {
         self.unsubscribe(gossip_topic)
     }
 
-    /// Subscribe to all currently subscribed topics with the new fork digest.
-    pub fn subscribe_new_fork_topics(&mut self, new_fork_digest: [u8; 4]) {
+    /// Subscribe to all currently subscribed topics with the new fork digest, along with the core
+    /// topics introduced by `new_fork`.
+    pub fn subscribe_new_fork_topics(&mut self, new_fork: ForkName, new_fork_digest: [u8; 4]) {
         let subscriptions = self.network_globals.gossipsub_subscriptions.read().clone();
         for mut topic in subscriptions.into_iter() {
             topic.fork_digest = new_fork_digest;
             self.subscribe(topic);
         }
+
+        for kind in core_topics_to_subscribe(new_fork) {
+            let topic = GossipTopic::new(kind, GossipEncoding::default(), new_fork_digest);
+            self.subscribe(topic);
+        }
     }
 
     /// Unsubscribe from all topics that doesn't have the given fork_digest
@@ -454,6 +498,7 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Behaviour<AppReqId, TSpec> {
         for message in messages {
             for topic in message.topics(GossipEncoding::default(), self.enr_fork_id.fork_digest) {
                 let message_data = message.encode(GossipEncoding::default());
+                self.check_publish_mesh(&topic);
                 if let Err(e) = self
                     .gossipsub
                     .publish(topic.clone().into(), message_data.clone())
@@ -470,6 +515,14 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Behaviour<AppReqId, TSpec> {
                                 v.inc()
                             };
                         }
+                        GossipKind::SyncCommitteeMessage(subnet_id) => {
+                            if let Some(v) = metrics::get_int_gauge(
+                                &metrics::FAILED_SYNC_COMMITTEE_PUBLISHES_PER_SUBNET,
+                                &[subnet_id.as_ref()],
+                            ) {
+                                v.inc()
+                            };
+                        }
                         kind => {
                             if let Some(v) = metrics::get_int_gauge(
                                 &metrics::FAILED_PUBLISHES_PER_MAIN_TOPIC,
@@ -488,6 +541,39 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Behaviour<AppReqId, TSpec> {
         }
     }
 
+    /// Informs the RPC of the `BlocksByRange` limit negotiated with a peer, when it connects or
+    /// updates its ENR.
+    fn set_max_request_blocks(&mut self, peer_id: PeerId) {
+        let max_request_blocks = self.network_globals.max_request_blocks(&peer_id);
+        self.eth2_rpc
+            .set_max_request_blocks(peer_id, max_request_blocks);
+    }
+
+    /// Warns if a message is about to be published to a subscribed topic without any mesh peers.
+    /// Such a message only reaches the peers we flood publish to, if there are any, so a validator
+    /// publishing to it may miss rewards.
+    ///
+    /// Messages published to topics we aren't subscribed to are sent to fanout peers, so they are
+    /// not checked.
+    fn check_publish_mesh(&self, topic: &GossipTopic) {
+        let topic_hash = Topic::from(topic.clone()).hash();
+        if self.gossipsub.topics().any(|hash| *hash == topic_hash)
+            && self.gossipsub.mesh_peers(&topic_hash).next().is_none()
+        {
+            let topic_kind: &str = topic.kind().as_ref();
+            metrics::inc_counter_vec(
+                &metrics::GOSSIP_EMPTY_MESH_PUBLISH_PER_TOPIC_KIND,
+                &[topic_kind],
+            );
+            warn!(
+                self.log,
+                "Publishing to a gossip topic with no mesh peers";
+                "topic" => %topic_hash,
+                "kind" => topic_kind,
+            );
+        }
+    }
+
     /// Informs the gossipsub about the result of a message validation.
     /// If the message is valid it will get propagated by gossipsub.
     pub fn report_message_validation_result(
@@ -515,6 +601,13 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Behaviour<AppReqId, TSpec> {
             }
         }
 
+        self.topic_score_tracker.message_validated(
+            *propagation_source,
+            &message_id,
+            &validation_result,
+            &self.score_params,
+        );
+
         if let Err(e) = self.gossipsub.report_message_validation_result(
             &message_id,
             propagation_source,
@@ -548,24 +641,93 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Behaviour<AppReqId, TSpec> {
             "beacon_attestation_subnet_params" => ?beacon_attestation_subnet_params,
         );
 
-        self.gossipsub
-            .set_topic_params(get_topic(GossipKind::BeaconBlock), beacon_block_params)?;
-
-        self.gossipsub.set_topic_params(
-            get_topic(GossipKind::BeaconAggregateAndProof),
-            beacon_aggregate_proof_params,
-        )?;
-
+        let mut topic_params = vec![
+            (get_topic(GossipKind::BeaconBlock), beacon_block_params),
+            (
+                get_topic(GossipKind::BeaconAggregateAndProof),
+                beacon_aggregate_proof_params,
+            ),
+        ];
         for i in 0..self.score_settings.attestation_subnet_count() {
-            self.gossipsub.set_topic_params(
+            topic_params.push((
                 get_topic(GossipKind::Attestation(SubnetId::new(i))),
                 beacon_attestation_subnet_params.clone(),
-            )?;
+            ));
+        }
+
+        for (topic, params) in topic_params {
+            self.score_params
+                .topics
+                .insert(topic.hash(), params.clone());
+            self.gossipsub.set_topic_params(topic, params)?;
         }
 
         Ok(())
     }
 
+    /// Publishes the gossipsub scores of all connected peers to the network globals.
+    fn update_gossipsub_scores_snapshot(&mut self) {
+        let now = Instant::now();
+        let mesh_peers = self
+            .gossipsub
+            .topics()
+            .map(|topic_hash| {
+                let peers = self
+                    .gossipsub
+                    .mesh_peers(topic_hash)
+                    .collect::<HashSet<_>>();
+                (topic_hash, peers)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let connected_peers = self
+            .gossipsub
+            .all_peers()
+            .map(|(peer_id, _)| *peer_id)
+            .collect::<HashSet<_>>();
+        self.topic_score_tracker
+            .refresh(&connected_peers, &mesh_peers, &self.score_params, now);
+
+        let peers = self
+            .gossipsub
+            .all_peers()
+            .filter_map(|(peer_id, topics)| {
+                let score = self.gossipsub.peer_score(peer_id)?;
+                let topics = topics
+                    .into_iter()
+                    .map(|topic_hash| PeerTopicScore {
+                        topic: topic_hash.to_string(),
+                        scored_topic: GossipTopic::decode(topic_hash.as_str())
+                            .ok()
+                            .and_then(|topic| ScoredTopic::from_kind(topic.kind())),
+                        in_mesh: mesh_peers
+                            .get(topic_hash)
+                            .map_or(false, |peers| peers.contains(&peer_id)),
+                        components: self.topic_score_tracker.components(
+                            peer_id,
+                            topic_hash,
+                            &self.score_params,
+                            now,
+                        ),
+                    })
+                    .collect();
+                Some(PeerGossipsubScore {
+                    peer_id: peer_id.to_string(),
+                    score,
+                    topics,
+                })
+            })
+            .collect();
+
+        *self.network_globals.gossipsub_scores.write() = GossipsubScores {
+            params: GossipsubScoringOverrides::from_params(
+                &self.score_params,
+                &self.score_thresholds,
+            ),
+            peers,
+        };
+    }
+
     /* Eth2 RPC behaviour functions */
 
     /// Send a request to a peer over RPC.
@@ -616,6 +778,14 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Behaviour<AppReqId, TSpec> {
         self.discovery.add_enr(enr);
     }
 
+    /// Restores the peer reputations persisted before the last shutdown, banning peers that are
+    /// still banned and redialing the peers we were previously connected to.
+    pub fn restore_peers(&mut self, persisted: &PersistedPeers) {
+        for enr in self.peer_manager.restore_peers(persisted) {
+            self.discovery.add_enr(enr);
+        }
+    }
+
     /// Updates a subnet value to the ENR attnets/syncnets bitfield.
     ///
     /// The `value` is `true` if a subnet is being added and false otherwise.
@@ -797,6 +967,12 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Behaviour<AppReqId, TSpec> {
             Request::BlocksByRoot { .. } => {
                 metrics::inc_counter_vec(&metrics::TOTAL_RPC_REQUESTS, &["blocks_by_root"])
             }
+            Request::BlobsByRange { .. } => {
+                metrics::inc_counter_vec(&metrics::TOTAL_RPC_REQUESTS, &["blobs_by_range"])
+            }
+            Request::BlobsByRoot { .. } => {
+                metrics::inc_counter_vec(&metrics::TOTAL_RPC_REQUESTS, &["blobs_by_root"])
+            }
         }
         self.add_event(BehaviourEvent::RequestReceived {
             peer_id,
@@ -822,7 +998,10 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Behaviour<AppReqId, TSpec> {
             .cached_enrs()
             .filter_map(|(peer_id, enr)| {
                 let peers = self.network_globals.peers.read();
-                if predicate(enr) && peers.should_dial(peer_id) {
+                if predicate(enr)
+                    && self.peer_manager.is_peer_allowed(peer_id)
+                    && peers.should_dial(peer_id)
+                {
                     Some(*peer_id)
                 } else {
                     None
@@ -864,6 +1043,9 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Behaviour<AppReqId, TSpec> {
             add(ProposerSlashing);
             add(AttesterSlashing);
             add(SignedContributionAndProof);
+            add(LightClientFinalityUpdate);
+            add(LightClientOptimisticUpdate);
+            add(BlsToExecutionChange);
             for id in 0..attestation_subnet_count {
                 add(Attestation(SubnetId::new(id)));
             }
@@ -900,6 +1082,12 @@ where
                     Err(e) => {
                         debug!(self.log, "Could not decode gossipsub message"; "topic" => ?gs_msg.topic,"error" => e);
                         //reject the message
+                        self.topic_score_tracker.message_validated_on_topic(
+                            propagation_source,
+                            gs_msg.topic,
+                            &MessageAcceptance::Reject,
+                            &self.score_params,
+                        );
                         if let Err(e) = self.gossipsub.report_message_validation_result(
                             &id,
                             &propagation_source,
@@ -909,6 +1097,11 @@ where
                         }
                     }
                     Ok(msg) => {
+                        self.topic_score_tracker.message_received(
+                            id.clone(),
+                            gs_msg.topic.clone(),
+                            &self.score_params,
+                        );
                         // Notify the network
                         self.add_event(BehaviourEvent::PubsubMessage {
                             id,
@@ -1073,6 +1266,12 @@ where
                     InboundRequest::BlocksByRoot(req) => {
                         self.propagate_request(peer_request_id, peer_id, Request::BlocksByRoot(req))
                     }
+                    InboundRequest::BlobsByRange(req) => {
+                        self.propagate_request(peer_request_id, peer_id, Request::BlobsByRange(req))
+                    }
+                    InboundRequest::BlobsByRoot(req) => {
+                        self.propagate_request(peer_request_id, peer_id, Request::BlobsByRoot(req))
+                    }
                 }
             }
             Ok(RPCReceived::Response(id, resp)) => {
@@ -1095,12 +1294,20 @@ where
                     RPCResponse::BlocksByRoot(resp) => {
                         self.propagate_response(id, peer_id, Response::BlocksByRoot(Some(resp)))
                     }
+                    RPCResponse::BlobsByRange(resp) => {
+                        self.propagate_response(id, peer_id, Response::BlobsByRange(Some(resp)))
+                    }
+                    RPCResponse::BlobsByRoot(resp) => {
+                        self.propagate_response(id, peer_id, Response::BlobsByRoot(Some(resp)))
+                    }
                 }
             }
             Ok(RPCReceived::EndOfStream(id, termination)) => {
                 let response = match termination {
                     ResponseTermination::BlocksByRange => Response::BlocksByRange(None),
                     ResponseTermination::BlocksByRoot => Response::BlocksByRoot(None),
+                    ResponseTermination::BlobsByRange => Response::BlobsByRange(None),
+                    ResponseTermination::BlobsByRoot => Response::BlobsByRoot(None),
                 };
                 self.propagate_response(id, peer_id, response);
             }
@@ -1127,11 +1334,30 @@ where
                     .push_back(InternalBehaviourMessage::SocketUpdated(multiaddr));
             }
             DiscoveryEvent::QueryResult(results) => {
+                // Connected peers may have updated their ENR since they connected, which changes
+                // the `BlocksByRange` limit they advertise.
+                for peer_id in results.keys() {
+                    if self.peer_manager.is_connected(peer_id) {
+                        if let Some(enr) = self.discovery_mut().enr_of_peer(peer_id) {
+                            if self.peer_manager.update_connected_enr(peer_id, enr) {
+                                self.set_max_request_blocks(*peer_id);
+                            }
+                        }
+                    }
+                }
                 let to_dial_peers = self.peer_manager.peers_discovered(results);
                 for peer_id in to_dial_peers {
+                    let enr = self.discovery_mut().enr_of_peer(&peer_id);
+                    // Keep our peers diverse by not dialing into over-represented networks.
+                    if enr
+                        .as_ref()
+                        .map_or(false, |enr| self.peer_manager.is_asn_saturated(enr))
+                    {
+                        debug!(self.log, "Not dialing discovered peer, ASN saturated"; "peer_id" => %peer_id);
+                        continue;
+                    }
                     debug!(self.log, "Dialing discovered peer"; "peer_id" => %peer_id);
                     // For any dial event, inform the peer manager
-                    let enr = self.discovery_mut().enr_of_peer(&peer_id);
                     self.peer_manager.inject_dialing(&peer_id, enr);
                     self.internal_events
                         .push_back(InternalBehaviourMessage::DialPeer(peer_id));
@@ -1218,6 +1444,7 @@ where
         // perform gossipsub score updates when necessary
         while self.update_gossipsub_scores.poll_tick(cx).is_ready() {
             self.peer_manager.update_gossipsub_scores(&self.gossipsub);
+            self.update_gossipsub_scores_snapshot();
         }
 
         // poll the gossipsub cache to clear expired messages
@@ -1245,9 +1472,11 @@ impl<AppReqId: ReqId, TSpec: EthSpec> NetworkBehaviourEventProcess<PeerManagerEv
     fn inject_event(&mut self, event: PeerManagerEvent) {
         match event {
             PeerManagerEvent::PeerConnectedIncoming(peer_id) => {
+                self.set_max_request_blocks(peer_id);
                 self.add_event(BehaviourEvent::PeerConnectedIncoming(peer_id));
             }
             PeerManagerEvent::PeerConnectedOutgoing(peer_id) => {
+                self.set_max_request_blocks(peer_id);
                 self.add_event(BehaviourEvent::PeerConnectedOutgoing(peer_id));
             }
             PeerManagerEvent::PeerDisconnected(peer_id) => {
@@ -1266,6 +1495,12 @@ impl<AppReqId: ReqId, TSpec: EthSpec> NetworkBehaviourEventProcess<PeerManagerEv
                 // the network to send a status to this peer
                 self.add_event(BehaviourEvent::StatusPeer(peer_id));
             }
+            PeerManagerEvent::DialPeer(peer_id) => {
+                let enr = self.discovery_mut().enr_of_peer(&peer_id);
+                self.peer_manager.inject_dialing(&peer_id, enr);
+                self.internal_events
+                    .push_back(InternalBehaviourMessage::DialPeer(peer_id));
+            }
             PeerManagerEvent::DiscoverPeers(peers_to_find) => {
                 // Peer manager has requested a discovery query for more peers.
                 self.discovery.discover_peers(peers_to_find);
@@ -1307,6 +1542,10 @@ pub enum Request {
     BlocksByRange(BlocksByRangeRequest),
     /// A request blocks root request.
     BlocksByRoot(BlocksByRootRequest),
+    /// A blobs by range request.
+    BlobsByRange(BlobsByRangeRequest),
+    /// A blobs by root request.
+    BlobsByRoot(BlobsByRootRequest),
 }
 
 impl<TSpec: EthSpec> std::convert::From<Request> for OutboundRequest<TSpec> {
@@ -1314,6 +1553,8 @@ impl<TSpec: EthSpec> std::convert::From<Request> for OutboundRequest<TSpec> {
         match req {
             Request::BlocksByRoot(r) => OutboundRequest::BlocksByRoot(r),
             Request::BlocksByRange(r) => OutboundRequest::BlocksByRange(r),
+            Request::BlobsByRange(r) => OutboundRequest::BlobsByRange(r),
+            Request::BlobsByRoot(r) => OutboundRequest::BlobsByRoot(r),
             Request::Status(s) => OutboundRequest::Status(s),
         }
     }
@@ -1333,6 +1574,11 @@ pub enum Response<TSpec: EthSpec> {
     BlocksByRange(Option<Box<SignedBeaconBlock<TSpec>>>),
     /// A response to a get BLOCKS_BY_ROOT request.
     BlocksByRoot(Option<Box<SignedBeaconBlock<TSpec>>>),
+    /// A response to a get BLOB_SIDECARS_BY_RANGE request. A None response signals the end of the
+    /// batch.
+    BlobsByRange(Option<Arc<BlobSidecar<TSpec>>>),
+    /// A response to a get BLOB_SIDECARS_BY_ROOT request.
+    BlobsByRoot(Option<Arc<BlobSidecar<TSpec>>>),
 }
 
 impl<TSpec: EthSpec> std::convert::From<Response<TSpec>> for RPCCodedResponse<TSpec> {
@@ -1346,6 +1592,14 @@ impl<TSpec: EthSpec> std::convert::From<Response<TSpec>> for RPCCodedResponse<TS
                 Some(b) => RPCCodedResponse::Success(RPCResponse::BlocksByRange(b)),
                 None => RPCCodedResponse::StreamTermination(ResponseTermination::BlocksByRange),
             },
+            Response::BlobsByRange(r) => match r {
+                Some(b) => RPCCodedResponse::Success(RPCResponse::BlobsByRange(b)),
+                None => RPCCodedResponse::StreamTermination(ResponseTermination::BlobsByRange),
+            },
+            Response::BlobsByRoot(r) => match r {
+                Some(b) => RPCCodedResponse::Success(RPCResponse::BlobsByRoot(b)),
+                None => RPCCodedResponse::StreamTermination(ResponseTermination::BlobsByRoot),
+            },
             Response::Status(s) => RPCCodedResponse::Success(RPCResponse::Status(s)),
         }
     }
@@ -0,0 +1,337 @@
+//! Tracks the per-topic counters which make up the gossipsub score of each peer.
+//!
+//! Gossipsub doesn't expose the counters behind a peer's score, so they're mirrored here from the
+//! events gossipsub reports to us and decayed with the same topic parameters. Gossipsub only
+//! reports the first delivery of each message, so duplicates delivered by mesh peers within the
+//! mesh message deliveries window aren't counted, and the mesh message deliveries reported here
+//! may be lower than those gossipsub uses.
+use crate::TopicHash;
+use libp2p::gossipsub::{MessageAcceptance, MessageId, PeerScoreParams, TopicScoreParams};
+use libp2p::PeerId;
+use lru::LruCache;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+/// The number of messages awaiting validation whose topic is remembered, so that the rejection of a
+/// message can be attributed to its topic.
+const PENDING_MESSAGES_CAPACITY: usize = 4_096;
+
+/// The contribution of each component to a peer's score for a topic, before the topic weight is
+/// applied.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TopicScoreComponents {
+    /// The reward for the time the peer has spent in our mesh (P1).
+    pub time_in_mesh: f64,
+    /// The reward for messages first delivered by the peer (P2).
+    pub first_message_deliveries: f64,
+    /// The decayed number of messages delivered by the peer whilst in our mesh.
+    pub mesh_message_deliveries: f64,
+    /// The penalty for delivering fewer messages than the mesh message deliveries threshold (P3).
+    pub mesh_message_deliveries_deficit: f64,
+    /// The penalty for delivering invalid messages (P4).
+    pub invalid_message_deliveries: f64,
+}
+
+#[derive(Default)]
+struct TopicStats {
+    /// When the peer joined our mesh for the topic, or `None` if it isn't in the mesh.
+    graft_time: Option<Instant>,
+    first_message_deliveries: f64,
+    mesh_message_deliveries: f64,
+    invalid_message_deliveries: f64,
+}
+
+pub struct TopicScoreTracker {
+    stats: HashMap<PeerId, HashMap<TopicHash, TopicStats>>,
+    pending: LruCache<MessageId, TopicHash>,
+}
+
+impl TopicScoreTracker {
+    pub fn new() -> Self {
+        Self {
+            stats: HashMap::new(),
+            pending: LruCache::new(PENDING_MESSAGES_CAPACITY),
+        }
+    }
+
+    /// Record the receipt of a message which is awaiting validation.
+    pub fn message_received(
+        &mut self,
+        message_id: MessageId,
+        topic: TopicHash,
+        params: &PeerScoreParams,
+    ) {
+        if params.topics.contains_key(&topic) {
+            self.pending.put(message_id, topic);
+        }
+    }
+
+    /// Record the validation result of a message first delivered by `peer_id`.
+    ///
+    /// As with gossipsub, accepted messages count as deliveries and rejected messages are
+    /// penalised, whilst ignored messages affect neither.
+    pub fn message_validated(
+        &mut self,
+        peer_id: PeerId,
+        message_id: &MessageId,
+        acceptance: &MessageAcceptance,
+        params: &PeerScoreParams,
+    ) {
+        if let Some(topic) = self.pending.pop(message_id) {
+            self.message_validated_on_topic(peer_id, topic, acceptance, params);
+        }
+    }
+
+    /// Record the validation result of a message on `topic` first delivered by `peer_id`.
+    pub fn message_validated_on_topic(
+        &mut self,
+        peer_id: PeerId,
+        topic: TopicHash,
+        acceptance: &MessageAcceptance,
+        params: &PeerScoreParams,
+    ) {
+        let topic_params = match params.topics.get(&topic) {
+            Some(topic_params) => topic_params,
+            None => return,
+        };
+        let stats = self
+            .stats
+            .entry(peer_id)
+            .or_default()
+            .entry(topic)
+            .or_default();
+
+        match acceptance {
+            MessageAcceptance::Accept => {
+                stats.first_message_deliveries = (stats.first_message_deliveries + 1.0)
+                    .min(topic_params.first_message_deliveries_cap);
+                if stats.graft_time.is_some() {
+                    stats.mesh_message_deliveries = (stats.mesh_message_deliveries + 1.0)
+                        .min(topic_params.mesh_message_deliveries_cap);
+                }
+            }
+            MessageAcceptance::Reject => stats.invalid_message_deliveries += 1.0,
+            MessageAcceptance::Ignore => {}
+        }
+    }
+
+    /// Decay the counters and update the mesh membership of each peer. Should be called once per
+    /// decay interval of the score parameters.
+    ///
+    /// Peers which aren't in `peers` are forgotten.
+    pub fn refresh(
+        &mut self,
+        peers: &HashSet<PeerId>,
+        mesh_peers: &HashMap<&TopicHash, HashSet<&PeerId>>,
+        params: &PeerScoreParams,
+        now: Instant,
+    ) {
+        self.stats.retain(|peer_id, _| peers.contains(peer_id));
+
+        for (topic, topic_peers) in mesh_peers {
+            if !params.topics.contains_key(*topic) {
+                continue;
+            }
+            for peer_id in topic_peers {
+                self.stats
+                    .entry(**peer_id)
+                    .or_default()
+                    .entry((*topic).clone())
+                    .or_default();
+            }
+        }
+
+        let decay = |value: f64, decay: f64| {
+            let value = value * decay;
+            if value < params.decay_to_zero {
+                0.0
+            } else {
+                value
+            }
+        };
+
+        for (peer_id, topics) in self.stats.iter_mut() {
+            topics.retain(|topic, _| params.topics.contains_key(topic));
+            for (topic, stats) in topics.iter_mut() {
+                let topic_params = &params.topics[topic];
+
+                let in_mesh = mesh_peers
+                    .get(topic)
+                    .map_or(false, |peers| peers.contains(peer_id));
+                stats.graft_time = match (in_mesh, stats.graft_time) {
+                    (true, Some(graft_time)) => Some(graft_time),
+                    (true, None) => Some(now),
+                    (false, _) => None,
+                };
+
+                stats.first_message_deliveries = decay(
+                    stats.first_message_deliveries,
+                    topic_params.first_message_deliveries_decay,
+                );
+                stats.mesh_message_deliveries = decay(
+                    stats.mesh_message_deliveries,
+                    topic_params.mesh_message_deliveries_decay,
+                );
+                stats.invalid_message_deliveries = decay(
+                    stats.invalid_message_deliveries,
+                    topic_params.invalid_message_deliveries_decay,
+                );
+            }
+        }
+    }
+
+    /// Returns the score components of `peer_id` for `topic`, or `None` if the topic isn't scored.
+    pub fn components(
+        &self,
+        peer_id: &PeerId,
+        topic: &TopicHash,
+        params: &PeerScoreParams,
+        now: Instant,
+    ) -> Option<TopicScoreComponents> {
+        let topic_params = params.topics.get(topic)?;
+        let components = self
+            .stats
+            .get(peer_id)
+            .and_then(|topics| topics.get(topic))
+            .map_or_else(TopicScoreComponents::default, |stats| {
+                stats.components(topic_params, now)
+            });
+        Some(components)
+    }
+}
+
+impl TopicStats {
+    /// Computes the score components as gossipsub does.
+    fn components(&self, params: &TopicScoreParams, now: Instant) -> TopicScoreComponents {
+        let mesh_time = self
+            .graft_time
+            .map(|graft_time| now.saturating_duration_since(graft_time));
+
+        let time_in_mesh = mesh_time.map_or(0.0, |mesh_time| {
+            let quanta = mesh_time.as_secs_f64() / params.time_in_mesh_quantum.as_secs_f64();
+            quanta.min(params.time_in_mesh_cap) * params.time_in_mesh_weight
+        });
+
+        // The deficit is only penalised once the peer has been in the mesh long enough to have
+        // delivered messages.
+        let mesh_message_deliveries_deficit = match mesh_time {
+            Some(mesh_time)
+                if mesh_time > params.mesh_message_deliveries_activation
+                    && self.mesh_message_deliveries < params.mesh_message_deliveries_threshold =>
+            {
+                let deficit =
+                    params.mesh_message_deliveries_threshold - self.mesh_message_deliveries;
+                deficit * deficit * params.mesh_message_deliveries_weight
+            }
+            _ => 0.0,
+        };
+
+        TopicScoreComponents {
+            time_in_mesh,
+            first_message_deliveries: self.first_message_deliveries
+                * params.first_message_deliveries_weight,
+            mesh_message_deliveries: self.mesh_message_deliveries,
+            mesh_message_deliveries_deficit,
+            invalid_message_deliveries: self.invalid_message_deliveries
+                * self.invalid_message_deliveries
+                * params.invalid_message_deliveries_weight,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn params(topic: &TopicHash) -> PeerScoreParams {
+        let topic_params = TopicScoreParams {
+            time_in_mesh_weight: 1.0,
+            time_in_mesh_quantum: Duration::from_secs(1),
+            time_in_mesh_cap: 10.0,
+            first_message_deliveries_weight: 2.0,
+            first_message_deliveries_decay: 0.5,
+            first_message_deliveries_cap: 3.0,
+            mesh_message_deliveries_weight: -1.0,
+            mesh_message_deliveries_decay: 0.5,
+            mesh_message_deliveries_cap: 10.0,
+            mesh_message_deliveries_threshold: 4.0,
+            mesh_message_deliveries_activation: Duration::from_secs(5),
+            invalid_message_deliveries_weight: -10.0,
+            invalid_message_deliveries_decay: 0.5,
+            ..TopicScoreParams::default()
+        };
+        let mut params = PeerScoreParams::default();
+        params.topics.insert(topic.clone(), topic_params);
+        params
+    }
+
+    #[test]
+    fn components_follow_deliveries_and_mesh_time() {
+        let topic = TopicHash::from_raw("topic");
+        let params = params(&topic);
+        let peer_id = PeerId::random();
+        let peers = vec![peer_id].into_iter().collect::<HashSet<_>>();
+        let start = Instant::now();
+
+        let mut tracker = TopicScoreTracker::new();
+        let mesh_peers = vec![(&topic, vec![&peer_id].into_iter().collect())]
+            .into_iter()
+            .collect();
+        tracker.refresh(&peers, &mesh_peers, &params, start);
+
+        for i in 0..7_u8 {
+            tracker.message_received(MessageId::new(&[i]), topic.clone(), &params);
+        }
+        for i in 0..5_u8 {
+            tracker.message_validated(
+                peer_id,
+                &MessageId::new(&[i]),
+                &MessageAcceptance::Accept,
+                &params,
+            );
+        }
+        tracker.message_validated(
+            peer_id,
+            &MessageId::new(&[5]),
+            &MessageAcceptance::Reject,
+            &params,
+        );
+        tracker.message_validated(
+            peer_id,
+            &MessageId::new(&[6]),
+            &MessageAcceptance::Ignore,
+            &params,
+        );
+
+        let components = tracker
+            .components(&peer_id, &topic, &params, start + Duration::from_secs(3))
+            .unwrap();
+        assert_eq!(components.time_in_mesh, 3.0);
+        // First message deliveries are capped.
+        assert_eq!(components.first_message_deliveries, 6.0);
+        assert_eq!(components.mesh_message_deliveries, 5.0);
+        assert_eq!(components.mesh_message_deliveries_deficit, 0.0);
+        assert_eq!(components.invalid_message_deliveries, -10.0);
+
+        // Once decayed below the threshold after the activation window, the deficit is penalised.
+        tracker.refresh(&peers, &mesh_peers, &params, start);
+        let components = tracker
+            .components(&peer_id, &topic, &params, start + Duration::from_secs(20))
+            .unwrap();
+        assert_eq!(components.time_in_mesh, 10.0);
+        assert_eq!(components.mesh_message_deliveries, 2.5);
+        assert_eq!(components.mesh_message_deliveries_deficit, -2.25);
+
+        // Leaving the mesh resets the time in mesh, and unknown topics aren't scored.
+        tracker.refresh(&peers, &HashMap::new(), &params, start);
+        let components = tracker
+            .components(&peer_id, &topic, &params, start + Duration::from_secs(20))
+            .unwrap();
+        assert_eq!(components.time_in_mesh, 0.0);
+        assert!(tracker
+            .components(&peer_id, &TopicHash::from_raw("other"), &params, start)
+            .is_none());
+    }
+}
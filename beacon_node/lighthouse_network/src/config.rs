@@ -1,3 +1,4 @@
+use crate::behaviour::gossipsub_scoring_parameters::GossipsubScoringOverrides;
 use crate::types::GossipKind;
 use crate::{Enr, PeerIdSerialized};
 use directory::{
@@ -75,9 +76,27 @@ pub struct Config {
     /// The tcp port to broadcast to peers in order to reach back for libp2p services.
     pub enr_tcp_port: Option<u16>,
 
+    /// Custom key/value pairs to add to the local ENR, allowing operators to tag their nodes.
+    pub enr_custom_fields: Vec<(String, Vec<u8>)>,
+
+    /// The largest `BlocksByRange` request served to and sent to peers which advertise support
+    /// for larger requests, if greater than the spec's `MAX_REQUEST_BLOCKS`. Advertised in our ENR.
+    pub max_request_blocks: Option<u64>,
+
     /// Target number of connected peers.
     pub target_peers: usize,
 
+    /// The largest fraction of `target_peers` that may run the same client before peers of that
+    /// client are preferentially pruned.
+    pub peer_diversity_max_client_share: Option<f32>,
+
+    /// The largest fraction of `target_peers` that may connect from the same autonomous system
+    /// before peers from it are preferentially pruned and no longer dialed.
+    pub peer_diversity_max_asn_share: Option<f32>,
+
+    /// Path to an `ip2asn` database used to find the autonomous system of each peer.
+    pub asn_database: Option<PathBuf>,
+
     /// Gossipsub configuration parameters.
     #[serde(skip)]
     pub gs_config: GossipsubConfig,
@@ -98,6 +117,17 @@ pub struct Config {
     /// List of trusted libp2p nodes which are not scored.
     pub trusted_peers: Vec<PeerIdSerialized>,
 
+    /// Addresses of trusted peers, ending in their `/p2p/` peer id, which are dialed on start up
+    /// and redialed whenever we lose the connection.
+    pub trusted_peer_addresses: Vec<Multiaddr>,
+
+    /// If set, the only peers we accept connections from and dial, for private networks.
+    pub peer_allowlist: Option<Vec<PeerIdSerialized>>,
+
+    /// Addresses of allowlisted peers, ending in their `/p2p/` peer id, which are dialed on start
+    /// up.
+    pub peer_allowlist_addresses: Vec<Multiaddr>,
+
     /// Client version
     pub client_version: String,
 
@@ -107,6 +137,10 @@ pub struct Config {
     /// Attempt to construct external port mappings with UPnP.
     pub upnp_enabled: bool,
 
+    /// Don't listen for inbound connections. All peers are dialed by us, and discovery uses an
+    /// ephemeral UDP port which isn't advertised in our ENR.
+    pub disable_inbound: bool,
+
     /// Subscribe to all subnets for the duration of the runtime.
     pub subscribe_all_subnets: bool,
 
@@ -114,6 +148,28 @@ pub struct Config {
     /// runtime.
     pub import_all_attestations: bool,
 
+    /// Subscribe to the long-lived attestation subnets computed from our node id, rather than to
+    /// random subnets for each connected validator.
+    pub deterministic_long_lived_attnets: bool,
+
+    /// Subscribe to the light client gossip topics, so that light client updates are verified and
+    /// forwarded.
+    pub enable_light_client_server: bool,
+
+    /// The maximum number of workers processing network messages, defaults to the CPU count.
+    pub beacon_processor_max_workers: Option<usize>,
+
+    /// The number of workers processing network messages that are reserved for blocks and recent
+    /// aggregates.
+    pub beacon_processor_priority_workers: usize,
+
+    /// The maximum bandwidth in megabits per second used to download blocks by range and
+    /// backfill sync.
+    pub sync_max_bandwidth: Option<u64>,
+
+    /// The maximum bandwidth in megabits per second used to download blocks by backfill sync.
+    pub backfill_max_bandwidth: Option<u64>,
+
     /// A setting specifying a range of values that tune the network parameters of lighthouse. The
     /// lower the value the less bandwidth used, but the slower messages will be received.
     pub network_load: u8,
@@ -128,6 +184,9 @@ pub struct Config {
     /// List of extra topics to initially subscribe to as strings.
     pub topics: Vec<GossipKind>,
 
+    /// Overrides for the gossipsub peer scoring parameters.
+    pub gossipsub_scoring_overrides: Option<GossipsubScoringOverrides>,
+
     /// Whether metrics are enabled.
     pub metrics_enabled: bool,
 }
@@ -190,22 +249,38 @@ impl Default for Config {
             enr_address: None,
             enr_udp_port: None,
             enr_tcp_port: None,
+            enr_custom_fields: Vec::new(),
+            max_request_blocks: None,
             target_peers: 50,
+            peer_diversity_max_client_share: None,
+            peer_diversity_max_asn_share: None,
+            asn_database: None,
             gs_config,
             discv5_config,
             boot_nodes_enr: vec![],
             boot_nodes_multiaddr: vec![],
             libp2p_nodes: vec![],
             trusted_peers: vec![],
+            trusted_peer_addresses: vec![],
+            peer_allowlist: None,
+            peer_allowlist_addresses: vec![],
             client_version: lighthouse_version::version_with_platform(),
             disable_discovery: false,
             upnp_enabled: true,
+            disable_inbound: false,
             network_load: 3,
+            beacon_processor_max_workers: None,
+            beacon_processor_priority_workers: 1,
+            sync_max_bandwidth: None,
+            backfill_max_bandwidth: None,
             private: false,
             subscribe_all_subnets: false,
             import_all_attestations: false,
+            deterministic_long_lived_attnets: false,
+            enable_light_client_server: false,
             shutdown_after_sync: false,
             topics: Vec::new(),
+            gossipsub_scoring_overrides: None,
             metrics_enabled: false,
         }
     }
@@ -296,7 +371,7 @@ pub fn gossipsub_config(network_load: u8, fork_context: Arc<ForkContext>) -> Gos
         match fork_context.current_fork() {
             // according to: https://github.com/ethereum/consensus-specs/blob/dev/specs/merge/p2p-interface.md#the-gossip-domain-gossipsub
             // the derivation of the message-id remains the same in the merge
-            ForkName::Altair | ForkName::Merge => {
+            ForkName::Altair | ForkName::Merge | ForkName::Capella | ForkName::Deneb => {
                 let topic_len_bytes = topic_bytes.len().to_le_bytes();
                 let mut vec = Vec::with_capacity(
                     prefix.len() + topic_len_bytes.len() + topic_bytes.len() + message.data.len(),
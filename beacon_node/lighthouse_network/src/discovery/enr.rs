@@ -23,6 +23,25 @@ pub const ETH2_ENR_KEY: &str = "eth2";
 pub const ATTESTATION_BITFIELD_ENR_KEY: &str = "attnets";
 /// The ENR field specifying the sync committee subnet bitfield.
 pub const SYNC_COMMITTEE_BITFIELD_ENR_KEY: &str = "syncnets";
+/// The ENR field specifying the largest `BlocksByRange` request the node serves, if it supports
+/// requests larger than the spec's `MAX_REQUEST_BLOCKS`.
+pub const MAX_REQUEST_BLOCKS_ENR_KEY: &str = "lh_max_blocks";
+/// The ENR fields set by the ENR scheme or by lighthouse itself, which can't be set as custom
+/// fields.
+pub const RESERVED_ENR_KEYS: &[&str] = &[
+    "id",
+    "secp256k1",
+    "ip",
+    "ip6",
+    "tcp",
+    "tcp6",
+    "udp",
+    "udp6",
+    ETH2_ENR_KEY,
+    ATTESTATION_BITFIELD_ENR_KEY,
+    SYNC_COMMITTEE_BITFIELD_ENR_KEY,
+    MAX_REQUEST_BLOCKS_ENR_KEY,
+];
 
 /// Extension trait for ENR's within Eth2.
 pub trait Eth2Enr {
@@ -37,6 +56,13 @@ pub trait Eth2Enr {
     ) -> Result<EnrSyncCommitteeBitfield<TSpec>, &'static str>;
 
     fn eth2(&self) -> Result<EnrForkId, &'static str>;
+
+    /// The largest `BlocksByRange` request the node advertises it serves, if any.
+    fn max_request_blocks(&self) -> Option<u64>;
+
+    /// The fields of the ENR which aren't reserved, such as those added by operators to tag
+    /// their nodes.
+    fn custom_fields(&self) -> Vec<(String, Vec<u8>)>;
 }
 
 impl Eth2Enr for Enr {
@@ -67,6 +93,29 @@ impl Eth2Enr for Enr {
 
         EnrForkId::from_ssz_bytes(eth2_bytes).map_err(|_| "Could not decode EnrForkId")
     }
+
+    fn max_request_blocks(&self) -> Option<u64> {
+        self.get(MAX_REQUEST_BLOCKS_ENR_KEY)
+            .and_then(|bytes| u64::from_ssz_bytes(bytes).ok())
+    }
+
+    fn custom_fields(&self) -> Vec<(String, Vec<u8>)> {
+        self.iter()
+            .map(|(key, value)| (String::from_utf8_lossy(key).into_owned(), value.to_vec()))
+            .filter(|(key, _)| !RESERVED_ENR_KEYS.contains(&key.as_str()))
+            .collect()
+    }
+}
+
+/// Returns an error if any of `custom_fields` would overwrite a reserved ENR field.
+pub fn verify_custom_fields(custom_fields: &[(String, Vec<u8>)]) -> Result<(), String> {
+    match custom_fields
+        .iter()
+        .find(|(key, _)| RESERVED_ENR_KEYS.contains(&key.as_str()))
+    {
+        Some((key, _)) => Err(format!("The {} ENR field cannot be set directly", key)),
+        None => Ok(()),
+    }
 }
 
 /// Either use the given ENR or load an ENR from file if it exists and matches the current NodeId
@@ -165,7 +214,8 @@ pub fn build_enr<T: EthSpec>(
     config: &NetworkConfig,
     enr_fork_id: &EnrForkId,
 ) -> Result<Enr, String> {
-    let mut builder = create_enr_builder_from_config(config, true);
+    // peers can't connect to us if we don't accept inbound connections
+    let mut builder = create_enr_builder_from_config(config, !config.disable_inbound);
 
     // set the `eth2` field on our ENR
     builder.add_value(ETH2_ENR_KEY, &enr_fork_id.as_ssz_bytes());
@@ -180,6 +230,20 @@ pub fn build_enr<T: EthSpec>(
 
     builder.add_value(SYNC_COMMITTEE_BITFIELD_ENR_KEY, &bitfield.as_ssz_bytes());
 
+    // advertise support for larger `BlocksByRange` requests
+    if let Some(max_request_blocks) = config.max_request_blocks {
+        builder.add_value(
+            MAX_REQUEST_BLOCKS_ENR_KEY,
+            &max_request_blocks.as_ssz_bytes(),
+        );
+    }
+
+    // set the operator's custom fields
+    verify_custom_fields(&config.enr_custom_fields)?;
+    for (key, value) in &config.enr_custom_fields {
+        builder.add_value(key, value);
+    }
+
     builder
         .build(enr_key)
         .map_err(|e| format!("Could not build Local ENR: {:?}", e))
@@ -200,6 +264,10 @@ fn compare_enr(local_enr: &Enr, disk_enr: &Enr) -> bool {
         // otherwise we use a new ENR. This will likely only be true for non-validating nodes
         && local_enr.get(ATTESTATION_BITFIELD_ENR_KEY) == disk_enr.get(ATTESTATION_BITFIELD_ENR_KEY)
         && local_enr.get(SYNC_COMMITTEE_BITFIELD_ENR_KEY) == disk_enr.get(SYNC_COMMITTEE_BITFIELD_ENR_KEY)
+        // the advertised `BlocksByRange` limit must match
+        && local_enr.get(MAX_REQUEST_BLOCKS_ENR_KEY) == disk_enr.get(MAX_REQUEST_BLOCKS_ENR_KEY)
+        // the custom fields must match, so that removed fields are no longer advertised
+        && local_enr.custom_fields() == disk_enr.custom_fields()
 }
 
 /// Loads enr from the given directory
@@ -12,8 +12,8 @@ use crate::metrics;
 use crate::{error, Enr, NetworkConfig, NetworkGlobals, Subnet, SubnetDiscovery};
 use discv5::{enr::NodeId, Discv5, Discv5Event};
 pub use enr::{
-    build_enr, create_enr_builder_from_config, load_enr_from_disk, use_or_load_enr, CombinedKey,
-    Eth2Enr,
+    build_enr, create_enr_builder_from_config, load_enr_from_disk, use_or_load_enr,
+    verify_custom_fields, CombinedKey, Eth2Enr,
 };
 pub use enr_ext::{peer_id_to_node_id, CombinedKeyExt, EnrExt};
 pub use libp2p::core::identity::{Keypair, PublicKey};
@@ -76,6 +76,20 @@ pub enum DiscoveryEvent {
     SocketUpdated(SocketAddr),
 }
 
+/// A set of changes to the advertised fields of the local ENR.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnrUpdate {
+    /// The IP address to advertise. IPv6 addresses set the `ip6` field, and cause the ports to be
+    /// set in the `tcp6` and `udp6` fields.
+    pub ip: Option<IpAddr>,
+    /// The TCP port to advertise for libp2p connections.
+    pub tcp_port: Option<u16>,
+    /// The UDP port to advertise for discovery.
+    pub udp_port: Option<u16>,
+    /// Arbitrary key-value pairs to insert. Keys used by the protocol may not be overwritten.
+    pub custom_fields: Vec<(String, Vec<u8>)>,
+}
+
 #[derive(Clone, PartialEq)]
 struct SubnetQuery {
     subnet: Subnet,
@@ -156,6 +170,9 @@ pub struct Discovery<TSpec: EthSpec> {
     /// static lifetime.
     discv5: Discv5,
 
+    /// The key used to sign the local ENR, used to validate updates before they're applied.
+    enr_key: CombinedKey,
+
     /// A collection of network constants that can be read from other threads.
     network_globals: Arc<NetworkGlobals<TSpec>>,
 
@@ -199,13 +216,21 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
 
         info!(log, "ENR Initialised"; "enr" => local_enr.to_base64(), "seq" => local_enr.seq(), "id"=> %local_enr.node_id(), "ip" => ?local_enr.ip(), "udp"=> ?local_enr.udp(), "tcp" => ?local_enr.tcp());
 
-        let listen_socket = SocketAddr::new(config.listen_address, config.discovery_port);
+        // Without inbound connections, discovery only needs to receive responses to its own
+        // requests, which a NAT forwards to whichever port the OS assigns.
+        let discovery_port = if config.disable_inbound {
+            0
+        } else {
+            config.discovery_port
+        };
+        let listen_socket = SocketAddr::new(config.listen_address, discovery_port);
 
         // convert the keypair into an ENR key
         let enr_key: CombinedKey = CombinedKey::from_libp2p(local_key)?;
 
         let mut discv5 = Discv5::new(local_enr, enr_key, config.discv5_config.clone())
             .map_err(|e| format!("Discv5 service failed. Error: {:?}", e))?;
+        let enr_key: CombinedKey = CombinedKey::from_libp2p(local_key)?;
 
         // Add bootnodes to routing table
         for bootnode_enr in config.boot_nodes_enr.clone() {
@@ -294,6 +319,7 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
             queued_queries: VecDeque::with_capacity(10),
             active_queries: FuturesUnordered::new(),
             discv5,
+            enr_key,
             event_stream,
             started: !config.disable_discovery,
             log,
@@ -428,6 +454,75 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
         Ok(())
     }
 
+    /// Updates the advertised address, ports and custom fields of the local ENR.
+    ///
+    /// Updating the ENR increments its sequence number, so peers learn of the new record the next
+    /// time they communicate with us. A peer search is started to hasten this.
+    ///
+    /// The update is applied to a copy of the local ENR first, so an invalid update (e.g. one which
+    /// would make the ENR exceed the maximum size) leaves the local ENR untouched.
+    pub fn update_enr(&mut self, update: EnrUpdate) -> Result<Enr, String> {
+        enr::verify_custom_fields(&update.custom_fields)?;
+
+        let mut updated_enr = self.discv5.local_enr();
+        let mut updated_keys = vec![];
+        let is_ipv6 = matches!(update.ip, Some(IpAddr::V6(_)));
+
+        if let Some(ip) = update.ip {
+            updated_enr
+                .set_ip(ip, &self.enr_key)
+                .map_err(|e| format!("{:?}", e))?;
+            updated_keys.push(if is_ipv6 { "ip6" } else { "ip" });
+        }
+        if let Some(port) = update.tcp_port {
+            if is_ipv6 {
+                updated_enr.set_tcp6(port, &self.enr_key)
+            } else {
+                updated_enr.set_tcp(port, &self.enr_key)
+            }
+            .map_err(|e| format!("{:?}", e))?;
+            updated_keys.push(if is_ipv6 { "tcp6" } else { "tcp" });
+        }
+        if let Some(port) = update.udp_port {
+            if is_ipv6 {
+                updated_enr.set_udp6(port, &self.enr_key)
+            } else {
+                updated_enr.set_udp(port, &self.enr_key)
+            }
+            .map_err(|e| format!("{:?}", e))?;
+            updated_keys.push(if is_ipv6 { "udp6" } else { "udp" });
+        }
+        for (key, value) in &update.custom_fields {
+            updated_enr
+                .insert(key, value, &self.enr_key)
+                .map_err(|e| format!("{:?}", e))?;
+            updated_keys.push(key);
+        }
+
+        // The updated ENR is valid, so copy its fields to the local ENR.
+        for key in updated_keys {
+            let value = updated_enr
+                .get(key)
+                .ok_or_else(|| format!("updated ENR is missing the {} field", key))?;
+            self.discv5
+                .enr_insert(key, value)
+                .map_err(|e| format!("{:?}", e))?;
+        }
+
+        let enr = self.discv5.local_enr();
+        info!(self.log, "Updated local ENR"; "seq" => enr.seq(), "enr" => enr.to_base64());
+
+        // replace the global version
+        *self.network_globals.local_enr.write() = enr.clone();
+        // persist modified enr to disk
+        enr::save_enr_to_disk(Path::new(&self.enr_dir), &enr, &self.log);
+
+        // contact other nodes so that they learn of the new sequence number
+        self.discover_peers(FIND_NODE_QUERY_CLOSEST_PEERS);
+
+        Ok(enr)
+    }
+
     /// Adds/Removes a subnet from the ENR attnets/syncnets Bitfield
     pub fn update_enr_bitfield(&mut self, subnet: Subnet, value: bool) -> Result<(), String> {
         let local_enr = self.discv5.local_enr();
@@ -1187,4 +1282,24 @@ mod tests {
         // when a peer belongs to multiple subnet ids, we use the highest ttl.
         assert_eq!(results.get(&enr1.peer_id()).unwrap(), &instant1);
     }
+
+    #[test]
+    fn test_enr_custom_fields() {
+        let keypair = libp2p::identity::Keypair::generate_secp256k1();
+        let enr_key: CombinedKey = CombinedKey::from_libp2p(&keypair).unwrap();
+        let custom_fields = vec![("operator".to_string(), b"example".to_vec())];
+        let config = NetworkConfig {
+            enr_custom_fields: custom_fields.clone(),
+            ..Default::default()
+        };
+        let enr: Enr = build_enr::<E>(&enr_key, &config, &EnrForkId::default()).unwrap();
+        assert_eq!(enr.custom_fields(), custom_fields);
+
+        // Reserved fields can't be overwritten.
+        let config = NetworkConfig {
+            enr_custom_fields: vec![(ETH2_ENR_KEY.to_string(), vec![0])],
+            ..Default::default()
+        };
+        assert!(build_enr::<E>(&enr_key, &config, &EnrForkId::default()).is_err());
+    }
 }
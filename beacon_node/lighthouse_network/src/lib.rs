@@ -25,6 +25,12 @@ use std::str::FromStr;
 #[derive(Clone, Debug)]
 pub struct PeerIdSerialized(libp2p::PeerId);
 
+impl From<PeerId> for PeerIdSerialized {
+    fn from(peer_id: PeerId) -> Self {
+        Self(peer_id)
+    }
+}
+
 impl From<PeerIdSerialized> for PeerId {
     fn from(peer_id: PeerIdSerialized) -> Self {
         peer_id.0
@@ -63,15 +69,19 @@ impl<'de> Deserialize<'de> for PeerIdSerialized {
 }
 
 pub use crate::types::{
-    error, Enr, EnrSyncCommitteeBitfield, GossipTopic, NetworkGlobals, PubsubMessage, Subnet,
-    SubnetDiscovery,
+    error, ClockSkewStatus, Enr, EnrSyncCommitteeBitfield, GossipTopic, NetworkGlobals,
+    PeerDiversity, PortMappingMethod, PortMappingStatus, PubsubMessage, Subnet, SubnetDiscovery,
 };
 
 pub use prometheus_client;
 
+pub use behaviour::gossipsub_scoring_parameters::{
+    GossipsubScores, GossipsubScoringOverrides, PeerGossipsubScore, PeerTopicScore, ScoredTopic,
+};
+pub use behaviour::topic_score_tracker::TopicScoreComponents;
 pub use behaviour::{BehaviourEvent, Gossipsub, PeerRequestId, Request, Response};
 pub use config::Config as NetworkConfig;
-pub use discovery::{CombinedKeyExt, EnrExt, Eth2Enr};
+pub use discovery::{CombinedKeyExt, EnrExt, EnrUpdate, Eth2Enr};
 pub use discv5;
 pub use libp2p;
 pub use libp2p::bandwidth::BandwidthSinks;
@@ -81,7 +91,8 @@ pub use libp2p::{multiaddr, Multiaddr};
 pub use metrics::scrape_discovery_metrics;
 pub use peer_manager::{
     peerdb::client::Client,
-    peerdb::score::{PeerAction, ReportSource},
+    peerdb::persisted::PersistedPeers,
+    peerdb::score::{PeerAction, PeerActionCounts, ReportSource},
     peerdb::PeerDB,
     ConnectionDirection, PeerConnectionStatus, PeerInfo, PeerManager, SyncInfo, SyncStatus,
 };
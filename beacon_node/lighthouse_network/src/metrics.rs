@@ -54,6 +54,12 @@ lazy_static! {
             "Failed attestation publishes per subnet",
             &["subnet"]
         );
+    pub static ref FAILED_SYNC_COMMITTEE_PUBLISHES_PER_SUBNET: Result<IntGaugeVec> =
+        try_create_int_gauge_vec(
+            "gossipsub_failed_sync_committee_publishes_per_subnet",
+            "Failed sync committee message publishes per subnet",
+            &["subnet"]
+        );
     pub static ref FAILED_PUBLISHES_PER_MAIN_TOPIC: Result<IntGaugeVec> = try_create_int_gauge_vec(
         "gossipsub_failed_publishes_per_main_topic",
         "Failed gossip publishes",
@@ -99,6 +105,12 @@ lazy_static! {
             "Messages that failed to be published on retry to gossipsub per topic kind.",
             &["topic_kind"]
         );
+    pub static ref GOSSIP_EMPTY_MESH_PUBLISH_PER_TOPIC_KIND: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+            "gossipsub_empty_mesh_publish_per_topic_kind",
+            "Messages published to subscribed topics without any mesh peers per topic kind.",
+            &["topic_kind"]
+        );
     pub static ref PEER_SCORE_DISTRIBUTION: Result<IntGaugeVec> =
         try_create_int_gauge_vec(
             "peer_score_distribution",
@@ -123,6 +135,20 @@ lazy_static! {
     pub static ref NETWORK_OUTBOUND_PEERS: Result<IntGauge> =
         try_create_int_gauge("network_outbound_peers","The number of peers that are currently connected that we dialed.");
 
+    pub static ref RPC_RESPONSES_DELAYED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "libp2p_rpc_responses_delayed_total",
+        "Number of RPC response chunks held back by the outbound response rate limiter",
+        &["protocol"]
+    );
+
+    pub static ref RPC_RESPONSE_SUBSTREAMS_DROPPED: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+            "libp2p_rpc_response_substreams_dropped_total",
+            "Number of RPC response substreams ended with an error by the outbound response rate \
+             limiter, as their held back responses exceeded the queue size or response timeout",
+            &["reason"]
+        );
+
     /*
      * Peer Reporting
      */
@@ -131,6 +157,18 @@ lazy_static! {
         "Number of peer reports per msg",
         &["msg"]
     );
+
+    /*
+     * Clock skew
+     */
+    pub static ref CLOCK_SKEW_ESTIMATE: Result<IntGauge> = try_create_int_gauge(
+        "network_clock_skew_estimate_milliseconds",
+        "The estimated offset of the local clock from the network's, from the arrival times of aggregates"
+    );
+    pub static ref CLOCK_SKEW_SAMPLE_PEERS: Result<IntGauge> = try_create_int_gauge(
+        "network_clock_skew_sample_peers",
+        "The number of peers with a recent sample of the clock offset"
+    );
 }
 
 /// Checks if we consider the NAT open.
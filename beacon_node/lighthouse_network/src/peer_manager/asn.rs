@@ -0,0 +1,131 @@
+//! A minimal IP to autonomous system number (ASN) database.
+//!
+//! The database is read from a tab-separated file in the format published by
+//! [iptoasn.com](https://iptoasn.com) (`ip2asn-combined.tsv`), where each line is:
+//!
+//! ```text
+//! range_start	range_end	AS_number	country_code	AS_description
+//! ```
+//!
+//! Ranges with an AS number of `0` denote unrouted space and are ignored.
+
+use std::fs;
+use std::net::IpAddr;
+use std::path::Path;
+
+/// A sorted collection of non-overlapping IP ranges and the ASN that announces them.
+#[derive(Debug, Default)]
+pub struct AsnDatabase {
+    /// `(range_start, range_end, asn)`, sorted by `range_start`. IPv4 addresses are stored as
+    /// IPv4-mapped IPv6 addresses so that both families share one table.
+    ranges: Vec<(u128, u128, u32)>,
+}
+
+impl AsnDatabase {
+    /// Reads the database from the file at `path`.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Unable to read ASN database {:?}: {}", path, e))?;
+        Self::parse(&contents)
+    }
+
+    /// Parses the database from the contents of a `ip2asn` file.
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut ranges = Vec::new();
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid =
+                |field: &str| format!("Invalid {} on line {}: {}", field, index + 1, line);
+            let mut fields = line.split('\t');
+            let start = fields
+                .next()
+                .and_then(|s| s.parse::<IpAddr>().ok())
+                .ok_or_else(|| invalid("range start"))?;
+            let end = fields
+                .next()
+                .and_then(|s| s.parse::<IpAddr>().ok())
+                .ok_or_else(|| invalid("range end"))?;
+            let asn = fields
+                .next()
+                .and_then(|s| s.parse::<u32>().ok())
+                .ok_or_else(|| invalid("AS number"))?;
+
+            if asn == 0 {
+                continue;
+            }
+            let (start, end) = (ip_to_u128(start), ip_to_u128(end));
+            if start > end {
+                return Err(invalid("range"));
+            }
+            ranges.push((start, end, asn));
+        }
+        ranges.sort_unstable_by_key(|(start, _, _)| *start);
+        Ok(AsnDatabase { ranges })
+    }
+
+    /// Returns the ASN announcing `ip`, if it is known.
+    pub fn lookup(&self, ip: IpAddr) -> Option<u32> {
+        let ip = ip_to_u128(ip);
+        // The last range starting at or before `ip`.
+        let index = self
+            .ranges
+            .partition_point(|(start, _, _)| *start <= ip)
+            .checked_sub(1)?;
+        let (_, end, asn) = self.ranges[index];
+        if ip <= end {
+            Some(asn)
+        } else {
+            None
+        }
+    }
+
+    /// The number of ranges in the database.
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Returns `true` if the database contains no ranges.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+fn ip_to_u128(ip: IpAddr) -> u128 {
+    match ip {
+        IpAddr::V4(ip) => u128::from(ip.to_ipv6_mapped()),
+        IpAddr::V6(ip) => u128::from(ip),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DB: &str = "1.0.0.0\t1.0.0.255\t13335\tUS\tCLOUDFLARENET\n\
+                      1.0.1.0\t1.0.3.255\t0\tNone\tNot routed\n\
+                      8.8.8.0\t8.8.8.255\t15169\tUS\tGOOGLE\n\
+                      2001:db8::\t2001:db8:ffff:ffff:ffff:ffff:ffff:ffff\t64496\tZZ\tDOC\n";
+
+    #[test]
+    fn lookup() {
+        let db = AsnDatabase::parse(DB).unwrap();
+        assert_eq!(db.len(), 3);
+        assert_eq!(db.lookup("1.0.0.1".parse().unwrap()), Some(13335));
+        assert_eq!(db.lookup("1.0.2.1".parse().unwrap()), None);
+        assert_eq!(db.lookup("8.8.8.8".parse().unwrap()), Some(15169));
+        assert_eq!(db.lookup("8.8.9.0".parse().unwrap()), None);
+        assert_eq!(db.lookup("0.0.0.1".parse().unwrap()), None);
+        assert_eq!(db.lookup("2001:db8::1".parse().unwrap()), Some(64496));
+        assert_eq!(db.lookup("2001:db9::1".parse().unwrap()), None);
+    }
+
+    #[test]
+    fn invalid_lines() {
+        assert!(AsnDatabase::parse("1.0.0.0\t1.0.0.255\tAS1\n").is_err());
+        assert!(AsnDatabase::parse("1.0.0.0\n").is_err());
+        assert!(AsnDatabase::parse("1.0.0.255\t1.0.0.0\t1\n").is_err());
+    }
+}
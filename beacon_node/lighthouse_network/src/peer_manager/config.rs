@@ -1,3 +1,7 @@
+use libp2p::{Multiaddr, PeerId};
+use std::collections::HashSet;
+use std::path::PathBuf;
+
 /// The time in seconds between re-status's peers.
 pub const DEFAULT_STATUS_INTERVAL: u64 = 300;
 
@@ -20,6 +24,23 @@ pub struct Config {
     pub metrics_enabled: bool,
     /// Target number of peers to connect to.
     pub target_peer_count: usize,
+    /// Addresses of trusted peers, each ending in the `/p2p/` id of the peer, used to redial them.
+    pub trusted_peer_addresses: Vec<Multiaddr>,
+    /// If set, the only peers we may connect to. All other peers are disconnected and are never
+    /// dialed.
+    pub peer_allowlist: Option<HashSet<PeerId>>,
+    /// Whether inbound connections are disabled, so that all peers must be dialed by us.
+    pub outbound_only: bool,
+
+    /* Diversity related configurations */
+    /// The largest fraction of our target peers that may run the same client before we prune
+    /// peers of that client in favour of others.
+    pub max_client_share: Option<f32>,
+    /// The largest fraction of our target peers that may connect from the same autonomous system
+    /// before we prune and stop dialing peers from it. Requires `asn_database`.
+    pub max_asn_share: Option<f32>,
+    /// An `ip2asn` database used to find the autonomous system of each peer.
+    pub asn_database: Option<PathBuf>,
 
     /* RPC related configurations */
     /// Time in seconds between status requests sent to peers.
@@ -38,6 +59,12 @@ impl Default for Config {
             discovery_enabled: true,
             metrics_enabled: false,
             target_peer_count: DEFAULT_TARGET_PEERS,
+            trusted_peer_addresses: Vec::new(),
+            peer_allowlist: None,
+            outbound_only: false,
+            max_client_share: None,
+            max_asn_share: None,
+            asn_database: None,
             status_interval: DEFAULT_STATUS_INTERVAL,
             ping_interval_inbound: DEFAULT_PING_INTERVAL_INBOUND,
             ping_interval_outbound: DEFAULT_PING_INTERVAL_OUTBOUND,
@@ -1,20 +1,25 @@
 //! Implementation of Lighthouse's peer management system.
 
 use crate::behaviour::TARGET_SUBNET_PEERS;
+use crate::discovery::enr_ext::EnrExt;
 use crate::rpc::{GoodbyeReason, MetaData, Protocol, RPCError, RPCResponseErrorCode};
 use crate::{error, metrics, Gossipsub};
-use crate::{NetworkGlobals, PeerId};
+use crate::{NetworkGlobals, PeerDiversity, PeerId};
 use crate::{Subnet, SubnetDiscovery};
+use asn::AsnDatabase;
 use discv5::Enr;
 use hashset_delay::HashSetDelay;
 use libp2p::identify::IdentifyInfo;
-use peerdb::{client::ClientKind, BanOperation, BanResult, ScoreUpdateResult};
+use libp2p::multiaddr::Protocol as MProtocol;
+use peerdb::{
+    client::ClientKind, persisted::PersistedPeers, BanOperation, BanResult, ScoreUpdateResult,
+};
 use rand::seq::SliceRandom;
-use slog::{debug, error, trace, warn};
+use slog::{debug, error, info, trace, warn};
 use smallvec::SmallVec;
 use std::{
     sync::Arc,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 use strum::IntoEnumIterator;
 use types::{EthSpec, SyncSubnetId};
@@ -29,8 +34,9 @@ pub use peerdb::peer_info::{
 };
 use peerdb::score::{PeerAction, ReportSource};
 pub use peerdb::sync_status::{SyncInfo, SyncStatus};
-use std::collections::{hash_map::Entry, HashMap};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 use std::net::IpAddr;
+mod asn;
 pub mod config;
 mod network_behaviour;
 
@@ -38,6 +44,9 @@ mod network_behaviour;
 /// requests. This defines the interval in seconds.
 const HEARTBEAT_INTERVAL: u64 = 30;
 
+/// The delay in seconds before redialing a trusted peer we are not connected to.
+const TRUSTED_PEER_REDIAL_INTERVAL: u64 = 5;
+
 /// This is used in the pruning logic. We avoid pruning peers on sync-committees if doing so would
 /// lower our peer count below this number. Instead we favour a non-uniform distribution of subnet
 /// peers.
@@ -57,6 +66,19 @@ pub const MIN_OUTBOUND_ONLY_FACTOR: f32 = 0.2;
 /// dialing priority peers we need for validator duties.
 pub const PRIORITY_PEER_EXCESS: f32 = 0.2;
 
+/// Returns `share` of `target_peers`, rounded up and at least one peer.
+fn share_of_peers(share: f32, target_peers: usize) -> usize {
+    ((share * target_peers as f32).ceil() as usize).max(1)
+}
+
+/// Returns the current unix time in seconds, used to measure how long the node was offline.
+fn unix_time_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
 /// The main struct that handles peer's reputation and connection status.
 pub struct PeerManager<TSpec: EthSpec> {
     /// Storage of network globals to access the `PeerDB`.
@@ -71,6 +93,12 @@ pub struct PeerManager<TSpec: EthSpec> {
     status_peers: HashSetDelay<PeerId>,
     /// The target number of peers we would like to connect to.
     target_peers: usize,
+    /// Known addresses of trusted peers, used when redialing them.
+    trusted_peer_addresses: HashMap<PeerId, Vec<Multiaddr>>,
+    /// Trusted peers that are not connected, awaiting to be redialed.
+    trusted_peer_redials: HashSetDelay<PeerId>,
+    /// If set, the only peers we connect to or dial.
+    peer_allowlist: Option<HashSet<PeerId>>,
     /// A collection of sync committee subnets that we need to stay subscribed to.
     /// Sync committee subnets are longer term (256 epochs). Hence, we need to re-run
     /// discovery queries for subnet peers if we disconnect from existing sync
@@ -80,8 +108,16 @@ pub struct PeerManager<TSpec: EthSpec> {
     heartbeat: tokio::time::Interval,
     /// Keeps track of whether the discovery service is enabled or not.
     discovery_enabled: bool,
+    /// Whether inbound connections are disabled, in which case we dial up to our maximum peers.
+    outbound_only: bool,
     /// Keeps track if the current instance is reporting metrics or not.
     metrics_enabled: bool,
+    /// The largest fraction of `target_peers` we keep from a single client when pruning.
+    max_client_share: Option<f32>,
+    /// The largest fraction of `target_peers` we keep or dial from a single autonomous system.
+    max_asn_share: Option<f32>,
+    /// Maps the IP addresses of peers to the autonomous system announcing them.
+    asn_database: Option<AsnDatabase>,
     /// The logger associated with the `PeerManager`.
     log: slog::Logger,
 }
@@ -107,6 +143,8 @@ pub enum PeerManagerEvent {
     Banned(PeerId, Vec<IpAddr>),
     /// The peer should be unbanned with the associated ip addresses.
     UnBanned(PeerId, Vec<IpAddr>),
+    /// Request the behaviour to dial a (trusted) peer we are not connected to.
+    DialPeer(PeerId),
     /// Request the behaviour to discover more peers and the amount of peers to discover.
     DiscoverPeers(usize),
     /// Request the behaviour to discover peers on subnets.
@@ -124,11 +162,45 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             discovery_enabled,
             metrics_enabled,
             target_peer_count,
+            trusted_peer_addresses: trusted_peer_address_list,
+            peer_allowlist,
+            outbound_only,
             status_interval,
             ping_interval_inbound,
             ping_interval_outbound,
+            max_client_share,
+            max_asn_share,
+            asn_database,
         } = cfg;
 
+        let asn_database = asn_database
+            .map(|path| {
+                let database = AsnDatabase::open(&path)?;
+                info!(log, "Loaded ASN database"; "path" => ?path, "ranges" => database.len());
+                Ok::<_, String>(database)
+            })
+            .transpose()?;
+        if max_asn_share.is_some() && asn_database.is_none() {
+            warn!(
+                log,
+                "ASN diversity target ignored, no ASN database configured"
+            );
+        }
+
+        let mut trusted_peer_addresses: HashMap<PeerId, Vec<Multiaddr>> = HashMap::new();
+        for mut address in trusted_peer_address_list {
+            match address.pop().map(|protocol| match protocol {
+                MProtocol::P2p(multihash) => PeerId::from_multihash(multihash).ok(),
+                _ => None,
+            }) {
+                Some(Some(peer_id)) => trusted_peer_addresses
+                    .entry(peer_id)
+                    .or_default()
+                    .push(address),
+                _ => return Err(format!("Trusted peer address has no peer id: {}", address).into()),
+            }
+        }
+
         // Set up the peer manager heartbeat interval
         let heartbeat = tokio::time::interval(tokio::time::Duration::from_secs(HEARTBEAT_INTERVAL));
 
@@ -139,10 +211,19 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             outbound_ping_peers: HashSetDelay::new(Duration::from_secs(ping_interval_outbound)),
             status_peers: HashSetDelay::new(Duration::from_secs(status_interval)),
             target_peers: target_peer_count,
+            trusted_peer_addresses,
+            trusted_peer_redials: HashSetDelay::new(Duration::from_secs(
+                TRUSTED_PEER_REDIAL_INTERVAL,
+            )),
+            peer_allowlist,
             sync_committee_subnets: Default::default(),
             heartbeat,
             discovery_enabled,
+            outbound_only,
             metrics_enabled,
+            max_client_share,
+            max_asn_share,
+            asn_database,
             log: log.clone(),
         })
     }
@@ -286,6 +367,7 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             if (min_ttl.is_some()
                 && connected_or_dialing + to_dial_peers.len() < self.max_priority_peers()
                 || connected_or_dialing + to_dial_peers.len() < self.max_peers())
+                && self.is_peer_allowed(&peer_id)
                 && self.network_globals.peers.read().should_dial(&peer_id)
             {
                 // This should be updated with the peer dialing. In fact created once the peer is
@@ -306,6 +388,89 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         to_dial_peers
     }
 
+    /// Returns `true` if we may connect to `peer_id`, i.e. there is no peer allowlist or the peer
+    /// is on it.
+    pub fn is_peer_allowed(&self, peer_id: &PeerId) -> bool {
+        self.peer_allowlist
+            .as_ref()
+            .map_or(true, |allowlist| allowlist.contains(peer_id))
+    }
+
+    /// Returns `true` if we already have as many peers from the autonomous system of `enr` as our
+    /// diversity target allows, in which case it should not be dialed.
+    pub fn is_asn_saturated(&self, enr: &Enr) -> bool {
+        let max_peers = match self.max_peers_per_asn() {
+            Some(max_peers) => max_peers,
+            None => return false,
+        };
+        let asn = match enr
+            .ip()
+            .map(IpAddr::V4)
+            .or_else(|| enr.ip6().map(IpAddr::V6))
+            .and_then(|ip| self.lookup_asn(ip))
+        {
+            Some(asn) => asn,
+            None => return false,
+        };
+        self.network_globals
+            .peers
+            .read()
+            .connected_peers()
+            .filter(|(_, info)| info.asn() == Some(asn))
+            .count()
+            >= max_peers
+    }
+
+    /// Updates the ENR of a connected peer if `enr` is newer than the one it connected with,
+    /// returning `true` if the ENR changed.
+    pub fn update_connected_enr(&mut self, peer_id: &PeerId, enr: Enr) -> bool {
+        self.network_globals
+            .peers
+            .write()
+            .update_connected_enr(peer_id, enr)
+    }
+
+    /// Returns the peer reputations to persist across a restart.
+    pub fn persisted_peers(&self) -> PersistedPeers {
+        self.network_globals
+            .peers
+            .read()
+            .persisted_peers(unix_time_secs())
+    }
+
+    /// Restores the peer reputations persisted before the last shutdown. Peers whose ban has not
+    /// yet expired are banned again and previously connected peers in good standing are redialed.
+    ///
+    /// Returns the ENRs of the peers being redialed, which must be known to discovery in order to
+    /// dial them.
+    pub fn restore_peers(&mut self, persisted: &PersistedPeers) -> Vec<Enr> {
+        let offline = Duration::from_secs(unix_time_secs().saturating_sub(persisted.persisted_at));
+        let restored = self
+            .network_globals
+            .peers
+            .write()
+            .restore_peers(persisted, offline);
+
+        let banned = restored.banned.len();
+        for peer_id in restored.banned {
+            self.events
+                .push(PeerManagerEvent::Banned(peer_id, Vec::new()));
+        }
+
+        let to_dial: Vec<Enr> = restored
+            .reconnect
+            .into_iter()
+            .filter(|enr| self.is_peer_allowed(&enr.peer_id()))
+            .take(self.max_peers())
+            .collect();
+        for enr in &to_dial {
+            self.events.push(PeerManagerEvent::DialPeer(enr.peer_id()));
+        }
+
+        info!(self.log, "Restored persisted peers"; "offline_secs" => offline.as_secs(), "banned" => banned, "reconnecting" => to_dial.len());
+        to_dial
+    }
+
     /// A STATUS message has been received from a peer. This resets the status timer.
     pub fn peer_statusd(&mut self, peer_id: &PeerId) {
         self.status_peers.insert(*peer_id);
@@ -468,7 +633,10 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             }
             RPCError::ErrorResponse(code, _) => match code {
                 RPCResponseErrorCode::Unknown => PeerAction::HighToleranceError,
-                RPCResponseErrorCode::ResourceUnavailable => {
+                RPCResponseErrorCode::ResourceUnavailable => match protocol {
+                    // Blobs are pruned before their blocks, and peers may disagree on the edge
+                    // of the blob retention window.
+                    Protocol::BlobsByRange | Protocol::BlobsByRoot => PeerAction::MidToleranceError,
                     // NOTE: This error only makes sense for the `BlocksByRange` and `BlocksByRoot`
                     // protocols.
                     //
@@ -481,14 +649,16 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
                     // implement a new sync type which tracks these peers and prevents the sync
                     // algorithms from requesting blocks from them (at least for a set period of
                     // time, multiple failures would then lead to a ban).
-                    PeerAction::Fatal
-                }
+                    _ => PeerAction::Fatal,
+                },
                 RPCResponseErrorCode::ServerError => PeerAction::MidToleranceError,
                 RPCResponseErrorCode::InvalidRequest => PeerAction::LowToleranceError,
                 RPCResponseErrorCode::RateLimited => match protocol {
                     Protocol::Ping => PeerAction::MidToleranceError,
                     Protocol::BlocksByRange => PeerAction::MidToleranceError,
                     Protocol::BlocksByRoot => PeerAction::MidToleranceError,
+                    Protocol::BlobsByRange => PeerAction::MidToleranceError,
+                    Protocol::BlobsByRoot => PeerAction::MidToleranceError,
                     Protocol::Goodbye => PeerAction::LowToleranceError,
                     Protocol::MetaData => PeerAction::LowToleranceError,
                     Protocol::Status => PeerAction::LowToleranceError,
@@ -504,6 +674,8 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
                     Protocol::Ping => PeerAction::Fatal,
                     Protocol::BlocksByRange => return,
                     Protocol::BlocksByRoot => return,
+                    Protocol::BlobsByRange => return,
+                    Protocol::BlobsByRoot => return,
                     Protocol::Goodbye => return,
                     Protocol::MetaData => PeerAction::LowToleranceError,
                     Protocol::Status => PeerAction::LowToleranceError,
@@ -519,6 +691,8 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
                     Protocol::Ping => PeerAction::LowToleranceError,
                     Protocol::BlocksByRange => PeerAction::MidToleranceError,
                     Protocol::BlocksByRoot => PeerAction::MidToleranceError,
+                    Protocol::BlobsByRange => PeerAction::MidToleranceError,
+                    Protocol::BlobsByRoot => PeerAction::MidToleranceError,
                     Protocol::Goodbye => return,
                     Protocol::MetaData => return,
                     Protocol::Status => return,
@@ -678,6 +852,74 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
 
     /* Internal functions */
 
+    /// The most peers we keep from a single client, if limited.
+    fn max_peers_per_client(&self) -> Option<usize> {
+        self.max_client_share
+            .map(|share| share_of_peers(share, self.target_peers))
+    }
+
+    /// The most peers we keep or dial from a single autonomous system, if limited.
+    fn max_peers_per_asn(&self) -> Option<usize> {
+        self.asn_database.as_ref()?;
+        self.max_asn_share
+            .map(|share| share_of_peers(share, self.target_peers))
+    }
+
+    /// Returns the autonomous system announcing `ip`, if an ASN database is configured.
+    fn lookup_asn(&self, ip: IpAddr) -> Option<u32> {
+        self.asn_database.as_ref()?.lookup(ip)
+    }
+
+    /// Queues a redial of any trusted peer we are not connected to, in case it was never dialed or
+    /// its redials have lapsed.
+    fn maintain_trusted_peers(&mut self) {
+        let disconnected_trusted_peers = self
+            .network_globals
+            .peers
+            .read()
+            .peers()
+            .filter(|(_, info)| {
+                info.is_trusted() && !info.is_connected_or_dialing() && !info.is_banned()
+            })
+            .map(|(peer_id, _)| *peer_id)
+            .collect::<Vec<_>>();
+        for peer_id in disconnected_trusted_peers {
+            if !self.trusted_peer_redials.contains(&peer_id) {
+                self.trusted_peer_redials.insert(peer_id);
+            }
+        }
+    }
+
+    /// Returns the known addresses of a trusted peer.
+    pub(crate) fn trusted_peer_addresses(&self, peer_id: &PeerId) -> Vec<Multiaddr> {
+        self.trusted_peer_addresses
+            .get(peer_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Updates the distribution of connected peers across clients and autonomous systems reported
+    /// by the HTTP API.
+    fn update_peer_diversity(&self) {
+        let mut diversity = PeerDiversity {
+            max_peers_per_client: self.max_peers_per_client(),
+            max_peers_per_asn: self.max_peers_per_asn(),
+            ..Default::default()
+        };
+        for (_, info) in self.network_globals.peers.read().connected_peers() {
+            diversity.connected_peers += 1;
+            *diversity
+                .clients
+                .entry(info.client().kind.to_string())
+                .or_default() += 1;
+            match info.asn() {
+                Some(asn) => *diversity.asns.entry(asn).or_default() += 1,
+                None => diversity.unknown_asn += 1,
+            }
+        }
+        *self.network_globals.peer_diversity.write() = diversity;
+    }
+
     /// Sets a peer as connected as long as their reputation allows it
     /// Informs if the peer was accepted
     fn inject_connect_ingoing(
@@ -723,6 +965,17 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         self.inbound_ping_peers.remove(peer_id);
         self.outbound_ping_peers.remove(peer_id);
         self.status_peers.remove(peer_id);
+
+        // Trusted peers are redialed as soon as they disconnect or a dial to them fails.
+        if self
+            .network_globals
+            .peers
+            .read()
+            .peer_info(peer_id)
+            .map_or(false, |info| info.is_trusted())
+        {
+            self.trusted_peer_redials.insert(*peer_id);
+        }
         self.events.extend(
             purged_peers
                 .into_iter()
@@ -742,6 +995,18 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         connection: ConnectingType,
         enr: Option<Enr>,
     ) -> bool {
+        let asn = match &connection {
+            ConnectingType::Dialing => None,
+            ConnectingType::IngoingConnected { multiaddr }
+            | ConnectingType::OutgoingConnected { multiaddr } => {
+                multiaddr.iter().find_map(|protocol| match protocol {
+                    MProtocol::Ip4(ip) => self.lookup_asn(ip.into()),
+                    MProtocol::Ip6(ip) => self.lookup_asn(ip.into()),
+                    _ => None,
+                })
+            }
+        };
+
         {
             let mut peerdb = self.network_globals.peers.write();
             if !matches!(peerdb.ban_status(peer_id), BanResult::NotBanned) {
@@ -765,6 +1030,10 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
                     self.outbound_ping_peers.insert(*peer_id);
                 }
             }
+
+            if let Some(info) = peerdb.peer_info_mut(peer_id) {
+                info.set_asn(asn);
+            }
         }
 
         // start a ping and status timer for the peer
@@ -836,10 +1105,17 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         if self.discovery_enabled {
             let peer_count = self.network_globals.connected_or_dialing_peers();
             let outbound_only_peer_count = self.network_globals.connected_outbound_only_peers();
-            let wanted_peers = if peer_count < self.target_peers.saturating_sub(dialing_peers) {
+            // Peers never dial us if inbound connections are disabled, so we aim for our maximum
+            // peers to make up for dials that fail and peers that disconnect between heartbeats.
+            let target_peers = if self.outbound_only {
+                self.max_peers()
+            } else {
+                self.target_peers
+            };
+            let wanted_peers = if peer_count < target_peers.saturating_sub(dialing_peers) {
                 // We need more peers in general.
                 // Note: The maximum discovery query is bounded by `Discovery`.
-                self.target_peers.saturating_sub(dialing_peers) - peer_count
+                target_peers.saturating_sub(dialing_peers) - peer_count
             } else if outbound_only_peer_count < self.min_outbound_only_peers()
                 && peer_count < self.max_outbound_dialing_peers()
             {
@@ -852,7 +1128,7 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
 
             if wanted_peers != 0 {
                 // We need more peers, re-queue a discovery lookup.
-                debug!(self.log, "Starting a new peer discovery query"; "connected" => peer_count, "target" => self.target_peers, "outbound" => outbound_only_peer_count, "wanted" => wanted_peers);
+                debug!(self.log, "Starting a new peer discovery query"; "connected" => peer_count, "target" => target_peers, "outbound" => outbound_only_peer_count, "wanted" => wanted_peers);
                 self.events
                     .push(PeerManagerEvent::DiscoverPeers(wanted_peers));
             }
@@ -884,11 +1160,14 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
     ///     number should be set low as an absolute lower bound to maintain peers on the sync
     ///     committees.
     ///
+    /// Trusted peers are never pruned.
+    ///
     /// Prune peers in the following order:
     /// 1. Remove worst scoring peers
-    /// 2. Remove peers that are not subscribed to a subnet (they have less value)
-    /// 3. Remove peers that we have many on any particular subnet
-    /// 4. Randomly remove peers if all the above are satisfied
+    /// 2. Remove peers of clients or autonomous systems that exceed their diversity target
+    /// 3. Remove peers that are not subscribed to a subnet (they have less value)
+    /// 4. Remove peers that we have many on any particular subnet
+    /// 5. Randomly remove peers if all the above are satisfied
     ///
     fn prune_excess_peers(&mut self) {
         // The current number of connected peers.
@@ -913,7 +1192,9 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
                     .read()
                     .worst_connected_peers()
                     .iter()
-                    .filter(|(_, info)| !info.has_future_duty() && $filter(*info))
+                    .filter(|(_, info)| {
+                        !info.has_future_duty() && !info.is_trusted() && $filter(*info)
+                    })
                 {
                     if peers_to_prune.len()
                         >= connected_peer_count.saturating_sub(self.target_peers)
@@ -942,13 +1223,73 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         // 1. Look through peers that have the worst score (ignoring non-penalized scored peers).
         prune_peers!(|info: &PeerInfo<TSpec>| { info.score().score() < 0.0 });
 
-        // 2. Attempt to remove peers that are not subscribed to a subnet, if we still need to
+        // 2. Remove the worst peers of any client or autonomous system that is over-represented,
+        //    so that a single implementation or network operator cannot dominate our peers.
+        let max_peers_per_client = self.max_peers_per_client();
+        let max_peers_per_asn = self.max_peers_per_asn();
+        if (max_peers_per_client.is_some() || max_peers_per_asn.is_some())
+            && peers_to_prune.len() < connected_peer_count.saturating_sub(self.target_peers)
+        {
+            let peerdb = self.network_globals.peers.read();
+            let mut peers_per_client: HashMap<String, usize> = HashMap::new();
+            let mut peers_per_asn: HashMap<u32, usize> = HashMap::new();
+            for (peer_id, info) in peerdb.connected_peers() {
+                if peers_to_prune.contains(peer_id) {
+                    continue;
+                }
+                *peers_per_client
+                    .entry(info.client().kind.to_string())
+                    .or_default() += 1;
+                if let Some(asn) = info.asn() {
+                    *peers_per_asn.entry(asn).or_default() += 1;
+                }
+            }
+
+            for (peer_id, info) in peerdb.worst_connected_peers() {
+                if peers_to_prune.len() >= connected_peer_count.saturating_sub(self.target_peers) {
+                    break;
+                }
+                if info.has_future_duty() || info.is_trusted() || peers_to_prune.contains(peer_id) {
+                    continue;
+                }
+                let client = info.client().kind.to_string();
+                let client_count = peers_per_client.get(&client).copied().unwrap_or(0);
+                let asn_count = info
+                    .asn()
+                    .and_then(|asn| peers_per_asn.get(&asn).copied())
+                    .unwrap_or(0);
+                let over_represented = max_peers_per_client.map_or(false, |max| client_count > max)
+                    || max_peers_per_asn.map_or(false, |max| asn_count > max);
+                if !over_represented {
+                    continue;
+                }
+                // Only remove up to the target outbound peer count.
+                if info.is_outbound_only() {
+                    if self.target_outbound_peers() + outbound_peers_pruned
+                        < connected_outbound_peer_count
+                    {
+                        outbound_peers_pruned += 1;
+                    } else {
+                        continue;
+                    }
+                }
+                if let Some(count) = peers_per_client.get_mut(&client) {
+                    *count -= 1;
+                }
+                if let Some(count) = info.asn().and_then(|asn| peers_per_asn.get_mut(&asn)) {
+                    *count -= 1;
+                }
+                peers_to_prune.insert(*peer_id);
+            }
+        }
+
+        // 3. Attempt to remove peers that are not subscribed to a subnet, if we still need to
         //    prune more.
         if peers_to_prune.len() < connected_peer_count.saturating_sub(self.target_peers) {
             prune_peers!(|info: &PeerInfo<TSpec>| { !info.has_long_lived_subnet() });
         }
 
-        // 3. and 4. Remove peers that are too grouped on any given subnet. If all subnets are
+        // 4. and 5. Remove peers that are too grouped on any given subnet. If all subnets are
         //    uniformly distributed, remove random peers.
         if peers_to_prune.len() < connected_peer_count.saturating_sub(self.target_peers) {
             // Of our connected peers, build a map from subnet_id -> Vec<(PeerId, PeerInfo)>
@@ -963,8 +1304,8 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             > = HashMap::new();
 
             for (peer_id, info) in self.network_globals.peers.read().connected_peers() {
-                // Ignore peers we are already pruning
-                if peers_to_prune.contains(peer_id) {
+                // Ignore peers we are already pruning and trusted peers, which are never pruned.
+                if peers_to_prune.contains(peer_id) || info.is_trusted() {
                     continue;
                 }
 
@@ -1123,9 +1464,36 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         // Maintain minimum count for sync committee peers.
         self.maintain_sync_committee_peers();
 
+        // Reconnect to any trusted peers we have lost.
+        self.maintain_trusted_peers();
+
         // Prune any excess peers back to our target in such a way that incentivises good scores and
         // a uniform distribution of subnets.
         self.prune_excess_peers();
+
+        // Update the client and ASN distribution of our remaining peers.
+        self.update_peer_diversity();
+
+        // Check our clock against the arrival times of gossip messages.
+        self.check_clock_skew();
+    }
+
+    /// Reports the estimated offset of our clock, warning if it appears to be skewed.
+    fn check_clock_skew(&self) {
+        let status = self.network_globals.clock_skew_samples.read().status();
+        metrics::set_gauge(
+            &metrics::CLOCK_SKEW_SAMPLE_PEERS,
+            status.sample_peers as i64,
+        );
+        if let Some(offset_ms) = status.estimated_offset_ms {
+            metrics::set_gauge(&metrics::CLOCK_SKEW_ESTIMATE, offset_ms);
+            if status.skew_suspected {
+                warn!(self.log, "System clock may be skewed";
+                    "estimated_offset_ms" => offset_ms,
+                    "sample_peers" => status.sample_peers,
+                    "info" => "a positive offset means the clock is ahead, check NTP is running");
+            }
+        }
     }
 
     // Update metrics related to peer scoring.
@@ -1453,6 +1821,213 @@ mod tests {
         assert_eq!(peer_manager.network_globals.connected_or_dialing_peers(), 3);
     }
 
+    #[tokio::test]
+    /// Test that we discover peers up to our maximum when inbound connections are disabled.
+    async fn test_peer_manager_outbound_only_discovers_max_peers() {
+        for (outbound_only, wanted_peers) in [(false, 10), (true, 11)] {
+            let config = config::Config {
+                target_peer_count: 10,
+                outbound_only,
+                ..Default::default()
+            };
+            let log = build_log(slog::Level::Debug, false);
+            let globals = NetworkGlobals::new_test_globals(&log);
+            let mut peer_manager = PeerManager::new(config, Arc::new(globals), &log)
+                .await
+                .unwrap();
+
+            peer_manager.maintain_peer_count(0);
+            assert!(matches!(
+                peer_manager.events.as_slice(),
+                [PeerManagerEvent::DiscoverPeers(wanted)] if *wanted == wanted_peers
+            ));
+        }
+    }
+
+    #[tokio::test]
+    /// Test that only allowlisted peers are dialed from discovery results.
+    async fn test_peer_manager_allowlist_filters_discovered_peers() {
+        let allowed_peer = PeerId::random();
+        let config = config::Config {
+            target_peer_count: 5,
+            discovery_enabled: false,
+            peer_allowlist: Some(std::iter::once(allowed_peer).collect()),
+            ..Default::default()
+        };
+        let log = build_log(slog::Level::Debug, false);
+        let globals = NetworkGlobals::new_test_globals(&log);
+        let mut peer_manager = PeerManager::new(config, Arc::new(globals), &log)
+            .await
+            .unwrap();
+
+        let other_peer = PeerId::random();
+        assert!(peer_manager.is_peer_allowed(&allowed_peer));
+        assert!(!peer_manager.is_peer_allowed(&other_peer));
+
+        let discovered = vec![(allowed_peer, None), (other_peer, None)]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            peer_manager.peers_discovered(discovered),
+            vec![allowed_peer]
+        );
+    }
+
+    #[tokio::test]
+    /// Test that trusted peers are never pruned, and are queued for a redial when they disconnect.
+    async fn test_peer_manager_trusted_peers_not_pruned() {
+        let trusted_peer = PeerId::random();
+        let config = config::Config {
+            target_peer_count: 2,
+            discovery_enabled: false,
+            ..Default::default()
+        };
+        let log = build_log(slog::Level::Debug, false);
+        let globals = NetworkGlobals::new_test_globals_with_trusted_peers(vec![trusted_peer], &log);
+        let mut peer_manager = PeerManager::new(config, Arc::new(globals), &log)
+            .await
+            .unwrap();
+
+        // Two regular peers on long-lived subnets, and a trusted peer on none, which would
+        // otherwise be the first to be pruned.
+        let peer0 = PeerId::random();
+        let peer1 = PeerId::random();
+        peer_manager.inject_connect_ingoing(&trusted_peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+        for (peer, subnet) in [(peer0, 1), (peer1, 2)] {
+            peer_manager.inject_connect_ingoing(&peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+            let mut attnets = crate::types::EnrAttestationBitfield::<E>::new();
+            attnets.set(subnet, true).unwrap();
+            let metadata = crate::rpc::MetaDataV2 {
+                seq_number: 0,
+                attnets,
+                syncnets: Default::default(),
+            };
+            peer_manager
+                .network_globals
+                .peers
+                .write()
+                .peer_info_mut(&peer)
+                .unwrap()
+                .set_meta_data(MetaData::V2(metadata));
+            peer_manager
+                .network_globals
+                .peers
+                .write()
+                .add_subscription(&peer, Subnet::Attestation((subnet as u64).into()));
+        }
+
+        peer_manager.heartbeat();
+
+        assert_eq!(peer_manager.network_globals.connected_or_dialing_peers(), 2);
+        assert!(peer_manager
+            .network_globals
+            .peers
+            .read()
+            .is_connected(&trusted_peer));
+
+        // Losing the trusted peer queues it to be redialed.
+        peer_manager.inject_disconnect(&trusted_peer);
+        assert!(peer_manager.trusted_peer_redials.contains(&trusted_peer));
+    }
+
+    #[tokio::test]
+    /// Test that peers of an over-represented client are pruned before others.
+    async fn test_peer_manager_prune_over_represented_client() {
+        let config = config::Config {
+            target_peer_count: 3,
+            discovery_enabled: false,
+            max_client_share: Some(0.5),
+            ..Default::default()
+        };
+        let log = build_log(slog::Level::Debug, false);
+        let globals = NetworkGlobals::new_test_globals(&log);
+        let mut peer_manager = PeerManager::new(config, Arc::new(globals), &log)
+            .await
+            .unwrap();
+
+        // Connect 3 lighthouse peers and 2 prysm peers, allowing at most 2 of each.
+        let mut lighthouse_peers = Vec::new();
+        let mut prysm_peers = Vec::new();
+        for i in 0..5 {
+            let peer = PeerId::random();
+            peer_manager.inject_connect_ingoing(&peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+            let kind = if i < 3 {
+                lighthouse_peers.push(peer);
+                ClientKind::Lighthouse
+            } else {
+                prysm_peers.push(peer);
+                ClientKind::Prysm
+            };
+            peer_manager
+                .network_globals
+                .peers
+                .write()
+                .peer_info_mut(&peer)
+                .unwrap()
+                .set_client(peerdb::client::Client {
+                    kind,
+                    ..Default::default()
+                });
+        }
+
+        peer_manager.heartbeat();
+
+        // One lighthouse peer was pruned for diversity, and one more of any client to reach our
+        // target.
+        assert_eq!(peer_manager.network_globals.connected_or_dialing_peers(), 3);
+        let connected_lighthouse = lighthouse_peers
+            .iter()
+            .filter(|peer| peer_manager.network_globals.peers.read().is_connected(peer))
+            .count();
+        assert!(connected_lighthouse <= 2);
+
+        let diversity = peer_manager.network_globals.peer_diversity.read().clone();
+        assert_eq!(diversity.max_peers_per_client, Some(2));
+        assert_eq!(diversity.max_peers_per_asn, None);
+    }
+
+    #[tokio::test]
+    /// Test that peers from an over-represented autonomous system are pruned before others, and
+    /// that the ASN is not considered saturated once back under target.
+    async fn test_peer_manager_prune_over_represented_asn() {
+        let mut peer_manager = build_peer_manager(2).await;
+        peer_manager.max_asn_share = Some(0.5);
+        peer_manager.asn_database = Some(AsnDatabase::default());
+
+        // Connect 3 peers from AS 64496 and one from AS 64497, allowing at most 1 from each.
+        let mut crowded_peers = Vec::new();
+        let other_peer = PeerId::random();
+        for i in 0..4 {
+            let (peer, asn) = if i < 3 {
+                let peer = PeerId::random();
+                crowded_peers.push(peer);
+                (peer, 64496)
+            } else {
+                (other_peer, 64497)
+            };
+            peer_manager.inject_connect_ingoing(&peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+            peer_manager
+                .network_globals
+                .peers
+                .write()
+                .peer_info_mut(&peer)
+                .unwrap()
+                .set_asn(Some(asn));
+        }
+
+        peer_manager.heartbeat();
+
+        assert_eq!(peer_manager.network_globals.connected_or_dialing_peers(), 2);
+        assert!(peer_manager
+            .network_globals
+            .peers
+            .read()
+            .is_connected(&other_peer));
+
+        let diversity = peer_manager.network_globals.peer_diversity.read().clone();
+        assert_eq!(diversity.max_peers_per_asn, Some(1));
+    }
+
     #[tokio::test]
     /// We want to test that the peer manager removes peers that are not subscribed to a subnet as
     /// a priority over all else.
@@ -75,6 +75,29 @@ impl<TSpec: EthSpec> NetworkBehaviour for PeerManager<TSpec> {
             }
         }
 
+        loop {
+            match self.trusted_peer_redials.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(peer_id))) => {
+                    let should_dial = self
+                        .network_globals
+                        .peers
+                        .read()
+                        .peer_info(&peer_id)
+                        .map_or(false, |info| {
+                            !info.is_connected_or_dialing() && !info.is_banned()
+                        });
+                    if should_dial {
+                        debug!(self.log, "Redialing trusted peer"; "peer_id" => %peer_id);
+                        self.events.push(PeerManagerEvent::DialPeer(peer_id));
+                    }
+                }
+                Poll::Ready(Some(Err(e))) => {
+                    error!(self.log, "Failed to check for trusted peers to redial"; "error" => e.to_string())
+                }
+                Poll::Ready(None) | Poll::Pending => break,
+            }
+        }
+
         if !matches!(
             self.network_globals.sync_state(),
             SyncState::SyncingFinalized { .. } | SyncState::SyncingHead { .. }
@@ -104,6 +127,10 @@ impl<TSpec: EthSpec> NetworkBehaviour for PeerManager<TSpec> {
 
     /* Overwritten trait members */
 
+    fn addresses_of_peer(&mut self, peer_id: &PeerId) -> Vec<Multiaddr> {
+        self.trusted_peer_addresses(peer_id)
+    }
+
     fn inject_connection_established(
         &mut self,
         peer_id: &PeerId,
@@ -142,6 +169,13 @@ impl<TSpec: EthSpec> NetworkBehaviour for PeerManager<TSpec> {
             BanResult::NotBanned => {}
         }
 
+        // In a private network, only allowlisted peers may connect.
+        if !self.is_peer_allowed(peer_id) {
+            debug!(self.log, "Disconnecting peer not on the allowlist"; "peer_id" => %peer_id);
+            self.disconnect_peer(*peer_id, GoodbyeReason::IrrelevantNetwork);
+            return;
+        }
+
         // Count dialing peers in the limit if the peer dialied us.
         let count_dialing = endpoint.is_listener();
         // Check the connection limits
@@ -151,7 +185,7 @@ impl<TSpec: EthSpec> NetworkBehaviour for PeerManager<TSpec> {
                 .peers
                 .read()
                 .peer_info(peer_id)
-                .map_or(true, |peer| !peer.has_future_duty())
+                .map_or(true, |peer| !peer.has_future_duty() && !peer.is_trusted())
         {
             // Gracefully disconnect the peer.
             self.disconnect_peer(*peer_id, GoodbyeReason::TooManyPeers);
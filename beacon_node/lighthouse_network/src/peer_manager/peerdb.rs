@@ -1,22 +1,26 @@
 use crate::{
+    discovery::enr_ext::EnrExt,
     metrics,
     multiaddr::{Multiaddr, Protocol},
     types::Subnet,
     Enr, Gossipsub, PeerId,
 };
 use peer_info::{ConnectionDirection, PeerConnectionStatus, PeerInfo};
+use persisted::{PersistedPeer, PersistedPeers, RestoredPeers};
 use rand::seq::SliceRandom;
 use score::{PeerAction, ReportSource, Score, ScoreState};
 use slog::{crit, debug, error, trace, warn};
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::net::{IpAddr, SocketAddr};
-use std::time::Instant;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 use sync_status::SyncStatus;
 use types::EthSpec;
 
 pub mod client;
 pub mod peer_info;
+pub mod persisted;
 pub mod score;
 pub mod sync_status;
 
@@ -92,6 +96,21 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
         self.peers.get_mut(peer_id)
     }
 
+    /// Replaces the ENR of a connected peer if `enr` has a higher sequence number than the ENR we
+    /// know, returning `true` if it was replaced.
+    pub(super) fn update_connected_enr(&mut self, peer_id: &PeerId, enr: Enr) -> bool {
+        match self.peers.get_mut(peer_id) {
+            Some(info)
+                if info.is_connected()
+                    && info.enr().map_or(true, |known| known.seq() < enr.seq()) =>
+            {
+                info.set_enr(enr);
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Returns if the peer is already connected.
     pub fn is_connected(&self, peer_id: &PeerId) -> bool {
         matches!(
@@ -323,8 +342,101 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
             .map(|info| info.connection_status().clone())
     }
 
+    /// Returns the reputations worth remembering across a restart, i.e. the peers we are connected
+    /// to and the peers with a negative score. Trusted peers are not persisted.
+    pub fn persisted_peers(&self, persisted_at: u64) -> PersistedPeers {
+        let now = Instant::now();
+        let peers = self
+            .peers
+            .iter()
+            .filter(|(_, info)| info.is_connected() || info.score().score() < 0.0)
+            .filter_map(|(peer_id, info)| {
+                let (lighthouse_score, gossipsub_score, ban_remaining) =
+                    info.score().to_persisted(now)?;
+                Some(PersistedPeer {
+                    peer_id: peer_id.to_bytes(),
+                    lighthouse_score: lighthouse_score.to_bits(),
+                    gossipsub_score: gossipsub_score.to_bits(),
+                    ban_remaining_secs: ban_remaining.as_secs(),
+                    connected: info.is_connected(),
+                    enr: info
+                        .enr()
+                        .map(|enr| enr.to_base64().into_bytes())
+                        .unwrap_or_default(),
+                })
+            })
+            .collect();
+        PersistedPeers {
+            persisted_at,
+            peers,
+        }
+    }
+
     /* Mutability */
 
+    /// Restores the reputations of peers persisted `offline` ago. Scores decay over the time spent
+    /// offline and peers already known to the database are left untouched.
+    // VISIBILITY: Only the peer manager can restore peers.
+    pub(super) fn restore_peers(
+        &mut self,
+        persisted: &PersistedPeers,
+        offline: Duration,
+    ) -> RestoredPeers {
+        let now = Instant::now();
+        let mut restored = RestoredPeers::default();
+        for peer in &persisted.peers {
+            let peer_id = match PeerId::from_bytes(&peer.peer_id) {
+                Ok(peer_id) => peer_id,
+                Err(_) => {
+                    warn!(self.log, "Invalid persisted peer id");
+                    continue;
+                }
+            };
+            if self.peers.contains_key(&peer_id) {
+                continue;
+            }
+            let enr = std::str::from_utf8(&peer.enr)
+                .ok()
+                .filter(|enr| !enr.is_empty())
+                .and_then(|enr| Enr::from_str(enr).ok())
+                .filter(|enr| enr.peer_id() == peer_id);
+            let score = Score::from_persisted(
+                f64::from_bits(peer.lighthouse_score),
+                f64::from_bits(peer.gossipsub_score),
+                Duration::from_secs(peer.ban_remaining_secs),
+                offline,
+                now,
+            );
+
+            let connection_status = match score.state() {
+                ScoreState::Banned => {
+                    if self.banned_peers_count.banned_peers() >= MAX_BANNED_PEERS {
+                        continue;
+                    }
+                    self.banned_peers_count.add_banned_peer(std::iter::empty());
+                    restored.banned.push(peer_id);
+                    PeerConnectionStatus::Banned { since: now }
+                }
+                state => {
+                    if self.disconnected_peers >= MAX_DC_PEERS {
+                        continue;
+                    }
+                    self.disconnected_peers += 1;
+                    if peer.connected && state == ScoreState::Healthy {
+                        restored.reconnect.extend(enr.clone());
+                    }
+                    PeerConnectionStatus::Disconnected { since: now }
+                }
+            };
+            debug!(self.log, "Restored persisted peer"; "peer_id" => %peer_id, "score" => %score);
+            self.peers.insert(
+                peer_id,
+                PeerInfo::restored_peer_info(score, enr, connection_status),
+            );
+        }
+        restored
+    }
+
     /// Cleans up the connection state of dialing peers.
     // Libp2p dial's peerids, but sometimes the response is from another peer-id or libp2p
     // returns dial errors without a peer-id attached. This function reverts peers that have a
@@ -1438,6 +1550,39 @@ mod tests {
         assert_eq!(pdb.disconnected_peers, MAX_DC_PEERS);
     }
 
+    #[test]
+    fn test_persisted_peers_are_restored() {
+        let mut pdb = get_db();
+        let connected = PeerId::random();
+        let banned = PeerId::random();
+        let disconnected = PeerId::random();
+        for p in [connected, banned, disconnected] {
+            pdb.connect_ingoing(&p, "/ip4/0.0.0.0".parse().unwrap(), None);
+        }
+        let _ = pdb.report_peer(&banned, PeerAction::Fatal, ReportSource::PeerManager, "");
+        pdb.inject_disconnect(&banned);
+        pdb.inject_disconnect(&disconnected);
+
+        // Only the connected and badly scored peers are persisted.
+        let persisted = pdb.persisted_peers(0);
+        assert_eq!(persisted.peers.len(), 2);
+
+        let mut restored_db = get_db();
+        let restored = restored_db.restore_peers(&persisted, Duration::from_secs(60));
+        assert_eq!(restored.banned, vec![banned]);
+        assert!(restored_db.peer_info(&banned).unwrap().is_banned());
+        assert!(restored_db.peer_info(&connected).unwrap().is_disconnected());
+        assert!(restored_db.peer_info(&disconnected).is_none());
+        assert_eq!(restored_db.banned_peers_count.banned_peers(), 1);
+        assert_eq!(restored_db.disconnected_peers, 1);
+
+        // Once the ban has expired the peer is no longer banned.
+        let mut restored_db = get_db();
+        let restored = restored_db.restore_peers(&persisted, Duration::from_secs(24 * 3600));
+        assert!(restored.banned.is_empty());
+        assert!(!restored_db.peer_info(&banned).unwrap().is_banned());
+    }
+
     #[test]
     fn test_banned_are_bounded() {
         let mut pdb = get_db();
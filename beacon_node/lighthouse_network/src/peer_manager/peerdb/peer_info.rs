@@ -1,5 +1,5 @@
 use super::client::Client;
-use super::score::{PeerAction, Score, ScoreState};
+use super::score::{PeerAction, PeerActionCounts, Score, ScoreState};
 use super::sync_status::SyncStatus;
 use crate::discovery::Eth2Enr;
 use crate::Multiaddr;
@@ -32,6 +32,9 @@ pub struct PeerInfo<T: EthSpec> {
     /// This is addresses we have physically seen and this is what we use for banning/un-banning
     /// peers.
     seen_addresses: HashSet<SocketAddr>,
+    /// The autonomous system the peer connected from, if an ASN database is configured and the
+    /// address was found in it.
+    asn: Option<u32>,
     /// The current syncing state of the peer. The state may be determined after it's initial
     /// connection.
     sync_status: SyncStatus,
@@ -51,6 +54,8 @@ pub struct PeerInfo<T: EthSpec> {
     connection_direction: Option<ConnectionDirection>,
     /// The enr of the peer, if known.
     enr: Option<Enr>,
+    /// The number of times each kind of `PeerAction` has been reported against this peer.
+    peer_actions: PeerActionCounts,
 }
 
 impl<TSpec: EthSpec> Default for PeerInfo<TSpec> {
@@ -61,6 +66,7 @@ impl<TSpec: EthSpec> Default for PeerInfo<TSpec> {
             connection_status: Default::default(),
             listening_addresses: Vec::new(),
             seen_addresses: HashSet::new(),
+            asn: None,
             subnets: HashSet::new(),
             sync_status: SyncStatus::Unknown,
             meta_data: None,
@@ -68,6 +74,7 @@ impl<TSpec: EthSpec> Default for PeerInfo<TSpec> {
             is_trusted: false,
             connection_direction: None,
             enr: None,
+            peer_actions: PeerActionCounts::default(),
         }
     }
 }
@@ -82,6 +89,20 @@ impl<T: EthSpec> PeerInfo<T> {
         }
     }
 
+    /// Return a PeerInfo struct for a peer whose reputation was restored from the database.
+    pub(super) fn restored_peer_info(
+        score: Score,
+        enr: Option<Enr>,
+        connection_status: PeerConnectionStatus,
+    ) -> Self {
+        PeerInfo {
+            score,
+            enr,
+            connection_status,
+            ..Default::default()
+        }
+    }
+
     /// Returns if the peer is subscribed to a given `Subnet` from the metadata attnets/syncnets field.
     pub fn on_subnet_metadata(&self, subnet: &Subnet) -> bool {
         if let Some(meta_data) = &self.meta_data {
@@ -109,6 +130,11 @@ impl<T: EthSpec> PeerInfo<T> {
         &self.listening_addresses
     }
 
+    /// Returns the autonomous system the peer connected from, if known.
+    pub fn asn(&self) -> Option<u32> {
+        self.asn
+    }
+
     /// Returns the connection direction for the peer.
     pub fn connection_direction(&self) -> Option<&ConnectionDirection> {
         self.connection_direction.as_ref()
@@ -253,6 +279,11 @@ impl<T: EthSpec> PeerInfo<T> {
         &self.score
     }
 
+    /// Returns the number of times each kind of `PeerAction` has been reported against the peer.
+    pub fn peer_actions(&self) -> &PeerActionCounts {
+        &self.peer_actions
+    }
+
     /// Returns the state of the peer based on the score.
     pub(crate) fn score_state(&self) -> ScoreState {
         self.score.state()
@@ -326,6 +357,12 @@ impl<T: EthSpec> PeerInfo<T> {
         self.client = client
     }
 
+    /// Sets the autonomous system the peer connected from.
+    // VISIBILITY: The peer manager is able to set the ASN
+    pub(in crate::peer_manager) fn set_asn(&mut self, asn: Option<u32>) {
+        self.asn = asn
+    }
+
     /// Replaces the current listening addresses with those specified, returning the current
     /// listening addresses.
     // VISIBILITY: The peer manager is able to set the listening addresses
@@ -382,14 +419,18 @@ impl<T: EthSpec> PeerInfo<T> {
     /// Apply peer action to a non-trusted peer's score.
     // VISIBILITY: The peer manager is able to modify the score of a peer.
     pub(in crate::peer_manager) fn apply_peer_action_to_score(&mut self, peer_action: PeerAction) {
+        self.peer_actions.record(peer_action);
         if !self.is_trusted {
             self.score.apply_peer_action(peer_action)
         }
     }
 
-    /// Updates the gossipsub score with a new score. Optionally ignore the gossipsub score.
+    /// Updates the gossipsub score of a non-trusted peer with a new score. Optionally ignore the
+    /// gossipsub score.
     pub(super) fn update_gossipsub_score(&mut self, new_score: f64, ignore: bool) {
-        self.score.update_gossipsub_score(new_score, ignore);
+        if !self.is_trusted {
+            self.score.update_gossipsub_score(new_score, ignore);
+        }
     }
 
     #[cfg(test)]
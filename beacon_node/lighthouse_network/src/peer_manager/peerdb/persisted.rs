@@ -0,0 +1,41 @@
+//! The reputation of peers, saved when the node shuts down and restored when it starts up again.
+//!
+//! This prevents a restarted node from immediately reconnecting to peers it had banned, and allows
+//! it to redial the peers it was connected to.
+
+use crate::{Enr, PeerId};
+use ssz_derive::{Decode, Encode};
+
+/// The persisted reputation of a single peer.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct PersistedPeer {
+    /// The byte encoding of the peer id.
+    pub peer_id: Vec<u8>,
+    /// The bits of the `f64` score derived from lighthouse's reports of the peer.
+    pub lighthouse_score: u64,
+    /// The bits of the `f64` score last reported by gossipsub.
+    pub gossipsub_score: u64,
+    /// The number of seconds remaining before the score of a banned peer starts to decay.
+    pub ban_remaining_secs: u64,
+    /// Whether we were connected to the peer.
+    pub connected: bool,
+    /// The text encoding of the peer's ENR, or empty if it isn't known.
+    pub enr: Vec<u8>,
+}
+
+/// The persisted reputations of the peers worth remembering across a restart.
+#[derive(Debug, Clone, PartialEq, Default, Encode, Decode)]
+pub struct PersistedPeers {
+    /// The unix time in seconds at which the peers were persisted.
+    pub persisted_at: u64,
+    pub peers: Vec<PersistedPeer>,
+}
+
+/// The outcome of restoring persisted peers into the peer database.
+#[derive(Debug, Default)]
+pub struct RestoredPeers {
+    /// The peers whose ban has not yet expired.
+    pub banned: Vec<PeerId>,
+    /// The previously connected peers in good standing that may be redialed.
+    pub reconnect: Vec<Enr>,
+}
@@ -65,6 +65,28 @@ pub enum PeerAction {
     HighToleranceError,
 }
 
+/// The number of times each kind of `PeerAction` has been reported against a peer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct PeerActionCounts {
+    pub fatal: u64,
+    pub low_tolerance_error: u64,
+    pub mid_tolerance_error: u64,
+    pub high_tolerance_error: u64,
+}
+
+impl PeerActionCounts {
+    /// Increment the count for `peer_action`.
+    pub fn record(&mut self, peer_action: PeerAction) {
+        let count = match peer_action {
+            PeerAction::Fatal => &mut self.fatal,
+            PeerAction::LowToleranceError => &mut self.low_tolerance_error,
+            PeerAction::MidToleranceError => &mut self.mid_tolerance_error,
+            PeerAction::HighToleranceError => &mut self.high_tolerance_error,
+        };
+        *count = count.saturating_add(1);
+    }
+}
+
 /// Service reporting a `PeerAction` for a peer.
 #[derive(Debug)]
 pub enum ReportSource {
@@ -169,6 +191,14 @@ impl RealScore {
         self.score
     }
 
+    fn lighthouse_score(&self) -> f64 {
+        self.lighthouse_score
+    }
+
+    fn gossipsub_score(&self) -> f64 {
+        self.gossipsub_score
+    }
+
     /// Modifies the score based on a peer's action.
     pub fn apply_peer_action(&mut self, peer_action: PeerAction) {
         match peer_action {
@@ -265,6 +295,33 @@ impl RealScore {
     pub fn is_good_gossipsub_peer(&self) -> bool {
         self.gossipsub_score >= 0.0
     }
+
+    /// Restores a score saved `offline` ago by `Score::to_persisted`, as if it had continued to
+    /// decay while the node was not running.
+    fn from_persisted(
+        lighthouse_score: f64,
+        gossipsub_score: f64,
+        ban_remaining: Duration,
+        offline: Duration,
+        now: Instant,
+    ) -> Self {
+        let mut score = RealScore {
+            lighthouse_score,
+            last_updated: now,
+            ..Default::default()
+        };
+        if let Some(ban_remaining) = ban_remaining.checked_sub(offline) {
+            // The peer is still serving its ban, keep the score that caused it.
+            score.gossipsub_score = gossipsub_score;
+            score.last_updated = now + ban_remaining;
+        } else {
+            // The gossipsub score of the previous session no longer applies once decaying.
+            let decay_secs = offline.saturating_sub(ban_remaining).as_secs();
+            score.lighthouse_score *= (*HALFLIFE_DECAY * decay_secs as f64).exp();
+        }
+        score.recompute_score();
+        score
+    }
 }
 
 #[derive(PartialEq, Clone, Debug, Serialize)]
@@ -311,10 +368,57 @@ impl Score {
         }
     }
 
+    /// The component of the score which is derived from Lighthouse's own reports of the peer's
+    /// behaviour (RPC errors, invalid messages, etc).
+    pub fn lighthouse_score(&self) -> f64 {
+        match self {
+            Self::Max => f64::INFINITY,
+            Self::Real(score) => score.lighthouse_score(),
+        }
+    }
+
+    /// The most recent score reported by gossipsub.
+    pub fn gossipsub_score(&self) -> f64 {
+        match self {
+            Self::Max => f64::INFINITY,
+            Self::Real(score) => score.gossipsub_score(),
+        }
+    }
+
     pub fn max_score() -> Self {
         Self::Max
     }
 
+    /// Returns the lighthouse and gossipsub scores and the time remaining before a banned peer's
+    /// score starts to decay, or `None` for the maximum score of a trusted peer.
+    pub(crate) fn to_persisted(&self, now: Instant) -> Option<(f64, f64, Duration)> {
+        match self {
+            Self::Max => None,
+            Self::Real(score) => Some((
+                score.lighthouse_score,
+                score.gossipsub_score,
+                score.last_updated.saturating_duration_since(now),
+            )),
+        }
+    }
+
+    /// Restores a score saved by `to_persisted`, `offline` after it was saved.
+    pub(crate) fn from_persisted(
+        lighthouse_score: f64,
+        gossipsub_score: f64,
+        ban_remaining: Duration,
+        offline: Duration,
+        now: Instant,
+    ) -> Self {
+        Self::Real(RealScore::from_persisted(
+            lighthouse_score,
+            gossipsub_score,
+            ban_remaining,
+            offline,
+            now,
+        ))
+    }
+
     /// Returns the expected state of the peer given it's score.
     pub(crate) fn state(&self) -> ScoreState {
         match self.score() {
@@ -403,6 +507,40 @@ mod tests {
         assert!(score.score() > MIN_SCORE_BEFORE_BAN);
     }
 
+    #[test]
+    fn test_persisted_score() {
+        let now = Instant::now();
+        let mut score = Score::default();
+        score.test_add(MIN_SCORE_BEFORE_BAN);
+        assert_eq!(score.state(), ScoreState::Banned);
+        let (lighthouse_score, gossipsub_score, ban_remaining) = score.to_persisted(now).unwrap();
+        assert!(ban_remaining > BANNED_BEFORE_DECAY - Duration::from_secs(1));
+
+        // A peer restored during its ban remains banned.
+        let restored = Score::from_persisted(
+            lighthouse_score,
+            gossipsub_score,
+            ban_remaining,
+            Duration::from_secs(3600),
+            now,
+        );
+        assert_eq!(restored.state(), ScoreState::Banned);
+
+        // A peer restored after its ban has decayed for the remaining time offline.
+        let restored = Score::from_persisted(
+            lighthouse_score,
+            gossipsub_score,
+            ban_remaining,
+            ban_remaining + Duration::from_secs(SCORE_HALFLIFE as u64),
+            now,
+        );
+        assert_eq!(restored.state(), ScoreState::Disconnected);
+        assert!((restored.score() - MIN_SCORE_BEFORE_BAN / 2.0).abs() < 0.01);
+
+        // Trusted peers are not persisted.
+        assert_eq!(Score::max_score().to_persisted(now), None);
+    }
+
     #[test]
     fn test_very_negative_gossipsub_score() {
         let mut score = Score::default();
@@ -16,8 +16,9 @@ use std::marker::PhantomData;
 use std::sync::Arc;
 use tokio_util::codec::{Decoder, Encoder};
 use types::{
-    EthSpec, ForkContext, ForkName, SignedBeaconBlock, SignedBeaconBlockAltair,
-    SignedBeaconBlockBase, SignedBeaconBlockMerge,
+    BlobSidecar, EthSpec, ForkContext, ForkName, SignedBeaconBlock, SignedBeaconBlockAltair,
+    SignedBeaconBlockBase, SignedBeaconBlockCapella, SignedBeaconBlockDeneb,
+    SignedBeaconBlockMerge,
 };
 use unsigned_varint::codec::Uvi;
 
@@ -70,6 +71,8 @@ impl<TSpec: EthSpec> Encoder<RPCCodedResponse<TSpec>> for SSZSnappyInboundCodec<
                 RPCResponse::Status(res) => res.as_ssz_bytes(),
                 RPCResponse::BlocksByRange(res) => res.as_ssz_bytes(),
                 RPCResponse::BlocksByRoot(res) => res.as_ssz_bytes(),
+                RPCResponse::BlobsByRange(res) => res.as_ssz_bytes(),
+                RPCResponse::BlobsByRoot(res) => res.as_ssz_bytes(),
                 RPCResponse::Pong(res) => res.data.as_ssz_bytes(),
                 RPCResponse::MetaData(res) =>
                 // Encode the correct version of the MetaData response based on the negotiated version.
@@ -225,6 +228,8 @@ impl<TSpec: EthSpec> Encoder<OutboundRequest<TSpec>> for SSZSnappyOutboundCodec<
             OutboundRequest::Goodbye(req) => req.as_ssz_bytes(),
             OutboundRequest::BlocksByRange(req) => req.as_ssz_bytes(),
             OutboundRequest::BlocksByRoot(req) => req.block_roots.as_ssz_bytes(),
+            OutboundRequest::BlobsByRange(req) => req.as_ssz_bytes(),
+            OutboundRequest::BlobsByRoot(req) => req.blob_ids.as_ssz_bytes(),
             OutboundRequest::Ping(req) => req.as_ssz_bytes(),
             OutboundRequest::MetaData(_) => return Ok(()), // no metadata to encode
         };
@@ -307,7 +312,11 @@ impl<TSpec: EthSpec> Decoder for SSZSnappyOutboundCodec<TSpec> {
                 let _read_bytes = src.split_to(n as usize);
 
                 match self.protocol.version {
-                    Version::V1 => handle_v1_response(self.protocol.message_name, &decoded_buffer),
+                    Version::V1 => handle_v1_response(
+                        self.protocol.message_name,
+                        &decoded_buffer,
+                        &mut self.fork_name,
+                    ),
                     Version::V2 => handle_v2_response(
                         self.protocol.message_name,
                         &decoded_buffer,
@@ -398,12 +407,24 @@ fn context_bytes<T: EthSpec>(
     // Add the context bytes if required
     if protocol.has_context_bytes() {
         if let RPCCodedResponse::Success(rpc_variant) = resp {
+            if let RPCResponse::BlobsByRange(blob) | RPCResponse::BlobsByRoot(blob) = rpc_variant {
+                return fork_context
+                    .to_context_bytes(fork_context.fork_name_at_slot::<T>(blob.slot()));
+            }
             if let RPCResponse::BlocksByRange(ref_box_block)
             | RPCResponse::BlocksByRoot(ref_box_block) = rpc_variant
             {
                 return match **ref_box_block {
                     // NOTE: If you are adding another fork type here, be sure to modify the
                     //       `fork_context.to_context_bytes()` function to support it as well!
+                    SignedBeaconBlock::Deneb { .. } => {
+                        // Deneb context being `None` implies that "deneb never happened".
+                        fork_context.to_context_bytes(ForkName::Deneb)
+                    }
+                    SignedBeaconBlock::Capella { .. } => {
+                        // Capella context being `None` implies that "capella never happened".
+                        fork_context.to_context_bytes(ForkName::Capella)
+                    }
                     SignedBeaconBlock::Merge { .. } => {
                         // Merge context being `None` implies that "merge never happened".
                         fork_context.to_context_bytes(ForkName::Merge)
@@ -466,6 +487,12 @@ fn handle_v1_request<T: EthSpec>(
         Protocol::BlocksByRoot => Ok(Some(InboundRequest::BlocksByRoot(BlocksByRootRequest {
             block_roots: VariableList::from_ssz_bytes(decoded_buffer)?,
         }))),
+        Protocol::BlobsByRange => Ok(Some(InboundRequest::BlobsByRange(
+            BlobsByRangeRequest::from_ssz_bytes(decoded_buffer)?,
+        ))),
+        Protocol::BlobsByRoot => Ok(Some(InboundRequest::BlobsByRoot(BlobsByRootRequest {
+            blob_ids: VariableList::from_ssz_bytes(decoded_buffer)?,
+        }))),
         Protocol::Ping => Ok(Some(InboundRequest::Ping(Ping {
             data: u64::from_ssz_bytes(decoded_buffer)?,
         }))),
@@ -517,9 +544,12 @@ fn handle_v2_request<T: EthSpec>(
 /// Decodes a `Version::V1` `RPCResponse` from the byte stream.
 /// `decoded_buffer` should be an ssz-encoded bytestream with
 // length = length-prefix received in the beginning of the stream.
+///
+/// BlobsByRange/BlobsByRoot responses are only valid if the received `ForkName` is Deneb.
 fn handle_v1_response<T: EthSpec>(
     protocol: Protocol,
     decoded_buffer: &[u8],
+    fork_name: &mut Option<ForkName>,
 ) -> Result<Option<RPCResponse<T>>, RPCError> {
     match protocol {
         Protocol::Status => Ok(Some(RPCResponse::Status(StatusMessage::from_ssz_bytes(
@@ -535,6 +565,14 @@ fn handle_v1_response<T: EthSpec>(
         Protocol::BlocksByRoot => Ok(Some(RPCResponse::BlocksByRoot(Box::new(
             SignedBeaconBlock::Base(SignedBeaconBlockBase::from_ssz_bytes(decoded_buffer)?),
         )))),
+        Protocol::BlobsByRange => {
+            let blob = decode_blob_sidecar(protocol, decoded_buffer, fork_name)?;
+            Ok(Some(RPCResponse::BlobsByRange(blob)))
+        }
+        Protocol::BlobsByRoot => {
+            let blob = decode_blob_sidecar(protocol, decoded_buffer, fork_name)?;
+            Ok(Some(RPCResponse::BlobsByRoot(blob)))
+        }
         Protocol::Ping => Ok(Some(RPCResponse::Pong(Ping {
             data: u64::from_ssz_bytes(decoded_buffer)?,
         }))),
@@ -544,6 +582,25 @@ fn handle_v1_response<T: EthSpec>(
     }
 }
 
+/// Decodes a `BlobSidecar` response chunk, which must have Deneb context bytes.
+fn decode_blob_sidecar<T: EthSpec>(
+    protocol: Protocol,
+    decoded_buffer: &[u8],
+    fork_name: &mut Option<ForkName>,
+) -> Result<Arc<BlobSidecar<T>>, RPCError> {
+    match fork_name.take() {
+        Some(ForkName::Deneb) => Ok(Arc::new(BlobSidecar::from_ssz_bytes(decoded_buffer)?)),
+        Some(fork_name) => Err(RPCError::ErrorResponse(
+            RPCResponseErrorCode::InvalidRequest,
+            format!("Invalid fork name for {} response: {}", protocol, fork_name),
+        )),
+        None => Err(RPCError::ErrorResponse(
+            RPCResponseErrorCode::InvalidRequest,
+            format!("No context bytes provided for {} response", protocol),
+        )),
+    }
+}
+
 /// Decodes a `Version::V2` `RPCResponse` from the byte stream.
 /// `decoded_buffer` should be an ssz-encoded bytestream with
 // length = length-prefix received in the beginning of the stream.
@@ -583,6 +640,16 @@ fn handle_v2_response<T: EthSpec>(
                         decoded_buffer,
                     )?),
                 )))),
+                ForkName::Capella => Ok(Some(RPCResponse::BlocksByRange(Box::new(
+                    SignedBeaconBlock::Capella(SignedBeaconBlockCapella::from_ssz_bytes(
+                        decoded_buffer,
+                    )?),
+                )))),
+                ForkName::Deneb => Ok(Some(RPCResponse::BlocksByRange(Box::new(
+                    SignedBeaconBlock::Deneb(SignedBeaconBlockDeneb::from_ssz_bytes(
+                        decoded_buffer,
+                    )?),
+                )))),
             },
             Protocol::BlocksByRoot => match fork_name {
                 ForkName::Altair => Ok(Some(RPCResponse::BlocksByRoot(Box::new(
@@ -598,6 +665,16 @@ fn handle_v2_response<T: EthSpec>(
                         decoded_buffer,
                     )?),
                 )))),
+                ForkName::Capella => Ok(Some(RPCResponse::BlocksByRoot(Box::new(
+                    SignedBeaconBlock::Capella(SignedBeaconBlockCapella::from_ssz_bytes(
+                        decoded_buffer,
+                    )?),
+                )))),
+                ForkName::Deneb => Ok(Some(RPCResponse::BlocksByRoot(Box::new(
+                    SignedBeaconBlock::Deneb(SignedBeaconBlockDeneb::from_ssz_bytes(
+                        decoded_buffer,
+                    )?),
+                )))),
             },
             _ => Err(RPCError::ErrorResponse(
                 RPCResponseErrorCode::InvalidRequest,
@@ -632,6 +709,7 @@ mod tests {
         types::{EnrAttestationBitfield, EnrSyncCommitteeBitfield},
     };
     use std::sync::Arc;
+    use types::test_utils::{SeedableRng, TestRandom, XorShiftRng};
     use types::{
         BeaconBlock, BeaconBlockAltair, BeaconBlockBase, BeaconBlockMerge, Epoch, ForkContext,
         FullPayload, Hash256, Signature, SignedBeaconBlock, Slot,
@@ -647,14 +725,20 @@ mod tests {
         let mut chain_spec = Spec::default_spec();
         let altair_fork_epoch = Epoch::new(1);
         let merge_fork_epoch = Epoch::new(2);
+        let capella_fork_epoch = Epoch::new(3);
+        let deneb_fork_epoch = Epoch::new(4);
 
         chain_spec.altair_fork_epoch = Some(altair_fork_epoch);
         chain_spec.bellatrix_fork_epoch = Some(merge_fork_epoch);
+        chain_spec.capella_fork_epoch = Some(capella_fork_epoch);
+        chain_spec.deneb_fork_epoch = Some(deneb_fork_epoch);
 
         let current_slot = match fork_name {
             ForkName::Base => Slot::new(0),
             ForkName::Altair => altair_fork_epoch.start_slot(Spec::slots_per_epoch()),
             ForkName::Merge => merge_fork_epoch.start_slot(Spec::slots_per_epoch()),
+            ForkName::Capella => capella_fork_epoch.start_slot(Spec::slots_per_epoch()),
+            ForkName::Deneb => deneb_fork_epoch.start_slot(Spec::slots_per_epoch()),
         };
         ForkContext::new::<Spec>(current_slot, Hash256::zero(), &chain_spec)
     }
@@ -679,7 +763,11 @@ mod tests {
         let tx = VariableList::from(vec![0; 1024]);
         let txs = VariableList::from(std::iter::repeat(tx).take(5000).collect::<Vec<_>>());
 
-        block.body.execution_payload.execution_payload.transactions = txs;
+        *block
+            .body
+            .execution_payload
+            .execution_payload
+            .transactions_mut() = txs;
 
         let block = BeaconBlock::Merge(block);
         assert!(block.ssz_bytes_len() <= max_rpc_size(fork_context));
@@ -695,13 +783,24 @@ mod tests {
         let tx = VariableList::from(vec![0; 1024]);
         let txs = VariableList::from(std::iter::repeat(tx).take(100000).collect::<Vec<_>>());
 
-        block.body.execution_payload.execution_payload.transactions = txs;
+        *block
+            .body
+            .execution_payload
+            .execution_payload
+            .transactions_mut() = txs;
 
         let block = BeaconBlock::Merge(block);
         assert!(block.ssz_bytes_len() > max_rpc_size(fork_context));
         SignedBeaconBlock::from_block(block, Signature::empty())
     }
 
+    /// Blob sidecar of a block at the start of the Deneb fork.
+    fn blob_sidecar() -> Arc<BlobSidecar<Spec>> {
+        let mut blob = BlobSidecar::random_for_test(&mut XorShiftRng::from_seed([42; 16]));
+        blob.signed_block_header.message.slot = Epoch::new(4).start_slot(Spec::slots_per_epoch());
+        Arc::new(blob)
+    }
+
     fn status_message() -> StatusMessage {
         StatusMessage {
             fork_digest: [0; 4],
@@ -1268,6 +1367,57 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_encode_then_decode_blob_sidecars() {
+        assert_eq!(
+            encode_then_decode(
+                Protocol::BlobsByRange,
+                Version::V1,
+                RPCCodedResponse::Success(RPCResponse::BlobsByRange(blob_sidecar())),
+                ForkName::Deneb,
+            ),
+            Ok(Some(RPCResponse::BlobsByRange(blob_sidecar())))
+        );
+
+        assert_eq!(
+            encode_then_decode(
+                Protocol::BlobsByRoot,
+                Version::V1,
+                RPCCodedResponse::Success(RPCResponse::BlobsByRoot(blob_sidecar())),
+                ForkName::Deneb,
+            ),
+            Ok(Some(RPCResponse::BlobsByRoot(blob_sidecar())))
+        );
+
+        // Blob sidecars with pre-Deneb context bytes are rejected.
+        let mut encoded =
+            encode_without_length_checks(blob_sidecar().as_ssz_bytes(), ForkName::Capella).unwrap();
+        assert!(matches!(
+            decode(
+                Protocol::BlobsByRange,
+                Version::V1,
+                &mut encoded,
+                ForkName::Deneb
+            )
+            .unwrap_err(),
+            RPCError::ErrorResponse(RPCResponseErrorCode::InvalidRequest, _),
+        ));
+
+        // Blob sidecars of the wrong length are rejected.
+        let mut encoded =
+            encode_without_length_checks(altair_block().as_ssz_bytes(), ForkName::Deneb).unwrap();
+        assert!(matches!(
+            decode(
+                Protocol::BlobsByRoot,
+                Version::V1,
+                &mut encoded,
+                ForkName::Deneb
+            )
+            .unwrap_err(),
+            RPCError::InvalidData(_),
+        ));
+    }
+
     /// Test a malicious snappy encoding for a V1 `Status` message where the attacker
     /// sends a valid message filled with a stream of useless padding before the actual message.
     #[test]
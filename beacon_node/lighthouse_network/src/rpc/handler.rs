@@ -47,6 +47,13 @@ const MAX_INBOUND_SUBSTREAMS: usize = 32;
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
 pub struct SubstreamId(usize);
 
+#[cfg(test)]
+impl SubstreamId {
+    pub(crate) fn new(id: usize) -> Self {
+        SubstreamId(id)
+    }
+}
+
 type InboundSubstream<TSpec> = InboundFramed<NegotiatedSubstream, TSpec>;
 
 /// Events the handler emits to the behaviour.
@@ -933,6 +940,8 @@ where
                             let termination = match protocol {
                                 Protocol::BlocksByRange => Some(ResponseTermination::BlocksByRange),
                                 Protocol::BlocksByRoot => Some(ResponseTermination::BlocksByRoot),
+                                Protocol::BlobsByRange => Some(ResponseTermination::BlobsByRange),
+                                Protocol::BlobsByRoot => Some(ResponseTermination::BlobsByRoot),
                                 _ => None, // all other protocols are do not have multiple responses and we do not inform the user, we simply drop the stream.
                             };
 
@@ -5,17 +5,25 @@ use regex::bytes::Regex;
 use serde::Serialize;
 use ssz_derive::{Decode, Encode};
 use ssz_types::{
-    typenum::{U1024, U256},
+    typenum::{U1024, U256, U768},
     VariableList,
 };
 use std::ops::Deref;
+use std::sync::Arc;
 use strum::IntoStaticStr;
 use superstruct::superstruct;
-use types::{Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
+use types::{BlobIdentifier, BlobSidecar, Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
 
 /// Maximum number of blocks in a single request.
 pub type MaxRequestBlocks = U1024;
 pub const MAX_REQUEST_BLOCKS: u64 = 1024;
+/// Maximum number of blocks in a single `BlocksByRange` request between peers which both advertise
+/// support for larger requests in their ENR.
+pub const MAX_REQUEST_BLOCKS_HARD_CAP: u64 = 4096;
+
+/// Maximum number of blob sidecars in a single request.
+pub type MaxRequestBlobSidecars = U768;
+pub const MAX_REQUEST_BLOB_SIDECARS: u64 = 768;
 
 /// Maximum length of error message.
 pub type MaxErrorLen = U256;
@@ -217,6 +225,30 @@ pub struct BlocksByRootRequest {
     pub block_roots: VariableList<Hash256, MaxRequestBlocks>,
 }
 
+/// Request a number of blob sidecars from a peer.
+#[derive(Encode, Decode, Clone, Debug, PartialEq)]
+pub struct BlobsByRangeRequest {
+    /// The starting slot to request blob sidecars.
+    pub start_slot: u64,
+
+    /// The number of slots from the start slot.
+    pub count: u64,
+}
+
+impl BlobsByRangeRequest {
+    /// The maximum number of blob sidecars a peer may respond with.
+    pub fn max_blobs_requested<E: EthSpec>(&self) -> u64 {
+        self.count.saturating_mul(E::max_blobs_per_block() as u64)
+    }
+}
+
+/// Request a number of blob sidecars from a peer, by block root and index.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlobsByRootRequest {
+    /// The list of blob sidecars being requested.
+    pub blob_ids: VariableList<BlobIdentifier, MaxRequestBlobSidecars>,
+}
+
 /* RPC Handling and Grouping */
 // Collection of enums and structs used by the Codecs to encode/decode RPC messages
 
@@ -232,6 +264,12 @@ pub enum RPCResponse<T: EthSpec> {
     /// A response to a get BLOCKS_BY_ROOT request.
     BlocksByRoot(Box<SignedBeaconBlock<T>>),
 
+    /// A response to a get BLOB_SIDECARS_BY_RANGE request.
+    BlobsByRange(Arc<BlobSidecar<T>>),
+
+    /// A response to a get BLOB_SIDECARS_BY_ROOT request.
+    BlobsByRoot(Arc<BlobSidecar<T>>),
+
     /// A PONG response to a PING request.
     Pong(Ping),
 
@@ -247,6 +285,12 @@ pub enum ResponseTermination {
 
     /// Blocks by root stream termination.
     BlocksByRoot,
+
+    /// Blobs by range stream termination.
+    BlobsByRange,
+
+    /// Blobs by root stream termination.
+    BlobsByRoot,
 }
 
 /// The structured response containing a result/code indicating success or failure
@@ -308,6 +352,8 @@ impl<T: EthSpec> RPCCodedResponse<T> {
                 RPCResponse::Status(_) => false,
                 RPCResponse::BlocksByRange(_) => true,
                 RPCResponse::BlocksByRoot(_) => true,
+                RPCResponse::BlobsByRange(_) => true,
+                RPCResponse::BlobsByRoot(_) => true,
                 RPCResponse::Pong(_) => false,
                 RPCResponse::MetaData(_) => false,
             },
@@ -342,6 +388,8 @@ impl<T: EthSpec> RPCResponse<T> {
             RPCResponse::Status(_) => Protocol::Status,
             RPCResponse::BlocksByRange(_) => Protocol::BlocksByRange,
             RPCResponse::BlocksByRoot(_) => Protocol::BlocksByRoot,
+            RPCResponse::BlobsByRange(_) => Protocol::BlobsByRange,
+            RPCResponse::BlobsByRoot(_) => Protocol::BlobsByRoot,
             RPCResponse::Pong(_) => Protocol::Ping,
             RPCResponse::MetaData(_) => Protocol::MetaData,
         }
@@ -377,6 +425,12 @@ impl<T: EthSpec> std::fmt::Display for RPCResponse<T> {
             RPCResponse::BlocksByRoot(block) => {
                 write!(f, "BlocksByRoot: Block slot: {}", block.slot())
             }
+            RPCResponse::BlobsByRange(blob) => {
+                write!(f, "BlobsByRange: Blob slot: {}", blob.slot())
+            }
+            RPCResponse::BlobsByRoot(blob) => {
+                write!(f, "BlobsByRoot: Blob slot: {}", blob.slot())
+            }
             RPCResponse::Pong(ping) => write!(f, "Pong: {}", ping.data),
             RPCResponse::MetaData(metadata) => write!(f, "Metadata: {}", metadata.seq_number()),
         }
@@ -419,6 +473,12 @@ impl std::fmt::Display for BlocksByRangeRequest {
     }
 }
 
+impl std::fmt::Display for BlobsByRangeRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Start Slot: {}, Count: {}", self.start_slot, self.count)
+    }
+}
+
 impl slog::KV for StatusMessage {
     fn serialize(
         &self,
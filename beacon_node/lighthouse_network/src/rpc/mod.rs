@@ -7,12 +7,14 @@
 use futures::future::FutureExt;
 use handler::{HandlerEvent, RPCHandler};
 use libp2p::core::connection::ConnectionId;
+use libp2p::core::ConnectedPoint;
 use libp2p::swarm::{
     handler::ConnectionHandler, NetworkBehaviour, NetworkBehaviourAction, NotifyHandler,
     PollParameters, SubstreamProtocol,
 };
 use libp2p::PeerId;
 use rate_limiter::{RPCRateLimiter as RateLimiter, RPCRateLimiterBuilder, RateLimitedErr};
+use response_limiter::{QueuedResponse, ResponseLimiter};
 use slog::{crit, debug, o};
 use std::marker::PhantomData;
 use std::sync::Arc;
@@ -23,11 +25,14 @@ use types::{EthSpec, ForkContext};
 pub(crate) use handler::HandlerErr;
 pub(crate) use methods::{MetaData, MetaDataV1, MetaDataV2, Ping, RPCCodedResponse, RPCResponse};
 pub(crate) use protocol::{InboundRequest, RPCProtocol};
+pub(crate) use rate_limiter::blocks_by_range_token_cost;
 
 pub use handler::SubstreamId;
 pub use methods::{
-    BlocksByRangeRequest, BlocksByRootRequest, GoodbyeReason, MaxRequestBlocks,
-    RPCResponseErrorCode, ResponseTermination, StatusMessage, MAX_REQUEST_BLOCKS,
+    BlobsByRangeRequest, BlobsByRootRequest, BlocksByRangeRequest, BlocksByRootRequest,
+    GoodbyeReason, MaxRequestBlobSidecars, MaxRequestBlocks, RPCResponseErrorCode,
+    ResponseTermination, StatusMessage, MAX_REQUEST_BLOB_SIDECARS, MAX_REQUEST_BLOCKS,
+    MAX_REQUEST_BLOCKS_HARD_CAP,
 };
 pub(crate) use outbound::OutboundRequest;
 pub use protocol::{max_rpc_size, Protocol, RPCError};
@@ -38,6 +43,7 @@ pub mod methods;
 mod outbound;
 mod protocol;
 mod rate_limiter;
+mod response_limiter;
 
 /// Composite trait for a request id.
 pub trait ReqId: Send + 'static + std::fmt::Debug + Copy + Clone {}
@@ -104,6 +110,8 @@ pub struct RPCMessage<Id, TSpec: EthSpec> {
 pub struct RPC<Id: ReqId, TSpec: EthSpec> {
     /// Rate limiter
     limiter: RateLimiter,
+    /// Rate limiter for the responses we send.
+    response_limiter: ResponseLimiter<TSpec>,
     /// Queue of events to be processed.
     events: Vec<NetworkBehaviourAction<RPCMessage<Id, TSpec>, RPCHandler<Id, TSpec>>>,
     fork_context: Arc<ForkContext>,
@@ -119,16 +127,34 @@ impl<Id: ReqId, TSpec: EthSpec> RPC<Id, TSpec> {
             .n_every(Protocol::Ping, 2, Duration::from_secs(10))
             .n_every(Protocol::Status, 5, Duration::from_secs(15))
             .one_every(Protocol::Goodbye, Duration::from_secs(10))
+            // allows `MAX_REQUEST_BLOCKS` blocks, or more for peers with larger negotiated limits
             .n_every(
                 Protocol::BlocksByRange,
-                methods::MAX_REQUEST_BLOCKS,
-                Duration::from_secs(10),
+                rate_limiter::BLOCKS_BY_RANGE_QUOTA_TOKENS,
+                rate_limiter::BLOCKS_QUOTA_PERIOD,
+            )
+            .n_every(
+                Protocol::BlocksByRoot,
+                rate_limiter::BLOCKS_BY_ROOT_QUOTA,
+                rate_limiter::BLOCKS_QUOTA_PERIOD,
+            )
+            .n_every(
+                Protocol::BlobsByRange,
+                rate_limiter::BLOBS_BY_RANGE_QUOTA,
+                rate_limiter::BLOCKS_QUOTA_PERIOD,
+            )
+            .n_every(
+                Protocol::BlobsByRoot,
+                rate_limiter::BLOBS_BY_ROOT_QUOTA,
+                rate_limiter::BLOCKS_QUOTA_PERIOD,
             )
-            .n_every(Protocol::BlocksByRoot, 128, Duration::from_secs(10))
             .build()
             .expect("Configuration parameters are valid");
+        let response_limiter =
+            ResponseLimiter::new().expect("Response limiter configuration parameters are valid");
         RPC {
             limiter,
+            response_limiter,
             events: Vec::new(),
             fork_context,
             log,
@@ -137,20 +163,47 @@ impl<Id: ReqId, TSpec: EthSpec> RPC<Id, TSpec> {
 
     /// Sends an RPC response.
     ///
-    /// The peer must be connected for this to succeed.
+    /// The peer must be connected for this to succeed. Block responses in excess of the peer's
+    /// response quota are held back until the quota replenishes.
     pub fn send_response(
         &mut self,
         peer_id: PeerId,
         id: (ConnectionId, SubstreamId),
         event: RPCCodedResponse<TSpec>,
     ) {
+        let response = QueuedResponse {
+            peer_id,
+            id,
+            response: event,
+        };
+        if let Some(response) = self.response_limiter.allows(response) {
+            self.notify_response(response);
+        }
+    }
+
+    fn notify_response(&mut self, response: QueuedResponse<TSpec>) {
+        let QueuedResponse {
+            peer_id,
+            id,
+            response,
+        } = response;
         self.events.push(NetworkBehaviourAction::NotifyHandler {
             peer_id,
             handler: NotifyHandler::One(id.0),
-            event: RPCSend::Response(id.1, event),
+            event: RPCSend::Response(id.1, response),
         });
     }
 
+    /// Sets the largest `BlocksByRange` request which may be served to a connected peer, as
+    /// negotiated through our ENRs. Larger limits increase the peer's `BlocksByRange` quotas
+    /// proportionally.
+    pub fn set_max_request_blocks(&mut self, peer_id: PeerId, max_request_blocks: u64) {
+        self.limiter
+            .set_max_request_blocks(peer_id, max_request_blocks);
+        self.response_limiter
+            .set_max_request_blocks(peer_id, max_request_blocks);
+    }
+
     /// Submits an RPC request.
     ///
     /// The peer must be connected for this to succeed.
@@ -196,6 +249,21 @@ where
         )
     }
 
+    fn inject_connection_closed(
+        &mut self,
+        peer_id: &PeerId,
+        conn_id: &ConnectionId,
+        _endpoint: &ConnectedPoint,
+        _handler: Self::ConnectionHandler,
+        remaining_established: usize,
+    ) {
+        self.response_limiter.connection_closed(peer_id, conn_id);
+        if remaining_established == 0 {
+            self.limiter.remove_peer(peer_id);
+            self.response_limiter.remove_peer(peer_id);
+        }
+    }
+
     fn inject_event(
         &mut self,
         peer_id: PeerId,
@@ -265,6 +333,9 @@ where
     ) -> Poll<NetworkBehaviourAction<Self::OutEvent, Self::ConnectionHandler>> {
         // let the rate limiter prune
         let _ = self.limiter.poll_unpin(cx);
+        while let Poll::Ready(response) = self.response_limiter.poll_ready(cx) {
+            self.notify_response(response);
+        }
         if !self.events.is_empty() {
             return Poll::Ready(self.events.remove(0));
         }
@@ -292,6 +363,8 @@ where
                     match end {
                         ResponseTermination::BlocksByRange => Protocol::BlocksByRange,
                         ResponseTermination::BlocksByRoot => Protocol::BlocksByRoot,
+                        ResponseTermination::BlobsByRange => Protocol::BlobsByRange,
+                        ResponseTermination::BlobsByRoot => Protocol::BlobsByRoot,
                     },
                 ),
             },
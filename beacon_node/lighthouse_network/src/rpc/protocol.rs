@@ -2,7 +2,7 @@ use super::methods::*;
 use crate::rpc::{
     codec::{base::BaseInboundCodec, ssz_snappy::SSZSnappyInboundCodec, InboundCodec},
     methods::{MaxErrorLen, ResponseTermination, MAX_ERROR_LEN},
-    MaxRequestBlocks, MAX_REQUEST_BLOCKS,
+    MaxRequestBlobSidecars, MaxRequestBlocks, MAX_REQUEST_BLOB_SIDECARS, MAX_REQUEST_BLOCKS,
 };
 use futures::future::BoxFuture;
 use futures::prelude::{AsyncRead, AsyncWrite};
@@ -21,8 +21,9 @@ use tokio_util::{
     compat::{Compat, FuturesAsyncReadCompatExt},
 };
 use types::{
-    BeaconBlock, BeaconBlockAltair, BeaconBlockBase, BeaconBlockMerge, EthSpec, ForkContext,
-    ForkName, Hash256, MainnetEthSpec, Signature, SignedBeaconBlock,
+    BeaconBlock, BeaconBlockAltair, BeaconBlockBase, BeaconBlockMerge, BlobIdentifier, BlobSidecar,
+    EthSpec, ForkContext, ForkName, Hash256, KzgCommitment, MainnetEthSpec, Signature,
+    SignedBeaconBlock, SignedBlsToExecutionChange, Unsigned,
 };
 
 lazy_static! {
@@ -71,6 +72,23 @@ lazy_static! {
     + types::ExecutionPayload::<MainnetEthSpec>::max_execution_payload_size() // adding max size of execution payload (~16gb)
     + ssz::BYTES_PER_LENGTH_OFFSET; // Adding the additional ssz offset for the `ExecutionPayload` field
 
+    /// The `BeaconBlockCapella` block additionally has a list of `SignedBlsToExecutionChange`s.
+    pub static ref SIGNED_BEACON_BLOCK_CAPELLA_MAX: usize =
+    // Size of a full merge block
+    *SIGNED_BEACON_BLOCK_MERGE_MAX
+    + <MainnetEthSpec as EthSpec>::MaxBlsToExecutionChanges::to_usize()
+        * <SignedBlsToExecutionChange as Encode>::ssz_fixed_len() // adding max size of the BLS changes
+    + ssz::BYTES_PER_LENGTH_OFFSET; // Adding the additional ssz offset for the `bls_to_execution_changes` field
+
+    /// The `BeaconBlockDeneb` block additionally has a list of `KzgCommitment`s. The extra Deneb
+    /// payload fields are already accounted for by `max_execution_payload_size`.
+    pub static ref SIGNED_BEACON_BLOCK_DENEB_MAX: usize =
+    // Size of a full capella block
+    *SIGNED_BEACON_BLOCK_CAPELLA_MAX
+    + <MainnetEthSpec as EthSpec>::MaxBlobCommitmentsPerBlock::to_usize()
+        * <KzgCommitment as Encode>::ssz_fixed_len() // adding max size of the blob commitments
+    + ssz::BYTES_PER_LENGTH_OFFSET; // Adding the additional ssz offset for the `blob_kzg_commitments` field
+
     pub static ref BLOCKS_BY_ROOT_REQUEST_MIN: usize =
         VariableList::<Hash256, MaxRequestBlocks>::from(Vec::<Hash256>::new())
     .as_ssz_bytes()
@@ -83,6 +101,21 @@ lazy_static! {
         ])
     .as_ssz_bytes()
     .len();
+    pub static ref BLOBS_BY_ROOT_REQUEST_MIN: usize =
+        VariableList::<BlobIdentifier, MaxRequestBlobSidecars>::from(Vec::<BlobIdentifier>::new())
+    .as_ssz_bytes()
+    .len();
+    pub static ref BLOBS_BY_ROOT_REQUEST_MAX: usize =
+        VariableList::<BlobIdentifier, MaxRequestBlobSidecars>::from(vec![
+            BlobIdentifier {
+                block_root: Hash256::zero(),
+                index: 0,
+            };
+            MAX_REQUEST_BLOB_SIDECARS
+                as usize
+        ])
+    .as_ssz_bytes()
+    .len();
     pub static ref ERROR_TYPE_MIN: usize =
         VariableList::<u8, MaxErrorLen>::from(Vec::<u8>::new())
     .as_ssz_bytes()
@@ -113,7 +146,7 @@ const REQUEST_TIMEOUT: u64 = 15;
 /// Returns the maximum bytes that can be sent across the RPC.
 pub fn max_rpc_size(fork_context: &ForkContext) -> usize {
     match fork_context.current_fork() {
-        ForkName::Merge => MAX_RPC_SIZE_POST_MERGE,
+        ForkName::Merge | ForkName::Capella | ForkName::Deneb => MAX_RPC_SIZE_POST_MERGE,
         ForkName::Altair | ForkName::Base => MAX_RPC_SIZE,
     }
 }
@@ -135,9 +168,24 @@ pub fn rpc_block_limits_by_fork(current_fork: ForkName) -> RpcLimits {
             *SIGNED_BEACON_BLOCK_BASE_MIN, // Base block is smaller than altair and merge blocks
             *SIGNED_BEACON_BLOCK_MERGE_MAX, // Merge block is larger than base and altair blocks
         ),
+        ForkName::Capella => RpcLimits::new(
+            *SIGNED_BEACON_BLOCK_BASE_MIN, // Base block is smaller than all later blocks
+            *SIGNED_BEACON_BLOCK_CAPELLA_MAX, // Capella block is larger than all earlier blocks
+        ),
+        ForkName::Deneb => RpcLimits::new(
+            *SIGNED_BEACON_BLOCK_BASE_MIN, // Base block is smaller than all later blocks
+            *SIGNED_BEACON_BLOCK_DENEB_MAX, // Deneb block is larger than all earlier blocks
+        ),
     }
 }
 
+/// Returns the rpc limits for blob_sidecars_by_range and blob_sidecars_by_root responses.
+///
+/// Blob sidecars have a fixed size, and no responses are valid before Deneb.
+pub fn rpc_blob_limits<T: EthSpec>() -> RpcLimits {
+    RpcLimits::new(BlobSidecar::<T>::max_size(), BlobSidecar::<T>::max_size())
+}
+
 /// Protocol names to be used.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Protocol {
@@ -149,6 +197,10 @@ pub enum Protocol {
     BlocksByRange,
     /// The `BlocksByRoot` protocol name.
     BlocksByRoot,
+    /// The `BlobsByRange` protocol name.
+    BlobsByRange,
+    /// The `BlobsByRoot` protocol name.
+    BlobsByRoot,
     /// The `Ping` protocol name.
     Ping,
     /// The `MetaData` protocol name.
@@ -177,6 +229,8 @@ impl std::fmt::Display for Protocol {
             Protocol::Goodbye => "goodbye",
             Protocol::BlocksByRange => "beacon_blocks_by_range",
             Protocol::BlocksByRoot => "beacon_blocks_by_root",
+            Protocol::BlobsByRange => "blob_sidecars_by_range",
+            Protocol::BlobsByRoot => "blob_sidecars_by_root",
             Protocol::Ping => "ping",
             Protocol::MetaData => "metadata",
         };
@@ -216,7 +270,7 @@ impl<TSpec: EthSpec> UpgradeInfo for RPCProtocol<TSpec> {
 
     /// The list of supported RPC protocols for Lighthouse.
     fn protocol_info(&self) -> Self::InfoIter {
-        vec![
+        let mut supported_protocols = vec![
             ProtocolId::new(Protocol::Status, Version::V1, Encoding::SSZSnappy),
             ProtocolId::new(Protocol::Goodbye, Version::V1, Encoding::SSZSnappy),
             // V2 variants have higher preference then V1
@@ -227,7 +281,15 @@ impl<TSpec: EthSpec> UpgradeInfo for RPCProtocol<TSpec> {
             ProtocolId::new(Protocol::Ping, Version::V1, Encoding::SSZSnappy),
             ProtocolId::new(Protocol::MetaData, Version::V2, Encoding::SSZSnappy),
             ProtocolId::new(Protocol::MetaData, Version::V1, Encoding::SSZSnappy),
-        ]
+        ];
+        // The blob protocols are only supported once Deneb is scheduled.
+        if self.fork_context.fork_exists(ForkName::Deneb) {
+            supported_protocols.extend([
+                ProtocolId::new(Protocol::BlobsByRange, Version::V1, Encoding::SSZSnappy),
+                ProtocolId::new(Protocol::BlobsByRoot, Version::V1, Encoding::SSZSnappy),
+            ]);
+        }
+        supported_protocols
     }
 }
 
@@ -285,6 +347,13 @@ impl ProtocolId {
             Protocol::BlocksByRoot => {
                 RpcLimits::new(*BLOCKS_BY_ROOT_REQUEST_MIN, *BLOCKS_BY_ROOT_REQUEST_MAX)
             }
+            Protocol::BlobsByRange => RpcLimits::new(
+                <BlobsByRangeRequest as Encode>::ssz_fixed_len(),
+                <BlobsByRangeRequest as Encode>::ssz_fixed_len(),
+            ),
+            Protocol::BlobsByRoot => {
+                RpcLimits::new(*BLOBS_BY_ROOT_REQUEST_MIN, *BLOBS_BY_ROOT_REQUEST_MAX)
+            }
             Protocol::Ping => RpcLimits::new(
                 <Ping as Encode>::ssz_fixed_len(),
                 <Ping as Encode>::ssz_fixed_len(),
@@ -303,6 +372,8 @@ impl ProtocolId {
             Protocol::Goodbye => RpcLimits::new(0, 0), // Goodbye request has no response
             Protocol::BlocksByRange => rpc_block_limits_by_fork(fork_context.current_fork()),
             Protocol::BlocksByRoot => rpc_block_limits_by_fork(fork_context.current_fork()),
+            Protocol::BlobsByRange => rpc_blob_limits::<T>(),
+            Protocol::BlobsByRoot => rpc_blob_limits::<T>(),
 
             Protocol::Ping => RpcLimits::new(
                 <Ping as Encode>::ssz_fixed_len(),
@@ -318,13 +389,11 @@ impl ProtocolId {
     /// Returns `true` if the given `ProtocolId` should expect `context_bytes` in the
     /// beginning of the stream, else returns `false`.
     pub fn has_context_bytes(&self) -> bool {
-        if self.version == Version::V2 {
-            match self.message_name {
-                Protocol::BlocksByRange | Protocol::BlocksByRoot => return true,
-                _ => return false,
-            }
+        match self.message_name {
+            Protocol::BlocksByRange | Protocol::BlocksByRoot => self.version == Version::V2,
+            Protocol::BlobsByRange | Protocol::BlobsByRoot => true,
+            _ => false,
         }
-        false
     }
 }
 
@@ -417,6 +486,8 @@ pub enum InboundRequest<TSpec: EthSpec> {
     Goodbye(GoodbyeReason),
     BlocksByRange(BlocksByRangeRequest),
     BlocksByRoot(BlocksByRootRequest),
+    BlobsByRange(BlobsByRangeRequest),
+    BlobsByRoot(BlobsByRootRequest),
     Ping(Ping),
     MetaData(PhantomData<TSpec>),
 }
@@ -456,6 +527,16 @@ impl<TSpec: EthSpec> InboundRequest<TSpec> {
                 ProtocolId::new(Protocol::BlocksByRoot, Version::V2, Encoding::SSZSnappy),
                 ProtocolId::new(Protocol::BlocksByRoot, Version::V1, Encoding::SSZSnappy),
             ],
+            InboundRequest::BlobsByRange(_) => vec![ProtocolId::new(
+                Protocol::BlobsByRange,
+                Version::V1,
+                Encoding::SSZSnappy,
+            )],
+            InboundRequest::BlobsByRoot(_) => vec![ProtocolId::new(
+                Protocol::BlobsByRoot,
+                Version::V1,
+                Encoding::SSZSnappy,
+            )],
             InboundRequest::Ping(_) => vec![ProtocolId::new(
                 Protocol::Ping,
                 Version::V1,
@@ -477,6 +558,8 @@ impl<TSpec: EthSpec> InboundRequest<TSpec> {
             InboundRequest::Goodbye(_) => 0,
             InboundRequest::BlocksByRange(req) => req.count,
             InboundRequest::BlocksByRoot(req) => req.block_roots.len() as u64,
+            InboundRequest::BlobsByRange(req) => req.max_blobs_requested::<TSpec>(),
+            InboundRequest::BlobsByRoot(req) => req.blob_ids.len() as u64,
             InboundRequest::Ping(_) => 1,
             InboundRequest::MetaData(_) => 1,
         }
@@ -489,6 +572,8 @@ impl<TSpec: EthSpec> InboundRequest<TSpec> {
             InboundRequest::Goodbye(_) => Protocol::Goodbye,
             InboundRequest::BlocksByRange(_) => Protocol::BlocksByRange,
             InboundRequest::BlocksByRoot(_) => Protocol::BlocksByRoot,
+            InboundRequest::BlobsByRange(_) => Protocol::BlobsByRange,
+            InboundRequest::BlobsByRoot(_) => Protocol::BlobsByRoot,
             InboundRequest::Ping(_) => Protocol::Ping,
             InboundRequest::MetaData(_) => Protocol::MetaData,
         }
@@ -502,6 +587,8 @@ impl<TSpec: EthSpec> InboundRequest<TSpec> {
             // variants that have `multiple_responses()` can have values.
             InboundRequest::BlocksByRange(_) => ResponseTermination::BlocksByRange,
             InboundRequest::BlocksByRoot(_) => ResponseTermination::BlocksByRoot,
+            InboundRequest::BlobsByRange(_) => ResponseTermination::BlobsByRange,
+            InboundRequest::BlobsByRoot(_) => ResponseTermination::BlobsByRoot,
             InboundRequest::Status(_) => unreachable!(),
             InboundRequest::Goodbye(_) => unreachable!(),
             InboundRequest::Ping(_) => unreachable!(),
@@ -607,6 +694,8 @@ impl<TSpec: EthSpec> std::fmt::Display for InboundRequest<TSpec> {
             InboundRequest::Goodbye(reason) => write!(f, "Goodbye: {}", reason),
             InboundRequest::BlocksByRange(req) => write!(f, "Blocks by range: {}", req),
             InboundRequest::BlocksByRoot(req) => write!(f, "Blocks by root: {:?}", req),
+            InboundRequest::BlobsByRange(req) => write!(f, "Blobs by range: {}", req),
+            InboundRequest::BlobsByRoot(req) => write!(f, "Blobs by root: {:?}", req),
             InboundRequest::Ping(ping) => write!(f, "Ping: {}", ping.data),
             InboundRequest::MetaData(_) => write!(f, "MetaData request"),
         }
@@ -1,3 +1,6 @@
+use crate::rpc::methods::{
+    MAX_REQUEST_BLOB_SIDECARS, MAX_REQUEST_BLOCKS, MAX_REQUEST_BLOCKS_HARD_CAP,
+};
 use crate::rpc::{InboundRequest, Protocol};
 use fnv::FnvHashMap;
 use libp2p::PeerId;
@@ -55,6 +58,42 @@ pub struct Quota {
     max_tokens: u64,
 }
 
+impl Quota {
+    /// Allow `n` tokens to be used every `time_period`.
+    pub const fn n_every(n: u64, time_period: Duration) -> Self {
+        Quota {
+            max_tokens: n,
+            replenish_all_every: time_period,
+        }
+    }
+}
+
+/// How often the `BlocksByRange` and `BlocksByRoot` request quotas are fully replenished.
+pub(super) const BLOCKS_QUOTA_PERIOD: Duration = Duration::from_secs(10);
+/// The number of tokens of `BlocksByRange` requests allowed every `BLOCKS_QUOTA_PERIOD`, i.e.
+/// `MAX_REQUEST_BLOCKS` blocks, or more for peers with larger negotiated limits.
+pub(super) const BLOCKS_BY_RANGE_QUOTA_TOKENS: u64 = MAX_REQUEST_BLOCKS_HARD_CAP;
+/// The number of blocks which may be requested by `BlocksByRoot` every `BLOCKS_QUOTA_PERIOD`.
+pub(super) const BLOCKS_BY_ROOT_QUOTA: u64 = 128;
+/// The number of blob sidecars which may be requested by `BlobsByRange` every
+/// `BLOCKS_QUOTA_PERIOD`. Requests are charged for the most blobs their slots could hold.
+pub(super) const BLOBS_BY_RANGE_QUOTA: u64 = MAX_REQUEST_BLOB_SIDECARS;
+/// The number of blob sidecars which may be requested by `BlobsByRoot` every
+/// `BLOCKS_QUOTA_PERIOD`.
+pub(super) const BLOBS_BY_ROOT_QUOTA: u64 = 128;
+
+/// Returns the number of tokens charged per `BlocksByRange` block to a peer with the given
+/// negotiated `max_request_blocks`.
+///
+/// `BlocksByRange` quotas are sized in tokens for `MAX_REQUEST_BLOCKS_HARD_CAP` blocks, so peers
+/// limited to the spec's `MAX_REQUEST_BLOCKS` pay proportionally more per block. The cost is
+/// rounded up to a whole token, so a peer is never allowed more blocks than it negotiated.
+pub(crate) fn blocks_by_range_token_cost(max_request_blocks: u64) -> u64 {
+    let max_request_blocks =
+        max_request_blocks.clamp(MAX_REQUEST_BLOCKS, MAX_REQUEST_BLOCKS_HARD_CAP);
+    (MAX_REQUEST_BLOCKS_HARD_CAP + max_request_blocks - 1) / max_request_blocks
+}
+
 /// Manages rate limiting of requests per peer, with differentiated rates per protocol.
 pub struct RPCRateLimiter {
     /// Interval to prune peers for which their timer ran out.
@@ -73,6 +112,13 @@ pub struct RPCRateLimiter {
     bbrange_rl: Limiter<PeerId>,
     /// BlocksByRoot rate limiter.
     bbroots_rl: Limiter<PeerId>,
+    /// BlobsByRange rate limiter.
+    blbrange_rl: Limiter<PeerId>,
+    /// BlobsByRoot rate limiter.
+    blbroots_rl: Limiter<PeerId>,
+    /// The `BlocksByRange` token cost of peers with a negotiated `max_request_blocks` larger than
+    /// the spec's.
+    bbrange_token_costs: FnvHashMap<PeerId, u64>,
 }
 
 /// Error type for non conformant requests
@@ -98,6 +144,10 @@ pub struct RPCRateLimiterBuilder {
     bbrange_quota: Option<Quota>,
     /// Quota for the BlocksByRoot protocol.
     bbroots_quota: Option<Quota>,
+    /// Quota for the BlobsByRange protocol.
+    blbrange_quota: Option<Quota>,
+    /// Quota for the BlobsByRoot protocol.
+    blbroots_quota: Option<Quota>,
 }
 
 impl RPCRateLimiterBuilder {
@@ -116,6 +166,8 @@ impl RPCRateLimiterBuilder {
             Protocol::Goodbye => self.goodbye_quota = q,
             Protocol::BlocksByRange => self.bbrange_quota = q,
             Protocol::BlocksByRoot => self.bbroots_quota = q,
+            Protocol::BlobsByRange => self.blbrange_quota = q,
+            Protocol::BlobsByRoot => self.blbroots_quota = q,
         }
         self
     }
@@ -155,6 +207,12 @@ impl RPCRateLimiterBuilder {
         let bbrange_quota = self
             .bbrange_quota
             .ok_or("BlocksByRange quota not specified")?;
+        let blbrange_quota = self
+            .blbrange_quota
+            .ok_or("BlobsByRange quota not specified")?;
+        let blbroots_quota = self
+            .blbroots_quota
+            .ok_or("BlobsByRoot quota not specified")?;
 
         // create the rate limiters
         let ping_rl = Limiter::from_quota(ping_quota)?;
@@ -163,6 +221,8 @@ impl RPCRateLimiterBuilder {
         let goodbye_rl = Limiter::from_quota(goodbye_quota)?;
         let bbroots_rl = Limiter::from_quota(bbroots_quota)?;
         let bbrange_rl = Limiter::from_quota(bbrange_quota)?;
+        let blbrange_rl = Limiter::from_quota(blbrange_quota)?;
+        let blbroots_rl = Limiter::from_quota(blbroots_quota)?;
 
         // check for peers to prune every 30 seconds, starting in 30 seconds
         let prune_every = tokio::time::Duration::from_secs(30);
@@ -176,6 +236,9 @@ impl RPCRateLimiterBuilder {
             goodbye_rl,
             bbroots_rl,
             bbrange_rl,
+            blbrange_rl,
+            blbroots_rl,
+            bbrange_token_costs: FnvHashMap::default(),
             init_time: Instant::now(),
         })
     }
@@ -209,7 +272,7 @@ impl RPCRateLimiter {
 
         if let InboundRequest::BlocksByRange(bbr_req) = request {
             let penalty_factor = (bbr_req.step as f64 / 5.0).powi(2) as u64 + 1;
-            tokens *= penalty_factor;
+            tokens *= penalty_factor * self.bbrange_token_cost(peer_id);
         }
 
         let check =
@@ -221,10 +284,34 @@ impl RPCRateLimiter {
             Protocol::Goodbye => &mut self.goodbye_rl,
             Protocol::BlocksByRange => &mut self.bbrange_rl,
             Protocol::BlocksByRoot => &mut self.bbroots_rl,
+            Protocol::BlobsByRange => &mut self.blbrange_rl,
+            Protocol::BlobsByRoot => &mut self.blbroots_rl,
         };
         check(limiter)
     }
 
+    /// Sets the negotiated `max_request_blocks` of a connected peer.
+    pub fn set_max_request_blocks(&mut self, peer_id: PeerId, max_request_blocks: u64) {
+        if max_request_blocks > MAX_REQUEST_BLOCKS {
+            self.bbrange_token_costs
+                .insert(peer_id, blocks_by_range_token_cost(max_request_blocks));
+        } else {
+            self.bbrange_token_costs.remove(&peer_id);
+        }
+    }
+
+    /// Forgets the negotiated limits of a disconnected peer.
+    pub fn remove_peer(&mut self, peer_id: &PeerId) {
+        self.bbrange_token_costs.remove(peer_id);
+    }
+
+    fn bbrange_token_cost(&self, peer_id: &PeerId) -> u64 {
+        self.bbrange_token_costs
+            .get(peer_id)
+            .copied()
+            .unwrap_or_else(|| blocks_by_range_token_cost(MAX_REQUEST_BLOCKS))
+    }
+
     pub fn prune(&mut self) {
         let time_since_start = self.init_time.elapsed();
         self.ping_rl.prune(time_since_start);
@@ -233,6 +320,8 @@ impl RPCRateLimiter {
         self.goodbye_rl.prune(time_since_start);
         self.bbrange_rl.prune(time_since_start);
         self.bbroots_rl.prune(time_since_start);
+        self.blbrange_rl.prune(time_since_start);
+        self.blbroots_rl.prune(time_since_start);
     }
 }
 
@@ -328,7 +417,10 @@ impl<Key: Hash + Eq + Clone> Limiter<Key> {
 
 #[cfg(test)]
 mod tests {
-    use crate::rpc::rate_limiter::{Limiter, Quota};
+    use crate::rpc::rate_limiter::{
+        blocks_by_range_token_cost, Limiter, Quota, BLOCKS_BY_RANGE_QUOTA_TOKENS,
+        MAX_REQUEST_BLOCKS, MAX_REQUEST_BLOCKS_HARD_CAP,
+    };
     use std::time::Duration;
 
     #[test]
@@ -396,4 +488,15 @@ mod tests {
             .allows(Duration::from_secs_f32(0.4), &key, 1)
             .is_err());
     }
+
+    #[test]
+    fn blocks_by_range_token_cost_rounds_up() {
+        assert_eq!(blocks_by_range_token_cost(MAX_REQUEST_BLOCKS), 4);
+        assert_eq!(blocks_by_range_token_cost(MAX_REQUEST_BLOCKS_HARD_CAP), 1);
+        // A peer is never allowed more blocks than it negotiated.
+        for max_request_blocks in MAX_REQUEST_BLOCKS..=MAX_REQUEST_BLOCKS_HARD_CAP {
+            let cost = blocks_by_range_token_cost(max_request_blocks);
+            assert!(BLOCKS_BY_RANGE_QUOTA_TOKENS / cost <= max_request_blocks);
+        }
+    }
 }
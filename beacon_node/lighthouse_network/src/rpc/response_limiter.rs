@@ -0,0 +1,693 @@
+//! Self-imposed rate limiting of the block responses we send to each peer.
+//!
+//! Almost all of our RPC upload goes on responses to `BlocksByRange` and `BlocksByRoot` requests.
+//! Each peer is given a quota of response chunks per protocol, and chunks beyond it are held back
+//! until the quota replenishes, so that a handful of peers syncing from us can't saturate our
+//! upload. The quotas replenish at the same rate as the request quotas of the inbound rate limiter,
+//! so a peer which stays within its request quota is served at the rate it may request, with
+//! smaller bursts.
+//!
+//! Held back responses are released in a fixed order: all of a peer's held back `BlocksByRoot`
+//! responses which fit within its quota are released before any of its `BlocksByRange` responses.
+//! There's no other notion of priority.
+//!
+//! A peer's held back responses are bounded. If too many are held back, or a substream has waited
+//! so long for its next chunk that the peer would time it out, the substream is ended with a
+//! `RateLimited` error rather than holding its responses back any longer.
+
+use super::handler::RESPONSE_TIMEOUT;
+use super::methods::{
+    RPCCodedResponse, RPCResponse, RPCResponseErrorCode, MAX_REQUEST_BLOCKS,
+    MAX_REQUEST_BLOCKS_HARD_CAP,
+};
+use super::rate_limiter::{
+    blocks_by_range_token_cost, Limiter, Quota, RateLimitedErr, BLOCKS_BY_RANGE_QUOTA_TOKENS,
+    BLOCKS_BY_ROOT_QUOTA, BLOCKS_QUOTA_PERIOD,
+};
+use super::{Protocol, SubstreamId};
+use crate::metrics;
+use libp2p::core::connection::ConnectionId;
+use libp2p::PeerId;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::time::Interval;
+use tokio_util::time::DelayQueue;
+use types::EthSpec;
+
+/// The number of `BlocksByRange` response chunks that may be sent to a peer in a burst. Peers with
+/// a larger negotiated `max_request_blocks` may be sent proportionally more.
+const BLOCKS_BY_RANGE_RESPONSE_BURST: u64 = 128;
+/// The number of `BlocksByRoot` response chunks that may be sent to a peer in a burst.
+const BLOCKS_BY_ROOT_RESPONSE_BURST: u64 = BLOCKS_BY_ROOT_QUOTA;
+/// The maximum number of response chunks held back for a peer, enough for a full `BlocksByRange`
+/// response at the spec's `MAX_REQUEST_BLOCKS`. Peers with a larger negotiated `max_request_blocks`
+/// may have a full response of their limit held back. Beyond it, the substream which has waited
+/// longest is ended with an error.
+const MAX_DELAYED_RESPONSES_PER_PEER: usize = MAX_REQUEST_BLOCKS as usize;
+/// The longest a substream may wait for its next held back chunk to be sent before it is ended
+/// with an error.
+///
+/// The handler times out a substream if `RESPONSE_TIMEOUT` passes between two of its chunks, so this
+/// leaves time for the chunk to be sent once it's released.
+const MAX_RESPONSE_DELAY: Duration = Duration::from_secs(RESPONSE_TIMEOUT / 2);
+
+/// A response chunk, along with the peer and inbound substream it is sent on.
+pub(crate) struct QueuedResponse<TSpec: EthSpec> {
+    pub peer_id: PeerId,
+    pub id: (ConnectionId, SubstreamId),
+    pub response: RPCCodedResponse<TSpec>,
+}
+
+/// Rate limits the block responses sent to each peer, holding back those which exceed the quota.
+pub(crate) struct ResponseLimiter<TSpec: EthSpec> {
+    quotas: ResponseQuotas,
+    /// Responses held back for each peer.
+    delayed: HashMap<PeerId, DelayedResponses<TSpec>>,
+    /// Substreams which have been ended with an error, whose remaining responses are discarded.
+    dropped_substreams: HashSet<(ConnectionId, SubstreamId)>,
+    /// Peers whose held back responses may be sent once their quota has replenished.
+    next_peer_ready: DelayQueue<PeerId>,
+    /// Held back responses which may now be sent.
+    ready: VecDeque<QueuedResponse<TSpec>>,
+    /// Interval to prune peers for which their quota has fully replenished.
+    prune_interval: Interval,
+}
+
+struct ResponseQuotas {
+    /// Creation time of the rate limiter.
+    init_time: Instant,
+    /// BlocksByRange response rate limiter.
+    bbrange_rl: Limiter<PeerId>,
+    /// BlocksByRoot response rate limiter.
+    bbroots_rl: Limiter<PeerId>,
+    /// The `BlocksByRange` token cost of peers with a negotiated `max_request_blocks` larger than
+    /// the spec's.
+    bbrange_token_costs: HashMap<PeerId, u64>,
+}
+
+struct DelayedResponses<TSpec: EthSpec> {
+    /// Held back `BlocksByRoot` responses, in the order they were sent.
+    bbroots: VecDeque<QueuedResponse<TSpec>>,
+    /// Held back `BlocksByRange` responses, in the order they were sent.
+    bbrange: VecDeque<QueuedResponse<TSpec>>,
+    /// The substreams with held back responses.
+    ///
+    /// Every later response on one of these substreams is held back too, so that they are all sent
+    /// in order.
+    substreams: HashMap<(ConnectionId, SubstreamId), DelayedSubstream>,
+    /// Whether the peer is waiting in `next_peer_ready`.
+    scheduled: bool,
+}
+
+struct DelayedSubstream {
+    protocol: Protocol,
+    /// The number of responses held back.
+    count: usize,
+    /// When the substream was first held back, or its last held back response was released.
+    last_progress: Instant,
+}
+
+/// Why a substream was ended with an error.
+#[derive(Clone, Copy)]
+enum DropReason {
+    QueueFull,
+    Timeout,
+}
+
+impl DropReason {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DropReason::QueueFull => "queue_full",
+            DropReason::Timeout => "timeout",
+        }
+    }
+}
+
+impl<TSpec: EthSpec> ResponseLimiter<TSpec> {
+    pub fn new() -> Result<Self, &'static str> {
+        // check for peers to prune every 30 seconds, starting in 30 seconds
+        let prune_every = tokio::time::Duration::from_secs(30);
+        let prune_start = tokio::time::Instant::now() + prune_every;
+
+        let (bbrange_tokens, bbrange_period) = blocks_by_range_response_quota();
+        let (bbroots_tokens, bbroots_period) = blocks_by_root_response_quota();
+
+        Ok(ResponseLimiter {
+            quotas: ResponseQuotas {
+                init_time: Instant::now(),
+                bbrange_rl: Limiter::from_quota(Quota::n_every(bbrange_tokens, bbrange_period))?,
+                bbroots_rl: Limiter::from_quota(Quota::n_every(bbroots_tokens, bbroots_period))?,
+                bbrange_token_costs: HashMap::new(),
+            },
+            delayed: HashMap::new(),
+            dropped_substreams: HashSet::new(),
+            next_peer_ready: DelayQueue::new(),
+            ready: VecDeque::new(),
+            prune_interval: tokio::time::interval_at(prune_start, prune_every),
+        })
+    }
+
+    /// Returns the `response` if it can be sent now, otherwise holds it back to be returned from
+    /// `poll_ready` once it can.
+    pub fn allows(&mut self, response: QueuedResponse<TSpec>) -> Option<QueuedResponse<TSpec>> {
+        if self.dropped_substreams.contains(&response.id) {
+            // The substream has already been ended with an error. Forget it once the rest of its
+            // responses have been discarded.
+            if !matches!(response.response, RPCCodedResponse::Success(_)) {
+                self.dropped_substreams.remove(&response.id);
+            }
+            return None;
+        }
+
+        if let Some(delayed) = self.delayed.get_mut(&response.peer_id) {
+            if let Some(substream) = delayed.substreams.get(&response.id) {
+                let protocol = substream.protocol;
+                let peer_id = response.peer_id;
+                delayed.push(protocol, response, Instant::now());
+                self.enforce_queue_limit(peer_id);
+                return None;
+            }
+        }
+
+        let protocol = match limited_protocol(&response.response) {
+            Some(protocol) => protocol,
+            None => return Some(response),
+        };
+        match self.quotas.allows(protocol, &response.peer_id) {
+            Ok(()) => Some(response),
+            Err(wait_time) => {
+                metrics::inc_counter_vec(&metrics::RPC_RESPONSES_DELAYED, &[&protocol.to_string()]);
+                let peer_id = response.peer_id;
+                let delayed = self
+                    .delayed
+                    .entry(peer_id)
+                    .or_insert_with(DelayedResponses::new);
+                delayed.push(protocol, response, Instant::now());
+                if !delayed.scheduled {
+                    delayed.scheduled = true;
+                    self.next_peer_ready.insert(peer_id, wait_time);
+                }
+                self.enforce_queue_limit(peer_id);
+                None
+            }
+        }
+    }
+
+    /// Sets the negotiated `max_request_blocks` of a connected peer.
+    pub fn set_max_request_blocks(&mut self, peer_id: PeerId, max_request_blocks: u64) {
+        if max_request_blocks > MAX_REQUEST_BLOCKS {
+            self.quotas
+                .bbrange_token_costs
+                .insert(peer_id, blocks_by_range_token_cost(max_request_blocks));
+        } else {
+            self.quotas.bbrange_token_costs.remove(&peer_id);
+        }
+    }
+
+    /// Forgets the negotiated limits of a disconnected peer.
+    pub fn remove_peer(&mut self, peer_id: &PeerId) {
+        self.quotas.bbrange_token_costs.remove(peer_id);
+    }
+
+    /// Drops all responses held back for a connection which has closed.
+    pub fn connection_closed(&mut self, peer_id: &PeerId, conn_id: &ConnectionId) {
+        if let Some(delayed) = self.delayed.get_mut(peer_id) {
+            delayed.bbroots.retain(|response| response.id.0 != *conn_id);
+            delayed.bbrange.retain(|response| response.id.0 != *conn_id);
+            delayed.substreams.retain(|id, _| id.0 != *conn_id);
+            // The peer is removed once it is next ready and has nothing left to send.
+        }
+        self.dropped_substreams.retain(|id| id.0 != *conn_id);
+    }
+
+    /// Ends the substreams which have waited longest until no more responses are held back for
+    /// the peer than its limit allows.
+    fn enforce_queue_limit(&mut self, peer_id: PeerId) {
+        let max_delayed = self.quotas.max_delayed_responses(&peer_id);
+        loop {
+            let oldest = match self.delayed.get(&peer_id) {
+                Some(delayed) if delayed.len() > max_delayed => delayed
+                    .substreams
+                    .iter()
+                    .min_by_key(|(_, substream)| substream.last_progress)
+                    .map(|(id, _)| *id),
+                _ => None,
+            };
+            match oldest {
+                Some(id) => self.drop_substream(peer_id, id, DropReason::QueueFull),
+                None => break,
+            }
+        }
+    }
+
+    /// Ends the substreams of the peer which have waited longer than `MAX_RESPONSE_DELAY` for
+    /// their next held back response.
+    fn drop_stalled_substreams(&mut self, peer_id: PeerId, now: Instant) {
+        let stalled = match self.delayed.get(&peer_id) {
+            Some(delayed) => delayed
+                .substreams
+                .iter()
+                .filter(|(_, substream)| {
+                    now.saturating_duration_since(substream.last_progress) > MAX_RESPONSE_DELAY
+                })
+                .map(|(id, _)| *id)
+                .collect::<Vec<_>>(),
+            None => return,
+        };
+        for id in stalled {
+            self.drop_substream(peer_id, id, DropReason::Timeout);
+        }
+    }
+
+    /// Discards the held back responses of a substream and ends it with a `RateLimited` error.
+    fn drop_substream(
+        &mut self,
+        peer_id: PeerId,
+        id: (ConnectionId, SubstreamId),
+        reason: DropReason,
+    ) {
+        let delayed = match self.delayed.get_mut(&peer_id) {
+            Some(delayed) => delayed,
+            None => return,
+        };
+        let substream = match delayed.substreams.remove(&id) {
+            Some(substream) => substream,
+            None => return,
+        };
+        let queue = delayed.queue(substream.protocol);
+        // If the stream's termination was held back too, no more of its responses will follow.
+        let complete = queue.iter().any(|response| {
+            response.id == id && !matches!(response.response, RPCCodedResponse::Success(_))
+        });
+        queue.retain(|response| response.id != id);
+
+        metrics::inc_counter_vec(
+            &metrics::RPC_RESPONSE_SUBSTREAMS_DROPPED,
+            &[reason.as_str()],
+        );
+        if !complete {
+            self.dropped_substreams.insert(id);
+        }
+        self.ready.push_back(QueuedResponse {
+            peer_id,
+            id,
+            response: RPCCodedResponse::Error(
+                RPCResponseErrorCode::RateLimited,
+                "Response rate limited".into(),
+            ),
+        });
+    }
+
+    /// Sends as many of a peer's held back responses as its quota allows.
+    ///
+    /// `BlocksByRoot` responses are released before `BlocksByRange` ones, but otherwise each
+    /// protocol's responses are released in the order they were sent.
+    fn release(&mut self, peer_id: PeerId) {
+        let now = Instant::now();
+        self.drop_stalled_substreams(peer_id, now);
+
+        let delayed = match self.delayed.get_mut(&peer_id) {
+            Some(delayed) => delayed,
+            None => return,
+        };
+        delayed.scheduled = false;
+
+        let mut next_wait_time: Option<Duration> = None;
+        for protocol in [Protocol::BlocksByRoot, Protocol::BlocksByRange] {
+            while let Some(response) = delayed.queue(protocol).front() {
+                if limited_protocol(&response.response).is_some() {
+                    if let Err(wait_time) = self.quotas.allows(protocol, &peer_id) {
+                        next_wait_time =
+                            Some(next_wait_time.map_or(wait_time, |next| next.min(wait_time)));
+                        break;
+                    }
+                }
+                if let Some(response) = delayed.pop(protocol, now) {
+                    self.ready.push_back(response);
+                }
+            }
+        }
+
+        match next_wait_time {
+            Some(wait_time) => {
+                delayed.scheduled = true;
+                self.next_peer_ready.insert(peer_id, wait_time);
+            }
+            None => {
+                self.delayed.remove(&peer_id);
+            }
+        }
+    }
+
+    /// Returns the next held back response which may now be sent.
+    pub fn poll_ready(&mut self, cx: &mut Context) -> Poll<QueuedResponse<TSpec>> {
+        while self.prune_interval.poll_tick(cx).is_ready() {
+            self.quotas.prune();
+        }
+
+        while let Poll::Ready(Some(Ok(expired))) = self.next_peer_ready.poll_expired(cx) {
+            self.release(expired.into_inner());
+        }
+
+        match self.ready.pop_front() {
+            Some(response) => Poll::Ready(response),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl ResponseQuotas {
+    /// Checks whether one more response chunk of `protocol` may be sent to the peer, otherwise
+    /// returns how long until it can.
+    fn allows(&mut self, protocol: Protocol, peer_id: &PeerId) -> Result<(), Duration> {
+        let time_since_start = self.init_time.elapsed();
+        let (limiter, tokens) = match protocol {
+            Protocol::BlocksByRoot => (&mut self.bbroots_rl, 1),
+            _ => {
+                let tokens = self.bbrange_token_cost(peer_id);
+                (&mut self.bbrange_rl, tokens)
+            }
+        };
+        match limiter.allows(time_since_start, peer_id, tokens) {
+            Ok(()) => Ok(()),
+            Err(RateLimitedErr::TooSoon(wait_time)) => Err(wait_time),
+            // A single chunk always fits within a quota.
+            Err(RateLimitedErr::TooLarge) => Ok(()),
+        }
+    }
+
+    fn bbrange_token_cost(&self, peer_id: &PeerId) -> u64 {
+        self.bbrange_token_costs
+            .get(peer_id)
+            .copied()
+            .unwrap_or_else(|| blocks_by_range_token_cost(MAX_REQUEST_BLOCKS))
+    }
+
+    /// The number of response chunks which may be held back for the peer.
+    fn max_delayed_responses(&self, peer_id: &PeerId) -> usize {
+        let max_request_blocks = MAX_REQUEST_BLOCKS_HARD_CAP / self.bbrange_token_cost(peer_id);
+        (max_request_blocks as usize).max(MAX_DELAYED_RESPONSES_PER_PEER)
+    }
+
+    fn prune(&mut self) {
+        let time_since_start = self.init_time.elapsed();
+        self.bbrange_rl.prune(time_since_start);
+        self.bbroots_rl.prune(time_since_start);
+    }
+}
+
+impl<TSpec: EthSpec> DelayedResponses<TSpec> {
+    fn new() -> Self {
+        DelayedResponses {
+            bbroots: VecDeque::new(),
+            bbrange: VecDeque::new(),
+            substreams: HashMap::new(),
+            scheduled: false,
+        }
+    }
+
+    /// The number of held back responses.
+    fn len(&self) -> usize {
+        self.bbroots.len() + self.bbrange.len()
+    }
+
+    fn queue(&mut self, protocol: Protocol) -> &mut VecDeque<QueuedResponse<TSpec>> {
+        match protocol {
+            Protocol::BlocksByRoot => &mut self.bbroots,
+            _ => &mut self.bbrange,
+        }
+    }
+
+    fn push(&mut self, protocol: Protocol, response: QueuedResponse<TSpec>, now: Instant) {
+        self.substreams
+            .entry(response.id)
+            .or_insert(DelayedSubstream {
+                protocol,
+                count: 0,
+                last_progress: now,
+            })
+            .count += 1;
+        self.queue(protocol).push_back(response);
+    }
+
+    fn pop(&mut self, protocol: Protocol, now: Instant) -> Option<QueuedResponse<TSpec>> {
+        let response = self.queue(protocol).pop_front()?;
+        if let Some(substream) = self.substreams.get_mut(&response.id) {
+            substream.count -= 1;
+            substream.last_progress = now;
+            if substream.count == 0 {
+                self.substreams.remove(&response.id);
+            }
+        }
+        Some(response)
+    }
+}
+
+/// Returns the `BlocksByRange` response quota, as a burst of tokens and the period over which they
+/// replenish, which replenishes at the same rate as the `BlocksByRange` request quota.
+fn blocks_by_range_response_quota() -> (u64, Duration) {
+    let tokens = BLOCKS_BY_RANGE_RESPONSE_BURST * blocks_by_range_token_cost(MAX_REQUEST_BLOCKS);
+    let period = BLOCKS_QUOTA_PERIOD * tokens as u32 / BLOCKS_BY_RANGE_QUOTA_TOKENS as u32;
+    (tokens, period)
+}
+
+/// Returns the `BlocksByRoot` response quota, which replenishes at the same rate as the
+/// `BlocksByRoot` request quota.
+fn blocks_by_root_response_quota() -> (u64, Duration) {
+    let tokens = BLOCKS_BY_ROOT_RESPONSE_BURST;
+    let period = BLOCKS_QUOTA_PERIOD * tokens as u32 / BLOCKS_BY_ROOT_QUOTA as u32;
+    (tokens, period)
+}
+
+/// The protocol of a response chunk which counts towards a peer's quota, if any.
+///
+/// Stream terminations and errors don't count towards the quota, but are still held back behind
+/// any earlier chunks on the same substream.
+fn limited_protocol<TSpec: EthSpec>(response: &RPCCodedResponse<TSpec>) -> Option<Protocol> {
+    match response {
+        RPCCodedResponse::Success(RPCResponse::BlocksByRange(_)) => Some(Protocol::BlocksByRange),
+        RPCCodedResponse::Success(RPCResponse::BlocksByRoot(_)) => Some(Protocol::BlocksByRoot),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::methods::ResponseTermination;
+    use types::{BeaconBlock, MainnetEthSpec, Signature, SignedBeaconBlock};
+
+    type E = MainnetEthSpec;
+
+    fn block_response(
+        peer_id: PeerId,
+        substream_id: usize,
+        protocol: Protocol,
+    ) -> QueuedResponse<E> {
+        let block = Box::new(SignedBeaconBlock::from_block(
+            BeaconBlock::empty(&E::default_spec()),
+            Signature::empty(),
+        ));
+        let response = match protocol {
+            Protocol::BlocksByRoot => RPCResponse::BlocksByRoot(block),
+            _ => RPCResponse::BlocksByRange(block),
+        };
+        QueuedResponse {
+            peer_id,
+            id: (ConnectionId::new(0), SubstreamId::new(substream_id)),
+            response: RPCCodedResponse::Success(response),
+        }
+    }
+
+    fn termination(peer_id: PeerId, substream_id: usize) -> QueuedResponse<E> {
+        QueuedResponse {
+            peer_id,
+            id: (ConnectionId::new(0), SubstreamId::new(substream_id)),
+            response: RPCCodedResponse::StreamTermination(ResponseTermination::BlocksByRange),
+        }
+    }
+
+    #[tokio::test]
+    async fn holds_back_responses_over_quota_in_order() {
+        let mut limiter = ResponseLimiter::<E>::new().unwrap();
+        let peer_id = PeerId::random();
+        let other_peer_id = PeerId::random();
+
+        for _ in 0..BLOCKS_BY_RANGE_RESPONSE_BURST {
+            assert!(limiter
+                .allows(block_response(peer_id, 0, Protocol::BlocksByRange))
+                .is_some());
+        }
+        // The quota is used up, so the next chunk and the termination behind it are held back.
+        assert!(limiter
+            .allows(block_response(peer_id, 0, Protocol::BlocksByRange))
+            .is_none());
+        assert!(limiter.allows(termination(peer_id, 0)).is_none());
+
+        // Other protocols and peers have their own quotas.
+        assert!(limiter
+            .allows(block_response(peer_id, 1, Protocol::BlocksByRoot))
+            .is_some());
+        assert!(limiter
+            .allows(block_response(other_peer_id, 0, Protocol::BlocksByRange))
+            .is_some());
+
+        let chunk = futures::future::poll_fn(|cx| limiter.poll_ready(cx)).await;
+        assert!(matches!(
+            chunk.response,
+            RPCCodedResponse::Success(RPCResponse::BlocksByRange(_))
+        ));
+        let termination = futures::future::poll_fn(|cx| limiter.poll_ready(cx)).await;
+        assert!(matches!(
+            termination.response,
+            RPCCodedResponse::StreamTermination(_)
+        ));
+        assert!(limiter.delayed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn releases_blocks_by_root_first() {
+        let mut limiter = ResponseLimiter::<E>::new().unwrap();
+        let peer_id = PeerId::random();
+
+        for protocol in [Protocol::BlocksByRange, Protocol::BlocksByRoot] {
+            for _ in 0..BLOCKS_BY_RANGE_RESPONSE_BURST.max(BLOCKS_BY_ROOT_RESPONSE_BURST) {
+                limiter.allows(block_response(peer_id, 0, protocol));
+            }
+        }
+        assert!(limiter
+            .allows(block_response(peer_id, 0, Protocol::BlocksByRange))
+            .is_none());
+        assert!(limiter
+            .allows(block_response(peer_id, 1, Protocol::BlocksByRoot))
+            .is_none());
+
+        // Wait until both quotas have replenished enough for a chunk, so that both chunks are
+        // released together.
+        tokio::time::sleep(2 * BLOCKS_QUOTA_PERIOD / BLOCKS_BY_ROOT_QUOTA as u32).await;
+        let first = futures::future::poll_fn(|cx| limiter.poll_ready(cx)).await;
+        assert!(matches!(
+            first.response,
+            RPCCodedResponse::Success(RPCResponse::BlocksByRoot(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn larger_negotiated_limit_increases_quota() {
+        let mut limiter = ResponseLimiter::<E>::new().unwrap();
+        let peer_id = PeerId::random();
+        limiter.set_max_request_blocks(peer_id, 2 * MAX_REQUEST_BLOCKS);
+
+        for _ in 0..2 * BLOCKS_BY_RANGE_RESPONSE_BURST {
+            assert!(limiter
+                .allows(block_response(peer_id, 0, Protocol::BlocksByRange))
+                .is_some());
+        }
+        assert!(limiter
+            .allows(block_response(peer_id, 0, Protocol::BlocksByRange))
+            .is_none());
+
+        // The peer may have a full response of its limit held back.
+        assert_eq!(
+            limiter.quotas.max_delayed_responses(&peer_id),
+            2 * MAX_DELAYED_RESPONSES_PER_PEER
+        );
+
+        // The peer is back to the default quota once it disconnects.
+        limiter.remove_peer(&peer_id);
+        assert!(limiter.quotas.bbrange_token_costs.is_empty());
+    }
+
+    #[test]
+    fn response_quotas_agree_with_request_quotas() {
+        let rate = |(tokens, period): (u64, Duration)| tokens as f64 / period.as_secs_f64();
+        let request_rate = |tokens: u64| tokens as f64 / BLOCKS_QUOTA_PERIOD.as_secs_f64();
+
+        assert_eq!(
+            rate(blocks_by_range_response_quota()),
+            request_rate(BLOCKS_BY_RANGE_QUOTA_TOKENS)
+        );
+        assert_eq!(
+            rate(blocks_by_root_response_quota()),
+            request_rate(BLOCKS_BY_ROOT_QUOTA)
+        );
+    }
+
+    #[tokio::test]
+    async fn full_queue_ends_oldest_substream() {
+        let mut limiter = ResponseLimiter::<E>::new().unwrap();
+        let peer_id = PeerId::random();
+
+        for _ in 0..BLOCKS_BY_RANGE_RESPONSE_BURST {
+            limiter.allows(block_response(peer_id, 0, Protocol::BlocksByRange));
+        }
+        // Hold back a chunk on each substream before the quota can replenish, then fill the queue.
+        for substream_id in 0..2 {
+            assert!(limiter
+                .allows(block_response(
+                    peer_id,
+                    substream_id,
+                    Protocol::BlocksByRange
+                ))
+                .is_none());
+        }
+        for substream_id in 0..2 {
+            for _ in 1..MAX_DELAYED_RESPONSES_PER_PEER / 2 {
+                limiter.allows(block_response(
+                    peer_id,
+                    substream_id,
+                    Protocol::BlocksByRange,
+                ));
+            }
+        }
+        assert!(limiter.ready.is_empty());
+
+        // One more chunk exceeds the queue, so the substream held back first is ended.
+        limiter.allows(block_response(peer_id, 1, Protocol::BlocksByRange));
+        let error = limiter.ready.pop_front().unwrap();
+        assert_eq!(error.id.1, SubstreamId::new(0));
+        assert!(matches!(
+            error.response,
+            RPCCodedResponse::Error(RPCResponseErrorCode::RateLimited, _)
+        ));
+        assert_eq!(
+            limiter.delayed[&peer_id].len(),
+            MAX_DELAYED_RESPONSES_PER_PEER / 2 + 1
+        );
+
+        // The rest of the ended substream is discarded, up to and including its termination.
+        assert!(limiter
+            .allows(block_response(peer_id, 0, Protocol::BlocksByRange))
+            .is_none());
+        assert!(limiter.allows(termination(peer_id, 0)).is_none());
+        assert!(limiter.dropped_substreams.is_empty());
+        assert!(limiter.ready.is_empty());
+    }
+
+    #[tokio::test]
+    async fn stalled_substream_is_ended() {
+        let mut limiter = ResponseLimiter::<E>::new().unwrap();
+        let peer_id = PeerId::random();
+
+        for _ in 0..BLOCKS_BY_RANGE_RESPONSE_BURST {
+            limiter.allows(block_response(peer_id, 0, Protocol::BlocksByRange));
+        }
+        assert!(limiter
+            .allows(block_response(peer_id, 0, Protocol::BlocksByRange))
+            .is_none());
+
+        limiter.drop_stalled_substreams(peer_id, Instant::now());
+        assert!(limiter.ready.is_empty());
+
+        limiter.drop_stalled_substreams(
+            peer_id,
+            Instant::now() + MAX_RESPONSE_DELAY + Duration::from_secs(1),
+        );
+        assert!(matches!(
+            limiter.ready.pop_front().unwrap().response,
+            RPCCodedResponse::Error(RPCResponseErrorCode::RateLimited, _)
+        ));
+        assert_eq!(limiter.delayed[&peer_id].len(), 0);
+    }
+}
@@ -159,29 +159,33 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Service<AppReqId, TSpec> {
             )
         };
 
-        // listen on the specified address
-        let listen_multiaddr = {
-            let mut m = Multiaddr::from(config.listen_address);
-            m.push(Protocol::Tcp(config.libp2p_port));
-            m
-        };
+        if config.disable_inbound {
+            info!(log, "Inbound connections disabled, only dialing peers");
+        } else {
+            // listen on the specified address
+            let listen_multiaddr = {
+                let mut m = Multiaddr::from(config.listen_address);
+                m.push(Protocol::Tcp(config.libp2p_port));
+                m
+            };
 
-        match Swarm::listen_on(&mut swarm, listen_multiaddr.clone()) {
-            Ok(_) => {
-                let mut log_address = listen_multiaddr;
-                log_address.push(Protocol::P2p(local_peer_id.into()));
-                info!(log, "Listening established"; "address" => %log_address);
-            }
-            Err(err) => {
-                crit!(
-                    log,
-                    "Unable to listen on libp2p address";
-                    "error" => ?err,
-                    "listen_multiaddr" => %listen_multiaddr,
-                );
-                return Err("Libp2p was unable to listen on the given listen address.".into());
-            }
-        };
+            match Swarm::listen_on(&mut swarm, listen_multiaddr.clone()) {
+                Ok(_) => {
+                    let mut log_address = listen_multiaddr;
+                    log_address.push(Protocol::P2p(local_peer_id.into()));
+                    info!(log, "Listening established"; "address" => %log_address);
+                }
+                Err(err) => {
+                    crit!(
+                        log,
+                        "Unable to listen on libp2p address";
+                        "error" => ?err,
+                        "listen_multiaddr" => %listen_multiaddr,
+                    );
+                    return Err("Libp2p was unable to listen on the given listen address.".into());
+                }
+            };
+        }
 
         // helper closure for dialing peers
         let mut dial = |mut multiaddr: Multiaddr| {
@@ -196,8 +200,13 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Service<AppReqId, TSpec> {
             };
         };
 
-        // attempt to connect to user-input libp2p nodes
-        for multiaddr in &config.libp2p_nodes {
+        // attempt to connect to user-input libp2p nodes, trusted peers and allowlisted peers
+        for multiaddr in config
+            .libp2p_nodes
+            .iter()
+            .chain(&config.trusted_peer_addresses)
+            .chain(&config.peer_allowlist_addresses)
+        {
             dial(multiaddr.clone());
         }
 
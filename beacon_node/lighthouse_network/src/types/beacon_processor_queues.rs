@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// The work waiting in the beacon processor, as reported by the HTTP API.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BeaconProcessorQueues {
+    /// The number of workers currently processing work.
+    pub active_workers: usize,
+    pub max_workers: usize,
+    pub queues: Vec<BeaconProcessorQueueStatus>,
+}
+
+/// One of the queues of the beacon processor.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BeaconProcessorQueueStatus {
+    /// The name of the queue, which is the type of work it holds.
+    pub name: String,
+    pub length: usize,
+    pub max_length: usize,
+    /// The number of items dropped because the queue was full, since the node started.
+    pub dropped: u64,
+}
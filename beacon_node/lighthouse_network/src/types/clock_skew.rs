@@ -0,0 +1,115 @@
+//! Estimates the offset of our clock from the clocks of the rest of the network.
+//!
+//! Nodes don't exchange timestamps, but honest aggregators publish their aggregates two thirds of
+//! the way through the slot. The time at which an aggregate arrives, relative to when our clock
+//! says it was published, is therefore a sample of our clock's offset (plus the propagation delay
+//! of gossip). Only the latest sample of each peer is kept and the estimate is their median, so a
+//! minority of peers with bad clocks or relaying old messages can't move it.
+
+use crate::PeerId;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// The number of peers whose latest sample is kept.
+const MAX_SAMPLE_PEERS: usize = 128;
+/// The minimum number of peers with recent samples required to estimate the offset.
+const MIN_SAMPLE_PEERS: usize = 8;
+/// Samples older than this are not used in the estimate.
+const SAMPLE_EXPIRY: Duration = Duration::from_secs(10 * 60);
+/// The estimated offset beyond which our clock is suspected to be skewed. The estimate includes the
+/// propagation delay of gossip, so this is generous.
+pub const CLOCK_SKEW_WARN_THRESHOLD_MS: i64 = 1_000;
+
+/// The latest arrival time offsets of gossip messages from each peer.
+pub struct ClockSkewSamples {
+    samples: LruCache<PeerId, (i64, Instant)>,
+}
+
+impl Default for ClockSkewSamples {
+    fn default() -> Self {
+        Self {
+            samples: LruCache::new(MAX_SAMPLE_PEERS),
+        }
+    }
+}
+
+impl ClockSkewSamples {
+    /// Records that a message from `peer_id` arrived `offset_ms` milliseconds after it was
+    /// published according to our clock. The offset is negative if it arrived before.
+    pub fn record(&mut self, peer_id: PeerId, offset_ms: i64) {
+        self.samples.put(peer_id, (offset_ms, Instant::now()));
+    }
+
+    /// Returns the current estimate of our clock's offset.
+    pub fn status(&self) -> ClockSkewStatus {
+        self.status_at(Instant::now())
+    }
+
+    fn status_at(&self, now: Instant) -> ClockSkewStatus {
+        let mut offsets = self
+            .samples
+            .iter()
+            .filter(|(_, (_, seen))| now.saturating_duration_since(*seen) <= SAMPLE_EXPIRY)
+            .map(|(_, (offset_ms, _))| *offset_ms)
+            .collect::<Vec<_>>();
+        offsets.sort_unstable();
+
+        let estimated_offset_ms = if offsets.len() >= MIN_SAMPLE_PEERS {
+            Some(offsets[offsets.len() / 2])
+        } else {
+            None
+        };
+        ClockSkewStatus {
+            estimated_offset_ms,
+            sample_peers: offsets.len(),
+            skew_suspected: estimated_offset_ms
+                .map_or(false, |offset| offset.abs() > CLOCK_SKEW_WARN_THRESHOLD_MS),
+        }
+    }
+}
+
+/// The estimated offset of our clock, as reported by the HTTP API.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClockSkewStatus {
+    /// The median delay in milliseconds between when aggregates were published according to our
+    /// clock and when they arrived. Positive if our clock is ahead of the network's (or gossip is
+    /// slow), negative if it is behind. `None` until enough peers have been sampled.
+    pub estimated_offset_ms: Option<i64>,
+    /// The number of peers with a recent sample.
+    pub sample_peers: usize,
+    /// Whether the estimated offset exceeds `CLOCK_SKEW_WARN_THRESHOLD_MS`.
+    pub skew_suspected: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimate_is_median_of_recent_peers() {
+        let mut samples = ClockSkewSamples::default();
+        for _ in 0..MIN_SAMPLE_PEERS - 1 {
+            samples.record(PeerId::random(), -2_000);
+        }
+        assert_eq!(samples.status().estimated_offset_ms, None);
+
+        // A few peers relaying old messages don't affect the estimate.
+        samples.record(PeerId::random(), 30_000);
+        samples.record(PeerId::random(), 30_000);
+        let status = samples.status();
+        assert_eq!(status.estimated_offset_ms, Some(-2_000));
+        assert_eq!(status.sample_peers, MIN_SAMPLE_PEERS + 1);
+        assert!(status.skew_suspected);
+
+        // Only the latest sample of each peer is kept.
+        let peer_id = PeerId::random();
+        samples.record(peer_id, 100);
+        samples.record(peer_id, 200);
+        assert_eq!(samples.status().sample_peers, MIN_SAMPLE_PEERS + 2);
+
+        // Expired samples are ignored.
+        let later = Instant::now() + SAMPLE_EXPIRY + Duration::from_secs(1);
+        assert_eq!(samples.status_at(later), ClockSkewStatus::default());
+    }
+}
@@ -1,10 +1,15 @@
 //! A collection of variables that are accessible outside of the network thread itself.
+use crate::behaviour::gossipsub_scoring_parameters::GossipsubScores;
 use crate::peer_manager::peerdb::PeerDB;
-use crate::rpc::{MetaData, MetaDataV2};
-use crate::types::{BackFillState, SyncState};
+use crate::rpc::methods::{MAX_REQUEST_BLOCKS, MAX_REQUEST_BLOCKS_HARD_CAP};
+use crate::rpc::{blocks_by_range_token_cost, MetaData, MetaDataV2};
+use crate::types::{
+    BackFillState, BeaconProcessorQueues, ClockSkewSamples, PeerDiversity, PortMappingStatus,
+    SyncLookups, SyncState,
+};
 use crate::Client;
-use crate::EnrExt;
 use crate::{Enr, GossipTopic, Multiaddr, PeerId};
+use crate::{EnrExt, Eth2Enr};
 use parking_lot::RwLock;
 use std::collections::HashSet;
 use std::sync::atomic::{AtomicU16, Ordering};
@@ -31,6 +36,20 @@ pub struct NetworkGlobals<TSpec: EthSpec> {
     pub sync_state: RwLock<SyncState>,
     /// The current state of the backfill sync.
     pub backfill_state: RwLock<BackFillState>,
+    /// The block lookups in progress in sync.
+    pub sync_lookups: RwLock<SyncLookups>,
+    /// The gossipsub scores of connected peers, as of the last score update.
+    pub gossipsub_scores: RwLock<GossipsubScores>,
+    /// The port mappings established on the local gateway via UPnP or NAT-PMP.
+    pub port_mappings: RwLock<PortMappingStatus>,
+    /// The distribution of connected peers across clients and autonomous systems, as of the last
+    /// peer manager heartbeat.
+    pub peer_diversity: RwLock<PeerDiversity>,
+    /// The arrival times of gossip messages from each peer, from which the offset of our clock is
+    /// estimated.
+    pub clock_skew_samples: RwLock<ClockSkewSamples>,
+    /// The work waiting in the beacon processor, as of its last update.
+    pub beacon_processor_queues: RwLock<BeaconProcessorQueues>,
 }
 
 impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
@@ -53,6 +72,12 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
             gossipsub_subscriptions: RwLock::new(HashSet::new()),
             sync_state: RwLock::new(SyncState::Stalled),
             backfill_state: RwLock::new(BackFillState::NotRequired),
+            sync_lookups: RwLock::new(SyncLookups::default()),
+            gossipsub_scores: RwLock::new(GossipsubScores::default()),
+            port_mappings: RwLock::new(PortMappingStatus::default()),
+            peer_diversity: RwLock::new(PeerDiversity::default()),
+            clock_skew_samples: RwLock::new(ClockSkewSamples::default()),
+            beacon_processor_queues: RwLock::new(BeaconProcessorQueues::default()),
         }
     }
 
@@ -121,6 +146,27 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
             .unwrap_or_default()
     }
 
+    /// Returns the largest `BlocksByRange` request that may be sent to or served for the peer.
+    ///
+    /// This is the smaller of the limits advertised in our ENR and the peer's, bounded by
+    /// `MAX_REQUEST_BLOCKS_HARD_CAP` and rounded down so that the `BlocksByRange` quota, which
+    /// charges a whole number of tokens per block, covers a full request. Peers which don't
+    /// advertise a limit, or whose ENR is unknown, are limited to the spec's `MAX_REQUEST_BLOCKS`.
+    pub fn max_request_blocks(&self, peer_id: &PeerId) -> u64 {
+        let peer_max = self
+            .peers
+            .read()
+            .peer_info(peer_id)
+            .and_then(|info| info.enr())
+            .and_then(|enr| enr.max_request_blocks());
+        match (self.local_enr.read().max_request_blocks(), peer_max) {
+            (Some(local_max), Some(peer_max)) => {
+                MAX_REQUEST_BLOCKS_HARD_CAP / blocks_by_range_token_cost(local_max.min(peer_max))
+            }
+            _ => MAX_REQUEST_BLOCKS,
+        }
+    }
+
     /// Updates the syncing state of the node.
     ///
     /// The old state is returned
@@ -130,6 +176,14 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
 
     /// TESTING ONLY. Build a dummy NetworkGlobals instance.
     pub fn new_test_globals(log: &slog::Logger) -> NetworkGlobals<TSpec> {
+        Self::new_test_globals_with_trusted_peers(vec![], log)
+    }
+
+    /// TESTING ONLY. Build a dummy NetworkGlobals instance with the given trusted peers.
+    pub fn new_test_globals_with_trusted_peers(
+        trusted_peers: Vec<PeerId>,
+        log: &slog::Logger,
+    ) -> NetworkGlobals<TSpec> {
         use crate::CombinedKeyExt;
         let keypair = libp2p::identity::Keypair::generate_secp256k1();
         let enr_key: discv5::enr::CombinedKey =
@@ -144,7 +198,7 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
                 attnets: Default::default(),
                 syncnets: Default::default(),
             }),
-            vec![],
+            trusted_peers,
             log,
         )
     }
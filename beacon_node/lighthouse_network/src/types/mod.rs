@@ -1,7 +1,12 @@
+mod beacon_processor_queues;
+mod clock_skew;
 pub mod error;
 mod globals;
+mod peer_diversity;
+mod port_mappings;
 mod pubsub;
 mod subnet;
+mod sync_lookups;
 mod sync_state;
 mod topics;
 
@@ -12,8 +17,16 @@ pub type EnrSyncCommitteeBitfield<T> = BitVector<<T as EthSpec>::SyncCommitteeSu
 
 pub type Enr = discv5::enr::Enr<discv5::enr::CombinedKey>;
 
+pub use beacon_processor_queues::{BeaconProcessorQueueStatus, BeaconProcessorQueues};
+pub use clock_skew::{ClockSkewSamples, ClockSkewStatus, CLOCK_SKEW_WARN_THRESHOLD_MS};
 pub use globals::NetworkGlobals;
+pub use peer_diversity::PeerDiversity;
+pub use port_mappings::{PortMappingMethod, PortMappingStatus};
 pub use pubsub::{PubsubMessage, SnappyTransform};
 pub use subnet::{Subnet, SubnetDiscovery};
+pub use sync_lookups::{LookupState, ParentLookupStatus, SingleBlockLookupStatus, SyncLookups};
 pub use sync_state::{BackFillState, SyncState};
-pub use topics::{subnet_from_topic_hash, GossipEncoding, GossipKind, GossipTopic, CORE_TOPICS};
+pub use topics::{
+    core_topics_to_subscribe, subnet_from_topic_hash, GossipEncoding, GossipKind, GossipTopic,
+    CAPELLA_CORE_TOPICS, CORE_TOPICS, LIGHT_CLIENT_GOSSIP_TOPICS,
+};
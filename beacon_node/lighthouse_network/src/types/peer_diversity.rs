@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The distribution of our connected peers across clients and autonomous systems, as reported by
+/// the HTTP API.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PeerDiversity {
+    /// The number of connected peers.
+    pub connected_peers: usize,
+    /// The number of connected peers running each client.
+    pub clients: BTreeMap<String, usize>,
+    /// The number of connected peers in each autonomous system. Empty unless an ASN database has
+    /// been configured.
+    pub asns: BTreeMap<u32, usize>,
+    /// The number of connected peers whose autonomous system is unknown.
+    pub unknown_asn: usize,
+    /// The most peers we keep from any one client when pruning, if limited.
+    pub max_peers_per_client: Option<usize>,
+    /// The most peers we keep from any one autonomous system when pruning, if limited.
+    pub max_peers_per_asn: Option<usize>,
+}
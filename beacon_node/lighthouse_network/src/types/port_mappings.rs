@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+/// The protocol used to map our listening ports on the local gateway.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PortMappingMethod {
+    Upnp,
+    NatPmp,
+}
+
+/// The port mappings established on the local gateway, as reported by the HTTP API.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PortMappingStatus {
+    /// The protocol the mappings were established with, `None` if no gateway supporting UPnP or
+    /// NAT-PMP was found.
+    pub method: Option<PortMappingMethod>,
+    /// The external IP address of the gateway, if it reported one.
+    pub external_ip: Option<IpAddr>,
+    /// The external port mapped to our libp2p TCP port.
+    pub tcp_port: Option<u16>,
+    /// The external port mapped to our discovery UDP port.
+    pub udp_port: Option<u16>,
+    /// The time the mappings were last established or renewed, in seconds since the UNIX epoch.
+    pub last_renewed: Option<u64>,
+    /// Why the last attempt to establish the mappings failed, if it did.
+    pub error: Option<String>,
+}
+
+impl PortMappingStatus {
+    /// Returns `true` if at least one of our ports is mapped.
+    pub fn is_mapped(&self) -> bool {
+        self.tcp_port.is_some() || self.udp_port.is_some()
+    }
+}
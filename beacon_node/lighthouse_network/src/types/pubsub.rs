@@ -7,11 +7,14 @@ use snap::raw::{decompress_len, Decoder, Encoder};
 use ssz::{Decode, Encode};
 use std::boxed::Box;
 use std::io::{Error, ErrorKind};
+use tracing::debug_span;
 use types::{
-    Attestation, AttesterSlashing, EthSpec, ForkContext, ForkName, ProposerSlashing,
+    AttestationSszView, AttesterSlashing, EthSpec, ForkContext, ForkName,
+    LightClientFinalityUpdate, LightClientOptimisticUpdate, ProposerSlashing,
     SignedAggregateAndProof, SignedBeaconBlock, SignedBeaconBlockAltair, SignedBeaconBlockBase,
-    SignedBeaconBlockMerge, SignedContributionAndProof, SignedVoluntaryExit, SubnetId,
-    SyncCommitteeMessage, SyncSubnetId,
+    SignedBeaconBlockCapella, SignedBeaconBlockDeneb, SignedBeaconBlockMerge,
+    SignedBeaconBlockSszView, SignedBlsToExecutionChange, SignedContributionAndProof,
+    SignedVoluntaryExit, SubnetId, SyncCommitteeMessage, SyncSubnetId,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -32,6 +35,12 @@ pub enum PubsubMessage<T: EthSpec> {
     SignedContributionAndProof(Box<SignedContributionAndProof<T>>),
     /// Gossipsub message providing notification of unaggregated sync committee signatures with its subnet id.
     SyncCommitteeMessage(Box<(SyncSubnetId, SyncCommitteeMessage)>),
+    /// Gossipsub message providing notification of a light client finality update.
+    LightClientFinalityUpdate(Box<LightClientFinalityUpdate<T>>),
+    /// Gossipsub message providing notification of a light client optimistic update.
+    LightClientOptimisticUpdate(Box<LightClientOptimisticUpdate<T>>),
+    /// Gossipsub message providing notification of a change of withdrawal credentials.
+    BlsToExecutionChange(Box<SignedBlsToExecutionChange>),
 }
 
 // Implements the `DataTransform` trait of gossipsub to employ snappy compression
@@ -114,6 +123,11 @@ impl<T: EthSpec> PubsubMessage<T> {
             PubsubMessage::AttesterSlashing(_) => GossipKind::AttesterSlashing,
             PubsubMessage::SignedContributionAndProof(_) => GossipKind::SignedContributionAndProof,
             PubsubMessage::SyncCommitteeMessage(data) => GossipKind::SyncCommitteeMessage(data.0),
+            PubsubMessage::LightClientFinalityUpdate(_) => GossipKind::LightClientFinalityUpdate,
+            PubsubMessage::LightClientOptimisticUpdate(_) => {
+                GossipKind::LightClientOptimisticUpdate
+            }
+            PubsubMessage::BlsToExecutionChange(_) => GossipKind::BlsToExecutionChange,
         }
     }
 
@@ -144,35 +158,71 @@ impl<T: EthSpec> PubsubMessage<T> {
                         )))
                     }
                     GossipKind::Attestation(subnet_id) => {
+                        // Check the fixed-size attestation data before allocating the
+                        // aggregation bits, since unaggregated attestations can arrive in floods.
+                        let view = AttestationSszView::new(data).map_err(|e| format!("{:?}", e))?;
+                        let attestation_data = view.data();
+                        if attestation_data.slot.epoch(T::slots_per_epoch())
+                            != attestation_data.target.epoch
+                        {
+                            return Err(format!(
+                                "Attestation slot {} is not in target epoch {}",
+                                attestation_data.slot, attestation_data.target.epoch
+                            ));
+                        }
                         let attestation =
-                            Attestation::from_ssz_bytes(data).map_err(|e| format!("{:?}", e))?;
+                            view.to_attestation::<T>().map_err(|e| format!("{:?}", e))?;
                         Ok(PubsubMessage::Attestation(Box::new((
                             *subnet_id,
                             attestation,
                         ))))
                     }
                     GossipKind::BeaconBlock => {
-                        let beacon_block =
-                            match fork_context.from_context_bytes(gossip_topic.fork_digest) {
-                                Some(ForkName::Base) => SignedBeaconBlock::<T>::Base(
-                                    SignedBeaconBlockBase::from_ssz_bytes(data)
-                                        .map_err(|e| format!("{:?}", e))?,
-                                ),
-                                Some(ForkName::Altair) => SignedBeaconBlock::<T>::Altair(
-                                    SignedBeaconBlockAltair::from_ssz_bytes(data)
-                                        .map_err(|e| format!("{:?}", e))?,
-                                ),
-                                Some(ForkName::Merge) => SignedBeaconBlock::<T>::Merge(
-                                    SignedBeaconBlockMerge::from_ssz_bytes(data)
-                                        .map_err(|e| format!("{:?}", e))?,
-                                ),
-                                None => {
-                                    return Err(format!(
-                                        "Unknown gossipsub fork digest: {:?}",
-                                        gossip_topic.fork_digest
-                                    ))
-                                }
-                            };
+                        let _span = debug_span!("gossip_block_decode").entered();
+                        let topic_fork = fork_context.from_context_bytes(gossip_topic.fork_digest);
+
+                        // Read the slot from the fixed part of the block so that a block from a
+                        // different fork than its topic is rejected before the body is decoded.
+                        let view =
+                            SignedBeaconBlockSszView::new(data).map_err(|e| format!("{:?}", e))?;
+                        let block_fork = fork_context.fork_name_at_slot::<T>(view.slot());
+                        if let Some(topic_fork) = topic_fork.filter(|fork| **fork != block_fork) {
+                            return Err(format!(
+                                "Block at slot {} from fork {} published on {} topic",
+                                view.slot(),
+                                block_fork,
+                                topic_fork
+                            ));
+                        }
+
+                        let beacon_block = match topic_fork {
+                            Some(ForkName::Base) => SignedBeaconBlock::<T>::Base(
+                                SignedBeaconBlockBase::from_ssz_bytes(data)
+                                    .map_err(|e| format!("{:?}", e))?,
+                            ),
+                            Some(ForkName::Altair) => SignedBeaconBlock::<T>::Altair(
+                                SignedBeaconBlockAltair::from_ssz_bytes(data)
+                                    .map_err(|e| format!("{:?}", e))?,
+                            ),
+                            Some(ForkName::Merge) => SignedBeaconBlock::<T>::Merge(
+                                SignedBeaconBlockMerge::from_ssz_bytes(data)
+                                    .map_err(|e| format!("{:?}", e))?,
+                            ),
+                            Some(ForkName::Capella) => SignedBeaconBlock::<T>::Capella(
+                                SignedBeaconBlockCapella::from_ssz_bytes(data)
+                                    .map_err(|e| format!("{:?}", e))?,
+                            ),
+                            Some(ForkName::Deneb) => SignedBeaconBlock::<T>::Deneb(
+                                SignedBeaconBlockDeneb::from_ssz_bytes(data)
+                                    .map_err(|e| format!("{:?}", e))?,
+                            ),
+                            None => {
+                                return Err(format!(
+                                    "Unknown gossipsub fork digest: {:?}",
+                                    gossip_topic.fork_digest
+                                ))
+                            }
+                        };
                         Ok(PubsubMessage::BeaconBlock(Box::new(beacon_block)))
                     }
                     GossipKind::VoluntaryExit => {
@@ -205,11 +255,71 @@ impl<T: EthSpec> PubsubMessage<T> {
                             sync_committee,
                         ))))
                     }
+                    GossipKind::LightClientFinalityUpdate => {
+                        Self::check_light_client_fork(&gossip_topic, fork_context)?;
+                        let finality_update = LightClientFinalityUpdate::from_ssz_bytes(data)
+                            .map_err(|e| format!("{:?}", e))?;
+                        Ok(PubsubMessage::LightClientFinalityUpdate(Box::new(
+                            finality_update,
+                        )))
+                    }
+                    GossipKind::LightClientOptimisticUpdate => {
+                        Self::check_light_client_fork(&gossip_topic, fork_context)?;
+                        let optimistic_update = LightClientOptimisticUpdate::from_ssz_bytes(data)
+                            .map_err(|e| format!("{:?}", e))?;
+                        Ok(PubsubMessage::LightClientOptimisticUpdate(Box::new(
+                            optimistic_update,
+                        )))
+                    }
+                    GossipKind::BlsToExecutionChange => {
+                        Self::check_capella_fork(&gossip_topic, fork_context)?;
+                        let bls_to_execution_change =
+                            SignedBlsToExecutionChange::from_ssz_bytes(data)
+                                .map_err(|e| format!("{:?}", e))?;
+                        Ok(PubsubMessage::BlsToExecutionChange(Box::new(
+                            bls_to_execution_change,
+                        )))
+                    }
                 }
             }
         }
     }
 
+    /// Light client updates only exist from Altair onwards.
+    fn check_light_client_fork(
+        gossip_topic: &GossipTopic,
+        fork_context: &ForkContext,
+    ) -> Result<(), String> {
+        match fork_context.from_context_bytes(gossip_topic.fork_digest) {
+            Some(ForkName::Altair)
+            | Some(ForkName::Merge)
+            | Some(ForkName::Capella)
+            | Some(ForkName::Deneb) => Ok(()),
+            Some(ForkName::Base) => Err("Light client updates are not valid before Altair".into()),
+            None => Err(format!(
+                "Unknown gossipsub fork digest: {:?}",
+                gossip_topic.fork_digest
+            )),
+        }
+    }
+
+    /// BLS to execution changes only exist from Capella onwards.
+    fn check_capella_fork(
+        gossip_topic: &GossipTopic,
+        fork_context: &ForkContext,
+    ) -> Result<(), String> {
+        match fork_context.from_context_bytes(gossip_topic.fork_digest) {
+            Some(ForkName::Capella) | Some(ForkName::Deneb) => Ok(()),
+            Some(ForkName::Base) | Some(ForkName::Altair) | Some(ForkName::Merge) => {
+                Err("BLS to execution changes are not valid before Capella".into())
+            }
+            None => Err(format!(
+                "Unknown gossipsub fork digest: {:?}",
+                gossip_topic.fork_digest
+            )),
+        }
+    }
+
     /// Encodes a `PubsubMessage` based on the topic encodings. The first known encoding is used. If
     /// no encoding is known, and error is returned.
     pub fn encode(&self, _encoding: GossipEncoding) -> Vec<u8> {
@@ -226,6 +336,9 @@ impl<T: EthSpec> PubsubMessage<T> {
             PubsubMessage::Attestation(data) => data.1.as_ssz_bytes(),
             PubsubMessage::SignedContributionAndProof(data) => data.as_ssz_bytes(),
             PubsubMessage::SyncCommitteeMessage(data) => data.1.as_ssz_bytes(),
+            PubsubMessage::LightClientFinalityUpdate(data) => data.as_ssz_bytes(),
+            PubsubMessage::LightClientOptimisticUpdate(data) => data.as_ssz_bytes(),
+            PubsubMessage::BlsToExecutionChange(data) => data.as_ssz_bytes(),
         }
     }
 }
@@ -260,6 +373,21 @@ impl<T: EthSpec> std::fmt::Display for PubsubMessage<T> {
             PubsubMessage::SyncCommitteeMessage(data) => {
                 write!(f, "Sync committee message: subnet_id: {}", *data.0)
             }
+            PubsubMessage::LightClientFinalityUpdate(data) => write!(
+                f,
+                "Light client finality update: attested_slot: {}, finalized_slot: {}, signature_slot: {}",
+                data.attested_header.slot, data.finalized_header.slot, data.signature_slot
+            ),
+            PubsubMessage::LightClientOptimisticUpdate(data) => write!(
+                f,
+                "Light client optimistic update: attested_slot: {}, signature_slot: {}",
+                data.attested_header.slot, data.signature_slot
+            ),
+            PubsubMessage::BlsToExecutionChange(data) => write!(
+                f,
+                "Signed BLS to execution change: validator_index: {}, address: {:?}",
+                data.message.validator_index, data.message.to_execution_address
+            ),
         }
     }
 }
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use types::Hash256;
+
+/// The block lookups in progress in sync, as reported by the HTTP API.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncLookups {
+    /// The lookups of single blocks referenced by attestations but not yet known.
+    pub single_block_lookups: Vec<SingleBlockLookupStatus>,
+    /// The lookups of the unknown ancestors of blocks received on gossip.
+    pub parent_lookups: Vec<ParentLookupStatus>,
+    /// The maximum number of parent lookups that may be in progress at once.
+    pub max_parent_lookups: usize,
+    /// The maximum number of blocks a parent lookup may download before the chain is considered
+    /// invalid.
+    pub max_parent_depth: usize,
+}
+
+/// The state of the request for the block currently being looked up.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LookupState {
+    AwaitingDownload,
+    Downloading,
+    Processing,
+}
+
+/// A lookup of a single block.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SingleBlockLookupStatus {
+    pub block_root: Hash256,
+    pub state: LookupState,
+    /// The number of peers that are expected to have the block.
+    pub available_peers: usize,
+    pub failed_attempts: u8,
+}
+
+/// A lookup of the chain of unknown ancestors of a block.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ParentLookupStatus {
+    /// The root of the block which triggered the lookup.
+    pub chain_hash: Hash256,
+    /// The root of the ancestor currently being looked up.
+    pub parent_root: Hash256,
+    /// The number of blocks of the chain downloaded so far, including the triggering block.
+    pub depth: usize,
+    pub state: LookupState,
+    /// The number of peers that are expected to have the ancestor.
+    pub available_peers: usize,
+    pub failed_attempts: u8,
+}
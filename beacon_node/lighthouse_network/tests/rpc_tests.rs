@@ -25,7 +25,11 @@ fn merge_block_small(fork_context: &ForkContext) -> BeaconBlock<E> {
     let tx = VariableList::from(vec![0; 1024]);
     let txs = VariableList::from(std::iter::repeat(tx).take(5000).collect::<Vec<_>>());
 
-    block.body.execution_payload.execution_payload.transactions = txs;
+    *block
+        .body
+        .execution_payload
+        .execution_payload
+        .transactions_mut() = txs;
 
     let block = BeaconBlock::Merge(block);
     assert!(block.ssz_bytes_len() <= max_rpc_size(fork_context));
@@ -40,7 +44,11 @@ fn merge_block_large(fork_context: &ForkContext) -> BeaconBlock<E> {
     let tx = VariableList::from(vec![0; 1024]);
     let txs = VariableList::from(std::iter::repeat(tx).take(100000).collect::<Vec<_>>());
 
-    block.body.execution_payload.execution_payload.transactions = txs;
+    *block
+        .body
+        .execution_payload
+        .execution_payload
+        .transactions_mut() = txs;
 
     let block = BeaconBlock::Merge(block);
     assert!(block.ssz_bytes_len() > max_rpc_size(fork_context));
@@ -27,7 +27,8 @@
 //! - Indication that a worker has finished a parcel of work (worker idle).
 //! - A work ready for reprocessing (work event).
 //!
-//! Then, there is a maximum of `n` "worker" blocking threads, where `n` is the CPU count.
+//! Then, there is a maximum of `n` "worker" blocking threads, where `n` is the CPU count (unless
+//! configured otherwise).
 //!
 //! Whenever the manager receives a new parcel of work, it is either:
 //!
@@ -37,6 +38,11 @@
 //! Whenever the manager receives a notification that a worker has finished a parcel of work, it
 //! checks the queues to see if there are more parcels of work that can be spawned in a new worker
 //! task.
+//!
+//! A number of the workers (`priority_workers`) are reserved for priority work: blocks that
+//! extend our chain and aggregates from the current or previous slot. Other work is only spawned
+//! if it leaves the reserved workers free, so that under load block processing never waits behind
+//! a flood of attestations, RPC requests or backfill batches.
 
 use crate::sync::manager::BlockProcessType;
 use crate::{metrics, service::NetworkMessage, sync::SyncMessage};
@@ -46,23 +52,29 @@ use derivative::Derivative;
 use futures::stream::{Stream, StreamExt};
 use futures::task::Poll;
 use lighthouse_network::{
-    rpc::{BlocksByRangeRequest, BlocksByRootRequest, StatusMessage},
+    rpc::{
+        BlobsByRangeRequest, BlobsByRootRequest, BlocksByRangeRequest, BlocksByRootRequest,
+        StatusMessage,
+    },
+    types::{BeaconProcessorQueueStatus, BeaconProcessorQueues},
     Client, MessageId, NetworkGlobals, PeerId, PeerRequestId,
 };
 use logging::TimeLatch;
 use slog::{crit, debug, error, trace, warn, Logger};
+use slot_clock::SlotClock;
 use std::collections::VecDeque;
 use std::pin::Pin;
 use std::sync::{Arc, Weak};
 use std::task::Context;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::{cmp, collections::HashSet};
 use task_executor::TaskExecutor;
 use tokio::sync::mpsc;
 use types::{
-    Attestation, AttesterSlashing, Hash256, ProposerSlashing, SignedAggregateAndProof,
-    SignedBeaconBlock, SignedContributionAndProof, SignedVoluntaryExit, SubnetId,
-    SyncCommitteeMessage, SyncSubnetId,
+    Attestation, AttesterSlashing, Hash256, LightClientFinalityUpdate, LightClientOptimisticUpdate,
+    ProposerSlashing, SignedAggregateAndProof, SignedBeaconBlock, SignedBlsToExecutionChange,
+    SignedContributionAndProof, SignedVoluntaryExit, Slot, SubnetId, SyncCommitteeMessage,
+    SyncSubnetId,
 };
 use work_reprocessing_queue::{
     spawn_reprocess_scheduler, QueuedAggregate, QueuedUnaggregate, ReadyWork,
@@ -119,6 +131,10 @@ const MAX_DELAYED_BLOCK_QUEUE_LEN: usize = 1_024;
 /// before we start dropping them.
 const MAX_GOSSIP_EXIT_QUEUE_LEN: usize = 4_096;
 
+/// The maximum number of queued `SignedBlsToExecutionChange` objects received on gossip that will
+/// be stored before we start dropping them.
+const MAX_BLS_TO_EXECUTION_CHANGE_QUEUE_LEN: usize = 16_384;
+
 /// The maximum number of queued `ProposerSlashing` objects received on gossip that will be stored
 /// before we start dropping them.
 const MAX_GOSSIP_PROPOSER_SLASHING_QUEUE_LEN: usize = 4_096;
@@ -135,6 +151,14 @@ const MAX_SYNC_MESSAGE_QUEUE_LEN: usize = 2048;
 /// start dropping them.
 const MAX_SYNC_CONTRIBUTION_QUEUE_LEN: usize = 1024;
 
+/// The maximum number of queued `LightClientFinalityUpdate` objects that will be stored before we
+/// start dropping them.
+const MAX_LIGHT_CLIENT_FINALITY_UPDATE_QUEUE_LEN: usize = 512;
+
+/// The maximum number of queued `LightClientOptimisticUpdate` objects that will be stored before
+/// we start dropping them.
+const MAX_LIGHT_CLIENT_OPTIMISTIC_UPDATE_QUEUE_LEN: usize = 512;
+
 /// The maximum number of queued `SignedBeaconBlock` objects received from the network RPC that
 /// will be stored before we start dropping them.
 const MAX_RPC_BLOCK_QUEUE_LEN: usize = 1_024;
@@ -155,6 +179,17 @@ const MAX_BLOCKS_BY_RANGE_QUEUE_LEN: usize = 1_024;
 /// will be stored before we start dropping them.
 const MAX_BLOCKS_BY_ROOTS_QUEUE_LEN: usize = 1_024;
 
+/// The maximum number of queued `BlobsByRangeRequest` objects received from the network RPC that
+/// will be stored before we start dropping them.
+const MAX_BLOBS_BY_RANGE_QUEUE_LEN: usize = 1_024;
+
+/// The maximum number of queued `BlobsByRootRequest` objects received from the network RPC that
+/// will be stored before we start dropping them.
+const MAX_BLOBS_BY_ROOTS_QUEUE_LEN: usize = 1_024;
+
+/// The minimum time between updates of the queue summary reported by the HTTP API.
+const QUEUE_SUMMARY_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
 /// The name of the manager tokio task.
 const MANAGER_TASK_NAME: &str = "beacon_processor_manager";
 
@@ -187,20 +222,44 @@ pub const DELAYED_IMPORT_BLOCK: &str = "delayed_import_block";
 pub const GOSSIP_VOLUNTARY_EXIT: &str = "gossip_voluntary_exit";
 pub const GOSSIP_PROPOSER_SLASHING: &str = "gossip_proposer_slashing";
 pub const GOSSIP_ATTESTER_SLASHING: &str = "gossip_attester_slashing";
+pub const GOSSIP_BLS_TO_EXECUTION_CHANGE: &str = "gossip_bls_to_execution_change";
 pub const GOSSIP_SYNC_SIGNATURE: &str = "gossip_sync_signature";
 pub const GOSSIP_SYNC_CONTRIBUTION: &str = "gossip_sync_contribution";
+pub const GOSSIP_LIGHT_CLIENT_FINALITY_UPDATE: &str = "light_client_finality_update";
+pub const GOSSIP_LIGHT_CLIENT_OPTIMISTIC_UPDATE: &str = "light_client_optimistic_update";
 pub const RPC_BLOCK: &str = "rpc_block";
 pub const CHAIN_SEGMENT: &str = "chain_segment";
 pub const STATUS_PROCESSING: &str = "status_processing";
 pub const BLOCKS_BY_RANGE_REQUEST: &str = "blocks_by_range_request";
 pub const BLOCKS_BY_ROOTS_REQUEST: &str = "blocks_by_roots_request";
+pub const BLOBS_BY_RANGE_REQUEST: &str = "blobs_by_range_request";
+pub const BLOBS_BY_ROOTS_REQUEST: &str = "blobs_by_roots_request";
 pub const UNKNOWN_BLOCK_ATTESTATION: &str = "unknown_block_attestation";
 pub const UNKNOWN_BLOCK_AGGREGATE: &str = "unknown_block_aggregate";
 
+/// The name of the queue of backfill chain segments, which are otherwise `CHAIN_SEGMENT` work.
+pub const BACKFILL_CHAIN_SEGMENT_QUEUE: &str = "backfill_chain_segment";
+
+/// The reasons for dropping work events without processing them, used for metrics.
+pub const DROP_REASON_QUEUE_FULL: &str = "queue_full";
+pub const DROP_REASON_EVICTED: &str = "evicted";
+pub const DROP_REASON_SYNCING: &str = "syncing";
+pub const DROP_REASON_CHANNEL_FULL: &str = "channel_full";
+
+/// The state of a queue, as reported by metrics and the HTTP API.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct QueueSummary {
+    length: usize,
+    max_length: usize,
+    dropped: u64,
+}
+
 /// A simple first-in-first-out queue with a maximum length.
 struct FifoQueue<T> {
     queue: VecDeque<T>,
     max_length: usize,
+    /// The number of items dropped because the queue was full.
+    dropped: u64,
 }
 
 impl<T> FifoQueue<T> {
@@ -209,6 +268,7 @@ impl<T> FifoQueue<T> {
         Self {
             queue: VecDeque::default(),
             max_length,
+            dropped: 0,
         }
     }
 
@@ -217,6 +277,11 @@ impl<T> FifoQueue<T> {
     /// Drops `item` if the queue is full.
     pub fn push(&mut self, item: T, item_desc: &str, log: &Logger) {
         if self.queue.len() == self.max_length {
+            self.dropped += 1;
+            metrics::inc_counter_vec(
+                &metrics::BEACON_PROCESSOR_QUEUE_DROPPED_TOTAL,
+                &[item_desc, DROP_REASON_QUEUE_FULL],
+            );
             error!(
                 log,
                 "Work queue is full";
@@ -238,12 +303,22 @@ impl<T> FifoQueue<T> {
     pub fn len(&self) -> usize {
         self.queue.len()
     }
+
+    fn summary(&self) -> QueueSummary {
+        QueueSummary {
+            length: self.queue.len(),
+            max_length: self.max_length,
+            dropped: self.dropped,
+        }
+    }
 }
 
 /// A simple last-in-first-out queue with a maximum length.
 struct LifoQueue<T> {
     queue: VecDeque<T>,
     max_length: usize,
+    /// The number of items dropped because the queue was full.
+    dropped: u64,
 }
 
 impl<T> LifoQueue<T> {
@@ -252,14 +327,20 @@ impl<T> LifoQueue<T> {
         Self {
             queue: VecDeque::default(),
             max_length,
+            dropped: 0,
         }
     }
 
     /// Add a new item to the front of the queue.
     ///
     /// If the queue is full, the item at the back of the queue is dropped.
-    pub fn push(&mut self, item: T) {
+    pub fn push(&mut self, item: T, item_desc: &str) {
         if self.queue.len() == self.max_length {
+            self.dropped += 1;
+            metrics::inc_counter_vec(
+                &metrics::BEACON_PROCESSOR_QUEUE_DROPPED_TOTAL,
+                &[item_desc, DROP_REASON_EVICTED],
+            );
             self.queue.pop_back();
         }
         self.queue.push_front(item);
@@ -279,6 +360,14 @@ impl<T> LifoQueue<T> {
     pub fn len(&self) -> usize {
         self.queue.len()
     }
+
+    fn summary(&self) -> QueueSummary {
+        QueueSummary {
+            length: self.queue.len(),
+            max_length: self.max_length,
+            dropped: self.dropped,
+        }
+    }
 }
 
 /// A handle that sends a message on the provided channel to a receiver when it gets dropped.
@@ -437,6 +526,42 @@ impl<T: BeaconChainTypes> WorkEvent<T> {
         }
     }
 
+    /// Create a new `Work` event for some light client finality update.
+    pub fn gossip_light_client_finality_update(
+        message_id: MessageId,
+        peer_id: PeerId,
+        light_client_finality_update: LightClientFinalityUpdate<T::EthSpec>,
+        seen_timestamp: Duration,
+    ) -> Self {
+        Self {
+            drop_during_sync: true,
+            work: Work::GossipLightClientFinalityUpdate {
+                message_id,
+                peer_id,
+                light_client_finality_update: Box::new(light_client_finality_update),
+                seen_timestamp,
+            },
+        }
+    }
+
+    /// Create a new `Work` event for some light client optimistic update.
+    pub fn gossip_light_client_optimistic_update(
+        message_id: MessageId,
+        peer_id: PeerId,
+        light_client_optimistic_update: LightClientOptimisticUpdate<T::EthSpec>,
+        seen_timestamp: Duration,
+    ) -> Self {
+        Self {
+            drop_during_sync: true,
+            work: Work::GossipLightClientOptimisticUpdate {
+                message_id,
+                peer_id,
+                light_client_optimistic_update: Box::new(light_client_optimistic_update),
+                seen_timestamp,
+            },
+        }
+    }
+
     /// Create a new `Work` event for some exit.
     pub fn gossip_voluntary_exit(
         message_id: MessageId,
@@ -453,6 +578,22 @@ impl<T: BeaconChainTypes> WorkEvent<T> {
         }
     }
 
+    /// Create a new `Work` event for some BLS to execution change.
+    pub fn gossip_bls_to_execution_change(
+        message_id: MessageId,
+        peer_id: PeerId,
+        bls_to_execution_change: Box<SignedBlsToExecutionChange>,
+    ) -> Self {
+        Self {
+            drop_during_sync: false,
+            work: Work::GossipBlsToExecutionChange {
+                message_id,
+                peer_id,
+                bls_to_execution_change,
+            },
+        }
+    }
+
     /// Create a new `Work` event for some proposer slashing.
     pub fn gossip_proposer_slashing(
         message_id: MessageId,
@@ -553,6 +694,38 @@ impl<T: BeaconChainTypes> WorkEvent<T> {
         }
     }
 
+    /// Create a new work event to process `BlobsByRangeRequest`s from the RPC network.
+    pub fn blobs_by_range_request(
+        peer_id: PeerId,
+        request_id: PeerRequestId,
+        request: BlobsByRangeRequest,
+    ) -> Self {
+        Self {
+            drop_during_sync: false,
+            work: Work::BlobsByRangeRequest {
+                peer_id,
+                request_id,
+                request,
+            },
+        }
+    }
+
+    /// Create a new work event to process `BlobsByRootRequest`s from the RPC network.
+    pub fn blobs_by_roots_request(
+        peer_id: PeerId,
+        request_id: PeerRequestId,
+        request: BlobsByRootRequest,
+    ) -> Self {
+        Self {
+            drop_during_sync: false,
+            work: Work::BlobsByRootsRequest {
+                peer_id,
+                request_id,
+                request,
+            },
+        }
+    }
+
     /// Get a `str` representation of the type of work this `WorkEvent` contains.
     pub fn work_type(&self) -> &'static str {
         self.work.str_id()
@@ -665,6 +838,11 @@ pub enum Work<T: BeaconChainTypes> {
         peer_id: PeerId,
         voluntary_exit: Box<SignedVoluntaryExit>,
     },
+    GossipBlsToExecutionChange {
+        message_id: MessageId,
+        peer_id: PeerId,
+        bls_to_execution_change: Box<SignedBlsToExecutionChange>,
+    },
     GossipProposerSlashing {
         message_id: MessageId,
         peer_id: PeerId,
@@ -688,6 +866,18 @@ pub enum Work<T: BeaconChainTypes> {
         sync_contribution: Box<SignedContributionAndProof<T::EthSpec>>,
         seen_timestamp: Duration,
     },
+    GossipLightClientFinalityUpdate {
+        message_id: MessageId,
+        peer_id: PeerId,
+        light_client_finality_update: Box<LightClientFinalityUpdate<T::EthSpec>>,
+        seen_timestamp: Duration,
+    },
+    GossipLightClientOptimisticUpdate {
+        message_id: MessageId,
+        peer_id: PeerId,
+        light_client_optimistic_update: Box<LightClientOptimisticUpdate<T::EthSpec>>,
+        seen_timestamp: Duration,
+    },
     RpcBlock {
         block: Box<SignedBeaconBlock<T::EthSpec>>,
         seen_timestamp: Duration,
@@ -711,9 +901,36 @@ pub enum Work<T: BeaconChainTypes> {
         request_id: PeerRequestId,
         request: BlocksByRootRequest,
     },
+    BlobsByRangeRequest {
+        peer_id: PeerId,
+        request_id: PeerRequestId,
+        request: BlobsByRangeRequest,
+    },
+    BlobsByRootsRequest {
+        peer_id: PeerId,
+        request_id: PeerRequestId,
+        request: BlobsByRootRequest,
+    },
 }
 
 impl<T: BeaconChainTypes> Work<T> {
+    /// Returns `true` if this work may be spawned on the workers reserved for priority work: blocks
+    /// that extend our chain and aggregates from the current or previous slot.
+    fn is_priority(&self, current_slot: Option<Slot>) -> bool {
+        match self {
+            Work::GossipBlock { .. } | Work::DelayedImportBlock { .. } | Work::RpcBlock { .. } => {
+                true
+            }
+            Work::ChainSegment { process_id, .. } => {
+                !matches!(process_id, ChainSegmentProcessId::BackSyncBatchId(_))
+            }
+            Work::GossipAggregate { aggregate, .. } => current_slot.map_or(false, |current_slot| {
+                aggregate.message.aggregate.data.slot + 1 >= current_slot
+            }),
+            _ => false,
+        }
+    }
+
     /// Provides a `&str` that uniquely identifies each enum variant.
     fn str_id(&self) -> &'static str {
         match self {
@@ -726,13 +943,18 @@ impl<T: BeaconChainTypes> Work<T> {
             Work::GossipVoluntaryExit { .. } => GOSSIP_VOLUNTARY_EXIT,
             Work::GossipProposerSlashing { .. } => GOSSIP_PROPOSER_SLASHING,
             Work::GossipAttesterSlashing { .. } => GOSSIP_ATTESTER_SLASHING,
+            Work::GossipBlsToExecutionChange { .. } => GOSSIP_BLS_TO_EXECUTION_CHANGE,
             Work::GossipSyncSignature { .. } => GOSSIP_SYNC_SIGNATURE,
             Work::GossipSyncContribution { .. } => GOSSIP_SYNC_CONTRIBUTION,
+            Work::GossipLightClientFinalityUpdate { .. } => GOSSIP_LIGHT_CLIENT_FINALITY_UPDATE,
+            Work::GossipLightClientOptimisticUpdate { .. } => GOSSIP_LIGHT_CLIENT_OPTIMISTIC_UPDATE,
             Work::RpcBlock { .. } => RPC_BLOCK,
             Work::ChainSegment { .. } => CHAIN_SEGMENT,
             Work::Status { .. } => STATUS_PROCESSING,
             Work::BlocksByRangeRequest { .. } => BLOCKS_BY_RANGE_REQUEST,
             Work::BlocksByRootsRequest { .. } => BLOCKS_BY_ROOTS_REQUEST,
+            Work::BlobsByRangeRequest { .. } => BLOBS_BY_RANGE_REQUEST,
+            Work::BlobsByRootsRequest { .. } => BLOBS_BY_ROOTS_REQUEST,
             Work::UnknownBlockAttestation { .. } => UNKNOWN_BLOCK_ATTESTATION,
             Work::UnknownBlockAggregate { .. } => UNKNOWN_BLOCK_AGGREGATE,
         }
@@ -815,6 +1037,8 @@ pub struct BeaconProcessor<T: BeaconChainTypes> {
     pub network_globals: Arc<NetworkGlobals<T::EthSpec>>,
     pub executor: TaskExecutor,
     pub max_workers: usize,
+    /// The number of workers reserved for priority work (see `Work::is_priority`).
+    pub priority_workers: usize,
     pub current_workers: usize,
     pub importing_blocks: DuplicateCache,
     pub log: Logger,
@@ -827,8 +1051,9 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
     /// - Performed immediately, if a worker is available.
     /// - Queued for later processing, if no worker is currently available.
     ///
-    /// Only `self.max_workers` will ever be spawned at one time. Each worker is a `tokio` task
-    /// started with `spawn_blocking`.
+    /// Only `self.max_workers` will ever be spawned at one time, of which `self.priority_workers`
+    /// are reserved for priority work. Each worker is a `tokio` task started with
+    /// `spawn_blocking`.
     ///
     /// The optional `work_journal_tx` allows for an outside process to receive a log of all work
     /// events processed by `self`. This should only be used during testing.
@@ -855,6 +1080,11 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
         let mut sync_message_queue = LifoQueue::new(MAX_SYNC_MESSAGE_QUEUE_LEN);
         let mut sync_contribution_queue = LifoQueue::new(MAX_SYNC_CONTRIBUTION_QUEUE_LEN);
 
+        // Only the latest light client updates are forwarded, so process the newest first.
+        let mut finality_update_queue = LifoQueue::new(MAX_LIGHT_CLIENT_FINALITY_UPDATE_QUEUE_LEN);
+        let mut optimistic_update_queue =
+            LifoQueue::new(MAX_LIGHT_CLIENT_OPTIMISTIC_UPDATE_QUEUE_LEN);
+
         // Using a FIFO queue for voluntary exits since it prevents exit censoring. I don't have
         // a strong feeling about queue type for exits.
         let mut gossip_voluntary_exit_queue = FifoQueue::new(MAX_GOSSIP_EXIT_QUEUE_LEN);
@@ -866,6 +1096,10 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
         let mut gossip_attester_slashing_queue =
             FifoQueue::new(MAX_GOSSIP_ATTESTER_SLASHING_QUEUE_LEN);
 
+        // Using a FIFO queue for address changes, for the same reasons as exits.
+        let mut gossip_bls_to_execution_change_queue =
+            FifoQueue::new(MAX_BLS_TO_EXECUTION_CHANGE_QUEUE_LEN);
+
         // Using a FIFO queue since blocks need to be imported sequentially.
         let mut rpc_block_queue = FifoQueue::new(MAX_RPC_BLOCK_QUEUE_LEN);
         let mut chain_segment_queue = FifoQueue::new(MAX_CHAIN_SEGMENT_QUEUE_LEN);
@@ -876,18 +1110,21 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
         let mut status_queue = FifoQueue::new(MAX_STATUS_QUEUE_LEN);
         let mut bbrange_queue = FifoQueue::new(MAX_BLOCKS_BY_RANGE_QUEUE_LEN);
         let mut bbroots_queue = FifoQueue::new(MAX_BLOCKS_BY_ROOTS_QUEUE_LEN);
+        let mut blbrange_queue = FifoQueue::new(MAX_BLOBS_BY_RANGE_QUEUE_LEN);
+        let mut blbroots_queue = FifoQueue::new(MAX_BLOBS_BY_ROOTS_QUEUE_LEN);
 
         // Channels for sending work to the re-process scheduler (`work_reprocessing_tx`) and to
         // receive them back once they are ready (`ready_work_rx`).
         let (ready_work_tx, ready_work_rx) = mpsc::channel(MAX_SCHEDULED_WORK_QUEUE_LEN);
-        let work_reprocessing_tx = {
+        let (work_reprocessing_tx, slot_clock) = {
             if let Some(chain) = self.beacon_chain.upgrade() {
-                spawn_reprocess_scheduler(
+                let work_reprocessing_tx = spawn_reprocess_scheduler(
                     ready_work_tx,
                     &self.executor,
                     chain.slot_clock.clone(),
                     self.log.clone(),
-                )
+                );
+                (work_reprocessing_tx, chain.slot_clock.clone())
             } else {
                 // No need to proceed any further if the beacon chain has been dropped, the client
                 // is shutting down.
@@ -895,6 +1132,11 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
             }
         };
 
+        // Work which isn't priority work may only be spawned on this many workers, leaving the rest
+        // for priority work. Always allow at least one worker so that all work makes progress.
+        let max_low_priority_workers =
+            cmp::max(1, self.max_workers.saturating_sub(self.priority_workers));
+
         let executor = self.executor.clone();
 
         // The manager future will run on the core executor and delegate tasks to worker
@@ -905,6 +1147,7 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                 event_rx,
                 reprocess_work_rx: ready_work_rx,
             };
+            let mut next_queue_summary_update = Instant::now();
 
             loop {
                 let work_event = match inbound_events.next().await {
@@ -947,6 +1190,7 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                 }
 
                 let can_spawn = self.current_workers < self.max_workers;
+                let can_spawn_low_priority = self.current_workers < max_low_priority_workers;
                 let drop_during_sync = work_event
                     .as_ref()
                     .map_or(false, |event| event.drop_during_sync);
@@ -1023,6 +1267,12 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                                 // Process all aggregates with a single worker.
                                 self.spawn_worker(Work::GossipAggregateBatch { packages }, toolbox)
                             }
+                        // The remaining work is not priority work, so leave the workers reserved
+                        // for priority work idle.
+                        } else if !can_spawn_low_priority {
+                            metrics::inc_counter(
+                                &metrics::BEACON_PROCESSOR_PRIORITY_WORKERS_RESERVED_TOTAL,
+                            );
                         // Check the unaggregated attestation queue.
                         //
                         // Potentially use batching.
@@ -1084,6 +1334,12 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                             self.spawn_worker(item, toolbox);
                         } else if let Some(item) = sync_message_queue.pop() {
                             self.spawn_worker(item, toolbox);
+                        // Light client updates don't affect our own chain, so check them after
+                        // all other gossip consensus messages.
+                        } else if let Some(item) = finality_update_queue.pop() {
+                            self.spawn_worker(item, toolbox);
+                        } else if let Some(item) = optimistic_update_queue.pop() {
+                            self.spawn_worker(item, toolbox);
                         // Aggregates and unaggregates queued for re-processing are older and we
                         // care about fresher ones, so check those first.
                         } else if let Some(item) = unknown_block_aggregate_queue.pop() {
@@ -1091,14 +1347,18 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                         } else if let Some(item) = unknown_block_attestation_queue.pop() {
                             self.spawn_worker(item, toolbox);
                         // Check RPC methods next. Status messages are needed for sync so
-                        // prioritize them over syncing requests from other peers (BlocksByRange
-                        // and BlocksByRoot)
+                        // prioritize them over syncing requests from other peers (BlocksByRange,
+                        // BlocksByRoot and their blob counterparts)
                         } else if let Some(item) = status_queue.pop() {
                             self.spawn_worker(item, toolbox);
                         } else if let Some(item) = bbrange_queue.pop() {
                             self.spawn_worker(item, toolbox);
                         } else if let Some(item) = bbroots_queue.pop() {
                             self.spawn_worker(item, toolbox);
+                        } else if let Some(item) = blbrange_queue.pop() {
+                            self.spawn_worker(item, toolbox);
+                        } else if let Some(item) = blbroots_queue.pop() {
+                            self.spawn_worker(item, toolbox);
                         // Check slashings after all other consensus messages so we prioritize
                         // following head.
                         //
@@ -1111,6 +1371,10 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                         // Check exits last since our validators don't get rewards from them.
                         } else if let Some(item) = gossip_voluntary_exit_queue.pop() {
                             self.spawn_worker(item, toolbox);
+                        // Address changes are processed last, they don't affect our validators'
+                        // duties.
+                        } else if let Some(item) = gossip_bls_to_execution_change_queue.pop() {
+                            self.spawn_worker(item, toolbox);
                         // Handle backfill sync chain segments.
                         } else if let Some(item) = backfill_chain_segment.pop() {
                             self.spawn_worker(item, toolbox);
@@ -1145,6 +1409,10 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                             &metrics::BEACON_PROCESSOR_WORK_EVENTS_IGNORED_COUNT,
                             &[work_id],
                         );
+                        metrics::inc_counter_vec(
+                            &metrics::BEACON_PROCESSOR_QUEUE_DROPPED_TOTAL,
+                            &[work_id, DROP_REASON_SYNCING],
+                        );
                         trace!(
                             self.log,
                             "Gossip processor skipping work";
@@ -1162,8 +1430,13 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                         };
 
                         match work {
-                            _ if can_spawn => self.spawn_worker(work, toolbox),
-                            Work::GossipAttestation { .. } => attestation_queue.push(work),
+                            _ if can_spawn
+                                && (can_spawn_low_priority
+                                    || work.is_priority(slot_clock.now())) =>
+                            {
+                                self.spawn_worker(work, toolbox)
+                            }
+                            Work::GossipAttestation { .. } => attestation_queue.push(work, work_id),
                             // Attestation batches are formed internally within the
                             // `BeaconProcessor`, they are not sent from external services.
                             Work::GossipAttestationBatch { .. } => crit!(
@@ -1171,7 +1444,7 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                                     "Unsupported inbound event";
                                     "type" => "GossipAttestationBatch"
                             ),
-                            Work::GossipAggregate { .. } => aggregate_queue.push(work),
+                            Work::GossipAggregate { .. } => aggregate_queue.push(work, work_id),
                             // Aggregate batches are formed internally within the `BeaconProcessor`,
                             // they are not sent from external services.
                             Work::GossipAggregateBatch { .. } => crit!(
@@ -1194,9 +1467,20 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                             Work::GossipAttesterSlashing { .. } => {
                                 gossip_attester_slashing_queue.push(work, work_id, &self.log)
                             }
-                            Work::GossipSyncSignature { .. } => sync_message_queue.push(work),
+                            Work::GossipBlsToExecutionChange { .. } => {
+                                gossip_bls_to_execution_change_queue.push(work, work_id, &self.log)
+                            }
+                            Work::GossipSyncSignature { .. } => {
+                                sync_message_queue.push(work, work_id)
+                            }
                             Work::GossipSyncContribution { .. } => {
-                                sync_contribution_queue.push(work)
+                                sync_contribution_queue.push(work, work_id)
+                            }
+                            Work::GossipLightClientFinalityUpdate { .. } => {
+                                finality_update_queue.push(work, work_id)
+                            }
+                            Work::GossipLightClientOptimisticUpdate { .. } => {
+                                optimistic_update_queue.push(work, work_id)
                             }
                             Work::RpcBlock { .. } => rpc_block_queue.push(work, work_id, &self.log),
                             Work::ChainSegment { ref process_id, .. } => match process_id {
@@ -1215,11 +1499,17 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                             Work::BlocksByRootsRequest { .. } => {
                                 bbroots_queue.push(work, work_id, &self.log)
                             }
+                            Work::BlobsByRangeRequest { .. } => {
+                                blbrange_queue.push(work, work_id, &self.log)
+                            }
+                            Work::BlobsByRootsRequest { .. } => {
+                                blbroots_queue.push(work, work_id, &self.log)
+                            }
                             Work::UnknownBlockAttestation { .. } => {
-                                unknown_block_attestation_queue.push(work)
+                                unknown_block_attestation_queue.push(work, work_id)
                             }
                             Work::UnknownBlockAggregate { .. } => {
-                                unknown_block_aggregate_queue.push(work)
+                                unknown_block_aggregate_queue.push(work, work_id)
                             }
                         }
                     }
@@ -1245,6 +1535,14 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                     &metrics::BEACON_PROCESSOR_SYNC_CONTRIBUTION_QUEUE_TOTAL,
                     sync_contribution_queue.len() as i64,
                 );
+                metrics::set_gauge(
+                    &metrics::BEACON_PROCESSOR_LIGHT_CLIENT_FINALITY_UPDATE_QUEUE_TOTAL,
+                    finality_update_queue.len() as i64,
+                );
+                metrics::set_gauge(
+                    &metrics::BEACON_PROCESSOR_LIGHT_CLIENT_OPTIMISTIC_UPDATE_QUEUE_TOTAL,
+                    optimistic_update_queue.len() as i64,
+                );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_GOSSIP_BLOCK_QUEUE_TOTAL,
                     gossip_block_queue.len() as i64,
@@ -1273,6 +1571,86 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                     &metrics::BEACON_PROCESSOR_ATTESTER_SLASHING_QUEUE_TOTAL,
                     gossip_attester_slashing_queue.len() as i64,
                 );
+                metrics::set_gauge(
+                    &metrics::BEACON_PROCESSOR_BLS_TO_EXECUTION_CHANGE_QUEUE_TOTAL,
+                    gossip_bls_to_execution_change_queue.len() as i64,
+                );
+
+                let queue_summaries = [
+                    (GOSSIP_BLOCK, gossip_block_queue.summary()),
+                    (DELAYED_IMPORT_BLOCK, delayed_block_queue.summary()),
+                    (GOSSIP_AGGREGATE, aggregate_queue.summary()),
+                    (GOSSIP_ATTESTATION, attestation_queue.summary()),
+                    (
+                        UNKNOWN_BLOCK_AGGREGATE,
+                        unknown_block_aggregate_queue.summary(),
+                    ),
+                    (
+                        UNKNOWN_BLOCK_ATTESTATION,
+                        unknown_block_attestation_queue.summary(),
+                    ),
+                    (GOSSIP_SYNC_SIGNATURE, sync_message_queue.summary()),
+                    (GOSSIP_SYNC_CONTRIBUTION, sync_contribution_queue.summary()),
+                    (
+                        GOSSIP_LIGHT_CLIENT_FINALITY_UPDATE,
+                        finality_update_queue.summary(),
+                    ),
+                    (
+                        GOSSIP_LIGHT_CLIENT_OPTIMISTIC_UPDATE,
+                        optimistic_update_queue.summary(),
+                    ),
+                    (GOSSIP_VOLUNTARY_EXIT, gossip_voluntary_exit_queue.summary()),
+                    (
+                        GOSSIP_PROPOSER_SLASHING,
+                        gossip_proposer_slashing_queue.summary(),
+                    ),
+                    (
+                        GOSSIP_ATTESTER_SLASHING,
+                        gossip_attester_slashing_queue.summary(),
+                    ),
+                    (
+                        GOSSIP_BLS_TO_EXECUTION_CHANGE,
+                        gossip_bls_to_execution_change_queue.summary(),
+                    ),
+                    (RPC_BLOCK, rpc_block_queue.summary()),
+                    (CHAIN_SEGMENT, chain_segment_queue.summary()),
+                    (
+                        BACKFILL_CHAIN_SEGMENT_QUEUE,
+                        backfill_chain_segment.summary(),
+                    ),
+                    (STATUS_PROCESSING, status_queue.summary()),
+                    (BLOCKS_BY_RANGE_REQUEST, bbrange_queue.summary()),
+                    (BLOCKS_BY_ROOTS_REQUEST, bbroots_queue.summary()),
+                    (BLOBS_BY_RANGE_REQUEST, blbrange_queue.summary()),
+                    (BLOBS_BY_ROOTS_REQUEST, blbroots_queue.summary()),
+                ];
+                for (name, summary) in &queue_summaries {
+                    metrics::set_gauge_vec(
+                        &metrics::BEACON_PROCESSOR_QUEUE_LENGTH,
+                        &[*name],
+                        summary.length as i64,
+                    );
+                }
+
+                // Building the summary for the HTTP API allocates, so avoid doing it for every
+                // event.
+                let now = Instant::now();
+                if now >= next_queue_summary_update {
+                    next_queue_summary_update = now + QUEUE_SUMMARY_UPDATE_INTERVAL;
+                    *self.network_globals.beacon_processor_queues.write() = BeaconProcessorQueues {
+                        active_workers: self.current_workers,
+                        max_workers: self.max_workers,
+                        queues: queue_summaries
+                            .iter()
+                            .map(|(name, summary)| BeaconProcessorQueueStatus {
+                                name: name.to_string(),
+                                length: summary.length,
+                                max_length: summary.max_length,
+                                dropped: summary.dropped,
+                            })
+                            .collect(),
+                    };
+                }
 
                 if aggregate_queue.is_full() && aggregate_debounce.elapsed() {
                     error!(
@@ -1445,6 +1823,18 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                         peer_id,
                         voluntary_exit,
                     } => worker.process_gossip_voluntary_exit(message_id, peer_id, *voluntary_exit),
+                    /*
+                     * BLS to execution changes received on gossip.
+                     */
+                    Work::GossipBlsToExecutionChange {
+                        message_id,
+                        peer_id,
+                        bls_to_execution_change,
+                    } => worker.process_gossip_bls_to_execution_change(
+                        message_id,
+                        peer_id,
+                        *bls_to_execution_change,
+                    ),
                     /*
                      * Proposer slashings received on gossip.
                      */
@@ -1499,6 +1889,31 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                         *sync_contribution,
                         seen_timestamp,
                     ),
+                    /*
+                     * Light client update verification.
+                     */
+                    Work::GossipLightClientFinalityUpdate {
+                        message_id,
+                        peer_id,
+                        light_client_finality_update,
+                        seen_timestamp,
+                    } => worker.process_gossip_finality_update(
+                        message_id,
+                        peer_id,
+                        *light_client_finality_update,
+                        seen_timestamp,
+                    ),
+                    Work::GossipLightClientOptimisticUpdate {
+                        message_id,
+                        peer_id,
+                        light_client_optimistic_update,
+                        seen_timestamp,
+                    } => worker.process_gossip_optimistic_update(
+                        message_id,
+                        peer_id,
+                        *light_client_optimistic_update,
+                        seen_timestamp,
+                    ),
                     /*
                      * Verification for beacon blocks received during syncing via RPC.
                      */
@@ -1557,6 +1972,22 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                             request,
                         )
                     }
+                    /*
+                     * Processing of blobs by range requests from other peers.
+                     */
+                    Work::BlobsByRangeRequest {
+                        peer_id,
+                        request_id,
+                        request,
+                    } => worker.handle_blobs_by_range_request(peer_id, request_id, request),
+                    /*
+                     * Processing of blobs by roots requests from other peers.
+                     */
+                    Work::BlobsByRootsRequest {
+                        peer_id,
+                        request_id,
+                        request,
+                    } => worker.handle_blobs_by_root_request(peer_id, request_id, request),
                     Work::UnknownBlockAttestation {
                         message_id,
                         peer_id,
@@ -200,6 +200,7 @@ impl TestRig {
             network_globals,
             executor,
             max_workers: cmp::max(1, num_cpus::get()),
+            priority_workers: 0,
             current_workers: 0,
             importing_blocks: Default::default(),
             log: log.clone(),
@@ -831,3 +832,40 @@ fn import_misc_gossip_ops() {
         "op pool should have one more exit"
     );
 }
+
+#[test]
+fn full_fifo_queue_drops_new_items() {
+    let mut queue = FifoQueue::new(2);
+    for i in 0..3 {
+        queue.push(i, GOSSIP_BLOCK, &null_logger().unwrap());
+    }
+
+    assert_eq!(
+        queue.summary(),
+        QueueSummary {
+            length: 2,
+            max_length: 2,
+            dropped: 1
+        }
+    );
+    assert_eq!(queue.pop(), Some(0), "oldest item should be kept");
+}
+
+#[test]
+fn full_lifo_queue_evicts_old_items() {
+    let mut queue = LifoQueue::new(2);
+    for i in 0..3 {
+        queue.push(i, GOSSIP_ATTESTATION);
+    }
+
+    assert_eq!(
+        queue.summary(),
+        QueueSummary {
+            length: 2,
+            max_length: 2,
+            dropped: 1
+        }
+    );
+    assert_eq!(queue.pop(), Some(2), "newest item should be kept");
+    assert_eq!(queue.pop(), Some(1));
+}
@@ -16,10 +16,12 @@ use ssz::Encode;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use store::hot_cold_store::HotColdDBError;
 use tokio::sync::mpsc;
+use tracing::info_span;
 use types::{
-    Attestation, AttesterSlashing, EthSpec, Hash256, IndexedAttestation, ProposerSlashing,
-    SignedAggregateAndProof, SignedBeaconBlock, SignedContributionAndProof, SignedVoluntaryExit,
-    Slot, SubnetId, SyncCommitteeMessage, SyncSubnetId,
+    Attestation, AttesterSlashing, EthSpec, Hash256, IndexedAttestation, LightClientFinalityUpdate,
+    LightClientOptimisticUpdate, ProposerSlashing, SignedAggregateAndProof, SignedBeaconBlock,
+    SignedBlsToExecutionChange, SignedContributionAndProof, SignedVoluntaryExit, Slot, SubnetId,
+    SyncCommitteeMessage, SyncSubnetId,
 };
 
 use super::{
@@ -646,6 +648,9 @@ impl<T: BeaconChainTypes> Worker<T> {
         duplicate_cache: DuplicateCache,
         seen_duration: Duration,
     ) {
+        // The parent of the gossip verification and import spans of the block.
+        let _span = info_span!("gossip_block", slot = %block.slot(), peer = %peer_id).entered();
+
         if let Some(gossip_verified_block) = self.process_gossip_unverified_block(
             message_id,
             peer_id,
@@ -1023,6 +1028,89 @@ impl<T: BeaconChainTypes> Worker<T> {
         metrics::inc_counter(&metrics::BEACON_PROCESSOR_EXIT_IMPORTED_TOTAL);
     }
 
+    pub fn process_gossip_bls_to_execution_change(
+        self,
+        message_id: MessageId,
+        peer_id: PeerId,
+        bls_to_execution_change: SignedBlsToExecutionChange,
+    ) {
+        let validator_index = bls_to_execution_change.message.validator_index;
+        let address = bls_to_execution_change.message.to_execution_address;
+
+        let change = match self
+            .chain
+            .verify_bls_to_execution_change_for_gossip(bls_to_execution_change)
+        {
+            Ok(ObservationOutcome::New(change)) => change,
+            Ok(ObservationOutcome::AlreadyKnown) => {
+                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Ignore);
+                debug!(
+                    self.log,
+                    "Dropping BLS to execution change";
+                    "validator_index" => validator_index,
+                    "peer" => %peer_id
+                );
+                return;
+            }
+            Err(BeaconChainError::BlsToExecutionChangeBeforeCapella) => {
+                // Changes received before the fork are ignored without penalty, the peer may
+                // simply be slightly ahead of us.
+                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Ignore);
+                debug!(
+                    self.log,
+                    "Dropping BLS to execution change before Capella";
+                    "validator_index" => validator_index,
+                    "peer" => %peer_id
+                );
+                return;
+            }
+            Err(BeaconChainError::BlsExecutionChangeValidationError(e)) => {
+                debug!(
+                    self.log,
+                    "Dropping invalid BLS to execution change";
+                    "validator_index" => validator_index,
+                    "peer" => %peer_id,
+                    "error" => ?e
+                );
+                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.gossip_penalize_peer(
+                    peer_id,
+                    PeerAction::LowToleranceError,
+                    "invalid_bls_to_execution_change",
+                );
+                return;
+            }
+            Err(e) => {
+                debug!(
+                    self.log,
+                    "Error processing BLS to execution change";
+                    "validator_index" => validator_index,
+                    "peer" => %peer_id,
+                    "error" => ?e
+                );
+                // These errors occur due to a fault in the beacon chain. It is not necessarily
+                // the fault on the peer.
+                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Ignore);
+                return;
+            }
+        };
+
+        metrics::inc_counter(&metrics::BEACON_PROCESSOR_BLS_TO_EXECUTION_CHANGE_VERIFIED_TOTAL);
+
+        self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Accept);
+
+        self.chain.import_bls_to_execution_change(change);
+
+        debug!(
+            self.log,
+            "Successfully imported BLS to execution change";
+            "validator_index" => validator_index,
+            "address" => ?address,
+        );
+
+        metrics::inc_counter(&metrics::BEACON_PROCESSOR_BLS_TO_EXECUTION_CHANGE_IMPORTED_TOTAL);
+    }
+
     pub fn process_gossip_proposer_slashing(
         self,
         message_id: MessageId,
@@ -1179,6 +1267,15 @@ impl<T: BeaconChainTypes> Worker<T> {
         // If the message is still timely, propagate it.
         self.propagate_sync_message_if_timely(message_slot, message_id, peer_id);
 
+        // Duplicate messages fail verification, so this is the first time the message was seen.
+        if let Some(slot_start) = self.chain.slot_clock.start_of(message_slot) {
+            metrics::observe_timer_vec(
+                &metrics::SYNC_COMMITTEE_MESSAGE_GOSSIP_SLOT_START_DELAY_TIME,
+                &[subnet_id.as_ref()],
+                seen_timestamp.saturating_sub(slot_start),
+            );
+        }
+
         // Register the sync signature with any monitored validators.
         self.chain
             .validator_monitor
@@ -1267,6 +1364,70 @@ impl<T: BeaconChainTypes> Worker<T> {
         metrics::inc_counter(&metrics::BEACON_PROCESSOR_SYNC_CONTRIBUTION_IMPORTED_TOTAL);
     }
 
+    /// Process a light client finality update received from the gossip network. Updates which
+    /// match the update computed from our own blocks are propagated, all others are ignored.
+    pub fn process_gossip_finality_update(
+        self,
+        message_id: MessageId,
+        peer_id: PeerId,
+        finality_update: LightClientFinalityUpdate<T::EthSpec>,
+        seen_timestamp: Duration,
+    ) {
+        match self
+            .chain
+            .verify_finality_update_for_gossip(finality_update, seen_timestamp)
+        {
+            Ok(_verified_finality_update) => {
+                metrics::inc_counter(
+                    &metrics::BEACON_PROCESSOR_LIGHT_CLIENT_FINALITY_UPDATE_VERIFIED_TOTAL,
+                );
+                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Accept);
+            }
+            Err(e) => {
+                // None of the light client update errors indicate a faulty peer.
+                debug!(
+                    self.log,
+                    "Ignoring light client finality update";
+                    "error" => ?e,
+                    "peer" => %peer_id,
+                );
+                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Ignore);
+            }
+        }
+    }
+
+    /// Process a light client optimistic update received from the gossip network. Updates which
+    /// match the update computed from our own blocks are propagated, all others are ignored.
+    pub fn process_gossip_optimistic_update(
+        self,
+        message_id: MessageId,
+        peer_id: PeerId,
+        optimistic_update: LightClientOptimisticUpdate<T::EthSpec>,
+        seen_timestamp: Duration,
+    ) {
+        match self
+            .chain
+            .verify_optimistic_update_for_gossip(optimistic_update, seen_timestamp)
+        {
+            Ok(_verified_optimistic_update) => {
+                metrics::inc_counter(
+                    &metrics::BEACON_PROCESSOR_LIGHT_CLIENT_OPTIMISTIC_UPDATE_VERIFIED_TOTAL,
+                );
+                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Accept);
+            }
+            Err(e) => {
+                // None of the light client update errors indicate a faulty peer.
+                debug!(
+                    self.log,
+                    "Ignoring light client optimistic update";
+                    "error" => ?e,
+                    "peer" => %peer_id,
+                );
+                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Ignore);
+            }
+        }
+    }
+
     /// Handle an error whilst verifying an `Attestation` or `SignedAggregateAndProof` from the
     /// network.
     fn handle_attestation_verification_failure(
@@ -1316,6 +1477,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                     attestation_verification::verify_propagation_slot_range(
                         seen_clock,
                         failed_att.attestation(),
+                        self.chain.maximum_gossip_clock_disparity(),
                     );
 
                 // Only penalize the peer if it would have been invalid at the moment we received
@@ -1834,6 +1996,7 @@ impl<T: BeaconChainTypes> Worker<T> {
                         sync_committee_verification::verify_propagation_slot_range(
                             seen_clock,
                             &sync_committee_message_slot,
+                            self.chain.maximum_gossip_clock_disparity(),
                         );
                     hindsight_verification.is_err()
                 };
@@ -2127,6 +2290,7 @@ impl<T: BeaconChainTypes> Worker<T> {
         let is_timely = attestation_verification::verify_propagation_slot_range(
             &self.chain.slot_clock,
             attestation,
+            self.chain.maximum_gossip_clock_disparity(),
         )
         .is_ok();
 
@@ -10,7 +10,7 @@ use lighthouse_network::{PeerId, PeerRequestId, ReportSource, Response, SyncInfo
 use slog::{debug, error, warn};
 use slot_clock::SlotClock;
 use task_executor::TaskExecutor;
-use types::{Epoch, EthSpec, Hash256, Slot};
+use types::{BlobSidecarList, Epoch, EthSpec, Hash256, Slot};
 
 use super::Worker;
 
@@ -198,9 +198,10 @@ impl<T: BeaconChainTypes> Worker<T> {
             "start_slot" => req.start_slot,
             "step" => req.step);
 
-        // Should not send more than max request blocks
-        if req.count > MAX_REQUEST_BLOCKS {
-            req.count = MAX_REQUEST_BLOCKS;
+        // Should not send more than max request blocks. The RPC rate limiter has already rejected
+        // requests larger than the limit negotiated with the peer.
+        if req.count > MAX_REQUEST_BLOCKS_HARD_CAP {
+            req.count = MAX_REQUEST_BLOCKS_HARD_CAP;
         }
         if req.step == 0 {
             self.goodbye_peer(peer_id, GoodbyeReason::Fault);
@@ -342,4 +343,188 @@ impl<T: BeaconChainTypes> Worker<T> {
             "load_blocks_by_range_blocks",
         );
     }
+
+    /// Handle a `BlobsByRoot` request from the peer.
+    pub fn handle_blobs_by_root_request(
+        self,
+        peer_id: PeerId,
+        request_id: PeerRequestId,
+        request: BlobsByRootRequest,
+    ) {
+        let mut send_blob_count = 0;
+        // Consecutive identifiers usually share a block, so keep its blobs around.
+        let mut last_blobs: Option<(Hash256, Option<BlobSidecarList<T::EthSpec>>)> = None;
+        for id in request.blob_ids.iter() {
+            if last_blobs
+                .as_ref()
+                .map_or(true, |(root, _)| *root != id.block_root)
+            {
+                match self.chain.store.get_blobs(&id.block_root) {
+                    Ok(blobs) => last_blobs = Some((id.block_root, blobs)),
+                    Err(e) => {
+                        debug!(
+                            self.log,
+                            "Error fetching blobs for peer";
+                            "peer" => %peer_id,
+                            "block_root" => ?id.block_root,
+                            "error" => ?e,
+                        );
+                        continue;
+                    }
+                }
+            }
+
+            match last_blobs
+                .iter()
+                .flat_map(|(_, blobs)| blobs.iter().flat_map(|blobs| blobs.iter()))
+                .find(|blob| blob.index == id.index)
+            {
+                Some(blob) => {
+                    self.send_response(
+                        peer_id,
+                        Response::BlobsByRoot(Some(blob.clone())),
+                        request_id,
+                    );
+                    send_blob_count += 1;
+                }
+                None => {
+                    debug!(
+                        self.log,
+                        "Peer requested unknown blob";
+                        "peer" => %peer_id,
+                        "block_root" => ?id.block_root,
+                        "index" => id.index
+                    );
+                }
+            }
+        }
+        debug!(
+            self.log,
+            "Received BlobsByRoot Request";
+            "peer" => %peer_id,
+            "requested" => request.blob_ids.len(),
+            "returned" => send_blob_count
+        );
+
+        // send stream termination
+        self.send_response(peer_id, Response::BlobsByRoot(None), request_id);
+    }
+
+    /// Handle a `BlobsByRange` request from the peer.
+    ///
+    /// Only the blobs within the retention window are served, requests for earlier slots are
+    /// treated as requests starting at the window's boundary.
+    pub fn handle_blobs_by_range_request(
+        self,
+        peer_id: PeerId,
+        request_id: PeerRequestId,
+        req: BlobsByRangeRequest,
+    ) {
+        debug!(self.log, "Received BlobsByRange Request";
+            "peer_id" => %peer_id,
+            "count" => req.count,
+            "start_slot" => req.start_slot);
+
+        let end_slot = Slot::new(req.start_slot.saturating_add(req.count));
+        let boundary_slot = match self.chain.blob_sidecars_boundary() {
+            Some(epoch) => epoch.start_slot(T::EthSpec::slots_per_epoch()),
+            None => {
+                debug!(self.log, "Blobs requested before Deneb is scheduled"; "peer" => %peer_id);
+                return self.send_response(peer_id, Response::BlobsByRange(None), request_id);
+            }
+        };
+        let start_slot = std::cmp::max(Slot::new(req.start_slot), boundary_slot);
+
+        let oldest_blob_slot = self.chain.store.get_blob_info().oldest_blob_slot;
+        if start_slot < end_slot && start_slot < oldest_blob_slot {
+            debug!(
+                self.log,
+                "Range request failed during blob pruning or backfill";
+                "requested_slot" => start_slot,
+                "oldest_blob_slot" => oldest_blob_slot
+            );
+            return self.send_error_response(
+                peer_id,
+                RPCResponseErrorCode::ResourceUnavailable,
+                "Blobs unavailable".into(),
+                request_id,
+            );
+        }
+
+        let forwards_block_root_iter = match self.chain.forwards_iter_block_roots(start_slot) {
+            Ok(iter) => iter,
+            Err(BeaconChainError::HistoricalBlockError(
+                HistoricalBlockError::BlockOutOfRange {
+                    slot,
+                    oldest_block_slot,
+                },
+            )) => {
+                debug!(self.log, "Range request failed during backfill"; "requested_slot" => slot, "oldest_known_slot" => oldest_block_slot);
+                return self.send_error_response(
+                    peer_id,
+                    RPCResponseErrorCode::ResourceUnavailable,
+                    "Backfilling".into(),
+                    request_id,
+                );
+            }
+            Err(e) => return error!(self.log, "Unable to obtain root iter"; "error" => ?e),
+        };
+
+        // Skip slots repeat the previous block root, which is removed by `dedup`.
+        let maybe_block_roots = process_results(forwards_block_root_iter, |iter| {
+            iter.take_while(|(_, slot)| *slot < end_slot)
+                .map(|(root, _)| root)
+                .collect::<Vec<_>>()
+        });
+        let mut block_roots = match maybe_block_roots {
+            Ok(block_roots) => block_roots,
+            Err(e) => return error!(self.log, "Error during iteration over blocks"; "error" => ?e),
+        };
+        block_roots.dedup();
+
+        let mut blobs_sent: u64 = 0;
+        'blocks: for root in block_roots {
+            match self.chain.store.get_blobs(&root) {
+                Ok(Some(blobs)) => {
+                    for blob in blobs.iter() {
+                        // The first root may belong to a block before the range.
+                        if blob.slot() < start_slot || blob.slot() >= end_slot {
+                            continue 'blocks;
+                        }
+                        if blobs_sent >= MAX_REQUEST_BLOB_SIDECARS {
+                            break 'blocks;
+                        }
+                        blobs_sent += 1;
+                        self.send_response(
+                            peer_id,
+                            Response::BlobsByRange(Some(blob.clone())),
+                            request_id,
+                        );
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    error!(
+                        self.log,
+                        "Error fetching blobs for peer";
+                        "block_root" => ?root,
+                        "error" => ?e
+                    );
+                    break;
+                }
+            }
+        }
+
+        debug!(
+            self.log,
+            "BlobsByRange Response processed";
+            "peer" => %peer_id,
+            "start_slot" => start_slot,
+            "requested" => req.count,
+            "returned" => blobs_sent
+        );
+
+        // send the stream terminator
+        self.send_response(peer_id, Response::BlobsByRange(None), request_id);
+    }
 }
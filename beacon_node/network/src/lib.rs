@@ -11,6 +11,7 @@ mod beacon_processor;
 mod metrics;
 mod nat;
 mod persisted_dht;
+mod persisted_peers;
 mod router;
 mod status;
 mod subnet_service;
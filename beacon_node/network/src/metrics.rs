@@ -10,7 +10,7 @@ use lighthouse_network::{
 };
 use std::sync::Arc;
 use strum::IntoEnumIterator;
-use types::EthSpec;
+use types::{consts::altair::SYNC_COMMITTEE_SUBNET_COUNT, EthSpec, SyncSubnetId};
 
 lazy_static! {
 
@@ -28,6 +28,13 @@ lazy_static! {
             &["Client"]
         );
 
+    pub static ref SYNC_COMMITTEE_SUBNET_MESH_PEERS: Result<IntGaugeVec> =
+        try_create_int_gauge_vec(
+            "sync_committee_subnet_mesh_peers",
+            "Number of mesh peers for each subscribed SyncCommitteeMessage subnet",
+            &["subnet"]
+        );
+
     /*
      * Attestation subnet subscriptions
      */
@@ -39,6 +46,10 @@ lazy_static! {
         "validator_subnet_subscriptions_aggregator_total",
         "Count of validator subscription requests where the subscriber is an aggregator."
     );
+    pub static ref SUBNET_PRE_DUTY_DISCOVERY_REQUESTS: Result<IntCounter> = try_create_int_counter(
+        "validator_attestation_subnet_pre_duty_discovery_requests_total",
+        "Count of subnet peer discovery requests repeated shortly before an attestation duty."
+    );
     pub static ref SYNC_COMMITTEE_SUBSCRIPTION_REQUESTS: Result<IntCounter> = try_create_int_counter(
         "validator_sync_committee_subnet_subscriptions_total",
         "Count of validator sync committee subscription requests."
@@ -67,6 +78,20 @@ lazy_static! {
         "Time taken for a worker to fully process some parcel of work.",
         &["type"]
     );
+    pub static ref BEACON_PROCESSOR_QUEUE_DROPPED_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_processor_queue_dropped_total",
+        "Count of work events dropped without being processed, by type and reason",
+        &["type", "reason"]
+    );
+    pub static ref BEACON_PROCESSOR_QUEUE_LENGTH: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "beacon_processor_queue_length",
+        "Count of work events waiting in each queue of the beacon processor",
+        &["queue"]
+    );
+    pub static ref BEACON_PROCESSOR_PRIORITY_WORKERS_RESERVED_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_processor_priority_workers_reserved_total",
+        "Count of times a free worker was left idle, with work queued, since it is reserved for priority work"
+    );
     pub static ref BEACON_PROCESSOR_WORKERS_SPAWNED_TOTAL: Result<IntCounter> = try_create_int_counter(
         "beacon_processor_workers_spawned_total",
         "The number of workers ever spawned by the gossip processing pool."
@@ -117,6 +142,19 @@ lazy_static! {
         "beacon_processor_exit_imported_total",
         "Total number of voluntary exits imported to the op pool."
     );
+    // Gossip BLS to execution changes.
+    pub static ref BEACON_PROCESSOR_BLS_TO_EXECUTION_CHANGE_QUEUE_TOTAL: Result<IntGauge> = try_create_int_gauge(
+        "beacon_processor_bls_to_execution_change_queue_total",
+        "Count of address changes from gossip waiting to be verified."
+    );
+    pub static ref BEACON_PROCESSOR_BLS_TO_EXECUTION_CHANGE_VERIFIED_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_processor_bls_to_execution_change_verified_total",
+        "Total number of address changes verified for propagation."
+    );
+    pub static ref BEACON_PROCESSOR_BLS_TO_EXECUTION_CHANGE_IMPORTED_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_processor_bls_to_execution_change_imported_total",
+        "Total number of address changes imported to the op pool."
+    );
     // Gossip proposer slashings.
     pub static ref BEACON_PROCESSOR_PROPOSER_SLASHING_QUEUE_TOTAL: Result<IntGauge> = try_create_int_gauge(
         "beacon_processor_proposer_slashing_queue_total",
@@ -224,6 +262,11 @@ lazy_static! {
         "beacon_processor_sync_message_verified_total",
         "Total number of sync committee messages verified for gossip."
     );
+    pub static ref SYNC_COMMITTEE_MESSAGE_GOSSIP_SLOT_START_DELAY_TIME: Result<HistogramVec> = try_create_histogram_vec(
+        "sync_committee_message_gossip_slot_start_delay_time",
+        "Duration between the start of the slot and when a sync committee message was first seen, per subnet.",
+        &["subnet"]
+    );
     pub static ref BEACON_PROCESSOR_SYNC_MESSAGE_IMPORTED_TOTAL: Result<IntCounter> = try_create_int_counter(
         "beacon_processor_sync_message_imported_total",
         "Total number of sync committee messages imported to fork choice, etc."
@@ -242,6 +285,23 @@ lazy_static! {
         "beacon_processor_sync_contribution_imported_total",
         "Total number of sync committee contributions imported to fork choice, etc."
     );
+    // Light client updates.
+    pub static ref BEACON_PROCESSOR_LIGHT_CLIENT_FINALITY_UPDATE_QUEUE_TOTAL: Result<IntGauge> = try_create_int_gauge(
+        "beacon_processor_light_client_finality_update_queue_total",
+        "Count of light client finality updates waiting to be processed."
+    );
+    pub static ref BEACON_PROCESSOR_LIGHT_CLIENT_FINALITY_UPDATE_VERIFIED_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_processor_light_client_finality_update_verified_total",
+        "Total number of light client finality updates verified for gossip."
+    );
+    pub static ref BEACON_PROCESSOR_LIGHT_CLIENT_OPTIMISTIC_UPDATE_QUEUE_TOTAL: Result<IntGauge> = try_create_int_gauge(
+        "beacon_processor_light_client_optimistic_update_queue_total",
+        "Count of light client optimistic updates waiting to be processed."
+    );
+    pub static ref BEACON_PROCESSOR_LIGHT_CLIENT_OPTIMISTIC_UPDATE_VERIFIED_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_processor_light_client_optimistic_update_verified_total",
+        "Total number of light client optimistic updates verified for gossip."
+    );
 
     /// Errors and Debugging Stats
     pub static ref GOSSIP_ATTESTATION_ERRORS_PER_TYPE: Result<IntCounterVec> =
@@ -297,6 +357,18 @@ lazy_static! {
         "sync_parent_block_lookups",
         "Number of parent block lookups underway"
     );
+    pub static ref SYNC_RANGE_BATCH_DOWNLOAD_SECONDS: Result<Histogram> = try_create_histogram(
+        "sync_range_batch_download_seconds",
+        "Time taken to download a range sync batch from a peer"
+    );
+    pub static ref SYNC_RANGE_BATCH_PROCESSING_SECONDS: Result<Histogram> = try_create_histogram(
+        "sync_range_batch_processing_seconds",
+        "Time taken to process a range sync batch, including time queued in the beacon processor"
+    );
+    pub static ref SYNC_RANGE_BATCHES_AWAITING_PROCESSING: Result<IntGauge> = try_create_int_gauge(
+        "sync_range_batches_awaiting_processing",
+        "Number of downloaded batches buffered behind the batch last sent for processing"
+    );
 
     /*
      * Block Delay Metrics
@@ -369,6 +441,15 @@ pub fn update_gossip_metrics<T: EthSpec>(
             0_i64,
         );
     }
+    // Mesh peers per sync committee subnet
+    // Reset the gauges, so subnets we have unsubscribed from read zero
+    for subnet_id in 0..SYNC_COMMITTEE_SUBNET_COUNT {
+        set_gauge_vec(
+            &SYNC_COMMITTEE_SUBNET_MESH_PEERS,
+            &[SyncSubnetId::new(subnet_id).as_ref()],
+            0_i64,
+        );
+    }
 
     for topic_hash in gossipsub.topics() {
         if let Ok(topic) = GossipTopic::decode(topic_hash.as_str()) {
@@ -405,7 +486,13 @@ pub fn update_gossip_metrics<T: EthSpec>(
                         };
                     }
                 }
-                GossipKind::SyncCommitteeMessage(_subnet_id) => {}
+                GossipKind::SyncCommitteeMessage(subnet_id) => {
+                    set_gauge_vec(
+                        &SYNC_COMMITTEE_SUBNET_MESH_PEERS,
+                        &[subnet_id.as_ref()],
+                        gossipsub.mesh_peers(topic_hash).count() as i64,
+                    );
+                }
                 _kind => {}
             }
         }
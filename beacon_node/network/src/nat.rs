@@ -2,16 +2,43 @@
 //!
 //! Currently supported strategies:
 //! - UPnP
+//! - NAT-PMP
+//!
+//! Mappings are requested with a limited lease and renewed periodically, so that they are restored
+//! if the gateway restarts and expire by themselves if we don't shut down cleanly.
 
 use crate::{NetworkConfig, NetworkMessage};
 use if_addrs::get_if_addrs;
+use lighthouse_network::{PortMappingMethod, PortMappingStatus};
 use slog::{debug, info};
-use std::net::{IpAddr, SocketAddr, SocketAddrV4};
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, SocketAddrV4, UdpSocket};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
 use types::EthSpec;
 
-/// Configuration required to construct the UPnP port mappings.
-pub struct UPnPConfig {
+/// The lease requested for each port mapping.
+const MAPPING_LEASE: Duration = Duration::from_secs(3600);
+/// How often the port mappings are renewed. This must be well within `MAPPING_LEASE`.
+const MAPPING_RENEWAL_INTERVAL: Duration = Duration::from_secs(1800);
+
+/// The port NAT-PMP gateways listen on.
+const NATPMP_PORT: u16 = 5351;
+/// The NAT-PMP opcode to request the gateway's external address.
+const NATPMP_OPCODE_EXTERNAL_ADDRESS: u8 = 0;
+/// The NAT-PMP opcode to map a UDP port.
+const NATPMP_OPCODE_UDP: u8 = 1;
+/// The NAT-PMP opcode to map a TCP port.
+const NATPMP_OPCODE_TCP: u8 = 2;
+/// The number of times a NAT-PMP request is sent before giving up on the gateway.
+const NATPMP_ATTEMPTS: u32 = 4;
+/// How long to wait for a response to the first NAT-PMP request. This doubles with each attempt,
+/// as recommended by RFC 6886.
+const NATPMP_INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Configuration required to construct the port mappings.
+#[derive(Clone)]
+pub struct PortMappingConfig {
     /// The local tcp port.
     tcp_port: u16,
     /// The local udp port.
@@ -20,9 +47,9 @@ pub struct UPnPConfig {
     disable_discovery: bool,
 }
 
-impl From<&NetworkConfig> for UPnPConfig {
+impl From<&NetworkConfig> for PortMappingConfig {
     fn from(config: &NetworkConfig) -> Self {
-        UPnPConfig {
+        PortMappingConfig {
             tcp_port: config.libp2p_port,
             udp_port: config.discovery_port,
             disable_discovery: config.disable_discovery,
@@ -30,93 +57,117 @@ impl From<&NetworkConfig> for UPnPConfig {
     }
 }
 
-/// Attempts to construct external port mappings with UPnP.
-pub fn construct_upnp_mappings<T: EthSpec>(
-    config: UPnPConfig,
+/// Establishes port mappings on the local gateway and renews them every
+/// `MAPPING_RENEWAL_INTERVAL`, reporting the result of each attempt to the network service.
+pub async fn maintain_port_mappings<T: EthSpec>(
+    config: PortMappingConfig,
     network_send: mpsc::UnboundedSender<NetworkMessage<T>>,
     log: slog::Logger,
 ) {
-    info!(log, "UPnP Attempting to initialise routes");
-    match igd::search_gateway(Default::default()) {
-        Err(e) => info!(log, "UPnP not available"; "error" => %e),
-        Ok(gateway) => {
-            // Need to find the local listening address matched with the router subnet
-            let interfaces = match get_if_addrs() {
-                Ok(v) => v,
-                Err(e) => {
-                    info!(log, "UPnP failed to get local interfaces"; "error" => %e);
-                    return;
-                }
-            };
-            let local_ip = interfaces.iter().find_map(|interface| {
-                // Just use the first IP of the first interface that is not a loopback and not an
-                // ipv6 address.
-                if !interface.is_loopback() {
-                    interface.ip().is_ipv4().then(|| interface.ip())
-                } else {
-                    None
-                }
-            });
+    info!(log, "Attempting to map ports on the local gateway");
+    let mut renewal = tokio::time::interval(MAPPING_RENEWAL_INTERVAL);
+    loop {
+        renewal.tick().await;
 
-            let local_ip = match local_ip {
-                None => {
-                    info!(log, "UPnP failed to find local IP address");
-                    return;
-                }
-                Some(v) => v,
-            };
+        let task_config = config.clone();
+        let task_log = log.clone();
+        let status = match tokio::task::spawn_blocking(move || {
+            construct_port_mappings(&task_config, &task_log)
+        })
+        .await
+        {
+            Ok(status) => status,
+            Err(e) => {
+                debug!(log, "Port mapping task failed"; "error" => %e);
+                continue;
+            }
+        };
 
-            debug!(log, "UPnP Local IP Discovered"; "ip" => ?local_ip);
-
-            match local_ip {
-                IpAddr::V4(address) => {
-                    let libp2p_socket = SocketAddrV4::new(address, config.tcp_port);
-                    let external_ip = gateway.get_external_ip();
-                    // We add specific port mappings rather than getting the router to arbitrary assign
-                    // one.
-                    // I've found this to be more reliable. If multiple users are behind a single
-                    // router, they should ideally try to set different port numbers.
-                    let tcp_socket = add_port_mapping(
-                        &gateway,
-                        igd::PortMappingProtocol::TCP,
-                        libp2p_socket,
-                        "tcp",
-                        &log,
-                    ).and_then(|_| {
-                        let external_socket = external_ip.as_ref().map(|ip| SocketAddr::new((*ip).into(), config.tcp_port)).map_err(|_| ());
-                        info!(log, "UPnP TCP route established"; "external_socket" => format!("{}:{}", external_socket.as_ref().map(|ip| ip.to_string()).unwrap_or_else(|_| "".into()), config.tcp_port));
-                        external_socket
-                    }).ok();
-
-                    let udp_socket = if !config.disable_discovery {
-                        let discovery_socket = SocketAddrV4::new(address, config.udp_port);
-                        add_port_mapping(
-                            &gateway,
-                            igd::PortMappingProtocol::UDP,
-                            discovery_socket,
-                            "udp",
-                            &log,
-                        ).and_then(|_| {
-                            let external_socket = external_ip
-                                    .map(|ip| SocketAddr::new(ip.into(), config.udp_port)).map_err(|_| ());
-                        info!(log, "UPnP UDP route established"; "external_socket" => format!("{}:{}", external_socket.as_ref().map(|ip| ip.to_string()).unwrap_or_else(|_| "".into()), config.udp_port));
-                        external_socket
-                    }).ok()
-                    } else {
-                        None
-                    };
-
-                    // report any updates to the network service.
-                    network_send.send(NetworkMessage::UPnPMappingEstablished{ tcp_socket, udp_socket })
-            .unwrap_or_else(|e| debug!(log, "Could not send message to the network service"; "error" => %e));
-                }
-                _ => debug!(log, "UPnP no routes constructed. IPv6 not supported"),
+        if network_send
+            .send(NetworkMessage::PortMappingsUpdated(status))
+            .is_err()
+        {
+            debug!(
+                log,
+                "Network service has shut down, no longer renewing port mappings"
+            );
+            return;
+        }
+    }
+}
+
+/// Attempts to construct external port mappings with UPnP, falling back to NAT-PMP.
+fn construct_port_mappings(config: &PortMappingConfig, log: &slog::Logger) -> PortMappingStatus {
+    let upnp_error = match upnp_mappings(config, log) {
+        Ok(status) => return status,
+        Err(e) => {
+            debug!(log, "UPnP port mapping failed"; "error" => &e);
+            e
+        }
+    };
+
+    match natpmp_mappings(config, log) {
+        Ok(status) => status,
+        Err(e) => {
+            debug!(log, "NAT-PMP port mapping failed"; "error" => &e);
+            PortMappingStatus {
+                error: Some(format!("UPnP: {}, NAT-PMP: {}", upnp_error, e)),
+                ..PortMappingStatus::default()
             }
         }
+    }
+}
+
+fn upnp_mappings(
+    config: &PortMappingConfig,
+    log: &slog::Logger,
+) -> Result<PortMappingStatus, String> {
+    let gateway = igd::search_gateway(Default::default()).map_err(|e| e.to_string())?;
+    let local_ip = local_ipv4()?;
+    debug!(log, "UPnP Local IP Discovered"; "ip" => ?local_ip);
+
+    // We add specific port mappings rather than getting the router to arbitrary assign one.
+    // I've found this to be more reliable. If multiple users are behind a single router, they
+    // should ideally try to set different port numbers.
+    let tcp_port = add_port_mapping(
+        &gateway,
+        igd::PortMappingProtocol::TCP,
+        SocketAddrV4::new(local_ip, config.tcp_port),
+        "tcp",
+        log,
+    )
+    .ok()
+    .map(|()| config.tcp_port);
+
+    let udp_port = if !config.disable_discovery {
+        add_port_mapping(
+            &gateway,
+            igd::PortMappingProtocol::UDP,
+            SocketAddrV4::new(local_ip, config.udp_port),
+            "udp",
+            log,
+        )
+        .ok()
+        .map(|()| config.udp_port)
+    } else {
+        None
     };
+
+    if tcp_port.is_none() && udp_port.is_none() {
+        return Err("gateway refused the port mappings".into());
+    }
+
+    Ok(PortMappingStatus {
+        method: Some(PortMappingMethod::Upnp),
+        external_ip: gateway.get_external_ip().ok().map(IpAddr::V4),
+        tcp_port,
+        udp_port,
+        last_renewed: Some(timestamp_now()),
+        error: None,
+    })
 }
 
-/// Sets up a port mapping for a protocol returning the mapped port if successful.
+/// Sets up a UPnP port mapping for a protocol.
 fn add_port_mapping(
     gateway: &igd::Gateway,
     protocol: igd::PortMappingProtocol,
@@ -124,13 +175,10 @@ fn add_port_mapping(
     protocol_string: &'static str,
     log: &slog::Logger,
 ) -> Result<(), ()> {
-    // We add specific port mappings rather than getting the router to arbitrary assign
-    // one.
-    // I've found this to be more reliable. If multiple users are behind a single
-    // router, they should ideally try to set different port numbers.
     let mapping_string = &format!("lighthouse-{}", protocol_string);
-    for _ in 0..2 {
-        match gateway.add_port(protocol, socket.port(), socket, 0, mapping_string) {
+    let mut lease = MAPPING_LEASE.as_secs() as u32;
+    for _ in 0..3 {
+        match gateway.add_port(protocol, socket.port(), socket, lease, mapping_string) {
             Err(e) => {
                 match e {
                     igd::AddPortError::PortInUse => {
@@ -146,8 +194,14 @@ fn add_port_mapping(
                             }
                         }
                     }
+                    igd::AddPortError::OnlyPermanentLeasesSupported => {
+                        // The mapping will outlive us if we don't shut down cleanly, but this is
+                        // no worse than not renewing it.
+                        debug!(log, "UPnP gateway only supports permanent leases"; "protocol" => protocol_string);
+                        lease = 0;
+                    }
                     e => {
-                        info!(log, "UPnP TCP route not set"; "error" => %e);
+                        debug!(log, "UPnP route not set"; "protocol" => protocol_string, "error" => %e);
                         return Err(());
                     }
                 }
@@ -160,30 +214,289 @@ fn add_port_mapping(
     Err(())
 }
 
-/// Removes the specified TCP and UDP port mappings.
-pub fn remove_mappings(tcp_port: Option<u16>, udp_port: Option<u16>, log: &slog::Logger) {
-    if tcp_port.is_some() || udp_port.is_some() {
-        debug!(log, "Removing UPnP port mappings");
-        match igd::search_gateway(Default::default()) {
-            Ok(gateway) => {
-                if let Some(tcp_port) = tcp_port {
-                    match gateway.remove_port(igd::PortMappingProtocol::TCP, tcp_port) {
-                        Ok(()) => debug!(log, "UPnP Removed TCP port mapping"; "port" => tcp_port),
-                        Err(e) => {
-                            debug!(log, "UPnP Failed to remove TCP port mapping"; "port" => tcp_port, "error" => %e)
+fn natpmp_mappings(
+    config: &PortMappingConfig,
+    log: &slog::Logger,
+) -> Result<PortMappingStatus, String> {
+    let gateway = default_gateway().ok_or("unable to find the default gateway")?;
+    // Check the gateway speaks NAT-PMP before requesting any mappings.
+    let external_ip = natpmp_external_ip(gateway)?;
+
+    let lease = MAPPING_LEASE.as_secs() as u32;
+    let tcp_port = natpmp_port_mapping(gateway, NATPMP_OPCODE_TCP, config.tcp_port, lease)
+        .map_err(|e| debug!(log, "NAT-PMP route not set"; "protocol" => "tcp", "error" => e))
+        .ok();
+    let udp_port = if !config.disable_discovery {
+        natpmp_port_mapping(gateway, NATPMP_OPCODE_UDP, config.udp_port, lease)
+            .map_err(|e| debug!(log, "NAT-PMP route not set"; "protocol" => "udp", "error" => e))
+            .ok()
+    } else {
+        None
+    };
+
+    if tcp_port.is_none() && udp_port.is_none() {
+        return Err("gateway refused the port mappings".into());
+    }
+
+    Ok(PortMappingStatus {
+        method: Some(PortMappingMethod::NatPmp),
+        external_ip: Some(IpAddr::V4(external_ip)),
+        tcp_port,
+        udp_port,
+        last_renewed: Some(timestamp_now()),
+        error: None,
+    })
+}
+
+/// Requests a NAT-PMP mapping for `internal_port`, returning the external port the gateway mapped
+/// to it. A `lease` of zero removes the mapping.
+fn natpmp_port_mapping(
+    gateway: Ipv4Addr,
+    opcode: u8,
+    internal_port: u16,
+    lease: u32,
+) -> Result<u16, String> {
+    // As with UPnP, ask for the same external port. The gateway may assign a different one.
+    let suggested_port = if lease == 0 { 0 } else { internal_port };
+    let request = encode_natpmp_mapping_request(opcode, internal_port, suggested_port, lease);
+    let mut response = [0; 16];
+    let len = natpmp_request(gateway, &request, &mut response)?;
+    decode_natpmp_mapping_response(&response[..len], opcode)
+}
+
+fn natpmp_external_ip(gateway: Ipv4Addr) -> Result<Ipv4Addr, String> {
+    let mut response = [0; 16];
+    let len = natpmp_request(gateway, &[0, NATPMP_OPCODE_EXTERNAL_ADDRESS], &mut response)?;
+    decode_natpmp_external_ip(&response[..len])
+}
+
+/// Sends a NAT-PMP request to the gateway, retrying until it responds.
+fn natpmp_request(gateway: Ipv4Addr, request: &[u8], response: &mut [u8]) -> Result<usize, String> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).map_err(|e| e.to_string())?;
+    socket
+        .connect((gateway, NATPMP_PORT))
+        .map_err(|e| e.to_string())?;
+
+    let mut timeout = NATPMP_INITIAL_TIMEOUT;
+    for _ in 0..NATPMP_ATTEMPTS {
+        socket.send(request).map_err(|e| e.to_string())?;
+        socket
+            .set_read_timeout(Some(timeout))
+            .map_err(|e| e.to_string())?;
+        match socket.recv(response) {
+            Ok(len) => return Ok(len),
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                timeout *= 2;
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    Err(format!("no response from gateway {}", gateway))
+}
+
+fn encode_natpmp_mapping_request(
+    opcode: u8,
+    internal_port: u16,
+    suggested_port: u16,
+    lease: u32,
+) -> [u8; 12] {
+    let mut request = [0; 12];
+    request[1] = opcode;
+    request[4..6].copy_from_slice(&internal_port.to_be_bytes());
+    request[6..8].copy_from_slice(&suggested_port.to_be_bytes());
+    request[8..12].copy_from_slice(&lease.to_be_bytes());
+    request
+}
+
+/// Returns the external port from a NAT-PMP mapping response.
+fn decode_natpmp_mapping_response(response: &[u8], opcode: u8) -> Result<u16, String> {
+    check_natpmp_response(response, opcode, 16)?;
+    Ok(u16::from_be_bytes([response[10], response[11]]))
+}
+
+fn decode_natpmp_external_ip(response: &[u8]) -> Result<Ipv4Addr, String> {
+    check_natpmp_response(response, NATPMP_OPCODE_EXTERNAL_ADDRESS, 12)?;
+    Ok(Ipv4Addr::new(
+        response[8],
+        response[9],
+        response[10],
+        response[11],
+    ))
+}
+
+/// Checks `response` is a successful response to a NAT-PMP request with `opcode`.
+fn check_natpmp_response(response: &[u8], opcode: u8, len: usize) -> Result<(), String> {
+    if response.len() < len {
+        return Err(format!("truncated response of {} bytes", response.len()));
+    }
+    if response[0] != 0 || response[1] != opcode + 128 {
+        return Err(format!(
+            "unexpected response version {} and opcode {}",
+            response[0], response[1]
+        ));
+    }
+    match u16::from_be_bytes([response[2], response[3]]) {
+        0 => Ok(()),
+        result_code => Err(format!("gateway returned result code {}", result_code)),
+    }
+}
+
+/// Returns the first IPv4 address of a local interface that is not a loopback.
+fn local_ipv4() -> Result<Ipv4Addr, String> {
+    get_if_addrs()
+        .map_err(|e| format!("failed to get local interfaces: {}", e))?
+        .iter()
+        .find_map(|interface| match interface.ip() {
+            IpAddr::V4(ip) if !interface.is_loopback() => Some(ip),
+            _ => None,
+        })
+        .ok_or_else(|| "failed to find local IPv4 address".into())
+}
+
+/// Finds the default IPv4 gateway in the kernel routing table.
+#[cfg(target_os = "linux")]
+fn default_gateway() -> Option<Ipv4Addr> {
+    parse_default_gateway(&std::fs::read_to_string("/proc/net/route").ok()?)
+}
+
+/// NAT-PMP needs the address of the gateway, which is only looked up on Linux.
+#[cfg(not(target_os = "linux"))]
+fn default_gateway() -> Option<Ipv4Addr> {
+    None
+}
+
+/// Parses the default gateway from the contents of `/proc/net/route`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_default_gateway(routes: &str) -> Option<Ipv4Addr> {
+    // The first line is a header.
+    routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let _interface = fields.next()?;
+        let destination = fields.next()?;
+        let gateway = fields.next()?;
+        if destination != "00000000" {
+            return None;
+        }
+        // Addresses are printed in host byte order.
+        let gateway = u32::from_str_radix(gateway, 16).ok()?;
+        Some(Ipv4Addr::from(gateway.to_ne_bytes())).filter(|ip| !ip.is_unspecified())
+    })
+}
+
+fn timestamp_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// Removes the port mappings established on the gateway.
+pub fn remove_mappings(config: &PortMappingConfig, status: &PortMappingStatus, log: &slog::Logger) {
+    match status.method {
+        Some(PortMappingMethod::Upnp) => {
+            debug!(log, "Removing UPnP port mappings");
+            match igd::search_gateway(Default::default()) {
+                Ok(gateway) => {
+                    if let Some(tcp_port) = status.tcp_port {
+                        match gateway.remove_port(igd::PortMappingProtocol::TCP, tcp_port) {
+                            Ok(()) => {
+                                debug!(log, "UPnP Removed TCP port mapping"; "port" => tcp_port)
+                            }
+                            Err(e) => {
+                                debug!(log, "UPnP Failed to remove TCP port mapping"; "port" => tcp_port, "error" => %e)
+                            }
+                        }
+                    }
+                    if let Some(udp_port) = status.udp_port {
+                        match gateway.remove_port(igd::PortMappingProtocol::UDP, udp_port) {
+                            Ok(()) => {
+                                debug!(log, "UPnP Removed UDP port mapping"; "port" => udp_port)
+                            }
+                            Err(e) => {
+                                debug!(log, "UPnP Failed to remove UDP port mapping"; "port" => udp_port, "error" => %e)
+                            }
                         }
                     }
                 }
-                if let Some(udp_port) = udp_port {
-                    match gateway.remove_port(igd::PortMappingProtocol::UDP, udp_port) {
-                        Ok(()) => debug!(log, "UPnP Removed UDP port mapping"; "port" => udp_port),
+                Err(e) => debug!(log, "UPnP failed to remove mappings"; "error" => %e),
+            }
+        }
+        Some(PortMappingMethod::NatPmp) => {
+            debug!(log, "Removing NAT-PMP port mappings");
+            let gateway = match default_gateway() {
+                Some(gateway) => gateway,
+                None => return,
+            };
+            // NAT-PMP mappings are removed by their internal port.
+            let mappings = [
+                (status.tcp_port, NATPMP_OPCODE_TCP, config.tcp_port, "tcp"),
+                (status.udp_port, NATPMP_OPCODE_UDP, config.udp_port, "udp"),
+            ];
+            for (external_port, opcode, internal_port, protocol) in mappings {
+                if external_port.is_some() {
+                    match natpmp_port_mapping(gateway, opcode, internal_port, 0) {
+                        Ok(_) => {
+                            debug!(log, "NAT-PMP Removed port mapping"; "protocol" => protocol, "port" => internal_port)
+                        }
                         Err(e) => {
-                            debug!(log, "UPnP Failed to remove UDP port mapping"; "port" => udp_port, "error" => %e)
+                            debug!(log, "NAT-PMP Failed to remove port mapping"; "protocol" => protocol, "port" => internal_port, "error" => e)
                         }
                     }
                 }
             }
-            Err(e) => debug!(log, "UPnP failed to remove mappings"; "error" => %e),
         }
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn natpmp_mapping_round_trip() {
+        let request = encode_natpmp_mapping_request(NATPMP_OPCODE_TCP, 9000, 9000, 3600);
+        assert_eq!(
+            request,
+            [0, 2, 0, 0, 0x23, 0x28, 0x23, 0x28, 0, 0, 0x0e, 0x10]
+        );
+
+        // Version, opcode, result code, epoch, internal port, external port and lifetime.
+        let response = [
+            0, 130, 0, 0, 0, 0, 0, 1, 0x23, 0x28, 0x23, 0x29, 0, 0, 0x0e, 0x10,
+        ];
+        assert_eq!(
+            decode_natpmp_mapping_response(&response, NATPMP_OPCODE_TCP),
+            Ok(9001)
+        );
+        assert!(decode_natpmp_mapping_response(&response, NATPMP_OPCODE_UDP).is_err());
+        assert!(decode_natpmp_mapping_response(&response[..12], NATPMP_OPCODE_TCP).is_err());
+
+        let mut refused = response;
+        refused[3] = 2;
+        assert!(decode_natpmp_mapping_response(&refused, NATPMP_OPCODE_TCP).is_err());
+    }
+
+    #[test]
+    fn natpmp_external_ip_response() {
+        let response = [0, 128, 0, 0, 0, 0, 0, 1, 203, 0, 113, 7];
+        assert_eq!(
+            decode_natpmp_external_ip(&response),
+            Ok(Ipv4Addr::new(203, 0, 113, 7))
+        );
+    }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn default_gateway_from_route_table() {
+        let routes = "\
+Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT
+eth0\t0001A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0
+eth0\t00000000\t0101A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0
+";
+        assert_eq!(
+            parse_default_gateway(routes),
+            Some(Ipv4Addr::new(192, 168, 1, 1))
+        );
+        assert_eq!(parse_default_gateway(""), None);
     }
 }
@@ -0,0 +1,86 @@
+use lighthouse_network::PersistedPeers;
+use ssz::{Decode, Encode};
+use std::sync::Arc;
+use store::{DBColumn, Error as StoreError, HotColdDB, ItemStore, StoreItem};
+use types::{EthSpec, Hash256};
+
+/// 32-byte key for accessing the `PeerReputations`. All zero because `PeerReputations` has its own
+/// column.
+pub const PEERS_DB_KEY: Hash256 = Hash256::zero();
+
+/// Loads the peer reputations persisted on the last shutdown, if any.
+pub fn load_peers<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+) -> Option<PersistedPeers> {
+    match store.get_item(&PEERS_DB_KEY) {
+        Ok(Some(p)) => {
+            let p: PersistedPeerReputations = p;
+            Some(p.peers)
+        }
+        _ => None,
+    }
+}
+
+/// Attempt to persist the peer reputations to `self.store`.
+pub fn persist_peers<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+    peers: PersistedPeers,
+) -> Result<(), store::Error> {
+    store.put_item(&PEERS_DB_KEY, &PersistedPeerReputations { peers })
+}
+
+/// Wrapper around the peer reputations for persistence to disk.
+pub struct PersistedPeerReputations {
+    pub peers: PersistedPeers,
+}
+
+impl StoreItem for PersistedPeerReputations {
+    fn db_column() -> DBColumn {
+        DBColumn::PeerReputations
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.peers.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        Ok(PersistedPeerReputations {
+            peers: PersistedPeers::from_ssz_bytes(bytes)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lighthouse_network::peer_manager::peerdb::persisted::PersistedPeer;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use store::config::StoreConfig;
+    use store::{HotColdDB, MemoryStore};
+    use types::{ChainSpec, MinimalEthSpec};
+    #[test]
+    fn test_persisted_peers() {
+        let log = NullLoggerBuilder.build().unwrap();
+        let store: HotColdDB<
+            MinimalEthSpec,
+            MemoryStore<MinimalEthSpec>,
+            MemoryStore<MinimalEthSpec>,
+        > = HotColdDB::open_ephemeral(StoreConfig::default(), ChainSpec::minimal(), log).unwrap();
+        let store = Arc::new(store);
+        assert_eq!(load_peers(store.clone()), None);
+
+        let peers = PersistedPeers {
+            persisted_at: 1_650_000_000,
+            peers: vec![PersistedPeer {
+                peer_id: lighthouse_network::PeerId::random().to_bytes(),
+                lighthouse_score: (-55.0f64).to_bits(),
+                gossipsub_score: 0.0f64.to_bits(),
+                ban_remaining_secs: 3600,
+                connected: false,
+                enr: vec![],
+            }],
+        };
+        persist_peers(store.clone(), peers.clone()).unwrap();
+        assert_eq!(load_peers(store), Some(peers));
+    }
+}
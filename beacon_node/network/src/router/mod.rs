@@ -9,6 +9,7 @@ mod processor;
 
 use crate::error;
 use crate::service::{NetworkMessage, RequestId};
+use crate::sync::SyncBandwidthLimits;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use futures::prelude::*;
 use lighthouse_network::{
@@ -69,11 +70,15 @@ pub enum RouterMessage<T: EthSpec> {
 
 impl<T: BeaconChainTypes> Router<T> {
     /// Initializes and runs the Router.
+    #[allow(clippy::too_many_arguments)]
     pub fn spawn(
         beacon_chain: Arc<BeaconChain<T>>,
         network_globals: Arc<NetworkGlobals<T::EthSpec>>,
         network_send: mpsc::UnboundedSender<NetworkMessage<T::EthSpec>>,
         executor: task_executor::TaskExecutor,
+        max_workers: usize,
+        priority_workers: usize,
+        sync_bandwidth_limits: SyncBandwidthLimits,
         log: slog::Logger,
     ) -> error::Result<mpsc::UnboundedSender<RouterMessage<T::EthSpec>>> {
         let message_handler_log = log.new(o!("service"=> "router"));
@@ -87,6 +92,9 @@ impl<T: BeaconChainTypes> Router<T> {
             beacon_chain,
             network_globals.clone(),
             network_send,
+            max_workers,
+            priority_workers,
+            sync_bandwidth_limits,
             &log,
         );
 
@@ -168,6 +176,12 @@ impl<T: BeaconChainTypes> Router<T> {
             Request::BlocksByRoot(request) => self
                 .processor
                 .on_blocks_by_root_request(peer_id, id, request),
+            Request::BlobsByRange(request) => self
+                .processor
+                .on_blobs_by_range_request(peer_id, id, request),
+            Request::BlobsByRoot(request) => self
+                .processor
+                .on_blobs_by_root_request(peer_id, id, request),
         }
     }
 
@@ -192,6 +206,10 @@ impl<T: BeaconChainTypes> Router<T> {
                 self.processor
                     .on_blocks_by_root_response(peer_id, request_id, beacon_block);
             }
+            Response::BlobsByRange(blob_sidecar) | Response::BlobsByRoot(blob_sidecar) => {
+                self.processor
+                    .on_blobs_response(peer_id, request_id, blob_sidecar);
+            }
         }
     }
 
@@ -233,6 +251,18 @@ impl<T: BeaconChainTypes> Router<T> {
                 debug!(self.log, "Received a voluntary exit"; "peer_id" => %peer_id);
                 self.processor.on_voluntary_exit_gossip(id, peer_id, exit);
             }
+            PubsubMessage::BlsToExecutionChange(bls_to_execution_change) => {
+                debug!(
+                    self.log,
+                    "Received a BLS to execution change";
+                    "peer_id" => %peer_id
+                );
+                self.processor.on_bls_to_execution_change_gossip(
+                    id,
+                    peer_id,
+                    bls_to_execution_change,
+                );
+            }
             PubsubMessage::ProposerSlashing(proposer_slashing) => {
                 debug!(
                     self.log,
@@ -276,6 +306,30 @@ impl<T: BeaconChainTypes> Router<T> {
                     sync_committtee_msg.0,
                 );
             }
+            PubsubMessage::LightClientFinalityUpdate(light_client_finality_update) => {
+                trace!(
+                    self.log,
+                    "Received light client finality update";
+                    "peer_id" => %peer_id
+                );
+                self.processor.on_light_client_finality_update_gossip(
+                    id,
+                    peer_id,
+                    *light_client_finality_update,
+                );
+            }
+            PubsubMessage::LightClientOptimisticUpdate(light_client_optimistic_update) => {
+                trace!(
+                    self.log,
+                    "Received light client optimistic update";
+                    "peer_id" => %peer_id
+                );
+                self.processor.on_light_client_optimistic_update_gossip(
+                    id,
+                    peer_id,
+                    *light_client_optimistic_update,
+                );
+            }
         }
     }
 }
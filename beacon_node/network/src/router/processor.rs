@@ -1,23 +1,27 @@
 use crate::beacon_processor::{
-    BeaconProcessor, WorkEvent as BeaconWorkEvent, MAX_WORK_EVENT_QUEUE_LEN,
+    BeaconProcessor, WorkEvent as BeaconWorkEvent, DROP_REASON_CHANNEL_FULL,
+    MAX_WORK_EVENT_QUEUE_LEN,
 };
+use crate::metrics;
 use crate::service::{NetworkMessage, RequestId};
 use crate::sync::manager::RequestId as SyncId;
-use crate::sync::SyncMessage;
+use crate::sync::{SyncBandwidthLimits, SyncMessage};
 use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
 use lighthouse_network::rpc::*;
 use lighthouse_network::{
     Client, MessageId, NetworkGlobals, PeerId, PeerRequestId, Request, Response,
 };
 use slog::{debug, error, o, trace, warn};
-use std::cmp;
+use slot_clock::SlotClock;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use store::SyncCommitteeMessage;
 use tokio::sync::mpsc;
 use types::{
-    Attestation, AttesterSlashing, EthSpec, ProposerSlashing, SignedAggregateAndProof,
-    SignedBeaconBlock, SignedContributionAndProof, SignedVoluntaryExit, SubnetId, SyncSubnetId,
+    consts::merge::INTERVALS_PER_SLOT, Attestation, AttesterSlashing, BlobSidecar, EthSpec,
+    LightClientFinalityUpdate, LightClientOptimisticUpdate, ProposerSlashing,
+    SignedAggregateAndProof, SignedBeaconBlock, SignedBlsToExecutionChange,
+    SignedContributionAndProof, SignedVoluntaryExit, Slot, SubnetId, SyncSubnetId,
 };
 
 /// Processes validated messages from the network. It relays necessary data to the syncing thread
@@ -25,6 +29,8 @@ use types::{
 pub struct Processor<T: BeaconChainTypes> {
     /// A reference to the underlying beacon chain.
     chain: Arc<BeaconChain<T>>,
+    /// A collection of global variables, accessible outside of the network service.
+    network_globals: Arc<NetworkGlobals<T::EthSpec>>,
     /// A channel to the syncing thread.
     sync_send: mpsc::UnboundedSender<SyncMessage<T::EthSpec>>,
     /// A network context to return and handle RPC requests.
@@ -37,11 +43,15 @@ pub struct Processor<T: BeaconChainTypes> {
 
 impl<T: BeaconChainTypes> Processor<T> {
     /// Instantiate a `Processor` instance
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         executor: task_executor::TaskExecutor,
         beacon_chain: Arc<BeaconChain<T>>,
         network_globals: Arc<NetworkGlobals<T::EthSpec>>,
         network_send: mpsc::UnboundedSender<NetworkMessage<T::EthSpec>>,
+        max_workers: usize,
+        priority_workers: usize,
+        sync_bandwidth_limits: SyncBandwidthLimits,
         log: &slog::Logger,
     ) -> Self {
         let sync_logger = log.new(o!("service"=> "sync"));
@@ -55,6 +65,7 @@ impl<T: BeaconChainTypes> Processor<T> {
             network_globals.clone(),
             network_send.clone(),
             beacon_processor_send.clone(),
+            sync_bandwidth_limits,
             sync_logger,
         );
 
@@ -62,9 +73,10 @@ impl<T: BeaconChainTypes> Processor<T> {
             beacon_chain: Arc::downgrade(&beacon_chain),
             network_tx: network_send.clone(),
             sync_tx: sync_send.clone(),
-            network_globals,
+            network_globals: network_globals.clone(),
             executor,
-            max_workers: cmp::max(1, num_cpus::get()),
+            max_workers,
+            priority_workers,
             current_workers: 0,
             importing_blocks: Default::default(),
             log: log.clone(),
@@ -73,6 +85,7 @@ impl<T: BeaconChainTypes> Processor<T> {
 
         Processor {
             chain: beacon_chain,
+            network_globals,
             sync_send,
             network: HandlerNetworkContext::new(network_send, log.clone()),
             beacon_processor_send,
@@ -172,6 +185,30 @@ impl<T: BeaconChainTypes> Processor<T> {
         ))
     }
 
+    /// Handle a `BlobsByRoot` request from the peer.
+    pub fn on_blobs_by_root_request(
+        &mut self,
+        peer_id: PeerId,
+        request_id: PeerRequestId,
+        request: BlobsByRootRequest,
+    ) {
+        self.send_beacon_processor_work(BeaconWorkEvent::blobs_by_roots_request(
+            peer_id, request_id, request,
+        ))
+    }
+
+    /// Handle a `BlobsByRange` request from the peer.
+    pub fn on_blobs_by_range_request(
+        &mut self,
+        peer_id: PeerId,
+        request_id: PeerRequestId,
+        req: BlobsByRangeRequest,
+    ) {
+        self.send_beacon_processor_work(BeaconWorkEvent::blobs_by_range_request(
+            peer_id, request_id, req,
+        ))
+    }
+
     /// Handle a `BlocksByRange` response from the peer.
     /// A `beacon_block` behaves as a stream which is terminated on a `None` response.
     pub fn on_blocks_by_range_response(
@@ -186,6 +223,12 @@ impl<T: BeaconChainTypes> Processor<T> {
                     unreachable!("Block lookups do not request BBRange requests")
                 }
                 id @ (SyncId::BackFillSync { .. } | SyncId::RangeSync { .. }) => id,
+                SyncId::SingleBlockBlobs { .. }
+                | SyncId::ParentLookupBlobs { .. }
+                | SyncId::BackFillSyncBlobs { .. }
+                | SyncId::RangeSyncBlobs { .. } => {
+                    unreachable!("Blobs requests do not receive blocks")
+                }
             },
             RequestId::Router => unreachable!("All BBRange requests belong to sync"),
         };
@@ -217,6 +260,12 @@ impl<T: BeaconChainTypes> Processor<T> {
                 SyncId::BackFillSync { .. } | SyncId::RangeSync { .. } => {
                     unreachable!("Batch syncing do not request BBRoot requests")
                 }
+                SyncId::SingleBlockBlobs { .. }
+                | SyncId::ParentLookupBlobs { .. }
+                | SyncId::BackFillSyncBlobs { .. }
+                | SyncId::RangeSyncBlobs { .. } => {
+                    unreachable!("Blobs requests do not receive blocks")
+                }
             },
             RequestId::Router => unreachable!("All BBRoot requests belong to sync"),
         };
@@ -234,6 +283,43 @@ impl<T: BeaconChainTypes> Processor<T> {
         });
     }
 
+    /// Handle a `BlobsByRange` or `BlobsByRoot` response from the peer.
+    /// A `blob_sidecar` behaves as a stream which is terminated on a `None` response.
+    pub fn on_blobs_response(
+        &mut self,
+        peer_id: PeerId,
+        request_id: RequestId,
+        blob_sidecar: Option<Arc<BlobSidecar<T::EthSpec>>>,
+    ) {
+        let request_id = match request_id {
+            RequestId::Sync(sync_id) => match sync_id {
+                id @ (SyncId::SingleBlockBlobs { .. }
+                | SyncId::ParentLookupBlobs { .. }
+                | SyncId::BackFillSyncBlobs { .. }
+                | SyncId::RangeSyncBlobs { .. }) => id,
+                SyncId::SingleBlock { .. }
+                | SyncId::ParentLookup { .. }
+                | SyncId::BackFillSync { .. }
+                | SyncId::RangeSync { .. } => {
+                    unreachable!("Blocks requests do not receive blobs")
+                }
+            },
+            RequestId::Router => unreachable!("All blobs requests belong to sync"),
+        };
+
+        trace!(
+            self.log,
+            "Received Blobs Response";
+            "peer" => %peer_id,
+        );
+        self.send_to_sync(SyncMessage::RpcBlob {
+            peer_id,
+            request_id,
+            blob_sidecar,
+            seen_timestamp: timestamp_now(),
+        });
+    }
+
     /// Process a gossip message declaring a new block.
     ///
     /// Attempts to apply to block to the beacon chain. May queue the block for later processing.
@@ -279,14 +365,35 @@ impl<T: BeaconChainTypes> Processor<T> {
         peer_id: PeerId,
         aggregate: SignedAggregateAndProof<T::EthSpec>,
     ) {
+        let seen_timestamp = timestamp_now();
+        self.record_clock_skew_sample(
+            peer_id,
+            aggregate.message.aggregate.data.slot,
+            seen_timestamp,
+        );
         self.send_beacon_processor_work(BeaconWorkEvent::aggregated_attestation(
             message_id,
             peer_id,
             aggregate,
-            timestamp_now(),
+            seen_timestamp,
         ))
     }
 
+    /// Records the time an aggregate for `slot` arrived from `peer_id`, relative to when our clock
+    /// says honest aggregators publish it, as a sample of our clock's offset from the network's.
+    fn record_clock_skew_sample(&self, peer_id: PeerId, slot: Slot, seen_timestamp: Duration) {
+        let slot_clock = &self.chain.slot_clock;
+        if let Some(slot_start) = slot_clock.start_of(slot) {
+            // Aggregates are published two thirds of the way through the slot.
+            let published = slot_start + slot_clock.slot_duration() * 2 / INTERVALS_PER_SLOT as u32;
+            let offset_ms = seen_timestamp.as_millis() as i64 - published.as_millis() as i64;
+            self.network_globals
+                .clock_skew_samples
+                .write()
+                .record(peer_id, offset_ms);
+        }
+    }
+
     pub fn on_voluntary_exit_gossip(
         &mut self,
         message_id: MessageId,
@@ -300,6 +407,19 @@ impl<T: BeaconChainTypes> Processor<T> {
         ))
     }
 
+    pub fn on_bls_to_execution_change_gossip(
+        &mut self,
+        message_id: MessageId,
+        peer_id: PeerId,
+        bls_to_execution_change: Box<SignedBlsToExecutionChange>,
+    ) {
+        self.send_beacon_processor_work(BeaconWorkEvent::gossip_bls_to_execution_change(
+            message_id,
+            peer_id,
+            bls_to_execution_change,
+        ))
+    }
+
     pub fn on_proposer_slashing_gossip(
         &mut self,
         message_id: MessageId,
@@ -356,6 +476,34 @@ impl<T: BeaconChainTypes> Processor<T> {
         ))
     }
 
+    pub fn on_light_client_finality_update_gossip(
+        &mut self,
+        message_id: MessageId,
+        peer_id: PeerId,
+        light_client_finality_update: LightClientFinalityUpdate<T::EthSpec>,
+    ) {
+        self.send_beacon_processor_work(BeaconWorkEvent::gossip_light_client_finality_update(
+            message_id,
+            peer_id,
+            light_client_finality_update,
+            timestamp_now(),
+        ))
+    }
+
+    pub fn on_light_client_optimistic_update_gossip(
+        &mut self,
+        message_id: MessageId,
+        peer_id: PeerId,
+        light_client_optimistic_update: LightClientOptimisticUpdate<T::EthSpec>,
+    ) {
+        self.send_beacon_processor_work(BeaconWorkEvent::gossip_light_client_optimistic_update(
+            message_id,
+            peer_id,
+            light_client_optimistic_update,
+            timestamp_now(),
+        ))
+    }
+
     fn send_beacon_processor_work(&mut self, work: BeaconWorkEvent<T>) {
         self.beacon_processor_send
             .try_send(work)
@@ -364,6 +512,12 @@ impl<T: BeaconChainTypes> Processor<T> {
                     mpsc::error::TrySendError::Closed(work)
                     | mpsc::error::TrySendError::Full(work) => work.work_type(),
                 };
+                if let mpsc::error::TrySendError::Full(_) = e {
+                    metrics::inc_counter_vec(
+                        &metrics::BEACON_PROCESSOR_QUEUE_DROPPED_TOTAL,
+                        &[work_type, DROP_REASON_CHANNEL_FULL],
+                    );
+                }
                 error!(&self.log, "Unable to send message to the beacon processor";
                     "error" => %e, "type" => work_type)
             })
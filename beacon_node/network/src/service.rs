@@ -1,7 +1,10 @@
 use super::sync::manager::RequestId as SyncId;
+use crate::nat::PortMappingConfig;
 use crate::persisted_dht::{clear_dht, load_dht, persist_dht};
+use crate::persisted_peers::{load_peers, persist_peers};
 use crate::router::{Router, RouterMessage};
 use crate::subnet_service::SyncCommitteeService;
+use crate::sync::SyncBandwidthLimits;
 use crate::{error, metrics};
 use crate::{
     subnet_service::{AttestationService, SubnetServiceMessage},
@@ -21,17 +24,17 @@ use lighthouse_network::{
 };
 use lighthouse_network::{
     types::{GossipEncoding, GossipTopic},
-    BehaviourEvent, MessageId, NetworkGlobals, PeerId,
+    BehaviourEvent, Enr, EnrUpdate, MessageId, NetworkGlobals, PeerId, PortMappingStatus,
 };
 use slog::{crit, debug, error, info, o, trace, warn};
-use std::{net::SocketAddr, pin::Pin, sync::Arc, time::Duration};
+use std::{cmp, net::SocketAddr, pin::Pin, sync::Arc, time::Duration};
 use store::HotColdDB;
 use task_executor::ShutdownReason;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 use tokio::time::Sleep;
 use types::{
     ChainSpec, EthSpec, ForkContext, RelativeEpoch, Slot, SubnetId, SyncCommitteeSubscription,
-    SyncSubnetId, Unsigned, ValidatorSubscription,
+    SyncSubnetId, Uint256, Unsigned, ValidatorSubscription,
 };
 
 mod tests;
@@ -93,12 +96,13 @@ pub enum NetworkMessage<T: EthSpec> {
         /// The result of the validation
         validation_result: MessageAcceptance,
     },
-    /// Called if a known external TCP socket address has been updated.
-    UPnPMappingEstablished {
-        /// The external TCP address has been updated.
-        tcp_socket: Option<SocketAddr>,
-        /// The external UDP address has been updated.
-        udp_socket: Option<SocketAddr>,
+    /// Reports the result of an attempt to establish or renew the port mappings on the local
+    /// gateway.
+    PortMappingsUpdated(PortMappingStatus),
+    /// Update the advertised fields of the local ENR, returning the new ENR via `result_tx`.
+    UpdateEnr {
+        update: EnrUpdate,
+        result_tx: oneshot::Sender<Result<Enr, String>>,
     },
     /// Reports a peer to the peer manager for performing an action.
     ReportPeer {
@@ -134,11 +138,11 @@ pub struct NetworkService<T: BeaconChainTypes> {
     store: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
     /// A collection of global variables, accessible outside of the network service.
     network_globals: Arc<NetworkGlobals<T::EthSpec>>,
-    /// Stores potentially created UPnP mappings to be removed on shutdown. (TCP port and UDP
-    /// port).
-    upnp_mappings: (Option<u16>, Option<u16>),
+    /// The configuration of the port mappings, if enabled. Any mappings established with it are
+    /// removed on shutdown.
+    port_mapping_config: Option<PortMappingConfig>,
     /// Keeps track of if discovery is auto-updating or not. This is used to inform us if we should
-    /// update the UDP socket of discovery if the UPnP mappings get established.
+    /// update the UDP socket of discovery if the port mappings get established.
     discovery_auto_update: bool,
     /// A delay that expires when a new fork takes place.
     next_fork_update: Pin<Box<OptionFuture<Sleep>>>,
@@ -148,6 +152,8 @@ pub struct NetworkService<T: BeaconChainTypes> {
     next_unsubscribe: Pin<Box<OptionFuture<Sleep>>>,
     /// Subscribe to all the subnets once synced.
     subscribe_all_subnets: bool,
+    /// Subscribe to the light client gossip topics.
+    enable_light_client_server: bool,
     /// Shutdown beacon node after sync is complete.
     shutdown_after_sync: bool,
     /// Whether metrics are enabled or not.
@@ -176,16 +182,17 @@ impl<T: BeaconChainTypes> NetworkService<T> {
         // build the network channel
         let (network_send, network_recv) = mpsc::unbounded_channel::<NetworkMessage<T::EthSpec>>();
 
-        // try and construct UPnP port mappings if required.
-        let upnp_config = crate::nat::UPnPConfig::from(config);
-        let upnp_log = network_log.new(o!("service" => "UPnP"));
-        let upnp_network_send = network_send.clone();
-        if config.upnp_enabled {
-            executor.spawn_blocking(
-                move || {
-                    crate::nat::construct_upnp_mappings(upnp_config, upnp_network_send, upnp_log)
-                },
-                "UPnP",
+        // try and construct port mappings if required, renewing them for as long as we run.
+        let port_mapping_config = config.upnp_enabled.then(|| PortMappingConfig::from(config));
+        if let Some(port_mapping_config) = port_mapping_config.clone() {
+            let port_mapping_log = network_log.new(o!("service" => "port_mapping"));
+            executor.spawn(
+                crate::nat::maintain_port_mappings(
+                    port_mapping_config,
+                    network_send.clone(),
+                    port_mapping_log,
+                ),
+                "port_mapping",
             );
         }
 
@@ -238,6 +245,16 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             }
         }
 
+        // Restore the reputations of the peers we knew before the last shutdown, so that banned
+        // peers remain banned and good peers are redialed.
+        if let Some(peers) = load_peers::<T::EthSpec, T::HotStore, T::ColdStore>(store.clone()) {
+            debug!(
+                network_log,
+                "Restoring persisted peers"; "peers" => peers.peers.len()
+            );
+            libp2p.swarm.behaviour_mut().restore_peers(&peers);
+        }
+
         // launch derived network services
 
         // router task
@@ -246,12 +263,21 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             network_globals.clone(),
             network_send.clone(),
             executor.clone(),
+            config
+                .beacon_processor_max_workers
+                .unwrap_or_else(|| cmp::max(1, num_cpus::get())),
+            config.beacon_processor_priority_workers,
+            SyncBandwidthLimits {
+                sync_max_mbps: config.sync_max_bandwidth,
+                backfill_max_mbps: config.backfill_max_bandwidth,
+            },
             network_log.clone(),
         )?;
 
         // attestation subnet service
+        let node_id = Uint256::from_big_endian(&network_globals.local_enr().node_id().raw());
         let attestation_service =
-            AttestationService::new(beacon_chain.clone(), config, &network_log);
+            AttestationService::new(beacon_chain.clone(), node_id, config, &network_log);
 
         // sync committee subnet service
         let sync_committee_service =
@@ -274,12 +300,13 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             router_send,
             store,
             network_globals: network_globals.clone(),
-            upnp_mappings: (None, None),
+            port_mapping_config,
             discovery_auto_update: config.discv5_config.enr_update,
             next_fork_update,
             next_fork_subscriptions,
             next_unsubscribe,
             subscribe_all_subnets: config.subscribe_all_subnets,
+            enable_light_client_server: config.enable_light_client_server,
             shutdown_after_sync: config.shutdown_after_sync,
             metrics_enabled: config.metrics_enabled,
             metrics_update,
@@ -380,7 +407,7 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                             let fork_version = self.beacon_chain.spec.fork_version_for_name(fork_name);
                             let fork_digest = ChainSpec::compute_fork_digest(fork_version, self.beacon_chain.genesis_validators_root);
                             info!(self.log, "Subscribing to new fork topics");
-                            self.libp2p.swarm.behaviour_mut().subscribe_new_fork_topics(fork_digest);
+                            self.libp2p.swarm.behaviour_mut().subscribe_new_fork_topics(fork_name, fork_digest);
                             self.next_fork_subscriptions = Box::pin(None.into());
                         }
                         else {
@@ -528,38 +555,19 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             } => {
                 self.libp2p.respond_with_error(peer_id, id, error, reason);
             }
-            NetworkMessage::UPnPMappingEstablished {
-                tcp_socket,
-                udp_socket,
-            } => {
-                self.upnp_mappings = (tcp_socket.map(|s| s.port()), udp_socket.map(|s| s.port()));
-                // If there is an external TCP port update, modify our local ENR.
-                if let Some(tcp_socket) = tcp_socket {
-                    if let Err(e) = self
-                        .libp2p
-                        .swarm
-                        .behaviour_mut()
-                        .discovery_mut()
-                        .update_enr_tcp_port(tcp_socket.port())
-                    {
-                        warn!(self.log, "Failed to update ENR"; "error" => e);
-                    }
-                }
-                // if the discovery service is not auto-updating, update it with the
-                // UPnP mappings
-                if !self.discovery_auto_update {
-                    if let Some(udp_socket) = udp_socket {
-                        if let Err(e) = self
-                            .libp2p
-                            .swarm
-                            .behaviour_mut()
-                            .discovery_mut()
-                            .update_enr_udp_socket(udp_socket)
-                        {
-                            warn!(self.log, "Failed to update ENR"; "error" => e);
-                        }
-                    }
+            NetworkMessage::PortMappingsUpdated(status) => self.update_port_mappings(status),
+            NetworkMessage::UpdateEnr { update, result_tx } => {
+                let result = self
+                    .libp2p
+                    .swarm
+                    .behaviour_mut()
+                    .discovery_mut()
+                    .update_enr(update);
+                if let Err(e) = &result {
+                    warn!(self.log, "Failed to update ENR"; "error" => e);
                 }
+                // The requester may have given up waiting, which is fine.
+                let _ = result_tx.send(result);
             }
             NetworkMessage::ValidationResult {
                 propagation_source,
@@ -640,13 +648,16 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                     return;
                 }
                 let mut subscribed_topics: Vec<GossipTopic> = vec![];
-                for topic_kind in lighthouse_network::types::CORE_TOPICS.iter() {
-                    for fork_digest in self.required_gossip_fork_digests() {
-                        let topic = GossipTopic::new(
-                            topic_kind.clone(),
-                            GossipEncoding::default(),
-                            fork_digest,
-                        );
+                for fork_digest in self.required_gossip_fork_digests() {
+                    let fork_name = self
+                        .fork_context
+                        .from_context_bytes(fork_digest)
+                        .copied()
+                        .unwrap_or_else(|| self.fork_context.current_fork());
+                    for topic_kind in lighthouse_network::types::core_topics_to_subscribe(fork_name)
+                    {
+                        let topic =
+                            GossipTopic::new(topic_kind, GossipEncoding::default(), fork_digest);
                         if self.libp2p.swarm.behaviour_mut().subscribe(topic.clone()) {
                             subscribed_topics.push(topic);
                         } else {
@@ -655,6 +666,24 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                     }
                 }
 
+                if self.enable_light_client_server {
+                    let light_client_topics = lighthouse_network::types::LIGHT_CLIENT_GOSSIP_TOPICS;
+                    for topic_kind in light_client_topics.iter() {
+                        for fork_digest in self.required_gossip_fork_digests() {
+                            let topic = GossipTopic::new(
+                                topic_kind.clone(),
+                                GossipEncoding::default(),
+                                fork_digest,
+                            );
+                            if self.libp2p.swarm.behaviour_mut().subscribe(topic.clone()) {
+                                subscribed_topics.push(topic);
+                            } else {
+                                warn!(self.log, "Could not subscribe to topic"; "topic" => %topic);
+                            }
+                        }
+                    }
+                }
+
                 // If we are to subscribe to all subnets we do it here
                 if self.subscribe_all_subnets {
                     for subnet_id in 0..<<T as BeaconChainTypes>::EthSpec as EthSpec>::SubnetBitfieldLength::to_u64() {
@@ -704,6 +733,82 @@ impl<T: BeaconChainTypes> NetworkService<T> {
         }
     }
 
+    /// Records the latest port mappings, updating our ENR with any newly mapped external ports.
+    fn update_port_mappings(&mut self, status: PortMappingStatus) {
+        let previous = std::mem::replace(
+            &mut *self.network_globals.port_mappings.write(),
+            status.clone(),
+        );
+
+        if status.is_mapped() {
+            let unchanged = status.method == previous.method
+                && status.external_ip == previous.external_ip
+                && status.tcp_port == previous.tcp_port
+                && status.udp_port == previous.udp_port;
+            if unchanged {
+                debug!(self.log, "Port mappings renewed"; "method" => ?status.method);
+            } else {
+                info!(
+                    self.log,
+                    "Port mappings established";
+                    "method" => ?status.method,
+                    "external_ip" => ?status.external_ip,
+                    "tcp_port" => ?status.tcp_port,
+                    "udp_port" => ?status.udp_port,
+                );
+            }
+        } else if previous.is_mapped() {
+            warn!(
+                self.log,
+                "Port mappings could not be renewed";
+                "error" => ?status.error,
+            );
+        } else if previous.error.is_none() {
+            info!(
+                self.log,
+                "UPnP and NAT-PMP not available";
+                "error" => ?status.error,
+            );
+        }
+
+        // If there is an external TCP port update, modify our local ENR.
+        if let Some(tcp_port) = status
+            .tcp_port
+            .filter(|port| previous.tcp_port != Some(*port))
+        {
+            if let Err(e) = self
+                .libp2p
+                .swarm
+                .behaviour_mut()
+                .discovery_mut()
+                .update_enr_tcp_port(tcp_port)
+            {
+                warn!(self.log, "Failed to update ENR"; "error" => e);
+            }
+        }
+        // if the discovery service is not auto-updating, update it with the port mappings
+        if !self.discovery_auto_update {
+            if let (Some(ip), Some(udp_port)) = (status.external_ip, status.udp_port) {
+                let udp_socket = SocketAddr::new(ip, udp_port);
+                let previous_udp_socket = previous
+                    .external_ip
+                    .zip(previous.udp_port)
+                    .map(|(ip, port)| SocketAddr::new(ip, port));
+                if previous_udp_socket != Some(udp_socket) {
+                    if let Err(e) = self
+                        .libp2p
+                        .swarm
+                        .behaviour_mut()
+                        .discovery_mut()
+                        .update_enr_udp_socket(udp_socket)
+                    {
+                        warn!(self.log, "Failed to update ENR"; "error" => e);
+                    }
+                }
+            }
+        }
+    }
+
     fn update_gossipsub_parameters(&mut self) {
         if let Ok(slot) = self.beacon_chain.slot() {
             if let Some(active_validators) = self
@@ -907,8 +1012,31 @@ impl<T: BeaconChainTypes> Drop for NetworkService<T> {
             ),
         }
 
+        let peers = self
+            .libp2p
+            .swarm
+            .behaviour_mut()
+            .peer_manager()
+            .persisted_peers();
+        debug!(
+            self.log,
+            "Persisting peer reputations to store";
+            "Number of peers" => peers.peers.len(),
+        );
+        if let Err(e) =
+            persist_peers::<T::EthSpec, T::HotStore, T::ColdStore>(self.store.clone(), peers)
+        {
+            error!(self.log, "Failed to persist peer reputations on drop"; "error" => ?e);
+        }
+
         // attempt to remove port mappings
-        crate::nat::remove_mappings(self.upnp_mappings.0, self.upnp_mappings.1, &self.log);
+        if let Some(config) = &self.port_mapping_config {
+            crate::nat::remove_mappings(
+                config,
+                &self.network_globals.port_mappings.read(),
+                &self.log,
+            );
+        }
 
         info!(self.log, "Network service shutdown");
     }
@@ -4,6 +4,7 @@
 
 use super::SubnetServiceMessage;
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -17,7 +18,8 @@ use beacon_chain::{BeaconChain, BeaconChainTypes};
 use hashset_delay::HashSetDelay;
 use lighthouse_network::{NetworkConfig, Subnet, SubnetDiscovery};
 use slot_clock::SlotClock;
-use types::{Attestation, EthSpec, Slot, SubnetId, ValidatorSubscription};
+use tokio::time::Sleep;
+use types::{Attestation, EthSpec, Slot, SubnetId, Uint256, ValidatorSubscription};
 
 use crate::metrics;
 
@@ -25,6 +27,9 @@ use crate::metrics;
 /// slot is less than this number, skip the peer discovery process.
 /// Subnet discovery query takes at most 30 secs, 2 slots take 24s.
 const MIN_PEER_DISCOVERY_SLOT_LOOK_AHEAD: u64 = 2;
+/// The number of slots before a subscription slot that we check again for peers on its subnet, in
+/// case the peers found when the subscription was made have since disconnected.
+const PRE_DUTY_DISCOVERY_SLOT_LOOK_AHEAD: u64 = 3;
 /// The time (in slots) before a last seen validator is considered absent and we unsubscribe from the random
 /// gossip topics that we subscribed to due to the validator connection.
 const LAST_SEEN_VALIDATOR_TIMEOUT: u32 = 150;
@@ -55,6 +60,16 @@ pub struct AttestationService<T: BeaconChainTypes> {
     /// The collection of currently subscribed random subnets mapped to their expiry deadline.
     pub(crate) random_subnets: HashSetDelay<SubnetId>,
 
+    /// The node id from which our deterministic long-lived subnets are computed, if they are
+    /// used instead of random subnets.
+    long_lived_node_id: Option<Uint256>,
+
+    /// The currently subscribed deterministic long-lived subnets.
+    pub(crate) long_lived_subnets: HashSet<SubnetId>,
+
+    /// Fires when the deterministic long-lived subnets should next be recomputed.
+    next_long_lived_subscription_event: Option<Pin<Box<Sleep>>>,
+
     /// The collection of all currently subscribed subnets (long-lived **and** short-lived).
     subscriptions: HashSet<SubnetId>,
 
@@ -64,6 +79,9 @@ pub struct AttestationService<T: BeaconChainTypes> {
     /// A collection timeouts to track the existence of aggregate validator subscriptions at an `ExactSubnet`.
     aggregate_validators_on_subnet: HashSetDelay<ExactSubnet>,
 
+    /// Subscriptions for which to repeat the subnet peer discovery shortly before their slot.
+    pre_duty_discoveries: HashSetDelay<ExactSubnet>,
+
     /// A collection of seen validators. These dictate how many random subnets we should be
     /// subscribed to. As these time out, we unsubscribe for the required random subnets and update
     /// our ENR.
@@ -91,6 +109,7 @@ impl<T: BeaconChainTypes> AttestationService<T> {
 
     pub fn new(
         beacon_chain: Arc<BeaconChain<T>>,
+        node_id: Uint256,
         config: &NetworkConfig,
         log: &slog::Logger,
     ) -> Self {
@@ -112,20 +131,31 @@ impl<T: BeaconChainTypes> AttestationService<T> {
             .checked_mul(DEFAULT_EXPIRATION_TIMEOUT)
             .expect("DEFAULT_EXPIRATION_TIMEOUT must not be ridiculoustly large");
 
-        AttestationService {
+        let use_long_lived_subnets =
+            config.deterministic_long_lived_attnets && !config.subscribe_all_subnets;
+
+        let mut service = AttestationService {
             events: VecDeque::with_capacity(10),
             beacon_chain,
             random_subnets: HashSetDelay::new(Duration::from_millis(random_subnet_duration_millis)),
+            long_lived_node_id: use_long_lived_subnets.then(|| node_id),
+            long_lived_subnets: HashSet::new(),
+            next_long_lived_subscription_event: use_long_lived_subnets
+                .then(|| Box::pin(tokio::time::sleep(Duration::ZERO))),
             subscriptions: HashSet::new(),
             unsubscriptions: HashSetDelay::new(default_timeout),
             aggregate_validators_on_subnet: HashSetDelay::new(default_timeout),
+            pre_duty_discoveries: HashSetDelay::new(default_timeout),
             known_validators: HashSetDelay::new(last_seen_val_timeout),
             waker: None,
             subscribe_all_subnets: config.subscribe_all_subnets,
             import_all_attestations: config.import_all_attestations,
             discovery_disabled: config.disable_discovery,
             log,
-        }
+        };
+
+        service.recompute_long_lived_subnets();
+        service
     }
 
     /// Return count of all currently subscribed subnets (long-lived **and** short-lived).
@@ -199,6 +229,10 @@ impl<T: BeaconChainTypes> AttestationService<T> {
                 slot: subscription.slot,
             };
 
+            if !self.discovery_disabled {
+                self.schedule_pre_duty_discovery(exact_subnet.clone());
+            }
+
             // Determine if the validator is an aggregator. If so, we subscribe to the subnet and
             // if successful add the validator to a mapping of known aggregators for that exact
             // subnet.
@@ -311,6 +345,32 @@ impl<T: BeaconChainTypes> AttestationService<T> {
         Ok(())
     }
 
+    /// Schedules a repeat of the peer discovery for `exact_subnet` `PRE_DUTY_DISCOVERY_SLOT_LOOK_AHEAD`
+    /// slots before its slot, if the subscription is further away than that.
+    ///
+    /// Peers found when a subscription is made may be disconnected by the time of the duty, which
+    /// would leave us publishing to an empty mesh. The repeated request is ignored by the network
+    /// if we are still connected to enough peers on the subnet.
+    fn schedule_pre_duty_discovery(&mut self, exact_subnet: ExactSubnet) {
+        if self.pre_duty_discoveries.contains(&exact_subnet) {
+            return;
+        }
+        let slot_clock = &self.beacon_chain.slot_clock;
+        let current_slot = match slot_clock.now() {
+            Some(slot) => slot,
+            None => return,
+        };
+        let discovery_slot = exact_subnet
+            .slot
+            .saturating_sub(PRE_DUTY_DISCOVERY_SLOT_LOOK_AHEAD);
+        if discovery_slot <= current_slot {
+            return;
+        }
+        if let Some(delay) = slot_clock.duration_to_slot(discovery_slot) {
+            self.pre_duty_discoveries.insert_at(exact_subnet, delay);
+        }
+    }
+
     /// Checks the current random subnets and subscriptions to determine if a new subscription for this
     /// subnet is required for the given slot.
     ///
@@ -378,7 +438,10 @@ impl<T: BeaconChainTypes> AttestationService<T> {
     ///
     /// This also updates the ENR to indicate our long-lived subscription to the subnet
     fn add_known_validator(&mut self, validator_index: u64) {
-        if self.known_validators.get(&validator_index).is_none() && !self.subscribe_all_subnets {
+        if self.known_validators.get(&validator_index).is_none()
+            && !self.subscribe_all_subnets
+            && self.long_lived_node_id.is_none()
+        {
             // New validator has subscribed
             // Subscribe to random topics and update the ENR if needed.
 
@@ -452,6 +515,99 @@ impl<T: BeaconChainTypes> AttestationService<T> {
         }
     }
 
+    /// Subscribe to the deterministic long-lived subnets for the current epoch, unsubscribing from
+    /// any which are no longer required, and schedule the next recomputation.
+    ///
+    /// Does nothing if deterministic long-lived subnets aren't in use.
+    fn recompute_long_lived_subnets(&mut self) {
+        let node_id = match self.long_lived_node_id {
+            Some(node_id) => node_id,
+            None => return,
+        };
+        let slot_clock = &self.beacon_chain.slot_clock;
+        let slot_duration = slot_clock.slot_duration();
+
+        let computed = slot_clock
+            .now()
+            .ok_or("Could not get the current slot")
+            .and_then(|slot| {
+                SubnetId::compute_subnets_for_epoch(
+                    node_id,
+                    slot.epoch(T::EthSpec::slots_per_epoch()),
+                    &self.beacon_chain.spec,
+                )
+            });
+        let (subnets, valid_until) = match computed {
+            Ok((subnets, valid_until)) => (subnets.collect::<HashSet<_>>(), valid_until),
+            Err(e) => {
+                // Retry next slot, e.g. if we're before genesis.
+                debug!(self.log, "Unable to compute long-lived subnets"; "error" => e);
+                self.schedule_long_lived_subnets_recomputation(slot_duration);
+                return;
+            }
+        };
+
+        let next_event = slot_clock
+            .duration_to_slot(valid_until.start_slot(T::EthSpec::slots_per_epoch()))
+            .unwrap_or(slot_duration);
+        self.schedule_long_lived_subnets_recomputation(next_event);
+
+        let expired = self
+            .long_lived_subnets
+            .difference(&subnets)
+            .copied()
+            .collect::<Vec<_>>();
+        for subnet_id in expired {
+            self.long_lived_subnets.remove(&subnet_id);
+            // Remain subscribed if there are short-lived subscriptions for the subnet, they'll
+            // unsubscribe when they expire.
+            if !self
+                .unsubscriptions
+                .keys()
+                .any(|s| s.subnet_id == subnet_id)
+            {
+                debug!(self.log, "Unsubscribing from long-lived subnet"; "subnet_id" => *subnet_id);
+                self.subscriptions.remove(&subnet_id);
+                self.events
+                    .push_back(SubnetServiceMessage::Unsubscribe(Subnet::Attestation(
+                        subnet_id,
+                    )));
+            }
+            self.events
+                .push_back(SubnetServiceMessage::EnrRemove(Subnet::Attestation(
+                    subnet_id,
+                )));
+        }
+
+        let new_subnets = subnets
+            .difference(&self.long_lived_subnets)
+            .copied()
+            .collect::<Vec<_>>();
+        for subnet_id in new_subnets {
+            self.long_lived_subnets.insert(subnet_id);
+            self.events
+                .push_back(SubnetServiceMessage::DiscoverPeers(vec![SubnetDiscovery {
+                    subnet: Subnet::Attestation(subnet_id),
+                    min_ttl: None,
+                }]));
+            if self.subscriptions.insert(subnet_id) {
+                debug!(self.log, "Subscribing to long-lived subnet"; "subnet_id" => *subnet_id, "until_epoch" => valid_until);
+                self.events
+                    .push_back(SubnetServiceMessage::Subscribe(Subnet::Attestation(
+                        subnet_id,
+                    )));
+            }
+            self.events
+                .push_back(SubnetServiceMessage::EnrAdd(Subnet::Attestation(subnet_id)));
+        }
+    }
+
+    fn schedule_long_lived_subnets_recomputation(&mut self, delay: Duration) {
+        if let Some(event) = self.next_long_lived_subscription_event.as_mut() {
+            event.as_mut().reset(tokio::time::Instant::now() + delay);
+        }
+    }
+
     /* A collection of functions that handle the various timeouts */
 
     /// A queued subscription is ready.
@@ -497,8 +653,10 @@ impl<T: BeaconChainTypes> AttestationService<T> {
     /// Unsubscription events are added, even if we are subscribed to long-lived random subnets. If
     /// a random subnet is present, we do not unsubscribe from it.
     fn handle_unsubscriptions(&mut self, exact_subnet: ExactSubnet) {
-        // Check if the subnet currently exists as a long-lasting random subnet
-        if self.random_subnets.contains(&exact_subnet.subnet_id) {
+        // Check if the subnet currently exists as a long-lasting random or deterministic subnet
+        if self.random_subnets.contains(&exact_subnet.subnet_id)
+            || self.long_lived_subnets.contains(&exact_subnet.subnet_id)
+        {
             return;
         }
 
@@ -620,6 +778,15 @@ impl<T: BeaconChainTypes> Stream for AttestationService<T> {
             Poll::Ready(None) | Poll::Pending => {}
         }
 
+        // recompute the deterministic long-lived subnets when they rotate
+        while self
+            .next_long_lived_subscription_event
+            .as_mut()
+            .map_or(false, |event| event.as_mut().poll(cx).is_ready())
+        {
+            self.recompute_long_lived_subnets();
+        }
+
         // process any known validator expiries
         match self.known_validators.poll_next_unpin(cx) {
             Poll::Ready(Some(Ok(_validator_index))) => {
@@ -630,6 +797,20 @@ impl<T: BeaconChainTypes> Stream for AttestationService<T> {
             }
             Poll::Ready(None) | Poll::Pending => {}
         }
+        // repeat peer discoveries for upcoming subscriptions
+        match self.pre_duty_discoveries.poll_next_unpin(cx) {
+            Poll::Ready(Some(Ok(exact_subnet))) => {
+                metrics::inc_counter(&metrics::SUBNET_PRE_DUTY_DISCOVERY_REQUESTS);
+                if let Err(e) = self.discover_peers_request(std::iter::once(exact_subnet)) {
+                    warn!(self.log, "Discovery lookup request error"; "error" => e);
+                }
+            }
+            Poll::Ready(Some(Err(e))) => {
+                error!(self.log, "Failed to check for pre-duty subnet discoveries"; "error"=> e);
+            }
+            Poll::Ready(None) | Poll::Pending => {}
+        }
+
         // poll to remove entries on expiration, no need to act on expiration events
         if let Poll::Ready(Some(Err(e))) = self.aggregate_validators_on_subnet.poll_next_unpin(cx) {
             error!(self.log, "Failed to check for aggregate validator on subnet expirations"; "error"=> e);
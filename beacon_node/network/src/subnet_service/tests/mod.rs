@@ -2,6 +2,7 @@ use super::*;
 use beacon_chain::{
     builder::{BeaconChainBuilder, Witness},
     eth1_chain::CachingEth1Backend,
+    validator_monitor::DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD,
     BeaconChain,
 };
 use futures::prelude::*;
@@ -11,13 +12,14 @@ use lighthouse_network::NetworkConfig;
 use slog::Logger;
 use sloggers::{null::NullLoggerBuilder, Build};
 use slot_clock::{SlotClock, SystemTimeSlotClock};
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use store::config::StoreConfig;
 use store::{HotColdDB, MemoryStore};
 use types::{
     CommitteeIndex, Epoch, EthSpec, Hash256, MainnetEthSpec, Slot, SubnetId,
-    SyncCommitteeSubscription, SyncSubnetId, ValidatorSubscription,
+    SyncCommitteeSubscription, SyncSubnetId, Uint256, ValidatorSubscription,
 };
 
 const SLOT_DURATION_MILLIS: u64 = 400;
@@ -70,7 +72,7 @@ impl TestBeaconChain {
                     Duration::from_millis(SLOT_DURATION_MILLIS),
                 ))
                 .shutdown_sender(shutdown_tx)
-                .monitor_validators(true, vec![], log)
+                .monitor_validators(true, vec![], DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD, log)
                 .build()
                 .expect("should build"),
         );
@@ -99,7 +101,7 @@ fn get_attestation_service() -> AttestationService<TestBeaconChainType> {
 
     let beacon_chain = CHAIN.chain.clone();
 
-    AttestationService::new(beacon_chain, &config, &log)
+    AttestationService::new(beacon_chain, Uint256::zero(), &config, &log)
 }
 
 fn get_sync_committee_service() -> SyncCommitteeService<TestBeaconChainType> {
@@ -344,6 +346,59 @@ mod attestation_service {
         assert_eq!(attestation_service.subscription_count(), 1);
     }
 
+    /// Test that the peer discovery for a subscription is repeated shortly before its slot.
+    #[tokio::test]
+    async fn subscription_repeats_discovery_before_slot() {
+        let validator_index = 1;
+        let committee_index = 1;
+        let subscription_slot = 5;
+        let committee_count = 1;
+
+        let mut attestation_service = get_attestation_service();
+        let current_slot = attestation_service
+            .beacon_chain
+            .slot_clock
+            .now()
+            .expect("Could not get current slot");
+
+        let subscriptions = vec![get_subscription(
+            validator_index,
+            committee_index,
+            current_slot + Slot::new(subscription_slot),
+            committee_count,
+        )];
+        attestation_service
+            .validator_subscriptions(subscriptions)
+            .unwrap();
+
+        let subnet_id = SubnetId::compute_subnet::<MainnetEthSpec>(
+            current_slot + Slot::new(subscription_slot),
+            committee_index,
+            committee_count,
+            &attestation_service.beacon_chain.spec,
+        )
+        .unwrap();
+
+        // Discoveries for the subscription have a `min_ttl`, unlike those for random subnets.
+        let events = get_events(&mut attestation_service, None, 4).await;
+        let subscription_discoveries = events
+            .iter()
+            .filter(|event| match event {
+                SubnetServiceMessage::DiscoverPeers(discoveries) => {
+                    discoveries.iter().any(|discovery| {
+                        discovery.subnet == Subnet::Attestation(subnet_id)
+                            && discovery.min_ttl.is_some()
+                    })
+                }
+                _ => false,
+            })
+            .count();
+
+        // One discovery when subscribing and one `PRE_DUTY_DISCOVERY_SLOT_LOOK_AHEAD` slots
+        // before the subscription slot.
+        assert_eq!(subscription_discoveries, 2);
+    }
+
     #[tokio::test]
     async fn subscribe_all_random_subnets() {
         let attestation_subnet_count = MainnetEthSpec::default_spec().attestation_subnet_count;
@@ -457,6 +512,49 @@ mod attestation_service {
         assert_eq!(enr_add_count, 64);
         assert_eq!(unexpected_msg_count, 0);
     }
+
+    #[tokio::test]
+    async fn subscribe_deterministic_long_lived_subnets() {
+        let spec = MainnetEthSpec::default_spec();
+        let node_id = Uint256::from(0xdead_beef_u64) << 200;
+        let config = NetworkConfig {
+            deterministic_long_lived_attnets: true,
+            ..NetworkConfig::default()
+        };
+        let mut attestation_service =
+            AttestationService::new(CHAIN.chain.clone(), node_id, &config, &get_logger());
+
+        let current_epoch = attestation_service
+            .beacon_chain
+            .epoch()
+            .expect("Could not get current epoch");
+        let (expected_subnets, _) =
+            SubnetId::compute_subnets_for_epoch(node_id, current_epoch, &spec).unwrap();
+        let expected_subnets = expected_subnets.collect::<HashSet<_>>();
+
+        let events = get_events(&mut attestation_service, None, 3).await;
+
+        let mut subscribed = HashSet::new();
+        let mut enr_added = HashSet::new();
+        for event in &events {
+            match event {
+                SubnetServiceMessage::DiscoverPeers(_) => {}
+                SubnetServiceMessage::Subscribe(Subnet::Attestation(subnet_id)) => {
+                    subscribed.insert(*subnet_id);
+                }
+                SubnetServiceMessage::EnrAdd(Subnet::Attestation(subnet_id)) => {
+                    enr_added.insert(*subnet_id);
+                }
+                event => panic!("Unexpected event {:?}", event),
+            }
+        }
+
+        assert_eq!(expected_subnets.len(), spec.subnets_per_node as usize);
+        assert_eq!(subscribed, expected_subnets);
+        assert_eq!(enr_added, expected_subnets);
+        assert_eq!(attestation_service.long_lived_subnets, expected_subnets);
+        assert_eq!(attestation_service.random_subnets.len(), 0);
+    }
 }
 
 mod sync_committee_service {
@@ -164,7 +164,7 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
 
         let (state, current_start) = if let Some(anchor_info) = beacon_chain.store.get_anchor_info()
         {
-            if anchor_info.block_backfill_complete() {
+            if beacon_chain.store.block_backfill_complete(&anchor_info) {
                 (BackFillState::Completed, Epoch::new(0))
             } else {
                 (
@@ -304,6 +304,15 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
         }
     }
 
+    /// Requests any batches that were held back, e.g. by the sync bandwidth limits.
+    #[must_use = "A failure here indicates the backfill sync has failed and the global sync state should be updated"]
+    pub fn resume(
+        &mut self,
+        network: &mut SyncNetworkContext<T::EthSpec>,
+    ) -> Result<(), BackFillError> {
+        self.request_batches(network)
+    }
+
     /// A peer has disconnected.
     /// If the peer has active batches, those are considered failed and re-requested.
     #[must_use = "A failure here indicates the backfill sync has failed and the global sync state should be updated"]
@@ -1055,6 +1064,11 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
             return Ok(());
         }
 
+        // wait for the bandwidth limits to allow more downloads, sync is resumed periodically
+        if !network.backfill_request_allowed() {
+            return Ok(());
+        }
+
         // find the next pending batch and request it from the peer
 
         // randomize the peers for load balancing
@@ -1147,7 +1161,11 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
     /// not required.
     fn reset_start_epoch(&mut self) -> Result<(), ResetEpochError> {
         if let Some(anchor_info) = self.beacon_chain.store.get_anchor_info() {
-            if anchor_info.block_backfill_complete() {
+            if self
+                .beacon_chain
+                .store
+                .block_backfill_complete(&anchor_info)
+            {
                 Err(ResetEpochError::SyncCompleted)
             } else {
                 self.current_start = anchor_info
@@ -1162,16 +1180,18 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
 
     /// Checks with the beacon chain if backfill sync has completed.
     fn check_completed(&mut self) -> bool {
-        if self.current_start == 0 {
-            // Check that the beacon chain agrees
-
-            if let Some(anchor_info) = self.beacon_chain.store.get_anchor_info() {
-                // Conditions that we have completed a backfill sync
-                if anchor_info.block_backfill_complete() {
-                    return true;
-                } else {
-                    error!(self.log, "Backfill out of sync with beacon chain");
-                }
+        // Check with the beacon chain, which may not need blocks prior to its history retention
+        // window.
+        if let Some(anchor_info) = self.beacon_chain.store.get_anchor_info() {
+            // Conditions that we have completed a backfill sync
+            if self
+                .beacon_chain
+                .store
+                .block_backfill_complete(&anchor_info)
+            {
+                return true;
+            } else if self.current_start == 0 {
+                error!(self.log, "Backfill out of sync with beacon chain");
             }
         }
         false
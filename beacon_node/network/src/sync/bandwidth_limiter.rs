@@ -0,0 +1,100 @@
+//! Limits the rate at which sync downloads blocks from peers.
+//!
+//! Sync requests are only issued while the limiter has allowance remaining. The allowance is
+//! consumed by the blocks received and refills at the configured rate, so that on average sync
+//! does not download more than the configured number of bytes per second.
+
+use std::time::Instant;
+
+/// The number of bytes in a megabit.
+const BYTES_PER_MEGABIT: u64 = 125_000;
+
+/// The bandwidth limits applied to sync, in megabits per second.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SyncBandwidthLimits {
+    /// The limit for all range and backfill sync downloads.
+    pub sync_max_mbps: Option<u64>,
+    /// An additional limit for backfill sync downloads.
+    pub backfill_max_mbps: Option<u64>,
+}
+
+/// A token bucket over the bytes downloaded by sync.
+#[derive(Debug)]
+pub struct BandwidthLimiter {
+    /// The rate at which the allowance refills.
+    bytes_per_second: f64,
+    /// The number of bytes that may be downloaded before new requests are paused. This is negative
+    /// once more bytes have been received than allowed.
+    allowance: f64,
+    /// The last time the allowance was refilled.
+    last_update: Instant,
+}
+
+impl BandwidthLimiter {
+    pub fn from_mbps(mbps: u64) -> Self {
+        let bytes_per_second = mbps.saturating_mul(BYTES_PER_MEGABIT) as f64;
+        Self {
+            bytes_per_second,
+            // Allow a burst of up to one second of downloads.
+            allowance: bytes_per_second,
+            last_update: Instant::now(),
+        }
+    }
+
+    /// Records `bytes` being downloaded.
+    pub fn record(&mut self, bytes: usize) {
+        self.record_at(bytes, Instant::now())
+    }
+
+    /// Returns `true` if a new request may be issued.
+    pub fn is_available(&mut self) -> bool {
+        self.is_available_at(Instant::now())
+    }
+
+    fn record_at(&mut self, bytes: usize, now: Instant) {
+        self.refill(now);
+        self.allowance -= bytes as f64;
+    }
+
+    fn is_available_at(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.allowance > 0.0
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_update);
+        self.last_update = now;
+        self.allowance = (self.allowance + elapsed.as_secs_f64() * self.bytes_per_second)
+            .min(self.bytes_per_second);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn pauses_until_refilled() {
+        // 1 Mbps allows 125,000 bytes per second.
+        let mut limiter = BandwidthLimiter::from_mbps(1);
+        let start = limiter.last_update;
+        assert!(limiter.is_available_at(start));
+
+        // A 2 second burst of downloads exhausts the allowance for the next second.
+        limiter.record_at(250_000, start);
+        assert!(!limiter.is_available_at(start));
+        assert!(!limiter.is_available_at(start + Duration::from_millis(1000)));
+        assert!(limiter.is_available_at(start + Duration::from_millis(1001)));
+    }
+
+    #[test]
+    fn burst_is_capped_at_one_second() {
+        let mut limiter = BandwidthLimiter::from_mbps(1);
+        let start = limiter.last_update;
+
+        // Idling for a minute doesn't allow more than a second of downloads.
+        limiter.record_at(125_000, start + Duration::from_secs(60));
+        assert!(!limiter.is_available_at(start + Duration::from_secs(60)));
+    }
+}
@@ -3,7 +3,7 @@ use std::time::Duration;
 
 use beacon_chain::{BeaconChainTypes, BlockError};
 use fnv::FnvHashMap;
-use lighthouse_network::{PeerAction, PeerId};
+use lighthouse_network::{types::SyncLookups, PeerAction, PeerId};
 use lru_cache::LRUTimeCache;
 use slog::{crit, debug, error, trace, warn, Logger};
 use smallvec::SmallVec;
@@ -14,7 +14,7 @@ use crate::beacon_processor::{ChainSegmentProcessId, WorkEvent};
 use crate::metrics;
 
 use self::{
-    parent_lookup::{ParentLookup, VerifyError},
+    parent_lookup::{ParentLookup, VerifyError, PARENT_DEPTH_TOLERANCE},
     single_block_lookup::SingleBlockRequest,
 };
 
@@ -31,6 +31,10 @@ mod tests;
 
 const FAILED_CHAINS_CACHE_EXPIRY_SECONDS: u64 = 60;
 const SINGLE_BLOCK_LOOKUP_MAX_ATTEMPTS: u8 = 3;
+/// The maximum number of parent lookups in progress at once. Blocks with unknown parents received
+/// while this many chains are being looked up are dropped, bounding the work a burst of blocks far
+/// ahead of our chain can cause.
+pub(crate) const MAX_PARENT_LOOKUPS: usize = 16;
 
 pub(crate) struct BlockLookups<T: BeaconChainTypes> {
     /// A collection of parent block lookups.
@@ -82,6 +86,16 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
             return;
         }
 
+        // Nor one that is part of a chain being looked up.
+        if let Some(parent_lookup) = self
+            .parent_queue
+            .iter_mut()
+            .find(|parent_req| parent_req.is_for_block(&hash))
+        {
+            parent_lookup.add_chain_peer(&peer_id);
+            return;
+        }
+
         debug!(
             self.log,
             "Searching for block";
@@ -119,13 +133,20 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
         }
 
         // Make sure this block is not already downloaded, and that neither it or its parent is
-        // being searched for.
-        if self.parent_queue.iter_mut().any(|parent_req| {
-            parent_req.contains_block(&block)
-                || parent_req.add_peer(&block_root, &peer_id)
-                || parent_req.add_peer(&parent_root, &peer_id)
+        // being searched for. A peer sending us a block of a chain being looked up should have
+        // the chain's ancestors, so it is added to the lookup.
+        if let Some(parent_lookup) = self.parent_queue.iter_mut().find(|parent_req| {
+            parent_req.is_for_block(&block_root) || parent_req.add_peer(&parent_root, &peer_id)
         }) {
             // we are already searching for this block, ignore it
+            parent_lookup.add_chain_peer(&peer_id);
+            return;
+        }
+
+        if self.parent_queue.len() >= MAX_PARENT_LOOKUPS {
+            debug!(self.log, "Too many parent lookups in progress. Dropping block";
+                "block_root" => ?block_root, "block_slot" => block.slot(),
+                "max_parent_lookups" => MAX_PARENT_LOOKUPS);
             return;
         }
 
@@ -133,6 +154,20 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
         self.request_parent(parent_lookup, cx);
     }
 
+    /// Returns the lookups in progress, as reported by the HTTP API.
+    pub fn status(&self) -> SyncLookups {
+        SyncLookups {
+            single_block_lookups: self
+                .single_block_lookups
+                .values()
+                .map(SingleBlockRequest::status)
+                .collect(),
+            parent_lookups: self.parent_queue.iter().map(ParentLookup::status).collect(),
+            max_parent_lookups: MAX_PARENT_LOOKUPS,
+            max_parent_depth: PARENT_DEPTH_TOLERANCE,
+        }
+    }
+
     /* Lookup responses */
 
     pub fn single_block_lookup_response(
@@ -1,4 +1,4 @@
-use lighthouse_network::PeerId;
+use lighthouse_network::{types::ParentLookupStatus, PeerId};
 use store::{EthSpec, Hash256, SignedBeaconBlock};
 use strum::IntoStaticStr;
 
@@ -45,10 +45,16 @@ pub enum RequestError {
 }
 
 impl<T: EthSpec> ParentLookup<T> {
-    pub fn contains_block(&self, block: &SignedBeaconBlock<T>) -> bool {
-        self.downloaded_blocks
-            .iter()
-            .any(|d_block| d_block == block)
+    /// Returns `true` if `block_root` is one of the downloaded blocks or the parent currently being
+    /// looked up.
+    pub fn is_for_block(&self, block_root: &Hash256) -> bool {
+        // Each downloaded block's parent is either the next downloaded block or the current
+        // parent request, so the roots of the chain follow from the parent roots.
+        self.chain_hash == *block_root
+            || self
+                .downloaded_blocks
+                .iter()
+                .any(|d_block| d_block.parent_root() == *block_root)
     }
 
     pub fn new(block: SignedBeaconBlock<T>, peer_id: PeerId) -> Self {
@@ -150,6 +156,24 @@ impl<T: EthSpec> ParentLookup<T> {
         self.current_parent_request.add_peer(block_root, peer_id)
     }
 
+    /// Adds a peer which knows of a block in this chain, and so should have its ancestors.
+    pub fn add_chain_peer(&mut self, peer_id: &PeerId) {
+        self.current_parent_request.available_peers.insert(*peer_id);
+    }
+
+    /// Returns the status of this lookup, as reported by the HTTP API.
+    pub fn status(&self) -> ParentLookupStatus {
+        let request = self.current_parent_request.status();
+        ParentLookupStatus {
+            chain_hash: self.chain_hash,
+            parent_root: request.block_root,
+            depth: self.downloaded_blocks.len(),
+            state: request.state,
+            available_peers: request.available_peers,
+            failed_attempts: request.failed_attempts,
+        }
+    }
+
     pub fn used_peers(&self) -> impl Iterator<Item = &PeerId> + '_ {
         self.current_parent_request.used_peers.iter()
     }
@@ -1,6 +1,10 @@
 use std::collections::HashSet;
 
-use lighthouse_network::{rpc::BlocksByRootRequest, PeerId};
+use lighthouse_network::{
+    rpc::BlocksByRootRequest,
+    types::{LookupState, SingleBlockLookupStatus},
+    PeerId,
+};
 use rand::seq::IteratorRandom;
 use ssz_types::VariableList;
 use store::{EthSpec, Hash256, SignedBeaconBlock};
@@ -146,6 +150,26 @@ impl<const MAX_ATTEMPTS: u8> SingleBlockRequest<MAX_ATTEMPTS> {
             Err(())
         }
     }
+
+    /// Returns the status of this request, as reported by the HTTP API.
+    pub fn status(&self) -> SingleBlockLookupStatus {
+        SingleBlockLookupStatus {
+            block_root: self.hash,
+            state: (&self.state).into(),
+            available_peers: self.available_peers.len(),
+            failed_attempts: self.failed_attempts,
+        }
+    }
+}
+
+impl From<&State> for LookupState {
+    fn from(state: &State) -> Self {
+        match state {
+            State::AwaitingDownload => LookupState::AwaitingDownload,
+            State::Downloading { .. } => LookupState::Downloading,
+            State::Processing { .. } => LookupState::Processing,
+        }
+    }
 }
 
 impl<const MAX_ATTEMPTS: u8> slog::Value for SingleBlockRequest<MAX_ATTEMPTS> {
@@ -458,3 +458,63 @@ fn test_parent_lookup_disconnection() {
     bl.peer_disconnected(&peer_id, &mut cx);
     assert!(bl.parent_queue.is_empty());
 }
+
+#[test]
+fn test_parent_lookup_deduplicates_chain_blocks() {
+    let (mut bl, mut cx, mut rig) = TestRig::test_setup(None);
+
+    let grandparent = rig.rand_block();
+    let parent = rig.block_with_parent(grandparent.canonical_root());
+    let block = rig.block_with_parent(parent.canonical_root());
+    let chain_hash = block.canonical_root();
+    let peer_id = PeerId::random();
+
+    // Trigger the request and download the parent, whose parent is also unknown.
+    bl.search_parent(Box::new(block.clone()), peer_id, &mut cx);
+    let id = rig.expect_parent_request();
+    bl.parent_lookup_response(id, peer_id, Some(Box::new(parent.clone())), D, &mut cx);
+    rig.expect_block_process();
+    bl.parent_block_processed(
+        chain_hash,
+        Err(BlockError::ParentUnknown(Box::new(parent.clone()))),
+        &mut cx,
+    );
+    rig.expect_parent_request();
+
+    // Other peers sending blocks of the chain join the existing lookup rather than starting new
+    // ones.
+    let other_peer = PeerId::random();
+    bl.search_parent(Box::new(parent.clone()), other_peer, &mut cx);
+    bl.search_parent(Box::new(block), PeerId::random(), &mut cx);
+    bl.search_block(parent.canonical_root(), PeerId::random(), &mut cx);
+    rig.expect_empty_network();
+    assert_eq!(bl.parent_queue.len(), 1);
+    assert!(bl.single_block_lookups.is_empty());
+
+    let status = bl.status();
+    assert_eq!(status.parent_lookups.len(), 1);
+    assert_eq!(status.parent_lookups[0].chain_hash, chain_hash);
+    assert_eq!(
+        status.parent_lookups[0].parent_root,
+        grandparent.canonical_root()
+    );
+    assert_eq!(status.parent_lookups[0].depth, 2);
+    assert_eq!(status.parent_lookups[0].available_peers, 4);
+}
+
+#[test]
+fn test_parent_lookup_limit() {
+    let (mut bl, mut cx, mut rig) = TestRig::test_setup(None);
+
+    for _ in 0..MAX_PARENT_LOOKUPS {
+        let block = rig.rand_block();
+        bl.search_parent(Box::new(block), PeerId::random(), &mut cx);
+        rig.expect_parent_request();
+    }
+
+    // Further blocks with unknown parents are dropped until a lookup completes.
+    let block = rig.rand_block();
+    bl.search_parent(Box::new(block), PeerId::random(), &mut cx);
+    rig.expect_empty_network();
+    assert_eq!(bl.parent_queue.len(), MAX_PARENT_LOOKUPS);
+}
@@ -27,20 +27,35 @@
 //! fully sync'd peers. If `PARENT_FAIL_TOLERANCE` attempts at requesting the block fails, we
 //! drop the propagated block and downvote the peer that sent it to us.
 //!
+//! The ancestors of the block are requested one at a time until a known block is reached, for at
+//! most `PARENT_DEPTH_TOLERANCE` blocks. Blocks of a chain already being looked up only add their
+//! peer to the existing lookup, and at most `MAX_PARENT_LOOKUPS` chains are looked up at once. The
+//! lookups in progress are reported by the `lighthouse/sync/lookups` HTTP API endpoint.
+//!
 //! Block Lookup
 //!
 //! To keep the logic maintained to the syncing thread (and manage the request_ids), when a block
 //! needs to be searched for (i.e if an attestation references an unknown block) this manager can
 //! search for the block and subsequently search for parents if needed.
+//!
+//! ## Blobs
+//!
+//! Blocks within the blobs retention window are downloaded together with their blobs, from the
+//! same peer, using the `BlobsByRange` and `BlobsByRoot` requests. The blocks are only passed on
+//! once the blobs have been verified against them, and the blobs are held by the beacon chain
+//! until the blocks are imported.
 
 use super::backfill_sync::{BackFillSync, ProcessResult, SyncStart};
+use super::bandwidth_limiter::SyncBandwidthLimits;
 use super::block_lookups::BlockLookups;
-use super::network_context::SyncNetworkContext;
+use super::network_context::{BlockResponse, BlocksAndBlobsRequest, SyncNetworkContext};
 use super::peer_sync_info::{remote_sync_type, PeerSyncType};
 use super::range_sync::{RangeSync, RangeSyncType, EPOCHS_PER_BATCH};
 use crate::beacon_processor::{ChainSegmentProcessId, WorkEvent as BeaconWorkEvent};
 use crate::service::NetworkMessage;
 use crate::status::ToStatusMessage;
+use beacon_chain::blob_verification::{verify_blobs_for_block, Error as BlobError};
+use beacon_chain::memory_watermark::MEMORY_WATERMARK_CHECK_INTERVAL;
 use beacon_chain::{BeaconChain, BeaconChainTypes, BlockError};
 use lighthouse_network::rpc::methods::MAX_REQUEST_BLOCKS;
 use lighthouse_network::types::{NetworkGlobals, SyncState};
@@ -48,11 +63,12 @@ use lighthouse_network::SyncInfo;
 use lighthouse_network::{PeerAction, PeerId};
 use slog::{crit, debug, error, info, trace, Logger};
 use std::boxed::Box;
+use std::collections::HashMap;
 use std::ops::Sub;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
-use types::{EthSpec, Hash256, SignedBeaconBlock, Slot};
+use types::{BlobSidecar, EthSpec, Hash256, SignedBeaconBlock, Slot};
 
 /// The number of slots ahead of us that is allowed before requesting a long-range (batch)  Sync
 /// from a peer. If a peer is within this tolerance (forwards or backwards), it is treated as a
@@ -63,6 +79,10 @@ use types::{EthSpec, Hash256, SignedBeaconBlock, Slot};
 /// blocks for.
 pub const SLOT_IMPORT_TOLERANCE: usize = 32;
 
+/// How often range and backfill sync are resumed if their requests were held back by a bandwidth
+/// limit.
+const BANDWIDTH_LIMIT_RESUME_INTERVAL: Duration = Duration::from_millis(500);
+
 pub type Id = u32;
 
 /// Id of rpc requests sent by sync to the network.
@@ -76,6 +96,14 @@ pub enum RequestId {
     BackFillSync { id: Id },
     /// The request was from a chain in the range sync algorithm.
     RangeSync { id: Id },
+    /// Request for the blobs of a single block lookup's block. The id is the block request's.
+    SingleBlockBlobs { id: Id },
+    /// Request for the blobs of a parent lookup's block. The id is the block request's.
+    ParentLookupBlobs { id: Id },
+    /// Request for the blobs of a backfill sync batch. The id is the blocks request's.
+    BackFillSyncBlobs { id: Id },
+    /// Request for the blobs of a range sync batch. The id is the blocks request's.
+    RangeSyncBlobs { id: Id },
 }
 
 #[derive(Debug)]
@@ -92,6 +120,14 @@ pub enum SyncMessage<T: EthSpec> {
         seen_timestamp: Duration,
     },
 
+    /// A blob sidecar has been received from the RPC.
+    RpcBlob {
+        request_id: RequestId,
+        peer_id: PeerId,
+        blob_sidecar: Option<Arc<BlobSidecar<T>>>,
+        seen_timestamp: Duration,
+    },
+
     /// A block with an unknown parent has been received.
     UnknownBlock(PeerId, Box<SignedBeaconBlock<T>>),
 
@@ -165,6 +201,10 @@ pub struct SyncManager<T: BeaconChainTypes> {
 
     block_lookups: BlockLookups<T>,
 
+    /// Whether the memory watermark was exceeded when the sync state was last updated. Backfill
+    /// sync is paused whilst it is exceeded.
+    memory_watermark_exceeded: bool,
+
     /// The logger for the import manager.
     log: Logger,
 }
@@ -178,6 +218,7 @@ pub fn spawn<T: BeaconChainTypes>(
     network_globals: Arc<NetworkGlobals<T::EthSpec>>,
     network_send: mpsc::UnboundedSender<NetworkMessage<T::EthSpec>>,
     beacon_processor_send: mpsc::Sender<BeaconWorkEvent<T>>,
+    bandwidth_limits: SyncBandwidthLimits,
     log: slog::Logger,
 ) -> mpsc::UnboundedSender<SyncMessage<T::EthSpec>> {
     assert!(
@@ -192,7 +233,8 @@ pub fn spawn<T: BeaconChainTypes>(
         chain: beacon_chain.clone(),
         network_globals: network_globals.clone(),
         input_channel: sync_recv,
-        network: SyncNetworkContext::new(network_send, network_globals.clone(), log.clone()),
+        network: SyncNetworkContext::new(network_send, network_globals.clone(), log.clone())
+            .with_bandwidth_limits(bandwidth_limits),
         range_sync: RangeSync::new(
             beacon_chain.clone(),
             beacon_processor_send.clone(),
@@ -205,6 +247,7 @@ pub fn spawn<T: BeaconChainTypes>(
             log.clone(),
         ),
         block_lookups: BlockLookups::new(beacon_processor_send, log.clone()),
+        memory_watermark_exceeded: false,
         log: log.clone(),
     };
 
@@ -258,15 +301,27 @@ impl<T: BeaconChainTypes> SyncManager<T> {
     fn inject_error(&mut self, peer_id: PeerId, request_id: RequestId) {
         trace!(self.log, "Sync manager received a failed RPC");
         match request_id {
+            RequestId::SingleBlockBlobs { id }
+            | RequestId::ParentLookupBlobs { id }
+            | RequestId::BackFillSyncBlobs { id }
+            | RequestId::RangeSyncBlobs { id } => {
+                // Fail the blocks request, unless it has already failed.
+                if self.network.remove_blocks_and_blobs_request(id) {
+                    self.inject_error(peer_id, blocks_request_id(request_id));
+                }
+            }
             RequestId::SingleBlock { id } => {
+                self.network.remove_blocks_and_blobs_request(id);
                 self.block_lookups
                     .single_block_lookup_failed(id, &mut self.network);
             }
             RequestId::ParentLookup { id } => {
+                self.network.remove_blocks_and_blobs_request(id);
                 self.block_lookups
                     .parent_lookup_failed(id, peer_id, &mut self.network);
             }
             RequestId::BackFillSync { id } => {
+                self.network.remove_blocks_and_blobs_request(id);
                 if let Some(batch_id) = self.network.backfill_sync_response(id, true) {
                     match self
                         .backfill_sync
@@ -278,6 +333,7 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                 }
             }
             RequestId::RangeSync { id } => {
+                self.network.remove_blocks_and_blobs_request(id);
                 if let Some((chain_id, batch_id)) = self.network.range_sync_response(id, true) {
                     self.range_sync.inject_error(
                         &mut self.network,
@@ -293,6 +349,7 @@ impl<T: BeaconChainTypes> SyncManager<T> {
     }
 
     fn peer_disconnect(&mut self, peer_id: &PeerId) {
+        self.network.peer_disconnected(peer_id);
         self.range_sync.peer_disconnect(&mut self.network, peer_id);
         self.block_lookups
             .peer_disconnected(peer_id, &mut self.network);
@@ -363,7 +420,9 @@ impl<T: BeaconChainTypes> SyncManager<T> {
     /// backfill sync is running.
     /// - If there is no range sync and no required backfill and we have synced up to the currently
     /// known peers, we consider ourselves synced.
+    /// - Backfill sync is paused whilst the memory watermark is exceeded.
     fn update_sync_state(&mut self) {
+        self.memory_watermark_exceeded = self.chain.memory_watermark_exceeded();
         let new_state: SyncState = match self.range_sync.state() {
             Err(e) => {
                 crit!(self.log, "Error getting range sync state"; "error" => %e);
@@ -400,7 +459,10 @@ impl<T: BeaconChainTypes> SyncManager<T> {
 
                     // If we would otherwise be synced, first check if we need to perform or
                     // complete a backfill sync.
-                    if matches!(sync_state, SyncState::Synced) {
+                    if matches!(sync_state, SyncState::Synced) && self.memory_watermark_exceeded {
+                        // Pause any backfill sync until memory usage recovers.
+                        self.backfill_sync.pause();
+                    } else if matches!(sync_state, SyncState::Synced) {
                         // Determine if we need to start/resume/restart a backfill sync.
                         match self.backfill_sync.start(&mut self.network) {
                             Ok(SyncStart::Syncing {
@@ -463,110 +525,309 @@ impl<T: BeaconChainTypes> SyncManager<T> {
 
     /// The main driving future for the sync manager.
     async fn main(&mut self) {
+        let mut resume_interval = tokio::time::interval(BANDWIDTH_LIMIT_RESUME_INTERVAL);
+        let mut memory_watermark_interval = tokio::time::interval(MEMORY_WATERMARK_CHECK_INTERVAL);
+
         // process any inbound messages
         loop {
-            if let Some(sync_message) = self.input_channel.recv().await {
-                match sync_message {
-                    SyncMessage::AddPeer(peer_id, info) => {
-                        self.add_peer(peer_id, info);
-                    }
-                    SyncMessage::RpcBlock {
-                        request_id,
-                        peer_id,
-                        beacon_block,
-                        seen_timestamp,
-                    } => {
-                        self.rpc_block_received(request_id, peer_id, beacon_block, seen_timestamp);
+            tokio::select! {
+                Some(sync_message) = self.input_channel.recv() => {
+                    self.network.set_blobs_boundary(self.chain.blob_sidecars_boundary());
+                    self.handle_message(sync_message);
+                    *self.network_globals.sync_lookups.write() = self.block_lookups.status();
+                }
+                _ = resume_interval.tick(), if self.network.is_bandwidth_limited() => {
+                    self.resume_bandwidth_limited_sync();
+                }
+                _ = memory_watermark_interval.tick() => {
+                    // Pause or resume backfill sync if memory usage crossed the watermark.
+                    if self.chain.memory_watermark_exceeded() != self.memory_watermark_exceeded {
+                        self.update_sync_state();
                     }
-                    SyncMessage::UnknownBlock(peer_id, block) => {
-                        // If we are not synced or within SLOT_IMPORT_TOLERANCE of the block, ignore
-                        if !self.network_globals.sync_state.read().is_synced() {
-                            let head_slot = self
-                                .chain
-                                .head_info()
-                                .map(|info| info.slot)
-                                .unwrap_or_else(|_| Slot::from(0u64));
-                            let unknown_block_slot = block.slot();
-
-                            // if the block is far in the future, ignore it. If its within the slot tolerance of
-                            // our current head, regardless of the syncing state, fetch it.
-                            if (head_slot >= unknown_block_slot
-                                && head_slot.sub(unknown_block_slot).as_usize()
-                                    > SLOT_IMPORT_TOLERANCE)
-                                || (head_slot < unknown_block_slot
-                                    && unknown_block_slot.sub(head_slot).as_usize()
-                                        > SLOT_IMPORT_TOLERANCE)
-                            {
-                                continue;
-                            }
-                        }
-                        if self.network_globals.peers.read().is_connected(&peer_id) {
-                            self.block_lookups
-                                .search_parent(block, peer_id, &mut self.network);
-                        }
+                }
+                else => break,
+            }
+        }
+    }
+
+    /// Requests any batches held back by the sync bandwidth limits.
+    fn resume_bandwidth_limited_sync(&mut self) {
+        self.range_sync.resume(&mut self.network);
+        if self.backfill_sync.resume(&mut self.network).is_err() {
+            // The backfill sync has failed, errors are reported within.
+            self.update_sync_state();
+        }
+    }
+
+    /// Handles a message sent to the sync manager.
+    fn handle_message(&mut self, sync_message: SyncMessage<T::EthSpec>) {
+        match sync_message {
+            SyncMessage::AddPeer(peer_id, info) => {
+                self.add_peer(peer_id, info);
+            }
+            SyncMessage::RpcBlock {
+                request_id,
+                peer_id,
+                beacon_block,
+                seen_timestamp,
+            } => {
+                self.rpc_block_received(request_id, peer_id, beacon_block, seen_timestamp);
+            }
+            SyncMessage::RpcBlob {
+                request_id,
+                peer_id,
+                blob_sidecar,
+                seen_timestamp,
+            } => {
+                self.rpc_blob_received(request_id, peer_id, blob_sidecar, seen_timestamp);
+            }
+            SyncMessage::UnknownBlock(peer_id, block) => {
+                // If we are not synced or within SLOT_IMPORT_TOLERANCE of the block, ignore
+                if !self.network_globals.sync_state.read().is_synced() {
+                    let head_slot = self
+                        .chain
+                        .head_info()
+                        .map(|info| info.slot)
+                        .unwrap_or_else(|_| Slot::from(0u64));
+                    let unknown_block_slot = block.slot();
+
+                    // if the block is far in the future, ignore it. If its within the slot tolerance of
+                    // our current head, regardless of the syncing state, fetch it.
+                    if (head_slot >= unknown_block_slot
+                        && head_slot.sub(unknown_block_slot).as_usize() > SLOT_IMPORT_TOLERANCE)
+                        || (head_slot < unknown_block_slot
+                            && unknown_block_slot.sub(head_slot).as_usize() > SLOT_IMPORT_TOLERANCE)
+                    {
+                        return;
                     }
-                    SyncMessage::UnknownBlockHash(peer_id, block_hash) => {
-                        // If we are not synced, ignore this block.
-                        if self.network_globals.sync_state.read().is_synced()
-                            && self.network_globals.peers.read().is_connected(&peer_id)
-                        {
-                            self.block_lookups
-                                .search_block(block_hash, peer_id, &mut self.network);
+                }
+                if self.network_globals.peers.read().is_connected(&peer_id) {
+                    self.block_lookups
+                        .search_parent(block, peer_id, &mut self.network);
+                }
+            }
+            SyncMessage::UnknownBlockHash(peer_id, block_hash) => {
+                // If we are not synced, ignore this block.
+                if self.network_globals.sync_state.read().is_synced()
+                    && self.network_globals.peers.read().is_connected(&peer_id)
+                {
+                    self.block_lookups
+                        .search_block(block_hash, peer_id, &mut self.network);
+                }
+            }
+            SyncMessage::Disconnect(peer_id) => {
+                self.peer_disconnect(&peer_id);
+            }
+            SyncMessage::RpcError {
+                peer_id,
+                request_id,
+            } => self.inject_error(peer_id, request_id),
+            SyncMessage::BlockProcessed {
+                process_type,
+                result,
+            } => match process_type {
+                BlockProcessType::SingleBlock { id } => {
+                    self.block_lookups
+                        .single_block_processed(id, result, &mut self.network)
+                }
+                BlockProcessType::ParentLookup { chain_hash } => self
+                    .block_lookups
+                    .parent_block_processed(chain_hash, result, &mut self.network),
+            },
+            SyncMessage::BatchProcessed { sync_type, result } => match sync_type {
+                ChainSegmentProcessId::RangeBatchId(chain_id, epoch) => {
+                    self.range_sync.handle_block_process_result(
+                        &mut self.network,
+                        chain_id,
+                        epoch,
+                        result,
+                    );
+                    self.update_sync_state();
+                }
+                ChainSegmentProcessId::BackSyncBatchId(epoch) => {
+                    match self.backfill_sync.on_batch_process_result(
+                        &mut self.network,
+                        epoch,
+                        &result,
+                    ) {
+                        Ok(ProcessResult::Successful) => {}
+                        Ok(ProcessResult::SyncCompleted) => self.update_sync_state(),
+                        Err(error) => {
+                            error!(self.log, "Backfill sync failed"; "error" => ?error);
+                            // Update the global status
+                            self.update_sync_state();
                         }
                     }
-                    SyncMessage::Disconnect(peer_id) => {
-                        self.peer_disconnect(&peer_id);
+                }
+                ChainSegmentProcessId::ParentLookup(chain_hash) => self
+                    .block_lookups
+                    .parent_chain_processed(chain_hash, result, &mut self.network),
+            },
+        }
+    }
+
+    fn rpc_block_received(
+        &mut self,
+        request_id: RequestId,
+        peer_id: PeerId,
+        beacon_block: Option<Box<SignedBeaconBlock<T::EthSpec>>>,
+        seen_timestamp: Duration,
+    ) {
+        let (id, blobs_request_id) = match request_id {
+            RequestId::SingleBlock { id } => (id, RequestId::SingleBlockBlobs { id }),
+            RequestId::ParentLookup { id } => (id, RequestId::ParentLookupBlobs { id }),
+            RequestId::BackFillSync { id } => (id, RequestId::BackFillSyncBlobs { id }),
+            RequestId::RangeSync { id } => (id, RequestId::RangeSyncBlobs { id }),
+            RequestId::SingleBlockBlobs { .. }
+            | RequestId::ParentLookupBlobs { .. }
+            | RequestId::BackFillSyncBlobs { .. }
+            | RequestId::RangeSyncBlobs { .. } => {
+                return crit!(self.log, "Block received for a blobs request"; "peer_id" => %peer_id);
+            }
+        };
+
+        let beacon_block = match self
+            .network
+            .blocks_and_blobs_block_response(id, beacon_block)
+        {
+            BlockResponse::Uncoupled(beacon_block) => beacon_block,
+            BlockResponse::Pending => return,
+            BlockResponse::Complete(request) => {
+                return self.blocks_and_blobs_received(request_id, request, seen_timestamp)
+            }
+        };
+
+        // Blocks found by lookups are held whilst their blobs are downloaded.
+        let beacon_block = match beacon_block {
+            Some(block)
+                if matches!(
+                    request_id,
+                    RequestId::SingleBlock { .. } | RequestId::ParentLookup { .. }
+                ) && self.network.block_needs_blobs(&block) =>
+            {
+                match self
+                    .network
+                    .lookup_blobs_request(id, blobs_request_id, peer_id, *block)
+                {
+                    Ok(()) => return,
+                    Err(e) => {
+                        debug!(self.log, "Failed to request blobs"; "peer_id" => %peer_id, "error" => e);
+                        return self.inject_error(peer_id, request_id);
                     }
-                    SyncMessage::RpcError {
-                        peer_id,
+                }
+            }
+            beacon_block => beacon_block,
+        };
+
+        self.deliver_rpc_block(request_id, peer_id, beacon_block, seen_timestamp);
+    }
+
+    fn rpc_blob_received(
+        &mut self,
+        request_id: RequestId,
+        peer_id: PeerId,
+        blob_sidecar: Option<Arc<BlobSidecar<T::EthSpec>>>,
+        seen_timestamp: Duration,
+    ) {
+        let id = match request_id {
+            RequestId::SingleBlockBlobs { id }
+            | RequestId::ParentLookupBlobs { id }
+            | RequestId::BackFillSyncBlobs { id }
+            | RequestId::RangeSyncBlobs { id } => id,
+            RequestId::SingleBlock { .. }
+            | RequestId::ParentLookup { .. }
+            | RequestId::BackFillSync { .. }
+            | RequestId::RangeSync { .. } => {
+                return crit!(self.log, "Blob received for a blocks request"; "peer_id" => %peer_id);
+            }
+        };
+
+        if let Some(request) = self
+            .network
+            .blocks_and_blobs_blob_response(id, blob_sidecar)
+        {
+            self.blocks_and_blobs_received(blocks_request_id(request_id), request, seen_timestamp);
+        }
+    }
+
+    /// Both the blocks request with id `request_id` and the blobs request sent alongside it have
+    /// terminated.
+    ///
+    /// The blocks are passed on if their blobs are valid, otherwise the request fails.
+    fn blocks_and_blobs_received(
+        &mut self,
+        request_id: RequestId,
+        request: BlocksAndBlobsRequest<T::EthSpec>,
+        seen_timestamp: Duration,
+    ) {
+        let peer_id = request.peer_id;
+        match self.hold_blobs(&request) {
+            Ok(()) => {
+                for block in request.blocks {
+                    self.deliver_rpc_block(
                         request_id,
-                    } => self.inject_error(peer_id, request_id),
-                    SyncMessage::BlockProcessed {
-                        process_type,
-                        result,
-                    } => match process_type {
-                        BlockProcessType::SingleBlock { id } => self
-                            .block_lookups
-                            .single_block_processed(id, result, &mut self.network),
-                        BlockProcessType::ParentLookup { chain_hash } => self
-                            .block_lookups
-                            .parent_block_processed(chain_hash, result, &mut self.network),
-                    },
-                    SyncMessage::BatchProcessed { sync_type, result } => match sync_type {
-                        ChainSegmentProcessId::RangeBatchId(chain_id, epoch) => {
-                            self.range_sync.handle_block_process_result(
-                                &mut self.network,
-                                chain_id,
-                                epoch,
-                                result,
-                            );
-                            self.update_sync_state();
-                        }
-                        ChainSegmentProcessId::BackSyncBatchId(epoch) => {
-                            match self.backfill_sync.on_batch_process_result(
-                                &mut self.network,
-                                epoch,
-                                &result,
-                            ) {
-                                Ok(ProcessResult::Successful) => {}
-                                Ok(ProcessResult::SyncCompleted) => self.update_sync_state(),
-                                Err(error) => {
-                                    error!(self.log, "Backfill sync failed"; "error" => ?error);
-                                    // Update the global status
-                                    self.update_sync_state();
-                                }
-                            }
-                        }
-                        ChainSegmentProcessId::ParentLookup(chain_hash) => self
-                            .block_lookups
-                            .parent_chain_processed(chain_hash, result, &mut self.network),
-                    },
+                        peer_id,
+                        Some(Box::new(block)),
+                        seen_timestamp,
+                    );
                 }
+                self.deliver_rpc_block(request_id, peer_id, None, seen_timestamp);
+            }
+            Err(e) => {
+                debug!(self.log, "Peer sent invalid blobs"; "peer_id" => %peer_id, "error" => ?e);
+                self.network
+                    .report_peer(peer_id, PeerAction::LowToleranceError, "invalid_blobs");
+                self.inject_error(peer_id, request_id);
             }
         }
     }
 
-    fn rpc_block_received(
+    /// Verifies the blobs received alongside blocks, holding them in the beacon chain until the
+    /// blocks are imported.
+    fn hold_blobs(&self, request: &BlocksAndBlobsRequest<T::EthSpec>) -> Result<(), BlobError> {
+        let mut blobs_by_root: HashMap<Hash256, Vec<_>> = HashMap::new();
+        for blob in &request.blobs {
+            blobs_by_root
+                .entry(blob.block_root())
+                .or_default()
+                .push(blob.clone());
+        }
+
+        let mut verified = Vec::with_capacity(request.blocks.len());
+        for block in &request.blocks {
+            let block_root = block.canonical_root();
+            let blobs = blobs_by_root.remove(&block_root).unwrap_or_default();
+            if block.slot() < request.blobs_start_slot {
+                // The blobs of this block were not requested.
+                if let Some(blob) = blobs.first() {
+                    return Err(BlobError::UnexpectedBlob {
+                        block_root,
+                        index: blob.index,
+                    });
+                }
+                continue;
+            }
+            if let Some(blobs) = verify_blobs_for_block(block, block_root, blobs)? {
+                verified.push((block_root, blobs));
+            }
+        }
+
+        // Any remaining blobs belong to blocks which were not received.
+        if let Some(blob) = blobs_by_root.values().flatten().next() {
+            return Err(BlobError::UnexpectedBlob {
+                block_root: blob.block_root(),
+                index: blob.index,
+            });
+        }
+
+        for (block_root, blobs) in verified {
+            self.chain.pending_blobs.put_sidecars(block_root, blobs);
+        }
+        Ok(())
+    }
+
+    /// Passes a block received from the RPC on to the sync algorithm which requested it.
+    fn deliver_rpc_block(
         &mut self,
         request_id: RequestId,
         peer_id: PeerId,
@@ -589,6 +850,9 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                 &mut self.network,
             ),
             RequestId::BackFillSync { id } => {
+                if let Some(block) = &beacon_block {
+                    self.network.record_backfill_block(block);
+                }
                 if let Some(batch_id) = self
                     .network
                     .backfill_sync_response(id, beacon_block.is_none())
@@ -611,6 +875,9 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                 }
             }
             RequestId::RangeSync { id } => {
+                if let Some(block) = &beacon_block {
+                    self.network.record_range_block(block);
+                }
                 if let Some((chain_id, batch_id)) =
                     self.network.range_sync_response(id, beacon_block.is_none())
                 {
@@ -625,6 +892,23 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                     self.update_sync_state();
                 }
             }
+            RequestId::SingleBlockBlobs { .. }
+            | RequestId::ParentLookupBlobs { .. }
+            | RequestId::BackFillSyncBlobs { .. }
+            | RequestId::RangeSyncBlobs { .. } => {
+                crit!(self.log, "Block delivered for a blobs request"; "peer_id" => %peer_id);
+            }
         }
     }
 }
+
+/// Returns the id of the blocks request which a blobs request was sent alongside.
+fn blocks_request_id(request_id: RequestId) -> RequestId {
+    match request_id {
+        RequestId::SingleBlockBlobs { id } => RequestId::SingleBlock { id },
+        RequestId::ParentLookupBlobs { id } => RequestId::ParentLookup { id },
+        RequestId::BackFillSyncBlobs { id } => RequestId::BackFillSync { id },
+        RequestId::RangeSyncBlobs { id } => RequestId::RangeSync { id },
+        request_id => request_id,
+    }
+}
@@ -2,11 +2,13 @@
 //!
 //! Stores the various syncing methods for the beacon chain.
 mod backfill_sync;
+mod bandwidth_limiter;
 mod block_lookups;
 pub mod manager;
 mod network_context;
 mod peer_sync_info;
 mod range_sync;
 
+pub use bandwidth_limiter::SyncBandwidthLimits;
 pub use manager::{BatchProcessResult, SyncMessage};
 pub use range_sync::ChainId;
@@ -1,17 +1,67 @@
 //! Provides network functionality for the Syncing thread. This fundamentally wraps a network
 //! channel and stores a global RPC ID to perform requests.
 
+use super::bandwidth_limiter::{BandwidthLimiter, SyncBandwidthLimits};
 use super::manager::{Id, RequestId as SyncRequestId};
 use super::range_sync::{BatchId, ChainId};
 use crate::service::{NetworkMessage, RequestId};
 use crate::status::ToStatusMessage;
 use fnv::FnvHashMap;
-use lighthouse_network::rpc::{BlocksByRangeRequest, BlocksByRootRequest, GoodbyeReason};
+use lighthouse_network::rpc::{
+    BlobsByRangeRequest, BlobsByRootRequest, BlocksByRangeRequest, BlocksByRootRequest,
+    GoodbyeReason,
+};
 use lighthouse_network::{Client, NetworkGlobals, PeerAction, PeerId, ReportSource, Request};
 use slog::{debug, trace, warn};
+use ssz::Encode;
+use ssz_types::VariableList;
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use types::EthSpec;
+use types::{BlobIdentifier, BlobSidecar, Epoch, EthSpec, SignedBeaconBlock, Slot};
+
+/// A blocks request sent alongside a request for the blobs of the same blocks, to the same peer.
+///
+/// The responses are held until both streams have terminated, so that the blobs can be verified
+/// against their blocks before the blocks are passed on.
+pub struct BlocksAndBlobsRequest<T: EthSpec> {
+    /// The peer both requests were sent to.
+    pub peer_id: PeerId,
+    /// The blocks received so far.
+    pub blocks: Vec<SignedBeaconBlock<T>>,
+    /// The blob sidecars received so far.
+    pub blobs: Vec<Arc<BlobSidecar<T>>>,
+    /// The blobs of blocks before this slot were not requested.
+    pub blobs_start_slot: Slot,
+    blocks_finished: bool,
+    blobs_finished: bool,
+}
+
+impl<T: EthSpec> BlocksAndBlobsRequest<T> {
+    fn new(peer_id: PeerId, blobs_start_slot: Slot) -> Self {
+        Self {
+            peer_id,
+            blocks: vec![],
+            blobs: vec![],
+            blobs_start_slot,
+            blocks_finished: false,
+            blobs_finished: false,
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.blocks_finished && self.blobs_finished
+    }
+}
+
+/// The result of adding a block response to the requests in `SyncNetworkContext`.
+pub enum BlockResponse<T: EthSpec> {
+    /// The blocks request was not sent alongside a blobs request, the response is passed on.
+    Uncoupled(Option<Box<SignedBeaconBlock<T>>>),
+    /// The response is held until the blobs request has terminated.
+    Pending,
+    /// Both requests have terminated.
+    Complete(BlocksAndBlobsRequest<T>),
+}
 
 /// Wraps a Network channel to employ various RPC related network functionality for the Sync manager. This includes management of a global RPC request Id.
 
@@ -30,6 +80,18 @@ pub struct SyncNetworkContext<T: EthSpec> {
 
     backfill_requests: FnvHashMap<Id, BatchId>,
 
+    /// Blocks requests which were sent alongside a blobs request, by the id of the blocks request.
+    blocks_and_blobs_requests: FnvHashMap<Id, BlocksAndBlobsRequest<T>>,
+
+    /// The earliest epoch for which peers must serve blobs, if Deneb is scheduled.
+    blobs_boundary: Option<Epoch>,
+
+    /// Limits the bandwidth used by range and backfill sync, if configured.
+    sync_bandwidth: Option<BandwidthLimiter>,
+
+    /// Limits the bandwidth used by backfill sync, if configured.
+    backfill_bandwidth: Option<BandwidthLimiter>,
+
     /// Logger for the `SyncNetworkContext`.
     log: slog::Logger,
 }
@@ -46,10 +108,92 @@ impl<T: EthSpec> SyncNetworkContext<T> {
             request_id: 1,
             range_requests: FnvHashMap::default(),
             backfill_requests: FnvHashMap::default(),
+            blocks_and_blobs_requests: FnvHashMap::default(),
+            blobs_boundary: None,
+            sync_bandwidth: None,
+            backfill_bandwidth: None,
             log,
         }
     }
 
+    /// Limits the rate at which range and backfill sync download blocks.
+    pub fn with_bandwidth_limits(mut self, limits: SyncBandwidthLimits) -> Self {
+        self.sync_bandwidth = limits.sync_max_mbps.map(BandwidthLimiter::from_mbps);
+        self.backfill_bandwidth = limits.backfill_max_mbps.map(BandwidthLimiter::from_mbps);
+        self
+    }
+
+    /// Returns `true` if sync requests may be delayed by a bandwidth limit.
+    pub fn is_bandwidth_limited(&self) -> bool {
+        self.sync_bandwidth.is_some() || self.backfill_bandwidth.is_some()
+    }
+
+    /// Returns `true` if range sync is within its bandwidth limit and may request more batches.
+    pub fn range_request_allowed(&mut self) -> bool {
+        self.sync_bandwidth
+            .as_mut()
+            .map_or(true, |limiter| limiter.is_available())
+    }
+
+    /// Returns `true` if backfill sync is within its bandwidth limits and may request more
+    /// batches.
+    pub fn backfill_request_allowed(&mut self) -> bool {
+        self.range_request_allowed()
+            && self
+                .backfill_bandwidth
+                .as_mut()
+                .map_or(true, |limiter| limiter.is_available())
+    }
+
+    /// Counts a block received by range sync against the bandwidth limit.
+    pub fn record_range_block(&mut self, block: &SignedBeaconBlock<T>) {
+        if let Some(limiter) = self.sync_bandwidth.as_mut() {
+            limiter.record(block.ssz_bytes_len());
+        }
+    }
+
+    /// Counts a block received by backfill sync against the bandwidth limits.
+    pub fn record_backfill_block(&mut self, block: &SignedBeaconBlock<T>) {
+        let bytes = block.ssz_bytes_len();
+        for limiter in self
+            .sync_bandwidth
+            .iter_mut()
+            .chain(self.backfill_bandwidth.iter_mut())
+        {
+            limiter.record(bytes);
+        }
+    }
+
+    /// Sets the earliest epoch for which blobs are requested alongside blocks.
+    pub fn set_blobs_boundary(&mut self, boundary: Option<Epoch>) {
+        self.blobs_boundary = boundary;
+    }
+
+    /// Returns the slot from which blobs must be requested for blocks from `start_slot`, or
+    /// `None` if no blobs are expected for blocks before `end_slot`.
+    fn blobs_start_slot(&self, start_slot: Slot, end_slot: Slot) -> Option<Slot> {
+        let boundary_slot = self.blobs_boundary?.start_slot(T::slots_per_epoch());
+        let blobs_start_slot = std::cmp::max(start_slot, boundary_slot);
+        (blobs_start_slot < end_slot).then(|| blobs_start_slot)
+    }
+
+    /// Returns `true` if the blobs of `block` must be downloaded before it can be imported.
+    pub fn block_needs_blobs(&self, block: &SignedBeaconBlock<T>) -> bool {
+        block
+            .message()
+            .body()
+            .blob_kzg_commitments()
+            .map_or(false, |commitments| !commitments.is_empty())
+            && self
+                .blobs_start_slot(block.slot(), block.slot() + 1)
+                .is_some()
+    }
+
+    /// Returns the largest `BlocksByRange` request negotiated with the peer.
+    pub fn max_request_blocks(&self, peer_id: &PeerId) -> u64 {
+        self.network_globals.max_request_blocks(peer_id)
+    }
+
     /// Returns the Client type of the peer if known
     pub fn client_type(&self, peer_id: &PeerId) -> Client {
         self.network_globals
@@ -104,8 +248,9 @@ impl<T: EthSpec> SyncNetworkContext<T> {
             "count" => request.count,
             "peer" => %peer_id,
         );
-        let request = Request::BlocksByRange(request);
         let id = self.next_id();
+        self.blobs_by_range_request(peer_id, &request, id, SyncRequestId::RangeSyncBlobs { id })?;
+        let request = Request::BlocksByRange(request);
         let request_id = RequestId::Sync(SyncRequestId::RangeSync { id });
         self.send_network_msg(NetworkMessage::SendRequest {
             peer_id,
@@ -130,8 +275,14 @@ impl<T: EthSpec> SyncNetworkContext<T> {
             "count" => request.count,
             "peer" => %peer_id,
         );
-        let request = Request::BlocksByRange(request);
         let id = self.next_id();
+        self.blobs_by_range_request(
+            peer_id,
+            &request,
+            id,
+            SyncRequestId::BackFillSyncBlobs { id },
+        )?;
+        let request = Request::BlocksByRange(request);
         let request_id = RequestId::Sync(SyncRequestId::BackFillSync { id });
         self.send_network_msg(NetworkMessage::SendRequest {
             peer_id,
@@ -142,6 +293,143 @@ impl<T: EthSpec> SyncNetworkContext<T> {
         Ok(id)
     }
 
+    /// Requests the blobs of the blocks requested by `blocks_request`, if any are within the
+    /// blobs retention window.
+    ///
+    /// The blocks request with id `id` is then held until both requests have terminated.
+    fn blobs_by_range_request(
+        &mut self,
+        peer_id: PeerId,
+        blocks_request: &BlocksByRangeRequest,
+        id: Id,
+        sync_request_id: SyncRequestId,
+    ) -> Result<(), &'static str> {
+        let start_slot = Slot::new(blocks_request.start_slot);
+        let end_slot = start_slot + blocks_request.count;
+        let blobs_start_slot = match self.blobs_start_slot(start_slot, end_slot) {
+            Some(slot) => slot,
+            None => return Ok(()),
+        };
+        let request = BlobsByRangeRequest {
+            start_slot: blobs_start_slot.as_u64(),
+            count: (end_slot - blobs_start_slot).as_u64(),
+        };
+        trace!(
+            self.log,
+            "Sending BlobsByRange Request";
+            "method" => "BlobsByRange",
+            "start_slot" => request.start_slot,
+            "count" => request.count,
+            "peer" => %peer_id,
+        );
+        self.send_network_msg(NetworkMessage::SendRequest {
+            peer_id,
+            request: Request::BlobsByRange(request),
+            request_id: RequestId::Sync(sync_request_id),
+        })?;
+        self.blocks_and_blobs_requests
+            .insert(id, BlocksAndBlobsRequest::new(peer_id, blobs_start_slot));
+        Ok(())
+    }
+
+    /// Requests the blobs of a block received by a lookup from the peer which sent it.
+    ///
+    /// The block is held until both the blobs request and the lookup's request with id `id` have
+    /// terminated.
+    pub fn lookup_blobs_request(
+        &mut self,
+        id: Id,
+        sync_request_id: SyncRequestId,
+        peer_id: PeerId,
+        block: SignedBeaconBlock<T>,
+    ) -> Result<(), &'static str> {
+        let block_root = block.canonical_root();
+        let count = block
+            .message()
+            .body()
+            .blob_kzg_commitments()
+            .map_or(0, |commitments| commitments.len());
+        let blob_ids = (0..count as u64)
+            .map(|index| BlobIdentifier { block_root, index })
+            .collect::<Vec<_>>();
+        trace!(
+            self.log,
+            "Sending BlobsByRoot Request";
+            "method" => "BlobsByRoot",
+            "count" => blob_ids.len(),
+            "peer" => %peer_id
+        );
+        let request = BlobsByRootRequest {
+            blob_ids: VariableList::from(blob_ids),
+        };
+        self.send_network_msg(NetworkMessage::SendRequest {
+            peer_id,
+            request: Request::BlobsByRoot(request),
+            request_id: RequestId::Sync(sync_request_id),
+        })?;
+        let mut request = BlocksAndBlobsRequest::new(peer_id, block.slot());
+        request.blocks.push(block);
+        self.blocks_and_blobs_requests.insert(id, request);
+        Ok(())
+    }
+
+    /// Adds a response to the blocks request with id `id`, holding it if the request was sent
+    /// alongside a blobs request.
+    pub fn blocks_and_blobs_block_response(
+        &mut self,
+        id: Id,
+        block: Option<Box<SignedBeaconBlock<T>>>,
+    ) -> BlockResponse<T> {
+        let request = match self.blocks_and_blobs_requests.get_mut(&id) {
+            Some(request) => request,
+            None => return BlockResponse::Uncoupled(block),
+        };
+        match block {
+            Some(block) => request.blocks.push(*block),
+            None => request.blocks_finished = true,
+        }
+        if request.is_finished() {
+            self.blocks_and_blobs_requests
+                .remove(&id)
+                .map_or(BlockResponse::Pending, BlockResponse::Complete)
+        } else {
+            BlockResponse::Pending
+        }
+    }
+
+    /// Adds a response to the blobs request sent alongside the blocks request with id `id`.
+    ///
+    /// Returns the responses to both requests once both have terminated.
+    pub fn blocks_and_blobs_blob_response(
+        &mut self,
+        id: Id,
+        blob: Option<Arc<BlobSidecar<T>>>,
+    ) -> Option<BlocksAndBlobsRequest<T>> {
+        let request = self.blocks_and_blobs_requests.get_mut(&id)?;
+        match blob {
+            Some(blob) => request.blobs.push(blob),
+            None => request.blobs_finished = true,
+        }
+        if request.is_finished() {
+            self.blocks_and_blobs_requests.remove(&id)
+        } else {
+            None
+        }
+    }
+
+    /// Stops holding the responses to the blocks request with id `id`, returning `true` if they
+    /// were held.
+    pub fn remove_blocks_and_blobs_request(&mut self, id: Id) -> bool {
+        self.blocks_and_blobs_requests.remove(&id).is_some()
+    }
+
+    /// Stops holding the responses of a disconnected peer. The requests themselves are failed by
+    /// the sync algorithms which made them.
+    pub fn peer_disconnected(&mut self, peer_id: &PeerId) {
+        self.blocks_and_blobs_requests
+            .retain(|_, request| request.peer_id != *peer_id);
+    }
+
     /// Received a blocks by range response.
     pub fn range_sync_response(
         &mut self,
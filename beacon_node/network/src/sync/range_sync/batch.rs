@@ -4,6 +4,7 @@ use lighthouse_network::PeerId;
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
 use std::ops::Sub;
+use std::time::Instant;
 use types::{Epoch, EthSpec, SignedBeaconBlock, Slot};
 
 /// The number of times to retry a batch before it is considered failed.
@@ -91,6 +92,10 @@ pub struct BatchInfo<T: EthSpec, B: BatchConfig = RangeSyncBatchConfig> {
     failed_download_attempts: Vec<PeerId>,
     /// State of the batch.
     state: BatchState<T>,
+    /// When the current or last download of the batch was requested.
+    download_started: Option<Instant>,
+    /// When the current or last processing attempt of the batch started.
+    processing_started: Option<Instant>,
     /// Pin the generic
     marker: std::marker::PhantomData<B>,
 }
@@ -152,6 +157,8 @@ impl<T: EthSpec, B: BatchConfig> BatchInfo<T, B> {
             failed_download_attempts: Vec::new(),
             other_failed_processing_attempts: 0,
             state: BatchState::AwaitingDownload,
+            download_started: None,
+            processing_started: None,
             marker: std::marker::PhantomData,
         }
     }
@@ -194,6 +201,11 @@ impl<T: EthSpec, B: BatchConfig> BatchInfo<T, B> {
         }
     }
 
+    /// Returns the first slot of the batch.
+    pub fn start_slot(&self) -> Slot {
+        self.start_slot
+    }
+
     /// Returns a BlocksByRange request associated with the batch.
     pub fn to_blocks_by_range_request(&self) -> BlocksByRangeRequest {
         BlocksByRangeRequest {
@@ -211,6 +223,16 @@ impl<T: EthSpec, B: BatchConfig> BatchInfo<T, B> {
         &self.failed_processing_attempts
     }
 
+    /// When the current or last download of the batch was requested.
+    pub fn download_started(&self) -> Option<Instant> {
+        self.download_started
+    }
+
+    /// When the current or last processing attempt of the batch started.
+    pub fn processing_started(&self) -> Option<Instant> {
+        self.processing_started
+    }
+
     /// Adds a block to a downloading batch.
     pub fn add_block(&mut self, block: SignedBeaconBlock<T>) -> Result<(), WrongState> {
         match self.state.poison() {
@@ -325,6 +347,7 @@ impl<T: EthSpec, B: BatchConfig> BatchInfo<T, B> {
         match self.state.poison() {
             BatchState::AwaitingDownload => {
                 self.state = BatchState::Downloading(peer, Vec::new(), request_id);
+                self.download_started = Some(Instant::now());
                 Ok(())
             }
             BatchState::Poisoned => unreachable!("Poisoned batch"),
@@ -342,6 +365,7 @@ impl<T: EthSpec, B: BatchConfig> BatchInfo<T, B> {
         match self.state.poison() {
             BatchState::AwaitingProcessing(peer, blocks) => {
                 self.state = BatchState::Processing(Attempt::new::<B, T>(peer, &blocks));
+                self.processing_started = Some(Instant::now());
                 Ok(blocks)
             }
             BatchState::Poisoned => unreachable!("Poisoned batch"),
@@ -1,13 +1,15 @@
 use super::batch::{BatchInfo, BatchProcessingResult, BatchState};
 use crate::beacon_processor::ChainSegmentProcessId;
 use crate::beacon_processor::WorkEvent as BeaconWorkEvent;
+use crate::metrics;
 use crate::sync::{manager::Id, network_context::SyncNetworkContext, BatchProcessResult};
 use beacon_chain::BeaconChainTypes;
 use fnv::FnvHashMap;
+use lighthouse_network::rpc::methods::MAX_REQUEST_BLOCKS;
 use lighthouse_network::{PeerAction, PeerId};
 use rand::seq::SliceRandom;
 use slog::{crit, debug, o, warn};
-use std::collections::{btree_map::Entry, BTreeMap, HashSet};
+use std::collections::{btree_map::Entry, BTreeMap, HashSet, VecDeque};
 use std::hash::{Hash, Hasher};
 use tokio::sync::mpsc::Sender;
 use types::{Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
@@ -21,7 +23,16 @@ use types::{Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
 pub const EPOCHS_PER_BATCH: u64 = 2;
 
 /// The maximum number of batches to queue before requesting more.
-const BATCH_BUFFER_SIZE: u8 = 5;
+///
+/// Batches are downloaded this far ahead of the batch being processed, so that processing doesn't
+/// wait on the network. This bounds the blocks held in memory by a chain to
+/// `BATCH_BUFFER_SIZE * EPOCHS_PER_BATCH` epochs worth.
+const BATCH_BUFFER_SIZE: u8 = 8;
+
+/// The maximum number of batches requested from a single peer at once.
+///
+/// Allowing more than one lets a chain with few peers fill its batch buffer.
+pub const MAX_CONCURRENT_REQUESTS_PER_PEER: usize = 2;
 
 /// A return type for functions that act on a `Chain` which informs the caller whether the chain
 /// has been completed and should be removed or to be kept if further processing is
@@ -218,46 +229,60 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         request_id: Id,
         beacon_block: Option<SignedBeaconBlock<T::EthSpec>>,
     ) -> ProcessingResult {
-        // check if we have this batch
-        let batch = match self.batches.get_mut(&batch_id) {
-            None => {
+        // A batch could be retried without the peer failing the request (disconnecting/
+        // sending an error /timeout) if the peer is removed from the chain for other
+        // reasons. Only the batches expecting blocks from this peer and request are kept.
+        let batch_ids = self.request_batch_ids(batch_id, peer_id, request_id);
+        if batch_ids.is_empty() {
+            if !self.batches.contains_key(&batch_id) {
                 debug!(self.log, "Received a block for unknown batch"; "epoch" => batch_id);
-                // A batch might get removed when the chain advances, so this is non fatal.
-                return Ok(KeepChain);
-            }
-            Some(batch) => {
-                // A batch could be retried without the peer failing the request (disconnecting/
-                // sending an error /timeout) if the peer is removed from the chain for other
-                // reasons. Check that this block belongs to the expected peer, and that the
-                // request_id matches
-                if !batch.is_expecting_block(peer_id, &request_id) {
-                    return Ok(KeepChain);
-                }
-                batch
             }
-        };
+            // A batch might get removed when the chain advances, so this is non fatal.
+            return Ok(KeepChain);
+        }
 
         if let Some(block) = beacon_block {
-            // This is not a stream termination, simply add the block to the request
-            batch.add_block(block)?;
-            Ok(KeepChain)
-        } else {
-            // A stream termination has been sent. This batch has ended. Process a completed batch.
-            // Remove the request from the peer's active batches
+            // This is not a stream termination, simply add the block to the batch of the request
+            // covering its slot. Blocks before the first batch are added to it, to be rejected as
+            // out of range.
+            let block_batch_id = batch_ids
+                .iter()
+                .rev()
+                .find(|id| {
+                    self.batches
+                        .get(*id)
+                        .map_or(false, |batch| batch.start_slot() <= block.slot())
+                })
+                .unwrap_or(&batch_id);
+            if let Some(batch) = self.batches.get_mut(block_batch_id) {
+                batch.add_block(block)?;
+            }
+            return Ok(KeepChain);
+        }
+
+        // A stream termination has been sent. The request has ended, so every batch it downloaded
+        // is complete. Remove the batches from the peer's active batches
+        for batch_id in batch_ids {
             self.peers
                 .get_mut(peer_id)
                 .map(|active_requests| active_requests.remove(&batch_id));
 
+            let batch = match self.batches.get_mut(&batch_id) {
+                Some(batch) => batch,
+                None => continue,
+            };
             match batch.download_completed() {
                 Ok(received) => {
+                    if let Some(download_started) = batch.download_started() {
+                        metrics::observe_duration(
+                            &metrics::SYNC_RANGE_BATCH_DOWNLOAD_SECONDS,
+                            download_started.elapsed(),
+                        );
+                    }
                     let awaiting_batches = batch_id
                         .saturating_sub(self.optimistic_start.unwrap_or(self.processing_target))
                         / EPOCHS_PER_BATCH;
                     debug!(self.log, "Completed batch received"; "epoch" => batch_id, "blocks" => received, "awaiting_batches" => awaiting_batches);
-
-                    // pre-emptively request more blocks from peers whilst we process current blocks,
-                    self.request_batches(network)?;
-                    self.process_completed_batches(network)
                 }
                 Err(result) => {
                     let (expected_boundary, received_boundary, is_failed) = result?;
@@ -268,10 +293,29 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                         return Err(RemoveChain::ChainFailed(batch_id));
                     }
                     // this batch can't be used, so we need to request it again.
-                    self.retry_batch_download(network, batch_id)
+                    self.retry_batch_download(network, batch_id)?;
                 }
             }
         }
+
+        // pre-emptively request more blocks from peers whilst we process current blocks,
+        self.request_batches(network)?;
+        self.process_completed_batches(network)
+    }
+
+    /// Returns the ids of the batches downloading from `peer_id` in the request `request_id`, which
+    /// was sent for the batch `batch_id` and any batches following it.
+    fn request_batch_ids(
+        &self,
+        batch_id: BatchId,
+        peer_id: &PeerId,
+        request_id: Id,
+    ) -> Vec<BatchId> {
+        self.batches
+            .range(batch_id..)
+            .filter(|(_, batch)| batch.is_expecting_block(peer_id, &request_id))
+            .map(|(id, _)| *id)
+            .collect()
     }
 
     /// Processes the batch with the given id.
@@ -304,6 +348,16 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         let process_id = ChainSegmentProcessId::RangeBatchId(self.id, batch_id);
         self.current_processing_batch = Some(batch_id);
 
+        let awaiting_processing = self
+            .batches
+            .values()
+            .filter(|batch| matches!(batch.state(), BatchState::AwaitingProcessing(..)))
+            .count();
+        metrics::set_gauge(
+            &metrics::SYNC_RANGE_BATCHES_AWAITING_PROCESSING,
+            awaiting_processing as i64,
+        );
+
         if let Err(e) = self
             .beacon_processor_send
             .try_send(BeaconWorkEvent::chain_segment(process_id, blocks))
@@ -454,6 +508,17 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             }
         }
 
+        if let Some(processing_started) = self
+            .batches
+            .get(&batch_id)
+            .and_then(|batch| batch.processing_started())
+        {
+            metrics::observe_duration(
+                &metrics::SYNC_RANGE_BATCH_PROCESSING_SECONDS,
+                processing_started.elapsed(),
+            );
+        }
+
         match result {
             BatchProcessResult::Success(was_non_empty) => {
                 let batch = self.batches.get_mut(&batch_id).ok_or_else(|| {
@@ -805,7 +870,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
 
     /// An RPC error has occurred.
     ///
-    /// If the batch exists it is re-requested.
+    /// The batches of the request which still exist are re-requested.
     pub fn inject_error(
         &mut self,
         network: &mut SyncNetworkContext<T::EthSpec>,
@@ -813,25 +878,29 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         peer_id: &PeerId,
         request_id: Id,
     ) -> ProcessingResult {
-        if let Some(batch) = self.batches.get_mut(&batch_id) {
-            // A batch could be retried without the peer failing the request (disconnecting/
-            // sending an error /timeout) if the peer is removed from the chain for other
-            // reasons. Check that this block belongs to the expected peer
-            if !batch.is_expecting_block(peer_id, &request_id) {
-                return Ok(KeepChain);
-            }
-            debug!(self.log, "Batch failed. RPC Error"; "batch_epoch" => batch_id);
+        // A batch could be retried without the peer failing the request (disconnecting/
+        // sending an error /timeout) if the peer is removed from the chain for other
+        // reasons. Only the batches expecting blocks from this peer and request are failed. A
+        // batch could also have been removed when the chain advanced.
+        let batch_ids = self.request_batch_ids(batch_id, peer_id, request_id);
+        if batch_ids.is_empty() {
+            return Ok(KeepChain);
+        }
+        debug!(self.log, "Batch failed. RPC Error"; "batch_epoch" => batch_id, "batches" => batch_ids.len());
+        for batch_id in &batch_ids {
             if let Some(active_requests) = self.peers.get_mut(peer_id) {
-                active_requests.remove(&batch_id);
+                active_requests.remove(batch_id);
             }
-            if batch.download_failed(true)? {
-                return Err(RemoveChain::ChainFailed(batch_id));
+            if let Some(batch) = self.batches.get_mut(batch_id) {
+                if batch.download_failed(true)? {
+                    return Err(RemoveChain::ChainFailed(*batch_id));
+                }
             }
-            self.retry_batch_download(network, batch_id)
-        } else {
-            // this could be an error for an old batch, removed when the chain advances
-            Ok(KeepChain)
         }
+        for batch_id in batch_ids {
+            self.retry_batch_download(network, batch_id)?;
+        }
+        Ok(KeepChain)
     }
 
     /// Sends and registers the request of a batch awaiting download.
@@ -874,10 +943,35 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         batch_id: BatchId,
         peer: PeerId,
     ) -> ProcessingResult {
-        if let Some(batch) = self.batches.get_mut(&batch_id) {
-            let request = batch.to_blocks_by_range_request();
-            match network.blocks_by_range_request(peer, request, self.id, batch_id) {
-                Ok(request_id) => {
+        self.send_batches(network, &[batch_id], peer)
+    }
+
+    /// Requests the consecutive batches assigned to the given ids from a given peer, in a single
+    /// request.
+    fn send_batches(
+        &mut self,
+        network: &mut SyncNetworkContext<T::EthSpec>,
+        batch_ids: &[BatchId],
+        peer: PeerId,
+    ) -> ProcessingResult {
+        let (first_batch, last_batch) = match (
+            batch_ids.first().and_then(|id| self.batches.get(id)),
+            batch_ids.last().and_then(|id| self.batches.get(id)),
+        ) {
+            (Some(first_batch), Some(last_batch)) => (first_batch, last_batch),
+            _ => return Ok(KeepChain),
+        };
+        let mut request = first_batch.to_blocks_by_range_request();
+        let last_request = last_batch.to_blocks_by_range_request();
+        request.count = last_request.start_slot + last_request.count - request.start_slot;
+
+        match network.blocks_by_range_request(peer, request, self.id, batch_ids[0]) {
+            Ok(request_id) => {
+                for &batch_id in batch_ids {
+                    let batch = match self.batches.get_mut(&batch_id) {
+                        Some(batch) => batch,
+                        None => continue,
+                    };
                     // inform the batch about the new request
                     batch.start_downloading_from_peer(peer, request_id)?;
                     if self
@@ -890,21 +984,24 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                         debug!(self.log, "Requesting batch"; "epoch" => batch_id, &batch);
                     }
                     // register the batch for this peer
-                    return self
-                        .peers
+                    self.peers
                         .get_mut(&peer)
-                        .map(|requests| {
-                            requests.insert(batch_id);
-                            Ok(KeepChain)
-                        })
-                        .unwrap_or_else(|| {
-                            Err(RemoveChain::WrongChainState(format!(
+                        .map(|requests| requests.insert(batch_id))
+                        .ok_or_else(|| {
+                            RemoveChain::WrongChainState(format!(
                                 "Sending batch to a peer that is not in the chain: {}",
                                 peer
-                            )))
-                        });
+                            ))
+                        })?;
                 }
-                Err(e) => {
+                Ok(KeepChain)
+            }
+            Err(e) => {
+                for &batch_id in batch_ids {
+                    let batch = match self.batches.get_mut(&batch_id) {
+                        Some(batch) => batch,
+                        None => continue,
+                    };
                     // NOTE: under normal conditions this shouldn't happen but we handle it anyway
                     warn!(self.log, "Could not send batch request";
                         "batch_id" => batch_id, "error" => e, &batch);
@@ -915,14 +1012,19 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                         .map(|request| request.remove(&batch_id));
                     if batch.download_failed(true)? {
                         return Err(RemoveChain::ChainFailed(batch_id));
-                    } else {
-                        return self.retry_batch_download(network, batch_id);
                     }
                 }
+                for &batch_id in batch_ids {
+                    self.retry_batch_download(network, batch_id)?;
+                }
+                Ok(KeepChain)
             }
         }
+    }
 
-        Ok(KeepChain)
+    /// Requests any batches that were held back, e.g. by the sync bandwidth limit.
+    pub fn resume(&mut self, network: &mut SyncNetworkContext<T::EthSpec>) -> ProcessingResult {
+        self.request_batches(network)
     }
 
     /// Returns true if this chain is currently syncing.
@@ -943,28 +1045,37 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             return Ok(KeepChain);
         }
 
+        // wait for the bandwidth limit to allow more downloads, the chain is resumed periodically
+        if !network.range_request_allowed() {
+            return Ok(KeepChain);
+        }
+
         // find the next pending batch and request it from the peer
 
-        // randomize the peers for load balancing
+        // randomize the peers for load balancing, then order them by their active requests so
+        // that every peer is downloading a batch before any peer is given another
         let mut rng = rand::thread_rng();
-        let mut idle_peers = self
+        let mut available_peers = self
             .peers
             .iter()
-            .filter_map(|(peer, requests)| {
-                if requests.is_empty() {
-                    Some(*peer)
+            .filter_map(|(peer, batch_ids)| {
+                let active_requests = self.active_requests(batch_ids);
+                if active_requests < MAX_CONCURRENT_REQUESTS_PER_PEER {
+                    Some((active_requests, *peer))
                 } else {
                     None
                 }
             })
             .collect::<Vec<_>>();
-        idle_peers.shuffle(&mut rng);
+        available_peers.shuffle(&mut rng);
+        available_peers.sort_by_key(|(active_requests, _)| *active_requests);
+        let mut available_peers = VecDeque::from(available_peers);
 
         // check if we have the batch for our optimistic start. If not, request it first.
         // We wait for this batch before requesting any other batches.
         if let Some(epoch) = self.optimistic_start {
             if let Entry::Vacant(entry) = self.batches.entry(epoch) {
-                if let Some(peer) = idle_peers.pop() {
+                if let Some((_, peer)) = available_peers.pop_front() {
                     let optimistic_batch = BatchInfo::new(&epoch, EPOCHS_PER_BATCH);
                     entry.insert(optimistic_batch);
                     self.send_batch(network, epoch, peer)?;
@@ -973,19 +1084,51 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             return Ok(KeepChain);
         }
 
-        while let Some(peer) = idle_peers.pop() {
-            if let Some(batch_id) = self.include_next_batch() {
-                // send the batch
-                self.send_batch(network, batch_id, peer)?;
-            } else {
+        while let Some((active_requests, peer)) = available_peers.pop_front() {
+            let batch_ids = self.include_next_batches(batches_per_request(network, &peer));
+            if batch_ids.is_empty() {
                 // No more batches, simply stop
                 return Ok(KeepChain);
             }
+            // send the batches
+            self.send_batches(network, &batch_ids, peer)?;
+            // the peer may be sent another request once the other peers have theirs
+            if active_requests + 1 < MAX_CONCURRENT_REQUESTS_PER_PEER {
+                available_peers.push_back((active_requests + 1, peer));
+            }
         }
 
         Ok(KeepChain)
     }
 
+    /// Returns the number of requests downloading the given batches. A request may download
+    /// several batches.
+    fn active_requests(&self, batch_ids: &HashSet<BatchId>) -> usize {
+        batch_ids
+            .iter()
+            .filter_map(|id| match self.batches.get(id)?.state() {
+                BatchState::Downloading(_, _, request_id) => Some(*request_id),
+                _ => None,
+            })
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Creates up to `count` consecutive batches to be downloaded in a single request.
+    fn include_next_batches(&mut self, count: usize) -> Vec<BatchId> {
+        let mut batch_ids = Vec::with_capacity(count);
+        // a batch which already exists, e.g. the optimistic batch, ends the consecutive batches
+        while batch_ids.len() < count
+            && (batch_ids.is_empty() || !self.batches.contains_key(&self.to_be_downloaded))
+        {
+            match self.include_next_batch() {
+                Some(batch_id) => batch_ids.push(batch_id),
+                None => break,
+            }
+        }
+        batch_ids
+    }
+
     /// Creates the next required batch from the chain. If there are no more batches required,
     /// `false` is returned.
     fn include_next_batch(&mut self) -> Option<BatchId> {
@@ -1033,6 +1176,12 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
     }
 }
 
+/// The number of consecutive batches downloaded from `peer` in a single request. Peers with a
+/// larger negotiated `max_request_blocks` are sent proportionally larger requests.
+fn batches_per_request<T: EthSpec>(network: &SyncNetworkContext<T>, peer: &PeerId) -> usize {
+    (network.max_request_blocks(peer) / MAX_REQUEST_BLOCKS).max(1) as usize
+}
+
 impl<T: BeaconChainTypes> slog::KV for &mut SyncingChain<T> {
     fn serialize(
         &self,
@@ -274,6 +274,15 @@ where
         }
     }
 
+    /// Requests any batches that were held back by the chains, e.g. by the sync bandwidth limit.
+    pub fn resume(&mut self, network: &mut SyncNetworkContext<T::EthSpec>) {
+        for (removed_chain, sync_type, remove_reason) in
+            self.chains.call_all(|chain| chain.resume(network))
+        {
+            self.on_chain_removed(removed_chain, sync_type, remove_reason, network, "resume");
+        }
+    }
+
     /// A peer has disconnected. This removes the peer from any ongoing chains and mappings. A
     /// disconnected peer could remove a chain
     pub fn peer_disconnect(
@@ -393,6 +402,7 @@ mod tests {
     use crate::service::RequestId;
     use crate::NetworkMessage;
 
+    use super::super::chain::{EPOCHS_PER_BATCH, MAX_CONCURRENT_REQUESTS_PER_PEER};
     use super::*;
     use beacon_chain::builder::Witness;
     use beacon_chain::eth1_chain::CachingEth1Backend;
@@ -635,6 +645,24 @@ mod tests {
         range.assert_state(RangeSyncType::Finalized);
     }
 
+    #[test]
+    fn single_peer_downloads_batches_concurrently() {
+        let (mut rig, mut range) = range(false);
+
+        // Get a peer with a finalized epoch far enough ahead to need several batches.
+        let (peer_id, local_info, mut remote_info) = rig.finalized_peer();
+        remote_info.finalized_epoch = local_info.finalized_epoch + 10 * EPOCHS_PER_BATCH;
+        remote_info.head_slot = remote_info.finalized_epoch.start_slot(E::slots_per_epoch());
+        range.add_peer(&mut rig.cx, local_info, peer_id, remote_info);
+        range.assert_state(RangeSyncType::Finalized);
+
+        // The only peer should be given as many batches as it may download at once.
+        for _ in 0..MAX_CONCURRENT_REQUESTS_PER_PEER {
+            let _request = rig.grab_request(&peer_id);
+        }
+        assert!(rig.network_rx.try_recv().is_err());
+    }
+
     #[test]
     fn state_update_while_purging() {
         // NOTE: this is a regression test.
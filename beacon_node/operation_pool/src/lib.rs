@@ -17,8 +17,8 @@ use max_cover::maximum_cover;
 use parking_lot::RwLock;
 use state_processing::per_block_processing::errors::AttestationValidationError;
 use state_processing::per_block_processing::{
-    get_slashable_indices_modular, verify_attestation_for_block_inclusion, verify_exit,
-    VerifySignatures,
+    get_slashable_indices_modular, verify_attestation_for_block_inclusion,
+    verify_bls_to_execution_change, verify_exit, VerifySignatures,
 };
 use state_processing::SigVerifiedOp;
 use std::collections::{hash_map::Entry, HashMap, HashSet};
@@ -27,8 +27,8 @@ use std::ptr;
 use types::{
     sync_aggregate::Error as SyncAggregateError, typenum::Unsigned, Attestation, AttesterSlashing,
     BeaconState, BeaconStateError, ChainSpec, Epoch, EthSpec, Fork, ForkVersion, Hash256,
-    ProposerSlashing, SignedVoluntaryExit, Slot, SyncAggregate, SyncCommitteeContribution,
-    Validator,
+    ProposerSlashing, SignedBlsToExecutionChange, SignedVoluntaryExit, Slot, SyncAggregate,
+    SyncCommitteeContribution, Validator,
 };
 
 type SyncContributions<T> = RwLock<HashMap<SyncAggregateId, Vec<SyncCommitteeContribution<T>>>>;
@@ -45,6 +45,11 @@ pub struct OperationPool<T: EthSpec + Default> {
     proposer_slashings: RwLock<HashMap<u64, ProposerSlashing>>,
     /// Map from exiting validator to their exit data.
     voluntary_exits: RwLock<HashMap<u64, SignedVoluntaryExit>>,
+    /// Map from validator index to their BLS to execution change.
+    ///
+    /// These are not persisted to disk, since they are only kept until the change is included in
+    /// a block.
+    bls_to_execution_changes: RwLock<HashMap<u64, SignedBlsToExecutionChange>>,
     _phantom: PhantomData<T>,
 }
 
@@ -538,13 +543,60 @@ impl<T: EthSpec> OperationPool<T> {
         );
     }
 
+    /// Insert a BLS to execution change that has previously been checked elsewhere.
+    ///
+    /// Only the first change seen for each validator is kept.
+    pub fn insert_bls_to_execution_change(
+        &self,
+        verified_change: SigVerifiedOp<SignedBlsToExecutionChange>,
+    ) -> bool {
+        let change = verified_change.into_inner();
+        match self
+            .bls_to_execution_changes
+            .write()
+            .entry(change.message.validator_index)
+        {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(change);
+                true
+            }
+        }
+    }
+
+    /// Get a list of BLS to execution changes for inclusion in a block.
+    pub fn get_bls_to_execution_changes(
+        &self,
+        state: &BeaconState<T>,
+        spec: &ChainSpec,
+    ) -> Vec<SignedBlsToExecutionChange> {
+        filter_limit_operations(
+            self.bls_to_execution_changes.read().values(),
+            |change| {
+                verify_bls_to_execution_change(state, change, VerifySignatures::False, spec).is_ok()
+            },
+            T::MaxBlsToExecutionChanges::to_usize(),
+        )
+    }
+
+    /// Prune BLS to execution changes for validators which already have execution withdrawal
+    /// credentials in the head state.
+    pub fn prune_bls_to_execution_changes(&self, head_state: &BeaconState<T>, spec: &ChainSpec) {
+        prune_validator_hash_map(
+            &mut self.bls_to_execution_changes.write(),
+            |validator| validator.has_eth1_withdrawal_credential(spec),
+            head_state,
+        );
+    }
+
     /// Prune all types of transactions given the latest head state and head fork.
-    pub fn prune_all(&self, head_state: &BeaconState<T>, current_epoch: Epoch) {
+    pub fn prune_all(&self, head_state: &BeaconState<T>, current_epoch: Epoch, spec: &ChainSpec) {
         self.prune_attestations(current_epoch);
         self.prune_sync_contributions(head_state.slot());
         self.prune_proposer_slashings(head_state);
         self.prune_attester_slashings(head_state);
         self.prune_voluntary_exits(head_state);
+        self.prune_bls_to_execution_changes(head_state, spec);
     }
 
     /// Total number of voluntary exits in the pool.
@@ -552,6 +604,11 @@ impl<T: EthSpec> OperationPool<T> {
         self.voluntary_exits.read().len()
     }
 
+    /// Total number of BLS to execution changes in the pool.
+    pub fn num_bls_to_execution_changes(&self) -> usize {
+        self.bls_to_execution_changes.read().len()
+    }
+
     /// Returns all known `Attestation` objects.
     ///
     /// This method may return objects that are invalid for block inclusion.
@@ -611,6 +668,17 @@ impl<T: EthSpec> OperationPool<T> {
             .map(|(_, exit)| exit.clone())
             .collect()
     }
+
+    /// Returns all known `SignedBlsToExecutionChange` objects.
+    ///
+    /// This method may return objects that are invalid for block inclusion.
+    pub fn get_all_bls_to_execution_changes(&self) -> Vec<SignedBlsToExecutionChange> {
+        self.bls_to_execution_changes
+            .read()
+            .iter()
+            .map(|(_, change)| change.clone())
+            .collect()
+    }
 }
 
 /// Filter up to a maximum number of operations out of an iterator.
@@ -658,6 +726,7 @@ impl<T: EthSpec + Default> PartialEq for OperationPool<T> {
             && *self.attester_slashings.read() == *other.attester_slashings.read()
             && *self.proposer_slashings.read() == *other.proposer_slashings.read()
             && *self.voluntary_exits.read() == *other.voluntary_exits.read()
+            && *self.bls_to_execution_changes.read() == *other.bls_to_execution_changes.read()
     }
 }
 
@@ -122,6 +122,7 @@ impl<T: EthSpec> PersistedOperationPool<T> {
                     attester_slashings,
                     proposer_slashings,
                     voluntary_exits,
+                    bls_to_execution_changes: Default::default(),
                     _phantom: Default::default(),
                 }
             }
@@ -20,6 +20,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                        dir.")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("hot-db-backend")
+                .long("hot-db-backend")
+                .value_name("BACKEND")
+                .help("The key-value store used for the hot database. Redb is only available \
+                       when Lighthouse is compiled with the `redb` feature. Cannot be changed \
+                       after initialization.")
+                .possible_values(&["leveldb", "redb"])
+                .default_value("leveldb")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("freezer-dir")
                 .long("freezer-dir")
@@ -27,6 +38,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("Data directory for the freezer database.")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("freezer-backend")
+                .long("freezer-backend")
+                .value_name("BACKEND")
+                .help("The key-value store used for the freezer database. Sled may be preferable \
+                       when the freezer database is on a spinning disk. Cannot be changed after \
+                       initialization.")
+                .possible_values(&["leveldb", "sled"])
+                .default_value("leveldb")
+                .takes_value(true)
+        )
         /*
          * Network parameters.
          */
@@ -45,6 +67,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                        --subscribe-all-subnets to ensure all attestations are received for import.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("deterministic-long-lived-attnets")
+                .long("deterministic-long-lived-attnets")
+                .help("Subscribe to the long-lived attestation subnets computed from the node id, \
+                       as per the p2p spec, rather than to random subnets for each connected \
+                       validator. The subnets rotate every EPOCHS_PER_SUBNET_SUBSCRIPTION epochs \
+                       and are advertised in the ENR, so peers can find them without querying the \
+                       node.")
+                .takes_value(false),
+        )
         .arg(
             Arg::with_name("disable-packet-filter")
                 .long("disable-packet-filter")
@@ -96,6 +128,32 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("80")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("peer-diversity-client-share")
+                .long("peer-diversity-client-share")
+                .value_name("FRACTION")
+                .help("The largest fraction of the target peers that may run the same client. \
+                    When pruning excess peers, peers of clients over this share are removed first.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("peer-diversity-asn-share")
+                .long("peer-diversity-asn-share")
+                .value_name("FRACTION")
+                .help("The largest fraction of the target peers that may connect from the same \
+                    autonomous system. Peers from autonomous systems over this share are pruned \
+                    first and are not dialed. Requires --asn-database.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("asn-database")
+                .long("asn-database")
+                .value_name("PATH")
+                .help("Path to an IP to autonomous system number database, in the tab-separated \
+                    ip2asn format published by iptoasn.com. Used to keep peers diverse across \
+                    network operators.")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("boot-nodes")
                 .long("boot-nodes")
@@ -113,18 +171,74 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .set(clap::ArgSettings::Hidden)
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("sync-max-bandwidth")
+                .long("sync-max-bandwidth")
+                .value_name("MBPS")
+                .help("The maximum bandwidth in megabits per second used to download blocks while \
+                    syncing, including backfill sync. New block requests are held back while sync \
+                    is over this limit, leaving bandwidth for gossip and attestation publishing.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("backfill-max-bandwidth")
+                .long("backfill-max-bandwidth")
+                .value_name("MBPS")
+                .help("The maximum bandwidth in megabits per second used to download historical \
+                    blocks by backfill sync. This applies in addition to --sync-max-bandwidth.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("beacon-processor-max-workers")
+                .long("beacon-processor-max-workers")
+                .value_name("INTEGER")
+                .help("The maximum number of workers processing network messages (e.g. blocks, \
+                    attestations and RPC requests). Defaults to the number of CPUs.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("beacon-processor-priority-workers")
+                .long("beacon-processor-priority-workers")
+                .value_name("INTEGER")
+                .help("The number of workers processing network messages that are reserved for \
+                    blocks and aggregates from the current or previous slot, so that they are \
+                    never delayed by a backlog of other messages.")
+                .default_value("1")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("disable-upnp")
                 .long("disable-upnp")
                 .help("Disables UPnP support. Setting this will prevent Lighthouse from attempting to automatically establish external port mappings.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("disable-inbound")
+                .long("disable-inbound")
+                .help("Don't listen for inbound connections, for nodes behind a NAT or firewall \
+                    which can't open ports. Peers are only found by dialing them, so the node \
+                    dials more aggressively to maintain its peer count. The ENR doesn't advertise \
+                    any ports and UPnP is disabled.")
+                .conflicts_with_all(&["enr-tcp-port", "enr-udp-port", "enr-address", "enr-match"])
+                .takes_value(false),
+        )
         .arg(
             Arg::with_name("private")
                 .long("private")
                 .help("Prevents sending various client identification information.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("gossipsub-scoring-file")
+                .long("gossipsub-scoring-file")
+                .value_name("PATH")
+                .help("Path to a YAML file of overrides for the gossipsub peer scoring parameters \
+                    (e.g. decays, caps, weights and thresholds). Parameters which aren't set keep \
+                    the values derived from the chain spec. Intended for private or otherwise \
+                    unusual networks; the parameters in effect are reported by the \
+                    /lighthouse/gossipsub/scores HTTP API endpoint.")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("enr-udp-port")
                 .long("enr-udp-port")
@@ -152,6 +266,26 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .requires("enr-udp-port")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("enr-fields")
+                .long("enr-fields")
+                .value_name("FIELDS")
+                .help("One or more comma-delimited KEY=VALUE pairs to add to the local ENR, allowing \
+                    an operator to identify their nodes. Values are stored as UTF-8 text. The fields \
+                    set by the node itself (e.g. ip, tcp, eth2) cannot be set.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("rpc-max-request-blocks")
+                .long("rpc-max-request-blocks")
+                .value_name("COUNT")
+                .help("Serve and send BlocksByRange requests of up to this many blocks to peers which \
+                    advertise the same capability, such as other nodes run by the same operator, \
+                    allowing faster sync between them. Must be between the spec limit of 1024 and \
+                    4096. The limit is advertised in the ENR, and peers which don't advertise it \
+                    are limited to the spec defaults.")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("enr-match")
                 .short("e")
@@ -184,7 +318,22 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name("trusted-peers")
                 .long("trusted-peers")
                 .value_name("TRUSTED_PEERS")
-                .help("One or more comma-delimited trusted peer ids which always have the highest score according to the peer scoring system.")
+                .help("One or more comma-delimited trusted peers, given as peer ids, multiaddrs \
+                    ending in /p2p/<peer id> or ENRs. Trusted peers always have the highest score \
+                    according to the peer scoring system, are never pruned or disconnected for \
+                    their gossipsub score, and are redialed whenever the connection is lost (if \
+                    an address is given).")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("allowlist-file")
+                .long("allowlist-file")
+                .value_name("PATH")
+                .help("Path to a file of the only peers this node may connect to, one per line, \
+                    given as peer ids, multiaddrs ending in /p2p/<peer id> or ENRs. Connections \
+                    from any other peer are refused and discovery only dials allowlisted peers. \
+                    Peers with an address are dialed on start up. Intended for private and \
+                    consortium networks.")
                 .takes_value(true),
         )
         .arg(
@@ -221,11 +370,76 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .long("http-allow-origin")
                 .value_name("ORIGIN")
                 .help("Set the value of the Access-Control-Allow-Origin response HTTP header. \
+                    Multiple origins may be supplied as a comma-separated list, and each origin \
+                    may contain * wildcards (e.g., https://*.example.com). \
                     Use * to allow any origin (not recommended in production). \
                     If no value is supplied, the CORS allowed origin is set to the listen \
                     address of this server (e.g., http://localhost:5052).")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("http-disable-compression")
+                .long("http-disable-compression")
+                .help("Disable gzip and deflate compression of HTTP API responses. By default \
+                    responses are compressed for clients which send a supporting \
+                    Accept-Encoding header.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("http-max-concurrent-state-replays")
+                .long("http-max-concurrent-state-replays")
+                .value_name("COUNT")
+                .help("The maximum number of historical states which may be reconstructed by \
+                    block replay at once in order to serve the debug/beacon/states endpoint. \
+                    Further requests receive a 503 response with a Retry-After header.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-state-replay-timeout")
+                .long("http-state-replay-timeout")
+                .value_name("SECONDS")
+                .help("The time budget in seconds for reconstructing a single historical \
+                    state by block replay. Replays which exceed this budget are abandoned.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-rate-limit")
+                .long("http-rate-limit")
+                .value_name("TOKENS_PER_SECOND")
+                .help("Enable per-client rate limiting of the HTTP API. Each client (identified \
+                    by its API token, or otherwise by its IP address) has a bucket of tokens \
+                    which refills at this rate. Requests which can't be paid for receive a 429 \
+                    response with Retry-After and RateLimit headers.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-rate-limit-burst")
+                .long("http-rate-limit-burst")
+                .value_name("TOKENS")
+                .help("The maximum number of tokens in each client's bucket. [default: 200]")
+                .requires("http-rate-limit")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-rate-limit-costs")
+                .long("http-rate-limit-costs")
+                .value_name("CHEAP,STANDARD,EXPENSIVE")
+                .help("The number of tokens charged for requests to cheap endpoints (node status \
+                    and head queries), standard endpoints and expensive endpoints (state \
+                    downloads, validator lists and analysis routes), as a comma-separated \
+                    list. [default: 1,5,50]")
+                .requires("http-rate-limit")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-serve-finalized-only")
+                .long("http-serve-finalized-only")
+                .help("Only serve finalized data from the state and block endpoints. Requests \
+                    for non-finalized states and blocks (including the head) receive a 404. \
+                    Useful for checkpoint sync providers behind a CDN, as finalized responses \
+                    never change.")
+                .takes_value(false),
+        )
         .arg(
             Arg::with_name("http-disable-legacy-spec")
                 .long("http-disable-legacy-spec")
@@ -255,6 +469,26 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                     over TLS. Must not be password-protected.")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("http-validator-token-file")
+                .long("http-validator-token-file")
+                .value_name("PATH")
+                .help("Path to a file containing a token which must be supplied as a bearer token \
+                    in the Authorization header to access validator endpoints (block production, \
+                    pool submission, duties, etc). The admin token also grants access to these \
+                    endpoints. If neither token is supplied, no authorization is required.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("http-admin-token-file")
+                .long("http-admin-token-file")
+                .value_name("PATH")
+                .help("Path to a file containing a token which must be supplied as a bearer token \
+                    in the Authorization header to access admin endpoints (debug state downloads \
+                    and Lighthouse database routes). If supplied, this token is also required \
+                    for validator endpoints unless --http-validator-token-file is set.")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("http-allow-sync-stalled")
                 .long("http-allow-sync-stalled")
@@ -312,6 +546,25 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 and never provide an untrusted URL.")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("monitoring-endpoint-period")
+                .long("monitoring-endpoint-period")
+                .value_name("SECONDS")
+                .help("Defines how many seconds to wait between each message sent to \
+                       the monitoring-endpoint. Default: 60s")
+                .requires("monitoring-endpoint")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("monitoring-endpoint-token-file")
+                .long("monitoring-endpoint-token-file")
+                .value_name("PATH")
+                .help("Path to a file containing a token which is sent to the monitoring-endpoint \
+                       as a bearer token in the Authorization header of each message, for \
+                       services which require authentication.")
+                .requires("monitoring-endpoint")
+                .takes_value(true),
+        )
 
         /*
          * Standard staking flags
@@ -396,6 +649,54 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                        [default: 8192 (mainnet) or 64 (minimal)]")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("restore-points-per-snapshot")
+                .long("restore-points-per-snapshot")
+                .value_name("COUNT")
+                .help("Specifies how often a freezer DB restore point should be stored in full. \
+                       The restore points in between are stored as compact diffs. A value of 1 \
+                       stores every restore point in full. Can be changed at any time, and \
+                       existing restore points can be converted with `lighthouse db \
+                       convert-state-diffs`. [default: 32]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("compaction-duty-cycle")
+                .long("compaction-duty-cycle")
+                .value_name("PERCENT")
+                .help("The maximum percentage of time that background database compaction spends \
+                       compacting. Compaction pauses in between to leave disk I/O for block \
+                       import, which may help nodes on slow disks. [default: 25]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("prune-history")
+                .long("prune-history")
+                .help("Delete historic states from the freezer DB once they are older than the \
+                       history retention window. The node remains able to follow the head and \
+                       perform validator duties, but can't serve queries for pruned states.")
+                .takes_value(false)
+                .conflicts_with("reconstruct-historic-states")
+        )
+        .arg(
+            Arg::with_name("history-retention-epochs")
+                .long("history-retention-epochs")
+                .value_name("EPOCHS")
+                .help("The number of epochs prior to the latest finalized epoch for which historic \
+                       states are kept when pruning history. [default: 256]")
+                .requires("prune-history")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("prune-history-blocks")
+                .long("prune-history-blocks")
+                .help("Also delete blocks older than the history retention window when pruning \
+                       history. Pruned blocks are not backfilled and can't be served to peers, \
+                       which may be needed by peers syncing from a checkpoint. Blocks from the \
+                       last MIN_EPOCHS_FOR_BLOCK_REQUESTS epochs are always kept.")
+                .requires("prune-history")
+                .takes_value(false)
+        )
         .arg(
             Arg::with_name("block-cache-size")
                 .long("block-cache-size")
@@ -403,6 +704,37 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("Specifies how many blocks the database should cache in memory [default: 5]")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("epoch-boundary-state-cache-size")
+                .long("epoch-boundary-state-cache-size")
+                .value_name("SIZE")
+                .help("Specifies how many epoch boundary states the database should cache in \
+                       memory. Each state may use hundreds of megabytes, but keeping recent ones \
+                       cached speeds up state queries via the HTTP API and attestation \
+                       verification. [default: 4]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("historic-replay-threads")
+                .long("historic-replay-threads")
+                .value_name("THREADS")
+                .help("The number of threads used to replay blocks when serving queries over \
+                       ranges of historic states. Ranges spanning several restore points are split \
+                       at each restore point and the parts replayed in parallel. Loading a single \
+                       historic state only uses them to load the restore points either side of it \
+                       in parallel. [default: 4]")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("blob-prune-epochs")
+                .long("blob-prune-epochs")
+                .value_name("EPOCHS")
+                .help("The number of epochs prior to the latest finalized epoch for which blob \
+                       sidecars are kept. Blobs are pruned independently of states and blocks. \
+                       Must be at least MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS, which is the \
+                       default (4096 on mainnet).")
+                .takes_value(true)
+        )
         /*
          * Execution Layer Integration
          */
@@ -578,12 +910,25 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name("slasher-max-db-size")
                 .long("slasher-max-db-size")
                 .help(
-                    "Maximum size of the MDBX database used by the slasher."
+                    "Maximum size of the database used by the slasher. When the database \
+                    approaches this size the oldest history is trimmed to make room for new \
+                    attestations."
                 )
                 .value_name("GIGABYTES")
                 .requires("slasher")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("slasher-backend")
+                .long("slasher-backend")
+                .value_name("BACKEND")
+                .help("The key-value store used for the slasher database. Redb is only available \
+                       when Lighthouse is compiled with the `slasher-redb` feature. Cannot be \
+                       changed after initialization.")
+                .possible_values(&["mdbx", "redb"])
+                .takes_value(true)
+                .requires("slasher")
+        )
         .arg(
             Arg::with_name("slasher-att-cache-size")
                 .long("slasher-att-cache-size")
@@ -612,6 +957,30 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .requires("slasher")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("slasher-workers")
+                .long("slasher-workers")
+                .help(
+                    "Number of threads used by the slasher to process attestations. Each thread \
+                     processes the attestations of different validator chunks."
+                )
+                .value_name("NUM_THREADS")
+                .requires("slasher")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("slasher-backfill-epochs")
+                .long("slasher-backfill-epochs")
+                .help(
+                    "Number of past epochs of blocks stored by this node to check for slashings \
+                     when the slasher database is first created. Only blocks in the database are \
+                     checked, so a checkpoint synced node must backfill its blocks first. \
+                     Defaults to 0, which disables the backfill."
+                )
+                .value_name("EPOCHS")
+                .requires("slasher")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("slasher-broadcast")
                 .long("slasher-broadcast")
@@ -619,6 +988,34 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                        [disabled by default].")
                 .requires("slasher")
         )
+        .arg(
+            Arg::with_name("slasher-webhook-urls")
+                .long("slasher-webhook-urls")
+                .value_name("URLS")
+                .help("Comma-separated list of URLs to which slashings found by the slasher are \
+                       POSTed as JSON, e.g. for whistleblower infrastructure.")
+                .requires("slasher")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("slasher-vc-url")
+                .long("slasher-vc-url")
+                .value_name("URL")
+                .help("The HTTP API of a Lighthouse validator client on which to disable any \
+                       validator monitored by this node that the slasher finds to have signed \
+                       slashable messages. Requires --slasher-vc-api-token.")
+                .requires("slasher")
+                .requires("slasher-vc-api-token")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("slasher-vc-api-token")
+                .long("slasher-vc-api-token")
+                .value_name("PATH")
+                .help("Path to the API token of the validator client given by --slasher-vc-url.")
+                .requires("slasher-vc-url")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("wss-checkpoint")
                 .long("wss-checkpoint")
@@ -657,12 +1054,39 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .conflicts_with("checkpoint-state")
         )
+        .arg(
+            Arg::with_name("genesis-state-url")
+                .long("genesis-state-url")
+                .help("A URL from which to download the genesis state of a network which doesn't \
+                       include one in its config. The URL may point at an SSZ-encoded state, or at \
+                       a beacon node or checkpoint sync server serving the standard API. The \
+                       state must match the --genesis-state-root.")
+                .value_name("URL")
+                .takes_value(true)
+                .requires("genesis-state-root")
+        )
+        .arg(
+            Arg::with_name("genesis-state-root")
+                .long("genesis-state-root")
+                .help("The root of the genesis state downloaded from the --genesis-state-url.")
+                .value_name("STATE_ROOT")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("reconstruct-historic-states")
                 .long("reconstruct-historic-states")
                 .help("After a checkpoint sync, reconstruct historic states in the database.")
                 .takes_value(false)
         )
+        .arg(
+            Arg::with_name("reconstruct-payloads")
+                .long("reconstruct-payloads")
+                .help("Fetch the execution payloads of finalized blocks which are missing from the \
+                       database, e.g. because they were pruned, from the execution layer and store \
+                       them in the freezer DB. Reconstruction runs in the background and resumes \
+                       where it left off after a restart.")
+                .takes_value(false)
+        )
         .arg(
             Arg::with_name("validator-monitor-auto")
                 .long("validator-monitor-auto")
@@ -688,6 +1112,18 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .value_name("PATH")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("validator-monitor-individual-tracking-threshold")
+                .long("validator-monitor-individual-tracking-threshold")
+                .help("Once the validator monitor reaches this number of validators, it will \
+                    stop reporting metrics for each validator and instead report metrics \
+                    aggregated across all monitored validators, to avoid an excessive number of \
+                    Prometheus time series. Per-validator detail remains available via the \
+                    /lighthouse/validator_monitor HTTP API endpoint.")
+                .value_name("INTEGER")
+                .default_value("64")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("disable-lock-timeouts")
                 .long("disable-lock-timeouts")
@@ -696,6 +1132,25 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                        experimental as it may obscure performance issues.")
                 .takes_value(false)
         )
+        .arg(
+            Arg::with_name("light-client-server")
+                .long("light-client-server")
+                .help("Compute the light client updates of imported blocks, and subscribe to the \
+                       light client gossip topics to verify and forward the updates of peers. \
+                       Only has an effect from the Altair fork onwards.")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("maximum-gossip-clock-disparity")
+                .long("maximum-gossip-clock-disparity")
+                .value_name("MILLISECONDS")
+                .help("The number of milliseconds by which the clocks of peers gossiping blocks \
+                       and attestations may differ from our own. Messages from outside this \
+                       window are ignored. Only increase this on networks with poorly \
+                       synchronised clocks.")
+                .default_value("500")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("fork-choice-before-proposal-timeout")
                 .long("fork-choice-before-proposal-timeout")
@@ -705,4 +1160,24 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("250")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("memory-watermark")
+                .long("memory-watermark")
+                .value_name("MEGABYTES")
+                .help("The resident memory size in megabytes above which the beacon node sheds \
+                       load to avoid being killed by the operating system. Whilst above the \
+                       watermark caches are shrunk, expensive API queries are refused and \
+                       backfill sync is paused. Only supported on Linux.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("crash-dump-dir")
+                .long("crash-dump-dir")
+                .value_name("DIR")
+                .help("A directory to save the block and states to when a block fails with a \
+                       consensus-level error, such as a state root mismatch. Each failure is \
+                       logged with a correlation ID naming its directory, which can be attached \
+                       to a bug report.")
+                .takes_value(true)
+        )
 }
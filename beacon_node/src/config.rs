@@ -3,8 +3,12 @@ use clap_utils::flags::DISABLE_MALLOC_TUNING_FLAG;
 use client::{ClientConfig, ClientGenesis};
 use directory::{DEFAULT_BEACON_NODE_DIR, DEFAULT_NETWORK_DIR, DEFAULT_ROOT_DIR};
 use environment::RuntimeContext;
-use http_api::TlsConfig;
-use lighthouse_network::{multiaddr::Protocol, Enr, Multiaddr, NetworkConfig, PeerIdSerialized};
+use http_api::{RateLimitConfig, TlsConfig};
+use lighthouse_network::{
+    multiaddr::Protocol,
+    rpc::{MAX_REQUEST_BLOCKS, MAX_REQUEST_BLOCKS_HARD_CAP},
+    Enr, EnrExt, GossipsubScoringOverrides, Multiaddr, NetworkConfig, PeerId, PeerIdSerialized,
+};
 use sensitive_url::SensitiveUrl;
 use slog::{info, warn, Logger};
 use std::cmp;
@@ -13,6 +17,7 @@ use std::fs;
 use std::net::{IpAddr, Ipv4Addr, ToSocketAddrs};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 use types::{Checkpoint, Epoch, EthSpec, Hash256, PublicKeyBytes, GRAFFITI_BYTES_LEN};
 use unused_port::{unused_tcp_port, unused_udp_port};
 
@@ -116,6 +121,64 @@ pub fn get_config<E: EthSpec>(
         client_config.http_api.serve_legacy_spec = false;
     }
 
+    if cli_args.is_present("http-disable-compression") {
+        client_config.http_api.enable_compression = false;
+    }
+
+    if let Some(max_concurrent_state_replays) =
+        clap_utils::parse_optional(cli_args, "http-max-concurrent-state-replays")?
+    {
+        if max_concurrent_state_replays == 0 {
+            return Err("--http-max-concurrent-state-replays must be at least 1".to_string());
+        }
+        client_config.http_api.max_concurrent_state_replays = max_concurrent_state_replays;
+    }
+
+    if let Some(timeout) = clap_utils::parse_optional(cli_args, "http-state-replay-timeout")? {
+        client_config.http_api.state_replay_time_budget = Duration::from_secs(timeout);
+    }
+
+    if let Some(tokens_per_second) = clap_utils::parse_optional(cli_args, "http-rate-limit")? {
+        if tokens_per_second == 0 {
+            return Err("--http-rate-limit must be greater than zero".to_string());
+        }
+        let mut rate_limit = RateLimitConfig {
+            tokens_per_second,
+            ..RateLimitConfig::default()
+        };
+
+        if let Some(burst) = clap_utils::parse_optional(cli_args, "http-rate-limit-burst")? {
+            rate_limit.burst = burst;
+        }
+
+        if let Some(costs) = cli_args.value_of("http-rate-limit-costs") {
+            let costs = costs
+                .split(',')
+                .map(|cost| cost.trim().parse::<u64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Invalid --http-rate-limit-costs: {}", e))?;
+
+            match costs.as_slice() {
+                [cheap, standard, expensive] => {
+                    rate_limit.cheap_cost = *cheap;
+                    rate_limit.standard_cost = *standard;
+                    rate_limit.expensive_cost = *expensive;
+                }
+                _ => {
+                    return Err(
+                        "--http-rate-limit-costs must contain exactly three costs".to_string()
+                    )
+                }
+            }
+        }
+
+        client_config.http_api.rate_limit = Some(rate_limit);
+    }
+
+    if cli_args.is_present("http-serve-finalized-only") {
+        client_config.http_api.serve_finalized_only = true;
+    }
+
     if cli_args.is_present("http-enable-tls") {
         client_config.http_api.tls_config = Some(TlsConfig {
             cert: cli_args
@@ -135,6 +198,16 @@ pub fn get_config<E: EthSpec>(
         client_config.http_api.allow_sync_stalled = true;
     }
 
+    if let Some(path) = cli_args.value_of("http-validator-token-file") {
+        client_config.http_api.auth.validator_token =
+            Some(read_http_token_file(path, "http-validator-token-file")?);
+    }
+
+    if let Some(path) = cli_args.value_of("http-admin-token-file") {
+        client_config.http_api.auth.admin_token =
+            Some(read_http_token_file(path, "http-admin-token-file")?);
+    }
+
     /*
      * Prometheus metrics HTTP server
      */
@@ -168,10 +241,18 @@ pub fn get_config<E: EthSpec>(
      * Explorer metrics
      */
     if let Some(monitoring_endpoint) = cli_args.value_of("monitoring-endpoint") {
+        let update_period_secs =
+            clap_utils::parse_optional(cli_args, "monitoring-endpoint-period")?;
+        if update_period_secs == Some(0) {
+            return Err("--monitoring-endpoint-period must be greater than zero".to_string());
+        }
+
         client_config.monitoring_api = Some(monitoring_api::Config {
             db_path: None,
             freezer_db_path: None,
             monitoring_endpoint: monitoring_endpoint.to_string(),
+            update_period_secs,
+            api_token_path: clap_utils::parse_optional(cli_args, "monitoring-endpoint-token-file")?,
         });
     }
 
@@ -286,20 +367,81 @@ pub fn get_config<E: EthSpec>(
         client_config.execution_layer = Some(el_config);
     }
 
+    if let Some(hot_backend) = clap_utils::parse_optional(cli_args, "hot-db-backend")? {
+        client_config.store.hot_backend = hot_backend;
+    }
+
     if let Some(freezer_dir) = cli_args.value_of("freezer-dir") {
         client_config.freezer_db_path = Some(PathBuf::from(freezer_dir));
     }
 
+    if let Some(freezer_backend) = clap_utils::parse_optional(cli_args, "freezer-backend")? {
+        client_config.store.freezer_backend = freezer_backend;
+    }
+
     let (sprp, sprp_explicit) = get_slots_per_restore_point::<E>(cli_args)?;
     client_config.store.slots_per_restore_point = sprp;
     client_config.store.slots_per_restore_point_set_explicitly = sprp_explicit;
 
+    if let Some(restore_points_per_snapshot) =
+        clap_utils::parse_optional(cli_args, "restore-points-per-snapshot")?
+    {
+        if restore_points_per_snapshot == 0 {
+            return Err("restore-points-per-snapshot must be at least 1".to_string());
+        }
+        client_config.store.restore_points_per_snapshot = restore_points_per_snapshot;
+    }
+
+    if let Some(compaction_duty_cycle_percent) =
+        clap_utils::parse_optional::<u8>(cli_args, "compaction-duty-cycle")?
+    {
+        if !(1..=100).contains(&compaction_duty_cycle_percent) {
+            return Err("compaction-duty-cycle must be between 1 and 100".to_string());
+        }
+        client_config.store.compaction_duty_cycle_percent = compaction_duty_cycle_percent;
+    }
+
+    if cli_args.is_present("prune-history") {
+        client_config.store.history_retention_epochs = Some(
+            clap_utils::parse_optional(cli_args, "history-retention-epochs")?
+                .unwrap_or(store::config::DEFAULT_HISTORY_RETENTION_EPOCHS),
+        );
+        client_config.store.prune_history_blocks = cli_args.is_present("prune-history-blocks");
+    }
+
     if let Some(block_cache_size) = cli_args.value_of("block-cache-size") {
         client_config.store.block_cache_size = block_cache_size
             .parse()
             .map_err(|_| "block-cache-size is not a valid integer".to_string())?;
     }
 
+    if let Some(epoch_boundary_state_cache_size) =
+        clap_utils::parse_optional(cli_args, "epoch-boundary-state-cache-size")?
+    {
+        client_config.store.epoch_boundary_state_cache_size = epoch_boundary_state_cache_size;
+    }
+
+    if let Some(max_replay_threads) =
+        clap_utils::parse_optional::<usize>(cli_args, "historic-replay-threads")?
+    {
+        if max_replay_threads == 0 {
+            return Err("historic-replay-threads must be at least 1".to_string());
+        }
+        client_config.store.max_replay_threads = max_replay_threads;
+    }
+
+    if let Some(blob_retention_epochs) =
+        clap_utils::parse_optional::<u64>(cli_args, "blob-prune-epochs")?
+    {
+        if blob_retention_epochs < spec.min_epochs_for_blob_sidecars_requests {
+            return Err(format!(
+                "blob-prune-epochs must be at least {} (MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS)",
+                spec.min_epochs_for_blob_sidecars_requests
+            ));
+        }
+        client_config.store.blob_retention_epochs = Some(blob_retention_epochs);
+    }
+
     client_config.store.compact_on_init = cli_args.is_present("compact-db");
     if let Some(compact_on_prune) = cli_args.value_of("auto-compact-db") {
         client_config.store.compact_on_prune = compact_on_prune
@@ -365,8 +507,16 @@ pub fn get_config<E: EthSpec>(
         }
     }
 
-    client_config.genesis = if let Some(genesis_state_bytes) =
-        eth2_network_config.genesis_state_bytes.clone()
+    if let Some(genesis_state_url) = cli_args.value_of("genesis-state-url") {
+        client_config.genesis_state_url = Some(
+            SensitiveUrl::parse(genesis_state_url)
+                .map_err(|e| format!("Invalid genesis state URL: {:?}", e))?,
+        );
+    }
+    client_config.genesis_state_root = clap_utils::parse_optional(cli_args, "genesis-state-root")?;
+
+    client_config.genesis = if eth2_network_config.genesis_state_bytes.is_some()
+        || client_config.genesis_state_url.is_some()
     {
         // Set up weak subjectivity sync, or start from the hardcoded genesis state.
         if let (Some(initial_state_path), Some(initial_block_path)) = (
@@ -389,7 +539,6 @@ pub fn get_config<E: EthSpec>(
             let anchor_block_bytes = read(initial_block_path)?;
 
             ClientGenesis::WeakSubjSszBytes {
-                genesis_state_bytes,
                 anchor_state_bytes,
                 anchor_block_bytes,
             }
@@ -397,22 +546,15 @@ pub fn get_config<E: EthSpec>(
             let url = SensitiveUrl::parse(remote_bn_url)
                 .map_err(|e| format!("Invalid checkpoint sync URL: {:?}", e))?;
 
-            ClientGenesis::CheckpointSyncUrl {
-                genesis_state_bytes,
-                url,
-            }
+            ClientGenesis::CheckpointSyncUrl { url }
         } else {
-            // Note: re-serializing the genesis state is not so efficient, however it avoids adding
-            // trait bounds to the `ClientGenesis` enum. This would have significant flow-on
-            // effects.
-            ClientGenesis::SszBytes {
-                genesis_state_bytes,
-            }
+            ClientGenesis::GenesisState
         }
     } else {
         if cli_args.is_present("checkpoint-state") || cli_args.is_present("checkpoint-sync-url") {
             return Err(
-                "Checkpoint sync is not available for this network as no genesis state is known"
+                "Checkpoint sync is not available for this network as no genesis state is known, \
+                 use --genesis-state-url"
                     .to_string(),
             );
         }
@@ -423,6 +565,10 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.reconstruct_historic_states = true;
     }
 
+    if cli_args.is_present("reconstruct-payloads") {
+        client_config.chain.reconstruct_payloads = true;
+    }
+
     let raw_graffiti = if let Some(graffiti) = cli_args.value_of("graffiti") {
         if graffiti.len() > GRAFFITI_BYTES_LEN {
             return Err(format!(
@@ -529,6 +675,10 @@ pub fn get_config<E: EthSpec>(
             slasher_config.max_db_size_mbs = max_db_size_gbs * 1024;
         }
 
+        if let Some(backend) = clap_utils::parse_optional(cli_args, "slasher-backend")? {
+            slasher_config.backend = backend;
+        }
+
         if let Some(attestation_cache_size) =
             clap_utils::parse_optional(cli_args, "slasher-att-cache-size")?
         {
@@ -545,8 +695,35 @@ pub fn get_config<E: EthSpec>(
             slasher_config.validator_chunk_size = validator_chunk_size;
         }
 
+        if let Some(num_workers) = clap_utils::parse_optional(cli_args, "slasher-workers")? {
+            slasher_config.num_workers = num_workers;
+        }
+
+        if let Some(backfill_epochs) =
+            clap_utils::parse_optional(cli_args, "slasher-backfill-epochs")?
+        {
+            slasher_config.backfill_epochs = backfill_epochs;
+        }
+
+        if let Some(url) = cli_args.value_of("slasher-vc-url") {
+            slasher_config.validator_client_url = Some(
+                SensitiveUrl::parse(url)
+                    .map_err(|e| format!("slasher-vc-url was an invalid URL: {:?}", e))?,
+            );
+        }
+        slasher_config.validator_client_token_path =
+            clap_utils::parse_optional(cli_args, "slasher-vc-api-token")?;
+
         slasher_config.broadcast = cli_args.is_present("slasher-broadcast");
 
+        if let Some(urls) = cli_args.value_of("slasher-webhook-urls") {
+            slasher_config.webhook_urls = urls
+                .split(',')
+                .map(SensitiveUrl::parse)
+                .collect::<Result<_, _>>()
+                .map_err(|e| format!("slasher-webhook-urls contains an invalid URL {:?}", e))?;
+        }
+
         client_config.slasher = Some(slasher_config);
     }
 
@@ -583,6 +760,9 @@ pub fn get_config<E: EthSpec>(
             .extend_from_slice(&pubkeys);
     }
 
+    client_config.validator_monitor_individual_tracking_threshold =
+        clap_utils::parse_required(cli_args, "validator-monitor-individual-tracking-threshold")?;
+
     if cli_args.is_present("disable-lock-timeouts") {
         client_config.chain.enable_lock_timeouts = false;
     }
@@ -593,9 +773,79 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.fork_choice_before_proposal_timeout_ms = timeout;
     }
 
+    if cli_args.is_present("light-client-server") {
+        client_config.chain.enable_light_client_server = true;
+    }
+
+    if let Some(disparity) = clap_utils::parse_optional(cli_args, "maximum-gossip-clock-disparity")?
+    {
+        client_config.chain.maximum_gossip_clock_disparity_ms = disparity;
+    }
+
+    if let Some(watermark_mb) = clap_utils::parse_optional::<u64>(cli_args, "memory-watermark")? {
+        client_config.chain.memory_watermark = Some(watermark_mb * 1_048_576);
+    }
+
+    client_config.chain.crash_dump_dir = clap_utils::parse_optional(cli_args, "crash-dump-dir")?;
+
     Ok(client_config)
 }
 
+/// Read an HTTP API authorization token from the file at `path`.
+fn read_http_token_file(path: &str, flag: &str) -> Result<String, String> {
+    let token = fs::read_to_string(path)
+        .map_err(|e| format!("Unable to read --{}: {}", flag, e))?
+        .trim()
+        .to_string();
+
+    if token.is_empty() {
+        return Err(format!("--{} must not be empty", flag));
+    }
+
+    Ok(token)
+}
+
+/// Parses peers given as peer ids, multiaddrs ending in `/p2p/<peer id>` or ENRs, returning their
+/// peer ids and the addresses given for them.
+fn parse_peers<'a>(
+    peers: impl Iterator<Item = &'a str>,
+    description: &str,
+) -> Result<(Vec<PeerIdSerialized>, Vec<Multiaddr>), String> {
+    let mut peer_ids: Vec<PeerIdSerialized> = vec![];
+    let mut addresses: Vec<Multiaddr> = vec![];
+    for peer in peers {
+        if peer.starts_with("enr:") {
+            let enr: Enr = peer
+                .parse()
+                .map_err(|_| format!("Invalid {} ENR: {}", description, peer))?;
+            peer_ids.push(enr.peer_id().into());
+            addresses.extend(enr.multiaddr_p2p_tcp());
+        } else if peer.starts_with('/') {
+            let multiaddr: Multiaddr = peer
+                .parse()
+                .map_err(|_| format!("Invalid {} Multiaddr: {}", description, peer))?;
+            let peer_id = match multiaddr.iter().last() {
+                Some(Protocol::P2p(multihash)) => PeerId::from_multihash(multihash)
+                    .map_err(|_| format!("Invalid peer id in Multiaddr: {}", peer))?,
+                _ => {
+                    return Err(format!(
+                        "Missing P2P in {} Multiaddr: {}",
+                        description, peer
+                    ))
+                }
+            };
+            peer_ids.push(peer_id.into());
+            addresses.push(multiaddr);
+        } else {
+            peer_ids.push(
+                peer.parse()
+                    .map_err(|_| format!("Invalid {} id: {}", description, peer))?,
+            );
+        }
+    }
+    Ok((peer_ids, addresses))
+}
+
 /// Sets the network config from the command line arguments
 pub fn set_network_config(
     config: &mut NetworkConfig,
@@ -619,6 +869,14 @@ pub fn set_network_config(
         config.import_all_attestations = true;
     }
 
+    if cli_args.is_present("deterministic-long-lived-attnets") {
+        config.deterministic_long_lived_attnets = true;
+    }
+
+    if cli_args.is_present("light-client-server") {
+        config.enable_light_client_server = true;
+    }
+
     if cli_args.is_present("shutdown-after-sync") {
         config.shutdown_after_sync = true;
     }
@@ -636,6 +894,31 @@ pub fn set_network_config(
             .map_err(|_| format!("Invalid number of target peers: {}", target_peers_str))?;
     }
 
+    for (flag, share) in [
+        (
+            "peer-diversity-client-share",
+            &mut config.peer_diversity_max_client_share,
+        ),
+        (
+            "peer-diversity-asn-share",
+            &mut config.peer_diversity_max_asn_share,
+        ),
+    ] {
+        if let Some(share_str) = cli_args.value_of(flag) {
+            let value = share_str
+                .parse::<f32>()
+                .map_err(|_| format!("Invalid --{}: {}", flag, share_str))?;
+            if !(value > 0.0 && value <= 1.0) {
+                return Err(format!("--{} must be in the range (0, 1]", flag));
+            }
+            *share = Some(value);
+        }
+    }
+
+    if let Some(path) = cli_args.value_of("asn-database") {
+        config.asn_database = Some(PathBuf::from(path));
+    }
+
     if let Some(port_str) = cli_args.value_of("port") {
         let port = port_str
             .parse::<u16>()
@@ -658,6 +941,28 @@ pub fn set_network_config(
         config.network_load = network_load;
     }
 
+    config.sync_max_bandwidth = clap_utils::parse_optional(cli_args, "sync-max-bandwidth")?;
+    config.backfill_max_bandwidth = clap_utils::parse_optional(cli_args, "backfill-max-bandwidth")?;
+    if config.sync_max_bandwidth == Some(0) || config.backfill_max_bandwidth == Some(0) {
+        return Err("Sync bandwidth limits must be greater than zero".to_string());
+    }
+
+    if let Some(value) = cli_args.value_of("beacon-processor-max-workers") {
+        let max_workers = value
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid integer: {}", value))?;
+        if max_workers == 0 {
+            return Err("--beacon-processor-max-workers must be at least 1".to_string());
+        }
+        config.beacon_processor_max_workers = Some(max_workers);
+    }
+
+    if let Some(value) = cli_args.value_of("beacon-processor-priority-workers") {
+        config.beacon_processor_priority_workers = value
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid integer: {}", value))?;
+    }
+
     if let Some(boot_enr_str) = cli_args.value_of("boot-nodes") {
         let mut enrs: Vec<Enr> = vec![];
         let mut multiaddrs: Vec<Multiaddr> = vec![];
@@ -695,14 +1000,27 @@ pub fn set_network_config(
     }
 
     if let Some(trusted_peers_str) = cli_args.value_of("trusted-peers") {
-        config.trusted_peers = trusted_peers_str
-            .split(',')
-            .map(|peer_id| {
-                peer_id
-                    .parse()
-                    .map_err(|_| format!("Invalid trusted peer id: {}", peer_id))
-            })
-            .collect::<Result<Vec<PeerIdSerialized>, _>>()?;
+        let (trusted_peers, trusted_peer_addresses) =
+            parse_peers(trusted_peers_str.split(','), "trusted peer")?;
+        config.trusted_peers = trusted_peers;
+        config.trusted_peer_addresses = trusted_peer_addresses;
+    }
+
+    if let Some(path) = cli_args.value_of("allowlist-file") {
+        let allowlist = fs::read_to_string(path)
+            .map_err(|e| format!("Unable to read --allowlist-file: {}", e))?;
+        let (peers, addresses) = parse_peers(
+            allowlist
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#')),
+            "allowlisted peer",
+        )?;
+        if peers.is_empty() {
+            return Err("--allowlist-file must contain at least one peer".to_string());
+        }
+        config.peer_allowlist = Some(peers);
+        config.peer_allowlist_addresses = addresses;
     }
 
     if let Some(enr_udp_port_str) = cli_args.value_of("enr-udp-port") {
@@ -721,6 +1039,32 @@ pub fn set_network_config(
         );
     }
 
+    if let Some(enr_fields_str) = cli_args.value_of("enr-fields") {
+        config.enr_custom_fields = enr_fields_str
+            .split(',')
+            .map(|field| match field.split_once('=') {
+                Some((key, value)) if !key.is_empty() => {
+                    Ok((key.to_string(), value.as_bytes().to_vec()))
+                }
+                _ => Err(format!("Invalid ENR field, expected KEY=VALUE: {}", field)),
+            })
+            .collect::<Result<_, _>>()?;
+        lighthouse_network::discovery::verify_custom_fields(&config.enr_custom_fields)?;
+    }
+
+    if let Some(max_request_blocks) =
+        clap_utils::parse_optional::<u64>(cli_args, "rpc-max-request-blocks")?
+    {
+        if !(MAX_REQUEST_BLOCKS..=MAX_REQUEST_BLOCKS_HARD_CAP).contains(&max_request_blocks) {
+            return Err(format!(
+                "--rpc-max-request-blocks must be between {} and {}",
+                MAX_REQUEST_BLOCKS, MAX_REQUEST_BLOCKS_HARD_CAP
+            ));
+        }
+        config.max_request_blocks =
+            Some(max_request_blocks).filter(|max| *max > MAX_REQUEST_BLOCKS);
+    }
+
     if cli_args.is_present("enr-match") {
         // set the enr address to localhost if the address is 0.0.0.0
         if config.listen_address == "0.0.0.0".parse::<IpAddr>().expect("valid ip addr") {
@@ -791,10 +1135,22 @@ pub fn set_network_config(
         config.upnp_enabled = false;
     }
 
+    if cli_args.is_present("disable-inbound") {
+        config.disable_inbound = true;
+        // There are no listening ports to map or advertise.
+        config.upnp_enabled = false;
+        config.discv5_config.enr_update = false;
+    }
+
     if cli_args.is_present("private") {
         config.private = true;
     }
 
+    if let Some(path) = cli_args.value_of("gossipsub-scoring-file") {
+        config.gossipsub_scoring_overrides =
+            Some(GossipsubScoringOverrides::from_file(Path::new(path))?);
+    }
+
     if cli_args.is_present("metrics") {
         config.metrics_enabled = true;
     }
@@ -5,7 +5,7 @@ mod cli;
 mod config;
 
 pub use beacon_chain;
-use beacon_chain::store::LevelDB;
+use beacon_chain::store::{FreezerDB, HotDB};
 use beacon_chain::{
     builder::Witness, eth1_chain::CachingEth1Backend, slot_clock::SystemTimeSlotClock,
     TimeoutRwLock,
@@ -24,7 +24,7 @@ use types::EthSpec;
 
 /// A type-alias to the tighten the definition of a production-intended `Client`.
 pub type ProductionClient<E> =
-    Client<Witness<SystemTimeSlotClock, CachingEth1Backend<E>, E, LevelDB<E>, LevelDB<E>>>;
+    Client<Witness<SystemTimeSlotClock, CachingEth1Backend<E>, E, HotDB<E>, FreezerDB<E>>>;
 
 /// The beacon node `Client` that will be used in production.
 ///
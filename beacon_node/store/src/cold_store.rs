@@ -0,0 +1,168 @@
+use super::*;
+use crate::config::{FreezerBackend, StoreConfig, StoreConfigError};
+use crate::sled_store::SledDB;
+use parking_lot::MutexGuard;
+use std::path::Path;
+
+/// A key-value store which may be opened on disk to hold the freezer database.
+pub trait ColdStore<E: EthSpec>: ItemStore<E> {
+    /// Open the freezer database at `path`, creating it if it does not already exist.
+    fn open_cold(path: &Path, config: &StoreConfig) -> Result<Self, Error>;
+}
+
+impl<E: EthSpec> ColdStore<E> for LevelDB<E> {
+    fn open_cold(path: &Path, _config: &StoreConfig) -> Result<Self, Error> {
+        LevelDB::open(path)
+    }
+}
+
+impl<E: EthSpec> ColdStore<E> for SledDB<E> {
+    fn open_cold(path: &Path, _config: &StoreConfig) -> Result<Self, Error> {
+        SledDB::open(path)
+    }
+}
+
+/// A freezer database whose backend is selected by `StoreConfig::freezer_backend` when it is
+/// opened.
+pub enum FreezerDB<E: EthSpec> {
+    LevelDb(LevelDB<E>),
+    Sled(SledDB<E>),
+}
+
+impl<E: EthSpec> FreezerDB<E> {
+    /// Returns the backend of this database.
+    pub fn backend(&self) -> FreezerBackend {
+        match self {
+            FreezerDB::LevelDb(_) => FreezerBackend::LevelDb,
+            FreezerDB::Sled(_) => FreezerBackend::Sled,
+        }
+    }
+}
+
+/// Returns the backend of the database already initialized at `path`, if any.
+///
+/// Each backend writes a file that the other does not: leveldb its `CURRENT` manifest pointer and
+/// sled its `conf` file.
+fn existing_backend(path: &Path) -> Option<FreezerBackend> {
+    if path.join("CURRENT").exists() {
+        Some(FreezerBackend::LevelDb)
+    } else if path.join("conf").exists() {
+        Some(FreezerBackend::Sled)
+    } else {
+        None
+    }
+}
+
+impl<E: EthSpec> ColdStore<E> for FreezerDB<E> {
+    fn open_cold(path: &Path, config: &StoreConfig) -> Result<Self, Error> {
+        match existing_backend(path) {
+            Some(on_disk) if on_disk != config.freezer_backend => {
+                return Err(Error::ConfigError(
+                    StoreConfigError::MismatchedFreezerBackend {
+                        config: config.freezer_backend,
+                        on_disk,
+                    },
+                ))
+            }
+            _ => (),
+        }
+
+        match config.freezer_backend {
+            FreezerBackend::LevelDb => LevelDB::open(path).map(FreezerDB::LevelDb),
+            FreezerBackend::Sled => SledDB::open(path).map(FreezerDB::Sled),
+        }
+    }
+}
+
+impl<E: EthSpec> KeyValueStore<E> for FreezerDB<E> {
+    fn get_bytes(&self, col: &str, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        match self {
+            FreezerDB::LevelDb(db) => db.get_bytes(col, key),
+            FreezerDB::Sled(db) => db.get_bytes(col, key),
+        }
+    }
+
+    fn put_bytes(&self, col: &str, key: &[u8], val: &[u8]) -> Result<(), Error> {
+        match self {
+            FreezerDB::LevelDb(db) => db.put_bytes(col, key, val),
+            FreezerDB::Sled(db) => db.put_bytes(col, key, val),
+        }
+    }
+
+    fn put_bytes_sync(&self, col: &str, key: &[u8], val: &[u8]) -> Result<(), Error> {
+        match self {
+            FreezerDB::LevelDb(db) => db.put_bytes_sync(col, key, val),
+            FreezerDB::Sled(db) => db.put_bytes_sync(col, key, val),
+        }
+    }
+
+    fn sync(&self) -> Result<(), Error> {
+        match self {
+            FreezerDB::LevelDb(db) => db.sync(),
+            FreezerDB::Sled(db) => db.sync(),
+        }
+    }
+
+    fn key_exists(&self, col: &str, key: &[u8]) -> Result<bool, Error> {
+        match self {
+            FreezerDB::LevelDb(db) => db.key_exists(col, key),
+            FreezerDB::Sled(db) => db.key_exists(col, key),
+        }
+    }
+
+    fn key_delete(&self, col: &str, key: &[u8]) -> Result<(), Error> {
+        match self {
+            FreezerDB::LevelDb(db) => db.key_delete(col, key),
+            FreezerDB::Sled(db) => db.key_delete(col, key),
+        }
+    }
+
+    fn do_atomically(&self, batch: Vec<KeyValueStoreOp>) -> Result<(), Error> {
+        match self {
+            FreezerDB::LevelDb(db) => db.do_atomically(batch),
+            FreezerDB::Sled(db) => db.do_atomically(batch),
+        }
+    }
+
+    fn begin_rw_transaction(&self) -> MutexGuard<()> {
+        match self {
+            FreezerDB::LevelDb(db) => db.begin_rw_transaction(),
+            FreezerDB::Sled(db) => db.begin_rw_transaction(),
+        }
+    }
+
+    fn compact(&self) -> Result<(), Error> {
+        match self {
+            FreezerDB::LevelDb(db) => db.compact(),
+            FreezerDB::Sled(db) => db.compact(),
+        }
+    }
+
+    fn compact_column_range(
+        &self,
+        column: DBColumn,
+        start: &[u8],
+        end: &[u8],
+    ) -> Result<(), Error> {
+        match self {
+            FreezerDB::LevelDb(db) => db.compact_column_range(column, start, end),
+            FreezerDB::Sled(db) => db.compact_column_range(column, start, end),
+        }
+    }
+
+    fn iter_column(&self, column: DBColumn) -> ColumnIter {
+        match self {
+            FreezerDB::LevelDb(db) => db.iter_column(column),
+            FreezerDB::Sled(db) => db.iter_column(column),
+        }
+    }
+
+    fn iter_column_keys(&self, column: DBColumn) -> ColumnKeyIter {
+        match self {
+            FreezerDB::LevelDb(db) => db.iter_column_keys(column),
+            FreezerDB::Sled(db) => db.iter_column_keys(column),
+        }
+    }
+}
+
+impl<E: EthSpec> ItemStore<E> for FreezerDB<E> {}
@@ -2,11 +2,17 @@ use crate::{DBColumn, Error, StoreItem};
 use serde_derive::{Deserialize, Serialize};
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
+use strum::{Display, EnumString, EnumVariantNames};
 use types::{EthSpec, MinimalEthSpec};
 
 pub const PREV_DEFAULT_SLOTS_PER_RESTORE_POINT: u64 = 2048;
 pub const DEFAULT_SLOTS_PER_RESTORE_POINT: u64 = 8192;
 pub const DEFAULT_BLOCK_CACHE_SIZE: usize = 5;
+pub const DEFAULT_EPOCH_BOUNDARY_STATE_CACHE_SIZE: usize = 4;
+pub const DEFAULT_RESTORE_POINTS_PER_SNAPSHOT: u64 = 32;
+pub const DEFAULT_HISTORY_RETENTION_EPOCHS: u64 = 256;
+pub const DEFAULT_COMPACTION_DUTY_CYCLE_PERCENT: u8 = 25;
+pub const DEFAULT_MAX_REPLAY_THREADS: usize = 4;
 
 /// Database configuration parameters.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -15,12 +21,75 @@ pub struct StoreConfig {
     pub slots_per_restore_point: u64,
     /// Flag indicating whether the `slots_per_restore_point` was set explicitly by the user.
     pub slots_per_restore_point_set_explicitly: bool,
+    /// Number of restore points between those stored in full in the freezer database. The restore
+    /// points in between are stored as diffs. A value of 1 disables diffs.
+    pub restore_points_per_snapshot: u64,
     /// Maximum number of blocks to store in the in-memory block cache.
     pub block_cache_size: usize,
+    /// Maximum number of epoch boundary states to store in the in-memory state cache.
+    pub epoch_boundary_state_cache_size: usize,
     /// Whether to compact the database on initialization.
     pub compact_on_init: bool,
     /// Whether to compact the database during database pruning.
     pub compact_on_prune: bool,
+    /// The maximum percentage of time that background compaction spends compacting, rather than
+    /// pausing to leave disk I/O for other tasks.
+    pub compaction_duty_cycle_percent: u8,
+    /// The key-value store used for the hot database.
+    pub hot_backend: HotBackend,
+    /// The key-value store used for the freezer database.
+    pub freezer_backend: FreezerBackend,
+    /// When set, states from more than this many epochs prior to the split are deleted from the
+    /// freezer database as finalization advances.
+    pub history_retention_epochs: Option<u64>,
+    /// Whether to delete blocks as well as states from outside the history retention window.
+    pub prune_history_blocks: bool,
+    /// Number of threads used to replay blocks between restore points in parallel, for queries
+    /// over ranges of historic states. Loading a single historic state only uses them to load
+    /// the restore points either side of it in parallel.
+    pub max_replay_threads: usize,
+    /// The number of epochs prior to the split for which blob sidecars are kept. Blobs are always
+    /// kept for at least `MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS`, which is used when unset.
+    pub blob_retention_epochs: Option<u64>,
+}
+
+/// The key-value stores that may hold the hot database.
+///
+/// The backend can't be changed once the hot database has been initialized. Redb is only
+/// available when Lighthouse is compiled with the `redb` feature.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumString, EnumVariantNames,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum HotBackend {
+    LevelDb,
+    Redb,
+}
+
+impl Default for HotBackend {
+    fn default() -> Self {
+        HotBackend::LevelDb
+    }
+}
+
+/// The key-value stores that may hold the freezer database.
+///
+/// The backend can't be changed once the freezer database has been initialized.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumString, EnumVariantNames,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum FreezerBackend {
+    LevelDb,
+    Sled,
+}
+
+impl Default for FreezerBackend {
+    fn default() -> Self {
+        FreezerBackend::LevelDb
+    }
 }
 
 /// Variant of `StoreConfig` that gets written to disk. Contains immutable configuration params.
@@ -31,7 +100,20 @@ pub struct OnDiskStoreConfig {
 
 #[derive(Debug, Clone)]
 pub enum StoreConfigError {
-    MismatchedSlotsPerRestorePoint { config: u64, on_disk: u64 },
+    MismatchedSlotsPerRestorePoint {
+        config: u64,
+        on_disk: u64,
+    },
+    MismatchedFreezerBackend {
+        config: FreezerBackend,
+        on_disk: FreezerBackend,
+    },
+    MismatchedHotBackend {
+        config: HotBackend,
+        on_disk: HotBackend,
+    },
+    /// The backend isn't available in this build of Lighthouse.
+    HotBackendNotCompiled(HotBackend),
 }
 
 impl Default for StoreConfig {
@@ -40,9 +122,18 @@ impl Default for StoreConfig {
             // Safe default for tests, shouldn't ever be read by a CLI node.
             slots_per_restore_point: MinimalEthSpec::slots_per_historical_root() as u64,
             slots_per_restore_point_set_explicitly: false,
+            restore_points_per_snapshot: DEFAULT_RESTORE_POINTS_PER_SNAPSHOT,
             block_cache_size: DEFAULT_BLOCK_CACHE_SIZE,
+            epoch_boundary_state_cache_size: DEFAULT_EPOCH_BOUNDARY_STATE_CACHE_SIZE,
             compact_on_init: false,
             compact_on_prune: true,
+            compaction_duty_cycle_percent: DEFAULT_COMPACTION_DUTY_CYCLE_PERCENT,
+            hot_backend: HotBackend::default(),
+            freezer_backend: FreezerBackend::default(),
+            history_retention_epochs: None,
+            prune_history_blocks: false,
+            max_replay_threads: DEFAULT_MAX_REPLAY_THREADS,
+            blob_retention_epochs: None,
         }
     }
 }
@@ -1,12 +1,14 @@
 //! Garbage collection process that runs at start-up to clean up the database.
 use crate::hot_cold_store::HotColdDB;
-use crate::{Error, LevelDB, StoreOp};
+use crate::{ColdStore, Error, HotStore, StoreOp};
 use slog::debug;
 use types::EthSpec;
 
-impl<E> HotColdDB<E, LevelDB<E>, LevelDB<E>>
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
 where
     E: EthSpec,
+    Hot: HotStore<E>,
+    Cold: ColdStore<E>,
 {
     /// Clean up the database by performing one-off maintenance at start-up.
     pub fn remove_garbage(&self) -> Result<(), Error> {
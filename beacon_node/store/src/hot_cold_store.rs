@@ -5,25 +5,28 @@ use crate::config::{
     OnDiskStoreConfig, StoreConfig, DEFAULT_SLOTS_PER_RESTORE_POINT,
     PREV_DEFAULT_SLOTS_PER_RESTORE_POINT,
 };
-use crate::forwards_iter::{HybridForwardsBlockRootsIterator, HybridForwardsStateRootsIterator};
+use crate::forwards_iter::{
+    FrozenForwardsIterator, HybridForwardsBlockRootsIterator, HybridForwardsStateRootsIterator,
+};
 use crate::impls::beacon_state::{get_full_state, store_full_state};
 use crate::iter::{ParentRootBlockIterator, StateRootsIterator};
 use crate::leveldb_store::BytesKey;
-use crate::leveldb_store::LevelDB;
 use crate::memory_store::MemoryStore;
 use crate::metadata::{
-    AnchorInfo, CompactionTimestamp, PruningCheckpoint, SchemaVersion, ANCHOR_INFO_KEY,
-    COMPACTION_TIMESTAMP_KEY, CONFIG_KEY, CURRENT_SCHEMA_VERSION, PRUNING_CHECKPOINT_KEY,
-    SCHEMA_VERSION_KEY, SPLIT_KEY,
+    AnchorInfo, BlobInfo, CompactionTimestamp, PayloadReconstructionProgress, PruningCheckpoint,
+    SchemaVersion, ANCHOR_INFO_KEY, BLOB_INFO_KEY, COMPACTION_TIMESTAMP_KEY, CONFIG_KEY,
+    CURRENT_SCHEMA_VERSION, PAYLOAD_RECONSTRUCTION_KEY, PRUNING_CHECKPOINT_KEY, SCHEMA_VERSION_KEY,
+    SPLIT_KEY,
 };
 use crate::metrics;
+use crate::state_diff::{diff_base_restore_point, StateDiff};
 use crate::{
-    get_key_for_col, DBColumn, DatabaseBlock, Error, ItemStore, KeyValueStoreOp,
-    PartialBeaconState, StoreItem, StoreOp,
+    get_key_for_col, ColdStore, DBColumn, DatabaseBlock, Error, HotStore, ItemStore,
+    KeyValueStoreOp, PartialBeaconState, StoreItem, StoreOp, COMPACTION_COLUMNS,
 };
-use leveldb::iterator::LevelDBIterator;
 use lru::LruCache;
 use parking_lot::{Mutex, RwLock};
+use rayon::prelude::*;
 use serde_derive::{Deserialize, Serialize};
 use slog::{debug, error, info, trace, warn, Logger};
 use ssz::{Decode, Encode};
@@ -31,14 +34,27 @@ use ssz_derive::{Decode, Encode};
 use state_processing::{
     BlockProcessingError, BlockReplayer, SlotProcessingError, StateRootStrategy,
 };
-use std::cmp::min;
+use std::cmp::{max, min};
 use std::convert::TryInto;
 use std::marker::PhantomData;
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::thread;
+use std::time::{Duration, Instant};
 use types::*;
 
+/// Number of items deleted per database transaction when pruning.
+const PRUNE_BATCH_SIZE: usize = 1024;
+
+/// Number of chunks each column is split into by incremental compaction.
+const COMPACTION_CHUNKS_PER_COLUMN: usize = 16;
+/// Interval at which incremental compaction checks whether block imports have finished.
+const COMPACTION_IMPORT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Maximum time incremental compaction waits for block imports to finish before each chunk, so
+/// that it still makes progress while syncing.
+const MAX_COMPACTION_IMPORT_WAIT: Duration = Duration::from_secs(12);
+
 /// On-disk database that stores finalized states efficiently.
 ///
 /// Stores vector fields like the `block_roots` and `state_roots` separately, and only stores
@@ -52,6 +68,8 @@ pub struct HotColdDB<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
     pub(crate) split: RwLock<Split>,
     /// The starting slots for the range of blocks & states stored in the database.
     anchor_info: RwLock<Option<AnchorInfo>>,
+    /// The range of blob sidecars stored in the database, and their size.
+    blob_info: RwLock<BlobInfo>,
     pub(crate) config: StoreConfig,
     /// Cold database containing compact historical data.
     pub cold_db: Cold,
@@ -61,6 +79,16 @@ pub struct HotColdDB<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
     pub hot_db: Hot,
     /// LRU cache of deserialized blocks. Updated whenever a block is loaded.
     block_cache: Mutex<LruCache<Hash256, SignedBeaconBlock<E>>>,
+    /// LRU cache of hot epoch boundary states, from which all other hot states are replayed.
+    /// Updated whenever an epoch boundary state is loaded from disk.
+    epoch_boundary_state_cache: Mutex<LruCache<Hash256, BeaconState<E>>>,
+    /// Statistics about the compactions run since the database was opened.
+    compaction_stats: RwLock<CompactionStats>,
+    /// The number of blocks currently being imported, which incremental compaction yields to.
+    block_imports_in_progress: AtomicUsize,
+    /// Thread pool of `max_replay_threads` threads, on which historic states are loaded and
+    /// replayed in parallel.
+    replay_pool: rayon::ThreadPool,
     /// Chain spec.
     pub(crate) spec: ChainSpec,
     /// Logger.
@@ -69,6 +97,29 @@ pub struct HotColdDB<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
     _phantom: PhantomData<E>,
 }
 
+/// Statistics about the compactions run since the database was opened.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CompactionStats {
+    /// The number of compactions which have completed.
+    pub compactions: u64,
+    /// The time taken by the most recent compaction.
+    pub last_compaction_duration: Option<Duration>,
+    /// The number of states deleted from the hot database since it was last compacted.
+    pub deleted_states_since_compaction: u64,
+}
+
+/// Marks a block import as in progress until dropped, so that incremental compaction yields to it.
+pub struct BlockImportGuard<'a> {
+    block_imports_in_progress: &'a AtomicUsize,
+}
+
+impl Drop for BlockImportGuard<'_> {
+    fn drop(&mut self) {
+        self.block_imports_in_progress
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum HotColdDBError {
     UnsupportedSchemaVersion {
@@ -112,6 +163,17 @@ pub enum HotColdDBError {
         request_slot: Option<Slot>,
         state_root: Hash256,
     },
+    /// Replaying blocks to reconstruct a frozen state took longer than the caller allowed.
+    StateReplayDeadlineExceeded {
+        slot: Slot,
+        replayed_to_slot: Slot,
+    },
+    MissingSyncCommittee(u64),
+    ReplayThreadPoolError(String),
+    /// A stored state diff doesn't apply to the state of its base restore point.
+    InvalidStateDiff {
+        base_restore_point: u64,
+    },
 }
 
 impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
@@ -125,9 +187,16 @@ impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
         let db = HotColdDB {
             split: RwLock::new(Split::default()),
             anchor_info: RwLock::new(None),
+            blob_info: RwLock::new(BlobInfo::default()),
             cold_db: MemoryStore::open(),
             hot_db: MemoryStore::open(),
             block_cache: Mutex::new(LruCache::new(config.block_cache_size)),
+            epoch_boundary_state_cache: Mutex::new(LruCache::new(
+                config.epoch_boundary_state_cache_size,
+            )),
+            compaction_stats: RwLock::new(CompactionStats::default()),
+            block_imports_in_progress: AtomicUsize::new(0),
+            replay_pool: build_replay_pool(&config)?,
             config,
             spec,
             log,
@@ -138,9 +207,12 @@ impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
     }
 }
 
-impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
+impl<E: EthSpec, Hot: HotStore<E>, Cold: ColdStore<E>> HotColdDB<E, Hot, Cold> {
     /// Open a new or existing database, with the given paths to the hot and cold DBs.
     ///
+    /// The hot and cold DBs are opened by `Hot` and `Cold`, which for a `HotDB` and `FreezerDB`
+    /// are selected by the `hot_backend` and `freezer_backend` of the `config`.
+    ///
     /// The `slots_per_restore_point` parameter must be a divisor of `SLOTS_PER_HISTORICAL_ROOT`.
     ///
     /// The `migrate_schema` function is passed in so that the parent `BeaconChain` can provide
@@ -158,9 +230,16 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
         let mut db = HotColdDB {
             split: RwLock::new(Split::default()),
             anchor_info: RwLock::new(None),
-            cold_db: LevelDB::open(cold_path)?,
-            hot_db: LevelDB::open(hot_path)?,
+            blob_info: RwLock::new(BlobInfo::default()),
+            cold_db: Cold::open_cold(cold_path, &config)?,
+            hot_db: Hot::open_hot(hot_path, &config)?,
             block_cache: Mutex::new(LruCache::new(config.block_cache_size)),
+            epoch_boundary_state_cache: Mutex::new(LruCache::new(
+                config.epoch_boundary_state_cache_size,
+            )),
+            compaction_stats: RwLock::new(CompactionStats::default()),
+            block_imports_in_progress: AtomicUsize::new(0),
+            replay_pool: build_replay_pool(&config)?,
             config,
             spec,
             log,
@@ -202,6 +281,10 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
             );
         }
 
+        if let Some(blob_info) = db.load_blob_info()? {
+            *db.blob_info.write() = blob_info;
+        }
+
         // Ensure that the schema version of the on-disk database matches the software.
         // If the version is mismatched, an automatic migration will be attempted.
         let db = Arc::new(db);
@@ -238,23 +321,7 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
 
     /// Return an iterator over the state roots of all temporary states.
     pub fn iter_temporary_state_roots(&self) -> impl Iterator<Item = Result<Hash256, Error>> + '_ {
-        let column = DBColumn::BeaconStateTemporary;
-        let start_key =
-            BytesKey::from_vec(get_key_for_col(column.into(), Hash256::zero().as_bytes()));
-
-        let keys_iter = self.hot_db.keys_iter();
-        keys_iter.seek(&start_key);
-
-        keys_iter
-            .take_while(move |key| key.matches_column(column))
-            .map(move |bytes_key| {
-                bytes_key.remove_column(column).ok_or_else(|| {
-                    HotColdDBError::IterationError {
-                        unexpected_key: bytes_key,
-                    }
-                    .into()
-                })
-            })
+        self.hot_db.iter_column_keys(DBColumn::BeaconStateTemporary)
     }
 }
 
@@ -334,12 +401,16 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         };
 
         // If the block is after the split point then we should have the full execution payload
-        // stored in the database. Otherwise, just return the blinded block.
+        // stored in the database, as we should if its payload has been reconstructed. Otherwise,
+        // just return the blinded block.
         // Hold the split lock so that it can't change.
         let split = self.split.read_recursive();
 
         let block = if blinded_block.message().execution_payload().is_err()
             || blinded_block.slot() >= split.slot
+            || self
+                .cold_db
+                .key_exists(DBColumn::ExecPayload.into(), block_root.as_bytes())?
         {
             // Re-constructing the full block should always succeed here.
             let full_block = self.make_full_block(block_root, blinded_block)?;
@@ -430,12 +501,51 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     }
 
     /// Load the execution payload for a block from disk.
+    ///
+    /// Payloads reconstructed after being pruned are read from the freezer database.
     pub fn get_execution_payload(
         &self,
         block_root: &Hash256,
     ) -> Result<ExecutionPayload<E>, Error> {
-        self.get_item(block_root)?
-            .ok_or_else(|| HotColdDBError::MissingExecutionPayload(*block_root).into())
+        match self.get_item(block_root)? {
+            Some(payload) => Ok(payload),
+            None => self
+                .cold_db
+                .get(block_root)?
+                .ok_or_else(|| HotColdDBError::MissingExecutionPayload(*block_root).into()),
+        }
+    }
+
+    /// Determine whether the execution payload for a block is stored in either database.
+    pub fn execution_payload_exists(&self, block_root: &Hash256) -> Result<bool, Error> {
+        Ok(self
+            .hot_db
+            .key_exists(DBColumn::ExecPayload.into(), block_root.as_bytes())?
+            || self
+                .cold_db
+                .key_exists(DBColumn::ExecPayload.into(), block_root.as_bytes())?)
+    }
+
+    /// Load the progress of execution payload reconstruction, if it has begun.
+    pub fn get_payload_reconstruction_progress(
+        &self,
+    ) -> Result<Option<PayloadReconstructionProgress>, Error> {
+        self.cold_db.get(&PAYLOAD_RECONSTRUCTION_KEY)
+    }
+
+    /// Store reconstructed execution payloads in the freezer database, atomically with the
+    /// `progress` made by reconstructing them.
+    pub fn store_reconstructed_execution_payloads(
+        &self,
+        payloads: &[(Hash256, ExecutionPayload<E>)],
+        progress: PayloadReconstructionProgress,
+    ) -> Result<(), Error> {
+        let mut ops = payloads
+            .iter()
+            .map(|(block_root, payload)| payload.as_kv_store_op(*block_root))
+            .collect::<Vec<_>>();
+        ops.push(progress.as_kv_store_op(PAYLOAD_RECONSTRUCTION_KEY));
+        self.cold_db.do_atomically(ops)
     }
 
     /// Determine whether a block exists in the database.
@@ -453,6 +563,15 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             .key_delete(DBColumn::ExecPayload.into(), block_root.as_bytes())
     }
 
+    /// Fetch the blob sidecars of a block from the store, if any are stored.
+    pub fn get_blobs(&self, block_root: &Hash256) -> Result<Option<BlobSidecarList<E>>, Error> {
+        self.hot_db
+            .get_bytes(DBColumn::BeaconBlob.into(), block_root.as_bytes())?
+            .map(|bytes| BlobSidecarList::<E>::from_ssz_bytes(&bytes))
+            .transpose()
+            .map_err(Into::into)
+    }
+
     pub fn put_state_summary(
         &self,
         state_root: &Hash256,
@@ -548,6 +667,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     /// (which are frozen, and won't be deleted), or valid descendents of the finalized checkpoint
     /// (which will be deleted by this function but shouldn't be).
     pub fn delete_state(&self, state_root: &Hash256, slot: Slot) -> Result<(), Error> {
+        self.epoch_boundary_state_cache.lock().pop(state_root);
+
         // Delete the state summary.
         self.hot_db
             .key_delete(DBColumn::BeaconStateSummary.into(), state_root.as_bytes())?;
@@ -660,6 +781,58 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         self.hot_db.exists::<I>(key)
     }
 
+    /// Store the light client bootstrap for the block with root `block_root`.
+    ///
+    /// Only the header and branch are stored per block, the sync committee is stored once for each
+    /// sync committee period.
+    pub fn put_light_client_bootstrap(
+        &self,
+        block_root: &Hash256,
+        bootstrap: &LightClientBootstrap<E>,
+    ) -> Result<(), Error> {
+        let period = self.sync_committee_period(bootstrap.header.slot)?;
+        let branch = LightClientBootstrapBranch {
+            header: bootstrap.header.clone(),
+            current_sync_committee_branch: bootstrap.current_sync_committee_branch.clone(),
+        };
+
+        self.hot_db.do_atomically(vec![
+            bootstrap
+                .current_sync_committee
+                .as_kv_store_op(Hash256::from_low_u64_be(period)),
+            branch.as_kv_store_op(*block_root),
+        ])
+    }
+
+    /// Load the light client bootstrap for the block with root `block_root`, if one was stored.
+    pub fn get_light_client_bootstrap(
+        &self,
+        block_root: &Hash256,
+    ) -> Result<Option<LightClientBootstrap<E>>, Error> {
+        let branch = match self.hot_db.get::<LightClientBootstrapBranch>(block_root)? {
+            Some(branch) => branch,
+            None => return Ok(None),
+        };
+
+        let period = self.sync_committee_period(branch.header.slot)?;
+        let current_sync_committee = self
+            .hot_db
+            .get::<SyncCommittee<E>>(&Hash256::from_low_u64_be(period))?
+            .ok_or(HotColdDBError::MissingSyncCommittee(period))?;
+
+        Ok(Some(LightClientBootstrap {
+            header: branch.header,
+            current_sync_committee: Arc::new(current_sync_committee),
+            current_sync_committee_branch: branch.current_sync_committee_branch,
+        }))
+    }
+
+    fn sync_committee_period(&self, slot: Slot) -> Result<u64, Error> {
+        slot.epoch(E::slots_per_epoch())
+            .sync_committee_period(&self.spec)
+            .map_err(|e| BeaconStateError::ArithError(e).into())
+    }
+
     /// Convert a batch of `StoreOp` to a batch of `KeyValueStoreOp`.
     pub fn convert_to_kv_batch(
         &self,
@@ -711,16 +884,38 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                     let key = get_key_for_col(DBColumn::ExecPayload.into(), block_root.as_bytes());
                     key_value_batch.push(KeyValueStoreOp::DeleteKey(key));
                 }
+
+                StoreOp::PutBlobs(block_root, blobs) => {
+                    let key = get_key_for_col(DBColumn::BeaconBlob.into(), block_root.as_bytes());
+                    key_value_batch.push(KeyValueStoreOp::PutKeyValue(key, blobs.as_ssz_bytes()));
+                }
+
+                StoreOp::DeleteBlobs(block_root) => {
+                    let key = get_key_for_col(DBColumn::BeaconBlob.into(), block_root.as_bytes());
+                    key_value_batch.push(KeyValueStoreOp::DeleteKey(key));
+                }
+
+                StoreOp::KeyValueOp(kv_op) => {
+                    key_value_batch.push(kv_op);
+                }
             }
         }
         Ok(key_value_batch)
     }
 
+    /// Execute a batch of `StoreOp`s atomically, updating the in-memory caches and the blob info.
+    ///
+    /// Blob sidecars must be stored and deleted using this method rather than by writing the
+    /// result of `convert_to_kv_batch`, so that the blob info accounts for them.
     pub fn do_atomically(&self, batch: Vec<StoreOp<E>>) -> Result<(), Error> {
         // Update the block cache whilst holding a lock, to ensure that the cache updates atomically
         // with the database.
         let mut guard = self.block_cache.lock();
+        let mut state_cache_guard = self.epoch_boundary_state_cache.lock();
+        let mut blob_info_guard = self.blob_info.write();
+        let mut new_blob_info = *blob_info_guard;
 
+        let mut deleted_states = 0;
         for op in &batch {
             match op {
                 StoreOp::PutBlock(block_root, block) => {
@@ -739,16 +934,70 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                     guard.pop(block_root);
                 }
 
-                StoreOp::DeleteState(_, _) => (),
+                StoreOp::DeleteState(state_root, slot) => {
+                    // Only epoch boundary states are stored in full, see `convert_to_kv_batch`.
+                    if slot.map_or(true, |slot| slot % E::slots_per_epoch() == 0) {
+                        state_cache_guard.pop(state_root);
+                        deleted_states += 1;
+                    }
+                }
 
                 StoreOp::DeleteExecutionPayload(_) => (),
+
+                StoreOp::PutBlobs(block_root, blobs) => {
+                    // Blobs may be overwritten, e.g. if a block is imported again.
+                    self.remove_stored_blobs_from_info(&mut new_blob_info, block_root)?;
+                    new_blob_info.blocks_with_blobs += 1;
+                    new_blob_info.blob_sidecars += blobs.len() as u64;
+                    new_blob_info.blob_bytes += blobs.ssz_bytes_len() as u64;
+                }
+
+                StoreOp::DeleteBlobs(block_root) => {
+                    self.remove_stored_blobs_from_info(&mut new_blob_info, block_root)?;
+                }
+
+                StoreOp::KeyValueOp(_) => (),
             }
         }
 
-        self.hot_db
-            .do_atomically(self.convert_to_kv_batch(batch)?)?;
+        let mut key_value_batch = self.convert_to_kv_batch(batch)?;
+        if new_blob_info != *blob_info_guard {
+            key_value_batch.push(new_blob_info.as_kv_store_op(BLOB_INFO_KEY));
+        }
+        self.hot_db.do_atomically(key_value_batch)?;
+        *blob_info_guard = new_blob_info;
+        drop(blob_info_guard);
+        drop(state_cache_guard);
         drop(guard);
 
+        if deleted_states > 0 {
+            let mut stats = self.compaction_stats.write();
+            stats.deleted_states_since_compaction += deleted_states;
+            metrics::set_gauge(
+                &metrics::COMPACTION_DEBT,
+                stats.deleted_states_since_compaction as i64,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Subtract the blob sidecars stored for `block_root`, if any, from `blob_info`.
+    fn remove_stored_blobs_from_info(
+        &self,
+        blob_info: &mut BlobInfo,
+        block_root: &Hash256,
+    ) -> Result<(), Error> {
+        if let Some(bytes) = self
+            .hot_db
+            .get_bytes(DBColumn::BeaconBlob.into(), block_root.as_bytes())?
+        {
+            // Sidecars have a fixed size, so the list is encoded as their concatenation.
+            let sidecars = bytes.len() / BlobSidecar::<E>::max_size();
+            blob_info.blocks_with_blobs = blob_info.blocks_with_blobs.saturating_sub(1);
+            blob_info.blob_sidecars = blob_info.blob_sidecars.saturating_sub(sidecars as u64);
+            blob_info.blob_bytes = blob_info.blob_bytes.saturating_sub(bytes.len() as u64);
+        }
         Ok(())
     }
 
@@ -805,10 +1054,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             epoch_boundary_state_root,
         }) = self.load_hot_state_summary(state_root)?
         {
-            let boundary_state =
-                get_full_state(&self.hot_db, &epoch_boundary_state_root, &self.spec)?.ok_or(
-                    HotColdDBError::MissingEpochBoundaryState(epoch_boundary_state_root),
-                )?;
+            let boundary_state = self.load_epoch_boundary_state(&epoch_boundary_state_root)?;
 
             // Optimization to avoid even *thinking* about replaying blocks if we're already
             // on an epoch boundary.
@@ -823,6 +1069,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                     slot,
                     no_state_root_iter(),
                     state_root_strategy,
+                    None,
                 )?
             };
 
@@ -832,6 +1079,25 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         }
     }
 
+    /// Load a hot epoch boundary state, from the epoch boundary state cache if possible.
+    ///
+    /// Loading these states from disk accounts for most of the cost of loading any hot state, so
+    /// recently used ones are kept in memory for HTTP API queries and attestation verification.
+    fn load_epoch_boundary_state(&self, state_root: &Hash256) -> Result<BeaconState<E>, Error> {
+        if let Some(state) = self.epoch_boundary_state_cache.lock().get(state_root) {
+            metrics::inc_counter(&metrics::BEACON_EPOCH_BOUNDARY_STATE_CACHE_HIT_COUNT);
+            return Ok(state.clone_with_only_committee_caches());
+        }
+
+        let state = get_full_state(&self.hot_db, state_root, &self.spec)?
+            .ok_or(HotColdDBError::MissingEpochBoundaryState(*state_root))?;
+        self.epoch_boundary_state_cache
+            .lock()
+            .put(*state_root, state.clone_with_only_committee_caches());
+
+        Ok(state)
+    }
+
     /// Store a pre-finalization state in the freezer database.
     ///
     /// If the state doesn't lie on a restore point boundary then just its summary will be stored.
@@ -854,9 +1120,16 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             "state_root" => format!("{:?}", state_root)
         );
 
-        // 1. Convert to PartialBeaconState and store that in the DB.
+        // 1. Convert to PartialBeaconState and store that in the DB, either in full or as a diff
+        // against an earlier restore point.
+        let restore_point_index = state.slot().as_u64() / self.config.slots_per_restore_point;
         let partial_state = PartialBeaconState::from_state_forgetful(state);
-        let op = partial_state.as_kv_store_op(*state_root);
+        let op = match self.load_diff_base(restore_point_index)? {
+            Some((base_index, base)) => {
+                StateDiff::compute(base_index, &base, &partial_state).as_kv_store_op(*state_root)
+            }
+            None => partial_state.as_kv_store_op(*state_root),
+        };
         ops.push(op);
 
         // 2. Store updated vector entries.
@@ -867,7 +1140,6 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         store_updated_vector(RandaoMixes, db, state, &self.spec, ops)?;
 
         // 3. Store restore point.
-        let restore_point_index = state.slot().as_u64() / self.config.slots_per_restore_point;
         self.store_restore_point_hash(restore_point_index, *state_root, ops);
 
         Ok(())
@@ -887,6 +1159,19 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     ///
     /// Will reconstruct the state if it lies between restore points.
     pub fn load_cold_state_by_slot(&self, slot: Slot) -> Result<Option<BeaconState<E>>, Error> {
+        self.load_cold_state_by_slot_with_deadline(slot, None)
+    }
+
+    /// Load a pre-finalization state from the freezer database, giving up with
+    /// `HotColdDBError::StateReplayDeadlineExceeded` if block replay is still running at
+    /// `deadline`.
+    ///
+    /// States on restore points are loaded directly and are never subject to the deadline.
+    pub fn load_cold_state_by_slot_with_deadline(
+        &self,
+        slot: Slot,
+        deadline: Option<Instant>,
+    ) -> Result<Option<BeaconState<E>>, Error> {
         // Guard against fetching states that do not exist due to gaps in the historic state
         // database, which can occur due to checkpoint sync or re-indexing.
         // See the comments in `get_historic_state_limits` for more information.
@@ -897,7 +1182,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                 let restore_point_idx = slot.as_u64() / self.config.slots_per_restore_point;
                 self.load_restore_point_by_index(restore_point_idx)
             } else {
-                self.load_cold_intermediate_state(slot)
+                self.load_cold_intermediate_state(slot, deadline)
             }
             .map(Some)
         } else {
@@ -905,14 +1190,62 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         }
     }
 
-    /// Load a restore point state by its `state_root`.
-    fn load_restore_point(&self, state_root: &Hash256) -> Result<BeaconState<E>, Error> {
-        let partial_state_bytes = self
+    /// Load the partial state of a restore point by its `state_root`, applying diffs if it isn't
+    /// stored in full.
+    fn load_partial_restore_point(
+        &self,
+        state_root: &Hash256,
+    ) -> Result<PartialBeaconState<E>, Error> {
+        if let Some(partial_state_bytes) = self
             .cold_db
             .get_bytes(DBColumn::BeaconState.into(), state_root.as_bytes())?
+        {
+            return Ok(PartialBeaconState::from_ssz_bytes(
+                &partial_state_bytes,
+                &self.spec,
+            )?);
+        }
+
+        let diff: StateDiff = self
+            .cold_db
+            .get(state_root)?
             .ok_or(HotColdDBError::MissingRestorePoint(*state_root))?;
-        let mut partial_state: PartialBeaconState<E> =
-            PartialBeaconState::from_ssz_bytes(&partial_state_bytes, &self.spec)?;
+        let base_state_root = self.load_restore_point_hash(diff.base_restore_point)?;
+        let base = self.load_partial_restore_point(&base_state_root)?;
+        diff.apply(base, &self.spec)
+    }
+
+    /// Load the partial state of the restore point that the restore point at
+    /// `restore_point_index` should be stored as a diff against.
+    ///
+    /// Returns `None` if the restore point should be stored in full, either because it's a
+    /// snapshot or because its base is unavailable (e.g. due to checkpoint sync or pruning).
+    fn load_diff_base(
+        &self,
+        restore_point_index: u64,
+    ) -> Result<Option<(u64, PartialBeaconState<E>)>, Error> {
+        let base_index = match diff_base_restore_point(
+            restore_point_index,
+            self.config.restore_points_per_snapshot,
+        ) {
+            Some(base_index) => base_index,
+            None => return Ok(None),
+        };
+        match self
+            .cold_db
+            .get::<RestorePointHash>(&Self::restore_point_key(base_index))?
+        {
+            Some(RestorePointHash { state_root }) => Ok(Some((
+                base_index,
+                self.load_partial_restore_point(&state_root)?,
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    /// Load a restore point state by its `state_root`.
+    fn load_restore_point(&self, state_root: &Hash256) -> Result<BeaconState<E>, Error> {
+        let mut partial_state = self.load_partial_restore_point(state_root)?;
 
         // Fill in the fields of the partial state.
         partial_state.load_block_roots(&self.cold_db, &self.spec)?;
@@ -933,16 +1266,41 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     }
 
     /// Load a frozen state that lies between restore points.
-    fn load_cold_intermediate_state(&self, slot: Slot) -> Result<BeaconState<E>, Error> {
+    ///
+    /// The two restore points either side of the state are loaded in parallel on the replay pool.
+    /// This is the only parallelism in loading a single state: the blocks between them are
+    /// replayed on the calling thread. See `replay_blocks_parallel` for queries over ranges.
+    fn load_cold_intermediate_state(
+        &self,
+        slot: Slot,
+        deadline: Option<Instant>,
+    ) -> Result<BeaconState<E>, Error> {
         // 1. Load the restore points either side of the intermediate state.
         let low_restore_point_idx = slot.as_u64() / self.config.slots_per_restore_point;
         let high_restore_point_idx = low_restore_point_idx + 1;
 
+        // Both restore points may need diffs applied, so load them in parallel. This happens
+        // before the split lock is acquired, so that the calling thread never blocks on the pool
+        // while holding the lock.
+        let load_split = self.get_split_info();
+        let (low_restore_point, high_restore_point) = self.replay_pool.join(
+            || self.load_restore_point_by_index(low_restore_point_idx),
+            || self.get_restore_point(high_restore_point_idx, &load_split),
+        );
+        let low_restore_point = low_restore_point?;
+
         // Acquire the read lock, so that the split can't change while this is happening.
         let split = self.split.read_recursive();
 
-        let low_restore_point = self.load_restore_point_by_index(low_restore_point_idx)?;
-        let high_restore_point = self.get_restore_point(high_restore_point_idx, &split)?;
+        // If the split state was migrated to the freezer while it was loading, load the high
+        // restore point again against the new split.
+        let high_restore_point = match high_restore_point {
+            Ok(state) => state,
+            Err(_) if *split != load_split => {
+                self.get_restore_point(high_restore_point_idx, &split)?
+            }
+            Err(e) => return Err(e),
+        };
 
         // 2. Load the blocks from the high restore point back to the low restore point.
         let blocks = self.load_blocks_to_replay(
@@ -967,6 +1325,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             slot,
             Some(state_root_iter),
             StateRootStrategy::Accurate,
+            deadline,
         )
     }
 
@@ -1047,6 +1406,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     ///
     /// Will skip slots as necessary. The returned state is not guaranteed
     /// to have any caches built, beyond those immediately required by block processing.
+    ///
+    /// If a `deadline` is provided, replay is abandoned at the first slot processed after it.
     fn replay_blocks(
         &self,
         state: BeaconState<E>,
@@ -1054,12 +1415,27 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         target_slot: Slot,
         state_root_iter: Option<impl Iterator<Item = Result<(Hash256, Slot), Error>>>,
         state_root_strategy: StateRootStrategy,
+        deadline: Option<Instant>,
     ) -> Result<BeaconState<E>, Error> {
         let mut block_replayer = BlockReplayer::new(state, &self.spec)
             .state_root_strategy(state_root_strategy)
             .no_signature_verification()
             .minimal_block_root_verification();
 
+        if let Some(deadline) = deadline {
+            block_replayer = block_replayer.pre_slot_hook(Box::new(move |state| {
+                if Instant::now() >= deadline {
+                    Err(HotColdDBError::StateReplayDeadlineExceeded {
+                        slot: target_slot,
+                        replayed_to_slot: state.slot(),
+                    }
+                    .into())
+                } else {
+                    Ok(())
+                }
+            }));
+        }
+
         let have_state_root_iterator = state_root_iter.is_some();
         if let Some(state_root_iter) = state_root_iter {
             block_replayer = block_replayer.state_root_iter(state_root_iter);
@@ -1080,11 +1456,95 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             })
     }
 
+    /// Replay `blocks` on top of `state` up to `end_slot`, in parallel where possible.
+    ///
+    /// The range is split into chunks at each available restore point after `state`, and each
+    /// chunk is passed to `replay` along with the state at its start (the restore point), the
+    /// blocks it contains and the slot at which it ends. Chunks are replayed on the store's pool of
+    /// `max_replay_threads` threads, and the results are returned in slot order.
+    ///
+    /// Restore points are only used once they lie before the split, so a range which extends
+    /// into the hot database finishes with a single chunk.
+    pub fn replay_blocks_parallel<T, Err, F>(
+        &self,
+        state: BeaconState<E>,
+        mut blocks: Vec<SignedBeaconBlock<E, BlindedPayload<E>>>,
+        end_slot: Slot,
+        replay: F,
+    ) -> Result<Vec<T>, Err>
+    where
+        T: Send,
+        Err: From<Error> + Send,
+        F: Fn(BeaconState<E>, Vec<SignedBeaconBlock<E, BlindedPayload<E>>>, Slot) -> Result<T, Err>
+            + Sync,
+    {
+        let slots_per_restore_point = self.config.slots_per_restore_point;
+        let latest_restore_point_slot = self.get_latest_restore_point_slot();
+        let (lower_limit, upper_limit) = self.get_historic_state_limits();
+
+        let boundaries = (state.slot().as_u64() / slots_per_restore_point + 1..)
+            .map(|index| Slot::new(index * slots_per_restore_point))
+            .take_while(|slot| *slot < end_slot && *slot <= latest_restore_point_slot)
+            .filter(|slot| *slot <= lower_limit || *slot >= upper_limit)
+            .collect::<Vec<_>>();
+
+        // Divide the blocks between the chunks, starting from the last so that each chunk takes
+        // the blocks which lie after its restore point. The remaining blocks belong to the first
+        // chunk, which starts from `state`.
+        let mut chunks = Vec::with_capacity(boundaries.len());
+        let mut chunk_end_slot = end_slot;
+        for boundary in boundaries.into_iter().rev() {
+            let first_index = blocks.partition_point(|block| block.slot() <= boundary);
+            chunks.push((boundary, blocks.split_off(first_index), chunk_end_slot));
+            chunk_end_slot = boundary;
+        }
+        chunks.reverse();
+
+        let replay_chunk = |(start_slot, blocks, chunk_end_slot): (Slot, Vec<_>, Slot)| {
+            let chunk_state =
+                self.load_restore_point_by_index(start_slot.as_u64() / slots_per_restore_point)?;
+            replay(chunk_state, blocks, chunk_end_slot)
+        };
+
+        let num_threads = min(self.config.max_replay_threads, chunks.len() + 1);
+        if num_threads <= 1 {
+            return std::iter::once(replay(state, blocks, chunk_end_slot))
+                .chain(chunks.into_iter().map(replay_chunk))
+                .collect();
+        }
+
+        self.replay_pool.install(|| {
+            let (first_result, chunk_results) = rayon::join(
+                || replay(state, blocks, chunk_end_slot),
+                || {
+                    chunks
+                        .into_par_iter()
+                        .map(replay_chunk)
+                        .collect::<Result<Vec<_>, _>>()
+                },
+            );
+            let mut results = vec![first_result?];
+            results.extend(chunk_results?);
+            Ok(results)
+        })
+    }
+
     /// Get a reference to the `ChainSpec` used by the database.
     pub fn get_chain_spec(&self) -> &ChainSpec {
         &self.spec
     }
 
+    /// Empty the in-memory caches of blocks and epoch boundary states to reduce memory usage,
+    /// returning the number of entries removed.
+    pub fn clear_caches(&self) -> usize {
+        let mut block_cache = self.block_cache.lock();
+        let mut state_cache = self.epoch_boundary_state_cache.lock();
+        let num_removed = block_cache.len() + state_cache.len();
+        block_cache.clear();
+        state_cache.clear();
+        num_removed
+    }
+
     /// Fetch a copy of the current split slot from memory.
     pub fn get_split_slot(&self) -> Slot {
         self.split.read_recursive().slot
@@ -1106,7 +1566,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     }
 
     /// Load the database schema version from disk.
-    fn load_schema_version(&self) -> Result<Option<SchemaVersion>, Error> {
+    pub(crate) fn load_schema_version(&self) -> Result<Option<SchemaVersion>, Error> {
         self.hot_db.get(&SCHEMA_VERSION_KEY)
     }
 
@@ -1211,6 +1671,16 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         }
     }
 
+    /// Get a copy of the store's blob info.
+    pub fn get_blob_info(&self) -> BlobInfo {
+        *self.blob_info.read_recursive()
+    }
+
+    /// Load the blob info from disk, but do not set `self.blob_info`.
+    fn load_blob_info(&self) -> Result<Option<BlobInfo>, Error> {
+        self.hot_db.get(&BLOB_INFO_KEY)
+    }
+
     /// If an anchor exists, return its `anchor_slot` field.
     pub fn get_anchor_slot(&self) -> Option<Slot> {
         self.anchor_info
@@ -1277,7 +1747,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     }
 
     /// Load the split point from disk.
-    fn load_split(&self) -> Result<Option<Split>, Error> {
+    pub(crate) fn load_split(&self) -> Result<Option<Split>, Error> {
         self.hot_db.get(&SPLIT_KEY)
     }
 
@@ -1287,7 +1757,10 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     }
 
     /// Load the state root of a restore point.
-    fn load_restore_point_hash(&self, restore_point_index: u64) -> Result<Hash256, Error> {
+    pub(crate) fn load_restore_point_hash(
+        &self,
+        restore_point_index: u64,
+    ) -> Result<Hash256, Error> {
         let key = Self::restore_point_key(restore_point_index);
         self.cold_db
             .get(&key)?
@@ -1320,6 +1793,15 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             .map(|s: ColdStateSummary| s.slot))
     }
 
+    /// Load the slot of a hot or frozen state, given its root.
+    pub fn load_state_slot(&self, state_root: &Hash256) -> Result<Option<Slot>, Error> {
+        if let Some(summary) = self.load_hot_state_summary(state_root)? {
+            Ok(Some(summary.slot))
+        } else {
+            self.load_cold_state_slot(state_root)
+        }
+    }
+
     /// Load a hot state's summary, given its root.
     pub fn load_hot_state_summary(
         &self,
@@ -1371,10 +1853,654 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
 
     /// Run a compaction pass to free up space used by deleted states.
     pub fn compact(&self) -> Result<(), Error> {
+        let start = Instant::now();
+        let deleted_states = self.compaction_stats.read().deleted_states_since_compaction;
         self.hot_db.compact()?;
+
+        self.record_compaction(start.elapsed(), deleted_states);
+        Ok(())
+    }
+
+    /// Run a compaction pass to free up space used by deleted states, without stalling other
+    /// users of the database.
+    ///
+    /// The key space of each compacted column is compacted in chunks. Before each chunk,
+    /// compaction waits for any block imports to finish, and after each chunk it pauses so that
+    /// it spends at most `compaction_duty_cycle_percent` of its time compacting. This makes it
+    /// much slower than `compact`, so it should only be run in the background.
+    pub fn compact_incrementally(&self) -> Result<(), Error> {
+        let _timer = metrics::start_timer(&metrics::COMPACTION_TIMES);
+        let start = Instant::now();
+        let deleted_states = self.compaction_stats.read().deleted_states_since_compaction;
+        let duty_cycle_percent = u32::from(self.config.compaction_duty_cycle_percent.clamp(1, 100));
+        let chunk_width = 256 / COMPACTION_CHUNKS_PER_COLUMN;
+
+        for column in COMPACTION_COLUMNS {
+            for chunk in 0..COMPACTION_CHUNKS_PER_COLUMN {
+                let mut start_key = Hash256::zero();
+                start_key.as_bytes_mut()[0] = (chunk * chunk_width) as u8;
+                let mut end_key = Hash256::repeat_byte(0xff);
+                end_key.as_bytes_mut()[0] = ((chunk + 1) * chunk_width - 1) as u8;
+
+                self.wait_for_block_imports();
+
+                let chunk_start = Instant::now();
+                self.hot_db.compact_column_range(
+                    column,
+                    start_key.as_bytes(),
+                    end_key.as_bytes(),
+                )?;
+                let chunk_duration = chunk_start.elapsed();
+                metrics::observe_duration(&metrics::COMPACTION_CHUNK_TIMES, chunk_duration);
+
+                thread::sleep(chunk_duration * (100 - duty_cycle_percent) / duty_cycle_percent);
+            }
+        }
+
+        self.record_compaction(start.elapsed(), deleted_states);
+        Ok(())
+    }
+
+    /// Wait for the blocks currently being imported, for at most `MAX_COMPACTION_IMPORT_WAIT`.
+    fn wait_for_block_imports(&self) {
+        let start = Instant::now();
+        while self.block_imports_in_progress.load(Ordering::Relaxed) > 0
+            && start.elapsed() < MAX_COMPACTION_IMPORT_WAIT
+        {
+            thread::sleep(COMPACTION_IMPORT_POLL_INTERVAL);
+        }
+        metrics::observe_duration(&metrics::COMPACTION_IMPORT_WAIT_TIMES, start.elapsed());
+    }
+
+    /// Update the compaction statistics after a compaction which reclaimed the space of
+    /// `deleted_states` states.
+    fn record_compaction(&self, duration: Duration, deleted_states: u64) {
+        let mut stats = self.compaction_stats.write();
+        stats.compactions += 1;
+        stats.last_compaction_duration = Some(duration);
+        // States deleted during the compaction may not have been compacted.
+        stats.deleted_states_since_compaction = stats
+            .deleted_states_since_compaction
+            .saturating_sub(deleted_states);
+        metrics::set_gauge(
+            &metrics::COMPACTION_DEBT,
+            stats.deleted_states_since_compaction as i64,
+        );
+    }
+
+    /// Mark a block import as in progress until the returned guard is dropped.
+    ///
+    /// Incremental compaction waits for block imports, so that they aren't stalled by it.
+    pub fn begin_block_import(&self) -> BlockImportGuard {
+        self.block_imports_in_progress
+            .fetch_add(1, Ordering::Relaxed);
+        BlockImportGuard {
+            block_imports_in_progress: &self.block_imports_in_progress,
+        }
+    }
+
+    /// Delete the execution payloads of finalized blocks.
+    ///
+    /// Full blocks prior to the split are already served blinded by `try_get_full_block`, so their
+    /// payloads are only needed by callers able to fetch them from the execution layer.
+    ///
+    /// `progress` is called with the number of payloads deleted so far and the total number to be
+    /// deleted. Returns the number of payloads deleted.
+    pub fn prune_execution_payloads(
+        &self,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<usize, Error> {
+        let split_slot = self.get_split_slot();
+
+        let mut block_roots = vec![];
+        for block_root in self.hot_db.iter_column_keys(DBColumn::ExecPayload) {
+            let block_root = block_root?;
+            let is_finalized = self
+                .get_blinded_block(&block_root)?
+                .map_or(false, |block| block.slot() < split_slot);
+            if is_finalized {
+                block_roots.push(block_root);
+            }
+        }
+
+        let total = block_roots.len();
+        progress(0, total);
+        for (i, chunk) in block_roots.chunks(PRUNE_BATCH_SIZE).enumerate() {
+            let ops = chunk
+                .iter()
+                .map(|block_root| StoreOp::DeleteExecutionPayload(*block_root))
+                .collect();
+            self.do_atomically(ops)?;
+            progress(i * PRUNE_BATCH_SIZE + chunk.len(), total);
+        }
+
+        info!(
+            self.log,
+            "Pruned finalized execution payloads";
+            "count" => total,
+        );
+        Ok(total)
+    }
+
+    /// Delete the states stored in the freezer database at restore points prior to the split, other
+    /// than the genesis state.
+    ///
+    /// Afterwards, states prior to the split are unavailable just as they are for a node which has
+    /// been checkpoint synced, and they can be restored by reconstructing historic states.
+    ///
+    /// `progress` is called with the number of states deleted so far and the total number to be
+    /// deleted. Returns the number of states deleted.
+    pub fn prune_historic_states(
+        &self,
+        progress: impl FnMut(usize, usize),
+    ) -> Result<usize, Error> {
+        let slots_per_restore_point = self.config.slots_per_restore_point;
+        let split_slot = self.get_split_slot();
+
+        // Retain the restore points from the split onwards, so that states remain available for all
+        // subsequent slots.
+        let new_upper_limit = Slot::new(
+            (split_slot.as_u64() + slots_per_restore_point - 1) / slots_per_restore_point
+                * slots_per_restore_point,
+        );
+
+        self.prune_historic_states_to(new_upper_limit, progress)
+    }
+
+    /// Delete the states stored in the freezer database at restore points prior to
+    /// `new_upper_limit`, other than the genesis state.
+    ///
+    /// `new_upper_limit` must lie on a restore point. Restore points from `new_upper_limit` onwards
+    /// which are stored as diffs against deleted restore points are rewritten in full first.
+    fn prune_historic_states_to(
+        &self,
+        new_upper_limit: Slot,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<usize, Error> {
+        let slots_per_restore_point = self.config.slots_per_restore_point;
+        let (lower_limit, upper_limit) = self.get_historic_state_limits();
+        let new_upper_index = new_upper_limit.as_u64() / slots_per_restore_point;
+
+        // Retained restore points mustn't depend on the deleted ones.
+        let end_index = (self.get_split_slot().as_u64() + slots_per_restore_point - 1)
+            / slots_per_restore_point;
+        for restore_point_index in new_upper_index..end_index {
+            self.store_restore_point_in_full_if_based_before(restore_point_index, new_upper_index)?;
+        }
+
+        // Restore points which lie between the existing limits are already absent.
+        let restore_point_indices = (1..new_upper_index)
+            .filter(|index| {
+                let slot = Slot::new(index * slots_per_restore_point);
+                slot <= lower_limit || slot >= upper_limit
+            })
+            .collect::<Vec<_>>();
+
+        // Update the anchor before deleting anything so that the states aren't read meanwhile.
+        let old_anchor = self.get_anchor_info();
+        let new_anchor = AnchorInfo {
+            state_upper_limit: new_upper_limit,
+            state_lower_limit: self.spec.genesis_slot,
+            ..old_anchor.clone().unwrap_or(AnchorInfo {
+                anchor_slot: self.spec.genesis_slot,
+                oldest_block_slot: self.spec.genesis_slot,
+                oldest_block_parent: Hash256::zero(),
+                state_upper_limit: new_upper_limit,
+                state_lower_limit: self.spec.genesis_slot,
+            })
+        };
+        self.compare_and_set_anchor_info_with_write(old_anchor, Some(new_anchor))?;
+
+        let total = restore_point_indices.len();
+        progress(0, total);
+        for (i, chunk) in restore_point_indices.chunks(PRUNE_BATCH_SIZE).enumerate() {
+            let mut ops = Vec::with_capacity(3 * chunk.len());
+            for index in chunk {
+                let key = Self::restore_point_key(*index);
+                if let Some(RestorePointHash { state_root }) = self.cold_db.get(&key)? {
+                    ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                        DBColumn::BeaconState.into(),
+                        state_root.as_bytes(),
+                    )));
+                    ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                        DBColumn::BeaconStateDiff.into(),
+                        state_root.as_bytes(),
+                    )));
+                }
+                ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                    DBColumn::BeaconRestorePoint.into(),
+                    key.as_bytes(),
+                )));
+            }
+            self.cold_db.do_atomically(ops)?;
+            progress(i * PRUNE_BATCH_SIZE + chunk.len(), total);
+        }
+
+        info!(
+            self.log,
+            "Pruned historic states";
+            "count" => total,
+            "state_upper_limit" => new_upper_limit,
+        );
+        Ok(total)
+    }
+
+    /// Rewrite the restore point at `restore_point_index` in full if it's stored as a diff against
+    /// a restore point prior to `min_base_index`.
+    fn store_restore_point_in_full_if_based_before(
+        &self,
+        restore_point_index: u64,
+        min_base_index: u64,
+    ) -> Result<(), Error> {
+        let key = Self::restore_point_key(restore_point_index);
+        let state_root = match self.cold_db.get::<RestorePointHash>(&key)? {
+            Some(RestorePointHash { state_root }) => state_root,
+            None => return Ok(()),
+        };
+        match self.cold_db.get::<StateDiff>(&state_root)? {
+            Some(diff) if diff.base_restore_point < min_base_index => {
+                self.store_diff_restore_point_in_full(&state_root)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Replace the diff stored for the restore point with `state_root` by its full partial state.
+    fn store_diff_restore_point_in_full(&self, state_root: &Hash256) -> Result<(), Error> {
+        let partial_state = self.load_partial_restore_point(state_root)?;
+        self.cold_db.do_atomically(vec![
+            partial_state.as_kv_store_op(*state_root),
+            KeyValueStoreOp::DeleteKey(get_key_for_col(
+                DBColumn::BeaconStateDiff.into(),
+                state_root.as_bytes(),
+            )),
+        ])
+    }
+
+    /// Delete the states, and blocks if `prune_history_blocks` is set, which lie more than
+    /// `history_retention_epochs` before the split. Blocks are kept for at least
+    /// `MIN_EPOCHS_FOR_BLOCK_REQUESTS` regardless.
+    ///
+    /// Does nothing unless `history_retention_epochs` is set. Called after each migration to the
+    /// freezer database, so only the restore points and blocks which have left the retention
+    /// window since the last call are deleted.
+    pub fn prune_history(&self) -> Result<(), Error> {
+        let retention_epochs = match self.config.history_retention_epochs {
+            Some(retention_epochs) => retention_epochs,
+            None => return Ok(()),
+        };
+        let slots_per_restore_point = self.config.slots_per_restore_point;
+        let retention_start = self
+            .get_split_slot()
+            .saturating_sub(retention_epochs.saturating_mul(E::slots_per_epoch()));
+
+        // Keep the restore point at or before the start of the window so that all states in the
+        // window can be reconstructed. Block roots are only available up to the latest restore
+        // point, which bounds the blocks that can be deleted.
+        let new_upper_limit = std::cmp::min(
+            retention_start / slots_per_restore_point * slots_per_restore_point,
+            self.get_latest_restore_point_slot(),
+        );
+        let current_upper_limit = self
+            .get_anchor_info()
+            .map_or(self.spec.genesis_slot, |anchor| anchor.state_upper_limit);
+
+        let pruned_states = if new_upper_limit > current_upper_limit {
+            self.prune_historic_states_to(new_upper_limit, |_, _| ())?
+        } else {
+            0
+        };
+        // Blocks are never pruned from within the window in which peers may request them, so
+        // they lag behind the states when the retention window is shorter.
+        let pruned_blocks = if self.config.prune_history_blocks {
+            let block_retention_start = std::cmp::min(
+                new_upper_limit,
+                self.block_retention_start_slot(retention_epochs),
+            );
+            self.prune_historic_blocks_before(block_retention_start)?
+        } else {
+            0
+        };
+        if pruned_states == 0 && pruned_blocks == 0 {
+            return Ok(());
+        }
+
+        debug!(
+            self.log,
+            "Pruned history outside retention window";
+            "states" => pruned_states,
+            "blocks" => pruned_blocks,
+            "oldest_retained_slot" => new_upper_limit,
+        );
         Ok(())
     }
 
+    /// Delete the blocks prior to `slot`, other than the genesis block, from the hot database.
+    ///
+    /// `slot` must not be later than the latest restore point. Afterwards blocks are unavailable
+    /// prior to `slot` just as they are for a node which has been checkpoint synced, and they
+    /// won't be backfilled while `prune_history_blocks` is set. Returns the number of blocks
+    /// deleted.
+    fn prune_historic_blocks_before(&self, slot: Slot) -> Result<usize, Error> {
+        let old_anchor = self.get_anchor_info();
+        let oldest_block_slot = self.get_oldest_block_slot();
+        if slot <= oldest_block_slot {
+            return Ok(0);
+        }
+
+        let latest_restore_point_slot = self.get_latest_restore_point_slot();
+        let genesis_block_root = FrozenForwardsIterator::<E, BlockRoots, Hot, Cold>::new(
+            self,
+            self.spec.genesis_slot,
+            latest_restore_point_slot,
+            &self.spec,
+        )
+        .next()
+        .map(|(block_root, _)| block_root);
+
+        let mut block_roots = FrozenForwardsIterator::<E, BlockRoots, Hot, Cold>::new(
+            self,
+            oldest_block_slot,
+            latest_restore_point_slot,
+            &self.spec,
+        )
+        .take_while(|(_, block_slot)| *block_slot < slot)
+        .map(|(block_root, _)| block_root)
+        .collect::<Vec<_>>();
+        let oldest_block_parent = match block_roots.last() {
+            Some(block_root) => *block_root,
+            None => return Ok(0),
+        };
+        block_roots.dedup();
+        block_roots.retain(|block_root| Some(*block_root) != genesis_block_root);
+
+        // Update the anchor before deleting anything so that the blocks aren't read meanwhile.
+        let new_anchor = AnchorInfo {
+            oldest_block_slot: slot,
+            oldest_block_parent,
+            ..old_anchor
+                .clone()
+                .ok_or(HotColdDBError::MissingAnchorInfo)?
+        };
+        self.compare_and_set_anchor_info_with_write(old_anchor, Some(new_anchor))?;
+
+        for chunk in block_roots.chunks(PRUNE_BATCH_SIZE) {
+            let ops = chunk
+                .iter()
+                .flat_map(|block_root| {
+                    [
+                        StoreOp::DeleteBlock(*block_root),
+                        StoreOp::DeleteExecutionPayload(*block_root),
+                    ]
+                })
+                .collect();
+            self.do_atomically(ops)?;
+
+            // Delete any payloads that were reconstructed into the freezer database.
+            let cold_ops = chunk
+                .iter()
+                .map(|block_root| {
+                    KeyValueStoreOp::DeleteKey(get_key_for_col(
+                        DBColumn::ExecPayload.into(),
+                        block_root.as_bytes(),
+                    ))
+                })
+                .collect();
+            self.cold_db.do_atomically(cold_ops)?;
+        }
+        Ok(block_roots.len())
+    }
+
+    /// Returns `true` if no more blocks need to be backfilled, given the current `anchor_info`.
+    ///
+    /// Blocks are needed back to genesis, unless `prune_history_blocks` is set in which case they
+    /// are only needed back to the start of the retention window.
+    pub fn block_backfill_complete(&self, anchor_info: &AnchorInfo) -> bool {
+        if anchor_info.block_backfill_complete() {
+            return true;
+        }
+        match self.config.history_retention_epochs {
+            Some(retention_epochs) if self.config.prune_history_blocks => {
+                anchor_info.oldest_block_slot <= self.block_retention_start_slot(retention_epochs)
+            }
+            _ => false,
+        }
+    }
+
+    /// Return the slot prior to which blocks may be pruned when retaining `retention_epochs` of
+    /// history.
+    ///
+    /// Blocks are retained for at least `MIN_EPOCHS_FOR_BLOCK_REQUESTS` before the split so that
+    /// they can always be served to peers.
+    pub fn block_retention_start_slot(&self, retention_epochs: u64) -> Slot {
+        let block_retention_epochs =
+            std::cmp::max(retention_epochs, self.spec.min_epochs_for_block_requests());
+        self.get_split_slot()
+            .saturating_sub(block_retention_epochs.saturating_mul(E::slots_per_epoch()))
+    }
+
+    /// Return the slot prior to which blob sidecars may be pruned.
+    ///
+    /// Blobs are retained for at least `MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS` before the split so
+    /// that they can always be served to peers, or for `blob_retention_epochs` if that's longer.
+    pub fn blob_retention_start_slot(&self) -> Slot {
+        let blob_retention_epochs = max(
+            self.config.blob_retention_epochs.unwrap_or(0),
+            self.spec.min_epochs_for_blob_sidecars_requests,
+        );
+        self.get_split_slot()
+            .saturating_sub(blob_retention_epochs.saturating_mul(E::slots_per_epoch()))
+    }
+
+    /// Delete the blob sidecars of finalized blocks prior to the blob retention window.
+    ///
+    /// Blob pruning is independent of the pruning of states and blocks. Called after each migration
+    /// to the freezer database, so only the blobs of blocks which have left the window since the
+    /// last call are deleted. Returns the number of blocks whose blobs were deleted.
+    pub fn prune_blobs(&self) -> Result<usize, Error> {
+        // Blobs are only stored from the Deneb fork onwards.
+        let deneb_fork_slot = match self.spec.deneb_fork_epoch {
+            Some(epoch) => epoch.start_slot(E::slots_per_epoch()),
+            None => return Ok(0),
+        };
+        let old_blob_info = self.get_blob_info();
+        let start_slot = max(
+            max(old_blob_info.oldest_blob_slot, deneb_fork_slot),
+            self.get_oldest_block_slot(),
+        );
+        let retention_start = self.blob_retention_start_slot();
+        if retention_start <= start_slot {
+            return Ok(0);
+        }
+
+        // Block roots are only available up to the latest restore point, which bounds the blocks
+        // whose blobs can be deleted.
+        let latest_restore_point_slot = self.get_latest_restore_point_slot();
+        let end_slot = min(retention_start, latest_restore_point_slot);
+        let mut block_roots = FrozenForwardsIterator::<E, BlockRoots, Hot, Cold>::new(
+            self,
+            start_slot,
+            latest_restore_point_slot,
+            &self.spec,
+        )
+        .take_while(|(_, block_slot)| *block_slot < end_slot)
+        .map(|(block_root, _)| block_root)
+        .collect::<Vec<_>>();
+        block_roots.dedup();
+
+        // Update the oldest blob slot before deleting anything so that the blobs aren't read
+        // meanwhile.
+        {
+            let mut blob_info = self.blob_info.write();
+            let new_blob_info = BlobInfo {
+                oldest_blob_slot: end_slot,
+                ..*blob_info
+            };
+            self.hot_db.put(&BLOB_INFO_KEY, &new_blob_info)?;
+            *blob_info = new_blob_info;
+        }
+
+        let blob_info_before = self.get_blob_info();
+        for chunk in block_roots.chunks(PRUNE_BATCH_SIZE) {
+            let ops = chunk
+                .iter()
+                .map(|block_root| StoreOp::DeleteBlobs(*block_root))
+                .collect();
+            self.do_atomically(ops)?;
+        }
+        let blob_info = self.get_blob_info();
+        let pruned = blob_info_before
+            .blocks_with_blobs
+            .saturating_sub(blob_info.blocks_with_blobs);
+
+        debug!(
+            self.log,
+            "Pruned blobs outside retention window";
+            "blocks" => pruned,
+            "oldest_blob_slot" => end_slot,
+            "blob_bytes" => blob_info.blob_bytes,
+        );
+        Ok(pruned as usize)
+    }
+
+    /// Return the slot to which blocks must be backfilled, or `None` if no anchor is present
+    /// and the node holds all blocks back to genesis.
+    pub fn backfill_target_slot(&self) -> Option<Slot> {
+        self.get_anchor_info()?;
+        Some(match self.config.history_retention_epochs {
+            Some(retention_epochs) if self.config.prune_history_blocks => {
+                self.block_retention_start_slot(retention_epochs)
+            }
+            _ => self.spec.genesis_slot,
+        })
+    }
+
+    /// Returns `true` if blocks are held for the full `MIN_EPOCHS_FOR_BLOCK_REQUESTS` prior to
+    /// `current_slot`, i.e. the node can serve every block range peers are entitled to request.
+    pub fn has_min_block_history(&self, current_slot: Slot) -> bool {
+        let min_slots = self
+            .spec
+            .min_epochs_for_block_requests()
+            .saturating_mul(E::slots_per_epoch());
+        self.get_oldest_block_slot() <= current_slot.saturating_sub(min_slots)
+    }
+
+    /// Rewrite the restore points stored in full in the freezer database as diffs, wherever the
+    /// `restore_points_per_snapshot` of the config would store them as diffs.
+    ///
+    /// This converts a database created prior to state diffs (or with a smaller
+    /// `restore_points_per_snapshot`) to the more compact layout. Restore points are converted one
+    /// at a time so that the database is consistent if the conversion is interrupted.
+    ///
+    /// `progress` is called with the number of restore points examined so far and the total number
+    /// to examine. Returns the number of restore points converted.
+    pub fn convert_restore_points_to_diffs(
+        &self,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<usize, Error> {
+        let slots_per_restore_point = self.config.slots_per_restore_point;
+        let split_slot = self.get_split_slot().as_u64();
+        let end_index = (split_slot + slots_per_restore_point - 1) / slots_per_restore_point;
+
+        let total = end_index.saturating_sub(1) as usize;
+        let mut converted = 0;
+        progress(0, total);
+        for restore_point_index in 1..end_index {
+            if self.convert_restore_point_to_diff(restore_point_index)? {
+                converted += 1;
+            }
+            progress(restore_point_index as usize, total);
+        }
+
+        // Reclaim the space of the deleted states.
+        self.cold_db.compact()?;
+
+        info!(
+            self.log,
+            "Converted restore points to state diffs";
+            "count" => converted,
+        );
+        Ok(converted)
+    }
+
+    /// Rewrite the restore point at `restore_point_index` as a diff, if it's stored in full and
+    /// has a base available. Returns `true` if it was rewritten.
+    fn convert_restore_point_to_diff(&self, restore_point_index: u64) -> Result<bool, Error> {
+        let key = Self::restore_point_key(restore_point_index);
+        let state_root = match self.cold_db.get::<RestorePointHash>(&key)? {
+            Some(RestorePointHash { state_root }) => state_root,
+            None => return Ok(false),
+        };
+        let partial_state_bytes = match self
+            .cold_db
+            .get_bytes(DBColumn::BeaconState.into(), state_root.as_bytes())?
+        {
+            Some(bytes) => bytes,
+            None => return Ok(false),
+        };
+        let (base_index, base) = match self.load_diff_base(restore_point_index)? {
+            Some(base) => base,
+            None => return Ok(false),
+        };
+
+        let partial_state = PartialBeaconState::from_ssz_bytes(&partial_state_bytes, &self.spec)?;
+        let diff = StateDiff::compute(base_index, &base, &partial_state);
+        self.cold_db.do_atomically(vec![
+            diff.as_kv_store_op(state_root),
+            KeyValueStoreOp::DeleteKey(get_key_for_col(
+                DBColumn::BeaconState.into(),
+                state_root.as_bytes(),
+            )),
+        ])?;
+        Ok(true)
+    }
+
+    /// Rewrite every restore point stored as a diff in the freezer database in full.
+    ///
+    /// This is the inverse of `convert_restore_points_to_diffs`, used when downgrading to a schema
+    /// version which predates state diffs. Restore points are converted in ascending order, one at
+    /// a time, so that the database is consistent if the conversion is interrupted.
+    ///
+    /// `progress` is called with the number of restore points examined so far and the total number
+    /// to examine. Returns the number of restore points converted.
+    pub fn convert_state_diffs_to_restore_points(
+        &self,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<usize, Error> {
+        let slots_per_restore_point = self.config.slots_per_restore_point;
+        let split_slot = self.get_split_slot().as_u64();
+        let end_index = (split_slot + slots_per_restore_point - 1) / slots_per_restore_point;
+
+        let total = end_index.saturating_sub(1) as usize;
+        let mut converted = 0;
+        progress(0, total);
+        for restore_point_index in 1..end_index {
+            let key = Self::restore_point_key(restore_point_index);
+            if let Some(RestorePointHash { state_root }) = self.cold_db.get(&key)? {
+                if self
+                    .cold_db
+                    .key_exists(DBColumn::BeaconStateDiff.into(), state_root.as_bytes())?
+                {
+                    self.store_diff_restore_point_in_full(&state_root)?;
+                    converted += 1;
+                }
+            }
+            progress(restore_point_index as usize, total);
+        }
+
+        info!(
+            self.log,
+            "Converted state diffs to full restore points";
+            "count" => converted,
+        );
+        Ok(converted)
+    }
+
+    /// Return statistics about the compactions run since the database was opened.
+    pub fn compaction_stats(&self) -> CompactionStats {
+        *self.compaction_stats.read()
+    }
+
     /// Return `true` if compaction on finalization/pruning is enabled.
     pub fn compact_on_prune(&self) -> bool {
         self.config.compact_on_prune
@@ -1568,6 +2694,15 @@ impl StoreItem for Split {
     }
 }
 
+/// Build the thread pool on which historic states are loaded and replayed.
+fn build_replay_pool(config: &StoreConfig) -> Result<rayon::ThreadPool, Error> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(config.max_replay_threads)
+        .thread_name(|index| format!("historic_replay_{}", index))
+        .build()
+        .map_err(|e| HotColdDBError::ReplayThreadPoolError(e.to_string()).into())
+}
+
 /// Type hint.
 fn no_state_root_iter() -> Option<std::iter::Empty<Result<(Hash256, Slot), Error>>> {
     None
@@ -1660,6 +2795,28 @@ impl StoreItem for RestorePointHash {
     }
 }
 
+/// The parts of a `LightClientBootstrap` which are specific to its block.
+#[derive(Debug, Clone, Encode, Decode)]
+struct LightClientBootstrapBranch {
+    header: BeaconBlockHeader,
+    current_sync_committee_branch:
+        FixedVector<Hash256, light_client_bootstrap::CurrentSyncCommitteeProofLen>,
+}
+
+impl StoreItem for LightClientBootstrapBranch {
+    fn db_column() -> DBColumn {
+        DBColumn::LightClientBootstrap
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 pub struct TemporaryFlag;
 
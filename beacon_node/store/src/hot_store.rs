@@ -0,0 +1,151 @@
+use super::*;
+use crate::config::{HotBackend, StoreConfig, StoreConfigError};
+#[cfg(feature = "redb")]
+use crate::redb_store::RedbDB;
+use parking_lot::MutexGuard;
+use std::path::Path;
+
+/// The name of the redb database file within the hot database directory.
+pub const REDB_FILE_NAME: &str = "lighthouse.redb";
+
+/// A key-value store which may be opened on disk to hold the hot database.
+pub trait HotStore<E: EthSpec>: ItemStore<E> {
+    /// Open the hot database at `path`, creating it if it does not already exist.
+    fn open_hot(path: &Path, config: &StoreConfig) -> Result<Self, Error>;
+}
+
+impl<E: EthSpec> HotStore<E> for LevelDB<E> {
+    fn open_hot(path: &Path, _config: &StoreConfig) -> Result<Self, Error> {
+        LevelDB::open(path)
+    }
+}
+
+#[cfg(feature = "redb")]
+impl<E: EthSpec> HotStore<E> for RedbDB<E> {
+    fn open_hot(path: &Path, _config: &StoreConfig) -> Result<Self, Error> {
+        RedbDB::open(path)
+    }
+}
+
+/// A hot database whose backend is selected by `StoreConfig::hot_backend` when it is opened.
+pub enum HotDB<E: EthSpec> {
+    LevelDb(LevelDB<E>),
+    #[cfg(feature = "redb")]
+    Redb(RedbDB<E>),
+}
+
+/// Evaluate `$body` with `$inner` bound to whichever backend `$db` wraps.
+macro_rules! with_hot_db {
+    ($db:expr, $inner:ident => $body:expr) => {
+        match $db {
+            HotDB::LevelDb($inner) => $body,
+            #[cfg(feature = "redb")]
+            HotDB::Redb($inner) => $body,
+        }
+    };
+}
+
+impl<E: EthSpec> HotDB<E> {
+    /// Returns the backend of this database.
+    pub fn backend(&self) -> HotBackend {
+        match self {
+            HotDB::LevelDb(_) => HotBackend::LevelDb,
+            #[cfg(feature = "redb")]
+            HotDB::Redb(_) => HotBackend::Redb,
+        }
+    }
+}
+
+/// Returns the backend of the database already initialized at `path`, if any.
+///
+/// Leveldb writes its `CURRENT` manifest pointer, and redb keeps everything in a single file.
+pub fn existing_hot_backend(path: &Path) -> Option<HotBackend> {
+    if path.join("CURRENT").exists() {
+        Some(HotBackend::LevelDb)
+    } else if path.join(REDB_FILE_NAME).exists() {
+        Some(HotBackend::Redb)
+    } else {
+        None
+    }
+}
+
+impl<E: EthSpec> HotStore<E> for HotDB<E> {
+    fn open_hot(path: &Path, config: &StoreConfig) -> Result<Self, Error> {
+        match existing_hot_backend(path) {
+            Some(on_disk) if on_disk != config.hot_backend => {
+                return Err(Error::ConfigError(StoreConfigError::MismatchedHotBackend {
+                    config: config.hot_backend,
+                    on_disk,
+                }))
+            }
+            _ => (),
+        }
+
+        match config.hot_backend {
+            HotBackend::LevelDb => LevelDB::open(path).map(HotDB::LevelDb),
+            #[cfg(feature = "redb")]
+            HotBackend::Redb => RedbDB::open(path).map(HotDB::Redb),
+            #[cfg(not(feature = "redb"))]
+            HotBackend::Redb => Err(Error::ConfigError(StoreConfigError::HotBackendNotCompiled(
+                HotBackend::Redb,
+            ))),
+        }
+    }
+}
+
+impl<E: EthSpec> KeyValueStore<E> for HotDB<E> {
+    fn get_bytes(&self, col: &str, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        with_hot_db!(self, db => db.get_bytes(col, key))
+    }
+
+    fn put_bytes(&self, col: &str, key: &[u8], val: &[u8]) -> Result<(), Error> {
+        with_hot_db!(self, db => db.put_bytes(col, key, val))
+    }
+
+    fn put_bytes_sync(&self, col: &str, key: &[u8], val: &[u8]) -> Result<(), Error> {
+        with_hot_db!(self, db => db.put_bytes_sync(col, key, val))
+    }
+
+    fn sync(&self) -> Result<(), Error> {
+        with_hot_db!(self, db => db.sync())
+    }
+
+    fn key_exists(&self, col: &str, key: &[u8]) -> Result<bool, Error> {
+        with_hot_db!(self, db => db.key_exists(col, key))
+    }
+
+    fn key_delete(&self, col: &str, key: &[u8]) -> Result<(), Error> {
+        with_hot_db!(self, db => db.key_delete(col, key))
+    }
+
+    fn do_atomically(&self, batch: Vec<KeyValueStoreOp>) -> Result<(), Error> {
+        with_hot_db!(self, db => db.do_atomically(batch))
+    }
+
+    fn begin_rw_transaction(&self) -> MutexGuard<()> {
+        with_hot_db!(self, db => db.begin_rw_transaction())
+    }
+
+    fn compact(&self) -> Result<(), Error> {
+        with_hot_db!(self, db => db.compact())
+    }
+
+    fn compact_column_range(
+        &self,
+        column: DBColumn,
+        start: &[u8],
+        end: &[u8],
+    ) -> Result<(), Error> {
+        with_hot_db!(self, db => db.compact_column_range(column, start, end))
+    }
+
+    fn iter_column(&self, column: DBColumn) -> ColumnIter {
+        with_hot_db!(self, db => db.iter_column(column))
+    }
+
+    fn iter_column_keys(&self, column: DBColumn) -> ColumnKeyIter {
+        with_hot_db!(self, db => db.iter_column_keys(column))
+    }
+}
+
+impl<E: EthSpec> ItemStore<E> for HotDB<E> {}
@@ -1,2 +1,3 @@
 pub mod beacon_state;
 pub mod execution_payload;
+pub mod sync_committee;
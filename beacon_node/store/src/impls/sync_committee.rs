@@ -0,0 +1,17 @@
+use crate::{DBColumn, Error, StoreItem};
+use ssz::{Decode, Encode};
+use types::{EthSpec, SyncCommittee};
+
+impl<E: EthSpec> StoreItem for SyncCommittee<E> {
+    fn db_column() -> DBColumn {
+        DBColumn::SyncCommittee
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
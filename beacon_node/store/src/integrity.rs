@@ -0,0 +1,244 @@
+//! Startup checks of the consistency of the hot and freezer databases.
+//!
+//! A database which has been partially restored, copied between machines, or opened by an
+//! incompatible version of Lighthouse can otherwise fail deep inside the store with an opaque
+//! error some time after startup. These checks fail fast instead, with a hint about how to fix
+//! the problem.
+use crate::hot_cold_store::{HotColdDB, HotColdDBError};
+use crate::metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION};
+use crate::{Error, ItemStore};
+use std::fmt;
+use types::{EthSpec, Hash256, Slot};
+
+const RESYNC_HINT: &str = "Restore the database from a backup with `lighthouse db restore`, or \
+    re-sync by restarting with `--purge-db` and `--checkpoint-sync-url`.";
+
+#[derive(Debug)]
+pub enum IntegrityError {
+    /// The database couldn't be read.
+    Store(Error),
+    /// The database has no schema version, despite holding a split point.
+    MissingSchemaVersion,
+    /// The database schema doesn't match the one used by this version of Lighthouse.
+    SchemaVersionMismatch {
+        on_disk: SchemaVersion,
+        expected: SchemaVersion,
+    },
+    /// The split point doesn't lie on an epoch boundary.
+    UnalignedSplit { split_slot: Slot },
+    /// The state at the split point is missing from the hot database.
+    MissingSplitState {
+        split_slot: Slot,
+        state_root: Hash256,
+    },
+    /// The state at the split point has a different slot to the split point itself.
+    SplitSlotMismatch { split_slot: Slot, state_slot: Slot },
+    /// The anchor lies after the split point.
+    AnchorAfterSplit { anchor_slot: Slot, split_slot: Slot },
+    /// The first state after the checkpoint sync anchor is missing from the freezer database.
+    MissingAnchorState { restore_point_slot: Slot },
+    /// The genesis state is missing from the freezer database of a node synced from genesis.
+    MissingGenesisState,
+    /// The latest restore point prior to the split is missing from the freezer database, so
+    /// the freezer database is behind the hot database.
+    FreezerBehindHotDatabase {
+        restore_point_slot: Slot,
+        split_slot: Slot,
+    },
+}
+
+impl IntegrityError {
+    /// A suggestion for how the user can fix the database.
+    pub fn remediation(&self) -> &'static str {
+        match self {
+            IntegrityError::Store(_) => {
+                "Check that the data directory is readable and that the disk isn't failing."
+            }
+            IntegrityError::MissingSchemaVersion | IntegrityError::SchemaVersionMismatch { .. } => {
+                "Run the version of Lighthouse that last used this database, or migrate it with \
+                 `lighthouse db migrate --to <version>`."
+            }
+            IntegrityError::MissingAnchorState { .. } => {
+                "Re-run checkpoint sync by restarting with `--purge-db` and \
+                 `--checkpoint-sync-url`."
+            }
+            IntegrityError::FreezerBehindHotDatabase { .. } => {
+                "The hot and freezer databases may come from different backups. Restore both \
+                 from the same backup with `lighthouse db restore`, or re-sync by restarting \
+                 with `--purge-db` and `--checkpoint-sync-url`."
+            }
+            IntegrityError::UnalignedSplit { .. }
+            | IntegrityError::MissingSplitState { .. }
+            | IntegrityError::SplitSlotMismatch { .. }
+            | IntegrityError::AnchorAfterSplit { .. }
+            | IntegrityError::MissingGenesisState => RESYNC_HINT,
+        }
+    }
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IntegrityError::Store(e) => write!(f, "unable to read the database: {:?}", e),
+            IntegrityError::MissingSchemaVersion => {
+                write!(f, "the database has no schema version")
+            }
+            IntegrityError::SchemaVersionMismatch { on_disk, expected } => write!(
+                f,
+                "the database schema version is {} but this version of Lighthouse requires {}",
+                on_disk.as_u64(),
+                expected.as_u64()
+            ),
+            IntegrityError::UnalignedSplit { split_slot } => write!(
+                f,
+                "the split slot {} doesn't lie on an epoch boundary",
+                split_slot
+            ),
+            IntegrityError::MissingSplitState {
+                split_slot,
+                state_root,
+            } => write!(
+                f,
+                "the state at the split slot {} ({:?}) is missing from the hot database",
+                split_slot, state_root
+            ),
+            IntegrityError::SplitSlotMismatch {
+                split_slot,
+                state_slot,
+            } => write!(
+                f,
+                "the state at the split slot {} has slot {}",
+                split_slot, state_slot
+            ),
+            IntegrityError::AnchorAfterSplit {
+                anchor_slot,
+                split_slot,
+            } => write!(
+                f,
+                "the checkpoint sync anchor slot {} lies after the split slot {}",
+                anchor_slot, split_slot
+            ),
+            IntegrityError::MissingAnchorState { restore_point_slot } => write!(
+                f,
+                "the restore point at slot {} following the checkpoint sync anchor is missing \
+                 from the freezer database",
+                restore_point_slot
+            ),
+            IntegrityError::MissingGenesisState => {
+                write!(f, "the genesis state is missing from the freezer database")
+            }
+            IntegrityError::FreezerBehindHotDatabase {
+                restore_point_slot,
+                split_slot,
+            } => write!(
+                f,
+                "the restore point at slot {} is missing from the freezer database, which should \
+                 hold all states prior to the split slot {}",
+                restore_point_slot, split_slot
+            ),
+        }
+    }
+}
+
+impl From<Error> for IntegrityError {
+    fn from(e: Error) -> Self {
+        IntegrityError::Store(e)
+    }
+}
+
+impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold> {
+    /// Check that the hot and freezer databases are consistent with one another.
+    ///
+    /// Checks the schema version, that the state at the split point is present, and that the
+    /// freezer database holds the restore points that the anchor and split point require. A
+    /// database which hasn't yet stored a split point (i.e. a new database, or one synced from
+    /// genesis which has yet to finalize) passes trivially.
+    pub fn check_integrity(&self) -> Result<(), IntegrityError> {
+        let split = match self.load_split()? {
+            Some(split) => split,
+            None => return Ok(()),
+        };
+
+        match self.load_schema_version()? {
+            Some(on_disk) if on_disk != CURRENT_SCHEMA_VERSION => {
+                return Err(IntegrityError::SchemaVersionMismatch {
+                    on_disk,
+                    expected: CURRENT_SCHEMA_VERSION,
+                })
+            }
+            Some(_) => (),
+            None => return Err(IntegrityError::MissingSchemaVersion),
+        }
+
+        if split.slot % E::slots_per_epoch() != 0 {
+            return Err(IntegrityError::UnalignedSplit {
+                split_slot: split.slot,
+            });
+        }
+
+        match self.load_state_slot(&split.state_root)? {
+            Some(state_slot) if state_slot != split.slot => {
+                return Err(IntegrityError::SplitSlotMismatch {
+                    split_slot: split.slot,
+                    state_slot,
+                })
+            }
+            Some(_) => (),
+            None => {
+                return Err(IntegrityError::MissingSplitState {
+                    split_slot: split.slot,
+                    state_root: split.state_root,
+                })
+            }
+        }
+
+        if split.slot == self.spec.genesis_slot {
+            return Ok(());
+        }
+
+        let slots_per_restore_point = self.config.slots_per_restore_point;
+        let latest_restore_point_slot = self.get_latest_restore_point_slot();
+        let restore_point_present = |slot: Slot| -> Result<bool, IntegrityError> {
+            match self.load_restore_point_hash(slot.as_u64() / slots_per_restore_point) {
+                Ok(_) => Ok(true),
+                Err(Error::HotColdDBError(HotColdDBError::MissingRestorePointHash(_))) => Ok(false),
+                Err(e) => Err(e.into()),
+            }
+        };
+
+        match self.get_anchor_info() {
+            Some(anchor) => {
+                if anchor.anchor_slot > split.slot {
+                    return Err(IntegrityError::AnchorAfterSplit {
+                        anchor_slot: anchor.anchor_slot,
+                        split_slot: split.slot,
+                    });
+                }
+                // The restore point following the anchor is stored once the split passes it.
+                let restore_point_slot = anchor.state_upper_limit;
+                if restore_point_slot <= latest_restore_point_slot
+                    && !restore_point_present(restore_point_slot)?
+                {
+                    return Err(IntegrityError::MissingAnchorState { restore_point_slot });
+                }
+            }
+            None => {
+                if !restore_point_present(self.spec.genesis_slot)? {
+                    return Err(IntegrityError::MissingGenesisState);
+                }
+            }
+        }
+
+        let (lower_limit, upper_limit) = self.get_historic_state_limits();
+        if (latest_restore_point_slot <= lower_limit || latest_restore_point_slot >= upper_limit)
+            && !restore_point_present(latest_restore_point_slot)?
+        {
+            return Err(IntegrityError::FreezerBehindHotDatabase {
+                restore_point_slot: latest_restore_point_slot,
+                split_slot: split.slot,
+            });
+        }
+
+        Ok(())
+    }
+}
@@ -157,25 +157,28 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
 
     /// Compact all values in the states and states flag columns.
     fn compact(&self) -> Result<(), Error> {
-        let endpoints = |column: DBColumn| {
-            (
-                BytesKey::from_vec(get_key_for_col(column.as_str(), Hash256::zero().as_bytes())),
-                BytesKey::from_vec(get_key_for_col(
-                    column.as_str(),
-                    Hash256::repeat_byte(0xff).as_bytes(),
-                )),
-            )
-        };
-
-        for (start_key, end_key) in vec![
-            endpoints(DBColumn::BeaconStateTemporary),
-            endpoints(DBColumn::BeaconState),
-        ] {
-            self.db.compact(&start_key, &end_key);
+        for column in COMPACTION_COLUMNS {
+            self.compact_column_range(
+                column,
+                Hash256::zero().as_bytes(),
+                Hash256::repeat_byte(0xff).as_bytes(),
+            )?;
         }
         Ok(())
     }
 
+    fn compact_column_range(
+        &self,
+        column: DBColumn,
+        start: &[u8],
+        end: &[u8],
+    ) -> Result<(), Error> {
+        let start_key = BytesKey::from_vec(get_key_for_col(column.as_str(), start));
+        let end_key = BytesKey::from_vec(get_key_for_col(column.as_str(), end));
+        self.db.compact(&start_key, &end_key);
+        Ok(())
+    }
+
     /// Iterate through all keys and values in a particular column.
     fn iter_column(&self, column: DBColumn) -> ColumnIter {
         let start_key =
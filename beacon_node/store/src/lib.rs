@@ -2,7 +2,8 @@
 //!
 //! Provides the following stores:
 //!
-//! - `HotColdDB`: an on-disk store backed by leveldb. Used in production.
+//! - `HotColdDB`: an on-disk store backed by leveldb, with a freezer database backed by leveldb or
+//!   sled. Used in production.
 //! - `MemoryStore`: an in-memory store backed by a hash-map. Used for testing.
 //!
 //! Provides a simple API for storing/retrieving all types that sometimes needs type-hints. See
@@ -13,33 +14,46 @@ extern crate lazy_static;
 mod chunk_writer;
 pub mod chunked_iter;
 pub mod chunked_vector;
+mod cold_store;
 pub mod config;
 pub mod errors;
 mod forwards_iter;
 mod garbage_collection;
 pub mod hot_cold_store;
+mod hot_store;
 mod impls;
+pub mod integrity;
 mod leveldb_store;
 mod memory_store;
 pub mod metadata;
 pub mod metrics;
 mod partial_beacon_state;
 pub mod reconstruct;
+#[cfg(feature = "redb")]
+mod redb_store;
+mod sled_store;
+mod state_diff;
 
 pub mod iter;
 
 pub use self::chunk_writer::ChunkWriter;
-pub use self::config::StoreConfig;
-pub use self::hot_cold_store::{HotColdDB, HotStateSummary, Split};
+pub use self::cold_store::{ColdStore, FreezerDB};
+pub use self::config::{FreezerBackend, HotBackend, StoreConfig};
+pub use self::hot_cold_store::{CompactionStats, HotColdDB, HotStateSummary, Split};
+pub use self::hot_store::{existing_hot_backend, HotDB, HotStore};
+pub use self::integrity::IntegrityError;
 pub use self::leveldb_store::LevelDB;
 pub use self::memory_store::MemoryStore;
 pub use self::partial_beacon_state::PartialBeaconState;
+#[cfg(feature = "redb")]
+pub use self::redb_store::RedbDB;
+pub use self::sled_store::SledDB;
 pub use errors::Error;
 pub use impls::beacon_state::StorageContainer as BeaconStateStorageContainer;
-pub use metadata::AnchorInfo;
+pub use metadata::{AnchorInfo, BlobInfo, PayloadReconstructionProgress};
 pub use metrics::scrape_for_metrics;
 use parking_lot::MutexGuard;
-use strum::{EnumString, IntoStaticStr};
+use strum::{EnumIter, EnumString, IntoStaticStr};
 pub use types::*;
 
 pub type ColumnIter<'a> = Box<dyn Iterator<Item = Result<(Hash256, Vec<u8>), Error>> + 'a>;
@@ -67,6 +81,9 @@ pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
     fn key_delete(&self, column: &str, key: &[u8]) -> Result<(), Error>;
 
     /// Execute either all of the operations in `batch` or none at all, returning an error.
+    ///
+    /// Operations are applied in order, so a later operation on the same key wins. Readers never
+    /// observe part of a batch.
     fn do_atomically(&self, batch: Vec<KeyValueStoreOp>) -> Result<(), Error>;
 
     /// Return a mutex guard that can be used to synchronize sensitive transactions.
@@ -78,7 +95,21 @@ pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
     /// Compact the database, freeing space used by deleted items.
     fn compact(&self) -> Result<(), Error>;
 
+    /// Compact the keys of `column` between `start` and `end` inclusive, freeing space used by
+    /// deleted items.
+    fn compact_column_range(
+        &self,
+        _column: DBColumn,
+        _start: &[u8],
+        _end: &[u8],
+    ) -> Result<(), Error> {
+        // Default impl for non LevelDB databases
+        Ok(())
+    }
+
     /// Iterate through all keys and values in a particular column.
+    ///
+    /// Disk-backed stores yield entries in ascending key order.
     fn iter_column(&self, _column: DBColumn) -> ColumnIter {
         // Default impl for non LevelDB databases
         Box::new(std::iter::empty())
@@ -91,6 +122,10 @@ pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
     }
 }
 
+/// The columns of the hot database compacted to free the space used by deleted states.
+pub const COMPACTION_COLUMNS: [DBColumn; 2] =
+    [DBColumn::BeaconStateTemporary, DBColumn::BeaconState];
+
 pub fn get_key_for_col(column: &str, key: &[u8]) -> Vec<u8> {
     let mut result = column.as_bytes().to_vec();
     result.extend_from_slice(key);
@@ -160,10 +195,13 @@ pub enum StoreOp<'a, E: EthSpec> {
     DeleteBlock(Hash256),
     DeleteState(Hash256, Option<Slot>),
     DeleteExecutionPayload(Hash256),
+    PutBlobs(Hash256, BlobSidecarList<E>),
+    DeleteBlobs(Hash256),
+    KeyValueOp(KeyValueStoreOp),
 }
 
 /// A unique column identifier.
-#[derive(Debug, Clone, Copy, PartialEq, IntoStaticStr, EnumString)]
+#[derive(Debug, Clone, Copy, PartialEq, IntoStaticStr, EnumString, EnumIter)]
 pub enum DBColumn {
     /// For data related to the database itself.
     #[strum(serialize = "bma")]
@@ -173,6 +211,10 @@ pub enum DBColumn {
     /// For full `BeaconState`s in the hot database (finalized or fork-boundary states).
     #[strum(serialize = "ste")]
     BeaconState,
+    /// For restore point states in the freezer database stored as a diff against an earlier
+    /// restore point, rather than in the `BeaconState` column.
+    #[strum(serialize = "bsd")]
+    BeaconStateDiff,
     /// For the mapping from state roots to their slots or summaries.
     #[strum(serialize = "bss")]
     BeaconStateSummary,
@@ -183,6 +225,9 @@ pub enum DBColumn {
     /// Execution payloads for blocks more recent than the finalized checkpoint.
     #[strum(serialize = "exp")]
     ExecPayload,
+    /// For the blob sidecars of blocks, by block root.
+    #[strum(serialize = "blb")]
+    BeaconBlob,
     /// For persisting in-memory state to the database.
     #[strum(serialize = "bch")]
     BeaconChain,
@@ -207,6 +252,15 @@ pub enum DBColumn {
     BeaconRandaoMixes,
     #[strum(serialize = "dht")]
     DhtEnrs,
+    /// For the scores and bans of peers, persisted across restarts.
+    #[strum(serialize = "prp")]
+    PeerReputations,
+    /// For the header and sync committee branch of light client bootstraps, by block root.
+    #[strum(serialize = "lcb")]
+    LightClientBootstrap,
+    /// For the sync committees of light client bootstraps, by sync committee period.
+    #[strum(serialize = "syc")]
+    SyncCommittee,
 }
 
 /// A block from the database, which might have an execution payload or not.
@@ -300,6 +354,91 @@ mod tests {
         test_impl(store);
     }
 
+    #[test]
+    fn sleddb() {
+        let dir = tempdir().unwrap();
+        let store = SledDB::open(dir.path()).unwrap();
+
+        test_impl(store);
+    }
+
+    #[test]
+    fn freezer_db_rejects_mismatched_backend() {
+        let dir = tempdir().unwrap();
+        let sled_config = StoreConfig {
+            freezer_backend: FreezerBackend::Sled,
+            ..StoreConfig::default()
+        };
+        let store = FreezerDB::<MinimalEthSpec>::open_cold(dir.path(), &sled_config).unwrap();
+        assert_eq!(store.backend(), FreezerBackend::Sled);
+        test_impl(store);
+
+        // Reopening with the same backend succeeds, but another backend must not be used.
+        assert!(FreezerDB::<MinimalEthSpec>::open_cold(dir.path(), &sled_config).is_ok());
+        assert!(matches!(
+            FreezerDB::<MinimalEthSpec>::open_cold(dir.path(), &StoreConfig::default()),
+            Err(Error::ConfigError(
+                config::StoreConfigError::MismatchedFreezerBackend { .. }
+            ))
+        ));
+    }
+
+    #[cfg(feature = "redb")]
+    #[test]
+    fn redbdb() {
+        let dir = tempdir().unwrap();
+        let store = RedbDB::<MinimalEthSpec>::open(dir.path()).unwrap();
+
+        test_impl(store);
+    }
+
+    #[cfg(feature = "redb")]
+    #[test]
+    fn redbdb_iter_column_in_key_order() {
+        let dir = tempdir().unwrap();
+        let store = RedbDB::<MinimalEthSpec>::open(dir.path()).unwrap();
+        let item = StorableThing { a: 1, b: 42 };
+
+        // Write enough keys to span several batches of the column iterator, plus a key in
+        // another column which must not be returned.
+        let mut keys = (0..2500).map(|_| Hash256::random()).collect::<Vec<_>>();
+        let ops = keys
+            .iter()
+            .map(|key| item.as_kv_store_op(*key))
+            .chain(std::iter::once(KeyValueStoreOp::PutKeyValue(
+                get_key_for_col(DBColumn::BeaconState.into(), Hash256::zero().as_bytes()),
+                vec![],
+            )))
+            .collect();
+        store.do_atomically(ops).unwrap();
+        keys.sort();
+
+        let iterated = store
+            .iter_column_keys(DBColumn::BeaconBlock)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(iterated, keys);
+    }
+
+    #[test]
+    fn hot_db_rejects_mismatched_backend() {
+        let dir = tempdir().unwrap();
+        let store = HotDB::<MinimalEthSpec>::open_hot(dir.path(), &StoreConfig::default()).unwrap();
+        assert_eq!(store.backend(), HotBackend::LevelDb);
+        test_impl(store);
+
+        let redb_config = StoreConfig {
+            hot_backend: HotBackend::Redb,
+            ..StoreConfig::default()
+        };
+        assert!(matches!(
+            HotDB::<MinimalEthSpec>::open_hot(dir.path(), &redb_config),
+            Err(Error::ConfigError(
+                config::StoreConfigError::MismatchedHotBackend { .. }
+            ))
+        ));
+    }
+
     #[test]
     fn memorydb() {
         let store = MemoryStore::open();
@@ -4,7 +4,7 @@ use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use types::{Checkpoint, Hash256, Slot};
 
-pub const CURRENT_SCHEMA_VERSION: SchemaVersion = SchemaVersion(9);
+pub const CURRENT_SCHEMA_VERSION: SchemaVersion = SchemaVersion(12);
 
 // All the keys that get stored under the `BeaconMeta` column.
 //
@@ -15,6 +15,8 @@ pub const SPLIT_KEY: Hash256 = Hash256::repeat_byte(2);
 pub const PRUNING_CHECKPOINT_KEY: Hash256 = Hash256::repeat_byte(3);
 pub const COMPACTION_TIMESTAMP_KEY: Hash256 = Hash256::repeat_byte(4);
 pub const ANCHOR_INFO_KEY: Hash256 = Hash256::repeat_byte(5);
+pub const PAYLOAD_RECONSTRUCTION_KEY: Hash256 = Hash256::repeat_byte(6);
+pub const BLOB_INFO_KEY: Hash256 = Hash256::repeat_byte(7);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct SchemaVersion(pub u64);
@@ -117,3 +119,56 @@ impl StoreItem for AnchorInfo {
         Ok(Self::from_ssz_bytes(bytes)?)
     }
 }
+
+/// Progress of the reconstruction of execution payloads that were pruned from the database.
+///
+/// Stored in the freezer database, atomically with the reconstructed payloads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub struct PayloadReconstructionProgress {
+    /// The slot from which payloads remain to be reconstructed.
+    pub next_slot: Slot,
+}
+
+impl StoreItem for PayloadReconstructionProgress {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
+
+/// The range of blob sidecars held by the database, and the space they use.
+///
+/// Updated atomically with every write which stores or deletes blob sidecars.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+pub struct BlobInfo {
+    /// The slot from which blob sidecars are available (>=), other than those which have been
+    /// pruned.
+    pub oldest_blob_slot: Slot,
+    /// The number of blocks whose blob sidecars are stored.
+    pub blocks_with_blobs: u64,
+    /// The number of blob sidecars stored.
+    pub blob_sidecars: u64,
+    /// The total size of the stored blob sidecars in bytes.
+    pub blob_bytes: u64,
+}
+
+impl StoreItem for BlobInfo {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
@@ -43,6 +43,25 @@ lazy_static! {
         "store_disk_db_delete_count_total",
         "Total number of deletions from the hot on-disk DB"
     );
+    /*
+     * Compaction
+     */
+    pub static ref COMPACTION_DEBT: Result<IntGauge> = try_create_int_gauge(
+        "store_compaction_debt_states",
+        "Number of states deleted from the hot DB since it was last compacted"
+    );
+    pub static ref COMPACTION_TIMES: Result<Histogram> = try_create_histogram(
+        "store_compaction_seconds",
+        "Time taken to compact the hot DB, including pauses for throttling and block imports"
+    );
+    pub static ref COMPACTION_CHUNK_TIMES: Result<Histogram> = try_create_histogram(
+        "store_compaction_chunk_seconds",
+        "Time taken to compact a single chunk of the hot DB"
+    );
+    pub static ref COMPACTION_IMPORT_WAIT_TIMES: Result<Histogram> = try_create_histogram(
+        "store_compaction_import_wait_seconds",
+        "Time spent by compaction waiting for block imports to finish before each chunk"
+    );
     /*
      * Beacon State
      */
@@ -101,6 +120,10 @@ lazy_static! {
         "store_beacon_block_cache_hit_total",
         "Number of hits to the store's block cache"
     );
+    pub static ref BEACON_EPOCH_BOUNDARY_STATE_CACHE_HIT_COUNT: Result<IntCounter> = try_create_int_counter(
+        "store_beacon_epoch_boundary_state_cache_hit_total",
+        "Number of hits to the store's epoch boundary state cache"
+    );
     pub static ref BEACON_BLOCK_READ_TIMES: Result<Histogram> = try_create_histogram(
         "store_beacon_block_read_overhead_seconds",
         "Overhead on reading a beacon block from the DB (e.g., decoding)"
@@ -0,0 +1,280 @@
+use super::*;
+use crate::hot_cold_store::HotColdDBError;
+use crate::hot_store::REDB_FILE_NAME;
+use crate::leveldb_store::BytesKey;
+use crate::metrics;
+use parking_lot::{Mutex, MutexGuard};
+use redb::{Database, Durability, ReadableTable, TableDefinition};
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::ops::Bound;
+use std::path::Path;
+
+/// The single table holding all columns.
+///
+/// Keys are prefixed with their column, as in `LevelDB`, so that batches of operations spanning
+/// several columns can be applied in one transaction.
+const TABLE: TableDefinition<&[u8], &[u8]> = TableDefinition::new("lighthouse");
+
+/// The number of entries read by each read transaction while iterating over a column.
+const ITER_BATCH_SIZE: usize = 1024;
+
+/// A wrapped redb database.
+///
+/// Writes which aren't explicitly synced are committed with eventual durability, so like
+/// unsynced LevelDB writes they may be lost in a crash but never leave the database inconsistent.
+pub struct RedbDB<E: EthSpec> {
+    db: Database,
+    /// A mutex to synchronise sensitive read-write transactions.
+    transaction_mutex: Mutex<()>,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: EthSpec> RedbDB<E> {
+    /// Open a database in the directory at `path`, creating a new database if one does not
+    /// already exist.
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        std::fs::create_dir_all(path).map_err(|e| Error::DBError {
+            message: format!("Unable to create {}: {:?}", path.display(), e),
+        })?;
+        let db = Database::create(path.join(REDB_FILE_NAME)).map_err(redb_error)?;
+
+        // Create the table so that it can be read before anything is written.
+        let txn = db.begin_write().map_err(redb_error)?;
+        txn.open_table(TABLE).map_err(redb_error)?;
+        txn.commit().map_err(redb_error)?;
+
+        Ok(Self {
+            db,
+            transaction_mutex: Mutex::new(()),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Apply `ops` in a single write transaction with the given `durability`.
+    fn write(&self, ops: Vec<KeyValueStoreOp>, durability: Durability) -> Result<(), Error> {
+        let mut txn = self.db.begin_write().map_err(redb_error)?;
+        txn.set_durability(durability);
+        {
+            let mut table = txn.open_table(TABLE).map_err(redb_error)?;
+            for op in ops {
+                match op {
+                    KeyValueStoreOp::PutKeyValue(key, value) => {
+                        table
+                            .insert(key.as_slice(), value.as_slice())
+                            .map_err(redb_error)?;
+                    }
+
+                    KeyValueStoreOp::DeleteKey(key) => {
+                        table.remove(key.as_slice()).map_err(redb_error)?;
+                    }
+                }
+            }
+        }
+        txn.commit().map_err(redb_error)
+    }
+
+    fn put_bytes_with_durability(
+        &self,
+        col: &str,
+        key: &[u8],
+        val: &[u8],
+        durability: Durability,
+    ) -> Result<(), Error> {
+        let column_key = get_key_for_col(col, key);
+
+        metrics::inc_counter(&metrics::DISK_DB_WRITE_COUNT);
+        metrics::inc_counter_by(&metrics::DISK_DB_WRITE_BYTES, val.len() as u64);
+        let _timer = metrics::start_timer(&metrics::DISK_DB_WRITE_TIMES);
+
+        self.write(
+            vec![KeyValueStoreOp::PutKeyValue(column_key, val.to_vec())],
+            durability,
+        )
+    }
+
+    /// Return the column portion of `key` as a `Hash256`, or an error if it's not in `column`.
+    fn remove_column(column: DBColumn, key: Vec<u8>) -> Result<Hash256, Error> {
+        let bytes_key = BytesKey::from_vec(key);
+        bytes_key.remove_column(column).ok_or_else(|| {
+            HotColdDBError::IterationError {
+                unexpected_key: bytes_key,
+            }
+            .into()
+        })
+    }
+}
+
+impl<E: EthSpec> KeyValueStore<E> for RedbDB<E> {
+    /// Store some `value` in `column`, indexed with `key`.
+    fn put_bytes(&self, col: &str, key: &[u8], val: &[u8]) -> Result<(), Error> {
+        self.put_bytes_with_durability(col, key, val, Durability::Eventual)
+    }
+
+    fn put_bytes_sync(&self, col: &str, key: &[u8], val: &[u8]) -> Result<(), Error> {
+        self.put_bytes_with_durability(col, key, val, Durability::Immediate)
+    }
+
+    /// Committing an empty transaction with immediate durability persists all prior commits.
+    fn sync(&self) -> Result<(), Error> {
+        self.write(vec![], Durability::Immediate)
+    }
+
+    /// Retrieve some bytes in `column` with `key`.
+    fn get_bytes(&self, col: &str, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let column_key = get_key_for_col(col, key);
+
+        metrics::inc_counter(&metrics::DISK_DB_READ_COUNT);
+        let _timer = metrics::start_timer(&metrics::DISK_DB_READ_TIMES);
+
+        let txn = self.db.begin_read().map_err(redb_error)?;
+        let table = txn.open_table(TABLE).map_err(redb_error)?;
+        let value = table.get(column_key.as_slice()).map_err(redb_error)?;
+        Ok(value.map(|bytes| {
+            let bytes = bytes.value().to_vec();
+            metrics::inc_counter_by(&metrics::DISK_DB_READ_BYTES, bytes.len() as u64);
+            bytes
+        }))
+    }
+
+    /// Return `true` if `key` exists in `column`.
+    fn key_exists(&self, col: &str, key: &[u8]) -> Result<bool, Error> {
+        let column_key = get_key_for_col(col, key);
+
+        metrics::inc_counter(&metrics::DISK_DB_EXISTS_COUNT);
+
+        let txn = self.db.begin_read().map_err(redb_error)?;
+        let table = txn.open_table(TABLE).map_err(redb_error)?;
+        let exists = table
+            .get(column_key.as_slice())
+            .map_err(redb_error)?
+            .is_some();
+        Ok(exists)
+    }
+
+    /// Removes `key` from `column`.
+    fn key_delete(&self, col: &str, key: &[u8]) -> Result<(), Error> {
+        let column_key = get_key_for_col(col, key);
+
+        metrics::inc_counter(&metrics::DISK_DB_DELETE_COUNT);
+
+        self.write(
+            vec![KeyValueStoreOp::DeleteKey(column_key)],
+            Durability::Eventual,
+        )
+    }
+
+    fn do_atomically(&self, ops_batch: Vec<KeyValueStoreOp>) -> Result<(), Error> {
+        self.write(ops_batch, Durability::Eventual)
+    }
+
+    fn begin_rw_transaction(&self) -> MutexGuard<()> {
+        self.transaction_mutex.lock()
+    }
+
+    /// Redb reuses the pages freed by deleted items, so there is nothing to compact.
+    fn compact(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Iterate through all keys and values in a particular column.
+    fn iter_column(&self, column: DBColumn) -> ColumnIter {
+        Box::new(RedbColumnIter::new(self, column, true).map(move |res| {
+            let (key, value) = res?;
+            Ok((Self::remove_column(column, key)?, value))
+        }))
+    }
+
+    /// Iterate through all keys in a particular column.
+    fn iter_column_keys(&self, column: DBColumn) -> ColumnKeyIter {
+        Box::new(
+            RedbColumnIter::new(self, column, false)
+                .map(move |res| Self::remove_column(column, res?.0)),
+        )
+    }
+}
+
+impl<E: EthSpec> ItemStore<E> for RedbDB<E> {}
+
+/// Iterates through the entries of a column in key order.
+///
+/// Entries are read in batches, each in its own read transaction, so that the iterator doesn't
+/// hold a transaction open (and pin old pages) for its whole lifetime. Entries written during
+/// iteration may or may not be returned.
+struct RedbColumnIter<'a, E: EthSpec> {
+    db: &'a RedbDB<E>,
+    column: DBColumn,
+    /// Whether to read values, or only keys.
+    with_values: bool,
+    /// The bound from which the next batch is read, or `None` once the column is exhausted.
+    next_start: Option<Bound<Vec<u8>>>,
+    batch: VecDeque<(Vec<u8>, Vec<u8>)>,
+}
+
+impl<'a, E: EthSpec> RedbColumnIter<'a, E> {
+    fn new(db: &'a RedbDB<E>, column: DBColumn, with_values: bool) -> Self {
+        Self {
+            db,
+            column,
+            with_values,
+            next_start: Some(Bound::Included(column.as_bytes().to_vec())),
+            batch: VecDeque::new(),
+        }
+    }
+
+    fn read_batch(&mut self, start: Bound<Vec<u8>>) -> Result<(), Error> {
+        let txn = self.db.db.begin_read().map_err(redb_error)?;
+        let table = txn.open_table(TABLE).map_err(redb_error)?;
+        let start = match &start {
+            Bound::Included(key) => Bound::Included(key.as_slice()),
+            Bound::Excluded(key) => Bound::Excluded(key.as_slice()),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        let range = table
+            .range::<&[u8]>((start, Bound::Unbounded))
+            .map_err(redb_error)?;
+
+        for entry in range.take(ITER_BATCH_SIZE) {
+            let (key, value) = entry.map_err(redb_error)?;
+            let key = key.value();
+            if !key.starts_with(self.column.as_bytes()) {
+                return Ok(());
+            }
+            let value = if self.with_values {
+                value.value().to_vec()
+            } else {
+                vec![]
+            };
+            self.batch.push_back((key.to_vec(), value));
+        }
+
+        if self.batch.len() == ITER_BATCH_SIZE {
+            self.next_start = self
+                .batch
+                .back()
+                .map(|(key, _)| Bound::Excluded(key.clone()));
+        }
+        Ok(())
+    }
+}
+
+impl<'a, E: EthSpec> Iterator for RedbColumnIter<'a, E> {
+    type Item = Result<(Vec<u8>, Vec<u8>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.batch.is_empty() {
+            let start = self.next_start.take()?;
+            if let Err(e) = self.read_batch(start) {
+                return Some(Err(e));
+            }
+        }
+        self.batch.pop_front().map(Ok)
+    }
+}
+
+/// Convert any of redb's error types into an `Error`.
+fn redb_error(e: impl Into<redb::Error>) -> Error {
+    Error::DBError {
+        message: format!("{:?}", e.into()),
+    }
+}
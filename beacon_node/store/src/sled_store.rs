@@ -0,0 +1,153 @@
+use super::*;
+use crate::hot_cold_store::HotColdDBError;
+use crate::leveldb_store::BytesKey;
+use crate::metrics;
+use parking_lot::{Mutex, MutexGuard};
+use std::marker::PhantomData;
+use std::path::Path;
+
+/// A wrapped sled database.
+///
+/// Keys are prefixed with their column, as in `LevelDB`, so that all columns share a single tree
+/// and batches of operations can be applied atomically.
+pub struct SledDB<E: EthSpec> {
+    db: sled::Db,
+    /// A mutex to synchronise sensitive read-write transactions.
+    transaction_mutex: Mutex<()>,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: EthSpec> SledDB<E> {
+    /// Open a database at `path`, creating a new database if one does not already exist.
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        let db = sled::open(path)?;
+        let transaction_mutex = Mutex::new(());
+
+        Ok(Self {
+            db,
+            transaction_mutex,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Return the column portion of `key` as a `Hash256`, or an error if it's not in `column`.
+    fn remove_column(column: DBColumn, key: &[u8]) -> Result<Hash256, Error> {
+        let bytes_key = BytesKey::from_vec(key.to_vec());
+        bytes_key.remove_column(column).ok_or_else(|| {
+            HotColdDBError::IterationError {
+                unexpected_key: bytes_key,
+            }
+            .into()
+        })
+    }
+}
+
+impl<E: EthSpec> KeyValueStore<E> for SledDB<E> {
+    /// Store some `value` in `column`, indexed with `key`.
+    fn put_bytes(&self, col: &str, key: &[u8], val: &[u8]) -> Result<(), Error> {
+        let column_key = get_key_for_col(col, key);
+
+        metrics::inc_counter(&metrics::DISK_DB_WRITE_COUNT);
+        metrics::inc_counter_by(&metrics::DISK_DB_WRITE_BYTES, val.len() as u64);
+        let _timer = metrics::start_timer(&metrics::DISK_DB_WRITE_TIMES);
+
+        self.db.insert(column_key, val)?;
+        Ok(())
+    }
+
+    fn put_bytes_sync(&self, col: &str, key: &[u8], val: &[u8]) -> Result<(), Error> {
+        self.put_bytes(col, key, val)?;
+        self.sync()
+    }
+
+    fn sync(&self) -> Result<(), Error> {
+        self.db.flush()?;
+        Ok(())
+    }
+
+    /// Retrieve some bytes in `column` with `key`.
+    fn get_bytes(&self, col: &str, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let column_key = get_key_for_col(col, key);
+
+        metrics::inc_counter(&metrics::DISK_DB_READ_COUNT);
+        let _timer = metrics::start_timer(&metrics::DISK_DB_READ_TIMES);
+
+        Ok(self.db.get(column_key)?.map(|bytes| {
+            metrics::inc_counter_by(&metrics::DISK_DB_READ_BYTES, bytes.len() as u64);
+            bytes.to_vec()
+        }))
+    }
+
+    /// Return `true` if `key` exists in `column`.
+    fn key_exists(&self, col: &str, key: &[u8]) -> Result<bool, Error> {
+        let column_key = get_key_for_col(col, key);
+
+        metrics::inc_counter(&metrics::DISK_DB_EXISTS_COUNT);
+
+        Ok(self.db.contains_key(column_key)?)
+    }
+
+    /// Removes `key` from `column`.
+    fn key_delete(&self, col: &str, key: &[u8]) -> Result<(), Error> {
+        let column_key = get_key_for_col(col, key);
+
+        metrics::inc_counter(&metrics::DISK_DB_DELETE_COUNT);
+
+        self.db.remove(column_key)?;
+        Ok(())
+    }
+
+    fn do_atomically(&self, ops_batch: Vec<KeyValueStoreOp>) -> Result<(), Error> {
+        let mut sled_batch = sled::Batch::default();
+        for op in ops_batch {
+            match op {
+                KeyValueStoreOp::PutKeyValue(key, value) => {
+                    sled_batch.insert(key, value);
+                }
+
+                KeyValueStoreOp::DeleteKey(key) => {
+                    sled_batch.remove(key);
+                }
+            }
+        }
+        self.db.apply_batch(sled_batch)?;
+        Ok(())
+    }
+
+    fn begin_rw_transaction(&self) -> MutexGuard<()> {
+        self.transaction_mutex.lock()
+    }
+
+    /// Sled reclaims space in the background, so there is nothing to compact.
+    fn compact(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Iterate through all keys and values in a particular column.
+    fn iter_column(&self, column: DBColumn) -> ColumnIter {
+        Box::new(self.db.scan_prefix(column.as_bytes()).map(move |res| {
+            let (key, value) = res?;
+            Ok((Self::remove_column(column, &key)?, value.to_vec()))
+        }))
+    }
+
+    /// Iterate through all keys in a particular column.
+    fn iter_column_keys(&self, column: DBColumn) -> ColumnKeyIter {
+        Box::new(
+            self.db
+                .scan_prefix(column.as_bytes())
+                .keys()
+                .map(move |res| Self::remove_column(column, &res?)),
+        )
+    }
+}
+
+impl<E: EthSpec> ItemStore<E> for SledDB<E> {}
+
+impl From<sled::Error> for Error {
+    fn from(e: sled::Error) -> Error {
+        Error::DBError {
+            message: format!("{:?}", e),
+        }
+    }
+}
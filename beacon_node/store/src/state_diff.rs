@@ -0,0 +1,158 @@
+//! Compact storage of restore point states as differences from earlier restore points.
+//!
+//! The validator registry accounts for most of the size of a restore point, yet only a handful of
+//! validators change between consecutive restore points. Rather than storing the registry in full,
+//! a `StateDiff` stores only the validators which differ from those of its base restore point,
+//! along with the rest of the `PartialBeaconState`.
+//!
+//! Restore points are grouped into runs of `restore_points_per_snapshot`. The first restore point
+//! of each run is stored in full (a snapshot), while every other restore point is stored as a diff
+//! against an earlier restore point of the same run. The base of the restore point at offset `n`
+//! within its run is the one at offset `n` with its lowest set bit cleared, so that loading any
+//! restore point applies at most `log2(restore_points_per_snapshot)` diffs.
+
+use crate::hot_cold_store::HotColdDBError;
+use crate::partial_beacon_state::PartialBeaconState;
+use crate::{DBColumn, Error, StoreItem};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use types::{ChainSpec, EthSpec, PersistentList, Validator};
+
+/// Returns the index of the restore point which the restore point at `restore_point_index` is
+/// stored as a diff against, or `None` if it is a snapshot.
+pub fn diff_base_restore_point(
+    restore_point_index: u64,
+    restore_points_per_snapshot: u64,
+) -> Option<u64> {
+    let offset = restore_point_index % restore_points_per_snapshot.max(1);
+    if offset == 0 {
+        None
+    } else {
+        Some(restore_point_index - (offset & offset.wrapping_neg()))
+    }
+}
+
+/// The difference between a restore point state and the state of its base restore point.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct StateDiff {
+    /// The index of the restore point this diff is applied to.
+    pub base_restore_point: u64,
+    /// The SSZ bytes of the `PartialBeaconState` with an empty validator registry.
+    partial_state_bytes: Vec<u8>,
+    /// The number of validators in the registry.
+    validator_count: u64,
+    /// The indices of the validators which differ from the base, in ascending order.
+    changed_validator_indices: Vec<u64>,
+    /// The validators which differ from the base, in the same order as their indices.
+    changed_validators: Vec<Validator>,
+}
+
+impl StateDiff {
+    /// Compute the diff which transforms `base` into `target`.
+    pub fn compute<E: EthSpec>(
+        base_restore_point: u64,
+        base: &PartialBeaconState<E>,
+        target: &PartialBeaconState<E>,
+    ) -> Self {
+        let base_validators = base.validators();
+        let (changed_validator_indices, changed_validators) = target
+            .validators()
+            .iter()
+            .enumerate()
+            .filter(|(i, validator)| base_validators.get(*i) != Some(*validator))
+            .map(|(i, validator)| (i as u64, validator.clone()))
+            .unzip();
+
+        let mut partial_state = target.clone();
+        *partial_state.validators_mut() = PersistentList::empty();
+
+        Self {
+            base_restore_point,
+            partial_state_bytes: partial_state.as_ssz_bytes(),
+            validator_count: target.validators().len() as u64,
+            changed_validator_indices,
+            changed_validators,
+        }
+    }
+
+    /// Apply the diff to the state of its base restore point.
+    pub fn apply<E: EthSpec>(
+        &self,
+        mut base: PartialBeaconState<E>,
+        spec: &ChainSpec,
+    ) -> Result<PartialBeaconState<E>, Error> {
+        let invalid = || HotColdDBError::InvalidStateDiff {
+            base_restore_point: self.base_restore_point,
+        };
+
+        // Validators are never removed from the registry, so changed validators either replace
+        // one of the base's validators or are appended in order.
+        let mut validators = std::mem::replace(base.validators_mut(), PersistentList::empty());
+        for (index, validator) in self
+            .changed_validator_indices
+            .iter()
+            .zip(&self.changed_validators)
+        {
+            let index = *index as usize;
+            if let Some(existing) = validators.get_mut(index) {
+                *existing = validator.clone();
+            } else if index == validators.len() {
+                validators.push(validator.clone()).map_err(|_| invalid())?;
+            } else {
+                return Err(invalid().into());
+            }
+        }
+        if validators.len() as u64 != self.validator_count {
+            return Err(invalid().into());
+        }
+
+        let mut partial_state =
+            PartialBeaconState::from_ssz_bytes(&self.partial_state_bytes, spec)?;
+        *partial_state.validators_mut() = validators;
+        Ok(partial_state)
+    }
+}
+
+impl StoreItem for StateDiff {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconStateDiff
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_bases_form_a_hierarchy() {
+        let bases = (0..9)
+            .map(|i| diff_base_restore_point(i, 8))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bases,
+            vec![
+                None,
+                Some(0),
+                Some(0),
+                Some(2),
+                Some(0),
+                Some(4),
+                Some(4),
+                Some(6),
+                None
+            ]
+        );
+        assert_eq!(diff_base_restore_point(13, 8), Some(12));
+
+        // With one restore point per snapshot, every restore point is stored in full.
+        assert_eq!(diff_base_restore_point(5, 1), None);
+    }
+}
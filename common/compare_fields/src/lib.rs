@@ -125,6 +125,17 @@ impl Comparison {
         Self::parent(field_name, a == b, children)
     }
 
+    /// Compare collections which can be iterated but not viewed as a slice.
+    pub fn from_into_iter<'a, T: 'a + Debug + PartialEq<T>>(
+        field_name: String,
+        a: impl IntoIterator<Item = &'a T>,
+        b: impl IntoIterator<Item = &'a T>,
+    ) -> Self {
+        let a = a.into_iter().collect::<Vec<_>>();
+        let b = b.into_iter().collect::<Vec<_>>();
+        Self::from_slice(field_name, &a, &b)
+    }
+
     pub fn retain_children<F>(&mut self, f: F)
     where
         F: FnMut(&FieldComparison) -> bool,
@@ -5,13 +5,20 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, DeriveInput};
 
-fn is_slice(field: &syn::Field) -> bool {
+fn has_attribute(field: &syn::Field, value: &str) -> bool {
     field.attrs.iter().any(|attr| {
-        attr.path.is_ident("compare_fields")
-            && attr.tokens.to_string().replace(' ', "") == "(as_slice)"
+        attr.path.is_ident("compare_fields") && attr.tokens.to_string().replace(' ', "") == value
     })
 }
 
+fn is_slice(field: &syn::Field) -> bool {
+    has_attribute(field, "(as_slice)")
+}
+
+fn is_iter(field: &syn::Field) -> bool {
+    has_attribute(field, "(as_iter)")
+}
+
 #[proc_macro_derive(CompareFields, attributes(compare_fields))]
 pub fn compare_fields_derive(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as DeriveInput);
@@ -43,6 +50,14 @@ pub fn compare_fields_derive(input: TokenStream) -> TokenStream {
                         &b.#ident_b)
                 );
             }
+        } else if is_iter(field) {
+            quote! {
+                comparisons.push(compare_fields::Comparison::from_into_iter(
+                        #field_name.to_string(),
+                        &self.#ident_a,
+                        &b.#ident_b)
+                );
+            }
         } else {
             quote! {
                 comparisons.push(
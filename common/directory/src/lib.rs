@@ -8,6 +8,7 @@ pub const DEFAULT_ROOT_DIR: &str = ".lighthouse";
 pub const DEFAULT_BEACON_NODE_DIR: &str = "beacon";
 pub const DEFAULT_NETWORK_DIR: &str = "network";
 pub const DEFAULT_VALIDATOR_DIR: &str = "validators";
+pub const DEFAULT_SLASHER_DIR: &str = "slasher";
 pub const DEFAULT_SECRET_DIR: &str = "secrets";
 pub const DEFAULT_WALLET_DIR: &str = "wallets";
 
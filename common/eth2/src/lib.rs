@@ -16,7 +16,6 @@ pub mod types;
 
 use self::mixin::{RequestAccept, ResponseForkName, ResponseOptional};
 use self::types::{Error as ResponseError, *};
-use ::types::map_fork_name_with;
 use futures::Stream;
 use futures_util::StreamExt;
 use lighthouse_network::PeerId;
@@ -232,6 +231,26 @@ impl BeaconNodeHttpClient {
         }
     }
 
+    /// Perform a HTTP GET request for a `ForkVersionedResponse`.
+    async fn get_fork_versioned<T: ForkVersionDeserialize, U: IntoUrl>(
+        &self,
+        url: U,
+    ) -> Result<ForkVersionedResponse<T>, Error> {
+        let response = self.get_response(url, |b| b).await?;
+        fork_versioned_from_response(response).await
+    }
+
+    /// Perform a HTTP GET request for a `ForkVersionedResponse`, returning `None` on a 404 error.
+    async fn get_fork_versioned_opt<T: ForkVersionDeserialize, U: IntoUrl>(
+        &self,
+        url: U,
+    ) -> Result<Option<ForkVersionedResponse<T>>, Error> {
+        match self.get_response(url, |b| b).await.optional()? {
+            Some(response) => fork_versioned_from_response(response).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
     /// Perform a HTTP GET request using an 'accept' header, returning `None` on a 404 error.
     pub async fn get_bytes_opt_accept_header<U: IntoUrl>(
         &self,
@@ -268,6 +287,18 @@ impl BeaconNodeHttpClient {
             .map_err(Error::Reqwest)
     }
 
+    /// Perform a HTTP POST request, returning a JSON response or `None` on a 404 error.
+    async fn post_with_opt_response<T: Serialize, U: IntoUrl, R: DeserializeOwned>(
+        &self,
+        url: U,
+        body: &T,
+    ) -> Result<Option<R>, Error> {
+        match self.post_generic(url, body, None).await.optional()? {
+            Some(response) => Ok(Some(response.json().await?)),
+            None => Ok(None),
+        }
+    }
+
     /// Perform a HTTP POST request with a custom timeout.
     async fn post_with_timeout<T: Serialize, U: IntoUrl>(
         &self,
@@ -330,7 +361,7 @@ impl BeaconNodeHttpClient {
     pub async fn get_beacon_states_root(
         &self,
         state_id: StateId,
-    ) -> Result<Option<GenericResponse<RootData>>, Error> {
+    ) -> Result<Option<ExecutionOptimisticResponse<RootData>>, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
@@ -349,7 +380,7 @@ impl BeaconNodeHttpClient {
     pub async fn get_beacon_states_fork(
         &self,
         state_id: StateId,
-    ) -> Result<Option<GenericResponse<Fork>>, Error> {
+    ) -> Result<Option<ExecutionOptimisticResponse<Fork>>, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
@@ -368,7 +399,7 @@ impl BeaconNodeHttpClient {
     pub async fn get_beacon_states_finality_checkpoints(
         &self,
         state_id: StateId,
-    ) -> Result<Option<GenericResponse<FinalityCheckpointsData>>, Error> {
+    ) -> Result<Option<ExecutionOptimisticResponse<FinalityCheckpointsData>>, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
@@ -388,7 +419,7 @@ impl BeaconNodeHttpClient {
         &self,
         state_id: StateId,
         ids: Option<&[ValidatorId]>,
-    ) -> Result<Option<GenericResponse<Vec<ValidatorBalanceData>>>, Error> {
+    ) -> Result<Option<ExecutionOptimisticResponse<Vec<ValidatorBalanceData>>>, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
@@ -418,7 +449,24 @@ impl BeaconNodeHttpClient {
         state_id: StateId,
         ids: Option<&[ValidatorId]>,
         statuses: Option<&[ValidatorStatus]>,
-    ) -> Result<Option<GenericResponse<Vec<ValidatorData>>>, Error> {
+    ) -> Result<Option<ExecutionOptimisticResponse<Vec<ValidatorData>>>, Error> {
+        self.get_beacon_states_validators_paginated(state_id, ids, statuses, None, None)
+            .await
+    }
+
+    /// `GET beacon/states/{state_id}/validators?id,status,offset,limit`
+    ///
+    /// The `offset` and `limit` are applied after filtering by `ids` and `statuses`.
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_states_validators_paginated(
+        &self,
+        state_id: StateId,
+        ids: Option<&[ValidatorId]>,
+        statuses: Option<&[ValidatorStatus]>,
+        offset: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Option<ExecutionOptimisticResponse<Vec<ValidatorData>>>, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
@@ -446,9 +494,66 @@ impl BeaconNodeHttpClient {
             path.query_pairs_mut().append_pair("status", &status_string);
         }
 
+        if let Some(offset) = offset {
+            path.query_pairs_mut()
+                .append_pair("offset", &offset.to_string());
+        }
+
+        if let Some(limit) = limit {
+            path.query_pairs_mut()
+                .append_pair("limit", &limit.to_string());
+        }
+
         self.get_opt(path).await
     }
 
+    /// `POST beacon/states/{state_id}/validators`
+    ///
+    /// Equivalent to `get_beacon_states_validators`, but suitable for long lists of ids.
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn post_beacon_states_validators(
+        &self,
+        state_id: StateId,
+        ids: Option<Vec<ValidatorId>>,
+        statuses: Option<Vec<ValidatorStatus>>,
+    ) -> Result<Option<ExecutionOptimisticResponse<Vec<ValidatorData>>>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("states")
+            .push(&state_id.to_string())
+            .push("validators");
+
+        let request = ValidatorsRequestBody { ids, statuses };
+
+        self.post_with_opt_response(path, &request).await
+    }
+
+    /// `POST beacon/states/{state_id}/validator_balances`
+    ///
+    /// Equivalent to `get_beacon_states_validator_balances`, but suitable for long lists of ids.
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn post_beacon_states_validator_balances(
+        &self,
+        state_id: StateId,
+        ids: Vec<ValidatorId>,
+    ) -> Result<Option<ExecutionOptimisticResponse<Vec<ValidatorBalanceData>>>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("states")
+            .push(&state_id.to_string())
+            .push("validator_balances");
+
+        self.post_with_opt_response(path, &ids).await
+    }
+
     /// `GET beacon/states/{state_id}/committees?slot,index,epoch`
     ///
     /// Returns `Ok(None)` on a 404 error.
@@ -458,7 +563,7 @@ impl BeaconNodeHttpClient {
         slot: Option<Slot>,
         index: Option<u64>,
         epoch: Option<Epoch>,
-    ) -> Result<Option<GenericResponse<Vec<CommitteeData>>>, Error> {
+    ) -> Result<Option<ExecutionOptimisticResponse<Vec<CommitteeData>>>, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
@@ -491,7 +596,7 @@ impl BeaconNodeHttpClient {
         &self,
         state_id: StateId,
         epoch: Option<Epoch>,
-    ) -> Result<GenericResponse<SyncCommitteeByValidatorIndices>, Error> {
+    ) -> Result<ExecutionOptimisticResponse<SyncCommitteeByValidatorIndices>, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
@@ -516,7 +621,7 @@ impl BeaconNodeHttpClient {
         &self,
         state_id: StateId,
         validator_id: &ValidatorId,
-    ) -> Result<Option<GenericResponse<ValidatorData>>, Error> {
+    ) -> Result<Option<ExecutionOptimisticResponse<ValidatorData>>, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
@@ -530,6 +635,22 @@ impl BeaconNodeHttpClient {
         self.get_opt(path).await
     }
 
+    /// `GET beacon/deposit_snapshot`
+    ///
+    /// Returns `Ok(None)` on a 404 error, which occurs if no deposits have been finalized.
+    pub async fn get_deposit_snapshot(
+        &self,
+    ) -> Result<Option<GenericResponse<DepositTreeSnapshot>>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("deposit_snapshot");
+
+        self.get_opt(path).await
+    }
+
     /// `GET beacon/headers?slot,parent_root`
     ///
     /// Returns `Ok(None)` on a 404 error.
@@ -537,7 +658,7 @@ impl BeaconNodeHttpClient {
         &self,
         slot: Option<Slot>,
         parent_root: Option<Hash256>,
-    ) -> Result<Option<GenericResponse<Vec<BlockHeaderData>>>, Error> {
+    ) -> Result<Option<ExecutionOptimisticResponse<Vec<BlockHeaderData>>>, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
@@ -564,7 +685,7 @@ impl BeaconNodeHttpClient {
     pub async fn get_beacon_headers_block_id(
         &self,
         block_id: BlockId,
-    ) -> Result<Option<GenericResponse<BlockHeaderData>>, Error> {
+    ) -> Result<Option<ExecutionOptimisticResponse<BlockHeaderData>>, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
@@ -576,6 +697,25 @@ impl BeaconNodeHttpClient {
         self.get_opt(path).await
     }
 
+    /// `GET beacon/light_client/bootstrap/{block_root}`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_light_client_bootstrap<T: EthSpec>(
+        &self,
+        block_root: Hash256,
+    ) -> Result<Option<ForkVersionedResponse<LightClientBootstrap<T>>>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("light_client")
+            .push("bootstrap")
+            .push(&format!("{:?}", block_root));
+
+        self.get_fork_versioned_opt(path).await
+    }
+
     /// `POST beacon/blocks`
     ///
     /// Returns `Ok(None)` on a 404 error.
@@ -596,6 +736,28 @@ impl BeaconNodeHttpClient {
         Ok(())
     }
 
+    /// `POST beacon/blocks?broadcast_validation`
+    pub async fn post_beacon_blocks_with_validation<T: EthSpec, Payload: ExecPayload<T>>(
+        &self,
+        block: &SignedBeaconBlock<T, Payload>,
+        validation_level: BroadcastValidation,
+    ) -> Result<(), Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("blocks");
+
+        path.query_pairs_mut()
+            .append_pair("broadcast_validation", &validation_level.to_string());
+
+        self.post_with_timeout(path, block, self.timeouts.proposal)
+            .await?;
+
+        Ok(())
+    }
+
     /// `POST beacon/blinded_blocks`
     ///
     /// Returns `Ok(None)` on a 404 error.
@@ -616,6 +778,28 @@ impl BeaconNodeHttpClient {
         Ok(())
     }
 
+    /// `POST beacon/blinded_blocks?broadcast_validation`
+    pub async fn post_beacon_blinded_blocks_with_validation<T: EthSpec, Payload: ExecPayload<T>>(
+        &self,
+        block: &SignedBeaconBlock<T, Payload>,
+        validation_level: BroadcastValidation,
+    ) -> Result<(), Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("blinded_blocks");
+
+        path.query_pairs_mut()
+            .append_pair("broadcast_validation", &validation_level.to_string());
+
+        self.post_with_timeout(path, block, self.timeouts.proposal)
+            .await?;
+
+        Ok(())
+    }
+
     /// Path for `v2/beacon/blocks`
     pub fn get_beacon_blocks_path(&self, block_id: BlockId) -> Result<Url, Error> {
         let mut path = self.eth_path(V2)?;
@@ -635,29 +819,7 @@ impl BeaconNodeHttpClient {
         block_id: BlockId,
     ) -> Result<Option<ForkVersionedResponse<SignedBeaconBlock<T>>>, Error> {
         let path = self.get_beacon_blocks_path(block_id)?;
-        let response = match self.get_response(path, |b| b).await.optional()? {
-            Some(res) => res,
-            None => return Ok(None),
-        };
-
-        // If present, use the fork provided in the headers to decode the block. Gracefully handle
-        // missing and malformed fork names by falling back to regular deserialisation.
-        let (block, version) = match response.fork_name_from_header() {
-            Ok(Some(fork_name)) => {
-                map_fork_name_with!(fork_name, SignedBeaconBlock, {
-                    let ForkVersionedResponse { version, data } = response.json().await?;
-                    (data, version)
-                })
-            }
-            Ok(None) | Err(_) => {
-                let ForkVersionedResponse { version, data } = response.json().await?;
-                (data, version)
-            }
-        };
-        Ok(Some(ForkVersionedResponse {
-            version,
-            data: block,
-        }))
+        self.get_fork_versioned_opt(path).await
     }
 
     /// `GET v1/beacon/blocks` (LEGACY)
@@ -675,7 +837,7 @@ impl BeaconNodeHttpClient {
             .push("blocks")
             .push(&block_id.to_string());
 
-        self.get_opt(path).await
+        self.get_fork_versioned_opt(path).await
     }
 
     /// `GET beacon/blocks` as SSZ
@@ -700,7 +862,7 @@ impl BeaconNodeHttpClient {
     pub async fn get_beacon_blocks_root(
         &self,
         block_id: BlockId,
-    ) -> Result<Option<GenericResponse<RootData>>, Error> {
+    ) -> Result<Option<ExecutionOptimisticResponse<RootData>>, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
@@ -719,7 +881,7 @@ impl BeaconNodeHttpClient {
     pub async fn get_beacon_blocks_attestations<T: EthSpec>(
         &self,
         block_id: BlockId,
-    ) -> Result<Option<GenericResponse<Vec<Attestation<T>>>>, Error> {
+    ) -> Result<Option<ExecutionOptimisticResponse<Vec<Attestation<T>>>>, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
@@ -877,6 +1039,39 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `POST beacon/pool/bls_to_execution_changes`
+    pub async fn post_beacon_pool_bls_to_execution_changes(
+        &self,
+        address_changes: &[SignedBlsToExecutionChange],
+    ) -> Result<(), Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("pool")
+            .push("bls_to_execution_changes");
+
+        self.post(path, &address_changes).await?;
+
+        Ok(())
+    }
+
+    /// `GET beacon/pool/bls_to_execution_changes`
+    pub async fn get_beacon_pool_bls_to_execution_changes(
+        &self,
+    ) -> Result<GenericResponse<Vec<SignedBlsToExecutionChange>>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("pool")
+            .push("bls_to_execution_changes");
+
+        self.get(path).await
+    }
+
     /// `POST beacon/pool/sync_committees`
     pub async fn post_beacon_pool_sync_committee_signatures(
         &self,
@@ -929,6 +1124,31 @@ impl BeaconNodeHttpClient {
         Ok(())
     }
 
+    /// `GET builder/states/{state_id}/expected_withdrawals?proposal_slot`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_expected_withdrawals(
+        &self,
+        state_id: StateId,
+        proposal_slot: Option<Slot>,
+    ) -> Result<Option<GenericResponse<Vec<Withdrawal>>>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("builder")
+            .push("states")
+            .push(&state_id.to_string())
+            .push("expected_withdrawals");
+
+        if let Some(slot) = proposal_slot {
+            path.query_pairs_mut()
+                .append_pair("proposal_slot", &slot.to_string());
+        }
+
+        self.get_opt(path).await
+    }
+
     /// `GET config/fork_schedule`
     pub async fn get_config_fork_schedule(&self) -> Result<GenericResponse<Vec<Fork>>, Error> {
         let mut path = self.eth_path(V1)?;
@@ -1101,7 +1321,7 @@ impl BeaconNodeHttpClient {
         state_id: StateId,
     ) -> Result<Option<ForkVersionedResponse<BeaconState<T>>>, Error> {
         let path = self.get_debug_beacon_states_path(state_id)?;
-        self.get_opt(path).await
+        self.get_fork_versioned_opt(path).await
     }
 
     /// `GET v1/debug/beacon/states/{state_id}` (LEGACY)
@@ -1118,7 +1338,7 @@ impl BeaconNodeHttpClient {
             .push("states")
             .push(&state_id.to_string());
 
-        self.get_opt(path).await
+        self.get_fork_versioned_opt(path).await
     }
 
     /// `GET debug/beacon/states/{state_id}`
@@ -1211,7 +1431,7 @@ impl BeaconNodeHttpClient {
                 .append_pair("verify_randao", &verify_randao.to_string());
         }
 
-        self.get(path).await
+        self.get_fork_versioned(path).await
     }
 
     /// `GET v2/validator/blinded_blocks/{slot}`
@@ -1264,7 +1484,7 @@ impl BeaconNodeHttpClient {
                 .append_pair("verify_randao", &verify_randao.to_string());
         }
 
-        self.get(path).await
+        self.get_fork_versioned(path).await
     }
 
     /// `GET validator/attestation_data?slot,committee_index`
@@ -1505,3 +1725,16 @@ async fn ok_or_error(response: Response) -> Result<Response, Error> {
         Err(Error::StatusCode(status))
     }
 }
+
+/// Deserialize a `ForkVersionedResponse` from the JSON body of `response`.
+///
+/// If the body doesn't state its `version`, the fork provided in the `Eth-Consensus-Version`
+/// header is used instead. Missing and malformed headers are ignored, falling back to trying each
+/// fork in turn.
+async fn fork_versioned_from_response<T: ForkVersionDeserialize>(
+    response: Response,
+) -> Result<ForkVersionedResponse<T>, Error> {
+    let fork_hint = response.fork_name_from_header().ok().flatten();
+    let value = response.json().await?;
+    ForkVersionedResponse::from_json_value(value, fork_hint).map_err(Error::InvalidJson)
+}
@@ -3,10 +3,15 @@
 mod attestation_performance;
 mod block_packing_efficiency;
 mod block_rewards;
+mod fork_readiness;
+mod slot_delays;
 
 use crate::{
     ok_or_error,
-    types::{BeaconState, ChainSpec, Epoch, EthSpec, GenericResponse, ValidatorId},
+    types::{
+        Attestation, BeaconState, BlockId, ChainSpec, Epoch, EthSpec, GenericResponse,
+        PeerDirection, PeerState, PublicKeyBytes, Slot, ValidatorId,
+    },
     BeaconNodeHttpClient, DepositData, Error, Eth1Data, Hash256, StateId, StatusCode,
 };
 use proto_array::core::ProtoArray;
@@ -14,7 +19,9 @@ use reqwest::IntoUrl;
 use serde::{Deserialize, Serialize};
 use ssz::four_byte_option_impl;
 use ssz_derive::{Decode, Encode};
-use store::{AnchorInfo, Split, StoreConfig};
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use store::{AnchorInfo, BlobInfo, Split, StoreConfig};
 
 pub use attestation_performance::{
     AttestationPerformance, AttestationPerformanceQuery, AttestationPerformanceStatistics,
@@ -23,7 +30,13 @@ pub use block_packing_efficiency::{
     BlockPackingEfficiency, BlockPackingEfficiencyQuery, ProposerInfo, UniqueAttestation,
 };
 pub use block_rewards::{AttestationRewards, BlockReward, BlockRewardMeta, BlockRewardsQuery};
-pub use lighthouse_network::{types::SyncState, PeerInfo};
+pub use fork_readiness::{ForkReadiness, ReadinessCheck, ReadinessStatus};
+pub use lighthouse_network::{
+    types::{BeaconProcessorQueueStatus, BeaconProcessorQueues, SyncLookups, SyncState},
+    ClockSkewStatus, Enr, GossipsubScores, GossipsubScoringOverrides, PeerDiversity, PeerInfo,
+    PortMappingMethod, PortMappingStatus,
+};
+pub use slot_delays::{SlotDelays, SlotDelaysQuery};
 
 // Define "legacy" implementations of `Option<T>` which use four bytes for encoding the union
 // selector.
@@ -39,6 +52,199 @@ pub struct Peer<T: EthSpec> {
     pub peer_id: String,
     /// The PeerInfo associated with the peer.
     pub peer_info: PeerInfo<T>,
+    /// The custom fields of the peer's ENR, such as those added by its operator to tag it.
+    pub enr_custom_fields: Vec<EnrField>,
+}
+
+/// Detailed information about a peer, returned by `lighthouse/peers/verbose`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerbosePeerData {
+    pub peer_id: String,
+    pub enr: Option<String>,
+    pub state: PeerState,
+    /// `None` if we have never been connected to the peer.
+    pub direction: Option<PeerDirection>,
+    pub is_trusted: bool,
+    /// The overall score used by the peer manager.
+    pub score: f64,
+    /// The component of the score derived from Lighthouse's own reports of the peer's behaviour.
+    pub lighthouse_score: f64,
+    /// The most recent score reported by gossipsub.
+    pub gossipsub_score: f64,
+    /// The number of penalties applied to the peer.
+    pub penalties: PeerPenalties,
+    pub client: PeerClient,
+    /// The attestation subnets the peer is subscribed to.
+    pub attestation_subnets: Vec<u64>,
+    /// The sync committee subnets the peer is subscribed to.
+    pub sync_committee_subnets: Vec<u64>,
+    /// The head slot from the peer's last status message.
+    pub head_slot: Option<Slot>,
+    /// The finalized epoch from the peer's last status message.
+    pub finalized_epoch: Option<Epoch>,
+    /// The distance in slots between the peer's head and our own. Positive if the peer is ahead.
+    pub sync_distance: Option<i64>,
+}
+
+/// The number of penalties of each severity applied to a peer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PeerPenalties {
+    pub fatal: u64,
+    pub low_tolerance: u64,
+    pub mid_tolerance: u64,
+    pub high_tolerance: u64,
+}
+
+/// The client a peer identifies as, derived from its identify agent string.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeerClient {
+    pub kind: String,
+    pub version: String,
+    pub os_version: String,
+    pub agent_string: Option<String>,
+}
+
+/// An attestation held in the operation pool, returned by `lighthouse/op_pool/attestations`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "T: EthSpec")]
+pub struct OpPoolAttestation<T: EthSpec> {
+    pub attestation: Attestation<T>,
+    /// The number of validators whose votes are aggregated into the attestation.
+    pub num_attesters: usize,
+    /// True if the attestation would be packed into a block proposed on the head right now.
+    pub would_pack: bool,
+}
+
+/// A custom key-value pair to insert into the node's ENR.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnrField {
+    pub key: String,
+    #[serde(with = "eth2_serde_utils::hex_vec")]
+    pub value: Vec<u8>,
+}
+
+/// The request body of `lighthouse/enr`. Fields which are `None` are left unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnrUpdateRequest {
+    #[serde(default)]
+    pub ip: Option<IpAddr>,
+    #[serde(default)]
+    pub tcp_port: Option<u16>,
+    #[serde(default)]
+    pub udp_port: Option<u16>,
+    #[serde(default)]
+    pub custom_fields: Vec<EnrField>,
+}
+
+/// The request body of `lighthouse/validator_monitor`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValidatorMonitorRegistrationRequest {
+    pub pubkeys: Vec<PublicKeyBytes>,
+}
+
+/// The response of `lighthouse/validator_monitor`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorMonitorRegistration {
+    /// The number of validators in the request which weren't already monitored.
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub newly_monitored: u64,
+    /// The total number of validators monitored by the beacon node.
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub num_monitored: u64,
+}
+
+/// The recent history of a validator monitored by the beacon node, as returned by
+/// `GET lighthouse/validator_monitor`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitoredValidatorSummary {
+    pub pubkey: PublicKeyBytes,
+    /// The validator index, if the validator is known to the beacon chain.
+    pub index: Option<u64>,
+    /// Summaries of the most recent epochs, in ascending order of epoch.
+    pub epochs: Vec<ValidatorMonitorEpochSummary>,
+}
+
+/// The messages observed from a monitored validator during an epoch, and the duties it missed.
+///
+/// The `attestation_*` on-chain fields are `None` until the epoch has been processed, or if the
+/// validator wasn't active.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorMonitorEpochSummary {
+    pub epoch: Epoch,
+    pub attestations: u64,
+    pub attestation_min_delay_ms: Option<u64>,
+    pub attestation_aggregate_inclusions: u64,
+    pub attestation_block_inclusions: u64,
+    pub attestation_min_block_inclusion_distance: Option<Slot>,
+    pub attestation_included_on_chain: Option<bool>,
+    pub attestation_matched_head: Option<bool>,
+    pub attestation_matched_target: Option<bool>,
+    pub blocks: u64,
+    pub block_min_delay_ms: Option<u64>,
+    pub missed_blocks: u64,
+    pub aggregates: u64,
+    pub aggregate_min_delay_ms: Option<u64>,
+    pub sync_committee_messages: u64,
+    pub sync_committee_message_min_delay_ms: Option<u64>,
+    pub sync_signature_block_inclusions: u64,
+    pub sync_signature_block_misses: u64,
+    pub sync_signature_contribution_inclusions: u64,
+    pub sync_contributions: u64,
+    pub sync_contribution_min_delay_ms: Option<u64>,
+    pub exits: u64,
+    pub proposer_slashings: u64,
+    pub attester_slashings: u64,
+}
+
+/// The levels of the terminal logs of a beacon node, used by the `lighthouse/logging` endpoints.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogLevelsConfig {
+    /// The level of modules without an override, e.g. `info`.
+    pub debug_level: String,
+    /// Levels for the modules whose path starts with each key, e.g. `network` => `debug`.
+    #[serde(default)]
+    pub overrides: BTreeMap<String, String>,
+}
+
+/// Query parameters for the `lighthouse/beacon/block_roots` endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockRootsQuery {
+    /// The first slot of the range (inclusive).
+    pub start_slot: Slot,
+    /// The number of slots in the range.
+    pub count: u64,
+}
+
+/// The root of the canonical block at `slot`, returned by `lighthouse/beacon/block_roots`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlotBlockRoot {
+    pub slot: Slot,
+    pub root: Hash256,
+}
+
+/// Query parameters for the `lighthouse/beacon/proofs` endpoints.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MerkleProofQuery {
+    /// The generalized index of the node to prove.
+    pub gindex: u64,
+}
+
+/// A Merkle proof of a node of a state or block, returned by the `lighthouse/beacon/proofs`
+/// endpoints.
+///
+/// The proof may be checked with `types::generalized_index::verify_generalized_index_proof`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    /// The tree hash root of the state or block.
+    pub root: Hash256,
+    /// The generalized index of `leaf`.
+    pub gindex: u64,
+    /// The tree hash root of the node at `gindex`.
+    pub leaf: Hash256,
+    /// The sibling nodes on the path from `leaf` to `root`, from the bottom up.
+    pub proof: Vec<Hash256>,
 }
 
 /// The results of validators voting during an epoch.
@@ -285,6 +491,81 @@ impl Health {
     }
 }
 
+/// Resource usage of the beacon node, as returned by `lighthouse/system`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SystemResources {
+    pub process: ProcessResources,
+    pub database: DatabaseResources,
+}
+
+/// Resources used by the beacon node process.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProcessResources {
+    /// The pid of this process.
+    pub pid: u32,
+    /// Number of cpu seconds consumed by this process.
+    pub cpu_seconds_total: u64,
+    /// The total resident memory used by this process.
+    pub resident_set_size: u64,
+    /// The number of file descriptors held open by this process.
+    pub open_file_descriptors: u64,
+}
+
+impl ProcessResources {
+    #[cfg(not(target_os = "linux"))]
+    pub fn observe() -> Result<Self, String> {
+        Err("Process resources are only available on Linux".into())
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn observe() -> Result<Self, String> {
+        let process =
+            Process::current().map_err(|e| format!("Unable to get current process: {:?}", e))?;
+
+        let process_mem = process
+            .memory_info()
+            .map_err(|e| format!("Unable to get process memory info: {:?}", e))?;
+
+        let process_times = process
+            .cpu_times()
+            .map_err(|e| format!("Unable to get process cpu times : {:?}", e))?;
+
+        let open_file_descriptors = std::fs::read_dir("/proc/self/fd")
+            .map_err(|e| format!("Unable to read open file descriptors: {:?}", e))?
+            .count() as u64;
+
+        Ok(Self {
+            pid: process.pid(),
+            cpu_seconds_total: process_times.busy().as_secs(),
+            resident_set_size: process_mem.rss(),
+            open_file_descriptors,
+        })
+    }
+}
+
+/// Disk usage and compaction statistics of the beacon node database.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DatabaseResources {
+    /// The size of the hot database on disk, if it is stored on disk.
+    pub hot_db_bytes: Option<u64>,
+    /// The size of the freezer database on disk, if it is stored on disk.
+    pub freezer_db_bytes: Option<u64>,
+    pub compaction: CompactionData,
+}
+
+/// Statistics about database compaction.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CompactionData {
+    /// Whether the database is compacted after pruning.
+    pub compact_on_prune: bool,
+    /// The UNIX timestamp (in seconds) of the last compaction after pruning.
+    pub last_compaction_timestamp: Option<u64>,
+    /// The number of compactions completed since the node started.
+    pub compactions_since_start: u64,
+    /// The duration of the most recent compaction, in milliseconds.
+    pub last_compaction_duration_ms: Option<u64>,
+}
+
 /// Indicates how up-to-date the Eth1 caches are.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Eth1SyncStatusData {
@@ -337,6 +618,61 @@ pub struct DatabaseInfo {
     pub config: StoreConfig,
     pub split: Split,
     pub anchor: Option<AnchorInfo>,
+    pub block_history: BlockHistoryInfo,
+    /// The range of blob sidecars held by the database, and the space they use.
+    pub blob_info: BlobInfo,
+}
+
+/// The range of blocks held by the database, and the progress of backfill sync towards filling it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockHistoryInfo {
+    /// The slot of the checkpoint state that the node was synced from, or genesis.
+    pub anchor_slot: Slot,
+    /// The slot from which blocks are available (>=).
+    pub oldest_block_slot: Slot,
+    /// The slot to which blocks will be backfilled.
+    pub backfill_target_slot: Slot,
+    /// Whether all blocks back to `backfill_target_slot` are present.
+    pub backfill_complete: bool,
+    /// The percentage of the blocks between the anchor and the target which have been backfilled.
+    pub backfill_progress_percent: f64,
+    /// Whether blocks are available for the full `MIN_EPOCHS_FOR_BLOCK_REQUESTS`, so that every
+    /// block range request from peers can be served.
+    pub min_block_history_available: bool,
+}
+
+/// A database compaction or pruning job triggered via the HTTP API.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DatabaseJob {
+    pub id: u64,
+    pub kind: DatabaseJobKind,
+    pub status: DatabaseJobStatus,
+    /// The number of items (payloads or states) processed so far.
+    pub items_processed: u64,
+    /// The total number of items to process, once known.
+    pub items_total: Option<u64>,
+    /// The UNIX timestamp (in seconds) at which the job started.
+    pub started_at: u64,
+    /// The UNIX timestamp (in seconds) at which the job completed or failed.
+    pub finished_at: Option<u64>,
+    /// The reason the job failed, if it did.
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DatabaseJobKind {
+    Compact,
+    PrunePayloads,
+    PruneStates,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DatabaseJobStatus {
+    Running,
+    Completed,
+    Failed,
 }
 
 impl BeaconNodeHttpClient {
@@ -373,6 +709,71 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/system`
+    pub async fn get_lighthouse_system(&self) -> Result<GenericResponse<SystemResources>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("system");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/naive_aggregation_pool?slot,committee_index`
+    pub async fn get_lighthouse_naive_aggregation_pool<T: EthSpec>(
+        &self,
+        slot: Option<Slot>,
+        committee_index: Option<u64>,
+    ) -> Result<GenericResponse<Vec<Attestation<T>>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("naive_aggregation_pool");
+
+        if let Some(slot) = slot {
+            path.query_pairs_mut()
+                .append_pair("slot", &slot.to_string());
+        }
+
+        if let Some(index) = committee_index {
+            path.query_pairs_mut()
+                .append_pair("committee_index", &index.to_string());
+        }
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/op_pool/attestations?slot,committee_index`
+    pub async fn get_lighthouse_op_pool_attestations<T: EthSpec>(
+        &self,
+        slot: Option<Slot>,
+        committee_index: Option<u64>,
+    ) -> Result<GenericResponse<Vec<OpPoolAttestation<T>>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("op_pool")
+            .push("attestations");
+
+        if let Some(slot) = slot {
+            path.query_pairs_mut()
+                .append_pair("slot", &slot.to_string());
+        }
+
+        if let Some(index) = committee_index {
+            path.query_pairs_mut()
+                .append_pair("committee_index", &index.to_string());
+        }
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/syncing`
     pub async fn get_lighthouse_syncing(&self) -> Result<GenericResponse<SyncState>, Error> {
         let mut path = self.server.full.clone();
@@ -385,6 +786,48 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/sync/lookups`
+    pub async fn get_lighthouse_sync_lookups(&self) -> Result<GenericResponse<SyncLookups>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("sync")
+            .push("lookups");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/beacon_processor/queues`
+    pub async fn get_lighthouse_beacon_processor_queues(
+        &self,
+    ) -> Result<GenericResponse<BeaconProcessorQueues>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon_processor")
+            .push("queues");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/ready`
+    pub async fn get_lighthouse_ready(
+        &self,
+    ) -> Result<GenericResponse<Option<ForkReadiness>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("ready");
+
+        self.get(path).await
+    }
+
     /*
      * Note:
      *
@@ -393,6 +836,81 @@ impl BeaconNodeHttpClient {
      * fairly simply achieved, if desired.
      */
 
+    /// `GET lighthouse/peers/verbose`
+    pub async fn get_lighthouse_peers_verbose(
+        &self,
+    ) -> Result<GenericResponse<Vec<VerbosePeerData>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("peers")
+            .push("verbose");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/network/port_mappings`
+    pub async fn get_lighthouse_network_port_mappings(
+        &self,
+    ) -> Result<GenericResponse<PortMappingStatus>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network")
+            .push("port_mappings");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/network/clock_skew`
+    pub async fn get_lighthouse_network_clock_skew(
+        &self,
+    ) -> Result<GenericResponse<ClockSkewStatus>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network")
+            .push("clock_skew");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/peers/diversity`
+    pub async fn get_lighthouse_peers_diversity(
+        &self,
+    ) -> Result<GenericResponse<PeerDiversity>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("peers")
+            .push("diversity");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/gossipsub/scores`
+    pub async fn get_lighthouse_gossipsub_scores(
+        &self,
+    ) -> Result<GenericResponse<GossipsubScores>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("gossipsub")
+            .push("scores");
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/proto_array`
     pub async fn get_lighthouse_proto_array(&self) -> Result<GenericResponse<ProtoArray>, Error> {
         let mut path = self.server.full.clone();
@@ -507,6 +1025,71 @@ impl BeaconNodeHttpClient {
             .transpose()
     }
 
+    /// `GET lighthouse/beacon/block_roots?start_slot,count`
+    pub async fn get_lighthouse_beacon_block_roots(
+        &self,
+        start_slot: Slot,
+        count: u64,
+    ) -> Result<GenericResponse<Vec<SlotBlockRoot>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon")
+            .push("block_roots");
+
+        path.query_pairs_mut()
+            .append_pair("start_slot", &start_slot.to_string())
+            .append_pair("count", &count.to_string());
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/beacon/proofs/states/{state_id}?gindex`
+    pub async fn get_lighthouse_beacon_proofs_state(
+        &self,
+        state_id: StateId,
+        gindex: u64,
+    ) -> Result<GenericResponse<MerkleProof>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon")
+            .push("proofs")
+            .push("states")
+            .push(&state_id.to_string());
+
+        path.query_pairs_mut()
+            .append_pair("gindex", &gindex.to_string());
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/beacon/proofs/blocks/{block_id}?gindex`
+    pub async fn get_lighthouse_beacon_proofs_block(
+        &self,
+        block_id: BlockId,
+        gindex: u64,
+    ) -> Result<GenericResponse<MerkleProof>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon")
+            .push("proofs")
+            .push("blocks")
+            .push(&block_id.to_string());
+
+        path.query_pairs_mut()
+            .append_pair("gindex", &gindex.to_string());
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/staking`
     pub async fn get_lighthouse_staking(&self) -> Result<bool, Error> {
         let mut path = self.server.full.clone();
@@ -544,4 +1127,202 @@ impl BeaconNodeHttpClient {
 
         self.post_with_response(path, &()).await
     }
+
+    /// `POST lighthouse/database/compact`
+    pub async fn post_lighthouse_database_compact(
+        &self,
+    ) -> Result<GenericResponse<DatabaseJob>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("database")
+            .push("compact");
+
+        self.post_with_response(path, &()).await
+    }
+
+    /// `POST lighthouse/database/prune_payloads`
+    pub async fn post_lighthouse_database_prune_payloads(
+        &self,
+    ) -> Result<GenericResponse<DatabaseJob>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("database")
+            .push("prune_payloads");
+
+        self.post_with_response(path, &()).await
+    }
+
+    /// `POST lighthouse/database/prune_states`
+    pub async fn post_lighthouse_database_prune_states(
+        &self,
+    ) -> Result<GenericResponse<DatabaseJob>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("database")
+            .push("prune_states");
+
+        self.post_with_response(path, &()).await
+    }
+
+    /// `GET lighthouse/database/jobs`
+    pub async fn get_lighthouse_database_jobs(
+        &self,
+    ) -> Result<GenericResponse<Vec<DatabaseJob>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("database")
+            .push("jobs");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/database/jobs/{id}`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_lighthouse_database_job(
+        &self,
+        id: u64,
+    ) -> Result<Option<GenericResponse<DatabaseJob>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("database")
+            .push("jobs")
+            .push(&id.to_string());
+
+        self.get_opt(path).await
+    }
+
+    /// `POST lighthouse/enr`
+    pub async fn post_lighthouse_enr(
+        &self,
+        request: &EnrUpdateRequest,
+    ) -> Result<GenericResponse<Enr>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("enr");
+
+        self.post_with_response(path, request).await
+    }
+
+    /// `GET lighthouse/validator_monitor`
+    pub async fn get_lighthouse_validator_monitor(
+        &self,
+    ) -> Result<GenericResponse<Vec<MonitoredValidatorSummary>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validator_monitor");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/validator_monitor`
+    pub async fn post_lighthouse_validator_monitor(
+        &self,
+        pubkeys: &[PublicKeyBytes],
+    ) -> Result<GenericResponse<ValidatorMonitorRegistration>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validator_monitor");
+
+        self.post_with_response(
+            path,
+            &ValidatorMonitorRegistrationRequest {
+                pubkeys: pubkeys.to_vec(),
+            },
+        )
+        .await
+    }
+
+    /// `GET lighthouse/logging`
+    pub async fn get_lighthouse_logging(&self) -> Result<GenericResponse<LogLevelsConfig>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("logging");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/logging`
+    pub async fn post_lighthouse_logging(
+        &self,
+        levels: &LogLevelsConfig,
+    ) -> Result<GenericResponse<LogLevelsConfig>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("logging");
+
+        self.post_with_response(path, levels).await
+    }
+
+    /// `GET lighthouse/analysis/slot_delays?start_slot,end_slot`
+    pub async fn get_lighthouse_analysis_slot_delays(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+    ) -> Result<Vec<SlotDelays>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("analysis")
+            .push("slot_delays");
+
+        path.query_pairs_mut()
+            .append_pair("start_slot", &start_slot.to_string())
+            .append_pair("end_slot", &end_slot.to_string());
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/analysis/block_packing?start_epoch,end_epoch`
+    pub async fn get_lighthouse_analysis_block_packing(
+        &self,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<Vec<BlockPackingEfficiency>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("analysis")
+            .push("block_packing");
+
+        path.query_pairs_mut()
+            .append_pair("start_epoch", &start_epoch.to_string())
+            .append_pair("end_epoch", &end_epoch.to_string());
+
+        self.get(path).await
+    }
 }
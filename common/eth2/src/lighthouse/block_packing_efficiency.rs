@@ -24,6 +24,10 @@ pub struct BlockPackingEfficiency {
     pub proposer_info: ProposerInfo,
     pub available_attestations: usize,
     pub included_attestations: usize,
+    /// Upper bound on the number of available attestations which could have been included,
+    /// given that each aggregate in a block covers a single committee.
+    #[serde(default)]
+    pub max_includable_attestations: usize,
     pub prior_skip_slots: u64,
 }
 
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use types::{Epoch, ForkName};
+
+/// Whether the node is ready for the next scheduled fork.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ForkReadiness {
+    pub fork: ForkName,
+    pub fork_epoch: Epoch,
+    /// The number of seconds until the fork, as measured by the local clock.
+    pub seconds_until_fork: u64,
+    /// `true` if none of the checks found the node to be unready.
+    pub ready: bool,
+    pub checks: Vec<ReadinessCheck>,
+}
+
+impl ForkReadiness {
+    /// Returns the checks which found the node to be unready.
+    pub fn failures(&self) -> impl Iterator<Item = &ReadinessCheck> {
+        self.checks
+            .iter()
+            .filter(|check| check.status == ReadinessStatus::NotReady)
+    }
+}
+
+/// One of the checks performed to determine fork readiness.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ReadinessCheck {
+    pub name: String,
+    pub status: ReadinessStatus,
+    /// A description of the result of the check.
+    pub info: String,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadinessStatus {
+    Ready,
+    NotReady,
+    /// The check couldn't be completed, e.g. because the execution engine doesn't support the
+    /// required queries.
+    Unknown,
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use types::{Hash256, Slot};
+
+/// The timings of a recent slot as seen by the beacon node, in milliseconds since the start of
+/// the slot.
+///
+/// A late block is one which was set as head after the first attestation of its slot was produced,
+/// in which case `attested_to_parent` is `true`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SlotDelays {
+    pub slot: Slot,
+    /// The block of the slot, if one was seen.
+    pub block_root: Option<Hash256>,
+    /// When the block was first received, from gossip or RPC.
+    pub observed_delay_ms: Option<u64>,
+    pub imported_delay_ms: Option<u64>,
+    pub set_as_head_delay_ms: Option<u64>,
+    /// When the first attestation of the slot was produced.
+    pub attestation_delay_ms: Option<u64>,
+    /// Whether the first attestation of the slot voted for an earlier block than the block of the
+    /// slot. `None` if there was no block or no attestation.
+    pub attested_to_parent: Option<bool>,
+}
+
+/// Query parameters for the `/lighthouse/analysis/slot_delays` endpoint.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SlotDelaysQuery {
+    pub start_slot: Slot,
+    pub end_slot: Slot,
+}
@@ -204,6 +204,28 @@ impl<T: Serialize + serde::de::DeserializeOwned> From<T> for GenericResponse<T>
     }
 }
 
+impl<T: Serialize + serde::de::DeserializeOwned> GenericResponse<T> {
+    pub fn add_execution_optimistic(
+        self,
+        execution_optimistic: bool,
+    ) -> ExecutionOptimisticResponse<T> {
+        ExecutionOptimisticResponse {
+            execution_optimistic: Some(execution_optimistic),
+            data: self.data,
+        }
+    }
+}
+
+/// A response which indicates whether its `data` was derived from a block which has not yet been
+/// verified by the execution layer.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(bound = "T: Serialize + serde::de::DeserializeOwned")]
+pub struct ExecutionOptimisticResponse<T: Serialize + serde::de::DeserializeOwned> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_optimistic: Option<bool>,
+    pub data: T,
+}
+
 #[derive(Debug, PartialEq, Clone, Serialize)]
 #[serde(bound = "T: Serialize")]
 pub struct GenericResponseRef<'a, T: Serialize> {
@@ -216,13 +238,6 @@ impl<'a, T: Serialize> From<&'a T> for GenericResponseRef<'a, T> {
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-pub struct ForkVersionedResponse<T> {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub version: Option<ForkName>,
-    pub data: T,
-}
-
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct RootData {
     pub root: Hash256,
@@ -241,12 +256,31 @@ pub struct FinalityCheckpointsData {
     pub finalized: Checkpoint,
 }
 
+/// A validator public key or index.
+///
+/// Serialized as a string in the same format used in paths and query parameters, as required for
+/// validator ids in request bodies.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub enum ValidatorId {
     PublicKey(PublicKeyBytes),
     Index(u64),
 }
 
+impl TryFrom<String> for ValidatorId {
+    type Error = String;
+
+    fn try_from(string: String) -> Result<Self, Self::Error> {
+        Self::from_str(&string)
+    }
+}
+
+impl From<ValidatorId> for String {
+    fn from(id: ValidatorId) -> String {
+        id.to_string()
+    }
+}
+
 impl FromStr for ValidatorId {
     type Err = String;
 
@@ -440,6 +474,20 @@ pub struct ValidatorsQuery {
     pub id: Option<Vec<ValidatorId>>,
     #[serde(default, deserialize_with = "option_query_vec")]
     pub status: Option<Vec<ValidatorStatus>>,
+    /// Number of matching validators to skip.
+    pub offset: Option<u64>,
+    /// Maximum number of matching validators to return.
+    pub limit: Option<u64>,
+}
+
+/// Request body for `POST beacon/states/{state_id}/validators`.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValidatorsRequestBody {
+    #[serde(default)]
+    pub ids: Option<Vec<ValidatorId>>,
+    #[serde(default)]
+    pub statuses: Option<Vec<ValidatorStatus>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -465,6 +513,48 @@ pub struct SyncSubcommittee {
     pub indices: Vec<u64>,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ExpectedWithdrawalsQuery {
+    pub proposal_slot: Option<Slot>,
+}
+
+/// The level of validation a beacon node performs on a published block before broadcasting it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BroadcastValidation {
+    /// Only the gossip checks are performed before broadcasting.
+    Gossip,
+    /// The block must pass full consensus validation before it is broadcast.
+    Consensus,
+    /// As `Consensus`, and the proposer must not have produced a conflicting block for the slot.
+    ConsensusAndEquivocation,
+}
+
+impl Default for BroadcastValidation {
+    fn default() -> Self {
+        BroadcastValidation::Gossip
+    }
+}
+
+impl fmt::Display for BroadcastValidation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BroadcastValidation::Gossip => write!(f, "gossip"),
+            BroadcastValidation::Consensus => write!(f, "consensus"),
+            BroadcastValidation::ConsensusAndEquivocation => {
+                write!(f, "consensus_and_equivocation")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BroadcastValidationQuery {
+    #[serde(default)]
+    pub broadcast_validation: BroadcastValidation,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct HeadersQuery {
     pub slot: Option<Slot>,
@@ -495,6 +585,8 @@ pub struct DepositContractData {
 pub struct ChainHeadData {
     pub slot: Slot,
     pub root: Hash256,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_optimistic: Option<bool>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -522,6 +614,11 @@ pub struct VersionData {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SyncingData {
     pub is_syncing: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_optimistic: Option<bool>,
+    /// Whether the execution engine is unreachable, or `None` if none is configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub el_offline: Option<bool>,
     pub head_slot: Slot,
     pub sync_distance: Slot,
 }
@@ -825,6 +922,32 @@ pub struct SseChainReorg {
     pub epoch: Epoch,
 }
 
+/// Emitted when a re-org changes the dependent root of the attester or proposer duties for
+/// `epoch`, meaning that duties computed for it beforehand may no longer be valid.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct SseDutiesReorg {
+    pub epoch: Epoch,
+    pub old_dependent_root: Hash256,
+    pub new_dependent_root: Hash256,
+    pub slot: Slot,
+    pub new_head_block: Hash256,
+}
+
+/// A slashing found by the node's slasher, emitted once it has been verified against the head.
+///
+/// This is also the body of the requests sent to the slasher's webhooks.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+#[serde(
+    bound = "T: EthSpec",
+    tag = "kind",
+    content = "slashing",
+    rename_all = "snake_case"
+)]
+pub enum SseSlasherSlashing<T: EthSpec> {
+    Attester(Box<AttesterSlashing<T>>),
+    Proposer(ProposerSlashing),
+}
+
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
 pub struct SseLateHead {
     pub slot: Slot,
@@ -850,6 +973,9 @@ pub enum EventKind<T: EthSpec> {
     ChainReorg(SseChainReorg),
     ContributionAndProof(Box<SignedContributionAndProof<T>>),
     LateHead(SseLateHead),
+    AttesterDutiesReorg(SseDutiesReorg),
+    ProposerDutiesReorg(SseDutiesReorg),
+    SlasherSlashing(SseSlasherSlashing<T>),
     #[cfg(feature = "lighthouse")]
     BlockReward(BlockReward),
 }
@@ -865,6 +991,9 @@ impl<T: EthSpec> EventKind<T> {
             EventKind::ChainReorg(_) => "chain_reorg",
             EventKind::ContributionAndProof(_) => "contribution_and_proof",
             EventKind::LateHead(_) => "late_head",
+            EventKind::AttesterDutiesReorg(_) => "attester_duties_reorg",
+            EventKind::ProposerDutiesReorg(_) => "proposer_duties_reorg",
+            EventKind::SlasherSlashing(_) => "slasher_slashing",
             #[cfg(feature = "lighthouse")]
             EventKind::BlockReward(_) => "block_reward",
         }
@@ -909,6 +1038,21 @@ impl<T: EthSpec> EventKind<T> {
             "late_head" => Ok(EventKind::LateHead(serde_json::from_str(data).map_err(
                 |e| ServerError::InvalidServerSentEvent(format!("Late Head: {:?}", e)),
             )?)),
+            "attester_duties_reorg" => Ok(EventKind::AttesterDutiesReorg(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!("Attester Duties Reorg: {:?}", e))
+                })?,
+            )),
+            "proposer_duties_reorg" => Ok(EventKind::ProposerDutiesReorg(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!("Proposer Duties Reorg: {:?}", e))
+                })?,
+            )),
+            "slasher_slashing" => Ok(EventKind::SlasherSlashing(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!("Slasher Slashing: {:?}", e))
+                })?,
+            )),
             "voluntary_exit" => Ok(EventKind::VoluntaryExit(
                 serde_json::from_str(data).map_err(|e| {
                     ServerError::InvalidServerSentEvent(format!("Voluntary Exit: {:?}", e))
@@ -948,6 +1092,9 @@ pub enum EventTopic {
     ChainReorg,
     ContributionAndProof,
     LateHead,
+    AttesterDutiesReorg,
+    ProposerDutiesReorg,
+    SlasherSlashing,
     #[cfg(feature = "lighthouse")]
     BlockReward,
 }
@@ -965,6 +1112,9 @@ impl FromStr for EventTopic {
             "chain_reorg" => Ok(EventTopic::ChainReorg),
             "contribution_and_proof" => Ok(EventTopic::ContributionAndProof),
             "late_head" => Ok(EventTopic::LateHead),
+            "attester_duties_reorg" => Ok(EventTopic::AttesterDutiesReorg),
+            "proposer_duties_reorg" => Ok(EventTopic::ProposerDutiesReorg),
+            "slasher_slashing" => Ok(EventTopic::SlasherSlashing),
             #[cfg(feature = "lighthouse")]
             "block_reward" => Ok(EventTopic::BlockReward),
             _ => Err("event topic cannot be parsed.".to_string()),
@@ -983,6 +1133,9 @@ impl fmt::Display for EventTopic {
             EventTopic::ChainReorg => write!(f, "chain_reorg"),
             EventTopic::ContributionAndProof => write!(f, "contribution_and_proof"),
             EventTopic::LateHead => write!(f, "late_head"),
+            EventTopic::AttesterDutiesReorg => write!(f, "attester_duties_reorg"),
+            EventTopic::ProposerDutiesReorg => write!(f, "proposer_duties_reorg"),
+            EventTopic::SlasherSlashing => write!(f, "slasher_slashing"),
             #[cfg(feature = "lighthouse")]
             EventTopic::BlockReward => write!(f, "block_reward"),
         }
@@ -1070,6 +1223,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validator_id_serde() {
+        let ids: Vec<ValidatorId> = serde_json::from_str(r#"["1", "0x93247f2209abcacf57b75a51dafae777f9dd38bc7053d1af526f220a7489a6d3a2753e5f3e8b1cfe39b56f43611df74a"]"#).unwrap();
+        assert_eq!(ids[0], ValidatorId::Index(1));
+        assert!(matches!(ids[1], ValidatorId::PublicKey(_)));
+        assert_eq!(
+            serde_json::from_str::<Vec<ValidatorId>>(&serde_json::to_string(&ids).unwrap())
+                .unwrap(),
+            ids
+        );
+        assert!(serde_json::from_str::<ValidatorId>(r#""0xff""#).is_err());
+    }
+
+    #[test]
+    fn slasher_slashing_sse_round_trip() {
+        let header = |body_root| SignedBeaconBlockHeader {
+            message: BeaconBlockHeader {
+                slot: Slot::new(1),
+                proposer_index: 0,
+                parent_root: Hash256::zero(),
+                state_root: Hash256::zero(),
+                body_root,
+            },
+            signature: Signature::empty(),
+        };
+        let slashing = SseSlasherSlashing::<MainnetEthSpec>::Proposer(ProposerSlashing {
+            signed_header_1: header(Hash256::zero()),
+            signed_header_2: header(Hash256::repeat_byte(1)),
+        });
+        let json = serde_json::to_value(&slashing).unwrap();
+        assert_eq!(json["kind"], "proposer");
+        assert!(json["slashing"]["signed_header_1"].is_object());
+
+        let event = EventKind::SlasherSlashing(slashing);
+        let sse = format!(
+            "event:{}\ndata:{}",
+            event.topic_name(),
+            serde_json::to_string(&event).unwrap()
+        );
+        assert_eq!(EventKind::from_sse_bytes(sse.as_bytes()).unwrap(), event);
+    }
+
     #[test]
     fn parse_accept_header_content() {
         assert_eq!(
@@ -16,12 +16,13 @@ use eth2_config::{instantiate_hardcoded_nets, HardcodedNet};
 use std::fs::{create_dir_all, File};
 use std::io::{Read, Write};
 use std::path::PathBuf;
-use types::{BeaconState, ChainSpec, Config, EthSpec, EthSpecId};
+use types::{BeaconState, ChainSpec, Config, EthSpec, EthSpecId, PresetOverrides};
 
 pub const DEPLOY_BLOCK_FILE: &str = "deploy_block.txt";
 pub const BOOT_ENR_FILE: &str = "boot_enr.yaml";
 pub const GENESIS_STATE_FILE: &str = "genesis.ssz";
 pub const BASE_CONFIG_FILE: &str = "config.yaml";
+pub const PRESET_FILE: &str = "preset.yaml";
 
 // Creates definitions for:
 //
@@ -43,6 +44,8 @@ pub struct Eth2NetworkConfig {
     pub boot_enr: Option<Vec<Enr<CombinedKey>>>,
     pub genesis_state_bytes: Option<Vec<u8>>,
     pub config: Config,
+    /// Preset values which replace those of the compiled `EthSpec`, e.g. for a custom devnet.
+    pub preset_overrides: Option<PresetOverrides>,
 }
 
 impl Eth2NetworkConfig {
@@ -69,6 +72,7 @@ impl Eth2NetworkConfig {
                 .filter(|bytes| !bytes.is_empty()),
             config: serde_yaml::from_reader(net.config)
                 .map_err(|e| format!("Unable to parse yaml config: {:?}", e))?,
+            preset_overrides: None,
         })
     }
 
@@ -85,8 +89,13 @@ impl Eth2NetworkConfig {
         self.genesis_state_bytes.is_some()
     }
 
-    /// Construct a consolidated `ChainSpec` from the YAML config.
+    /// Construct a consolidated `ChainSpec` from the YAML config and preset overrides.
     pub fn chain_spec<E: EthSpec>(&self) -> Result<ChainSpec, String> {
+        if let Some(preset_overrides) = &self.preset_overrides {
+            return ChainSpec::from_config_and_preset::<E>(&self.config, preset_overrides)
+                .map_err(|e| format!("Invalid preset overrides in {}: {}", PRESET_FILE, e));
+        }
+
         ChainSpec::from_config::<E>(&self.config).ok_or_else(|| {
             format!(
                 "YAML configuration incompatible with spec constants for {}",
@@ -155,6 +164,10 @@ impl Eth2NetworkConfig {
 
         write_to_yaml_file!(BASE_CONFIG_FILE, &self.config);
 
+        if let Some(preset_overrides) = &self.preset_overrides {
+            write_to_yaml_file!(PRESET_FILE, preset_overrides);
+        }
+
         // The genesis state is a special case because it uses SSZ, not YAML.
         if let Some(genesis_state_bytes) = &self.genesis_state_bytes {
             let file = base_dir.join(GENESIS_STATE_FILE);
@@ -195,6 +208,7 @@ impl Eth2NetworkConfig {
         let deposit_contract_deploy_block = load_from_file!(DEPLOY_BLOCK_FILE);
         let boot_enr = optional_load_from_file!(BOOT_ENR_FILE);
         let config = load_from_file!(BASE_CONFIG_FILE);
+        let preset_overrides = optional_load_from_file!(PRESET_FILE);
 
         // The genesis state is a special case because it uses SSZ, not YAML.
         let genesis_file_path = base_dir.join(GENESIS_STATE_FILE);
@@ -217,6 +231,7 @@ impl Eth2NetworkConfig {
             boot_enr,
             genesis_state_bytes,
             config,
+            preset_overrides,
         })
     }
 }
@@ -294,15 +309,51 @@ mod tests {
         let genesis_state = Some(BeaconState::new(42, eth1_data, spec));
         let config = Config::from_chain_spec::<E>(spec);
 
-        do_test::<E>(boot_enr, genesis_state, config.clone());
-        do_test::<E>(None, None, config);
+        do_test::<E>(boot_enr, genesis_state, config.clone(), None);
+        do_test::<E>(None, None, config, None);
+    }
+
+    #[test]
+    fn preset_overrides_round_trip() {
+        let spec = &E::default_spec();
+        let config = Config::from_chain_spec::<E>(spec);
+        let preset_overrides: PresetOverrides = vec![
+            ("SHUFFLE_ROUND_COUNT".to_string(), 20.into()),
+            ("MAX_EFFECTIVE_BALANCE".to_string(), "64000000000".into()),
+        ]
+        .into_iter()
+        .collect();
+
+        let testnet = do_test::<E>(None, None, config, Some(preset_overrides));
+        let spec = testnet.chain_spec::<E>().expect("overrides should apply");
+        assert_eq!(spec.shuffle_round_count, 20);
+        assert_eq!(spec.max_effective_balance, 64_000_000_000);
+    }
+
+    #[test]
+    fn incompatible_preset_overrides() {
+        let mut testnet = Eth2NetworkConfig::from_hardcoded_net(&MAINNET).unwrap();
+        testnet.preset_overrides = Some(
+            vec![("SLOTS_PER_EPOCH".to_string(), 8.into())]
+                .into_iter()
+                .collect(),
+        );
+        assert!(testnet.chain_spec::<E>().is_err());
+
+        testnet.preset_overrides = Some(
+            vec![("NOT_A_PRESET_FIELD".to_string(), 8.into())]
+                .into_iter()
+                .collect(),
+        );
+        assert!(testnet.chain_spec::<E>().is_err());
     }
 
     fn do_test<E: EthSpec>(
         boot_enr: Option<Vec<Enr<CombinedKey>>>,
         genesis_state: Option<BeaconState<E>>,
         config: Config,
-    ) {
+        preset_overrides: Option<PresetOverrides>,
+    ) -> Eth2NetworkConfig {
         let temp_dir = TempBuilder::new()
             .prefix("eth2_testnet_test")
             .tempdir()
@@ -315,6 +366,7 @@ mod tests {
             boot_enr,
             genesis_state_bytes: genesis_state.as_ref().map(Encode::as_ssz_bytes),
             config,
+            preset_overrides,
         };
 
         testnet
@@ -324,5 +376,7 @@ mod tests {
         let decoded = Eth2NetworkConfig::load(base_dir).expect("should load struct");
 
         assert_eq!(testnet, decoded, "should decode as encoded");
+
+        decoded
     }
 }
@@ -9,6 +9,16 @@ use slog_term::Decorator;
 use std::io::{Result, Write};
 use std::time::{Duration, Instant};
 
+pub use log_levels::{
+    level_name, parse_level, parse_level_overrides, LevelsFilter, LogLevels, LEVEL_NAMES,
+};
+pub use rotated_files::prune_rotated_log_files;
+
+mod log_levels;
+#[cfg(feature = "otlp")]
+pub mod otlp;
+mod rotated_files;
+
 pub const MAX_MESSAGE_WIDTH: usize = 40;
 
 /// The minimum interval between log messages indicating that a queue is full.
@@ -0,0 +1,205 @@
+//! Log levels which can be overridden for individual modules and adjusted at runtime.
+use slog::{Drain, Level, OwnedKVList, Record};
+use std::sync::{Arc, RwLock};
+
+/// The names of the levels accepted by `parse_level`, from least to most verbose.
+pub const LEVEL_NAMES: [&str; 6] = ["crit", "error", "warn", "info", "debug", "trace"];
+
+/// Parse one of the `LEVEL_NAMES`.
+pub fn parse_level(level: &str) -> Result<Level, String> {
+    match level {
+        "info" => Ok(Level::Info),
+        "debug" => Ok(Level::Debug),
+        "trace" => Ok(Level::Trace),
+        "warn" => Ok(Level::Warning),
+        "error" => Ok(Level::Error),
+        "crit" => Ok(Level::Critical),
+        unknown => Err(format!("Unknown log level: {}", unknown)),
+    }
+}
+
+/// Returns the name of `level` as accepted by `parse_level`.
+pub fn level_name(level: Level) -> &'static str {
+    match level {
+        Level::Critical => "crit",
+        Level::Error => "error",
+        Level::Warning => "warn",
+        Level::Info => "info",
+        Level::Debug => "debug",
+        Level::Trace => "trace",
+    }
+}
+
+/// Parse a comma-separated list of `module=level` overrides, e.g. `network=debug,beacon_chain=info`.
+///
+/// Modules are given as a prefix of the Rust module path, so crate names must be written with
+/// underscores rather than hyphens.
+pub fn parse_level_overrides(overrides: &str) -> Result<Vec<(String, Level)>, String> {
+    overrides
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (module, level) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("Expected module=level, got: {}", entry))?;
+            let module = module.trim();
+            if module.is_empty() {
+                return Err(format!("Missing module name in: {}", entry));
+            }
+            Ok((module.to_string(), parse_level(level.trim())?))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Levels {
+    default: Level,
+    /// Sorted by descending module length, so that the most specific override matches first.
+    overrides: Vec<(String, Level)>,
+}
+
+impl Levels {
+    fn new(default: Level, mut overrides: Vec<(String, Level)>) -> Self {
+        overrides.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        overrides.dedup_by(|(a, _), (b, _)| a == b);
+        Self { default, overrides }
+    }
+
+    fn level_for(&self, module: &str) -> Level {
+        self.overrides
+            .iter()
+            .find(|(prefix, _)| {
+                module
+                    .strip_prefix(prefix.as_str())
+                    .map_or(false, |rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .map_or(self.default, |(_, level)| *level)
+    }
+}
+
+/// A shared handle to a set of log levels.
+///
+/// All clones of a `LogLevels` refer to the same levels, so a change made through one is applied
+/// to every drain created by `LogLevels::filter`.
+#[derive(Debug, Clone)]
+pub struct LogLevels {
+    levels: Arc<RwLock<Levels>>,
+}
+
+impl LogLevels {
+    pub fn new(default: Level, overrides: Vec<(String, Level)>) -> Self {
+        Self {
+            levels: Arc::new(RwLock::new(Levels::new(default, overrides))),
+        }
+    }
+
+    /// Replace the default level and all of the overrides.
+    pub fn set(&self, default: Level, overrides: Vec<(String, Level)>) {
+        let levels = Levels::new(default, overrides);
+        match self.levels.write() {
+            Ok(mut guard) => *guard = levels,
+            Err(poisoned) => *poisoned.into_inner() = levels,
+        }
+    }
+
+    /// The level of modules without an override.
+    pub fn default_level(&self) -> Level {
+        self.read(|levels| levels.default)
+    }
+
+    /// The overrides, with the most specific modules first.
+    pub fn overrides(&self) -> Vec<(String, Level)> {
+        self.read(|levels| levels.overrides.clone())
+    }
+
+    /// Returns `true` if a record at `level` from `module` should be logged.
+    pub fn is_enabled(&self, module: &str, level: Level) -> bool {
+        self.read(|levels| level.is_at_least(levels.level_for(module)))
+    }
+
+    /// Wrap `drain` so that it only receives the records enabled by these levels.
+    pub fn filter<D: Drain>(&self, drain: D) -> LevelsFilter<D> {
+        LevelsFilter {
+            drain,
+            levels: self.clone(),
+        }
+    }
+
+    fn read<T>(&self, f: impl FnOnce(&Levels) -> T) -> T {
+        match self.levels.read() {
+            Ok(guard) => f(&guard),
+            Err(poisoned) => f(&poisoned.into_inner()),
+        }
+    }
+}
+
+/// A drain which filters records using the current value of a `LogLevels`.
+pub struct LevelsFilter<D: Drain> {
+    drain: D,
+    levels: LogLevels,
+}
+
+impl<D: Drain> Drain for LevelsFilter<D> {
+    type Ok = Option<D::Ok>;
+    type Err = D::Err;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<Self::Ok, Self::Err> {
+        if self.levels.is_enabled(record.module(), record.level()) {
+            self.drain.log(record, values).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_overrides() {
+        assert_eq!(
+            parse_level_overrides("network=debug, beacon_chain=info,").unwrap(),
+            vec![
+                ("network".to_string(), Level::Debug),
+                ("beacon_chain".to_string(), Level::Info),
+            ]
+        );
+        assert!(parse_level_overrides("network").is_err());
+        assert!(parse_level_overrides("=debug").is_err());
+        assert!(parse_level_overrides("network=loud").is_err());
+    }
+
+    #[test]
+    fn most_specific_override_applies() {
+        let levels = LogLevels::new(
+            Level::Info,
+            vec![
+                ("network".to_string(), Level::Debug),
+                ("network::peer_manager".to_string(), Level::Error),
+            ],
+        );
+
+        assert!(levels.is_enabled("network::service", Level::Debug));
+        assert!(!levels.is_enabled("network::service", Level::Trace));
+        assert!(!levels.is_enabled("network::peer_manager::peerdb", Level::Warning));
+        assert!(levels.is_enabled("network::peer_manager", Level::Error));
+        // Only whole path segments match.
+        assert!(!levels.is_enabled("network_utils", Level::Debug));
+        assert!(levels.is_enabled("beacon_chain", Level::Info));
+        assert!(!levels.is_enabled("beacon_chain", Level::Debug));
+    }
+
+    #[test]
+    fn set_applies_to_clones() {
+        let levels = LogLevels::new(Level::Info, vec![]);
+        let clone = levels.clone();
+
+        clone.set(Level::Warning, vec![("store".to_string(), Level::Trace)]);
+
+        assert_eq!(levels.default_level(), Level::Warning);
+        assert!(!levels.is_enabled("beacon_chain", Level::Info));
+        assert!(levels.is_enabled("store::hot_cold_store", Level::Trace));
+    }
+}
@@ -0,0 +1,51 @@
+//! Exports the `tracing` spans of block import and production to an OpenTelemetry collector.
+//!
+//! Only the spans of Lighthouse crates are exported, the spans of dependencies such as libp2p are
+//! too numerous to be useful.
+use opentelemetry::sdk::{trace, Resource};
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use tracing::Level;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::SubscriberExt;
+
+/// The crates whose spans are exported.
+pub const TRACED_CRATES: &[&str] = &["beacon_chain", "lighthouse_network", "network", "http_api"];
+
+/// Export spans to the OTLP gRPC endpoint at `endpoint`, e.g. `http://localhost:4317`.
+///
+/// Spans are exported in batches from a background task, so this must be called from within a
+/// Tokio runtime.
+pub fn init(endpoint: &str, service_name: &str) -> Result<(), String> {
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint),
+        )
+        .with_trace_config(
+            trace::config().with_resource(Resource::new(vec![KeyValue::new(
+                "service.name",
+                service_name.to_string(),
+            )])),
+        )
+        .install_batch(opentelemetry::runtime::Tokio)
+        .map_err(|e| format!("Unable to start the OTLP exporter: {}", e))?;
+
+    let filter = TRACED_CRATES.iter().fold(Targets::new(), |filter, target| {
+        filter.with_target(*target, Level::DEBUG)
+    });
+
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .with(filter);
+
+    tracing::subscriber::set_global_default(subscriber)
+        .map_err(|e| format!("Unable to set the tracing subscriber: {}", e))
+}
+
+/// Export any spans which haven't been exported yet, and stop the exporter.
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
@@ -0,0 +1,98 @@
+//! Removal of rotated log files which have exceeded a maximum age.
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Delete the files rotated from the log file at `path` which were last modified at least
+/// `max_age` ago, returning the number of files deleted.
+///
+/// Rotated files are named `<path>.<n>`, or `<path>.<n>.gz` if compressed. The log file itself is
+/// never deleted.
+pub fn prune_rotated_log_files(path: &Path, max_age: Duration) -> Result<usize, String> {
+    let file_name = match path.file_name().and_then(|name| name.to_str()) {
+        Some(file_name) => file_name,
+        None => return Err(format!("Invalid log file path: {}", path.display())),
+    };
+    let dir = match path.parent() {
+        Some(dir) if dir.as_os_str().is_empty() => Path::new("."),
+        Some(dir) => dir,
+        None => return Err(format!("Invalid log file path: {}", path.display())),
+    };
+
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Unable to read {}: {:?}", dir.display(), e))?;
+
+    let now = SystemTime::now();
+    let mut num_deleted = 0;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Unable to read {}: {:?}", dir.display(), e))?;
+        let is_rotated = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.strip_prefix(file_name))
+            .and_then(|suffix| suffix.strip_prefix('.'))
+            .map_or(false, is_rotation_suffix);
+        if !is_rotated {
+            continue;
+        }
+
+        let modified = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .map_err(|e| format!("Unable to read {}: {:?}", entry.path().display(), e))?;
+        // A modification time in the future is treated as having no age.
+        let age = now.duration_since(modified).unwrap_or_default();
+        if age >= max_age {
+            fs::remove_file(entry.path())
+                .map_err(|e| format!("Unable to delete {}: {:?}", entry.path().display(), e))?;
+            num_deleted += 1;
+        }
+    }
+
+    Ok(num_deleted)
+}
+
+/// Returns `true` for the `<n>` or `<n>.gz` suffixes added to rotated log files.
+fn is_rotation_suffix(suffix: &str) -> bool {
+    let index = suffix.strip_suffix(".gz").unwrap_or(suffix);
+    !index.is_empty() && index.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn prunes_only_rotated_files() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("beacon.log");
+        let rotated = ["beacon.log.0", "beacon.log.1.gz"];
+        let kept = [
+            "beacon.log",
+            "beacon.log.old",
+            "beacon.log.gz",
+            "beacon-debug.log.0",
+        ];
+        for name in rotated.iter().chain(kept.iter()) {
+            fs::write(dir.path().join(name), "log").unwrap();
+        }
+
+        // None of the files are an hour old.
+        assert_eq!(
+            prune_rotated_log_files(&path, Duration::from_secs(3600)).unwrap(),
+            0
+        );
+
+        assert_eq!(
+            prune_rotated_log_files(&path, Duration::from_secs(0)).unwrap(),
+            rotated.len()
+        );
+        for name in rotated {
+            assert!(!dir.path().join(name).exists());
+        }
+        for name in kept {
+            assert!(dir.path().join(name).exists());
+        }
+    }
+}
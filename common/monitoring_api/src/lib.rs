@@ -55,6 +55,10 @@ pub struct Config {
     /// Path for the cold database required for fetching beacon db size metrics.
     /// Note: not relevant for validator and system metrics.
     pub freezer_db_path: Option<PathBuf>,
+    /// Seconds between updates, defaulting to `UPDATE_DURATION`.
+    pub update_period_secs: Option<u64>,
+    /// Path to a file containing a token which is sent as a bearer token with each update.
+    pub api_token_path: Option<PathBuf>,
 }
 
 #[derive(Clone)]
@@ -65,31 +69,56 @@ pub struct MonitoringHttpClient {
     /// Path to the freezer database.
     freezer_db_path: Option<PathBuf>,
     monitoring_endpoint: SensitiveUrl,
+    /// Time between updates.
+    update_period: Duration,
+    /// Token used to authenticate with the remote endpoint, if required.
+    api_token: Option<String>,
     log: slog::Logger,
 }
 
 impl MonitoringHttpClient {
     pub fn new(config: &Config, log: slog::Logger) -> Result<Self, String> {
+        let api_token = config
+            .api_token_path
+            .as_ref()
+            .map(|path| {
+                std::fs::read_to_string(path)
+                    .map(|token| token.trim().to_string())
+                    .map_err(|e| {
+                        format!(
+                            "Unable to read monitoring endpoint token from {}: {:?}",
+                            path.display(),
+                            e
+                        )
+                    })
+            })
+            .transpose()?;
+
         Ok(Self {
             client: reqwest::Client::new(),
             db_path: config.db_path.clone(),
             freezer_db_path: config.freezer_db_path.clone(),
             monitoring_endpoint: SensitiveUrl::parse(&config.monitoring_endpoint)
                 .map_err(|e| format!("Invalid monitoring endpoint: {:?}", e))?,
+            update_period: Duration::from_secs(
+                config.update_period_secs.unwrap_or(UPDATE_DURATION),
+            ),
+            api_token,
             log,
         })
     }
 
     /// Perform a HTTP POST request.
     async fn post<T: Serialize, U: IntoUrl>(&self, url: U, body: &T) -> Result<(), Error> {
-        let response = self
+        let mut request = self
             .client
             .post(url)
             .json(body)
-            .timeout(Duration::from_secs(TIMEOUT_DURATION))
-            .send()
-            .await
-            .map_err(Error::Reqwest)?;
+            .timeout(Duration::from_secs(TIMEOUT_DURATION));
+        if let Some(api_token) = &self.api_token {
+            request = request.bearer_auth(api_token);
+        }
+        let response = request.send().await.map_err(Error::Reqwest)?;
         ok_or_error(response).await?;
         Ok(())
     }
@@ -100,10 +129,15 @@ impl MonitoringHttpClient {
         let mut interval = interval_at(
             // Have some initial delay for the metrics to get initialized
             Instant::now() + Duration::from_secs(25),
-            Duration::from_secs(UPDATE_DURATION),
+            self.update_period,
         );
 
-        info!(self.log, "Starting monitoring api"; "endpoint" => %self.monitoring_endpoint);
+        info!(
+            self.log,
+            "Starting monitoring api";
+            "endpoint" => %self.monitoring_endpoint,
+            "update_period_secs" => self.update_period.as_secs(),
+        );
 
         let update_future = async move {
             loop {
@@ -0,0 +1,194 @@
+//! Response compression, negotiated using the `Accept-Encoding` request header.
+//!
+//! Bodies are compressed as they are streamed, so streaming responses remain streaming.
+
+use flate2::write::{DeflateEncoder, GzEncoder};
+use flate2::Compression;
+use futures::stream::{self, StreamExt};
+use std::io::{self, Write};
+use std::mem;
+use warp::http::header::{
+    HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, VARY,
+};
+use warp::hyper::Body;
+use warp::reply::{Reply, Response};
+
+/// A content encoding supported by the server.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    Gzip,
+    Deflate,
+}
+
+impl Encoding {
+    /// Select an encoding from the value of an `Accept-Encoding` header, preferring gzip.
+    ///
+    /// Encodings with a quality value of zero are not acceptable to the client and are ignored.
+    pub fn from_accept_encoding(accept_encoding: &str) -> Option<Self> {
+        let acceptable = accept_encoding
+            .split(',')
+            .filter_map(|item| {
+                let mut params = item.split(';');
+                let name = params.next()?.trim().to_ascii_lowercase();
+                let rejected = params.any(|param| {
+                    param
+                        .trim()
+                        .strip_prefix("q=")
+                        .and_then(|q| q.parse::<f32>().ok())
+                        .map_or(false, |q| q <= 0.0)
+                });
+                (!rejected).then(|| name)
+            })
+            .collect::<Vec<_>>();
+
+        if acceptable.iter().any(|name| name == "gzip" || name == "*") {
+            Some(Encoding::Gzip)
+        } else if acceptable.iter().any(|name| name == "deflate") {
+            Some(Encoding::Deflate)
+        } else {
+            None
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+        }
+    }
+}
+
+enum Encoder {
+    Gzip(GzEncoder<Vec<u8>>),
+    Deflate(DeflateEncoder<Vec<u8>>),
+}
+
+impl Encoder {
+    fn new(encoding: Encoding) -> Self {
+        match encoding {
+            Encoding::Gzip => Encoder::Gzip(GzEncoder::new(vec![], Compression::default())),
+            Encoding::Deflate => {
+                Encoder::Deflate(DeflateEncoder::new(vec![], Compression::default()))
+            }
+        }
+    }
+
+    /// Compress `chunk`, returning the compressed bytes which are ready to be sent.
+    ///
+    /// The encoder is flushed after each chunk so that streamed responses aren't delayed.
+    fn compress_chunk(&mut self, chunk: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            Encoder::Gzip(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.flush()?;
+                Ok(mem::take(encoder.get_mut()))
+            }
+            Encoder::Deflate(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.flush()?;
+                Ok(mem::take(encoder.get_mut()))
+            }
+        }
+    }
+
+    /// Finish the compressed stream, returning the remaining bytes.
+    fn finish(self) -> io::Result<Vec<u8>> {
+        match self {
+            Encoder::Gzip(encoder) => encoder.finish(),
+            Encoder::Deflate(encoder) => encoder.finish(),
+        }
+    }
+}
+
+/// Compress the body of `reply` using the preferred encoding in `accept_encoding`.
+///
+/// The reply is returned unmodified if the client doesn't accept a supported encoding, if the
+/// reply is already encoded or if it is a stream of server-sent events.
+pub fn compress_reply(accept_encoding: Option<String>, reply: impl Reply) -> Response {
+    let response = reply.into_response();
+
+    let encoding = match accept_encoding
+        .as_deref()
+        .and_then(Encoding::from_accept_encoding)
+    {
+        Some(encoding) => encoding,
+        None => return response,
+    };
+
+    let headers = response.headers();
+    let is_event_stream = headers.get(CONTENT_TYPE).map_or(false, |content_type| {
+        content_type.as_bytes().starts_with(b"text/event-stream")
+    });
+    if headers.contains_key(CONTENT_ENCODING) || is_event_stream {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+
+    let compressed = stream::unfold(Some((body, Encoder::new(encoding))), |state| async move {
+        let (mut body, mut encoder) = state?;
+        match body.next().await {
+            Some(Ok(chunk)) => match encoder.compress_chunk(&chunk) {
+                Ok(bytes) => Some((Ok(bytes), Some((body, encoder)))),
+                Err(e) => Some((Err(e), None)),
+            },
+            Some(Err(e)) => Some((Err(io::Error::new(io::ErrorKind::Other, e)), None)),
+            None => Some((encoder.finish(), None)),
+        }
+    });
+
+    parts.headers.remove(CONTENT_LENGTH);
+    parts.headers.insert(
+        CONTENT_ENCODING,
+        HeaderValue::from_static(encoding.as_str()),
+    );
+    parts
+        .headers
+        .append(VARY, HeaderValue::from_static(ACCEPT_ENCODING.as_str()));
+
+    Response::from_parts(parts, Body::wrap_stream(compressed))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn negotiate_encoding() {
+        assert_eq!(
+            Encoding::from_accept_encoding("gzip, deflate, br"),
+            Some(Encoding::Gzip)
+        );
+        assert_eq!(
+            Encoding::from_accept_encoding("deflate"),
+            Some(Encoding::Deflate)
+        );
+        assert_eq!(
+            Encoding::from_accept_encoding("gzip;q=0, deflate;q=0.5"),
+            Some(Encoding::Deflate)
+        );
+        assert_eq!(Encoding::from_accept_encoding("*"), Some(Encoding::Gzip));
+        assert_eq!(Encoding::from_accept_encoding("identity"), None);
+        assert_eq!(Encoding::from_accept_encoding("br"), None);
+    }
+
+    #[test]
+    fn compressed_chunks_round_trip() {
+        let chunks: Vec<&[u8]> = vec![b"{\"data\":[", b"1,2,3", b"]}"];
+
+        let mut encoder = Encoder::new(Encoding::Gzip);
+        let mut compressed = vec![];
+        for chunk in &chunks {
+            compressed.extend(encoder.compress_chunk(chunk).unwrap());
+        }
+        compressed.extend(encoder.finish().unwrap());
+
+        let mut decoded = String::new();
+        std::io::Read::read_to_string(
+            &mut flate2::read::GzDecoder::new(compressed.as_slice()),
+            &mut decoded,
+        )
+        .unwrap();
+        assert_eq!(decoded, "{\"data\":[1,2,3]}");
+    }
+}
@@ -1,5 +1,7 @@
 use std::net::IpAddr;
 use warp::filters::cors::Builder;
+use warp::filters::BoxedFilter;
+use warp::Filter;
 
 /// Configure a `cors::Builder`.
 ///
@@ -10,8 +12,8 @@ pub fn set_builder_origins(
     default_origin: (IpAddr, u16),
 ) -> Result<Builder, String> {
     if let Some(allow_origin) = allow_origin {
-        let origins = allow_origin
-            .split(',')
+        let origins = split_origins(allow_origin)?
+            .into_iter()
             .map(|s| verify_cors_origin_str(s).map(|_| s))
             .collect::<Result<Vec<_>, _>>()?;
         Ok(builder.allow_origins(origins))
@@ -26,6 +28,117 @@ pub fn set_builder_origins(
     }
 }
 
+/// Configure a `cors::Builder`, allowing origin patterns such as `https://*.example.com`.
+///
+/// Any origin containing a `*` wildcard (other than the lone `*` origin) is a pattern. Since
+/// `warp` is unable to match patterns, the builder will permit any origin when patterns are
+/// present and requests must additionally be checked with `origin_filter`.
+pub fn set_builder_origin_patterns(
+    builder: Builder,
+    allow_origin: Option<&str>,
+    default_origin: (IpAddr, u16),
+) -> Result<Builder, String> {
+    match allow_origin {
+        Some(allow_origin) if has_origin_patterns(allow_origin) => {
+            for origin in split_origins(allow_origin)? {
+                verify_cors_origin_pattern(origin)?;
+            }
+            Ok(builder.allow_any_origin())
+        }
+        _ => set_builder_origins(builder, allow_origin, default_origin),
+    }
+}
+
+/// Returns a filter which rejects requests with an `Origin` header that doesn't match any of the
+/// comma-separated origins or patterns in `allow_origin`.
+///
+/// The filter only rejects requests if `allow_origin` contains a pattern, otherwise origins are
+/// enforced by the `cors::Builder`.
+pub fn origin_filter(allow_origin: Option<&str>) -> BoxedFilter<()> {
+    let patterns = allow_origin
+        .filter(|allow_origin| has_origin_patterns(allow_origin))
+        .map(|allow_origin| {
+            allow_origin
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect::<Vec<_>>()
+        });
+
+    warp::header::optional::<String>("origin")
+        .and_then(move |origin: Option<String>| {
+            let result = match (&patterns, origin) {
+                (Some(patterns), Some(origin))
+                    if !patterns
+                        .iter()
+                        .any(|pattern| origin_matches(pattern, &origin)) =>
+                {
+                    Err(crate::reject::invalid_origin(origin))
+                }
+                _ => Ok(()),
+            };
+            async move { result }
+        })
+        .untuple_one()
+        .boxed()
+}
+
+/// Split the comma-separated origins in `allow_origin`, ignoring surrounding whitespace.
+///
+/// Returns an error if any of the origins is empty, e.g. due to a trailing comma.
+fn split_origins(allow_origin: &str) -> Result<Vec<&str>, String> {
+    allow_origin
+        .split(',')
+        .map(str::trim)
+        .map(|origin| {
+            if origin.is_empty() {
+                Err(format!("{} contains an empty origin", allow_origin))
+            } else {
+                Ok(origin)
+            }
+        })
+        .collect()
+}
+
+/// Returns `true` if any of the comma-separated origins in `allow_origin` is a pattern.
+fn has_origin_patterns(allow_origin: &str) -> bool {
+    allow_origin
+        .split(',')
+        .map(str::trim)
+        .any(|origin| origin != "*" && origin.contains('*'))
+}
+
+/// Returns `true` if `origin` matches `pattern`, where each `*` in `pattern` matches any sequence
+/// of characters.
+fn origin_matches(pattern: &str, origin: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let mut rest = match origin.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+
+    let parts = parts.collect::<Vec<_>>();
+    match parts.split_last() {
+        None => rest.is_empty(),
+        Some((last, middle)) => {
+            for part in middle {
+                match rest.find(part) {
+                    Some(i) => rest = &rest[i + part.len()..],
+                    None => return false,
+                }
+            }
+            rest.ends_with(last)
+        }
+    }
+}
+
+/// Verify that `s` can be used as a CORS origin pattern, by substituting each wildcard.
+fn verify_cors_origin_pattern(s: &str) -> Result<(), String> {
+    verify_cors_origin_str(&s.replace('*', "0"))
+}
+
 /// Verify that `s` can be used as a CORS origin.
 ///
 /// ## Notes
@@ -79,4 +192,69 @@ mod test {
         verify_cors_origin_str("localhost").unwrap_err();
         verify_cors_origin_str("[::1]").unwrap_err();
     }
+
+    #[test]
+    fn split_origin_lists() {
+        assert_eq!(
+            split_origins("http://localhost, https://*.example.com ").unwrap(),
+            vec!["http://localhost", "https://*.example.com"]
+        );
+        split_origins("http://localhost,").unwrap_err();
+        split_origins("http://localhost, ,http://127.0.0.1").unwrap_err();
+        split_origins("").unwrap_err();
+
+        let default_origin = ("127.0.0.1".parse().unwrap(), 5052);
+        set_builder_origin_patterns(
+            warp::cors(),
+            Some("http://localhost, https://*.example.com"),
+            default_origin,
+        )
+        .unwrap();
+        set_builder_origin_patterns(warp::cors(), Some("https://*.example.com,"), default_origin)
+            .unwrap_err();
+        set_builder_origins(warp::cors(), Some("http://localhost,"), default_origin).unwrap_err();
+    }
+
+    #[test]
+    fn origin_patterns() {
+        verify_cors_origin_pattern("https://*.example.com").unwrap();
+        verify_cors_origin_pattern("http://localhost:*").unwrap();
+        verify_cors_origin_pattern("*.example.com").unwrap_err();
+
+        assert!(has_origin_patterns(
+            "http://localhost,https://*.example.com"
+        ));
+        assert!(!has_origin_patterns("*"));
+        assert!(!has_origin_patterns("http://localhost,http://127.0.0.1"));
+        assert!(has_origin_patterns(
+            "http://localhost, https://*.example.com"
+        ));
+
+        assert!(origin_matches(
+            "https://*.example.com",
+            "https://app.example.com"
+        ));
+        assert!(origin_matches(
+            "https://*.example.com",
+            "https://a.b.example.com"
+        ));
+        assert!(!origin_matches(
+            "https://*.example.com",
+            "https://example.com"
+        ));
+        assert!(!origin_matches(
+            "https://*.example.com",
+            "http://app.example.com"
+        ));
+        assert!(!origin_matches(
+            "https://*.example.com",
+            "https://app.example.com.evil.com"
+        ));
+        assert!(origin_matches(
+            "http://localhost:*",
+            "http://localhost:8080"
+        ));
+        assert!(origin_matches("http://localhost", "http://localhost"));
+        assert!(!origin_matches("http://localhost", "http://localhost:8080"));
+    }
 }
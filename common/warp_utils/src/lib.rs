@@ -1,6 +1,7 @@
 //! This crate contains functions that are common across multiple `warp` HTTP servers in the
 //! Lighthouse project. E.g., the `http_api` and `http_metrics` crates.
 
+pub mod compression;
 pub mod cors;
 pub mod metrics;
 pub mod query;
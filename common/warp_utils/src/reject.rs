@@ -2,7 +2,12 @@ use eth2::types::{ErrorMessage, Failure, IndexedErrorMessage};
 use std::convert::Infallible;
 use std::error::Error;
 use std::fmt;
-use warp::{http::StatusCode, reject::Reject};
+use std::time::Duration;
+use warp::{
+    http::{header::RETRY_AFTER, HeaderValue, StatusCode},
+    reject::Reject,
+    Reply,
+};
 
 #[derive(Debug)]
 pub struct ServerSentEventError(pub String);
@@ -118,6 +123,39 @@ pub fn not_synced(msg: String) -> warp::reject::Rejection {
     warp::reject::custom(NotSynced(msg))
 }
 
+#[derive(Debug)]
+pub struct ServiceUnavailable {
+    pub message: String,
+    pub retry_after: Option<Duration>,
+}
+
+impl Reject for ServiceUnavailable {}
+
+/// Reject with a 503, optionally advising the client when to retry via a `Retry-After` header.
+pub fn service_unavailable(
+    message: String,
+    retry_after: Option<Duration>,
+) -> warp::reject::Rejection {
+    warp::reject::custom(ServiceUnavailable {
+        message,
+        retry_after,
+    })
+}
+
+#[derive(Debug)]
+pub struct TooManyRequests {
+    pub limit: u64,
+    pub retry_after: Duration,
+}
+
+impl Reject for TooManyRequests {}
+
+/// Reject with a 429, advising the client of its quota and when to retry via the `Retry-After`
+/// and `RateLimit-*` headers.
+pub fn too_many_requests(limit: u64, retry_after: Duration) -> warp::reject::Rejection {
+    warp::reject::custom(TooManyRequests { limit, retry_after })
+}
+
 #[derive(Debug)]
 pub struct InvalidAuthorization(pub String);
 
@@ -127,6 +165,15 @@ pub fn invalid_auth(msg: String) -> warp::reject::Rejection {
     warp::reject::custom(InvalidAuthorization(msg))
 }
 
+#[derive(Debug)]
+pub struct InvalidOrigin(pub String);
+
+impl Reject for InvalidOrigin {}
+
+pub fn invalid_origin(origin: String) -> warp::reject::Rejection {
+    warp::reject::custom(InvalidOrigin(origin))
+}
+
 #[derive(Debug)]
 pub struct IndexedBadRequestErrors {
     pub message: String,
@@ -141,7 +188,7 @@ pub fn indexed_bad_request(message: String, failures: Vec<Failure>) -> warp::rej
 
 /// This function receives a `Rejection` and tries to return a custom
 /// value, otherwise simply passes the rejection along.
-pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply, Infallible> {
+pub async fn handle_rejection(err: warp::Rejection) -> Result<warp::reply::Response, Infallible> {
     let code;
     let message;
 
@@ -155,7 +202,51 @@ pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply,
             failures: e.failures.clone(),
         });
 
-        return Ok(warp::reply::with_status(json, code));
+        return Ok(warp::reply::with_status(json, code).into_response());
+    }
+
+    if let Some(e) = err.find::<crate::reject::ServiceUnavailable>() {
+        code = StatusCode::SERVICE_UNAVAILABLE;
+
+        let json = warp::reply::json(&ErrorMessage {
+            code: code.as_u16(),
+            message: format!("SERVICE_UNAVAILABLE: {}", e.message),
+            stacktraces: vec![],
+        });
+
+        let mut response = warp::reply::with_status(json, code).into_response();
+        if let Some(retry_after) = e.retry_after {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(retry_after.as_secs()));
+        }
+
+        return Ok(response);
+    }
+
+    if let Some(e) = err.find::<crate::reject::TooManyRequests>() {
+        code = StatusCode::TOO_MANY_REQUESTS;
+
+        let json = warp::reply::json(&ErrorMessage {
+            code: code.as_u16(),
+            message: "TOO_MANY_REQUESTS: rate limit exceeded".to_string(),
+            stacktraces: vec![],
+        });
+
+        // Round up so that clients which honour the header don't retry too early.
+        let retry_after_secs = e
+            .retry_after
+            .as_secs()
+            .saturating_add(u64::from(e.retry_after.subsec_nanos() > 0));
+
+        let mut response = warp::reply::with_status(json, code).into_response();
+        let headers = response.headers_mut();
+        headers.insert(RETRY_AFTER, HeaderValue::from(retry_after_secs));
+        headers.insert("RateLimit-Limit", HeaderValue::from(e.limit));
+        headers.insert("RateLimit-Remaining", HeaderValue::from(0));
+        headers.insert("RateLimit-Reset", HeaderValue::from(retry_after_secs));
+
+        return Ok(response);
     }
 
     if err.is_not_found() {
@@ -204,6 +295,9 @@ pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply,
     } else if let Some(e) = err.find::<crate::reject::InvalidAuthorization>() {
         code = StatusCode::FORBIDDEN;
         message = format!("FORBIDDEN: Invalid auth token: {}", e.0);
+    } else if let Some(e) = err.find::<crate::reject::InvalidOrigin>() {
+        code = StatusCode::FORBIDDEN;
+        message = format!("FORBIDDEN: Origin not allowed: {}", e.0);
     } else if let Some(e) = err.find::<warp::reject::MissingHeader>() {
         code = StatusCode::BAD_REQUEST;
         message = format!("BAD_REQUEST: missing {} header", e.name());
@@ -224,5 +318,5 @@ pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply,
         stacktraces: vec![],
     });
 
-    Ok(warp::reply::with_status(json, code))
+    Ok(warp::reply::with_status(json, code).into_response())
 }
@@ -1,5 +1,5 @@
 use crate::{CacheArena, CachedTreeHash, Error, Hash256, TreeHashCache};
-use ssz_types::{typenum::Unsigned, FixedVector, VariableList};
+use ssz_types::{typenum::Unsigned, FixedVector, PersistentList, VariableList};
 use std::mem::size_of;
 use tree_hash::{mix_in_length, BYTES_PER_CHUNK};
 
@@ -126,6 +126,54 @@ impl<N: Unsigned> CachedTreeHash<TreeHashCache> for VariableList<u64, N> {
     }
 }
 
+impl<N: Unsigned> CachedTreeHash<TreeHashCache> for PersistentList<u64, N> {
+    fn new_tree_hash_cache(&self, arena: &mut CacheArena) -> TreeHashCache {
+        let vals_per_chunk = BYTES_PER_CHUNK / size_of::<u64>();
+        TreeHashCache::new(
+            arena,
+            int_log(N::to_usize() / vals_per_chunk),
+            u64_leaf_count(self.len()),
+        )
+    }
+
+    fn recalculate_tree_hash_root(
+        &self,
+        arena: &mut CacheArena,
+        cache: &mut TreeHashCache,
+    ) -> Result<Hash256, Error> {
+        // Every chunk of the list but the last contains a whole number of leaves, so the leaves
+        // of each chunk can be packed independently.
+        let leaves = ExactSizeLeaves {
+            iter: self.chunks().flat_map(u64_iter),
+            len: u64_leaf_count(self.len()),
+        };
+        Ok(mix_in_length(
+            &cache.recalculate_merkle_root(arena, leaves)?,
+            self.len(),
+        ))
+    }
+}
+
+/// Wraps an iterator of leaves whose length is known, but not to the compiler.
+struct ExactSizeLeaves<I> {
+    iter: I,
+    len: usize,
+}
+
+impl<I: Iterator<Item = [u8; BYTES_PER_CHUNK]>> Iterator for ExactSizeLeaves<I> {
+    type Item = [u8; BYTES_PER_CHUNK];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<I: Iterator<Item = [u8; BYTES_PER_CHUNK]>> ExactSizeIterator for ExactSizeLeaves<I> {}
+
 #[cfg(test)]
 mod test {
     use super::*;
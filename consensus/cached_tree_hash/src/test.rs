@@ -3,8 +3,8 @@ use crate::{CacheArena, CachedTreeHash, Error, Hash256, TreeHashCache};
 use eth2_hashing::ZERO_HASHES;
 use quickcheck_macros::quickcheck;
 use ssz_types::{
-    typenum::{Unsigned, U16, U255, U256, U257},
-    FixedVector, VariableList,
+    typenum::{Unsigned, U1099511627776, U16, U255, U256, U257},
+    FixedVector, PersistentList, VariableList, PERSISTENT_LIST_CHUNK_LEN,
 };
 use tree_hash::TreeHash;
 
@@ -151,3 +151,27 @@ fn variable_list_h256_test<Len: Unsigned>(leaves_and_skips: Vec<(u64, bool)>) ->
     }
     true
 }
+
+#[test]
+fn persistent_list_u64() {
+    let arena = &mut CacheArena::default();
+    let len = 3 * PERSISTENT_LIST_CHUNK_LEN + 5;
+    let mut list: PersistentList<u64, U1099511627776> =
+        PersistentList::new((0..len as u64).collect()).unwrap();
+    let mut cache = list.new_tree_hash_cache(arena);
+
+    let check = |list: &PersistentList<u64, U1099511627776>,
+                 arena: &mut CacheArena,
+                 cache: &mut TreeHashCache| {
+        assert_eq!(
+            list.recalculate_tree_hash_root(arena, cache).unwrap(),
+            list.tree_hash_root()
+        );
+    };
+
+    check(&list, arena, &mut cache);
+
+    list[PERSISTENT_LIST_CHUNK_LEN] = 42;
+    list.push(7).unwrap();
+    check(&list, arena, &mut cache);
+}
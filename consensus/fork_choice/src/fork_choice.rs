@@ -2,14 +2,26 @@ use crate::{ForkChoiceStore, InvalidationOperation};
 use proto_array::{Block as ProtoBlock, ExecutionStatus, ProtoArrayForkChoice};
 use ssz_derive::{Decode, Encode};
 use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
 use std::marker::PhantomData;
 use std::time::Duration;
 use types::{
-    consts::merge::INTERVALS_PER_SLOT, AttestationShufflingId, BeaconBlock, BeaconState,
-    BeaconStateError, ChainSpec, Checkpoint, Epoch, EthSpec, ExecPayload, ExecutionBlockHash,
-    Hash256, IndexedAttestation, RelativeEpoch, SignedBeaconBlock, Slot,
+    consts::merge::INTERVALS_PER_SLOT, AttestationShufflingId, AttesterSlashing, BeaconBlock,
+    BeaconState, BeaconStateError, ChainSpec, Checkpoint, Epoch, EthSpec, ExecPayload,
+    ExecutionBlockHash, Hash256, IndexedAttestation, RelativeEpoch, SignedBeaconBlock, Slot,
 };
 
+/// The weight of the head, as a percentage of the weight of a committee, below which the next
+/// proposer may re-org it out.
+pub const REORG_HEAD_WEIGHT_THRESHOLD: u64 = 20;
+
+/// The weight of the head's parent, as a percentage of the weight of a committee, above which the
+/// next proposer may build on it instead of the head.
+pub const REORG_PARENT_WEIGHT_THRESHOLD: u64 = 160;
+
+/// The maximum number of epochs since finalization at which the head may be re-orged out.
+pub const REORG_MAX_EPOCHS_SINCE_FINALIZATION: u64 = 2;
+
 #[derive(Debug)]
 pub enum Error<T> {
     InvalidAttestation(InvalidAttestation),
@@ -280,6 +292,9 @@ pub struct ForkChoice<T, E> {
     queued_attestations: Vec<QueuedAttestation>,
     /// Stores a cache of the values required to be sent to the execution layer.
     forkchoice_update_parameters: Option<ForkchoiceUpdateParameters>,
+    /// Whether each block imported since startup arrived before the attestation deadline of its
+    /// slot. Not persisted.
+    block_timeliness: HashMap<Hash256, bool>,
     _phantom: PhantomData<E>,
 }
 
@@ -325,17 +340,16 @@ where
                 .map_err(Error::BeaconStateError)?;
 
         // Default any non-merge execution block hashes to 0x000..000.
-        let execution_status = anchor_block.message_merge().map_or_else(
-            |()| ExecutionStatus::irrelevant(),
-            |message| {
-                let execution_payload = &message.body.execution_payload;
+        let execution_status = anchor_block.message().execution_payload().map_or_else(
+            |_| ExecutionStatus::irrelevant(),
+            |execution_payload| {
                 if execution_payload == &<_>::default() {
                     // A default payload does not have execution enabled.
                     ExecutionStatus::irrelevant()
                 } else {
                     // Assume that this payload is valid, since the anchor should be a trusted block and
                     // state.
-                    ExecutionStatus::Valid(message.body.execution_payload.block_hash())
+                    ExecutionStatus::Valid(execution_payload.block_hash())
                 }
             },
         );
@@ -355,6 +369,7 @@ where
             proto_array,
             queued_attestations: vec![],
             forkchoice_update_parameters: None,
+            block_timeliness: HashMap::new(),
             _phantom: PhantomData,
         })
     }
@@ -373,6 +388,7 @@ where
             proto_array,
             queued_attestations,
             forkchoice_update_parameters: None,
+            block_timeliness: HashMap::new(),
             _phantom: PhantomData,
         }
     }
@@ -452,6 +468,7 @@ where
             *store.finalized_checkpoint(),
             store.justified_balances(),
             store.proposer_boost_root(),
+            store.equivocating_indices(),
             spec,
         )?;
 
@@ -625,7 +642,8 @@ where
         // Add proposer score boost if the block is timely.
         let is_before_attesting_interval =
             block_delay < Duration::from_secs(spec.seconds_per_slot / INTERVALS_PER_SLOT);
-        if current_slot == block.slot() && is_before_attesting_interval {
+        let is_timely = current_slot == block.slot() && is_before_attesting_interval;
+        if is_timely {
             self.fc_store.set_proposer_boost_root(block_root);
         }
 
@@ -723,6 +741,8 @@ where
             execution_status,
         })?;
 
+        self.block_timeliness.insert(block_root, is_timely);
+
         Ok(())
     }
 
@@ -908,6 +928,31 @@ where
         Ok(())
     }
 
+    /// Apply an attester slashing to fork choice.
+    ///
+    /// We assume that the attester slashing provided to this function has already been verified.
+    ///
+    /// ## Specification
+    ///
+    /// Approximates:
+    ///
+    /// https://github.com/ethereum/consensus-specs/blob/v1.1.10/specs/phase0/fork-choice.md#on_attester_slashing
+    ///
+    /// It only approximates the specification since it does not check the validity of the
+    /// slashing, that should already have been done upstream.
+    pub fn on_attester_slashing(&mut self, slashing: &AttesterSlashing<E>) {
+        let attesting_indices_set = |att: &IndexedAttestation<E>| {
+            att.attesting_indices
+                .iter()
+                .copied()
+                .collect::<BTreeSet<_>>()
+        };
+        let att1_indices = attesting_indices_set(&slashing.attestation_1);
+        let att2_indices = attesting_indices_set(&slashing.attestation_2);
+        self.fc_store
+            .extend_equivocating_indices(att1_indices.intersection(&att2_indices).copied());
+    }
+
     /// Call `on_tick` for all slots between `fc_store.get_current_slot()` and the provided
     /// `current_slot`. Returns the value of `self.fc_store.get_current_slot`.
     pub fn update_time(&mut self, current_slot: Slot) -> Result<Slot, Error<T::Error>> {
@@ -1095,13 +1140,168 @@ where
         self.fc_store.proposer_boost_root()
     }
 
+    /// Returns `true` if the `forkchoiceUpdated` call for `head_block_root` should be withheld
+    /// from the execution layer, because the proposer of the next slot is connected to us and is
+    /// expected to re-org the head out by building on its parent.
+    ///
+    /// `time_into_slot` is the time elapsed since the start of the current slot.
+    ///
+    /// ## Specification
+    ///
+    /// Approximates:
+    ///
+    /// https://github.com/ethereum/consensus-specs/blob/v1.4.0/specs/phase0/fork-choice.md#should_override_forkchoice_update
+    ///
+    /// Unrealized justification is not tracked, so the head and its parent are considered FFG
+    /// competitive when their justified checkpoints are equal.
+    pub fn should_override_forkchoice_update(
+        &self,
+        head_block_root: Hash256,
+        time_into_slot: Duration,
+        proposer_is_connected: bool,
+        spec: &ChainSpec,
+    ) -> Result<bool, Error<T::Error>> {
+        let (head_block, parent_block) = match self.head_and_parent(head_block_root)? {
+            Some(blocks) => blocks,
+            None => return Ok(false),
+        };
+        let current_slot = self.fc_store.get_current_slot();
+        let proposal_slot = head_block.slot + 1;
+
+        // Only re-org a single slot, and only if the re-org block can be proposed on time.
+        let parent_slot_ok = parent_block.slot + 1 == head_block.slot;
+        let current_time_ok = head_block.slot == current_slot
+            || (proposal_slot == current_slot && is_proposing_on_time(time_into_slot, spec));
+        // The attestations for the head's slot are only counted once that slot has passed.
+        let weights_ok = current_slot <= head_block.slot
+            || (self.is_head_weak(&head_block, spec) && self.is_parent_strong(&parent_block, spec));
+
+        Ok(self.is_head_late(head_block.root)
+            && is_shuffling_stable::<E>(proposal_slot)
+            && is_ffg_competitive(&head_block, &parent_block)
+            && self.is_finalization_ok(proposal_slot)
+            && proposer_is_connected
+            && parent_slot_ok
+            && current_time_ok
+            && weights_ok)
+    }
+
+    /// Returns the root of the block which the proposer at `slot` should build on: the parent of
+    /// `head_block_root` if the head should be re-orged out, otherwise the head itself.
+    ///
+    /// `time_into_slot` is the time elapsed since the start of the current slot.
+    ///
+    /// ## Specification
+    ///
+    /// Approximates:
+    ///
+    /// https://github.com/ethereum/consensus-specs/blob/v1.4.0/specs/phase0/fork-choice.md#get_proposer_head
+    ///
+    /// Unrealized justification is not tracked, so the head and its parent are considered FFG
+    /// competitive when their justified checkpoints are equal.
+    pub fn get_proposer_head(
+        &self,
+        head_block_root: Hash256,
+        slot: Slot,
+        time_into_slot: Duration,
+        spec: &ChainSpec,
+    ) -> Result<Hash256, Error<T::Error>> {
+        let (head_block, parent_block) = match self.head_and_parent(head_block_root)? {
+            Some(blocks) => blocks,
+            None => return Ok(head_block_root),
+        };
+
+        // Only re-org a single slot.
+        let parent_slot_ok = parent_block.slot + 1 == head_block.slot;
+        let current_time_ok = head_block.slot + 1 == slot;
+        // The head's weight must not include the proposer boost.
+        let boost_worn_off = self.fc_store.proposer_boost_root() != head_block.root;
+
+        let re_org = self.is_head_late(head_block.root)
+            && is_shuffling_stable::<E>(slot)
+            && is_ffg_competitive(&head_block, &parent_block)
+            && self.is_finalization_ok(slot)
+            && is_proposing_on_time(time_into_slot, spec)
+            && parent_slot_ok
+            && current_time_ok
+            && boost_worn_off
+            && self.is_head_weak(&head_block, spec)
+            && self.is_parent_strong(&parent_block, spec);
+
+        Ok(if re_org {
+            parent_block.root
+        } else {
+            head_block.root
+        })
+    }
+
+    /// Returns the head block and its parent, or `None` if the parent is not known to fork choice.
+    fn head_and_parent(
+        &self,
+        head_block_root: Hash256,
+    ) -> Result<Option<(ProtoBlock, ProtoBlock)>, Error<T::Error>> {
+        let head_block = self
+            .proto_array
+            .get_block(&head_block_root)
+            .ok_or(Error::MissingProtoArrayBlock(head_block_root))?;
+        Ok(head_block
+            .parent_root
+            .and_then(|parent_root| self.proto_array.get_block(&parent_root))
+            .map(|parent_block| (head_block, parent_block)))
+    }
+
+    /// Returns `true` if the block arrived after the attestation deadline of its slot.
+    ///
+    /// Blocks imported before startup are considered timely.
+    fn is_head_late(&self, block_root: Hash256) -> bool {
+        self.block_timeliness.get(&block_root) == Some(&false)
+    }
+
+    /// Returns `true` if the chain has finalized recently enough to allow a re-org at `slot`.
+    fn is_finalization_ok(&self, slot: Slot) -> bool {
+        let epochs_since_finalization = slot
+            .epoch(E::slots_per_epoch())
+            .saturating_sub(self.fc_store.finalized_checkpoint().epoch);
+        epochs_since_finalization.as_u64() <= REORG_MAX_EPOCHS_SINCE_FINALIZATION
+    }
+
+    /// Returns `percent` percent of the weight of a single committee, computed from the justified
+    /// balances.
+    fn committee_fraction(&self, percent: u64, spec: &ChainSpec) -> u64 {
+        let total_active_balance = self
+            .fc_store
+            .justified_balances()
+            .iter()
+            .sum::<u64>()
+            .max(spec.effective_balance_increment);
+        total_active_balance / E::slots_per_epoch() * percent / 100
+    }
+
+    fn is_head_weak(&self, head_block: &ProtoBlock, spec: &ChainSpec) -> bool {
+        let reorg_threshold = self.committee_fraction(REORG_HEAD_WEIGHT_THRESHOLD, spec);
+        self.proto_array
+            .get_weight(&head_block.root)
+            .map_or(false, |weight| weight < reorg_threshold)
+    }
+
+    fn is_parent_strong(&self, parent_block: &ProtoBlock, spec: &ChainSpec) -> bool {
+        let parent_threshold = self.committee_fraction(REORG_PARENT_WEIGHT_THRESHOLD, spec);
+        self.proto_array
+            .get_weight(&parent_block.root)
+            .map_or(false, |weight| weight > parent_threshold)
+    }
+
     /// Prunes the underlying fork choice DAG.
     pub fn prune(&mut self) -> Result<(), Error<T::Error>> {
         let finalized_root = self.fc_store.finalized_checkpoint().root;
 
-        self.proto_array
-            .maybe_prune(finalized_root)
-            .map_err(Into::into)
+        self.proto_array.maybe_prune(finalized_root)?;
+
+        let proto_array = &self.proto_array;
+        self.block_timeliness
+            .retain(|block_root, _| proto_array.contains_block(block_root));
+
+        Ok(())
     }
 
     /// Instantiate `Self` from some `PersistedForkChoice` generated by a earlier call to
@@ -1118,6 +1318,7 @@ where
             proto_array,
             queued_attestations: persisted.queued_attestations,
             forkchoice_update_parameters: None,
+            block_timeliness: HashMap::new(),
             _phantom: PhantomData,
         })
     }
@@ -1132,6 +1333,24 @@ where
     }
 }
 
+/// Returns `true` if the proposer shuffling cannot change between the head and `slot`, i.e. `slot`
+/// is not the first slot of an epoch.
+fn is_shuffling_stable<E: EthSpec>(slot: Slot) -> bool {
+    slot % E::slots_per_epoch() != 0
+}
+
+/// Returns `true` if building on `parent_block` would not lose FFG information held by
+/// `head_block`.
+fn is_ffg_competitive(head_block: &ProtoBlock, parent_block: &ProtoBlock) -> bool {
+    head_block.justified_checkpoint == parent_block.justified_checkpoint
+}
+
+/// Returns `true` if `time_into_slot` is early enough for a re-org block to be proposed on time.
+fn is_proposing_on_time(time_into_slot: Duration, spec: &ChainSpec) -> bool {
+    let proposer_reorg_cutoff = spec.seconds_per_slot / INTERVALS_PER_SLOT / 2;
+    time_into_slot.as_secs() <= proposer_reorg_cutoff
+}
+
 /// Helper struct that is used to encode/decode the state of the `ForkChoice` as SSZ bytes.
 ///
 /// This is used when persisting the state of the fork choice to disk.
@@ -1169,6 +1388,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn shuffling_stable() {
+        assert!(!is_shuffling_stable::<E>(Slot::new(0)));
+        assert!(is_shuffling_stable::<E>(Slot::new(1)));
+        assert!(is_shuffling_stable::<E>(Slot::new(
+            E::slots_per_epoch() - 1
+        )));
+        assert!(!is_shuffling_stable::<E>(Slot::new(E::slots_per_epoch())));
+    }
+
+    #[test]
+    fn proposing_on_time() {
+        let spec = E::default_spec();
+        let cutoff = spec.seconds_per_slot / INTERVALS_PER_SLOT / 2;
+        assert!(is_proposing_on_time(Duration::from_secs(0), &spec));
+        assert!(is_proposing_on_time(Duration::from_secs(cutoff), &spec));
+        assert!(!is_proposing_on_time(
+            Duration::from_secs(cutoff + 1),
+            &spec
+        ));
+    }
+
     fn get_queued_attestations() -> Vec<QueuedAttestation> {
         (1..4)
             .into_iter()
@@ -17,8 +17,13 @@ lazy_static! {
 ///
 /// Efficiently represents a Merkle tree of fixed depth where only the first N
 /// indices are populated by non-zero leaves (perfect for the deposit contract tree).
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum MerkleTree {
+    /// Finalized Node
+    ///
+    /// A full subtree whose contents have been pruned, leaving only its hash. Proofs can not be
+    /// generated for leaves within a finalized subtree (see EIP-4881).
+    Finalized(H256),
     /// Leaf node with the hash of its content.
     Leaf(H256),
     /// Internal node with hash, left subtree and right subtree.
@@ -41,6 +46,24 @@ pub enum MerkleTreeError {
     DepthTooSmall,
     // Overflow occurred
     ArithError,
+    // Can't finalize a zero node
+    ZeroNodeFinalized,
+    // Can't push to finalized node
+    FinalizedNodePushed,
+    // Invalid Snapshot
+    InvalidSnapshot(InvalidSnapshot),
+    // Can't proof a finalized node
+    ProofEncounteredFinalizedNode,
+    // This should never happen
+    PleaseNotifyTheDevs,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum InvalidSnapshot {
+    // Branch hashes are empty but deposits are not
+    EmptyBranchWithNonZeroDeposits(usize),
+    // End of tree reached but deposits != 1
+    EndOfTree,
 }
 
 impl MerkleTree {
@@ -89,6 +112,7 @@ impl MerkleTree {
 
         match self {
             Leaf(_) => return Err(MerkleTreeError::LeafReached),
+            Finalized(_) => return Err(MerkleTreeError::FinalizedNodePushed),
             Zero(_) => {
                 *self = MerkleTree::create(&[elem], depth);
             }
@@ -97,19 +121,20 @@ impl MerkleTree {
                 let right: &mut MerkleTree = &mut *right;
                 match (&*left, &*right) {
                     // Tree is full
-                    (Leaf(_), Leaf(_)) => return Err(MerkleTreeError::MerkleTreeFull),
+                    (Leaf(_), Leaf(_)) | (Finalized(_), Leaf(_)) => {
+                        return Err(MerkleTreeError::MerkleTreeFull)
+                    }
                     // There is a right node so insert in right node
-                    (Node(_, _, _), Node(_, _, _)) => {
-                        if let Err(e) = right.push_leaf(elem, depth - 1) {
-                            return Err(e);
-                        }
+                    (Node(_, _, _), Node(_, _, _)) | (Finalized(_), Node(_, _, _)) => {
+                        right.push_leaf(elem, depth - 1)?;
                     }
                     // Both branches are zero, insert in left one
                     (Zero(_), Zero(_)) => {
                         *left = MerkleTree::create(&[elem], depth - 1);
                     }
-                    // Leaf on left branch and zero on right branch, insert on right side
-                    (Leaf(_), Zero(_)) => {
+                    // Leaf or finalized node on left branch and zero on right branch, insert on
+                    // right side
+                    (Leaf(_), Zero(_)) | (Finalized(_), Zero(_)) => {
                         *right = MerkleTree::create(&[elem], depth - 1);
                     }
                     // Try inserting on the left node -> if it fails because it is full, insert in right side.
@@ -139,6 +164,7 @@ impl MerkleTree {
     /// Retrieve the root hash of this Merkle tree.
     pub fn hash(&self) -> H256 {
         match *self {
+            MerkleTree::Finalized(h) => h,
             MerkleTree::Leaf(h) => h,
             MerkleTree::Node(h, _, _) => h,
             MerkleTree::Zero(depth) => H256::from_slice(&ZERO_HASHES[depth]),
@@ -148,7 +174,7 @@ impl MerkleTree {
     /// Get a reference to the left and right subtrees if they exist.
     pub fn left_and_right_branches(&self) -> Option<(&Self, &Self)> {
         match *self {
-            MerkleTree::Leaf(_) | MerkleTree::Zero(0) => None,
+            MerkleTree::Finalized(_) | MerkleTree::Leaf(_) | MerkleTree::Zero(0) => None,
             MerkleTree::Node(_, ref l, ref r) => Some((l, r)),
             MerkleTree::Zero(depth) => Some((&ZERO_NODES[depth - 1], &ZERO_NODES[depth - 1])),
         }
@@ -163,12 +189,21 @@ impl MerkleTree {
     ///
     /// The Merkle proof is in "bottom-up" order, starting with a leaf node
     /// and moving up the tree. Its length will be exactly equal to `depth`.
-    pub fn generate_proof(&self, index: usize, depth: usize) -> (H256, Vec<H256>) {
+    ///
+    /// Returns an error if the leaf at `index` is part of a finalized subtree.
+    pub fn generate_proof(
+        &self,
+        index: usize,
+        depth: usize,
+    ) -> Result<(H256, Vec<H256>), MerkleTreeError> {
         let mut proof = vec![];
         let mut current_node = self;
         let mut current_depth = depth;
         while current_depth > 0 {
             let ith_bit = (index >> (current_depth - 1)) & 0x01;
+            if let MerkleTree::Finalized(_) = current_node {
+                return Err(MerkleTreeError::ProofEncounteredFinalizedNode);
+            }
             // Note: unwrap is safe because leaves are only ever constructed at depth == 0.
             let (left, right) = current_node.left_and_right_branches().unwrap();
 
@@ -183,13 +218,129 @@ impl MerkleTree {
             current_depth -= 1;
         }
 
+        if let MerkleTree::Finalized(_) = current_node {
+            return Err(MerkleTreeError::ProofEncounteredFinalizedNode);
+        }
+
         debug_assert_eq!(proof.len(), depth);
         debug_assert!(current_node.is_leaf());
 
         // Put proof in bottom-up order.
         proof.reverse();
 
-        (current_node.hash(), proof)
+        Ok((current_node.hash(), proof))
+    }
+
+    /// Finalize the first `deposits_to_finalize` leaves of a tree of the given `level`, replacing
+    /// every full subtree within that range by a `Finalized` node.
+    pub fn finalize_deposits(
+        &mut self,
+        deposits_to_finalize: usize,
+        level: usize,
+    ) -> Result<(), MerkleTreeError> {
+        if deposits_to_finalize == 0 {
+            return Ok(());
+        }
+
+        match self {
+            MerkleTree::Finalized(_) => return Ok(()),
+            MerkleTree::Zero(_) => return Err(MerkleTreeError::ZeroNodeFinalized),
+            MerkleTree::Leaf(hash) => {
+                if level != 0 {
+                    // This shouldn't happen but this is a sanity check
+                    return Err(MerkleTreeError::PleaseNotifyTheDevs);
+                }
+                *self = MerkleTree::Finalized(*hash);
+            }
+            MerkleTree::Node(hash, left, right) => {
+                if level == 0 {
+                    // This shouldn't happen but we'll put it here for safety
+                    return Err(MerkleTreeError::PleaseNotifyTheDevs);
+                }
+                let deposits = 0x1 << level;
+                if deposits <= deposits_to_finalize {
+                    *self = MerkleTree::Finalized(*hash);
+                    return Ok(());
+                }
+                left.finalize_deposits(deposits_to_finalize, level - 1)?;
+                if deposits_to_finalize > deposits / 2 {
+                    let remaining = deposits_to_finalize - deposits / 2;
+                    right.finalize_deposits(remaining, level - 1)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the hashes of the finalized subtrees of this tree, from left to right.
+    ///
+    /// Together with the number of finalized leaves these are sufficient to rebuild the tree with
+    /// `from_finalized_snapshot`.
+    pub fn get_finalized_hashes(&self) -> Vec<H256> {
+        let mut result = vec![];
+        self.append_finalized_hashes(&mut result);
+        result
+    }
+
+    fn append_finalized_hashes(&self, result: &mut Vec<H256>) {
+        match self {
+            MerkleTree::Zero(_) | MerkleTree::Leaf(_) => {}
+            MerkleTree::Finalized(h) => result.push(*h),
+            MerkleTree::Node(_, left, right) => {
+                left.append_finalized_hashes(result);
+                right.append_finalized_hashes(result);
+            }
+        }
+    }
+
+    /// Rebuild a tree of the given `level` from the hashes of its finalized subtrees and the
+    /// number of finalized leaves.
+    pub fn from_finalized_snapshot(
+        finalized_branch: &[H256],
+        deposit_count: usize,
+        level: usize,
+    ) -> Result<Self, MerkleTreeError> {
+        if finalized_branch.is_empty() {
+            return if deposit_count == 0 {
+                Ok(MerkleTree::Zero(level))
+            } else {
+                Err(InvalidSnapshot::EmptyBranchWithNonZeroDeposits(deposit_count).into())
+            };
+        }
+        if deposit_count == (0x1 << level) {
+            return Ok(MerkleTree::Finalized(
+                *finalized_branch
+                    .get(0)
+                    .ok_or(MerkleTreeError::PleaseNotifyTheDevs)?,
+            ));
+        }
+        if level == 0 {
+            return Err(InvalidSnapshot::EndOfTree.into());
+        }
+
+        let (left, right) = match deposit_count.checked_sub(0x1 << (level - 1)) {
+            // left tree is fully finalized
+            Some(right_deposits) => {
+                let (left_hash, right_branch) = finalized_branch
+                    .split_first()
+                    .ok_or(MerkleTreeError::PleaseNotifyTheDevs)?;
+                (
+                    MerkleTree::Finalized(*left_hash),
+                    MerkleTree::from_finalized_snapshot(right_branch, right_deposits, level - 1)?,
+                )
+            }
+            // left tree is not fully finalized -> right tree is zero
+            None => (
+                MerkleTree::from_finalized_snapshot(finalized_branch, deposit_count, level - 1)?,
+                MerkleTree::Zero(level - 1),
+            ),
+        };
+
+        let hash = H256::from_slice(&hash32_concat(
+            left.hash().as_bytes(),
+            right.hash().as_bytes(),
+        ));
+        Ok(MerkleTree::Node(hash, Box::new(left), Box::new(right)))
     }
 }
 
@@ -237,6 +388,12 @@ impl From<ArithError> for MerkleTreeError {
     }
 }
 
+impl From<InvalidSnapshot> for MerkleTreeError {
+    fn from(e: InvalidSnapshot) -> Self {
+        MerkleTreeError::InvalidSnapshot(e)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -257,7 +414,9 @@ mod tests {
         let merkle_root = merkle_tree.hash();
 
         let proofs_ok = (0..leaves.len()).all(|i| {
-            let (leaf, branch) = merkle_tree.generate_proof(i, depth);
+            let (leaf, branch) = merkle_tree
+                .generate_proof(i, depth)
+                .expect("should generate proof");
             leaf == leaves[i] && verify_merkle_proof(leaf, &branch, depth, i, merkle_root)
         });
 
@@ -276,13 +435,62 @@ mod tests {
 
         let proofs_ok = leaves_iter.enumerate().all(|(i, leaf)| {
             assert_eq!(merkle_tree.push_leaf(leaf, depth), Ok(()));
-            let (stored_leaf, branch) = merkle_tree.generate_proof(i, depth);
+            let (stored_leaf, branch) = merkle_tree
+                .generate_proof(i, depth)
+                .expect("should generate proof");
             stored_leaf == leaf && verify_merkle_proof(leaf, &branch, depth, i, merkle_tree.hash())
         });
 
         TestResult::from_bool(proofs_ok)
     }
 
+    /// Check that finalizing a prefix of a tree preserves its root, and that a tree rebuilt from
+    /// the finalized hashes can be extended with the remaining leaves and still produce valid
+    /// proofs.
+    #[quickcheck]
+    fn quickcheck_finalize_and_rebuild_from_snapshot(
+        int_leaves: Vec<u64>,
+        finalized: usize,
+    ) -> TestResult {
+        let depth = 8;
+        if int_leaves.len() > 2usize.pow(depth as u32) || finalized > int_leaves.len() {
+            return TestResult::discard();
+        }
+
+        let leaves: Vec<_> = int_leaves.into_iter().map(H256::from_low_u64_be).collect();
+        let mut merkle_tree = MerkleTree::create(&leaves, depth);
+        let root = merkle_tree.hash();
+
+        merkle_tree
+            .finalize_deposits(finalized, depth)
+            .expect("should finalize deposits");
+        if merkle_tree.hash() != root {
+            return TestResult::failed();
+        }
+
+        let finalized_hashes = merkle_tree.get_finalized_hashes();
+        let mut rebuilt = MerkleTree::from_finalized_snapshot(&finalized_hashes, finalized, depth)
+            .expect("should rebuild from snapshot");
+        for leaf in &leaves[finalized..] {
+            rebuilt.push_leaf(*leaf, depth).expect("should push leaf");
+        }
+        if rebuilt.hash() != root {
+            return TestResult::failed();
+        }
+
+        let proofs_ok = (finalized..leaves.len()).all(|i| {
+            let (leaf, branch) = rebuilt
+                .generate_proof(i, depth)
+                .expect("should generate proof");
+            leaf == leaves[i] && verify_merkle_proof(leaf, &branch, depth, i, root)
+        });
+        let finalized_proofs_err = (0..finalized).all(|i| {
+            rebuilt.generate_proof(i, depth) == Err(MerkleTreeError::ProofEncounteredFinalizedNode)
+        });
+
+        TestResult::from_bool(proofs_ok && finalized_proofs_err)
+    }
+
     #[test]
     fn sparse_zero_correct() {
         let depth = 2;
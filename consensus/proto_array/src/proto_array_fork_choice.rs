@@ -4,7 +4,7 @@ use crate::ssz_container::SszContainer;
 use serde_derive::{Deserialize, Serialize};
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use types::{
     AttestationShufflingId, ChainSpec, Checkpoint, Epoch, EthSpec, ExecutionBlockHash, Hash256,
     Slot,
@@ -258,6 +258,7 @@ impl ProtoArrayForkChoice {
         finalized_checkpoint: Checkpoint,
         justified_state_balances: &[u64],
         proposer_boost_root: Hash256,
+        equivocating_indices: &BTreeSet<u64>,
         spec: &ChainSpec,
     ) -> Result<Hash256, String> {
         let old_balances = &mut self.balances;
@@ -269,6 +270,7 @@ impl ProtoArrayForkChoice {
             &mut self.votes,
             old_balances,
             new_balances,
+            equivocating_indices,
         )
         .map_err(|e| format!("find_head compute_deltas failed: {:?}", e))?;
 
@@ -417,6 +419,9 @@ impl ProtoArrayForkChoice {
 ///
 /// The deltas are formed by a change between `old_balances` and `new_balances`, and/or a change of vote in `votes`.
 ///
+/// The votes of any validator in `equivocating_indices` are removed from the tree and never
+/// counted again.
+///
 /// ## Errors
 ///
 /// - If a value in `indices` is greater to or equal to `indices.len()`.
@@ -427,10 +432,34 @@ fn compute_deltas(
     votes: &mut ElasticList<VoteTracker>,
     old_balances: &[u64],
     new_balances: &[u64],
+    equivocating_indices: &BTreeSet<u64>,
 ) -> Result<Vec<i64>, Error> {
     let mut deltas = vec![0_i64; indices.len()];
 
     for (val_index, vote) in votes.iter_mut().enumerate() {
+        // An equivocating validator has its weight removed from its current vote the first time
+        // the equivocation is processed. Its current root is then set to the zero hash so that its
+        // weight is never subtracted again, and its future votes are ignored.
+        if equivocating_indices.contains(&(val_index as u64)) {
+            if vote.current_root != Hash256::zero() {
+                let old_balance = old_balances.get(val_index).copied().unwrap_or(0);
+
+                if let Some(current_delta_index) = indices.get(&vote.current_root).copied() {
+                    let delta = deltas
+                        .get(current_delta_index)
+                        .ok_or(Error::InvalidNodeDelta(current_delta_index))?
+                        .checked_sub(old_balance as i64)
+                        .ok_or(Error::DeltaOverflow(current_delta_index))?;
+
+                    // Array access safe due to check on previous line.
+                    deltas[current_delta_index] = delta;
+                }
+
+                vote.current_root = Hash256::zero();
+            }
+            continue;
+        }
+
         // There is no need to create a score change if the validator has never voted or both their
         // votes are for the zero hash (alias to the genesis block).
         if vote.current_root == Hash256::zero() && vote.next_root == Hash256::zero() {
@@ -594,8 +623,14 @@ mod test_compute_deltas {
             new_balances.push(0);
         }
 
-        let deltas = compute_deltas(&indices, &mut votes, &old_balances, &new_balances)
-            .expect("should compute deltas");
+        let deltas = compute_deltas(
+            &indices,
+            &mut votes,
+            &old_balances,
+            &new_balances,
+            &BTreeSet::new(),
+        )
+        .expect("should compute deltas");
 
         assert_eq!(
             deltas.len(),
@@ -638,8 +673,14 @@ mod test_compute_deltas {
             new_balances.push(BALANCE);
         }
 
-        let deltas = compute_deltas(&indices, &mut votes, &old_balances, &new_balances)
-            .expect("should compute deltas");
+        let deltas = compute_deltas(
+            &indices,
+            &mut votes,
+            &old_balances,
+            &new_balances,
+            &BTreeSet::new(),
+        )
+        .expect("should compute deltas");
 
         assert_eq!(
             deltas.len(),
@@ -689,8 +730,14 @@ mod test_compute_deltas {
             new_balances.push(BALANCE);
         }
 
-        let deltas = compute_deltas(&indices, &mut votes, &old_balances, &new_balances)
-            .expect("should compute deltas");
+        let deltas = compute_deltas(
+            &indices,
+            &mut votes,
+            &old_balances,
+            &new_balances,
+            &BTreeSet::new(),
+        )
+        .expect("should compute deltas");
 
         assert_eq!(
             deltas.len(),
@@ -735,8 +782,14 @@ mod test_compute_deltas {
             new_balances.push(BALANCE);
         }
 
-        let deltas = compute_deltas(&indices, &mut votes, &old_balances, &new_balances)
-            .expect("should compute deltas");
+        let deltas = compute_deltas(
+            &indices,
+            &mut votes,
+            &old_balances,
+            &new_balances,
+            &BTreeSet::new(),
+        )
+        .expect("should compute deltas");
 
         assert_eq!(
             deltas.len(),
@@ -796,8 +849,14 @@ mod test_compute_deltas {
             next_epoch: Epoch::new(0),
         });
 
-        let deltas = compute_deltas(&indices, &mut votes, &old_balances, &new_balances)
-            .expect("should compute deltas");
+        let deltas = compute_deltas(
+            &indices,
+            &mut votes,
+            &old_balances,
+            &new_balances,
+            &BTreeSet::new(),
+        )
+        .expect("should compute deltas");
 
         assert_eq!(deltas.len(), 1, "deltas should have expected length");
 
@@ -838,8 +897,14 @@ mod test_compute_deltas {
             new_balances.push(NEW_BALANCE);
         }
 
-        let deltas = compute_deltas(&indices, &mut votes, &old_balances, &new_balances)
-            .expect("should compute deltas");
+        let deltas = compute_deltas(
+            &indices,
+            &mut votes,
+            &old_balances,
+            &new_balances,
+            &BTreeSet::new(),
+        )
+        .expect("should compute deltas");
 
         assert_eq!(
             deltas.len(),
@@ -898,8 +963,14 @@ mod test_compute_deltas {
             });
         }
 
-        let deltas = compute_deltas(&indices, &mut votes, &old_balances, &new_balances)
-            .expect("should compute deltas");
+        let deltas = compute_deltas(
+            &indices,
+            &mut votes,
+            &old_balances,
+            &new_balances,
+            &BTreeSet::new(),
+        )
+        .expect("should compute deltas");
 
         assert_eq!(deltas.len(), 2, "deltas should have expected length");
 
@@ -947,8 +1018,14 @@ mod test_compute_deltas {
             });
         }
 
-        let deltas = compute_deltas(&indices, &mut votes, &old_balances, &new_balances)
-            .expect("should compute deltas");
+        let deltas = compute_deltas(
+            &indices,
+            &mut votes,
+            &old_balances,
+            &new_balances,
+            &BTreeSet::new(),
+        )
+        .expect("should compute deltas");
 
         assert_eq!(deltas.len(), 2, "deltas should have expected length");
 
@@ -969,4 +1046,74 @@ mod test_compute_deltas {
             );
         }
     }
+
+    #[test]
+    fn validator_equivocates() {
+        const OLD_BALANCE: u64 = 42;
+        const NEW_BALANCE: u64 = 43;
+
+        let mut indices = HashMap::new();
+        let mut votes = ElasticList::default();
+
+        // There are two blocks.
+        indices.insert(hash_from_index(1), 0);
+        indices.insert(hash_from_index(2), 1);
+
+        // There are two validators.
+        let balances = vec![OLD_BALANCE; 2];
+
+        // Both validators move votes from block 1 to block 2.
+        for _ in 0..2 {
+            votes.0.push(VoteTracker {
+                current_root: hash_from_index(1),
+                next_root: hash_from_index(2),
+                next_epoch: Epoch::new(0),
+            });
+        }
+
+        // Validator 0 is slashed.
+        let equivocating_indices = BTreeSet::from_iter([0]);
+
+        let deltas = compute_deltas(
+            &indices,
+            &mut votes,
+            &balances,
+            &balances,
+            &equivocating_indices,
+        )
+        .expect("should compute deltas");
+
+        assert_eq!(deltas.len(), 2, "deltas should have expected length");
+        assert_eq!(
+            deltas[0],
+            -2 * OLD_BALANCE as i64,
+            "block 1 should have lost two old balances"
+        );
+        assert_eq!(
+            deltas[1], OLD_BALANCE as i64,
+            "block 2 should have gained one balance"
+        );
+
+        // Validator 0's current vote should be zeroed, and validator 1's should be updated.
+        assert_eq!(votes.0[0].current_root, Hash256::zero());
+        assert_eq!(votes.0[1].current_root, hash_from_index(2));
+
+        // A second call, with a change in balances, should not affect the equivocating validator.
+        let new_balances = vec![NEW_BALANCE; 2];
+
+        let deltas = compute_deltas(
+            &indices,
+            &mut votes,
+            &balances,
+            &new_balances,
+            &equivocating_indices,
+        )
+        .expect("should compute deltas");
+
+        assert_eq!(
+            deltas,
+            vec![0, 1],
+            "only validator 1's balance change counts"
+        );
+    }
 }
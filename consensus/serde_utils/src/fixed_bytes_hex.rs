@@ -50,3 +50,7 @@ pub mod bytes_4_hex {
 pub mod bytes_8_hex {
     bytes_hex!(8);
 }
+
+pub mod bytes_48_hex {
+    bytes_hex!(48);
+}
@@ -10,5 +10,5 @@ pub mod u32_hex;
 pub mod u64_hex_be;
 pub mod u8_hex;
 
-pub use fixed_bytes_hex::{bytes_4_hex, bytes_8_hex};
+pub use fixed_bytes_hex::{bytes_48_hex, bytes_4_hex, bytes_8_hex};
 pub use quoted_int::{quoted_u256, quoted_u32, quoted_u64, quoted_u8};
@@ -463,4 +463,65 @@ mod derive_macro {
         assert_encode_decode(&TwoVecUnion::A(vec![0, 1]), &[0, 0, 1]);
         assert_encode_decode(&TwoVecUnion::B(vec![0, 1]), &[1, 0, 1]);
     }
+
+    #[derive(PartialEq, Debug, Encode, Decode)]
+    #[ssz(struct_behaviour = "stable_container", max_fields = 4)]
+    struct Stable {
+        a: Option<u8>,
+        b: Option<u16>,
+        c: Option<Vec<u8>>,
+    }
+
+    #[derive(PartialEq, Debug, Encode, Decode)]
+    #[ssz(struct_behaviour = "stable_container", max_fields = 4)]
+    struct StableTruncated {
+        a: Option<u8>,
+        b: Option<u16>,
+    }
+
+    #[test]
+    fn stable_container() {
+        assert_encode_decode(
+            &Stable {
+                a: None,
+                b: None,
+                c: None,
+            },
+            &[0],
+        );
+        assert_encode_decode(
+            &Stable {
+                a: Some(1),
+                b: None,
+                c: Some(vec![2, 3]),
+            },
+            &[0b101, 1, 5, 0, 0, 0, 2, 3],
+        );
+        assert_encode_decode(
+            &Stable {
+                a: None,
+                b: Some(1),
+                c: None,
+            },
+            &[0b010, 1, 0],
+        );
+
+        // Values which don't use a field encode identically without it.
+        assert_encode_decode(
+            &StableTruncated {
+                a: None,
+                b: Some(1),
+            },
+            &[0b010, 1, 0],
+        );
+
+        // Fields unknown to the struct must not be active.
+        assert!(StableTruncated::from_ssz_bytes(&[0b101, 1, 5, 0, 0, 0, 2, 3]).is_err());
+        assert!(Stable::from_ssz_bytes(&[0b1000]).is_err());
+
+        // The active fields must match the fields present.
+        assert!(Stable::from_ssz_bytes(&[]).is_err());
+        assert!(Stable::from_ssz_bytes(&[0b001]).is_err());
+        assert!(Stable::from_ssz_bytes(&[0b000, 1]).is_err());
+    }
 }
@@ -18,6 +18,10 @@ const MAX_UNION_SELECTOR: u8 = 127;
 struct StructOpts {
     #[darling(default)]
     enum_behaviour: Option<String>,
+    #[darling(default)]
+    struct_behaviour: Option<String>,
+    #[darling(default)]
+    max_fields: Option<usize>,
 }
 
 /// Field-level configuration.
@@ -55,6 +59,97 @@ impl EnumBehaviour {
     }
 }
 
+const STRUCT_CONTAINER: &str = "container";
+const STRUCT_STABLE_CONTAINER: &str = "stable_container";
+const STRUCT_VARIANTS: &[&str] = &[STRUCT_CONTAINER, STRUCT_STABLE_CONTAINER];
+
+enum StructBehaviour {
+    Container,
+    /// An EIP-7495 stable container with capacity for `max_fields` fields.
+    StableContainer {
+        max_fields: usize,
+    },
+}
+
+impl StructBehaviour {
+    pub fn new(s: Option<String>, max_fields: Option<usize>) -> Self {
+        match s.as_deref() {
+            None | Some(STRUCT_CONTAINER) => {
+                if max_fields.is_some() {
+                    panic!("max_fields is only valid for stable containers");
+                }
+                StructBehaviour::Container
+            }
+            Some(STRUCT_STABLE_CONTAINER) => StructBehaviour::StableContainer {
+                max_fields: max_fields.expect(
+                    "stable containers require a \"max_fields\" attribute, \
+                     e.g., #[ssz(struct_behaviour = \"stable_container\", max_fields = 8)]",
+                ),
+            },
+            Some(other) => panic!(
+                "{} is an invalid struct_behaviour, use either {:?}",
+                other, STRUCT_VARIANTS
+            ),
+        }
+    }
+}
+
+/// Returns `T` if `ty` is `Option<T>`.
+fn option_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(type_path) if type_path.qself.is_none() => type_path.path.segments.last(),
+        _ => None,
+    }?;
+
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the fields of a stable container, and the type wrapped by each field's `Option`.
+///
+/// ## Panics
+///
+/// Will panic at compile-time if a field isn't an `Option`, has field attributes, or if there are
+/// more than `max_fields` fields.
+fn parse_stable_container_fields(
+    struct_data: &syn::DataStruct,
+    max_fields: usize,
+) -> Vec<(&syn::Type, &syn::Ident)> {
+    let fields = parse_ssz_fields(struct_data)
+        .into_iter()
+        .map(|(ty, ident, field_opts)| {
+            if field_opts.with.is_some()
+                || field_opts.skip_serializing
+                || field_opts.skip_deserializing
+            {
+                panic!("field attributes are not supported for stable containers");
+            }
+            let inner_ty = option_inner_type(ty)
+                .unwrap_or_else(|| panic!("stable container field {} must be an Option", ident));
+            (inner_ty, ident)
+        })
+        .collect::<Vec<_>>();
+
+    if fields.len() > max_fields {
+        panic!(
+            "stable container has {} fields, exceeding max_fields of {}",
+            fields.len(),
+            max_fields
+        );
+    }
+
+    fields
+}
+
 fn parse_ssz_fields(struct_data: &syn::DataStruct) -> Vec<(&syn::Type, &syn::Ident, FieldOpts)> {
     struct_data
         .fields
@@ -95,13 +190,22 @@ pub fn ssz_encode_derive(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as DeriveInput);
     let opts = StructOpts::from_derive_input(&item).unwrap();
     let enum_opt = EnumBehaviour::new(opts.enum_behaviour);
+    let struct_behaviour = StructBehaviour::new(opts.struct_behaviour, opts.max_fields);
 
     match &item.data {
         syn::Data::Struct(s) => {
             if enum_opt.is_some() {
                 panic!("enum_behaviour is invalid for structs");
             }
-            ssz_encode_derive_struct(&item, s)
+            match struct_behaviour {
+                StructBehaviour::Container => ssz_encode_derive_struct(&item, s),
+                StructBehaviour::StableContainer { max_fields } => {
+                    ssz_encode_derive_stable_container(&item, s, max_fields)
+                }
+            }
+        }
+        syn::Data::Enum(_) if !matches!(struct_behaviour, StructBehaviour::Container) => {
+            panic!("struct_behaviour is invalid for enums")
         }
         syn::Data::Enum(s) => match enum_opt.expect(NO_ENUM_BEHAVIOUR_ERROR) {
             EnumBehaviour::Transparent => ssz_encode_derive_enum_transparent(&item, s),
@@ -219,6 +323,89 @@ fn ssz_encode_derive_struct(derive_input: &DeriveInput, struct_data: &DataStruct
     output.into()
 }
 
+/// Derive `ssz::Encode` for an EIP-7495 "stable container".
+///
+/// The encoding is a `BitVector[max_fields]` of the fields which are `Some`, followed by those
+/// fields encoded as a container. Fields which are `None` take no space, so fields may be
+/// appended to the struct (up to `max_fields`) without changing the encoding of existing values.
+///
+/// ## Limitations
+///
+/// Only supports structs where every field is an `Option`, without field attributes.
+fn ssz_encode_derive_stable_container(
+    derive_input: &DeriveInput,
+    struct_data: &DataStruct,
+    max_fields: usize,
+) -> TokenStream {
+    let name = &derive_input.ident;
+    let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
+
+    let (types, idents): (Vec<_>, Vec<_>) = parse_stable_container_fields(struct_data, max_fields)
+        .into_iter()
+        .unzip();
+    let indices = (0..idents.len()).collect::<Vec<_>>();
+    let active_fields_len = (max_fields + 7) / 8;
+
+    let output = quote! {
+        impl #impl_generics ssz::Encode for #name #ty_generics #where_clause {
+            fn is_ssz_fixed_len() -> bool {
+                false
+            }
+
+            fn ssz_bytes_len(&self) -> usize {
+                let mut len: usize = #active_fields_len;
+                #(
+                    if let Some(field) = &self.#idents {
+                        if <#types as ssz::Encode>::is_ssz_fixed_len() {
+                            len = len
+                                .checked_add(<#types as ssz::Encode>::ssz_fixed_len())
+                                .expect("encode ssz_bytes_len length overflow");
+                        } else {
+                            len = len
+                                .checked_add(ssz::BYTES_PER_LENGTH_OFFSET)
+                                .expect("encode ssz_bytes_len length overflow for offset");
+                            len = len
+                                .checked_add(field.ssz_bytes_len())
+                                .expect("encode ssz_bytes_len length overflow for bytes");
+                        }
+                    }
+                )*
+                len
+            }
+
+            fn ssz_append(&self, buf: &mut Vec<u8>) {
+                let mut active_fields = [0_u8; #active_fields_len];
+                #(
+                    if self.#idents.is_some() {
+                        active_fields[#indices / 8] |= 1 << (#indices % 8);
+                    }
+                )*
+                buf.extend_from_slice(&active_fields);
+
+                let mut offset: usize = 0;
+                #(
+                    if self.#idents.is_some() {
+                        offset = offset
+                            .checked_add(<#types as ssz::Encode>::ssz_fixed_len())
+                            .expect("encode ssz_append offset overflow");
+                    }
+                )*
+
+                let mut encoder = ssz::SszEncoder::container(buf, offset);
+
+                #(
+                    if let Some(field) = &self.#idents {
+                        encoder.append(field);
+                    }
+                )*
+
+                encoder.finalize();
+            }
+        }
+    };
+    output.into()
+}
+
 /// Derive `ssz::Encode` for an enum in the "transparent" method.
 ///
 /// The "transparent" method is distinct from the "union" method specified in the SSZ specification.
@@ -369,13 +556,22 @@ pub fn ssz_decode_derive(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as DeriveInput);
     let opts = StructOpts::from_derive_input(&item).unwrap();
     let enum_opt = EnumBehaviour::new(opts.enum_behaviour);
+    let struct_behaviour = StructBehaviour::new(opts.struct_behaviour, opts.max_fields);
 
     match &item.data {
         syn::Data::Struct(s) => {
             if enum_opt.is_some() {
                 panic!("enum_behaviour is invalid for structs");
             }
-            ssz_decode_derive_struct(&item, s)
+            match struct_behaviour {
+                StructBehaviour::Container => ssz_decode_derive_struct(&item, s),
+                StructBehaviour::StableContainer { max_fields } => {
+                    ssz_decode_derive_stable_container(&item, s, max_fields)
+                }
+            }
+        }
+        syn::Data::Enum(_) if !matches!(struct_behaviour, StructBehaviour::Container) => {
+            panic!("struct_behaviour is invalid for enums")
         }
         syn::Data::Enum(s) => match enum_opt.expect(NO_ENUM_BEHAVIOUR_ERROR) {
             EnumBehaviour::Transparent => panic!(
@@ -545,6 +741,77 @@ fn ssz_decode_derive_struct(item: &DeriveInput, struct_data: &DataStruct) -> Tok
     output.into()
 }
 
+/// Implements `ssz::Decode` for an EIP-7495 "stable container".
+///
+/// See `ssz_encode_derive_stable_container` for the encoding. Decoding fails if the active fields
+/// include a field which the struct doesn't have.
+fn ssz_decode_derive_stable_container(
+    item: &DeriveInput,
+    struct_data: &DataStruct,
+    max_fields: usize,
+) -> TokenStream {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    let (types, idents): (Vec<_>, Vec<_>) = parse_stable_container_fields(struct_data, max_fields)
+        .into_iter()
+        .unzip();
+    let indices = (0..idents.len()).collect::<Vec<_>>();
+    let num_fields = idents.len();
+    let active_fields_len = (max_fields + 7) / 8;
+
+    let output = quote! {
+        impl #impl_generics ssz::Decode for #name #ty_generics #where_clause {
+            fn is_ssz_fixed_len() -> bool {
+                false
+            }
+
+            fn from_ssz_bytes(bytes: &[u8]) -> std::result::Result<Self, ssz::DecodeError> {
+                if bytes.len() < #active_fields_len {
+                    return Err(ssz::DecodeError::InvalidByteLength {
+                        len: bytes.len(),
+                        expected: #active_fields_len,
+                    });
+                }
+                let (active_fields, body) = bytes.split_at(#active_fields_len);
+                let is_active = |i: usize| (active_fields[i / 8] >> (i % 8)) & 1 == 1;
+
+                if let Some(i) = (#num_fields..#active_fields_len * 8).find(|&i| is_active(i)) {
+                    return Err(ssz::DecodeError::BytesInvalid(format!(
+                        "stable container field {} is active but unknown",
+                        i
+                    )));
+                }
+
+                let mut builder = ssz::SszDecoderBuilder::new(body);
+
+                #(
+                    if is_active(#indices) {
+                        builder.register_type::<#types>()?;
+                    }
+                )*
+
+                let mut decoder = builder.build()?;
+
+                #(
+                    let #idents = if is_active(#indices) {
+                        Some(decoder.decode_next::<#types>()?)
+                    } else {
+                        None
+                    };
+                )*
+
+                Ok(Self {
+                    #(
+                        #idents,
+                    )*
+                })
+            }
+        }
+    };
+    output.into()
+}
+
 /// Derive `ssz::Decode` for an `enum` following the "union" SSZ spec.
 fn ssz_decode_derive_enum_union(derive_input: &DeriveInput, enum_data: &DataEnum) -> TokenStream {
     let name = &derive_input.ident;
@@ -2,8 +2,11 @@
 //!
 //! - `FixedVector`: A heap-allocated list with a size that is fixed at compile time.
 //! - `VariableList`: A heap-allocated list that cannot grow past a type-level maximum length.
+//! - `PersistentList`: A `VariableList` which shares its values between copies of the list.
 //! - `BitList`: A heap-allocated bitfield that with a type-level _maximum_ length.
 //! - `BitVector`: A heap-allocated bitfield that with a type-level _fixed__ length.
+//! - `ProgressiveList`: A heap-allocated list without a maximum length, which is merkleized
+//!   progressively.
 //!
 //! These structs are required as SSZ serialization and Merklization rely upon type-level lengths
 //! for padding and verification.
@@ -40,12 +43,16 @@
 #[macro_use]
 mod bitfield;
 mod fixed_vector;
+mod persistent_list;
+mod progressive_list;
 pub mod serde_utils;
 mod tree_hash;
 mod variable_list;
 
 pub use bitfield::{BitList, BitVector, Bitfield};
 pub use fixed_vector::FixedVector;
+pub use persistent_list::{PersistentList, PERSISTENT_LIST_CHUNK_LEN};
+pub use progressive_list::ProgressiveList;
 pub use typenum;
 pub use variable_list::VariableList;
 
@@ -0,0 +1,582 @@
+use crate::tree_hash::vec_tree_hash_root;
+use crate::{Error, VariableList};
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+use ssz::{SszEncoder, BYTES_PER_LENGTH_OFFSET};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+use std::sync::Arc;
+use tree_hash::Hash256;
+use typenum::Unsigned;
+
+/// The number of values stored in each chunk of a `PersistentList`.
+///
+/// Cloning a list costs one reference count increment per chunk, whilst mutating a value which
+/// is shared with another list copies the chunk containing it.
+pub const PERSISTENT_LIST_CHUNK_LEN: usize = 1024;
+
+/// Emulates a SSZ `List`, sharing its values between copies of the list.
+///
+/// An ordered, heap-allocated, variable-length, homogeneous collection of `T`, with no more than
+/// `N` values. It serializes and merkleizes identically to a `VariableList`.
+///
+/// The values are stored in reference-counted chunks of `PERSISTENT_LIST_CHUNK_LEN` values. A
+/// clone of the list shares all of its chunks with the original and a chunk is only copied when
+/// one of its values is mutated (copy-on-write). This makes cloning cheap and allows many similar
+/// lists (e.g. the validator registries of consecutive `BeaconState`s) to be held in memory at
+/// once.
+///
+/// Since the values are not contiguous in memory, the list cannot be viewed as a slice.
+///
+/// ## Example
+///
+/// ```
+/// use ssz_types::{PersistentList, typenum};
+///
+/// let mut list: PersistentList<u64, typenum::U4> = PersistentList::new(vec![1, 2, 3]).unwrap();
+/// let copy = list.clone();
+///
+/// list.push(4).unwrap();
+/// list[0] = 5;
+/// assert!(list.push(6).is_err());
+///
+/// assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![5, 2, 3, 4]);
+/// assert_eq!(copy.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+/// ```
+#[derive(Clone)]
+pub struct PersistentList<T, N> {
+    /// Every chunk is full, except the last which is non-empty.
+    chunks: Vec<Arc<Vec<T>>>,
+    len: usize,
+    _phantom: PhantomData<N>,
+}
+
+impl<T, N: Unsigned> PersistentList<T, N> {
+    /// Returns `Ok` if the given `vec` is no longer than the maximum length of `Self`.
+    pub fn new(vec: Vec<T>) -> Result<Self, Error> {
+        if vec.len() <= N::to_usize() {
+            Ok(Self::from_vec_unchecked(vec))
+        } else {
+            Err(Error::OutOfBounds {
+                i: vec.len(),
+                len: Self::max_len(),
+            })
+        }
+    }
+
+    fn from_vec_unchecked(vec: Vec<T>) -> Self {
+        let len = vec.len();
+        let mut chunks =
+            Vec::with_capacity((len + PERSISTENT_LIST_CHUNK_LEN - 1) / PERSISTENT_LIST_CHUNK_LEN);
+        let mut chunk = Vec::with_capacity(std::cmp::min(len, PERSISTENT_LIST_CHUNK_LEN));
+        for value in vec {
+            if chunk.len() == PERSISTENT_LIST_CHUNK_LEN {
+                let full_chunk =
+                    std::mem::replace(&mut chunk, Vec::with_capacity(PERSISTENT_LIST_CHUNK_LEN));
+                chunks.push(Arc::new(full_chunk));
+            }
+            chunk.push(value);
+        }
+        if !chunk.is_empty() {
+            chunks.push(Arc::new(chunk));
+        }
+
+        Self {
+            chunks,
+            len,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Create an empty list.
+    pub fn empty() -> Self {
+        Self {
+            chunks: vec![],
+            len: 0,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the number of values presently in `self`.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// True if `self` does not contain any values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the type-level maximum length.
+    pub fn max_len() -> usize {
+        N::to_usize()
+    }
+
+    /// Returns a reference to the value at `index`, if any.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.chunks
+            .get(index / PERSISTENT_LIST_CHUNK_LEN)?
+            .get(index % PERSISTENT_LIST_CHUNK_LEN)
+    }
+
+    /// Returns an iterator over the values of `self`.
+    pub fn iter(&self) -> Iter<'_, T, N> {
+        Iter {
+            list: self,
+            front: 0,
+            back: self.len,
+        }
+    }
+
+    /// Returns the values of `self` as consecutive slices, in order.
+    pub fn chunks(&self) -> impl Iterator<Item = &[T]> + '_ {
+        self.chunks.iter().map(|chunk| chunk.as_slice())
+    }
+
+    /// Returns the number of chunks which are shared by `self` and `other`.
+    pub fn shared_chunk_count(&self, other: &Self) -> usize {
+        self.chunks
+            .iter()
+            .zip(&other.chunks)
+            .filter(|(a, b)| Arc::ptr_eq(a, b))
+            .count()
+    }
+}
+
+impl<T: Clone, N: Unsigned> PersistentList<T, N> {
+    /// Returns a mutable reference to the value at `index`, if any.
+    ///
+    /// Copies the chunk containing `index` if it is shared with another list.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len {
+            return None;
+        }
+        Arc::make_mut(self.chunks.get_mut(index / PERSISTENT_LIST_CHUNK_LEN)?)
+            .get_mut(index % PERSISTENT_LIST_CHUNK_LEN)
+    }
+
+    /// Returns a mutable iterator over the values of `self`.
+    ///
+    /// Copies every chunk which is shared with another list, prefer `Self::get_mut` when only a
+    /// few values need to change.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> + '_ {
+        self.chunks
+            .iter_mut()
+            .flat_map(|chunk| Arc::make_mut(chunk).iter_mut())
+    }
+
+    /// Appends `value` to the back of `self`.
+    ///
+    /// Returns `Err(())` when appending `value` would exceed the maximum length.
+    pub fn push(&mut self, value: T) -> Result<(), Error> {
+        if self.len >= Self::max_len() {
+            return Err(Error::OutOfBounds {
+                i: self.len + 1,
+                len: Self::max_len(),
+            });
+        }
+
+        match self.chunks.last_mut() {
+            Some(chunk) if chunk.len() < PERSISTENT_LIST_CHUNK_LEN => {
+                Arc::make_mut(chunk).push(value)
+            }
+            _ => {
+                let mut chunk = Vec::with_capacity(PERSISTENT_LIST_CHUNK_LEN);
+                chunk.push(value);
+                self.chunks.push(Arc::new(chunk))
+            }
+        }
+        self.len += 1;
+
+        Ok(())
+    }
+}
+
+impl<T, N: Unsigned> From<Vec<T>> for PersistentList<T, N> {
+    fn from(mut vec: Vec<T>) -> Self {
+        vec.truncate(N::to_usize());
+        Self::from_vec_unchecked(vec)
+    }
+}
+
+impl<T, N: Unsigned> From<VariableList<T, N>> for PersistentList<T, N> {
+    fn from(list: VariableList<T, N>) -> Self {
+        Self::from_vec_unchecked(list.into())
+    }
+}
+
+impl<T: Clone, N: Unsigned> From<PersistentList<T, N>> for Vec<T> {
+    fn from(list: PersistentList<T, N>) -> Vec<T> {
+        let mut vec = Vec::with_capacity(list.len);
+        for chunk in list.chunks {
+            match Arc::try_unwrap(chunk) {
+                Ok(chunk) => vec.extend(chunk),
+                Err(chunk) => vec.extend_from_slice(&chunk),
+            }
+        }
+        vec
+    }
+}
+
+impl<T, N: Unsigned> Default for PersistentList<T, N> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T: fmt::Debug, N: Unsigned> fmt::Debug for PersistentList<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, N: Unsigned> PartialEq for PersistentList<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len
+            && self
+                .chunks
+                .iter()
+                .zip(&other.chunks)
+                .all(|(a, b)| Arc::ptr_eq(a, b) || a == b)
+    }
+}
+
+impl<T: Eq, N: Unsigned> Eq for PersistentList<T, N> {}
+
+impl<T: Hash, N: Unsigned> Hash for PersistentList<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        for value in self.iter() {
+            value.hash(state);
+        }
+    }
+}
+
+impl<T, N: Unsigned> Index<usize> for PersistentList<T, N> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        let len = self.len;
+        self.get(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                len, index
+            )
+        })
+    }
+}
+
+impl<T: Clone, N: Unsigned> IndexMut<usize> for PersistentList<T, N> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        let len = self.len;
+        self.get_mut(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                len, index
+            )
+        })
+    }
+}
+
+/// An iterator over the values of a `PersistentList`.
+pub struct Iter<'a, T, N> {
+    list: &'a PersistentList<T, N>,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, T, N: Unsigned> Iterator for Iter<'a, T, N> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.front < self.back {
+            let value = self.list.get(self.front);
+            self.front += 1;
+            value
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<&'a T> {
+        self.front = std::cmp::min(self.front.saturating_add(n), self.back);
+        self.next()
+    }
+}
+
+impl<'a, T, N: Unsigned> DoubleEndedIterator for Iter<'a, T, N> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.front < self.back {
+            self.back -= 1;
+            self.list.get(self.back)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, T, N: Unsigned> ExactSizeIterator for Iter<'a, T, N> {}
+
+impl<'a, T, N: Unsigned> FusedIterator for Iter<'a, T, N> {}
+
+impl<'a, T, N: Unsigned> IntoIterator for &'a PersistentList<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T, N: Unsigned> tree_hash::TreeHash for PersistentList<T, N>
+where
+    T: tree_hash::TreeHash,
+{
+    fn tree_hash_type() -> tree_hash::TreeHashType {
+        tree_hash::TreeHashType::List
+    }
+
+    fn tree_hash_packed_encoding(&self) -> Vec<u8> {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        let root = vec_tree_hash_root::<T, N>(self);
+
+        tree_hash::mix_in_length(&root, self.len())
+    }
+}
+
+impl<T, N: Unsigned> ssz::Encode for PersistentList<T, N>
+where
+    T: ssz::Encode,
+{
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        if T::is_ssz_fixed_len() {
+            T::ssz_fixed_len() * self.len()
+        } else {
+            self.iter().map(|item| item.ssz_bytes_len()).sum::<usize>()
+                + BYTES_PER_LENGTH_OFFSET * self.len()
+        }
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        if T::is_ssz_fixed_len() {
+            buf.reserve(T::ssz_fixed_len() * self.len());
+
+            for item in self {
+                item.ssz_append(buf);
+            }
+        } else {
+            let mut encoder = SszEncoder::container(buf, self.len() * BYTES_PER_LENGTH_OFFSET);
+
+            for item in self {
+                encoder.append(item);
+            }
+
+            encoder.finalize();
+        }
+    }
+}
+
+impl<T, N> ssz::Decode for PersistentList<T, N>
+where
+    T: ssz::Decode,
+    N: Unsigned,
+{
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
+        VariableList::<T, N>::from_ssz_bytes(bytes).map(Into::into)
+    }
+}
+
+impl<T: Serialize, N: Unsigned> Serialize for PersistentList<T, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for value in self {
+            seq.serialize_element(value)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de, T: Deserialize<'de>, N: Unsigned> Deserialize<'de> for PersistentList<T, N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let vec = Vec::deserialize(deserializer)?;
+        Self::new(vec).map_err(|e| serde::de::Error::custom(format!("PersistentList: {:?}", e)))
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, T: arbitrary::Arbitrary<'a>, N: 'static + Unsigned> arbitrary::Arbitrary<'a>
+    for PersistentList<T, N>
+{
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        <VariableList<T, N> as arbitrary::Arbitrary>::arbitrary(u).map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ssz::*;
+    use tree_hash::TreeHash;
+    use typenum::*;
+
+    type List = PersistentList<u64, U1048576>;
+
+    fn values(len: usize) -> Vec<u64> {
+        (0..len as u64).collect()
+    }
+
+    #[test]
+    fn new() {
+        assert!(PersistentList::<u64, U4>::new(vec![42; 5]).is_err());
+        assert!(PersistentList::<u64, U4>::new(vec![42; 4]).is_ok());
+        assert!(PersistentList::<u64, U4>::new(vec![]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn chunk_boundaries() {
+        for len in [
+            0,
+            1,
+            PERSISTENT_LIST_CHUNK_LEN - 1,
+            PERSISTENT_LIST_CHUNK_LEN,
+            PERSISTENT_LIST_CHUNK_LEN + 1,
+            3 * PERSISTENT_LIST_CHUNK_LEN + 7,
+        ] {
+            let vec = values(len);
+            let list = List::new(vec.clone()).unwrap();
+
+            assert_eq!(list.len(), len);
+            assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec);
+            assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), {
+                let mut reversed = vec.clone();
+                reversed.reverse();
+                reversed
+            });
+            assert_eq!(list.iter().len(), len);
+            assert_eq!(list.get(len), None);
+            assert_eq!(Vec::from(list), vec);
+
+            let mut pushed = List::empty();
+            for value in vec.clone() {
+                pushed.push(value).unwrap();
+            }
+            assert_eq!(pushed, List::new(vec).unwrap());
+        }
+    }
+
+    #[test]
+    fn iter_nth() {
+        let list = List::new(values(3 * PERSISTENT_LIST_CHUNK_LEN)).unwrap();
+        let mut iter = list.iter().skip(PERSISTENT_LIST_CHUNK_LEN + 1);
+        assert_eq!(iter.next(), Some(&(PERSISTENT_LIST_CHUNK_LEN as u64 + 1)));
+        assert_eq!(list.iter().nth(10 * PERSISTENT_LIST_CHUNK_LEN), None);
+    }
+
+    #[test]
+    fn copy_on_write() {
+        let original = List::new(values(3 * PERSISTENT_LIST_CHUNK_LEN)).unwrap();
+        let mut copy = original.clone();
+        assert_eq!(copy.shared_chunk_count(&original), 3);
+
+        copy[PERSISTENT_LIST_CHUNK_LEN] = 0;
+        assert_eq!(copy.shared_chunk_count(&original), 2);
+        assert_eq!(
+            original[PERSISTENT_LIST_CHUNK_LEN],
+            PERSISTENT_LIST_CHUNK_LEN as u64
+        );
+        assert_ne!(copy, original);
+
+        copy.push(42).unwrap();
+        assert_eq!(copy.shared_chunk_count(&original), 2);
+        assert_eq!(original.len(), 3 * PERSISTENT_LIST_CHUNK_LEN);
+
+        for value in copy.iter_mut() {
+            *value += 1;
+        }
+        assert_eq!(copy.shared_chunk_count(&original), 0);
+        assert_eq!(copy[0], 1);
+        assert_eq!(original[0], 0);
+    }
+
+    #[test]
+    fn get_mut_out_of_bounds() {
+        let original = List::new(values(2)).unwrap();
+        let mut copy = original.clone();
+        assert_eq!(copy.get_mut(2), None);
+        assert_eq!(copy.shared_chunk_count(&original), 1);
+    }
+
+    #[test]
+    fn push_max_len() {
+        let mut list = PersistentList::<u64, U2>::empty();
+        list.push(1).unwrap();
+        list.push(2).unwrap();
+        assert!(list.push(3).is_err());
+    }
+
+    fn matches_variable_list<T>(vec: Vec<T>)
+    where
+        T: Clone + Encode + Decode + TreeHash + PartialEq + fmt::Debug,
+    {
+        let persistent: PersistentList<T, U1048576> = PersistentList::new(vec.clone()).unwrap();
+        let variable: VariableList<T, U1048576> = VariableList::new(vec).unwrap();
+
+        assert_eq!(persistent.as_ssz_bytes(), variable.as_ssz_bytes());
+        assert_eq!(persistent.ssz_bytes_len(), variable.ssz_bytes_len());
+        assert_eq!(persistent.tree_hash_root(), variable.tree_hash_root());
+        assert_eq!(
+            PersistentList::<T, U1048576>::from_ssz_bytes(&variable.as_ssz_bytes()).unwrap(),
+            persistent
+        );
+    }
+
+    #[test]
+    fn ssz_and_tree_hash() {
+        for len in [0, 1, PERSISTENT_LIST_CHUNK_LEN + 1] {
+            matches_variable_list(values(len));
+            matches_variable_list(
+                values(len)
+                    .into_iter()
+                    .map(|i| VariableList::<u8, U4>::from(vec![i as u8; i as usize % 3]))
+                    .collect(),
+            );
+        }
+    }
+
+    #[test]
+    fn serde() {
+        let list = PersistentList::<u64, U4>::new(vec![1, 2, 3]).unwrap();
+        let json = serde_json::to_string(&list).unwrap();
+        assert_eq!(json, "[1,2,3]");
+        assert_eq!(
+            serde_json::from_str::<PersistentList<u64, U4>>(&json).unwrap(),
+            list
+        );
+        serde_json::from_str::<PersistentList<u64, U4>>("[1,2,3,4,5]").unwrap_err();
+    }
+}
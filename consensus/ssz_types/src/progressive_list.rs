@@ -0,0 +1,241 @@
+use crate::tree_hash::progressive_vec_tree_hash_root;
+use derivative::Derivative;
+use serde_derive::{Deserialize, Serialize};
+use std::ops::{Deref, DerefMut, Index, IndexMut};
+use std::slice::SliceIndex;
+use tree_hash::Hash256;
+
+/// Emulates a SSZ `ProgressiveList`.
+///
+/// An ordered, heap-allocated, variable-length, homogeneous collection of `T` without a
+/// type-level maximum length.
+///
+/// It serializes identically to a `VariableList`, but is merkleized progressively (see
+/// `tree_hash::merkleize_progressive`) so that the generalized index of each item doesn't depend
+/// on a maximum length, and stays the same as the list grows.
+///
+/// ## Example
+///
+/// ```
+/// use ssz_types::ProgressiveList;
+///
+/// let mut list: ProgressiveList<u64> = ProgressiveList::from(vec![1, 2, 3]);
+/// list.push(4);
+/// assert_eq!(&list[..], &[1, 2, 3, 4]);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, Derivative)]
+#[derivative(PartialEq, Eq, Hash(bound = "T: std::hash::Hash"))]
+#[serde(transparent)]
+pub struct ProgressiveList<T> {
+    vec: Vec<T>,
+}
+
+impl<T> ProgressiveList<T> {
+    /// Create an empty list.
+    pub fn empty() -> Self {
+        Self { vec: vec![] }
+    }
+
+    /// Returns the number of values presently in `self`.
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// True if `self` does not contain any values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends `value` to the back of `self`.
+    pub fn push(&mut self, value: T) {
+        self.vec.push(value)
+    }
+}
+
+impl<T> From<Vec<T>> for ProgressiveList<T> {
+    fn from(vec: Vec<T>) -> Self {
+        Self { vec }
+    }
+}
+
+impl<T> From<ProgressiveList<T>> for Vec<T> {
+    fn from(list: ProgressiveList<T>) -> Vec<T> {
+        list.vec
+    }
+}
+
+impl<T> Default for ProgressiveList<T> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T, I: SliceIndex<[T]>> Index<I> for ProgressiveList<T> {
+    type Output = I::Output;
+
+    #[inline]
+    fn index(&self, index: I) -> &Self::Output {
+        Index::index(&self.vec, index)
+    }
+}
+
+impl<T, I: SliceIndex<[T]>> IndexMut<I> for ProgressiveList<T> {
+    #[inline]
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        IndexMut::index_mut(&mut self.vec, index)
+    }
+}
+
+impl<T> Deref for ProgressiveList<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.vec[..]
+    }
+}
+
+impl<T> DerefMut for ProgressiveList<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.vec[..]
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ProgressiveList<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> tree_hash::TreeHash for ProgressiveList<T>
+where
+    T: tree_hash::TreeHash,
+{
+    fn tree_hash_type() -> tree_hash::TreeHashType {
+        tree_hash::TreeHashType::List
+    }
+
+    fn tree_hash_packed_encoding(&self) -> Vec<u8> {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        let root = progressive_vec_tree_hash_root(&self.vec);
+
+        tree_hash::mix_in_length(&root, self.len())
+    }
+}
+
+impl<T> ssz::Encode for ProgressiveList<T>
+where
+    T: ssz::Encode,
+{
+    fn is_ssz_fixed_len() -> bool {
+        <Vec<T>>::is_ssz_fixed_len()
+    }
+
+    fn ssz_fixed_len() -> usize {
+        <Vec<T>>::ssz_fixed_len()
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        self.vec.ssz_bytes_len()
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        self.vec.ssz_append(buf)
+    }
+}
+
+impl<T> ssz::Decode for ProgressiveList<T>
+where
+    T: ssz::Decode,
+{
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
+        <Vec<T>>::from_ssz_bytes(bytes).map(Into::into)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, T: arbitrary::Arbitrary<'a>> arbitrary::Arbitrary<'a> for ProgressiveList<T> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Vec::<T>::arbitrary(u).map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::VariableList;
+    use ssz::*;
+    use tree_hash::{merkleize_progressive, mix_in_length, TreeHash};
+    use tree_hash_derive::TreeHash;
+    use typenum::*;
+
+    fn round_trip<T: Encode + Decode + std::fmt::Debug + PartialEq>(item: T) {
+        let encoded = &item.as_ssz_bytes();
+        assert_eq!(item.ssz_bytes_len(), encoded.len());
+        assert_eq!(T::from_ssz_bytes(encoded), Ok(item));
+    }
+
+    #[test]
+    fn ssz_round_trip() {
+        round_trip::<ProgressiveList<u16>>(vec![].into());
+        round_trip::<ProgressiveList<u16>>(vec![42; 1000].into());
+        round_trip::<ProgressiveList<ProgressiveList<u8>>>(
+            vec![vec![1, 2].into(), vec![].into(), vec![3].into()].into(),
+        );
+    }
+
+    #[test]
+    fn encodes_like_variable_list() {
+        let vec = vec![1_u64, 2, 3];
+        let list: ProgressiveList<u64> = vec.clone().into();
+        let variable_list: VariableList<u64, U8> = vec.into();
+        assert_eq!(list.as_ssz_bytes(), variable_list.as_ssz_bytes());
+    }
+
+    #[test]
+    fn tree_hash_basic() {
+        let empty: ProgressiveList<u64> = ProgressiveList::empty();
+        assert_eq!(empty.tree_hash_root(), mix_in_length(&Hash256::zero(), 0));
+
+        // Eight `u64`s pack into two chunks, spanning the first two subtrees.
+        let list: ProgressiveList<u64> = (0..8).collect::<Vec<_>>().into();
+        let packed = (0..8_u64).flat_map(|i| i.to_le_bytes()).collect::<Vec<_>>();
+        assert_eq!(
+            list.tree_hash_root(),
+            mix_in_length(&merkleize_progressive(&packed), 8)
+        );
+    }
+
+    #[derive(Clone, Copy, TreeHash, Default)]
+    struct A {
+        a: u32,
+        b: u32,
+    }
+
+    #[test]
+    fn tree_hash_composite() {
+        let items = vec![A { a: 1, b: 2 }; 6];
+        let list: ProgressiveList<A> = items.clone().into();
+        let roots = items
+            .iter()
+            .flat_map(|item| item.tree_hash_root().to_fixed_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            list.tree_hash_root(),
+            mix_in_length(&merkleize_progressive(&roots), 6)
+        );
+    }
+}
@@ -2,4 +2,5 @@ pub mod hex_fixed_vec;
 pub mod hex_var_list;
 pub mod list_of_hex_var_list;
 pub mod quoted_u64_fixed_vec;
+pub mod quoted_u64_persistent_list;
 pub mod quoted_u64_var_list;
@@ -0,0 +1,89 @@
+//! Formats `PersistentList<u64,N>` using quotes.
+//!
+//! E.g., `PersistentList::from(vec![0, 1, 2])` serializes as `["0", "1", "2"]`.
+//!
+//! Quotes can be optional during decoding. If the length of the `Vec` is greater than `N`, deserialization fails.
+
+use crate::serde_utils::quoted_u64_var_list::deserialize_max;
+use crate::PersistentList;
+use eth2_serde_utils::quoted_u64_vec::QuotedIntWrapper;
+use serde::ser::SerializeSeq;
+use serde::{Deserializer, Serializer};
+use std::marker::PhantomData;
+use typenum::Unsigned;
+
+pub struct QuotedIntPersistentListVisitor<N> {
+    _phantom: PhantomData<N>,
+}
+
+impl<'a, N> serde::de::Visitor<'a> for QuotedIntPersistentListVisitor<N>
+where
+    N: Unsigned,
+{
+    type Value = PersistentList<u64, N>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "a list of quoted or unquoted integers")
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'a>,
+    {
+        let vec = deserialize_max(seq, N::to_usize())?;
+        let list: PersistentList<u64, N> = PersistentList::new(vec)
+            .map_err(|e| serde::de::Error::custom(format!("PersistentList: {:?}", e)))?;
+        Ok(list)
+    }
+}
+
+pub fn serialize<S, N>(value: &PersistentList<u64, N>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    N: Unsigned,
+{
+    let mut seq = serializer.serialize_seq(Some(value.len()))?;
+    for &int in value {
+        seq.serialize_element(&QuotedIntWrapper { int })?;
+    }
+    seq.end()
+}
+
+pub fn deserialize<'de, D, N>(deserializer: D) -> Result<PersistentList<u64, N>, D::Error>
+where
+    D: Deserializer<'de>,
+    N: Unsigned,
+{
+    deserializer.deserialize_any(QuotedIntPersistentListVisitor {
+        _phantom: PhantomData,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_derive::{Deserialize, Serialize};
+    use typenum::U4;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Obj {
+        #[serde(with = "crate::serde_utils::quoted_u64_persistent_list")]
+        values: PersistentList<u64, U4>,
+    }
+
+    #[test]
+    fn quoted_list_round_trip() {
+        let obj: Obj = serde_json::from_str(r#"{ "values": ["1", 2, "3"] }"#).unwrap();
+        let expected: PersistentList<u64, U4> = PersistentList::from(vec![1, 2, 3]);
+        assert_eq!(obj.values, expected);
+        assert_eq!(
+            serde_json::to_string(&obj).unwrap(),
+            r#"{"values":["1","2","3"]}"#
+        );
+    }
+
+    #[test]
+    fn long_list_err() {
+        serde_json::from_str::<Obj>(r#"{ "values": [1, 2, 3, 4, 5] }"#).unwrap_err();
+    }
+}
@@ -1,11 +1,13 @@
-use tree_hash::{Hash256, MerkleHasher, TreeHash, TreeHashType, BYTES_PER_CHUNK};
+use tree_hash::{
+    merkleize_progressive, Hash256, MerkleHasher, TreeHash, TreeHashType, BYTES_PER_CHUNK,
+};
 use typenum::Unsigned;
 
 /// A helper function providing common functionality between the `TreeHash` implementations for
-/// `FixedVector` and `VariableList`.
-pub fn vec_tree_hash_root<T, N>(vec: &[T]) -> Hash256
+/// `FixedVector`, `VariableList` and `PersistentList`.
+pub fn vec_tree_hash_root<'a, T, N>(vec: impl IntoIterator<Item = &'a T>) -> Hash256
 where
-    T: TreeHash,
+    T: TreeHash + 'a,
     N: Unsigned,
 {
     match T::tree_hash_type() {
@@ -40,6 +42,22 @@ where
     }
 }
 
+/// A helper function providing the progressive merkleization of the items of a `ProgressiveList`.
+pub fn progressive_vec_tree_hash_root<T: TreeHash>(vec: &[T]) -> Hash256 {
+    let bytes = match T::tree_hash_type() {
+        TreeHashType::Basic => vec
+            .iter()
+            .flat_map(|item| item.tree_hash_packed_encoding())
+            .collect::<Vec<_>>(),
+        TreeHashType::Container | TreeHashType::List | TreeHashType::Vector => vec
+            .iter()
+            .flat_map(|item| item.tree_hash_root().to_fixed_bytes())
+            .collect(),
+    };
+
+    merkleize_progressive(&bytes)
+}
+
 /// A helper function providing common functionality for finding the Merkle root of some bytes that
 /// represent a bitfield.
 pub fn bitfield_bytes_tree_hash_root<N: Unsigned>(bytes: &[u8]) -> Hash256 {
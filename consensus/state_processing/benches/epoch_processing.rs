@@ -0,0 +1,251 @@
+//! Benchmarks of per-epoch processing on a mainnet-scale Altair state.
+//!
+//! The number of validators defaults to 1M and may be set with the
+//! `EPOCH_PROCESSING_BENCH_VALIDATORS` environment variable.
+//!
+//! To measure the effect of a change, save a baseline before making it and compare against that
+//! baseline afterwards:
+//!
+//! ```bash
+//! make bench-epoch-processing-baseline BENCH_BASELINE=before
+//! # ... make changes ...
+//! make bench-epoch-processing-compare BENCH_BASELINE=before
+//! ```
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use state_processing::per_epoch_processing::{
+    altair::{
+        process_inactivity_updates, process_justification_and_finalization,
+        process_rewards_and_penalties, ParticipationCache,
+    },
+    effective_balance_updates::process_effective_balance_updates,
+    process_epoch, process_registry_updates, process_slashings,
+};
+use state_processing::upgrade::upgrade_to_altair;
+use types::test_utils::{generate_deterministic_keypair, RngCore, SeedableRng, XorShiftRng};
+use types::{
+    BeaconState, ChainSpec, Epoch, Eth1Data, EthSpec, ForkName, Hash256, MainnetEthSpec,
+    ParticipationFlags, PublicKeyBytes, RelativeEpoch, Validator,
+};
+
+type E = MainnetEthSpec;
+
+const DEFAULT_VALIDATOR_COUNT: usize = 1_000_000;
+/// The number of distinct keypairs shared amongst the validators.
+///
+/// Only the sync committee computations use the pubkeys, and generating a keypair for every
+/// validator would dominate the setup time.
+const KEYPAIR_COUNT: usize = 1_024;
+/// The epoch at the end of which the state is processed. It is not at the end of a sync committee
+/// period, so that the result is representative of most epochs.
+const STATE_EPOCH: u64 = 10;
+
+fn validator_count() -> usize {
+    std::env::var("EPOCH_PROCESSING_BENCH_VALIDATORS")
+        .ok()
+        .map(|count| count.parse().expect("validator count should be an integer"))
+        .unwrap_or(DEFAULT_VALIDATOR_COUNT)
+}
+
+/// Builds an Altair state at the last slot of `STATE_EPOCH`, resembling a healthy network:
+///
+/// - ~95% of validators have timely source, target and head votes in both epochs,
+/// - balances are spread around the maximum effective balance, so some effective balances change,
+/// - a small number of validators are slashed, exiting or waiting to be activated.
+fn build_state(validator_count: usize, spec: &ChainSpec) -> BeaconState<E> {
+    let mut rng = XorShiftRng::from_seed([42; 16]);
+    let pubkeys = (0..KEYPAIR_COUNT)
+        .map(|i| PublicKeyBytes::from(generate_deterministic_keypair(i).pk))
+        .collect::<Vec<_>>();
+
+    let current_epoch = Epoch::new(STATE_EPOCH);
+    let far_future_epoch = spec.far_future_epoch;
+
+    let validators = (0..validator_count)
+        .map(|i| {
+            let mut validator = Validator {
+                pubkey: pubkeys[i % KEYPAIR_COUNT],
+                withdrawal_credentials: Hash256::from_low_u64_le(i as u64),
+                effective_balance: spec.max_effective_balance,
+                slashed: false,
+                activation_eligibility_epoch: Epoch::new(0),
+                activation_epoch: Epoch::new(0),
+                exit_epoch: far_future_epoch,
+                withdrawable_epoch: far_future_epoch,
+            };
+            match rng.next_u32() % 1_000 {
+                // Slashed, with a withdrawable epoch that is reached by `process_slashings`.
+                0 => {
+                    validator.slashed = true;
+                    validator.exit_epoch = current_epoch;
+                    validator.withdrawable_epoch =
+                        current_epoch + E::epochs_per_slashings_vector() as u64 / 2;
+                }
+                // Voluntarily exiting.
+                1 => {
+                    validator.exit_epoch = current_epoch + 4;
+                    validator.withdrawable_epoch =
+                        validator.exit_epoch + spec.min_validator_withdrawability_delay;
+                }
+                // Deposited and waiting in the activation queue.
+                2 => {
+                    validator.activation_eligibility_epoch = current_epoch - 2;
+                    validator.activation_epoch = far_future_epoch;
+                }
+                _ => (),
+            }
+            validator
+        })
+        .collect::<Vec<_>>();
+
+    let balances = validators
+        .iter()
+        .map(|validator| {
+            let deviation = rng.next_u64() % (2 * spec.effective_balance_increment);
+            (validator.effective_balance + deviation)
+                .saturating_sub(spec.effective_balance_increment)
+        })
+        .collect::<Vec<_>>();
+
+    let eth1_data = Eth1Data {
+        deposit_root: Hash256::zero(),
+        deposit_count: validator_count as u64,
+        block_hash: Hash256::zero(),
+    };
+    let mut state = BeaconState::new(0, eth1_data, spec);
+    *state.validators_mut() = validators.into();
+    *state.balances_mut() = balances.into();
+    *state.slot_mut() = current_epoch.end_slot(E::slots_per_epoch());
+
+    upgrade_to_altair(&mut state, spec).expect("should upgrade to altair");
+
+    let participation = (0..validator_count)
+        .map(|_| {
+            let mut flags = ParticipationFlags::default();
+            if rng.next_u32() % 100 < 95 {
+                for flag_index in 0..3 {
+                    flags.add_flag(flag_index).expect("flag index is valid");
+                }
+            }
+            flags
+        })
+        .collect::<Vec<_>>();
+    *state
+        .previous_epoch_participation_mut()
+        .expect("state is altair") = participation.clone().into();
+    *state
+        .current_epoch_participation_mut()
+        .expect("state is altair") = participation.into();
+
+    for score in state
+        .inactivity_scores_mut()
+        .expect("state is altair")
+        .iter_mut()
+        .step_by(50)
+    {
+        *score = rng.next_u64() % 64;
+    }
+
+    state.build_all_caches(spec).expect("should build caches");
+    state
+}
+
+fn epoch_processing(c: &mut Criterion) {
+    let validator_count = validator_count();
+    let spec = ForkName::Altair.make_genesis_spec(E::default_spec());
+    let state = build_state(validator_count, &spec);
+    let participation_cache =
+        ParticipationCache::new(&state, &spec).expect("should build participation cache");
+
+    let mut group = c.benchmark_group(format!("epoch_processing_{}_validators", validator_count));
+    group.sample_size(10);
+
+    group.bench_function("participation_cache", |b| {
+        b.iter(|| black_box(ParticipationCache::new(&state, &spec).unwrap()))
+    });
+
+    group.bench_function("justification_and_finalization", |b| {
+        b.iter_batched_ref(
+            || state.clone(),
+            |state| {
+                process_justification_and_finalization(state, &participation_cache).unwrap();
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("inactivity_updates", |b| {
+        b.iter_batched_ref(
+            || state.clone(),
+            |state| process_inactivity_updates(state, &participation_cache, &spec).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("rewards_and_penalties", |b| {
+        b.iter_batched_ref(
+            || state.clone(),
+            |state| process_rewards_and_penalties(state, &participation_cache, &spec).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("registry_updates", |b| {
+        b.iter_batched_ref(
+            || state.clone(),
+            |state| process_registry_updates(state, &spec).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("slashings", |b| {
+        b.iter_batched_ref(
+            || state.clone(),
+            |state| {
+                process_slashings(
+                    state,
+                    participation_cache.current_epoch_total_active_balance(),
+                    &spec,
+                )
+                .unwrap()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("effective_balance_updates", |b| {
+        b.iter_batched_ref(
+            || state.clone(),
+            |state| process_effective_balance_updates(state, &spec).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("process_epoch", |b| {
+        b.iter_batched_ref(
+            || state.clone(),
+            |state| black_box(process_epoch(state, &spec).unwrap()),
+            BatchSize::LargeInput,
+        )
+    });
+
+    // Epoch processing builds the next epoch's committee cache if it is absent, which is the
+    // case for a state loaded from disk.
+    group.bench_function("process_epoch_without_committee_caches", |b| {
+        b.iter_batched_ref(
+            || {
+                let mut state = state.clone();
+                state
+                    .drop_committee_cache(RelativeEpoch::Next)
+                    .expect("should drop cache");
+                state
+            },
+            |state| black_box(process_epoch(state, &spec).unwrap()),
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, epoch_processing);
+criterion_main!(benches);
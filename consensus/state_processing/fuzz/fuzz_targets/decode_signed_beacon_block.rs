@@ -0,0 +1,24 @@
+//! Fuzzing of `SignedBeaconBlock` SSZ decoding, as applied to blocks received over gossip and RPC.
+//!
+//! Decoding must never panic, and any bytes which decode must re-encode to exactly the same bytes.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ssz::Encode;
+use types::{ChainSpec, ForkName, MinimalEthSpec, SignedBeaconBlock};
+
+type E = MinimalEthSpec;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(block) = SignedBeaconBlock::<E>::any_from_ssz_bytes(data) {
+        assert_eq!(block.as_ssz_bytes(), data, "decoding is not canonical");
+    }
+
+    // Decoding by slot selects the variant from the fork schedule, so try each fork at genesis.
+    for fork_name in ForkName::list_all() {
+        let spec = fork_name.make_genesis_spec(ChainSpec::minimal());
+        if let Ok(block) = SignedBeaconBlock::<E>::from_ssz_bytes(data, &spec) {
+            assert_eq!(block.as_ssz_bytes(), data, "decoding is not canonical");
+        }
+    }
+});
@@ -0,0 +1,105 @@
+//! Differential fuzzing of `per_block_processing` over arbitrary states and blocks.
+//!
+//! Besides checking that processing never panics, each input is processed several times to check
+//! that:
+//!
+//! - Processing is deterministic.
+//! - Bulk and individual signature verification agree on whether the block is valid.
+//! - Skipping signature verification never rejects a block which is valid with verification.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use state_processing::{
+    per_block_processing, BlockProcessingError, BlockSignatureStrategy, VerifyBlockRoot,
+};
+use types::{BeaconState, ChainSpec, EthSpec, MinimalEthSpec, SignedBeaconBlock};
+
+type E = MinimalEthSpec;
+
+#[derive(Debug, arbitrary::Arbitrary)]
+struct Input {
+    state: BeaconState<E>,
+    block: SignedBeaconBlock<E>,
+}
+
+/// Returns a spec in which the fork of `state` is active from genesis.
+///
+/// Without this almost every arbitrary state would be rejected for being inconsistent with the
+/// fork schedule.
+fn spec_for_state(state: &BeaconState<E>) -> ChainSpec {
+    let fork_name = state
+        .fork_name(&E::default_spec())
+        .unwrap_or_else(|inconsistent| inconsistent.object_fork);
+    fork_name.make_genesis_spec(E::default_spec())
+}
+
+fn process(
+    state: &BeaconState<E>,
+    block: &SignedBeaconBlock<E>,
+    strategy: BlockSignatureStrategy,
+    spec: &ChainSpec,
+) -> (Result<(), BlockProcessingError>, BeaconState<E>) {
+    let mut state = state.clone();
+    let result = per_block_processing(
+        &mut state,
+        block,
+        None,
+        strategy,
+        VerifyBlockRoot::True,
+        spec,
+    );
+    (result, state)
+}
+
+fuzz_target!(|input: Input| {
+    let Input { state, block } = input;
+    let spec = spec_for_state(&state);
+
+    let (individual, individual_state) = process(
+        &state,
+        &block,
+        BlockSignatureStrategy::VerifyIndividual,
+        &spec,
+    );
+
+    let (repeated, repeated_state) = process(
+        &state,
+        &block,
+        BlockSignatureStrategy::VerifyIndividual,
+        &spec,
+    );
+    assert_eq!(individual, repeated, "processing is not deterministic");
+    if individual.is_ok() {
+        assert_eq!(individual_state, repeated_state, "post-states differ");
+    }
+
+    let (bulk, bulk_state) = process(&state, &block, BlockSignatureStrategy::VerifyBulk, &spec);
+    assert_eq!(
+        individual.is_ok(),
+        bulk.is_ok(),
+        "individual verification gave {:?}, bulk verification gave {:?}",
+        individual,
+        bulk
+    );
+    if individual.is_ok() {
+        assert_eq!(individual_state, bulk_state, "bulk post-state differs");
+    }
+
+    let (unverified, unverified_state) = process(
+        &state,
+        &block,
+        BlockSignatureStrategy::NoVerification,
+        &spec,
+    );
+    if individual.is_ok() {
+        assert_eq!(
+            unverified,
+            Ok(()),
+            "valid block rejected without verification"
+        );
+        assert_eq!(
+            individual_state, unverified_state,
+            "unverified post-state differs"
+        );
+    }
+});
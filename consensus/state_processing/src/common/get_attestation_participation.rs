@@ -44,7 +44,10 @@ pub fn get_attestation_participation_flag_indices<T: EthSpec>(
     if is_matching_source && inclusion_delay <= T::slots_per_epoch().integer_sqrt() {
         participation_flag_indices.push(TIMELY_SOURCE_FLAG_INDEX);
     }
-    if is_matching_target && inclusion_delay <= T::slots_per_epoch() {
+    // From Deneb the timely target flag is awarded regardless of inclusion delay (EIP-7045).
+    let is_timely_target =
+        matches!(state, BeaconState::Deneb(_)) || inclusion_delay <= T::slots_per_epoch();
+    if is_matching_target && is_timely_target {
         participation_flag_indices.push(TIMELY_TARGET_FLAG_INDEX);
     }
     if is_matching_head && inclusion_delay == spec.min_attestation_inclusion_delay {
@@ -2,7 +2,9 @@ use super::per_block_processing::{
     errors::BlockProcessingError, process_operations::process_deposit,
 };
 use crate::common::DepositDataTree;
-use crate::upgrade::{upgrade_to_altair, upgrade_to_bellatrix};
+use crate::upgrade::{
+    upgrade_to_altair, upgrade_to_bellatrix, upgrade_to_capella, upgrade_to_deneb,
+};
 use safe_arith::{ArithError, SafeArith};
 use tree_hash::TreeHash;
 use types::DEPOSIT_TREE_DEPTH;
@@ -68,8 +70,41 @@ pub fn initialize_beacon_state_from_eth1<T: EthSpec>(
 
         // Override latest execution payload header.
         // See https://github.com/ethereum/consensus-specs/blob/v1.1.0/specs/merge/beacon-chain.md#testing
-        *state.latest_execution_payload_header_mut()? =
-            execution_payload_header.unwrap_or_default();
+        if let Some(ExecutionPayloadHeader::Merge(header)) = &execution_payload_header {
+            *state.latest_execution_payload_header_merge_mut()? = header.clone();
+        }
+    }
+
+    // Upgrade to Capella if configured from genesis.
+    if spec
+        .capella_fork_epoch
+        .map_or(false, |fork_epoch| fork_epoch == T::genesis_epoch())
+    {
+        upgrade_to_capella(&mut state, spec)?;
+
+        // Remove intermediate Merge fork from `state.fork`.
+        state.fork_mut().previous_version = spec.capella_fork_version;
+
+        // Override latest execution payload header.
+        if let Some(ExecutionPayloadHeader::Capella(header)) = &execution_payload_header {
+            *state.latest_execution_payload_header_capella_mut()? = header.clone();
+        }
+    }
+
+    // Upgrade to Deneb if configured from genesis.
+    if spec
+        .deneb_fork_epoch
+        .map_or(false, |fork_epoch| fork_epoch == T::genesis_epoch())
+    {
+        upgrade_to_deneb(&mut state, spec)?;
+
+        // Remove intermediate Capella fork from `state.fork`.
+        state.fork_mut().previous_version = spec.deneb_fork_version;
+
+        // Override latest execution payload header.
+        if let Some(ExecutionPayloadHeader::Deneb(header)) = &execution_payload_header {
+            *state.latest_execution_payload_header_deneb_mut()? = header.clone();
+        }
     }
 
     // Now that we have our validators, initialize the caches (including the committees)
@@ -1,3 +1,4 @@
+use crate::common::decrease_balance;
 use errors::{BlockOperationError, BlockProcessingError, HeaderInvalid};
 use rayon::prelude::*;
 use safe_arith::{ArithError, SafeArith};
@@ -17,6 +18,7 @@ pub use process_operations::process_operations;
 pub use verify_attestation::{
     verify_attestation_for_block_inclusion, verify_attestation_for_state,
 };
+pub use verify_bls_to_execution_change::verify_bls_to_execution_change;
 pub use verify_deposit::{
     get_existing_validator_index, verify_deposit_merkle_proof, verify_deposit_signature,
 };
@@ -31,6 +33,7 @@ pub mod signature_sets;
 pub mod tests;
 mod verify_attestation;
 mod verify_attester_slashing;
+mod verify_bls_to_execution_change;
 mod verify_deposit;
 mod verify_exit;
 mod verify_proposer_slashing;
@@ -154,7 +157,19 @@ pub fn per_block_processing<T: EthSpec, Payload: ExecPayload<T>>(
     // previous block.
     if is_execution_enabled(state, block.body()) {
         let payload = block.body().execution_payload()?;
+        if state.next_withdrawal_index().is_ok() {
+            process_withdrawals(state, payload, spec)?;
+        }
         process_execution_payload(state, payload, spec)?;
+        if let Ok(blob_kzg_commitments) = block.body().blob_kzg_commitments() {
+            block_verify!(
+                blob_kzg_commitments.len() <= T::max_blobs_per_block(),
+                BlockProcessingError::ExecutionInvalidBlobsLen {
+                    max: T::max_blobs_per_block(),
+                    actual: blob_kzg_commitments.len(),
+                }
+            );
+        }
     }
 
     process_randao(state, block, verify_randao, spec)?;
@@ -324,11 +339,22 @@ pub fn partially_verify_execution_payload<T: EthSpec, Payload: ExecPayload<T>>(
     payload: &Payload,
     spec: &ChainSpec,
 ) -> Result<(), BlockProcessingError> {
+    let state_fork = state
+        .fork_name(spec)
+        .map_err(BlockProcessingError::InconsistentStateFork)?;
+    block_verify!(
+        payload.fork_name() == state_fork,
+        BlockProcessingError::ExecutionPayloadInconsistentFork(InconsistentFork {
+            fork_at_slot: state_fork,
+            object_fork: payload.fork_name(),
+        })
+    );
+
     if is_merge_transition_complete(state) {
         block_verify!(
-            payload.parent_hash() == state.latest_execution_payload_header()?.block_hash,
+            payload.parent_hash() == state.latest_execution_payload_header()?.block_hash(),
             BlockProcessingError::ExecutionHashChainIncontiguous {
-                expected: state.latest_execution_payload_header()?.block_hash,
+                expected: state.latest_execution_payload_header()?.block_hash(),
                 found: payload.parent_hash(),
             }
         );
@@ -367,7 +393,9 @@ pub fn process_execution_payload<T: EthSpec, Payload: ExecPayload<T>>(
 ) -> Result<(), BlockProcessingError> {
     partially_verify_execution_payload(state, payload, spec)?;
 
-    *state.latest_execution_payload_header_mut()? = payload.to_execution_payload_header();
+    state
+        .latest_execution_payload_header_mut()?
+        .replace(payload.to_execution_payload_header())?;
 
     Ok(())
 }
@@ -380,7 +408,7 @@ pub fn process_execution_payload<T: EthSpec, Payload: ExecPayload<T>>(
 pub fn is_merge_transition_complete<T: EthSpec>(state: &BeaconState<T>) -> bool {
     state
         .latest_execution_payload_header()
-        .map(|header| *header != <ExecutionPayloadHeader<T>>::default())
+        .map(|header| !header.is_default())
         .unwrap_or(false)
 }
 /// https://github.com/ethereum/consensus-specs/blob/dev/specs/merge/beacon-chain.md#is_merge_transition_block
@@ -389,7 +417,7 @@ pub fn is_merge_transition_block<T: EthSpec, Payload: ExecPayload<T>>(
     body: BeaconBlockBodyRef<T, Payload>,
 ) -> bool {
     body.execution_payload()
-        .map(|payload| !is_merge_transition_complete(state) && *payload != Payload::default())
+        .map(|payload| !is_merge_transition_complete(state) && !payload.is_default())
         .unwrap_or(false)
 }
 /// https://github.com/ethereum/consensus-specs/blob/dev/specs/merge/beacon-chain.md#is_execution_enabled
@@ -409,3 +437,112 @@ pub fn compute_timestamp_at_slot<T: EthSpec>(
         .safe_mul(spec.seconds_per_slot)
         .and_then(|since_genesis| state.genesis_time().safe_add(since_genesis))
 }
+
+/// Compute the withdrawals that the next execution payload built on `state` must contain.
+///
+/// https://github.com/ethereum/consensus-specs/blob/dev/specs/capella/beacon-chain.md#new-get_expected_withdrawals
+pub fn get_expected_withdrawals<T: EthSpec>(
+    state: &BeaconState<T>,
+    spec: &ChainSpec,
+) -> Result<Withdrawals<T>, BlockProcessingError> {
+    let epoch = state.current_epoch();
+    let mut withdrawal_index = state.next_withdrawal_index()?;
+    let mut validator_index = state.next_withdrawal_validator_index()?;
+    let mut withdrawals = vec![];
+
+    let bound = std::cmp::min(
+        state.validators().len() as u64,
+        spec.max_validators_per_withdrawals_sweep,
+    );
+    for _ in 0..bound {
+        let validator = state.get_validator(validator_index as usize)?;
+        let balance = *state.balances().get(validator_index as usize).ok_or(
+            BeaconStateError::BalancesOutOfBounds(validator_index as usize),
+        )?;
+        if validator.is_fully_withdrawable_at(balance, epoch, spec) {
+            withdrawals.push(Withdrawal {
+                index: withdrawal_index,
+                validator_index,
+                address: validator
+                    .get_eth1_withdrawal_address(spec)
+                    .ok_or(BlockProcessingError::WithdrawalCredentialsInvalid)?,
+                amount: balance,
+            });
+            withdrawal_index.safe_add_assign(1)?;
+        } else if validator.is_partially_withdrawable_validator(balance, spec) {
+            withdrawals.push(Withdrawal {
+                index: withdrawal_index,
+                validator_index,
+                address: validator
+                    .get_eth1_withdrawal_address(spec)
+                    .ok_or(BlockProcessingError::WithdrawalCredentialsInvalid)?,
+                amount: balance.safe_sub(spec.max_effective_balance)?,
+            });
+            withdrawal_index.safe_add_assign(1)?;
+        }
+        if withdrawals.len() == T::max_withdrawals_per_payload() {
+            break;
+        }
+        validator_index = validator_index
+            .safe_add(1)?
+            .safe_rem(state.validators().len() as u64)?;
+    }
+
+    Ok(withdrawals.into())
+}
+
+/// Apply the withdrawals in `payload`, which must be exactly those from `get_expected_withdrawals`.
+///
+/// https://github.com/ethereum/consensus-specs/blob/dev/specs/capella/beacon-chain.md#new-process_withdrawals
+pub fn process_withdrawals<T: EthSpec, Payload: ExecPayload<T>>(
+    state: &mut BeaconState<T>,
+    payload: &Payload,
+    spec: &ChainSpec,
+) -> Result<(), BlockProcessingError> {
+    let expected_withdrawals = get_expected_withdrawals(state, spec)?;
+    let expected_root = expected_withdrawals.tree_hash_root();
+    let withdrawals_root = payload.withdrawals_root()?;
+
+    // Comparing roots (rather than lists) allows blinded payloads to be processed.
+    block_verify!(
+        expected_root == withdrawals_root,
+        BlockProcessingError::WithdrawalsRootMismatch {
+            expected: expected_root,
+            found: withdrawals_root,
+        }
+    );
+
+    for withdrawal in expected_withdrawals.iter() {
+        decrease_balance(
+            state,
+            withdrawal.validator_index as usize,
+            withdrawal.amount,
+        )?;
+    }
+
+    // Update the next withdrawal index if this block contained withdrawals.
+    if let Some(latest_withdrawal) = expected_withdrawals.last() {
+        *state.next_withdrawal_index_mut()? = latest_withdrawal.index.safe_add(1)?;
+
+        // Update the next validator index to start the next withdrawal sweep.
+        if expected_withdrawals.len() == T::max_withdrawals_per_payload() {
+            // Next sweep starts after the latest withdrawal's validator index.
+            let next_validator_index = latest_withdrawal
+                .validator_index
+                .safe_add(1)?
+                .safe_rem(state.validators().len() as u64)?;
+            *state.next_withdrawal_validator_index_mut()? = next_validator_index;
+        }
+    }
+
+    // Advance sweep by the max length of the sweep if there was not a full set of withdrawals.
+    if expected_withdrawals.len() != T::max_withdrawals_per_payload() {
+        let next_validator_index = state
+            .next_withdrawal_validator_index()?
+            .safe_add(spec.max_validators_per_withdrawals_sweep)?
+            .safe_rem(state.validators().len() as u64)?;
+        *state.next_withdrawal_validator_index_mut()? = next_validator_index;
+    }
+
+    Ok(())
+}
@@ -155,6 +155,7 @@ where
         // Deposits are not included because they can legally have invalid signatures.
         self.include_exits(block)?;
         self.include_sync_aggregate(block)?;
+        self.include_bls_to_execution_changes(block)?;
 
         Ok(())
     }
@@ -332,6 +333,23 @@ where
         Ok(())
     }
 
+    /// Include the signatures of the block's BLS to execution changes (if any) for verification.
+    pub fn include_bls_to_execution_changes<Payload: ExecPayload<T>>(
+        &mut self,
+        block: &'a SignedBeaconBlock<T, Payload>,
+    ) -> Result<()> {
+        if let Ok(bls_to_execution_changes) = block.message().body().bls_to_execution_changes() {
+            for bls_to_execution_change in bls_to_execution_changes {
+                self.sets.push(bls_execution_change_signature_set(
+                    self.state,
+                    bls_to_execution_change,
+                    self.spec,
+                )?);
+            }
+        }
+        Ok(())
+    }
+
     /// Verify all the signatures that have been included in `self`, returning `true` if and only if
     /// all the signatures are valid.
     ///
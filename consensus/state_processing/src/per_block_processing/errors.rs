@@ -47,6 +47,10 @@ pub enum BlockProcessingError {
         index: usize,
         reason: ExitInvalid,
     },
+    BlsExecutionChangeInvalid {
+        index: usize,
+        reason: BlsExecutionChangeInvalid,
+    },
     SyncAggregateInvalid {
         reason: SyncAggregateInvalid,
     },
@@ -70,6 +74,20 @@ pub enum BlockProcessingError {
         found: u64,
     },
     ExecutionInvalid,
+    ExecutionInvalidBlobsLen {
+        max: usize,
+        actual: usize,
+    },
+    ExecutionPayloadInconsistentFork(InconsistentFork),
+    WithdrawalsRootMismatch {
+        expected: Hash256,
+        found: Hash256,
+    },
+    WithdrawalCredentialsInvalid,
+    /// The payload carries execution layer requests but the Electra fork is not active.
+    ExecutionRequestsBeforeFork,
+    /// Applying execution layer requests to the state is not yet supported.
+    ExecutionRequestsUnsupported,
 }
 
 impl From<BeaconStateError> for BlockProcessingError {
@@ -150,7 +168,8 @@ impl_into_block_processing_error_with_index!(
     IndexedAttestationInvalid,
     AttestationInvalid,
     DepositInvalid,
-    ExitInvalid
+    ExitInvalid,
+    BlsExecutionChangeInvalid
 );
 
 pub type HeaderValidationError = BlockOperationError<HeaderInvalid>;
@@ -160,6 +179,7 @@ pub type AttestationValidationError = BlockOperationError<AttestationInvalid>;
 pub type SyncCommitteeMessageValidationError = BlockOperationError<SyncAggregateInvalid>;
 pub type DepositValidationError = BlockOperationError<DepositInvalid>;
 pub type ExitValidationError = BlockOperationError<ExitInvalid>;
+pub type BlsExecutionChangeValidationError = BlockOperationError<BlsExecutionChangeInvalid>;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum BlockOperationError<T> {
@@ -367,6 +387,18 @@ pub enum ExitInvalid {
     SignatureSetError(SignatureSetError),
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub enum BlsExecutionChangeInvalid {
+    /// The specified validator is not in the state's validator registry.
+    ValidatorUnknown(u64),
+    /// Validator does not have BLS withdrawal credentials before this change.
+    NonBlsWithdrawalCredentials,
+    /// Provided BLS pubkey does not match withdrawal credentials.
+    WithdrawalCredentialsMismatch,
+    /// The signature is invalid.
+    BadSignature,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum SyncAggregateInvalid {
     /// One or more of the aggregate public keys is invalid.
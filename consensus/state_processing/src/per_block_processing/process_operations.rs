@@ -31,6 +31,11 @@ pub fn process_operations<'a, T: EthSpec, Payload: ExecPayload<T>>(
     process_attestations(state, block_body, proposer_index, verify_signatures, spec)?;
     process_deposits(state, block_body.deposits(), spec)?;
     process_exits(state, block_body.voluntary_exits(), verify_signatures, spec)?;
+
+    if let Ok(bls_to_execution_changes) = block_body.bls_to_execution_changes() {
+        process_bls_to_execution_changes(state, bls_to_execution_changes, verify_signatures, spec)?;
+    }
+
     Ok(())
 }
 
@@ -230,7 +235,10 @@ pub fn process_attestations<'a, T: EthSpec, Payload: ExecPayload<T>>(
         BeaconBlockBodyRef::Base(_) => {
             base::process_attestations(state, block_body.attestations(), verify_signatures, spec)?;
         }
-        BeaconBlockBodyRef::Altair(_) | BeaconBlockBodyRef::Merge(_) => {
+        BeaconBlockBodyRef::Altair(_)
+        | BeaconBlockBodyRef::Merge(_)
+        | BeaconBlockBodyRef::Capella(_)
+        | BeaconBlockBodyRef::Deneb(_) => {
             altair::process_attestations(
                 state,
                 block_body.attestations(),
@@ -263,6 +271,106 @@ pub fn process_exits<T: EthSpec>(
     Ok(())
 }
 
+/// Validates each `SignedBlsToExecutionChange` and updates the state, short-circuiting on an
+/// invalid object.
+///
+/// Returns `Ok(())` if the validation and state updates completed successfully, otherwise returns
+/// an `Err` describing the invalid object or cause of failure.
+pub fn process_bls_to_execution_changes<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    bls_to_execution_changes: &[SignedBlsToExecutionChange],
+    verify_signatures: VerifySignatures,
+    spec: &ChainSpec,
+) -> Result<(), BlockProcessingError> {
+    for (i, signed_address_change) in bls_to_execution_changes.iter().enumerate() {
+        verify_bls_to_execution_change(state, signed_address_change, verify_signatures, spec)
+            .map_err(|e| e.into_with_index(i))?;
+
+        state
+            .get_validator_mut(signed_address_change.message.validator_index as usize)?
+            .change_withdrawal_credentials(
+                &signed_address_change.message.to_execution_address,
+                spec,
+            );
+    }
+
+    Ok(())
+}
+
+/// Processes the requests made by the execution layer while executing the block's payload.
+///
+/// Requests only exist from Electra onwards, so before the fork the requests must be empty. The
+/// state does not yet carry the Electra queues which the requests are applied to, so any request
+/// made after the fork is rejected.
+pub fn process_execution_requests<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    requests: &ExecutionRequests<T>,
+    spec: &ChainSpec,
+) -> Result<(), BlockProcessingError> {
+    let electra_enabled = spec
+        .electra_fork_epoch
+        .map_or(false, |fork_epoch| state.current_epoch() >= fork_epoch);
+
+    if !electra_enabled {
+        block_verify!(
+            requests.is_empty(),
+            BlockProcessingError::ExecutionRequestsBeforeFork
+        );
+        return Ok(());
+    }
+
+    process_deposit_requests(state, &requests.deposits, spec)?;
+    process_withdrawal_requests(state, &requests.withdrawals, spec)?;
+    process_consolidation_requests(state, &requests.consolidations, spec)?;
+
+    Ok(())
+}
+
+/// Applies each `DepositRequest` to the state.
+///
+/// Not yet supported: an empty list is a no-op, any request is an error.
+pub fn process_deposit_requests<T: EthSpec>(
+    _state: &mut BeaconState<T>,
+    deposit_requests: &[DepositRequest],
+    _spec: &ChainSpec,
+) -> Result<(), BlockProcessingError> {
+    block_verify!(
+        deposit_requests.is_empty(),
+        BlockProcessingError::ExecutionRequestsUnsupported
+    );
+    Ok(())
+}
+
+/// Applies each `WithdrawalRequest` to the state.
+///
+/// Not yet supported: an empty list is a no-op, any request is an error.
+pub fn process_withdrawal_requests<T: EthSpec>(
+    _state: &mut BeaconState<T>,
+    withdrawal_requests: &[WithdrawalRequest],
+    _spec: &ChainSpec,
+) -> Result<(), BlockProcessingError> {
+    block_verify!(
+        withdrawal_requests.is_empty(),
+        BlockProcessingError::ExecutionRequestsUnsupported
+    );
+    Ok(())
+}
+
+/// Applies each `ConsolidationRequest` to the state.
+///
+/// Not yet supported: an empty list is a no-op, any request is an error.
+pub fn process_consolidation_requests<T: EthSpec>(
+    _state: &mut BeaconState<T>,
+    consolidation_requests: &[ConsolidationRequest],
+    _spec: &ChainSpec,
+) -> Result<(), BlockProcessingError> {
+    block_verify!(
+        consolidation_requests.is_empty(),
+        BlockProcessingError::ExecutionRequestsUnsupported
+    );
+    Ok(())
+}
+
 /// Validates each `Deposit` and updates the state, short-circuiting on an invalid object.
 ///
 /// Returns `Ok(())` if the validation and state updates completed successfully, otherwise returns
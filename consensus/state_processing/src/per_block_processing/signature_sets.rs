@@ -6,13 +6,14 @@ use bls::SignatureSet;
 use ssz::DecodeError;
 use std::borrow::Cow;
 use tree_hash::TreeHash;
+use types::signing_domain::{get_bls_to_execution_change_domain, get_voluntary_exit_domain};
 use types::{
     AggregateSignature, AttesterSlashing, BeaconBlockRef, BeaconState, BeaconStateError, ChainSpec,
     DepositData, Domain, Epoch, EthSpec, ExecPayload, Fork, Hash256, InconsistentFork,
     IndexedAttestation, ProposerSlashing, PublicKey, PublicKeyBytes, Signature,
     SignedAggregateAndProof, SignedBeaconBlock, SignedBeaconBlockHeader,
-    SignedContributionAndProof, SignedRoot, SignedVoluntaryExit, SigningData, Slot, SyncAggregate,
-    SyncAggregatorSelectionData, Unsigned,
+    SignedBlsToExecutionChange, SignedContributionAndProof, SignedRoot, SignedVoluntaryExit,
+    SigningData, Slot, SyncAggregate, SyncAggregatorSelectionData, Unsigned,
 };
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -351,11 +352,11 @@ where
     let exit = &signed_exit.message;
     let proposer_index = exit.validator_index as usize;
 
-    let domain = spec.get_domain(
+    let domain = get_voluntary_exit_domain(
         exit.epoch,
-        Domain::VoluntaryExit,
         &state.fork(),
         state.genesis_validators_root(),
+        spec,
     );
 
     let message = exit.signing_root(domain);
@@ -367,6 +368,30 @@ where
     ))
 }
 
+/// Returns a signature set that is valid if the `SignedBlsToExecutionChange` was signed by the BLS
+/// withdrawal key that it names.
+pub fn bls_execution_change_signature_set<'a, T: EthSpec>(
+    state: &'a BeaconState<T>,
+    signed_address_change: &'a SignedBlsToExecutionChange,
+    spec: &'a ChainSpec,
+) -> Result<SignatureSet<'a>> {
+    let domain = get_bls_to_execution_change_domain(state.genesis_validators_root(), spec);
+    let message = signed_address_change.message.signing_root(domain);
+    let signing_key = Cow::Owned(
+        signed_address_change
+            .message
+            .from_bls_pubkey
+            .decompress()
+            .map_err(|_| Error::PublicKeyDecompressionFailed)?,
+    );
+
+    Ok(SignatureSet::single_pubkey(
+        &signed_address_change.signature,
+        signing_key,
+        message,
+    ))
+}
+
 pub fn signed_aggregate_selection_proof_signature_set<'a, T, F>(
     get_pubkey: F,
     signed_aggregate_and_proof: &'a SignedAggregateAndProof<T>,
@@ -822,3 +822,30 @@ fn invalid_proposer_slashing_proposal_epoch_mismatch() {
         })
     );
 }
+
+#[test]
+fn invalid_execution_requests_before_electra() {
+    let spec = MainnetEthSpec::default_spec();
+    let harness = get_harness::<MainnetEthSpec>(EPOCH_OFFSET, VALIDATOR_COUNT);
+    let mut state = harness.get_current_state();
+
+    let mut requests = ExecutionRequests::<MainnetEthSpec>::default();
+    let result = process_operations::process_execution_requests(&mut state, &requests, &spec);
+    assert_eq!(result, Ok(()));
+
+    requests
+        .withdrawals
+        .push(WithdrawalRequest {
+            source_address: Address::repeat_byte(0xaa),
+            validator_pubkey: state.validators()[0].pubkey,
+            amount: WithdrawalRequest::FULL_EXIT_REQUEST_AMOUNT,
+        })
+        .unwrap();
+    let result = process_operations::process_execution_requests(&mut state, &requests, &spec);
+
+    // Expecting ExecutionRequestsBeforeFork because Electra is not scheduled
+    assert_eq!(
+        result,
+        Err(BlockProcessingError::ExecutionRequestsBeforeFork)
+    );
+}
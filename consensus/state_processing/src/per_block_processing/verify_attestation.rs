@@ -31,13 +31,17 @@ pub fn verify_attestation_for_block_inclusion<T: EthSpec>(
             attestation: data.slot,
         }
     );
-    verify!(
-        state.slot() <= data.slot.safe_add(T::slots_per_epoch())?,
-        Invalid::IncludedTooLate {
-            state: state.slot(),
-            attestation: data.slot,
-        }
-    );
+    // From Deneb the upper bound is implied by the target epoch check in
+    // `verify_attestation_for_state` (EIP-7045).
+    if !matches!(state, BeaconState::Deneb(_)) {
+        verify!(
+            state.slot() <= data.slot.safe_add(T::slots_per_epoch())?,
+            Invalid::IncludedTooLate {
+                state: state.slot(),
+                attestation: data.slot,
+            }
+        );
+    }
 
     verify_attestation_for_state(state, attestation, verify_signatures, spec)
 }
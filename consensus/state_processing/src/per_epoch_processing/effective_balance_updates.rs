@@ -14,16 +14,21 @@ pub fn process_effective_balance_updates<T: EthSpec>(
     let downward_threshold = hysteresis_increment.safe_mul(spec.hysteresis_downward_multiplier)?;
     let upward_threshold = hysteresis_increment.safe_mul(spec.hysteresis_upward_multiplier)?;
     let (validators, balances) = state.validators_and_balances_mut();
-    for (index, validator) in validators.iter_mut().enumerate() {
-        let balance = balances
+    for (index, balance) in balances.iter().enumerate() {
+        let effective_balance = validators
             .get(index)
-            .copied()
-            .ok_or(BeaconStateError::BalancesOutOfBounds(index))?;
+            .ok_or(BeaconStateError::UnknownValidator(index))?
+            .effective_balance;
 
-        if balance.safe_add(downward_threshold)? < validator.effective_balance
-            || validator.effective_balance.safe_add(upward_threshold)? < balance
+        if balance.safe_add(downward_threshold)? < effective_balance
+            || effective_balance.safe_add(upward_threshold)? < *balance
         {
-            validator.effective_balance = std::cmp::min(
+            // Only mutate the validators whose effective balance changes, so that the rest of the
+            // registry stays shared with other copies of the state.
+            validators
+                .get_mut(index)
+                .ok_or(BeaconStateError::UnknownValidator(index))?
+                .effective_balance = std::cmp::min(
                 balance.safe_sub(balance.safe_rem(spec.effective_balance_increment)?)?,
                 spec.max_effective_balance,
             );
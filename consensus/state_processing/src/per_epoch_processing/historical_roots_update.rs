@@ -5,7 +5,7 @@ use safe_arith::SafeArith;
 use tree_hash::TreeHash;
 use types::beacon_state::BeaconState;
 use types::eth_spec::EthSpec;
-use types::Unsigned;
+use types::{HistoricalSummary, Unsigned};
 
 pub fn process_historical_roots_update<T: EthSpec>(
     state: &mut BeaconState<T>,
@@ -16,10 +16,17 @@ pub fn process_historical_roots_update<T: EthSpec>(
         .safe_rem(T::SlotsPerHistoricalRoot::to_u64().safe_div(T::slots_per_epoch())?)?
         == 0
     {
-        let historical_batch = state.historical_batch();
-        state
-            .historical_roots_mut()
-            .push(historical_batch.tree_hash_root())?;
+        // From Capella onwards the block and state roots are summarised separately, and the
+        // `historical_roots` list is frozen.
+        if state.historical_summaries().is_ok() {
+            let summary = HistoricalSummary::new(state);
+            state.historical_summaries_mut()?.push(summary)?;
+        } else {
+            let historical_batch = state.historical_batch();
+            state
+                .historical_roots_mut()
+                .push(historical_batch.tree_hash_root())?;
+        }
     }
     Ok(())
 }
@@ -1,4 +1,6 @@
-use crate::upgrade::{upgrade_to_altair, upgrade_to_bellatrix};
+use crate::upgrade::{
+    upgrade_to_altair, upgrade_to_bellatrix, upgrade_to_capella, upgrade_to_deneb,
+};
 use crate::{per_epoch_processing::EpochProcessingSummary, *};
 use safe_arith::{ArithError, SafeArith};
 use types::*;
@@ -55,6 +57,14 @@ pub fn per_slot_processing<T: EthSpec>(
         if spec.bellatrix_fork_epoch == Some(state.current_epoch()) {
             upgrade_to_bellatrix(state, spec)?;
         }
+        // If the Capella fork epoch is reached, perform an irregular state upgrade.
+        if spec.capella_fork_epoch == Some(state.current_epoch()) {
+            upgrade_to_capella(state, spec)?;
+        }
+        // If the Deneb fork epoch is reached, perform an irregular state upgrade.
+        if spec.deneb_fork_epoch == Some(state.current_epoch()) {
+            upgrade_to_deneb(state, spec)?;
+        }
     }
 
     Ok(summary)
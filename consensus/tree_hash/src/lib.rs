@@ -1,10 +1,12 @@
 pub mod impls;
 mod merkle_hasher;
 mod merkleize_padded;
+mod merkleize_progressive;
 mod merkleize_standard;
 
 pub use merkle_hasher::{Error, MerkleHasher};
 pub use merkleize_padded::merkleize_padded;
+pub use merkleize_progressive::merkleize_progressive;
 pub use merkleize_standard::merkleize_standard;
 
 use eth2_hashing::{hash_fixed, ZERO_HASHES, ZERO_HASHES_MAX_INDEX};
@@ -89,6 +91,25 @@ pub fn mix_in_selector(root: &Hash256, selector: u8) -> Option<Hash256> {
     Some(Hash256::from_slice(&root))
 }
 
+/// Returns the node created by hashing `root` and `aux`.
+///
+/// Used in `TreeHash` for inserting the active fields of a "stable container" above its root.
+///
+/// ## Specification
+///
+/// ```ignore,text
+/// mix_in_aux: Given a Merkle root root and an auxiliary SSZ object root aux return
+/// hash(root + aux).
+/// ```
+///
+/// https://eips.ethereum.org/EIPS/eip-7495
+pub fn mix_in_aux(root: &Hash256, aux: &Hash256) -> Hash256 {
+    Hash256::from_slice(&eth2_hashing::hash32_concat(
+        root.as_bytes(),
+        aux.as_bytes(),
+    ))
+}
+
 /// Returns a cached padding node for a given height.
 fn get_zero_hash(height: usize) -> &'static [u8] {
     if height <= ZERO_HASHES_MAX_INDEX {
@@ -0,0 +1,98 @@
+use super::*;
+
+/// The number of leaves in the first (right-most) subtree of a progressive Merkle tree. Each
+/// subsequent subtree has four times as many leaves as the one before it.
+const FIRST_SUBTREE_LEAVES: usize = 1;
+
+/// Merkleizes bytes into a progressive Merkle tree and returns the root.
+///
+/// Rather than padding to a fixed leaf count, chunks are placed into a sequence of subtrees of
+/// 1, 4, 16, ... leaves. Each subtree hangs off the right of a spine, with the remaining subtrees
+/// recursively to its left:
+///
+/// ```ignore,text
+/// merkleize_progressive(chunks, num_leaves=1): Given ordered BYTES_PER_CHUNK-byte chunks:
+///     if len(chunks) == 0: return Bytes32()
+///     return hash(
+///         merkleize_progressive(chunks[num_leaves:], num_leaves * 4),
+///         merkleize(chunks[:num_leaves], num_leaves),
+///     )
+/// ```
+///
+/// This gives lists without a type-level limit stable generalized indices as they grow.
+pub fn merkleize_progressive(bytes: &[u8]) -> Hash256 {
+    merkleize_progressive_from(bytes, FIRST_SUBTREE_LEAVES)
+}
+
+fn merkleize_progressive_from(bytes: &[u8], num_leaves: usize) -> Hash256 {
+    if bytes.is_empty() {
+        return Hash256::zero();
+    }
+
+    let (subtree_bytes, remaining_bytes) =
+        bytes.split_at(std::cmp::min(bytes.len(), num_leaves * BYTES_PER_CHUNK));
+
+    let subtree_root = merkle_root(subtree_bytes, num_leaves);
+    let remaining_root = merkleize_progressive_from(remaining_bytes, num_leaves * 4);
+
+    Hash256::from_slice(&eth2_hashing::hash32_concat(
+        remaining_root.as_bytes(),
+        subtree_root.as_bytes(),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn hash_concat(left: Hash256, right: Hash256) -> Hash256 {
+        Hash256::from_slice(&eth2_hashing::hash32_concat(
+            left.as_bytes(),
+            right.as_bytes(),
+        ))
+    }
+
+    fn chunks(n: u8) -> Vec<u8> {
+        (1..=n).flat_map(|i| [i; BYTES_PER_CHUNK]).collect()
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(merkleize_progressive(&[]), Hash256::zero());
+    }
+
+    #[test]
+    fn single_chunk() {
+        let bytes = chunks(1);
+        assert_eq!(
+            merkleize_progressive(&bytes),
+            hash_concat(Hash256::zero(), Hash256::from_slice(&bytes))
+        );
+    }
+
+    #[test]
+    fn partial_chunk() {
+        let mut padded = [0; BYTES_PER_CHUNK];
+        padded[0] = 42;
+        assert_eq!(merkleize_progressive(&[42]), merkleize_progressive(&padded));
+    }
+
+    #[test]
+    fn spans_subtrees() {
+        // Six chunks fill the first subtree, the second subtree of four leaves and one leaf of the
+        // third subtree of sixteen leaves.
+        let bytes = chunks(6);
+        let (first, rest) = bytes.split_at(BYTES_PER_CHUNK);
+        let (second, third) = rest.split_at(4 * BYTES_PER_CHUNK);
+
+        let expected = hash_concat(
+            hash_concat(
+                hash_concat(Hash256::zero(), merkle_root(third, 16)),
+                merkle_root(second, 4),
+            ),
+            merkle_root(first, 1),
+        );
+
+        assert_eq!(merkleize_progressive(&bytes), expected);
+    }
+}
@@ -126,3 +126,78 @@ fn variable_union() {
         mix_in_selector(u8_hash_concat(2, 1), 1)
     );
 }
+
+#[derive(TreeHash)]
+#[tree_hash(struct_behaviour = "stable_container", max_fields = 4)]
+struct Stable {
+    a: Option<u8>,
+    b: Option<u8>,
+}
+
+#[derive(TreeHash)]
+#[tree_hash(struct_behaviour = "stable_container", max_fields = 4)]
+struct StableExtended {
+    a: Option<u8>,
+    b: Option<u8>,
+    c: Option<HashVec>,
+}
+
+#[test]
+fn stable_container() {
+    let zero = Hash256::zero();
+    let hash_concat = |a: Hash256, b: Hash256| {
+        Hash256::from_slice(&eth2_hashing::hash32_concat(a.as_bytes(), b.as_bytes()))
+    };
+
+    // Fields are padded to `max_fields` leaves, and the active fields bitvector is mixed in.
+    assert_eq!(
+        Stable {
+            a: Some(2),
+            b: None
+        }
+        .tree_hash_root(),
+        hash_concat(
+            hash_concat(u8_hash_concat(2, 0), hash_concat(zero, zero)),
+            u8_hash(0b01)
+        )
+    );
+    assert_eq!(
+        Stable {
+            a: Some(2),
+            b: Some(3)
+        }
+        .tree_hash_root(),
+        hash_concat(
+            hash_concat(u8_hash_concat(2, 3), hash_concat(zero, zero)),
+            u8_hash(0b11)
+        )
+    );
+
+    // Adding a field doesn't change the root of values which don't use it.
+    assert_eq!(
+        StableExtended {
+            a: Some(2),
+            b: Some(3),
+            c: None
+        }
+        .tree_hash_root(),
+        Stable {
+            a: Some(2),
+            b: Some(3)
+        }
+        .tree_hash_root()
+    );
+    assert_ne!(
+        StableExtended {
+            a: Some(2),
+            b: Some(3),
+            c: Some(HashVec::from(vec![]))
+        }
+        .tree_hash_root(),
+        Stable {
+            a: Some(2),
+            b: Some(3)
+        }
+        .tree_hash_root()
+    );
+}
@@ -14,6 +14,10 @@ const MAX_UNION_SELECTOR: u8 = 127;
 struct StructOpts {
     #[darling(default)]
     enum_behaviour: Option<String>,
+    #[darling(default)]
+    struct_behaviour: Option<String>,
+    #[darling(default)]
+    max_fields: Option<usize>,
 }
 
 const ENUM_TRANSPARENT: &str = "transparent";
@@ -40,6 +44,41 @@ impl EnumBehaviour {
     }
 }
 
+const STRUCT_CONTAINER: &str = "container";
+const STRUCT_STABLE_CONTAINER: &str = "stable_container";
+const STRUCT_VARIANTS: &[&str] = &[STRUCT_CONTAINER, STRUCT_STABLE_CONTAINER];
+
+enum StructBehaviour {
+    Container,
+    /// An EIP-7495 stable container with capacity for `max_fields` fields.
+    StableContainer {
+        max_fields: usize,
+    },
+}
+
+impl StructBehaviour {
+    pub fn new(s: Option<String>, max_fields: Option<usize>) -> Self {
+        match s.as_deref() {
+            None | Some(STRUCT_CONTAINER) => {
+                if max_fields.is_some() {
+                    panic!("max_fields is only valid for stable containers");
+                }
+                StructBehaviour::Container
+            }
+            Some(STRUCT_STABLE_CONTAINER) => StructBehaviour::StableContainer {
+                max_fields: max_fields.expect(
+                    "stable containers require a \"max_fields\" attribute, \
+                     e.g., #[tree_hash(struct_behaviour = \"stable_container\", max_fields = 8)]",
+                ),
+            },
+            Some(other) => panic!(
+                "{} is an invalid struct_behaviour, use either {:?}",
+                other, STRUCT_VARIANTS
+            ),
+        }
+    }
+}
+
 /// Return a Vec of `syn::Ident` for each named field in the struct, whilst filtering out fields
 /// that should not be hashed.
 ///
@@ -121,13 +160,22 @@ pub fn tree_hash_derive(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as DeriveInput);
     let opts = StructOpts::from_derive_input(&item).unwrap();
     let enum_opt = EnumBehaviour::new(opts.enum_behaviour);
+    let struct_behaviour = StructBehaviour::new(opts.struct_behaviour, opts.max_fields);
 
     match &item.data {
         syn::Data::Struct(s) => {
             if enum_opt.is_some() {
                 panic!("enum_behaviour is invalid for structs");
             }
-            tree_hash_derive_struct(&item, s)
+            match struct_behaviour {
+                StructBehaviour::Container => tree_hash_derive_struct(&item, s),
+                StructBehaviour::StableContainer { max_fields } => {
+                    tree_hash_derive_stable_container(&item, s, max_fields)
+                }
+            }
+        }
+        syn::Data::Enum(_) if !matches!(struct_behaviour, StructBehaviour::Container) => {
+            panic!("struct_behaviour is invalid for enums")
         }
         syn::Data::Enum(s) => match enum_opt.expect(NO_ENUM_BEHAVIOUR_ERROR) {
             EnumBehaviour::Transparent => tree_hash_derive_enum_transparent(&item, s),
@@ -173,6 +221,78 @@ fn tree_hash_derive_struct(item: &DeriveInput, struct_data: &DataStruct) -> Toke
     output.into()
 }
 
+/// Derive `TreeHash` for an EIP-7495 "stable container".
+///
+/// The fields are merkleized as if the struct had `max_fields` fields, with fields that are
+/// `None` (or absent) hashed as zero, and the root of the `BitVector[max_fields]` of active fields
+/// is mixed in. This keeps the generalized index of each field stable as fields are added.
+///
+/// ## Limitations
+///
+/// Only supports structs where every hashed field is an `Option`.
+fn tree_hash_derive_stable_container(
+    item: &DeriveInput,
+    struct_data: &DataStruct,
+    max_fields: usize,
+) -> TokenStream {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    let idents = get_hashable_fields(struct_data);
+    if idents.len() > max_fields {
+        panic!(
+            "stable container has {} fields, exceeding max_fields of {}",
+            idents.len(),
+            max_fields
+        );
+    }
+    let indices = (0..idents.len()).collect::<Vec<_>>();
+    let active_fields_len = (max_fields + 7) / 8;
+    let active_fields_leaves = (active_fields_len + 31) / 32;
+
+    let output = quote! {
+        impl #impl_generics tree_hash::TreeHash for #name #ty_generics #where_clause {
+            fn tree_hash_type() -> tree_hash::TreeHashType {
+                tree_hash::TreeHashType::Container
+            }
+
+            fn tree_hash_packed_encoding(&self) -> Vec<u8> {
+                unreachable!("Struct should never be packed.")
+            }
+
+            fn tree_hash_packing_factor() -> usize {
+                unreachable!("Struct should never be packed.")
+            }
+
+            fn tree_hash_root(&self) -> tree_hash::Hash256 {
+                let mut hasher = tree_hash::MerkleHasher::with_leaves(#max_fields);
+                let mut active_fields = [0_u8; #active_fields_len];
+
+                #(
+                    let root = match &self.#idents {
+                        Some(field) => {
+                            active_fields[#indices / 8] |= 1 << (#indices % 8);
+                            field.tree_hash_root()
+                        }
+                        None => tree_hash::Hash256::zero(),
+                    };
+                    hasher.write(root.as_bytes())
+                        .expect("tree hash derive should not apply too many leaves");
+                )*
+
+                let root = hasher
+                    .finish()
+                    .expect("tree hash derive should not have a remaining buffer");
+                let active_fields_root =
+                    tree_hash::merkle_root(&active_fields, #active_fields_leaves);
+
+                tree_hash::mix_in_aux(&root, &active_fields_root)
+            }
+        }
+    };
+    output.into()
+}
+
 /// Derive `TreeHash` for an enum in the "transparent" method.
 ///
 /// The "transparent" method is distinct from the "union" method specified in the SSZ specification.
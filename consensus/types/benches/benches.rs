@@ -145,6 +145,58 @@ fn all_benches(c: &mut Criterion) {
         .sample_size(10),
     );
 
+    let inner_state = state.clone();
+    c.bench(
+        &format!("{}_validators", validator_count),
+        Benchmark::new("uncached_tree_hash/beacon_state", move |b| {
+            b.iter_batched_ref(
+                || inner_state.clone(),
+                |state| black_box(state.canonical_root()),
+                criterion::BatchSize::SmallInput,
+            )
+        })
+        .sample_size(10),
+    );
+
+    let mut inner_state = state.clone();
+    let inner_state_root = inner_state
+        .update_tree_hash_cache()
+        .expect("should update tree hash");
+    c.bench(
+        &format!("{}_validators", validator_count),
+        Benchmark::new(
+            "initialized_cached_tree_hash_with_changed_fields/beacon_state",
+            move |b| {
+                b.iter_batched_ref(
+                    || {
+                        // Mimic the changes made by processing a block in the next slot.
+                        let mut state = inner_state.clone();
+                        *state.slot_mut() += 1;
+                        state
+                            .set_state_root(state.slot() - 1, inner_state_root)
+                            .expect("should set state root");
+                        state
+                            .set_block_root(state.slot() - 1, Hash256::repeat_byte(1))
+                            .expect("should set block root");
+                        state
+                            .set_randao_mix(state.current_epoch(), Hash256::repeat_byte(2))
+                            .expect("should set randao mix");
+                        for balance in state.balances_mut().iter_mut().step_by(64) {
+                            *balance += 1;
+                        }
+                        for validator in state.validators_mut().iter_mut().step_by(1024) {
+                            validator.effective_balance -= 1_000_000_000;
+                        }
+                        state
+                    },
+                    |state| black_box(state.update_tree_hash_cache()),
+                    criterion::BatchSize::SmallInput,
+                )
+            },
+        )
+        .sample_size(10),
+    );
+
     let inner_state = state.clone();
     c.bench(
         &format!("{}_validators", validator_count),
@@ -0,0 +1,21 @@
+/// The domain type of builder API messages, i.e. `DOMAIN_APPLICATION_BUILDER`.
+///
+/// This is application index `0` with the application mask `0x00000001` applied. As a little
+/// endian `u32` the mask is `1 << 24`.
+pub const APPLICATION_DOMAIN_BUILDER: u32 = 16_777_216;
+
+/// A signature domain reserved for applications other than the consensus protocol itself.
+///
+/// See: https://github.com/ethereum/consensus-specs/blob/dev/specs/phase0/beacon-chain.md#domain-types
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ApplicationDomain {
+    Builder,
+}
+
+impl ApplicationDomain {
+    pub fn get_domain_constant(&self) -> u32 {
+        match self {
+            ApplicationDomain::Builder => APPLICATION_DOMAIN_BUILDER,
+        }
+    }
+}
@@ -1,19 +1,31 @@
 use crate::test_utils::TestRandom;
 use crate::*;
 use derivative::Derivative;
+use int_to_bytes::int_to_fixed_bytes32;
+use merkle_proof::MerkleTree;
+use safe_arith::SafeArith;
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use ssz_types::VariableList;
 use std::marker::PhantomData;
 use superstruct::superstruct;
 use test_random_derive::TestRandom;
+use tree_hash::TreeHash;
 use tree_hash_derive::TreeHash;
 
+pub type KzgCommitments<T> =
+    VariableList<KzgCommitment, <T as EthSpec>::MaxBlobCommitmentsPerBlock>;
+
+/// The index of `blob_kzg_commitments` amongst the fields of a Deneb block body.
+pub const BLOB_KZG_COMMITMENTS_INDEX: usize = 11;
+/// The depth of the Merkle tree formed by the fields of a Deneb block body.
+pub const BLOCK_BODY_FIELDS_DEPTH: usize = 4;
+
 /// The body of a `BeaconChain` block, containing operations.
 ///
 /// This *superstruct* abstracts over the hard-fork.
 #[superstruct(
-    variants(Base, Altair, Merge),
+    variants(Base, Altair, Merge, Capella, Deneb),
     variant_attributes(
         derive(
             Debug,
@@ -47,14 +59,19 @@ pub struct BeaconBlockBody<T: EthSpec, Payload: ExecPayload<T> = FullPayload<T>>
     pub attestations: VariableList<Attestation<T>, T::MaxAttestations>,
     pub deposits: VariableList<Deposit, T::MaxDeposits>,
     pub voluntary_exits: VariableList<SignedVoluntaryExit, T::MaxVoluntaryExits>,
-    #[superstruct(only(Altair, Merge))]
+    #[superstruct(only(Altair, Merge, Capella, Deneb))]
     pub sync_aggregate: SyncAggregate<T>,
     // We flatten the execution payload so that serde can use the name of the inner type,
     // either `execution_payload` for full payloads, or `execution_payload_header` for blinded
     // payloads.
-    #[superstruct(only(Merge))]
+    #[superstruct(only(Merge, Capella, Deneb))]
     #[serde(flatten)]
     pub execution_payload: Payload,
+    #[superstruct(only(Capella, Deneb))]
+    pub bls_to_execution_changes:
+        VariableList<SignedBlsToExecutionChange, T::MaxBlsToExecutionChanges>,
+    #[superstruct(only(Deneb))]
+    pub blob_kzg_commitments: KzgCommitments<T>,
     #[superstruct(only(Base, Altair))]
     #[ssz(skip_serializing, skip_deserializing)]
     #[tree_hash(skip_hashing)]
@@ -69,10 +86,76 @@ impl<'a, T: EthSpec> BeaconBlockBodyRef<'a, T> {
             BeaconBlockBodyRef::Base { .. } => ForkName::Base,
             BeaconBlockBodyRef::Altair { .. } => ForkName::Altair,
             BeaconBlockBodyRef::Merge { .. } => ForkName::Merge,
+            BeaconBlockBodyRef::Capella { .. } => ForkName::Capella,
+            BeaconBlockBodyRef::Deneb { .. } => ForkName::Deneb,
         }
     }
 }
 
+impl<T: EthSpec, Payload: ExecPayload<T>> BeaconBlockBodyDeneb<T, Payload> {
+    /// Compute a Merkle proof of the KZG commitment at `index` against the tree hash root of this
+    /// body, i.e. the `kzg_commitment_inclusion_proof` of a `BlobSidecar`.
+    pub fn kzg_commitment_merkle_proof(
+        &self,
+        index: usize,
+    ) -> Result<FixedVector<Hash256, T::KzgCommitmentInclusionProofDepth>, Error> {
+        if index >= self.blob_kzg_commitments.len() {
+            return Err(Error::IndexNotSupported(index));
+        }
+
+        // Prove the commitment against the root of the list's data, then mix in the length.
+        let commitments_depth = T::max_blob_commitments_per_block()
+            .next_power_of_two()
+            .trailing_zeros() as usize;
+        let leaves = self
+            .blob_kzg_commitments
+            .iter()
+            .map(|commitment| commitment.tree_hash_root())
+            .collect::<Vec<_>>();
+        let tree = MerkleTree::create(&leaves, commitments_depth);
+        let (_, mut proof) = tree.generate_proof(index, commitments_depth)?;
+
+        proof.push(Hash256::from(int_to_fixed_bytes32(
+            self.blob_kzg_commitments.len() as u64,
+        )));
+
+        // Prove the `blob_kzg_commitments` field against the body root.
+        let fields = [
+            self.randao_reveal.tree_hash_root(),
+            self.eth1_data.tree_hash_root(),
+            self.graffiti.tree_hash_root(),
+            self.proposer_slashings.tree_hash_root(),
+            self.attester_slashings.tree_hash_root(),
+            self.attestations.tree_hash_root(),
+            self.deposits.tree_hash_root(),
+            self.voluntary_exits.tree_hash_root(),
+            self.sync_aggregate.tree_hash_root(),
+            self.execution_payload.tree_hash_root(),
+            self.bls_to_execution_changes.tree_hash_root(),
+            self.blob_kzg_commitments.tree_hash_root(),
+        ];
+        let tree = MerkleTree::create(&fields, BLOCK_BODY_FIELDS_DEPTH);
+        let (_, field_proof) =
+            tree.generate_proof(BLOB_KZG_COMMITMENTS_INDEX, BLOCK_BODY_FIELDS_DEPTH)?;
+        proof.extend(field_proof);
+
+        Ok(proof.into())
+    }
+}
+
+/// Return the index of the commitment at `index` in the Merkle tree used by
+/// `kzg_commitment_merkle_proof`, for use with `verify_merkle_proof`.
+pub fn kzg_commitment_subtree_index<T: EthSpec>(
+    index: usize,
+) -> Result<usize, safe_arith::ArithError> {
+    let commitments_depth = T::max_blob_commitments_per_block()
+        .next_power_of_two()
+        .trailing_zeros() as usize;
+    BLOB_KZG_COMMITMENTS_INDEX
+        .safe_shl(commitments_depth.safe_add(1)? as u32)?
+        .safe_add(index)
+}
+
 // We can convert pre-Bellatrix block bodies without payloads into block bodies "with" payloads.
 impl<E: EthSpec> From<BeaconBlockBodyBase<E, BlindedPayload<E>>>
     for BeaconBlockBodyBase<E, FullPayload<E>>
@@ -251,6 +334,92 @@ impl<E: EthSpec> From<BeaconBlockBodyMerge<E, FullPayload<E>>>
     }
 }
 
+impl<E: EthSpec> From<BeaconBlockBodyCapella<E, FullPayload<E>>>
+    for (
+        BeaconBlockBodyCapella<E, BlindedPayload<E>>,
+        Option<ExecutionPayload<E>>,
+    )
+{
+    fn from(body: BeaconBlockBodyCapella<E, FullPayload<E>>) -> Self {
+        let BeaconBlockBodyCapella {
+            randao_reveal,
+            eth1_data,
+            graffiti,
+            proposer_slashings,
+            attester_slashings,
+            attestations,
+            deposits,
+            voluntary_exits,
+            sync_aggregate,
+            execution_payload: FullPayload { execution_payload },
+            bls_to_execution_changes,
+        } = body;
+
+        (
+            BeaconBlockBodyCapella {
+                randao_reveal,
+                eth1_data,
+                graffiti,
+                proposer_slashings,
+                attester_slashings,
+                attestations,
+                deposits,
+                voluntary_exits,
+                sync_aggregate,
+                execution_payload: BlindedPayload {
+                    execution_payload_header: From::from(&execution_payload),
+                },
+                bls_to_execution_changes,
+            },
+            Some(execution_payload),
+        )
+    }
+}
+
+impl<E: EthSpec> From<BeaconBlockBodyDeneb<E, FullPayload<E>>>
+    for (
+        BeaconBlockBodyDeneb<E, BlindedPayload<E>>,
+        Option<ExecutionPayload<E>>,
+    )
+{
+    fn from(body: BeaconBlockBodyDeneb<E, FullPayload<E>>) -> Self {
+        let BeaconBlockBodyDeneb {
+            randao_reveal,
+            eth1_data,
+            graffiti,
+            proposer_slashings,
+            attester_slashings,
+            attestations,
+            deposits,
+            voluntary_exits,
+            sync_aggregate,
+            execution_payload: FullPayload { execution_payload },
+            bls_to_execution_changes,
+            blob_kzg_commitments,
+        } = body;
+
+        (
+            BeaconBlockBodyDeneb {
+                randao_reveal,
+                eth1_data,
+                graffiti,
+                proposer_slashings,
+                attester_slashings,
+                attestations,
+                deposits,
+                voluntary_exits,
+                sync_aggregate,
+                execution_payload: BlindedPayload {
+                    execution_payload_header: From::from(&execution_payload),
+                },
+                bls_to_execution_changes,
+                blob_kzg_commitments,
+            },
+            Some(execution_payload),
+        )
+    }
+}
+
 impl<E: EthSpec> From<BeaconBlockBody<E, FullPayload<E>>>
     for (
         BeaconBlockBody<E, BlindedPayload<E>>,
@@ -30,6 +30,16 @@ pub use eth_spec::*;
 pub use iter::BlockRootsIter;
 pub use tree_hash_cache::BeaconTreeHashCache;
 
+/// The indices of the container fields of the state which `compute_merkle_proof` can descend
+/// into. These are the same for all forks.
+const FORK_FIELD_INDEX: usize = 3;
+const LATEST_BLOCK_HEADER_FIELD_INDEX: usize = 4;
+const ETH1_DATA_FIELD_INDEX: usize = 8;
+const PREVIOUS_JUSTIFIED_CHECKPOINT_FIELD_INDEX: usize = 18;
+const CURRENT_JUSTIFIED_CHECKPOINT_FIELD_INDEX: usize = 19;
+const FINALIZED_CHECKPOINT_FIELD_INDEX: usize = 20;
+const LATEST_EXECUTION_PAYLOAD_HEADER_FIELD_INDEX: usize = 24;
+
 #[macro_use]
 mod committee_cache;
 mod clone_config;
@@ -124,6 +134,9 @@ pub enum Error {
         current_epoch: Epoch,
         epoch: Epoch,
     },
+    /// Merkle proofs can't be computed for the field at this generalized index.
+    IndexNotSupported(usize),
+    MerkleTreeError(merkle_proof::MerkleTreeError),
 }
 
 /// Control whether an epoch-indexed field can be indexed at the next epoch or not.
@@ -172,7 +185,7 @@ impl From<BeaconStateHash> for Hash256 {
 
 /// The state of the `BeaconChain` at some slot.
 #[superstruct(
-    variants(Base, Altair, Merge),
+    variants(Base, Altair, Merge, Capella, Deneb),
     variant_attributes(
         derive(
             Derivative,
@@ -230,11 +243,11 @@ where
     pub eth1_deposit_index: u64,
 
     // Registry
-    #[compare_fields(as_slice)]
-    pub validators: VariableList<Validator, T::ValidatorRegistryLimit>,
-    #[compare_fields(as_slice)]
-    #[serde(with = "ssz_types::serde_utils::quoted_u64_var_list")]
-    pub balances: VariableList<u64, T::ValidatorRegistryLimit>,
+    #[compare_fields(as_iter)]
+    pub validators: PersistentList<Validator, T::ValidatorRegistryLimit>,
+    #[compare_fields(as_iter)]
+    #[serde(with = "ssz_types::serde_utils::quoted_u64_persistent_list")]
+    pub balances: PersistentList<u64, T::ValidatorRegistryLimit>,
 
     // Randomness
     pub randao_mixes: FixedVector<Hash256, T::EpochsPerHistoricalVector>,
@@ -250,9 +263,9 @@ where
     pub current_epoch_attestations: VariableList<PendingAttestation<T>, T::MaxPendingAttestations>,
 
     // Participation (Altair and later)
-    #[superstruct(only(Altair, Merge))]
+    #[superstruct(only(Altair, Merge, Capella, Deneb))]
     pub previous_epoch_participation: VariableList<ParticipationFlags, T::ValidatorRegistryLimit>,
-    #[superstruct(only(Altair, Merge))]
+    #[superstruct(only(Altair, Merge, Capella, Deneb))]
     pub current_epoch_participation: VariableList<ParticipationFlags, T::ValidatorRegistryLimit>,
 
     // Finality
@@ -267,18 +280,43 @@ where
 
     // Inactivity
     #[serde(with = "ssz_types::serde_utils::quoted_u64_var_list")]
-    #[superstruct(only(Altair, Merge))]
+    #[superstruct(only(Altair, Merge, Capella, Deneb))]
     pub inactivity_scores: VariableList<u64, T::ValidatorRegistryLimit>,
 
     // Light-client sync committees
-    #[superstruct(only(Altair, Merge))]
+    #[superstruct(only(Altair, Merge, Capella, Deneb))]
     pub current_sync_committee: Arc<SyncCommittee<T>>,
-    #[superstruct(only(Altair, Merge))]
+    #[superstruct(only(Altair, Merge, Capella, Deneb))]
     pub next_sync_committee: Arc<SyncCommittee<T>>,
 
     // Execution
-    #[superstruct(only(Merge))]
-    pub latest_execution_payload_header: ExecutionPayloadHeader<T>,
+    #[superstruct(
+        only(Merge),
+        partial_getter(rename = "latest_execution_payload_header_merge")
+    )]
+    pub latest_execution_payload_header: ExecutionPayloadHeaderMerge<T>,
+    #[superstruct(
+        only(Capella),
+        partial_getter(rename = "latest_execution_payload_header_capella")
+    )]
+    pub latest_execution_payload_header: ExecutionPayloadHeaderCapella<T>,
+    #[superstruct(
+        only(Deneb),
+        partial_getter(rename = "latest_execution_payload_header_deneb")
+    )]
+    pub latest_execution_payload_header: ExecutionPayloadHeaderDeneb<T>,
+
+    // Withdrawals
+    #[superstruct(only(Capella, Deneb), partial_getter(copy))]
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub next_withdrawal_index: u64,
+    #[superstruct(only(Capella, Deneb), partial_getter(copy))]
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub next_withdrawal_validator_index: u64,
+
+    // Deep history valid from Capella onwards
+    #[superstruct(only(Capella, Deneb))]
+    pub historical_summaries: VariableList<HistoricalSummary, T::HistoricalRootsLimit>,
 
     // Caching (not in the spec)
     #[serde(skip_serializing, skip_deserializing)]
@@ -347,8 +385,8 @@ impl<T: EthSpec> BeaconState<T> {
             eth1_deposit_index: 0,
 
             // Validator registry
-            validators: VariableList::empty(), // Set later.
-            balances: VariableList::empty(),   // Set later.
+            validators: PersistentList::empty(), // Set later.
+            balances: PersistentList::empty(),   // Set later.
 
             // Randomness
             randao_mixes: FixedVector::from_elem(Hash256::zero()),
@@ -389,6 +427,8 @@ impl<T: EthSpec> BeaconState<T> {
             BeaconState::Base { .. } => ForkName::Base,
             BeaconState::Altair { .. } => ForkName::Altair,
             BeaconState::Merge { .. } => ForkName::Merge,
+            BeaconState::Capella { .. } => ForkName::Capella,
+            BeaconState::Deneb { .. } => ForkName::Deneb,
         };
 
         if fork_at_slot == object_fork {
@@ -432,6 +472,39 @@ impl<T: EthSpec> BeaconState<T> {
         Hash256::from_slice(&self.tree_hash_root()[..])
     }
 
+    /// Returns the header of the latest execution payload, for states from the merge onwards.
+    pub fn latest_execution_payload_header(&self) -> Result<ExecutionPayloadHeaderRef<T>, Error> {
+        match self {
+            BeaconState::Base(_) | BeaconState::Altair(_) => Err(Error::IncorrectStateVariant),
+            BeaconState::Merge(state) => Ok(ExecutionPayloadHeaderRef::Merge(
+                &state.latest_execution_payload_header,
+            )),
+            BeaconState::Capella(state) => Ok(ExecutionPayloadHeaderRef::Capella(
+                &state.latest_execution_payload_header,
+            )),
+            BeaconState::Deneb(state) => Ok(ExecutionPayloadHeaderRef::Deneb(
+                &state.latest_execution_payload_header,
+            )),
+        }
+    }
+
+    pub fn latest_execution_payload_header_mut(
+        &mut self,
+    ) -> Result<ExecutionPayloadHeaderRefMut<T>, Error> {
+        match self {
+            BeaconState::Base(_) | BeaconState::Altair(_) => Err(Error::IncorrectStateVariant),
+            BeaconState::Merge(state) => Ok(ExecutionPayloadHeaderRefMut::Merge(
+                &mut state.latest_execution_payload_header,
+            )),
+            BeaconState::Capella(state) => Ok(ExecutionPayloadHeaderRefMut::Capella(
+                &mut state.latest_execution_payload_header,
+            )),
+            BeaconState::Deneb(state) => Ok(ExecutionPayloadHeaderRefMut::Deneb(
+                &mut state.latest_execution_payload_header,
+            )),
+        }
+    }
+
     pub fn historical_batch(&self) -> HistoricalBatch<T> {
         HistoricalBatch {
             block_roots: self.block_roots().clone(),
@@ -1090,11 +1163,18 @@ impl<T: EthSpec> BeaconState<T> {
     }
 
     /// Convenience accessor for validators and balances simultaneously.
-    pub fn validators_and_balances_mut(&mut self) -> (&mut [Validator], &mut [u64]) {
+    pub fn validators_and_balances_mut(
+        &mut self,
+    ) -> (
+        &mut PersistentList<Validator, T::ValidatorRegistryLimit>,
+        &mut PersistentList<u64, T::ValidatorRegistryLimit>,
+    ) {
         match self {
             BeaconState::Base(state) => (&mut state.validators, &mut state.balances),
             BeaconState::Altair(state) => (&mut state.validators, &mut state.balances),
             BeaconState::Merge(state) => (&mut state.validators, &mut state.balances),
+            BeaconState::Capella(state) => (&mut state.validators, &mut state.balances),
+            BeaconState::Deneb(state) => (&mut state.validators, &mut state.balances),
         }
     }
 
@@ -1205,6 +1285,23 @@ impl<T: EthSpec> BeaconState<T> {
         ))
     }
 
+    /// Return the activation churn limit for the current epoch.
+    ///
+    /// From Deneb this is additionally capped by `max_per_epoch_activation_churn_limit`
+    /// (EIP-7514).
+    pub fn get_activation_churn_limit(&self, spec: &ChainSpec) -> Result<u64, Error> {
+        Ok(match self {
+            BeaconState::Base(_)
+            | BeaconState::Altair(_)
+            | BeaconState::Merge(_)
+            | BeaconState::Capella(_) => self.get_churn_limit(spec)?,
+            BeaconState::Deneb(_) => std::cmp::min(
+                spec.max_per_epoch_activation_churn_limit,
+                self.get_churn_limit(spec)?,
+            ),
+        })
+    }
+
     /// Returns the `slot`, `index`, `committee_position` and `committee_len` for which a validator must produce an
     /// attestation.
     ///
@@ -1291,12 +1388,16 @@ impl<T: EthSpec> BeaconState<T> {
                 BeaconState::Base(_) => Err(BeaconStateError::IncorrectStateVariant),
                 BeaconState::Altair(state) => Ok(&mut state.current_epoch_participation),
                 BeaconState::Merge(state) => Ok(&mut state.current_epoch_participation),
+                BeaconState::Capella(state) => Ok(&mut state.current_epoch_participation),
+                BeaconState::Deneb(state) => Ok(&mut state.current_epoch_participation),
             }
         } else if epoch == self.previous_epoch() {
             match self {
                 BeaconState::Base(_) => Err(BeaconStateError::IncorrectStateVariant),
                 BeaconState::Altair(state) => Ok(&mut state.previous_epoch_participation),
                 BeaconState::Merge(state) => Ok(&mut state.previous_epoch_participation),
+                BeaconState::Capella(state) => Ok(&mut state.previous_epoch_participation),
+                BeaconState::Deneb(state) => Ok(&mut state.previous_epoch_participation),
             }
         } else {
             Err(BeaconStateError::EpochOutOfBounds)
@@ -1551,6 +1652,69 @@ impl<T: EthSpec> BeaconState<T> {
         }
     }
 
+    /// Compute a Merkle proof of the node of the state at `generalized_index` against the tree
+    /// hash root of the state.
+    ///
+    /// The tree hash cache is initialized if it isn't already.
+    pub fn compute_merkle_proof(
+        &mut self,
+        generalized_index: usize,
+    ) -> Result<Vec<Hash256>, Error> {
+        self.compute_merkle_proof_with_leaf(generalized_index)
+            .map(|(_, proof)| proof)
+    }
+
+    /// Compute the leaf at `generalized_index` and its Merkle proof against the tree hash root of
+    /// the state.
+    ///
+    /// The index may refer to any field of the state, or to a node beneath one of the container
+    /// fields (e.g. `latest_execution_payload_header.block_hash`). Nodes within lists and vectors
+    /// are not supported.
+    pub fn compute_merkle_proof_with_leaf(
+        &mut self,
+        generalized_index: usize,
+    ) -> Result<(Hash256, Vec<Hash256>), Error> {
+        self.initialize_tree_hash_cache();
+
+        let mut cache = self
+            .tree_hash_cache_mut()
+            .take()
+            .ok_or(Error::TreeHashCacheNotInitialized)?;
+        let leaves = cache.recalculate_tree_hash_leaves(self);
+        self.tree_hash_cache_mut().restore(cache);
+        let leaves = leaves?;
+
+        generalized_index::compute_container_proof(
+            &leaves,
+            generalized_index,
+            |field_index, subtree_generalized_index| {
+                match field_index {
+                    FORK_FIELD_INDEX => self.fork().compute_merkle_proof(subtree_generalized_index),
+                    LATEST_BLOCK_HEADER_FIELD_INDEX => self
+                        .latest_block_header()
+                        .compute_merkle_proof(subtree_generalized_index),
+                    ETH1_DATA_FIELD_INDEX => self
+                        .eth1_data()
+                        .compute_merkle_proof(subtree_generalized_index),
+                    PREVIOUS_JUSTIFIED_CHECKPOINT_FIELD_INDEX => self
+                        .previous_justified_checkpoint()
+                        .compute_merkle_proof(subtree_generalized_index),
+                    CURRENT_JUSTIFIED_CHECKPOINT_FIELD_INDEX => self
+                        .current_justified_checkpoint()
+                        .compute_merkle_proof(subtree_generalized_index),
+                    FINALIZED_CHECKPOINT_FIELD_INDEX => self
+                        .finalized_checkpoint()
+                        .compute_merkle_proof(subtree_generalized_index),
+                    LATEST_EXECUTION_PAYLOAD_HEADER_FIELD_INDEX => self
+                        .latest_execution_payload_header()
+                        .and_then(|header| header.compute_merkle_proof(subtree_generalized_index)),
+                    _ => Err(Error::IndexNotSupported(generalized_index)),
+                }
+                .map_err(|_| Error::IndexNotSupported(generalized_index))
+            },
+        )
+    }
+
     /// Compute the tree hash root of the validators using the tree hash cache.
     ///
     /// Initialize the tree hash cache if it isn't already initialized.
@@ -1581,6 +1745,8 @@ impl<T: EthSpec> BeaconState<T> {
             BeaconState::Base(inner) => BeaconState::Base(inner.clone()),
             BeaconState::Altair(inner) => BeaconState::Altair(inner.clone()),
             BeaconState::Merge(inner) => BeaconState::Merge(inner.clone()),
+            BeaconState::Capella(inner) => BeaconState::Capella(inner.clone()),
+            BeaconState::Deneb(inner) => BeaconState::Deneb(inner.clone()),
         };
         if config.committee_caches {
             *res.committee_caches_mut() = self.committee_caches().clone();
@@ -1674,6 +1840,12 @@ impl From<tree_hash::Error> for Error {
     }
 }
 
+impl From<merkle_proof::MerkleTreeError> for Error {
+    fn from(e: merkle_proof::MerkleTreeError) -> Error {
+        Error::MerkleTreeError(e)
+    }
+}
+
 impl From<ArithError> for Error {
     fn from(e: ArithError) -> Error {
         Error::ArithError(e)
@@ -1691,6 +1863,8 @@ impl<T: EthSpec> CompareFields for BeaconState<T> {
             (BeaconState::Base(x), BeaconState::Base(y)) => x.compare_fields(y),
             (BeaconState::Altair(x), BeaconState::Altair(y)) => x.compare_fields(y),
             (BeaconState::Merge(x), BeaconState::Merge(y)) => x.compare_fields(y),
+            (BeaconState::Capella(x), BeaconState::Capella(y)) => x.compare_fields(y),
+            (BeaconState::Deneb(x), BeaconState::Deneb(y)) => x.compare_fields(y),
             _ => panic!("compare_fields: mismatched state variants",),
         }
     }
@@ -312,7 +312,10 @@ pub fn epoch_committee_count(committees_per_slot: usize, slots_per_epoch: usize)
 /// `epoch`.
 ///
 /// Spec v0.12.1
-pub fn get_active_validator_indices(validators: &[Validator], epoch: Epoch) -> Vec<usize> {
+pub fn get_active_validator_indices<N: Unsigned>(
+    validators: &PersistentList<Validator, N>,
+    epoch: Epoch,
+) -> Vec<usize> {
     let mut active = Vec::with_capacity(validators.len());
 
     for (index, validator) in validators.iter().enumerate() {
@@ -1,4 +1,4 @@
-use super::{BeaconStateError, ChainSpec, Epoch, Validator};
+use super::{BeaconStateError, ChainSpec, Epoch, PersistentList, Unsigned, Validator};
 use safe_arith::SafeArith;
 use serde_derive::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -12,7 +12,10 @@ pub struct ExitCache {
 
 impl ExitCache {
     /// Initialize a new cache for the given list of validators.
-    pub fn new(validators: &[Validator], spec: &ChainSpec) -> Result<Self, BeaconStateError> {
+    pub fn new<N: Unsigned>(
+        validators: &PersistentList<Validator, N>,
+        spec: &ChainSpec,
+    ) -> Result<Self, BeaconStateError> {
         let mut exit_cache = ExitCache {
             initialized: true,
             ..ExitCache::default()
@@ -6,8 +6,9 @@ use beacon_chain::test_utils::{
     DEFAULT_ETH1_BLOCK_HASH,
 };
 use beacon_chain::types::{
-    test_utils::TestRandom, BeaconState, BeaconStateAltair, BeaconStateBase, BeaconStateError,
-    ChainSpec, CloneConfig, Domain, Epoch, EthSpec, FixedVector, Hash256, Keypair, MainnetEthSpec,
+    generalized_index, light_client_finality_update, test_utils::TestRandom, BeaconState,
+    BeaconStateAltair, BeaconStateBase, BeaconStateError, ChainSpec, Checkpoint, CloneConfig,
+    Domain, Epoch, EthSpec, ExecutionBlockHash, FixedVector, Hash256, Keypair, MainnetEthSpec,
     MinimalEthSpec, RelativeEpoch, Slot,
 };
 use safe_arith::SafeArith;
@@ -548,6 +549,50 @@ fn tree_hash_cache_linear_history() {
     assert_eq!(root.as_bytes(), &state.tree_hash_root()[..]);
 }
 
+#[test]
+fn tree_hash_cache_updates_all_fields() {
+    let mut rng = XorShiftRng::from_seed([42; 16]);
+
+    let mut state: BeaconState<MainnetEthSpec> =
+        BeaconState::Altair(BeaconStateAltair::random_for_test(&mut rng));
+    state.update_tree_hash_cache().unwrap();
+
+    // Advance the slot so that the cache is updated rather than rebuilt, then change each of the
+    // fields that are hashed concurrently.
+    let root = state.update_tree_hash_cache().unwrap();
+    *state.slot_mut() += 1;
+    state.set_state_root(state.slot() - 1, root).unwrap();
+
+    state
+        .set_block_root(state.slot() - 1, Hash256::repeat_byte(1))
+        .unwrap();
+    state
+        .historical_roots_mut()
+        .push(Hash256::repeat_byte(2))
+        .unwrap();
+    state.eth1_data_votes_mut().push(<_>::default()).unwrap();
+    state.validators_mut().push(<_>::default()).unwrap();
+    state.balances_mut().push(32).unwrap();
+    state
+        .set_randao_mix(state.current_epoch(), Hash256::repeat_byte(3))
+        .unwrap();
+    state.slashings_mut()[0] = 42;
+    state
+        .previous_epoch_participation_mut()
+        .unwrap()
+        .push(<_>::default())
+        .unwrap();
+    state
+        .current_epoch_participation_mut()
+        .unwrap()
+        .push(<_>::default())
+        .unwrap();
+    state.inactivity_scores_mut().unwrap().push(7).unwrap();
+
+    let root = state.update_tree_hash_cache().unwrap();
+    assert_eq!(root.as_bytes(), &state.tree_hash_root()[..]);
+}
+
 // Check how the cache behaves when there's a distance larger than `SLOTS_PER_HISTORICAL_ROOT`
 // since its last update.
 #[test]
@@ -610,3 +655,63 @@ fn tree_hash_cache_linear_history_long_skip() {
         target_slot
     );
 }
+
+#[test]
+fn finalized_root_merkle_proof() {
+    let mut state = build_state::<MinimalEthSpec>(16);
+    *state.finalized_checkpoint_mut() = Checkpoint {
+        epoch: Epoch::new(3),
+        root: Hash256::repeat_byte(7),
+    };
+
+    let proof = state
+        .compute_merkle_proof(light_client_finality_update::FINALIZED_ROOT_INDEX)
+        .unwrap();
+    let state_root = state.update_tree_hash_cache().unwrap();
+    assert_eq!(proof.len(), 6);
+    assert!(merkle_proof::verify_merkle_proof(
+        Hash256::repeat_byte(7),
+        &proof,
+        proof.len(),
+        light_client_finality_update::FINALIZED_ROOT_INDEX % (1 << proof.len()),
+        state_root,
+    ));
+}
+
+#[test]
+fn execution_payload_header_block_hash_merkle_proof() {
+    let spec = MinimalEthSpec::default_spec();
+    let mut state = build_state::<MinimalEthSpec>(16);
+    state_processing::upgrade::upgrade_to_altair(&mut state, &spec).unwrap();
+    state_processing::upgrade::upgrade_to_bellatrix(&mut state, &spec).unwrap();
+
+    let block_hash = ExecutionBlockHash::from_root(Hash256::repeat_byte(42));
+    state
+        .latest_execution_payload_header_merge_mut()
+        .unwrap()
+        .block_hash = block_hash;
+
+    // `BeaconState.latest_execution_payload_header.block_hash`.
+    let generalized_index = generalized_index::concat_generalized_indices(&[56, 28]).unwrap();
+    assert_eq!(generalized_index, 908);
+
+    let (leaf, proof) = state
+        .compute_merkle_proof_with_leaf(generalized_index)
+        .unwrap();
+    let state_root = state.update_tree_hash_cache().unwrap();
+    assert_eq!(leaf, block_hash.tree_hash_root());
+    assert_eq!(proof.len(), 9);
+    assert!(generalized_index::verify_generalized_index_proof(
+        leaf,
+        &proof,
+        generalized_index,
+        state_root
+    ));
+
+    // Lists cannot be descended into.
+    let validators_index = generalized_index::concat_generalized_indices(&[43, 2]).unwrap();
+    assert!(matches!(
+        state.compute_merkle_proof(validators_index),
+        Err(BeaconStateError::IndexNotSupported(_))
+    ));
+}
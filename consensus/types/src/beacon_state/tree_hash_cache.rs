@@ -3,7 +3,9 @@
 #![allow(clippy::indexing_slicing)]
 
 use super::Error;
-use crate::{BeaconState, EthSpec, Hash256, ParticipationList, Slot, Unsigned, Validator};
+use crate::{
+    BeaconState, EthSpec, Hash256, ParticipationList, PersistentList, Slot, Unsigned, Validator,
+};
 use cached_tree_hash::{int_log, CacheArena, CachedTreeHash, TreeHashCache};
 use rayon::prelude::*;
 use ssz_derive::{Decode, Encode};
@@ -18,7 +20,7 @@ use tree_hash::{mix_in_length, MerkleHasher, TreeHash};
 ///
 /// This constant is set with the assumption that there are `> 16` and `<= 32` fields on the
 /// `BeaconState`. **Tree hashing will fail if this value is set incorrectly.**
-const NUM_BEACON_STATE_HASH_TREE_ROOT_LEAVES: usize = 32;
+pub(crate) const NUM_BEACON_STATE_HASH_TREE_ROOT_LEAVES: usize = 32;
 
 /// The number of nodes in the Merkle tree of a validator record.
 const NODES_PER_VALIDATOR: usize = 15;
@@ -135,7 +137,12 @@ pub struct BeaconTreeHashCacheInner<T: EthSpec> {
     // Validators cache
     validators: ValidatorsListTreeHashCache,
     // Arenas
-    fixed_arena: CacheArena,
+    //
+    // Each list has its own arena so that the lists can be hashed concurrently.
+    block_roots_arena: CacheArena,
+    state_roots_arena: CacheArena,
+    historical_roots_arena: CacheArena,
+    randao_mixes_arena: CacheArena,
     balances_arena: CacheArena,
     slashings_arena: CacheArena,
     // Caches
@@ -158,13 +165,25 @@ impl<T: EthSpec> BeaconTreeHashCacheInner<T> {
     /// Allocates the necessary memory to store all of the cached Merkle trees. Only the leaves are
     /// hashed, leaving the internal nodes as all-zeros.
     pub fn new(state: &BeaconState<T>) -> Self {
-        let mut fixed_arena = CacheArena::default();
-        let block_roots = state.block_roots().new_tree_hash_cache(&mut fixed_arena);
-        let state_roots = state.state_roots().new_tree_hash_cache(&mut fixed_arena);
+        let mut block_roots_arena = CacheArena::default();
+        let block_roots = state
+            .block_roots()
+            .new_tree_hash_cache(&mut block_roots_arena);
+
+        let mut state_roots_arena = CacheArena::default();
+        let state_roots = state
+            .state_roots()
+            .new_tree_hash_cache(&mut state_roots_arena);
+
+        let mut historical_roots_arena = CacheArena::default();
         let historical_roots = state
             .historical_roots()
-            .new_tree_hash_cache(&mut fixed_arena);
-        let randao_mixes = state.randao_mixes().new_tree_hash_cache(&mut fixed_arena);
+            .new_tree_hash_cache(&mut historical_roots_arena);
+
+        let mut randao_mixes_arena = CacheArena::default();
+        let randao_mixes = state
+            .randao_mixes()
+            .new_tree_hash_cache(&mut randao_mixes_arena);
 
         let validators = ValidatorsListTreeHashCache::new::<T>(state.validators());
 
@@ -194,7 +213,10 @@ impl<T: EthSpec> BeaconTreeHashCacheInner<T> {
         Self {
             previous_state: None,
             validators,
-            fixed_arena,
+            block_roots_arena,
+            state_roots_arena,
+            historical_roots_arena,
+            randao_mixes_arena,
             balances_arena,
             slashings_arena,
             block_roots,
@@ -216,6 +238,25 @@ impl<T: EthSpec> BeaconTreeHashCacheInner<T> {
     /// the `Self::new` function. If the state is more than `SLOTS_PER_HISTORICAL_ROOT` slots
     /// after `self.previous_state` then the whole cache will be re-initialized.
     pub fn recalculate_tree_hash_root(&mut self, state: &BeaconState<T>) -> Result<Hash256, Error> {
+        let mut hasher = MerkleHasher::with_leaves(NUM_BEACON_STATE_HASH_TREE_ROOT_LEAVES);
+
+        for leaf in self.recalculate_tree_hash_leaves(state)? {
+            hasher.write(leaf.as_bytes())?;
+        }
+
+        let root = hasher.finish()?;
+
+        self.previous_state = Some((root, state.slot()));
+
+        Ok(root)
+    }
+
+    /// Updates the cache and returns the tree hash roots of each of the fields of `state`, in the
+    /// order in which they're merkleized.
+    pub fn recalculate_tree_hash_leaves(
+        &mut self,
+        state: &BeaconState<T>,
+    ) -> Result<Vec<Hash256>, Error> {
         // If this cache has previously produced a root, ensure that it is in the state root
         // history of this state.
         //
@@ -244,141 +285,206 @@ impl<T: EthSpec> BeaconTreeHashCacheInner<T> {
             }
         }
 
-        let mut hasher = MerkleHasher::with_leaves(NUM_BEACON_STATE_HASH_TREE_ROOT_LEAVES);
+        // Hash the lists concurrently, since they make up the bulk of the work. The validators
+        // and balances dominate for large validator sets; the validators cache additionally
+        // hashes its own chunks in parallel.
+        let Self {
+            validators,
+            block_roots_arena,
+            state_roots_arena,
+            historical_roots_arena,
+            randao_mixes_arena,
+            balances_arena,
+            slashings_arena,
+            block_roots,
+            state_roots,
+            historical_roots,
+            balances,
+            randao_mixes,
+            slashings,
+            eth1_data_votes,
+            inactivity_scores,
+            previous_epoch_participation,
+            current_epoch_participation,
+            ..
+        } = self;
+
+        let mut block_roots_root = None;
+        let mut state_roots_root = None;
+        let mut historical_roots_root = None;
+        let mut eth1_data_votes_root = None;
+        let mut validators_root = None;
+        let mut balances_root = None;
+        let mut randao_mixes_root = None;
+        let mut slashings_root = None;
+        let mut previous_epoch_participation_root = None;
+        let mut current_epoch_participation_root = None;
+        let mut inactivity_scores_root = None;
+
+        rayon::scope(|s| {
+            s.spawn(|_| {
+                block_roots_root = Some(
+                    state
+                        .block_roots()
+                        .recalculate_tree_hash_root(block_roots_arena, block_roots)
+                        .map_err(Error::from),
+                )
+            });
+            s.spawn(|_| {
+                state_roots_root = Some(
+                    state
+                        .state_roots()
+                        .recalculate_tree_hash_root(state_roots_arena, state_roots)
+                        .map_err(Error::from),
+                )
+            });
+            s.spawn(|_| {
+                historical_roots_root = Some(
+                    state
+                        .historical_roots()
+                        .recalculate_tree_hash_root(historical_roots_arena, historical_roots)
+                        .map_err(Error::from),
+                )
+            });
+            s.spawn(|_| {
+                eth1_data_votes_root = Some(eth1_data_votes.recalculate_tree_hash_root(state))
+            });
+            s.spawn(|_| {
+                validators_root = Some(validators.recalculate_tree_hash_root(state.validators()))
+            });
+            s.spawn(|_| {
+                balances_root = Some(
+                    state
+                        .balances()
+                        .recalculate_tree_hash_root(balances_arena, balances)
+                        .map_err(Error::from),
+                )
+            });
+            s.spawn(|_| {
+                randao_mixes_root = Some(
+                    state
+                        .randao_mixes()
+                        .recalculate_tree_hash_root(randao_mixes_arena, randao_mixes)
+                        .map_err(Error::from),
+                )
+            });
+            s.spawn(|_| {
+                slashings_root = Some(
+                    state
+                        .slashings()
+                        .recalculate_tree_hash_root(slashings_arena, slashings)
+                        .map_err(Error::from),
+                )
+            });
+
+            // Participation and inactivity (Altair and later).
+            s.spawn(|_| {
+                if let Ok(participation) = state.previous_epoch_participation() {
+                    previous_epoch_participation_root = Some(
+                        previous_epoch_participation
+                            .recalculate_tree_hash_root(&ParticipationList::new(participation)),
+                    )
+                }
+            });
+            s.spawn(|_| {
+                if let Ok(participation) = state.current_epoch_participation() {
+                    current_epoch_participation_root = Some(
+                        current_epoch_participation
+                            .recalculate_tree_hash_root(&ParticipationList::new(participation)),
+                    )
+                }
+            });
+            s.spawn(|_| {
+                if let Ok(scores) = state.inactivity_scores() {
+                    inactivity_scores_root =
+                        Some(inactivity_scores.recalculate_tree_hash_root(scores))
+                }
+            });
+        });
 
-        hasher.write(state.genesis_time().tree_hash_root().as_bytes())?;
-        hasher.write(state.genesis_validators_root().tree_hash_root().as_bytes())?;
-        hasher.write(state.slot().tree_hash_root().as_bytes())?;
-        hasher.write(state.fork().tree_hash_root().as_bytes())?;
-        hasher.write(state.latest_block_header().tree_hash_root().as_bytes())?;
-        hasher.write(
-            state
-                .block_roots()
-                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.block_roots)?
-                .as_bytes(),
-        )?;
-        hasher.write(
-            state
-                .state_roots()
-                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.state_roots)?
-                .as_bytes(),
-        )?;
-        hasher.write(
-            state
-                .historical_roots()
-                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.historical_roots)?
-                .as_bytes(),
-        )?;
-        hasher.write(state.eth1_data().tree_hash_root().as_bytes())?;
-        hasher.write(
-            self.eth1_data_votes
-                .recalculate_tree_hash_root(state)?
-                .as_bytes(),
-        )?;
-        hasher.write(state.eth1_deposit_index().tree_hash_root().as_bytes())?;
-        hasher.write(
-            self.validators
-                .recalculate_tree_hash_root(state.validators())?
-                .as_bytes(),
-        )?;
-        hasher.write(
-            state
-                .balances()
-                .recalculate_tree_hash_root(&mut self.balances_arena, &mut self.balances)?
-                .as_bytes(),
-        )?;
-        hasher.write(
-            state
-                .randao_mixes()
-                .recalculate_tree_hash_root(&mut self.fixed_arena, &mut self.randao_mixes)?
-                .as_bytes(),
-        )?;
-        hasher.write(
-            state
-                .slashings()
-                .recalculate_tree_hash_root(&mut self.slashings_arena, &mut self.slashings)?
-                .as_bytes(),
-        )?;
+        let mut leaves = Vec::with_capacity(NUM_BEACON_STATE_HASH_TREE_ROOT_LEAVES);
+
+        leaves.push(state.genesis_time().tree_hash_root());
+        leaves.push(state.genesis_validators_root().tree_hash_root());
+        leaves.push(state.slot().tree_hash_root());
+        leaves.push(state.fork().tree_hash_root());
+        leaves.push(state.latest_block_header().tree_hash_root());
+        leaves.push(spawned_root(block_roots_root)?);
+        leaves.push(spawned_root(state_roots_root)?);
+        leaves.push(spawned_root(historical_roots_root)?);
+        leaves.push(state.eth1_data().tree_hash_root());
+        leaves.push(spawned_root(eth1_data_votes_root)?);
+        leaves.push(state.eth1_deposit_index().tree_hash_root());
+        leaves.push(spawned_root(validators_root)?);
+        leaves.push(spawned_root(balances_root)?);
+        leaves.push(spawned_root(randao_mixes_root)?);
+        leaves.push(spawned_root(slashings_root)?);
 
         // Participation
         if let BeaconState::Base(state) = state {
-            hasher.write(
-                state
-                    .previous_epoch_attestations
-                    .tree_hash_root()
-                    .as_bytes(),
-            )?;
-            hasher.write(state.current_epoch_attestations.tree_hash_root().as_bytes())?;
+            leaves.push(state.previous_epoch_attestations.tree_hash_root());
+            leaves.push(state.current_epoch_attestations.tree_hash_root());
         } else {
-            hasher.write(
-                self.previous_epoch_participation
-                    .recalculate_tree_hash_root(&ParticipationList::new(
-                        state.previous_epoch_participation()?,
-                    ))?
-                    .as_bytes(),
-            )?;
-            hasher.write(
-                self.current_epoch_participation
-                    .recalculate_tree_hash_root(&ParticipationList::new(
-                        state.current_epoch_participation()?,
-                    ))?
-                    .as_bytes(),
-            )?;
+            leaves.push(spawned_root(previous_epoch_participation_root)?);
+            leaves.push(spawned_root(current_epoch_participation_root)?);
         }
 
-        hasher.write(state.justification_bits().tree_hash_root().as_bytes())?;
-        hasher.write(
-            state
-                .previous_justified_checkpoint()
-                .tree_hash_root()
-                .as_bytes(),
-        )?;
-        hasher.write(
-            state
-                .current_justified_checkpoint()
-                .tree_hash_root()
-                .as_bytes(),
-        )?;
-        hasher.write(state.finalized_checkpoint().tree_hash_root().as_bytes())?;
+        leaves.push(state.justification_bits().tree_hash_root());
+        leaves.push(state.previous_justified_checkpoint().tree_hash_root());
+        leaves.push(state.current_justified_checkpoint().tree_hash_root());
+        leaves.push(state.finalized_checkpoint().tree_hash_root());
 
         // Inactivity & light-client sync committees (Altair and later).
-        if let Ok(inactivity_scores) = state.inactivity_scores() {
-            hasher.write(
-                self.inactivity_scores
-                    .recalculate_tree_hash_root(inactivity_scores)?
-                    .as_bytes(),
-            )?;
+        if state.inactivity_scores().is_ok() {
+            leaves.push(spawned_root(inactivity_scores_root)?);
         }
 
         if let Ok(current_sync_committee) = state.current_sync_committee() {
-            hasher.write(current_sync_committee.tree_hash_root().as_bytes())?;
+            leaves.push(current_sync_committee.tree_hash_root());
         }
 
         if let Ok(next_sync_committee) = state.next_sync_committee() {
-            hasher.write(next_sync_committee.tree_hash_root().as_bytes())?;
+            leaves.push(next_sync_committee.tree_hash_root());
         }
 
         // Execution payload (merge and later).
         if let Ok(payload_header) = state.latest_execution_payload_header() {
-            hasher.write(payload_header.tree_hash_root().as_bytes())?;
+            leaves.push(payload_header.tree_hash_root());
         }
 
-        let root = hasher.finish()?;
+        // Withdrawals & historical summaries (Capella and later).
+        if let Ok(next_withdrawal_index) = state.next_withdrawal_index() {
+            leaves.push(next_withdrawal_index.tree_hash_root());
+        }
 
-        self.previous_state = Some((root, state.slot()));
+        if let Ok(next_withdrawal_validator_index) = state.next_withdrawal_validator_index() {
+            leaves.push(next_withdrawal_validator_index.tree_hash_root());
+        }
 
-        Ok(root)
+        if let Ok(historical_summaries) = state.historical_summaries() {
+            leaves.push(historical_summaries.tree_hash_root());
+        }
+
+        Ok(leaves)
     }
 
     /// Updates the cache and provides the root of the given `validators`.
     pub fn recalculate_validators_tree_hash_root(
         &mut self,
-        validators: &[Validator],
+        validators: &PersistentList<Validator, T::ValidatorRegistryLimit>,
     ) -> Result<Hash256, Error> {
         self.validators.recalculate_tree_hash_root(validators)
     }
 }
 
+/// Returns the root computed by a task spawned in `BeaconTreeHashCacheInner::recalculate_tree_hash_leaves`.
+///
+/// A missing root means the task wasn't spawned for a field that the state has, which would be a
+/// bug.
+fn spawned_root(root: Option<Result<Hash256, Error>>) -> Result<Hash256, Error> {
+    root.ok_or(Error::TreeHashCacheInconsistent)?
+}
+
 /// A specialized cache for computing the tree hash root of `state.validators`.
 #[derive(Debug, PartialEq, Clone, Default, Encode, Decode)]
 struct ValidatorsListTreeHashCache {
@@ -392,7 +498,7 @@ impl ValidatorsListTreeHashCache {
     ///
     /// Allocates the necessary memory to store all of the cached Merkle trees but does perform any
     /// hashing.
-    fn new<E: EthSpec>(validators: &[Validator]) -> Self {
+    fn new<E: EthSpec>(validators: &PersistentList<Validator, E::ValidatorRegistryLimit>) -> Self {
         let mut list_arena = CacheArena::default();
         Self {
             list_cache: TreeHashCache::new(
@@ -409,7 +515,10 @@ impl ValidatorsListTreeHashCache {
     ///
     /// This function makes assumptions that the `validators` list will only change in accordance
     /// with valid per-block/per-slot state transitions.
-    fn recalculate_tree_hash_root(&mut self, validators: &[Validator]) -> Result<Hash256, Error> {
+    fn recalculate_tree_hash_root<N: Unsigned>(
+        &mut self,
+        validators: &PersistentList<Validator, N>,
+    ) -> Result<Hash256, Error> {
         let mut list_arena = std::mem::take(&mut self.list_arena);
 
         let leaves = self.values.leaves(validators)?;
@@ -466,7 +575,7 @@ impl ParallelValidatorTreeHash {
     ///
     /// Allocates the necessary memory to store all of the cached Merkle trees but does perform any
     /// hashing.
-    fn new<E: EthSpec>(validators: &[Validator]) -> Self {
+    fn new<E: EthSpec>(validators: &PersistentList<Validator, E::ValidatorRegistryLimit>) -> Self {
         let num_arenas = std::cmp::max(
             1,
             (validators.len() + VALIDATORS_PER_ARENA - 1) / VALIDATORS_PER_ARENA,
@@ -505,7 +614,10 @@ impl ParallelValidatorTreeHash {
     ///
     /// This function makes assumptions that the `validators` list will only change in accordance
     /// with valid per-block/per-slot state transitions.
-    fn leaves(&mut self, validators: &[Validator]) -> Result<Vec<Vec<Hash256>>, Error> {
+    fn leaves<N: Unsigned>(
+        &mut self,
+        validators: &PersistentList<Validator, N>,
+    ) -> Result<Vec<Vec<Hash256>>, Error> {
         match self.len().cmp(&validators.len()) {
             Ordering::Less => validators.iter().skip(self.len()).for_each(|v| {
                 if self
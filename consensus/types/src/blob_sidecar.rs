@@ -0,0 +1,160 @@
+use crate::beacon_block_body::kzg_commitment_subtree_index;
+use crate::test_utils::TestRandom;
+use crate::*;
+use derivative::Derivative;
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use ssz_types::{FixedVector, VariableList};
+use std::sync::Arc;
+use test_random_derive::TestRandom;
+use tree_hash::TreeHash;
+use tree_hash_derive::TreeHash;
+
+pub type Blob<T> = FixedVector<u8, <T as EthSpec>::BytesPerBlob>;
+pub type BlobSidecarList<T> = VariableList<Arc<BlobSidecar<T>>, <T as EthSpec>::MaxBlobsPerBlock>;
+
+/// Identifies a blob by the root of the block which commits to it and its index in that block.
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode, TreeHash,
+)]
+pub struct BlobIdentifier {
+    pub block_root: Hash256,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub index: u64,
+}
+
+/// A blob, its KZG commitment and proof, and a proof that the commitment is included in the block
+/// described by `signed_block_header`.
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug, Clone, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom, Derivative,
+)]
+#[derivative(PartialEq, Hash(bound = "T: EthSpec"))]
+#[serde(bound = "T: EthSpec")]
+pub struct BlobSidecar<T: EthSpec> {
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub index: u64,
+    #[serde(with = "ssz_types::serde_utils::hex_fixed_vec")]
+    pub blob: Blob<T>,
+    pub kzg_commitment: KzgCommitment,
+    pub kzg_proof: KzgProof,
+    pub signed_block_header: SignedBeaconBlockHeader,
+    pub kzg_commitment_inclusion_proof: FixedVector<Hash256, T::KzgCommitmentInclusionProofDepth>,
+}
+
+impl<T: EthSpec> BlobSidecar<T> {
+    /// Create the sidecar for the blob at `index` of `block`.
+    ///
+    /// Returns an error if the block predates Deneb or has no commitment at `index`.
+    pub fn new<Payload: ExecPayload<T>>(
+        index: usize,
+        blob: Blob<T>,
+        block: &SignedBeaconBlock<T, Payload>,
+        kzg_proof: KzgProof,
+    ) -> Result<Self, BeaconStateError> {
+        let body = block
+            .message()
+            .body_deneb()
+            .map_err(|_| BeaconStateError::IncorrectStateVariant)?;
+        let kzg_commitment = *body
+            .blob_kzg_commitments
+            .get(index)
+            .ok_or(BeaconStateError::IndexNotSupported(index))?;
+        let kzg_commitment_inclusion_proof = body.kzg_commitment_merkle_proof(index)?;
+
+        Ok(Self {
+            index: index as u64,
+            blob,
+            kzg_commitment,
+            kzg_proof,
+            signed_block_header: block.signed_block_header(),
+            kzg_commitment_inclusion_proof,
+        })
+    }
+
+    pub fn id(&self) -> BlobIdentifier {
+        BlobIdentifier {
+            block_root: self.block_root(),
+            index: self.index,
+        }
+    }
+
+    pub fn slot(&self) -> Slot {
+        self.signed_block_header.message.slot
+    }
+
+    pub fn block_root(&self) -> Hash256 {
+        self.signed_block_header.message.tree_hash_root()
+    }
+
+    pub fn block_parent_root(&self) -> Hash256 {
+        self.signed_block_header.message.parent_root
+    }
+
+    pub fn block_proposer_index(&self) -> u64 {
+        self.signed_block_header.message.proposer_index
+    }
+
+    /// Returns `true` if `kzg_commitment_inclusion_proof` proves `kzg_commitment` against the
+    /// body root of `signed_block_header`.
+    pub fn verify_blob_sidecar_inclusion_proof(&self) -> bool {
+        let index = match kzg_commitment_subtree_index::<T>(self.index as usize) {
+            Ok(index) => index,
+            Err(_) => return false,
+        };
+        merkle_proof::verify_merkle_proof(
+            self.kzg_commitment.tree_hash_root(),
+            &self.kzg_commitment_inclusion_proof,
+            T::kzg_commitment_inclusion_proof_depth(),
+            index,
+            self.signed_block_header.message.body_root,
+        )
+    }
+
+    /// Returns the maximum size of a `BlobSidecar` in bytes, which is fixed.
+    pub fn max_size() -> usize {
+        <Self as ssz::Encode>::ssz_fixed_len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{SeedableRng, XorShiftRng};
+
+    type E = MinimalEthSpec;
+
+    #[test]
+    fn inclusion_proof_roundtrip() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let spec = ForkName::Deneb.make_genesis_spec(E::default_spec());
+
+        let mut block = BeaconBlock::<E>::Deneb(BeaconBlockDeneb::empty(&spec));
+        *block.body_mut().blob_kzg_commitments_mut().unwrap() = VariableList::new(
+            (0..3)
+                .map(|_| KzgCommitment::random_for_test(rng))
+                .collect(),
+        )
+        .unwrap();
+        let block = SignedBeaconBlock::from_block(block, Signature::empty());
+
+        for index in 0..3 {
+            let sidecar = BlobSidecar::new(
+                index,
+                Blob::<E>::default(),
+                &block,
+                KzgProof::random_for_test(rng),
+            )
+            .unwrap();
+            assert_eq!(sidecar.block_root(), block.canonical_root());
+            assert!(sidecar.verify_blob_sidecar_inclusion_proof());
+
+            let mut bad_sidecar = sidecar.clone();
+            bad_sidecar.index = (index as u64 + 1) % 3;
+            assert!(!bad_sidecar.verify_blob_sidecar_inclusion_proof());
+        }
+
+        assert!(BlobSidecar::new(3, Blob::<E>::default(), &block, KzgProof([0; 48])).is_err());
+    }
+}
@@ -0,0 +1,52 @@
+use crate::signing_domain::get_bls_to_execution_change_domain;
+use crate::{
+    test_utils::TestRandom, Address, ChainSpec, Hash256, PublicKeyBytes, SecretKey,
+    SignedBlsToExecutionChange, SignedRoot,
+};
+
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use test_random_derive::TestRandom;
+use tree_hash_derive::TreeHash;
+
+/// A request to change a validator's `0x00` (BLS) withdrawal credentials to `0x01` credentials
+/// which pay out to an execution layer address.
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom,
+)]
+pub struct BlsToExecutionChange {
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    /// The public key of the BLS withdrawal credentials, which must sign the change.
+    pub from_bls_pubkey: PublicKeyBytes,
+    pub to_execution_address: Address,
+}
+
+impl SignedRoot for BlsToExecutionChange {}
+
+impl BlsToExecutionChange {
+    /// Sign the change with the BLS withdrawal key.
+    ///
+    /// Changes are signed with the genesis fork version so that they remain valid across forks.
+    pub fn sign(
+        self,
+        secret_key: &SecretKey,
+        genesis_validators_root: Hash256,
+        spec: &ChainSpec,
+    ) -> SignedBlsToExecutionChange {
+        let domain = get_bls_to_execution_change_domain(genesis_validators_root, spec);
+        let message = self.signing_root(domain);
+        SignedBlsToExecutionChange {
+            message: self,
+            signature: secret_key.sign(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ssz_and_tree_hash_tests!(BlsToExecutionChange);
+}
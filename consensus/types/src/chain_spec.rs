@@ -20,6 +20,8 @@ pub enum Domain {
     SyncCommittee,
     ContributionAndProof,
     SyncCommitteeSelectionProof,
+    BlsToExecutionChange,
+    ApplicationMask(ApplicationDomain),
 }
 
 /// Lighthouse's internal configuration struct.
@@ -69,6 +71,7 @@ pub struct ChainSpec {
      */
     pub genesis_fork_version: [u8; 4],
     pub bls_withdrawal_prefix_byte: u8,
+    pub eth1_address_withdrawal_prefix_byte: u8,
 
     /*
      * Time parameters
@@ -148,6 +151,33 @@ pub struct ChainSpec {
     pub terminal_block_hash_activation_epoch: Epoch,
     pub safe_slots_to_import_optimistically: u64,
 
+    /*
+     * Capella hard fork params
+     */
+    pub capella_fork_version: [u8; 4],
+    /// The Capella fork epoch is optional, with `None` representing "Capella never happens".
+    pub capella_fork_epoch: Option<Epoch>,
+    pub max_validators_per_withdrawals_sweep: u64,
+    pub(crate) domain_bls_to_execution_change: u32,
+
+    /*
+     * Deneb hard fork params
+     */
+    pub deneb_fork_version: [u8; 4],
+    /// The Deneb fork epoch is optional, with `None` representing "Deneb never happens".
+    pub deneb_fork_epoch: Option<Epoch>,
+    pub max_per_epoch_activation_churn_limit: u64,
+    pub max_request_blob_sidecars: u64,
+    pub min_epochs_for_blob_sidecars_requests: u64,
+    pub blob_sidecar_subnet_count: u64,
+
+    /*
+     * Electra hard fork params
+     */
+    pub electra_fork_version: [u8; 4],
+    /// The Electra fork epoch is optional, with `None` representing "Electra never happens".
+    pub electra_fork_epoch: Option<Epoch>,
+
     /*
      * Networking
      */
@@ -159,6 +189,9 @@ pub struct ChainSpec {
     pub attestation_subnet_count: u64,
     pub random_subnets_per_validator: u64,
     pub epochs_per_random_subnet_subscription: u64,
+    pub subnets_per_node: u8,
+    pub epochs_per_subnet_subscription: u64,
+    pub attestation_subnet_extra_bits: u8,
 }
 
 impl ChainSpec {
@@ -168,6 +201,27 @@ impl ChainSpec {
         config.apply_to_chain_spec::<T>(&spec)
     }
 
+    /// Construct a `ChainSpec` from a standard config and a set of preset overrides.
+    ///
+    /// Preset values which are fixed at compile time by `T` may be present in the overrides, but
+    /// must match `T`. All other preset values replace those of `T::default_spec`.
+    pub fn from_config_and_preset<T: EthSpec>(
+        config: &Config,
+        preset_overrides: &PresetOverrides,
+    ) -> Result<Self, String> {
+        let mut spec = Self::from_config::<T>(config).ok_or_else(|| {
+            format!(
+                "Config preset base {} is incompatible with {}",
+                config.preset_base,
+                T::spec_name()
+            )
+        })?;
+        FullPreset::from_chain_spec::<T>(&spec)
+            .with_overrides(preset_overrides)?
+            .apply_to_chain_spec::<T>(&mut spec)?;
+        Ok(spec)
+    }
+
     /// Returns an `EnrForkId` for the given `slot`.
     pub fn enr_fork_id<T: EthSpec>(
         &self,
@@ -223,11 +277,17 @@ impl ChainSpec {
 
     /// Returns the name of the fork which is active at `epoch`.
     pub fn fork_name_at_epoch(&self, epoch: Epoch) -> ForkName {
-        match self.bellatrix_fork_epoch {
-            Some(fork_epoch) if epoch >= fork_epoch => ForkName::Merge,
-            _ => match self.altair_fork_epoch {
-                Some(fork_epoch) if epoch >= fork_epoch => ForkName::Altair,
-                _ => ForkName::Base,
+        match self.deneb_fork_epoch {
+            Some(fork_epoch) if epoch >= fork_epoch => ForkName::Deneb,
+            _ => match self.capella_fork_epoch {
+                Some(fork_epoch) if epoch >= fork_epoch => ForkName::Capella,
+                _ => match self.bellatrix_fork_epoch {
+                    Some(fork_epoch) if epoch >= fork_epoch => ForkName::Merge,
+                    _ => match self.altair_fork_epoch {
+                        Some(fork_epoch) if epoch >= fork_epoch => ForkName::Altair,
+                        _ => ForkName::Base,
+                    },
+                },
             },
         }
     }
@@ -238,6 +298,8 @@ impl ChainSpec {
             ForkName::Base => self.genesis_fork_version,
             ForkName::Altair => self.altair_fork_version,
             ForkName::Merge => self.bellatrix_fork_version,
+            ForkName::Capella => self.capella_fork_version,
+            ForkName::Deneb => self.deneb_fork_version,
         }
     }
 
@@ -247,6 +309,8 @@ impl ChainSpec {
             ForkName::Base => Some(Epoch::new(0)),
             ForkName::Altair => self.altair_fork_epoch,
             ForkName::Merge => self.bellatrix_fork_epoch,
+            ForkName::Capella => self.capella_fork_epoch,
+            ForkName::Deneb => self.deneb_fork_epoch,
         }
     }
 
@@ -255,7 +319,9 @@ impl ChainSpec {
         match state {
             BeaconState::Base(_) => self.inactivity_penalty_quotient,
             BeaconState::Altair(_) => self.inactivity_penalty_quotient_altair,
-            BeaconState::Merge(_) => self.inactivity_penalty_quotient_bellatrix,
+            BeaconState::Merge(_) | BeaconState::Capella(_) | BeaconState::Deneb(_) => {
+                self.inactivity_penalty_quotient_bellatrix
+            }
         }
     }
 
@@ -267,7 +333,9 @@ impl ChainSpec {
         match state {
             BeaconState::Base(_) => self.proportional_slashing_multiplier,
             BeaconState::Altair(_) => self.proportional_slashing_multiplier_altair,
-            BeaconState::Merge(_) => self.proportional_slashing_multiplier_bellatrix,
+            BeaconState::Merge(_) | BeaconState::Capella(_) | BeaconState::Deneb(_) => {
+                self.proportional_slashing_multiplier_bellatrix
+            }
         }
     }
 
@@ -279,7 +347,9 @@ impl ChainSpec {
         match state {
             BeaconState::Base(_) => self.min_slashing_penalty_quotient,
             BeaconState::Altair(_) => self.min_slashing_penalty_quotient_altair,
-            BeaconState::Merge(_) => self.min_slashing_penalty_quotient_bellatrix,
+            BeaconState::Merge(_) | BeaconState::Capella(_) | BeaconState::Deneb(_) => {
+                self.min_slashing_penalty_quotient_bellatrix
+            }
         }
     }
 
@@ -326,6 +396,8 @@ impl ChainSpec {
             Domain::SyncCommittee => self.domain_sync_committee,
             Domain::ContributionAndProof => self.domain_contribution_and_proof,
             Domain::SyncCommitteeSelectionProof => self.domain_sync_committee_selection_proof,
+            Domain::BlsToExecutionChange => self.domain_bls_to_execution_change,
+            Domain::ApplicationMask(application_domain) => application_domain.get_domain_constant(),
         }
     }
 
@@ -353,6 +425,18 @@ impl ChainSpec {
         self.compute_domain(Domain::Deposit, self.genesis_fork_version, Hash256::zero())
     }
 
+    /// Get the domain for signatures of builder API messages, e.g. validator registrations.
+    ///
+    /// Like deposits, these are valid across forks and chains, so the domain is computed with the
+    /// genesis fork version and a zero genesis validators root.
+    pub fn get_builder_domain(&self) -> Hash256 {
+        self.compute_domain(
+            Domain::ApplicationMask(ApplicationDomain::Builder),
+            self.genesis_fork_version,
+            Hash256::zero(),
+        )
+    }
+
     /// Return the 32-byte fork data root for the `current_version` and `genesis_validators_root`.
     ///
     /// This is used primarily in signature domains to avoid collisions across forks/chains.
@@ -387,6 +471,23 @@ impl ChainSpec {
         result
     }
 
+    /// The number of leading bits of a node id used to compute its long-lived attestation subnets,
+    /// i.e. `ceil(log2(attestation_subnet_count)) + attestation_subnet_extra_bits`.
+    pub fn attestation_subnet_prefix_bits(&self) -> u32 {
+        self.attestation_subnet_count
+            .next_power_of_two()
+            .trailing_zeros()
+            .saturating_add(self.attestation_subnet_extra_bits as u32)
+    }
+
+    /// The minimum number of epochs for which nodes must serve blocks to peers, i.e.
+    /// `MIN_EPOCHS_FOR_BLOCK_REQUESTS` from the p2p spec.
+    pub fn min_epochs_for_block_requests(&self) -> u64 {
+        self.min_validator_withdrawability_delay
+            .as_u64()
+            .saturating_add(self.churn_limit_quotient / 2)
+    }
+
     /// Compute a domain by applying the given `fork_version`.
     pub fn compute_domain(
         &self,
@@ -462,6 +563,7 @@ impl ChainSpec {
              */
             genesis_fork_version: [0; 4],
             bls_withdrawal_prefix_byte: 0,
+            eth1_address_withdrawal_prefix_byte: 1,
 
             /*
              * Time parameters
@@ -554,6 +656,30 @@ impl ChainSpec {
             terminal_block_hash_activation_epoch: Epoch::new(u64::MAX),
             safe_slots_to_import_optimistically: 128u64,
 
+            /*
+             * Capella hard fork params
+             */
+            capella_fork_version: [0x03, 0x00, 0x00, 0x00],
+            capella_fork_epoch: None,
+            max_validators_per_withdrawals_sweep: 16384,
+            domain_bls_to_execution_change: 10,
+
+            /*
+             * Deneb hard fork params
+             */
+            deneb_fork_version: [0x04, 0x00, 0x00, 0x00],
+            deneb_fork_epoch: None,
+            max_per_epoch_activation_churn_limit: 8,
+            max_request_blob_sidecars: 768,
+            min_epochs_for_blob_sidecars_requests: 4096,
+            blob_sidecar_subnet_count: 6,
+
+            /*
+             * Electra hard fork params
+             */
+            electra_fork_version: [0x05, 0x00, 0x00, 0x00],
+            electra_fork_epoch: None,
+
             /*
              * Network specific
              */
@@ -565,6 +691,9 @@ impl ChainSpec {
             maximum_gossip_clock_disparity_millis: 500,
             target_aggregators_per_committee: 16,
             epochs_per_random_subnet_subscription: 256,
+            subnets_per_node: 2,
+            epochs_per_subnet_subscription: 256,
+            attestation_subnet_extra_bits: 0,
         }
     }
 
@@ -597,6 +726,17 @@ impl ChainSpec {
             // Merge
             bellatrix_fork_version: [0x02, 0x00, 0x00, 0x01],
             bellatrix_fork_epoch: None,
+            // Capella
+            capella_fork_version: [0x03, 0x00, 0x00, 0x01],
+            capella_fork_epoch: None,
+            max_validators_per_withdrawals_sweep: 16,
+            // Deneb
+            deneb_fork_version: [0x04, 0x00, 0x00, 0x01],
+            deneb_fork_epoch: None,
+            max_per_epoch_activation_churn_limit: 4,
+            // Electra
+            electra_fork_version: [0x05, 0x00, 0x00, 0x01],
+            electra_fork_epoch: None,
             // Other
             network_id: 2, // lighthouse testnet network id
             deposit_chain_id: 5,
@@ -660,6 +800,7 @@ impl ChainSpec {
              */
             genesis_fork_version: [0x00, 0x00, 0x00, 0x64],
             bls_withdrawal_prefix_byte: 0,
+            eth1_address_withdrawal_prefix_byte: 1,
 
             /*
              * Time parameters
@@ -752,6 +893,30 @@ impl ChainSpec {
             terminal_block_hash_activation_epoch: Epoch::new(u64::MAX),
             safe_slots_to_import_optimistically: 128u64,
 
+            /*
+             * Capella hard fork params
+             */
+            capella_fork_version: [0x03, 0x00, 0x00, 0x64],
+            capella_fork_epoch: None,
+            max_validators_per_withdrawals_sweep: 8192,
+            domain_bls_to_execution_change: 10,
+
+            /*
+             * Deneb hard fork params
+             */
+            deneb_fork_version: [0x04, 0x00, 0x00, 0x64],
+            deneb_fork_epoch: None,
+            max_per_epoch_activation_churn_limit: 2,
+            max_request_blob_sidecars: 768,
+            min_epochs_for_blob_sidecars_requests: 4096,
+            blob_sidecar_subnet_count: 6,
+
+            /*
+             * Electra hard fork params
+             */
+            electra_fork_version: [0x05, 0x00, 0x00, 0x64],
+            electra_fork_epoch: None,
+
             /*
              * Network specific
              */
@@ -763,6 +928,9 @@ impl ChainSpec {
             maximum_gossip_clock_disparity_millis: 500,
             target_aggregators_per_committee: 16,
             epochs_per_random_subnet_subscription: 256,
+            subnets_per_node: 2,
+            epochs_per_subnet_subscription: 256,
+            attestation_subnet_extra_bits: 0,
         }
     }
 }
@@ -824,6 +992,30 @@ pub struct Config {
     #[serde(deserialize_with = "deserialize_fork_epoch")]
     pub bellatrix_fork_epoch: Option<MaybeQuoted<Epoch>>,
 
+    #[serde(default = "default_capella_fork_version")]
+    #[serde(with = "eth2_serde_utils::bytes_4_hex")]
+    capella_fork_version: [u8; 4],
+    #[serde(default = "default_capella_fork_epoch")]
+    #[serde(serialize_with = "serialize_fork_epoch")]
+    #[serde(deserialize_with = "deserialize_fork_epoch")]
+    pub capella_fork_epoch: Option<MaybeQuoted<Epoch>>,
+
+    #[serde(default = "default_deneb_fork_version")]
+    #[serde(with = "eth2_serde_utils::bytes_4_hex")]
+    deneb_fork_version: [u8; 4],
+    #[serde(default = "default_deneb_fork_epoch")]
+    #[serde(serialize_with = "serialize_fork_epoch")]
+    #[serde(deserialize_with = "deserialize_fork_epoch")]
+    pub deneb_fork_epoch: Option<MaybeQuoted<Epoch>>,
+
+    #[serde(default = "default_electra_fork_version")]
+    #[serde(with = "eth2_serde_utils::bytes_4_hex")]
+    electra_fork_version: [u8; 4],
+    #[serde(default = "default_electra_fork_epoch")]
+    #[serde(serialize_with = "serialize_fork_epoch")]
+    #[serde(deserialize_with = "deserialize_fork_epoch")]
+    pub electra_fork_epoch: Option<MaybeQuoted<Epoch>>,
+
     #[serde(with = "eth2_serde_utils::quoted_u64")]
     seconds_per_slot: u64,
     #[serde(with = "eth2_serde_utils::quoted_u64")]
@@ -845,6 +1037,9 @@ pub struct Config {
     min_per_epoch_churn_limit: u64,
     #[serde(with = "eth2_serde_utils::quoted_u64")]
     churn_limit_quotient: u64,
+    #[serde(default = "default_max_per_epoch_activation_churn_limit")]
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    max_per_epoch_activation_churn_limit: u64,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     proposer_score_boost: Option<MaybeQuoted<u64>>,
@@ -865,6 +1060,37 @@ fn default_bellatrix_fork_epoch() -> Option<MaybeQuoted<Epoch>> {
     None
 }
 
+fn default_capella_fork_version() -> [u8; 4] {
+    // This value shouldn't be used.
+    [0xff, 0xff, 0xff, 0xff]
+}
+
+fn default_capella_fork_epoch() -> Option<MaybeQuoted<Epoch>> {
+    None
+}
+
+fn default_deneb_fork_version() -> [u8; 4] {
+    // This value shouldn't be used.
+    [0xff, 0xff, 0xff, 0xff]
+}
+
+fn default_deneb_fork_epoch() -> Option<MaybeQuoted<Epoch>> {
+    None
+}
+
+fn default_electra_fork_version() -> [u8; 4] {
+    // This value shouldn't be used.
+    [0xff, 0xff, 0xff, 0xff]
+}
+
+fn default_electra_fork_epoch() -> Option<MaybeQuoted<Epoch>> {
+    None
+}
+
+const fn default_max_per_epoch_activation_churn_limit() -> u64 {
+    8
+}
+
 /// Placeholder value: 2^256-2^10 (115792089237316195423570985008687907853269984665640564039457584007913129638912).
 ///
 /// Taken from https://github.com/ethereum/consensus-specs/blob/d5e4828aecafaf1c57ef67a5f23c4ae7b08c5137/configs/mainnet.yaml#L15-L16
@@ -961,6 +1187,18 @@ impl Config {
             bellatrix_fork_epoch: spec
                 .bellatrix_fork_epoch
                 .map(|epoch| MaybeQuoted { value: epoch }),
+            capella_fork_version: spec.capella_fork_version,
+            capella_fork_epoch: spec
+                .capella_fork_epoch
+                .map(|epoch| MaybeQuoted { value: epoch }),
+            deneb_fork_version: spec.deneb_fork_version,
+            deneb_fork_epoch: spec
+                .deneb_fork_epoch
+                .map(|epoch| MaybeQuoted { value: epoch }),
+            electra_fork_version: spec.electra_fork_version,
+            electra_fork_epoch: spec
+                .electra_fork_epoch
+                .map(|epoch| MaybeQuoted { value: epoch }),
 
             seconds_per_slot: spec.seconds_per_slot,
             seconds_per_eth1_block: spec.seconds_per_eth1_block,
@@ -973,6 +1211,7 @@ impl Config {
             ejection_balance: spec.ejection_balance,
             churn_limit_quotient: spec.churn_limit_quotient,
             min_per_epoch_churn_limit: spec.min_per_epoch_churn_limit,
+            max_per_epoch_activation_churn_limit: spec.max_per_epoch_activation_churn_limit,
 
             proposer_score_boost: spec.proposer_score_boost.map(|value| MaybeQuoted { value }),
 
@@ -1006,6 +1245,12 @@ impl Config {
             altair_fork_epoch,
             bellatrix_fork_epoch,
             bellatrix_fork_version,
+            capella_fork_epoch,
+            capella_fork_version,
+            deneb_fork_epoch,
+            deneb_fork_version,
+            electra_fork_epoch,
+            electra_fork_version,
             seconds_per_slot,
             seconds_per_eth1_block,
             min_validator_withdrawability_delay,
@@ -1016,6 +1261,7 @@ impl Config {
             ejection_balance,
             min_per_epoch_churn_limit,
             churn_limit_quotient,
+            max_per_epoch_activation_churn_limit,
             proposer_score_boost,
             deposit_chain_id,
             deposit_network_id,
@@ -1036,6 +1282,12 @@ impl Config {
             altair_fork_epoch: altair_fork_epoch.map(|q| q.value),
             bellatrix_fork_epoch: bellatrix_fork_epoch.map(|q| q.value),
             bellatrix_fork_version,
+            capella_fork_epoch: capella_fork_epoch.map(|q| q.value),
+            capella_fork_version,
+            deneb_fork_epoch: deneb_fork_epoch.map(|q| q.value),
+            deneb_fork_version,
+            electra_fork_epoch: electra_fork_epoch.map(|q| q.value),
+            electra_fork_version,
             seconds_per_slot,
             seconds_per_eth1_block,
             min_validator_withdrawability_delay,
@@ -1046,6 +1298,7 @@ impl Config {
             ejection_balance,
             min_per_epoch_churn_limit,
             churn_limit_quotient,
+            max_per_epoch_activation_churn_limit,
             proposer_score_boost: proposer_score_boost.map(|q| q.value),
             deposit_chain_id,
             deposit_network_id,
@@ -1078,6 +1331,11 @@ mod tests {
         let _ = ChainSpec::mainnet();
     }
 
+    #[test]
+    fn min_epochs_for_block_requests() {
+        assert_eq!(ChainSpec::mainnet().min_epochs_for_block_requests(), 33024);
+    }
+
     #[allow(clippy::useless_vec)]
     fn test_domain(domain_type: Domain, raw_domain: u32, spec: &ChainSpec) {
         let previous_version = [0, 0, 0, 1];
@@ -1119,6 +1377,31 @@ mod tests {
             &spec,
         );
         test_domain(Domain::SyncCommittee, spec.domain_sync_committee, &spec);
+        test_domain(
+            Domain::BlsToExecutionChange,
+            spec.domain_bls_to_execution_change,
+            &spec,
+        );
+        test_domain(
+            Domain::ApplicationMask(ApplicationDomain::Builder),
+            APPLICATION_DOMAIN_BUILDER,
+            &spec,
+        );
+    }
+
+    #[test]
+    fn builder_domain() {
+        let spec = ChainSpec::mainnet();
+        let domain = spec.get_builder_domain();
+
+        assert_eq!(&domain.as_bytes()[0..4], &[0, 0, 0, 1]);
+        assert_eq!(
+            domain,
+            Hash256::from_slice(
+                &hex::decode("00000001f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a9")
+                    .unwrap()
+            )
+        );
     }
 
     // Test that `fork_name_at_epoch` and `fork_epoch` are consistent.
@@ -1249,6 +1532,10 @@ mod yaml_tests {
         ALTAIR_FORK_EPOCH: 74240
         #BELLATRIX_FORK_VERSION: 0x02000000
         #BELLATRIX_FORK_EPOCH: 18446744073709551614
+        #CAPELLA_FORK_VERSION: 0x03000000
+        #CAPELLA_FORK_EPOCH: 18446744073709551614
+        #DENEB_FORK_VERSION: 0x04000000
+        #DENEB_FORK_EPOCH: 18446744073709551614
         SHARDING_FORK_VERSION: 0x03000000
         SHARDING_FORK_EPOCH: 18446744073709551615
         SECONDS_PER_SLOT: 12
@@ -1261,6 +1548,7 @@ mod yaml_tests {
         EJECTION_BALANCE: 16000000000
         MIN_PER_EPOCH_CHURN_LIMIT: 4
         CHURN_LIMIT_QUOTIENT: 65536
+        #MAX_PER_EPOCH_ACTIVATION_CHURN_LIMIT: 8
         PROPOSER_SCORE_BOOST: 40
         DEPOSIT_CHAIN_ID: 1
         DEPOSIT_NETWORK_ID: 1
@@ -1294,6 +1582,24 @@ mod yaml_tests {
             chain_spec.bellatrix_fork_version,
             default_bellatrix_fork_version()
         );
+
+        assert_eq!(chain_spec.capella_fork_epoch, default_capella_fork_epoch());
+        assert_eq!(
+            chain_spec.capella_fork_version,
+            default_capella_fork_version()
+        );
+
+        assert_eq!(chain_spec.deneb_fork_epoch, default_deneb_fork_epoch());
+        assert_eq!(chain_spec.deneb_fork_version, default_deneb_fork_version());
+        assert_eq!(chain_spec.electra_fork_epoch, default_electra_fork_epoch());
+        assert_eq!(
+            chain_spec.electra_fork_version,
+            default_electra_fork_version()
+        );
+        assert_eq!(
+            chain_spec.max_per_epoch_activation_churn_limit,
+            default_max_per_epoch_activation_churn_limit()
+        );
     }
 
     #[test]
@@ -81,6 +81,15 @@ impl ConfigAndPreset {
                 "epochs_per_random_subnet_subscription",
                 spec.epochs_per_random_subnet_subscription.to_string(),
             ),
+            ("subnets_per_node", spec.subnets_per_node.to_string()),
+            (
+                "epochs_per_subnet_subscription",
+                spec.epochs_per_subnet_subscription.to_string(),
+            ),
+            (
+                "attestation_subnet_extra_bits",
+                spec.attestation_subnet_extra_bits.to_string(),
+            ),
         ];
         for (key, value) in fields {
             self.extra_fields.insert(key.to_uppercase(), value.into());
@@ -10,6 +10,7 @@ pub mod altair {
     pub const WEIGHT_DENOMINATOR: u64 = 64;
     pub const SYNC_COMMITTEE_SUBNET_COUNT: u64 = 4;
     pub const TARGET_AGGREGATORS_PER_SYNC_SUBCOMMITTEE: u64 = 16;
+    pub const MIN_SYNC_COMMITTEE_PARTICIPANTS: u64 = 1;
 
     pub const PARTICIPATION_FLAG_WEIGHTS: [u64; NUM_FLAG_INDICES] = [
         TIMELY_SOURCE_WEIGHT,
@@ -0,0 +1,45 @@
+use crate::test_utils::TestRandom;
+use crate::{DepositData, Hash256, PublicKeyBytes, SignatureBytes};
+
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use test_random_derive::TestRandom;
+use tree_hash_derive::TreeHash;
+
+/// A deposit observed by the execution layer in the deposit contract logs of a payload.
+///
+/// From Electra deposits are passed to the consensus layer by the execution layer, rather than
+/// being voted in via `eth1_data`.
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug, PartialEq, Hash, Clone, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom,
+)]
+pub struct DepositRequest {
+    pub pubkey: PublicKeyBytes,
+    pub withdrawal_credentials: Hash256,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub amount: u64,
+    pub signature: SignatureBytes,
+    /// The index of the deposit in the deposit contract.
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub index: u64,
+}
+
+impl DepositRequest {
+    /// The `DepositData` described by this request, for signature verification.
+    pub fn as_deposit_data(&self) -> DepositData {
+        DepositData {
+            pubkey: self.pubkey,
+            withdrawal_credentials: self.withdrawal_credentials,
+            amount: self.amount,
+            signature: self.signature.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ssz_and_tree_hash_tests!(DepositRequest);
+}
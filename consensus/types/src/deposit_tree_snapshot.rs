@@ -0,0 +1,106 @@
+use crate::test_utils::TestRandom;
+use crate::*;
+use eth2_hashing::{hash32_concat, ZERO_HASHES};
+use int_to_bytes::int_to_bytes32;
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use test_random_derive::TestRandom;
+
+/// The execution block up to which the deposit tree has been finalized.
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Encode, Decode, Deserialize, Serialize, Clone, Debug, PartialEq, TestRandom)]
+pub struct FinalizedExecutionBlock {
+    pub deposit_root: Hash256,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub deposit_count: u64,
+    pub block_hash: Hash256,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub block_height: u64,
+}
+
+impl From<&DepositTreeSnapshot> for FinalizedExecutionBlock {
+    fn from(snapshot: &DepositTreeSnapshot) -> Self {
+        Self {
+            deposit_root: snapshot.deposit_root,
+            deposit_count: snapshot.deposit_count,
+            block_hash: snapshot.execution_block_hash,
+            block_height: snapshot.execution_block_height,
+        }
+    }
+}
+
+/// A snapshot of the finalized portion of the deposit contract tree, as defined in EIP-4881.
+///
+/// The `finalized` hashes are the roots of the finalized subtrees, ordered from left to right.
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Encode, Decode, Deserialize, Serialize, Clone, Debug, PartialEq, TestRandom)]
+pub struct DepositTreeSnapshot {
+    pub finalized: Vec<Hash256>,
+    pub deposit_root: Hash256,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub deposit_count: u64,
+    pub execution_block_hash: Hash256,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub execution_block_height: u64,
+}
+
+impl Default for DepositTreeSnapshot {
+    fn default() -> Self {
+        let mut result = Self {
+            finalized: vec![],
+            deposit_root: Hash256::default(),
+            deposit_count: 0,
+            execution_block_hash: Hash256::zero(),
+            execution_block_height: 0,
+        };
+        // properly set the empty deposit root
+        result.deposit_root = result.calculate_root().unwrap_or_default();
+        result
+    }
+}
+
+impl DepositTreeSnapshot {
+    /// Calculate the deposit root from the finalized hashes and the deposit count.
+    ///
+    /// Returns `None` if the number of finalized hashes is inconsistent with the deposit count.
+    pub fn calculate_root(&self) -> Option<Hash256> {
+        let mut size = self.deposit_count;
+        let mut index = self.finalized.len();
+        let mut deposit_root = [0; 32];
+        for zero_hash in ZERO_HASHES.iter().take(DEPOSIT_TREE_DEPTH) {
+            deposit_root = if size & 1 == 1 {
+                index = index.checked_sub(1)?;
+                hash32_concat(self.finalized.get(index)?.as_bytes(), &deposit_root)
+            } else {
+                hash32_concat(&deposit_root, zero_hash)
+            };
+            size /= 2;
+        }
+        // All finalized hashes must have been consumed.
+        if index != 0 {
+            return None;
+        }
+        // add mix-in-length
+        deposit_root = hash32_concat(&deposit_root, &int_to_bytes32(self.deposit_count));
+
+        Some(Hash256::from_slice(&deposit_root))
+    }
+
+    /// Returns `true` if the `deposit_root` is consistent with the finalized hashes and count.
+    pub fn is_valid(&self) -> bool {
+        self.calculate_root()
+            .map_or(false, |calculated| calculated == self.deposit_root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ssz_tests!(DepositTreeSnapshot);
+
+    #[test]
+    fn default_snapshot_is_valid() {
+        assert!(DepositTreeSnapshot::default().is_valid());
+    }
+}
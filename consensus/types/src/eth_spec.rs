@@ -3,8 +3,8 @@ use crate::*;
 use safe_arith::SafeArith;
 use serde_derive::{Deserialize, Serialize};
 use ssz_types::typenum::{
-    bit::B0, UInt, Unsigned, U0, U1024, U1048576, U1073741824, U1099511627776, U128, U16,
-    U16777216, U2, U2048, U256, U32, U4, U4096, U512, U625, U64, U65536, U8, U8192,
+    bit::B0, UInt, Unsigned, U0, U1024, U1048576, U1073741824, U1099511627776, U128, U131072, U16,
+    U16777216, U17, U2, U2048, U256, U32, U4, U4096, U512, U6, U625, U64, U65536, U8, U8192, U9,
 };
 use std::fmt::{self, Debug};
 use std::str::FromStr;
@@ -95,6 +95,25 @@ pub trait EthSpec: 'static + Default + Sync + Send + Clone + Debug + PartialEq +
     type GasLimitDenominator: Unsigned + Clone + Sync + Send + Debug + PartialEq;
     type MinGasLimit: Unsigned + Clone + Sync + Send + Debug + PartialEq;
     type MaxExtraDataBytes: Unsigned + Clone + Sync + Send + Debug + PartialEq;
+    /*
+     * New in Capella
+     */
+    type MaxBlsToExecutionChanges: Unsigned + Clone + Sync + Send + Debug + PartialEq;
+    type MaxWithdrawalsPerPayload: Unsigned + Clone + Sync + Send + Debug + PartialEq;
+    /*
+     * New in Deneb
+     */
+    type MaxBlobCommitmentsPerBlock: Unsigned + Clone + Sync + Send + Debug + PartialEq;
+    type MaxBlobsPerBlock: Unsigned + Clone + Sync + Send + Debug + PartialEq;
+    type FieldElementsPerBlob: Unsigned + Clone + Sync + Send + Debug + PartialEq;
+    type BytesPerFieldElement: Unsigned + Clone + Sync + Send + Debug + PartialEq;
+    type KzgCommitmentInclusionProofDepth: Unsigned + Clone + Sync + Send + Debug + PartialEq;
+    /*
+     * New in Electra
+     */
+    type MaxDepositRequestsPerPayload: Unsigned + Clone + Sync + Send + Debug + PartialEq;
+    type MaxWithdrawalRequestsPerPayload: Unsigned + Clone + Sync + Send + Debug + PartialEq;
+    type MaxConsolidationRequestsPerPayload: Unsigned + Clone + Sync + Send + Debug + PartialEq;
     /*
      * Derived values (set these CAREFULLY)
      */
@@ -112,6 +131,10 @@ pub trait EthSpec: 'static + Default + Sync + Send + Clone + Debug + PartialEq +
     ///
     /// Must be set to `SyncCommitteeSize / SyncCommitteeSubnetCount`.
     type SyncSubcommitteeSize: Unsigned + Clone + Sync + Send + Debug + PartialEq;
+    /// The size of a `Blob` in bytes.
+    ///
+    /// Must be set to `FieldElementsPerBlob * BytesPerFieldElement`.
+    type BytesPerBlob: Unsigned + Clone + Sync + Send + Debug + PartialEq;
 
     fn default_spec() -> ChainSpec;
 
@@ -222,6 +245,51 @@ pub trait EthSpec: 'static + Default + Sync + Send + Clone + Debug + PartialEq +
     fn bytes_per_logs_bloom() -> usize {
         Self::BytesPerLogsBloom::to_usize()
     }
+
+    /// Returns the `MAX_BLS_TO_EXECUTION_CHANGES` constant for this specification.
+    fn max_bls_to_execution_changes() -> usize {
+        Self::MaxBlsToExecutionChanges::to_usize()
+    }
+
+    /// Returns the `MAX_WITHDRAWALS_PER_PAYLOAD` constant for this specification.
+    fn max_withdrawals_per_payload() -> usize {
+        Self::MaxWithdrawalsPerPayload::to_usize()
+    }
+
+    /// Returns the `MAX_BLOB_COMMITMENTS_PER_BLOCK` constant for this specification.
+    fn max_blob_commitments_per_block() -> usize {
+        Self::MaxBlobCommitmentsPerBlock::to_usize()
+    }
+
+    /// Returns the `MAX_BLOBS_PER_BLOCK` constant for this specification.
+    fn max_blobs_per_block() -> usize {
+        Self::MaxBlobsPerBlock::to_usize()
+    }
+
+    /// Returns the `BYTES_PER_BLOB` constant for this specification.
+    fn bytes_per_blob() -> usize {
+        Self::BytesPerBlob::to_usize()
+    }
+
+    /// Returns the `KZG_COMMITMENT_INCLUSION_PROOF_DEPTH` constant for this specification.
+    fn kzg_commitment_inclusion_proof_depth() -> usize {
+        Self::KzgCommitmentInclusionProofDepth::to_usize()
+    }
+
+    /// Returns the `MAX_DEPOSIT_REQUESTS_PER_PAYLOAD` constant for this specification.
+    fn max_deposit_requests_per_payload() -> usize {
+        Self::MaxDepositRequestsPerPayload::to_usize()
+    }
+
+    /// Returns the `MAX_WITHDRAWAL_REQUESTS_PER_PAYLOAD` constant for this specification.
+    fn max_withdrawal_requests_per_payload() -> usize {
+        Self::MaxWithdrawalRequestsPerPayload::to_usize()
+    }
+
+    /// Returns the `MAX_CONSOLIDATION_REQUESTS_PER_PAYLOAD` constant for this specification.
+    fn max_consolidation_requests_per_payload() -> usize {
+        Self::MaxConsolidationRequestsPerPayload::to_usize()
+    }
 }
 
 /// Macro to inherit some type values from another EthSpec.
@@ -262,9 +330,20 @@ impl EthSpec for MainnetEthSpec {
     type GasLimitDenominator = U1024;
     type MinGasLimit = U5000;
     type MaxExtraDataBytes = U32;
+    type MaxBlsToExecutionChanges = U16;
+    type MaxWithdrawalsPerPayload = U16;
+    type MaxBlobCommitmentsPerBlock = U4096;
+    type MaxBlobsPerBlock = U6;
+    type FieldElementsPerBlob = U4096;
+    type BytesPerFieldElement = U32;
+    type KzgCommitmentInclusionProofDepth = U17;
+    type MaxDepositRequestsPerPayload = U8192;
+    type MaxWithdrawalRequestsPerPayload = U16;
+    type MaxConsolidationRequestsPerPayload = U2;
     type SyncSubcommitteeSize = U128; // 512 committee size / 4 sync committee subnet count
     type MaxPendingAttestations = U4096; // 128 max attestations * 32 slots per epoch
     type SlotsPerEth1VotingPeriod = U2048; // 64 epochs * 32 slots per epoch
+    type BytesPerBlob = U131072; // 4096 field elements * 32 bytes per field element
 
     fn default_spec() -> ChainSpec {
         ChainSpec::mainnet()
@@ -290,6 +369,11 @@ impl EthSpec for MinimalEthSpec {
     type SyncSubcommitteeSize = U8; // 32 committee size / 4 sync committee subnet count
     type MaxPendingAttestations = U1024; // 128 max attestations * 8 slots per epoch
     type SlotsPerEth1VotingPeriod = U32; // 4 epochs * 8 slots per epoch
+    type MaxWithdrawalsPerPayload = U4;
+    type MaxBlobCommitmentsPerBlock = U16;
+    type KzgCommitmentInclusionProofDepth = U9;
+    type MaxDepositRequestsPerPayload = U4;
+    type MaxWithdrawalRequestsPerPayload = U2;
 
     params_from_eth_spec!(MainnetEthSpec {
         JustificationBitsLength,
@@ -309,7 +393,13 @@ impl EthSpec for MinimalEthSpec {
         BytesPerLogsBloom,
         GasLimitDenominator,
         MinGasLimit,
-        MaxExtraDataBytes
+        MaxExtraDataBytes,
+        MaxBlsToExecutionChanges,
+        MaxBlobsPerBlock,
+        FieldElementsPerBlob,
+        BytesPerFieldElement,
+        BytesPerBlob,
+        MaxConsolidationRequestsPerPayload
     });
 
     fn default_spec() -> ChainSpec {
@@ -351,9 +441,20 @@ impl EthSpec for GnosisEthSpec {
     type GasLimitDenominator = U1024;
     type MinGasLimit = U5000;
     type MaxExtraDataBytes = U32;
+    type MaxBlsToExecutionChanges = U16;
+    type MaxWithdrawalsPerPayload = U16;
+    type MaxBlobCommitmentsPerBlock = U4096;
+    type MaxBlobsPerBlock = U6;
+    type FieldElementsPerBlob = U4096;
+    type BytesPerFieldElement = U32;
+    type KzgCommitmentInclusionProofDepth = U17;
+    type MaxDepositRequestsPerPayload = U8192;
+    type MaxWithdrawalRequestsPerPayload = U16;
+    type MaxConsolidationRequestsPerPayload = U2;
     type SyncSubcommitteeSize = U128; // 512 committee size / 4 sync committee subnet count
     type MaxPendingAttestations = U2048; // 128 max attestations * 16 slots per epoch
     type SlotsPerEth1VotingPeriod = U1024; // 64 epochs * 16 slots per epoch
+    type BytesPerBlob = U131072; // 4096 field elements * 32 bytes per field element
 
     fn default_spec() -> ChainSpec {
         ChainSpec::gnosis()
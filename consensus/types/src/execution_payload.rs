@@ -1,8 +1,9 @@
 use crate::{test_utils::TestRandom, *};
 use derivative::Derivative;
 use serde_derive::{Deserialize, Serialize};
-use ssz::Encode;
+use ssz::{Decode, DecodeError, Encode};
 use ssz_derive::{Decode, Encode};
+use superstruct::superstruct;
 use test_random_derive::TestRandom;
 use tree_hash_derive::TreeHash;
 
@@ -12,50 +13,146 @@ pub type Transactions<T> = VariableList<
     <T as EthSpec>::MaxTransactionsPerPayload,
 >;
 
-#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
-#[derive(
-    Default, Debug, Clone, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom, Derivative,
+pub type Withdrawals<T> = VariableList<Withdrawal, <T as EthSpec>::MaxWithdrawalsPerPayload>;
+
+/// An execution payload, as included in a `BeaconBlockBody`.
+///
+/// This *superstruct* abstracts over the hard-fork.
+#[superstruct(
+    variants(Merge, Capella, Deneb),
+    variant_attributes(
+        derive(
+            Default,
+            Debug,
+            Clone,
+            Serialize,
+            Deserialize,
+            Encode,
+            Decode,
+            TreeHash,
+            TestRandom,
+            Derivative,
+        ),
+        derivative(PartialEq, Hash(bound = "T: EthSpec")),
+        serde(bound = "T: EthSpec", deny_unknown_fields),
+        cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))
+    ),
+    cast_error(ty = "Error", expr = "Error::IncorrectStateVariant"),
+    partial_getter_error(ty = "Error", expr = "Error::IncorrectStateVariant")
 )]
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, TreeHash, Derivative)]
 #[derivative(PartialEq, Hash(bound = "T: EthSpec"))]
+#[serde(untagged)]
 #[serde(bound = "T: EthSpec")]
+#[ssz(enum_behaviour = "transparent")]
+#[tree_hash(enum_behaviour = "transparent")]
 pub struct ExecutionPayload<T: EthSpec> {
+    #[superstruct(getter(copy))]
     pub parent_hash: ExecutionBlockHash,
+    #[superstruct(getter(copy))]
     pub fee_recipient: Address,
+    #[superstruct(getter(copy))]
     pub state_root: Hash256,
+    #[superstruct(getter(copy))]
     pub receipts_root: Hash256,
     #[serde(with = "ssz_types::serde_utils::hex_fixed_vec")]
     pub logs_bloom: FixedVector<u8, T::BytesPerLogsBloom>,
+    #[superstruct(getter(copy))]
     pub prev_randao: Hash256,
     #[serde(with = "eth2_serde_utils::quoted_u64")]
+    #[superstruct(getter(copy))]
     pub block_number: u64,
     #[serde(with = "eth2_serde_utils::quoted_u64")]
+    #[superstruct(getter(copy))]
     pub gas_limit: u64,
     #[serde(with = "eth2_serde_utils::quoted_u64")]
+    #[superstruct(getter(copy))]
     pub gas_used: u64,
     #[serde(with = "eth2_serde_utils::quoted_u64")]
+    #[superstruct(getter(copy))]
     pub timestamp: u64,
     #[serde(with = "ssz_types::serde_utils::hex_var_list")]
     pub extra_data: VariableList<u8, T::MaxExtraDataBytes>,
     #[serde(with = "eth2_serde_utils::quoted_u256")]
+    #[superstruct(getter(copy))]
     pub base_fee_per_gas: Uint256,
+    #[superstruct(getter(copy))]
     pub block_hash: ExecutionBlockHash,
     #[serde(with = "ssz_types::serde_utils::list_of_hex_var_list")]
     pub transactions: Transactions<T>,
+    #[superstruct(only(Capella, Deneb))]
+    pub withdrawals: Withdrawals<T>,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    #[superstruct(only(Deneb), partial_getter(copy))]
+    pub blob_gas_used: u64,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    #[superstruct(only(Deneb), partial_getter(copy))]
+    pub excess_blob_gas: u64,
 }
 
 impl<T: EthSpec> ExecutionPayload<T> {
-    pub fn empty() -> Self {
-        Self::default()
+    /// Returns an empty payload for the given `fork_name`, or `None` if the fork has no payloads.
+    pub fn empty_for_fork(fork_name: ForkName) -> Option<Self> {
+        match fork_name {
+            ForkName::Base | ForkName::Altair => None,
+            ForkName::Merge => Some(ExecutionPayload::Merge(<_>::default())),
+            ForkName::Capella => Some(ExecutionPayload::Capella(<_>::default())),
+            ForkName::Deneb => Some(ExecutionPayload::Deneb(<_>::default())),
+        }
+    }
+
+    pub fn fork_name(&self) -> ForkName {
+        match self {
+            ExecutionPayload::Merge(_) => ForkName::Merge,
+            ExecutionPayload::Capella(_) => ForkName::Capella,
+            ExecutionPayload::Deneb(_) => ForkName::Deneb,
+        }
     }
 
     #[allow(clippy::integer_arithmetic)]
     /// Returns the maximum size of an execution payload.
     pub fn max_execution_payload_size() -> usize {
         // Fixed part
-        Self::empty().as_ssz_bytes().len()
+        ExecutionPayloadDeneb::<T>::default().as_ssz_bytes().len()
             // Max size of variable length `extra_data` field
             + (T::max_extra_data_bytes() * <u8 as Encode>::ssz_fixed_len())
             // Max size of variable length `transactions` field
             + (T::max_transactions_per_payload() * (ssz::BYTES_PER_LENGTH_OFFSET + T::max_bytes_per_transaction()))
+            // Max size of variable length `withdrawals` field
+            + (T::max_withdrawals_per_payload() * <Withdrawal as Encode>::ssz_fixed_len())
+    }
+}
+
+/// The default payload is the pre-Capella one, which is what `is_merge_transition_block` compares
+/// against.
+impl<T: EthSpec> Default for ExecutionPayload<T> {
+    fn default() -> Self {
+        ExecutionPayload::Merge(<_>::default())
+    }
+}
+
+/// Decode a payload without knowing its fork.
+///
+/// The variants are distinguished by the offset of their first variable-length field, which is
+/// required to equal the length of the fixed part. Each fork is tried from newest to oldest.
+impl<T: EthSpec> Decode for ExecutionPayload<T> {
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        ExecutionPayloadDeneb::from_ssz_bytes(bytes)
+            .map(ExecutionPayload::Deneb)
+            .or_else(|_| {
+                ExecutionPayloadCapella::from_ssz_bytes(bytes).map(ExecutionPayload::Capella)
+            })
+            .or_else(|_| ExecutionPayloadMerge::from_ssz_bytes(bytes).map(ExecutionPayload::Merge))
+    }
+}
+
+impl<T: EthSpec> TestRandom for ExecutionPayload<T> {
+    fn random_for_test(rng: &mut impl rand::RngCore) -> Self {
+        ExecutionPayload::Merge(ExecutionPayloadMerge::random_for_test(rng))
     }
 }
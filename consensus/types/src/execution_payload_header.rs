@@ -1,49 +1,219 @@
 use crate::{test_utils::TestRandom, *};
 use derivative::Derivative;
 use serde_derive::{Deserialize, Serialize};
+use ssz::{Decode, DecodeError};
 use ssz_derive::{Decode, Encode};
+use superstruct::superstruct;
 use test_random_derive::TestRandom;
 use tree_hash::TreeHash;
 use tree_hash_derive::TreeHash;
 
-#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
-#[derive(
-    Default, Debug, Clone, Serialize, Deserialize, Derivative, Encode, Decode, TreeHash, TestRandom,
+/// The header of an `ExecutionPayload`, with its variable-length lists replaced by their roots.
+///
+/// This *superstruct* abstracts over the hard-fork.
+#[superstruct(
+    variants(Merge, Capella, Deneb),
+    variant_attributes(
+        derive(
+            Default,
+            Debug,
+            Clone,
+            Serialize,
+            Deserialize,
+            Encode,
+            Decode,
+            TreeHash,
+            TestRandom,
+            Derivative,
+        ),
+        derivative(PartialEq, Hash(bound = "T: EthSpec")),
+        serde(bound = "T: EthSpec", deny_unknown_fields),
+        cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))
+    ),
+    cast_error(ty = "Error", expr = "Error::IncorrectStateVariant"),
+    partial_getter_error(ty = "Error", expr = "Error::IncorrectStateVariant")
 )]
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, TreeHash, Derivative)]
 #[derivative(PartialEq, Hash(bound = "T: EthSpec"))]
+#[serde(untagged)]
+#[serde(bound = "T: EthSpec")]
+#[ssz(enum_behaviour = "transparent")]
+#[tree_hash(enum_behaviour = "transparent")]
 pub struct ExecutionPayloadHeader<T: EthSpec> {
+    #[superstruct(getter(copy))]
     pub parent_hash: ExecutionBlockHash,
+    #[superstruct(getter(copy))]
     pub fee_recipient: Address,
+    #[superstruct(getter(copy))]
     pub state_root: Hash256,
+    #[superstruct(getter(copy))]
     pub receipts_root: Hash256,
     #[serde(with = "ssz_types::serde_utils::hex_fixed_vec")]
     pub logs_bloom: FixedVector<u8, T::BytesPerLogsBloom>,
+    #[superstruct(getter(copy))]
     pub prev_randao: Hash256,
     #[serde(with = "eth2_serde_utils::quoted_u64")]
+    #[superstruct(getter(copy))]
     pub block_number: u64,
     #[serde(with = "eth2_serde_utils::quoted_u64")]
+    #[superstruct(getter(copy))]
     pub gas_limit: u64,
     #[serde(with = "eth2_serde_utils::quoted_u64")]
+    #[superstruct(getter(copy))]
     pub gas_used: u64,
     #[serde(with = "eth2_serde_utils::quoted_u64")]
+    #[superstruct(getter(copy))]
     pub timestamp: u64,
     #[serde(with = "ssz_types::serde_utils::hex_var_list")]
     pub extra_data: VariableList<u8, T::MaxExtraDataBytes>,
     #[serde(with = "eth2_serde_utils::quoted_u256")]
+    #[superstruct(getter(copy))]
     pub base_fee_per_gas: Uint256,
+    #[superstruct(getter(copy))]
     pub block_hash: ExecutionBlockHash,
+    #[superstruct(getter(copy))]
     pub transactions_root: Hash256,
+    #[superstruct(only(Capella, Deneb), partial_getter(copy))]
+    pub withdrawals_root: Hash256,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    #[superstruct(only(Deneb), partial_getter(copy))]
+    pub blob_gas_used: u64,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    #[superstruct(only(Deneb), partial_getter(copy))]
+    pub excess_blob_gas: u64,
 }
 
 impl<T: EthSpec> ExecutionPayloadHeader<T> {
-    pub fn empty() -> Self {
-        Self::default()
+    /// Returns an empty header for the given `fork_name`, or `None` if the fork has no payloads.
+    pub fn empty_for_fork(fork_name: ForkName) -> Option<Self> {
+        match fork_name {
+            ForkName::Base | ForkName::Altair => None,
+            ForkName::Merge => Some(ExecutionPayloadHeader::Merge(<_>::default())),
+            ForkName::Capella => Some(ExecutionPayloadHeader::Capella(<_>::default())),
+            ForkName::Deneb => Some(ExecutionPayloadHeader::Deneb(<_>::default())),
+        }
+    }
+
+    pub fn fork_name(&self) -> ForkName {
+        match self {
+            ExecutionPayloadHeader::Merge(_) => ForkName::Merge,
+            ExecutionPayloadHeader::Capella(_) => ForkName::Capella,
+            ExecutionPayloadHeader::Deneb(_) => ForkName::Deneb,
+        }
     }
 }
 
-impl<'a, T: EthSpec> From<&'a ExecutionPayload<T>> for ExecutionPayloadHeader<T> {
-    fn from(payload: &'a ExecutionPayload<T>) -> Self {
-        ExecutionPayloadHeader {
+impl<'a, T: EthSpec> ExecutionPayloadHeaderRef<'a, T> {
+    pub fn tree_hash_root(self) -> Hash256 {
+        match self {
+            ExecutionPayloadHeaderRef::Merge(header) => header.tree_hash_root(),
+            ExecutionPayloadHeaderRef::Capella(header) => header.tree_hash_root(),
+            ExecutionPayloadHeaderRef::Deneb(header) => header.tree_hash_root(),
+        }
+    }
+
+    /// Returns `true` if this is the default header of its fork, i.e. the merge has not happened.
+    pub fn is_default(self) -> bool {
+        match self {
+            ExecutionPayloadHeaderRef::Merge(header) => {
+                *header == ExecutionPayloadHeaderMerge::default()
+            }
+            ExecutionPayloadHeaderRef::Capella(header) => {
+                *header == ExecutionPayloadHeaderCapella::default()
+            }
+            ExecutionPayloadHeaderRef::Deneb(header) => {
+                *header == ExecutionPayloadHeaderDeneb::default()
+            }
+        }
+    }
+}
+
+impl<'a, T: EthSpec> ExecutionPayloadHeaderRefMut<'a, T> {
+    /// Replace the referenced header with `header`, which must belong to the same fork.
+    pub fn replace(self, header: ExecutionPayloadHeader<T>) -> Result<(), Error> {
+        match (self, header) {
+            (
+                ExecutionPayloadHeaderRefMut::Merge(mut_ref),
+                ExecutionPayloadHeader::Merge(header),
+            ) => {
+                *mut_ref = header;
+            }
+            (
+                ExecutionPayloadHeaderRefMut::Capella(mut_ref),
+                ExecutionPayloadHeader::Capella(header),
+            ) => {
+                *mut_ref = header;
+            }
+            (
+                ExecutionPayloadHeaderRefMut::Deneb(mut_ref),
+                ExecutionPayloadHeader::Deneb(header),
+            ) => {
+                *mut_ref = header;
+            }
+            _ => return Err(Error::IncorrectStateVariant),
+        }
+        Ok(())
+    }
+}
+
+/// The default header is the pre-Capella one, see `ExecutionPayload`.
+impl<T: EthSpec> Default for ExecutionPayloadHeader<T> {
+    fn default() -> Self {
+        ExecutionPayloadHeader::Merge(<_>::default())
+    }
+}
+
+/// Decode a header without knowing its fork, see `ExecutionPayload`.
+impl<T: EthSpec> Decode for ExecutionPayloadHeader<T> {
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        ExecutionPayloadHeaderDeneb::from_ssz_bytes(bytes)
+            .map(ExecutionPayloadHeader::Deneb)
+            .or_else(|_| {
+                ExecutionPayloadHeaderCapella::from_ssz_bytes(bytes)
+                    .map(ExecutionPayloadHeader::Capella)
+            })
+            .or_else(|_| {
+                ExecutionPayloadHeaderMerge::from_ssz_bytes(bytes)
+                    .map(ExecutionPayloadHeader::Merge)
+            })
+    }
+}
+
+impl<T: EthSpec> TestRandom for ExecutionPayloadHeader<T> {
+    fn random_for_test(rng: &mut impl rand::RngCore) -> Self {
+        ExecutionPayloadHeader::Merge(ExecutionPayloadHeaderMerge::random_for_test(rng))
+    }
+}
+
+impl<'a, T: EthSpec> From<&'a ExecutionPayloadMerge<T>> for ExecutionPayloadHeaderMerge<T> {
+    fn from(payload: &'a ExecutionPayloadMerge<T>) -> Self {
+        ExecutionPayloadHeaderMerge {
+            parent_hash: payload.parent_hash,
+            fee_recipient: payload.fee_recipient,
+            state_root: payload.state_root,
+            receipts_root: payload.receipts_root,
+            logs_bloom: payload.logs_bloom.clone(),
+            prev_randao: payload.prev_randao,
+            block_number: payload.block_number,
+            gas_limit: payload.gas_limit,
+            gas_used: payload.gas_used,
+            timestamp: payload.timestamp,
+            extra_data: payload.extra_data.clone(),
+            base_fee_per_gas: payload.base_fee_per_gas,
+            block_hash: payload.block_hash,
+            transactions_root: payload.transactions.tree_hash_root(),
+        }
+    }
+}
+
+impl<'a, T: EthSpec> From<&'a ExecutionPayloadCapella<T>> for ExecutionPayloadHeaderCapella<T> {
+    fn from(payload: &'a ExecutionPayloadCapella<T>) -> Self {
+        ExecutionPayloadHeaderCapella {
             parent_hash: payload.parent_hash,
             fee_recipient: payload.fee_recipient,
             state_root: payload.state_root,
@@ -58,6 +228,89 @@ impl<'a, T: EthSpec> From<&'a ExecutionPayload<T>> for ExecutionPayloadHeader<T>
             base_fee_per_gas: payload.base_fee_per_gas,
             block_hash: payload.block_hash,
             transactions_root: payload.transactions.tree_hash_root(),
+            withdrawals_root: payload.withdrawals.tree_hash_root(),
+        }
+    }
+}
+
+impl<'a, T: EthSpec> From<&'a ExecutionPayloadDeneb<T>> for ExecutionPayloadHeaderDeneb<T> {
+    fn from(payload: &'a ExecutionPayloadDeneb<T>) -> Self {
+        ExecutionPayloadHeaderDeneb {
+            parent_hash: payload.parent_hash,
+            fee_recipient: payload.fee_recipient,
+            state_root: payload.state_root,
+            receipts_root: payload.receipts_root,
+            logs_bloom: payload.logs_bloom.clone(),
+            prev_randao: payload.prev_randao,
+            block_number: payload.block_number,
+            gas_limit: payload.gas_limit,
+            gas_used: payload.gas_used,
+            timestamp: payload.timestamp,
+            extra_data: payload.extra_data.clone(),
+            base_fee_per_gas: payload.base_fee_per_gas,
+            block_hash: payload.block_hash,
+            transactions_root: payload.transactions.tree_hash_root(),
+            withdrawals_root: payload.withdrawals.tree_hash_root(),
+            blob_gas_used: payload.blob_gas_used,
+            excess_blob_gas: payload.excess_blob_gas,
+        }
+    }
+}
+
+impl<'a, T: EthSpec> From<&'a ExecutionPayload<T>> for ExecutionPayloadHeader<T> {
+    fn from(payload: &'a ExecutionPayload<T>) -> Self {
+        match payload {
+            ExecutionPayload::Merge(payload) => ExecutionPayloadHeader::Merge(payload.into()),
+            ExecutionPayload::Capella(payload) => ExecutionPayloadHeader::Capella(payload.into()),
+            ExecutionPayload::Deneb(payload) => ExecutionPayloadHeader::Deneb(payload.into()),
+        }
+    }
+}
+
+/// Upgrade a Bellatrix header to Capella, as part of the Capella fork upgrade.
+impl<T: EthSpec> ExecutionPayloadHeaderMerge<T> {
+    pub fn upgrade_to_capella(&self) -> ExecutionPayloadHeaderCapella<T> {
+        ExecutionPayloadHeaderCapella {
+            parent_hash: self.parent_hash,
+            fee_recipient: self.fee_recipient,
+            state_root: self.state_root,
+            receipts_root: self.receipts_root,
+            logs_bloom: self.logs_bloom.clone(),
+            prev_randao: self.prev_randao,
+            block_number: self.block_number,
+            gas_limit: self.gas_limit,
+            gas_used: self.gas_used,
+            timestamp: self.timestamp,
+            extra_data: self.extra_data.clone(),
+            base_fee_per_gas: self.base_fee_per_gas,
+            block_hash: self.block_hash,
+            transactions_root: self.transactions_root,
+            withdrawals_root: Hash256::zero(),
+        }
+    }
+}
+
+/// Upgrade a Capella header to Deneb, as part of the Deneb fork upgrade.
+impl<T: EthSpec> ExecutionPayloadHeaderCapella<T> {
+    pub fn upgrade_to_deneb(&self) -> ExecutionPayloadHeaderDeneb<T> {
+        ExecutionPayloadHeaderDeneb {
+            parent_hash: self.parent_hash,
+            fee_recipient: self.fee_recipient,
+            state_root: self.state_root,
+            receipts_root: self.receipts_root,
+            logs_bloom: self.logs_bloom.clone(),
+            prev_randao: self.prev_randao,
+            block_number: self.block_number,
+            gas_limit: self.gas_limit,
+            gas_used: self.gas_used,
+            timestamp: self.timestamp,
+            extra_data: self.extra_data.clone(),
+            base_fee_per_gas: self.base_fee_per_gas,
+            block_hash: self.block_hash,
+            transactions_root: self.transactions_root,
+            withdrawals_root: self.withdrawals_root,
+            blob_gas_used: 0,
+            excess_blob_gas: 0,
         }
     }
 }
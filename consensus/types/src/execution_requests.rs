@@ -0,0 +1,94 @@
+use crate::test_utils::TestRandom;
+use crate::{ConsolidationRequest, DepositRequest, EthSpec, VariableList, WithdrawalRequest};
+use derivative::Derivative;
+use serde_derive::{Deserialize, Serialize};
+use ssz::Encode;
+use ssz_derive::{Decode, Encode};
+use test_random_derive::TestRandom;
+use tree_hash_derive::TreeHash;
+
+/// The EIP-7685 type byte of a deposit request.
+pub const DEPOSIT_REQUEST_TYPE: u8 = 0x00;
+/// The EIP-7685 type byte of a withdrawal request.
+pub const WITHDRAWAL_REQUEST_TYPE: u8 = 0x01;
+/// The EIP-7685 type byte of a consolidation request.
+pub const CONSOLIDATION_REQUEST_TYPE: u8 = 0x02;
+
+pub type DepositRequests<T> =
+    VariableList<DepositRequest, <T as EthSpec>::MaxDepositRequestsPerPayload>;
+pub type WithdrawalRequests<T> =
+    VariableList<WithdrawalRequest, <T as EthSpec>::MaxWithdrawalRequestsPerPayload>;
+pub type ConsolidationRequests<T> =
+    VariableList<ConsolidationRequest, <T as EthSpec>::MaxConsolidationRequestsPerPayload>;
+
+/// The requests made to the consensus layer by the execution layer while executing a payload.
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug, Clone, Default, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom, Derivative,
+)]
+#[derivative(PartialEq, Hash(bound = "T: EthSpec"))]
+#[serde(bound = "T: EthSpec")]
+pub struct ExecutionRequests<T: EthSpec> {
+    pub deposits: DepositRequests<T>,
+    pub withdrawals: WithdrawalRequests<T>,
+    pub consolidations: ConsolidationRequests<T>,
+}
+
+impl<T: EthSpec> ExecutionRequests<T> {
+    /// Returns `true` if the execution layer made no requests at all.
+    pub fn is_empty(&self) -> bool {
+        self.deposits.is_empty() && self.withdrawals.is_empty() && self.consolidations.is_empty()
+    }
+
+    /// Encode the requests in the EIP-7685 form expected by the execution layer.
+    ///
+    /// Each non-empty list is SSZ encoded and prefixed with its request type. Empty lists are
+    /// omitted.
+    pub fn get_execution_requests_list(&self) -> Vec<Vec<u8>> {
+        let mut requests_list = vec![];
+        for (request_type, request_bytes) in [
+            (DEPOSIT_REQUEST_TYPE, self.deposits.as_ssz_bytes()),
+            (WITHDRAWAL_REQUEST_TYPE, self.withdrawals.as_ssz_bytes()),
+            (
+                CONSOLIDATION_REQUEST_TYPE,
+                self.consolidations.as_ssz_bytes(),
+            ),
+        ] {
+            if !request_bytes.is_empty() {
+                let mut bytes = Vec::with_capacity(request_bytes.len().saturating_add(1));
+                bytes.push(request_type);
+                bytes.extend_from_slice(&request_bytes);
+                requests_list.push(bytes);
+            }
+        }
+        requests_list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MainnetEthSpec;
+
+    ssz_and_tree_hash_tests!(ExecutionRequests<MainnetEthSpec>);
+
+    #[test]
+    fn empty_lists_are_omitted() {
+        let mut requests = ExecutionRequests::<MainnetEthSpec>::default();
+        assert!(requests.is_empty());
+        assert!(requests.get_execution_requests_list().is_empty());
+
+        let consolidation = ConsolidationRequest {
+            source_address: <_>::default(),
+            source_pubkey: <_>::empty(),
+            target_pubkey: <_>::empty(),
+        };
+        requests.consolidations.push(consolidation.clone()).unwrap();
+        assert!(!requests.is_empty());
+
+        let requests_list = requests.get_execution_requests_list();
+        assert_eq!(requests_list.len(), 1);
+        assert_eq!(requests_list[0][0], CONSOLIDATION_REQUEST_TYPE);
+        assert_eq!(requests_list[0][1..], consolidation.as_ssz_bytes());
+    }
+}
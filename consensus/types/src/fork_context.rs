@@ -1,6 +1,6 @@
 use parking_lot::RwLock;
 
-use crate::{ChainSpec, EthSpec, ForkName, Hash256, Slot};
+use crate::{ChainSpec, Epoch, EthSpec, ForkName, Hash256, Slot};
 use std::collections::HashMap;
 
 /// Provides fork specific info like the current fork name and the fork digests corresponding to every valid fork.
@@ -9,6 +9,8 @@ pub struct ForkContext {
     current_fork: RwLock<ForkName>,
     fork_to_digest: HashMap<ForkName, [u8; 4]>,
     digest_to_fork: HashMap<[u8; 4], ForkName>,
+    /// The activation epoch of every enabled fork, in ascending order.
+    fork_epochs: Vec<(Epoch, ForkName)>,
 }
 
 impl ForkContext {
@@ -47,6 +49,30 @@ impl ForkContext {
             ));
         }
 
+        // Only add Capella to list of forks if it's enabled
+        // Note: `capella_fork_epoch == None` implies capella hasn't been activated yet on the config.
+        if spec.capella_fork_epoch.is_some() {
+            fork_to_digest.push((
+                ForkName::Capella,
+                ChainSpec::compute_fork_digest(spec.capella_fork_version, genesis_validators_root),
+            ));
+        }
+
+        // Only add Deneb to list of forks if it's enabled
+        // Note: `deneb_fork_epoch == None` implies deneb hasn't been activated yet on the config.
+        if spec.deneb_fork_epoch.is_some() {
+            fork_to_digest.push((
+                ForkName::Deneb,
+                ChainSpec::compute_fork_digest(spec.deneb_fork_version, genesis_validators_root),
+            ));
+        }
+
+        let mut fork_epochs: Vec<(Epoch, ForkName)> = fork_to_digest
+            .iter()
+            .filter_map(|(fork_name, _)| Some((spec.fork_epoch(*fork_name)?, *fork_name)))
+            .collect();
+        fork_epochs.sort_by_key(|(fork_epoch, _)| *fork_epoch);
+
         let fork_to_digest: HashMap<ForkName, [u8; 4]> = fork_to_digest.into_iter().collect();
 
         let digest_to_fork = fork_to_digest
@@ -59,6 +85,7 @@ impl ForkContext {
             current_fork: RwLock::new(spec.fork_name_at_slot::<T>(current_slot)),
             fork_to_digest,
             digest_to_fork,
+            fork_epochs,
         }
     }
 
@@ -77,6 +104,19 @@ impl ForkContext {
         *self.current_fork.write() = new_fork;
     }
 
+    /// Returns the fork which is scheduled to be active at `slot`.
+    ///
+    /// Unlike `current_fork`, this does not depend on the wall-clock time and can be used to
+    /// check the fork of objects received from the network.
+    pub fn fork_name_at_slot<T: EthSpec>(&self, slot: Slot) -> ForkName {
+        let epoch = slot.epoch(T::slots_per_epoch());
+        self.fork_epochs
+            .iter()
+            .rev()
+            .find(|(fork_epoch, _)| *fork_epoch <= epoch)
+            .map_or(ForkName::Base, |(_, fork_name)| *fork_name)
+    }
+
     /// Returns the context bytes/fork_digest corresponding to the genesis fork version.
     pub fn genesis_context_bytes(&self) -> [u8; 4] {
         *self
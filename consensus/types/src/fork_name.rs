@@ -11,11 +11,19 @@ pub enum ForkName {
     Base,
     Altair,
     Merge,
+    Capella,
+    Deneb,
 }
 
 impl ForkName {
     pub fn list_all() -> Vec<ForkName> {
-        vec![ForkName::Base, ForkName::Altair, ForkName::Merge]
+        vec![
+            ForkName::Base,
+            ForkName::Altair,
+            ForkName::Merge,
+            ForkName::Capella,
+            ForkName::Deneb,
+        ]
     }
 
     /// Set the activation slots in the given `ChainSpec` so that the fork named by `self`
@@ -26,16 +34,36 @@ impl ForkName {
             ForkName::Base => {
                 spec.altair_fork_epoch = None;
                 spec.bellatrix_fork_epoch = None;
+                spec.capella_fork_epoch = None;
+                spec.deneb_fork_epoch = None;
                 spec
             }
             ForkName::Altair => {
                 spec.altair_fork_epoch = Some(Epoch::new(0));
                 spec.bellatrix_fork_epoch = None;
+                spec.capella_fork_epoch = None;
+                spec.deneb_fork_epoch = None;
                 spec
             }
             ForkName::Merge => {
                 spec.altair_fork_epoch = Some(Epoch::new(0));
                 spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+                spec.capella_fork_epoch = None;
+                spec.deneb_fork_epoch = None;
+                spec
+            }
+            ForkName::Capella => {
+                spec.altair_fork_epoch = Some(Epoch::new(0));
+                spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+                spec.capella_fork_epoch = Some(Epoch::new(0));
+                spec.deneb_fork_epoch = None;
+                spec
+            }
+            ForkName::Deneb => {
+                spec.altair_fork_epoch = Some(Epoch::new(0));
+                spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+                spec.capella_fork_epoch = Some(Epoch::new(0));
+                spec.deneb_fork_epoch = Some(Epoch::new(0));
                 spec
             }
         }
@@ -49,6 +77,8 @@ impl ForkName {
             ForkName::Base => None,
             ForkName::Altair => Some(ForkName::Base),
             ForkName::Merge => Some(ForkName::Altair),
+            ForkName::Capella => Some(ForkName::Merge),
+            ForkName::Deneb => Some(ForkName::Capella),
         }
     }
 
@@ -59,7 +89,9 @@ impl ForkName {
         match self {
             ForkName::Base => Some(ForkName::Altair),
             ForkName::Altair => Some(ForkName::Merge),
-            ForkName::Merge => None,
+            ForkName::Merge => Some(ForkName::Capella),
+            ForkName::Capella => Some(ForkName::Deneb),
+            ForkName::Deneb => None,
         }
     }
 }
@@ -101,6 +133,14 @@ macro_rules! map_fork_name_with {
                 let (value, extra_data) = $body;
                 ($t::Merge(value), extra_data)
             }
+            ForkName::Capella => {
+                let (value, extra_data) = $body;
+                ($t::Capella(value), extra_data)
+            }
+            ForkName::Deneb => {
+                let (value, extra_data) = $body;
+                ($t::Deneb(value), extra_data)
+            }
         }
     };
 }
@@ -113,6 +153,8 @@ impl FromStr for ForkName {
             "phase0" | "base" => ForkName::Base,
             "altair" => ForkName::Altair,
             "bellatrix" | "merge" => ForkName::Merge,
+            "capella" => ForkName::Capella,
+            "deneb" => ForkName::Deneb,
             _ => return Err(()),
         })
     }
@@ -124,6 +166,8 @@ impl Display for ForkName {
             ForkName::Base => "phase0".fmt(f),
             ForkName::Altair => "altair".fmt(f),
             ForkName::Merge => "bellatrix".fmt(f),
+            ForkName::Capella => "capella".fmt(f),
+            ForkName::Deneb => "deneb".fmt(f),
         }
     }
 }
@@ -155,7 +199,7 @@ mod test {
 
     #[test]
     fn previous_and_next_fork_consistent() {
-        assert_eq!(ForkName::Merge.next_fork(), None);
+        assert_eq!(ForkName::Deneb.next_fork(), None);
         assert_eq!(ForkName::Base.previous_fork(), None);
 
         for (prev_fork, fork) in ForkName::list_all().into_iter().tuple_windows() {
@@ -188,4 +232,18 @@ mod test {
         assert_eq!(ForkName::from_str("merge"), Ok(ForkName::Merge));
         assert_eq!(ForkName::Merge.to_string(), "bellatrix");
     }
+
+    #[test]
+    fn fork_name_capella() {
+        assert_eq!(ForkName::from_str("CAPELLA"), Ok(ForkName::Capella));
+        assert_eq!(ForkName::from_str("capella"), Ok(ForkName::Capella));
+        assert_eq!(ForkName::Capella.to_string(), "capella");
+    }
+
+    #[test]
+    fn fork_name_deneb() {
+        assert_eq!(ForkName::from_str("DENEB"), Ok(ForkName::Deneb));
+        assert_eq!(ForkName::from_str("deneb"), Ok(ForkName::Deneb));
+        assert_eq!(ForkName::Deneb.to_string(), "deneb");
+    }
 }
@@ -0,0 +1,211 @@
+use crate::{
+    map_fork_name, map_fork_name_with, BeaconBlock, BeaconState, EthSpec, ExecPayload,
+    ExecutionPayload, ForkName, LightClientBootstrap, LightClientFinalityUpdate,
+    LightClientOptimisticUpdate, SignedBeaconBlock,
+};
+use serde::{de::DeserializeOwned, Deserializer};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::value::Value;
+use std::sync::Arc;
+
+/// A type which may be deserialized from JSON given the name of the fork it belongs to.
+///
+/// Types with a variant per fork (e.g. `BeaconBlock`) deserialize directly into the variant for
+/// `fork_name`, rather than trying each variant in turn. This is both faster and produces useful
+/// errors, since the untagged deserialization of a superstruct only ever reports that no variant
+/// matched.
+pub trait ForkVersionDeserialize: Sized + DeserializeOwned {
+    fn deserialize_by_fork(value: Value, fork_name: ForkName) -> Result<Self, serde_json::Error>;
+}
+
+/// Implement `ForkVersionDeserialize` for a superstruct type with a variant for every fork.
+macro_rules! impl_fork_version_deserialize_superstruct {
+    ($t:tt, [$($generics:tt)*], $ty:ty) => {
+        impl<$($generics)*> ForkVersionDeserialize for $ty {
+            fn deserialize_by_fork(
+                value: Value,
+                fork_name: ForkName,
+            ) -> Result<Self, serde_json::Error> {
+                Ok(map_fork_name!(fork_name, $t, serde_json::from_value(value)?))
+            }
+        }
+    };
+}
+
+/// Implement `ForkVersionDeserialize` for a type which is the same at every fork it exists in.
+macro_rules! impl_fork_version_deserialize_unversioned {
+    ([$($generics:tt)*], $ty:ty) => {
+        impl<$($generics)*> ForkVersionDeserialize for $ty {
+            fn deserialize_by_fork(
+                value: Value,
+                _fork_name: ForkName,
+            ) -> Result<Self, serde_json::Error> {
+                serde_json::from_value(value)
+            }
+        }
+    };
+}
+
+impl_fork_version_deserialize_superstruct!(
+    BeaconBlock,
+    [T: EthSpec, Payload: ExecPayload<T>],
+    BeaconBlock<T, Payload>
+);
+impl_fork_version_deserialize_superstruct!(
+    SignedBeaconBlock,
+    [T: EthSpec, Payload: ExecPayload<T>],
+    SignedBeaconBlock<T, Payload>
+);
+impl_fork_version_deserialize_superstruct!(BeaconState, [T: EthSpec], BeaconState<T>);
+impl_fork_version_deserialize_unversioned!([T: EthSpec], LightClientBootstrap<T>);
+impl_fork_version_deserialize_unversioned!([T: EthSpec], LightClientFinalityUpdate<T>);
+impl_fork_version_deserialize_unversioned!([T: EthSpec], LightClientOptimisticUpdate<T>);
+
+impl<T: EthSpec> ForkVersionDeserialize for ExecutionPayload<T> {
+    fn deserialize_by_fork(value: Value, fork_name: ForkName) -> Result<Self, serde_json::Error> {
+        Ok(match fork_name {
+            ForkName::Merge => ExecutionPayload::Merge(serde_json::from_value(value)?),
+            ForkName::Capella => ExecutionPayload::Capella(serde_json::from_value(value)?),
+            ForkName::Deneb => ExecutionPayload::Deneb(serde_json::from_value(value)?),
+            ForkName::Base | ForkName::Altair => {
+                return Err(serde::de::Error::custom(format!(
+                    "ExecutionPayload failed to deserialize: unsupported fork '{}'",
+                    fork_name
+                )));
+            }
+        })
+    }
+}
+
+impl<F: ForkVersionDeserialize> ForkVersionDeserialize for Arc<F> {
+    fn deserialize_by_fork(value: Value, fork_name: ForkName) -> Result<Self, serde_json::Error> {
+        F::deserialize_by_fork(value, fork_name).map(Arc::new)
+    }
+}
+
+/// A response from the HTTP API which may state the fork its `data` belongs to.
+///
+/// When deserializing, `version` determines how `data` is deserialized (see
+/// `ForkVersionDeserialize`). Responses without a `version` fall back to trying each fork in turn.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct ForkVersionedResponse<T> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<ForkName>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_optimistic: Option<bool>,
+    pub data: T,
+}
+
+impl<F: ForkVersionDeserialize> ForkVersionedResponse<F> {
+    /// Deserialize a response from a JSON `value`.
+    ///
+    /// If the response doesn't state its `version`, `fork_hint` is used in its place. This allows
+    /// the `Eth-Consensus-Version` header to be used for responses to V1 endpoints.
+    pub fn from_json_value(
+        value: Value,
+        fork_hint: Option<ForkName>,
+    ) -> Result<Self, serde_json::Error> {
+        #[derive(Deserialize)]
+        struct Helper {
+            version: Option<ForkName>,
+            execution_optimistic: Option<bool>,
+            data: Value,
+        }
+
+        let helper: Helper = serde_json::from_value(value)?;
+        let data = match helper.version.or(fork_hint) {
+            Some(fork_name) => F::deserialize_by_fork(helper.data, fork_name)?,
+            None => serde_json::from_value(helper.data)?,
+        };
+
+        Ok(ForkVersionedResponse {
+            version: helper.version,
+            execution_optimistic: helper.execution_optimistic,
+            data,
+        })
+    }
+}
+
+impl<'de, F: ForkVersionDeserialize> serde::Deserialize<'de> for ForkVersionedResponse<F> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value: Value = serde::Deserialize::deserialize(deserializer)?;
+        Self::from_json_value(value, None).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+    use crate::{BeaconBlockAltair, MainnetEthSpec};
+
+    type E = MainnetEthSpec;
+
+    fn altair_block() -> BeaconBlock<E> {
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+        BeaconBlock::Altair(BeaconBlockAltair::random_for_test(&mut rng))
+    }
+
+    #[test]
+    fn round_trip_with_version() {
+        let response = ForkVersionedResponse {
+            version: Some(ForkName::Altair),
+            execution_optimistic: Some(false),
+            data: altair_block(),
+        };
+        let json = serde_json::to_string(&response).unwrap();
+        let decoded: ForkVersionedResponse<BeaconBlock<E>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, response);
+    }
+
+    #[test]
+    fn version_selects_variant() {
+        let response = ForkVersionedResponse {
+            version: Some(ForkName::Base),
+            execution_optimistic: None,
+            data: altair_block(),
+        };
+        let json = serde_json::to_string(&response).unwrap();
+
+        // An Altair block can't be deserialized as a Base block.
+        assert!(serde_json::from_str::<ForkVersionedResponse<BeaconBlock<E>>>(&json).is_err());
+    }
+
+    #[test]
+    fn fork_hint_used_without_version() {
+        let response = ForkVersionedResponse {
+            version: None,
+            execution_optimistic: None,
+            data: altair_block(),
+        };
+        let value = serde_json::to_value(&response).unwrap();
+
+        let decoded = ForkVersionedResponse::<BeaconBlock<E>>::from_json_value(
+            value.clone(),
+            Some(ForkName::Altair),
+        )
+        .unwrap();
+        assert_eq!(decoded, response);
+
+        // Without a version or a hint, each fork is tried in turn.
+        let decoded: ForkVersionedResponse<BeaconBlock<E>> = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded, response);
+
+        assert!(ForkVersionedResponse::<BeaconBlock<E>>::from_json_value(
+            serde_json::to_value(&response).unwrap(),
+            Some(ForkName::Base)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn arc_delegates_to_inner() {
+        let block = Arc::new(altair_block());
+        let value = serde_json::to_value(&block).unwrap();
+        let decoded = Arc::<BeaconBlock<E>>::deserialize_by_fork(value, ForkName::Altair).unwrap();
+        assert_eq!(decoded, block);
+    }
+}
@@ -0,0 +1,383 @@
+//! Merkle proofs of the fields of SSZ containers, addressed by generalized index.
+//!
+//! A generalized index identifies a node in the Merkle tree of an object: the root is `1` and the
+//! children of node `i` are `2 * i` and `2 * i + 1`. Proofs generated here may descend through
+//! nested containers (e.g. `BeaconState.latest_execution_payload_header.block_hash`), but not
+//! into lists or vectors.
+//!
+//! See: https://github.com/ethereum/consensus-specs/blob/dev/ssz/merkle-proofs.md
+
+use crate::beacon_state::Error;
+use crate::*;
+use merkle_proof::MerkleTree;
+use safe_arith::SafeArith;
+use tree_hash::TreeHash;
+
+/// The depth of the tree formed by the fields of a container with `num_fields` fields.
+pub fn container_depth(num_fields: usize) -> usize {
+    num_fields.next_power_of_two().trailing_zeros() as usize
+}
+
+/// The generalized index of the field at `field_index` of a container with `num_fields` fields.
+pub fn field_generalized_index(num_fields: usize, field_index: usize) -> Result<usize, Error> {
+    Ok(num_fields.next_power_of_two().safe_add(field_index)?)
+}
+
+/// The depth of the node at `generalized_index`, i.e. the length of its proof.
+pub fn get_generalized_index_length(generalized_index: usize) -> Result<usize, Error> {
+    if generalized_index == 0 {
+        return Err(Error::IndexNotSupported(generalized_index));
+    }
+    Ok(usize::BITS
+        .safe_sub(1)?
+        .safe_sub(generalized_index.leading_zeros())? as usize)
+}
+
+/// The index of the node at `generalized_index` amongst the nodes at the same depth.
+pub fn get_subtree_index(generalized_index: usize) -> Result<usize, Error> {
+    let depth = get_generalized_index_length(generalized_index)?;
+    Ok(generalized_index.safe_sub(1_usize.safe_shl(depth as u32)?)?)
+}
+
+/// Combine the generalized indices of a path through nested objects into a single generalized
+/// index relative to the outermost object.
+pub fn concat_generalized_indices(indices: &[usize]) -> Result<usize, Error> {
+    indices.iter().try_fold(1_usize, |acc, &index| {
+        let depth = get_generalized_index_length(index)?;
+        Ok(acc
+            .safe_shl(depth as u32)?
+            .safe_add(get_subtree_index(index)?)?)
+    })
+}
+
+/// Verify a proof generated by `MerkleProofContainer::compute_merkle_proof` (or
+/// `BeaconState::compute_merkle_proof`) against `root`.
+pub fn verify_generalized_index_proof(
+    leaf: Hash256,
+    proof: &[Hash256],
+    generalized_index: usize,
+    root: Hash256,
+) -> bool {
+    match (
+        get_generalized_index_length(generalized_index),
+        get_subtree_index(generalized_index),
+    ) {
+        (Ok(depth), Ok(index)) if depth == proof.len() => {
+            merkle_proof::verify_merkle_proof(leaf, proof, depth, index, root)
+        }
+        _ => false,
+    }
+}
+
+/// Compute the leaf and proof at `generalized_index` of a container whose fields have the given
+/// `field_roots`.
+///
+/// If the index lies beneath one of the fields, `nested_proof` is called with the field index and
+/// the generalized index relative to that field.
+pub fn compute_container_proof(
+    field_roots: &[Hash256],
+    generalized_index: usize,
+    nested_proof: impl FnOnce(usize, usize) -> Result<(Hash256, Vec<Hash256>), Error>,
+) -> Result<(Hash256, Vec<Hash256>), Error> {
+    let depth = container_depth(field_roots.len());
+    let index_depth = get_generalized_index_length(generalized_index)?;
+    let subtree_depth = index_depth
+        .checked_sub(depth)
+        .ok_or(Error::IndexNotSupported(generalized_index))?;
+
+    let field_index = get_subtree_index(generalized_index.safe_shr(subtree_depth as u32)?)?;
+    if field_index >= field_roots.len() {
+        return Err(Error::IndexNotSupported(generalized_index));
+    }
+
+    let (leaf, mut proof) = if subtree_depth == 0 {
+        let leaf = field_roots
+            .get(field_index)
+            .copied()
+            .ok_or(Error::IndexNotSupported(generalized_index))?;
+        (leaf, vec![])
+    } else {
+        let subtree_mask = 1_usize.safe_shl(subtree_depth as u32)?.safe_sub(1)?;
+        let subtree_generalized_index =
+            (subtree_mask.safe_add(1)?) | (generalized_index & subtree_mask);
+        nested_proof(field_index, subtree_generalized_index)?
+    };
+
+    let tree = MerkleTree::create(field_roots, depth);
+    let (_, field_proof) = tree.generate_proof(field_index, depth)?;
+    proof.extend(field_proof);
+
+    Ok((leaf, proof))
+}
+
+/// An SSZ container which can produce Merkle proofs of its fields.
+pub trait MerkleProofContainer {
+    /// The tree hash roots of each of the fields of this container, in order.
+    fn field_roots(&self) -> Vec<Hash256>;
+
+    /// Compute the leaf and proof at `generalized_index` relative to the field at `field_index`.
+    ///
+    /// Only containers with nested containers need to override this.
+    fn nested_field_proof(
+        &self,
+        _field_index: usize,
+        generalized_index: usize,
+    ) -> Result<(Hash256, Vec<Hash256>), Error> {
+        Err(Error::IndexNotSupported(generalized_index))
+    }
+
+    /// Compute the leaf at `generalized_index` and its proof against the tree hash root of
+    /// `self`.
+    fn compute_merkle_proof(
+        &self,
+        generalized_index: usize,
+    ) -> Result<(Hash256, Vec<Hash256>), Error> {
+        compute_container_proof(
+            &self.field_roots(),
+            generalized_index,
+            |field_index, subtree_generalized_index| {
+                self.nested_field_proof(field_index, subtree_generalized_index)
+                    .map_err(|_| Error::IndexNotSupported(generalized_index))
+            },
+        )
+    }
+}
+
+impl MerkleProofContainer for Checkpoint {
+    fn field_roots(&self) -> Vec<Hash256> {
+        vec![self.epoch.tree_hash_root(), self.root.tree_hash_root()]
+    }
+}
+
+impl MerkleProofContainer for Fork {
+    fn field_roots(&self) -> Vec<Hash256> {
+        vec![
+            self.previous_version.tree_hash_root(),
+            self.current_version.tree_hash_root(),
+            self.epoch.tree_hash_root(),
+        ]
+    }
+}
+
+impl MerkleProofContainer for Eth1Data {
+    fn field_roots(&self) -> Vec<Hash256> {
+        vec![
+            self.deposit_root.tree_hash_root(),
+            self.deposit_count.tree_hash_root(),
+            self.block_hash.tree_hash_root(),
+        ]
+    }
+}
+
+impl MerkleProofContainer for BeaconBlockHeader {
+    fn field_roots(&self) -> Vec<Hash256> {
+        vec![
+            self.slot.tree_hash_root(),
+            self.proposer_index.tree_hash_root(),
+            self.parent_root.tree_hash_root(),
+            self.state_root.tree_hash_root(),
+            self.body_root.tree_hash_root(),
+        ]
+    }
+}
+
+impl<'a, T: EthSpec> MerkleProofContainer for ExecutionPayloadHeaderRef<'a, T> {
+    fn field_roots(&self) -> Vec<Hash256> {
+        let mut roots = vec![
+            self.parent_hash().tree_hash_root(),
+            self.fee_recipient().tree_hash_root(),
+            self.state_root().tree_hash_root(),
+            self.receipts_root().tree_hash_root(),
+            self.logs_bloom().tree_hash_root(),
+            self.prev_randao().tree_hash_root(),
+            self.block_number().tree_hash_root(),
+            self.gas_limit().tree_hash_root(),
+            self.gas_used().tree_hash_root(),
+            self.timestamp().tree_hash_root(),
+            self.extra_data().tree_hash_root(),
+            self.base_fee_per_gas().tree_hash_root(),
+            self.block_hash().tree_hash_root(),
+            self.transactions_root().tree_hash_root(),
+        ];
+        if let Ok(withdrawals_root) = self.withdrawals_root() {
+            roots.push(withdrawals_root.tree_hash_root());
+        }
+        if let Ok(blob_gas_used) = self.blob_gas_used() {
+            roots.push(blob_gas_used.tree_hash_root());
+        }
+        if let Ok(excess_blob_gas) = self.excess_blob_gas() {
+            roots.push(excess_blob_gas.tree_hash_root());
+        }
+        roots
+    }
+}
+
+impl<'a, T: EthSpec, Payload: ExecPayload<T>> MerkleProofContainer
+    for BeaconBlockBodyRef<'a, T, Payload>
+{
+    fn field_roots(&self) -> Vec<Hash256> {
+        let mut roots = vec![
+            self.randao_reveal().tree_hash_root(),
+            self.eth1_data().tree_hash_root(),
+            self.graffiti().tree_hash_root(),
+            self.proposer_slashings().tree_hash_root(),
+            self.attester_slashings().tree_hash_root(),
+            self.attestations().tree_hash_root(),
+            self.deposits().tree_hash_root(),
+            self.voluntary_exits().tree_hash_root(),
+        ];
+        if let Ok(sync_aggregate) = self.sync_aggregate() {
+            roots.push(sync_aggregate.tree_hash_root());
+        }
+        if let Ok(execution_payload) = self.execution_payload() {
+            roots.push(execution_payload.tree_hash_root());
+        }
+        if let Ok(bls_to_execution_changes) = self.bls_to_execution_changes() {
+            roots.push(bls_to_execution_changes.tree_hash_root());
+        }
+        if let Ok(blob_kzg_commitments) = self.blob_kzg_commitments() {
+            roots.push(blob_kzg_commitments.tree_hash_root());
+        }
+        roots
+    }
+
+    fn nested_field_proof(
+        &self,
+        field_index: usize,
+        generalized_index: usize,
+    ) -> Result<(Hash256, Vec<Hash256>), Error> {
+        match field_index {
+            ETH1_DATA_FIELD_INDEX => self.eth1_data().compute_merkle_proof(generalized_index),
+            // The header of a payload shares the tree hash root and field roots of the payload.
+            EXECUTION_PAYLOAD_FIELD_INDEX => self
+                .execution_payload()
+                .map_err(|_| Error::IndexNotSupported(generalized_index))?
+                .to_execution_payload_header()
+                .to_ref()
+                .compute_merkle_proof(generalized_index),
+            _ => Err(Error::IndexNotSupported(generalized_index)),
+        }
+    }
+}
+
+/// The index of `eth1_data` amongst the fields of a `BeaconBlockBody`.
+const ETH1_DATA_FIELD_INDEX: usize = 1;
+/// The index of `execution_payload` amongst the fields of a `BeaconBlockBody`.
+const EXECUTION_PAYLOAD_FIELD_INDEX: usize = 9;
+/// The index of `body` amongst the fields of a `BeaconBlock`.
+const BLOCK_BODY_FIELD_INDEX: usize = 4;
+
+impl<'a, T: EthSpec, Payload: ExecPayload<T>> MerkleProofContainer
+    for BeaconBlockRef<'a, T, Payload>
+{
+    /// The fields of a block have the same roots as those of its header.
+    fn field_roots(&self) -> Vec<Hash256> {
+        self.block_header().field_roots()
+    }
+
+    fn nested_field_proof(
+        &self,
+        field_index: usize,
+        generalized_index: usize,
+    ) -> Result<(Hash256, Vec<Hash256>), Error> {
+        match field_index {
+            BLOCK_BODY_FIELD_INDEX => self.body().compute_merkle_proof(generalized_index),
+            _ => Err(Error::IndexNotSupported(generalized_index)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+
+    type E = MainnetEthSpec;
+
+    #[test]
+    fn generalized_index_helpers() {
+        assert_eq!(get_generalized_index_length(1).unwrap(), 0);
+        assert_eq!(get_generalized_index_length(105).unwrap(), 6);
+        assert_eq!(get_subtree_index(105).unwrap(), 41);
+        assert_eq!(field_generalized_index(28, 20).unwrap(), 52);
+        assert_eq!(concat_generalized_indices(&[52, 3]).unwrap(), 105);
+        assert_eq!(concat_generalized_indices(&[1, 52, 1, 3]).unwrap(), 105);
+        assert!(get_generalized_index_length(0).is_err());
+    }
+
+    #[test]
+    fn checkpoint_root_proof() {
+        let checkpoint = Checkpoint {
+            epoch: Epoch::new(3),
+            root: Hash256::repeat_byte(7),
+        };
+        let (leaf, proof) = checkpoint.compute_merkle_proof(3).unwrap();
+        assert_eq!(leaf, checkpoint.root);
+        assert_eq!(proof, vec![checkpoint.epoch.tree_hash_root()]);
+        assert!(verify_generalized_index_proof(
+            leaf,
+            &proof,
+            3,
+            checkpoint.tree_hash_root()
+        ));
+
+        assert!(checkpoint.compute_merkle_proof(1).is_err());
+        assert!(checkpoint.compute_merkle_proof(4).is_err());
+    }
+
+    #[test]
+    fn block_execution_payload_block_hash_proof() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let block = BeaconBlock::<E>::Merge(BeaconBlockMerge::random_for_test(rng));
+
+        // `BeaconBlock.body.execution_payload.block_hash`.
+        let generalized_index = concat_generalized_indices(&[
+            field_generalized_index(5, 4).unwrap(),
+            field_generalized_index(10, 9).unwrap(),
+            field_generalized_index(14, 12).unwrap(),
+        ])
+        .unwrap();
+
+        let (leaf, proof) = block
+            .to_ref()
+            .compute_merkle_proof(generalized_index)
+            .unwrap();
+        assert_eq!(
+            leaf,
+            block
+                .body()
+                .execution_payload()
+                .unwrap()
+                .block_hash()
+                .tree_hash_root()
+        );
+        assert_eq!(proof.len(), 11);
+        assert!(verify_generalized_index_proof(
+            leaf,
+            &proof,
+            generalized_index,
+            block.tree_hash_root()
+        ));
+        assert!(!verify_generalized_index_proof(
+            leaf,
+            &proof,
+            generalized_index + 1,
+            block.tree_hash_root()
+        ));
+    }
+
+    #[test]
+    fn block_body_field_out_of_range() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let block = BeaconBlock::<E>::Base(BeaconBlockBase::random_for_test(rng));
+
+        // The Base body has only 8 fields, so the index of `sync_aggregate` in later forks lies
+        // beneath `attester_slashings`.
+        let generalized_index =
+            concat_generalized_indices(&[field_generalized_index(5, 4).unwrap(), 24]).unwrap();
+        assert!(matches!(
+            block.to_ref().compute_merkle_proof(generalized_index),
+            Err(Error::IndexNotSupported(_))
+        ));
+    }
+}
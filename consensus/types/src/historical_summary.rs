@@ -0,0 +1,50 @@
+use crate::test_utils::TestRandom;
+use crate::{BeaconState, EthSpec, Hash256};
+
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use test_random_derive::TestRandom;
+use tree_hash::TreeHash;
+use tree_hash_derive::TreeHash;
+
+/// The roots of the `block_roots` and `state_roots` vectors for one historical period.
+///
+/// From Capella this replaces the `HistoricalBatch` root accumulated in `historical_roots`,
+/// so that block roots can be proven against the state without the state roots.
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Clone,
+    Copy,
+    Default,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+    TreeHash,
+    TestRandom,
+)]
+pub struct HistoricalSummary {
+    pub block_summary_root: Hash256,
+    pub state_summary_root: Hash256,
+}
+
+impl HistoricalSummary {
+    /// The summary of the historical period ending at the current slot of `state`.
+    pub fn new<T: EthSpec>(state: &BeaconState<T>) -> Self {
+        Self {
+            block_summary_root: state.block_roots().tree_hash_root(),
+            state_summary_root: state.state_roots().tree_hash_root(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ssz_and_tree_hash_tests!(HistoricalSummary);
+}
@@ -0,0 +1,149 @@
+use crate::test_utils::{RngCore, TestRandom};
+use crate::Hash256;
+use eth2_hashing::hash_fixed;
+use serde_derive::{Deserialize, Serialize};
+use ssz::{Decode, DecodeError, Encode};
+use std::fmt;
+use tree_hash::TreeHash;
+
+pub const BYTES_PER_COMMITMENT: usize = 48;
+
+/// The version byte prepended to the hash of a KZG commitment to form its versioned hash.
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// A versioned hash of a KZG commitment, as referenced by blob transactions on the execution
+/// layer.
+pub type VersionedHash = Hash256;
+
+/// A KZG commitment to a blob, as included in a Deneb `BeaconBlockBody`.
+///
+/// The commitment is treated as opaque bytes here, it is only ever interpreted as a curve point by
+/// a KZG library.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+#[serde(transparent)]
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+pub struct KzgCommitment(
+    #[serde(with = "eth2_serde_utils::bytes_48_hex")] pub [u8; BYTES_PER_COMMITMENT],
+);
+
+impl KzgCommitment {
+    /// Compute the versioned hash of this commitment, i.e. `kzg_commitment_to_versioned_hash`.
+    pub fn calculate_versioned_hash(&self) -> VersionedHash {
+        let mut versioned_hash = hash_fixed(&self.0);
+        versioned_hash[0] = VERSIONED_HASH_VERSION_KZG;
+        VersionedHash::from(versioned_hash)
+    }
+
+    /// The commitment to the empty polynomial, i.e. the compressed point at infinity.
+    pub fn empty_for_testing() -> Self {
+        let mut bytes = [0; BYTES_PER_COMMITMENT];
+        bytes[0] = 0xc0;
+        KzgCommitment(bytes)
+    }
+}
+
+impl fmt::Display for KzgCommitment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", eth2_serde_utils::hex::encode(self.0))
+    }
+}
+
+impl fmt::Debug for KzgCommitment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", eth2_serde_utils::hex::encode(self.0))
+    }
+}
+
+impl From<[u8; BYTES_PER_COMMITMENT]> for KzgCommitment {
+    fn from(bytes: [u8; BYTES_PER_COMMITMENT]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl Encode for KzgCommitment {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        BYTES_PER_COMMITMENT
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        BYTES_PER_COMMITMENT
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.0)
+    }
+}
+
+impl Decode for KzgCommitment {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        BYTES_PER_COMMITMENT
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        if bytes.len() != BYTES_PER_COMMITMENT {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: BYTES_PER_COMMITMENT,
+            });
+        }
+
+        let mut array = [0; BYTES_PER_COMMITMENT];
+        array.copy_from_slice(bytes);
+        Ok(Self(array))
+    }
+}
+
+impl TreeHash for KzgCommitment {
+    fn tree_hash_type() -> tree_hash::TreeHashType {
+        tree_hash::TreeHashType::Vector
+    }
+
+    fn tree_hash_packed_encoding(&self) -> Vec<u8> {
+        unreachable!("Vector should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("Vector should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        let minimum_chunk_count =
+            (BYTES_PER_COMMITMENT + tree_hash::BYTES_PER_CHUNK - 1) / tree_hash::BYTES_PER_CHUNK;
+        tree_hash::merkle_root(&self.0, minimum_chunk_count)
+    }
+}
+
+impl TestRandom for KzgCommitment {
+    fn random_for_test(rng: &mut impl RngCore) -> Self {
+        let mut bytes = [0; BYTES_PER_COMMITMENT];
+        rng.fill_bytes(&mut bytes);
+        Self(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ssz_and_tree_hash_tests!(KzgCommitment);
+
+    #[test]
+    fn versioned_hash_has_kzg_version() {
+        let commitment = KzgCommitment::empty_for_testing();
+        let versioned_hash = commitment.calculate_versioned_hash();
+
+        assert_eq!(versioned_hash.as_bytes()[0], VERSIONED_HASH_VERSION_KZG);
+        assert_eq!(
+            versioned_hash.as_bytes()[1..],
+            hash_fixed(&commitment.0)[1..]
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use crate::test_utils::{RngCore, TestRandom};
+use crate::Hash256;
+use serde_derive::{Deserialize, Serialize};
+use ssz::{Decode, DecodeError, Encode};
+use std::fmt;
+use tree_hash::TreeHash;
+
+pub const BYTES_PER_PROOF: usize = 48;
+
+/// A KZG proof that a blob is consistent with its `KzgCommitment`, as included in a `BlobSidecar`.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+#[serde(transparent)]
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+pub struct KzgProof(#[serde(with = "eth2_serde_utils::bytes_48_hex")] pub [u8; BYTES_PER_PROOF]);
+
+impl fmt::Display for KzgProof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", eth2_serde_utils::hex::encode(self.0))
+    }
+}
+
+impl fmt::Debug for KzgProof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", eth2_serde_utils::hex::encode(self.0))
+    }
+}
+
+impl From<[u8; BYTES_PER_PROOF]> for KzgProof {
+    fn from(bytes: [u8; BYTES_PER_PROOF]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl Encode for KzgProof {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        BYTES_PER_PROOF
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        BYTES_PER_PROOF
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.0)
+    }
+}
+
+impl Decode for KzgProof {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        BYTES_PER_PROOF
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        if bytes.len() != BYTES_PER_PROOF {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: BYTES_PER_PROOF,
+            });
+        }
+
+        let mut array = [0; BYTES_PER_PROOF];
+        array.copy_from_slice(bytes);
+        Ok(Self(array))
+    }
+}
+
+impl TreeHash for KzgProof {
+    fn tree_hash_type() -> tree_hash::TreeHashType {
+        tree_hash::TreeHashType::Vector
+    }
+
+    fn tree_hash_packed_encoding(&self) -> Vec<u8> {
+        unreachable!("Vector should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("Vector should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        let minimum_chunk_count =
+            (BYTES_PER_PROOF + tree_hash::BYTES_PER_CHUNK - 1) / tree_hash::BYTES_PER_CHUNK;
+        tree_hash::merkle_root(&self.0, minimum_chunk_count)
+    }
+}
+
+impl TestRandom for KzgProof {
+    fn random_for_test(rng: &mut impl RngCore) -> Self {
+        let mut bytes = [0; BYTES_PER_PROOF];
+        rng.fill_bytes(&mut bytes);
+        Self(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ssz_and_tree_hash_tests!(KzgProof);
+}
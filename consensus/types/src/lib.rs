@@ -18,6 +18,7 @@ extern crate lazy_static;
 pub mod test_utils;
 
 pub mod aggregate_and_proof;
+pub mod application_domain;
 pub mod attestation;
 pub mod attestation_data;
 pub mod attestation_duty;
@@ -27,26 +28,40 @@ pub mod beacon_block_body;
 pub mod beacon_block_header;
 pub mod beacon_committee;
 pub mod beacon_state;
+pub mod blob_sidecar;
+pub mod bls_to_execution_change;
 pub mod chain_spec;
 pub mod checkpoint;
+pub mod consolidation_request;
 pub mod consts;
 pub mod contribution_and_proof;
 pub mod deposit;
 pub mod deposit_data;
 pub mod deposit_message;
+pub mod deposit_request;
+pub mod deposit_tree_snapshot;
 pub mod enr_fork_id;
 pub mod eth1_data;
 pub mod eth_spec;
 pub mod execution_block_hash;
 pub mod execution_payload;
 pub mod execution_payload_header;
+pub mod execution_requests;
 pub mod fork;
 pub mod fork_data;
 pub mod fork_name;
+pub mod fork_versioned_response;
 pub mod free_attestation;
+pub mod generalized_index;
 pub mod graffiti;
 pub mod historical_batch;
+pub mod historical_summary;
 pub mod indexed_attestation;
+pub mod kzg_commitment;
+pub mod kzg_proof;
+pub mod light_client_bootstrap;
+pub mod light_client_finality_update;
+pub mod light_client_optimistic_update;
 pub mod pending_attestation;
 pub mod proposer_preparation_data;
 pub mod proposer_slashing;
@@ -56,14 +71,19 @@ pub mod shuffling_id;
 pub mod signed_aggregate_and_proof;
 pub mod signed_beacon_block;
 pub mod signed_beacon_block_header;
+pub mod signed_bls_to_execution_change;
 pub mod signed_contribution_and_proof;
 pub mod signed_voluntary_exit;
 pub mod signing_data;
+pub mod signing_domain;
 pub mod sync_committee_subscription;
 pub mod sync_duty;
 pub mod validator;
+pub mod validator_registration_data;
 pub mod validator_subscription;
 pub mod voluntary_exit;
+pub mod withdrawal;
+pub mod withdrawal_request;
 #[macro_use]
 pub mod slot_epoch_macros;
 pub mod config_and_preset;
@@ -86,51 +106,81 @@ mod tree_hash_impls;
 pub mod slot_data;
 #[cfg(feature = "sqlite")]
 pub mod sqlite;
+pub mod ssz_view;
 
 use ethereum_types::{H160, H256};
 
 pub use crate::aggregate_and_proof::AggregateAndProof;
+pub use crate::application_domain::{ApplicationDomain, APPLICATION_DOMAIN_BUILDER};
 pub use crate::attestation::{Attestation, Error as AttestationError};
 pub use crate::attestation_data::AttestationData;
 pub use crate::attestation_duty::AttestationDuty;
 pub use crate::attester_slashing::AttesterSlashing;
 pub use crate::beacon_block::{
-    BeaconBlock, BeaconBlockAltair, BeaconBlockBase, BeaconBlockMerge, BeaconBlockRef,
-    BeaconBlockRefMut,
+    BeaconBlock, BeaconBlockAltair, BeaconBlockBase, BeaconBlockCapella, BeaconBlockDeneb,
+    BeaconBlockMerge, BeaconBlockRef, BeaconBlockRefMut,
 };
 pub use crate::beacon_block_body::{
-    BeaconBlockBody, BeaconBlockBodyAltair, BeaconBlockBodyBase, BeaconBlockBodyMerge,
-    BeaconBlockBodyRef, BeaconBlockBodyRefMut,
+    BeaconBlockBody, BeaconBlockBodyAltair, BeaconBlockBodyBase, BeaconBlockBodyCapella,
+    BeaconBlockBodyDeneb, BeaconBlockBodyMerge, BeaconBlockBodyRef, BeaconBlockBodyRefMut,
+    KzgCommitments,
 };
 pub use crate::beacon_block_header::BeaconBlockHeader;
 pub use crate::beacon_committee::{BeaconCommittee, OwnedBeaconCommittee};
 pub use crate::beacon_state::{BeaconTreeHashCache, Error as BeaconStateError, *};
+pub use crate::blob_sidecar::{Blob, BlobIdentifier, BlobSidecar, BlobSidecarList};
+pub use crate::bls_to_execution_change::BlsToExecutionChange;
 pub use crate::chain_spec::{ChainSpec, Config, Domain};
 pub use crate::checkpoint::Checkpoint;
 pub use crate::config_and_preset::ConfigAndPreset;
+pub use crate::consolidation_request::ConsolidationRequest;
 pub use crate::contribution_and_proof::ContributionAndProof;
 pub use crate::deposit::{Deposit, DEPOSIT_TREE_DEPTH};
 pub use crate::deposit_data::DepositData;
 pub use crate::deposit_message::DepositMessage;
+pub use crate::deposit_request::DepositRequest;
+pub use crate::deposit_tree_snapshot::{DepositTreeSnapshot, FinalizedExecutionBlock};
 pub use crate::enr_fork_id::EnrForkId;
 pub use crate::eth1_data::Eth1Data;
 pub use crate::eth_spec::EthSpecId;
 pub use crate::execution_block_hash::ExecutionBlockHash;
-pub use crate::execution_payload::{ExecutionPayload, Transaction, Transactions};
-pub use crate::execution_payload_header::ExecutionPayloadHeader;
+pub use crate::execution_payload::{
+    ExecutionPayload, ExecutionPayloadCapella, ExecutionPayloadDeneb, ExecutionPayloadMerge,
+    ExecutionPayloadRef, Transaction, Transactions, Withdrawals,
+};
+pub use crate::execution_payload_header::{
+    ExecutionPayloadHeader, ExecutionPayloadHeaderCapella, ExecutionPayloadHeaderDeneb,
+    ExecutionPayloadHeaderMerge, ExecutionPayloadHeaderRef, ExecutionPayloadHeaderRefMut,
+};
+pub use crate::execution_requests::{
+    ConsolidationRequests, DepositRequests, ExecutionRequests, WithdrawalRequests,
+};
 pub use crate::fork::Fork;
 pub use crate::fork_context::ForkContext;
 pub use crate::fork_data::ForkData;
 pub use crate::fork_name::{ForkName, InconsistentFork};
+pub use crate::fork_versioned_response::{ForkVersionDeserialize, ForkVersionedResponse};
 pub use crate::free_attestation::FreeAttestation;
+pub use crate::generalized_index::MerkleProofContainer;
 pub use crate::graffiti::{Graffiti, GRAFFITI_BYTES_LEN};
 pub use crate::historical_batch::HistoricalBatch;
+pub use crate::historical_summary::HistoricalSummary;
 pub use crate::indexed_attestation::IndexedAttestation;
+pub use crate::kzg_commitment::{KzgCommitment, VersionedHash, VERSIONED_HASH_VERSION_KZG};
+pub use crate::kzg_proof::KzgProof;
+pub use crate::light_client_bootstrap::LightClientBootstrap;
+pub use crate::light_client_finality_update::LightClientFinalityUpdate;
+pub use crate::light_client_optimistic_update::LightClientOptimisticUpdate;
 pub use crate::participation_flags::ParticipationFlags;
 pub use crate::participation_list::ParticipationList;
-pub use crate::payload::{BlindedPayload, BlockType, ExecPayload, FullPayload};
+pub use crate::payload::{
+    AbstractExecPayload, BlindedPayload, BlockType, ExecPayload, FullPayload,
+};
 pub use crate::pending_attestation::PendingAttestation;
-pub use crate::preset::{AltairPreset, BasePreset, BellatrixPreset};
+pub use crate::preset::{
+    AltairPreset, BasePreset, BellatrixPreset, CapellaPreset, DenebPreset, ElectraPreset,
+    FullPreset, PresetOverrides,
+};
 pub use crate::proposer_preparation_data::ProposerPreparationData;
 pub use crate::proposer_slashing::ProposerSlashing;
 pub use crate::relative_epoch::{Error as RelativeEpochError, RelativeEpoch};
@@ -138,14 +188,18 @@ pub use crate::selection_proof::SelectionProof;
 pub use crate::shuffling_id::AttestationShufflingId;
 pub use crate::signed_aggregate_and_proof::SignedAggregateAndProof;
 pub use crate::signed_beacon_block::{
-    SignedBeaconBlock, SignedBeaconBlockAltair, SignedBeaconBlockBase, SignedBeaconBlockHash,
-    SignedBeaconBlockMerge, SignedBlindedBeaconBlock,
+    SignedBeaconBlock, SignedBeaconBlockAltair, SignedBeaconBlockBase, SignedBeaconBlockCapella,
+    SignedBeaconBlockDeneb, SignedBeaconBlockHash, SignedBeaconBlockMerge,
+    SignedBlindedBeaconBlock,
 };
 pub use crate::signed_beacon_block_header::SignedBeaconBlockHeader;
+pub use crate::signed_bls_to_execution_change::SignedBlsToExecutionChange;
 pub use crate::signed_contribution_and_proof::SignedContributionAndProof;
 pub use crate::signed_voluntary_exit::SignedVoluntaryExit;
 pub use crate::signing_data::{SignedRoot, SigningData};
-pub use crate::slot_epoch::{Epoch, Slot};
+pub use crate::signing_domain::get_signing_domain;
+pub use crate::slot_epoch::{Epoch, Slot, SyncCommitteePeriodIter};
+pub use crate::ssz_view::{AttestationSszView, SignedBeaconBlockSszView};
 pub use crate::subnet_id::SubnetId;
 pub use crate::sync_aggregate::SyncAggregate;
 pub use crate::sync_aggregator_selection_data::SyncAggregatorSelectionData;
@@ -157,8 +211,13 @@ pub use crate::sync_duty::SyncDuty;
 pub use crate::sync_selection_proof::SyncSelectionProof;
 pub use crate::sync_subnet_id::SyncSubnetId;
 pub use crate::validator::Validator;
+pub use crate::validator_registration_data::{
+    SignedValidatorRegistrationData, ValidatorRegistrationData,
+};
 pub use crate::validator_subscription::ValidatorSubscription;
 pub use crate::voluntary_exit::VoluntaryExit;
+pub use crate::withdrawal::Withdrawal;
+pub use crate::withdrawal_request::WithdrawalRequest;
 
 pub type CommitteeIndex = u64;
 pub type Hash256 = H256;
@@ -170,5 +229,7 @@ pub use bls::{
     AggregatePublicKey, AggregateSignature, Keypair, PublicKey, PublicKeyBytes, SecretKey,
     Signature, SignatureBytes,
 };
-pub use ssz_types::{typenum, typenum::Unsigned, BitList, BitVector, FixedVector, VariableList};
+pub use ssz_types::{
+    typenum, typenum::Unsigned, BitList, BitVector, FixedVector, PersistentList, VariableList,
+};
 pub use superstruct::superstruct;
@@ -0,0 +1,74 @@
+use crate::test_utils::TestRandom;
+use crate::*;
+
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use ssz_types::typenum::U5;
+use ssz_types::FixedVector;
+use std::sync::Arc;
+use test_random_derive::TestRandom;
+use tree_hash::TreeHash;
+use tree_hash_derive::TreeHash;
+
+/// The generalized index of `current_sync_committee` in the `BeaconState` tree.
+pub const CURRENT_SYNC_COMMITTEE_INDEX: usize = 54;
+/// The generalized index of `next_sync_committee` in the `BeaconState` tree.
+pub const NEXT_SYNC_COMMITTEE_INDEX: usize = 55;
+
+/// The length of a Merkle proof of a sync committee against a `BeaconState` root.
+pub type CurrentSyncCommitteeProofLen = U5;
+
+/// The data a light client requires to begin syncing from a trusted block root.
+///
+/// The sync committee of the block's state can be verified against the header's `state_root` using
+/// `current_sync_committee_branch`.
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom)]
+#[serde(bound = "T: EthSpec")]
+pub struct LightClientBootstrap<T: EthSpec> {
+    pub header: BeaconBlockHeader,
+    pub current_sync_committee: Arc<SyncCommittee<T>>,
+    pub current_sync_committee_branch: FixedVector<Hash256, CurrentSyncCommitteeProofLen>,
+}
+
+impl<T: EthSpec> LightClientBootstrap<T> {
+    /// Create the bootstrap for the block that `state` is the post-state of.
+    ///
+    /// Returns an error if `state` predates Altair.
+    pub fn from_beacon_state(state: &mut BeaconState<T>) -> Result<Self, BeaconStateError> {
+        let current_sync_committee = state.current_sync_committee()?.clone();
+        let current_sync_committee_branch = state
+            .compute_merkle_proof(CURRENT_SYNC_COMMITTEE_INDEX)?
+            .into();
+
+        // The `state_root` of the latest block header isn't filled in until the next slot.
+        let mut header = state.latest_block_header().clone();
+        header.state_root = state.update_tree_hash_cache()?;
+
+        Ok(Self {
+            header,
+            current_sync_committee,
+            current_sync_committee_branch,
+        })
+    }
+
+    /// Returns `true` if `current_sync_committee_branch` proves `current_sync_committee` against
+    /// `header.state_root`.
+    pub fn is_valid_branch(&self) -> bool {
+        let depth = self.current_sync_committee_branch.len();
+        merkle_proof::verify_merkle_proof(
+            self.current_sync_committee.tree_hash_root(),
+            &self.current_sync_committee_branch,
+            depth,
+            CURRENT_SYNC_COMMITTEE_INDEX % (1 << depth),
+            self.header.state_root,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ssz_and_tree_hash_tests!(LightClientBootstrap<MainnetEthSpec>);
+}
@@ -0,0 +1,58 @@
+use crate::test_utils::TestRandom;
+use crate::*;
+
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use ssz_types::typenum::U6;
+use ssz_types::FixedVector;
+use test_random_derive::TestRandom;
+use tree_hash::TreeHash;
+use tree_hash_derive::TreeHash;
+
+/// The generalized index of `finalized_checkpoint.root` in the `BeaconState` tree.
+pub const FINALIZED_ROOT_INDEX: usize = 105;
+
+/// The length of a Merkle proof of the finalized root against a `BeaconState` root.
+pub type FinalizedRootProofLen = U6;
+
+/// The finalized header most recently attested to by the sync committee, published on gossip for
+/// light clients.
+///
+/// The finalized header can be verified against the `attested_header`'s `state_root` using
+/// `finality_branch`.
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom)]
+#[serde(bound = "T: EthSpec")]
+pub struct LightClientFinalityUpdate<T: EthSpec> {
+    /// The header attested to by the sync committee.
+    pub attested_header: BeaconBlockHeader,
+    /// The header of the finalized checkpoint of the attested header's state.
+    pub finalized_header: BeaconBlockHeader,
+    pub finality_branch: FixedVector<Hash256, FinalizedRootProofLen>,
+    /// The sync committee's signature over the attested header.
+    pub sync_aggregate: SyncAggregate<T>,
+    /// The slot of the block containing `sync_aggregate`.
+    pub signature_slot: Slot,
+}
+
+impl<T: EthSpec> LightClientFinalityUpdate<T> {
+    /// Returns `true` if `finality_branch` proves the root of `finalized_header` against
+    /// `attested_header.state_root`.
+    pub fn is_valid_branch(&self) -> bool {
+        let depth = self.finality_branch.len();
+        merkle_proof::verify_merkle_proof(
+            self.finalized_header.canonical_root(),
+            &self.finality_branch,
+            depth,
+            FINALIZED_ROOT_INDEX % (1 << depth),
+            self.attested_header.state_root,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ssz_and_tree_hash_tests!(LightClientFinalityUpdate<MainnetEthSpec>);
+}
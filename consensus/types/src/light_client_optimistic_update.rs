@@ -0,0 +1,38 @@
+use crate::test_utils::TestRandom;
+use crate::*;
+
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use test_random_derive::TestRandom;
+use tree_hash_derive::TreeHash;
+
+/// The header most recently attested to by the sync committee, published on gossip for light
+/// clients following the head of the chain.
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom)]
+#[serde(bound = "T: EthSpec")]
+pub struct LightClientOptimisticUpdate<T: EthSpec> {
+    /// The header attested to by the sync committee.
+    pub attested_header: BeaconBlockHeader,
+    /// The sync committee's signature over the attested header.
+    pub sync_aggregate: SyncAggregate<T>,
+    /// The slot of the block containing `sync_aggregate`.
+    pub signature_slot: Slot,
+}
+
+impl<T: EthSpec> From<&LightClientFinalityUpdate<T>> for LightClientOptimisticUpdate<T> {
+    fn from(update: &LightClientFinalityUpdate<T>) -> Self {
+        Self {
+            attested_header: update.attested_header.clone(),
+            sync_aggregate: update.sync_aggregate.clone(),
+            signature_slot: update.signature_slot,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ssz_and_tree_hash_tests!(LightClientOptimisticUpdate<MainnetEthSpec>);
+}
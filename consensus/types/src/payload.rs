@@ -42,8 +42,52 @@ pub trait ExecPayload<T: EthSpec>:
     fn block_number(&self) -> u64;
     fn timestamp(&self) -> u64;
     fn block_hash(&self) -> ExecutionBlockHash;
+
+    /// The fork of the payload, which must match the fork of the block containing it.
+    fn fork_name(&self) -> ForkName;
+
+    /// The root of the payload's withdrawals, which only exist from Capella onwards.
+    fn withdrawals_root(&self) -> Result<Hash256, Error>;
+
+    /// Returns `true` if this is the default payload for its fork, i.e. the payload included in
+    /// blocks prior to the merge transition.
+    fn is_default(&self) -> bool;
 }
 
+/// An `ExecPayload` which can be produced for a block at any post-merge fork.
+///
+/// Block production is generic over this trait, so that it is written once and monomorphized for
+/// `FullPayload` and `BlindedPayload`. Nothing in this trait is specific to a fork, so adding a
+/// fork only requires extending `ExecutionPayload` and `ExecutionPayloadHeader`.
+pub trait AbstractExecPayload<T: EthSpec>: ExecPayload<T> {
+    /// Returns the default payload for a block at `fork_name`.
+    ///
+    /// Errors if blocks at `fork_name` do not contain a payload.
+    fn default_at_fork(fork_name: ForkName) -> Result<Self, Error> {
+        ExecutionPayload::empty_for_fork(fork_name)
+            .map(Self::from)
+            .ok_or(Error::IncorrectStateVariant)
+    }
+
+    /// Converts a payload returned by an execution engine into a payload of this type.
+    fn from_execution_payload(execution_payload: ExecutionPayload<T>) -> Self {
+        Self::from(execution_payload)
+    }
+
+    /// Converts a payload header returned by a builder into a payload of this type.
+    ///
+    /// Returns `None` if a payload of this type cannot be built from a header alone.
+    fn from_execution_payload_header(
+        execution_payload_header: ExecutionPayloadHeader<T>,
+    ) -> Option<Self> {
+        Self::try_from(execution_payload_header).ok()
+    }
+}
+
+impl<T: EthSpec> AbstractExecPayload<T> for FullPayload<T> {}
+
+impl<T: EthSpec> AbstractExecPayload<T> for BlindedPayload<T> {}
+
 impl<T: EthSpec> ExecPayload<T> for FullPayload<T> {
     fn block_type() -> BlockType {
         BlockType::Full
@@ -54,23 +98,37 @@ impl<T: EthSpec> ExecPayload<T> for FullPayload<T> {
     }
 
     fn parent_hash(&self) -> ExecutionBlockHash {
-        self.execution_payload.parent_hash
+        self.execution_payload.parent_hash()
     }
 
     fn prev_randao(&self) -> Hash256 {
-        self.execution_payload.prev_randao
+        self.execution_payload.prev_randao()
     }
 
     fn block_number(&self) -> u64 {
-        self.execution_payload.block_number
+        self.execution_payload.block_number()
     }
 
     fn timestamp(&self) -> u64 {
-        self.execution_payload.timestamp
+        self.execution_payload.timestamp()
     }
 
     fn block_hash(&self) -> ExecutionBlockHash {
-        self.execution_payload.block_hash
+        self.execution_payload.block_hash()
+    }
+
+    fn fork_name(&self) -> ForkName {
+        self.execution_payload.fork_name()
+    }
+
+    fn withdrawals_root(&self) -> Result<Hash256, Error> {
+        self.execution_payload
+            .withdrawals()
+            .map(|withdrawals| withdrawals.tree_hash_root())
+    }
+
+    fn is_default(&self) -> bool {
+        ExecutionPayload::empty_for_fork(self.fork_name()).as_ref() == Some(&self.execution_payload)
     }
 }
 
@@ -84,26 +142,42 @@ impl<T: EthSpec> ExecPayload<T> for BlindedPayload<T> {
     }
 
     fn parent_hash(&self) -> ExecutionBlockHash {
-        self.execution_payload_header.parent_hash
+        self.execution_payload_header.parent_hash()
     }
 
     fn prev_randao(&self) -> Hash256 {
-        self.execution_payload_header.prev_randao
+        self.execution_payload_header.prev_randao()
     }
 
     fn block_number(&self) -> u64 {
-        self.execution_payload_header.block_number
+        self.execution_payload_header.block_number()
     }
 
     fn timestamp(&self) -> u64 {
-        self.execution_payload_header.timestamp
+        self.execution_payload_header.timestamp()
     }
 
     fn block_hash(&self) -> ExecutionBlockHash {
-        self.execution_payload_header.block_hash
+        self.execution_payload_header.block_hash()
+    }
+
+    fn fork_name(&self) -> ForkName {
+        self.execution_payload_header.fork_name()
+    }
+
+    fn withdrawals_root(&self) -> Result<Hash256, Error> {
+        self.execution_payload_header.withdrawals_root()
+    }
+
+    fn is_default(&self) -> bool {
+        // See the `Default` implementation below for why this is not the default header.
+        ExecutionPayload::empty_for_fork(self.fork_name()).map_or(false, |payload| {
+            ExecutionPayloadHeader::from(&payload) == self.execution_payload_header
+        })
     }
 }
 
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, TestRandom, Serialize, Deserialize, Derivative)]
 #[derivative(PartialEq, Hash(bound = "T: EthSpec"))]
 #[serde(bound = "T: EthSpec")]
@@ -196,6 +270,7 @@ impl<T: EthSpec> Encode for BlindedPayload<T> {
     }
 }
 
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
 #[derive(Default, Debug, Clone, Serialize, Deserialize, TestRandom, Derivative)]
 #[derivative(PartialEq, Hash(bound = "T: EthSpec"))]
 #[serde(bound = "T: EthSpec")]
@@ -260,3 +335,62 @@ impl<T: EthSpec> Encode for FullPayload<T> {
         self.execution_payload.ssz_bytes_len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type E = MainnetEthSpec;
+
+    fn default_at_each_fork<Payload: AbstractExecPayload<E>>() {
+        for fork_name in ForkName::list_all() {
+            match Payload::default_at_fork(fork_name) {
+                Ok(payload) => {
+                    assert_eq!(payload.fork_name(), fork_name);
+                    assert!(payload.is_default(), "{}", fork_name);
+                }
+                Err(e) => {
+                    assert!(
+                        matches!(fork_name, ForkName::Base | ForkName::Altair),
+                        "{}: {:?}",
+                        fork_name,
+                        e
+                    );
+                }
+            }
+        }
+        assert!(Payload::default().is_default());
+    }
+
+    #[test]
+    fn full_payload_default_at_fork() {
+        default_at_each_fork::<FullPayload<E>>();
+    }
+
+    #[test]
+    fn blinded_payload_default_at_fork() {
+        default_at_each_fork::<BlindedPayload<E>>();
+    }
+
+    #[test]
+    fn blinded_default_is_blinded_full_default() {
+        for fork_name in ForkName::list_all() {
+            if let Ok(full) = FullPayload::<E>::default_at_fork(fork_name) {
+                let blinded = BlindedPayload::from_execution_payload(full.execution_payload);
+                assert_eq!(blinded, BlindedPayload::default_at_fork(fork_name).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn non_default_payload() {
+        let payload = FullPayload::<E>::default_at_fork(ForkName::Capella).unwrap();
+        let mut execution_payload = payload.execution_payload;
+        *execution_payload.block_number_mut() = 1;
+
+        assert!(!FullPayload::<E>::from_execution_payload(execution_payload.clone()).is_default());
+        assert!(!BlindedPayload::<E>::from_execution_payload(execution_payload).is_default());
+        // The default header is not the blinded default payload.
+        assert!(!BlindedPayload::<E>::from(ExecutionPayloadHeader::default()).is_default());
+    }
+}
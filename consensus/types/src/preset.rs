@@ -1,5 +1,7 @@
 use crate::{ChainSpec, Epoch, EthSpec, Unsigned};
 use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 
 /// Value-level representation of an Ethereum consensus "preset".
 ///
@@ -118,6 +120,132 @@ impl BasePreset {
             max_voluntary_exits: T::MaxVoluntaryExits::to_u64(),
         }
     }
+
+    /// Apply the runtime values of `self` to `spec`.
+    ///
+    /// Returns an error if a value which is fixed at compile time by `T` differs.
+    pub fn apply_to_chain_spec<T: EthSpec>(&self, spec: &mut ChainSpec) -> Result<(), String> {
+        let Self {
+            max_committees_per_slot,
+            target_committee_size,
+            max_validators_per_committee,
+            shuffle_round_count,
+            hysteresis_quotient,
+            hysteresis_downward_multiplier,
+            hysteresis_upward_multiplier,
+            safe_slots_to_update_justified,
+            min_deposit_amount,
+            max_effective_balance,
+            effective_balance_increment,
+            min_attestation_inclusion_delay,
+            slots_per_epoch,
+            min_seed_lookahead,
+            max_seed_lookahead,
+            epochs_per_eth1_voting_period,
+            slots_per_historical_root,
+            min_epochs_to_inactivity_penalty,
+            epochs_per_historical_vector,
+            epochs_per_slashings_vector,
+            historical_roots_limit,
+            validator_registry_limit,
+            base_reward_factor,
+            whistleblower_reward_quotient,
+            proposer_reward_quotient,
+            inactivity_penalty_quotient,
+            min_slashing_penalty_quotient,
+            proportional_slashing_multiplier,
+            max_proposer_slashings,
+            max_attester_slashings,
+            max_attestations,
+            max_deposits,
+            max_voluntary_exits,
+        } = *self;
+
+        check_compile_time_value(
+            "max_validators_per_committee",
+            max_validators_per_committee,
+            T::MaxValidatorsPerCommittee::to_u64(),
+        )?;
+        check_compile_time_value(
+            "slots_per_epoch",
+            slots_per_epoch,
+            T::SlotsPerEpoch::to_u64(),
+        )?;
+        check_compile_time_value(
+            "epochs_per_eth1_voting_period",
+            epochs_per_eth1_voting_period,
+            T::EpochsPerEth1VotingPeriod::to_u64(),
+        )?;
+        check_compile_time_value(
+            "slots_per_historical_root",
+            slots_per_historical_root,
+            T::SlotsPerHistoricalRoot::to_u64(),
+        )?;
+        check_compile_time_value(
+            "epochs_per_historical_vector",
+            epochs_per_historical_vector,
+            T::EpochsPerHistoricalVector::to_u64(),
+        )?;
+        check_compile_time_value(
+            "epochs_per_slashings_vector",
+            epochs_per_slashings_vector,
+            T::EpochsPerSlashingsVector::to_u64(),
+        )?;
+        check_compile_time_value(
+            "historical_roots_limit",
+            historical_roots_limit,
+            T::HistoricalRootsLimit::to_u64(),
+        )?;
+        check_compile_time_value(
+            "validator_registry_limit",
+            validator_registry_limit,
+            T::ValidatorRegistryLimit::to_u64(),
+        )?;
+        check_compile_time_value(
+            "max_proposer_slashings",
+            max_proposer_slashings,
+            T::MaxProposerSlashings::to_u64(),
+        )?;
+        check_compile_time_value(
+            "max_attester_slashings",
+            max_attester_slashings,
+            T::MaxAttesterSlashings::to_u64(),
+        )?;
+        check_compile_time_value(
+            "max_attestations",
+            max_attestations,
+            T::MaxAttestations::to_u64(),
+        )?;
+        check_compile_time_value("max_deposits", max_deposits, T::MaxDeposits::to_u64())?;
+        check_compile_time_value(
+            "max_voluntary_exits",
+            max_voluntary_exits,
+            T::MaxVoluntaryExits::to_u64(),
+        )?;
+
+        spec.max_committees_per_slot = max_committees_per_slot as usize;
+        spec.target_committee_size = target_committee_size as usize;
+        spec.shuffle_round_count = shuffle_round_count;
+        spec.hysteresis_quotient = hysteresis_quotient;
+        spec.hysteresis_downward_multiplier = hysteresis_downward_multiplier;
+        spec.hysteresis_upward_multiplier = hysteresis_upward_multiplier;
+        spec.safe_slots_to_update_justified = safe_slots_to_update_justified;
+        spec.min_deposit_amount = min_deposit_amount;
+        spec.max_effective_balance = max_effective_balance;
+        spec.effective_balance_increment = effective_balance_increment;
+        spec.min_attestation_inclusion_delay = min_attestation_inclusion_delay;
+        spec.min_seed_lookahead = min_seed_lookahead;
+        spec.max_seed_lookahead = max_seed_lookahead;
+        spec.min_epochs_to_inactivity_penalty = min_epochs_to_inactivity_penalty;
+        spec.base_reward_factor = base_reward_factor;
+        spec.whistleblower_reward_quotient = whistleblower_reward_quotient;
+        spec.proposer_reward_quotient = proposer_reward_quotient;
+        spec.inactivity_penalty_quotient = inactivity_penalty_quotient;
+        spec.min_slashing_penalty_quotient = min_slashing_penalty_quotient;
+        spec.proportional_slashing_multiplier = proportional_slashing_multiplier;
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -148,6 +276,34 @@ impl AltairPreset {
             min_sync_committee_participants: spec.min_sync_committee_participants,
         }
     }
+
+    /// Apply the runtime values of `self` to `spec`.
+    ///
+    /// Returns an error if a value which is fixed at compile time by `T` differs.
+    pub fn apply_to_chain_spec<T: EthSpec>(&self, spec: &mut ChainSpec) -> Result<(), String> {
+        let Self {
+            inactivity_penalty_quotient_altair,
+            min_slashing_penalty_quotient_altair,
+            proportional_slashing_multiplier_altair,
+            sync_committee_size,
+            epochs_per_sync_committee_period,
+            min_sync_committee_participants,
+        } = *self;
+
+        check_compile_time_value(
+            "sync_committee_size",
+            sync_committee_size,
+            T::SyncCommitteeSize::to_u64(),
+        )?;
+
+        spec.inactivity_penalty_quotient_altair = inactivity_penalty_quotient_altair;
+        spec.min_slashing_penalty_quotient_altair = min_slashing_penalty_quotient_altair;
+        spec.proportional_slashing_multiplier_altair = proportional_slashing_multiplier_altair;
+        spec.epochs_per_sync_committee_period = epochs_per_sync_committee_period;
+        spec.min_sync_committee_participants = min_sync_committee_participants;
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -182,6 +338,278 @@ impl BellatrixPreset {
             max_extra_data_bytes: T::max_extra_data_bytes() as u64,
         }
     }
+
+    /// Apply the runtime values of `self` to `spec`.
+    ///
+    /// Returns an error if a value which is fixed at compile time by `T` differs.
+    pub fn apply_to_chain_spec<T: EthSpec>(&self, spec: &mut ChainSpec) -> Result<(), String> {
+        let Self {
+            inactivity_penalty_quotient_bellatrix,
+            min_slashing_penalty_quotient_bellatrix,
+            proportional_slashing_multiplier_bellatrix,
+            max_bytes_per_transaction,
+            max_transactions_per_payload,
+            bytes_per_logs_bloom,
+            max_extra_data_bytes,
+        } = *self;
+
+        check_compile_time_value(
+            "max_bytes_per_transaction",
+            max_bytes_per_transaction,
+            T::max_bytes_per_transaction() as u64,
+        )?;
+        check_compile_time_value(
+            "max_transactions_per_payload",
+            max_transactions_per_payload,
+            T::max_transactions_per_payload() as u64,
+        )?;
+        check_compile_time_value(
+            "bytes_per_logs_bloom",
+            bytes_per_logs_bloom,
+            T::bytes_per_logs_bloom() as u64,
+        )?;
+        check_compile_time_value(
+            "max_extra_data_bytes",
+            max_extra_data_bytes,
+            T::max_extra_data_bytes() as u64,
+        )?;
+
+        spec.inactivity_penalty_quotient_bellatrix = inactivity_penalty_quotient_bellatrix;
+        spec.min_slashing_penalty_quotient_bellatrix = min_slashing_penalty_quotient_bellatrix;
+        spec.proportional_slashing_multiplier_bellatrix =
+            proportional_slashing_multiplier_bellatrix;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct CapellaPreset {
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub max_bls_to_execution_changes: u64,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub max_withdrawals_per_payload: u64,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub max_validators_per_withdrawals_sweep: u64,
+}
+
+impl CapellaPreset {
+    pub fn from_chain_spec<T: EthSpec>(spec: &ChainSpec) -> Self {
+        Self {
+            max_bls_to_execution_changes: T::max_bls_to_execution_changes() as u64,
+            max_withdrawals_per_payload: T::max_withdrawals_per_payload() as u64,
+            max_validators_per_withdrawals_sweep: spec.max_validators_per_withdrawals_sweep,
+        }
+    }
+
+    /// Apply the runtime values of `self` to `spec`.
+    ///
+    /// Returns an error if a value which is fixed at compile time by `T` differs.
+    pub fn apply_to_chain_spec<T: EthSpec>(&self, spec: &mut ChainSpec) -> Result<(), String> {
+        let Self {
+            max_bls_to_execution_changes,
+            max_withdrawals_per_payload,
+            max_validators_per_withdrawals_sweep,
+        } = *self;
+
+        check_compile_time_value(
+            "max_bls_to_execution_changes",
+            max_bls_to_execution_changes,
+            T::max_bls_to_execution_changes() as u64,
+        )?;
+        check_compile_time_value(
+            "max_withdrawals_per_payload",
+            max_withdrawals_per_payload,
+            T::max_withdrawals_per_payload() as u64,
+        )?;
+
+        spec.max_validators_per_withdrawals_sweep = max_validators_per_withdrawals_sweep;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct DenebPreset {
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub field_elements_per_blob: u64,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub max_blob_commitments_per_block: u64,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub max_blobs_per_block: u64,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub kzg_commitment_inclusion_proof_depth: u64,
+}
+
+impl DenebPreset {
+    pub fn from_chain_spec<T: EthSpec>(_spec: &ChainSpec) -> Self {
+        Self {
+            field_elements_per_blob: T::FieldElementsPerBlob::to_u64(),
+            max_blob_commitments_per_block: T::max_blob_commitments_per_block() as u64,
+            max_blobs_per_block: T::max_blobs_per_block() as u64,
+            kzg_commitment_inclusion_proof_depth: T::kzg_commitment_inclusion_proof_depth() as u64,
+        }
+    }
+
+    /// Check `self` against `T`. Every Deneb preset value is fixed at compile time.
+    pub fn apply_to_chain_spec<T: EthSpec>(&self, _spec: &mut ChainSpec) -> Result<(), String> {
+        let Self {
+            field_elements_per_blob,
+            max_blob_commitments_per_block,
+            max_blobs_per_block,
+            kzg_commitment_inclusion_proof_depth,
+        } = *self;
+
+        check_compile_time_value(
+            "field_elements_per_blob",
+            field_elements_per_blob,
+            T::FieldElementsPerBlob::to_u64(),
+        )?;
+        check_compile_time_value(
+            "max_blob_commitments_per_block",
+            max_blob_commitments_per_block,
+            T::max_blob_commitments_per_block() as u64,
+        )?;
+        check_compile_time_value(
+            "max_blobs_per_block",
+            max_blobs_per_block,
+            T::max_blobs_per_block() as u64,
+        )?;
+        check_compile_time_value(
+            "kzg_commitment_inclusion_proof_depth",
+            kzg_commitment_inclusion_proof_depth,
+            T::kzg_commitment_inclusion_proof_depth() as u64,
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct ElectraPreset {
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub max_deposit_requests_per_payload: u64,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub max_withdrawal_requests_per_payload: u64,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub max_consolidation_requests_per_payload: u64,
+}
+
+impl ElectraPreset {
+    pub fn from_chain_spec<T: EthSpec>(_spec: &ChainSpec) -> Self {
+        Self {
+            max_deposit_requests_per_payload: T::max_deposit_requests_per_payload() as u64,
+            max_withdrawal_requests_per_payload: T::max_withdrawal_requests_per_payload() as u64,
+            max_consolidation_requests_per_payload: T::max_consolidation_requests_per_payload()
+                as u64,
+        }
+    }
+
+    /// Check `self` against `T`. Every Electra preset value is fixed at compile time.
+    pub fn apply_to_chain_spec<T: EthSpec>(&self, _spec: &mut ChainSpec) -> Result<(), String> {
+        let Self {
+            max_deposit_requests_per_payload,
+            max_withdrawal_requests_per_payload,
+            max_consolidation_requests_per_payload,
+        } = *self;
+
+        check_compile_time_value(
+            "max_deposit_requests_per_payload",
+            max_deposit_requests_per_payload,
+            T::max_deposit_requests_per_payload() as u64,
+        )?;
+        check_compile_time_value(
+            "max_withdrawal_requests_per_payload",
+            max_withdrawal_requests_per_payload,
+            T::max_withdrawal_requests_per_payload() as u64,
+        )?;
+        check_compile_time_value(
+            "max_consolidation_requests_per_payload",
+            max_consolidation_requests_per_payload,
+            T::max_consolidation_requests_per_payload() as u64,
+        )
+    }
+}
+
+/// Preset values to override, keyed by their upper-case YAML names.
+pub type PresetOverrides = BTreeMap<String, Value>;
+
+/// The preset values of every fork, as they appear in a single merged preset YAML file.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct FullPreset {
+    #[serde(flatten)]
+    pub base: BasePreset,
+    #[serde(flatten)]
+    pub altair: AltairPreset,
+    #[serde(flatten)]
+    pub bellatrix: BellatrixPreset,
+    #[serde(flatten)]
+    pub capella: CapellaPreset,
+    #[serde(flatten)]
+    pub deneb: DenebPreset,
+    #[serde(flatten)]
+    pub electra: ElectraPreset,
+}
+
+impl FullPreset {
+    pub fn from_chain_spec<T: EthSpec>(spec: &ChainSpec) -> Self {
+        Self {
+            base: BasePreset::from_chain_spec::<T>(spec),
+            altair: AltairPreset::from_chain_spec::<T>(spec),
+            bellatrix: BellatrixPreset::from_chain_spec::<T>(spec),
+            capella: CapellaPreset::from_chain_spec::<T>(spec),
+            deneb: DenebPreset::from_chain_spec::<T>(spec),
+            electra: ElectraPreset::from_chain_spec::<T>(spec),
+        }
+    }
+
+    /// Returns a copy of `self` with each value in `overrides` replaced.
+    ///
+    /// Returns an error if `overrides` contains a field which is not part of any preset.
+    pub fn with_overrides(&self, overrides: &PresetOverrides) -> Result<Self, String> {
+        let mut preset = serde_json::to_value(self)
+            .map_err(|e| format!("Unable to serialize preset: {:?}", e))?;
+        let fields = preset
+            .as_object_mut()
+            .ok_or("Preset does not serialize as a map")?;
+
+        for (name, value) in overrides {
+            let field = fields
+                .get_mut(name)
+                .ok_or_else(|| format!("Unknown preset field {}", name))?;
+            *field = value.clone();
+        }
+
+        serde_json::from_value(preset).map_err(|e| format!("Invalid preset override: {:?}", e))
+    }
+
+    /// Apply the runtime values of `self` to `spec`.
+    ///
+    /// Returns an error if a value which is fixed at compile time by `T` differs.
+    pub fn apply_to_chain_spec<T: EthSpec>(&self, spec: &mut ChainSpec) -> Result<(), String> {
+        self.base.apply_to_chain_spec::<T>(spec)?;
+        self.altair.apply_to_chain_spec::<T>(spec)?;
+        self.bellatrix.apply_to_chain_spec::<T>(spec)?;
+        self.capella.apply_to_chain_spec::<T>(spec)?;
+        self.deneb.apply_to_chain_spec::<T>(spec)?;
+        self.electra.apply_to_chain_spec::<T>(spec)
+    }
+}
+
+/// Returns an error if the preset value `name` differs from the value compiled into the `EthSpec`.
+fn check_compile_time_value(name: &str, value: u64, compiled: u64) -> Result<(), String> {
+    if value == compiled {
+        Ok(())
+    } else {
+        Err(format!(
+            "Preset value {} = {} is incompatible with the compiled value {}, use a different \
+             preset base",
+            name.to_uppercase(),
+            value,
+            compiled
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -219,6 +647,15 @@ mod test {
 
         let bellatrix: BellatrixPreset = preset_from_file(&preset_name, "bellatrix.yaml");
         assert_eq!(bellatrix, BellatrixPreset::from_chain_spec::<E>(&spec));
+
+        let capella: CapellaPreset = preset_from_file(&preset_name, "capella.yaml");
+        assert_eq!(capella, CapellaPreset::from_chain_spec::<E>(&spec));
+
+        let deneb: DenebPreset = preset_from_file(&preset_name, "deneb.yaml");
+        assert_eq!(deneb, DenebPreset::from_chain_spec::<E>(&spec));
+
+        let electra: ElectraPreset = preset_from_file(&preset_name, "electra.yaml");
+        assert_eq!(electra, ElectraPreset::from_chain_spec::<E>(&spec));
     }
 
     #[test]
@@ -235,4 +672,52 @@ mod test {
     fn minimal_presets_consistent() {
         preset_test::<MinimalEthSpec>();
     }
+
+    fn overrides(fields: &[(&str, Value)]) -> PresetOverrides {
+        fields
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn preset_overrides_apply_to_chain_spec() {
+        let mut spec = MinimalEthSpec::default_spec();
+        let preset = FullPreset::from_chain_spec::<MinimalEthSpec>(&spec)
+            .with_overrides(&overrides(&[
+                ("SHUFFLE_ROUND_COUNT", 20.into()),
+                ("MAX_EFFECTIVE_BALANCE", "64000000000".into()),
+                ("EPOCHS_PER_SYNC_COMMITTEE_PERIOD", 16.into()),
+                // Unchanged compile-time values are permitted.
+                ("SLOTS_PER_EPOCH", 8.into()),
+            ]))
+            .unwrap();
+        preset
+            .apply_to_chain_spec::<MinimalEthSpec>(&mut spec)
+            .unwrap();
+
+        assert_eq!(spec.shuffle_round_count, 20);
+        assert_eq!(spec.max_effective_balance, 64_000_000_000);
+        assert_eq!(spec.epochs_per_sync_committee_period, Epoch::new(16));
+        assert_eq!(FullPreset::from_chain_spec::<MinimalEthSpec>(&spec), preset);
+    }
+
+    #[test]
+    fn preset_overrides_reject_unknown_field() {
+        let spec = MinimalEthSpec::default_spec();
+        let result = FullPreset::from_chain_spec::<MinimalEthSpec>(&spec)
+            .with_overrides(&overrides(&[("NOT_A_PRESET_FIELD", 1.into())]));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn preset_overrides_reject_compile_time_change() {
+        let mut spec = MinimalEthSpec::default_spec();
+        let preset = FullPreset::from_chain_spec::<MinimalEthSpec>(&spec)
+            .with_overrides(&overrides(&[("SLOTS_PER_EPOCH", 32.into())]))
+            .unwrap();
+        assert!(preset
+            .apply_to_chain_spec::<MinimalEthSpec>(&mut spec)
+            .is_err());
+    }
 }
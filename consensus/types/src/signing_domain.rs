@@ -0,0 +1,309 @@
+//! Computes the domain mixed into the signing root of each kind of message signed by a validator.
+//!
+//! Most messages are signed with the fork version in effect at the message's epoch, see
+//! `ChainSpec::get_domain`. The exceptions are:
+//!
+//! - Deposits and builder API messages, which are valid on any chain and use the genesis fork
+//!   version with a zero genesis validators root.
+//! - BLS to execution changes, which use the genesis fork version.
+//! - Voluntary exits verified from Deneb onwards, which always use the Capella fork version
+//!   (EIP-7044) so that an exit signed once remains valid across future forks.
+//!
+//! Signing and verification should both call `get_signing_domain` so that these exceptions are
+//! applied consistently.
+use crate::{ChainSpec, Domain, Epoch, Fork, ForkName, Hash256};
+
+/// Returns the domain for a message of type `domain` at `epoch`, on the chain with the given
+/// `fork` and `genesis_validators_root`.
+///
+/// The `fork` should be that of the state against which the message will be verified, e.g. the
+/// head state when signing.
+pub fn get_signing_domain(
+    domain: Domain,
+    epoch: Epoch,
+    fork: &Fork,
+    genesis_validators_root: Hash256,
+    spec: &ChainSpec,
+) -> Hash256 {
+    match domain {
+        Domain::Deposit => spec.get_deposit_domain(),
+        Domain::ApplicationMask(_) => {
+            spec.compute_domain(domain, spec.genesis_fork_version, Hash256::zero())
+        }
+        Domain::BlsToExecutionChange => {
+            get_bls_to_execution_change_domain(genesis_validators_root, spec)
+        }
+        Domain::VoluntaryExit => {
+            get_voluntary_exit_domain(epoch, fork, genesis_validators_root, spec)
+        }
+        Domain::BeaconProposer
+        | Domain::BeaconAttester
+        | Domain::Randao
+        | Domain::SelectionProof
+        | Domain::AggregateAndProof
+        | Domain::SyncCommittee
+        | Domain::ContributionAndProof
+        | Domain::SyncCommitteeSelectionProof => {
+            spec.get_domain(epoch, domain, fork, genesis_validators_root)
+        }
+    }
+}
+
+/// Returns the domain for a voluntary exit at `epoch`, see `get_signing_domain`.
+pub fn get_voluntary_exit_domain(
+    epoch: Epoch,
+    fork: &Fork,
+    genesis_validators_root: Hash256,
+    spec: &ChainSpec,
+) -> Hash256 {
+    if has_fixed_exit_domain(fork, spec) {
+        spec.compute_domain(
+            Domain::VoluntaryExit,
+            spec.capella_fork_version,
+            genesis_validators_root,
+        )
+    } else {
+        spec.get_domain(epoch, Domain::VoluntaryExit, fork, genesis_validators_root)
+    }
+}
+
+/// Returns the domain for a BLS to execution change, which is the same at every fork.
+pub fn get_bls_to_execution_change_domain(
+    genesis_validators_root: Hash256,
+    spec: &ChainSpec,
+) -> Hash256 {
+    spec.compute_domain(
+        Domain::BlsToExecutionChange,
+        spec.genesis_fork_version,
+        genesis_validators_root,
+    )
+}
+
+/// Returns `true` if voluntary exits verified against a state with the given `fork` are signed
+/// with the Capella fork version, rather than the version at the exit's epoch.
+///
+/// The fork is identified by its current version rather than its epoch, so that a `Fork` from a
+/// state which is inconsistent with the `spec` cannot select the wrong domain.
+pub fn has_fixed_exit_domain(fork: &Fork, spec: &ChainSpec) -> bool {
+    let fork_name = ForkName::list_all()
+        .into_iter()
+        .rev()
+        .find(|&fork_name| spec.fork_version_for_name(fork_name) == fork.current_version);
+
+    match fork_name {
+        Some(ForkName::Deneb) => true,
+        Some(ForkName::Base | ForkName::Altair | ForkName::Merge | ForkName::Capella) | None => {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application_domain::ApplicationDomain;
+    use crate::{EthSpec, MainnetEthSpec};
+
+    type E = MainnetEthSpec;
+
+    const ALL_DOMAINS: [Domain; 12] = [
+        Domain::BeaconProposer,
+        Domain::BeaconAttester,
+        Domain::Randao,
+        Domain::Deposit,
+        Domain::VoluntaryExit,
+        Domain::SelectionProof,
+        Domain::AggregateAndProof,
+        Domain::SyncCommittee,
+        Domain::ContributionAndProof,
+        Domain::SyncCommitteeSelectionProof,
+        Domain::BlsToExecutionChange,
+        Domain::ApplicationMask(ApplicationDomain::Builder),
+    ];
+
+    /// A spec with every fork scheduled one epoch after the previous.
+    fn spec() -> ChainSpec {
+        let mut spec = E::default_spec();
+        spec.altair_fork_epoch = Some(Epoch::new(1));
+        spec.bellatrix_fork_epoch = Some(Epoch::new(2));
+        spec.capella_fork_epoch = Some(Epoch::new(3));
+        spec.deneb_fork_epoch = Some(Epoch::new(4));
+        spec
+    }
+
+    fn genesis_validators_root() -> Hash256 {
+        Hash256::repeat_byte(42)
+    }
+
+    /// The domain of each message type, computed without `get_signing_domain`.
+    fn expected_domain(
+        domain: Domain,
+        epoch: Epoch,
+        fork_name: ForkName,
+        spec: &ChainSpec,
+    ) -> Hash256 {
+        let fork = spec.fork_for_name(fork_name).unwrap();
+        match domain {
+            Domain::Deposit => spec.get_deposit_domain(),
+            Domain::ApplicationMask(ApplicationDomain::Builder) => spec.get_builder_domain(),
+            Domain::BlsToExecutionChange => {
+                spec.compute_domain(domain, spec.genesis_fork_version, genesis_validators_root())
+            }
+            Domain::VoluntaryExit if fork_name == ForkName::Deneb => {
+                spec.compute_domain(domain, spec.capella_fork_version, genesis_validators_root())
+            }
+            _ => spec.compute_domain(
+                domain,
+                fork.get_fork_version(epoch),
+                genesis_validators_root(),
+            ),
+        }
+    }
+
+    #[test]
+    fn all_domains_at_all_forks() {
+        let spec = spec();
+        for fork_name in ForkName::list_all() {
+            let fork = spec.fork_for_name(fork_name).unwrap();
+            let fork_epoch = spec.fork_epoch(fork_name).unwrap();
+            for epoch in [fork_epoch.saturating_sub(1u64), fork_epoch, fork_epoch + 10] {
+                for domain in ALL_DOMAINS {
+                    assert_eq!(
+                        get_signing_domain(domain, epoch, &fork, genesis_validators_root(), &spec),
+                        expected_domain(domain, epoch, fork_name, &spec),
+                        "{:?} at epoch {} of {}",
+                        domain,
+                        epoch,
+                        fork_name
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fork_independent_domains() {
+        let spec = spec();
+        let domains_at_each_fork = |domain| {
+            ForkName::list_all()
+                .into_iter()
+                .map(|fork_name| {
+                    let fork = spec.fork_for_name(fork_name).unwrap();
+                    let epoch = spec.fork_epoch(fork_name).unwrap();
+                    get_signing_domain(domain, epoch, &fork, genesis_validators_root(), &spec)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for domain in [
+            Domain::Deposit,
+            Domain::BlsToExecutionChange,
+            Domain::ApplicationMask(ApplicationDomain::Builder),
+        ] {
+            let domains = domains_at_each_fork(domain);
+            assert!(
+                domains.iter().all(|d| *d == domains[0]),
+                "{:?} differs across forks",
+                domain
+            );
+        }
+
+        // Deposits and builder messages are valid on any chain.
+        let fork = spec.fork_for_name(ForkName::Capella).unwrap();
+        for domain in [
+            Domain::Deposit,
+            Domain::ApplicationMask(ApplicationDomain::Builder),
+        ] {
+            assert_eq!(
+                get_signing_domain(domain, Epoch::new(3), &fork, Hash256::zero(), &spec),
+                get_signing_domain(
+                    domain,
+                    Epoch::new(3),
+                    &fork,
+                    genesis_validators_root(),
+                    &spec
+                ),
+            );
+        }
+    }
+
+    #[test]
+    fn exits_from_deneb_use_capella_domain() {
+        let spec = spec();
+        let capella_fork = spec.fork_for_name(ForkName::Capella).unwrap();
+        let deneb_fork = spec.fork_for_name(ForkName::Deneb).unwrap();
+        let capella_exit_domain = spec.compute_domain(
+            Domain::VoluntaryExit,
+            spec.capella_fork_version,
+            genesis_validators_root(),
+        );
+
+        // An exit from any epoch is verified with the Capella domain by a Deneb state.
+        for epoch in [Epoch::new(0), Epoch::new(3), Epoch::new(4), Epoch::new(100)] {
+            assert_eq!(
+                get_signing_domain(
+                    Domain::VoluntaryExit,
+                    epoch,
+                    &deneb_fork,
+                    genesis_validators_root(),
+                    &spec
+                ),
+                capella_exit_domain
+            );
+        }
+
+        // Prior to Deneb, an exit from an earlier fork is verified with that fork's domain.
+        assert_eq!(
+            get_signing_domain(
+                Domain::VoluntaryExit,
+                Epoch::new(2),
+                &capella_fork,
+                genesis_validators_root(),
+                &spec
+            ),
+            spec.compute_domain(
+                Domain::VoluntaryExit,
+                spec.bellatrix_fork_version,
+                genesis_validators_root()
+            )
+        );
+
+        // Other messages are not affected.
+        assert_ne!(
+            get_signing_domain(
+                Domain::BeaconProposer,
+                Epoch::new(4),
+                &deneb_fork,
+                genesis_validators_root(),
+                &spec
+            ),
+            spec.compute_domain(
+                Domain::BeaconProposer,
+                spec.capella_fork_version,
+                genesis_validators_root()
+            )
+        );
+    }
+
+    #[test]
+    fn fixed_exit_domain_by_fork_version() {
+        let spec = spec();
+        for fork_name in ForkName::list_all() {
+            let fork = spec.fork_for_name(fork_name).unwrap();
+            assert_eq!(
+                has_fixed_exit_domain(&fork, &spec),
+                fork_name == ForkName::Deneb,
+                "{}",
+                fork_name
+            );
+        }
+
+        // An unknown fork version is never treated as Deneb.
+        let unknown_fork = Fork {
+            previous_version: spec.deneb_fork_version,
+            current_version: [0xff; 4],
+            epoch: Epoch::new(5),
+        };
+        assert!(!has_fixed_exit_domain(&unknown_fork, &spec));
+    }
+}
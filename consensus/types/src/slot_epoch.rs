@@ -97,6 +97,53 @@ impl Epoch {
             .as_u64())
     }
 
+    /// Compute the position of an epoch within its sync committee period.
+    ///
+    /// E.g., the first epoch of a period is at offset `0`.
+    pub fn sync_committee_period_offset(&self, spec: &ChainSpec) -> Result<u64, ArithError> {
+        Ok(self
+            .safe_rem(spec.epochs_per_sync_committee_period)?
+            .as_u64())
+    }
+
+    /// Compute the first epoch of the given sync committee period.
+    pub fn sync_committee_period_start(
+        sync_committee_period: u64,
+        spec: &ChainSpec,
+    ) -> Result<Epoch, ArithError> {
+        spec.epochs_per_sync_committee_period
+            .safe_mul(sync_committee_period)
+    }
+
+    /// Compute the last epoch of the given sync committee period.
+    pub fn sync_committee_period_end(
+        sync_committee_period: u64,
+        spec: &ChainSpec,
+    ) -> Result<Epoch, ArithError> {
+        Self::sync_committee_period_start(sync_committee_period.safe_add(1)?, spec)?.safe_sub(1)
+    }
+
+    /// Iterate over the sync committee period boundaries at or after this epoch, yielding each
+    /// period along with its first epoch.
+    ///
+    /// The first item is the period containing this epoch if this epoch is its first epoch,
+    /// otherwise it is the next period.
+    pub fn sync_committee_period_boundaries(
+        &self,
+        spec: &ChainSpec,
+    ) -> Result<SyncCommitteePeriodIter, ArithError> {
+        let period = self.sync_committee_period(spec)?;
+        let next_period = if self.sync_committee_period_offset(spec)? == 0 {
+            period
+        } else {
+            period.safe_add(1)?
+        };
+        Ok(SyncCommitteePeriodIter {
+            next_period: Some(next_period),
+            epochs_per_sync_committee_period: spec.epochs_per_sync_committee_period,
+        })
+    }
+
     pub fn slot_iter(&self, slots_per_epoch: u64) -> SlotIter {
         SlotIter {
             current_iteration: 0,
@@ -127,6 +174,26 @@ impl<'a> Iterator for SlotIter<'a> {
     }
 }
 
+/// Iterates over consecutive sync committee periods, see `Epoch::sync_committee_period_boundaries`.
+pub struct SyncCommitteePeriodIter {
+    next_period: Option<u64>,
+    epochs_per_sync_committee_period: Epoch,
+}
+
+impl Iterator for SyncCommitteePeriodIter {
+    type Item = (u64, Epoch);
+
+    fn next(&mut self) -> Option<(u64, Epoch)> {
+        let period = self.next_period?;
+        let start_epoch = self
+            .epochs_per_sync_committee_period
+            .safe_mul(period)
+            .ok()?;
+        self.next_period = period.checked_add(1);
+        Some((period, start_epoch))
+    }
+}
+
 #[cfg(test)]
 mod slot_tests {
     use super::*;
@@ -181,6 +248,72 @@ mod epoch_tests {
         assert_eq!(epoch.position(Slot::new(8), slots_per_epoch), Some(0));
     }
 
+    #[test]
+    fn sync_committee_period_helpers() {
+        let spec = ChainSpec::mainnet();
+        assert_eq!(spec.epochs_per_sync_committee_period, Epoch::new(256));
+
+        for (epoch, period, offset, start, end) in [
+            (0, 0, 0, 0, 255),
+            (1, 0, 1, 0, 255),
+            (255, 0, 255, 0, 255),
+            (256, 1, 0, 256, 511),
+            (257, 1, 1, 256, 511),
+            (1000, 3, 232, 768, 1023),
+        ] {
+            let epoch = Epoch::new(epoch);
+            assert_eq!(epoch.sync_committee_period(&spec), Ok(period));
+            assert_eq!(epoch.sync_committee_period_offset(&spec), Ok(offset));
+            assert_eq!(
+                Epoch::sync_committee_period_start(period, &spec),
+                Ok(Epoch::new(start))
+            );
+            assert_eq!(
+                Epoch::sync_committee_period_end(period, &spec),
+                Ok(Epoch::new(end))
+            );
+        }
+
+        assert!(Epoch::sync_committee_period_start(u64::max_value(), &spec).is_err());
+        assert!(Epoch::sync_committee_period_end(u64::max_value(), &spec).is_err());
+    }
+
+    #[test]
+    fn sync_committee_period_boundaries() {
+        let spec = ChainSpec::mainnet();
+
+        let from_boundary = Epoch::new(256)
+            .sync_committee_period_boundaries(&spec)
+            .unwrap()
+            .take(3)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            from_boundary,
+            vec![
+                (1, Epoch::new(256)),
+                (2, Epoch::new(512)),
+                (3, Epoch::new(768))
+            ]
+        );
+
+        let within_period = Epoch::new(257)
+            .sync_committee_period_boundaries(&spec)
+            .unwrap()
+            .next();
+        assert_eq!(within_period, Some((2, Epoch::new(512))));
+
+        // Iteration stops rather than overflowing.
+        let last_period = Epoch::max_value().sync_committee_period(&spec).unwrap();
+        let last_period_start = Epoch::sync_committee_period_start(last_period, &spec).unwrap();
+        assert_eq!(
+            last_period_start
+                .sync_committee_period_boundaries(&spec)
+                .unwrap()
+                .collect::<Vec<_>>(),
+            vec![(last_period, last_period_start)]
+        );
+    }
+
     #[test]
     fn slot_iter() {
         let slots_per_epoch = 8;
@@ -0,0 +1,308 @@
+//! Zero-copy views into the SSZ encoding of objects received over gossip.
+//!
+//! A view validates the fixed-length part of an encoding and reads fields directly from the
+//! underlying bytes. This allows cheap checks (e.g. on the slot of an attestation) to run before
+//! the variable-length parts of the object are allocated and decoded.
+
+use crate::{
+    Attestation, AttestationData, BeaconBlock, ChainSpec, EthSpec, Hash256, SignatureBytes,
+    SignedBeaconBlock, Slot,
+};
+use ssz::{read_offset, Decode, DecodeError};
+
+/// The length of the fixed part of an `Attestation`:
+///
+/// `aggregation_bits` offset (4) + `data` (128) + `signature` (96).
+pub const ATTESTATION_FIXED_LEN: usize = 228;
+/// The byte range of `Attestation.data`.
+const ATTESTATION_DATA_START: usize = 4;
+const ATTESTATION_DATA_END: usize = 132;
+/// The byte range of `Attestation.signature`.
+const ATTESTATION_SIGNATURE_END: usize = 228;
+
+/// The length of the fixed part of a `SignedBeaconBlock`:
+///
+/// `message` offset (4) + `signature` (96).
+pub const SIGNED_BEACON_BLOCK_FIXED_LEN: usize = 100;
+/// The byte range of `SignedBeaconBlock.signature`.
+const SIGNED_BEACON_BLOCK_SIGNATURE_START: usize = 4;
+
+/// The length of the fixed part of a `BeaconBlock`, which is identical across forks:
+///
+/// `slot` (8) + `proposer_index` (8) + `parent_root` (32) + `state_root` (32) + `body` offset (4).
+pub const BEACON_BLOCK_FIXED_LEN: usize = 84;
+/// The positions of the fields within the fixed part of a `BeaconBlock`.
+const BEACON_BLOCK_PROPOSER_INDEX_START: usize = 8;
+const BEACON_BLOCK_PARENT_ROOT_START: usize = 16;
+const BEACON_BLOCK_STATE_ROOT_START: usize = 48;
+const BEACON_BLOCK_BODY_OFFSET_START: usize = 80;
+
+/// Returns `bytes[start..end]`, or an error if `bytes` is too short.
+fn get_bytes(bytes: &[u8], start: usize, end: usize) -> Result<&[u8], DecodeError> {
+    bytes.get(start..end).ok_or(DecodeError::InvalidByteLength {
+        len: bytes.len(),
+        expected: end,
+    })
+}
+
+/// Checks that the offset at `bytes[position..]` points exactly at the end of a fixed part of
+/// `fixed_len` bytes, as is required for a container with a single variable-length field.
+fn check_first_offset(bytes: &[u8], position: usize, fixed_len: usize) -> Result<(), DecodeError> {
+    let offset = read_offset(get_bytes(bytes, position, fixed_len)?)?;
+    if offset != fixed_len {
+        return Err(DecodeError::OffsetIntoFixedPortion(offset));
+    }
+    Ok(())
+}
+
+/// A view into an SSZ-encoded `Attestation`.
+///
+/// Only the fixed-size `data` and `signature` are read on construction, the `aggregation_bits`
+/// are left as bytes until `Self::to_attestation` is called.
+#[derive(Debug, Clone, Copy)]
+pub struct AttestationSszView<'a> {
+    bytes: &'a [u8],
+    data: AttestationData,
+}
+
+impl<'a> AttestationSszView<'a> {
+    /// Validates the fixed part of the encoded attestation in `bytes`.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        if bytes.len() < ATTESTATION_FIXED_LEN {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: ATTESTATION_FIXED_LEN,
+            });
+        }
+        check_first_offset(bytes, 0, ATTESTATION_FIXED_LEN)?;
+
+        let data = AttestationData::from_ssz_bytes(get_bytes(
+            bytes,
+            ATTESTATION_DATA_START,
+            ATTESTATION_DATA_END,
+        )?)?;
+
+        Ok(Self { bytes, data })
+    }
+
+    pub fn data(&self) -> &AttestationData {
+        &self.data
+    }
+
+    pub fn slot(&self) -> Slot {
+        self.data.slot
+    }
+
+    pub fn beacon_block_root(&self) -> Hash256 {
+        self.data.beacon_block_root
+    }
+
+    /// The compressed, unverified aggregate signature.
+    pub fn signature_bytes(&self) -> Result<SignatureBytes, DecodeError> {
+        SignatureBytes::from_ssz_bytes(get_bytes(
+            self.bytes,
+            ATTESTATION_DATA_END,
+            ATTESTATION_SIGNATURE_END,
+        )?)
+    }
+
+    /// The SSZ encoding of the aggregation bitfield.
+    pub fn aggregation_bits_bytes(&self) -> &'a [u8] {
+        self.bytes.get(ATTESTATION_FIXED_LEN..).unwrap_or_default()
+    }
+
+    /// Fully decode the attestation.
+    pub fn to_attestation<T: EthSpec>(&self) -> Result<Attestation<T>, DecodeError> {
+        Attestation::from_ssz_bytes(self.bytes)
+    }
+}
+
+/// A view into an SSZ-encoded `SignedBeaconBlock` of any fork.
+///
+/// Only the fixed part of the block message is read on construction, the block body is left as
+/// bytes until `Self::to_signed_block` is called.
+#[derive(Debug, Clone, Copy)]
+pub struct SignedBeaconBlockSszView<'a> {
+    bytes: &'a [u8],
+    slot: Slot,
+    proposer_index: u64,
+}
+
+impl<'a> SignedBeaconBlockSszView<'a> {
+    /// Validates the fixed parts of the encoded block and its message in `bytes`.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        check_first_offset(bytes, 0, SIGNED_BEACON_BLOCK_FIXED_LEN)?;
+
+        let view = Self {
+            bytes,
+            slot: Slot::new(0),
+            proposer_index: 0,
+        };
+        let message = view.message_bytes();
+        if message.len() < BEACON_BLOCK_FIXED_LEN {
+            return Err(DecodeError::InvalidByteLength {
+                len: message.len(),
+                expected: BEACON_BLOCK_FIXED_LEN,
+            });
+        }
+        check_first_offset(
+            message,
+            BEACON_BLOCK_BODY_OFFSET_START,
+            BEACON_BLOCK_FIXED_LEN,
+        )?;
+
+        Ok(Self {
+            slot: Slot::from_ssz_bytes(get_bytes(message, 0, BEACON_BLOCK_PROPOSER_INDEX_START)?)?,
+            proposer_index: u64::from_ssz_bytes(get_bytes(
+                message,
+                BEACON_BLOCK_PROPOSER_INDEX_START,
+                BEACON_BLOCK_PARENT_ROOT_START,
+            )?)?,
+            ..view
+        })
+    }
+
+    pub fn slot(&self) -> Slot {
+        self.slot
+    }
+
+    pub fn proposer_index(&self) -> u64 {
+        self.proposer_index
+    }
+
+    pub fn parent_root(&self) -> Result<Hash256, DecodeError> {
+        Hash256::from_ssz_bytes(get_bytes(
+            self.message_bytes(),
+            BEACON_BLOCK_PARENT_ROOT_START,
+            BEACON_BLOCK_STATE_ROOT_START,
+        )?)
+    }
+
+    pub fn state_root(&self) -> Result<Hash256, DecodeError> {
+        Hash256::from_ssz_bytes(get_bytes(
+            self.message_bytes(),
+            BEACON_BLOCK_STATE_ROOT_START,
+            BEACON_BLOCK_BODY_OFFSET_START,
+        )?)
+    }
+
+    /// The compressed, unverified proposer signature.
+    pub fn signature_bytes(&self) -> Result<SignatureBytes, DecodeError> {
+        SignatureBytes::from_ssz_bytes(get_bytes(
+            self.bytes,
+            SIGNED_BEACON_BLOCK_SIGNATURE_START,
+            SIGNED_BEACON_BLOCK_FIXED_LEN,
+        )?)
+    }
+
+    /// The SSZ encoding of the (unsigned) block message.
+    pub fn message_bytes(&self) -> &'a [u8] {
+        self.bytes
+            .get(SIGNED_BEACON_BLOCK_FIXED_LEN..)
+            .unwrap_or_default()
+    }
+
+    /// Fully decode the block, selecting the fork variant from its slot.
+    pub fn to_signed_block<T: EthSpec>(
+        &self,
+        spec: &ChainSpec,
+    ) -> Result<SignedBeaconBlock<T>, DecodeError> {
+        SignedBeaconBlock::from_ssz_bytes(self.bytes, spec)
+    }
+
+    /// Fully decode the block with a custom decoder for the block message.
+    pub fn to_signed_block_with<T: EthSpec>(
+        &self,
+        block_decoder: impl FnOnce(&[u8]) -> Result<BeaconBlock<T>, DecodeError>,
+    ) -> Result<SignedBeaconBlock<T>, DecodeError> {
+        SignedBeaconBlock::from_ssz_bytes_with(self.bytes, block_decoder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+    use crate::{BeaconBlockAltair, ForkName, MainnetEthSpec, Signature};
+    use ssz::Encode;
+
+    type E = MainnetEthSpec;
+
+    #[test]
+    fn attestation_view() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let attestation = Attestation::<E>::random_for_test(rng);
+        let bytes = attestation.as_ssz_bytes();
+
+        let view = AttestationSszView::new(&bytes).unwrap();
+        assert_eq!(view.data(), &attestation.data);
+        assert_eq!(view.slot(), attestation.data.slot);
+        assert_eq!(
+            view.signature_bytes().unwrap().serialize(),
+            attestation.signature.serialize()
+        );
+        assert_eq!(
+            view.aggregation_bits_bytes(),
+            attestation.aggregation_bits.as_ssz_bytes().as_slice()
+        );
+        assert_eq!(view.to_attestation::<E>().unwrap(), attestation);
+    }
+
+    #[test]
+    fn attestation_view_invalid() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let bytes = Attestation::<E>::random_for_test(rng).as_ssz_bytes();
+
+        assert!(AttestationSszView::new(&bytes[..ATTESTATION_FIXED_LEN - 1]).is_err());
+
+        let mut bad_offset = bytes;
+        bad_offset[0] = bad_offset[0].wrapping_add(1);
+        assert_eq!(
+            AttestationSszView::new(&bad_offset).unwrap_err(),
+            DecodeError::OffsetIntoFixedPortion(ATTESTATION_FIXED_LEN + 1)
+        );
+    }
+
+    #[test]
+    fn signed_block_view() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let spec = ForkName::Altair.make_genesis_spec(E::default_spec());
+
+        let mut block = BeaconBlockAltair::<E>::random_for_test(rng);
+        block.slot = Slot::new(42);
+        let signed_block =
+            SignedBeaconBlock::from_block(BeaconBlock::Altair(block), Signature::empty());
+        let bytes = signed_block.as_ssz_bytes();
+
+        let view = SignedBeaconBlockSszView::new(&bytes).unwrap();
+        assert_eq!(view.slot(), signed_block.slot());
+        assert_eq!(
+            view.proposer_index(),
+            signed_block.message().proposer_index()
+        );
+        assert_eq!(view.parent_root().unwrap(), signed_block.parent_root());
+        assert_eq!(view.state_root().unwrap(), signed_block.state_root());
+        assert_eq!(
+            view.signature_bytes().unwrap().serialize(),
+            signed_block.signature().serialize()
+        );
+        assert_eq!(
+            view.message_bytes(),
+            signed_block.message().as_ssz_bytes().as_slice()
+        );
+        assert_eq!(view.to_signed_block::<E>(&spec).unwrap(), signed_block);
+    }
+
+    #[test]
+    fn signed_block_view_invalid() {
+        assert!(SignedBeaconBlockSszView::new(&[]).is_err());
+
+        // A valid outer container whose message is too short.
+        let mut bytes = (SIGNED_BEACON_BLOCK_FIXED_LEN as u32).as_ssz_bytes();
+        bytes.resize(
+            SIGNED_BEACON_BLOCK_FIXED_LEN + BEACON_BLOCK_FIXED_LEN - 1,
+            0,
+        );
+        assert!(SignedBeaconBlockSszView::new(&bytes).is_err());
+    }
+}
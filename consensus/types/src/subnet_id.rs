@@ -1,8 +1,13 @@
 //! Identifies each shard by an integer identifier.
-use crate::{AttestationData, ChainSpec, CommitteeIndex, EthSpec, Slot};
+use crate::{AttestationData, ChainSpec, CommitteeIndex, Epoch, EthSpec, Slot, Uint256};
+use eth2_hashing::hash;
 use safe_arith::{ArithError, SafeArith};
 use serde_derive::{Deserialize, Serialize};
 use std::ops::{Deref, DerefMut};
+use swap_or_not_shuffle::compute_shuffled_index;
+
+/// The number of bits in a discv5 node id.
+const NODE_ID_BITS: u32 = 256;
 
 const MAX_SUBNET_ID: usize = 64;
 
@@ -71,6 +76,48 @@ impl SubnetId {
             .safe_rem(spec.attestation_subnet_count)?
             .into())
     }
+
+    /// Compute the long-lived subnets a node with `node_id` should be subscribed to at `epoch`,
+    /// as per `compute_subscribed_subnets` in the p2p spec.
+    ///
+    /// Also returns the first epoch at which the subnets change. Nodes' subscriptions are offset
+    /// by their node id so that the network doesn't rotate its subnets all at once.
+    pub fn compute_subnets_for_epoch(
+        node_id: Uint256,
+        epoch: Epoch,
+        spec: &ChainSpec,
+    ) -> Result<(impl Iterator<Item = SubnetId>, Epoch), &'static str> {
+        let prefix_bits = spec.attestation_subnet_prefix_bits();
+        if prefix_bits == 0 || prefix_bits >= usize::BITS {
+            return Err("Invalid attestation subnet prefix bits");
+        }
+        let node_id_prefix = (node_id >> (NODE_ID_BITS - prefix_bits) as usize).as_usize();
+
+        let epochs_per_subscription = spec.epochs_per_subnet_subscription;
+        let node_offset = (node_id % Uint256::from(epochs_per_subscription)).as_u64();
+        let subscription_index = epoch
+            .as_u64()
+            .safe_add(node_offset)
+            .map_err(|_| "Epoch overflow")?
+            / epochs_per_subscription;
+        let permutation_seed = hash(&subscription_index.to_le_bytes());
+
+        let permutated_prefix = compute_shuffled_index(
+            node_id_prefix,
+            1 << prefix_bits,
+            &permutation_seed,
+            spec.shuffle_round_count,
+        )
+        .ok_or("Unable to shuffle node id prefix")? as u64;
+
+        let subnet_count = spec.attestation_subnet_count;
+        let subnets = (0..spec.subnets_per_node as u64)
+            .map(move |index| SubnetId::new((permutated_prefix + index) % subnet_count));
+
+        let valid_until = (subscription_index + 1) * epochs_per_subscription - node_offset;
+
+        Ok((subnets, Epoch::new(valid_until)))
+    }
 }
 
 impl Deref for SubnetId {
@@ -110,3 +157,77 @@ impl AsRef<str> for SubnetId {
         subnet_id_to_string(self.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node_ids() -> Vec<Uint256> {
+        [
+            "0",
+            "1",
+            "0x9e3779b97f4a7c15f39cc0605cedc8341082276bf3a27251f86c6a11d0c18e95",
+            "0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        ]
+        .iter()
+        .map(|id| {
+            if let Some(hex) = id.strip_prefix("0x") {
+                Uint256::from_str_radix(hex, 16).unwrap()
+            } else {
+                Uint256::from_dec_str(id).unwrap()
+            }
+        })
+        .collect()
+    }
+
+    #[test]
+    fn compute_subnets_for_epoch_is_stable_until_rotation() {
+        let spec = ChainSpec::mainnet();
+
+        for node_id in node_ids() {
+            let epoch = Epoch::new(54_321);
+            let (subnets, valid_until) =
+                SubnetId::compute_subnets_for_epoch(node_id, epoch, &spec).unwrap();
+            let subnets = subnets.collect::<Vec<_>>();
+
+            assert_eq!(subnets.len(), spec.subnets_per_node as usize);
+            assert!(subnets
+                .iter()
+                .all(|subnet| **subnet < spec.attestation_subnet_count));
+            // Consecutive subnets, wrapping around.
+            assert_eq!(
+                *subnets[1],
+                (*subnets[0] + 1) % spec.attestation_subnet_count
+            );
+
+            assert!(valid_until > epoch);
+            assert!(valid_until <= epoch + spec.epochs_per_subnet_subscription);
+
+            // The subnets don't change until `valid_until`.
+            let (last_subnets, last_valid_until) =
+                SubnetId::compute_subnets_for_epoch(node_id, valid_until - 1, &spec).unwrap();
+            assert_eq!(last_subnets.collect::<Vec<_>>(), subnets);
+            assert_eq!(last_valid_until, valid_until);
+
+            // The next subscription period lasts for a full `epochs_per_subnet_subscription`.
+            let (_, next_valid_until) =
+                SubnetId::compute_subnets_for_epoch(node_id, valid_until, &spec).unwrap();
+            assert_eq!(
+                next_valid_until,
+                valid_until + spec.epochs_per_subnet_subscription
+            );
+        }
+    }
+
+    #[test]
+    fn attestation_subnet_prefix_bits() {
+        let mut spec = ChainSpec::mainnet();
+        assert_eq!(spec.attestation_subnet_prefix_bits(), 6);
+
+        spec.attestation_subnet_count = 65;
+        assert_eq!(spec.attestation_subnet_prefix_bits(), 7);
+
+        spec.attestation_subnet_extra_bits = 2;
+        assert_eq!(spec.attestation_subnet_prefix_bits(), 9);
+    }
+}
@@ -73,13 +73,14 @@ impl<T: EthSpec> SyncCommittee<T> {
         let mut subnet_positions = HashMap::new();
         for (committee_index, validator_pubkey) in self.pubkeys.iter().enumerate() {
             if pubkey == validator_pubkey {
-                let subcommittee_index = committee_index.safe_div(T::sync_subcommittee_size())?;
+                let committee_index = committee_index as u64;
+                let subnet_id = SyncSubnetId::for_sync_committee_index::<T>(committee_index)?;
                 let position_in_subcommittee =
-                    committee_index.safe_rem(T::sync_subcommittee_size())?;
+                    SyncSubnetId::position_in_subcommittee::<T>(committee_index)?;
                 subnet_positions
-                    .entry(SyncSubnetId::new(subcommittee_index as u64))
+                    .entry(subnet_id)
                     .or_insert_with(Vec::new)
-                    .push(position_in_subcommittee);
+                    .push(position_in_subcommittee as usize);
             }
         }
         Ok(subnet_positions)
@@ -39,15 +39,29 @@ impl SyncSubnetId {
         id.into()
     }
 
+    /// Compute the subnet of the subcommittee containing the given sync committee index.
+    pub fn for_sync_committee_index<T: EthSpec>(
+        sync_committee_index: u64,
+    ) -> Result<Self, ArithError> {
+        sync_committee_index
+            .safe_div(T::SyncSubcommitteeSize::to_u64())
+            .map(Self::new)
+    }
+
+    /// Compute the position of the given sync committee index within its subcommittee.
+    pub fn position_in_subcommittee<T: EthSpec>(
+        sync_committee_index: u64,
+    ) -> Result<u64, ArithError> {
+        sync_committee_index.safe_rem(T::SyncSubcommitteeSize::to_u64())
+    }
+
     /// Compute required subnets to subscribe to given the sync committee indices.
     pub fn compute_subnets_for_sync_committee<T: EthSpec>(
         sync_committee_indices: &[u64],
     ) -> Result<HashSet<Self>, ArithError> {
-        let subcommittee_size = T::SyncSubcommitteeSize::to_u64();
-
         sync_committee_indices
             .iter()
-            .map(|index| index.safe_div(subcommittee_size).map(Self::new))
+            .map(|&index| Self::for_sync_committee_index::<T>(index))
             .collect()
     }
 }
@@ -95,3 +109,41 @@ impl AsRef<str> for SyncSubnetId {
         sync_subnet_id_to_string(self.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MainnetEthSpec;
+
+    type E = MainnetEthSpec;
+
+    #[test]
+    fn subcommittee_assignment() {
+        let subcommittee_size = <E as EthSpec>::SyncSubcommitteeSize::to_u64();
+        assert_eq!(subcommittee_size, 128);
+
+        for (index, subnet_id, position) in [(0, 0, 0), (127, 0, 127), (128, 1, 0), (511, 3, 127)] {
+            assert_eq!(
+                SyncSubnetId::for_sync_committee_index::<E>(index),
+                Ok(SyncSubnetId::new(subnet_id))
+            );
+            assert_eq!(
+                SyncSubnetId::position_in_subcommittee::<E>(index),
+                Ok(position)
+            );
+        }
+
+        let subnets =
+            SyncSubnetId::compute_subnets_for_sync_committee::<E>(&[1, 2, 300, 400]).unwrap();
+        assert_eq!(
+            subnets,
+            [
+                SyncSubnetId::new(0),
+                SyncSubnetId::new(2),
+                SyncSubnetId::new(3)
+            ]
+            .into_iter()
+            .collect()
+        );
+    }
+}
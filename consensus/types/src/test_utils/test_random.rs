@@ -117,6 +117,15 @@ where
     }
 }
 
+impl<T, N: Unsigned> TestRandom for PersistentList<T, N>
+where
+    T: TestRandom,
+{
+    fn random_for_test(rng: &mut impl RngCore) -> Self {
+        VariableList::<T, N>::random_for_test(rng).into()
+    }
+}
+
 macro_rules! impl_test_random_for_u8_array {
     ($len: expr) => {
         impl TestRandom for [u8; $len] {
@@ -1,5 +1,6 @@
 use crate::{
-    test_utils::TestRandom, BeaconState, ChainSpec, Epoch, EthSpec, Hash256, PublicKeyBytes,
+    test_utils::TestRandom, Address, BeaconState, ChainSpec, Epoch, EthSpec, Hash256,
+    PublicKeyBytes,
 };
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
@@ -65,6 +66,42 @@ impl Validator {
         // Has not yet been activated
         && self.activation_epoch == spec.far_future_epoch
     }
+
+    /// Returns `true` if the validator has an `0x01` (eth1 address) withdrawal credential.
+    pub fn has_eth1_withdrawal_credential(&self, spec: &ChainSpec) -> bool {
+        self.withdrawal_credentials
+            .as_bytes()
+            .first()
+            .map(|byte| *byte == spec.eth1_address_withdrawal_prefix_byte)
+            .unwrap_or(false)
+    }
+
+    /// Returns the execution layer address encoded in the withdrawal credentials, if the
+    /// validator has an `0x01` withdrawal credential.
+    pub fn get_eth1_withdrawal_address(&self, spec: &ChainSpec) -> Option<Address> {
+        self.has_eth1_withdrawal_credential(spec)
+            .then(|| Address::from_slice(&self.withdrawal_credentials.as_bytes()[12..]))
+    }
+
+    /// Changes the withdrawal credentials to the `0x01` credentials for `execution_address`.
+    pub fn change_withdrawal_credentials(&mut self, execution_address: &Address, spec: &ChainSpec) {
+        let mut bytes = [0u8; 32];
+        bytes[0] = spec.eth1_address_withdrawal_prefix_byte;
+        bytes[12..].copy_from_slice(execution_address.as_bytes());
+        self.withdrawal_credentials = Hash256::from(bytes);
+    }
+
+    /// Returns `true` if the entire balance of the validator can be withdrawn at `epoch`.
+    pub fn is_fully_withdrawable_at(&self, balance: u64, epoch: Epoch, spec: &ChainSpec) -> bool {
+        self.has_eth1_withdrawal_credential(spec) && self.withdrawable_epoch <= epoch && balance > 0
+    }
+
+    /// Returns `true` if the balance in excess of `max_effective_balance` can be withdrawn.
+    pub fn is_partially_withdrawable_validator(&self, balance: u64, spec: &ChainSpec) -> bool {
+        self.has_eth1_withdrawal_credential(spec)
+            && self.effective_balance == spec.max_effective_balance
+            && balance > spec.max_effective_balance
+    }
 }
 
 impl Default for Validator {
@@ -141,5 +178,29 @@ mod tests {
         assert!(v.is_withdrawable_at(epoch + 1));
     }
 
+    #[test]
+    fn eth1_withdrawal_address() {
+        let spec = ChainSpec::mainnet();
+        let address = Address::repeat_byte(0x42);
+
+        let mut credentials = [0; 32];
+        credentials[0] = spec.eth1_address_withdrawal_prefix_byte;
+        credentials[12..].copy_from_slice(address.as_bytes());
+
+        let v = Validator {
+            withdrawal_credentials: Hash256::from(credentials),
+            ..Validator::default()
+        };
+        assert!(v.has_eth1_withdrawal_credential(&spec));
+        assert_eq!(v.get_eth1_withdrawal_address(&spec), Some(address));
+
+        let mut v = Validator::default();
+        assert!(!v.has_eth1_withdrawal_credential(&spec));
+        assert_eq!(v.get_eth1_withdrawal_address(&spec), None);
+
+        v.change_withdrawal_credentials(&address, &spec);
+        assert_eq!(v.withdrawal_credentials, Hash256::from(credentials));
+    }
+
     ssz_and_tree_hash_tests!(Validator);
 }
@@ -0,0 +1,145 @@
+use crate::test_utils::TestRandom;
+use crate::*;
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use test_random_derive::TestRandom;
+use tree_hash_derive::TreeHash;
+
+/// A validator's registration with a builder, which states the fee recipient and gas limit to use
+/// when building payloads for it.
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom,
+)]
+pub struct ValidatorRegistrationData {
+    pub fee_recipient: Address,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub gas_limit: u64,
+    /// The time of the registration, in seconds since the UNIX epoch. Builders use this to prefer
+    /// the most recent registration of each validator.
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub timestamp: u64,
+    pub pubkey: PublicKeyBytes,
+}
+
+impl SignedRoot for ValidatorRegistrationData {}
+
+impl ValidatorRegistrationData {
+    /// Sign the registration with the validator's signing key.
+    pub fn sign(self, secret_key: &SecretKey, spec: &ChainSpec) -> SignedValidatorRegistrationData {
+        let message = self.signing_root(spec.get_builder_domain());
+        SignedValidatorRegistrationData {
+            message: self,
+            signature: secret_key.sign(message),
+        }
+    }
+
+    /// Returns `true` if `signature` is a valid signature of `self` by `self.pubkey`.
+    ///
+    /// Registrations are signed in the builder domain, which is computed from the genesis fork
+    /// version and is therefore the same for all forks.
+    pub fn verify_signature(&self, signature: &Signature, spec: &ChainSpec) -> bool {
+        self.pubkey
+            .decompress()
+            .map(|pubkey| signature.verify(&pubkey, self.signing_root(spec.get_builder_domain())))
+            .unwrap_or(false)
+    }
+}
+
+/// A `ValidatorRegistrationData` signed by the validator.
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom,
+)]
+pub struct SignedValidatorRegistrationData {
+    pub message: ValidatorRegistrationData,
+    pub signature: Signature,
+}
+
+impl SignedValidatorRegistrationData {
+    /// Returns `true` if the registration is validly signed by the validator it registers.
+    pub fn verify_signature(&self, spec: &ChainSpec) -> bool {
+        self.message.verify_signature(&self.signature, spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::generate_deterministic_keypair;
+
+    ssz_and_tree_hash_tests!(ValidatorRegistrationData);
+
+    fn registration(keypair: &Keypair) -> ValidatorRegistrationData {
+        ValidatorRegistrationData {
+            fee_recipient: Address::repeat_byte(42),
+            gas_limit: 30_000_000,
+            timestamp: 1_650_000_000,
+            pubkey: keypair.pk.compress(),
+        }
+    }
+
+    #[test]
+    fn valid_signature() {
+        let spec = MainnetEthSpec::default_spec();
+        let keypair = generate_deterministic_keypair(0);
+
+        let signed = registration(&keypair).sign(&keypair.sk, &spec);
+        assert!(signed.verify_signature(&spec));
+    }
+
+    #[test]
+    fn signature_valid_across_forks() {
+        let keypair = generate_deterministic_keypair(0);
+        let signed = registration(&keypair).sign(
+            &keypair.sk,
+            &ForkName::Base.make_genesis_spec(MainnetEthSpec::default_spec()),
+        );
+
+        for fork_name in ForkName::list_all() {
+            let spec = fork_name.make_genesis_spec(MainnetEthSpec::default_spec());
+            assert!(signed.verify_signature(&spec), "{}", fork_name);
+        }
+    }
+
+    #[test]
+    fn invalid_signatures() {
+        let spec = MainnetEthSpec::default_spec();
+        let keypair = generate_deterministic_keypair(0);
+        let signed = registration(&keypair).sign(&keypair.sk, &spec);
+
+        // Changed message.
+        let mut changed = signed.clone();
+        changed.message.gas_limit += 1;
+        assert!(!changed.verify_signature(&spec));
+
+        // Signed by another key.
+        let other_keypair = generate_deterministic_keypair(1);
+        let other = SignedValidatorRegistrationData {
+            message: signed.message.clone(),
+            signature: other_keypair
+                .sk
+                .sign(signed.message.signing_root(spec.get_builder_domain())),
+        };
+        assert!(!other.verify_signature(&spec));
+
+        // Signed in the wrong domain.
+        let deposit_domain = SignedValidatorRegistrationData {
+            message: signed.message.clone(),
+            signature: keypair
+                .sk
+                .sign(signed.message.signing_root(spec.get_deposit_domain())),
+        };
+        assert!(!deposit_domain.verify_signature(&spec));
+
+        // Different genesis fork version.
+        let mut other_spec = spec.clone();
+        other_spec.genesis_fork_version = [0, 0, 16, 32];
+        assert!(!signed.verify_signature(&other_spec));
+
+        // Invalid pubkey.
+        let mut invalid_pubkey = signed;
+        invalid_pubkey.message.pubkey = PublicKeyBytes::empty();
+        assert!(!invalid_pubkey.verify_signature(&spec));
+    }
+}
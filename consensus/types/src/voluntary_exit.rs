@@ -1,5 +1,6 @@
+use crate::signing_domain::get_voluntary_exit_domain;
 use crate::{
-    test_utils::TestRandom, ChainSpec, Domain, Epoch, Fork, Hash256, SecretKey, SignedRoot,
+    test_utils::TestRandom, ChainSpec, Epoch, Fork, Hash256, SecretKey, SignedRoot,
     SignedVoluntaryExit,
 };
 
@@ -32,12 +33,7 @@ impl VoluntaryExit {
         genesis_validators_root: Hash256,
         spec: &ChainSpec,
     ) -> SignedVoluntaryExit {
-        let domain = spec.get_domain(
-            self.epoch,
-            Domain::VoluntaryExit,
-            fork,
-            genesis_validators_root,
-        );
+        let domain = get_voluntary_exit_domain(self.epoch, fork, genesis_validators_root, spec);
         let message = self.signing_root(domain);
         SignedVoluntaryExit {
             message: self,
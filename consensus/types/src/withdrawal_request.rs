@@ -0,0 +1,38 @@
+use crate::test_utils::TestRandom;
+use crate::{Address, PublicKeyBytes};
+
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use test_random_derive::TestRandom;
+use tree_hash_derive::TreeHash;
+
+/// A withdrawal or exit triggered from the execution layer by a validator's withdrawal address.
+///
+/// An `amount` of `FULL_EXIT_REQUEST_AMOUNT` requests a full exit, any other amount requests a
+/// partial withdrawal.
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(
+    Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom,
+)]
+pub struct WithdrawalRequest {
+    pub source_address: Address,
+    pub validator_pubkey: PublicKeyBytes,
+    #[serde(with = "eth2_serde_utils::quoted_u64")]
+    pub amount: u64,
+}
+
+impl WithdrawalRequest {
+    /// The `amount` which signals a request for a full exit.
+    pub const FULL_EXIT_REQUEST_AMOUNT: u64 = 0;
+
+    pub fn is_full_exit_request(&self) -> bool {
+        self.amount == Self::FULL_EXIT_REQUEST_AMOUNT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ssz_and_tree_hash_tests!(WithdrawalRequest);
+}
@@ -0,0 +1,230 @@
+//! Round-trip property tests of the SSZ, JSON and tree hash encodings of blocks, attestations and
+//! execution payloads at every fork.
+//!
+//! Each property is checked against many randomly generated values for every fork in
+//! `ForkName::list_all`. The generators match exhaustively on `ForkName`, so adding a fork
+//! variant fails to compile until it is covered here.
+use ssz::{Decode, Encode};
+use std::fmt::Debug;
+use tree_hash::TreeHash;
+use types::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+use types::{
+    Attestation, BeaconBlock, BeaconBlockAltair, BeaconBlockBase, BeaconBlockBodyCapella,
+    BeaconBlockBodyDeneb, BeaconBlockBodyMerge, BeaconBlockCapella, BeaconBlockDeneb,
+    BeaconBlockMerge, ChainSpec, EthSpec, ExecutionPayload, ExecutionPayloadHeader, ForkName,
+    ForkVersionDeserialize, MainnetEthSpec, Signature, SignedAggregateAndProof, SignedBeaconBlock,
+    SignedBlindedBeaconBlock, Slot,
+};
+
+type E = MainnetEthSpec;
+
+/// The number of random values checked for each fork.
+const CASES_PER_FORK: u8 = 32;
+
+/// Returns a deterministic RNG for the `case`th value at `fork`, so failures are reproducible.
+fn rng_for_case(fork: ForkName, case: u8) -> XorShiftRng {
+    let mut seed = [case; 16];
+    seed[0] = fork as u8;
+    XorShiftRng::from_seed(seed)
+}
+
+fn for_all_cases(mut test: impl FnMut(ForkName, &ChainSpec, &mut XorShiftRng)) {
+    for fork in ForkName::list_all() {
+        let spec = fork.make_genesis_spec(E::default_spec());
+        for case in 0..CASES_PER_FORK {
+            test(fork, &spec, &mut rng_for_case(fork, case));
+        }
+    }
+}
+
+fn random_payload(fork: ForkName, rng: &mut XorShiftRng) -> Option<ExecutionPayload<E>> {
+    match fork {
+        ForkName::Base | ForkName::Altair => None,
+        ForkName::Merge => Some(ExecutionPayload::Merge(<_>::random_for_test(rng))),
+        ForkName::Capella => Some(ExecutionPayload::Capella(<_>::random_for_test(rng))),
+        ForkName::Deneb => Some(ExecutionPayload::Deneb(<_>::random_for_test(rng))),
+    }
+}
+
+fn random_block(fork: ForkName, rng: &mut XorShiftRng) -> BeaconBlock<E> {
+    // Keep the slot within the range of an epoch which can be computed without overflow.
+    let slot = Slot::new(u64::random_for_test(rng) >> 8);
+    let payload = random_payload(fork, rng);
+    let payload = || {
+        payload
+            .expect("fork should have an execution payload")
+            .into()
+    };
+
+    match fork {
+        ForkName::Base => BeaconBlock::Base(BeaconBlockBase {
+            slot,
+            ..<_>::random_for_test(rng)
+        }),
+        ForkName::Altair => BeaconBlock::Altair(BeaconBlockAltair {
+            slot,
+            ..<_>::random_for_test(rng)
+        }),
+        ForkName::Merge => BeaconBlock::Merge(BeaconBlockMerge {
+            slot,
+            body: BeaconBlockBodyMerge {
+                execution_payload: payload(),
+                ..<_>::random_for_test(rng)
+            },
+            ..<_>::random_for_test(rng)
+        }),
+        ForkName::Capella => BeaconBlock::Capella(BeaconBlockCapella {
+            slot,
+            body: BeaconBlockBodyCapella {
+                execution_payload: payload(),
+                ..<_>::random_for_test(rng)
+            },
+            ..<_>::random_for_test(rng)
+        }),
+        ForkName::Deneb => BeaconBlock::Deneb(BeaconBlockDeneb {
+            slot,
+            body: BeaconBlockBodyDeneb {
+                execution_payload: payload(),
+                ..<_>::random_for_test(rng)
+            },
+            ..<_>::random_for_test(rng)
+        }),
+    }
+}
+
+/// Checks that `value` survives an SSZ round trip, and that the encoding is stable.
+fn assert_ssz_round_trip<T: Encode + Debug + PartialEq>(
+    value: &T,
+    decoder: impl FnOnce(&[u8]) -> Result<T, ssz::DecodeError>,
+    context: &str,
+) {
+    let bytes = value.as_ssz_bytes();
+    assert_eq!(value.ssz_bytes_len(), bytes.len(), "{}", context);
+
+    let decoded = decoder(&bytes).unwrap_or_else(|e| panic!("{}: {:?}", context, e));
+    assert_eq!(&decoded, value, "{}", context);
+    assert_eq!(decoded.as_ssz_bytes(), bytes, "{}", context);
+}
+
+/// Checks that `value` survives a JSON round trip when deserialized for `fork`.
+fn assert_json_round_trip<T: ForkVersionDeserialize + serde::Serialize + Debug + PartialEq>(
+    value: &T,
+    fork: ForkName,
+    context: &str,
+) {
+    let json = serde_json::to_value(value).unwrap();
+    let decoded = T::deserialize_by_fork(json.clone(), fork)
+        .unwrap_or_else(|e| panic!("{}: {:?}", context, e));
+    assert_eq!(&decoded, value, "{}", context);
+    assert_eq!(serde_json::to_value(&decoded).unwrap(), json, "{}", context);
+}
+
+#[test]
+fn signed_block_round_trips() {
+    for_all_cases(|fork, spec, rng| {
+        let block = random_block(fork, rng);
+        let signed_block =
+            SignedBeaconBlock::from_block(block.clone(), Signature::random_for_test(rng));
+        let context = format!("{} block at slot {}", fork, block.slot());
+
+        assert_eq!(signed_block.fork_name(spec).unwrap(), fork, "{}", context);
+        assert_ssz_round_trip(
+            &signed_block,
+            |bytes| SignedBeaconBlock::from_ssz_bytes(bytes, spec),
+            &context,
+        );
+        assert_ssz_round_trip(
+            &block,
+            |bytes| BeaconBlock::from_ssz_bytes(bytes, spec),
+            &context,
+        );
+        assert_json_round_trip(&signed_block, fork, &context);
+        assert_json_round_trip(&block, fork, &context);
+    });
+}
+
+#[test]
+fn block_tree_hash_roots_agree() {
+    for_all_cases(|fork, spec, rng| {
+        let block = random_block(fork, rng);
+        let context = format!("{} block at slot {}", fork, block.slot());
+        let root = block.tree_hash_root();
+
+        // The root of a block is the root of its header.
+        assert_eq!(block.block_header().tree_hash_root(), root, "{}", context);
+
+        // The root is unchanged by an SSZ round trip.
+        let decoded = BeaconBlock::<E>::from_ssz_bytes(&block.as_ssz_bytes(), spec).unwrap();
+        assert_eq!(decoded.tree_hash_root(), root, "{}", context);
+
+        // A blinded block has the same root as the full block, and round trips by itself.
+        let signed_block = SignedBeaconBlock::from_block(block, Signature::empty());
+        let blinded_block = SignedBlindedBeaconBlock::from(signed_block);
+        assert_eq!(blinded_block.canonical_root(), root, "{}", context);
+        assert_ssz_round_trip(
+            &blinded_block,
+            |bytes| SignedBlindedBeaconBlock::from_ssz_bytes(bytes, spec),
+            &context,
+        );
+        assert_json_round_trip(&blinded_block, fork, &context);
+    });
+}
+
+#[test]
+fn execution_payload_round_trips() {
+    for_all_cases(|fork, _, rng| {
+        let payload = match random_payload(fork, rng) {
+            Some(payload) => payload,
+            None => return,
+        };
+        let context = format!("{} payload {:?}", fork, payload.block_hash());
+
+        // Payloads are decoded without knowledge of their fork.
+        assert_ssz_round_trip(&payload, ExecutionPayload::from_ssz_bytes, &context);
+        assert_json_round_trip(&payload, fork, &context);
+
+        let header = ExecutionPayloadHeader::from(&payload);
+        assert_eq!(header.fork_name(), fork, "{}", context);
+        assert_eq!(
+            header.tree_hash_root(),
+            payload.tree_hash_root(),
+            "{}",
+            context
+        );
+        assert_ssz_round_trip(&header, ExecutionPayloadHeader::from_ssz_bytes, &context);
+    });
+}
+
+/// Attestations are the same at every fork, so are only checked once per case.
+#[test]
+fn attestation_round_trips() {
+    for case in 0..CASES_PER_FORK {
+        let rng = &mut rng_for_case(ForkName::Base, case);
+        let attestation = Attestation::<E>::random_for_test(rng);
+        let context = format!("attestation {:?}", attestation.data);
+
+        assert_ssz_round_trip(&attestation, Attestation::from_ssz_bytes, &context);
+        let json = serde_json::to_string(&attestation).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Attestation<E>>(&json).unwrap(),
+            attestation,
+            "{}",
+            context
+        );
+
+        let aggregate = SignedAggregateAndProof::<E>::random_for_test(rng);
+        assert_ssz_round_trip(
+            &aggregate,
+            SignedAggregateAndProof::from_ssz_bytes,
+            &context,
+        );
+        assert_eq!(
+            SignedAggregateAndProof::<E>::from_ssz_bytes(&aggregate.as_ssz_bytes())
+                .unwrap()
+                .tree_hash_root(),
+            aggregate.tree_hash_root(),
+            "{}",
+            context
+        );
+    }
+}
@@ -1,18 +1,24 @@
 use beacon_chain::{
-    builder::Witness, eth1_chain::CachingEth1Backend, schema_change::migrate_schema,
+    builder::Witness,
+    eth1_chain::CachingEth1Backend,
+    schema_change::{migrate_schema, migration_plan},
     slot_clock::SystemTimeSlotClock,
 };
 use beacon_node::{get_data_dir, get_slots_per_restore_point, ClientConfig};
 use clap::{App, Arg, ArgMatches};
 use environment::{Environment, RuntimeContext};
-use slog::{info, Logger};
+use slog::{info, warn, Logger};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 use store::{
     errors::Error,
     metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION},
-    DBColumn, HotColdDB, KeyValueStore, LevelDB,
+    ColumnIter, DBColumn, FreezerBackend, FreezerDB, HotBackend, HotColdDB, HotDB, KeyValueStore,
+    StoreOp,
 };
-use strum::{EnumString, EnumVariantNames, VariantNames};
-use types::EthSpec;
+use strum::{EnumString, EnumVariantNames, IntoEnumIterator, VariantNames};
+use types::{EthSpec, Slot};
 
 pub const CMD: &str = "database_manager";
 
@@ -35,6 +41,15 @@ pub fn migrate_cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .required(true),
         )
+        .arg(
+            Arg::with_name("dry-run")
+                .long("dry-run")
+                .help(
+                    "Check that the migration is supported and estimate the time and disk space \
+                     it requires, without modifying the database",
+                )
+                .takes_value(false),
+        )
 }
 
 pub fn inspect_cli_app<'a, 'b>() -> App<'a, 'b> {
@@ -59,6 +74,115 @@ pub fn inspect_cli_app<'a, 'b>() -> App<'a, 'b> {
         )
 }
 
+pub fn info_cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new("info")
+        .setting(clap::AppSettings::ColoredHelp)
+        .about("Display the schema version, split and anchor of the database")
+}
+
+pub fn columns_cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new("columns")
+        .setting(clap::AppSettings::ColoredHelp)
+        .about(
+            "Display the number of items and total size of each column of the hot and freezer \
+             databases. This reads the whole database, so may take some time.",
+        )
+}
+
+pub fn verify_cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new("verify")
+        .setting(clap::AppSettings::ColoredHelp)
+        .about(
+            "Check that the blocks in a range of slots can be decoded and that their states are \
+             known to the database",
+        )
+        .arg(
+            Arg::with_name("start-slot")
+                .long("start-slot")
+                .value_name("SLOT")
+                .help("The first slot to check [default: 0]")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("end-slot")
+                .long("end-slot")
+                .value_name("SLOT")
+                .help("The last slot to check [default: the latest block]")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("delete-corrupt")
+                .long("delete-corrupt")
+                .help(
+                    "Delete the blocks and hot state summaries which can't be decoded or don't \
+                     match their keys. The node may need to resync the deleted blocks.",
+                )
+                .takes_value(false),
+        )
+}
+
+pub fn convert_state_diffs_cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new("convert-state-diffs")
+        .setting(clap::AppSettings::ColoredHelp)
+        .about("Rewrite the restore points stored in full in the freezer database as state diffs")
+}
+
+pub fn prune_states_cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new("prune-states")
+        .setting(clap::AppSettings::ColoredHelp)
+        .about("Delete historic states from the freezer database")
+        .arg(
+            Arg::with_name("history-retention-epochs")
+                .long("history-retention-epochs")
+                .value_name("EPOCHS")
+                .help(
+                    "Keep the states from this many epochs prior to the latest finalized epoch. \
+                     By default all historic states other than the genesis state are deleted.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("prune-blocks")
+                .long("prune-blocks")
+                .help("Also delete the blocks prior to the history retention window.")
+                .requires("history-retention-epochs")
+                .takes_value(false),
+        )
+}
+
+pub fn backup_cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new("backup")
+        .setting(clap::AppSettings::ColoredHelp)
+        .about(
+            "Copy the database to a new directory. The beacon node must be stopped. Immutable \
+             database files are hard-linked where possible, so the backup should be moved to \
+             another disk if it's intended to survive a disk failure.",
+        )
+        .arg(
+            Arg::with_name("backup-dir")
+                .value_name("DIR")
+                .help("Directory to write the backup to, which must not already contain files")
+                .index(1)
+                .required(true),
+        )
+}
+
+pub fn restore_cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new("restore")
+        .setting(clap::AppSettings::ColoredHelp)
+        .about(
+            "Restore the database from a backup created with `lighthouse db backup`. The \
+             database directories of the datadir must not already contain files.",
+        )
+        .arg(
+            Arg::with_name("backup-dir")
+                .value_name("DIR")
+                .help("Directory containing the backup")
+                .index(1)
+                .required(true),
+        )
+}
+
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
     App::new(CMD)
         .visible_aliases(&["db"])
@@ -75,6 +199,24 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 )
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("restore-points-per-snapshot")
+                .long("restore-points-per-snapshot")
+                .value_name("COUNT")
+                .help(
+                    "Specifies how often a freezer DB restore point should be stored in full. \
+                       [default: 32]",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("hot-db-backend")
+                .long("hot-db-backend")
+                .value_name("BACKEND")
+                .help("The key-value store used by the hot database.")
+                .possible_values(HotBackend::VARIANTS)
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("freezer-dir")
                 .long("freezer-dir")
@@ -82,9 +224,24 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("Data directory for the freezer database.")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("freezer-backend")
+                .long("freezer-backend")
+                .value_name("BACKEND")
+                .help("The key-value store used by the freezer database.")
+                .possible_values(FreezerBackend::VARIANTS)
+                .takes_value(true),
+        )
         .subcommand(migrate_cli_app())
         .subcommand(version_cli_app())
         .subcommand(inspect_cli_app())
+        .subcommand(info_cli_app())
+        .subcommand(columns_cli_app())
+        .subcommand(verify_cli_app())
+        .subcommand(convert_state_diffs_cli_app())
+        .subcommand(prune_states_cli_app())
+        .subcommand(backup_cli_app())
+        .subcommand(restore_cli_app())
 }
 
 fn parse_client_config<E: EthSpec>(
@@ -100,10 +257,24 @@ fn parse_client_config<E: EthSpec>(
         client_config.freezer_db_path = Some(freezer_dir);
     }
 
+    if let Some(hot_backend) = clap_utils::parse_optional(cli_args, "hot-db-backend")? {
+        client_config.store.hot_backend = hot_backend;
+    }
+
+    if let Some(freezer_backend) = clap_utils::parse_optional(cli_args, "freezer-backend")? {
+        client_config.store.freezer_backend = freezer_backend;
+    }
+
     let (sprp, sprp_explicit) = get_slots_per_restore_point::<E>(cli_args)?;
     client_config.store.slots_per_restore_point = sprp;
     client_config.store.slots_per_restore_point_set_explicitly = sprp_explicit;
 
+    if let Some(restore_points_per_snapshot) =
+        clap_utils::parse_optional(cli_args, "restore-points-per-snapshot")?
+    {
+        client_config.store.restore_points_per_snapshot = restore_points_per_snapshot;
+    }
+
     Ok(client_config)
 }
 
@@ -117,7 +288,7 @@ pub fn display_db_version<E: EthSpec>(
     let cold_path = client_config.get_freezer_db_path();
 
     let mut version = CURRENT_SCHEMA_VERSION;
-    HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+    HotColdDB::<E, HotDB<E>, FreezerDB<E>>::open(
         &hot_path,
         &cold_path,
         |_, from, _| {
@@ -142,6 +313,231 @@ pub fn display_db_version<E: EthSpec>(
     Ok(())
 }
 
+pub fn display_db_info<E: EthSpec>(
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), Error> {
+    let spec = runtime_context.eth2_config.spec.clone();
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+
+    let mut version = CURRENT_SCHEMA_VERSION;
+    let db = HotColdDB::<E, HotDB<E>, FreezerDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        |_, from, _| {
+            version = from;
+            Ok(())
+        },
+        client_config.store,
+        spec,
+        log.clone(),
+    )?;
+
+    info!(log, "Database version: {}", version.as_u64());
+    info!(log, "Split: {:?}", db.get_split_info());
+    match db.get_anchor_info() {
+        Some(anchor) => info!(
+            log,
+            "Anchor";
+            "anchor_slot" => anchor.anchor_slot,
+            "oldest_block_slot" => anchor.oldest_block_slot,
+            "oldest_block_parent" => ?anchor.oldest_block_parent,
+            "state_upper_limit" => anchor.state_upper_limit,
+            "state_lower_limit" => anchor.state_lower_limit,
+        ),
+        None => info!(
+            log,
+            "Anchor: none, all blocks and states are available from genesis"
+        ),
+    }
+    info!(log, "Config: {:?}", db.get_config());
+
+    Ok(())
+}
+
+/// Return the number of items in a column and their total size in bytes.
+fn column_totals(iter: ColumnIter) -> Result<(usize, usize), Error> {
+    let (mut count, mut total) = (0, 0);
+    for res in iter {
+        let (_, value) = res?;
+        count += 1;
+        total += value.len();
+    }
+    Ok((count, total))
+}
+
+pub fn display_db_columns<E: EthSpec>(
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), Error> {
+    let spec = runtime_context.eth2_config.spec.clone();
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+
+    let db = HotColdDB::<E, HotDB<E>, FreezerDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec,
+        log,
+    )?;
+
+    println!(
+        "{:<8} {:>12} {:>16} {:>12} {:>16}",
+        "Column", "Hot items", "Hot bytes", "Cold items", "Cold bytes"
+    );
+    for column in DBColumn::iter() {
+        let (hot_count, hot_total) = column_totals(db.hot_db.iter_column(column))?;
+        let (cold_count, cold_total) = column_totals(db.cold_db.iter_column(column))?;
+        if hot_count + cold_count > 0 {
+            println!(
+                "{:<8} {:>12} {:>16} {:>12} {:>16}",
+                column.as_str(),
+                hot_count,
+                hot_total,
+                cold_count,
+                cold_total
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub struct VerifyConfig {
+    start_slot: Slot,
+    end_slot: Option<Slot>,
+    delete_corrupt: bool,
+}
+
+fn parse_verify_config(cli_args: &ArgMatches) -> Result<VerifyConfig, String> {
+    let start_slot = clap_utils::parse_optional(cli_args, "start-slot")?.unwrap_or(Slot::new(0));
+    let end_slot = clap_utils::parse_optional(cli_args, "end-slot")?;
+    let delete_corrupt = cli_args.is_present("delete-corrupt");
+
+    Ok(VerifyConfig {
+        start_slot,
+        end_slot,
+        delete_corrupt,
+    })
+}
+
+pub fn verify_db<E: EthSpec>(
+    verify_config: VerifyConfig,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), Error> {
+    let spec = runtime_context.eth2_config.spec.clone();
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+
+    let db = HotColdDB::<E, HotDB<E>, FreezerDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec,
+        log.clone(),
+    )?;
+
+    let split_slot = db.get_split_slot();
+    let (lower_limit, upper_limit) = db.get_historic_state_limits();
+    let end_slot = verify_config
+        .end_slot
+        .unwrap_or_else(|| Slot::new(u64::MAX));
+
+    let mut blocks_checked = 0;
+    let mut missing_states = 0;
+    let mut corrupt_ops = vec![];
+
+    for res in db.hot_db.iter_column_keys(DBColumn::BeaconBlock) {
+        let block_root = res?;
+        let block = match db.get_blinded_block(&block_root) {
+            Ok(Some(block)) => block,
+            Ok(None) => continue,
+            Err(e) => {
+                // The slot of an undecodable block is unknown, so it's reported whatever the range.
+                warn!(log, "Unable to decode block"; "block_root" => ?block_root, "error" => ?e);
+                corrupt_ops.push(StoreOp::DeleteBlock(block_root));
+                corrupt_ops.push(StoreOp::DeleteExecutionPayload(block_root));
+                continue;
+            }
+        };
+        let slot = block.slot();
+        if slot < verify_config.start_slot || slot > end_slot {
+            continue;
+        }
+        blocks_checked += 1;
+
+        if block.canonical_root() != block_root {
+            warn!(
+                log,
+                "Block doesn't match its root";
+                "block_root" => ?block_root,
+                "computed_root" => ?block.canonical_root(),
+                "slot" => slot,
+            );
+            corrupt_ops.push(StoreOp::DeleteBlock(block_root));
+            corrupt_ops.push(StoreOp::DeleteExecutionPayload(block_root));
+            continue;
+        }
+
+        // Frozen states are absent between the historic state limits, e.g. after checkpoint sync.
+        let state_root = block.state_root();
+        let state_expected = slot >= split_slot || slot <= lower_limit || slot >= upper_limit;
+        match db.load_state_slot(&state_root) {
+            Ok(Some(state_slot)) if state_slot == slot => (),
+            Ok(None) if !state_expected => (),
+            Ok(state_slot) => {
+                warn!(
+                    log,
+                    "Block state is missing or at the wrong slot";
+                    "block_root" => ?block_root,
+                    "state_root" => ?state_root,
+                    "slot" => slot,
+                    "state_slot" => ?state_slot,
+                );
+                missing_states += 1;
+            }
+            Err(e) => {
+                warn!(
+                    log,
+                    "Unable to decode state summary";
+                    "state_root" => ?state_root,
+                    "slot" => slot,
+                    "error" => ?e,
+                );
+                if slot >= split_slot {
+                    corrupt_ops.push(StoreOp::DeleteState(state_root, None));
+                } else {
+                    missing_states += 1;
+                }
+            }
+        }
+    }
+
+    info!(
+        log,
+        "Database verification complete";
+        "blocks_checked" => blocks_checked,
+        "missing_states" => missing_states,
+        "corrupt_entries" => corrupt_ops.len(),
+    );
+
+    if verify_config.delete_corrupt && !corrupt_ops.is_empty() {
+        let deleted = corrupt_ops.len();
+        db.do_atomically(corrupt_ops)?;
+        info!(log, "Deleted corrupt entries"; "count" => deleted);
+    }
+
+    Ok(())
+}
+
 #[derive(Debug, EnumString, EnumVariantNames)]
 pub enum InspectTarget {
     #[strum(serialize = "sizes")]
@@ -172,7 +568,7 @@ pub fn inspect_db<E: EthSpec>(
     let hot_path = client_config.get_db_path();
     let cold_path = client_config.get_freezer_db_path();
 
-    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+    let db = HotColdDB::<E, HotDB<E>, FreezerDB<E>>::open(
         &hot_path,
         &cold_path,
         |_, _, _| Ok(()),
@@ -208,14 +604,19 @@ pub fn inspect_db<E: EthSpec>(
 
 pub struct MigrateConfig {
     to: SchemaVersion,
+    dry_run: bool,
 }
 
 fn parse_migrate_config(cli_args: &ArgMatches) -> Result<MigrateConfig, String> {
     let to = SchemaVersion(clap_utils::parse_required(cli_args, "to")?);
+    let dry_run = cli_args.is_present("dry-run");
 
-    Ok(MigrateConfig { to })
+    Ok(MigrateConfig { to, dry_run })
 }
 
+/// A conservative rate at which migrations read and rewrite data, used to estimate their duration.
+const MIGRATION_BYTES_PER_SECOND: usize = 20 * 1024 * 1024;
+
 pub fn migrate_db<E: EthSpec>(
     migrate_config: MigrateConfig,
     client_config: ClientConfig,
@@ -228,7 +629,7 @@ pub fn migrate_db<E: EthSpec>(
 
     let mut from = CURRENT_SCHEMA_VERSION;
     let to = migrate_config.to;
-    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+    let db = HotColdDB::<E, HotDB<E>, FreezerDB<E>>::open(
         &hot_path,
         &cold_path,
         |_, db_initial_version, _| {
@@ -240,6 +641,40 @@ pub fn migrate_db<E: EthSpec>(
         log.clone(),
     )?;
 
+    // Check that every step is supported before modifying the database.
+    let plan = migration_plan(from, to)?;
+
+    if migrate_config.dry_run {
+        for step in plan {
+            let (mut items, mut bytes) = (0, 0);
+            for column in step.columns {
+                let (column_items, column_bytes) = column_totals(db.hot_db.iter_column(*column))?;
+                items += column_items;
+                bytes += column_bytes;
+            }
+            for column in step.freezer_columns {
+                let (column_items, column_bytes) = column_totals(db.cold_db.iter_column(*column))?;
+                items += column_items;
+                bytes += column_bytes;
+            }
+            info!(
+                log,
+                "Migration step";
+                "from" => step.from.as_u64(),
+                "to" => step.to.as_u64(),
+                "items_rewritten" => items,
+                "bytes_rewritten" => bytes,
+                "estimated_seconds" => bytes / MIGRATION_BYTES_PER_SECOND,
+            );
+        }
+        info!(
+            log,
+            "Dry run complete, the database was not modified";
+            "info" => "rewritten items take up space until the database is next compacted",
+        );
+        return Ok(());
+    }
+
     info!(
         log,
         "Migrating database schema";
@@ -256,6 +691,274 @@ pub fn migrate_db<E: EthSpec>(
     )
 }
 
+pub fn convert_state_diffs<E: EthSpec>(
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), Error> {
+    let spec = runtime_context.eth2_config.spec.clone();
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+
+    let db = HotColdDB::<E, HotDB<E>, FreezerDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec,
+        log.clone(),
+    )?;
+
+    db.convert_restore_points_to_diffs(|examined, total| {
+        if examined % 64 == 0 {
+            info!(
+                log,
+                "Converting restore points to state diffs";
+                "examined" => examined,
+                "total" => total,
+            );
+        }
+    })?;
+
+    Ok(())
+}
+
+pub struct PruneStatesConfig {
+    history_retention_epochs: Option<u64>,
+    prune_blocks: bool,
+}
+
+fn parse_prune_states_config(cli_args: &ArgMatches) -> Result<PruneStatesConfig, String> {
+    let history_retention_epochs =
+        clap_utils::parse_optional(cli_args, "history-retention-epochs")?;
+    let prune_blocks = cli_args.is_present("prune-blocks");
+
+    Ok(PruneStatesConfig {
+        history_retention_epochs,
+        prune_blocks,
+    })
+}
+
+pub fn prune_states<E: EthSpec>(
+    prune_states_config: PruneStatesConfig,
+    mut client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), Error> {
+    let spec = runtime_context.eth2_config.spec.clone();
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+
+    client_config.store.history_retention_epochs = prune_states_config.history_retention_epochs;
+    client_config.store.prune_history_blocks = prune_states_config.prune_blocks;
+
+    let db = HotColdDB::<E, HotDB<E>, FreezerDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec,
+        log.clone(),
+    )?;
+
+    if prune_states_config.history_retention_epochs.is_some() {
+        db.prune_history()?;
+    } else {
+        db.prune_historic_states(|deleted, total| {
+            if deleted % 64 == 0 {
+                info!(
+                    log,
+                    "Pruning historic states";
+                    "deleted" => deleted,
+                    "total" => total,
+                );
+            }
+        })?;
+    }
+
+    // Reclaim the space of the deleted states and blocks.
+    db.compact()?;
+    db.cold_db.compact()?;
+
+    Ok(())
+}
+
+/// The names of the hot and freezer database directories within a backup.
+const BACKUP_HOT_DB_DIR: &str = "chain_db";
+const BACKUP_FREEZER_DB_DIR: &str = "freezer_db";
+
+pub struct BackupConfig {
+    backup_dir: PathBuf,
+}
+
+fn parse_backup_config(cli_args: &ArgMatches) -> Result<BackupConfig, String> {
+    let backup_dir = clap_utils::parse_required(cli_args, "backup-dir")?;
+
+    Ok(BackupConfig { backup_dir })
+}
+
+fn io_error(message: &str, path: &Path, e: io::Error) -> Error {
+    Error::DBError {
+        message: format!("{} {}: {:?}", message, path.display(), e),
+    }
+}
+
+/// Return an error if `dir` exists and isn't empty.
+fn ensure_empty_dir(dir: &Path) -> Result<(), Error> {
+    if dir.exists()
+        && fs::read_dir(dir)
+            .map_err(|e| io_error("Unable to read", dir, e))?
+            .next()
+            .is_some()
+    {
+        return Err(Error::DBError {
+            message: format!("{} already contains files", dir.display()),
+        });
+    }
+    Ok(())
+}
+
+/// Copy the database files in `src` to `dst`, returning the number of files hard-linked and
+/// copied.
+///
+/// LevelDB table files are never modified once written, so they're hard-linked if possible. All
+/// other files are copied, so that the copy isn't affected by subsequent writes to `src`.
+fn copy_db_files(src: &Path, dst: &Path) -> Result<(usize, usize), Error> {
+    fs::create_dir_all(dst).map_err(|e| io_error("Unable to create", dst, e))?;
+
+    let (mut linked, mut copied) = (0, 0);
+    for entry in fs::read_dir(src).map_err(|e| io_error("Unable to read", src, e))? {
+        let src_path = entry
+            .map_err(|e| io_error("Unable to read", src, e))?
+            .path();
+        let dst_path = dst.join(src_path.file_name().unwrap_or_default());
+
+        if src_path.is_dir() {
+            let (dir_linked, dir_copied) = copy_db_files(&src_path, &dst_path)?;
+            linked += dir_linked;
+            copied += dir_copied;
+            continue;
+        }
+
+        let immutable = src_path
+            .extension()
+            .map_or(false, |ext| ext == "ldb" || ext == "sst");
+        if immutable && fs::hard_link(&src_path, &dst_path).is_ok() {
+            linked += 1;
+        } else {
+            fs::copy(&src_path, &dst_path).map_err(|e| io_error("Unable to copy", &src_path, e))?;
+            copied += 1;
+        }
+    }
+    Ok((linked, copied))
+}
+
+/// Open the database at `hot_path` and `cold_path` and log its split, to check that it's intact.
+///
+/// This fails if the database is in use by another process, which holds its lock.
+fn check_db<E: EthSpec>(
+    hot_path: &Path,
+    cold_path: &Path,
+    client_config: &ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: &Logger,
+) -> Result<(), Error> {
+    let db = HotColdDB::<E, HotDB<E>, FreezerDB<E>>::open(
+        hot_path,
+        cold_path,
+        |_, _, _| Ok(()),
+        client_config.store.clone(),
+        runtime_context.eth2_config.spec.clone(),
+        log.clone(),
+    )?;
+
+    info!(
+        log,
+        "Database is consistent";
+        "path" => ?hot_path,
+        "split_slot" => db.get_split_slot(),
+    );
+    Ok(())
+}
+
+pub fn backup_db<E: EthSpec>(
+    backup_config: BackupConfig,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), Error> {
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let backup_hot_path = backup_config.backup_dir.join(BACKUP_HOT_DB_DIR);
+    let backup_cold_path = backup_config.backup_dir.join(BACKUP_FREEZER_DB_DIR);
+
+    if !hot_path.exists() {
+        return Err(Error::DBError {
+            message: format!("No database found at {}", hot_path.display()),
+        });
+    }
+    ensure_empty_dir(&backup_config.backup_dir)?;
+
+    // Opening the database ensures that the beacon node isn't running and replays any writes
+    // which were still in the write-ahead logs. Once closed, the files are a consistent snapshot.
+    check_db(&hot_path, &cold_path, &client_config, runtime_context, &log)?;
+
+    let (hot_linked, hot_copied) = copy_db_files(&hot_path, &backup_hot_path)?;
+    let (cold_linked, cold_copied) = copy_db_files(&cold_path, &backup_cold_path)?;
+
+    // Check the backup by opening it, as `lighthouse db restore` will.
+    check_db(
+        &backup_hot_path,
+        &backup_cold_path,
+        &client_config,
+        runtime_context,
+        &log,
+    )?;
+
+    info!(
+        log,
+        "Database backup complete";
+        "backup_dir" => ?backup_config.backup_dir,
+        "files_linked" => hot_linked + cold_linked,
+        "files_copied" => hot_copied + cold_copied,
+    );
+    Ok(())
+}
+
+pub fn restore_db<E: EthSpec>(
+    backup_config: BackupConfig,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), Error> {
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let backup_hot_path = backup_config.backup_dir.join(BACKUP_HOT_DB_DIR);
+    let backup_cold_path = backup_config.backup_dir.join(BACKUP_FREEZER_DB_DIR);
+
+    if !backup_hot_path.exists() || !backup_cold_path.exists() {
+        return Err(Error::DBError {
+            message: format!("No backup found at {}", backup_config.backup_dir.display()),
+        });
+    }
+    ensure_empty_dir(&hot_path)?;
+    ensure_empty_dir(&cold_path)?;
+
+    let (hot_linked, hot_copied) = copy_db_files(&backup_hot_path, &hot_path)?;
+    let (cold_linked, cold_copied) = copy_db_files(&backup_cold_path, &cold_path)?;
+
+    check_db(&hot_path, &cold_path, &client_config, runtime_context, &log)?;
+
+    info!(
+        log,
+        "Database restore complete";
+        "backup_dir" => ?backup_config.backup_dir,
+        "files_linked" => hot_linked + cold_linked,
+        "files_copied" => hot_copied + cold_copied,
+    );
+    Ok(())
+}
+
 /// Run the database manager, returning an error string if the operation did not succeed.
 pub fn run<T: EthSpec>(cli_args: &ArgMatches<'_>, mut env: Environment<T>) -> Result<(), String> {
     let client_config = parse_client_config(cli_args, &env)?;
@@ -272,6 +975,25 @@ pub fn run<T: EthSpec>(cli_args: &ArgMatches<'_>, mut env: Environment<T>) -> Re
             let inspect_config = parse_inspect_config(cli_args)?;
             inspect_db(inspect_config, client_config, &context, log)
         }
+        ("info", Some(_)) => display_db_info(client_config, &context, log),
+        ("columns", Some(_)) => display_db_columns(client_config, &context, log),
+        ("verify", Some(cli_args)) => {
+            let verify_config = parse_verify_config(cli_args)?;
+            verify_db(verify_config, client_config, &context, log)
+        }
+        ("convert-state-diffs", Some(_)) => convert_state_diffs(client_config, &context, log),
+        ("prune-states", Some(cli_args)) => {
+            let prune_states_config = parse_prune_states_config(cli_args)?;
+            prune_states(prune_states_config, client_config, &context, log)
+        }
+        ("backup", Some(cli_args)) => {
+            let backup_config = parse_backup_config(cli_args)?;
+            backup_db(backup_config, client_config, &context, log)
+        }
+        ("restore", Some(cli_args)) => {
+            let backup_config = parse_backup_config(cli_args)?;
+            restore_db(backup_config, client_config, &context, log)
+        }
         _ => {
             return Err("Unknown subcommand, for help `lighthouse database_manager --help`".into())
         }
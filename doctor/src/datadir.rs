@@ -0,0 +1,164 @@
+use crate::{Problem, Severity};
+use beacon_chain::schema_change::migration_plan;
+use std::path::Path;
+use store::{
+    existing_hot_backend,
+    metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION, SCHEMA_VERSION_KEY},
+    HotDB, HotStore, ItemStore, StoreConfig,
+};
+use types::EthSpec;
+
+const CHECK: &str = "datadir";
+
+const GIB: u64 = 1 << 30;
+
+/// Below this much free disk space the database may be unable to grow during non-finality.
+pub const DISK_HEADROOM_WARN_GIB: u64 = 64;
+
+/// Below this much free disk space the database is at risk of running out of space imminently.
+pub const DISK_HEADROOM_CRITICAL_GIB: u64 = 8;
+
+pub fn check<E: EthSpec>(beacon_dir: &Path, db_path: &Path) -> Vec<Problem> {
+    check_schema::<E>(db_path)
+        .into_iter()
+        .chain(check_disk_headroom(beacon_dir))
+        .collect()
+}
+
+/// Check that the schema version of the database can be used by this version of Lighthouse.
+fn check_schema<E: EthSpec>(db_path: &Path) -> Option<Problem> {
+    // Opening the database would create it if it didn't exist.
+    let hot_backend =
+        match existing_hot_backend(db_path) {
+            Some(hot_backend) => hot_backend,
+            None => return Some(Problem::new(
+                Severity::Info,
+                CHECK,
+                format!("No database found at {}", db_path.display()),
+                "If the beacon node has been run before, check the --datadir and --network flags \
+                 match those of the beacon node.",
+            )),
+        };
+    let config = StoreConfig {
+        hot_backend,
+        ..StoreConfig::default()
+    };
+
+    let db = match HotDB::<E>::open_hot(db_path, &config) {
+        Ok(db) => db,
+        Err(e) => {
+            return Some(Problem::new(
+                Severity::Info,
+                CHECK,
+                format!("Unable to open the database to check its schema: {:?}", e),
+                "The database can't be opened whilst the beacon node is running, stop it to \
+                 check the schema version.",
+            ))
+        }
+    };
+
+    let schema_version = match db.get::<SchemaVersion>(&SCHEMA_VERSION_KEY) {
+        Ok(Some(schema_version)) => schema_version,
+        Ok(None) => return None,
+        Err(e) => {
+            return Some(Problem::new(
+                Severity::Critical,
+                CHECK,
+                format!("Unable to read the database schema version: {:?}", e),
+                "The database may be corrupt, use `lighthouse db verify` to check it.",
+            ))
+        }
+    };
+
+    if schema_version > CURRENT_SCHEMA_VERSION {
+        Some(Problem::new(
+            Severity::Critical,
+            CHECK,
+            format!(
+                "The database has schema v{}, which is newer than the v{} supported by this \
+                 version of Lighthouse",
+                schema_version.as_u64(),
+                CURRENT_SCHEMA_VERSION.as_u64()
+            ),
+            format!(
+                "Run `lighthouse db migrate --to {}` with the newer version of Lighthouse \
+                 before downgrading.",
+                CURRENT_SCHEMA_VERSION.as_u64()
+            ),
+        ))
+    } else if schema_version != CURRENT_SCHEMA_VERSION {
+        match migration_plan(schema_version, CURRENT_SCHEMA_VERSION) {
+            Ok(_) => Some(Problem::new(
+                Severity::Info,
+                CHECK,
+                format!(
+                    "The database has schema v{} and will be migrated to v{} when the beacon \
+                     node starts",
+                    schema_version.as_u64(),
+                    CURRENT_SCHEMA_VERSION.as_u64()
+                ),
+                "Use `lighthouse db migrate --dry-run` to estimate the time and disk space the \
+                 migration requires.",
+            )),
+            Err(e) => Some(Problem::new(
+                Severity::Critical,
+                CHECK,
+                format!(
+                    "The database has schema v{}, which can't be migrated to v{}: {:?}",
+                    schema_version.as_u64(),
+                    CURRENT_SCHEMA_VERSION.as_u64(),
+                    e
+                ),
+                "Upgrade via an intermediate release of Lighthouse, or delete the database and \
+                 resync using checkpoint sync.",
+            )),
+        }
+    } else {
+        None
+    }
+}
+
+/// Check that there is enough free space for the database to grow.
+fn check_disk_headroom(beacon_dir: &Path) -> Option<Problem> {
+    // The beacon node directory may not have been created yet.
+    let existing_dir = beacon_dir.ancestors().find(|dir| dir.exists())?;
+
+    let available = match fs2::available_space(existing_dir) {
+        Ok(available) => available,
+        Err(e) => {
+            return Some(Problem::new(
+                Severity::Info,
+                CHECK,
+                format!(
+                    "Unable to read the free disk space at {}: {:?}",
+                    existing_dir.display(),
+                    e
+                ),
+                "Check the free disk space manually.",
+            ))
+        }
+    };
+
+    let severity = if available < DISK_HEADROOM_CRITICAL_GIB * GIB {
+        Severity::Critical
+    } else if available < DISK_HEADROOM_WARN_GIB * GIB {
+        Severity::Warning
+    } else {
+        return None;
+    };
+
+    Some(Problem::new(
+        severity,
+        CHECK,
+        format!(
+            "Only {} GiB of disk space is free at {}",
+            available / GIB,
+            existing_dir.display()
+        ),
+        format!(
+            "Free up disk space or move the datadir to a larger disk, at least {} GiB of free \
+             space is recommended for the database to grow during periods of non-finality.",
+            DISK_HEADROOM_WARN_GIB
+        ),
+    ))
+}
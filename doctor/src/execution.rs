@@ -0,0 +1,97 @@
+use crate::{Problem, Severity};
+use execution_layer::auth::{Auth, JwtKey};
+use execution_layer::{ApiError, HttpJsonRpc};
+use sensitive_url::SensitiveUrl;
+use std::fs;
+use std::path::Path;
+
+const CHECK: &str = "execution";
+
+/// Check that the JWT secret is valid and that the execution engine accepts it.
+pub async fn check(endpoint: &SensitiveUrl, jwt_secret_path: &Path) -> Vec<Problem> {
+    let secret = match read_jwt_secret(jwt_secret_path) {
+        Ok(secret) => secret,
+        Err(problem) => return vec![problem],
+    };
+
+    let engine: HttpJsonRpc =
+        match HttpJsonRpc::new_with_auth(endpoint.clone(), Auth::new(secret, None, None)) {
+            Ok(engine) => engine,
+            Err(e) => {
+                return vec![Problem::new(
+                    Severity::Critical,
+                    CHECK,
+                    format!("Unable to create an engine API client: {:?}", e),
+                    "Check the --execution-endpoint URL.",
+                )]
+            }
+        };
+
+    match engine.upcheck().await {
+        Ok(()) => vec![],
+        Err(ApiError::IsSyncing) => vec![Problem::new(
+            Severity::Warning,
+            CHECK,
+            format!("The execution engine at {} is syncing", endpoint),
+            "Validators can't perform their duties until the execution engine has synced.",
+        )],
+        Err(ApiError::Reqwest(e))
+            if matches!(
+                e.status().map(|status| status.as_u16()),
+                Some(401) | Some(403)
+            ) =>
+        {
+            vec![Problem::new(
+                Severity::Critical,
+                CHECK,
+                format!(
+                    "The execution engine at {} rejected the JWT secret in {}",
+                    endpoint,
+                    jwt_secret_path.display()
+                ),
+                "Configure the execution engine and the beacon node (--jwt-secrets) with the \
+                 same JWT secret file, and check that the system clocks of both are correct.",
+            )]
+        }
+        Err(e) => vec![Problem::new(
+            Severity::Critical,
+            CHECK,
+            format!(
+                "Unable to reach the execution engine at {}: {:?}",
+                endpoint, e
+            ),
+            "Check that the execution engine is running and serving its authenticated engine \
+             API at --execution-endpoint.",
+        )],
+    }
+}
+
+/// Read a hex-encoded JWT secret in the format accepted by the beacon node.
+fn read_jwt_secret(path: &Path) -> Result<JwtKey, Problem> {
+    if !path.exists() {
+        return Err(Problem::new(
+            Severity::Warning,
+            CHECK,
+            format!("No JWT secret found at {}", path.display()),
+            "The beacon node will generate a secret at this path when it starts, which must \
+             then be given to the execution engine. Use --execution-jwt if the secret is \
+             elsewhere.",
+        ));
+    }
+
+    let invalid = |reason: String| {
+        Problem::new(
+            Severity::Critical,
+            CHECK,
+            format!("Invalid JWT secret in {}: {}", path.display(), reason),
+            "The JWT secret must be a 32 byte hex string, e.g. as generated by `openssl rand \
+             -hex 32`.",
+        )
+    };
+
+    let contents = fs::read_to_string(path).map_err(|e| invalid(format!("{:?}", e)))?;
+    let hex_secret = contents.trim();
+    let bytes = hex::decode(hex_secret.strip_prefix("0x").unwrap_or(hex_secret))
+        .map_err(|e| invalid(format!("invalid hex string: {:?}", e)))?;
+    JwtKey::from_slice(&bytes).map_err(invalid)
+}
@@ -0,0 +1,74 @@
+use crate::{Problem, Severity};
+use account_utils::validator_definitions::{ValidatorDefinitions, CONFIG_FILENAME};
+use std::path::Path;
+use types::Address;
+
+const CHECK: &str = "fee recipient";
+
+/// Check the fee recipients of the enabled validators in `validators_dir`.
+pub fn check(validators_dir: &Path) -> Vec<Problem> {
+    // There's nothing to check if the validator client isn't used with this datadir.
+    if !validators_dir.join(CONFIG_FILENAME).exists() {
+        return vec![];
+    }
+
+    let definitions = match ValidatorDefinitions::open(validators_dir) {
+        Ok(definitions) => definitions,
+        Err(e) => {
+            return vec![Problem::new(
+                Severity::Critical,
+                CHECK,
+                format!(
+                    "Unable to read {}: {:?}",
+                    validators_dir.join(CONFIG_FILENAME).display(),
+                    e
+                ),
+                "The validator client won't start until this file is fixed.",
+            )]
+        }
+    };
+
+    let enabled = || {
+        definitions
+            .as_slice()
+            .iter()
+            .filter(|definition| definition.enabled)
+    };
+    let num_zero = enabled()
+        .filter(|definition| definition.suggested_fee_recipient == Some(Address::zero()))
+        .count();
+    let num_unset = enabled()
+        .filter(|definition| definition.suggested_fee_recipient.is_none())
+        .count();
+
+    let mut problems = vec![];
+    if num_zero > 0 {
+        problems.push(Problem::new(
+            Severity::Critical,
+            CHECK,
+            format!(
+                "{} enabled validator(s) have the zero address as their suggested_fee_recipient",
+                num_zero
+            ),
+            format!(
+                "Set suggested_fee_recipient in {} to an address you control, otherwise the \
+                 priority fees of proposed blocks are burnt.",
+                CONFIG_FILENAME
+            ),
+        ));
+    }
+    if num_unset > 0 {
+        problems.push(Problem::new(
+            Severity::Warning,
+            CHECK,
+            format!(
+                "{} enabled validator(s) have no suggested_fee_recipient in {}",
+                num_unset, CONFIG_FILENAME
+            ),
+            "Check that the validator client is run with --suggested-fee-recipient, otherwise \
+             these validators are unable to propose post-merge blocks with a fee recipient of \
+             your choosing.",
+        ));
+    }
+    problems
+}
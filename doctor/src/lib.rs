@@ -0,0 +1,237 @@
+//! Diagnoses the problems behind most support requests.
+//!
+//! Each check inspects one part of a Lighthouse installation (the datadir, the execution engine,
+//! the beacon node's peers, the system clock and the validator configuration) and reports the
+//! problems it finds, which are printed with the most severe first.
+mod datadir;
+mod execution;
+mod fee_recipient;
+mod network;
+mod ntp;
+
+use beacon_node::{get_data_dir, ClientConfig};
+use clap::{App, Arg, ArgMatches};
+use directory::DEFAULT_VALIDATOR_DIR;
+use environment::Environment;
+use execution_layer::DEFAULT_JWT_FILE;
+use sensitive_url::SensitiveUrl;
+use std::fmt;
+use std::path::PathBuf;
+use types::EthSpec;
+
+pub const CMD: &str = "doctor";
+
+pub const DEFAULT_EXECUTION_ENDPOINT: &str = "http://localhost:8551";
+pub const DEFAULT_BEACON_NODE: &str = "http://localhost:5052";
+pub const DEFAULT_NTP_SERVER: &str = "pool.ntp.org:123";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .setting(clap::AppSettings::ColoredHelp)
+        .about(
+            "Check the datadir, execution engine, network connectivity, system clock and fee \
+             recipients for common problems, and print the problems found with the most severe \
+             first.",
+        )
+        .arg(
+            Arg::with_name("execution-endpoint")
+                .long("execution-endpoint")
+                .value_name("URL")
+                .help("The authenticated engine API endpoint of the execution engine.")
+                .default_value(DEFAULT_EXECUTION_ENDPOINT)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("execution-jwt")
+                .long("execution-jwt")
+                .value_name("PATH")
+                .help(
+                    "The file containing the JWT secret shared with the execution engine. \
+                     [default: jwt.hex in the beacon node directory]",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("beacon-node")
+                .long("beacon-node")
+                .value_name("URL")
+                .help("The HTTP API of the beacon node, used to check its peer connectivity.")
+                .default_value(DEFAULT_BEACON_NODE)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("ntp-server")
+                .long("ntp-server")
+                .value_name("HOST:PORT")
+                .help("The NTP server to measure the offset of the system clock against.")
+                .default_value(DEFAULT_NTP_SERVER)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("validators-dir")
+                .long("validators-dir")
+                .value_name("VALIDATORS_DIRECTORY")
+                .help(
+                    "The directory containing validator_definitions.yml, whose fee recipients are \
+                     checked. [default: the validator client's default]",
+                )
+                .takes_value(true),
+        )
+}
+
+/// The parts of the installation to be checked.
+pub struct DoctorConfig {
+    /// The beacon node directory within the datadir.
+    pub beacon_dir: PathBuf,
+    /// The hot database directory.
+    pub db_path: PathBuf,
+    pub execution_endpoint: SensitiveUrl,
+    pub jwt_secret_path: PathBuf,
+    pub beacon_node: SensitiveUrl,
+    pub ntp_server: String,
+    pub validators_dir: PathBuf,
+}
+
+impl DoctorConfig {
+    pub fn from_cli(cli_args: &ArgMatches) -> Result<Self, String> {
+        let client_config = ClientConfig {
+            data_dir: get_data_dir(cli_args),
+            ..Default::default()
+        };
+        let beacon_dir = client_config.get_data_dir();
+        let db_path = client_config.get_db_path();
+
+        let execution_endpoint = clap_utils::parse_required(cli_args, "execution-endpoint")
+            .and_then(|url: String| {
+                SensitiveUrl::parse(&url)
+                    .map_err(|e| format!("Invalid --execution-endpoint: {:?}", e))
+            })?;
+        let jwt_secret_path = clap_utils::parse_optional(cli_args, "execution-jwt")?
+            .unwrap_or_else(|| beacon_dir.join(DEFAULT_JWT_FILE));
+        let beacon_node =
+            clap_utils::parse_required(cli_args, "beacon-node").and_then(|url: String| {
+                SensitiveUrl::parse(&url).map_err(|e| format!("Invalid --beacon-node: {:?}", e))
+            })?;
+        let ntp_server = clap_utils::parse_required(cli_args, "ntp-server")?;
+
+        // Find the validators directory in the same way as the validator client.
+        let validators_dir = match (
+            cli_args.value_of("validators-dir"),
+            cli_args.value_of("datadir"),
+        ) {
+            (None, Some(datadir)) => PathBuf::from(datadir).join(DEFAULT_VALIDATOR_DIR),
+            _ => directory::parse_path_or_default_with_flag(
+                cli_args,
+                "validators-dir",
+                DEFAULT_VALIDATOR_DIR,
+            )?,
+        };
+
+        Ok(Self {
+            beacon_dir,
+            db_path,
+            execution_endpoint,
+            jwt_secret_path,
+            beacon_node,
+            ntp_server,
+            validators_dir,
+        })
+    }
+}
+
+/// How urgently a problem needs attention, from most to least urgent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The node can't work correctly until the problem is fixed.
+    Critical,
+    /// The node works, but its performance or rewards are likely to suffer.
+    Warning,
+    /// A check couldn't be completed, or something may need attention in future.
+    Info,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Critical => write!(f, "critical"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Info => write!(f, "info"),
+        }
+    }
+}
+
+/// A problem found by one of the checks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    pub severity: Severity,
+    /// The name of the check which found the problem.
+    pub check: &'static str,
+    pub description: String,
+    /// What the user should do about the problem.
+    pub remedy: String,
+}
+
+impl Problem {
+    pub fn new(
+        severity: Severity,
+        check: &'static str,
+        description: impl Into<String>,
+        remedy: impl Into<String>,
+    ) -> Self {
+        Self {
+            severity,
+            check,
+            description: description.into(),
+            remedy: remedy.into(),
+        }
+    }
+}
+
+/// Run all of the checks, returning the problems found with the most severe first.
+pub fn diagnose<E: EthSpec>(config: &DoctorConfig, env: &Environment<E>) -> Vec<Problem> {
+    let mut problems = datadir::check::<E>(&config.beacon_dir, &config.db_path);
+    problems.extend(env.runtime().block_on(execution::check(
+        &config.execution_endpoint,
+        &config.jwt_secret_path,
+    )));
+    problems.extend(env.runtime().block_on(network::check(&config.beacon_node)));
+    problems.extend(ntp::check(&config.ntp_server));
+    problems.extend(fee_recipient::check(&config.validators_dir));
+
+    // The sort is stable, so problems of the same severity keep the order of the checks.
+    problems.sort_by_key(|problem| problem.severity);
+    problems
+}
+
+pub fn run<E: EthSpec>(cli_args: &ArgMatches<'_>, env: Environment<E>) -> Result<(), String> {
+    let config = DoctorConfig::from_cli(cli_args)?;
+    let problems = diagnose(&config, &env);
+
+    if problems.is_empty() {
+        println!("No problems found");
+        return Ok(());
+    }
+
+    println!("Found {} problem(s):", problems.len());
+    for (i, problem) in problems.iter().enumerate() {
+        println!();
+        println!(
+            "{}. [{}] {}: {}",
+            i + 1,
+            problem.severity,
+            problem.check,
+            problem.description
+        );
+        println!("   {}", problem.remedy);
+    }
+
+    let num_critical = problems
+        .iter()
+        .filter(|problem| problem.severity == Severity::Critical)
+        .count();
+    if num_critical > 0 {
+        Err(format!("{} critical problem(s) found", num_critical))
+    } else {
+        Ok(())
+    }
+}
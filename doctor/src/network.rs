@@ -0,0 +1,72 @@
+use crate::{Problem, Severity};
+use eth2::types::{PeerDirection, PeerState};
+use eth2::{BeaconNodeHttpClient, Timeouts};
+use sensitive_url::SensitiveUrl;
+use std::time::Duration;
+
+const CHECK: &str = "network";
+
+const BEACON_NODE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// With at least this many connected peers and none of them inbound, it's likely that other nodes
+/// are unable to reach the beacon node.
+pub const MIN_PEERS_FOR_INBOUND_CHECK: usize = 10;
+
+/// Check that the beacon node is connected to peers, and that other nodes are able to dial its
+/// P2P port.
+///
+/// The peers of the beacon node act as external probes of the port: if none of them have dialled
+/// the beacon node, the port is most likely unreachable from the internet.
+pub async fn check(beacon_node: &SensitiveUrl) -> Vec<Problem> {
+    let client =
+        BeaconNodeHttpClient::new(beacon_node.clone(), Timeouts::set_all(BEACON_NODE_TIMEOUT));
+
+    let peers = match client
+        .get_node_peers(Some(&[PeerState::Connected]), None)
+        .await
+    {
+        Ok(peers) => peers.data,
+        Err(e) => {
+            return vec![Problem::new(
+                Severity::Info,
+                CHECK,
+                format!(
+                    "Unable to reach the beacon node HTTP API at {}: {}",
+                    beacon_node, e
+                ),
+                "Run the beacon node with --http, and use --beacon-node if it's listening \
+                 elsewhere, to check its peers and port reachability.",
+            )]
+        }
+    };
+
+    let num_inbound = peers
+        .iter()
+        .filter(|peer| peer.direction == PeerDirection::Inbound)
+        .count();
+
+    if peers.is_empty() {
+        vec![Problem::new(
+            Severity::Critical,
+            CHECK,
+            "The beacon node isn't connected to any peers",
+            "Check the internet connection of the beacon node, and that a firewall isn't \
+             blocking its --port (default 9000).",
+        )]
+    } else if num_inbound == 0 && peers.len() >= MIN_PEERS_FOR_INBOUND_CHECK {
+        vec![Problem::new(
+            Severity::Warning,
+            CHECK,
+            format!(
+                "None of the {} connected peers dialled the beacon node, its P2P port is \
+                 probably unreachable from the internet",
+                peers.len()
+            ),
+            "Forward the TCP and UDP --port (default 9000) to this machine, or enable UPnP on \
+             the router. Reachable nodes find peers faster and are less likely to miss \
+             attestations.",
+        )]
+    } else {
+        vec![]
+    }
+}
@@ -0,0 +1,137 @@
+//! Measures the offset of the system clock using a single SNTP (RFC 4330) request.
+use crate::{Problem, Severity};
+use std::net::UdpSocket;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const CHECK: &str = "clock";
+
+const NTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The number of seconds between the NTP epoch (1900) and the UNIX epoch (1970).
+const NTP_UNIX_OFFSET_SECS: u64 = 2_208_988_800;
+
+const NTP_PACKET_LENGTH: usize = 48;
+
+/// An offset beyond this may cause attestations and blocks to arrive late.
+pub const CLOCK_OFFSET_WARN_MS: i64 = 100;
+
+/// Beyond this offset gossip messages are ignored by peers with the default
+/// `--maximum-gossip-clock-disparity`.
+pub const CLOCK_OFFSET_CRITICAL_MS: i64 = 500;
+
+pub fn check(server: &str) -> Option<Problem> {
+    let offset_ms = match query_offset_ms(server) {
+        Ok(offset_ms) => offset_ms,
+        Err(e) => {
+            return Some(Problem::new(
+                Severity::Info,
+                CHECK,
+                format!("Unable to query the NTP server {}: {}", server, e),
+                "Use --ntp-server to query a different server, or check the clock offset \
+                 manually (e.g. with `timedatectl timesync-status`).",
+            ))
+        }
+    };
+
+    let severity = if offset_ms.abs() > CLOCK_OFFSET_CRITICAL_MS {
+        Severity::Critical
+    } else if offset_ms.abs() > CLOCK_OFFSET_WARN_MS {
+        Severity::Warning
+    } else {
+        return None;
+    };
+
+    Some(Problem::new(
+        severity,
+        CHECK,
+        format!(
+            "The system clock is {}ms {} according to {}",
+            offset_ms.abs(),
+            if offset_ms > 0 { "behind" } else { "ahead" },
+            server
+        ),
+        "Run an NTP client (e.g. chrony or systemd-timesyncd) to keep the system clock in sync.",
+    ))
+}
+
+/// Returns the number of milliseconds that must be added to the system clock to match `server`.
+fn query_offset_ms(server: &str) -> Result<i64, String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| format!("{:?}", e))?;
+    socket
+        .set_read_timeout(Some(NTP_TIMEOUT))
+        .map_err(|e| format!("{:?}", e))?;
+    socket.connect(server).map_err(|e| format!("{:?}", e))?;
+
+    let mut request = [0; NTP_PACKET_LENGTH];
+    // Leap indicator 0, version 3, client mode.
+    request[0] = 0x1b;
+
+    let originate_ms = unix_time_ms()?;
+    socket.send(&request).map_err(|e| format!("{:?}", e))?;
+    let mut response = [0; NTP_PACKET_LENGTH];
+    let len = socket.recv(&mut response).map_err(|e| format!("{:?}", e))?;
+    let destination_ms = unix_time_ms()?;
+
+    if len < NTP_PACKET_LENGTH || response[0] & 0x07 != 4 {
+        return Err("invalid response".to_string());
+    }
+    let receive_ms = ntp_timestamp_to_unix_ms(&response[32..40])?;
+    let transmit_ms = ntp_timestamp_to_unix_ms(&response[40..48])?;
+
+    Ok(clock_offset_ms(
+        originate_ms,
+        receive_ms,
+        transmit_ms,
+        destination_ms,
+    ))
+}
+
+/// The clock offset given the times a request was sent and received by the client and server.
+fn clock_offset_ms(originate: i64, receive: i64, transmit: i64, destination: i64) -> i64 {
+    ((receive - originate) + (transmit - destination)) / 2
+}
+
+/// Convert an 8 byte NTP timestamp to milliseconds since the UNIX epoch.
+fn ntp_timestamp_to_unix_ms(bytes: &[u8]) -> Result<i64, String> {
+    let mut seconds = [0; 4];
+    let mut fraction = [0; 4];
+    seconds.copy_from_slice(&bytes[0..4]);
+    fraction.copy_from_slice(&bytes[4..8]);
+    let seconds = u64::from(u32::from_be_bytes(seconds));
+    let fraction = u64::from(u32::from_be_bytes(fraction));
+
+    let unix_seconds = seconds
+        .checked_sub(NTP_UNIX_OFFSET_SECS)
+        .ok_or("timestamp is before the UNIX epoch")?;
+    Ok((unix_seconds * 1000 + ((fraction * 1000) >> 32)) as i64)
+}
+
+fn unix_time_ms() -> Result<i64, String> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .map_err(|e| format!("system clock is before the UNIX epoch: {:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_ntp_timestamps() {
+        let mut bytes = [0; 8];
+        bytes[0..4].copy_from_slice(&((NTP_UNIX_OFFSET_SECS + 12) as u32).to_be_bytes());
+        bytes[4..8].copy_from_slice(&(1u32 << 31).to_be_bytes());
+        assert_eq!(ntp_timestamp_to_unix_ms(&bytes), Ok(12_500));
+
+        assert!(ntp_timestamp_to_unix_ms(&[0; 8]).is_err());
+    }
+
+    #[test]
+    fn offset_excludes_round_trip_delay() {
+        // The server's clock is 1s ahead and the request takes 100ms each way.
+        assert_eq!(clock_offset_ms(0, 1_100, 1_150, 250), 1_000);
+        // The server's clock is 1s behind.
+        assert_eq!(clock_offset_ms(2_000, 1_100, 1_150, 2_250), -1_000);
+    }
+}
@@ -22,7 +22,6 @@ use parse_ssz::run_parse_ssz;
 use std::path::PathBuf;
 use std::process;
 use std::str::FromStr;
-use transition_blocks::run_transition_blocks;
 use types::{EthSpec, EthSpecId};
 
 fn main() {
@@ -81,28 +80,72 @@ fn main() {
         )
         .subcommand(
             SubCommand::with_name("transition-blocks")
-                .about("Performs a state transition given a pre-state and block")
+                .about("Performs a state transition from some state across some number of blocks")
                 .arg(
-                    Arg::with_name("pre-state")
-                        .value_name("BEACON_STATE")
+                    Arg::with_name("pre-state-path")
+                        .long("pre-state-path")
+                        .value_name("PATH")
                         .takes_value(true)
-                        .required(true)
-                        .help("Path to a SSZ file of the pre-state."),
+                        .conflicts_with("beacon-url")
+                        .requires("block-path")
+                        .help("Path to load a BeaconState from file as SSZ."),
                 )
                 .arg(
-                    Arg::with_name("block")
-                        .value_name("BEACON_BLOCK")
+                    Arg::with_name("block-path")
+                        .long("block-path")
+                        .value_name("PATH")
                         .takes_value(true)
-                        .required(true)
-                        .help("Path to a SSZ file of the block to apply to pre-state."),
+                        .multiple(true)
+                        .number_of_values(1)
+                        .conflicts_with("beacon-url")
+                        .requires("pre-state-path")
+                        .help("Path to load a SignedBeaconBlock from file as SSZ. May be supplied \
+                            multiple times, in which case the blocks are applied in order."),
                 )
                 .arg(
-                    Arg::with_name("output")
-                        .value_name("SSZ_FILE")
+                    Arg::with_name("post-state-output-path")
+                        .long("post-state-output-path")
+                        .value_name("PATH")
                         .takes_value(true)
-                        .required(true)
-                        .default_value("./output.ssz")
-                        .help("Path to output a SSZ file."),
+                        .help("Path to output the post-state."),
+                )
+                .arg(
+                    Arg::with_name("pre-state-output-path")
+                        .long("pre-state-output-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .help("Path to output the pre-state, useful when used with --beacon-url."),
+                )
+                .arg(
+                    Arg::with_name("block-output-path")
+                        .long("block-output-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .help("Path to output the block, useful when used with --beacon-url."),
+                )
+                .arg(
+                    Arg::with_name("beacon-url")
+                        .long("beacon-url")
+                        .value_name("URL")
+                        .takes_value(true)
+                        .requires("block-id")
+                        .help("URL to a beacon-API provider."),
+                )
+                .arg(
+                    Arg::with_name("block-id")
+                        .long("block-id")
+                        .value_name("BLOCK_ID")
+                        .takes_value(true)
+                        .requires("beacon-url")
+                        .help("Identifier for a block as per beacon-API standards (slot, root, etc.)"),
+                )
+                .arg(
+                    Arg::with_name("runs")
+                        .long("runs")
+                        .value_name("INTEGER")
+                        .takes_value(true)
+                        .default_value("1")
+                        .help("Number of repeat runs, useful for benchmarking."),
                 )
                 .arg(
                     Arg::with_name("no-signature-verification")
@@ -110,6 +153,34 @@ fn main() {
                         .takes_value(false)
                         .help("Disable signature verification.")
                 )
+                .arg(
+                    Arg::with_name("no-block-root-verification")
+                        .long("no-block-root-verification")
+                        .takes_value(false)
+                        .help("Disable the check that the block's parent root matches the \
+                            latest block header of the pre-state.")
+                )
+                .arg(
+                    Arg::with_name("no-state-root-verification")
+                        .long("no-state-root-verification")
+                        .takes_value(false)
+                        .help("Disable the check that the post-state root matches the block's \
+                            state root.")
+                )
+                .arg(
+                    Arg::with_name("exclude-cache-builds")
+                        .long("exclude-cache-builds")
+                        .takes_value(false)
+                        .help("If present, pre-build the committee and tree-hash caches without \
+                            including them in the timings."),
+                )
+                .arg(
+                    Arg::with_name("exclude-post-block-thc")
+                        .long("exclude-post-block-thc")
+                        .takes_value(false)
+                        .help("If present, don't rebuild the tree-hash-cache after applying \
+                            the block. This also skips the state root verification."),
+                )
         )
         .subcommand(
             SubCommand::with_name("pretty-ssz")
@@ -656,11 +727,14 @@ fn run<T: EthSpec>(
         .initialize_logger(LoggerConfig {
             path: None,
             debug_level: "trace",
+            debug_level_overrides: None,
             logfile_debug_level: "trace",
             log_format: None,
             max_log_size: 0,
             max_log_number: 0,
+            max_log_age: None,
             compression: false,
+            debug_ring_size: 0,
         })
         .map_err(|e| format!("should start logger: {:?}", e))?
         .build()
@@ -673,8 +747,10 @@ fn run<T: EthSpec>(
     )?;
 
     match matches.subcommand() {
-        ("transition-blocks", Some(matches)) => run_transition_blocks::<T>(testnet_dir, matches)
-            .map_err(|e| format!("Failed to transition blocks: {}", e)),
+        ("transition-blocks", Some(matches)) => {
+            transition_blocks::run::<T>(env, testnet_dir, matches)
+                .map_err(|e| format!("Failed to transition blocks: {}", e))
+        }
         ("skip-slots", Some(matches)) => skip_slots::run::<T>(testnet_dir, matches)
             .map_err(|e| format!("Failed to skip slots: {}", e)),
         ("pretty-ssz", Some(matches)) => {
@@ -1,5 +1,52 @@
+//! # Transition Blocks
+//!
+//! Use this tool to apply one or more `SignedBeaconBlock`s to a `BeaconState`. Useful for
+//! benchmarking or troubleshooting consensus failures.
+//!
+//! It can load states and blocks from file or pull them from a beacon node HTTP API. Objects pulled
+//! from a beacon node can be saved to disk to reduce future calls to that server.
+//!
+//! ## Examples
+//!
+//! ### Run using a block from a beacon node
+//!
+//! ```ignore
+//! lcli transition-blocks \
+//!     --beacon-url http://localhost:5052 \
+//!     --block-id 0x3d887d30ee25c9c1ce7621ec30a7b49b07d6a03200df9c7206faca52a533f432 \
+//!     --runs 10
+//! ```
+//!
+//! ### Download a block and pre-state from a beacon node to the filesystem
+//!
+//! ```ignore
+//! lcli transition-blocks \
+//!     --beacon-url http://localhost:5052 \
+//!     --block-id 0x3d887d30ee25c9c1ce7621ec30a7b49b07d6a03200df9c7206faca52a533f432 \
+//!     --pre-state-output-path /tmp/prestate.ssz \
+//!     --block-output-path /tmp/block.ssz
+//! ```
+//!
+//! ### Use a pre-state and blocks from the filesystem
+//!
+//! Blocks are applied in the order they are supplied.
+//!
+//! ```ignore
+//! lcli transition-blocks \
+//!     --pre-state-path /tmp/prestate.ssz \
+//!     --block-path /tmp/block_1.ssz \
+//!     --block-path /tmp/block_2.ssz \
+//!     --post-state-output-path /tmp/poststate.ssz
+//! ```
 use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use environment::Environment;
+use eth2::{
+    types::{BlockId, StateId},
+    BeaconNodeHttpClient, Timeouts,
+};
 use eth2_network_config::Eth2NetworkConfig;
+use sensitive_url::SensitiveUrl;
 use ssz::Encode;
 use state_processing::{
     per_block_processing, per_slot_processing, BlockSignatureStrategy, VerifyBlockRoot,
@@ -7,121 +54,291 @@ use state_processing::{
 use std::fs::File;
 use std::io::prelude::*;
 use std::path::{Path, PathBuf};
-use std::time::Instant;
-use types::{BeaconState, ChainSpec, EthSpec, SignedBeaconBlock};
+use std::time::{Duration, Instant};
+use types::{BeaconState, ChainSpec, EthSpec, Hash256, SignedBeaconBlock};
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The verification steps and timed phases of a transition which may be disabled.
+#[derive(Debug, Clone, Copy)]
+struct Config {
+    no_signature_verification: bool,
+    no_block_root_verification: bool,
+    no_state_root_verification: bool,
+    exclude_cache_builds: bool,
+    exclude_post_block_thc: bool,
+}
 
-pub fn run_transition_blocks<T: EthSpec>(
+pub fn run<T: EthSpec>(
+    env: Environment<T>,
     testnet_dir: PathBuf,
     matches: &ArgMatches,
 ) -> Result<(), String> {
-    let pre_state_path = matches
-        .value_of("pre-state")
-        .ok_or("No pre-state file supplied")?
-        .parse::<PathBuf>()
-        .map_err(|e| format!("Failed to parse pre-state path: {}", e))?;
-
-    let block_path = matches
-        .value_of("block")
-        .ok_or("No block file supplied")?
-        .parse::<PathBuf>()
-        .map_err(|e| format!("Failed to parse block path: {}", e))?;
-
-    let output_path = matches
-        .value_of("output")
-        .ok_or("No output file supplied")?
-        .parse::<PathBuf>()
-        .map_err(|e| format!("Failed to parse output path: {}", e))?;
-
-    let no_signature_verification = matches.is_present("no-signature-verification");
-    let signature_strategy = if no_signature_verification {
-        BlockSignatureStrategy::NoVerification
-    } else {
-        BlockSignatureStrategy::VerifyIndividual
+    let spec = &Eth2NetworkConfig::load(testnet_dir)?.chain_spec::<T>()?;
+
+    let pre_state_path: Option<PathBuf> = parse_optional(matches, "pre-state-path")?;
+    let block_paths: Vec<PathBuf> = matches
+        .values_of("block-path")
+        .map(|paths| paths.map(PathBuf::from).collect())
+        .unwrap_or_default();
+    let beacon_url: Option<String> = parse_optional(matches, "beacon-url")?;
+    let runs: usize = parse_required(matches, "runs")?;
+    let config = Config {
+        no_signature_verification: matches.is_present("no-signature-verification"),
+        no_block_root_verification: matches.is_present("no-block-root-verification"),
+        no_state_root_verification: matches.is_present("no-state-root-verification"),
+        exclude_cache_builds: matches.is_present("exclude-cache-builds"),
+        exclude_post_block_thc: matches.is_present("exclude-post-block-thc"),
     };
 
     info!("Using {} spec", T::spec_name());
-    info!("Pre-state path: {:?}", pre_state_path);
-    info!("Block path: {:?}", block_path);
+    info!("Doing {} runs", runs);
+    info!("{:?}", &config);
 
-    let eth2_network_config = Eth2NetworkConfig::load(testnet_dir)?;
-    let spec = &eth2_network_config.chain_spec::<T>()?;
+    /*
+     * Load the block(s) and pre-state from disk or beacon node.
+     */
 
-    let pre_state: BeaconState<T> =
-        load_from_ssz_with(&pre_state_path, spec, BeaconState::from_ssz_bytes)?;
-    let block: SignedBeaconBlock<T> =
-        load_from_ssz_with(&block_path, spec, SignedBeaconBlock::from_ssz_bytes)?;
+    let (mut pre_state, mut state_root_opt, blocks) = match (pre_state_path, beacon_url) {
+        (Some(pre_state_path), None) => {
+            if block_paths.is_empty() {
+                return Err("At least one --block-path is required with --pre-state-path".into());
+            }
 
-    let t = Instant::now();
-    let post_state = do_transition(pre_state, block, signature_strategy, spec)?;
-    println!("Total transition time: {}ms", t.elapsed().as_millis());
+            info!("Pre-state path: {:?}", pre_state_path);
+            let pre_state = load_from_ssz_with(&pre_state_path, spec, BeaconState::from_ssz_bytes)?;
+            let blocks = block_paths
+                .iter()
+                .map(|block_path| {
+                    info!("Block path: {:?}", block_path);
+                    load_from_ssz_with(block_path, spec, SignedBeaconBlock::from_ssz_bytes)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            (pre_state, None, blocks)
+        }
+        (None, Some(beacon_url)) => {
+            let block_id: BlockId = parse_required(matches, "block-id")?;
+            let client = BeaconNodeHttpClient::new(
+                SensitiveUrl::parse(&beacon_url)
+                    .map_err(|e| format!("Unable to parse beacon URL: {:?}", e))?,
+                Timeouts::set_all(HTTP_TIMEOUT),
+            );
+            let (pre_state, state_root, block) = env
+                .runtime()
+                .block_on(fetch_block_and_pre_state::<T>(&client, block_id, spec))?;
+            (pre_state, Some(state_root), vec![block])
+        }
+        _ => {
+            return Err(
+                "Supply either --pre-state-path and --block-path, or --beacon-url and --block-id"
+                    .into(),
+            )
+        }
+    };
+
+    // Save the pre-state and block(s) to file, if required.
+    if let Some(path) = parse_optional::<PathBuf>(matches, "pre-state-output-path")? {
+        info!("Writing pre-state to {:?}", path);
+        write_to_ssz_file(&path, &pre_state)?;
+    }
+    if let Some(path) = parse_optional::<PathBuf>(matches, "block-output-path")? {
+        let block = blocks
+            .first()
+            .ok_or("No block available to write to --block-output-path")?;
+        info!("Writing block to {:?}", path);
+        write_to_ssz_file(&path, block)?;
+    }
+
+    /*
+     * Optionally pre-compute the caches so they're excluded from the timings.
+     */
 
-    let mut output_file =
-        File::create(output_path).map_err(|e| format!("Unable to create output file: {:?}", e))?;
+    if config.exclude_cache_builds {
+        pre_state
+            .build_all_caches(spec)
+            .map_err(|e| format!("Unable to build caches: {:?}", e))?;
+        let state_root = pre_state
+            .update_tree_hash_cache()
+            .map_err(|e| format!("Unable to build tree hash cache: {:?}", e))?;
 
-    output_file
-        .write_all(&post_state.as_ssz_bytes())
-        .map_err(|e| format!("Unable to write to output file: {:?}", e))?;
+        if let Some(expected) = state_root_opt {
+            if state_root != expected {
+                return Err(format!(
+                    "State root mismatch! Expected {}, computed {}",
+                    expected, state_root
+                ));
+            }
+        }
+        state_root_opt = Some(state_root);
+    }
+
+    /*
+     * Perform the core "runs".
+     */
+
+    let mut output_post_state = None;
+    for i in 0..runs {
+        let t = Instant::now();
+        let mut state = pre_state.clone();
+        let mut state_root_opt = state_root_opt;
+
+        for block in &blocks {
+            let (post_state, post_state_root_opt) =
+                do_transition(state, state_root_opt, block, config, spec)?;
+            state = post_state;
+            state_root_opt = post_state_root_opt;
+        }
+
+        info!("Run {}: {:?}", i, t.elapsed());
+
+        if output_post_state.is_none() {
+            output_post_state = Some(state);
+        }
+    }
+
+    let post_state = output_post_state.ok_or_else(|| {
+        format!(
+            "Post state was not computed because {} runs were requested",
+            runs
+        )
+    })?;
+
+    if let Some(path) = parse_optional::<PathBuf>(matches, "post-state-output-path")? {
+        info!("Writing post-state to {:?}", path);
+        write_to_ssz_file(&path, &post_state)?;
+    }
 
     Ok(())
 }
 
+/// Fetch the block identified by `block_id` and the post-state of its parent from a beacon node.
+///
+/// Returns the pre-state along with its state root.
+async fn fetch_block_and_pre_state<T: EthSpec>(
+    client: &BeaconNodeHttpClient,
+    block_id: BlockId,
+    spec: &ChainSpec,
+) -> Result<(BeaconState<T>, Hash256, SignedBeaconBlock<T>), String> {
+    let block: SignedBeaconBlock<T> = client
+        .get_beacon_blocks_ssz(block_id, spec)
+        .await
+        .map_err(|e| format!("Failed to download block: {:?}", e))?
+        .ok_or_else(|| format!("Unable to locate block at {}", block_id))?;
+
+    if block.slot() == spec.genesis_slot {
+        return Err("Cannot run on the genesis block".into());
+    }
+
+    let parent_block: SignedBeaconBlock<T> = client
+        .get_beacon_blocks_ssz(BlockId::Root(block.parent_root()), spec)
+        .await
+        .map_err(|e| format!("Failed to download parent block: {:?}", e))?
+        .ok_or_else(|| format!("Unable to locate parent block at {:?}", block.parent_root()))?;
+
+    let state_root = parent_block.state_root();
+    let state_id = StateId::Root(state_root);
+    let pre_state = client
+        .get_debug_beacon_states_ssz::<T>(state_id, spec)
+        .await
+        .map_err(|e| format!("Failed to download state: {:?}", e))?
+        .ok_or_else(|| format!("Unable to locate state at {}", state_id))?;
+
+    Ok((pre_state, state_root, block))
+}
+
+/// Apply `block` to `pre_state`, logging the time taken by each phase.
+///
+/// Returns the post-state along with its state root, if it was computed.
 fn do_transition<T: EthSpec>(
     mut pre_state: BeaconState<T>,
-    block: SignedBeaconBlock<T>,
-    signature_strategy: BlockSignatureStrategy,
+    mut state_root_opt: Option<Hash256>,
+    block: &SignedBeaconBlock<T>,
+    config: Config,
     spec: &ChainSpec,
-) -> Result<BeaconState<T>, String> {
-    let t = Instant::now();
-    pre_state
-        .build_all_caches(spec)
-        .map_err(|e| format!("Unable to build caches: {:?}", e))?;
-    println!("Build caches: {}ms", t.elapsed().as_millis());
+) -> Result<(BeaconState<T>, Option<Hash256>), String> {
+    if !config.exclude_cache_builds {
+        let t = Instant::now();
+        pre_state
+            .build_all_caches(spec)
+            .map_err(|e| format!("Unable to build caches: {:?}", e))?;
+        debug!("Build caches: {:?}", t.elapsed());
 
-    let t = Instant::now();
-    pre_state
-        .update_tree_hash_cache()
-        .map_err(|e| format!("Unable to build tree hash cache: {:?}", e))?;
-    println!("Initial tree hash: {}ms", t.elapsed().as_millis());
+        let t = Instant::now();
+        let state_root = pre_state
+            .update_tree_hash_cache()
+            .map_err(|e| format!("Unable to build tree hash cache: {:?}", e))?;
+        debug!("Initial tree hash: {:?}", t.elapsed());
+
+        if let Some(expected) = state_root_opt {
+            if state_root != expected {
+                return Err(format!(
+                    "State root mismatch! Expected {}, computed {}",
+                    expected, state_root
+                ));
+            }
+        }
+        state_root_opt = Some(state_root);
+    }
 
     // Transition the parent state to the block slot.
     let t = Instant::now();
     for i in pre_state.slot().as_u64()..block.slot().as_u64() {
-        per_slot_processing(&mut pre_state, None, spec)
+        per_slot_processing(&mut pre_state, state_root_opt, spec)
             .map_err(|e| format!("Failed to advance slot on iteration {}: {:?}", i, e))?;
+        state_root_opt = None;
     }
-    println!("Slot processing: {}ms", t.elapsed().as_millis());
+    debug!("Slot processing: {:?}", t.elapsed());
 
-    let t = Instant::now();
-    pre_state
-        .update_tree_hash_cache()
-        .map_err(|e| format!("Unable to build tree hash cache: {:?}", e))?;
-    println!("Pre-block tree hash: {}ms", t.elapsed().as_millis());
+    if !config.exclude_cache_builds {
+        let t = Instant::now();
+        pre_state
+            .build_all_caches(spec)
+            .map_err(|e| format!("Unable to build caches: {:?}", e))?;
+        debug!("Build all caches (again): {:?}", t.elapsed());
+    }
 
-    let t = Instant::now();
-    pre_state
-        .build_all_caches(spec)
-        .map_err(|e| format!("Unable to build caches: {:?}", e))?;
-    println!("Build all caches (again): {}ms", t.elapsed().as_millis());
+    let signature_strategy = if config.no_signature_verification {
+        BlockSignatureStrategy::NoVerification
+    } else {
+        BlockSignatureStrategy::VerifyIndividual
+    };
+    let verify_block_root = if config.no_block_root_verification {
+        VerifyBlockRoot::False
+    } else {
+        VerifyBlockRoot::True
+    };
 
     let t = Instant::now();
     per_block_processing(
         &mut pre_state,
-        &block,
+        block,
         None,
         signature_strategy,
-        VerifyBlockRoot::True,
+        verify_block_root,
         spec,
     )
     .map_err(|e| format!("State transition failed: {:?}", e))?;
-    println!("Process block: {}ms", t.elapsed().as_millis());
+    debug!("Process block: {:?}", t.elapsed());
+
+    if config.exclude_post_block_thc {
+        return Ok((pre_state, None));
+    }
 
     let t = Instant::now();
-    pre_state
+    let state_root = pre_state
         .update_tree_hash_cache()
         .map_err(|e| format!("Unable to build tree hash cache: {:?}", e))?;
-    println!("Post-block tree hash: {}ms", t.elapsed().as_millis());
+    debug!("Post-block tree hash: {:?}", t.elapsed());
+
+    if !config.no_state_root_verification && state_root != block.state_root() {
+        return Err(format!(
+            "State root mismatch! Block has {}, computed {}",
+            block.state_root(),
+            state_root
+        ));
+    }
 
-    Ok(pre_state)
+    Ok((pre_state, Some(state_root)))
 }
 
 pub fn load_from_ssz_with<T>(
@@ -136,10 +353,14 @@ pub fn load_from_ssz_with<T>(
         .map_err(|e| format!("Unable to read from file {:?}: {:?}", path, e))?;
     let t = Instant::now();
     let result = decoder(&bytes, spec).map_err(|e| format!("Ssz decode failed: {:?}", e));
-    println!(
-        "SSZ decoding {}: {}ms",
-        path.display(),
-        t.elapsed().as_millis()
-    );
+    debug!("SSZ decoding {}: {:?}", path.display(), t.elapsed());
     result
 }
+
+fn write_to_ssz_file<T: Encode>(path: &Path, item: &T) -> Result<(), String> {
+    let mut file = File::create(path)
+        .map_err(|e| format!("Unable to create file {}: {:?}", path.display(), e))?;
+
+    file.write_all(&item.as_ssz_bytes())
+        .map_err(|e| format!("Unable to write to file {}: {:?}", path.display(), e))
+}
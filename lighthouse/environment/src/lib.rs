@@ -12,11 +12,13 @@ use eth2_network_config::Eth2NetworkConfig;
 use futures::channel::mpsc::{channel, Receiver, Sender};
 use futures::{future, StreamExt};
 
-use slog::{error, info, o, warn, Drain, Duplicate, Level, Logger};
+use logging::LogLevels;
+use slog::{debug, error, info, o, warn, Drain, Duplicate, Logger};
 use sloggers::{file::FileLoggerBuilder, types::Format, types::Severity, Build};
 use std::fs::create_dir_all;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use task_executor::{ShutdownReason, TaskExecutor};
 use tokio::runtime::{Builder as RuntimeBuilder, Runtime};
 use types::{EthSpec, GnosisEthSpec, MainnetEthSpec, MinimalEthSpec};
@@ -36,26 +38,40 @@ pub use task_executor::test_utils::null_logger;
 const LOG_CHANNEL_SIZE: usize = 2048;
 /// The maximum time in seconds the client will wait for all internal tasks to shutdown.
 const MAXIMUM_SHUTDOWN_TIME: u64 = 15;
+/// The interval between checks for rotated log files exceeding the maximum age.
+const LOG_PRUNING_INTERVAL: Duration = Duration::from_secs(3_600);
 
 /// Configuration for logging.
 /// Background file logging is disabled if one of:
 /// - `path` == None,
 /// - `max_log_size` == 0,
 /// - `max_log_number` == 0,
+///
+/// The debug log ring buffer is only written alongside the background log file, and is disabled if
+/// `debug_ring_size` == 0.
 pub struct LoggerConfig<'a> {
     pub path: Option<PathBuf>,
     pub debug_level: &'a str,
+    /// Comma-separated `module=level` pairs overriding the `debug_level` for those modules.
+    pub debug_level_overrides: Option<&'a str>,
     pub logfile_debug_level: &'a str,
     pub log_format: Option<&'a str>,
     pub max_log_size: u64,
     pub max_log_number: usize,
+    /// Rotated log files older than this are deleted, regardless of `max_log_number`.
+    pub max_log_age: Option<Duration>,
     pub compression: bool,
+    /// The maximum size in bytes of the debug level logs kept in a separate file.
+    pub debug_ring_size: u64,
 }
 
 /// Builds an `Environment`.
 pub struct EnvironmentBuilder<E: EthSpec> {
     runtime: Option<Arc<Runtime>>,
     log: Option<Logger>,
+    log_levels: Option<LogLevels>,
+    /// The path of the log file and the age after which its rotated files are deleted.
+    log_pruning: Option<(PathBuf, Duration)>,
     eth_spec_instance: E,
     eth2_config: Eth2Config,
     eth2_network_config: Option<Eth2NetworkConfig>,
@@ -67,6 +83,8 @@ impl EnvironmentBuilder<MinimalEthSpec> {
         Self {
             runtime: None,
             log: None,
+            log_levels: None,
+            log_pruning: None,
             eth_spec_instance: MinimalEthSpec,
             eth2_config: Eth2Config::minimal(),
             eth2_network_config: None,
@@ -80,6 +98,8 @@ impl EnvironmentBuilder<MainnetEthSpec> {
         Self {
             runtime: None,
             log: None,
+            log_levels: None,
+            log_pruning: None,
             eth_spec_instance: MainnetEthSpec,
             eth2_config: Eth2Config::mainnet(),
             eth2_network_config: None,
@@ -93,6 +113,8 @@ impl EnvironmentBuilder<GnosisEthSpec> {
         Self {
             runtime: None,
             log: None,
+            log_levels: None,
+            log_pruning: None,
             eth_spec_instance: GnosisEthSpec,
             eth2_config: Eth2Config::gnosis(),
             eth2_network_config: None,
@@ -126,18 +148,22 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
     /// does not have to wait for the logs to be flushed.
     /// The logger can be duplicated and more detailed logs can be output to `logfile`.
     /// Note that background file logging will spawn a new thread.
+    ///
+    /// The levels of the terminal output can be changed after the logger is built, using the
+    /// `LogLevels` made available by `RuntimeContext::log_levels`.
     pub fn initialize_logger(mut self, config: LoggerConfig) -> Result<Self, String> {
         // Setting up the initial logger format and build it.
-        let stdout_drain = if let Some(format) = config.log_format {
-            match format.to_uppercase().as_str() {
-                "JSON" => {
-                    let stdout_drain = slog_json::Json::default(std::io::stdout()).fuse();
-                    slog_async::Async::new(stdout_drain)
-                        .chan_size(LOG_CHANNEL_SIZE)
-                        .build()
-                }
-                _ => return Err("Logging format provided is not supported".to_string()),
-            }
+        let json_format = match config.log_format {
+            Some(format) if format.eq_ignore_ascii_case("JSON") => true,
+            Some(_) => return Err("Logging format provided is not supported".to_string()),
+            None => false,
+        };
+
+        let stdout_drain = if json_format {
+            let stdout_drain = slog_json::Json::default(std::io::stdout()).fuse();
+            slog_async::Async::new(stdout_drain)
+                .chan_size(LOG_CHANNEL_SIZE)
+                .build()
         } else {
             let stdout_decorator = slog_term::TermDecorator::new().build();
             let stdout_decorator =
@@ -148,15 +174,17 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
                 .build()
         };
 
-        let stdout_drain = match config.debug_level {
-            "info" => stdout_drain.filter_level(Level::Info),
-            "debug" => stdout_drain.filter_level(Level::Debug),
-            "trace" => stdout_drain.filter_level(Level::Trace),
-            "warn" => stdout_drain.filter_level(Level::Warning),
-            "error" => stdout_drain.filter_level(Level::Error),
-            "crit" => stdout_drain.filter_level(Level::Critical),
-            unknown => return Err(format!("Unknown debug-level: {}", unknown)),
-        };
+        let debug_level = logging::parse_level(config.debug_level)
+            .map_err(|_| format!("Unknown debug-level: {}", config.debug_level))?;
+        let overrides = config
+            .debug_level_overrides
+            .map(logging::parse_level_overrides)
+            .transpose()
+            .map_err(|e| format!("Invalid debug-level-overrides: {}", e))?
+            .unwrap_or_default();
+        let log_levels = LogLevels::new(debug_level, overrides);
+        let stdout_drain = log_levels.filter(stdout_drain);
+        self.log_levels = Some(log_levels);
 
         let stdout_logger = Logger::root(stdout_drain.fuse(), o!());
 
@@ -213,9 +241,10 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
         let file_logger = FileLoggerBuilder::new(&path)
             .level(logfile_level)
             .channel_size(LOG_CHANNEL_SIZE)
-            .format(match config.log_format {
-                Some("JSON") => Format::Json,
-                _ => Format::default(),
+            .format(if json_format {
+                Format::Json
+            } else {
+                Format::default()
             })
             .rotate_size(config.max_log_size)
             .rotate_keep(config.max_log_number)
@@ -224,7 +253,7 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
             .build()
             .map_err(|e| format!("Unable to build file logger: {}", e))?;
 
-        let log = Logger::root(Duplicate::new(stdout_logger, file_logger).fuse(), o!());
+        let mut log = Logger::root(Duplicate::new(stdout_logger, file_logger).fuse(), o!());
 
         info!(
             log,
@@ -232,6 +261,36 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
             "path" => format!("{:?}", path)
         );
 
+        // Keep the most recent debug logs in a pair of files alternately overwritten, so they're
+        // available when investigating an issue even if the log file has a higher level.
+        if config.debug_ring_size > 0 {
+            let debug_path = debug_ring_path(&path);
+            let debug_logger = FileLoggerBuilder::new(&debug_path)
+                .level(Severity::Debug)
+                .channel_size(LOG_CHANNEL_SIZE)
+                .format(if json_format {
+                    Format::Json
+                } else {
+                    Format::default()
+                })
+                .rotate_size(std::cmp::max(config.debug_ring_size / 2, 1))
+                .rotate_keep(1)
+                .restrict_permissions(true)
+                .build()
+                .map_err(|e| format!("Unable to build debug file logger: {}", e))?;
+
+            log = Logger::root(Duplicate::new(log, debug_logger).fuse(), o!());
+
+            info!(
+                log,
+                "Logging debug logs to file";
+                "path" => format!("{:?}", debug_path),
+                "max_size" => config.debug_ring_size,
+            );
+        }
+
+        self.log_pruning = config.max_log_age.map(|max_age| (path, max_age));
+
         self.log = Some(log);
 
         Ok(self)
@@ -265,15 +324,23 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
     pub fn build(self) -> Result<Environment<E>, String> {
         let (signal, exit) = exit_future::signal();
         let (signal_tx, signal_rx) = channel(1);
+        let runtime = self
+            .runtime
+            .ok_or("Cannot build environment without runtime")?;
+        let log = self.log.ok_or("Cannot build environment without log")?;
+
+        if let Some((path, max_age)) = self.log_pruning {
+            runtime.spawn(prune_rotated_log_files(path, max_age, log.clone()));
+        }
+
         Ok(Environment {
-            runtime: self
-                .runtime
-                .ok_or("Cannot build environment without runtime")?,
+            runtime,
             signal_tx,
             signal_rx: Some(signal_rx),
             signal: Some(signal),
             exit,
-            log: self.log.ok_or("Cannot build environment without log")?,
+            log,
+            log_levels: self.log_levels,
             eth_spec_instance: self.eth_spec_instance,
             eth2_config: self.eth2_config,
             eth2_network_config: self.eth2_network_config.map(Arc::new),
@@ -291,6 +358,8 @@ pub struct RuntimeContext<E: EthSpec> {
     pub eth_spec_instance: E,
     pub eth2_config: Eth2Config,
     pub eth2_network_config: Option<Arc<Eth2NetworkConfig>>,
+    /// The levels of the terminal logs, if they can be changed at runtime.
+    pub log_levels: Option<LogLevels>,
 }
 
 impl<E: EthSpec> RuntimeContext<E> {
@@ -303,6 +372,7 @@ impl<E: EthSpec> RuntimeContext<E> {
             eth_spec_instance: self.eth_spec_instance.clone(),
             eth2_config: self.eth2_config.clone(),
             eth2_network_config: self.eth2_network_config.clone(),
+            log_levels: self.log_levels.clone(),
         }
     }
 
@@ -328,6 +398,7 @@ pub struct Environment<E: EthSpec> {
     signal: Option<exit_future::Signal>,
     exit: exit_future::Exit,
     log: Logger,
+    log_levels: Option<LogLevels>,
     eth_spec_instance: E,
     pub eth2_config: Eth2Config,
     pub eth2_network_config: Option<Arc<Eth2NetworkConfig>>,
@@ -354,6 +425,7 @@ impl<E: EthSpec> Environment<E> {
             eth_spec_instance: self.eth_spec_instance.clone(),
             eth2_config: self.eth2_config.clone(),
             eth2_network_config: self.eth2_network_config.clone(),
+            log_levels: self.log_levels.clone(),
         }
     }
 
@@ -369,6 +441,7 @@ impl<E: EthSpec> Environment<E> {
             eth_spec_instance: self.eth_spec_instance.clone(),
             eth2_config: self.eth2_config.clone(),
             eth2_network_config: self.eth2_network_config.clone(),
+            log_levels: self.log_levels.clone(),
         }
     }
 
@@ -506,6 +579,33 @@ impl<E: EthSpec> Environment<E> {
     }
 }
 
+/// Returns the path of the debug log ring buffer kept alongside the log file at `path`, e.g.
+/// `beacon-debug.log` for `beacon.log`.
+fn debug_ring_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut debug_path = path.with_file_name(format!("{}-debug", stem));
+    if let Some(extension) = path.extension() {
+        debug_path.set_extension(extension);
+    }
+    debug_path
+}
+
+/// Periodically delete the files rotated from the log file at `path` which are older than
+/// `max_age`.
+async fn prune_rotated_log_files(path: PathBuf, max_age: Duration, log: Logger) {
+    loop {
+        match logging::prune_rotated_log_files(&path, max_age) {
+            Ok(0) => (),
+            Ok(count) => debug!(log, "Deleted expired log files"; "count" => count),
+            Err(e) => warn!(log, "Unable to delete expired log files"; "error" => e),
+        }
+        tokio::time::sleep(LOG_PRUNING_INTERVAL).await;
+    }
+}
+
 #[cfg(target_family = "unix")]
 struct SignalFuture {
     signal: Signal,
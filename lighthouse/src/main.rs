@@ -15,6 +15,7 @@ use malloc_utils::configure_memory_allocator;
 use slog::{crit, info, warn};
 use std::path::PathBuf;
 use std::process::exit;
+use std::time::Duration;
 use task_executor::ShutdownReason;
 use types::{EthSpec, EthSpecId};
 use validator_client::ProductionValidatorClient;
@@ -121,6 +122,29 @@ fn main() {
                 .default_value("5")
                 .global(true),
         )
+        .arg(
+            Arg::with_name("logfile-max-age")
+                .long("logfile-max-age")
+                .value_name("DAYS")
+                .help(
+                    "If present, log files which were rotated more than this many days ago are \
+                    deleted, even if there are fewer than `--logfile-max-number` of them.")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("logfile-debug-ring-size")
+                .long("logfile-debug-ring-size")
+                .value_name("SIZE")
+                .help(
+                    "The maximum size (in MB) of a separate file alongside the log file which \
+                    holds the most recent debug level logs, regardless of \
+                    `--logfile-debug-level`. The oldest logs are overwritten once the size is \
+                    reached. If set to 0, the debug log file is disabled.")
+                .takes_value(true)
+                .default_value("0")
+                .global(true),
+        )
         .arg(
             Arg::with_name("logfile-compress")
                 .long("logfile-compress")
@@ -135,6 +159,7 @@ fn main() {
                 .value_name("FORMAT")
                 .help("Specifies the log format used when emitting logs to the terminal.")
                 .possible_values(&["JSON"])
+                .case_insensitive(true)
                 .takes_value(true)
                 .global(true),
         )
@@ -148,6 +173,27 @@ fn main() {
                 .global(true)
                 .default_value("info"),
         )
+        .arg(
+            Arg::with_name("debug-level-overrides")
+                .long("debug-level-overrides")
+                .value_name("MODULE=LEVEL,...")
+                .help("Overrides the --debug-level for the given modules, as a comma-separated \
+                    list of module=level pairs, e.g. `network=debug,beacon_chain=info`. A module \
+                    is a prefix of the Rust module path of the log, such as a crate name. The \
+                    levels can also be changed at runtime via the beacon node HTTP API.")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("otlp-endpoint")
+                .long("otlp-endpoint")
+                .value_name("URL")
+                .help("Export tracing spans of block import and production to the OTLP gRPC \
+                    endpoint of an OpenTelemetry collector, e.g. http://localhost:4317. \
+                    Requires Lighthouse to be compiled with the `otlp` feature.")
+                .takes_value(true)
+                .global(true),
+        )
         .arg(
             Arg::with_name("datadir")
                 .long("datadir")
@@ -276,6 +322,9 @@ fn main() {
         .subcommand(validator_client::cli_app())
         .subcommand(account_manager::cli_app())
         .subcommand(database_manager::cli_app())
+        .subcommand(doctor::cli_app())
+        .subcommand(top::cli_app())
+        .subcommand(slasher_standalone::cli_app())
         .get_matches();
 
     // Configure the allocator early in the process, before it has the chance to use the default values for
@@ -370,6 +419,8 @@ fn run<E: EthSpec>(
         .value_of("debug-level")
         .ok_or("Expected --debug-level flag")?;
 
+    let debug_level_overrides = matches.value_of("debug-level-overrides");
+
     let log_format = matches.value_of("log-format");
 
     let logfile_debug_level = matches
@@ -388,8 +439,14 @@ fn run<E: EthSpec>(
         .parse()
         .map_err(|e| format!("Failed to parse `logfile-max-number`: {:?}", e))?;
 
+    let logfile_max_age = clap_utils::parse_optional::<u64>(matches, "logfile-max-age")?
+        .map(|days| Duration::from_secs(days * 24 * 60 * 60));
+
     let logfile_compress = matches.is_present("logfile-compress");
 
+    let logfile_debug_ring_size: u64 =
+        clap_utils::parse_required(matches, "logfile-debug-ring-size")?;
+
     // Construct the path to the log file.
     let mut log_path: Option<PathBuf> = clap_utils::parse_optional(matches, "logfile")?;
     if log_path.is_none() {
@@ -422,11 +479,14 @@ fn run<E: EthSpec>(
     let logger_config = LoggerConfig {
         path: log_path,
         debug_level,
+        debug_level_overrides,
         logfile_debug_level,
         log_format,
         max_log_size: logfile_max_size * 1_024 * 1_024,
         max_log_number: logfile_max_number,
+        max_log_age: logfile_max_age,
         compression: logfile_compress,
+        debug_ring_size: logfile_debug_ring_size * 1_024 * 1_024,
     };
 
     let builder = environment_builder.initialize_logger(logger_config)?;
@@ -438,6 +498,12 @@ fn run<E: EthSpec>(
 
     let log = environment.core_context().log().clone();
 
+    let otlp_endpoint = clap_utils::parse_optional::<String>(matches, "otlp-endpoint")?;
+    if let Some(endpoint) = &otlp_endpoint {
+        init_otlp_tracing(&environment, endpoint)?;
+        info!(log, "Exporting tracing spans"; "endpoint" => endpoint);
+    }
+
     // Allow Prometheus to export the time at which the process was started.
     metrics::expose_process_start_time(&log);
 
@@ -497,6 +563,31 @@ fn run<E: EthSpec>(
         return Ok(());
     }
 
+    if let Some(sub_matches) = matches.subcommand_matches(doctor::CMD) {
+        eprintln!("Running diagnostics for {} network", network_name);
+        doctor::run(sub_matches, environment)?;
+
+        // Exit as soon as the diagnostics are complete.
+        return Ok(());
+    }
+
+    if let Some(sub_matches) = matches.subcommand_matches(top::CMD) {
+        // Runs until interrupted.
+        top::run(sub_matches, environment)?;
+
+        return Ok(());
+    }
+
+    if let Some(sub_matches) = matches
+        .subcommand_matches(slasher_standalone::CMD)
+        .and_then(|matches| matches.subcommand_matches(slasher_standalone::export::CMD))
+    {
+        slasher_standalone::export::run::<E>(sub_matches, log)?;
+
+        // Exit as soon as the export is complete.
+        return Ok(());
+    }
+
     info!(log, "Lighthouse started"; "version" => VERSION);
     info!(
         log,
@@ -563,6 +654,25 @@ fn run<E: EthSpec>(
                 ));
             }
         }
+        (slasher_standalone::CMD, Some(matches)) => {
+            let context = environment.core_context();
+            let log = context.log().clone();
+            let executor = context.executor.clone();
+            let config = slasher_standalone::Config::from_cli(matches)?;
+            executor.clone().spawn(
+                async move {
+                    if let Err(e) = slasher_standalone::run(context, config).await {
+                        crit!(log, "Failed to start slasher"; "reason" => e);
+                        // Ignore the error since it always occurs during normal operation when
+                        // shutting down.
+                        let _ = executor
+                            .shutdown_sender()
+                            .try_send(ShutdownReason::Failure("Failed to start slasher"));
+                    }
+                },
+                "slasher",
+            );
+        }
         _ => {
             crit!(log, "No subcommand supplied. See --help .");
             return Err("No subcommand supplied.".into());
@@ -573,6 +683,12 @@ fn run<E: EthSpec>(
     let shutdown_reason = environment.block_until_shutdown_requested()?;
     info!(log, "Shutting down.."; "reason" => ?shutdown_reason);
 
+    // Export the remaining spans whilst the runtime is still running.
+    #[cfg(feature = "otlp")]
+    if otlp_endpoint.is_some() {
+        logging::otlp::shutdown();
+    }
+
     environment.fire_signal();
 
     // Shutdown the environment once all tasks have completed.
@@ -583,3 +699,21 @@ fn run<E: EthSpec>(
         ShutdownReason::Failure(msg) => Err(msg.to_string()),
     }
 }
+
+#[cfg(feature = "otlp")]
+fn init_otlp_tracing<E: EthSpec>(
+    environment: &environment::Environment<E>,
+    endpoint: &str,
+) -> Result<(), String> {
+    // The exporter runs as a task on the runtime.
+    let _guard = environment.runtime().enter();
+    logging::otlp::init(endpoint, "lighthouse")
+}
+
+#[cfg(not(feature = "otlp"))]
+fn init_otlp_tracing<E: EthSpec>(
+    _environment: &environment::Environment<E>,
+    _endpoint: &str,
+) -> Result<(), String> {
+    Err("--otlp-endpoint requires Lighthouse to be compiled with the otlp feature".to_string())
+}
@@ -1,7 +1,7 @@
 use beacon_node::ClientConfig as Config;
 
 use crate::exec::{CommandLineTestExec, CompletedTest};
-use lighthouse_network::PeerId;
+use lighthouse_network::{PeerId, ScoredTopic};
 use std::fs::File;
 use std::io::Write;
 use std::net::IpAddr;
@@ -9,6 +9,7 @@ use std::path::PathBuf;
 use std::process::Command;
 use std::str::FromStr;
 use std::string::ToString;
+use std::time::Duration;
 use tempfile::TempDir;
 use types::{Address, Checkpoint, Epoch, ExecutionBlockHash, Hash256, MainnetEthSpec};
 use unused_port::{unused_tcp_port, unused_udp_port};
@@ -128,6 +129,68 @@ fn fork_choice_before_proposal_timeout_zero() {
         .with_config(|config| assert_eq!(config.chain.fork_choice_before_proposal_timeout_ms, 0));
 }
 
+#[test]
+fn maximum_gossip_clock_disparity_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.maximum_gossip_clock_disparity_ms,
+                beacon_node::beacon_chain::chain_config::DEFAULT_MAXIMUM_GOSSIP_CLOCK_DISPARITY_MS
+            )
+        });
+}
+
+#[test]
+fn maximum_gossip_clock_disparity_flag() {
+    CommandLineTest::new()
+        .flag("maximum-gossip-clock-disparity", Some("1500"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.maximum_gossip_clock_disparity_ms, 1500));
+}
+
+#[test]
+fn memory_watermark_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.memory_watermark, None));
+}
+
+#[test]
+fn memory_watermark_flag() {
+    CommandLineTest::new()
+        .flag("memory-watermark", Some("4096"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.memory_watermark, Some(4096 * 1_048_576)));
+}
+
+#[test]
+fn crash_dump_dir_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.crash_dump_dir, None));
+}
+
+#[test]
+fn crash_dump_dir_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag("crash-dump-dir", dir.path().as_os_str().to_str())
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.chain.crash_dump_dir, Some(dir.path().to_path_buf()))
+        });
+}
+
+#[test]
+#[should_panic]
+#[cfg(not(feature = "otlp"))]
+fn otlp_endpoint_flag_without_otlp_feature() {
+    CommandLineTest::new()
+        .flag("otlp-endpoint", Some("http://localhost:4317"))
+        .run_with_zero_port();
+}
+
 #[test]
 fn freezer_dir_flag() {
     let dir = TempDir::new().expect("Unable to create temporary directory");
@@ -137,6 +200,40 @@ fn freezer_dir_flag() {
         .with_config(|config| assert_eq!(config.freezer_db_path, Some(dir.path().to_path_buf())));
 }
 
+#[test]
+fn hot_db_backend_default() {
+    use beacon_node::beacon_chain::store::HotBackend;
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.hot_backend, HotBackend::LevelDb));
+}
+
+#[test]
+fn hot_db_backend_flag() {
+    use beacon_node::beacon_chain::store::HotBackend;
+    CommandLineTest::new()
+        .flag("hot-db-backend", Some("redb"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.hot_backend, HotBackend::Redb));
+}
+
+#[test]
+fn freezer_backend_default() {
+    use beacon_node::beacon_chain::store::FreezerBackend;
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.freezer_backend, FreezerBackend::LevelDb));
+}
+
+#[test]
+fn freezer_backend_flag() {
+    use beacon_node::beacon_chain::store::FreezerBackend;
+    CommandLineTest::new()
+        .flag("freezer-backend", Some("sled"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.freezer_backend, FreezerBackend::Sled));
+}
+
 #[test]
 fn graffiti_flag() {
     CommandLineTest::new()
@@ -170,6 +267,68 @@ fn trusted_peers_flag() {
             );
         });
 }
+#[test]
+fn trusted_peers_flag_multiaddr() {
+    let peer = PeerId::random();
+    let multiaddr = format!("/ip4/127.0.0.1/tcp/9000/p2p/{}", peer);
+    CommandLineTest::new()
+        .flag("trusted-peers", Some(multiaddr.as_str()))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                PeerId::from(config.network.trusted_peers[0].clone()).to_bytes(),
+                peer.to_bytes()
+            );
+            assert_eq!(
+                config.network.trusted_peer_addresses[0].to_string(),
+                multiaddr
+            );
+        });
+}
+#[test]
+#[should_panic]
+fn trusted_peers_flag_multiaddr_without_peer_id() {
+    CommandLineTest::new()
+        .flag("trusted-peers", Some("/ip4/127.0.0.1/tcp/9000"))
+        .run_with_zero_port();
+}
+#[test]
+fn allowlist_file_flag() {
+    let peer = PeerId::random();
+    let multiaddr = format!("/ip4/10.0.0.2/tcp/9000/p2p/{}", PeerId::random());
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("allowlist.txt");
+    std::fs::write(
+        &path,
+        format!("# consortium peers\n{}\n\n{}\n", peer, multiaddr),
+    )
+    .expect("Unable to write to file");
+    CommandLineTest::new()
+        .flag("allowlist-file", path.as_os_str().to_str())
+        .run_with_zero_port()
+        .with_config(|config| {
+            let allowlist = config.network.peer_allowlist.as_ref().unwrap();
+            assert_eq!(allowlist.len(), 2);
+            assert_eq!(
+                PeerId::from(allowlist[0].clone()).to_bytes(),
+                peer.to_bytes()
+            );
+            assert_eq!(
+                config.network.peer_allowlist_addresses[0].to_string(),
+                multiaddr
+            );
+        });
+}
+#[test]
+#[should_panic]
+fn allowlist_file_flag_empty() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("allowlist.txt");
+    std::fs::write(&path, "# no peers\n").expect("Unable to write to file");
+    CommandLineTest::new()
+        .flag("allowlist-file", path.as_os_str().to_str())
+        .run_with_zero_port();
+}
 
 // Tests for Eth1 flags.
 #[test]
@@ -420,6 +579,32 @@ fn network_import_all_attestations_flag() {
         .with_config(|config| assert!(config.network.import_all_attestations));
 }
 #[test]
+fn light_client_server_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(!config.chain.enable_light_client_server);
+            assert!(!config.network.enable_light_client_server);
+        });
+}
+#[test]
+fn light_client_server_flag() {
+    CommandLineTest::new()
+        .flag("light-client-server", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.chain.enable_light_client_server);
+            assert!(config.network.enable_light_client_server);
+        });
+}
+#[test]
+fn network_deterministic_long_lived_attnets_flag() {
+    CommandLineTest::new()
+        .flag("deterministic-long-lived-attnets", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.network.deterministic_long_lived_attnets));
+}
+#[test]
 fn network_shutdown_after_sync_flag() {
     CommandLineTest::new()
         .flag("shutdown-after-sync", None)
@@ -582,6 +767,97 @@ fn network_load_flag() {
             assert_eq!(config.network.network_load, 4);
         });
 }
+#[test]
+fn sync_max_bandwidth_flags() {
+    CommandLineTest::new()
+        .flag("sync-max-bandwidth", Some("20"))
+        .flag("backfill-max-bandwidth", Some("5"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.sync_max_bandwidth, Some(20));
+            assert_eq!(config.network.backfill_max_bandwidth, Some(5));
+        });
+}
+#[test]
+fn sync_max_bandwidth_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.sync_max_bandwidth, None);
+            assert_eq!(config.network.backfill_max_bandwidth, None);
+        });
+}
+#[test]
+#[should_panic]
+fn sync_max_bandwidth_zero() {
+    CommandLineTest::new()
+        .flag("sync-max-bandwidth", Some("0"))
+        .run_with_zero_port();
+}
+#[test]
+fn beacon_processor_workers_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.beacon_processor_max_workers, None);
+            assert_eq!(config.network.beacon_processor_priority_workers, 1);
+        });
+}
+#[test]
+fn beacon_processor_workers_flags() {
+    CommandLineTest::new()
+        .flag("beacon-processor-max-workers", Some("8"))
+        .flag("beacon-processor-priority-workers", Some("2"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.beacon_processor_max_workers, Some(8));
+            assert_eq!(config.network.beacon_processor_priority_workers, 2);
+        });
+}
+#[test]
+fn peer_diversity_flags() {
+    CommandLineTest::new()
+        .flag("peer-diversity-client-share", Some("0.4"))
+        .flag("peer-diversity-asn-share", Some("0.25"))
+        .flag("asn-database", Some("/tmp/ip2asn-combined.tsv"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.peer_diversity_max_client_share, Some(0.4));
+            assert_eq!(config.network.peer_diversity_max_asn_share, Some(0.25));
+            assert_eq!(
+                config.network.asn_database,
+                Some(PathBuf::from("/tmp/ip2asn-combined.tsv"))
+            );
+        });
+}
+#[test]
+#[should_panic]
+fn peer_diversity_share_out_of_range() {
+    CommandLineTest::new()
+        .flag("peer-diversity-client-share", Some("1.5"))
+        .run_with_zero_port();
+}
+#[test]
+fn gossipsub_scoring_file_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("scoring.yaml");
+    std::fs::write(
+        &path,
+        "topic_score_cap: 10.0\ntopics:\n  beacon_block:\n    topic_weight: 2.0\n",
+    )
+    .expect("Unable to write to file");
+    CommandLineTest::new()
+        .flag("gossipsub-scoring-file", path.as_os_str().to_str())
+        .run_with_zero_port()
+        .with_config(|config| {
+            let overrides = config.network.gossipsub_scoring_overrides.as_ref().unwrap();
+            assert_eq!(overrides.topic_score_cap, Some(10.0));
+            assert_eq!(
+                overrides.topics[&ScoredTopic::BeaconBlock].topic_weight,
+                Some(2.0)
+            );
+        });
+}
 
 // Tests for ENR flags.
 #[test]
@@ -601,6 +877,68 @@ fn enr_tcp_port_flags() {
         .with_config(|config| assert_eq!(config.network.enr_tcp_port, Some(port)));
 }
 #[test]
+fn enr_fields_flag() {
+    CommandLineTest::new()
+        .flag("enr-fields", Some("operator=example,region=eu-west"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.enr_custom_fields,
+                vec![
+                    ("operator".to_string(), b"example".to_vec()),
+                    ("region".to_string(), b"eu-west".to_vec()),
+                ]
+            )
+        });
+}
+#[test]
+#[should_panic]
+fn enr_fields_flag_reserved_key() {
+    CommandLineTest::new()
+        .flag("enr-fields", Some("tcp=9000"))
+        .run_with_zero_port();
+}
+#[test]
+fn disable_inbound_flag() {
+    CommandLineTest::new()
+        .flag("disable-inbound", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.network.disable_inbound);
+            assert!(!config.network.upnp_enabled);
+            assert!(!config.network.discv5_config.enr_update);
+        });
+}
+#[test]
+#[should_panic]
+fn disable_inbound_flag_with_enr_tcp_port() {
+    CommandLineTest::new()
+        .flag("disable-inbound", None)
+        .flag("enr-tcp-port", Some("9000"))
+        .run_with_zero_port();
+}
+#[test]
+fn rpc_max_request_blocks_flag() {
+    CommandLineTest::new()
+        .flag("rpc-max-request-blocks", Some("4096"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.max_request_blocks, Some(4096)));
+}
+#[test]
+fn rpc_max_request_blocks_flag_spec_limit() {
+    CommandLineTest::new()
+        .flag("rpc-max-request-blocks", Some("1024"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.max_request_blocks, None));
+}
+#[test]
+#[should_panic]
+fn rpc_max_request_blocks_flag_above_hard_cap() {
+    CommandLineTest::new()
+        .flag("rpc-max-request-blocks", Some("4097"))
+        .run_with_zero_port();
+}
+#[test]
 fn enr_match_flag() {
     let addr = "127.0.0.2".parse::<IpAddr>().unwrap();
     let port1 = unused_udp_port().expect("Unable to find unused port.");
@@ -707,82 +1045,234 @@ fn http_allow_origin_all_flag() {
         .with_config(|config| assert_eq!(config.http_api.allow_origin, Some("*".to_string())));
 }
 #[test]
-fn http_allow_sync_stalled_flag() {
-    CommandLineTest::new()
-        .flag("http-allow-sync-stalled", None)
-        .run_with_zero_port()
-        .with_config(|config| assert_eq!(config.http_api.allow_sync_stalled, true));
-}
-#[test]
-fn http_tls_flags() {
-    let dir = TempDir::new().expect("Unable to create temporary directory");
+fn http_allow_origin_pattern_flag() {
     CommandLineTest::new()
-        .flag("http-enable-tls", None)
-        .flag(
-            "http-tls-cert",
-            dir.path().join("certificate.crt").as_os_str().to_str(),
-        )
         .flag(
-            "http-tls-key",
-            dir.path().join("private.key").as_os_str().to_str(),
+            "http-allow-origin",
+            Some("http://localhost:5052,https://*.example.com"),
         )
         .run_with_zero_port()
         .with_config(|config| {
-            let tls_config = config
-                .http_api
-                .tls_config
-                .as_ref()
-                .expect("tls_config was empty.");
-            assert_eq!(tls_config.cert, dir.path().join("certificate.crt"));
-            assert_eq!(tls_config.key, dir.path().join("private.key"));
+            assert_eq!(
+                config.http_api.allow_origin,
+                Some("http://localhost:5052,https://*.example.com".to_string())
+            )
         });
 }
-
-// Tests for Metrics flags.
 #[test]
-fn metrics_flag() {
+fn http_compression_enabled_by_default() {
     CommandLineTest::new()
-        .flag("metrics", None)
         .run_with_zero_port()
-        .with_config(|config| {
-            assert!(config.http_metrics.enabled);
-            assert!(config.network.metrics_enabled);
-        });
+        .with_config(|config| assert!(config.http_api.enable_compression));
 }
 #[test]
-fn metrics_address_flag() {
-    let addr = "127.0.0.99".parse::<IpAddr>().unwrap();
+fn http_disable_compression_flag() {
     CommandLineTest::new()
-        .flag("metrics", None)
-        .flag("metrics-address", Some("127.0.0.99"))
+        .flag("http-disable-compression", None)
         .run_with_zero_port()
-        .with_config(|config| assert_eq!(config.http_metrics.listen_addr, addr));
+        .with_config(|config| assert!(!config.http_api.enable_compression));
 }
 #[test]
-fn metrics_address_ipv6_flag() {
-    let addr = "::1".parse::<IpAddr>().unwrap();
+fn http_max_concurrent_state_replays_flag() {
     CommandLineTest::new()
-        .flag("metrics", None)
-        .flag("metrics-address", Some("::1"))
+        .flag("http-max-concurrent-state-replays", Some("4"))
         .run_with_zero_port()
-        .with_config(|config| assert_eq!(config.http_metrics.listen_addr, addr));
+        .with_config(|config| assert_eq!(config.http_api.max_concurrent_state_replays, 4));
 }
 #[test]
-fn metrics_port_flag() {
-    let port1 = unused_tcp_port().expect("Unable to find unused port.");
-    let port2 = unused_tcp_port().expect("Unable to find unused port.");
+#[should_panic]
+fn http_max_concurrent_state_replays_flag_zero() {
     CommandLineTest::new()
-        .flag("metrics", None)
-        .flag("metrics-port", Some(port1.to_string().as_str()))
-        .flag("port", Some(port2.to_string().as_str()))
-        .run()
-        .with_config(|config| assert_eq!(config.http_metrics.listen_port, port1));
+        .flag("http-max-concurrent-state-replays", Some("0"))
+        .run_with_zero_port();
 }
 #[test]
-fn metrics_allow_origin_flag() {
+fn http_state_replay_timeout_flag() {
     CommandLineTest::new()
-        .flag("metrics", None)
-        .flag("metrics-allow-origin", Some("http://localhost:5059"))
+        .flag("http-state-replay-timeout", Some("10"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.http_api.state_replay_time_budget,
+                Duration::from_secs(10)
+            )
+        });
+}
+#[test]
+fn http_rate_limit_flags() {
+    CommandLineTest::new()
+        .flag("http-rate-limit", Some("50"))
+        .flag("http-rate-limit-burst", Some("500"))
+        .flag("http-rate-limit-costs", Some("2,10,100"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let rate_limit = config.http_api.rate_limit.as_ref().unwrap();
+            assert_eq!(rate_limit.tokens_per_second, 50);
+            assert_eq!(rate_limit.burst, 500);
+            assert_eq!(rate_limit.cheap_cost, 2);
+            assert_eq!(rate_limit.standard_cost, 10);
+            assert_eq!(rate_limit.expensive_cost, 100);
+        });
+}
+#[test]
+#[should_panic]
+fn http_rate_limit_flag_zero() {
+    CommandLineTest::new()
+        .flag("http-rate-limit", Some("0"))
+        .run_with_zero_port();
+}
+#[test]
+fn http_rate_limit_default_costs() {
+    CommandLineTest::new()
+        .flag("http-rate-limit", Some("50"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let rate_limit = config.http_api.rate_limit.as_ref().unwrap();
+            assert_eq!(rate_limit.tokens_per_second, 50);
+            assert_eq!(rate_limit.burst, 200);
+            assert_eq!(rate_limit.cheap_cost, 1);
+            assert_eq!(rate_limit.standard_cost, 5);
+            assert_eq!(rate_limit.expensive_cost, 50);
+        });
+}
+#[test]
+fn http_rate_limit_disabled_by_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.rate_limit, None));
+}
+#[test]
+#[should_panic]
+fn http_rate_limit_costs_invalid() {
+    CommandLineTest::new()
+        .flag("http-rate-limit", Some("50"))
+        .flag("http-rate-limit-costs", Some("1,5"))
+        .run_with_zero_port();
+}
+#[test]
+fn http_serve_finalized_only_flag() {
+    CommandLineTest::new()
+        .flag("http-serve-finalized-only", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.http_api.serve_finalized_only));
+}
+#[test]
+fn http_serve_finalized_only_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.http_api.serve_finalized_only));
+}
+#[test]
+fn http_allow_sync_stalled_flag() {
+    CommandLineTest::new()
+        .flag("http-allow-sync-stalled", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.allow_sync_stalled, true));
+}
+#[test]
+fn http_auth_token_flags() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    std::fs::write(dir.path().join("validator-token"), "validator-secret\n")
+        .expect("Unable to write to file");
+    std::fs::write(dir.path().join("admin-token"), "admin-secret\n")
+        .expect("Unable to write to file");
+    CommandLineTest::new()
+        .flag(
+            "http-validator-token-file",
+            dir.path().join("validator-token").as_os_str().to_str(),
+        )
+        .flag(
+            "http-admin-token-file",
+            dir.path().join("admin-token").as_os_str().to_str(),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.http_api.auth.validator_token,
+                Some("validator-secret".to_string())
+            );
+            assert_eq!(
+                config.http_api.auth.admin_token,
+                Some("admin-secret".to_string())
+            );
+        });
+}
+#[test]
+fn http_auth_disabled_by_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.auth, Default::default()));
+}
+#[test]
+fn http_tls_flags() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag("http-enable-tls", None)
+        .flag(
+            "http-tls-cert",
+            dir.path().join("certificate.crt").as_os_str().to_str(),
+        )
+        .flag(
+            "http-tls-key",
+            dir.path().join("private.key").as_os_str().to_str(),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            let tls_config = config
+                .http_api
+                .tls_config
+                .as_ref()
+                .expect("tls_config was empty.");
+            assert_eq!(tls_config.cert, dir.path().join("certificate.crt"));
+            assert_eq!(tls_config.key, dir.path().join("private.key"));
+        });
+}
+
+// Tests for Metrics flags.
+#[test]
+fn metrics_flag() {
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.http_metrics.enabled);
+            assert!(config.network.metrics_enabled);
+        });
+}
+#[test]
+fn metrics_address_flag() {
+    let addr = "127.0.0.99".parse::<IpAddr>().unwrap();
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .flag("metrics-address", Some("127.0.0.99"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_metrics.listen_addr, addr));
+}
+#[test]
+fn metrics_address_ipv6_flag() {
+    let addr = "::1".parse::<IpAddr>().unwrap();
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .flag("metrics-address", Some("::1"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_metrics.listen_addr, addr));
+}
+#[test]
+fn metrics_port_flag() {
+    let port1 = unused_tcp_port().expect("Unable to find unused port.");
+    let port2 = unused_tcp_port().expect("Unable to find unused port.");
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .flag("metrics-port", Some(port1.to_string().as_str()))
+        .flag("port", Some(port2.to_string().as_str()))
+        .run()
+        .with_config(|config| assert_eq!(config.http_metrics.listen_port, port1));
+}
+#[test]
+fn metrics_allow_origin_flag() {
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .flag("metrics-allow-origin", Some("http://localhost:5059"))
         .run_with_zero_port()
         .with_config(|config| {
             assert_eq!(
@@ -799,6 +1289,28 @@ fn metrics_allow_origin_all_flag() {
         .run_with_zero_port()
         .with_config(|config| assert_eq!(config.http_metrics.allow_origin, Some("*".to_string())));
 }
+#[test]
+fn monitoring_endpoint_flags() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let token_path = dir.path().join("token.txt");
+    File::create(&token_path)
+        .and_then(|mut file| file.write_all(b"secret"))
+        .expect("Unable to write token file");
+    CommandLineTest::new()
+        .flag("monitoring-endpoint", Some("https://example.com/api"))
+        .flag("monitoring-endpoint-period", Some("30"))
+        .flag("monitoring-endpoint-token-file", token_path.to_str())
+        .run_with_zero_port()
+        .with_config(|config| {
+            let monitoring_api = config.monitoring_api.as_ref().unwrap();
+            assert_eq!(
+                monitoring_api.monitoring_endpoint,
+                "https://example.com/api"
+            );
+            assert_eq!(monitoring_api.update_period_secs, Some(30));
+            assert_eq!(monitoring_api.api_token_path, Some(token_path.clone()));
+        });
+}
 
 // Tests for Validator Monitor flags.
 #[test]
@@ -834,6 +1346,26 @@ fn validator_monitor_file_flag() {
             assert_eq!(config.validator_monitor_pubkeys[1].to_string(), "0xbeefdeadbeefdeaddeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef");
         });
 }
+#[test]
+fn validator_monitor_individual_tracking_threshold_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.validator_monitor_individual_tracking_threshold, 64)
+        });
+}
+#[test]
+fn validator_monitor_individual_tracking_threshold_flag() {
+    CommandLineTest::new()
+        .flag(
+            "validator-monitor-individual-tracking-threshold",
+            Some("1000"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.validator_monitor_individual_tracking_threshold, 1000)
+        });
+}
 
 // Tests for Store flags.
 #[test]
@@ -877,6 +1409,125 @@ fn slots_per_restore_point_update_prev_default() {
         })
 }
 
+#[test]
+fn restore_points_per_snapshot_default() {
+    use beacon_node::beacon_chain::store::config::DEFAULT_RESTORE_POINTS_PER_SNAPSHOT;
+
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.restore_points_per_snapshot,
+                DEFAULT_RESTORE_POINTS_PER_SNAPSHOT
+            )
+        });
+}
+#[test]
+fn restore_points_per_snapshot_flag() {
+    CommandLineTest::new()
+        .flag("restore-points-per-snapshot", Some("1"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.restore_points_per_snapshot, 1));
+}
+#[test]
+#[should_panic]
+fn restore_points_per_snapshot_zero_flag() {
+    CommandLineTest::new()
+        .flag("restore-points-per-snapshot", Some("0"))
+        .run_with_zero_port();
+}
+#[test]
+fn prune_history_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.store.history_retention_epochs, None);
+            assert!(!config.store.prune_history_blocks);
+        });
+}
+#[test]
+fn prune_history_flag() {
+    use beacon_node::beacon_chain::store::config::DEFAULT_HISTORY_RETENTION_EPOCHS;
+
+    CommandLineTest::new()
+        .flag("prune-history", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.history_retention_epochs,
+                Some(DEFAULT_HISTORY_RETENTION_EPOCHS)
+            );
+            assert!(!config.store.prune_history_blocks);
+        });
+}
+#[test]
+fn history_retention_epochs_flag() {
+    CommandLineTest::new()
+        .flag("prune-history", None)
+        .flag("history-retention-epochs", Some("1024"))
+        .flag("prune-history-blocks", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.store.history_retention_epochs, Some(1024));
+            assert!(config.store.prune_history_blocks);
+        });
+}
+#[test]
+#[should_panic]
+fn prune_history_blocks_without_prune_history_flag() {
+    CommandLineTest::new()
+        .flag("prune-history-blocks", None)
+        .run_with_zero_port();
+}
+#[test]
+#[should_panic]
+fn prune_history_with_reconstruct_historic_states_flag() {
+    CommandLineTest::new()
+        .flag("prune-history", None)
+        .flag("reconstruct-historic-states", None)
+        .run_with_zero_port();
+}
+#[test]
+fn reconstruct_payloads_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.chain.reconstruct_payloads));
+}
+#[test]
+fn reconstruct_payloads_flag() {
+    CommandLineTest::new()
+        .flag("reconstruct-payloads", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.chain.reconstruct_payloads));
+}
+#[test]
+fn genesis_state_url_flag() {
+    let root = "0x7e76880eb67bbdc86250aa578958e9d0675e64e714337855204fb5abaaf82c2b";
+    CommandLineTest::new()
+        .flag("genesis-state-url", Some("http://localhost:5052"))
+        .flag("genesis-state-root", Some(root))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config
+                    .genesis_state_url
+                    .as_ref()
+                    .map(|url| url.full.as_str()),
+                Some("http://localhost:5052/")
+            );
+            assert_eq!(
+                config.genesis_state_root,
+                Some(Hash256::from_str(root).unwrap())
+            );
+        });
+}
+#[test]
+#[should_panic]
+fn genesis_state_url_without_root() {
+    CommandLineTest::new()
+        .flag("genesis-state-url", Some("http://localhost:5052"))
+        .run_with_zero_port();
+}
 #[test]
 fn block_cache_size_flag() {
     CommandLineTest::new()
@@ -885,6 +1536,70 @@ fn block_cache_size_flag() {
         .with_config(|config| assert_eq!(config.store.block_cache_size, 4_usize));
 }
 #[test]
+fn epoch_boundary_state_cache_size_default() {
+    use beacon_node::beacon_chain::store::config::DEFAULT_EPOCH_BOUNDARY_STATE_CACHE_SIZE;
+
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.epoch_boundary_state_cache_size,
+                DEFAULT_EPOCH_BOUNDARY_STATE_CACHE_SIZE
+            )
+        });
+}
+#[test]
+fn epoch_boundary_state_cache_size_flag() {
+    CommandLineTest::new()
+        .flag("epoch-boundary-state-cache-size", Some("16"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.epoch_boundary_state_cache_size, 16_usize));
+}
+#[test]
+fn historic_replay_threads_default() {
+    use beacon_node::beacon_chain::store::config::DEFAULT_MAX_REPLAY_THREADS;
+
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.store.max_replay_threads, DEFAULT_MAX_REPLAY_THREADS)
+        });
+}
+#[test]
+fn historic_replay_threads_flag() {
+    CommandLineTest::new()
+        .flag("historic-replay-threads", Some("8"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.max_replay_threads, 8));
+}
+#[test]
+#[should_panic]
+fn historic_replay_threads_zero() {
+    CommandLineTest::new()
+        .flag("historic-replay-threads", Some("0"))
+        .run_with_zero_port();
+}
+#[test]
+fn blob_prune_epochs_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.blob_retention_epochs, None));
+}
+#[test]
+fn blob_prune_epochs_flag() {
+    CommandLineTest::new()
+        .flag("blob-prune-epochs", Some("8192"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.blob_retention_epochs, Some(8192)));
+}
+#[test]
+#[should_panic]
+fn blob_prune_epochs_below_spec_minimum() {
+    CommandLineTest::new()
+        .flag("blob-prune-epochs", Some("4095"))
+        .run_with_zero_port();
+}
+#[test]
 fn auto_compact_db_flag() {
     CommandLineTest::new()
         .flag("auto-compact-db", Some("false"))
@@ -900,6 +1615,33 @@ fn compact_db_flag() {
         .with_config(|config| assert!(config.store.compact_on_init));
 }
 #[test]
+fn compaction_duty_cycle_default() {
+    use beacon_node::beacon_chain::store::config::DEFAULT_COMPACTION_DUTY_CYCLE_PERCENT;
+
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.compaction_duty_cycle_percent,
+                DEFAULT_COMPACTION_DUTY_CYCLE_PERCENT
+            )
+        });
+}
+#[test]
+fn compaction_duty_cycle_flag() {
+    CommandLineTest::new()
+        .flag("compaction-duty-cycle", Some("100"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.compaction_duty_cycle_percent, 100));
+}
+#[test]
+#[should_panic]
+fn compaction_duty_cycle_zero_flag() {
+    CommandLineTest::new()
+        .flag("compaction-duty-cycle", Some("0"))
+        .run_with_zero_port();
+}
+#[test]
 fn reconstruct_historic_states_flag() {
     CommandLineTest::new()
         .flag("reconstruct-historic-states", None)
@@ -1007,6 +1749,33 @@ fn slasher_max_db_size_flag() {
         });
 }
 #[test]
+fn slasher_backend_default() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            let slasher_config = config
+                .slasher
+                .as_ref()
+                .expect("Unable to parse Slasher config");
+            assert_eq!(slasher_config.backend.to_string(), "mdbx");
+        });
+}
+#[test]
+fn slasher_backend_flag() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-backend", Some("redb"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let slasher_config = config
+                .slasher
+                .as_ref()
+                .expect("Unable to parse Slasher config");
+            assert_eq!(slasher_config.backend.to_string(), "redb");
+        });
+}
+#[test]
 fn slasher_attestation_cache_size_flag() {
     CommandLineTest::new()
         .flag("slasher", None)
@@ -1049,6 +1818,34 @@ fn slasher_validator_chunk_size_flag() {
         });
 }
 #[test]
+fn slasher_workers_flag() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-workers", Some("8"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let slasher_config = config
+                .slasher
+                .as_ref()
+                .expect("Unable to parse Slasher config");
+            assert_eq!(slasher_config.num_workers, 8);
+        });
+}
+#[test]
+fn slasher_backfill_epochs_flag() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-backfill-epochs", Some("256"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let slasher_config = config
+                .slasher
+                .as_ref()
+                .expect("Unable to parse Slasher config");
+            assert_eq!(slasher_config.backfill_epochs, 256);
+        });
+}
+#[test]
 fn slasher_broadcast_flag() {
     CommandLineTest::new()
         .flag("slasher", None)
@@ -1063,6 +1860,67 @@ fn slasher_broadcast_flag() {
         });
 }
 #[test]
+fn slasher_webhook_urls_flag() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag(
+            "slasher-webhook-urls",
+            Some("http://localhost:8080/slashings,https://example.com/hook"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            let slasher_config = config
+                .slasher
+                .as_ref()
+                .expect("Unable to parse Slasher config");
+            let urls = slasher_config
+                .webhook_urls
+                .iter()
+                .map(|url| url.full.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                urls,
+                vec![
+                    "http://localhost:8080/slashings",
+                    "https://example.com/hook"
+                ]
+            );
+        });
+}
+#[test]
+fn slasher_vc_url_flag() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-vc-url", Some("http://localhost:5062"))
+        .flag("slasher-vc-api-token", Some("/tmp/api-token.txt"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let slasher_config = config
+                .slasher
+                .as_ref()
+                .expect("Unable to parse Slasher config");
+            assert_eq!(
+                slasher_config
+                    .validator_client_url
+                    .as_ref()
+                    .map(|url| url.full.to_string()),
+                Some("http://localhost:5062/".to_string())
+            );
+            assert_eq!(
+                slasher_config.validator_client_token_path,
+                Some(PathBuf::from("/tmp/api-token.txt"))
+            );
+        });
+}
+#[test]
+#[should_panic]
+fn slasher_vc_url_flag_without_api_token() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-vc-url", Some("http://localhost:5062"))
+        .run_with_zero_port();
+}
+#[test]
 pub fn malloc_tuning_flag() {
     CommandLineTest::new()
         .flag("disable-malloc-tuning", None)
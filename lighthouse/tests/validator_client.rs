@@ -160,6 +160,47 @@ fn beacon_nodes_tls_certs_flag() {
         });
 }
 
+#[test]
+fn beacon_nodes_token_file_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag(
+            "beacon-nodes",
+            Some("http://localhost:1001,http://localhost:1002"),
+        )
+        .flag(
+            "beacon-nodes-token-file",
+            Some(dir.path().join("token").to_str().unwrap()),
+        )
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.beacon_nodes_token_files,
+                Some(vec![dir.path().join("token")])
+            )
+        });
+}
+#[test]
+#[should_panic]
+fn beacon_nodes_token_file_flag_count_mismatch() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag("beacon-nodes", Some("http://localhost:1001"))
+        .flag(
+            "beacon-nodes-token-file",
+            Some(
+                vec![
+                    dir.path().join("token").to_str().unwrap(),
+                    dir.path().join("token2").to_str().unwrap(),
+                ]
+                .join(",")
+                .as_str(),
+            ),
+        )
+        .run()
+        .with_config(|_| ());
+}
+
 // Tests for Graffiti flags.
 #[test]
 fn graffiti_flag() {
@@ -423,6 +464,28 @@ fn metrics_allow_origin_all_flag() {
         .with_config(|config| assert_eq!(config.http_metrics.allow_origin, Some("*".to_string())));
 }
 #[test]
+fn monitoring_endpoint_flags() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let token_path = dir.path().join("token.txt");
+    File::create(&token_path)
+        .and_then(|mut file| file.write_all(b"secret"))
+        .expect("Unable to write token file");
+    CommandLineTest::new()
+        .flag("monitoring-endpoint", Some("https://example.com/api"))
+        .flag("monitoring-endpoint-period", Some("30"))
+        .flag("monitoring-endpoint-token-file", token_path.to_str())
+        .run()
+        .with_config(|config| {
+            let monitoring_api = config.monitoring_api.as_ref().unwrap();
+            assert_eq!(
+                monitoring_api.monitoring_endpoint,
+                "https://example.com/api"
+            );
+            assert_eq!(monitoring_api.update_period_secs, Some(30));
+            assert_eq!(monitoring_api.api_token_path, Some(token_path.clone()));
+        });
+}
+#[test]
 pub fn malloc_tuning_flag() {
     CommandLineTest::new()
         .flag("disable-malloc-tuning", None)
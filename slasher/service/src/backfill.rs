@@ -0,0 +1,175 @@
+//! Backfill of the slasher database from the blocks already stored by the beacon node.
+//!
+//! Without a backfill the slasher only sees messages received after it was enabled, so it can't
+//! detect slashable messages conflicting with earlier ones. The backfill checks the proposals
+//! and on-chain attestations of recent epochs, one epoch per batch, and is only run once for
+//! each database.
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use slasher::{
+    metrics::{self, SLASHER_BACKFILL_EPOCH},
+    Slasher,
+};
+use slog::{debug, info};
+use state_processing::common::get_indexed_attestation;
+use std::cmp::{max, min};
+use types::{Epoch, EthSpec, Hash256, RelativeEpoch};
+
+/// Check the blocks stored for the last `Config::backfill_epochs` epochs for slashings.
+///
+/// Does nothing if the backfill is disabled or has already completed. An interrupted backfill is
+/// restarted from the beginning, which is harmless as messages already stored are ignored.
+pub fn backfill_slasher<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    slasher: &Slasher<T::EthSpec>,
+) -> Result<(), String> {
+    let log = slasher.log();
+    let config = slasher.config();
+    if config.backfill_epochs == 0 {
+        return Ok(());
+    }
+    if let Some(backfill_start) = slasher
+        .backfill_start()
+        .map_err(|e| format!("Unable to load slasher backfill status: {:?}", e))?
+    {
+        debug!(
+            log,
+            "Slasher backfill already complete";
+            "start_epoch" => backfill_start,
+        );
+        return Ok(());
+    }
+
+    let slots_per_epoch = T::EthSpec::slots_per_epoch();
+    let current_epoch = beacon_chain
+        .epoch()
+        .map_err(|e| format!("Unable to read current epoch: {:?}", e))?;
+
+    // Attestations with sources older than the history length would be dropped anyway.
+    let num_epochs = min(config.backfill_epochs, config.history_length as u64 - 1);
+    let start_slot = max(
+        current_epoch
+            .saturating_sub(num_epochs)
+            .start_slot(slots_per_epoch),
+        beacon_chain.store.get_oldest_block_slot(),
+    );
+    let start_epoch = start_slot.epoch(slots_per_epoch);
+
+    info!(
+        log,
+        "Starting slasher backfill";
+        "start_epoch" => start_epoch,
+        "current_epoch" => current_epoch,
+    );
+
+    let block_roots = beacon_chain
+        .forwards_iter_block_roots(start_slot)
+        .map_err(|e| format!("Unable to iterate block roots: {:?}", e))?;
+
+    // Group the roots of the canonical blocks by epoch, skipping the repeated roots of skipped
+    // slots.
+    let mut epoch = start_epoch;
+    let mut epoch_block_roots = vec![];
+    let mut prev_block_root = None;
+    for result in block_roots {
+        let (block_root, slot) =
+            result.map_err(|e| format!("Unable to iterate block roots: {:?}", e))?;
+        if slot.epoch(slots_per_epoch) != epoch {
+            backfill_epoch(
+                beacon_chain,
+                slasher,
+                epoch,
+                &epoch_block_roots,
+                current_epoch,
+            )?;
+            epoch = slot.epoch(slots_per_epoch);
+            epoch_block_roots.clear();
+        }
+        if prev_block_root != Some(block_root) {
+            epoch_block_roots.push(block_root);
+            prev_block_root = Some(block_root);
+        }
+    }
+    backfill_epoch(
+        beacon_chain,
+        slasher,
+        epoch,
+        &epoch_block_roots,
+        current_epoch,
+    )?;
+
+    slasher
+        .set_backfill_complete(start_epoch)
+        .map_err(|e| format!("Unable to store slasher backfill status: {:?}", e))?;
+
+    info!(
+        log,
+        "Slasher backfill complete";
+        "start_epoch" => start_epoch,
+        "end_epoch" => epoch,
+    );
+    Ok(())
+}
+
+/// Queue the headers and attestations of the blocks of a single `epoch` and process them.
+fn backfill_epoch<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+    slasher: &Slasher<T::EthSpec>,
+    epoch: Epoch,
+    block_roots: &[Hash256],
+    current_epoch: Epoch,
+) -> Result<(), String> {
+    let blocks = block_roots
+        .iter()
+        .map(|block_root| {
+            beacon_chain
+                .get_blinded_block(block_root)
+                .map_err(|e| format!("Unable to load block {:?}: {:?}", block_root, e))?
+                .ok_or_else(|| format!("Missing block {:?}", block_root))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let last_block = match blocks.last() {
+        Some(block) => block,
+        None => return Ok(()),
+    };
+
+    // The attestations of the epoch's blocks are all from this epoch or the previous one, so the
+    // committees of both can be read from the state of the epoch's last block.
+    let mut state = beacon_chain
+        .get_state(&last_block.state_root(), Some(last_block.slot()))
+        .map_err(|e| format!("Unable to load state for epoch {}: {:?}", epoch, e))?
+        .ok_or_else(|| format!("Missing state for epoch {}", epoch))?;
+    for relative_epoch in [RelativeEpoch::Previous, RelativeEpoch::Current] {
+        state
+            .build_committee_cache(relative_epoch, &beacon_chain.spec)
+            .map_err(|e| format!("Unable to build committee cache: {:?}", e))?;
+    }
+
+    let mut num_attestations = 0;
+    for block in &blocks {
+        slasher.accept_block_header(block.signed_block_header());
+        for attestation in block.message().body().attestations() {
+            let committee = state
+                .get_beacon_committee(attestation.data.slot, attestation.data.index)
+                .map_err(|e| format!("Unable to get committee: {:?}", e))?;
+            let indexed_attestation = get_indexed_attestation(committee.committee, attestation)
+                .map_err(|e| format!("Invalid attestation: {:?}", e))?;
+            slasher.accept_attestation(indexed_attestation);
+            num_attestations += 1;
+        }
+    }
+
+    slasher
+        .process_queued(current_epoch)
+        .map_err(|e| format!("Slasher backfill of epoch {} failed: {:?}", epoch, e))?;
+    metrics::set_gauge(&SLASHER_BACKFILL_EPOCH, epoch.as_u64() as i64);
+
+    debug!(
+        slasher.log(),
+        "Completed slasher backfill of epoch";
+        "epoch" => epoch,
+        "num_blocks" => blocks.len(),
+        "num_attestations" => num_attestations,
+    );
+    Ok(())
+}
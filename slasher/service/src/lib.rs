@@ -1,3 +1,8 @@
+mod backfill;
+mod local_validators;
 mod service;
+mod webhook;
 
+pub use local_validators::{LocalValidatorSlashings, LOCAL_SLASHINGS_FILENAME};
 pub use service::SlasherService;
+pub use webhook::Webhooks;
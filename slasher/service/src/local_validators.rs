@@ -0,0 +1,194 @@
+//! Emergency response to slashings of the validators attached to this beacon node.
+use beacon_chain::{events::SseSlasherSlashing, BeaconChain, BeaconChainTypes};
+use eth2::lighthouse_vc::http_client::ValidatorClientHttpClient;
+use parking_lot::Mutex;
+use sensitive_url::SensitiveUrl;
+use slasher::metrics::{self, SLASHER_NUM_LOCAL_VALIDATOR_SLASHINGS};
+use slog::{crit, error, info, Logger};
+use std::collections::HashSet;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use task_executor::TaskExecutor;
+use types::{EthSpec, PublicKeyBytes};
+
+/// File within the slasher directory to which slashings of local validators are appended.
+pub const LOCAL_SLASHINGS_FILENAME: &str = "local_validator_slashings.jsonl";
+
+/// Responds to slashings of validators whose keys are attached to this beacon node.
+///
+/// A local validator being slashable means that its key has signed conflicting messages, which
+/// almost always means that the key is also in use by another validator client. Each such
+/// validator is reported with a critical log, recorded in the `LOCAL_SLASHINGS_FILENAME` file and,
+/// if a validator client is configured, disabled on that validator client to stop it signing
+/// anything further.
+///
+/// Local validators are those tracked by the validator monitor, so the monitor must be enabled for
+/// local validators to be recognised.
+pub struct LocalValidatorSlashings {
+    validator_client: Option<ValidatorClientHttpClient>,
+    record_path: PathBuf,
+    /// Validators which have already been responded to.
+    responded: Mutex<HashSet<u64>>,
+    executor: TaskExecutor,
+    log: Logger,
+}
+
+impl LocalValidatorSlashings {
+    pub fn new(
+        slasher_dir: &Path,
+        validator_client_url: Option<SensitiveUrl>,
+        api_token_path: Option<&Path>,
+        executor: TaskExecutor,
+        log: Logger,
+    ) -> Result<Self, String> {
+        let validator_client = match (validator_client_url, api_token_path) {
+            (Some(url), Some(api_token_path)) => {
+                let api_token = std::fs::read_to_string(api_token_path).map_err(|e| {
+                    format!(
+                        "Unable to read validator client API token from {}: {:?}",
+                        api_token_path.display(),
+                        e
+                    )
+                })?;
+                let client = ValidatorClientHttpClient::new(url, api_token.trim().to_string())
+                    .map_err(|e| format!("Invalid validator client API token: {:?}", e))?;
+                Some(client)
+            }
+            (None, None) => None,
+            _ => {
+                return Err(
+                    "A validator client URL and API token must be provided together".to_string(),
+                )
+            }
+        };
+
+        Ok(Self {
+            validator_client,
+            record_path: slasher_dir.join(LOCAL_SLASHINGS_FILENAME),
+            responded: Mutex::new(HashSet::new()),
+            executor,
+            log,
+        })
+    }
+
+    /// Respond to any local validators made slashable by the verified `slashing`.
+    pub fn check<T: BeaconChainTypes>(
+        &self,
+        beacon_chain: &BeaconChain<T>,
+        slashing: &SseSlasherSlashing<T::EthSpec>,
+    ) {
+        let local_validators = {
+            let validator_monitor = beacon_chain.validator_monitor.read();
+            slashed_indices(slashing)
+                .into_iter()
+                .filter_map(|index| {
+                    validator_monitor
+                        .get_monitored_validator_pubkey(index)
+                        .map(|pubkey| (index, pubkey))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for (validator_index, pubkey) in local_validators {
+            if !self.responded.lock().insert(validator_index) {
+                continue;
+            }
+            metrics::inc_counter(&SLASHER_NUM_LOCAL_VALIDATOR_SLASHINGS);
+            crit!(
+                self.log,
+                "Local validator has signed slashable messages";
+                "reason" => "the validator's key is probably in use by another validator client",
+                "advice" => "stop every other validator client using this key immediately",
+                "validator_index" => validator_index,
+                "pubkey" => %pubkey,
+            );
+
+            if let Err(e) = self.record(validator_index, pubkey, slashing) {
+                error!(
+                    self.log,
+                    "Unable to record local validator slashing";
+                    "error" => e,
+                    "path" => %self.record_path.display(),
+                );
+            }
+
+            if let Some(validator_client) = self.validator_client.clone() {
+                let log = self.log.clone();
+                self.executor.spawn(
+                    async move {
+                        match validator_client
+                            .patch_lighthouse_validators(&pubkey, false)
+                            .await
+                        {
+                            Ok(()) => info!(
+                                log,
+                                "Disabled slashable validator on validator client";
+                                "validator_index" => validator_index,
+                                "pubkey" => %pubkey,
+                            ),
+                            Err(e) => crit!(
+                                log,
+                                "Unable to disable slashable validator on validator client";
+                                "error" => ?e,
+                                "validator_index" => validator_index,
+                                "pubkey" => %pubkey,
+                            ),
+                        }
+                    },
+                    "slasher_disable_validator",
+                );
+            }
+        }
+    }
+
+    /// Append a record of the slashing of a local validator to the `record_path`.
+    fn record<E: EthSpec>(
+        &self,
+        validator_index: u64,
+        pubkey: PublicKeyBytes,
+        slashing: &SseSlasherSlashing<E>,
+    ) -> Result<(), String> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("{:?}", e))?
+            .as_secs();
+        let record = serde_json::json!({
+            "timestamp": timestamp,
+            "validator_index": validator_index,
+            "pubkey": pubkey,
+            "slashing": slashing,
+        });
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.record_path)
+            .map_err(|e| format!("{:?}", e))?;
+        writeln!(file, "{}", record).map_err(|e| format!("{:?}", e))
+    }
+}
+
+/// Return the indices of the validators slashed by `slashing`.
+fn slashed_indices<E: EthSpec>(slashing: &SseSlasherSlashing<E>) -> Vec<u64> {
+    match slashing {
+        SseSlasherSlashing::Attester(slashing) => {
+            let attesters_2 = slashing
+                .attestation_2
+                .attesting_indices
+                .iter()
+                .collect::<HashSet<_>>();
+            slashing
+                .attestation_1
+                .attesting_indices
+                .iter()
+                .filter(|index| attesters_2.contains(index))
+                .copied()
+                .collect()
+        }
+        SseSlasherSlashing::Proposer(slashing) => {
+            vec![slashing.signed_header_1.message.proposer_index]
+        }
+    }
+}
@@ -1,11 +1,16 @@
+use crate::backfill::backfill_slasher;
+use crate::local_validators::LocalValidatorSlashings;
+use crate::webhook::Webhooks;
 use beacon_chain::{
-    observed_operations::ObservationOutcome, BeaconChain, BeaconChainError, BeaconChainTypes,
+    events::{EventKind, SseSlasherSlashing},
+    observed_operations::ObservationOutcome,
+    BeaconChain, BeaconChainError, BeaconChainTypes,
 };
 use directory::size_of_dir;
 use lighthouse_network::PubsubMessage;
 use network::NetworkMessage;
 use slasher::{
-    metrics::{self, SLASHER_DATABASE_SIZE, SLASHER_RUN_TIME},
+    metrics::{self, SLASHER_DATABASE_SIZE, SLASHER_EPOCH_LAG, SLASHER_RUN_TIME},
     Slasher,
 };
 use slog::{debug, error, info, trace, warn, Logger};
@@ -49,7 +54,25 @@ impl<T: BeaconChainTypes> SlasherService<T> {
             .ok_or("No slasher is configured")?;
         let log = slasher.log().clone();
 
-        info!(log, "Starting slasher"; "broadcast" => slasher.config().broadcast);
+        info!(
+            log,
+            "Starting slasher";
+            "broadcast" => slasher.config().broadcast,
+            "num_webhooks" => slasher.config().webhook_urls.len(),
+            "disable_local_validators" => slasher.config().validator_client_url.is_some(),
+        );
+        let webhooks = Webhooks::new(
+            slasher.config().webhook_urls.clone(),
+            executor.clone(),
+            log.clone(),
+        )?;
+        let local_validators = LocalValidatorSlashings::new(
+            &slasher.config().database_path,
+            slasher.config().validator_client_url.clone(),
+            slasher.config().validator_client_token_path.as_deref(),
+            executor.clone(),
+            log.clone(),
+        )?;
 
         // Buffer just a single message in the channel. If the receiver is still processing, we
         // don't need to burden them with more work (we can wait).
@@ -71,7 +94,16 @@ impl<T: BeaconChainTypes> SlasherService<T> {
         );
 
         executor.spawn_blocking(
-            || Self::run_processor(beacon_chain, slasher, notif_receiver, network_sender),
+            || {
+                Self::run_processor(
+                    beacon_chain,
+                    slasher,
+                    notif_receiver,
+                    network_sender,
+                    webhooks,
+                    local_validators,
+                )
+            },
             "slasher_server_processor",
         );
 
@@ -115,8 +147,22 @@ impl<T: BeaconChainTypes> SlasherService<T> {
         slasher: Arc<Slasher<T::EthSpec>>,
         notif_receiver: Receiver<Epoch>,
         network_sender: UnboundedSender<NetworkMessage<T::EthSpec>>,
+        webhooks: Webhooks,
+        local_validators: LocalValidatorSlashings,
     ) {
         let log = slasher.log();
+
+        // Check the stored blocks before any live messages, so that the first batch reports any
+        // slashings found.
+        if let Err(e) = backfill_slasher(&beacon_chain, &slasher) {
+            error!(
+                log,
+                "Slasher backfill failed";
+                "error" => e,
+                "advice" => "the backfill will be retried when the beacon node restarts",
+            );
+        }
+
         while let Ok(current_epoch) = notif_receiver.recv() {
             let t = Instant::now();
 
@@ -148,11 +194,25 @@ impl<T: BeaconChainTypes> SlasherService<T> {
             };
 
             // Provide slashings to the beacon chain, and optionally publish them.
-            Self::process_slashings(&beacon_chain, &slasher, &network_sender);
+            Self::process_slashings(
+                &beacon_chain,
+                &slasher,
+                &network_sender,
+                &webhooks,
+                &local_validators,
+            );
 
             let database_size = size_of_dir(&slasher.config().database_path);
             metrics::set_gauge(&SLASHER_DATABASE_SIZE, database_size as i64);
 
+            if let Ok(head_slot) = beacon_chain.best_slot() {
+                let head_epoch = head_slot.epoch(T::EthSpec::slots_per_epoch());
+                metrics::set_gauge(
+                    &SLASHER_EPOCH_LAG,
+                    head_epoch.as_u64() as i64 - current_epoch.as_u64() as i64,
+                );
+            }
+
             if let Some(stats) = stats {
                 debug!(
                     log,
@@ -171,15 +231,49 @@ impl<T: BeaconChainTypes> SlasherService<T> {
         beacon_chain: &BeaconChain<T>,
         slasher: &Slasher<T::EthSpec>,
         network_sender: &UnboundedSender<NetworkMessage<T::EthSpec>>,
+        webhooks: &Webhooks,
+        local_validators: &LocalValidatorSlashings,
     ) {
-        Self::process_attester_slashings(beacon_chain, slasher, network_sender);
-        Self::process_proposer_slashings(beacon_chain, slasher, network_sender);
+        Self::process_attester_slashings(
+            beacon_chain,
+            slasher,
+            network_sender,
+            webhooks,
+            local_validators,
+        );
+        Self::process_proposer_slashings(
+            beacon_chain,
+            slasher,
+            network_sender,
+            webhooks,
+            local_validators,
+        );
+    }
+
+    /// Emit a server-sent event for a verified slashing, send it to any webhooks, and respond to
+    /// the slashing of any local validators.
+    fn notify_slashing(
+        beacon_chain: &BeaconChain<T>,
+        webhooks: &Webhooks,
+        local_validators: &LocalValidatorSlashings,
+        slashing: SseSlasherSlashing<T::EthSpec>,
+    ) {
+        local_validators.check(beacon_chain, &slashing);
+        webhooks.notify(&slashing);
+
+        if let Some(event_handler) = beacon_chain.event_handler.as_ref() {
+            if event_handler.has_slasher_slashing_subscribers() {
+                event_handler.register(EventKind::SlasherSlashing(slashing));
+            }
+        }
     }
 
     fn process_attester_slashings(
         beacon_chain: &BeaconChain<T>,
         slasher: &Slasher<T::EthSpec>,
         network_sender: &UnboundedSender<NetworkMessage<T::EthSpec>>,
+        webhooks: &Webhooks,
+        local_validators: &LocalValidatorSlashings,
     ) {
         let log = slasher.log();
         let attester_slashings = slasher.get_attester_slashings();
@@ -215,6 +309,13 @@ impl<T: BeaconChainTypes> SlasherService<T> {
                 }
             };
 
+            Self::notify_slashing(
+                beacon_chain,
+                webhooks,
+                local_validators,
+                SseSlasherSlashing::Attester(Box::new(slashing.clone())),
+            );
+
             // Add to local op pool.
             if let Err(e) = beacon_chain.import_attester_slashing(verified_slashing) {
                 error!(
@@ -244,6 +345,8 @@ impl<T: BeaconChainTypes> SlasherService<T> {
         beacon_chain: &BeaconChain<T>,
         slasher: &Slasher<T::EthSpec>,
         network_sender: &UnboundedSender<NetworkMessage<T::EthSpec>>,
+        webhooks: &Webhooks,
+        local_validators: &LocalValidatorSlashings,
     ) {
         let log = slasher.log();
         let proposer_slashings = slasher.get_proposer_slashings();
@@ -277,6 +380,12 @@ impl<T: BeaconChainTypes> SlasherService<T> {
                     continue;
                 }
             };
+            Self::notify_slashing(
+                beacon_chain,
+                webhooks,
+                local_validators,
+                SseSlasherSlashing::Proposer(slashing.clone()),
+            );
             beacon_chain.import_proposer_slashing(verified_slashing);
 
             if slasher.config().broadcast {
@@ -0,0 +1,66 @@
+//! Delivery of the slashings found by the slasher to user-configured webhooks.
+//!
+//! Each slashing is POSTed as JSON to every webhook, in the same format as the `slasher_slashing`
+//! server-sent event. This allows external infrastructure to act on slashings even if they are
+//! never included on chain by this node.
+use beacon_chain::events::SseSlasherSlashing;
+use reqwest::Client;
+use sensitive_url::SensitiveUrl;
+use slog::{debug, warn, Logger};
+use std::time::Duration;
+use task_executor::TaskExecutor;
+use types::EthSpec;
+
+/// Timeout for each request to a webhook.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(12);
+
+#[derive(Clone)]
+pub struct Webhooks {
+    client: Client,
+    urls: Vec<SensitiveUrl>,
+    executor: TaskExecutor,
+    log: Logger,
+}
+
+impl Webhooks {
+    pub fn new(
+        urls: Vec<SensitiveUrl>,
+        executor: TaskExecutor,
+        log: Logger,
+    ) -> Result<Self, String> {
+        let client = Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Unable to build webhook client: {:?}", e))?;
+        Ok(Self {
+            client,
+            urls,
+            executor,
+            log,
+        })
+    }
+
+    /// Send `slashing` to every webhook without waiting for the requests to complete.
+    pub fn notify<E: EthSpec>(&self, slashing: &SseSlasherSlashing<E>) {
+        for url in &self.urls {
+            let request = self.client.post(url.full.clone()).json(slashing);
+            let url = url.clone();
+            let log = self.log.clone();
+
+            self.executor.spawn(
+                async move {
+                    match request.send().await.and_then(|r| r.error_for_status()) {
+                        Ok(_) => debug!(log, "Sent slashing to webhook"; "url" => %url),
+                        Err(e) => warn!(
+                            log,
+                            "Unable to send slashing to webhook";
+                            "url" => %url,
+                            "error" => %e,
+                        ),
+                    }
+                },
+                "slasher_webhook",
+            );
+        }
+    }
+}
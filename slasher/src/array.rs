@@ -1,5 +1,7 @@
-use crate::metrics::{self, SLASHER_COMPRESSION_RATIO, SLASHER_NUM_CHUNKS_UPDATED};
-use crate::RwTransaction;
+use crate::database::interface::{Database, RwTransaction};
+use crate::metrics::{
+    self, SLASHER_COMPRESSION_RATIO, SLASHER_DETECTION_LATENCY, SLASHER_NUM_CHUNKS_UPDATED,
+};
 use crate::{AttesterSlashingStatus, Config, Error, IndexedAttesterRecord, SlasherDB};
 use flate2::bufread::{ZlibDecoder, ZlibEncoder};
 use serde_derive::{Deserialize, Serialize};
@@ -38,6 +40,16 @@ impl Chunk {
         epoch: Epoch,
         config: &Config,
     ) -> Result<Epoch, Error> {
+        self.get_raw_distance(validator_index, epoch, config)
+            .map(|distance| epoch + u64::from(distance))
+    }
+
+    pub fn get_raw_distance(
+        &self,
+        validator_index: u64,
+        epoch: Epoch,
+        config: &Config,
+    ) -> Result<u16, Error> {
         assert_eq!(
             self.data.len(),
             config.chunk_size * config.validator_chunk_size
@@ -47,7 +59,7 @@ impl Chunk {
         let cell_index = config.cell_index(validator_offset, chunk_offset);
         self.data
             .get(cell_index)
-            .map(|distance| epoch + u64::from(*distance))
+            .copied()
             .ok_or(Error::ChunkIndexOutOfBounds(cell_index))
     }
 
@@ -147,33 +159,27 @@ pub trait TargetArrayChunk: Sized + serde::Serialize + serde::de::DeserializeOwn
 
     fn next_start_epoch(start_epoch: Epoch, config: &Config) -> Epoch;
 
-    fn select_db<'txn, E: EthSpec>(
-        db: &SlasherDB<E>,
-        txn: &'txn RwTransaction<'txn>,
-    ) -> Result<mdbx::Database<'txn>, Error>;
+    fn select_db() -> Database;
 
-    fn load<E: EthSpec>(
-        db: &SlasherDB<E>,
+    fn load(
         txn: &mut RwTransaction<'_>,
         validator_chunk_index: usize,
         chunk_index: usize,
         config: &Config,
     ) -> Result<Option<Self>, Error> {
         let disk_key = config.disk_key(validator_chunk_index, chunk_index);
-        let chunk_bytes: Cow<[u8]> =
-            match txn.get(&Self::select_db(db, txn)?, &disk_key.to_be_bytes())? {
-                Some(chunk_bytes) => chunk_bytes,
-                None => return Ok(None),
-            };
+        let chunk_bytes: Cow<[u8]> = match txn.get(Self::select_db(), &disk_key.to_be_bytes())? {
+            Some(chunk_bytes) => chunk_bytes,
+            None => return Ok(None),
+        };
 
         let chunk = bincode::deserialize_from(ZlibDecoder::new(chunk_bytes.borrow()))?;
 
         Ok(Some(chunk))
     }
 
-    fn store<E: EthSpec>(
+    fn store(
         &self,
-        db: &SlasherDB<E>,
         txn: &mut RwTransaction<'_>,
         validator_chunk_index: usize,
         chunk_index: usize,
@@ -189,10 +195,9 @@ pub trait TargetArrayChunk: Sized + serde::Serialize + serde::de::DeserializeOwn
         metrics::set_float_gauge(&SLASHER_COMPRESSION_RATIO, compression_ratio);
 
         txn.put(
-            &Self::select_db(db, txn)?,
+            Self::select_db(),
             &disk_key.to_be_bytes(),
             &compressed_value,
-            SlasherDB::<E>::write_flags(),
         )?;
         Ok(())
     }
@@ -233,7 +238,8 @@ impl TargetArrayChunk for MinTargetChunk {
         let min_target =
             self.chunk
                 .get_target(validator_index, attestation.data.source.epoch, config)?;
-        if attestation.data.target.epoch > min_target {
+        // The attestation with the minimum target may have been trimmed from the database.
+        if attestation.data.target.epoch > min_target && min_target >= db.history_start() {
             let existing_attestation =
                 db.get_attestation_for_validator(txn, validator_index, min_target)?;
 
@@ -296,11 +302,8 @@ impl TargetArrayChunk for MinTargetChunk {
         start_epoch / chunk_size * chunk_size - 1
     }
 
-    fn select_db<'txn, E: EthSpec>(
-        db: &SlasherDB<E>,
-        txn: &'txn RwTransaction<'txn>,
-    ) -> Result<mdbx::Database<'txn>, Error> {
-        db.min_targets_db(txn)
+    fn select_db() -> Database {
+        Database::MinTargets
     }
 }
 
@@ -398,16 +401,12 @@ impl TargetArrayChunk for MaxTargetChunk {
         (start_epoch / chunk_size + 1) * chunk_size
     }
 
-    fn select_db<'txn, E: EthSpec>(
-        db: &SlasherDB<E>,
-        txn: &'txn RwTransaction<'txn>,
-    ) -> Result<mdbx::Database<'txn>, Error> {
-        db.max_targets_db(txn)
+    fn select_db() -> Database {
+        Database::MaxTargets
     }
 }
 
-pub fn get_chunk_for_update<'a, E: EthSpec, T: TargetArrayChunk>(
-    db: &SlasherDB<E>,
+pub fn get_chunk_for_update<'a, T: TargetArrayChunk>(
     txn: &mut RwTransaction<'_>,
     updated_chunks: &'a mut BTreeMap<usize, T>,
     validator_chunk_index: usize,
@@ -418,7 +417,7 @@ pub fn get_chunk_for_update<'a, E: EthSpec, T: TargetArrayChunk>(
         Entry::Occupied(occupied) => occupied.into_mut(),
         Entry::Vacant(vacant) => {
             let chunk = if let Some(disk_chunk) =
-                T::load(db, txn, validator_chunk_index, chunk_index, config)?
+                T::load(txn, validator_chunk_index, chunk_index, config)?
             {
                 disk_chunk
             } else {
@@ -442,7 +441,6 @@ pub fn apply_attestation_for_validator<E: EthSpec, T: TargetArrayChunk>(
 ) -> Result<AttesterSlashingStatus<E>, Error> {
     let mut chunk_index = config.chunk_index(attestation.data.source.epoch);
     let mut current_chunk = get_chunk_for_update(
-        db,
         txn,
         updated_chunks,
         validator_chunk_index,
@@ -468,7 +466,6 @@ pub fn apply_attestation_for_validator<E: EthSpec, T: TargetArrayChunk>(
     loop {
         chunk_index = config.chunk_index(start_epoch);
         current_chunk = get_chunk_for_update(
-            db,
             txn,
             updated_chunks,
             validator_chunk_index,
@@ -557,7 +554,6 @@ pub fn epoch_update_for_validator<E: EthSpec, T: TargetArrayChunk>(
     while epoch <= current_epoch {
         let chunk_index = config.chunk_index(epoch);
         let current_chunk = get_chunk_for_update(
-            db,
             txn,
             updated_chunks,
             validator_chunk_index,
@@ -620,7 +616,12 @@ pub fn update_array<E: EthSpec, T: TargetArrayChunk>(
                     config,
                 )?;
                 if let Some(slashing) = slashing_status.into_slashing(&attestation.indexed) {
-                    slashings.insert(slashing);
+                    if slashings.insert(slashing) {
+                        metrics::observe_duration(
+                            &SLASHER_DETECTION_LATENCY,
+                            attestation.received.elapsed(),
+                        );
+                    }
                 }
             }
         }
@@ -634,7 +635,7 @@ pub fn update_array<E: EthSpec, T: TargetArrayChunk>(
     );
 
     for (chunk_index, chunk) in updated_chunks {
-        chunk.store(db, txn, validator_chunk_index, chunk_index, config)?;
+        chunk.store(txn, validator_chunk_index, chunk_index, config)?;
     }
 
     Ok(slashings)
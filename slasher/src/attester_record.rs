@@ -5,6 +5,7 @@ use std::sync::{
     atomic::{AtomicU64, Ordering},
     Arc,
 };
+use std::time::Instant;
 use tree_hash::TreeHash as _;
 use tree_hash_derive::TreeHash;
 use types::{AggregateSignature, EthSpec, Hash256, IndexedAttestation, VariableList};
@@ -56,6 +57,8 @@ pub struct IndexedAttesterRecord<E: EthSpec> {
     pub indexed: IndexedAttestation<E>,
     pub record: AttesterRecord,
     pub indexed_attestation_id: AtomicU64,
+    /// The time at which the attestation was queued, for measuring detection latency.
+    pub received: Instant,
 }
 
 impl<E: EthSpec> IndexedAttesterRecord<E> {
@@ -64,6 +67,7 @@ impl<E: EthSpec> IndexedAttesterRecord<E> {
             indexed,
             record,
             indexed_attestation_id: AtomicU64::new(0),
+            received: Instant::now(),
         })
     }
 
@@ -1,6 +1,8 @@
 use crate::Error;
+use sensitive_url::SensitiveUrl;
 use serde_derive::{Deserialize, Serialize};
 use std::path::PathBuf;
+use strum::{Display, EnumString, EnumVariantNames};
 use types::{Epoch, EthSpec, IndexedAttestation};
 
 pub const DEFAULT_CHUNK_SIZE: usize = 16;
@@ -11,9 +13,16 @@ pub const DEFAULT_SLOT_OFFSET: f64 = 10.5;
 pub const DEFAULT_MAX_DB_SIZE: usize = 256 * 1024; // 256 GiB
 pub const DEFAULT_ATTESTATION_ROOT_CACHE_SIZE: usize = 100_000;
 pub const DEFAULT_BROADCAST: bool = false;
+pub const DEFAULT_NUM_WORKERS: usize = 4;
+pub const DEFAULT_BACKFILL_EPOCHS: u64 = 0;
 
 pub const MAX_HISTORY_LENGTH: usize = 1 << 16;
+pub const MEGABYTE: usize = 1 << 20;
 pub const MDBX_GROWTH_STEP: isize = 256 * (1 << 20); // 256 MiB
+/// Percentage of the maximum database size beyond which the oldest history is trimmed.
+pub const TRIM_THRESHOLD_PERCENT: usize = 90;
+/// Minimum number of epochs of history retained when trimming, if the history length allows.
+pub const MIN_TRIMMED_HISTORY_LENGTH: usize = 16;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -30,8 +39,43 @@ pub struct Config {
     pub max_db_size_mbs: usize,
     /// Maximum size of the in-memory cache for attestation roots.
     pub attestation_root_cache_size: usize,
+    /// Number of threads applying attestations to the database, each handling distinct validator
+    /// chunks.
+    pub num_workers: usize,
+    /// Number of past epochs of stored blocks to check for slashings when the database is new.
+    pub backfill_epochs: u64,
     /// Whether to broadcast slashings found to the network.
     pub broadcast: bool,
+    /// URLs to which slashings found are POSTed as JSON.
+    pub webhook_urls: Vec<SensitiveUrl>,
+    /// Validator client on which local validators found to be slashable are disabled.
+    pub validator_client_url: Option<SensitiveUrl>,
+    /// Path to the API token for the `validator_client_url`.
+    pub validator_client_token_path: Option<PathBuf>,
+    /// The key-value store used for the database.
+    pub backend: DatabaseBackend,
+}
+
+/// The key-value stores that may hold the slasher database.
+///
+/// The backend can't be changed once the database has been initialized. Redb is only available
+/// when Lighthouse is compiled with the `slasher-redb` feature. The in-memory backend doesn't
+/// persist anything and is intended for testing.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumString, EnumVariantNames,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum DatabaseBackend {
+    Mdbx,
+    Redb,
+    Memory,
+}
+
+impl Default for DatabaseBackend {
+    fn default() -> Self {
+        DatabaseBackend::Mdbx
+    }
 }
 
 /// Immutable configuration parameters which are stored on disk and checked for consistency.
@@ -53,7 +97,13 @@ impl Config {
             slot_offset: DEFAULT_SLOT_OFFSET,
             max_db_size_mbs: DEFAULT_MAX_DB_SIZE,
             attestation_root_cache_size: DEFAULT_ATTESTATION_ROOT_CACHE_SIZE,
+            num_workers: DEFAULT_NUM_WORKERS,
+            backfill_epochs: DEFAULT_BACKFILL_EPOCHS,
             broadcast: DEFAULT_BROADCAST,
+            webhook_urls: vec![],
+            validator_client_url: None,
+            validator_client_token_path: None,
+            backend: DatabaseBackend::default(),
         }
     }
 
@@ -62,6 +112,7 @@ impl Config {
             || self.validator_chunk_size == 0
             || self.history_length == 0
             || self.max_db_size_mbs == 0
+            || self.num_workers == 0
         {
             Err(Error::ConfigInvalidZeroParameter {
                 config: self.clone(),
@@ -89,6 +140,11 @@ impl Config {
         }
     }
 
+    /// The database size in bytes beyond which the oldest history is trimmed.
+    pub fn trim_threshold_bytes(&self) -> usize {
+        self.max_db_size_mbs * MEGABYTE / 100 * TRIM_THRESHOLD_PERCENT
+    }
+
     pub fn chunk_index(&self, epoch: Epoch) -> usize {
         (epoch.as_usize() % self.history_length) / self.chunk_size
     }
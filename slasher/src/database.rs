@@ -1,18 +1,28 @@
-use crate::config::MDBX_GROWTH_STEP;
+pub mod interface;
+mod mdbx_impl;
+mod memory_impl;
+#[cfg(feature = "redb")]
+mod redb_impl;
+pub(crate) mod worker_impl;
+
+use crate::config::{DatabaseBackend, MIN_TRIMMED_HISTORY_LENGTH};
 use crate::{
     metrics, utils::TxnMapFull, AttesterRecord, AttesterSlashingStatus, CompactAttesterRecord,
-    Config, Environment, Error, ProposerSlashingStatus, RwTransaction,
+    Config, Error, ProposerSlashingStatus,
 };
 use byteorder::{BigEndian, ByteOrder};
+use interface::{Database, Environment, RwTransaction};
 use lru::LruCache;
-use mdbx::{Database, DatabaseFlags, Geometry, WriteFlags};
-use parking_lot::Mutex;
+use mdbx_impl::MdbxEnvironment;
+use memory_impl::MemoryEnvironment;
+use parking_lot::{Mutex, RwLock};
+#[cfg(feature = "redb")]
+use redb_impl::RedbEnvironment;
 use serde::de::DeserializeOwned;
 use slog::{info, Logger};
 use ssz::{Decode, Encode};
 use std::borrow::{Borrow, Cow};
 use std::marker::PhantomData;
-use std::ops::Range;
 use std::path::Path;
 use std::sync::Arc;
 use tree_hash::TreeHash;
@@ -23,32 +33,12 @@ use types::{
 /// Current database schema version, to check compatibility of on-disk DB with software.
 pub const CURRENT_SCHEMA_VERSION: u64 = 3;
 
-/// Metadata about the slashing database itself.
-const METADATA_DB: &str = "metadata";
-/// Map from `(target_epoch, validator_index)` to `CompactAttesterRecord`.
-const ATTESTERS_DB: &str = "attesters";
-/// Companion database for the attesters DB mapping `validator_index` to largest `target_epoch`
-/// stored for that validator in the attesters DB.
-///
-/// Used to implement wrap-around semantics for target epochs modulo the history length.
-const ATTESTERS_MAX_TARGETS_DB: &str = "attesters_max_targets";
-/// Map from `indexed_attestation_id` to `IndexedAttestation`.
-const INDEXED_ATTESTATION_DB: &str = "indexed_attestations";
-/// Map from `(target_epoch, indexed_attestation_hash)` to `indexed_attestation_id`.
-const INDEXED_ATTESTATION_ID_DB: &str = "indexed_attestation_ids";
-/// Table of minimum targets for every source epoch within range.
-const MIN_TARGETS_DB: &str = "min_targets";
-/// Table of maximum targets for every source epoch within range.
-const MAX_TARGETS_DB: &str = "max_targets";
-/// Map from `validator_index` to the `current_epoch` for that validator.
-///
-/// Used to implement wrap-around semantics for the min and max target arrays.
-const CURRENT_EPOCHS_DB: &str = "current_epochs";
-/// Map from `(slot, validator_index)` to `SignedBeaconBlockHeader`.
-const PROPOSERS_DB: &str = "proposers";
-
-/// The number of DBs for MDBX to use (equal to the number of DBs defined above).
-const MAX_NUM_DBS: usize = 9;
+/// The name of the MDBX data file within the slasher directory.
+pub const MDBX_DATA_FILENAME: &str = "mdbx.dat";
+/// The name of the MDBX lock file within the slasher directory.
+pub const MDBX_LOCK_FILENAME: &str = "mdbx.lck";
+/// The name of the redb database file within the slasher directory.
+pub const REDB_FILENAME: &str = "slasher.redb";
 
 /// Filename for the legacy (LMDB) database file, so that it may be deleted.
 const LEGACY_DB_FILENAME: &str = "data.mdb";
@@ -58,23 +48,39 @@ const LEGACY_DB_LOCK_FILENAME: &str = "lock.mdb";
 const METADATA_VERSION_KEY: &[u8] = &[0];
 /// Constant key under which the slasher configuration is stored in the `metadata_db`.
 const METADATA_CONFIG_KEY: &[u8] = &[1];
+/// Constant key under which the earliest epoch of retained history is stored in the `metadata_db`.
+const METADATA_HISTORY_START_KEY: &[u8] = &[2];
+/// Constant key under which the first epoch of a completed backfill is stored in the `metadata_db`.
+const METADATA_BACKFILL_START_KEY: &[u8] = &[3];
 
 const ATTESTER_KEY_SIZE: usize = 7;
 const PROPOSER_KEY_SIZE: usize = 16;
 const CURRENT_EPOCH_KEY_SIZE: usize = 8;
 const INDEXED_ATTESTATION_ID_SIZE: usize = 6;
 const INDEXED_ATTESTATION_ID_KEY_SIZE: usize = 40;
-const MEGABYTE: usize = 1 << 20;
 
 #[derive(Debug)]
 pub struct SlasherDB<E: EthSpec> {
-    pub(crate) env: Environment,
+    pub(crate) env: Box<dyn Environment>,
     /// LRU cache mapping indexed attestation IDs to their attestation data roots.
     attestation_root_cache: Mutex<LruCache<IndexedAttestationId, Hash256>>,
+    /// Earliest epoch of history retained after trimming, cached from the `metadata_db`.
+    history_start: RwLock<Epoch>,
     pub(crate) config: Arc<Config>,
     _phantom: PhantomData<E>,
 }
 
+/// Returns the backend of the database already initialized at `path`, if any.
+fn existing_backend(path: &Path) -> Option<DatabaseBackend> {
+    if path.join(MDBX_DATA_FILENAME).exists() {
+        Some(DatabaseBackend::Mdbx)
+    } else if path.join(REDB_FILENAME).exists() {
+        Some(DatabaseBackend::Redb)
+    } else {
+        None
+    }
+}
+
 /// Database key for the `attesters` database.
 ///
 /// Stored as big-endian `(target_epoch, validator_index)` to enable efficient iteration
@@ -249,43 +255,16 @@ fn ssz_decode<T: Decode>(bytes: Cow<[u8]>) -> Result<T, Error> {
 
 impl<E: EthSpec> SlasherDB<E> {
     pub fn open(config: Arc<Config>, log: Logger) -> Result<Self, Error> {
-        // Delete any legacy LMDB database.
-        Self::delete_legacy_file(&config.database_path, LEGACY_DB_FILENAME, &log)?;
-        Self::delete_legacy_file(&config.database_path, LEGACY_DB_LOCK_FILENAME, &log)?;
+        info!(log, "Opening slasher database"; "backend" => %config.backend);
 
-        std::fs::create_dir_all(&config.database_path)?;
-
-        let env = Environment::new()
-            .set_max_dbs(MAX_NUM_DBS)
-            .set_geometry(Self::geometry(&config))
-            .open_with_permissions(&config.database_path, 0o600)?;
-
-        let txn = env.begin_rw_txn()?;
-        txn.create_db(Some(INDEXED_ATTESTATION_DB), Self::db_flags())?;
-        txn.create_db(Some(INDEXED_ATTESTATION_ID_DB), Self::db_flags())?;
-        txn.create_db(Some(ATTESTERS_DB), Self::db_flags())?;
-        txn.create_db(Some(ATTESTERS_MAX_TARGETS_DB), Self::db_flags())?;
-        txn.create_db(Some(MIN_TARGETS_DB), Self::db_flags())?;
-        txn.create_db(Some(MAX_TARGETS_DB), Self::db_flags())?;
-        txn.create_db(Some(CURRENT_EPOCHS_DB), Self::db_flags())?;
-        txn.create_db(Some(PROPOSERS_DB), Self::db_flags())?;
-        txn.create_db(Some(METADATA_DB), Self::db_flags())?;
-        txn.commit()?;
-
-        #[cfg(windows)]
-        {
-            use filesystem::restrict_file_permissions;
-            let data = config.database_path.join("mdbx.dat");
-            let lock = config.database_path.join("mdbx.lck");
-            restrict_file_permissions(data).map_err(Error::DatabasePermissionsError)?;
-            restrict_file_permissions(lock).map_err(Error::DatabasePermissionsError)?;
-        }
+        let env = Self::open_environment(&config, &log)?;
 
         let attestation_root_cache = Mutex::new(LruCache::new(config.attestation_root_cache_size));
 
         let mut db = Self {
             env,
             attestation_root_cache,
+            history_start: RwLock::new(Epoch::new(0)),
             config,
             _phantom: PhantomData,
         };
@@ -302,11 +281,56 @@ impl<E: EthSpec> SlasherDB<E> {
                 });
             }
         }
+        let history_start = db
+            .load_history_start(&mut txn)?
+            .unwrap_or_else(|| Epoch::new(0));
         txn.commit()?;
+        *db.history_start.write() = history_start;
 
         Ok(db)
     }
 
+    /// Open the database using the backend selected by `config.backend`.
+    fn open_environment(config: &Config, log: &Logger) -> Result<Box<dyn Environment>, Error> {
+        match config.backend {
+            DatabaseBackend::Mdbx => {
+                Self::prepare_database_dir(config, log)?;
+                Ok(Box::new(MdbxEnvironment::open(config)?))
+            }
+            #[cfg(feature = "redb")]
+            DatabaseBackend::Redb => {
+                Self::prepare_database_dir(config, log)?;
+                Ok(Box::new(RedbEnvironment::open(config)?))
+            }
+            #[cfg(not(feature = "redb"))]
+            DatabaseBackend::Redb => {
+                Self::prepare_database_dir(config, log)?;
+                Err(Error::BackendNotCompiled(DatabaseBackend::Redb))
+            }
+            DatabaseBackend::Memory => Ok(Box::new(MemoryEnvironment::default())),
+        }
+    }
+
+    /// Create the database directory for an on-disk backend.
+    ///
+    /// The backend of a database on disk can't be changed, so an error is returned if another
+    /// backend has already been used in `config.database_path`.
+    fn prepare_database_dir(config: &Config, log: &Logger) -> Result<(), Error> {
+        // Delete any legacy LMDB database.
+        Self::delete_legacy_file(&config.database_path, LEGACY_DB_FILENAME, log)?;
+        Self::delete_legacy_file(&config.database_path, LEGACY_DB_LOCK_FILENAME, log)?;
+
+        std::fs::create_dir_all(&config.database_path)?;
+
+        match existing_backend(&config.database_path) {
+            Some(on_disk) if on_disk != config.backend => Err(Error::MismatchedBackend {
+                config: config.backend,
+                on_disk,
+            }),
+            _ => Ok(()),
+        }
+    }
+
     fn delete_legacy_file(slasher_dir: &Path, filename: &str, log: &Logger) -> Result<(), Error> {
         let path = slasher_dir.join(filename);
 
@@ -321,88 +345,21 @@ impl<E: EthSpec> SlasherDB<E> {
         Ok(())
     }
 
-    fn open_db<'a>(&self, txn: &'a RwTransaction<'a>, name: &str) -> Result<Database<'a>, Error> {
-        Ok(txn.open_db(Some(name))?)
-    }
-
-    pub fn indexed_attestation_db<'a>(
-        &self,
-        txn: &'a RwTransaction<'a>,
-    ) -> Result<Database<'a>, Error> {
-        self.open_db(txn, INDEXED_ATTESTATION_DB)
-    }
-
-    pub fn indexed_attestation_id_db<'a>(
-        &self,
-        txn: &'a RwTransaction<'a>,
-    ) -> Result<Database<'a>, Error> {
-        self.open_db(txn, INDEXED_ATTESTATION_ID_DB)
-    }
-
-    pub fn attesters_db<'a>(&self, txn: &'a RwTransaction<'a>) -> Result<Database<'a>, Error> {
-        self.open_db(txn, ATTESTERS_DB)
-    }
-
-    pub fn attesters_max_targets_db<'a>(
-        &self,
-        txn: &'a RwTransaction<'a>,
-    ) -> Result<Database<'a>, Error> {
-        self.open_db(txn, ATTESTERS_MAX_TARGETS_DB)
-    }
-
-    pub fn min_targets_db<'a>(&self, txn: &'a RwTransaction<'a>) -> Result<Database<'a>, Error> {
-        self.open_db(txn, MIN_TARGETS_DB)
-    }
-
-    pub fn max_targets_db<'a>(&self, txn: &'a RwTransaction<'a>) -> Result<Database<'a>, Error> {
-        self.open_db(txn, MAX_TARGETS_DB)
-    }
-
-    pub fn current_epochs_db<'a>(&self, txn: &'a RwTransaction<'a>) -> Result<Database<'a>, Error> {
-        self.open_db(txn, CURRENT_EPOCHS_DB)
-    }
-
-    pub fn proposers_db<'a>(&self, txn: &'a RwTransaction<'a>) -> Result<Database<'a>, Error> {
-        self.open_db(txn, PROPOSERS_DB)
-    }
-
-    pub fn metadata_db<'a>(&self, txn: &'a RwTransaction<'a>) -> Result<Database<'a>, Error> {
-        self.open_db(txn, METADATA_DB)
-    }
-
-    pub fn db_flags() -> DatabaseFlags {
-        DatabaseFlags::default()
-    }
-
-    pub fn write_flags() -> WriteFlags {
-        WriteFlags::default()
-    }
-
     pub fn begin_rw_txn(&self) -> Result<RwTransaction<'_>, Error> {
-        Ok(self.env.begin_rw_txn()?)
-    }
-
-    pub fn geometry(config: &Config) -> Geometry<Range<usize>> {
-        Geometry {
-            size: Some(0..config.max_db_size_mbs * MEGABYTE),
-            growth_step: Some(MDBX_GROWTH_STEP),
-            shrink_threshold: None,
-            page_size: None,
-        }
+        self.env.begin_rw_txn()
     }
 
     pub fn load_schema_version(&self, txn: &mut RwTransaction<'_>) -> Result<Option<u64>, Error> {
-        txn.get(&self.metadata_db(txn)?, METADATA_VERSION_KEY)?
+        txn.get(Database::Metadata, METADATA_VERSION_KEY)?
             .map(bincode_deserialize)
             .transpose()
     }
 
     pub fn store_schema_version(&self, txn: &mut RwTransaction<'_>) -> Result<(), Error> {
         txn.put(
-            &self.metadata_db(txn)?,
-            &METADATA_VERSION_KEY,
+            Database::Metadata,
+            METADATA_VERSION_KEY,
             &bincode::serialize(&CURRENT_SCHEMA_VERSION)?,
-            Self::write_flags(),
         )?;
         Ok(())
     }
@@ -415,28 +372,101 @@ impl<E: EthSpec> SlasherDB<E> {
         &self,
         txn: &mut RwTransaction<'_>,
     ) -> Result<Option<T>, Error> {
-        txn.get(&self.metadata_db(txn)?, METADATA_CONFIG_KEY)?
+        txn.get(Database::Metadata, METADATA_CONFIG_KEY)?
             .map(bincode_deserialize)
             .transpose()
     }
 
     pub fn store_config(&self, config: &Config, txn: &mut RwTransaction<'_>) -> Result<(), Error> {
         txn.put(
-            &self.metadata_db(txn)?,
-            &METADATA_CONFIG_KEY,
+            Database::Metadata,
+            METADATA_CONFIG_KEY,
             &bincode::serialize(config)?,
-            Self::write_flags(),
         )?;
         Ok(())
     }
 
+    pub fn load_history_start(&self, txn: &mut RwTransaction<'_>) -> Result<Option<Epoch>, Error> {
+        txn.get(Database::Metadata, METADATA_HISTORY_START_KEY)?
+            .map(ssz_decode)
+            .transpose()
+    }
+
+    fn store_history_start(
+        &self,
+        history_start: Epoch,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<(), Error> {
+        txn.put(
+            Database::Metadata,
+            METADATA_HISTORY_START_KEY,
+            &history_start.as_ssz_bytes(),
+        )?;
+        Ok(())
+    }
+
+    /// Return the first epoch checked by the backfill from stored blocks, if it has completed.
+    pub fn load_backfill_start(&self, txn: &mut RwTransaction<'_>) -> Result<Option<Epoch>, Error> {
+        txn.get(Database::Metadata, METADATA_BACKFILL_START_KEY)?
+            .map(ssz_decode)
+            .transpose()
+    }
+
+    pub fn store_backfill_start(
+        &self,
+        backfill_start: Epoch,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<(), Error> {
+        txn.put(
+            Database::Metadata,
+            METADATA_BACKFILL_START_KEY,
+            &backfill_start.as_ssz_bytes(),
+        )?;
+        Ok(())
+    }
+
+    /// Return the earliest epoch of history retained after trimming.
+    ///
+    /// Attestations targeting earlier epochs are not checked for slashings.
+    pub fn history_start(&self) -> Epoch {
+        *self.history_start.read()
+    }
+
+    /// Return the number of bytes used by the database, see `Environment::used_size`.
+    pub fn used_size(&self) -> Result<usize, Error> {
+        self.env.used_size()
+    }
+
+    /// Return the number of bytes used by each table, see `Environment::table_size`.
+    ///
+    /// Tables whose size can't be measured by the backend are omitted.
+    pub fn table_sizes(&self) -> Result<Vec<(Database, usize)>, Error> {
+        let mut sizes = vec![];
+        for db in Database::ALL {
+            if let Some(size) = self.env.table_size(db)? {
+                sizes.push((db, size));
+            }
+        }
+        Ok(sizes)
+    }
+
+    /// Return the earliest epoch retained at `current_epoch`, accounting for trimming.
+    fn min_retained_epoch(&self, current_epoch: Epoch) -> Epoch {
+        std::cmp::max(
+            current_epoch
+                .saturating_add(1u64)
+                .saturating_sub(self.config.history_length),
+            self.history_start(),
+        )
+    }
+
     pub fn get_attester_max_target(
         &self,
         validator_index: u64,
         txn: &mut RwTransaction<'_>,
     ) -> Result<Option<Epoch>, Error> {
         txn.get(
-            &self.attesters_max_targets_db(txn)?,
+            Database::AttestersMaxTargets,
             CurrentEpochKey::new(validator_index).as_ref(),
         )?
         .map(ssz_decode)
@@ -466,19 +496,17 @@ impl<E: EthSpec> SlasherDB<E> {
             );
             for target_epoch in (start_epoch..max_target.as_u64()).map(Epoch::new) {
                 txn.put(
-                    &self.attesters_db(txn)?,
-                    &AttesterKey::new(validator_index, target_epoch, &self.config),
+                    Database::Attesters,
+                    AttesterKey::new(validator_index, target_epoch, &self.config).as_ref(),
                     &CompactAttesterRecord::null().as_bytes(),
-                    Self::write_flags(),
                 )?;
             }
         }
 
         txn.put(
-            &self.attesters_max_targets_db(txn)?,
-            &CurrentEpochKey::new(validator_index),
+            Database::AttestersMaxTargets,
+            CurrentEpochKey::new(validator_index).as_ref(),
             &max_target.as_ssz_bytes(),
-            Self::write_flags(),
         )?;
         Ok(())
     }
@@ -489,7 +517,7 @@ impl<E: EthSpec> SlasherDB<E> {
         txn: &mut RwTransaction<'_>,
     ) -> Result<Option<Epoch>, Error> {
         txn.get(
-            &self.current_epochs_db(txn)?,
+            Database::CurrentEpochs,
             CurrentEpochKey::new(validator_index).as_ref(),
         )?
         .map(ssz_decode)
@@ -503,10 +531,9 @@ impl<E: EthSpec> SlasherDB<E> {
         txn: &mut RwTransaction<'_>,
     ) -> Result<(), Error> {
         txn.put(
-            &self.current_epochs_db(txn)?,
-            &CurrentEpochKey::new(validator_index),
+            Database::CurrentEpochs,
+            CurrentEpochKey::new(validator_index).as_ref(),
             &current_epoch.as_ssz_bytes(),
-            Self::write_flags(),
         )?;
         Ok(())
     }
@@ -516,7 +543,7 @@ impl<E: EthSpec> SlasherDB<E> {
         txn: &mut RwTransaction<'_>,
         key: &IndexedAttestationIdKey,
     ) -> Result<Option<u64>, Error> {
-        txn.get(&self.indexed_attestation_id_db(txn)?, key.as_ref())?
+        txn.get(Database::IndexedAttestationId, key.as_ref())?
             .map(IndexedAttestationId::parse)
             .transpose()
     }
@@ -527,12 +554,7 @@ impl<E: EthSpec> SlasherDB<E> {
         key: &IndexedAttestationIdKey,
         value: IndexedAttestationId,
     ) -> Result<(), Error> {
-        txn.put(
-            &self.indexed_attestation_id_db(txn)?,
-            key,
-            &value,
-            Self::write_flags(),
-        )?;
+        txn.put(Database::IndexedAttestationId, key.as_ref(), value.as_ref())?;
         Ok(())
     }
 
@@ -556,19 +578,20 @@ impl<E: EthSpec> SlasherDB<E> {
         }
 
         // Store the new indexed attestation at the end of the current table.
-        let mut cursor = txn.cursor(&self.indexed_attestation_db(txn)?)?;
-
-        let indexed_att_id = match cursor.last::<_, ()>()? {
+        let indexed_att_id = match txn.last_key(Database::IndexedAttestation)? {
             // First ID is 1 so that 0 can be used to represent `null` in `CompactAttesterRecord`.
             None => 1,
-            Some((key_bytes, _)) => IndexedAttestationId::parse(key_bytes)? + 1,
+            Some(key_bytes) => IndexedAttestationId::parse(key_bytes)? + 1,
         };
 
         let attestation_key = IndexedAttestationId::new(indexed_att_id);
         let data = indexed_attestation.as_ssz_bytes();
 
-        cursor.put(attestation_key.as_ref(), &data, Self::write_flags())?;
-        drop(cursor);
+        txn.put(
+            Database::IndexedAttestation,
+            attestation_key.as_ref(),
+            &data,
+        )?;
 
         // Update the (epoch, hash) to ID mapping.
         self.put_indexed_attestation_id(txn, &id_key, attestation_key)?;
@@ -583,7 +606,7 @@ impl<E: EthSpec> SlasherDB<E> {
     ) -> Result<IndexedAttestation<E>, Error> {
         let bytes = txn
             .get(
-                &self.indexed_attestation_db(txn)?,
+                Database::IndexedAttestation,
                 indexed_attestation_id.as_ref(),
             )?
             .ok_or(Error::MissingIndexedAttestation {
@@ -685,10 +708,9 @@ impl<E: EthSpec> SlasherDB<E> {
             self.update_attester_max_target(validator_index, prev_max_target, target_epoch, txn)?;
 
             txn.put(
-                &self.attesters_db(txn)?,
-                &AttesterKey::new(validator_index, target_epoch, &self.config),
-                &indexed_attestation_id,
-                Self::write_flags(),
+                Database::Attesters,
+                AttesterKey::new(validator_index, target_epoch, &self.config).as_ref(),
+                indexed_attestation_id.as_ref(),
             )?;
 
             Ok(AttesterSlashingStatus::NotSlashable)
@@ -725,7 +747,7 @@ impl<E: EthSpec> SlasherDB<E> {
 
         let attester_key = AttesterKey::new(validator_index, target, &self.config);
         Ok(txn
-            .get(&self.attesters_db(txn)?, attester_key.as_ref())?
+            .get(Database::Attesters, attester_key.as_ref())?
             .map(CompactAttesterRecord::parse)
             .transpose()?
             .filter(|record| !record.is_null()))
@@ -738,7 +760,7 @@ impl<E: EthSpec> SlasherDB<E> {
         slot: Slot,
     ) -> Result<Option<SignedBeaconBlockHeader>, Error> {
         let proposer_key = ProposerKey::new(proposer_index, slot);
-        txn.get(&self.proposers_db(txn)?, proposer_key.as_ref())?
+        txn.get(Database::Proposers, proposer_key.as_ref())?
             .map(ssz_decode)
             .transpose()
     }
@@ -764,10 +786,9 @@ impl<E: EthSpec> SlasherDB<E> {
             }
         } else {
             txn.put(
-                &self.proposers_db(txn)?,
-                &ProposerKey::new(proposer_index, slot),
+                Database::Proposers,
+                ProposerKey::new(proposer_index, slot).as_ref(),
                 &block_header.as_ssz_bytes(),
-                Self::write_flags(),
             )?;
             Ok(ProposerSlashingStatus::NotSlashable)
         }
@@ -789,94 +810,79 @@ impl<E: EthSpec> SlasherDB<E> {
         current_epoch: Epoch,
         txn: &mut RwTransaction<'_>,
     ) -> Result<(), Error> {
-        self.prune_proposers(current_epoch, txn)?;
-        self.prune_indexed_attestations(current_epoch, txn)?;
+        let min_epoch = self.min_retained_epoch(current_epoch);
+        self.prune_proposers(min_epoch, txn)?;
+        self.prune_indexed_attestations(min_epoch, txn)?;
         Ok(())
     }
 
-    fn prune_proposers(
-        &self,
-        current_epoch: Epoch,
-        txn: &mut RwTransaction<'_>,
-    ) -> Result<(), Error> {
-        let min_slot = current_epoch
-            .saturating_add(1u64)
-            .saturating_sub(self.config.history_length)
-            .start_slot(E::slots_per_epoch());
-
-        let mut cursor = txn.cursor(&self.proposers_db(txn)?)?;
-
-        // Position cursor at first key, bailing out if the database is empty.
-        if cursor.first::<(), ()>()?.is_none() {
-            return Ok(());
+    /// Trim the oldest history from the database in order to reduce its size.
+    ///
+    /// The number of epochs retained is halved, down to a minimum of `MIN_TRIMMED_HISTORY_LENGTH`.
+    /// Blocks and attestations prior to the new start of history are deleted, and the space they
+    /// occupied is reused by later writes.
+    ///
+    /// Return the new start of history, or `None` if the history can't be trimmed any further.
+    pub fn trim_history(&self, current_epoch: Epoch) -> Result<Option<Epoch>, Error> {
+        let history_end = current_epoch.saturating_add(1u64);
+        let retained = history_end
+            .saturating_sub(self.min_retained_epoch(current_epoch))
+            .as_u64();
+        let min_retained = std::cmp::min(MIN_TRIMMED_HISTORY_LENGTH, self.config.history_length);
+        let min_retained = min_retained as u64;
+
+        if retained <= min_retained {
+            return Ok(None);
         }
+        let history_start = history_end.saturating_sub(std::cmp::max(retained / 2, min_retained));
 
-        loop {
-            let (key_bytes, ()) = cursor.get_current()?.ok_or(Error::MissingProposerKey)?;
+        let mut txn = self.begin_rw_txn()?;
+        self.store_history_start(history_start, &mut txn)?;
+        self.prune_proposers(history_start, &mut txn)?;
+        self.prune_indexed_attestations(history_start, &mut txn)?;
+        txn.commit()?;
 
-            let (slot, _) = ProposerKey::parse(key_bytes)?;
-            if slot < min_slot {
-                cursor.del(Self::write_flags())?;
+        *self.history_start.write() = history_start;
 
-                // End the loop if there is no next entry.
-                if cursor.next::<(), ()>()?.is_none() {
-                    break;
-                }
-            } else {
-                break;
-            }
-        }
+        Ok(Some(history_start))
+    }
 
-        Ok(())
+    fn prune_proposers(&self, min_epoch: Epoch, txn: &mut RwTransaction<'_>) -> Result<(), Error> {
+        let min_slot = min_epoch.start_slot(E::slots_per_epoch());
+
+        txn.delete_while(Database::Proposers, &mut |key_bytes, _| {
+            let (slot, _) = ProposerKey::parse(Cow::Borrowed(key_bytes))?;
+            Ok(slot < min_slot)
+        })
     }
 
     fn prune_indexed_attestations(
         &self,
-        current_epoch: Epoch,
+        min_epoch: Epoch,
         txn: &mut RwTransaction<'_>,
     ) -> Result<(), Error> {
-        let min_epoch = current_epoch
-            .saturating_add(1u64)
-            .saturating_sub(self.config.history_length as u64);
-
         // Collect indexed attestation IDs to delete.
         let mut indexed_attestation_ids = vec![];
 
-        let mut cursor = txn.cursor(&self.indexed_attestation_id_db(txn)?)?;
-
-        // Position cursor at first key, bailing out if the database is empty.
-        if cursor.first::<(), ()>()?.is_none() {
-            return Ok(());
-        }
-
-        loop {
-            let (key_bytes, value) = cursor
-                .get_current()?
-                .ok_or(Error::MissingIndexedAttestationIdKey)?;
-
-            let (target_epoch, _) = IndexedAttestationIdKey::parse(key_bytes)?;
+        txn.delete_while(Database::IndexedAttestationId, &mut |key_bytes, value| {
+            let (target_epoch, _) = IndexedAttestationIdKey::parse(Cow::Borrowed(key_bytes))?;
 
             if target_epoch < min_epoch {
                 indexed_attestation_ids.push(IndexedAttestationId::new(
-                    IndexedAttestationId::parse(value)?,
+                    IndexedAttestationId::parse(Cow::Borrowed(value))?,
                 ));
-
-                cursor.del(Self::write_flags())?;
-
-                if cursor.next::<(), ()>()?.is_none() {
-                    break;
-                }
+                Ok(true)
             } else {
-                break;
+                Ok(false)
             }
-        }
-        drop(cursor);
+        })?;
 
         // Delete the indexed attestations.
-        // Optimisation potential: use a cursor here.
-        let indexed_attestation_db = self.indexed_attestation_db(txn)?;
         for indexed_attestation_id in &indexed_attestation_ids {
-            txn.del(&indexed_attestation_db, indexed_attestation_id, None)?;
+            txn.del(
+                Database::IndexedAttestation,
+                indexed_attestation_id.as_ref(),
+            )?;
         }
         self.delete_attestation_data_roots(indexed_attestation_ids);
 
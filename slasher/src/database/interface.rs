@@ -0,0 +1,113 @@
+//! The interface between the slasher and the key-value store that backs its database.
+//!
+//! Each backend implements `Environment` and `Transaction`, and the slasher only interacts with
+//! the store through these traits. All reads and writes happen inside a read-write transaction,
+//! which is discarded unless it is committed.
+use crate::Error;
+use std::borrow::Cow;
+use std::fmt::Debug;
+
+/// A read-write transaction on any backend.
+pub type RwTransaction<'env> = Box<dyn Transaction + 'env>;
+
+/// An open slasher database.
+pub trait Environment: Debug + Send + Sync {
+    /// Begin a read-write transaction.
+    ///
+    /// Only one read-write transaction may be open at a time, so this blocks until any other
+    /// transaction has been committed or dropped.
+    fn begin_rw_txn(&self) -> Result<RwTransaction<'_>, Error>;
+
+    /// Return the number of bytes used to store the database's contents.
+    ///
+    /// Space which has been freed and will be reused by later writes is not included.
+    fn used_size(&self) -> Result<usize, Error>;
+
+    /// Return the number of bytes used to store the contents of `db`, if the backend can measure
+    /// the size of individual tables.
+    fn table_size(&self, db: Database) -> Result<Option<usize>, Error>;
+}
+
+/// A read-write transaction, which is aborted if it is dropped without being committed.
+pub trait Transaction {
+    /// Return the value stored under `key` in `db`, if any.
+    fn get(&self, db: Database, key: &[u8]) -> Result<Option<Cow<'_, [u8]>>, Error>;
+
+    /// Store `value` under `key` in `db`, replacing any existing value.
+    fn put(&mut self, db: Database, key: &[u8], value: &[u8]) -> Result<(), Error>;
+
+    /// Delete `key` from `db`, if it is present.
+    fn del(&mut self, db: Database, key: &[u8]) -> Result<(), Error>;
+
+    /// Return the greatest key in `db`, or `None` if it is empty.
+    fn last_key(&self, db: Database) -> Result<Option<Cow<'_, [u8]>>, Error>;
+
+    /// Delete entries from the start of `db` in key order while `f` returns `true` for them.
+    ///
+    /// Deletion stops at the first entry for which `f` returns `false`, which is kept.
+    fn delete_while(
+        &mut self,
+        db: Database,
+        f: &mut dyn FnMut(&[u8], &[u8]) -> Result<bool, Error>,
+    ) -> Result<(), Error>;
+
+    /// Commit all changes made by the transaction.
+    fn commit(self: Box<Self>) -> Result<(), Error>;
+}
+
+/// The tables of the slasher database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Database {
+    /// Metadata about the slashing database itself.
+    Metadata,
+    /// Map from `(target_epoch, validator_index)` to `CompactAttesterRecord`.
+    Attesters,
+    /// Companion database for the attesters DB mapping `validator_index` to largest
+    /// `target_epoch` stored for that validator in the attesters DB.
+    ///
+    /// Used to implement wrap-around semantics for target epochs modulo the history length.
+    AttestersMaxTargets,
+    /// Map from `indexed_attestation_id` to `IndexedAttestation`.
+    IndexedAttestation,
+    /// Map from `(target_epoch, indexed_attestation_hash)` to `indexed_attestation_id`.
+    IndexedAttestationId,
+    /// Table of minimum targets for every source epoch within range.
+    MinTargets,
+    /// Table of maximum targets for every source epoch within range.
+    MaxTargets,
+    /// Map from `validator_index` to the `current_epoch` for that validator.
+    ///
+    /// Used to implement wrap-around semantics for the min and max target arrays.
+    CurrentEpochs,
+    /// Map from `(slot, validator_index)` to `SignedBeaconBlockHeader`.
+    Proposers,
+}
+
+impl Database {
+    pub const ALL: [Database; 9] = [
+        Database::Metadata,
+        Database::Attesters,
+        Database::AttestersMaxTargets,
+        Database::IndexedAttestation,
+        Database::IndexedAttestationId,
+        Database::MinTargets,
+        Database::MaxTargets,
+        Database::CurrentEpochs,
+        Database::Proposers,
+    ];
+
+    /// The name under which the table is stored on disk.
+    pub fn name(self) -> &'static str {
+        match self {
+            Database::Metadata => "metadata",
+            Database::Attesters => "attesters",
+            Database::AttestersMaxTargets => "attesters_max_targets",
+            Database::IndexedAttestation => "indexed_attestations",
+            Database::IndexedAttestationId => "indexed_attestation_ids",
+            Database::MinTargets => "min_targets",
+            Database::MaxTargets => "max_targets",
+            Database::CurrentEpochs => "current_epochs",
+            Database::Proposers => "proposers",
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::config::{MDBX_GROWTH_STEP, MEGABYTE};
+use crate::database::interface::{Database, Environment, RwTransaction, Transaction};
+#[cfg(windows)]
+use crate::database::{MDBX_DATA_FILENAME, MDBX_LOCK_FILENAME};
+use crate::{Config, Error};
+use mdbx::{DatabaseFlags, Geometry, NoWriteMap, WriteFlags, RW};
+use std::borrow::Cow;
+use std::ops::Range;
+
+#[derive(Debug)]
+pub struct MdbxEnvironment {
+    env: mdbx::Environment<NoWriteMap>,
+}
+
+pub struct MdbxTransaction<'env> {
+    txn: mdbx::Transaction<'env, RW, NoWriteMap>,
+}
+
+impl MdbxEnvironment {
+    /// Open the database in `config.database_path`, creating all tables if necessary.
+    pub fn open(config: &Config) -> Result<Self, Error> {
+        let env = mdbx::Environment::<NoWriteMap>::new()
+            .set_max_dbs(Database::ALL.len())
+            .set_geometry(Self::geometry(config))
+            .open_with_permissions(&config.database_path, 0o600)?;
+
+        let txn = env.begin_rw_txn()?;
+        for db in Database::ALL {
+            txn.create_db(Some(db.name()), DatabaseFlags::default())?;
+        }
+        txn.commit()?;
+
+        #[cfg(windows)]
+        {
+            use filesystem::restrict_file_permissions;
+            let data = config.database_path.join(MDBX_DATA_FILENAME);
+            let lock = config.database_path.join(MDBX_LOCK_FILENAME);
+            restrict_file_permissions(data).map_err(Error::DatabasePermissionsError)?;
+            restrict_file_permissions(lock).map_err(Error::DatabasePermissionsError)?;
+        }
+
+        Ok(Self { env })
+    }
+
+    pub fn geometry(config: &Config) -> Geometry<Range<usize>> {
+        Geometry {
+            size: Some(0..config.max_db_size_mbs * MEGABYTE),
+            growth_step: Some(MDBX_GROWTH_STEP),
+            shrink_threshold: None,
+            page_size: None,
+        }
+    }
+}
+
+impl Environment for MdbxEnvironment {
+    fn begin_rw_txn(&self) -> Result<RwTransaction<'_>, Error> {
+        Ok(Box::new(MdbxTransaction {
+            txn: self.env.begin_rw_txn()?,
+        }))
+    }
+
+    /// Pages on the freelist are reused by later writes, so aren't counted.
+    fn used_size(&self) -> Result<usize, Error> {
+        let page_size = self.env.stat()?.page_size() as usize;
+        let allocated_pages = self.env.info()?.last_pgno() + 1;
+        let free_pages = self.env.freelist()?;
+        Ok(allocated_pages.saturating_sub(free_pages) * page_size)
+    }
+
+    fn table_size(&self, db: Database) -> Result<Option<usize>, Error> {
+        let txn = self.env.begin_ro_txn()?;
+        let db = txn.open_db(Some(db.name()))?;
+        let stat = txn.db_stat(&db)?;
+        let num_pages = stat.branch_pages() + stat.leaf_pages() + stat.overflow_pages();
+        Ok(Some(num_pages * stat.page_size() as usize))
+    }
+}
+
+impl<'env> Transaction for MdbxTransaction<'env> {
+    fn get(&self, db: Database, key: &[u8]) -> Result<Option<Cow<'_, [u8]>>, Error> {
+        let db = self.txn.open_db(Some(db.name()))?;
+        Ok(self.txn.get(&db, key)?)
+    }
+
+    fn put(&mut self, db: Database, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        let db = self.txn.open_db(Some(db.name()))?;
+        self.txn.put(&db, key, value, WriteFlags::default())?;
+        Ok(())
+    }
+
+    fn del(&mut self, db: Database, key: &[u8]) -> Result<(), Error> {
+        let db = self.txn.open_db(Some(db.name()))?;
+        self.txn.del(&db, key, None)?;
+        Ok(())
+    }
+
+    fn last_key(&self, db: Database) -> Result<Option<Cow<'_, [u8]>>, Error> {
+        let db = self.txn.open_db(Some(db.name()))?;
+        let mut cursor = self.txn.cursor(&db)?;
+        Ok(cursor.last::<Cow<[u8]>, ()>()?.map(|(key, ())| key))
+    }
+
+    fn delete_while(
+        &mut self,
+        db: Database,
+        f: &mut dyn FnMut(&[u8], &[u8]) -> Result<bool, Error>,
+    ) -> Result<(), Error> {
+        let db = self.txn.open_db(Some(db.name()))?;
+        let mut cursor = self.txn.cursor(&db)?;
+
+        let mut entry = cursor.first::<Cow<[u8]>, Cow<[u8]>>()?;
+        while let Some((key, value)) = entry {
+            if !f(&key, &value)? {
+                break;
+            }
+            cursor.del(WriteFlags::default())?;
+            // Deleting the current entry leaves the cursor positioned such that `next` returns
+            // the entry after it.
+            entry = cursor.next()?;
+        }
+        Ok(())
+    }
+
+    fn commit(self: Box<Self>) -> Result<(), Error> {
+        self.txn.commit()?;
+        Ok(())
+    }
+}
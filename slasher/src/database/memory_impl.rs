@@ -0,0 +1,127 @@
+use crate::database::interface::{Database, Environment, RwTransaction, Transaction};
+use crate::Error;
+use parking_lot::{Mutex, MutexGuard};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+
+type Table = BTreeMap<Vec<u8>, Vec<u8>>;
+
+/// A database held entirely in memory, which is lost when it is dropped.
+///
+/// Intended for testing.
+#[derive(Debug, Default)]
+pub struct MemoryEnvironment {
+    tables: Mutex<HashMap<Database, Table>>,
+}
+
+/// A transaction on a `MemoryEnvironment`.
+///
+/// Writes are applied immediately, and the previous value of each key written is recorded so
+/// that the writes may be undone if the transaction is dropped without being committed.
+pub struct MemoryTransaction<'env> {
+    tables: MutexGuard<'env, HashMap<Database, Table>>,
+    undo_log: Vec<(Database, Vec<u8>, Option<Vec<u8>>)>,
+}
+
+impl Environment for MemoryEnvironment {
+    fn begin_rw_txn(&self) -> Result<RwTransaction<'_>, Error> {
+        Ok(Box::new(MemoryTransaction {
+            tables: self.tables.lock(),
+            undo_log: vec![],
+        }))
+    }
+
+    fn used_size(&self) -> Result<usize, Error> {
+        Ok(self
+            .tables
+            .lock()
+            .values()
+            .flat_map(|table| table.iter())
+            .map(|(key, value)| key.len() + value.len())
+            .sum())
+    }
+
+    fn table_size(&self, db: Database) -> Result<Option<usize>, Error> {
+        Ok(Some(self.tables.lock().get(&db).map_or(0, |table| {
+            table
+                .iter()
+                .map(|(key, value)| key.len() + value.len())
+                .sum()
+        })))
+    }
+}
+
+impl<'env> MemoryTransaction<'env> {
+    fn table_mut(&mut self, db: Database) -> &mut Table {
+        self.tables.entry(db).or_default()
+    }
+}
+
+impl<'env> Transaction for MemoryTransaction<'env> {
+    fn get(&self, db: Database, key: &[u8]) -> Result<Option<Cow<'_, [u8]>>, Error> {
+        Ok(self
+            .tables
+            .get(&db)
+            .and_then(|table| table.get(key))
+            .map(|value| Cow::Borrowed(value.as_slice())))
+    }
+
+    fn put(&mut self, db: Database, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        let previous = self.table_mut(db).insert(key.to_vec(), value.to_vec());
+        self.undo_log.push((db, key.to_vec(), previous));
+        Ok(())
+    }
+
+    fn del(&mut self, db: Database, key: &[u8]) -> Result<(), Error> {
+        if let Some(previous) = self.table_mut(db).remove(key) {
+            self.undo_log.push((db, key.to_vec(), Some(previous)));
+        }
+        Ok(())
+    }
+
+    fn last_key(&self, db: Database) -> Result<Option<Cow<'_, [u8]>>, Error> {
+        Ok(self
+            .tables
+            .get(&db)
+            .and_then(|table| table.keys().next_back())
+            .map(|key| Cow::Borrowed(key.as_slice())))
+    }
+
+    fn delete_while(
+        &mut self,
+        db: Database,
+        f: &mut dyn FnMut(&[u8], &[u8]) -> Result<bool, Error>,
+    ) -> Result<(), Error> {
+        let mut to_delete = vec![];
+        if let Some(table) = self.tables.get(&db) {
+            for (key, value) in table {
+                if !f(key, value)? {
+                    break;
+                }
+                to_delete.push(key.clone());
+            }
+        }
+
+        for key in to_delete {
+            self.del(db, &key)?;
+        }
+        Ok(())
+    }
+
+    fn commit(mut self: Box<Self>) -> Result<(), Error> {
+        self.undo_log.clear();
+        Ok(())
+    }
+}
+
+impl<'env> Drop for MemoryTransaction<'env> {
+    fn drop(&mut self) {
+        while let Some((db, key, previous)) = self.undo_log.pop() {
+            let table = self.table_mut(db);
+            match previous {
+                Some(value) => table.insert(key, value),
+                None => table.remove(&key),
+            };
+        }
+    }
+}
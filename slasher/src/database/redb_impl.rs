@@ -0,0 +1,143 @@
+use crate::database::interface::{Database, Environment, RwTransaction, Transaction};
+use crate::database::REDB_FILENAME;
+use crate::{Config, Error};
+use redb::{ReadableTable, TableDefinition, WriteTransaction};
+use std::borrow::Cow;
+use std::fmt;
+
+pub struct RedbEnvironment {
+    db: redb::Database,
+}
+
+pub struct RedbTransaction<'env> {
+    txn: WriteTransaction<'env>,
+}
+
+fn table_definition(db: Database) -> TableDefinition<'static, &'static [u8], &'static [u8]> {
+    TableDefinition::new(db.name())
+}
+
+impl RedbEnvironment {
+    /// Open the database in `config.database_path`, creating all tables if necessary.
+    ///
+    /// Redb grows its file as required, so `config.max_db_size_mbs` is not applied.
+    pub fn open(config: &Config) -> Result<Self, Error> {
+        let path = config.database_path.join(REDB_FILENAME);
+        let db = redb::Database::create(&path).map_err(redb_error)?;
+        filesystem::restrict_file_permissions(&path).map_err(Error::DatabasePermissionsError)?;
+
+        let txn = db.begin_write().map_err(redb_error)?;
+        for table in Database::ALL {
+            txn.open_table(table_definition(table))
+                .map_err(redb_error)?;
+        }
+        txn.commit().map_err(redb_error)?;
+
+        Ok(Self { db })
+    }
+}
+
+impl fmt::Debug for RedbEnvironment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RedbEnvironment").finish_non_exhaustive()
+    }
+}
+
+impl Environment for RedbEnvironment {
+    fn begin_rw_txn(&self) -> Result<RwTransaction<'_>, Error> {
+        Ok(Box::new(RedbTransaction {
+            txn: self.db.begin_write().map_err(redb_error)?,
+        }))
+    }
+
+    fn used_size(&self) -> Result<usize, Error> {
+        let txn = self.db.begin_write().map_err(redb_error)?;
+        let stats = txn.stats().map_err(redb_error)?;
+        txn.abort().map_err(redb_error)?;
+        Ok(stats.allocated_pages() as usize * stats.page_size())
+    }
+
+    /// Redb only reports statistics for the whole database.
+    fn table_size(&self, _db: Database) -> Result<Option<usize>, Error> {
+        Ok(None)
+    }
+}
+
+impl<'env> Transaction for RedbTransaction<'env> {
+    fn get(&self, db: Database, key: &[u8]) -> Result<Option<Cow<'_, [u8]>>, Error> {
+        let table = self
+            .txn
+            .open_table(table_definition(db))
+            .map_err(redb_error)?;
+        let value = table.get(key).map_err(redb_error)?;
+        Ok(value.map(|value| Cow::Owned(value.value().to_vec())))
+    }
+
+    fn put(&mut self, db: Database, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        let mut table = self
+            .txn
+            .open_table(table_definition(db))
+            .map_err(redb_error)?;
+        table.insert(key, value).map_err(redb_error)?;
+        Ok(())
+    }
+
+    fn del(&mut self, db: Database, key: &[u8]) -> Result<(), Error> {
+        let mut table = self
+            .txn
+            .open_table(table_definition(db))
+            .map_err(redb_error)?;
+        table.remove(key).map_err(redb_error)?;
+        Ok(())
+    }
+
+    fn last_key(&self, db: Database) -> Result<Option<Cow<'_, [u8]>>, Error> {
+        let table = self
+            .txn
+            .open_table(table_definition(db))
+            .map_err(redb_error)?;
+        let mut range = table.range::<&[u8]>(..).map_err(redb_error)?;
+        range
+            .next_back()
+            .map(|entry| {
+                let (key, _) = entry.map_err(redb_error)?;
+                Ok(Cow::Owned(key.value().to_vec()))
+            })
+            .transpose()
+    }
+
+    fn delete_while(
+        &mut self,
+        db: Database,
+        f: &mut dyn FnMut(&[u8], &[u8]) -> Result<bool, Error>,
+    ) -> Result<(), Error> {
+        let mut table = self
+            .txn
+            .open_table(table_definition(db))
+            .map_err(redb_error)?;
+
+        // The table can't be modified while it is being iterated, so collect the keys first.
+        let mut to_delete = vec![];
+        for entry in table.range::<&[u8]>(..).map_err(redb_error)? {
+            let (key, value) = entry.map_err(redb_error)?;
+            if !f(key.value(), value.value())? {
+                break;
+            }
+            to_delete.push(key.value().to_vec());
+        }
+
+        for key in to_delete {
+            table.remove(key.as_slice()).map_err(redb_error)?;
+        }
+        Ok(())
+    }
+
+    fn commit(self: Box<Self>) -> Result<(), Error> {
+        self.txn.commit().map_err(redb_error)
+    }
+}
+
+/// Convert any of redb's error types into an `Error`.
+fn redb_error(e: impl Into<redb::Error>) -> Error {
+    Error::RedbError(e.into())
+}
@@ -0,0 +1,158 @@
+//! Transactions for worker threads, which share the single read-write transaction of a batch.
+//!
+//! A backend's read-write transaction can't be shared between threads, so a `WorkerTransaction`
+//! forwards its reads over a channel to the thread that owns the transaction. Writes are buffered
+//! by the worker, and applied to the shared transaction when the worker commits. Workers must
+//! write to disjoint keys, as the slasher's workers do by processing distinct validator chunks.
+use crate::database::interface::{Database, RwTransaction, Transaction};
+use crate::Error;
+use crossbeam_channel::{bounded, unbounded, Receiver, Sender};
+use rayon::ThreadPool;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Buffered writes, mapping each key to its new value, or `None` if it was deleted.
+type Writes = HashMap<(Database, Vec<u8>), Option<Vec<u8>>>;
+
+enum Request {
+    Get {
+        db: Database,
+        key: Vec<u8>,
+        reply: Sender<Result<Option<Vec<u8>>, Error>>,
+    },
+    Commit {
+        writes: Writes,
+        reply: Sender<Result<(), Error>>,
+    },
+}
+
+struct WorkerTransaction {
+    requests: Sender<Request>,
+    writes: Writes,
+}
+
+impl WorkerTransaction {
+    fn request<T>(&self, request: impl FnOnce(Sender<T>) -> Request) -> Result<T, Error> {
+        let (reply, response) = bounded(1);
+        self.requests
+            .send(request(reply))
+            .map_err(|_| Error::WorkerDisconnected)?;
+        response.recv().map_err(|_| Error::WorkerDisconnected)
+    }
+}
+
+impl Transaction for WorkerTransaction {
+    fn get(&self, db: Database, key: &[u8]) -> Result<Option<Cow<'_, [u8]>>, Error> {
+        if let Some(value) = self.writes.get(&(db, key.to_vec())) {
+            return Ok(value.as_deref().map(Cow::Borrowed));
+        }
+        let key = key.to_vec();
+        Ok(self
+            .request(|reply| Request::Get { db, key, reply })??
+            .map(Cow::Owned))
+    }
+
+    fn put(&mut self, db: Database, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        self.writes.insert((db, key.to_vec()), Some(value.to_vec()));
+        Ok(())
+    }
+
+    fn del(&mut self, db: Database, key: &[u8]) -> Result<(), Error> {
+        self.writes.insert((db, key.to_vec()), None);
+        Ok(())
+    }
+
+    fn last_key(&self, _: Database) -> Result<Option<Cow<'_, [u8]>>, Error> {
+        Err(Error::UnsupportedInWorker("last_key"))
+    }
+
+    fn delete_while(
+        &mut self,
+        _: Database,
+        _: &mut dyn FnMut(&[u8], &[u8]) -> Result<bool, Error>,
+    ) -> Result<(), Error> {
+        Err(Error::UnsupportedInWorker("delete_while"))
+    }
+
+    fn commit(self: Box<Self>) -> Result<(), Error> {
+        let writes = self.writes;
+        let requests = self.requests;
+        let (reply, response) = bounded(1);
+        requests
+            .send(Request::Commit { writes, reply })
+            .map_err(|_| Error::WorkerDisconnected)?;
+        response.recv().map_err(|_| Error::WorkerDisconnected)?
+    }
+}
+
+/// Call `work` on each of the `items` in parallel on the `pool`, sharing `txn` between them.
+///
+/// Each call is given its own transaction, whose writes are applied to `txn` once the call
+/// succeeds. The calls must write to disjoint keys. Return the results in the order of `items`,
+/// or the first error encountered.
+pub fn run_in_workers<T, R, F>(
+    pool: &ThreadPool,
+    txn: &mut RwTransaction<'_>,
+    items: Vec<T>,
+    work: F,
+) -> Result<Vec<R>, Error>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T, &mut RwTransaction<'_>) -> Result<R, Error> + Sync,
+{
+    let num_items = items.len();
+    let (request_tx, request_rx) = unbounded();
+    let (result_tx, result_rx) = unbounded();
+
+    pool.in_place_scope(|scope| {
+        for (i, item) in items.into_iter().enumerate() {
+            let requests = request_tx.clone();
+            let result_tx = result_tx.clone();
+            let work = &work;
+            scope.spawn(move |_| {
+                let mut worker_txn: RwTransaction<'_> = Box::new(WorkerTransaction {
+                    requests,
+                    writes: Writes::new(),
+                });
+                let result = work(item, &mut worker_txn)
+                    .and_then(|result| worker_txn.commit().map(|()| result));
+                // The receiver outlives the scope.
+                let _ = result_tx.send((i, result));
+            });
+        }
+        // Serve requests until every worker has finished and dropped its sender.
+        drop(request_tx);
+        serve_requests(txn, request_rx);
+    });
+    drop(result_tx);
+
+    let mut results = result_rx.iter().collect::<Vec<_>>();
+    debug_assert_eq!(results.len(), num_items);
+    results.sort_unstable_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+fn serve_requests(txn: &mut RwTransaction<'_>, requests: Receiver<Request>) {
+    for request in requests {
+        // Workers which have stopped waiting for a reply have already failed, so errors sending
+        // replies are ignored.
+        match request {
+            Request::Get { db, key, reply } => {
+                let value = txn
+                    .get(db, &key)
+                    .map(|value| value.map(|value| value.into_owned()));
+                let _ = reply.send(value);
+            }
+            Request::Commit { writes, reply } => {
+                let result = writes
+                    .into_iter()
+                    .try_for_each(|((db, key), value)| match value {
+                        Some(value) => txn.put(db, &key, &value),
+                        None => txn.del(db, &key),
+                    });
+                let _ = reply.send(result);
+            }
+        }
+    }
+}
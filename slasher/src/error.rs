@@ -1,10 +1,12 @@
-use crate::config::{Config, DiskConfig};
+use crate::config::{Config, DatabaseBackend, DiskConfig};
 use std::io;
 use types::Epoch;
 
 #[derive(Debug)]
 pub enum Error {
     DatabaseError(mdbx::Error),
+    #[cfg(feature = "redb")]
+    RedbError(redb::Error),
     DatabaseIOError(io::Error),
     DatabasePermissionsError(filesystem::Error),
     SszDecodeError(ssz::DecodeError),
@@ -31,6 +33,13 @@ pub enum Error {
         config: DiskConfig,
     },
     ConfigMissing,
+    /// The database on disk was created by a different backend to the one configured.
+    MismatchedBackend {
+        config: DatabaseBackend,
+        on_disk: DatabaseBackend,
+    },
+    /// The backend isn't available in this build of Lighthouse.
+    BackendNotCompiled(DatabaseBackend),
     DistanceTooLarge,
     DistanceCalculationOverflow,
     /// Missing an attester record that we expected to exist.
@@ -57,10 +66,20 @@ pub enum Error {
         id: u64,
     },
     MissingAttesterKey,
-    MissingProposerKey,
     MissingIndexedAttestationId,
-    MissingIndexedAttestationIdKey,
     InconsistentAttestationDataRoot,
+    /// A worker's transaction lost contact with the thread owning the batch's transaction.
+    WorkerDisconnected,
+    /// The operation can't be performed by a worker's transaction.
+    UnsupportedInWorker(&'static str),
+    WorkerPoolError(String),
+}
+
+impl Error {
+    /// Returns `true` if the error was caused by the database reaching its maximum size.
+    pub fn is_database_full(&self) -> bool {
+        matches!(self, Error::DatabaseError(mdbx::Error::MapFull))
+    }
 }
 
 impl From<mdbx::Error> for Error {
@@ -0,0 +1,172 @@
+//! Export of everything the slasher has stored for a single validator.
+//!
+//! The export contains the validator's min-max target arrays, decoded from their chunks, and the
+//! attestations it signed. Together these allow an operator to check offline why a pair of
+//! attestations was or wasn't reported as slashable.
+use crate::array::{get_chunk_for_update, MaxTargetChunk, MinTargetChunk, TargetArrayChunk};
+use crate::{Config, Error, RwTransaction, SlasherDB};
+use serde_derive::Serialize;
+use std::collections::BTreeMap;
+use types::{Epoch, EthSpec, IndexedAttestation};
+
+#[derive(Debug, Serialize)]
+#[serde(bound = "E: EthSpec")]
+pub struct ValidatorExport<E: EthSpec> {
+    pub validator_index: u64,
+    /// The epoch up to which the validator's arrays have been updated, if it has ever attested.
+    pub current_epoch: Option<Epoch>,
+    /// The highest target epoch of the validator's attestations, if any.
+    pub max_target: Option<Epoch>,
+    /// Earliest epoch of history retained after trimming.
+    pub history_start: Epoch,
+    pub history_length: usize,
+    pub chunk_size: usize,
+    pub validator_chunk_size: usize,
+    /// The min and max targets for each source epoch within the history length of the
+    /// `current_epoch`, in ascending order of source epoch.
+    pub targets: Vec<TargetEntry>,
+    /// The validator's attestations, in ascending order of target epoch.
+    pub attestations: Vec<AttestationEntry<E>>,
+}
+
+/// The entries of the min-max arrays for a single source epoch.
+///
+/// An attestation with this source epoch surrounds an existing attestation if its target is
+/// greater than the `min_target`, and is surrounded by an existing attestation if its target is
+/// less than the `max_target`. `None` means that no attestation bounds the target.
+#[derive(Debug, Serialize)]
+pub struct TargetEntry {
+    pub source_epoch: Epoch,
+    pub min_target: Option<Epoch>,
+    pub max_target: Option<Epoch>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(bound = "E: EthSpec")]
+pub struct AttestationEntry<E: EthSpec> {
+    pub target_epoch: Epoch,
+    pub indexed_attestation_id: u64,
+    /// The attestation, or `None` if it has been pruned from the database.
+    pub attestation: Option<IndexedAttestation<E>>,
+}
+
+/// Read everything stored for `validator_index` from the `db`.
+///
+/// Nothing is written, but a read-write transaction is used and so batch processing by a slasher
+/// using the same database is blocked while the export runs.
+pub fn export_validator<E: EthSpec>(
+    db: &SlasherDB<E>,
+    validator_index: u64,
+) -> Result<ValidatorExport<E>, Error> {
+    let config = &db.config;
+    let mut txn = db.begin_rw_txn()?;
+
+    let current_epoch = db.get_current_epoch_for_validator(validator_index, &mut txn)?;
+    let max_target = db.get_attester_max_target(validator_index, &mut txn)?;
+    let history_start = db.history_start();
+
+    let targets = if let Some(current_epoch) = current_epoch {
+        export_targets(&mut txn, validator_index, current_epoch, config)?
+    } else {
+        vec![]
+    };
+
+    let mut attestations = vec![];
+    if let Some(max_target) = max_target {
+        let start_epoch = std::cmp::max(
+            max_target.saturating_sub(config.history_length as u64 - 1),
+            history_start,
+        );
+        for target_epoch in (start_epoch.as_u64()..=max_target.as_u64()).map(Epoch::new) {
+            let record = match db.get_attester_record(
+                &mut txn,
+                validator_index,
+                target_epoch,
+                Some(max_target),
+            )? {
+                Some(record) => record,
+                None => continue,
+            };
+            let attestation =
+                match db.get_indexed_attestation(&mut txn, record.indexed_attestation_id) {
+                    Ok(attestation) => Some(attestation),
+                    Err(Error::MissingIndexedAttestation { .. }) => None,
+                    Err(e) => return Err(e),
+                };
+            attestations.push(AttestationEntry {
+                target_epoch,
+                indexed_attestation_id: record.indexed_attestation_id.as_u64(),
+                attestation,
+            });
+        }
+    }
+
+    Ok(ValidatorExport {
+        validator_index,
+        current_epoch,
+        max_target,
+        history_start,
+        history_length: config.history_length,
+        chunk_size: config.chunk_size,
+        validator_chunk_size: config.validator_chunk_size,
+        targets,
+        attestations,
+    })
+}
+
+fn export_targets(
+    txn: &mut RwTransaction<'_>,
+    validator_index: u64,
+    current_epoch: Epoch,
+    config: &Config,
+) -> Result<Vec<TargetEntry>, Error> {
+    let validator_chunk_index = config.validator_chunk_index(validator_index);
+    let mut min_chunks = BTreeMap::new();
+    let mut max_chunks = BTreeMap::new();
+
+    let start_epoch = current_epoch.saturating_sub(config.history_length as u64 - 1);
+    (start_epoch.as_u64()..=current_epoch.as_u64())
+        .map(Epoch::new)
+        .map(|source_epoch| {
+            let chunk_index = config.chunk_index(source_epoch);
+            Ok(TargetEntry {
+                source_epoch,
+                min_target: target_for_epoch::<MinTargetChunk>(
+                    txn,
+                    &mut min_chunks,
+                    validator_chunk_index,
+                    chunk_index,
+                    validator_index,
+                    source_epoch,
+                    config,
+                )?,
+                max_target: target_for_epoch::<MaxTargetChunk>(
+                    txn,
+                    &mut max_chunks,
+                    validator_chunk_index,
+                    chunk_index,
+                    validator_index,
+                    source_epoch,
+                    config,
+                )?,
+            })
+        })
+        .collect()
+}
+
+/// Return the target stored for `source_epoch`, or `None` if it is the array's neutral element.
+fn target_for_epoch<T: TargetArrayChunk>(
+    txn: &mut RwTransaction<'_>,
+    chunks: &mut BTreeMap<usize, T>,
+    validator_chunk_index: usize,
+    chunk_index: usize,
+    validator_index: u64,
+    source_epoch: Epoch,
+    config: &Config,
+) -> Result<Option<Epoch>, Error> {
+    let chunk = get_chunk_for_update(txn, chunks, validator_chunk_index, chunk_index, config)?;
+    let distance = chunk
+        .chunk()
+        .get_raw_distance(validator_index, source_epoch, config)?;
+    Ok((distance != T::neutral_element()).then(|| source_epoch + u64::from(distance)))
+}
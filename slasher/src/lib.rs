@@ -8,6 +8,7 @@ mod block_queue;
 pub mod config;
 mod database;
 mod error;
+pub mod export;
 pub mod metrics;
 mod migrate;
 mod slasher;
@@ -18,16 +19,15 @@ pub use crate::slasher::Slasher;
 pub use attestation_queue::{AttestationBatch, AttestationQueue, SimpleBatch};
 pub use attester_record::{AttesterRecord, CompactAttesterRecord, IndexedAttesterRecord};
 pub use block_queue::BlockQueue;
-pub use config::Config;
-pub use database::{IndexedAttestationId, SlasherDB};
+pub use config::{Config, DatabaseBackend};
+pub use database::{
+    interface::{Database, Environment, RwTransaction, Transaction},
+    IndexedAttestationId, SlasherDB,
+};
 pub use error::Error;
 
 use types::{AttesterSlashing, EthSpec, IndexedAttestation, ProposerSlashing};
 
-/// LMDB-to-MDBX compatibility shims.
-pub type Environment = mdbx::Environment<mdbx::NoWriteMap>;
-pub type RwTransaction<'env> = mdbx::Transaction<'env, mdbx::RW, mdbx::NoWriteMap>;
-
 #[derive(Debug, PartialEq)]
 pub enum AttesterSlashingStatus<E: EthSpec> {
     NotSlashable,
@@ -6,10 +6,70 @@ lazy_static! {
         "slasher_database_size",
         "Size of the database backing the slasher, in bytes"
     );
+    pub static ref SLASHER_DATABASE_USED_SIZE: Result<IntGauge> = try_create_int_gauge(
+        "slasher_database_used_size",
+        "Bytes used by the slasher database, excluding free space reused by later writes"
+    );
+    pub static ref SLASHER_TABLE_SIZE: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "slasher_table_size",
+        "Bytes used by each table of the slasher database",
+        &["table"]
+    );
+    pub static ref SLASHER_PROCESSED_EPOCH: Result<IntGauge> = try_create_int_gauge(
+        "slasher_processed_epoch",
+        "Current epoch of the last batch successfully processed by the slasher"
+    );
+    pub static ref SLASHER_EPOCH_LAG: Result<IntGauge> = try_create_int_gauge(
+        "slasher_epoch_lag",
+        "Number of epochs by which the last slasher batch trails the head"
+    );
+    pub static ref SLASHER_DETECTION_LATENCY: Result<Histogram> = try_create_histogram_with_buckets(
+        "slasher_detection_latency_seconds",
+        "Time from the slasher receiving an attestation to finding it slashable",
+        vec![1.0, 2.0, 4.0, 8.0, 12.0, 16.0, 24.0, 36.0, 48.0, 72.0, 96.0, 192.0, 384.0]
+    );
+    pub static ref SLASHER_HISTORY_START_EPOCH: Result<IntGauge> = try_create_int_gauge(
+        "slasher_history_start_epoch",
+        "Earliest epoch of history retained after trimming the slasher database"
+    );
+    pub static ref SLASHER_NUM_HISTORY_TRIMS: Result<IntCounter> = try_create_int_counter(
+        "slasher_num_history_trims",
+        "Number of times the slasher history has been trimmed to limit the database size"
+    );
     pub static ref SLASHER_RUN_TIME: Result<Histogram> = try_create_histogram(
         "slasher_process_batch_time",
         "Time taken to process a batch of blocks and attestations"
     );
+    pub static ref SLASHER_PROCESS_BLOCKS_TIME: Result<Histogram> = try_create_histogram(
+        "slasher_process_blocks_time",
+        "Time taken to check the blocks of a batch for proposer slashings"
+    );
+    pub static ref SLASHER_STORE_ATTESTATIONS_TIME: Result<Histogram> = try_create_histogram(
+        "slasher_store_attestations_time",
+        "Time taken to store the attestations of a batch in the database"
+    );
+    pub static ref SLASHER_PROCESS_VALIDATOR_CHUNKS_TIME: Result<Histogram> = try_create_histogram(
+        "slasher_process_validator_chunks_time",
+        "Time taken to check the attestations of a batch for attester slashings, across all \
+             validator chunks"
+    );
+    pub static ref SLASHER_COMMIT_TIME: Result<Histogram> = try_create_histogram(
+        "slasher_commit_time",
+        "Time taken to commit a batch to the database"
+    );
+    pub static ref SLASHER_NUM_LOCAL_VALIDATOR_SLASHINGS: Result<IntCounter> =
+        try_create_int_counter(
+            "slasher_num_local_validator_slashings",
+            "Number of validators monitored by this node found to have signed slashable messages"
+        );
+    pub static ref SLASHER_BACKFILL_EPOCH: Result<IntGauge> = try_create_int_gauge(
+        "slasher_backfill_epoch",
+        "Latest epoch of stored blocks checked by the slasher's backfill"
+    );
+    pub static ref SLASHER_NUM_VALIDATOR_CHUNKS_PER_BATCH: Result<IntGauge> = try_create_int_gauge(
+        "slasher_num_validator_chunks_per_batch",
+        "Number of validator chunks with attestations in the last batch"
+    );
     pub static ref SLASHER_NUM_ATTESTATIONS_DROPPED: Result<IntGauge> = try_create_int_gauge(
         "slasher_num_attestations_dropped",
         "Number of attestations dropped per batch"
@@ -1,15 +1,20 @@
 use crate::batch_stats::{AttestationStats, BatchStats, BlockStats};
+use crate::database::worker_impl::run_in_workers;
 use crate::metrics::{
-    self, SLASHER_NUM_ATTESTATIONS_DEFERRED, SLASHER_NUM_ATTESTATIONS_DROPPED,
-    SLASHER_NUM_ATTESTATIONS_STORED_PER_BATCH, SLASHER_NUM_ATTESTATIONS_VALID,
-    SLASHER_NUM_BLOCKS_PROCESSED,
+    self, SLASHER_COMMIT_TIME, SLASHER_DATABASE_USED_SIZE, SLASHER_DETECTION_LATENCY,
+    SLASHER_HISTORY_START_EPOCH, SLASHER_NUM_ATTESTATIONS_DEFERRED,
+    SLASHER_NUM_ATTESTATIONS_DROPPED, SLASHER_NUM_ATTESTATIONS_STORED_PER_BATCH,
+    SLASHER_NUM_ATTESTATIONS_VALID, SLASHER_NUM_BLOCKS_PROCESSED, SLASHER_NUM_HISTORY_TRIMS,
+    SLASHER_NUM_VALIDATOR_CHUNKS_PER_BATCH, SLASHER_PROCESSED_EPOCH, SLASHER_PROCESS_BLOCKS_TIME,
+    SLASHER_PROCESS_VALIDATOR_CHUNKS_TIME, SLASHER_STORE_ATTESTATIONS_TIME, SLASHER_TABLE_SIZE,
 };
 use crate::{
     array, AttestationBatch, AttestationQueue, AttesterRecord, BlockQueue, Config, Error,
     IndexedAttestationId, ProposerSlashingStatus, RwTransaction, SimpleBatch, SlasherDB,
 };
 use parking_lot::Mutex;
-use slog::{debug, error, info, Logger};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use slog::{debug, error, info, warn, Logger};
 use std::collections::HashSet;
 use std::sync::Arc;
 use types::{
@@ -23,6 +28,8 @@ pub struct Slasher<E: EthSpec> {
     block_queue: BlockQueue,
     attester_slashings: Mutex<HashSet<AttesterSlashing<E>>>,
     proposer_slashings: Mutex<HashSet<ProposerSlashing>>,
+    /// Threads which process the attestations for distinct validator chunks in parallel.
+    workers: ThreadPool,
     config: Arc<Config>,
     log: Logger,
 }
@@ -32,16 +39,26 @@ impl<E: EthSpec> Slasher<E> {
         config.validate()?;
         let config = Arc::new(config);
         let db = SlasherDB::open(config.clone(), log.clone())?;
+        metrics::set_gauge(
+            &SLASHER_HISTORY_START_EPOCH,
+            db.history_start().as_u64() as i64,
+        );
         let attester_slashings = Mutex::new(HashSet::new());
         let proposer_slashings = Mutex::new(HashSet::new());
         let attestation_queue = AttestationQueue::default();
         let block_queue = BlockQueue::default();
+        let workers = ThreadPoolBuilder::new()
+            .num_threads(config.num_workers)
+            .thread_name(|i| format!("slasher_worker_{}", i))
+            .build()
+            .map_err(|e| Error::WorkerPoolError(e.to_string()))?;
         Ok(Self {
             db,
             attestation_queue,
             block_queue,
             attester_slashings,
             proposer_slashings,
+            workers,
             config,
             log,
         })
@@ -65,6 +82,26 @@ impl<E: EthSpec> Slasher<E> {
         &self.log
     }
 
+    /// Return the earliest epoch of history retained after trimming, see `SlasherDB::history_start`.
+    pub fn history_start(&self) -> Epoch {
+        self.db.history_start()
+    }
+
+    /// Return the first epoch checked by the backfill from stored blocks, if it has completed.
+    pub fn backfill_start(&self) -> Result<Option<Epoch>, Error> {
+        let mut txn = self.db.begin_rw_txn()?;
+        let backfill_start = self.db.load_backfill_start(&mut txn)?;
+        txn.commit()?;
+        Ok(backfill_start)
+    }
+
+    /// Record that the backfill from stored blocks has completed, starting from `backfill_start`.
+    pub fn set_backfill_complete(&self, backfill_start: Epoch) -> Result<(), Error> {
+        let mut txn = self.db.begin_rw_txn()?;
+        self.db.store_backfill_start(backfill_start, &mut txn)?;
+        txn.commit()
+    }
+
     /// Accept an attestation from the network and queue it for processing.
     pub fn accept_attestation(&self, attestation: IndexedAttestation<E>) {
         self.attestation_queue.queue(attestation);
@@ -76,21 +113,117 @@ impl<E: EthSpec> Slasher<E> {
     }
 
     /// Apply queued blocks and attestations to the on-disk database, and detect slashings!
+    ///
+    /// The oldest history is trimmed beforehand if the database is approaching its maximum size.
+    /// If the database fills up during processing then the batch fails and history is trimmed so
+    /// that the next batch may succeed.
     pub fn process_queued(&self, current_epoch: Epoch) -> Result<BatchStats, Error> {
+        if let Err(e) = self.trim_history_if_near_max_size(current_epoch) {
+            error!(
+                self.log,
+                "Unable to trim slasher history";
+                "epoch" => current_epoch,
+                "error" => ?e,
+            );
+        }
+
+        match self.try_process_queued(current_epoch) {
+            Err(e) if e.is_database_full() => {
+                warn!(
+                    self.log,
+                    "Slasher database is full";
+                    "max_db_size_mbs" => self.config.max_db_size_mbs,
+                );
+                if let Err(e) = self.trim_history(current_epoch) {
+                    error!(
+                        self.log,
+                        "Unable to trim slasher history";
+                        "epoch" => current_epoch,
+                        "error" => ?e,
+                    );
+                }
+                Err(e)
+            }
+            result => result,
+        }
+    }
+
+    fn try_process_queued(&self, current_epoch: Epoch) -> Result<BatchStats, Error> {
         let mut txn = self.db.begin_rw_txn()?;
         let block_stats = self.process_blocks(&mut txn)?;
         let attestation_stats = self.process_attestations(current_epoch, &mut txn)?;
+
+        let commit_timer = metrics::start_timer(&SLASHER_COMMIT_TIME);
         txn.commit()?;
+        metrics::stop_timer(commit_timer);
+
+        metrics::set_gauge(&SLASHER_PROCESSED_EPOCH, current_epoch.as_u64() as i64);
+        self.update_table_size_metrics();
+
         Ok(BatchStats {
             block_stats,
             attestation_stats,
         })
     }
 
+    /// Update the `SLASHER_TABLE_SIZE` metric, see `SlasherDB::table_sizes`.
+    fn update_table_size_metrics(&self) {
+        match self.db.table_sizes() {
+            Ok(table_sizes) => {
+                for (db, size) in table_sizes {
+                    metrics::set_gauge_vec(&SLASHER_TABLE_SIZE, &[db.name()], size as i64);
+                }
+            }
+            Err(e) => debug!(
+                self.log,
+                "Unable to measure slasher table sizes";
+                "error" => ?e,
+            ),
+        }
+    }
+
+    /// Trim the oldest history if the database has grown beyond `Config::trim_threshold_bytes`.
+    fn trim_history_if_near_max_size(&self, current_epoch: Epoch) -> Result<(), Error> {
+        let used_size = self.db.used_size()?;
+        metrics::set_gauge(&SLASHER_DATABASE_USED_SIZE, used_size as i64);
+
+        if used_size >= self.config.trim_threshold_bytes() {
+            self.trim_history(current_epoch)?;
+        }
+        Ok(())
+    }
+
+    /// Trim the oldest history from the database, see `SlasherDB::trim_history`.
+    fn trim_history(&self, current_epoch: Epoch) -> Result<(), Error> {
+        match self.db.trim_history(current_epoch)? {
+            Some(history_start) => {
+                warn!(
+                    self.log,
+                    "Trimmed slasher history to limit database size";
+                    "history_start" => history_start,
+                    "max_db_size_mbs" => self.config.max_db_size_mbs,
+                    "advice" => "increase --slasher-max-db-size to retain more history",
+                );
+                metrics::inc_counter(&SLASHER_NUM_HISTORY_TRIMS);
+                metrics::set_gauge(&SLASHER_HISTORY_START_EPOCH, history_start.as_u64() as i64);
+            }
+            None => {
+                error!(
+                    self.log,
+                    "Slasher history can't be trimmed any further";
+                    "max_db_size_mbs" => self.config.max_db_size_mbs,
+                    "advice" => "increase --slasher-max-db-size",
+                );
+            }
+        }
+        Ok(())
+    }
+
     /// Apply queued blocks to the on-disk database.
     ///
     /// Return the number of blocks
     pub fn process_blocks(&self, txn: &mut RwTransaction<'_>) -> Result<BlockStats, Error> {
+        let _timer = metrics::start_timer(&SLASHER_PROCESS_BLOCKS_TIME);
         let blocks = self.block_queue.dequeue();
         let num_processed = blocks.len();
         let mut slashings = vec![];
@@ -155,6 +288,7 @@ impl<E: EthSpec> Slasher<E> {
         }
 
         // Insert relevant attestations into database.
+        let store_timer = metrics::start_timer(&SLASHER_STORE_ATTESTATIONS_TIME);
         let mut num_stored = 0;
         for weak_record in &batch.attestations {
             if let Some(indexed_record) = weak_record.upgrade() {
@@ -174,6 +308,7 @@ impl<E: EthSpec> Slasher<E> {
                 num_stored += 1;
             }
         }
+        metrics::stop_timer(store_timer);
 
         debug!(
             self.log,
@@ -188,9 +323,7 @@ impl<E: EthSpec> Slasher<E> {
 
         // Group attestations into chunked batches and process them.
         let grouped_attestations = batch.group_by_validator_chunk_index(&self.config);
-        for (subqueue_id, subqueue) in grouped_attestations.subqueues.into_iter().enumerate() {
-            self.process_batch(txn, subqueue_id, subqueue, current_epoch)?;
-        }
+        self.process_validator_chunks(txn, grouped_attestations.subqueues, current_epoch)?;
 
         metrics::set_gauge(
             &metrics::SLASHER_ATTESTATION_ROOT_CACHE_SIZE,
@@ -200,6 +333,45 @@ impl<E: EthSpec> Slasher<E> {
         Ok(AttestationStats { num_processed })
     }
 
+    /// Process the attestations for each validator chunk, with `subqueues[i]` holding the
+    /// attestations for the `i`th chunk.
+    ///
+    /// Validator chunks are stored under distinct keys, so are processed in parallel when more
+    /// than one worker is configured.
+    fn process_validator_chunks(
+        &self,
+        txn: &mut RwTransaction<'_>,
+        subqueues: Vec<SimpleBatch<E>>,
+        current_epoch: Epoch,
+    ) -> Result<(), Error> {
+        let _timer = metrics::start_timer(&SLASHER_PROCESS_VALIDATOR_CHUNKS_TIME);
+        let subqueues = subqueues
+            .into_iter()
+            .enumerate()
+            .filter(|(_, subqueue)| !subqueue.is_empty())
+            .collect::<Vec<_>>();
+        metrics::set_gauge(
+            &SLASHER_NUM_VALIDATOR_CHUNKS_PER_BATCH,
+            subqueues.len() as i64,
+        );
+
+        if self.config.num_workers == 1 || subqueues.len() <= 1 {
+            for (subqueue_id, subqueue) in subqueues {
+                self.process_batch(txn, subqueue_id, subqueue, current_epoch)?;
+            }
+        } else {
+            run_in_workers(
+                &self.workers,
+                txn,
+                subqueues,
+                |(subqueue_id, subqueue), txn| {
+                    self.process_batch(txn, subqueue_id, subqueue, current_epoch)
+                },
+            )?;
+        }
+        Ok(())
+    }
+
     /// Process a batch of attestations for a range of validator indices.
     fn process_batch(
         &self,
@@ -226,6 +398,12 @@ impl<E: EthSpec> Slasher<E> {
                             slashings.len()
                         );
                     }
+                    for _ in &slashings {
+                        metrics::observe_duration(
+                            &SLASHER_DETECTION_LATENCY,
+                            attestation.received.elapsed(),
+                        );
+                    }
                     self.attester_slashings.lock().extend(slashings);
                 }
                 Err(e) => {
@@ -322,14 +500,17 @@ impl<E: EthSpec> Slasher<E> {
         let mut keep = Vec::with_capacity(batch.len());
         let mut defer = vec![];
         let mut drop_count = 0;
+        let history_start = self.db.history_start();
 
         for indexed_record in batch {
             let attestation = &indexed_record.indexed;
             let target_epoch = attestation.data.target.epoch;
             let source_epoch = attestation.data.source.epoch;
 
+            // Attestations targeting epochs that have been trimmed can't be checked.
             if source_epoch > target_epoch
                 || source_epoch + self.config.history_length as u64 <= current_epoch
+                || target_epoch < history_start
             {
                 drop_count += 1;
                 continue;
@@ -0,0 +1,130 @@
+use crate::export;
+use clap::{App, Arg};
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(crate::CMD)
+        .visible_aliases(&["sl"])
+        .setting(clap::AppSettings::ColoredHelp)
+        .setting(clap::AppSettings::SubcommandsNegateReqs)
+        .about(
+            "Runs a slasher which watches one or more remote beacon nodes, without running a \
+             beacon node locally. Blocks and attestations are streamed from the beacon nodes' \
+             event APIs, and any slashings found are submitted to their operation pools.",
+        )
+        .arg(
+            Arg::with_name("beacon-nodes")
+                .long("beacon-nodes")
+                .alias("beacon-node")
+                .value_name("NETWORK_ADDRESSES")
+                .help(
+                    "Comma-separated addresses of the HTTP APIs of the beacon nodes to watch, \
+                     e.g. http://localhost:5052.",
+                )
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("slasher-dir")
+                .long("slasher-dir")
+                .value_name("PATH")
+                .help(
+                    "Set the slasher's database directory. Defaults to a `slasher` directory \
+                     within the data directory.",
+                )
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("update-period")
+                .long("update-period")
+                .value_name("SECONDS")
+                .help("Configure how often the slasher runs batch processing.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slot-offset")
+                .long("slot-offset")
+                .value_name("SECONDS")
+                .help(
+                    "Set the delay from the start of the slot at which the slasher should ingest \
+                     attestations. Only effective if the update-period is a multiple of the \
+                     slot duration.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("history-length")
+                .long("history-length")
+                .value_name("EPOCHS")
+                .help(
+                    "Configure how many epochs of history the slasher keeps. Immutable after \
+                     initialization.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("chunk-size")
+                .long("chunk-size")
+                .value_name("EPOCHS")
+                .help(
+                    "Number of epochs per validator per chunk stored on disk. Immutable after \
+                     initialization.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("validator-chunk-size")
+                .long("validator-chunk-size")
+                .value_name("NUM_VALIDATORS")
+                .help(
+                    "Number of validators per chunk stored on disk. Immutable after \
+                     initialization.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("workers")
+                .long("workers")
+                .value_name("NUM_THREADS")
+                .help(
+                    "Number of threads used to process attestations. Each thread processes the \
+                     attestations of different validator chunks.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("max-db-size")
+                .long("max-db-size")
+                .value_name("GIGABYTES")
+                .help(
+                    "Maximum size of the database used by the slasher. When the database \
+                     approaches this size the oldest history is trimmed to make room for new \
+                     attestations.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("backend")
+                .long("backend")
+                .value_name("BACKEND")
+                .help(
+                    "The key-value store used for the slasher database. Redb is only available \
+                     when Lighthouse is compiled with the `slasher-redb` feature. Cannot be \
+                     changed after initialization.",
+                )
+                .possible_values(&["mdbx", "redb"])
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("webhook-urls")
+                .long("webhook-urls")
+                .value_name("URLS")
+                .help(
+                    "Comma-separated list of URLs to which slashings are POSTed as JSON once they \
+                     have been accepted by a beacon node.",
+                )
+                .takes_value(true),
+        )
+        .subcommand(export::cli_app())
+}
@@ -0,0 +1,166 @@
+use clap::ArgMatches;
+use clap_utils::parse_optional;
+use directory::{parse_path_or_default, DEFAULT_SLASHER_DIR};
+use sensitive_url::SensitiveUrl;
+use std::path::PathBuf;
+
+/// Configuration for the standalone slasher.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Beacon nodes from which blocks and attestations are streamed, and to which slashings are
+    /// submitted.
+    pub beacon_nodes: Vec<SensitiveUrl>,
+    pub slasher: slasher::Config,
+}
+
+impl Config {
+    pub fn from_cli(cli_args: &ArgMatches) -> Result<Self, String> {
+        let beacon_nodes = cli_args
+            .value_of("beacon-nodes")
+            .ok_or("--beacon-nodes is required")?
+            .split(',')
+            .map(SensitiveUrl::parse)
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("beacon-nodes contains an invalid URL {:?}", e))?;
+
+        let mut slasher = slasher::Config::new(parse_slasher_dir(cli_args)?);
+
+        if let Some(update_period) = parse_optional(cli_args, "update-period")? {
+            slasher.update_period = update_period;
+        }
+
+        if let Some(slot_offset) = parse_optional(cli_args, "slot-offset")? {
+            slasher.slot_offset = slot_offset;
+        }
+
+        if let Some(history_length) = parse_optional(cli_args, "history-length")? {
+            slasher.history_length = history_length;
+        }
+
+        if let Some(chunk_size) = parse_optional(cli_args, "chunk-size")? {
+            slasher.chunk_size = chunk_size;
+        }
+
+        if let Some(validator_chunk_size) = parse_optional(cli_args, "validator-chunk-size")? {
+            slasher.validator_chunk_size = validator_chunk_size;
+        }
+
+        if let Some(num_workers) = parse_optional(cli_args, "workers")? {
+            slasher.num_workers = num_workers;
+        }
+
+        if let Some(max_db_size_gbs) = parse_optional::<usize>(cli_args, "max-db-size")? {
+            slasher.max_db_size_mbs = max_db_size_gbs * 1024;
+        }
+
+        if let Some(backend) = parse_optional(cli_args, "backend")? {
+            slasher.backend = backend;
+        }
+
+        if let Some(urls) = cli_args.value_of("webhook-urls") {
+            slasher.webhook_urls = urls
+                .split(',')
+                .map(SensitiveUrl::parse)
+                .collect::<Result<_, _>>()
+                .map_err(|e| format!("webhook-urls contains an invalid URL {:?}", e))?;
+        }
+
+        Ok(Self {
+            beacon_nodes,
+            slasher,
+        })
+    }
+}
+
+/// Parse the `--slasher-dir`, defaulting to a directory within the data directory.
+pub fn parse_slasher_dir(cli_args: &ArgMatches) -> Result<PathBuf, String> {
+    if let Some(slasher_dir) = parse_optional(cli_args, "slasher-dir")? {
+        Ok(slasher_dir)
+    } else {
+        Ok(parse_path_or_default(cli_args, "datadir")?.join(DEFAULT_SLASHER_DIR))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli_app;
+    use slasher::DatabaseBackend;
+
+    fn config_from_args(args: &[&str]) -> Result<Config, String> {
+        let matches = cli_app()
+            .get_matches_from_safe(std::iter::once(crate::CMD).chain(args.iter().copied()))
+            .map_err(|e| e.to_string())?;
+        Config::from_cli(&matches)
+    }
+
+    #[test]
+    fn beacon_nodes_required() {
+        assert!(config_from_args(&[]).is_err());
+    }
+
+    #[test]
+    fn parse_flags() {
+        let config = config_from_args(&[
+            "--beacon-node",
+            "http://localhost:5052,http://10.0.0.2:5052",
+            "--slasher-dir",
+            "/tmp/slasher",
+            "--history-length",
+            "256",
+            "--update-period",
+            "6",
+            "--chunk-size",
+            "32",
+            "--validator-chunk-size",
+            "1024",
+            "--workers",
+            "16",
+            "--max-db-size",
+            "16",
+            "--backend",
+            "mdbx",
+            "--webhook-urls",
+            "https://example.com/hook",
+        ])
+        .unwrap();
+
+        assert_eq!(config.beacon_nodes.len(), 2);
+        assert_eq!(
+            config.beacon_nodes[1].full.as_str(),
+            "http://10.0.0.2:5052/"
+        );
+        assert_eq!(config.slasher.database_path, PathBuf::from("/tmp/slasher"));
+        assert_eq!(config.slasher.history_length, 256);
+        assert_eq!(config.slasher.update_period, 6);
+        assert_eq!(config.slasher.chunk_size, 32);
+        assert_eq!(config.slasher.validator_chunk_size, 1024);
+        assert_eq!(config.slasher.num_workers, 16);
+        assert_eq!(config.slasher.max_db_size_mbs, 16 * 1024);
+        assert_eq!(config.slasher.backend, DatabaseBackend::Mdbx);
+        assert_eq!(config.slasher.webhook_urls.len(), 1);
+    }
+
+    #[test]
+    fn export_without_beacon_nodes() {
+        let matches = cli_app()
+            .get_matches_from_safe(vec![
+                crate::CMD,
+                "--slasher-dir",
+                "/tmp/slasher",
+                crate::export::CMD,
+                "--validator",
+                "5",
+            ])
+            .unwrap();
+        let export_matches = matches
+            .subcommand_matches(crate::export::CMD)
+            .expect("should match export subcommand");
+
+        assert_eq!(
+            parse_slasher_dir(export_matches).unwrap(),
+            PathBuf::from("/tmp/slasher")
+        );
+        assert_eq!(export_matches.value_of("validator"), Some("5"));
+    }
+}
@@ -0,0 +1,92 @@
+//! Dumps the data stored by the slasher for a single validator, see `slasher::export`.
+use crate::config::parse_slasher_dir;
+use clap::{App, Arg, ArgMatches};
+use clap_utils::{parse_optional, parse_required};
+use slasher::{export::export_validator, Config, Error, SlasherDB};
+use slog::{info, Logger};
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::Arc;
+use types::EthSpec;
+
+pub const CMD: &str = "export";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .setting(clap::AppSettings::ColoredHelp)
+        .about(
+            "Export the min-max target arrays and attestations stored by the slasher for a \
+             validator as JSON, to explain why a pair of attestations was or wasn't found to be \
+             slashable. Batch processing by a slasher using the same database is paused while \
+             the export runs.",
+        )
+        .arg(
+            Arg::with_name("validator")
+                .long("validator")
+                .value_name("INDEX")
+                .help("The index of the validator to export.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("output")
+                .long("output")
+                .value_name("FILE")
+                .help("Write the export to this file rather than to stdout.")
+                .takes_value(true),
+        )
+}
+
+pub fn run<E: EthSpec>(cli_args: &ArgMatches, log: Logger) -> Result<(), String> {
+    let validator_index = parse_required(cli_args, "validator")?;
+    let output_path: Option<PathBuf> = parse_optional(cli_args, "output")?;
+
+    let slasher_dir = parse_slasher_dir(cli_args)?;
+    if !slasher_dir.exists() {
+        return Err(format!(
+            "No slasher database found at {}",
+            slasher_dir.display()
+        ));
+    }
+
+    let mut config = Config::new(slasher_dir);
+    if let Some(backend) = parse_optional(cli_args, "backend")? {
+        config.backend = backend;
+    }
+    let db =
+        open_db::<E>(config, &log).map_err(|e| format!("Unable to open slasher DB: {:?}", e))?;
+
+    let export = export_validator(&db, validator_index)
+        .map_err(|e| format!("Unable to export validator {}: {:?}", validator_index, e))?;
+
+    if let Some(path) = output_path {
+        let file = File::create(&path)
+            .map_err(|e| format!("Unable to create {}: {:?}", path.display(), e))?;
+        serde_json::to_writer_pretty(file, &export)
+            .map_err(|e| format!("Unable to write export: {:?}", e))?;
+        info!(
+            log,
+            "Exported slasher data";
+            "validator_index" => validator_index,
+            "path" => %path.display(),
+        );
+    } else {
+        let json = serde_json::to_string_pretty(&export)
+            .map_err(|e| format!("Unable to serialize export: {:?}", e))?;
+        println!("{}", json);
+    }
+    Ok(())
+}
+
+/// Open the database, adopting the chunk sizes and history length it was initialized with.
+fn open_db<E: EthSpec>(mut config: Config, log: &Logger) -> Result<SlasherDB<E>, Error> {
+    match SlasherDB::open(Arc::new(config.clone()), log.clone()) {
+        Err(Error::ConfigIncompatible { on_disk_config, .. }) => {
+            config.chunk_size = on_disk_config.chunk_size;
+            config.validator_chunk_size = on_disk_config.validator_chunk_size;
+            config.history_length = on_disk_config.history_length;
+            SlasherDB::open(Arc::new(config), log.clone())
+        }
+        result => result,
+    }
+}
@@ -0,0 +1,218 @@
+//! Ingestion of blocks and attestations from the event stream of a remote beacon node.
+use eth2::types::{BlockId, EventKind, EventTopic, StateId};
+use eth2::{BeaconNodeHttpClient, Error as ApiError};
+use futures::{pin_mut, StreamExt};
+use slasher::Slasher;
+use slog::{debug, info, warn, Logger};
+use state_processing::common::get_indexed_attestation;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+use types::{Attestation, Epoch, EthSpec, Hash256, IndexedAttestation, Slot};
+
+/// Delay before reconnecting to a beacon node after its event stream fails.
+const RECONNECT_DELAY: Duration = Duration::from_secs(12);
+/// Number of epochs for which committees are cached.
+const MAX_CACHED_EPOCHS: usize = 4;
+
+/// The committees for an epoch, keyed by slot and committee index.
+type EpochCommittees = HashMap<(Slot, u64), Vec<usize>>;
+
+/// Caches the committees fetched from a beacon node, so that attestations may be converted into
+/// indexed attestations.
+///
+/// Committees are fetched from the beacon node's head state. They are keyed by epoch alone, so an
+/// attestation from a fork with a different shuffling may be indexed incorrectly. The slashings
+/// resulting from such an attestation would be rejected by the beacon node.
+#[derive(Default)]
+struct CommitteeCache {
+    epochs: BTreeMap<Epoch, Arc<EpochCommittees>>,
+}
+
+impl CommitteeCache {
+    async fn get_indexed_attestation<E: EthSpec>(
+        &mut self,
+        client: &BeaconNodeHttpClient,
+        attestation: &Attestation<E>,
+    ) -> Result<IndexedAttestation<E>, String> {
+        let committees = self
+            .epoch_committees::<E>(client, attestation.data.target.epoch)
+            .await?;
+        let committee = committees
+            .get(&(attestation.data.slot, attestation.data.index))
+            .ok_or_else(|| {
+                format!(
+                    "no committee {} at slot {}",
+                    attestation.data.index, attestation.data.slot
+                )
+            })?;
+        get_indexed_attestation(committee, attestation).map_err(|e| format!("{:?}", e))
+    }
+
+    async fn epoch_committees<E: EthSpec>(
+        &mut self,
+        client: &BeaconNodeHttpClient,
+        epoch: Epoch,
+    ) -> Result<Arc<EpochCommittees>, String> {
+        if let Some(committees) = self.epochs.get(&epoch) {
+            return Ok(committees.clone());
+        }
+
+        // The head state can only provide the committees for epochs close to the head, so fall
+        // back to the state at the start of the epoch.
+        let response = match client
+            .get_beacon_states_committees(StateId::Head, None, None, Some(epoch))
+            .await
+        {
+            Ok(response) => Ok(response),
+            Err(_) => {
+                let state_id = StateId::Slot(epoch.start_slot(E::slots_per_epoch()));
+                client
+                    .get_beacon_states_committees(state_id, None, None, Some(epoch))
+                    .await
+            }
+        }
+        .map_err(|e| format!("unable to fetch committees for epoch {}: {:?}", epoch, e))?;
+        let committees = response
+            .ok_or_else(|| format!("no state to compute committees for epoch {}", epoch))?
+            .data
+            .into_iter()
+            .map(|committee| {
+                let validators = committee
+                    .validators
+                    .into_iter()
+                    .map(|index| index as usize)
+                    .collect();
+                ((committee.slot, committee.index), validators)
+            })
+            .collect::<EpochCommittees>();
+        let committees = Arc::new(committees);
+
+        self.epochs.insert(epoch, committees.clone());
+        while self.epochs.len() > MAX_CACHED_EPOCHS {
+            let oldest = *self.epochs.keys().next().expect("cache is not empty");
+            self.epochs.remove(&oldest);
+        }
+
+        Ok(committees)
+    }
+}
+
+/// Stream blocks and attestations from `client` into the `slasher` indefinitely, reconnecting
+/// whenever the event stream fails.
+pub async fn ingest_from_beacon_node<E: EthSpec>(
+    client: BeaconNodeHttpClient,
+    slasher: Arc<Slasher<E>>,
+    log: Logger,
+) {
+    let mut committees = CommitteeCache::default();
+
+    loop {
+        info!(log, "Subscribing to beacon node events"; "beacon_node" => %client);
+
+        match stream_events(&client, &slasher, &mut committees, &log).await {
+            Ok(()) => warn!(
+                log,
+                "Beacon node event stream ended";
+                "beacon_node" => %client,
+            ),
+            Err(e) => warn!(
+                log,
+                "Beacon node event stream failed";
+                "beacon_node" => %client,
+                "error" => ?e,
+            ),
+        }
+
+        sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn stream_events<E: EthSpec>(
+    client: &BeaconNodeHttpClient,
+    slasher: &Slasher<E>,
+    committees: &mut CommitteeCache,
+    log: &Logger,
+) -> Result<(), ApiError> {
+    let events = client
+        .get_events::<E>(&[EventTopic::Attestation, EventTopic::Block])
+        .await?;
+    pin_mut!(events);
+
+    while let Some(event) = events.next().await {
+        match event {
+            Ok(EventKind::Attestation(attestation)) => {
+                accept_attestation(client, slasher, committees, &attestation, log).await
+            }
+            Ok(EventKind::Block(block)) => {
+                accept_block(client, slasher, committees, block.block, log).await
+            }
+            Ok(_) => (),
+            // Skip malformed events rather than reconnecting.
+            Err(ApiError::InvalidServerSentEvent(e)) => {
+                debug!(log, "Unable to parse event"; "beacon_node" => %client, "error" => e)
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(())
+}
+
+async fn accept_attestation<E: EthSpec>(
+    client: &BeaconNodeHttpClient,
+    slasher: &Slasher<E>,
+    committees: &mut CommitteeCache,
+    attestation: &Attestation<E>,
+    log: &Logger,
+) {
+    match committees
+        .get_indexed_attestation(client, attestation)
+        .await
+    {
+        Ok(indexed) => slasher.accept_attestation(indexed),
+        Err(e) => debug!(
+            log,
+            "Unable to index attestation";
+            "beacon_node" => %client,
+            "slot" => attestation.data.slot,
+            "error" => e,
+        ),
+    }
+}
+
+/// Fetch the block with `block_root`, and pass its header and attestations to the `slasher`.
+async fn accept_block<E: EthSpec>(
+    client: &BeaconNodeHttpClient,
+    slasher: &Slasher<E>,
+    committees: &mut CommitteeCache,
+    block_root: Hash256,
+    log: &Logger,
+) {
+    let block = match client
+        .get_beacon_blocks::<E>(BlockId::Root(block_root))
+        .await
+    {
+        Ok(Some(response)) => response.data,
+        Ok(None) => {
+            debug!(log, "Block not found"; "beacon_node" => %client, "block_root" => ?block_root);
+            return;
+        }
+        Err(e) => {
+            warn!(
+                log,
+                "Unable to fetch block";
+                "beacon_node" => %client,
+                "block_root" => ?block_root,
+                "error" => ?e,
+            );
+            return;
+        }
+    };
+
+    slasher.accept_block_header(block.signed_block_header());
+    for attestation in block.message().body().attestations() {
+        accept_attestation(client, slasher, committees, attestation, log).await;
+    }
+}
@@ -0,0 +1,17 @@
+//! Runs the slasher as a standalone process, watching one or more remote beacon nodes.
+//!
+//! Blocks and attestations are streamed from the event APIs of the beacon nodes and fed into the
+//! slasher. The slashings it finds are submitted to the operation pool of every beacon node, which
+//! verifies them and publishes them on the network. This allows a single slasher to watch a fleet
+//! of beacon nodes, none of which need to run a slasher themselves.
+mod cli;
+mod config;
+pub mod export;
+mod ingest;
+mod service;
+
+pub use cli::cli_app;
+pub use config::Config;
+pub use service::run;
+
+pub const CMD: &str = "slasher";
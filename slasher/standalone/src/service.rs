@@ -0,0 +1,298 @@
+use crate::ingest::ingest_from_beacon_node;
+use crate::Config;
+use environment::RuntimeContext;
+use eth2::types::{GenesisData, SseSlasherSlashing};
+use eth2::{BeaconNodeHttpClient, Timeouts};
+use slasher::{
+    metrics::{self, SLASHER_EPOCH_LAG, SLASHER_RUN_TIME},
+    Slasher,
+};
+use slasher_service::Webhooks;
+use slog::{debug, error, info, warn, Logger};
+use slot_clock::{SlotClock, SystemTimeSlotClock};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::time::Duration;
+use task_executor::TaskExecutor;
+use tokio::time::{interval_at, Instant};
+use types::{AttesterSlashing, Epoch, EthSpec, ProposerSlashing};
+
+/// Timeout for requests to the beacon nodes.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(12);
+
+/// Start the standalone slasher's tasks on the `context`'s executor.
+pub async fn run<E: EthSpec>(context: RuntimeContext<E>, config: Config) -> Result<(), String> {
+    let log = context.log().clone();
+    let executor = context.executor.clone();
+    let spec = &context.eth2_config.spec;
+
+    let beacon_nodes = config
+        .beacon_nodes
+        .iter()
+        .map(|url| BeaconNodeHttpClient::new(url.clone(), Timeouts::set_all(HTTP_TIMEOUT)))
+        .collect::<Vec<_>>();
+    let genesis = get_genesis(&beacon_nodes, &log).await?;
+    let slot_clock = SystemTimeSlotClock::new(
+        spec.genesis_slot,
+        Duration::from_secs(genesis.genesis_time),
+        Duration::from_secs(spec.seconds_per_slot),
+    );
+
+    info!(
+        log,
+        "Starting standalone slasher";
+        "num_beacon_nodes" => beacon_nodes.len(),
+        "num_webhooks" => config.slasher.webhook_urls.len(),
+        "database_path" => ?config.slasher.database_path,
+    );
+    let webhooks = Webhooks::new(
+        config.slasher.webhook_urls.clone(),
+        executor.clone(),
+        log.clone(),
+    )?;
+    let update_period = config.slasher.update_period;
+    let slot_offset = Duration::from_secs_f64(config.slasher.slot_offset);
+    let slasher = Arc::new(
+        Slasher::open(config.slasher, log.clone())
+            .map_err(|e| format!("Unable to open slasher: {:?}", e))?,
+    );
+
+    for client in &beacon_nodes {
+        executor.spawn(
+            ingest_from_beacon_node(client.clone(), slasher.clone(), log.clone()),
+            "slasher_ingest",
+        );
+    }
+
+    // Buffer just a single message in the channel, as in the beacon node's slasher service.
+    let (notif_sender, notif_receiver) = sync_channel(1);
+    executor.spawn(
+        run_notifier::<E>(
+            slot_clock.clone(),
+            update_period,
+            slot_offset,
+            notif_sender,
+            log.clone(),
+        ),
+        "slasher_notifier",
+    );
+
+    let processor_executor = executor.clone();
+    executor.spawn_blocking(
+        move || {
+            run_processor(
+                slasher,
+                slot_clock,
+                notif_receiver,
+                beacon_nodes,
+                webhooks,
+                processor_executor,
+            )
+        },
+        "slasher_processor",
+    );
+
+    Ok(())
+}
+
+/// Fetch the genesis data from the beacon nodes, checking that they all follow the same chain.
+async fn get_genesis(
+    beacon_nodes: &[BeaconNodeHttpClient],
+    log: &Logger,
+) -> Result<GenesisData, String> {
+    let mut genesis: Option<GenesisData> = None;
+
+    for client in beacon_nodes {
+        match client.get_beacon_genesis().await {
+            Ok(response) => match &genesis {
+                Some(genesis)
+                    if genesis.genesis_validators_root != response.data.genesis_validators_root =>
+                {
+                    return Err(format!(
+                        "Beacon node {} is on a different chain to the other beacon nodes",
+                        client
+                    ));
+                }
+                Some(_) => (),
+                None => genesis = Some(response.data),
+            },
+            Err(e) => warn!(
+                log,
+                "Unable to fetch genesis from beacon node";
+                "beacon_node" => %client,
+                "error" => ?e,
+            ),
+        }
+    }
+
+    genesis.ok_or_else(|| "Unable to fetch genesis from any beacon node".to_string())
+}
+
+/// Periodically prompt the processor to run, in line with the slot clock.
+async fn run_notifier<E: EthSpec>(
+    slot_clock: SystemTimeSlotClock,
+    update_period: u64,
+    slot_offset: Duration,
+    notif_sender: SyncSender<Epoch>,
+    log: Logger,
+) {
+    let start_instant = if let Some(duration_to_next_slot) = slot_clock.duration_to_next_slot() {
+        Instant::now() + duration_to_next_slot + slot_offset
+    } else {
+        error!(log, "Error aligning slasher to slot clock");
+        Instant::now()
+    };
+    let mut interval = interval_at(start_instant, Duration::from_secs(update_period));
+
+    loop {
+        interval.tick().await;
+        if let Some(current_slot) = slot_clock.now() {
+            let current_epoch = current_slot.epoch(E::slots_per_epoch());
+            if let Err(TrySendError::Disconnected(_)) = notif_sender.try_send(current_epoch) {
+                break;
+            }
+        } else {
+            debug!(log, "Slasher has nothing to do: we are pre-genesis");
+        }
+    }
+}
+
+/// Run the blocking task that processes batches and publishes the slashings found.
+fn run_processor<E: EthSpec>(
+    slasher: Arc<Slasher<E>>,
+    slot_clock: SystemTimeSlotClock,
+    notif_receiver: Receiver<Epoch>,
+    beacon_nodes: Vec<BeaconNodeHttpClient>,
+    webhooks: Webhooks,
+    executor: TaskExecutor,
+) {
+    let log = slasher.log();
+    while let Ok(current_epoch) = notif_receiver.recv() {
+        let batch_timer = metrics::start_timer(&SLASHER_RUN_TIME);
+        if let Err(e) = slasher.process_queued(current_epoch) {
+            error!(
+                log,
+                "Error during scheduled slasher processing";
+                "epoch" => current_epoch,
+                "error" => ?e,
+            );
+        }
+        drop(batch_timer);
+
+        // The remote heads aren't tracked, so the lag is measured from the wall-clock epoch.
+        if let Some(slot) = slot_clock.now() {
+            let epoch = slot.epoch(E::slots_per_epoch());
+            metrics::set_gauge(
+                &SLASHER_EPOCH_LAG,
+                epoch.as_u64() as i64 - current_epoch.as_u64() as i64,
+            );
+        }
+
+        if let Err(e) = slasher.prune_database(current_epoch) {
+            error!(
+                log,
+                "Error during slasher database pruning";
+                "epoch" => current_epoch,
+                "error" => ?e,
+            );
+        }
+
+        let attester_slashings = slasher.get_attester_slashings();
+        let proposer_slashings = slasher.get_proposer_slashings();
+        if attester_slashings.is_empty() && proposer_slashings.is_empty() {
+            continue;
+        }
+
+        let beacon_nodes = beacon_nodes.clone();
+        let webhooks = webhooks.clone();
+        let log = log.clone();
+        executor.spawn(
+            async move {
+                for slashing in attester_slashings {
+                    if publish_attester_slashing(&beacon_nodes, &slashing, &log).await {
+                        webhooks.notify(&SseSlasherSlashing::Attester(Box::new(slashing)));
+                    }
+                }
+                for slashing in proposer_slashings {
+                    if publish_proposer_slashing(&beacon_nodes, &slashing, &log).await {
+                        webhooks.notify(&SseSlasherSlashing::<E>::Proposer(slashing));
+                    }
+                }
+            },
+            "slasher_publish",
+        );
+    }
+}
+
+/// Submit an attester slashing to every beacon node, returning `true` if any of them accepted it.
+///
+/// The beacon nodes verify the slashing, and reject it if the validators are already slashed.
+async fn publish_attester_slashing<E: EthSpec>(
+    beacon_nodes: &[BeaconNodeHttpClient],
+    slashing: &AttesterSlashing<E>,
+    log: &Logger,
+) -> bool {
+    let mut accepted = false;
+    for client in beacon_nodes {
+        match client.post_beacon_pool_attester_slashings(slashing).await {
+            Ok(()) => accepted = true,
+            Err(e) => debug!(
+                log,
+                "Beacon node refused attester slashing";
+                "beacon_node" => %client,
+                "error" => ?e,
+            ),
+        }
+    }
+
+    if accepted {
+        info!(
+            log,
+            "Published attester slashing";
+            "target_epoch" => slashing.attestation_1.data.target.epoch,
+        );
+    } else {
+        warn!(
+            log,
+            "Attester slashing refused by all beacon nodes";
+            "slashing" => ?slashing,
+        );
+    }
+    accepted
+}
+
+/// Submit a proposer slashing to every beacon node, returning `true` if any of them accepted it.
+async fn publish_proposer_slashing(
+    beacon_nodes: &[BeaconNodeHttpClient],
+    slashing: &ProposerSlashing,
+    log: &Logger,
+) -> bool {
+    let mut accepted = false;
+    for client in beacon_nodes {
+        match client.post_beacon_pool_proposer_slashings(slashing).await {
+            Ok(()) => accepted = true,
+            Err(e) => debug!(
+                log,
+                "Beacon node refused proposer slashing";
+                "beacon_node" => %client,
+                "error" => ?e,
+            ),
+        }
+    }
+
+    if accepted {
+        info!(
+            log,
+            "Published proposer slashing";
+            "proposer_index" => slashing.signed_header_1.message.proposer_index,
+            "slot" => slashing.signed_header_1.message.slot,
+        );
+    } else {
+        warn!(
+            log,
+            "Proposer slashing refused by all beacon nodes";
+            "slashing" => ?slashing,
+        );
+    }
+    accepted
+}
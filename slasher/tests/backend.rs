@@ -0,0 +1,206 @@
+use logging::test_logger;
+use maplit::hashset;
+use slasher::{
+    test_utils::{att_slashing, block as test_block, indexed_att, E},
+    Config, Database, DatabaseBackend, Error, Slasher, SlasherDB,
+};
+use std::sync::Arc;
+use tempfile::tempdir;
+use types::Epoch;
+
+/// The backends available in this build.
+fn backends() -> Vec<DatabaseBackend> {
+    let mut backends = vec![DatabaseBackend::Mdbx, DatabaseBackend::Memory];
+    if cfg!(feature = "redb") {
+        backends.push(DatabaseBackend::Redb);
+    }
+    backends
+}
+
+fn open_db(config: Config) -> SlasherDB<E> {
+    SlasherDB::open(Arc::new(config), test_logger()).unwrap()
+}
+
+#[test]
+fn slashings_detected_by_all_backends() {
+    for backend in backends() {
+        let tempdir = tempdir().unwrap();
+        let mut config = Config::new(tempdir.path().into());
+        config.backend = backend;
+        let slasher = Slasher::<E>::open(config, test_logger()).unwrap();
+        let current_epoch = Epoch::new(10);
+
+        let att1 = indexed_att(&[1], 3, 5, 0);
+        let att2 = indexed_att(&[1], 3, 5, 1);
+        let att3 = indexed_att(&[2], 2, 7, 0);
+        let att4 = indexed_att(&[2], 3, 6, 0);
+        for attestation in [&att1, &att2, &att3, &att4] {
+            slasher.accept_attestation(attestation.clone());
+            slasher.process_queued(current_epoch).unwrap();
+        }
+        slasher.accept_block_header(test_block(8, 3, 0));
+        slasher.accept_block_header(test_block(8, 3, 1));
+        slasher.process_queued(current_epoch).unwrap();
+
+        assert_eq!(
+            slasher.get_attester_slashings(),
+            hashset![att_slashing(&att1, &att2), att_slashing(&att3, &att4)],
+            "{}",
+            backend
+        );
+        assert_eq!(slasher.get_proposer_slashings().len(), 1, "{}", backend);
+
+        slasher.prune_database(current_epoch).unwrap();
+    }
+}
+
+#[test]
+fn uncommitted_transactions_are_discarded() {
+    for backend in backends() {
+        let tempdir = tempdir().unwrap();
+        let mut config = Config::new(tempdir.path().into());
+        config.backend = backend;
+        let db = open_db(config);
+
+        let mut txn = db.begin_rw_txn().unwrap();
+        txn.put(Database::Proposers, &[1], &[1]).unwrap();
+        drop(txn);
+
+        let mut txn = db.begin_rw_txn().unwrap();
+        assert_eq!(
+            txn.get(Database::Proposers, &[1]).unwrap(),
+            None,
+            "{}",
+            backend
+        );
+        txn.put(Database::Proposers, &[1], &[2]).unwrap();
+        txn.commit().unwrap();
+
+        let mut txn = db.begin_rw_txn().unwrap();
+        assert_eq!(
+            txn.get(Database::Proposers, &[1]).unwrap().as_deref(),
+            Some(&[2][..]),
+            "{}",
+            backend
+        );
+        txn.del(Database::Proposers, &[1]).unwrap();
+        drop(txn);
+
+        let txn = db.begin_rw_txn().unwrap();
+        assert!(
+            txn.get(Database::Proposers, &[1]).unwrap().is_some(),
+            "{}",
+            backend
+        );
+    }
+}
+
+#[test]
+fn table_sizes_grow_with_contents() {
+    for backend in backends() {
+        let tempdir = tempdir().unwrap();
+        let mut config = Config::new(tempdir.path().into());
+        config.backend = backend;
+        let db = open_db(config);
+        let proposers_size = |db: &SlasherDB<E>| {
+            db.table_sizes()
+                .unwrap()
+                .into_iter()
+                .find(|(table, _)| *table == Database::Proposers)
+                .map(|(_, size)| size)
+        };
+
+        let initial_size = proposers_size(&db);
+        if backend == DatabaseBackend::Redb {
+            assert_eq!(initial_size, None);
+            continue;
+        }
+
+        let mut txn = db.begin_rw_txn().unwrap();
+        for i in 0..100u8 {
+            txn.put(Database::Proposers, &[i], &[0; 64]).unwrap();
+        }
+        txn.commit().unwrap();
+
+        assert!(
+            proposers_size(&db).unwrap() > initial_size.unwrap(),
+            "{}",
+            backend
+        );
+    }
+}
+
+#[test]
+fn iteration_in_key_order() {
+    for backend in backends() {
+        let tempdir = tempdir().unwrap();
+        let mut config = Config::new(tempdir.path().into());
+        config.backend = backend;
+        let db = open_db(config);
+
+        let mut txn = db.begin_rw_txn().unwrap();
+        assert_eq!(txn.last_key(Database::Proposers).unwrap(), None);
+
+        for key in [3u8, 1, 4, 2, 5] {
+            txn.put(Database::Proposers, &[key], &[key * 10]).unwrap();
+        }
+        // Other tables are unaffected.
+        txn.put(Database::Attesters, &[9], &[]).unwrap();
+        assert_eq!(
+            txn.last_key(Database::Proposers).unwrap().as_deref(),
+            Some(&[5][..]),
+            "{}",
+            backend
+        );
+
+        // Deletion stops at the first entry that is kept, even if later entries match.
+        let mut visited = vec![];
+        txn.delete_while(Database::Proposers, &mut |key, value| {
+            visited.push((key[0], value[0]));
+            Ok(key[0] != 3)
+        })
+        .unwrap();
+        assert_eq!(visited, vec![(1, 10), (2, 20), (3, 30)], "{}", backend);
+
+        for (key, exists) in [(1, false), (2, false), (3, true), (4, true), (5, true)] {
+            assert_eq!(
+                txn.get(Database::Proposers, &[key]).unwrap().is_some(),
+                exists,
+                "{} key {}",
+                backend,
+                key
+            );
+        }
+    }
+}
+
+#[test]
+fn backend_cannot_be_changed() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+    drop(open_db(config.clone()));
+
+    let redb_config = Config {
+        backend: DatabaseBackend::Redb,
+        ..config
+    };
+    assert!(matches!(
+        SlasherDB::<E>::open(Arc::new(redb_config), test_logger()),
+        Err(Error::MismatchedBackend {
+            config: DatabaseBackend::Redb,
+            on_disk: DatabaseBackend::Mdbx,
+        })
+    ));
+}
+
+#[cfg(not(feature = "redb"))]
+#[test]
+fn redb_not_compiled() {
+    let tempdir = tempdir().unwrap();
+    let mut config = Config::new(tempdir.path().into());
+    config.backend = DatabaseBackend::Redb;
+    assert!(matches!(
+        SlasherDB::<E>::open(Arc::new(config), test_logger()),
+        Err(Error::BackendNotCompiled(DatabaseBackend::Redb))
+    ));
+}
@@ -0,0 +1,71 @@
+use logging::test_logger;
+use slasher::{
+    export::export_validator,
+    test_utils::{indexed_att, E},
+    Config, Slasher, SlasherDB,
+};
+use std::sync::Arc;
+use tempfile::tempdir;
+use types::Epoch;
+
+#[test]
+fn export_validator_arrays_and_attestations() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+    let current_epoch = Epoch::new(6);
+
+    let att1 = indexed_att(&[3], 1, 2, 0);
+    let att2 = indexed_att(&[3, 4], 3, 5, 0);
+    {
+        let slasher = Slasher::<E>::open(config.clone(), test_logger()).unwrap();
+        slasher.accept_attestation(att1.clone());
+        slasher.accept_attestation(att2.clone());
+        slasher.process_queued(current_epoch).unwrap();
+        assert!(slasher.get_attester_slashings().is_empty());
+    }
+
+    let db = SlasherDB::<E>::open(Arc::new(config), test_logger()).unwrap();
+    let export = export_validator(&db, 3).unwrap();
+
+    assert_eq!(export.current_epoch, Some(current_epoch));
+    assert_eq!(export.max_target, Some(Epoch::new(5)));
+
+    // An attestation with source 0 surrounds `att1` if its target is after 2, and one with source 4
+    // is surrounded by `att2` if its target is before 5.
+    let targets = export
+        .targets
+        .iter()
+        .map(|entry| {
+            (
+                entry.source_epoch.as_u64(),
+                entry.min_target,
+                entry.max_target,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        targets,
+        vec![
+            (0, Some(Epoch::new(2)), None),
+            (1, Some(Epoch::new(5)), None),
+            (2, Some(Epoch::new(5)), None),
+            (3, None, None),
+            (4, None, Some(Epoch::new(5))),
+            (5, None, None),
+            (6, None, None),
+        ]
+    );
+
+    let attestations = export
+        .attestations
+        .into_iter()
+        .map(|entry| (entry.target_epoch.as_u64(), entry.attestation))
+        .collect::<Vec<_>>();
+    assert_eq!(attestations, vec![(2, Some(att1)), (5, Some(att2))]);
+
+    // A validator in a chunk which has never attested has nothing stored.
+    let export = export_validator(&db, 1000).unwrap();
+    assert_eq!(export.current_epoch, None);
+    assert!(export.targets.is_empty());
+    assert!(export.attestations.is_empty());
+}
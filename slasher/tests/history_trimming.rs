@@ -0,0 +1,72 @@
+use logging::test_logger;
+use slasher::{
+    test_utils::{block as test_block, E},
+    Config, DatabaseBackend, Slasher, SlasherDB,
+};
+use std::sync::Arc;
+use tempfile::tempdir;
+use types::{Epoch, EthSpec};
+
+#[test]
+fn trimmed_history_persists() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+    let current_epoch = Epoch::new(100);
+
+    {
+        let db = SlasherDB::<E>::open(Arc::new(config.clone()), test_logger()).unwrap();
+        assert_eq!(db.history_start(), Epoch::new(0));
+
+        // Each trim halves the history retained.
+        assert_eq!(
+            db.trim_history(current_epoch).unwrap(),
+            Some(Epoch::new(51))
+        );
+        assert_eq!(
+            db.trim_history(current_epoch).unwrap(),
+            Some(Epoch::new(76))
+        );
+    }
+
+    let db = SlasherDB::<E>::open(Arc::new(config), test_logger()).unwrap();
+    assert_eq!(db.history_start(), Epoch::new(76));
+
+    // The history can't be trimmed below the minimum length.
+    assert_eq!(
+        db.trim_history(current_epoch).unwrap(),
+        Some(Epoch::new(85))
+    );
+    assert_eq!(db.trim_history(current_epoch).unwrap(), None);
+    assert_eq!(db.history_start(), Epoch::new(85));
+}
+
+#[test]
+fn history_trimmed_near_max_db_size() {
+    let tempdir = tempdir().unwrap();
+    let mut config = Config::new(tempdir.path().into());
+    config.backend = DatabaseBackend::Memory;
+    config.max_db_size_mbs = 1;
+    let slasher = Slasher::<E>::open(config, test_logger()).unwrap();
+
+    // Insert a block for every slot, which is enough to fill the database several times over
+    // without trimming.
+    let slots_per_epoch = E::slots_per_epoch();
+    let num_epochs = 512;
+    for epoch in 0..num_epochs {
+        for slot in epoch * slots_per_epoch..(epoch + 1) * slots_per_epoch {
+            slasher.accept_block_header(test_block(slot, slot, 0));
+        }
+        slasher.process_queued(Epoch::new(epoch)).unwrap();
+    }
+
+    let current_epoch = Epoch::new(num_epochs - 1);
+    let history_start = slasher.history_start();
+    assert!(history_start > 0);
+    assert!(history_start <= current_epoch);
+
+    // Blocks in the retained history are still checked for slashings.
+    let slot = current_epoch.start_slot(slots_per_epoch).as_u64();
+    slasher.accept_block_header(test_block(slot, slot, 1));
+    slasher.process_queued(current_epoch).unwrap();
+    assert_eq!(slasher.get_proposer_slashings().len(), 1);
+}
@@ -2,6 +2,7 @@ use logging::test_logger;
 use rand::prelude::*;
 use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
 use slasher::{
+    config::DEFAULT_NUM_WORKERS,
     test_utils::{
         block, indexed_att, slashed_validators_from_attestations,
         slashed_validators_from_slashings, E,
@@ -18,6 +19,7 @@ struct TestConfig {
     max_attestations: usize,
     check_slashings: bool,
     add_blocks: bool,
+    num_workers: usize,
 }
 
 impl Default for TestConfig {
@@ -27,6 +29,7 @@ impl Default for TestConfig {
             max_attestations: 50,
             check_slashings: false,
             add_blocks: false,
+            num_workers: DEFAULT_NUM_WORKERS,
         }
     }
 }
@@ -47,6 +50,7 @@ fn random_test(seed: u64, test_config: TestConfig) {
     let chunk_size_exponent = rng.gen_range(1..4);
     config.chunk_size = 1 << chunk_size_exponent;
     config.history_length = 1 << rng.gen_range(chunk_size_exponent..chunk_size_exponent + 3);
+    config.num_workers = test_config.num_workers;
 
     let slasher = Slasher::<E>::open(config.clone(), test_logger()).unwrap();
 
@@ -207,6 +211,35 @@ fn check_slashings_example3() {
     );
 }
 
+// Many validator chunks, processed serially and in parallel.
+#[test]
+fn check_slashings_single_worker() {
+    random_test(
+        4,
+        TestConfig {
+            check_slashings: true,
+            num_validators: 64,
+            max_attestations: 100,
+            num_workers: 1,
+            ..TestConfig::default()
+        },
+    );
+}
+
+#[test]
+fn check_slashings_many_workers() {
+    random_test(
+        4,
+        TestConfig {
+            check_slashings: true,
+            num_validators: 64,
+            max_attestations: 100,
+            num_workers: 8,
+            ..TestConfig::default()
+        },
+    );
+}
+
 #[test]
 fn no_crash_example1() {
     random_test(1, TestConfig::default());
@@ -78,5 +78,7 @@ pub fn previous_fork(fork_name: ForkName) -> ForkName {
         ForkName::Base => ForkName::Base,
         ForkName::Altair => ForkName::Base,
         ForkName::Merge => ForkName::Altair, // TODO: Check this when tests are released..
+        ForkName::Capella => ForkName::Merge,
+        ForkName::Deneb => ForkName::Capella,
     }
 }
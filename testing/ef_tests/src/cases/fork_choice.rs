@@ -9,13 +9,14 @@ use beacon_chain::{
     test_utils::{BeaconChainHarness, EphemeralHarnessType},
     BeaconChainTypes, HeadInfo,
 };
+use execution_layer::{json_structures::JsonPayloadStatusV1Status, PayloadStatusV1};
 use serde_derive::Deserialize;
 use ssz_derive::Decode;
 use state_processing::state_advance::complete_state_advance;
 use std::time::Duration;
 use types::{
-    Attestation, BeaconBlock, BeaconState, Checkpoint, Epoch, EthSpec, ExecutionBlockHash,
-    ForkName, Hash256, IndexedAttestation, SignedBeaconBlock, Slot, Uint256,
+    Attestation, AttesterSlashing, BeaconBlock, BeaconState, Checkpoint, Epoch, EthSpec,
+    ExecutionBlockHash, ForkName, Hash256, IndexedAttestation, SignedBeaconBlock, Slot, Uint256,
 };
 
 #[derive(Default, Debug, PartialEq, Clone, Deserialize, Decode)]
@@ -33,6 +34,31 @@ pub struct Head {
     root: Hash256,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PayloadStatus {
+    status: JsonPayloadStatusV1Status,
+    latest_valid_hash: Option<ExecutionBlockHash>,
+    validation_error: Option<String>,
+}
+
+impl From<PayloadStatus> for PayloadStatusV1 {
+    fn from(status: PayloadStatus) -> Self {
+        PayloadStatusV1 {
+            status: status.status.into(),
+            latest_valid_hash: status.latest_valid_hash,
+            validation_error: status.validation_error,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ShouldOverrideFcu {
+    validator_is_connected: bool,
+    result: bool,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Checks {
@@ -44,17 +70,39 @@ pub struct Checks {
     finalized_checkpoint: Option<Checkpoint>,
     best_justified_checkpoint: Option<Checkpoint>,
     proposer_boost_root: Option<Hash256>,
+    get_proposer_head: Option<Hash256>,
+    should_override_forkchoice_update: Option<ShouldOverrideFcu>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(untagged, deny_unknown_fields)]
-pub enum Step<B, A, P> {
-    Tick { tick: u64 },
-    ValidBlock { block: B },
-    MaybeValidBlock { block: B, valid: bool },
-    Attestation { attestation: A },
-    PowBlock { pow_block: P },
-    Checks { checks: Box<Checks> },
+pub enum Step<B, A, P, S> {
+    Tick {
+        tick: u64,
+    },
+    ValidBlock {
+        block: B,
+    },
+    MaybeValidBlock {
+        block: B,
+        valid: bool,
+    },
+    Attestation {
+        attestation: A,
+    },
+    AttesterSlashing {
+        attester_slashing: S,
+    },
+    PowBlock {
+        pow_block: P,
+    },
+    PayloadStatus {
+        block_hash: ExecutionBlockHash,
+        payload_status: PayloadStatus,
+    },
+    Checks {
+        checks: Box<Checks>,
+    },
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -69,7 +117,7 @@ pub struct ForkChoiceTest<E: EthSpec> {
     pub description: String,
     pub anchor_state: BeaconState<E>,
     pub anchor_block: BeaconBlock<E>,
-    pub steps: Vec<Step<SignedBeaconBlock<E>, Attestation<E>, PowBlock>>,
+    pub steps: Vec<Step<SignedBeaconBlock<E>, Attestation<E>, PowBlock, AttesterSlashing<E>>>,
 }
 
 /// Spec for fork choice tests, with proposer boosting enabled.
@@ -91,7 +139,8 @@ impl<E: EthSpec> LoadCase for ForkChoiceTest<E> {
             .expect("path must be valid OsStr")
             .to_string();
         let spec = &fork_choice_spec::<E>(fork_name);
-        let steps: Vec<Step<String, String, String>> = yaml_decode_file(&path.join("steps.yaml"))?;
+        let steps: Vec<Step<String, String, String, String>> =
+            yaml_decode_file(&path.join("steps.yaml"))?;
         // Resolve the object names in `steps.yaml` into actual decoded block/attestation objects.
         let steps = steps
             .into_iter()
@@ -113,10 +162,21 @@ impl<E: EthSpec> LoadCase for ForkChoiceTest<E> {
                     ssz_decode_file(&path.join(format!("{}.ssz_snappy", attestation)))
                         .map(|attestation| Step::Attestation { attestation })
                 }
+                Step::AttesterSlashing { attester_slashing } => {
+                    ssz_decode_file(&path.join(format!("{}.ssz_snappy", attester_slashing)))
+                        .map(|attester_slashing| Step::AttesterSlashing { attester_slashing })
+                }
                 Step::PowBlock { pow_block } => {
                     ssz_decode_file(&path.join(format!("{}.ssz_snappy", pow_block)))
                         .map(|pow_block| Step::PowBlock { pow_block })
                 }
+                Step::PayloadStatus {
+                    block_hash,
+                    payload_status,
+                } => Ok(Step::PayloadStatus {
+                    block_hash,
+                    payload_status,
+                }),
                 Step::Checks { checks } => Ok(Step::Checks { checks }),
             })
             .collect::<Result<_, _>>()?;
@@ -169,7 +229,14 @@ impl<E: EthSpec> Case for ForkChoiceTest<E> {
                     tester.process_block(block.clone(), *valid)?
                 }
                 Step::Attestation { attestation } => tester.process_attestation(attestation)?,
+                Step::AttesterSlashing { attester_slashing } => {
+                    tester.process_attester_slashing(attester_slashing)
+                }
                 Step::PowBlock { pow_block } => tester.process_pow_block(pow_block),
+                Step::PayloadStatus {
+                    block_hash,
+                    payload_status,
+                } => tester.process_payload_status(*block_hash, payload_status.clone().into()),
                 Step::Checks { checks } => {
                     let Checks {
                         head,
@@ -180,6 +247,8 @@ impl<E: EthSpec> Case for ForkChoiceTest<E> {
                         finalized_checkpoint,
                         best_justified_checkpoint,
                         proposer_boost_root,
+                        get_proposer_head,
+                        should_override_forkchoice_update,
                     } = checks.as_ref();
 
                     if let Some(expected_head) = head {
@@ -215,6 +284,14 @@ impl<E: EthSpec> Case for ForkChoiceTest<E> {
                     if let Some(expected_proposer_boost_root) = proposer_boost_root {
                         tester.check_expected_proposer_boost_root(*expected_proposer_boost_root)?;
                     }
+
+                    if let Some(expected_proposer_head) = get_proposer_head {
+                        tester.check_expected_proposer_head(*expected_proposer_head)?;
+                    }
+
+                    if let Some(should_override_fcu) = should_override_forkchoice_update {
+                        tester.check_should_override_fcu(*should_override_fcu)?;
+                    }
                 }
             }
         }
@@ -408,6 +485,23 @@ impl<E: EthSpec> Tester<E> {
             .map_err(|e| Error::InternalError(format!("attestation import failed with {:?}", e)))
     }
 
+    pub fn process_attester_slashing(&self, attester_slashing: &AttesterSlashing<E>) {
+        self.harness
+            .chain
+            .fork_choice
+            .write()
+            .on_attester_slashing(attester_slashing)
+    }
+
+    pub fn process_payload_status(&self, block_hash: ExecutionBlockHash, status: PayloadStatusV1) {
+        self.harness
+            .mock_execution_layer
+            .as_ref()
+            .unwrap()
+            .server
+            .set_payload_status(block_hash, status);
+    }
+
     pub fn process_pow_block(&self, pow_block: &PowBlock) {
         let el = self.harness.mock_execution_layer.as_ref().unwrap();
 
@@ -522,6 +616,61 @@ impl<E: EthSpec> Tester<E> {
             expected_proposer_boost_root,
         )
     }
+
+    pub fn check_expected_proposer_head(
+        &self,
+        expected_proposer_head: Hash256,
+    ) -> Result<(), Error> {
+        let head_root = self.find_head()?.block_root;
+        let slot = self.harness.chain.slot().map_err(|e| {
+            Error::InternalError(format!("reading current slot failed with {:?}", e))
+        })?;
+        let proposer_head = self
+            .harness
+            .chain
+            .fork_choice
+            .read()
+            .get_proposer_head(head_root, slot, self.time_into_slot()?, &self.spec)
+            .map_err(|e| Error::InternalError(format!("get_proposer_head failed with {:?}", e)))?;
+        check_equal("get_proposer_head", proposer_head, expected_proposer_head)
+    }
+
+    pub fn check_should_override_fcu(
+        &self,
+        should_override_fcu: ShouldOverrideFcu,
+    ) -> Result<(), Error> {
+        let head_root = self.find_head()?.block_root;
+        let should_override = self
+            .harness
+            .chain
+            .fork_choice
+            .read()
+            .should_override_forkchoice_update(
+                head_root,
+                self.time_into_slot()?,
+                should_override_fcu.validator_is_connected,
+                &self.spec,
+            )
+            .map_err(|e| {
+                Error::InternalError(format!(
+                    "should_override_forkchoice_update failed with {:?}",
+                    e
+                ))
+            })?;
+        check_equal(
+            "should_override_forkchoice_update",
+            should_override,
+            should_override_fcu.result,
+        )
+    }
+
+    fn time_into_slot(&self) -> Result<Duration, Error> {
+        self.harness
+            .chain
+            .slot_clock
+            .seconds_from_current_slot_start(self.spec.seconds_per_slot)
+            .ok_or_else(|| Error::InternalError("reading time into slot failed".into()))
+    }
 }
 
 /// Checks that the `head` checkpoint from the beacon chain head matches the `fc` checkpoint gleaned
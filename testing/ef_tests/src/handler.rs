@@ -24,22 +24,30 @@ pub trait Handler {
 
     fn run(&self) {
         for fork_name in ForkName::list_all() {
+            // The pinned version of the spec tests does not include Capella or Deneb vectors yet.
+            if fork_name == ForkName::Capella || fork_name == ForkName::Deneb {
+                continue;
+            }
+
             if self.is_enabled_for_fork(fork_name) {
                 self.run_for_fork(fork_name)
             }
         }
     }
 
-    fn run_for_fork(&self, fork_name: ForkName) {
-        let fork_name_str = fork_name.to_string();
-
-        let handler_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    fn handler_path(&self, fork_name: ForkName) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
             .join("consensus-spec-tests")
             .join("tests")
             .join(Self::config_name())
-            .join(&fork_name_str)
+            .join(fork_name.to_string())
             .join(Self::runner_name())
-            .join(self.handler_name());
+            .join(self.handler_name())
+    }
+
+    fn run_for_fork(&self, fork_name: ForkName) {
+        let fork_name_str = fork_name.to_string();
+        let handler_path = self.handler_path(fork_name);
 
         // Iterate through test suites
         let as_directory = |entry: Result<DirEntry, std::io::Error>| -> Option<DirEntry> {
@@ -474,6 +482,36 @@ impl<E: EthSpec + TypeName> Handler for ForkChoiceHandler<E> {
     }
 }
 
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+pub struct OptimisticSyncHandler<E>(PhantomData<E>);
+
+impl<E: EthSpec + TypeName> Handler for OptimisticSyncHandler<E> {
+    // Reuse the fork choice case runner.
+    type Case = cases::ForkChoiceTest<E>;
+
+    fn config_name() -> &'static str {
+        E::name()
+    }
+
+    fn runner_name() -> &'static str {
+        "sync"
+    }
+
+    fn handler_name(&self) -> String {
+        "optimistic".into()
+    }
+
+    fn is_enabled_for_fork(&self, fork_name: ForkName) -> bool {
+        // Optimistic sync tests are only defined for Bellatrix or later, and aren't present in
+        // older releases of the spec tests.
+        fork_name != ForkName::Base
+            && fork_name != ForkName::Altair
+            && self.handler_path(fork_name).exists()
+            && cfg!(not(feature = "fake_crypto"))
+    }
+}
+
 #[derive(Derivative)]
 #[derivative(Default(bound = ""))]
 pub struct GenesisValidityHandler<E>(PhantomData<E>);
@@ -178,9 +178,12 @@ impl<E: GenericExecutionEngine> TestRig<E> {
                 prev_randao,
                 finalized_block_hash,
                 proposer_index,
+                None,
+                None,
             )
             .await
             .unwrap()
+            .payload
             .execution_payload;
 
         /*
@@ -189,7 +192,7 @@ impl<E: GenericExecutionEngine> TestRig<E> {
          * Indicate that the payload is the head of the chain, before submitting a
          * `notify_new_payload`.
          */
-        let head_block_hash = valid_payload.block_hash;
+        let head_block_hash = valid_payload.block_hash();
         let finalized_block_hash = ExecutionBlockHash::zero();
         let slot = Slot::new(42);
         let head_block_root = Hash256::repeat_byte(42);
@@ -210,7 +213,7 @@ impl<E: GenericExecutionEngine> TestRig<E> {
         let status = self
             .ee_a
             .execution_layer
-            .notify_new_payload(&valid_payload)
+            .notify_new_payload(&valid_payload, vec![], Hash256::zero())
             .await
             .unwrap();
         assert_eq!(status, PayloadStatus::Valid);
@@ -223,7 +226,7 @@ impl<E: GenericExecutionEngine> TestRig<E> {
          *
          * Do not provide payload attributes (we'll test that later).
          */
-        let head_block_hash = valid_payload.block_hash;
+        let head_block_hash = valid_payload.block_hash();
         let finalized_block_hash = ExecutionBlockHash::zero();
         let slot = Slot::new(42);
         let head_block_root = Hash256::repeat_byte(42);
@@ -242,11 +245,11 @@ impl<E: GenericExecutionEngine> TestRig<E> {
          */
 
         let mut invalid_payload = valid_payload.clone();
-        invalid_payload.prev_randao = Hash256::from_low_u64_be(42);
+        *invalid_payload.prev_randao_mut() = Hash256::from_low_u64_be(42);
         let status = self
             .ee_a
             .execution_layer
-            .notify_new_payload(&invalid_payload)
+            .notify_new_payload(&invalid_payload, vec![], Hash256::zero())
             .await
             .unwrap();
         assert!(matches!(status, PayloadStatus::InvalidBlockHash { .. }));
@@ -257,8 +260,8 @@ impl<E: GenericExecutionEngine> TestRig<E> {
          * Produce another payload atop the previous one.
          */
 
-        let parent_hash = valid_payload.block_hash;
-        let timestamp = valid_payload.timestamp + 1;
+        let parent_hash = valid_payload.block_hash();
+        let timestamp = valid_payload.timestamp() + 1;
         let prev_randao = Hash256::zero();
         let finalized_block_hash = ExecutionBlockHash::zero();
         let proposer_index = 0;
@@ -271,9 +274,12 @@ impl<E: GenericExecutionEngine> TestRig<E> {
                 prev_randao,
                 finalized_block_hash,
                 proposer_index,
+                None,
+                None,
             )
             .await
             .unwrap()
+            .payload
             .execution_payload;
 
         /*
@@ -285,7 +291,7 @@ impl<E: GenericExecutionEngine> TestRig<E> {
         let status = self
             .ee_a
             .execution_layer
-            .notify_new_payload(&second_payload)
+            .notify_new_payload(&second_payload, vec![], Hash256::zero())
             .await
             .unwrap();
         assert_eq!(status, PayloadStatus::Valid);
@@ -296,12 +302,14 @@ impl<E: GenericExecutionEngine> TestRig<E> {
          *
          * Indicate that the payload is the head of the chain, providing payload attributes.
          */
-        let head_block_hash = valid_payload.block_hash;
+        let head_block_hash = valid_payload.block_hash();
         let finalized_block_hash = ExecutionBlockHash::zero();
         let payload_attributes = PayloadAttributes {
-            timestamp: second_payload.timestamp + 1,
+            timestamp: second_payload.timestamp() + 1,
             prev_randao: Hash256::zero(),
             suggested_fee_recipient: Address::zero(),
+            withdrawals: None,
+            parent_beacon_block_root: None,
         };
         let slot = Slot::new(42);
         let head_block_root = Hash256::repeat_byte(100);
@@ -326,7 +334,7 @@ impl<E: GenericExecutionEngine> TestRig<E> {
         let status = self
             .ee_b
             .execution_layer
-            .notify_new_payload(&second_payload)
+            .notify_new_payload(&second_payload, vec![], Hash256::zero())
             .await
             .unwrap();
         assert_eq!(status, PayloadStatus::Accepted);
@@ -336,7 +344,7 @@ impl<E: GenericExecutionEngine> TestRig<E> {
          *
          * Set the second payload as the head, without providing payload attributes.
          */
-        let head_block_hash = second_payload.block_hash;
+        let head_block_hash = second_payload.block_hash();
         let finalized_block_hash = ExecutionBlockHash::zero();
         let slot = Slot::new(42);
         let head_block_root = Hash256::repeat_byte(42);
@@ -357,7 +365,7 @@ impl<E: GenericExecutionEngine> TestRig<E> {
         let status = self
             .ee_b
             .execution_layer
-            .notify_new_payload(&valid_payload)
+            .notify_new_payload(&valid_payload, vec![], Hash256::zero())
             .await
             .unwrap();
         assert_eq!(status, PayloadStatus::Valid);
@@ -371,7 +379,7 @@ impl<E: GenericExecutionEngine> TestRig<E> {
         let status = self
             .ee_b
             .execution_layer
-            .notify_new_payload(&second_payload)
+            .notify_new_payload(&second_payload, vec![], Hash256::zero())
             .await
             .unwrap();
         assert_eq!(status, PayloadStatus::Valid);
@@ -382,7 +390,7 @@ impl<E: GenericExecutionEngine> TestRig<E> {
          *
          * Set the second payload as the head, without providing payload attributes.
          */
-        let head_block_hash = second_payload.block_hash;
+        let head_block_hash = second_payload.block_hash();
         let finalized_block_hash = ExecutionBlockHash::zero();
         let slot = Slot::new(42);
         let head_block_root = Hash256::repeat_byte(42);
@@ -405,7 +413,7 @@ async fn check_payload_reconstruction<E: GenericExecutionEngine>(
 ) {
     let reconstructed = ee
         .execution_layer
-        .get_payload_by_block_hash(payload.block_hash)
+        .get_payload_by_block_hash(payload.block_hash())
         .await
         .unwrap()
         .unwrap();
@@ -56,11 +56,14 @@ pub fn run_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
         .initialize_logger(LoggerConfig {
             path: None,
             debug_level: log_level,
+            debug_level_overrides: None,
             logfile_debug_level: "debug",
             log_format,
             max_log_size: 0,
             max_log_number: 0,
+            max_log_age: None,
             compression: false,
+            debug_ring_size: 0,
         })?
         .multi_threaded_tokio_runtime()?
         .build()?;
@@ -49,11 +49,14 @@ fn syncing_sim(
         .initialize_logger(LoggerConfig {
             path: None,
             debug_level: log_level,
+            debug_level_overrides: None,
             logfile_debug_level: "debug",
             log_format,
             max_log_size: 0,
             max_log_number: 0,
+            max_log_age: None,
             compression: false,
+            debug_ring_size: 0,
         })?
         .multi_threaded_tokio_runtime()?
         .build()?;
@@ -0,0 +1,81 @@
+//! A live dashboard of a beacon node's status, for operators without a metrics stack.
+//!
+//! The status is polled from the beacon node's HTTP API and redrawn in the terminal on a fixed
+//! interval until the process is interrupted.
+mod render;
+mod status;
+
+pub use render::render;
+pub use status::NodeStatus;
+
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use eth2::{BeaconNodeHttpClient, Timeouts};
+use sensitive_url::SensitiveUrl;
+use std::io::{self, Write};
+use std::time::Duration;
+use types::EthSpec;
+
+pub const CMD: &str = "top";
+
+pub const DEFAULT_BEACON_NODE: &str = "http://localhost:5052";
+pub const DEFAULT_INTERVAL_SECONDS: &str = "2";
+
+/// Clears the terminal and moves the cursor to the top-left corner.
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .setting(clap::AppSettings::ColoredHelp)
+        .about(
+            "Show a live dashboard of the sync status, peers, head and finalized checkpoints, \
+             execution engine and monitored validators of a beacon node. Press Ctrl-C to exit.",
+        )
+        .arg(
+            Arg::with_name("beacon-node")
+                .long("beacon-node")
+                .value_name("URL")
+                .help("The HTTP API of the beacon node.")
+                .default_value(DEFAULT_BEACON_NODE)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("interval")
+                .long("interval")
+                .value_name("SECONDS")
+                .help("The number of seconds between refreshes of the dashboard.")
+                .default_value(DEFAULT_INTERVAL_SECONDS)
+                .takes_value(true),
+        )
+}
+
+pub fn run<E: EthSpec>(cli_args: &ArgMatches<'_>, env: Environment<E>) -> Result<(), String> {
+    let beacon_node =
+        clap_utils::parse_required(cli_args, "beacon-node").and_then(|url: String| {
+            SensitiveUrl::parse(&url).map_err(|e| format!("Invalid --beacon-node: {:?}", e))
+        })?;
+    let interval_seconds: u64 = clap_utils::parse_required(cli_args, "interval")?;
+    if interval_seconds == 0 {
+        return Err("--interval must be at least 1 second".to_string());
+    }
+    let interval = Duration::from_secs(interval_seconds);
+
+    // Don't wait longer than one refresh for a response, so a slow beacon node can't stall the
+    // dashboard.
+    let client = BeaconNodeHttpClient::new(beacon_node.clone(), Timeouts::set_all(interval));
+
+    loop {
+        let status = env.runtime().block_on(NodeStatus::fetch(&client));
+        let mut stdout = io::stdout();
+        write!(
+            stdout,
+            "{}{}",
+            CLEAR_SCREEN,
+            render(&beacon_node, &status, E::slots_per_epoch())
+        )
+        .and_then(|()| stdout.flush())
+        .map_err(|e| format!("Unable to write to the terminal: {}", e))?;
+
+        std::thread::sleep(interval);
+    }
+}
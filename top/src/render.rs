@@ -0,0 +1,263 @@
+use crate::NodeStatus;
+use eth2::lighthouse::{MonitoredValidatorSummary, ValidatorMonitorEpochSummary};
+use eth2::types::Checkpoint;
+use sensitive_url::SensitiveUrl;
+
+/// At most this many monitored validators are listed, to keep the dashboard on one screen.
+pub const MAX_VALIDATOR_ROWS: usize = 16;
+
+/// Render the dashboard for `status` as lines of text.
+pub fn render(beacon_node: &SensitiveUrl, status: &NodeStatus, slots_per_epoch: u64) -> String {
+    let mut lines = vec![
+        format!("Lighthouse beacon node at {}", beacon_node),
+        String::new(),
+    ];
+
+    lines.push("Sync".to_string());
+    match &status.sync_state {
+        Ok(sync_state) => lines.push(format!("  State:             {}", sync_state)),
+        Err(e) => lines.push(unavailable("State", e)),
+    }
+    match &status.syncing {
+        Ok(syncing) => {
+            lines.push(format!(
+                "  Sync distance:     {} slot(s)",
+                syncing.sync_distance
+            ));
+            lines.push(format!(
+                "  Optimistic:        {}",
+                yes_no(syncing.is_optimistic)
+            ));
+            let execution_engine = match syncing.el_offline {
+                Some(false) => "online",
+                Some(true) => "offline",
+                None => "not configured",
+            };
+            lines.push(format!("  Execution engine:  {}", execution_engine));
+        }
+        Err(e) => lines.push(unavailable("Sync distance", e)),
+    }
+    lines.push(String::new());
+
+    lines.push("Peers".to_string());
+    match &status.peers {
+        Ok(peers) => lines.push(format!(
+            "  Connected:         {} ({} connecting, {} disconnecting)",
+            peers.connected, peers.connecting, peers.disconnecting
+        )),
+        Err(e) => lines.push(unavailable("Connected", e)),
+    }
+    lines.push(String::new());
+
+    lines.push("Chain".to_string());
+    match &status.head {
+        Ok(head) => lines.push(format!(
+            "  Head:              slot {} ({})",
+            head.header.message.slot, head.root
+        )),
+        Err(e) => lines.push(unavailable("Head", e)),
+    }
+    match &status.finality {
+        Ok(finality) => {
+            lines.push(checkpoint(
+                "Justified",
+                &finality.current_justified,
+                slots_per_epoch,
+            ));
+            lines.push(checkpoint(
+                "Finalized",
+                &finality.finalized,
+                slots_per_epoch,
+            ));
+        }
+        Err(e) => lines.push(unavailable("Finalized", e)),
+    }
+    lines.push(String::new());
+
+    match &status.validators {
+        Ok(validators) if validators.is_empty() => {
+            lines.push("Validators".to_string());
+            lines.push(
+                "  None monitored, run the beacon node with --validator-monitor-auto or \
+                 --validator-monitor-pubkeys"
+                    .to_string(),
+            );
+        }
+        Ok(validators) => {
+            lines.push(format!("Validators ({} monitored)", validators.len()));
+            lines.push(format!(
+                "  {:<10} {:<8} {:<12} {:<6} {:<6} {:<10} {:<10}",
+                "INDEX", "EPOCH", "ATTESTATION", "HEAD", "TARGET", "BLOCKS", "SYNC"
+            ));
+            lines.extend(
+                validators
+                    .iter()
+                    .take(MAX_VALIDATOR_ROWS)
+                    .map(validator_row),
+            );
+            if validators.len() > MAX_VALIDATOR_ROWS {
+                lines.push(format!(
+                    "  ... and {} more",
+                    validators.len() - MAX_VALIDATOR_ROWS
+                ));
+            }
+        }
+        Err(e) => {
+            lines.push("Validators".to_string());
+            lines.push(unavailable("Monitored", e));
+        }
+    }
+
+    lines.push(String::new());
+    lines.push("Press Ctrl-C to exit".to_string());
+    lines.join("\n") + "\n"
+}
+
+/// Render the duty outcomes of a validator in its most recent epoch to have been processed.
+///
+/// Blocks are shown as `proposed/missed` and sync committee signatures as `included/missed`.
+fn validator_row(validator: &MonitoredValidatorSummary) -> String {
+    let index = validator
+        .index
+        .map_or_else(|| "unknown".to_string(), |index| index.to_string());
+
+    let summary = if let Some(summary) = latest_processed_epoch(&validator.epochs) {
+        summary
+    } else {
+        return format!("  {:<10} no epochs observed yet", index);
+    };
+
+    let attestation = match summary.attestation_included_on_chain {
+        Some(true) => "included",
+        Some(false) => "missed",
+        None if summary.attestations > 0 => "seen",
+        None => "-",
+    };
+
+    let blocks = format!("{}/{}", summary.blocks, summary.missed_blocks);
+    let sync_signatures = format!(
+        "{}/{}",
+        summary.sync_signature_block_inclusions, summary.sync_signature_block_misses
+    );
+
+    format!(
+        "  {:<10} {:<8} {:<12} {:<6} {:<6} {:<10} {:<10}",
+        index,
+        summary.epoch,
+        attestation,
+        yes_no(summary.attestation_matched_head),
+        yes_no(summary.attestation_matched_target),
+        blocks,
+        sync_signatures,
+    )
+}
+
+/// Returns the most recent epoch whose attestation outcome is known, or the most recent epoch if
+/// none are.
+///
+/// The outcome of the current epoch isn't known until it has been processed, so showing it would
+/// make every validator look like it's missing attestations.
+fn latest_processed_epoch(
+    epochs: &[ValidatorMonitorEpochSummary],
+) -> Option<&ValidatorMonitorEpochSummary> {
+    epochs
+        .iter()
+        .rev()
+        .find(|summary| summary.attestation_included_on_chain.is_some())
+        .or_else(|| epochs.last())
+}
+
+fn checkpoint(name: &str, checkpoint: &Checkpoint, slots_per_epoch: u64) -> String {
+    let label = format!("{}:", name);
+    format!(
+        "  {:<19}epoch {} (slot {})",
+        label,
+        checkpoint.epoch,
+        checkpoint.epoch.start_slot(slots_per_epoch)
+    )
+}
+
+fn unavailable(name: &str, error: &str) -> String {
+    let label = format!("{}:", name);
+    format!("  {:<19}unavailable ({})", label, error)
+}
+
+fn yes_no(value: Option<bool>) -> &'static str {
+    match value {
+        Some(true) => "yes",
+        Some(false) => "no",
+        None => "-",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{Epoch, PublicKeyBytes};
+
+    fn epoch_summary(epoch: u64, included: Option<bool>) -> ValidatorMonitorEpochSummary {
+        ValidatorMonitorEpochSummary {
+            epoch: Epoch::new(epoch),
+            attestations: 1,
+            attestation_min_delay_ms: None,
+            attestation_aggregate_inclusions: 0,
+            attestation_block_inclusions: 0,
+            attestation_min_block_inclusion_distance: None,
+            attestation_included_on_chain: included,
+            attestation_matched_head: included,
+            attestation_matched_target: included,
+            blocks: 0,
+            block_min_delay_ms: None,
+            missed_blocks: 1,
+            aggregates: 0,
+            aggregate_min_delay_ms: None,
+            sync_committee_messages: 0,
+            sync_committee_message_min_delay_ms: None,
+            sync_signature_block_inclusions: 0,
+            sync_signature_block_misses: 0,
+            sync_signature_contribution_inclusions: 0,
+            sync_contributions: 0,
+            sync_contribution_min_delay_ms: None,
+            exits: 0,
+            proposer_slashings: 0,
+            attester_slashings: 0,
+        }
+    }
+
+    #[test]
+    fn validator_row_shows_latest_processed_epoch() {
+        let validator = MonitoredValidatorSummary {
+            pubkey: PublicKeyBytes::empty(),
+            index: Some(42),
+            epochs: vec![epoch_summary(9, Some(false)), epoch_summary(10, None)],
+        };
+        let row = validator_row(&validator);
+        assert!(row.contains("42"), "{}", row);
+        assert!(row.contains(" 9 "), "{}", row);
+        assert!(row.contains("missed"), "{}", row);
+        assert!(row.contains("0/1"), "{}", row);
+
+        let validator = MonitoredValidatorSummary {
+            index: None,
+            epochs: vec![],
+            ..validator
+        };
+        assert!(validator_row(&validator).contains("no epochs observed yet"));
+    }
+
+    #[test]
+    fn render_unavailable_status() {
+        let error = || Err("connection refused".to_string());
+        let status = NodeStatus {
+            syncing: error(),
+            sync_state: error(),
+            peers: error(),
+            head: error(),
+            finality: error(),
+            validators: error(),
+        };
+        let beacon_node = SensitiveUrl::parse("http://localhost:5052").unwrap();
+        let dashboard = render(&beacon_node, &status, 32);
+        assert_eq!(dashboard.matches("unavailable").count(), 6, "{}", dashboard);
+    }
+}
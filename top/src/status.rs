@@ -0,0 +1,66 @@
+use eth2::lighthouse::{MonitoredValidatorSummary, SyncState};
+use eth2::types::{
+    BlockHeaderData, BlockId, FinalityCheckpointsData, PeerCount, StateId, SyncingData,
+};
+use eth2::BeaconNodeHttpClient;
+
+/// A snapshot of the status of a beacon node.
+///
+/// Each part is fetched from a separate endpoint, and holds an error message if that request
+/// failed so that the rest of the status can still be shown.
+pub struct NodeStatus {
+    pub syncing: Result<SyncingData, String>,
+    pub sync_state: Result<SyncState, String>,
+    pub peers: Result<PeerCount, String>,
+    pub head: Result<BlockHeaderData, String>,
+    pub finality: Result<FinalityCheckpointsData, String>,
+    pub validators: Result<Vec<MonitoredValidatorSummary>, String>,
+}
+
+impl NodeStatus {
+    /// Fetch the status from the beacon node.
+    ///
+    /// The requests are cheap for the beacon node, so they're made one at a time.
+    pub async fn fetch(client: &BeaconNodeHttpClient) -> Self {
+        Self {
+            syncing: client
+                .get_node_syncing()
+                .await
+                .map(|response| response.data)
+                .map_err(|e| e.to_string()),
+            sync_state: client
+                .get_lighthouse_syncing()
+                .await
+                .map(|response| response.data)
+                .map_err(|e| e.to_string()),
+            peers: client
+                .get_node_peer_count()
+                .await
+                .map(|response| response.data)
+                .map_err(|e| e.to_string()),
+            head: client
+                .get_beacon_headers_block_id(BlockId::Head)
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|response| {
+                    response
+                        .map(|response| response.data)
+                        .ok_or_else(|| "head block not found".to_string())
+                }),
+            finality: client
+                .get_beacon_states_finality_checkpoints(StateId::Head)
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|response| {
+                    response
+                        .map(|response| response.data)
+                        .ok_or_else(|| "head state not found".to_string())
+                }),
+            validators: client
+                .get_lighthouse_validator_monitor()
+                .await
+                .map(|response| response.data)
+                .map_err(|e| e.to_string()),
+        }
+    }
+}
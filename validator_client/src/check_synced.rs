@@ -16,6 +16,9 @@ const SYNC_TOLERANCE: u64 = 4;
 ///
 ///  The second condition means the even if the beacon node thinks that it's syncing, we'll still
 ///  try to use it if it's close enough to the head.
+///
+///  A beacon node with an optimistic head (one which has not been verified by the execution
+///  layer) is always considered to be `NotSynced`.
 pub async fn check_synced<T: SlotClock>(
     beacon_node: &BeaconNodeHttpClient,
     slot_clock: &T,
@@ -36,7 +39,9 @@ pub async fn check_synced<T: SlotClock>(
         }
     };
 
-    let is_synced = !resp.data.is_syncing || (resp.data.sync_distance.as_u64() < SYNC_TOLERANCE);
+    let is_optimistic = resp.data.is_optimistic.unwrap_or(false);
+    let is_synced = !is_optimistic
+        && (!resp.data.is_syncing || (resp.data.sync_distance.as_u64() < SYNC_TOLERANCE));
 
     if let Some(log) = log_opt {
         if !is_synced {
@@ -51,6 +56,7 @@ pub async fn check_synced<T: SlotClock>(
                 "Beacon node is not synced";
                 "sync_distance" => resp.data.sync_distance.as_u64(),
                 "head_slot" => resp.data.head_slot.as_u64(),
+                "is_optimistic" => is_optimistic,
                 "endpoint" => %beacon_node,
             );
         }
@@ -112,6 +112,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                         in addition to the OS trust store. Commas must only be used as a \
                         delimiter, and must not be part of the certificate path.")
         )
+        .arg(
+            Arg::with_name("beacon-nodes-token-file")
+                .long("beacon-nodes-token-file")
+                .value_name("TOKEN-FILES")
+                .takes_value(true)
+                .help("Comma-separated paths to files containing the bearer token to send to the \
+                        beacon nodes, as set with --http-validator-token-file on the beacon node. \
+                        Either a single file used for every beacon node, or one file per beacon \
+                        node in the order of --beacon-nodes.")
+        )
         // This overwrites the graffiti configured in the beacon node.
         .arg(
             Arg::with_name("graffiti")
@@ -243,6 +253,25 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 and never provide an untrusted URL.")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("monitoring-endpoint-period")
+                .long("monitoring-endpoint-period")
+                .value_name("SECONDS")
+                .help("Defines how many seconds to wait between each message sent to \
+                       the monitoring-endpoint. Default: 60s")
+                .requires("monitoring-endpoint")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("monitoring-endpoint-token-file")
+                .long("monitoring-endpoint-token-file")
+                .value_name("PATH")
+                .help("Path to a file containing a token which is sent to the monitoring-endpoint \
+                       as a bearer token in the Authorization header of each message, for \
+                       services which require authentication.")
+                .requires("monitoring-endpoint")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("enable-doppelganger-protection")
                 .long("enable-doppelganger-protection")
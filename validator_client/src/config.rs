@@ -59,6 +59,9 @@ pub struct Config {
     /// A list of custom certificates that the validator client will additionally use when
     /// connecting to a beacon node over SSL/TLS.
     pub beacon_nodes_tls_certs: Option<Vec<PathBuf>>,
+    /// Files containing the bearer tokens sent to the beacon nodes. Either a single file for every
+    /// beacon node, or one file per beacon node.
+    pub beacon_nodes_token_files: Option<Vec<PathBuf>>,
 }
 
 impl Default for Config {
@@ -92,6 +95,7 @@ impl Default for Config {
             monitoring_api: None,
             enable_doppelganger_protection: false,
             beacon_nodes_tls_certs: None,
+            beacon_nodes_token_files: None,
             private_tx_proposals: false,
         }
     }
@@ -229,6 +233,17 @@ impl Config {
             config.beacon_nodes_tls_certs = Some(tls_certs.split(',').map(PathBuf::from).collect());
         }
 
+        if let Some(token_files) = parse_optional::<String>(cli_args, "beacon-nodes-token-file")? {
+            let token_files: Vec<PathBuf> = token_files.split(',').map(PathBuf::from).collect();
+            if token_files.len() != 1 && token_files.len() != config.beacon_nodes.len() {
+                return Err(format!(
+                    "--beacon-nodes-token-file must list 1 or {} files, one per beacon node",
+                    config.beacon_nodes.len()
+                ));
+            }
+            config.beacon_nodes_token_files = Some(token_files);
+        }
+
         /*
          * Http API server
          */
@@ -297,10 +312,17 @@ impl Config {
          * Explorer metrics
          */
         if let Some(monitoring_endpoint) = cli_args.value_of("monitoring-endpoint") {
+            let update_period_secs = parse_optional(cli_args, "monitoring-endpoint-period")?;
+            if update_period_secs == Some(0) {
+                return Err("--monitoring-endpoint-period must be greater than zero".to_string());
+            }
+
             config.monitoring_api = Some(monitoring_api::Config {
                 db_path: None,
                 freezer_db_path: None,
                 monitoring_endpoint: monitoring_endpoint.to_string(),
+                update_period_secs,
+                api_token_path: parse_optional(cli_args, "monitoring-endpoint-token-file")?,
             });
         }
 
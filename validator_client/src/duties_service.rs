@@ -243,10 +243,12 @@ pub fn start_update_service<T: SlotClock + 'static, E: EthSpec>(
     let duties_service = core_duties_service.clone();
     core_duties_service.context.executor.spawn(
         async move {
+            let mut last_monitor_registration = None;
             loop {
                 // Run this poll before the wait, this should hopefully download all the indices
                 // before the block/attestation tasks need them.
                 poll_validator_indices(&duties_service).await;
+                register_validator_monitor(&duties_service, &mut last_monitor_registration).await;
 
                 if let Some(duration) = duties_service.slot_clock.duration_to_next_slot() {
                     sleep(duration).await;
@@ -433,6 +435,53 @@ async fn poll_validator_indices<T: SlotClock + 'static, E: EthSpec>(
     }
 }
 
+/// Register all local validators with the validator monitor of the beacon node, so that it logs
+/// and reports metrics for their duties, including any which are missed.
+///
+/// The registration is repeated each epoch and whenever the number of validators changes, since
+/// the beacon node may have restarted or a fallback may have taken over. A beacon node without
+/// automatic registration enabled rejects the request, which is harmless.
+async fn register_validator_monitor<T: SlotClock + 'static, E: EthSpec>(
+    duties_service: &DutiesService<T, E>,
+    last_registration: &mut Option<(Epoch, usize)>,
+) {
+    let log = duties_service.context.log();
+
+    let current_epoch = match duties_service.slot_clock.now() {
+        Some(slot) => slot.epoch(E::slots_per_epoch()),
+        None => return,
+    };
+    let pubkeys: Vec<_> = duties_service
+        .validator_store
+        .voting_pubkeys(DoppelgangerStatus::ignored);
+    if pubkeys.is_empty() || *last_registration == Some((current_epoch, pubkeys.len())) {
+        return;
+    }
+    *last_registration = Some((current_epoch, pubkeys.len()));
+
+    let pubkeys = &pubkeys;
+    let result = duties_service
+        .beacon_nodes
+        .first_success(duties_service.require_synced, |beacon_node| async move {
+            beacon_node.post_lighthouse_validator_monitor(pubkeys).await
+        })
+        .await;
+
+    match result {
+        Ok(response) => debug!(
+            log,
+            "Registered validators for monitoring";
+            "newly_monitored" => response.data.newly_monitored,
+            "num_monitored" => response.data.num_monitored,
+        ),
+        Err(e) => debug!(
+            log,
+            "Unable to register validators for monitoring";
+            "error" => %e,
+        ),
+    }
+}
+
 /// Query the beacon node for attestation duties for any known validators.
 ///
 /// This function will perform (in the following order):
@@ -110,12 +110,14 @@ impl SyncDutiesMap {
         committee_period: u64,
         current_epoch: Epoch,
         spec: &ChainSpec,
-    ) -> (Epoch, Vec<(Epoch, SyncDuty)>) {
-        let default_start_epoch =
-            std::cmp::max(current_epoch, first_epoch_of_period(committee_period, spec));
+    ) -> Result<(Epoch, Vec<(Epoch, SyncDuty)>), Error> {
+        let default_start_epoch = std::cmp::max(
+            current_epoch,
+            Epoch::sync_committee_period_start(committee_period, spec)?,
+        );
         let pre_compute_epoch = std::cmp::min(
             current_epoch + AGGREGATION_PRE_COMPUTE_EPOCHS,
-            last_epoch_of_period(committee_period, spec),
+            Epoch::sync_committee_period_end(committee_period, spec)?,
         );
 
         let pre_compute_duties = self.committees.read().get(&committee_period).map_or_else(
@@ -147,7 +149,7 @@ impl SyncDutiesMap {
                     .collect()
             },
         );
-        (pre_compute_epoch, pre_compute_duties)
+        Ok((pre_compute_epoch, pre_compute_duties))
     }
 
     fn get_or_create_committee_duties<'a, 'b>(
@@ -261,14 +263,6 @@ fn epoch_offset(spec: &ChainSpec) -> u64 {
     spec.epochs_per_sync_committee_period.as_u64() / 2
 }
 
-fn first_epoch_of_period(sync_committee_period: u64, spec: &ChainSpec) -> Epoch {
-    spec.epochs_per_sync_committee_period * sync_committee_period
-}
-
-fn last_epoch_of_period(sync_committee_period: u64, spec: &ChainSpec) -> Epoch {
-    first_epoch_of_period(sync_committee_period + 1, spec) - 1
-}
-
 pub async fn poll_sync_committee_duties<T: SlotClock + 'static, E: EthSpec>(
     duties_service: &Arc<DutiesService<T, E>>,
 ) -> Result<(), Error> {
@@ -327,7 +321,7 @@ pub async fn poll_sync_committee_duties<T: SlotClock + 'static, E: EthSpec>(
 
     // Pre-compute aggregator selection proofs for the current period.
     let (current_pre_compute_epoch, new_pre_compute_duties) = sync_duties
-        .prepare_for_aggregator_pre_compute(current_sync_committee_period, current_epoch, spec);
+        .prepare_for_aggregator_pre_compute(current_sync_committee_period, current_epoch, spec)?;
 
     if !new_pre_compute_duties.is_empty() {
         let sub_duties_service = duties_service.clone();
@@ -348,7 +342,7 @@ pub async fn poll_sync_committee_duties<T: SlotClock + 'static, E: EthSpec>(
 
     // If we're past the point in the current period where we should determine duties for the next
     // period and they are not yet known, then poll.
-    if current_epoch.as_u64() % spec.epochs_per_sync_committee_period.as_u64() >= epoch_offset(spec)
+    if current_epoch.sync_committee_period_offset(spec)? >= epoch_offset(spec)
         && !sync_duties.all_duties_known(next_sync_committee_period, &local_indices)
     {
         poll_sync_committee_duties_for_period(
@@ -368,7 +362,7 @@ pub async fn poll_sync_committee_duties<T: SlotClock + 'static, E: EthSpec>(
         == next_sync_committee_period
     {
         let (pre_compute_epoch, new_pre_compute_duties) = sync_duties
-            .prepare_for_aggregator_pre_compute(next_sync_committee_period, current_epoch, spec);
+            .prepare_for_aggregator_pre_compute(next_sync_committee_period, current_epoch, spec)?;
 
         if !new_pre_compute_duties.is_empty() {
             let sub_duties_service = duties_service.clone();
@@ -416,7 +410,7 @@ pub async fn poll_sync_committee_duties_for_period<T: SlotClock + 'static, E: Et
         "num_validators" => local_indices.len(),
     );
 
-    let period_start_epoch = spec.epochs_per_sync_committee_period * sync_committee_period;
+    let period_start_epoch = Epoch::sync_committee_period_start(sync_committee_period, spec)?;
 
     let duties_response = duties_service
         .beacon_nodes
@@ -36,7 +36,11 @@ use block_service::{BlockService, BlockServiceBuilder};
 use clap::ArgMatches;
 use duties_service::DutiesService;
 use environment::RuntimeContext;
-use eth2::{reqwest::ClientBuilder, BeaconNodeHttpClient, StatusCode, Timeouts};
+use eth2::reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use eth2::{
+    reqwest::{Client, ClientBuilder},
+    BeaconNodeHttpClient, StatusCode, Timeouts,
+};
 use http_api::ApiSecret;
 use notifier::spawn_notifier;
 use parking_lot::RwLock;
@@ -45,7 +49,7 @@ use reqwest::Certificate;
 use slog::{error, info, warn, Logger};
 use slot_clock::SlotClock;
 use slot_clock::SystemTimeSlotClock;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::Read;
 use std::marker::PhantomData;
 use std::net::SocketAddr;
@@ -253,21 +257,8 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .map(|(i, url)| {
                 let slot_duration = Duration::from_secs(context.eth2_config.spec.seconds_per_slot);
 
-                let mut beacon_node_http_client_builder = ClientBuilder::new();
-
-                // Add new custom root certificates if specified.
-                if let Some(certificates) = &config.beacon_nodes_tls_certs {
-                    for cert in certificates {
-                        beacon_node_http_client_builder = beacon_node_http_client_builder
-                            .add_root_certificate(load_pem_certificate(cert)?);
-                    }
-                }
-
-                let beacon_node_http_client = beacon_node_http_client_builder
-                    // Set default timeout to be the full slot duration.
-                    .timeout(slot_duration)
-                    .build()
-                    .map_err(|e| format!("Unable to build HTTP client: {:?}", e))?;
+                // Set default timeout to be the full slot duration.
+                let beacon_node_http_client = build_beacon_node_client(&config, i, slot_duration)?;
 
                 // Use quicker timeouts if a fallback beacon node exists.
                 let timeouts = if i < last_beacon_node_index && !config.use_long_timeouts {
@@ -700,6 +691,60 @@ async fn poll_whilst_waiting_for_genesis<E: EthSpec>(
     }
 }
 
+/// Builds the HTTP client used to connect to the beacon node at `index` in `config.beacon_nodes`,
+/// with the root certificates and bearer token configured for it.
+pub fn build_beacon_node_client(
+    config: &Config,
+    index: usize,
+    timeout: Duration,
+) -> Result<Client, String> {
+    let mut beacon_node_http_client_builder = ClientBuilder::new();
+
+    // Add new custom root certificates if specified.
+    if let Some(certificates) = &config.beacon_nodes_tls_certs {
+        for cert in certificates {
+            beacon_node_http_client_builder =
+                beacon_node_http_client_builder.add_root_certificate(load_pem_certificate(cert)?);
+        }
+    }
+
+    // Send the beacon node's token with every request if specified. A single token file is used
+    // for every beacon node.
+    if let Some(token_files) = &config.beacon_nodes_token_files {
+        let token_file = if token_files.len() == 1 {
+            token_files.first()
+        } else {
+            token_files.get(index)
+        }
+        .ok_or_else(|| format!("No token file for beacon node {}", index))?;
+        beacon_node_http_client_builder =
+            beacon_node_http_client_builder.default_headers(load_bearer_token(token_file)?);
+    }
+
+    beacon_node_http_client_builder
+        .timeout(timeout)
+        .build()
+        .map_err(|e| format!("Unable to build HTTP client: {:?}", e))
+}
+
+/// Loads the token in `token_path` and returns the headers which send it as a bearer token.
+pub fn load_bearer_token<P: AsRef<Path>>(token_path: P) -> Result<HeaderMap, String> {
+    let token =
+        fs::read_to_string(&token_path).map_err(|e| format!("Unable to read token file: {}", e))?;
+    let token = token.trim();
+    if token.is_empty() {
+        return Err("Token file must not be empty".to_string());
+    }
+
+    let mut value = HeaderValue::from_str(&format!("Bearer {}", token))
+        .map_err(|e| format!("Invalid token: {}", e))?;
+    value.set_sensitive(true);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, value);
+    Ok(headers)
+}
+
 pub fn load_pem_certificate<P: AsRef<Path>>(pem_path: P) -> Result<Certificate, String> {
     let mut buf = Vec::new();
     File::open(&pem_path)
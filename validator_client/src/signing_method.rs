@@ -102,11 +102,12 @@ pub struct SigningContext {
 impl SigningContext {
     /// Returns the `Hash256` to be mixed-in with the signature.
     pub fn domain_hash(&self, spec: &ChainSpec) -> Hash256 {
-        spec.get_domain(
-            self.epoch,
+        get_signing_domain(
             self.domain,
+            self.epoch,
             &self.fork,
             self.genesis_validators_root,
+            spec,
         )
     }
 }
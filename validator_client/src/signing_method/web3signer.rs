@@ -71,7 +71,9 @@ impl<'a, T: EthSpec, Payload: ExecPayload<T>> Web3SignerObject<'a, T, Payload> {
         let version = match block {
             BeaconBlock::Base(_) => ForkName::Phase0,
             BeaconBlock::Altair(_) => ForkName::Altair,
-            BeaconBlock::Merge(_) => return Err(Error::MergeForkNotSupported),
+            BeaconBlock::Merge(_) | BeaconBlock::Capella(_) | BeaconBlock::Deneb(_) => {
+                return Err(Error::MergeForkNotSupported)
+            }
         };
 
         Ok(Web3SignerObject::BeaconBlock { version, block })
@@ -12,7 +12,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::time::{sleep, sleep_until, Duration, Instant};
 use types::{
-    ChainSpec, EthSpec, Hash256, PublicKeyBytes, Slot, SyncCommitteeSubscription,
+    ChainSpec, Epoch, EthSpec, Hash256, PublicKeyBytes, Slot, SyncCommitteeSubscription,
     SyncContributionData, SyncDuty, SyncSelectionProof, SyncSubnetId,
 };
 
@@ -493,7 +493,7 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                     duties.duties,
                     sync_committee_period,
                     spec,
-                )),
+                )?),
                 None => {
                     debug!(
                         log,
@@ -569,13 +569,14 @@ fn subscriptions_from_sync_duties(
     duties: Vec<SyncDuty>,
     sync_committee_period: u64,
     spec: &ChainSpec,
-) -> impl Iterator<Item = SyncCommitteeSubscription> {
-    let until_epoch = spec.epochs_per_sync_committee_period * (sync_committee_period + 1);
-    duties
+) -> Result<impl Iterator<Item = SyncCommitteeSubscription>, String> {
+    let until_epoch = Epoch::sync_committee_period_start(sync_committee_period + 1, spec)
+        .map_err(|e| format!("Error computing end of sync period: {:?}", e))?;
+    Ok(duties
         .into_iter()
         .map(move |duty| SyncCommitteeSubscription {
             validator_index: duty.validator_index,
             sync_committee_indices: duty.validator_sync_committee_indices,
             until_epoch,
-        })
+        }))
 }